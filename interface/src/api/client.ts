@@ -23,6 +23,24 @@ export interface ChannelsResponse {
 	channels: ChannelInfo[];
 }
 
+export interface ChannelRouting {
+	channel?: string | null;
+	branch?: string | null;
+	worker?: string | null;
+	compactor?: string | null;
+}
+
+export interface ChannelRoutingResponse {
+	channel_id: string;
+	routing: ChannelRouting;
+	resolved: {
+		channel: string;
+		branch: string;
+		worker: string;
+		compactor: string;
+	};
+}
+
 export type ProcessType = "channel" | "branch" | "worker";
 
 export interface InboundMessageEvent {
@@ -56,6 +74,13 @@ export interface TypingStateEvent {
 	is_typing: boolean;
 }
 
+export interface TurnInterruptedEvent {
+	type: "turn_interrupted";
+	agent_id: string;
+	channel_id: string;
+	sender_id: string;
+}
+
 export interface WorkerStartedEvent {
 	type: "worker_started";
 	agent_id: string;
@@ -162,11 +187,22 @@ export interface CortexChatMessageEvent {
 	tool_calls?: CortexChatToolCall[];
 }
 
+export interface OllamaPullProgressEvent {
+	type: "ollama_pull_progress";
+	model: string;
+	status: string;
+	completed: number | null;
+	total: number | null;
+	done: boolean;
+	error: string | null;
+}
+
 export type ApiEvent =
 	| InboundMessageEvent
 	| OutboundMessageEvent
 	| OutboundMessageDeltaEvent
 	| TypingStateEvent
+	| TurnInterruptedEvent
 	| WorkerStartedEvent
 	| WorkerStatusEvent
 	| WorkerIdleEvent
@@ -177,7 +213,8 @@ export type ApiEvent =
 	| ToolCompletedEvent
 	| OpenCodePartUpdatedEvent
 	| WorkerTextEvent
-	| CortexChatMessageEvent;
+	| CortexChatMessageEvent
+	| OllamaPullProgressEvent;
 
 async function fetchJson<T>(path: string): Promise<T> {
 	const response = await fetch(`${API_BASE}${path}`);
@@ -204,6 +241,7 @@ export interface TimelineBranchRun {
 	conclusion: string | null;
 	started_at: string;
 	completed_at: string | null;
+	cache_hit: boolean;
 }
 
 export interface TimelineWorkerRun {
@@ -223,6 +261,32 @@ export interface MessagesResponse {
 	has_more: boolean;
 }
 
+export interface ConversationSearchResult {
+	agent_id: string;
+	id: string;
+	channel_id: string;
+	channel_name: string | null;
+	role: string;
+	sender_name: string | null;
+	content: string;
+	/** Excerpt with matched terms wrapped in <mark> tags. */
+	snippet: string;
+	rank: number;
+	created_at: string;
+}
+
+export interface ConversationSearchResponse {
+	results: ConversationSearchResult[];
+}
+
+export interface ConversationSearchParams {
+	agent_id?: string;
+	channel_id?: string;
+	before?: string;
+	after?: string;
+	limit?: number;
+}
+
 export interface WorkerStatusInfo {
 	id: string;
 	task: string;
@@ -474,10 +538,25 @@ export interface MemoriesListResponse {
 	total: number;
 }
 
+export interface MemorySourceScore {
+	rank: number;
+	score: number;
+	contribution: number;
+}
+
+export interface MemoryScoreExplanation {
+	vector: MemorySourceScore | null;
+	fts: MemorySourceScore | null;
+	graph: MemorySourceScore | null;
+	rrf_k: number;
+	min_score: number;
+}
+
 export interface MemorySearchResultItem {
 	memory: MemoryItem;
 	score: number;
 	rank: number;
+	explanation?: MemoryScoreExplanation;
 }
 
 export interface MemoriesSearchResponse {
@@ -534,6 +613,7 @@ export interface MemoriesListParams {
 export interface MemoriesSearchParams {
 	limit?: number;
 	memory_type?: MemoryType;
+	explain?: boolean;
 }
 
 export type CortexEventType =
@@ -609,6 +689,14 @@ export interface CortexChatThread {
 	message_count: number;
 	first_message_at: string;
 	last_message_at: string;
+	parent_thread_id: string | null;
+}
+
+export interface CortexChatThreadFork {
+	thread_id: string;
+	parent_thread_id: string;
+	forked_from_message_id: string;
+	message_count: number;
 }
 
 export interface CortexChatThreadsResponse {
@@ -703,6 +791,7 @@ export interface CoalesceSection {
 	max_wait_ms: number;
 	min_messages: number;
 	multi_user_only: boolean;
+	sender_debounce_ms: number;
 }
 
 export interface MemoryPersistenceSection {
@@ -741,6 +830,15 @@ export interface DiscordSection {
 	allow_bot_messages: boolean;
 }
 
+export interface AgentImportReport {
+	source_agent_id: string;
+	agent_created: boolean;
+	tables: Record<string, { read: number; inserted: number }>;
+	memories_embedded: number;
+	identity_files: string[];
+	identity_files_skipped: string[];
+}
+
 export interface AgentConfigResponse {
 	routing: RoutingSection;
 	tuning: TuningSection;
@@ -802,6 +900,7 @@ export interface CoalesceUpdate {
 	max_wait_ms?: number;
 	min_messages?: number;
 	multi_user_only?: boolean;
+	sender_debounce_ms?: number;
 }
 
 export interface MemoryPersistenceUpdate {
@@ -892,6 +991,68 @@ export interface CronActionResponse {
 	message: string;
 }
 
+export type ConfigFileKind = "config" | "identity" | "skill";
+
+export interface ConfigChangeSummary {
+	version: number;
+	changed_at: string;
+	kind: ConfigFileKind;
+	path: string;
+	agent_id: string | null;
+	lines_added: number;
+	lines_removed: number;
+	reverted_version: number | null;
+}
+
+export interface ConfigHistoryResponse {
+	changes: ConfigChangeSummary[];
+}
+
+export interface ConfigHistoryEntry extends ConfigChangeSummary {
+	before: string | null;
+	after: string | null;
+	diff: string;
+}
+
+export interface RevertConfigResponse {
+	success: boolean;
+	version: number | null;
+	path: string;
+	message: string;
+}
+
+export interface JobResult {
+	success: boolean;
+	summary: string;
+}
+
+export interface JobSnapshot {
+	name: string;
+	description: string;
+	enabled: boolean;
+	running: boolean;
+	interval_secs: number | null;
+	next_run_at: string | null;
+	last_started_at: string | null;
+	last_finished_at: string | null;
+	last_result: JobResult | null;
+	run_count: number;
+}
+
+export interface AgentJobs {
+	agent_id: string;
+	jobs: JobSnapshot[];
+}
+
+export interface JobsResponse {
+	agents: AgentJobs[];
+}
+
+export interface JobActionResponse {
+	success: boolean;
+	message: string;
+}
+
 export interface CreateCronRequest {
 	id: string;
 	prompt: string;
@@ -935,9 +1096,39 @@ export interface ProviderStatus {
 	github_copilot: boolean;
 }
 
+export interface ProviderQuotaStatus {
+	month: string;
+	tokens_used: number;
+	cost_usd: number;
+	monthly_tokens: number | null;
+	monthly_usd: number | null;
+	exceeded: boolean;
+}
+
+export interface ProviderProbeStatus {
+	reachable: boolean;
+	auth_error: boolean;
+	status: number | null;
+	latency_ms: number;
+	error: string | null;
+	consecutive_failures: number;
+	checked_at: string;
+}
+
 export interface ProvidersResponse {
 	providers: ProviderStatus;
 	has_any: boolean;
+	quotas: Record<string, ProviderQuotaStatus>;
+	health: Record<string, ProviderProbeStatus>;
+	response_cache: ResponseCacheStats;
+}
+
+export interface ResponseCacheStats {
+	enabled: boolean;
+	entries: number;
+	hits: number;
+	misses: number;
+	tokens_saved: number;
 }
 
 export interface ProviderActionResponse {
@@ -945,6 +1136,27 @@ export interface ProviderActionResponse {
 	message: string;
 }
 
+export interface OllamaModelEntry {
+	name: string;
+	size: number;
+	digest: string;
+	modified_at: string | null;
+	details: {
+		family: string | null;
+		parameter_size: string | null;
+		quantization_level: string | null;
+	};
+	loaded: boolean;
+	loaded_size: number | null;
+	size_vram: number | null;
+	expires_at: string | null;
+}
+
+export interface OllamaModelsResponse {
+	models: OllamaModelEntry[];
+	vram_used: number;
+}
+
 export interface ProviderModelTestResponse {
 	success: boolean;
 	message: string;
@@ -1009,6 +1221,75 @@ export interface IngestDeleteResponse {
 	success: boolean;
 }
 
+// -- Storage Types --
+
+export type StorageLevel = "ok" | "warning" | "exceeded";
+
+export interface AgentDiskUsage {
+	database_bytes: number;
+	memory_index_bytes: number;
+	attachments_bytes: number;
+	screenshots_bytes: number;
+	logs_bytes: number;
+	archives_bytes: number;
+	other_bytes: number;
+	total_bytes: number;
+	quota_bytes: number | null;
+	percent_used: number | null;
+	level: StorageLevel;
+	measured_at: string;
+}
+
+export interface StorageUsageResponse {
+	agent_id: string;
+	usage: AgentDiskUsage;
+}
+
+export interface StorageCleanupReport {
+	logs_removed: number;
+	screenshots_removed: number;
+	bytes_freed: number;
+	database_checkpointed: boolean;
+	memory_index_optimized: boolean;
+}
+
+export interface StorageCleanupResponse {
+	agent_id: string;
+	report: StorageCleanupReport;
+	usage: AgentDiskUsage;
+}
+
+export interface VectorTableReport {
+	table: "memories" | "episodes";
+	optimized: boolean;
+	error?: string;
+	fragments_removed: number;
+	fragments_added: number;
+	versions_pruned: number;
+	bytes_reclaimed: number;
+}
+
+export interface VectorOptimizeResponse {
+	results: { agent_id: string; tables: VectorTableReport[] }[];
+}
+
+export interface AppliedMigration {
+	version: number;
+	description: string;
+	installed_on: string;
+	success: boolean;
+	execution_time_ms: number;
+}
+
+export interface AgentSchemaResponse {
+	agent_id: string;
+	current_version: number | null;
+	latest_known_version: number | null;
+	applied: AppliedMigration[];
+	pending: { version: number; description: string }[];
+	unknown: number[];
+}
+
 // -- Skills Types --
 
 export interface SkillInfo {
@@ -1230,6 +1511,8 @@ export interface BindingInfo {
 	channel_ids: string[];
 	require_mention: boolean;
 	dm_allowed_users: string[];
+	mention: string | null;
+	prefix: string | null;
 }
 
 export interface BindingsListResponse {
@@ -1246,6 +1529,8 @@ export interface CreateBindingRequest {
 	channel_ids?: string[];
 	require_mention?: boolean;
 	dm_allowed_users?: string[];
+	mention?: string;
+	prefix?: string;
 	platform_credentials?: {
 		discord_token?: string;
 		slack_bot_token?: string;
@@ -1291,6 +1576,8 @@ export interface UpdateBindingRequest {
 	channel_ids?: string[];
 	require_mention?: boolean;
 	dm_allowed_users?: string[];
+	mention?: string;
+	prefix?: string;
 }
 
 export interface UpdateBindingResponse {
@@ -1669,12 +1956,30 @@ export const api = {
 		if (!response.ok) throw new Error(`API error: ${response.status}`);
 		return response.json() as Promise<{ success: boolean }>;
 	},
+	setChannelRouting: async (agentId: string, channelId: string, routing: ChannelRouting) => {
+		const response = await fetch(`${API_BASE}/channels/routing`, {
+			method: "PUT",
+			headers: { "Content-Type": "application/json" },
+			body: JSON.stringify({ agent_id: agentId, channel_id: channelId, ...routing }),
+		});
+		if (!response.ok) throw new Error(`API error: ${response.status}`);
+		return response.json() as Promise<ChannelRoutingResponse>;
+	},
 	channelMessages: (channelId: string, limit = 20, before?: string) => {
 		const params = new URLSearchParams({ channel_id: channelId, limit: String(limit) });
 		if (before) params.set("before", before);
 		return fetchJson<MessagesResponse>(`/channels/messages?${params}`);
 	},
 	channelStatus: () => fetchJson<ChannelStatusResponse>("/channels/status"),
+	searchConversations: (query: string, params: ConversationSearchParams = {}) => {
+		const search = new URLSearchParams({ q: query });
+		if (params.agent_id) search.set("agent_id", params.agent_id);
+		if (params.channel_id) search.set("channel_id", params.channel_id);
+		if (params.before) search.set("before", params.before);
+		if (params.after) search.set("after", params.after);
+		if (params.limit) search.set("limit", String(params.limit));
+		return fetchJson<ConversationSearchResponse>(`/conversations/search?${search}`);
+	},
 	inspectPrompt: (channelId: string) =>
 		fetchJson<PromptInspectResponse>(`/channels/inspect?channel_id=${encodeURIComponent(channelId)}`),
 	setPromptCapture: async (channelId: string, enabled: boolean) => {
@@ -1715,6 +2020,7 @@ export const api = {
 		const search = new URLSearchParams({ agent_id: agentId, q: query });
 		if (params.limit) search.set("limit", String(params.limit));
 		if (params.memory_type) search.set("memory_type", params.memory_type);
+		if (params.explain) search.set("explain", "true");
 		return fetchJson<MemoriesSearchResponse>(`/agents/memories/search?${search}`);
 	},
 	memoryGraph: (agentId: string, params: MemoryGraphParams = {}) => {
@@ -1766,6 +2072,15 @@ export const api = {
 		});
 		if (!response.ok) throw new Error(`HTTP ${response.status}`);
 	},
+	cortexChatForkThread: async (agentId: string, threadId: string, messageId: string) => {
+		const response = await fetch(`${API_BASE}/cortex-chat/thread/fork`, {
+			method: "POST",
+			headers: { "Content-Type": "application/json" },
+			body: JSON.stringify({ agent_id: agentId, thread_id: threadId, message_id: messageId }),
+		});
+		if (!response.ok) throw new Error(`HTTP ${response.status}`);
+		return response.json() as Promise<CortexChatThreadFork>;
+	},
 	agentProfile: (agentId: string) =>
 		fetchJson<AgentProfileResponse>(`/agents/profile?agent_id=${encodeURIComponent(agentId)}`),
 	agentIdentity: (agentId: string) =>
@@ -1845,6 +2160,34 @@ export const api = {
 		return response.json() as Promise<{ success: boolean; message: string }>;
 	},
 
+	/** Download a portable export archive of an agent's state. */
+	exportAgent: async (agentId: string) => {
+		const response = await fetch(`${API_BASE}/agents/${encodeURIComponent(agentId)}/export`, {
+			method: "POST",
+		});
+		if (!response.ok) {
+			throw new Error(`API error: ${response.status}`);
+		}
+		return response.blob();
+	},
+
+	/** Import an agent export archive, creating the agent if it doesn't exist. */
+	importAgent: async (agentId: string, archive: Blob, overwriteIdentity = false) => {
+		const params = new URLSearchParams({
+			agent_id: agentId,
+			overwrite_identity: String(overwriteIdentity),
+		});
+		const response = await fetch(`${API_BASE}/agents/import?${params}`, {
+			method: "POST",
+			headers: { "Content-Type": "application/gzip" },
+			body: archive,
+		});
+		if (!response.ok) {
+			throw new Error(`API error: ${response.status}`);
+		}
+		return response.json() as Promise<AgentImportReport>;
+	},
+
 	agentConfig: (agentId: string) =>
 		fetchJson<AgentConfigResponse>(`/agents/config?agent_id=${encodeURIComponent(agentId)}`),
 	updateAgentConfig: async (request: AgentConfigUpdateRequest) => {
@@ -1917,6 +2260,71 @@ export const api = {
 		return response.json() as Promise<CronActionResponse>;
 	},
 
+	configHistory: async (agentId?: string, limit = 50) => {
+		const search = new URLSearchParams({ limit: String(limit) });
+		if (agentId) search.set("agent_id", agentId);
+		const response = await fetch(`${API_BASE}/config/history?${search}`);
+		if (!response.ok) {
+			throw new Error(`API error: ${response.status}`);
+		}
+		return response.json() as Promise<ConfigHistoryResponse>;
+	},
+
+	configHistoryEntry: async (version: number) => {
+		const search = new URLSearchParams({ version: String(version) });
+		const response = await fetch(`${API_BASE}/config/history/entry?${search}`);
+		if (!response.ok) {
+			throw new Error(`API error: ${response.status}`);
+		}
+		return response.json() as Promise<ConfigHistoryEntry>;
+	},
+
+	revertConfigChange: async (version: number) => {
+		const response = await fetch(`${API_BASE}/config/history/revert`, {
+			method: "POST",
+			headers: { "Content-Type": "application/json" },
+			body: JSON.stringify({ version }),
+		});
+		if (!response.ok) {
+			throw new Error(`API error: ${response.status}`);
+		}
+		return response.json() as Promise<RevertConfigResponse>;
+	},
+
+	listJobs: async (agentId?: string) => {
+		const search = new URLSearchParams();
+		if (agentId) search.set("agent_id", agentId);
+		const response = await fetch(`${API_BASE}/jobs?${search}`);
+		if (!response.ok) {
+			throw new Error(`API error: ${response.status}`);
+		}
+		return response.json() as Promise<JobsResponse>;
+	},
+
+	triggerJob: async (agentId: string, name: string) => {
+		const response = await fetch(`${API_BASE}/jobs/trigger`, {
+			method: "POST",
+			headers: { "Content-Type": "application/json" },
+			body: JSON.stringify({ agent_id: agentId, name }),
+		});
+		if (!response.ok) {
+			throw new Error(`API error: ${response.status}`);
+		}
+		return response.json() as Promise<JobActionResponse>;
+	},
+
+	toggleJob: async (agentId: string, name: string, enabled: boolean) => {
+		const response = await fetch(`${API_BASE}/jobs/toggle`, {
+			method: "PUT",
+			headers: { "Content-Type": "application/json" },
+			body: JSON.stringify({ agent_id: agentId, name, enabled }),
+		});
+		if (!response.ok) {
+			throw new Error(`API error: ${response.status}`);
+		}
+		return response.json() as Promise<JobActionResponse>;
+	},
+
 	cancelProcess: async (channelId: string, processType: "worker" | "branch", processId: string) => {
 		const response = await fetch(`${API_BASE}/channels/cancel`, {
 			method: "POST",
@@ -1931,11 +2339,11 @@ export const api = {
 
 	// Provider management
 	providers: () => fetchJson<ProvidersResponse>("/providers"),
-	updateProvider: async (provider: string, apiKey: string, model: string) => {
+	updateProvider: async (provider: string, apiKey: string, model: string, validate = false) => {
 		const response = await fetch(`${API_BASE}/providers`, {
 			method: "PUT",
 			headers: { "Content-Type": "application/json" },
-			body: JSON.stringify({ provider, api_key: apiKey, model }),
+			body: JSON.stringify({ provider, api_key: apiKey, model, validate }),
 		});
 		if (!response.ok) {
 			throw new Error(`API error: ${response.status}`);
@@ -1984,6 +2392,19 @@ export const api = {
 		}
 		return response.json() as Promise<ProviderActionResponse>;
 	},
+	ollamaModels: () => fetchJson<OllamaModelsResponse>("/ollama/models"),
+	/** Progress streams back on the response as SSE `progress` and `done` events. */
+	pullOllamaModel: async (model: string) => {
+		const response = await fetch(`${API_BASE}/ollama/pull`, {
+			method: "POST",
+			headers: { "Content-Type": "application/json" },
+			body: JSON.stringify({ model }),
+		});
+		if (!response.ok) {
+			throw new Error(`API error: ${response.status}`);
+		}
+		return response;
+	},
 
 	// Model listing
 	models: (provider?: string, capability?: "input_audio" | "voice_transcription") => {
@@ -2033,6 +2454,45 @@ export const api = {
 		return response.json() as Promise<IngestDeleteResponse>;
 	},
 
+	// Storage API
+	agentStorage: (agentId: string, refresh = false) => {
+		const params = new URLSearchParams({ agent_id: agentId });
+		if (refresh) params.set("refresh", "true");
+		return fetchJson<StorageUsageResponse>(`/agents/storage?${params}`);
+	},
+
+	cleanupAgentStorage: async (agentId: string) => {
+		const response = await fetch(`${API_BASE}/agents/storage/cleanup`, {
+			method: "POST",
+			headers: { "Content-Type": "application/json" },
+			body: JSON.stringify({ agent_id: agentId }),
+		});
+		if (!response.ok) {
+			throw new Error(`API error: ${response.status}`);
+		}
+		return response.json() as Promise<StorageCleanupResponse>;
+	},
+
+	optimizeVectorTables: async (agentId?: string) => {
+		const response = await fetch(`${API_BASE}/vector/optimize`, {
+			method: "POST",
+			headers: { "Content-Type": "application/json" },
+			body: JSON.stringify(agentId ? { agent_id: agentId } : {}),
+		});
+		if (!response.ok) {
+			throw new Error(`API error: ${response.status}`);
+		}
+		return response.json() as Promise<VectorOptimizeResponse>;
+	},
+
+	agentSchema: async (agentId: string) => {
+		const response = await fetch(`${API_BASE}/agents/${encodeURIComponent(agentId)}/schema`);
+		if (!response.ok) {
+			throw new Error(`API error: ${response.status}`);
+		}
+		return response.json() as Promise<AgentSchemaResponse>;
+	},
+
 	// Messaging / Bindings API
 	messagingStatus: () => fetchJson<MessagingStatusResponse>("/messaging/status"),
 
@@ -2374,9 +2834,33 @@ export const api = {
 			}),
 		}),
 
+	/** Send a message with attachments. Audio files are transcribed before
+	 *  the agent sees them. */
+	webChatUpload: (agentId: string, sessionId: string, files: File[], message?: string, senderName?: string) => {
+		const formData = new FormData();
+		formData.append("agent_id", agentId);
+		formData.append("session_id", sessionId);
+		formData.append("sender_name", senderName ?? "user");
+		if (message) formData.append("message", message);
+		for (const file of files) {
+			formData.append("files", file);
+		}
+		return fetch(`${API_BASE}/webchat/upload`, { method: "POST", body: formData });
+	},
+
 	webChatHistory: (agentId: string, sessionId: string, limit = 100) =>
 		fetch(`${API_BASE}/webchat/history?agent_id=${encodeURIComponent(agentId)}&session_id=${encodeURIComponent(sessionId)}&limit=${limit}`),
 
+	/** Open a persistent webchat socket. Send `{type: "message", message}` frames;
+	 * session events arrive in the same shape as the SSE stream. */
+	webChatSocket: (agentId: string, sessionId: string, senderName?: string) => {
+		const params = new URLSearchParams({ agent_id: agentId, session_id: sessionId });
+		if (senderName) params.set("sender_name", senderName);
+		const url = new URL(`${API_BASE}/webchat/ws?${params}`, window.location.href);
+		url.protocol = url.protocol === "https:" ? "wss:" : "ws:";
+		return new WebSocket(url);
+	},
+
 	// Tasks API
 	listTasks: (agentId: string, params?: { status?: TaskStatus; priority?: TaskPriority; limit?: number }) => {
 		const search = new URLSearchParams({ agent_id: agentId });
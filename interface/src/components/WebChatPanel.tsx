@@ -256,7 +256,7 @@ export function WebChatPanel({agentId}: WebChatPanelProps) {
 				value={input}
 				onChange={setInput}
 				onSubmit={handleSubmit}
-				disabled={isSending || isTyping}
+				disabled={isSending}
 				agentId={agentId}
 			/>
 		</div>
@@ -10,6 +10,7 @@ import {
 	type TimelineItem,
 	type ToolCompletedEvent,
 	type ToolStartedEvent,
+	type TurnInterruptedEvent,
 	type TypingStateEvent,
 	type WorkerCompletedEvent,
 	type WorkerStartedEvent,
@@ -53,6 +54,8 @@ export interface ChannelLiveState {
 	workers: Record<string, ActiveWorker>;
 	branches: Record<string, ActiveBranch>;
 	streamingMessageId: string | null;
+	/** Whether the running turn was interrupted by a new message and is restarting. */
+	interrupted: boolean;
 	historyLoaded: boolean;
 	hasMore: boolean;
 	loadingMore: boolean;
@@ -67,6 +70,7 @@ function emptyLiveState(): ChannelLiveState {
 		workers: {},
 		branches: {},
 		streamingMessageId: null,
+		interrupted: false,
 		historyLoaded: false,
 		hasMore: true,
 		loadingMore: false,
@@ -365,7 +369,36 @@ export function useChannelLiveState(channels: ChannelInfo[]) {
 		const event = data as TypingStateEvent;
 		setLiveStates((prev) => {
 			const existing = getOrCreate(prev, event.channel_id);
-			return { ...prev, [event.channel_id]: { ...existing, isTyping: event.is_typing } };
+			return {
+				...prev,
+				[event.channel_id]: {
+					...existing,
+					isTyping: event.is_typing,
+					interrupted: event.is_typing && existing.interrupted,
+				},
+			};
+		});
+	}, []);
+
+	const handleTurnInterrupted = useCallback((data: unknown) => {
+		const event = data as TurnInterruptedEvent;
+		setLiveStates((prev) => {
+			const existing = getOrCreate(prev, event.channel_id);
+			// The interrupted turn never sent its draft, so drop it.
+			const timeline = existing.streamingMessageId
+				? existing.timeline.filter(
+						(item) => !(item.type === "message" && item.id === existing.streamingMessageId),
+					)
+				: existing.timeline;
+			return {
+				...prev,
+				[event.channel_id]: {
+					...existing,
+					timeline,
+					streamingMessageId: null,
+					interrupted: true,
+				},
+			};
 		});
 	}, []);
 
@@ -569,6 +602,7 @@ export function useChannelLiveState(channels: ChannelInfo[]) {
 			conclusion: null,
 			started_at: new Date().toISOString(),
 			completed_at: null,
+			cache_hit: false,
 		});
 	}, [pushItem]);
 
@@ -807,6 +841,7 @@ export function useChannelLiveState(channels: ChannelInfo[]) {
 		outbound_message: handleOutboundMessage,
 		outbound_message_delta: handleOutboundMessageDelta,
 		typing_state: handleTypingState,
+		turn_interrupted: handleTurnInterrupted,
 		worker_started: handleWorkerStarted,
 		worker_status: handleWorkerStatus,
 		worker_idle: handleWorkerIdle,
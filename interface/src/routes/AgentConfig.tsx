@@ -1120,6 +1120,15 @@ function ConfigSectionEditor({ sectionId, label, description, detail, config, on
 							value={localValues.multi_user_only as boolean}
 							onChange={(v) => handleChange("multi_user_only", v)}
 						/>
+						<NumberStepper
+							label="Sender Debounce"
+							description="Wait for one sender to pause before replying, in DMs too (0 disables)"
+							value={localValues.sender_debounce_ms as number}
+							onChange={(v) => handleChange("sender_debounce_ms", v)}
+							min={0}
+							max={10000}
+							suffix="ms"
+						/>
 					</div>
 				);
 			case "memory":
@@ -158,6 +158,11 @@ function BranchRunItem({ item }: { item: TimelineBranchRun }) {
 						}`}>
 							{item.description}
 						</span>
+						{item.cache_hit && (
+							<span className="flex-shrink-0 self-start text-tiny leading-5 text-violet-300/70">
+								cached
+							</span>
+						)}
 						{item.conclusion && (
 							<span className="flex-shrink-0 self-start text-tiny leading-5 text-ink-faint">
 								{expanded ? "▾" : "▸"}
@@ -379,7 +384,9 @@ export function ChannelDetail({ agentId, channelId, channel, liveState, onLoadMo
 								<span className="inline-block h-1.5 w-1.5 animate-pulse rounded-full bg-accent" />
 								<span className="inline-block h-1.5 w-1.5 animate-pulse rounded-full bg-accent [animation-delay:0.2s]" />
 								<span className="inline-block h-1.5 w-1.5 animate-pulse rounded-full bg-accent [animation-delay:0.4s]" />
-								<span className="ml-1 text-tiny text-ink-faint">typing</span>
+								<span className="ml-1 text-tiny text-ink-faint">
+									{liveState?.interrupted ? "restarting" : "typing"}
+								</span>
 							</div>
 						)}
 						<div className="flex overflow-hidden rounded-md border border-app-line bg-app-darkBox">
@@ -0,0 +1,40 @@
+-- Full-text index over conversation messages for keyword search across channels.
+--
+-- External-content FTS5 table keyed by the implicit rowid of
+-- conversation_messages. Triggers keep the index in sync with inserts,
+-- updates, and deletes. If rowids are ever renumbered (e.g. VACUUM), the
+-- index must be rebuilt with:
+--   INSERT INTO conversation_messages_fts(conversation_messages_fts) VALUES('rebuild');
+CREATE VIRTUAL TABLE IF NOT EXISTS conversation_messages_fts USING fts5(
+    content,
+    sender_name,
+    content = 'conversation_messages',
+    content_rowid = 'rowid',
+    tokenize = 'porter unicode61'
+);
+
+CREATE TRIGGER IF NOT EXISTS conversation_messages_fts_insert
+AFTER INSERT ON conversation_messages
+BEGIN
+    INSERT INTO conversation_messages_fts(rowid, content, sender_name)
+    VALUES (new.rowid, new.content, new.sender_name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS conversation_messages_fts_delete
+AFTER DELETE ON conversation_messages
+BEGIN
+    INSERT INTO conversation_messages_fts(conversation_messages_fts, rowid, content, sender_name)
+    VALUES ('delete', old.rowid, old.content, old.sender_name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS conversation_messages_fts_update
+AFTER UPDATE OF content, sender_name ON conversation_messages
+BEGIN
+    INSERT INTO conversation_messages_fts(conversation_messages_fts, rowid, content, sender_name)
+    VALUES ('delete', old.rowid, old.content, old.sender_name);
+    INSERT INTO conversation_messages_fts(rowid, content, sender_name)
+    VALUES (new.rowid, new.content, new.sender_name);
+END;
+
+-- Backfill existing messages.
+INSERT INTO conversation_messages_fts(conversation_messages_fts) VALUES ('rebuild');
@@ -0,0 +1,11 @@
+-- Fork links for cortex chat threads. Threads are implicit (a thread_id on
+-- each message); this records where a forked thread branched off.
+
+CREATE TABLE IF NOT EXISTS cortex_chat_thread_forks (
+    thread_id TEXT PRIMARY KEY,
+    parent_thread_id TEXT NOT NULL,
+    forked_from_message_id TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_cortex_chat_thread_forks_parent ON cortex_chat_thread_forks(parent_thread_id);
@@ -0,0 +1,3 @@
+-- Per-channel model overrides, stored as a JSON object keyed by process
+-- type (channel, branch, worker, compactor). NULL means no override.
+ALTER TABLE channels ADD COLUMN routing TEXT;
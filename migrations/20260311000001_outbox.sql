@@ -0,0 +1,31 @@
+-- Durable outbound delivery queue. A row is written before a response is
+-- handed to the messaging adapter and removed once the platform accepts it,
+-- so responses that were in flight during a crash are replayed on startup.
+-- `adapter` and `target` are the resolved broadcast destination; the inbound
+-- message that triggered the response is not stored.
+CREATE TABLE IF NOT EXISTS outbox (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    channel_id TEXT NOT NULL,
+    adapter TEXT NOT NULL,
+    response TEXT NOT NULL,
+    target TEXT NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_outbox_channel ON outbox(channel_id, id);
+
+-- Dead letters: responses that failed permanently or ran out of retries.
+CREATE TABLE IF NOT EXISTS outbox_failures (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    channel_id TEXT NOT NULL,
+    adapter TEXT NOT NULL,
+    response TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    error TEXT NOT NULL,
+    enqueued_at TIMESTAMP NOT NULL,
+    failed_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_outbox_failures_failed_at ON outbox_failures(failed_at);
@@ -0,0 +1,3 @@
+-- Per-channel response pacing override ("instant", "casual", "relaxed").
+-- NULL means the agent's `[channel] pace` default applies.
+ALTER TABLE channels ADD COLUMN pace TEXT;
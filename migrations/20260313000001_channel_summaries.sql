@@ -0,0 +1,34 @@
+-- Summaries of archived channel history. Each row covers one contiguous
+-- segment of a channel's oldest messages, written by the archival job before
+-- the raw rows are moved out of conversation_messages.
+CREATE TABLE IF NOT EXISTS channel_summaries (
+    id TEXT PRIMARY KEY,
+    channel_id TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    message_count INTEGER NOT NULL,
+    first_message_at TIMESTAMP NOT NULL,
+    last_message_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_channel_summaries_channel_time
+    ON channel_summaries(channel_id, first_message_at);
+
+-- Cold storage for archived raw messages. Same columns as
+-- conversation_messages, but not loaded into channel history and not part of
+-- the full-text index.
+CREATE TABLE IF NOT EXISTS conversation_messages_archive (
+    id TEXT PRIMARY KEY,
+    channel_id TEXT NOT NULL,
+    role TEXT NOT NULL,
+    sender_name TEXT,
+    sender_id TEXT,
+    content TEXT NOT NULL,
+    metadata TEXT,
+    created_at TIMESTAMP NOT NULL,
+    summary_id TEXT NOT NULL,
+    archived_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_messages_archive_channel_time
+    ON conversation_messages_archive(channel_id, created_at);
@@ -0,0 +1,29 @@
+-- Long-term profiles for the people an agent talks to, keyed by platform and
+-- platform user ID. Created the first time someone speaks; facts are added by
+-- memory persistence branches and reviewed or edited through the API.
+CREATE TABLE IF NOT EXISTS user_profiles (
+    id TEXT PRIMARY KEY,              -- "{platform}:{sender_id}"
+    platform TEXT NOT NULL,
+    sender_id TEXT NOT NULL,
+    display_name TEXT,
+    timezone TEXT,                    -- IANA name, e.g. "Europe/Berlin"
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_seen_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_profiles_platform_name
+    ON user_profiles(platform, display_name);
+
+CREATE TABLE IF NOT EXISTS user_profile_facts (
+    id TEXT PRIMARY KEY,
+    profile_id TEXT NOT NULL REFERENCES user_profiles(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,               -- preference | role | fact
+    content TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(profile_id, kind, content)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_profile_facts_profile
+    ON user_profile_facts(profile_id);
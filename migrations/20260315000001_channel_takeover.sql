@@ -0,0 +1,4 @@
+-- When an operator took over the channel from the agent. NULL means the
+-- agent is in control; while set, inbound messages are recorded but the
+-- agent doesn't reply.
+ALTER TABLE channels ADD COLUMN takeover_started_at TIMESTAMP;
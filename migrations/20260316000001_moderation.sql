@@ -0,0 +1,19 @@
+-- Per-channel moderation strictness override ("off", "lenient", "standard",
+-- "strict"). NULL means the agent's `[moderation] strictness` applies.
+ALTER TABLE channels ADD COLUMN moderation TEXT;
+
+-- Audit log of outbound replies that moderation flagged, rewrote, or blocked.
+CREATE TABLE IF NOT EXISTS moderation_events (
+    id TEXT PRIMARY KEY,
+    channel_id TEXT NOT NULL,
+    action TEXT NOT NULL,             -- flag | rewrite | block
+    source TEXT NOT NULL,             -- rules | classifier
+    rules TEXT,                       -- comma-separated names of matched rules
+    strictness TEXT NOT NULL,
+    original_content TEXT NOT NULL,
+    delivered_content TEXT,           -- NULL when the reply was blocked
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_moderation_events_channel
+    ON moderation_events(channel_id, created_at);
@@ -0,0 +1,13 @@
+-- Edited versions of prompt templates. The latest version of a template is
+-- the one in use; a NULL content means it was reset to the bundled template.
+-- Rolling back saves an older version's content as a new version, so the
+-- history only ever grows.
+CREATE TABLE IF NOT EXISTS prompt_template_versions (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,               -- template name, e.g. "channel"
+    version INTEGER NOT NULL,
+    content TEXT,
+    note TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(name, version)
+);
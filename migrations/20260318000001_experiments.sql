@@ -0,0 +1,40 @@
+-- A/B experiments comparing two variants of the channel prompt or model.
+-- At most one experiment per agent runs at a time.
+CREATE TABLE IF NOT EXISTS experiments (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    template TEXT,                    -- prompt template the variants replace
+    variant_a TEXT NOT NULL,          -- JSON: {"prompt": ..., "model": ...}
+    variant_b TEXT NOT NULL,
+    traffic_b INTEGER NOT NULL,       -- percent of channels assigned to B
+    status TEXT NOT NULL DEFAULT 'running',  -- running | stopped
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    stopped_at TIMESTAMP
+);
+
+-- The variant each channel got. Assigned on the channel's first turn during
+-- the experiment, or pinned through the API.
+CREATE TABLE IF NOT EXISTS experiment_assignments (
+    experiment_id TEXT NOT NULL REFERENCES experiments(id) ON DELETE CASCADE,
+    channel_id TEXT NOT NULL,
+    variant TEXT NOT NULL,            -- a | b
+    pinned INTEGER NOT NULL DEFAULT 0,
+    assigned_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (experiment_id, channel_id)
+);
+
+-- One row per channel turn or piece of feedback.
+CREATE TABLE IF NOT EXISTS experiment_outcomes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    experiment_id TEXT NOT NULL REFERENCES experiments(id) ON DELETE CASCADE,
+    channel_id TEXT NOT NULL,
+    variant TEXT NOT NULL,
+    kind TEXT NOT NULL,               -- turn | feedback
+    tool_calls INTEGER NOT NULL DEFAULT 0,
+    tool_errors INTEGER NOT NULL DEFAULT 0,
+    score INTEGER NOT NULL DEFAULT 0, -- feedback: 1 positive, -1 negative
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_experiment_outcomes_experiment
+    ON experiment_outcomes(experiment_id, variant);
@@ -0,0 +1,10 @@
+-- Facts pinned into the memory bulletin by the cortex or an admin. Pinned
+-- items are rendered ahead of the generated bulletin in every prompt until
+-- they are unpinned or expire.
+CREATE TABLE IF NOT EXISTS bulletin_pins (
+    id TEXT PRIMARY KEY,
+    content TEXT NOT NULL,
+    pinned_by TEXT NOT NULL,          -- cortex | api
+    expires_at TIMESTAMP,             -- NULL pins never expire
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
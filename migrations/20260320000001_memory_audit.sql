@@ -0,0 +1,13 @@
+-- Trail of manual memory edits made through the API. Each row stores the
+-- memory as it was before and after the change, as JSON.
+CREATE TABLE IF NOT EXISTS memory_audit (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    memory_id TEXT NOT NULL,
+    action TEXT NOT NULL,             -- create | update | forget
+    actor TEXT NOT NULL,
+    before_json TEXT,                 -- NULL for create
+    after_json TEXT,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_memory_audit_memory ON memory_audit(memory_id, id);
@@ -0,0 +1,10 @@
+-- Memory visibility. Global memories are readable from every channel and
+-- feed the bulletin; channel-scoped memories are readable only from the
+-- channel in `channel_id`.
+ALTER TABLE memories ADD COLUMN scope TEXT NOT NULL DEFAULT 'global';
+
+CREATE INDEX IF NOT EXISTS idx_memories_scope ON memories(scope, channel_id);
+
+-- Per-channel override of `[defaults.channel] memory_scope`. NULL uses the
+-- agent default.
+ALTER TABLE channels ADD COLUMN memory_scope TEXT;
@@ -0,0 +1,10 @@
+-- Channels whose conversations never form memories automatically.
+ALTER TABLE channels ADD COLUMN ephemeral INTEGER NOT NULL DEFAULT 0;
+
+-- How far the fact extraction pass has read each channel's transcript, as a
+-- conversation_messages rowid.
+CREATE TABLE IF NOT EXISTS fact_extraction_state (
+    channel_id TEXT PRIMARY KEY,
+    extracted_through_rowid INTEGER NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,14 @@
+-- Writes to the LanceDB embeddings table, recorded before they are applied so
+-- a write cut short by a crash can be repaired on restart.
+CREATE TABLE IF NOT EXISTS embedding_journal (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    memory_id TEXT NOT NULL,
+    -- 'upsert' or 'delete'
+    operation TEXT NOT NULL,
+    -- LanceDB table version once the write landed; NULL while in flight.
+    applied_version INTEGER,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_embedding_journal_applied
+    ON embedding_journal(applied_version);
@@ -0,0 +1,16 @@
+-- Requests a team coordinator fanned out to its members with team_dispatch.
+CREATE TABLE IF NOT EXISTS team_runs (
+    id TEXT PRIMARY KEY,
+    team_id TEXT NOT NULL,
+    -- Coordinator worker that dispatched the run; its result is the synthesis.
+    worker_id TEXT,
+    request TEXT NOT NULL,
+    -- 'running', 'completed', 'partial' or 'failed'
+    status TEXT NOT NULL DEFAULT 'running',
+    -- JSON array of per-member assignments and results.
+    members TEXT NOT NULL DEFAULT '[]',
+    started_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    completed_at TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_team_runs_team ON team_runs(team_id, started_at);
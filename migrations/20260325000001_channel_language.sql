@@ -0,0 +1,5 @@
+-- Per-channel reply language, as ISO 639-3 codes ("eng", "spa", "deu").
+-- `language` is set through the API and wins over `detected_language`, which
+-- tracks the language people in the channel are writing in.
+ALTER TABLE channels ADD COLUMN language TEXT;
+ALTER TABLE channels ADD COLUMN detected_language TEXT;
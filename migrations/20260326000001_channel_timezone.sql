@@ -0,0 +1,9 @@
+-- Per-channel timezone, as IANA names ("Europe/Berlin"). `timezone` is set
+-- through the API and wins over `inferred_timezone`, which tracks what the
+-- platform or people in the channel have said about their timezone.
+ALTER TABLE channels ADD COLUMN timezone TEXT;
+ALTER TABLE channels ADD COLUMN inferred_timezone TEXT;
+
+-- Cron jobs created from a channel run on that channel's clock instead of the
+-- agent's cron_timezone.
+ALTER TABLE cron_jobs ADD COLUMN timezone TEXT;
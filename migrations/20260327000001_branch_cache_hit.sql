@@ -0,0 +1,2 @@
+-- Branch runs answered from the branch result cache instead of running.
+ALTER TABLE branch_runs ADD COLUMN cache_hit INTEGER NOT NULL DEFAULT 0;
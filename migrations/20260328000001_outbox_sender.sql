@@ -0,0 +1,7 @@
+-- The user whose message a queued response answers, so erasing a user can
+-- remove responses still waiting to be delivered to them.
+ALTER TABLE outbox ADD COLUMN sender_id TEXT;
+ALTER TABLE outbox_failures ADD COLUMN sender_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_outbox_sender ON outbox(sender_id);
+CREATE INDEX IF NOT EXISTS idx_outbox_failures_sender ON outbox_failures(sender_id);
@@ -0,0 +1,57 @@
+-- Coordination tables shared by every node in a cluster.
+-- Timestamps are RFC 3339 UTC with milliseconds, so they compare as text.
+
+-- One row per node, refreshed by its heartbeat. `status` is a JSON snapshot
+-- of the node's load for the dashboard.
+CREATE TABLE IF NOT EXISTS cluster_nodes (
+    node_id TEXT PRIMARY KEY,
+    advertise_url TEXT,
+    version TEXT NOT NULL,
+    started_at TEXT NOT NULL,
+    last_seen TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT '{}'
+);
+
+-- Which node handles a conversation. Held while the node is alive and
+-- taken over by another node once it expires.
+CREATE TABLE IF NOT EXISTS conversation_leases (
+    conversation_key TEXT PRIMARY KEY,
+    node_id TEXT NOT NULL,
+    expires_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_conversation_leases_node ON conversation_leases(node_id);
+
+-- Shared inbox. Every node that receives a message records it here; the
+-- first record wins, and the node holding the conversation lease claims it.
+CREATE TABLE IF NOT EXISTS inbox (
+    message_key TEXT PRIMARY KEY,
+    conversation_key TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    received_by TEXT NOT NULL,
+    received_at TEXT NOT NULL,
+    claimed_by TEXT,
+    claimed_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_inbox_unclaimed ON inbox(received_at) WHERE claimed_by IS NULL;
+
+-- Ready tasks handed to another node's workers. The origin node keeps the
+-- task on its own board and applies the result when the job finishes.
+CREATE TABLE IF NOT EXISTS cluster_jobs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    agent_id TEXT NOT NULL,
+    task_number INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    prompt TEXT NOT NULL,
+    origin_node TEXT NOT NULL,
+    target_node TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'queued',
+    worker_node TEXT,
+    lease_expires_at TEXT,
+    result TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_cluster_jobs_agent_status ON cluster_jobs(agent_id, status);
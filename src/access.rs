@@ -0,0 +1,359 @@
+//! Dashboard API users and role-based route permissions.
+//!
+//! Besides the keys in `[api.auth]`, the API accepts tokens issued to named
+//! users. Users live in an instance-level SQLite database (`data/access.db`)
+//! with only a SHA-256 hash of their token, so the plaintext token is shown
+//! once when it's issued and never again.
+
+use crate::config::ApiRole;
+use crate::error::Result;
+
+use anyhow::Context as _;
+use axum::http::Method;
+use rand::RngCore;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Row as _, SqlitePool};
+
+use std::path::Path;
+
+/// Migrations for the instance access database, kept apart from the
+/// per-agent set in `./migrations`.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations/instance");
+
+/// Prefix on issued user tokens, so they're recognizable in logs and leak
+/// scans.
+pub const USER_TOKEN_PREFIX: &str = "sbu_";
+
+/// Routes only admins may use, whatever the method: credentials, raw config
+/// and its history, backups, agent export and import, updates, user
+/// management, and the audit log. A `*` segment matches any single segment.
+const ADMIN_ROUTES: &[&str] = &[
+    "/secrets",
+    "/ssh",
+    "/settings",
+    "/config",
+    "/system/backup",
+    "/agents/*/export",
+    "/agents/import",
+    "/update",
+    "/access/users",
+    "/audit",
+    "/users",
+    "/providers/debug",
+];
+
+/// Routes whose changes touch credentials. Anyone may read them; only
+/// admins may write.
+const ADMIN_WRITE_ROUTES: &[&str] = &[
+    "/providers",
+    "/ollama",
+    "/messaging/instances",
+    "/mcp/servers",
+];
+
+/// Routes that act on GET, such as sockets that accept messages and the
+/// OpenCode proxy, and reads of operator conversations: cortex chat history
+/// and LLM traces carry full prompts and replies.
+const OPERATOR_ROUTES: &[&str] = &[
+    "/webchat/ws",
+    "/opencode",
+    "/cortex-chat/messages",
+    "/cortex-chat/threads",
+    "/traces",
+];
+
+/// The least role allowed to call `method` on `path` (relative to `/api`).
+///
+/// Reads need a viewer, changes need an operator, and anything touching
+/// credentials or access needs an admin.
+pub fn required_role(method: &Method, path: &str) -> ApiRole {
+    let matches = |routes: &[&str]| routes.iter().any(|route| under(path, route));
+    let read = matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+
+    if matches(ADMIN_ROUTES) || (!read && matches(ADMIN_WRITE_ROUTES)) {
+        ApiRole::Admin
+    } else if !read || matches(OPERATOR_ROUTES) {
+        ApiRole::Operator
+    } else {
+        ApiRole::Viewer
+    }
+}
+
+/// Whether `path` is `route` or below it.
+fn under(path: &str, route: &str) -> bool {
+    let mut segments = path.split('/');
+    route.split('/').all(|expected| {
+        segments
+            .next()
+            .is_some_and(|segment| expected == "*" || segment == expected)
+    })
+}
+
+/// A named API user.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiUser {
+    pub id: String,
+    pub name: String,
+    pub role: ApiRole,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Open (creating if needed) `access.db` in the instance data directory and
+/// run its migrations. The pool backs both [`AccessStore`] and
+/// [`crate::audit::AuditLog`].
+pub async fn connect(data_dir: &Path) -> Result<SqlitePool> {
+    std::fs::create_dir_all(data_dir)
+        .with_context(|| format!("failed to create data directory: {}", data_dir.display()))?;
+    let options = SqliteConnectOptions::new()
+        .filename(data_dir.join("access.db"))
+        .create_if_missing(true);
+    let pool = SqlitePool::connect_with(options)
+        .await
+        .context("failed to open access database")?;
+    MIGRATOR
+        .run(&pool)
+        .await
+        .context("failed to run access database migrations")?;
+    Ok(pool)
+}
+
+/// SQLite storage for API users.
+#[derive(Debug, Clone)]
+pub struct AccessStore {
+    pool: SqlitePool,
+}
+
+impl AccessStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list(&self) -> Result<Vec<ApiUser>> {
+        let rows = sqlx::query(
+            "SELECT id, name, role, created_at, updated_at FROM api_users ORDER BY name",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list API users")?;
+        rows.into_iter().map(row_to_user).collect()
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<ApiUser>> {
+        let row = sqlx::query(
+            "SELECT id, name, role, created_at, updated_at FROM api_users WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .context("failed to load API user")?;
+        row.map(row_to_user).transpose()
+    }
+
+    pub async fn name_exists(&self, name: &str) -> Result<bool> {
+        let row = sqlx::query("SELECT 1 FROM api_users WHERE name = ?")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await
+            .context("failed to look up API user")?;
+        Ok(row.is_some())
+    }
+
+    /// Create a user and return it with its token.
+    pub async fn create(&self, name: &str, role: ApiRole) -> Result<(ApiUser, String)> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let token = generate_token();
+        sqlx::query("INSERT INTO api_users (id, name, role, token_hash) VALUES (?, ?, ?, ?)")
+            .bind(&id)
+            .bind(name)
+            .bind(role.as_str())
+            .bind(hash_token(&token))
+            .execute(&self.pool)
+            .await
+            .context("failed to create API user")?;
+        let user = self
+            .get(&id)
+            .await?
+            .context("API user missing after insert")?;
+        Ok((user, token))
+    }
+
+    /// Change a user's role. Returns None when the user doesn't exist.
+    pub async fn set_role(&self, id: &str, role: ApiRole) -> Result<Option<ApiUser>> {
+        sqlx::query("UPDATE api_users SET role = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(role.as_str())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("failed to update API user")?;
+        self.get(id).await
+    }
+
+    /// Issue a new token, invalidating the old one. Returns None when the
+    /// user doesn't exist.
+    pub async fn rotate_token(&self, id: &str) -> Result<Option<String>> {
+        let token = generate_token();
+        let result = sqlx::query(
+            "UPDATE api_users SET token_hash = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        )
+        .bind(hash_token(&token))
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .context("failed to rotate API user token")?;
+        Ok((result.rows_affected() > 0).then_some(token))
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM api_users WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .context("failed to delete API user")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The user a presented token belongs to.
+    pub async fn authenticate(&self, token: &str) -> Result<Option<ApiUser>> {
+        if !token.starts_with(USER_TOKEN_PREFIX) {
+            return Ok(None);
+        }
+        let row = sqlx::query(
+            "SELECT id, name, role, created_at, updated_at FROM api_users WHERE token_hash = ?",
+        )
+        .bind(hash_token(token))
+        .fetch_optional(&self.pool)
+        .await
+        .context("failed to authenticate API user")?;
+        row.map(row_to_user).transpose()
+    }
+}
+
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::rng().fill_bytes(&mut bytes);
+    format!("{USER_TOKEN_PREFIX}{}", hex::encode(bytes))
+}
+
+/// Tokens are 256 random bits, so an unsalted hash is enough to keep them
+/// out of the database without making lookups expensive.
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn row_to_user(row: sqlx::sqlite::SqliteRow) -> Result<ApiUser> {
+    let role: String = row.try_get("role").context("failed to read role")?;
+    let created_at: chrono::NaiveDateTime = row
+        .try_get("created_at")
+        .context("failed to read created_at")?;
+    let updated_at: chrono::NaiveDateTime = row
+        .try_get("updated_at")
+        .context("failed to read updated_at")?;
+    Ok(ApiUser {
+        id: row.try_get("id").context("failed to read id")?,
+        name: row.try_get("name").context("failed to read name")?,
+        role: ApiRole::parse(&role)
+            .with_context(|| format!("unknown API role '{role}' in access database"))?,
+        created_at: created_at.and_utc().to_rfc3339(),
+        updated_at: updated_at.and_utc().to_rfc3339(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn store() -> AccessStore {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        MIGRATOR.run(&pool).await.unwrap();
+        AccessStore::new(pool)
+    }
+
+    #[test]
+    fn routes_require_the_expected_role() {
+        let cases = [
+            (Method::GET, "/channels", ApiRole::Viewer),
+            (Method::GET, "/providers", ApiRole::Viewer),
+            (Method::POST, "/cortex-chat/send", ApiRole::Operator),
+            (Method::PUT, "/channels/settings", ApiRole::Operator),
+            (Method::GET, "/webchat/ws", ApiRole::Operator),
+            (Method::PUT, "/providers", ApiRole::Admin),
+            (Method::DELETE, "/providers/openai", ApiRole::Admin),
+            (Method::GET, "/ollama/models", ApiRole::Viewer),
+            (Method::POST, "/ollama/pull", ApiRole::Admin),
+            (Method::GET, "/secrets", ApiRole::Admin),
+            (Method::GET, "/settings", ApiRole::Admin),
+            (Method::GET, "/config/raw", ApiRole::Admin),
+            (Method::GET, "/access/users", ApiRole::Admin),
+            (Method::GET, "/access/me", ApiRole::Viewer),
+            (Method::GET, "/audit", ApiRole::Admin),
+            (Method::GET, "/agents/config", ApiRole::Viewer),
+            (Method::POST, "/agents/main/export", ApiRole::Admin),
+            (Method::POST, "/agents/import", ApiRole::Admin),
+            (Method::PUT, "/agents/main/identity", ApiRole::Operator),
+            (Method::GET, "/usersettings", ApiRole::Viewer),
+        ];
+        for (method, path, role) in cases {
+            assert_eq!(required_role(&method, path), role, "{method} {path}");
+        }
+    }
+
+    #[test]
+    fn viewers_cannot_read_cortex_chat_or_traces() {
+        for path in [
+            "/cortex-chat/messages",
+            "/cortex-chat/threads",
+            "/traces/portal:chat:main",
+        ] {
+            assert_eq!(
+                required_role(&Method::GET, path),
+                ApiRole::Operator,
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn operators_cannot_export_or_import_agents() {
+        for path in ["/agents/main/export", "/agents/import"] {
+            let required = required_role(&Method::POST, path);
+            assert!(ApiRole::Operator < required, "{path}");
+            assert!(ApiRole::Admin >= required, "{path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn tokens_authenticate_until_rotated() {
+        let store = store().await;
+        let (user, token) = store.create("ops", ApiRole::Operator).await.unwrap();
+        assert!(token.starts_with(USER_TOKEN_PREFIX));
+
+        let found = store.authenticate(&token).await.unwrap().unwrap();
+        assert_eq!(found.id, user.id);
+        assert_eq!(found.role, ApiRole::Operator);
+        assert!(store.authenticate("sbu_wrong").await.unwrap().is_none());
+
+        let (stored,): (String,) = sqlx::query_as("SELECT token_hash FROM api_users")
+            .fetch_one(&store.pool)
+            .await
+            .unwrap();
+        assert_ne!(stored, token);
+
+        let rotated = store.rotate_token(&user.id).await.unwrap().unwrap();
+        assert!(store.authenticate(&token).await.unwrap().is_none());
+        assert!(store.authenticate(&rotated).await.unwrap().is_some());
+
+        let viewer = store
+            .set_role(&user.id, ApiRole::Viewer)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(viewer.role, ApiRole::Viewer);
+        assert!(store.delete(&user.id).await.unwrap());
+        assert!(store.authenticate(&rotated).await.unwrap().is_none());
+        assert!(store.rotate_token(&user.id).await.unwrap().is_none());
+    }
+}
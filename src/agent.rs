@@ -1,21 +1,34 @@
 //! Agent processes: channels, branches, workers, compactor, cortex.
 
+pub mod archival;
 pub mod branch;
+pub mod branch_cache;
 pub mod channel;
 pub mod channel_attachments;
 pub mod channel_dispatch;
 pub mod channel_history;
+pub mod channel_pacing;
 pub mod channel_prompt;
 pub mod compactor;
 pub mod cortex;
 pub mod cortex_chat;
+pub mod experiments;
+pub mod fact_extraction;
 pub mod ingestion;
 #[cfg(test)]
 mod invariant_harness;
+pub mod jobs;
+pub mod prefetch;
 pub mod process_control;
 pub mod prompt_snapshot;
+pub mod replay;
 pub mod status;
+pub mod storage;
+pub mod structured_output;
+pub mod takeover;
 pub mod worker;
+pub mod worker_batch;
+pub mod worker_mailbox;
 
 pub(crate) fn panic_payload_to_string(panic_payload: &(dyn std::any::Any + Send)) -> String {
     panic_payload
@@ -45,3 +58,76 @@ pub(crate) fn extract_last_assistant_text(history: &[rig::message::Message]) ->
 
     None
 }
+
+/// Loops every agent runs in the background, spawned as
+/// `spawn(deps, logger)`.
+type BackgroundLoop = fn(crate::AgentDeps, cortex::CortexLogger) -> tokio::task::JoinHandle<()>;
+
+/// Start an agent's long-lived background loops under the global supervisor:
+/// the cortex loops, storage and archival maintenance, and memory ingestion
+/// when it is enabled. They stop when the agent is removed.
+pub fn supervise_background_loops(deps: &crate::AgentDeps, ingest_dir: std::path::PathBuf) {
+    use crate::supervisor::{ProcessKind, ProcessSpec, Supervisor};
+
+    let supervisor = Supervisor::global();
+    let agent_id = deps.agent_id.to_string();
+    let loops: [(ProcessKind, &str, BackgroundLoop); 9] = [
+        (ProcessKind::Cortex, "warmup", cortex::spawn_warmup_loop),
+        (ProcessKind::Cortex, "cortex", cortex::spawn_cortex_loop),
+        (
+            ProcessKind::Cortex,
+            "association",
+            cortex::spawn_association_loop,
+        ),
+        (
+            ProcessKind::Cortex,
+            "ready_task",
+            cortex::spawn_ready_task_loop,
+        ),
+        (
+            ProcessKind::Cortex,
+            "fact_extraction",
+            fact_extraction::spawn_fact_extraction_loop,
+        ),
+        (
+            ProcessKind::Maintenance,
+            "storage_monitor",
+            storage::spawn_storage_monitor,
+        ),
+        (
+            ProcessKind::Maintenance,
+            "vector_optimizer",
+            storage::spawn_vector_optimizer,
+        ),
+        (
+            ProcessKind::Maintenance,
+            "db_maintenance",
+            storage::spawn_db_maintenance,
+        ),
+        (
+            ProcessKind::Maintenance,
+            "channel_archiver",
+            archival::spawn_channel_archiver,
+        ),
+    ];
+
+    for (kind, name, spawn) in loops {
+        let deps = deps.clone();
+        supervisor.supervise(ProcessSpec::new(kind, name).agent(&agent_id), move || {
+            spawn(
+                deps.clone(),
+                cortex::CortexLogger::new(deps.sqlite_pool.clone()),
+            )
+        });
+        tracing::info!(agent_id = %agent_id, name, "background loop started");
+    }
+
+    if deps.runtime_config.ingestion.load().enabled {
+        let deps = deps.clone();
+        supervisor.supervise(
+            ProcessSpec::new(ProcessKind::Maintenance, "ingestion").agent(&agent_id),
+            move || ingestion::spawn_ingestion_loop(ingest_dir.clone(), deps.clone()),
+        );
+        tracing::info!(agent_id = %agent_id, "memory ingestion loop started");
+    }
+}
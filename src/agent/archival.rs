@@ -0,0 +1,185 @@
+//! Channel history archival.
+//!
+//! Periodically checks every channel against the archive thresholds. The
+//! oldest messages past them are summarized in segments by the compactor
+//! model (which also extracts memories along the way), the summaries are
+//! stored in `channel_summaries`, and the raw rows are moved to cold storage
+//! or deleted. `channel_recall` returns the summaries alongside whatever raw
+//! history is left.
+
+use crate::AgentDeps;
+use crate::agent::compactor::summarize_transcript;
+use crate::agent::cortex::CortexLogger;
+use crate::config::ArchiveConfig;
+use crate::conversation::archive::{ChannelArchive, messages_due};
+use crate::conversation::history::ConversationMessage;
+
+use serde::Serialize;
+
+use std::time::Duration;
+
+/// How often to re-check whether disabled archival was re-enabled.
+const DISABLED_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Delay before the first pass, so startup isn't slowed down.
+const INITIAL_DELAY: Duration = Duration::from_secs(120);
+
+/// Upper bound on segments summarized in one pass, across all channels.
+/// A large backlog is worked through over several passes.
+const MAX_SEGMENTS_PER_PASS: usize = 20;
+
+/// Result of one archival pass.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ArchiveReport {
+    pub channels_archived: usize,
+    pub segments_archived: usize,
+    pub messages_archived: usize,
+    pub segments_failed: usize,
+}
+
+/// Spawn the channel archival loop for an agent.
+pub fn spawn_channel_archiver(
+    deps: AgentDeps,
+    logger: CortexLogger,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        tracing::info!("channel archiver started");
+        let job = deps.runtime_config.jobs.register(
+            "channel_archive",
+            "Summarize and archive old channel history",
+        );
+        job.wait(INITIAL_DELAY).await;
+
+        loop {
+            let config = **deps.runtime_config.archive.load();
+            if !config.is_enabled() {
+                job.wait(DISABLED_POLL_INTERVAL).await;
+                continue;
+            }
+
+            job.started();
+            match archive_once(&deps, config).await {
+                Ok(report) => {
+                    let summary = format!(
+                        "archived {} messages in {} segments",
+                        report.messages_archived, report.segments_archived
+                    );
+                    if report.segments_archived > 0 || report.segments_failed > 0 {
+                        logger.log(
+                            "channel_archive",
+                            &format!("Channel archival {summary}"),
+                            serde_json::to_value(&report).ok(),
+                        );
+                    }
+                    job.finished(report.segments_failed == 0, summary);
+                }
+                Err(error) => {
+                    tracing::warn!(%error, "channel archival pass failed");
+                    job.finished(false, error.to_string());
+                }
+            }
+
+            job.wait(Duration::from_secs(config.check_interval_secs))
+                .await;
+        }
+    })
+}
+
+/// Run one archival pass over every channel.
+pub async fn archive_once(
+    deps: &AgentDeps,
+    config: ArchiveConfig,
+) -> crate::error::Result<ArchiveReport> {
+    let archive = ChannelArchive::new(deps.sqlite_pool.clone());
+    let prompt_engine = deps.runtime_config.prompts.load();
+    let compactor_prompt = prompt_engine.render_static("compactor")?;
+
+    let mut report = ArchiveReport::default();
+    let mut budget = MAX_SEGMENTS_PER_PASS;
+
+    for (channel_id, total) in archive.channel_message_counts().await? {
+        if budget == 0 {
+            break;
+        }
+        let older = if config.max_age_days > 0 {
+            archive
+                .count_older_than(&channel_id, config.max_age_days)
+                .await?
+        } else {
+            0
+        };
+        let due = messages_due(&config, total, older);
+        if due == 0 {
+            continue;
+        }
+
+        let segment_size = config.segment_size as i64;
+        let segments = ((due / segment_size) as usize).min(budget);
+        let channel: crate::ChannelId = channel_id.as_str().into();
+        let mut archived_any = false;
+
+        for _ in 0..segments {
+            budget -= 1;
+            let messages = archive.load_oldest(&channel_id, segment_size).await?;
+            let transcript = prompt_engine
+                .render_system_archive_transcript(&render_archive_transcript(&messages))?;
+
+            // On failure the raw messages stay in place and the next pass
+            // tries again.
+            let summary =
+                match summarize_transcript(deps, &compactor_prompt, &channel, &transcript).await {
+                    Ok(summary) if !summary.is_empty() => summary,
+                    Ok(_) => {
+                        tracing::warn!(channel_id = %channel_id, "archival summary was empty");
+                        report.segments_failed += 1;
+                        break;
+                    }
+                    Err(error) => {
+                        tracing::warn!(
+                            channel_id = %channel_id,
+                            %error,
+                            "archival summarization failed"
+                        );
+                        report.segments_failed += 1;
+                        break;
+                    }
+                };
+
+            archive
+                .archive_segment(&channel_id, &summary, &messages, config.raw_messages)
+                .await?;
+            report.segments_archived += 1;
+            report.messages_archived += messages.len();
+            archived_any = true;
+        }
+
+        if archived_any {
+            report.channels_archived += 1;
+            tracing::info!(
+                channel_id = %channel_id,
+                "archived old channel history"
+            );
+        }
+    }
+
+    Ok(report)
+}
+
+/// Render stored messages as transcript lines for the compactor.
+fn render_archive_transcript(messages: &[ConversationMessage]) -> String {
+    let mut output = String::new();
+    for message in messages {
+        let sender = match (&message.sender_name, message.role.as_str()) {
+            (Some(name), _) => name.as_str(),
+            (None, "assistant") => "Assistant",
+            (None, _) => "User",
+        };
+        output.push_str(&format!(
+            "[{}] {}: {}\n",
+            message.created_at.format("%Y-%m-%d %H:%M"),
+            sender,
+            message.content
+        ));
+    }
+    output
+}
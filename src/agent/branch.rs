@@ -1,9 +1,11 @@
 //! Branch: Fork context for thinking and delegation.
 
+use crate::agent::branch_cache::BranchCacheKey;
 use crate::agent::compactor::estimate_history_tokens;
 use crate::error::Result;
 use crate::hooks::SpacebotHook;
 use crate::llm::SpacebotModel;
+use crate::llm::budget::SpendScope;
 use crate::llm::routing::is_context_overflow_error;
 use crate::tools::MemoryPersistenceContractState;
 use crate::{AgentDeps, BranchId, ChannelId, ProcessEvent, ProcessId, ProcessType};
@@ -35,12 +37,15 @@ pub struct Branch {
     pub max_turns: usize,
     /// Optional completion contract state used only by silent memory-persistence branches.
     pub memory_persistence_contract: Option<Arc<MemoryPersistenceContractState>>,
+    /// Result cache slot to fill if the branch reaches a final answer.
+    pub cache_key: Option<BranchCacheKey>,
 }
 
 #[derive(Debug, Clone)]
 pub struct BranchExecutionConfig {
     pub max_turns: usize,
     pub memory_persistence_contract: Option<Arc<MemoryPersistenceContractState>>,
+    pub cache_key: Option<BranchCacheKey>,
 }
 
 impl Branch {
@@ -78,6 +83,7 @@ impl Branch {
             tool_server,
             max_turns: execution_config.max_turns,
             memory_persistence_contract: execution_config.memory_persistence_contract,
+            cache_key: execution_config.cache_key,
         }
     }
 
@@ -105,10 +111,18 @@ impl Branch {
         self.maybe_compact_history();
 
         let routing = self.deps.runtime_config.routing.load();
-        let model_name = routing.resolve(ProcessType::Branch, None).to_string();
+        let model_name = routing
+            .resolve(ProcessType::Branch, Some(&*self.channel_id))
+            .to_string();
         let model = SpacebotModel::make(&self.deps.llm_manager, &model_name)
             .with_context(&*self.deps.agent_id, "branch")
-            .with_routing((**routing).clone());
+            .with_channel(&*self.channel_id)
+            .with_process_id(self.id.to_string())
+            .with_routing((**routing).clone())
+            .with_budget(
+                self.deps
+                    .budget_guard(SpendScope::channel(self.channel_id.clone())),
+            );
 
         let agent = AgentBuilder::new(model)
             .preamble(&self.system_prompt)
@@ -120,6 +134,7 @@ impl Branch {
         let mut overflow_retries = 0;
         let mut memory_contract_retries = 0;
         let enforce_memory_contract = self.memory_persistence_contract.is_some();
+        let mut completed = false;
 
         let conclusion = loop {
             if enforce_memory_contract {
@@ -130,7 +145,10 @@ impl Branch {
                 .prompt_once(&agent, &mut self.history, &current_prompt)
                 .await
             {
-                Ok(response) => break response,
+                Ok(response) => {
+                    completed = true;
+                    break response;
+                }
                 Err(rig::completion::PromptError::MaxTurnsError { .. }) => {
                     self.hook.set_completion_contract_request_active(false);
                     if enforce_memory_contract {
@@ -240,6 +258,16 @@ impl Branch {
         };
         let conclusion = crate::secrets::scrub::scrub_leaks(&conclusion);
 
+        // Only a final answer is worth replaying. Partial and cancelled
+        // conclusions should be re-derived by the next spawn.
+        if completed && let Some(cache_key) = self.cache_key.take() {
+            self.deps.branch_cache.insert(
+                cache_key,
+                &conclusion,
+                &self.deps.runtime_config.branch_cache.load(),
+            );
+        }
+
         // Send conclusion back to the channel
         let _ = self.deps.event_tx.send(ProcessEvent::BranchResult {
             agent_id: self.deps.agent_id.clone(),
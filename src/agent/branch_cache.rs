@@ -0,0 +1,212 @@
+//! Branch result cache.
+//!
+//! Branches often re-derive the same analysis — summarizing the same window
+//! of transcript, recalling the same memories for the same question. When
+//! `[defaults.branch_cache]` is enabled, a branch spawned with the same task on
+//! the same channel over exactly the same history as an earlier one is answered
+//! with that branch's conclusion instead of running again. The history is part
+//! of the key, so any new message invalidates every earlier entry for the
+//! channel.
+//!
+//! Only branches that reach a final answer are stored; partial, cancelled and
+//! failed conclusions always run again.
+
+use crate::config::BranchCacheConfig;
+
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Upper bound on cached conclusions per agent. Expired entries are swept
+/// first, then the oldest are evicted.
+const MAX_ENTRIES: usize = 256;
+
+/// Identifies a repeatable branch: channel, task fingerprint, history watermark.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BranchCacheKey {
+    channel_id: String,
+    task_hash: String,
+    history_watermark: String,
+}
+
+impl BranchCacheKey {
+    /// Fingerprint a branch spawn. Returns `None` if the history can't be
+    /// serialized, in which case the branch simply isn't cached.
+    pub fn new(channel_id: &str, task: &str, history: &[rig::message::Message]) -> Option<Self> {
+        let history_bytes = serde_json::to_vec(history).ok()?;
+        Some(Self::from_parts(channel_id, task, &history_bytes))
+    }
+
+    fn from_parts(channel_id: &str, task: &str, history_bytes: &[u8]) -> Self {
+        Self {
+            channel_id: channel_id.to_string(),
+            task_hash: hex::encode(Sha256::digest(normalize_task(task).as_bytes())),
+            history_watermark: hex::encode(Sha256::digest(history_bytes)),
+        }
+    }
+}
+
+/// Case and whitespace don't change what a branch is asked to do.
+fn normalize_task(task: &str) -> String {
+    task.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[derive(Debug, Clone)]
+struct CachedConclusion {
+    conclusion: String,
+    stored_at: Instant,
+}
+
+/// In-memory branch conclusion cache shared by every channel of an agent.
+#[derive(Debug, Default)]
+pub struct BranchResultCache {
+    entries: Mutex<HashMap<BranchCacheKey, CachedConclusion>>,
+}
+
+impl BranchResultCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Look up a fresh conclusion for a branch about to be spawned.
+    pub fn get(&self, key: &BranchCacheKey, config: &BranchCacheConfig) -> Option<String> {
+        if !config.enabled {
+            return None;
+        }
+
+        let ttl = Duration::from_secs(config.ttl_secs);
+        let mut entries = self.lock();
+        match entries.get(key) {
+            Some(cached) if cached.stored_at.elapsed() < ttl => Some(cached.conclusion.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Store the conclusion of a branch that completed normally.
+    pub fn insert(&self, key: BranchCacheKey, conclusion: &str, config: &BranchCacheConfig) {
+        if !config.enabled {
+            return;
+        }
+
+        let ttl = Duration::from_secs(config.ttl_secs);
+        let mut entries = self.lock();
+        if entries.len() >= MAX_ENTRIES {
+            entries.retain(|_, cached| cached.stored_at.elapsed() < ttl);
+        }
+        while entries.len() >= MAX_ENTRIES {
+            let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, cached)| cached.stored_at)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            entries.remove(&oldest);
+        }
+
+        entries.insert(
+            key,
+            CachedConclusion {
+                conclusion: conclusion.to_string(),
+                stored_at: Instant::now(),
+            },
+        );
+    }
+
+    /// Drop every conclusion cached for a channel. Returns how many were
+    /// removed.
+    pub fn clear_channel(&self, channel_id: &str) -> usize {
+        let mut entries = self.lock();
+        let before = entries.len();
+        entries.retain(|key, _| key.channel_id != channel_id);
+        before - entries.len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<BranchCacheKey, CachedConclusion>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled() -> BranchCacheConfig {
+        BranchCacheConfig {
+            enabled: true,
+            ttl_secs: 60,
+        }
+    }
+
+    #[test]
+    fn repeated_task_over_same_history_hits() {
+        let cache = BranchResultCache::new();
+        let config = enabled();
+        let key = BranchCacheKey::from_parts("discord:1", "Summarize the thread", b"[1,2]");
+        cache.insert(key, "the summary", &config);
+
+        let again = BranchCacheKey::from_parts("discord:1", "  summarize THE\nthread ", b"[1,2]");
+        assert_eq!(cache.get(&again, &config).as_deref(), Some("the summary"));
+    }
+
+    #[test]
+    fn new_history_channel_or_task_misses() {
+        let cache = BranchResultCache::new();
+        let config = enabled();
+        cache.insert(
+            BranchCacheKey::from_parts("discord:1", "summarize", b"[1,2]"),
+            "the summary",
+            &config,
+        );
+
+        for key in [
+            BranchCacheKey::from_parts("discord:1", "summarize", b"[1,2,3]"),
+            BranchCacheKey::from_parts("discord:2", "summarize", b"[1,2]"),
+            BranchCacheKey::from_parts("discord:1", "summarize the decisions", b"[1,2]"),
+        ] {
+            assert!(cache.get(&key, &config).is_none());
+        }
+    }
+
+    #[test]
+    fn expired_and_disabled_entries_are_not_served() {
+        let cache = BranchResultCache::new();
+        let key = BranchCacheKey::from_parts("discord:1", "summarize", b"[]");
+        cache.insert(key.clone(), "the summary", &enabled());
+
+        let disabled = BranchCacheConfig {
+            enabled: false,
+            ..enabled()
+        };
+        assert!(cache.get(&key, &disabled).is_none());
+
+        let expired = BranchCacheConfig {
+            ttl_secs: 0,
+            ..enabled()
+        };
+        assert!(cache.get(&key, &expired).is_none());
+        assert!(cache.get(&key, &enabled()).is_none());
+    }
+
+    #[test]
+    fn insert_stays_within_capacity() {
+        let cache = BranchResultCache::new();
+        let config = enabled();
+        for index in 0..=MAX_ENTRIES {
+            let key = BranchCacheKey::from_parts("discord:1", &format!("task {index}"), b"[]");
+            cache.insert(key, "conclusion", &config);
+        }
+
+        assert_eq!(cache.lock().len(), MAX_ENTRIES);
+    }
+}
@@ -1,8 +1,9 @@
 //! Channel: User-facing conversation process.
 
 use crate::agent::channel_attachments;
-use crate::agent::channel_attachments::download_attachments;
-use crate::agent::channel_dispatch::spawn_memory_persistence_branch;
+use crate::agent::channel_dispatch::{
+    spawn_memory_persistence_branch, spawn_prefetch_branch, spawn_worker_from_state,
+};
 use crate::agent::channel_history::{
     apply_history_after_turn, event_is_for_channel, extract_message_id,
     extract_reply_from_tool_syntax, format_batched_user_message, format_user_message,
@@ -12,6 +13,8 @@ use crate::agent::channel_prompt::{
     MAX_RETRIGGERS_PER_TURN, RETRIGGER_DEBOUNCE_MS, RETRIGGER_MAX_TURNS, TemporalContext,
 };
 use crate::agent::compactor::Compactor;
+use crate::agent::experiments;
+use crate::agent::prefetch::PrefetchedContext;
 use crate::agent::process_control::ControlActionResult;
 use crate::agent::status::{StatusBlock, SystemInfo};
 use crate::agent::worker::Worker;
@@ -19,6 +22,7 @@ use crate::conversation::{ChannelStore, ConversationLogger, ProcessRunLogger};
 use crate::error::{AgentError, Result};
 use crate::hooks::SpacebotHook;
 use crate::llm::SpacebotModel;
+use crate::llm::budget::SpendScope;
 use crate::{
     AgentDeps, BranchId, ChannelId, InboundMessage, OutboundResponse, ProcessEvent, ProcessId,
     ProcessType, RoutedResponse, RoutedSender, WorkerId,
@@ -30,6 +34,7 @@ use rig::one_or_many::OneOrMany;
 use rig::tool::server::ToolServer;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::sync::{Arc, Weak};
 use tokio::sync::broadcast;
 use tokio::sync::{RwLock, mpsc};
@@ -46,7 +51,7 @@ pub type LiveWorkerTranscripts =
 /// LLM unambiguous, ID-tagged results to relay.
 #[derive(Clone, Debug)]
 struct PendingResult {
-    /// "branch" or "worker"
+    /// "branch", "worker", or "worker_batch"
     process_type: &'static str,
     /// The branch or worker ID (short UUID).
     process_id: String,
@@ -58,6 +63,18 @@ struct PendingResult {
 
 const EVENT_LAG_WARNING_INTERVAL_SECS: u64 = 30;
 
+/// Outcome of one agent turn: the prompt result, the skip and replied flags,
+/// and whether a retrigger relay reply was preserved in history.
+type AgentTurn = (
+    std::result::Result<String, rig::completion::PromptError>,
+    crate::tools::SkipFlag,
+    crate::tools::RepliedFlag,
+    bool,
+);
+
+/// Most sender profiles injected into the prompt for one coalesced batch.
+const MAX_BATCH_SENDER_PROFILES: usize = 3;
+
 async fn recv_channel_event(
     event_rx: &mut broadcast::Receiver<ProcessEvent>,
 ) -> crate::BroadcastRecvResult<ProcessEvent> {
@@ -79,6 +96,66 @@ fn should_flush_coalesce_buffer_for_event(event: &ProcessEvent) -> bool {
     )
 }
 
+/// Slash commands run immediately and are never batched.
+fn looks_like_command(message: &InboundMessage) -> bool {
+    match &message.content {
+        crate::MessageContent::Text(text) => text.trim_start().starts_with('/'),
+        crate::MessageContent::Media { text, .. } => text
+            .as_deref()
+            .is_some_and(|value| value.trim_start().starts_with('/')),
+        crate::MessageContent::Interaction { .. } => false,
+        crate::MessageContent::Command { .. } => true,
+    }
+}
+
+/// Watches the inbound queue while a turn runs, so a new message can cancel
+/// the turn before its first tool call.
+struct InterruptWatch {
+    /// Only this sender can interrupt; `None` lets anyone.
+    sender_id: Option<String>,
+    message_rx: mpsc::Receiver<InboundMessage>,
+    /// Messages received during the turn that didn't interrupt it.
+    held: Vec<InboundMessage>,
+    /// The message that interrupted the turn.
+    follow_up: Option<InboundMessage>,
+}
+
+impl InterruptWatch {
+    fn new(sender_id: Option<String>, message_rx: mpsc::Receiver<InboundMessage>) -> Self {
+        Self {
+            sender_id,
+            message_rx,
+            held: Vec::new(),
+            follow_up: None,
+        }
+    }
+
+    /// Wait for a message that interrupts the turn and store it in `follow_up`.
+    ///
+    /// Messages that can't interrupt are held for after the turn: anything
+    /// from other senders when one is watched, system messages, commands, and
+    /// anything arriving after the turn started a tool call (restarting it
+    /// would repeat the call) or after a held message (to keep order).
+    async fn wait_for_follow_up(&mut self, tool_started: &std::sync::atomic::AtomicBool) {
+        while let Some(message) = self.message_rx.recv().await {
+            let interrupts = self.held.is_empty()
+                && self
+                    .sender_id
+                    .as_ref()
+                    .is_none_or(|sender_id| *sender_id == message.sender_id)
+                && message.source != "system"
+                && !looks_like_command(&message)
+                && !tool_started.load(std::sync::atomic::Ordering::Relaxed);
+            if interrupts {
+                self.follow_up = Some(message);
+                return;
+            }
+            self.held.push(message);
+        }
+        std::future::pending::<()>().await;
+    }
+}
+
 /// Shared state that channel tools need to act on the channel.
 ///
 /// Wrapped in Arc and passed to tools (branch, spawn_worker, route, cancel)
@@ -423,12 +500,20 @@ pub struct Channel {
     message_count: usize,
     /// Branch IDs for silent memory persistence branches (results not injected into history).
     memory_persistence_branches: HashSet<BranchId>,
+    /// Silent speculative prefetch branches, keyed to the user turn they were spawned after.
+    prefetch_branches: HashMap<BranchId, u64>,
+    /// Count of user turns, used to discard prefetch results that arrive too late.
+    prefetch_turn: u64,
+    /// Context gathered by the last prefetch branch, waiting for a matching follow-up.
+    prefetched_context: Option<PrefetchedContext>,
     /// Optional Discord reply target captured when each branch was started.
     branch_reply_targets: HashMap<BranchId, String>,
     /// Buffer for coalescing rapid-fire messages.
     coalesce_buffer: Vec<InboundMessage>,
     /// Deadline for flushing the coalesce buffer.
     coalesce_deadline: Option<tokio::time::Instant>,
+    /// Messages received while a turn was running, handled before new ones.
+    inbound_backlog: VecDeque<InboundMessage>,
     /// Number of retriggers fired since the last real user message.
     retrigger_count: usize,
     /// Whether a retrigger is pending (debounce window active).
@@ -446,6 +531,8 @@ pub struct Channel {
     /// Injected into the system prompt (not into chat history) so the LLM
     /// treats it as read-only context rather than actionable user messages.
     backfill_transcript: Option<String>,
+    /// Experiment variant for the current turn, resolved when the turn starts.
+    experiment_arm: Option<crate::agent::experiments::ExperimentArm>,
     /// Channel-local reply mode toggle.
     /// When true, suppress unsolicited replies unless explicitly invoked.
     listen_only_mode: bool,
@@ -576,9 +663,13 @@ impl Channel {
             compactor,
             message_count: 0,
             memory_persistence_branches: HashSet::new(),
+            prefetch_branches: HashMap::new(),
+            prefetch_turn: 0,
+            prefetched_context: None,
             branch_reply_targets: HashMap::new(),
             coalesce_buffer: Vec::new(),
             coalesce_deadline: None,
+            inbound_backlog: VecDeque::new(),
             retrigger_count: 0,
             pending_retrigger: false,
             pending_retrigger_metadata: HashMap::new(),
@@ -586,6 +677,7 @@ impl Channel {
             pending_results: Vec::new(),
             send_agent_message_tool,
             backfill_transcript: None,
+            experiment_arm: None,
             listen_only_mode: resolved_listen_only_mode,
             listen_only_session_override: None,
             control_handle,
@@ -730,6 +822,107 @@ impl Channel {
         self.state
             .channel_store
             .upsert(&message.conversation_id, &metadata);
+        crate::memory::UserProfileStore::new(self.deps.sqlite_pool.clone()).touch(
+            crate::memory::profiles::channel_platform(&self.state.channel_id),
+            &message.sender_id,
+            sender_name,
+        );
+    }
+
+    /// The speaker's profile as a prompt section, if anything is known
+    /// about them.
+    async fn sender_profile_context(&self, sender_id: &str) -> Option<String> {
+        let store = crate::memory::UserProfileStore::new(self.deps.sqlite_pool.clone());
+        let id = crate::memory::profiles::profile_id(
+            crate::memory::profiles::channel_platform(&self.state.channel_id),
+            sender_id,
+        );
+        match store.get(&id).await {
+            Ok(profile) => profile
+                .as_ref()
+                .and_then(crate::memory::profiles::render_profile_context),
+            Err(error) => {
+                tracing::debug!(%error, channel_id = %self.id, "failed to load sender profile");
+                None
+            }
+        }
+    }
+
+    /// Current time, read in the channel's timezone when it has one.
+    async fn temporal_context(&self) -> TemporalContext {
+        let timezone = match self
+            .state
+            .channel_store
+            .timezone(&self.state.channel_id)
+            .await
+        {
+            Ok(timezone) => timezone,
+            Err(error) => {
+                tracing::debug!(%error, channel_id = %self.id, "failed to load channel timezone");
+                Default::default()
+            }
+        };
+        TemporalContext::from_runtime(self.deps.runtime_config.as_ref())
+            .with_channel_timezone(timezone.resolved())
+    }
+
+    /// The language to reply in as a prompt section.
+    ///
+    /// `message`'s text updates the channel's detected language first. A
+    /// language set through the API wins over the detected one.
+    async fn language_context(&self, message: Option<&InboundMessage>) -> Option<String> {
+        let store = &self.state.channel_store;
+        let mut language = match store.language(&self.state.channel_id).await {
+            Ok(language) => language,
+            Err(error) => {
+                tracing::debug!(%error, channel_id = %self.id, "failed to load channel language");
+                return None;
+            }
+        };
+
+        let text = message.and_then(|message| match &message.content {
+            crate::MessageContent::Text(text) => Some(text.as_str()),
+            crate::MessageContent::Media { text, .. } => text.as_deref(),
+            crate::MessageContent::Interaction { .. } | crate::MessageContent::Command { .. } => {
+                None
+            }
+        });
+        if let Some(detected) = text.and_then(crate::conversation::language::detect)
+            && language.detected.as_deref() != Some(detected)
+        {
+            tracing::debug!(channel_id = %self.id, language = detected, "channel language detected");
+            store.set_detected_language(&self.state.channel_id, detected);
+            language.detected = Some(detected.to_string());
+        }
+
+        let name = crate::conversation::language::display_name(language.resolved()?)?;
+        match self
+            .turn_prompt_engine()
+            .render_language_context(name, language.preferred.is_some())
+        {
+            Ok(context) => Some(context),
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.id, "failed to render language context");
+                None
+            }
+        }
+    }
+
+    /// Whether an operator has taken the channel over. While they have,
+    /// inbound messages are recorded but no turn runs.
+    async fn under_takeover(&self) -> bool {
+        match self
+            .state
+            .channel_store
+            .takeover_started_at(&self.state.channel_id)
+            .await
+        {
+            Ok(started_at) => started_at.is_some(),
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.id, "failed to check channel takeover");
+                false
+            }
+        }
     }
 
     fn suppress_plaintext_fallback(&self) -> bool {
@@ -793,6 +986,7 @@ impl Channel {
             Some(target) => RoutedResponse {
                 response,
                 target: target.clone(),
+                receipt: None,
             },
             None => {
                 tracing::warn!(
@@ -802,6 +996,7 @@ impl Channel {
                 RoutedResponse {
                     response,
                     target: InboundMessage::empty(),
+                    receipt: None,
                 }
             }
         };
@@ -839,6 +1034,41 @@ impl Channel {
         }
     }
 
+    /// Hand a skill-bound slash command straight to a worker running the
+    /// skill, skipping the model's interpretation of the message. Commands
+    /// with no matching skill fall through and reach the model as text.
+    async fn try_dispatch_skill_command(&mut self, message: &InboundMessage) -> bool {
+        let crate::MessageContent::Command { name, args } = &message.content else {
+            return false;
+        };
+        let (skill_name, task) = {
+            let skills = self.deps.runtime_config.skills.load();
+            let Some((skill, command)) = skills.find_by_command(name) else {
+                return false;
+            };
+            (skill.name.clone(), command.worker_task(&skill.name, args))
+        };
+
+        match spawn_worker_from_state(&self.state, task, false, &[skill_name.as_str()], None).await
+        {
+            Ok(worker_id) => {
+                tracing::info!(
+                    channel_id = %self.id,
+                    command = %name,
+                    skill = %skill_name,
+                    %worker_id,
+                    "skill command dispatched"
+                );
+            }
+            Err(error) => {
+                tracing::warn!(channel_id = %self.id, command = %name, %error, "skill command failed to start");
+                self.send_builtin_text(format!("couldn't start /{name}: {error}"), "skill-command")
+                    .await;
+            }
+        }
+        true
+    }
+
     async fn try_handle_builtin_ops_commands(
         &mut self,
         raw_text: &str,
@@ -860,14 +1090,18 @@ impl Channel {
             return Ok(false);
         }
 
-        let temporal_context = TemporalContext::from_runtime(self.deps.runtime_config.as_ref());
+        let temporal_context = self.temporal_context().await;
         let now_line = temporal_context.current_time_line();
 
         match text {
             "/status" => {
                 let routing = self.deps.runtime_config.routing.load();
-                let channel_model = routing.resolve(ProcessType::Channel, None).to_string();
-                let branch_model = routing.resolve(ProcessType::Branch, None).to_string();
+                let channel_model = routing
+                    .resolve(ProcessType::Channel, Some(&*self.id))
+                    .to_string();
+                let branch_model = routing
+                    .resolve(ProcessType::Branch, Some(&*self.id))
+                    .to_string();
                 let mode = if self.listen_only_mode {
                     "quiet"
                 } else {
@@ -943,6 +1177,12 @@ impl Channel {
         let mut last_lag_warning: Option<std::time::Instant> = None;
 
         loop {
+            // Messages that arrived while the last turn ran go before new ones.
+            if let Some(message) = self.inbound_backlog.pop_front() {
+                self.receive_inbound(message).await;
+                continue;
+            }
+
             // Compute next deadline from coalesce and retrigger timers
             let next_deadline = match (self.coalesce_deadline, self.retrigger_deadline) {
                 (Some(a), Some(b)) => Some(a.min(b)),
@@ -963,19 +1203,7 @@ impl Channel {
 
             tokio::select! {
                 Some(message) = self.message_rx.recv() => {
-                    let config = self.deps.runtime_config.coalesce.load();
-                    if self.should_coalesce(&message, &config) {
-                        self.coalesce_buffer.push(message);
-                        self.update_coalesce_deadline(&config).await;
-                    } else {
-                        // Flush any pending buffer before handling this message
-                        if let Err(error) = self.flush_coalesce_buffer().await {
-                            tracing::error!(%error, channel_id = %self.id, "error flushing coalesce buffer");
-                        }
-                        if let Err(error) = self.handle_message(message).await {
-                            tracing::error!(%error, channel_id = %self.id, "error handling message");
-                        }
-                    }
+                    self.receive_inbound(message).await;
                 }
                 event = recv_channel_event(&mut self.event_rx) => {
                     match event {
@@ -1051,6 +1279,23 @@ impl Channel {
         Ok(())
     }
 
+    /// Buffer an inbound message for coalescing or debouncing, or handle it now.
+    async fn receive_inbound(&mut self, message: InboundMessage) {
+        let config = self.deps.runtime_config.coalesce.load();
+        if self.should_coalesce(&message, &config) || self.should_debounce(&message, &config) {
+            self.coalesce_buffer.push(message);
+            self.update_coalesce_deadline(&config).await;
+        } else {
+            // Flush any pending buffer before handling this message
+            if let Err(error) = self.flush_coalesce_buffer().await {
+                tracing::error!(%error, channel_id = %self.id, "error flushing coalesce buffer");
+            }
+            if let Err(error) = self.handle_message(message).await {
+                tracing::error!(%error, channel_id = %self.id, "error handling message");
+            }
+        }
+    }
+
     /// Determine if a message should be coalesced (batched with other messages).
     ///
     /// Returns false for:
@@ -1072,17 +1317,37 @@ impl Channel {
             return false;
         }
         // Built-in slash commands should execute immediately and never be batched.
-        let looks_like_command = match &message.content {
-            crate::MessageContent::Text(text) => text.trim_start().starts_with('/'),
-            crate::MessageContent::Media { text, .. } => text
-                .as_deref()
-                .is_some_and(|value| value.trim_start().starts_with('/')),
-            crate::MessageContent::Interaction { .. } => false,
-        };
-        if looks_like_command {
-            return false;
-        }
-        true
+        !looks_like_command(message)
+    }
+
+    /// Determine if a message should wait for more from the same sender.
+    ///
+    /// Unlike coalescing this applies in DMs too. A message from someone other
+    /// than the buffered sender isn't debounced, so the buffer flushes first.
+    fn should_debounce(
+        &self,
+        message: &InboundMessage,
+        config: &crate::config::CoalesceConfig,
+    ) -> bool {
+        config.enabled
+            && config.sender_debounce_ms > 0
+            && message.source != "system"
+            && !looks_like_command(message)
+            && self
+                .coalesce_buffer
+                .iter()
+                .all(|buffered| buffered.sender_id == message.sender_id)
+    }
+
+    /// The sender of every message in the coalesce buffer, if there is just one.
+    fn coalesce_buffer_sender(&self) -> Option<&str> {
+        let first = self.coalesce_buffer.first()?;
+        let single_sender = first.source != "system"
+            && self
+                .coalesce_buffer
+                .iter()
+                .all(|message| message.sender_id == first.sender_id);
+        single_sender.then_some(first.sender_id.as_str())
     }
 
     /// Check if this is a DM (direct message) conversation based on conversation_id.
@@ -1110,6 +1375,17 @@ impl Channel {
             let max_wait_ms = config.max_wait_ms;
             let debounce_ms = config.debounce_ms;
 
+            // One person typing in pieces: wait until they pause, but never
+            // past max_wait from their first message.
+            if config.sender_debounce_ms > 0 && self.coalesce_buffer_sender().is_some() {
+                let remaining_wait_ms = max_wait_ms.saturating_sub(elapsed_millis);
+                let max_deadline = now + std::time::Duration::from_millis(remaining_wait_ms);
+                let quiet_deadline =
+                    now + std::time::Duration::from_millis(config.sender_debounce_ms);
+                self.coalesce_deadline = Some(quiet_deadline.min(max_deadline));
+                return;
+            }
+
             // If we have enough messages to trigger coalescing (min_messages threshold)
             if self.coalesce_buffer.len() >= config.min_messages {
                 // Cap at max_wait from the first message
@@ -1267,13 +1543,15 @@ impl Channel {
         let saved_dir = self.deps.runtime_config.saved_dir();
 
         // Entries: (formatted_text, attachments, optional saved bytes per attachment)
-        let mut pending_batch_entries: Vec<(
+        type BatchEntry = (
             String,
             Vec<crate::Attachment>,
             Option<Vec<channel_attachments::SavedAttachmentWithBytes>>,
-        )> = Vec::new();
+            Vec<channel_attachments::AudioTranscription>,
+        );
+        let mut pending_batch_entries: Vec<BatchEntry> = Vec::new();
         let mut conversation_id = String::new();
-        let temporal_context = TemporalContext::from_runtime(self.deps.runtime_config.as_ref());
+        let temporal_context = self.temporal_context().await;
         let mut batch_has_invoke = false;
 
         for message in &messages {
@@ -1289,8 +1567,9 @@ impl Channel {
                     crate::MessageContent::Media { text, attachments } => {
                         (text.clone().unwrap_or_default(), attachments.clone())
                     }
-                    // Render interactions as their Display form so the LLM sees plain text.
-                    crate::MessageContent::Interaction { .. } => {
+                    // Render interactions and commands as their Display form so the LLM sees plain text.
+                    crate::MessageContent::Interaction { .. }
+                    | crate::MessageContent::Command { .. } => {
                         (message.content.to_string(), Vec::new())
                     }
                 };
@@ -1318,8 +1597,15 @@ impl Channel {
                     None
                 };
 
-                // Enrich metadata with saved attachment info
-                let metadata = if let Some(ref data) = saved_data {
+                let transcriptions = channel_attachments::transcribe_audio_attachments(
+                    &self.deps,
+                    &attachments,
+                    saved_data.as_deref(),
+                )
+                .await;
+
+                // Enrich metadata with saved attachment info and transcripts
+                let mut metadata = if let Some(ref data) = saved_data {
                     let metas: Vec<_> = data.iter().map(|(meta, _)| meta.clone()).collect();
                     let mut enriched = message.metadata.clone();
                     if let Ok(json) = serde_json::to_value(&metas) {
@@ -1329,6 +1615,7 @@ impl Channel {
                 } else {
                     message.metadata.clone()
                 };
+                channel_attachments::record_transcriptions(&mut metadata, &transcriptions);
 
                 self.state.conversation_logger.log_user_message(
                     &self.state.channel_id,
@@ -1366,10 +1653,24 @@ impl Channel {
                     &raw_text,
                 );
 
-                pending_batch_entries.push((formatted_text, attachments, saved_data));
+                pending_batch_entries.push((
+                    formatted_text,
+                    attachments,
+                    saved_data,
+                    transcriptions,
+                ));
             }
         }
 
+        if self.under_takeover().await {
+            tracing::debug!(
+                channel_id = %self.id,
+                message_count,
+                "operator takeover: recorded batch without replying"
+            );
+            return Ok(());
+        }
+
         if self.listen_only_mode && !batch_has_invoke {
             tracing::debug!(
                 channel_id = %self.id,
@@ -1383,31 +1684,15 @@ impl Channel {
         }
 
         let mut user_contents: Vec<UserContent> = Vec::new();
-        for (formatted_text, attachments, saved_data) in pending_batch_entries {
+        for (formatted_text, attachments, saved_data, transcriptions) in pending_batch_entries {
             if !attachments.is_empty() {
-                let attachment_content = if let Some(ref saved) = saved_data {
-                    let mut content = Vec::new();
-                    let mut unsaved = Vec::new();
-                    for (index, attachment) in attachments.iter().enumerate() {
-                        if let Some((_, bytes)) = saved.get(index) {
-                            if attachment.mime_type.starts_with("audio/") {
-                                unsaved.push(attachment.clone());
-                            } else {
-                                content.push(channel_attachments::content_from_bytes(
-                                    bytes, attachment,
-                                ));
-                            }
-                        } else {
-                            unsaved.push(attachment.clone());
-                        }
-                    }
-                    if !unsaved.is_empty() {
-                        content.extend(download_attachments(&self.deps, &unsaved).await);
-                    }
-                    content
-                } else {
-                    download_attachments(&self.deps, &attachments).await
-                };
+                let attachment_content = channel_attachments::attachment_contents(
+                    &self.deps,
+                    &attachments,
+                    saved_data.as_deref(),
+                    &transcriptions,
+                )
+                .await;
                 for content in attachment_content {
                     user_contents.push(content);
                 }
@@ -1431,17 +1716,48 @@ impl Channel {
             text_parts.join("\n")
         );
 
+        self.experiment_arm = experiments::arm_for_channel(&self.deps, &self.id).await;
+
         // Build system prompt with coalesce hint
-        let system_prompt = self
+        let mut system_prompt = self
             .build_system_prompt_with_coalesce(message_count, elapsed_secs, unique_sender_count)
             .await?;
+        if let Some(prefetched) = self.take_prefetched_context(&combined_text) {
+            system_prompt.push_str("\n\n");
+            system_prompt.push_str(&prefetched);
+        }
+        let mut profiled_senders = std::collections::HashSet::new();
+        for message in messages.iter().filter(|m| m.source != "system") {
+            if profiled_senders.len() >= MAX_BATCH_SENDER_PROFILES
+                || !profiled_senders.insert(message.sender_id.as_str())
+            {
+                continue;
+            }
+            if let Some(profile_context) = self.sender_profile_context(&message.sender_id).await {
+                system_prompt.push_str("\n\n");
+                system_prompt.push_str(&profile_context);
+            }
+        }
+        let last_message = messages.iter().rev().find(|m| m.source != "system");
+        if let Some(language_context) = self.language_context(last_message).await {
+            system_prompt.push_str("\n\n");
+            system_prompt.push_str(&language_context);
+        }
 
         // Extract adapter from messages (prefer explicit message.adapter, fall back to stored source_adapter)
         // This preserves per-message adapter for Signal named instances (e.g., "signal:work")
         let batch_adapter = messages
             .iter()
-            .find_map(|m| m.adapter.as_deref())
-            .or(self.source_adapter.as_deref());
+            .find_map(|m| m.adapter.clone())
+            .or_else(|| self.source_adapter.clone());
+        let batch_sender = messages
+            .first()
+            .filter(|first| {
+                messages
+                    .iter()
+                    .all(|m| m.source != "system" && m.sender_id == first.sender_id)
+            })
+            .map(|first| first.sender_id.clone());
 
         {
             let mut reply_target = self.state.reply_target_message_id.write().await;
@@ -1456,21 +1772,30 @@ impl Channel {
         }
 
         // Run agent turn with any image/audio attachments preserved
-        let (result, skip_flag, replied_flag, _) = self
-            .run_agent_turn(
+        let Some((result, skip_flag, replied_flag, _)) = self
+            .run_turn(
                 &combined_text,
                 &system_prompt,
                 &conversation_id,
                 attachment_parts,
                 false, // not a retrigger
-                batch_adapter,
+                batch_adapter.as_deref(),
+                batch_sender.as_deref(),
             )
-            .await?;
+            .await?
+        else {
+            return Ok(());
+        };
 
         self.handle_agent_result(result, &skip_flag, &replied_flag, false)
             .await;
         // Check compaction
-        if let Err(error) = self.compactor.check_and_compact().await {
+        let routing = self.deps.runtime_config.routing.load();
+        if let Err(error) = self
+            .compactor
+            .check_and_compact(self.turn_model(&routing))
+            .await
+        {
             tracing::warn!(channel_id = %self.id, %error, "compaction check failed");
         }
 
@@ -1489,10 +1814,10 @@ impl Channel {
         unique_senders: usize,
     ) -> Result<String> {
         let rc = &self.deps.runtime_config;
-        let prompt_engine = rc.prompts.load();
+        let prompt_engine = self.turn_prompt_engine();
 
         let identity_context = rc.identity.load().render();
-        let memory_bulletin = rc.memory_bulletin.load();
+        let memory_bulletin = rc.rendered_bulletin();
         let skills = rc.skills.load();
         let skills_prompt = skills.render_channel_prompt(&prompt_engine)?;
 
@@ -1508,7 +1833,7 @@ impl Channel {
             &mcp_tool_names,
         )?;
 
-        let temporal_context = TemporalContext::from_runtime(rc.as_ref());
+        let temporal_context = self.temporal_context().await;
         let current_time_line = temporal_context.current_time_line();
         let system_info = self.build_system_info().await;
         let status_text = {
@@ -1536,7 +1861,7 @@ impl Channel {
 
         prompt_engine.render_channel_prompt_with_links(
             empty_to_none(identity_context),
-            empty_to_none(memory_bulletin.to_string()),
+            empty_to_none(memory_bulletin),
             empty_to_none(skills_prompt),
             worker_capabilities,
             self.conversation_context.clone(),
@@ -1557,7 +1882,7 @@ impl Channel {
     /// spawn_worker (to delegate), route (to follow up with a worker), cancel, or
     /// memory_save. The tools act on the channel's shared state directly.
     #[tracing::instrument(skip(self, message), fields(channel_id = %self.id, agent_id = %self.deps.agent_id, message_id = %message.id))]
-    async fn handle_message(&mut self, message: InboundMessage) -> Result<()> {
+    async fn handle_message(&mut self, mut message: InboundMessage) -> Result<()> {
         // Apply runtime-config updates immediately without requiring a restart.
         self.sync_listen_only_mode_from_runtime();
 
@@ -1616,8 +1941,10 @@ impl Channel {
             crate::MessageContent::Media { text, attachments } => {
                 (text.clone().unwrap_or_default(), attachments.clone())
             }
-            // Render interactions as their Display form so the LLM sees plain text.
-            crate::MessageContent::Interaction { .. } => (message.content.to_string(), Vec::new()),
+            // Render interactions and commands as their Display form so the LLM sees plain text.
+            crate::MessageContent::Interaction { .. } | crate::MessageContent::Command { .. } => {
+                (message.content.to_string(), Vec::new())
+            }
         };
 
         // Save attachments to disk when enabled, capturing bytes for LLM reuse
@@ -1647,8 +1974,27 @@ impl Channel {
             .as_ref()
             .map(|data| data.iter().map(|(meta, _)| meta.clone()).collect());
 
+        // Transcribe audio before persisting so the transcript is stored with
+        // the message.
+        let transcriptions = channel_attachments::transcribe_audio_attachments(
+            &self.deps,
+            &attachments,
+            saved_attachment_data.as_deref(),
+        )
+        .await;
+        channel_attachments::record_transcriptions(&mut message.metadata, &transcriptions);
+
         self.persist_inbound_user_message(&message, &raw_text, saved_metas.as_deref());
 
+        if self.under_takeover().await {
+            tracing::debug!(
+                channel_id = %self.id,
+                source = %message.source,
+                "operator takeover: recorded message without replying"
+            );
+            return Ok(());
+        }
+
         // Deterministic built-in command: bypass model output drift for agent identity checks.
         if message.source != "system" && raw_text.trim() == "/agent-id" {
             self.send_builtin_text(self.deps.agent_id.to_string(), "agent-id")
@@ -1700,6 +2046,10 @@ impl Channel {
             return Ok(());
         }
 
+        if self.try_dispatch_skill_command(&message).await {
+            return Ok(());
+        }
+
         let rewritten_text = if message.source == "system" {
             raw_text.clone()
         } else {
@@ -1707,7 +2057,7 @@ impl Channel {
                 .unwrap_or_else(|| raw_text.clone())
         };
 
-        let temporal_context = TemporalContext::from_runtime(self.deps.runtime_config.as_ref());
+        let temporal_context = self.temporal_context().await;
         let message_timestamp = temporal_context.format_timestamp(message.timestamp);
         let user_text = format_user_message(&rewritten_text, &message, &message_timestamp);
 
@@ -1736,7 +2086,8 @@ impl Channel {
             }
         }
 
-        let system_prompt = self.build_system_prompt().await?;
+        self.experiment_arm = experiments::arm_for_channel(&self.deps, &self.id).await;
+        let mut system_prompt = self.build_system_prompt().await?;
 
         {
             let mut reply_target = self.state.reply_target_message_id.write().await;
@@ -1744,54 +2095,53 @@ impl Channel {
         }
 
         let is_retrigger = message.source == "system";
+        if !is_retrigger && let Some(prefetched) = self.take_prefetched_context(&raw_text) {
+            system_prompt.push_str("\n\n");
+            system_prompt.push_str(&prefetched);
+        }
+        if !is_retrigger
+            && let Some(profile_context) = self.sender_profile_context(&message.sender_id).await
+        {
+            system_prompt.push_str("\n\n");
+            system_prompt.push_str(&profile_context);
+        }
+        if let Some(language_context) = self
+            .language_context((!is_retrigger).then_some(&message))
+            .await
+        {
+            system_prompt.push_str("\n\n");
+            system_prompt.push_str(&language_context);
+        }
         let attachment_content = if !attachments.is_empty() {
-            if let Some(ref saved_data) = saved_attachment_data {
-                // Reuse already-downloaded bytes for images/text; audio still
-                // needs transcription via the normal path so we fall through.
-                let mut content = Vec::new();
-                let mut unsaved_attachments = Vec::new();
-
-                for (index, attachment) in attachments.iter().enumerate() {
-                    if let Some((_, bytes)) = saved_data.get(index) {
-                        // Audio attachments need transcription, not just bytes
-                        if attachment.mime_type.starts_with("audio/") {
-                            unsaved_attachments.push(attachment.clone());
-                        } else {
-                            content
-                                .push(channel_attachments::content_from_bytes(bytes, attachment));
-                        }
-                    } else {
-                        unsaved_attachments.push(attachment.clone());
-                    }
-                }
-
-                // Process any attachments that weren't saved (or need transcription)
-                if !unsaved_attachments.is_empty() {
-                    let extra = download_attachments(&self.deps, &unsaved_attachments).await;
-                    content.extend(extra);
-                }
-                content
-            } else {
-                download_attachments(&self.deps, &attachments).await
-            }
+            channel_attachments::attachment_contents(
+                &self.deps,
+                &attachments,
+                saved_attachment_data.as_deref(),
+                &transcriptions,
+            )
+            .await
         } else {
             Vec::new()
         };
 
         let adapter = message
             .adapter
-            .as_deref()
-            .or_else(|| self.current_adapter());
-        let (result, skip_flag, replied_flag, retrigger_reply_preserved) = self
-            .run_agent_turn(
+            .clone()
+            .or_else(|| self.current_adapter().map(str::to_string));
+        let Some((result, skip_flag, replied_flag, retrigger_reply_preserved)) = self
+            .run_turn(
                 &user_text,
                 &system_prompt,
                 &message.conversation_id,
                 attachment_content,
                 is_retrigger,
-                adapter,
+                adapter.as_deref(),
+                Some(message.sender_id.as_str()),
             )
-            .await?;
+            .await?
+        else {
+            return Ok(());
+        };
 
         self.handle_agent_result(result, &skip_flag, &replied_flag, is_retrigger)
             .await;
@@ -1893,7 +2243,12 @@ impl Channel {
         }
 
         // Check context size and trigger compaction if needed
-        if let Err(error) = self.compactor.check_and_compact().await {
+        let routing = self.deps.runtime_config.routing.load();
+        if let Err(error) = self
+            .compactor
+            .check_and_compact(self.turn_model(&routing))
+            .await
+        {
             tracing::warn!(channel_id = %self.id, %error, "compaction check failed");
         }
 
@@ -1902,6 +2257,10 @@ impl Channel {
             self.retrigger_count = 0;
             self.message_count += 1;
             self.check_memory_persistence().await;
+
+            if replied_flag.load(std::sync::atomic::Ordering::Relaxed) {
+                self.check_prefetch().await;
+            }
         }
 
         Ok(())
@@ -2141,13 +2500,31 @@ impl Channel {
         info
     }
 
+    /// The prompt engine for the current turn: the experiment variant's, if
+    /// it replaces a template, otherwise the agent's.
+    fn turn_prompt_engine(&self) -> Arc<crate::prompts::PromptEngine> {
+        self.experiment_arm
+            .as_ref()
+            .and_then(|arm| arm.prompt_engine.clone())
+            .unwrap_or_else(|| self.deps.runtime_config.prompts.load_full())
+    }
+
+    /// The model for the current turn: the experiment variant's, if it pins
+    /// one, otherwise the channel's routed model.
+    fn turn_model<'a>(&'a self, routing: &'a crate::llm::RoutingConfig) -> &'a str {
+        self.experiment_arm
+            .as_ref()
+            .and_then(|arm| arm.model.as_deref())
+            .unwrap_or_else(|| routing.resolve(ProcessType::Channel, Some(&*self.id)))
+    }
+
     /// Assemble the full system prompt using the PromptEngine.
     async fn build_system_prompt(&self) -> crate::error::Result<String> {
         let rc = &self.deps.runtime_config;
-        let prompt_engine = rc.prompts.load();
+        let prompt_engine = self.turn_prompt_engine();
 
         let identity_context = rc.identity.load().render();
-        let memory_bulletin = rc.memory_bulletin.load();
+        let memory_bulletin = rc.rendered_bulletin();
         let skills = rc.skills.load();
         let skills_prompt = skills.render_channel_prompt(&prompt_engine)?;
 
@@ -2163,7 +2540,7 @@ impl Channel {
             &mcp_tool_names,
         )?;
 
-        let temporal_context = TemporalContext::from_runtime(rc.as_ref());
+        let temporal_context = self.temporal_context().await;
         let current_time_line = temporal_context.current_time_line();
         let system_info = self.build_system_info().await;
         let status_text = {
@@ -2185,7 +2562,7 @@ impl Channel {
 
         prompt_engine.render_channel_prompt_with_links(
             empty_to_none(identity_context),
-            empty_to_none(memory_bulletin.to_string()),
+            empty_to_none(memory_bulletin),
             empty_to_none(skills_prompt),
             worker_capabilities,
             self.conversation_context.clone(),
@@ -2200,11 +2577,108 @@ impl Channel {
         )
     }
 
+    /// Run an agent turn that a new message may interrupt, per `[channel] interrupt`.
+    ///
+    /// `trigger_sender` is the one person the turn answers, if there is one.
+    /// Returns `None` when the turn was interrupted. The cancelled turn's
+    /// message is kept in history, and the new message is handled next, so
+    /// the restarted turn sees both.
+    #[allow(clippy::too_many_arguments)]
+    async fn run_turn(
+        &mut self,
+        user_text: &str,
+        system_prompt: &str,
+        conversation_id: &str,
+        attachment_content: Vec<UserContent>,
+        is_retrigger: bool,
+        adapter: Option<&str>,
+        trigger_sender: Option<&str>,
+    ) -> Result<Option<AgentTurn>> {
+        // Retrigger turns relay background results and always finish. In
+        // listen-only mode the new message might be ignored, so turns finish too.
+        let watched_sender = if is_retrigger || self.listen_only_mode {
+            None
+        } else {
+            match self.deps.runtime_config.channel_config.load().interrupt {
+                crate::config::TurnInterrupt::Queue => None,
+                crate::config::TurnInterrupt::Sender => {
+                    trigger_sender.map(|sender| Some(sender.to_string()))
+                }
+                crate::config::TurnInterrupt::Any => Some(None),
+            }
+        };
+
+        // The receiver moves into the watch for the turn and comes back after.
+        let mut watch = watched_sender.map(|sender_id| {
+            let message_rx = std::mem::replace(&mut self.message_rx, mpsc::channel(1).1);
+            InterruptWatch::new(sender_id, message_rx)
+        });
+        // Kept so an interrupted turn's message is recorded with its attachments.
+        let interrupted_attachments = watch.as_ref().map(|_| attachment_content.clone());
+        let result = self
+            .run_agent_turn(
+                user_text,
+                system_prompt,
+                conversation_id,
+                attachment_content,
+                is_retrigger,
+                adapter,
+                watch.as_mut(),
+            )
+            .await;
+        let Some(watch) = watch else {
+            return result;
+        };
+        self.message_rx = watch.message_rx;
+        self.inbound_backlog.extend(watch.held);
+        let turn = result?;
+
+        if let Some(follow_up) = watch.follow_up {
+            tracing::info!(
+                channel_id = %self.id,
+                sender_id = %follow_up.sender_id,
+                "new message interrupted the turn"
+            );
+            // The marker keeps history alternating and tells the model it
+            // never answered this message.
+            let marker = self
+                .deps
+                .runtime_config
+                .prompts
+                .load()
+                .render_system_interrupted_turn()?;
+            let mut content = interrupted_attachments.unwrap_or_default();
+            content.push(UserContent::text(user_text));
+            {
+                let mut history = self.state.history.write().await;
+                history.push(rig::message::Message::User {
+                    content: OneOrMany::many(content)
+                        .unwrap_or_else(|_| OneOrMany::one(UserContent::text(user_text))),
+                });
+                history.push(rig::message::Message::Assistant {
+                    id: None,
+                    content: OneOrMany::one(rig::message::AssistantContent::text(marker)),
+                });
+            }
+            if let Err(error) = self.deps.event_tx.send(ProcessEvent::TurnInterrupted {
+                agent_id: self.deps.agent_id.clone(),
+                channel_id: self.id.clone(),
+                sender_id: follow_up.sender_id.clone(),
+            }) {
+                tracing::debug!(%error, channel_id = %self.id, "failed to emit turn interrupted event");
+            }
+            // Goes through the usual debounce, so the sender can keep typing.
+            self.inbound_backlog.push_front(follow_up);
+        }
+        Ok(turn)
+    }
+
     /// Register per-turn tools, run the LLM agentic loop, and clean up.
     ///
-    /// Returns the prompt result and per-turn flags for the caller to dispatch.
-    #[allow(clippy::type_complexity)]
-    #[tracing::instrument(skip(self, user_text, system_prompt, attachment_content), fields(channel_id = %self.id, agent_id = %self.deps.agent_id))]
+    /// Returns the prompt result and per-turn flags for the caller to dispatch,
+    /// or `None` when `interrupt` saw a follow-up before the turn's first tool call.
+    #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(skip(self, user_text, system_prompt, attachment_content, interrupt), fields(channel_id = %self.id, agent_id = %self.deps.agent_id))]
     async fn run_agent_turn(
         &self,
         user_text: &str,
@@ -2213,15 +2687,16 @@ impl Channel {
         attachment_content: Vec<UserContent>,
         is_retrigger: bool,
         adapter: Option<&str>,
-    ) -> Result<(
-        std::result::Result<String, rig::completion::PromptError>,
-        crate::tools::SkipFlag,
-        crate::tools::RepliedFlag,
-        bool,
-    )> {
+        interrupt: Option<&mut InterruptWatch>,
+    ) -> Result<Option<AgentTurn>> {
         let skip_flag = crate::tools::new_skip_flag();
         let replied_flag = crate::tools::new_replied_flag();
         let allow_direct_reply = !self.suppress_plaintext_fallback();
+        let tool_started = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let hook = self
+            .hook
+            .clone()
+            .with_tool_started_flag(tool_started.clone());
 
         // Set the originating channel on the delegation tool so task completion
         // notifications route back to this conversation.
@@ -2270,10 +2745,12 @@ impl Channel {
         } else {
             **rc.max_turns.load()
         };
-        let model_name = routing.resolve(ProcessType::Channel, None);
+        let model_name = self.turn_model(&routing);
         let model = SpacebotModel::make(&self.deps.llm_manager, model_name)
             .with_context(&*self.deps.agent_id, "channel")
-            .with_routing((**routing).clone());
+            .with_channel(&*self.id)
+            .with_routing((**routing).clone())
+            .with_budget(self.deps.budget_guard(SpendScope::channel(self.id.clone())));
 
         let agent = AgentBuilder::new(model)
             .preamble(system_prompt)
@@ -2286,7 +2763,8 @@ impl Channel {
             .ok();
 
         // Inject attachments as a user message before the text prompt
-        if !attachment_content.is_empty() {
+        let attachments_injected = !attachment_content.is_empty();
+        if attachments_injected {
             let mut history = self.state.history.write().await;
             let content = OneOrMany::many(attachment_content).unwrap_or_else(|_| {
                 OneOrMany::one(UserContent::text("[attachment processing failed]"))
@@ -2318,6 +2796,17 @@ impl Channel {
             drop(history);
         }
 
+        // Compact now if this turn's prompt is close to the model's context
+        // window, rather than waiting for the post-turn check. Past the
+        // aggressive threshold this waits for the summary to land in history.
+        if let Err(error) = self
+            .compactor
+            .compact_before_turn(model_name, system_prompt, user_text)
+            .await
+        {
+            tracing::warn!(channel_id = %self.id, %error, "pre-turn compaction failed");
+        }
+
         // Clone history out so the write lock is released before the agentic loop.
         // The branch tool needs a read lock on history to clone it for the branch,
         // and holding a write lock across the entire agentic loop would deadlock.
@@ -2328,9 +2817,34 @@ impl Channel {
         let history_len_before = history.len();
 
         // ── Prompt snapshot capture (fire-and-forget) ──
-        self.maybe_capture_snapshot(system_prompt, user_text, &history);
+        self.maybe_capture_snapshot(model_name, system_prompt, user_text, &history)
+            .await;
 
-        let mut result = self.hook.prompt_once(&agent, &mut history, user_text).await;
+        let first_attempt = hook.prompt_once(&agent, &mut history, user_text);
+        let mut result = match interrupt {
+            Some(watch) => tokio::select! {
+                result = first_attempt => result,
+                () = watch.wait_for_follow_up(&tool_started) => {
+                    // Discard the turn's partial history and the attachment
+                    // message injected above; `run_turn` records the message,
+                    // attachments included, with the interrupted marker instead.
+                    if attachments_injected {
+                        let mut history = self.state.history.write().await;
+                        if matches!(history.last(), Some(rig::message::Message::User { .. })) {
+                            history.pop();
+                        }
+                    }
+                    if let Err(error) =
+                        crate::tools::remove_channel_tools(&self.tool_server, allow_direct_reply)
+                            .await
+                    {
+                        tracing::warn!(%error, "failed to remove channel tools");
+                    }
+                    return Ok(None);
+                }
+            },
+            None => first_attempt.await,
+        };
 
         // If the LLM responded with text that looks like tool call syntax, it failed
         // to use the tool calling API. Inject a correction and retry a couple
@@ -2353,10 +2867,25 @@ impl Channel {
 
             let prompt_engine = self.deps.runtime_config.prompts.load();
             let correction = prompt_engine.render_system_tool_syntax_correction()?;
-            result = self
-                .hook
-                .prompt_once(&agent, &mut history, &correction)
-                .await;
+            result = hook.prompt_once(&agent, &mut history, &correction).await;
+        }
+
+        if let Some(arm) = &self.experiment_arm {
+            let (tool_calls, tool_errors) =
+                experiments::tool_call_stats(history.get(history_len_before..).unwrap_or_default());
+            let store = experiments::ExperimentStore::new(self.deps.sqlite_pool.clone());
+            if let Err(error) = store
+                .record_turn(
+                    &arm.experiment_id,
+                    &self.id,
+                    arm.variant,
+                    tool_calls,
+                    tool_errors,
+                )
+                .await
+            {
+                tracing::warn!(%error, channel_id = %self.id, "failed to record experiment outcome");
+            }
         }
 
         let retrigger_reply_preserved = {
@@ -2377,7 +2906,12 @@ impl Channel {
             tracing::warn!(%error, "failed to remove channel tools");
         }
 
-        Ok((result, skip_flag, replied_flag, retrigger_reply_preserved))
+        Ok(Some((
+            result,
+            skip_flag,
+            replied_flag,
+            retrigger_reply_preserved,
+        )))
     }
 
     /// Send outbound text and record send metrics.
@@ -2701,6 +3235,7 @@ impl Channel {
                     .remove(branch_id)
                     .is_some();
                 let was_memory_persistence = self.memory_persistence_branches.remove(branch_id);
+                let prefetch_turn = self.prefetch_branches.remove(branch_id);
                 if !was_active {
                     if was_memory_persistence {
                         tracing::info!(
@@ -2708,6 +3243,12 @@ impl Channel {
                             "stale memory-persistence branch completion ignored"
                         );
                     }
+                    if prefetch_turn.is_some() {
+                        tracing::debug!(
+                            branch_id = %branch_id,
+                            "stale prefetch branch completion ignored"
+                        );
+                    }
                     self.branch_reply_targets.remove(branch_id);
                     return Ok(());
                 }
@@ -2725,6 +3266,11 @@ impl Channel {
                 // happened inside the branch via tool calls.
                 if was_memory_persistence {
                     tracing::info!(branch_id = %branch_id, "memory persistence branch completed");
+                } else if let Some(spawned_turn) = prefetch_turn {
+                    // Prefetch branches complete silently too. The conclusion is
+                    // cached until the next user message, and only if no user
+                    // message has arrived since the branch was spawned.
+                    self.cache_prefetch_result(*branch_id, spawned_turn, conclusion);
                 } else {
                     // Regular branch: accumulate result for the next retrigger.
                     // The result text will be embedded directly in the retrigger
@@ -2823,6 +3369,22 @@ impl Channel {
             } => {
                 run_logger.log_opencode_metadata(*worker_id, session_id, *port);
             }
+            ProcessEvent::WorkerBatchComplete {
+                batch_id,
+                results,
+                summary,
+                success,
+                ..
+            } => {
+                self.pending_results.push(PendingResult {
+                    process_type: "worker_batch",
+                    process_id: batch_id.to_string(),
+                    result: crate::agent::worker_batch::render(summary, results),
+                    success: *success,
+                });
+                should_retrigger = true;
+                tracing::info!(batch_id = %batch_id, %summary, "worker batch completed, results queued for retrigger");
+            }
             ProcessEvent::WorkerInitialResult {
                 worker_id, result, ..
             } => {
@@ -3061,13 +3623,103 @@ impl Channel {
 
     /// Get the current status block as a string.
     pub async fn get_status(&self) -> String {
-        let temporal_context = TemporalContext::from_runtime(self.deps.runtime_config.as_ref());
+        let temporal_context = self.temporal_context().await;
         let current_time_line = temporal_context.current_time_line();
         let system_info = self.build_system_info().await;
         let status = self.state.status_block.read().await;
         status.render_full(&current_time_line, &system_info)
     }
 
+    /// Spawn a speculative prefetch branch after a reply, if enabled.
+    ///
+    /// Only one prefetch runs at a time so a chatty channel doesn't fill its
+    /// branch slots with speculative work.
+    async fn check_prefetch(&mut self) {
+        let config = **self.deps.runtime_config.prefetch.load();
+        if !config.enabled || !self.prefetch_branches.is_empty() {
+            return;
+        }
+
+        match spawn_prefetch_branch(&self.state).await {
+            Ok(branch_id) => {
+                self.prefetch_branches.insert(branch_id, self.prefetch_turn);
+                tracing::debug!(
+                    channel_id = %self.id,
+                    branch_id = %branch_id,
+                    "prefetch branch spawned"
+                );
+            }
+            Err(error) => {
+                tracing::debug!(
+                    channel_id = %self.id,
+                    %error,
+                    "skipped prefetch branch"
+                );
+            }
+        }
+    }
+
+    /// Cache a completed prefetch branch's conclusion for the next user turn.
+    fn cache_prefetch_result(&mut self, branch_id: BranchId, spawned_turn: u64, conclusion: &str) {
+        if spawned_turn != self.prefetch_turn {
+            tracing::debug!(
+                channel_id = %self.id,
+                branch_id = %branch_id,
+                "prefetch branch finished after the follow-up arrived, discarding"
+            );
+            return;
+        }
+
+        self.prefetched_context = PrefetchedContext::parse(conclusion);
+        tracing::debug!(
+            channel_id = %self.id,
+            branch_id = %branch_id,
+            cached = self.prefetched_context.is_some(),
+            "prefetch branch completed"
+        );
+    }
+
+    /// Consume the cached prefetch for this user turn.
+    ///
+    /// Returns the rendered prompt fragment when the message matches the
+    /// predicted follow-up. The cache is cleared either way: a prediction is
+    /// only useful for the message right after the reply it was made from.
+    fn take_prefetched_context(&mut self, message_text: &str) -> Option<String> {
+        self.prefetch_turn += 1;
+        let prefetched = self.prefetched_context.take()?;
+
+        let config = **self.deps.runtime_config.prefetch.load();
+        if !config.enabled || prefetched.is_expired(std::time::Duration::from_secs(config.ttl_secs))
+        {
+            return None;
+        }
+        if !prefetched.matches(message_text) {
+            tracing::debug!(
+                channel_id = %self.id,
+                topics = ?prefetched.topics,
+                "prefetched context did not match follow-up"
+            );
+            return None;
+        }
+
+        tracing::info!(
+            channel_id = %self.id,
+            topics = ?prefetched.topics,
+            "injecting prefetched context into turn"
+        );
+        let prompt_engine = self.deps.runtime_config.prompts.load();
+        prompt_engine
+            .render_prefetched_context(
+                &prefetched.topics,
+                &prefetched.age_label(),
+                &prefetched.context,
+            )
+            .inspect_err(|error| {
+                tracing::warn!(channel_id = %self.id, %error, "failed to render prefetched context");
+            })
+            .ok()
+    }
+
     /// Check if a memory persistence branch should be spawned based on message count.
     async fn check_memory_persistence(&mut self) {
         let config = **self.deps.runtime_config.memory_persistence.load();
@@ -3103,10 +3755,11 @@ impl Channel {
     }
 
     /// If prompt capture is enabled for this channel, snapshot the current
-    /// system prompt sections and conversation history. The save is
-    /// fire-and-forget so it never blocks the agentic loop.
-    fn maybe_capture_snapshot(
+    /// system prompt sections, conversation history and offered tools. The
+    /// save is fire-and-forget so it never blocks the agentic loop.
+    async fn maybe_capture_snapshot(
         &self,
+        model_name: &str,
         system_prompt: &str,
         user_message: &str,
         history: &[rig::message::Message],
@@ -3145,6 +3798,15 @@ impl Channel {
         let history_length = history.len();
         let system_prompt_chars = system_prompt.chars().count();
 
+        // 4. Record the tools offered this turn so the snapshot can be replayed.
+        let tool_definitions = match self.tool_server.get_tool_defs(None).await {
+            Ok(definitions) => definitions,
+            Err(error) => {
+                tracing::debug!(channel_id = %self.id, %error, "failed to list tools for prompt snapshot");
+                Vec::new()
+            }
+        };
+
         let snapshot = crate::agent::prompt_snapshot::PromptSnapshot {
             channel_id: self.id.to_string(),
             timestamp_ms: chrono::Utc::now().timestamp_millis(),
@@ -3153,6 +3815,8 @@ impl Channel {
             system_prompt_chars,
             history: history_json,
             history_length,
+            model: Some(model_name.to_string()),
+            tool_definitions,
         };
 
         // 5. Fire-and-forget save.
@@ -3214,6 +3878,21 @@ fn compute_listen_mode_invocation(message: &InboundMessage, raw_text: &str) -> (
             .get("twitch_mentions_or_replies_to_bot")
             .and_then(|v| v.as_bool())
             .unwrap_or(false),
+        "github" => message
+            .metadata
+            .get("github_mentions_or_replies_to_bot")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        "matrix" => message
+            .metadata
+            .get("matrix_mentions_or_replies_to_bot")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        "irc" => message
+            .metadata
+            .get("irc_mentions_or_replies_to_bot")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
         _ => false,
     };
     let invoked_by_reply = match message.source.as_str() {
@@ -3302,14 +3981,14 @@ fn should_send_quiet_mode_fallback(
         && !state.replied_flag
         && matches!(
             message.source.as_str(),
-            "discord" | "telegram" | "slack" | "twitch" | "signal"
+            "discord" | "telegram" | "slack" | "twitch" | "signal" | "matrix" | "irc"
         )
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        QuietModeFallbackState, compute_listen_mode_invocation, recv_channel_event,
+        InterruptWatch, QuietModeFallbackState, compute_listen_mode_invocation, recv_channel_event,
         should_process_event_for_channel, should_send_discord_quiet_mode_ping_ack,
         should_send_quiet_mode_fallback,
     };
@@ -3375,6 +4054,100 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn interrupt_watch_only_takes_the_senders_next_message() {
+        let (message_tx, message_rx) = tokio::sync::mpsc::channel(8);
+        let tool_started = std::sync::atomic::AtomicBool::new(false);
+
+        // Another sender's message is held, and after it nothing interrupts.
+        let mut watch = InterruptWatch::new(Some("user-1".into()), message_rx);
+        let mut other_sender = inbound_message("discord", &[], "hello");
+        other_sender.sender_id = "user-2".into();
+        message_tx.send(other_sender).await.unwrap();
+        message_tx
+            .send(inbound_message("discord", &[], "also"))
+            .await
+            .unwrap();
+        let waited = tokio::time::timeout(
+            std::time::Duration::from_millis(20),
+            watch.wait_for_follow_up(&tool_started),
+        )
+        .await;
+        assert!(waited.is_err());
+        assert_eq!(watch.held.len(), 2);
+        assert!(watch.follow_up.is_none());
+
+        // Commands never interrupt.
+        let mut watch = InterruptWatch::new(Some("user-1".into()), watch.message_rx);
+        message_tx
+            .send(inbound_message("discord", &[], "/status"))
+            .await
+            .unwrap();
+        let waited = tokio::time::timeout(
+            std::time::Duration::from_millis(20),
+            watch.wait_for_follow_up(&tool_started),
+        )
+        .await;
+        assert!(waited.is_err());
+        assert_eq!(watch.held.len(), 1);
+
+        // The sender's next plain message does.
+        let mut watch = InterruptWatch::new(Some("user-1".into()), watch.message_rx);
+        message_tx
+            .send(inbound_message("discord", &[], "one more thing"))
+            .await
+            .unwrap();
+        watch.wait_for_follow_up(&tool_started).await;
+        assert!(watch.held.is_empty());
+        assert!(watch.follow_up.is_some());
+
+        // Once the turn has started a tool call, follow-ups wait for the next turn.
+        let mut watch = InterruptWatch::new(Some("user-1".into()), watch.message_rx);
+        tool_started.store(true, std::sync::atomic::Ordering::Relaxed);
+        message_tx
+            .send(inbound_message("discord", &[], "thanks"))
+            .await
+            .unwrap();
+        let waited = tokio::time::timeout(
+            std::time::Duration::from_millis(20),
+            watch.wait_for_follow_up(&tool_started),
+        )
+        .await;
+        assert!(waited.is_err());
+        assert_eq!(watch.held.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn interrupt_watch_without_a_sender_takes_anyones_message() {
+        let (message_tx, message_rx) = tokio::sync::mpsc::channel(8);
+        let tool_started = std::sync::atomic::AtomicBool::new(false);
+        let mut watch = InterruptWatch::new(None, message_rx);
+
+        message_tx
+            .send(inbound_message("system", &[], "worker finished"))
+            .await
+            .unwrap();
+        let mut other_sender = inbound_message("discord", &[], "wait, one more");
+        other_sender.sender_id = "user-2".into();
+        message_tx.send(other_sender).await.unwrap();
+        let waited = tokio::time::timeout(
+            std::time::Duration::from_millis(20),
+            watch.wait_for_follow_up(&tool_started),
+        )
+        .await;
+        assert!(waited.is_err(), "a held message keeps later ones in order");
+
+        let mut watch = InterruptWatch::new(None, watch.message_rx);
+        let mut other_sender = inbound_message("discord", &[], "actually, make it Friday");
+        other_sender.sender_id = "user-2".into();
+        message_tx.send(other_sender).await.unwrap();
+        watch.wait_for_follow_up(&tool_started).await;
+        assert_eq!(
+            watch.follow_up.map(|message| message.sender_id),
+            Some("user-2".to_string())
+        );
+    }
+
     #[tokio::test]
     async fn channel_event_loop_stops_when_event_bus_closes() {
         let (event_tx, mut event_rx) = tokio::sync::broadcast::channel::<ProcessEvent>(2);
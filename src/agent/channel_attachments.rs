@@ -52,7 +52,7 @@ pub(crate) async fn download_attachments(
         } else if is_text {
             download_text_attachment(http, attachment).await
         } else if attachment.mime_type.starts_with("audio/") {
-            transcribe_audio_attachment(deps, http, attachment).await
+            transcribe_audio_attachment(deps, attachment).await
         } else {
             let size_str = attachment
                 .size_bytes
@@ -79,7 +79,9 @@ async fn download_attachment_bytes(
     http: &reqwest::Client,
     attachment: &crate::Attachment,
 ) -> std::result::Result<Vec<u8>, String> {
-    if attachment.auth_header.is_some() {
+    if let Some(data_url) = attachment.url.strip_prefix("data:") {
+        decode_data_url(data_url)
+    } else if attachment.auth_header.is_some() {
         download_attachment_bytes_with_auth(attachment).await
     } else {
         let response = http
@@ -98,6 +100,21 @@ async fn download_attachment_bytes(
     }
 }
 
+/// Decode the part of a base64 `data:` URL after the scheme. Webchat uploads
+/// arrive this way since they have no hosted URL to fetch from.
+fn decode_data_url(data_url: &str) -> std::result::Result<Vec<u8>, String> {
+    let (media_type, data) = data_url
+        .split_once(',')
+        .ok_or_else(|| "malformed data URL".to_string())?;
+    if !media_type.ends_with(";base64") {
+        return Err("data URL is not base64-encoded".into());
+    }
+    use base64::Engine as _;
+    base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| format!("invalid base64 in data URL: {e}"))
+}
+
 /// Slack-specific download: manually follows redirects, only forwarding the
 /// Authorization header when the redirect target shares the same host as the
 /// original URL. This prevents credential leakage on cross-origin redirects.
@@ -187,65 +204,241 @@ async fn download_image_attachment(
     UserContent::image_base64(base64_data, media_type, None)
 }
 
-/// Download an audio attachment and transcribe it with the configured voice model.
+/// Transcript of one audio attachment. Recorded in the inbound message's
+/// metadata under `transcriptions` so history and recall can show it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioTranscription {
+    /// Position of the attachment within its message.
+    pub index: usize,
+    pub filename: String,
+    pub mime_type: String,
+    /// Transcript text. None when transcription failed.
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl AudioTranscription {
+    /// Render as LLM content: the transcript, or a failure placeholder.
+    pub(crate) fn to_content(&self) -> UserContent {
+        match &self.text {
+            Some(text) => UserContent::text(format!(
+                "<voice_transcript name=\"{}\" mime=\"{}\">\n{}\n</voice_transcript>",
+                self.filename, self.mime_type, text
+            )),
+            None => UserContent::text(format!(
+                "[Audio transcription failed for {}: {}]",
+                self.filename,
+                self.error.as_deref().unwrap_or("unknown error")
+            )),
+        }
+    }
+}
+
+/// Transcribe every audio attachment in a message.
+///
+/// Bytes already downloaded by `save_channel_attachments` are reused when
+/// available. Results are returned in attachment order.
+pub(crate) async fn transcribe_audio_attachments(
+    deps: &AgentDeps,
+    attachments: &[crate::Attachment],
+    saved: Option<&[SavedAttachmentWithBytes]>,
+) -> Vec<AudioTranscription> {
+    let http = deps.llm_manager.http_client();
+    let mut transcriptions = Vec::new();
+
+    for (index, attachment) in attachments.iter().enumerate() {
+        if !attachment.mime_type.starts_with("audio/") {
+            continue;
+        }
+
+        let saved_bytes = saved
+            .and_then(|saved| saved.get(index))
+            .filter(|(meta, _)| meta.filename == attachment.filename)
+            .map(|(_, bytes)| bytes.clone());
+        let bytes = match saved_bytes {
+            Some(bytes) => Ok(bytes),
+            None => download_attachment_bytes(http, attachment).await,
+        };
+
+        let result = match bytes {
+            Ok(bytes) => transcribe_audio(deps, attachment, bytes).await,
+            Err(error) => {
+                tracing::warn!(%error, filename = %attachment.filename, "failed to download audio");
+                Err(format!("download failed ({error})"))
+            }
+        };
+
+        let (text, error) = match result {
+            Ok(text) => (Some(text), None),
+            Err(error) => (None, Some(error)),
+        };
+        transcriptions.push(AudioTranscription {
+            index,
+            filename: attachment.filename.clone(),
+            mime_type: attachment.mime_type.clone(),
+            text,
+            error,
+        });
+    }
+
+    transcriptions
+}
+
+/// Download an audio attachment and transcribe it.
 async fn transcribe_audio_attachment(
     deps: &AgentDeps,
-    http: &reqwest::Client,
     attachment: &crate::Attachment,
 ) -> UserContent {
-    let bytes = match download_attachment_bytes(http, attachment).await {
-        Ok(b) => b,
-        Err(error) => {
-            tracing::warn!(%error, filename = %attachment.filename, "failed to download audio");
-            return UserContent::text(format!(
-                "[Failed to download audio: {}]",
-                attachment.filename
-            ));
-        }
-    };
+    transcribe_audio_attachments(deps, std::slice::from_ref(attachment), None)
+        .await
+        .first()
+        .map(AudioTranscription::to_content)
+        .unwrap_or_else(|| UserContent::text(format!("[Attachment: {}]", attachment.filename)))
+}
 
+/// Transcribe audio bytes with the configured backend: a Whisper-compatible
+/// endpoint when `transcription.url` is set, otherwise the `routing.voice`
+/// model. Errors are short, user-presentable reasons.
+async fn transcribe_audio(
+    deps: &AgentDeps,
+    attachment: &crate::Attachment,
+    bytes: Vec<u8>,
+) -> std::result::Result<String, String> {
     tracing::info!(
         filename = %attachment.filename,
         mime = %attachment.mime_type,
         size = bytes.len(),
-        "downloaded audio attachment"
+        "transcribing audio attachment"
     );
 
+    let config = deps.runtime_config.transcription.load_full();
+    let max_bytes = config.max_file_mb.saturating_mul(1024 * 1024);
+    if bytes.len() as u64 > max_bytes {
+        return Err(format!(
+            "file exceeds the {} MB transcription limit",
+            config.max_file_mb
+        ));
+    }
+
+    let transcript = match config.url.as_deref() {
+        Some(url) => transcribe_with_whisper_endpoint(deps, &config, url, attachment, bytes).await,
+        None => transcribe_with_voice_model(deps, attachment, bytes).await,
+    }?;
+
+    if transcript.is_empty() {
+        tracing::warn!(filename = %attachment.filename, "empty transcription returned");
+        return Err("transcription returned empty text".into());
+    }
+    Ok(transcript)
+}
+
+/// POST the audio to a Whisper-compatible `/audio/transcriptions` endpoint.
+async fn transcribe_with_whisper_endpoint(
+    deps: &AgentDeps,
+    config: &crate::config::TranscriptionConfig,
+    url: &str,
+    attachment: &crate::Attachment,
+    bytes: Vec<u8>,
+) -> std::result::Result<String, String> {
+    let file_part = reqwest::multipart::Part::bytes(bytes)
+        .file_name(whisper_upload_filename(attachment))
+        .mime_str(&attachment.mime_type)
+        .map_err(|error| format!("invalid audio type ({error})"))?;
+    let mut form = reqwest::multipart::Form::new()
+        .part("file", file_part)
+        .text("model", config.model.clone())
+        .text("response_format", "json");
+    if let Some(language) = &config.language {
+        form = form.text("language", language.clone());
+    }
+
+    let mut request = deps
+        .llm_manager
+        .http_client()
+        .post(url)
+        .multipart(form)
+        .timeout(std::time::Duration::from_secs(300));
+    if let Some(api_key) = &config.api_key {
+        request = request.bearer_auth(api_key);
+    }
+
+    let response = request.send().await.map_err(|error| {
+        tracing::warn!(%error, %url, "transcription endpoint request failed");
+        "transcription endpoint unreachable".to_string()
+    })?;
+    let status = response.status();
+    let body = response.text().await.map_err(|error| error.to_string())?;
+
+    if !status.is_success() {
+        let message = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|value| value["error"]["message"].as_str().map(str::to_string))
+            .unwrap_or_else(|| format!("HTTP {status}"));
+        tracing::warn!(%status, %url, error = %message, "transcription endpoint returned error");
+        return Err(message);
+    }
+
+    // `response_format=json` returns {"text": ...}; some local servers ignore
+    // the format and answer with plain text.
+    Ok(match serde_json::from_str::<serde_json::Value>(&body) {
+        Ok(value) => value["text"]
+            .as_str()
+            .unwrap_or_default()
+            .trim()
+            .to_string(),
+        Err(_) => body.trim().to_string(),
+    })
+}
+
+/// Whisper endpoints infer the container from the upload's extension.
+fn whisper_upload_filename(attachment: &crate::Attachment) -> String {
+    let has_extension = Path::new(&attachment.filename).extension().is_some();
+    if has_extension {
+        attachment.filename.clone()
+    } else {
+        format!(
+            "{}.{}",
+            attachment.filename,
+            audio_format_for_attachment(attachment)
+        )
+    }
+}
+
+/// Transcribe through chat completions with the `routing.voice` model.
+async fn transcribe_with_voice_model(
+    deps: &AgentDeps,
+    attachment: &crate::Attachment,
+    bytes: Vec<u8>,
+) -> std::result::Result<String, String> {
     let routing = deps.runtime_config.routing.load();
     let voice_model = routing.voice.trim();
     if voice_model.is_empty() {
-        return UserContent::text(format!(
-            "[Audio attachment received but no voice model is configured in routing.voice: {}]",
-            attachment.filename
-        ));
+        return Err(
+            "no voice model is configured in routing.voice or transcription.url".to_string(),
+        );
     }
 
-    let (provider_id, model_name) = match deps.llm_manager.resolve_model(voice_model) {
-        Ok(parts) => parts,
-        Err(error) => {
-            tracing::warn!(%error, model = %voice_model, "invalid voice model route");
-            return UserContent::text(format!(
-                "[Audio transcription failed for {}: invalid voice model '{}']",
-                attachment.filename, voice_model
-            ));
-        }
-    };
+    let (provider_id, model_name) =
+        deps.llm_manager
+            .resolve_model(voice_model)
+            .map_err(|error| {
+                tracing::warn!(%error, model = %voice_model, "invalid voice model route");
+                format!("invalid voice model '{voice_model}'")
+            })?;
 
-    let provider = match deps.llm_manager.get_provider(&provider_id) {
-        Ok(provider) => provider,
-        Err(error) => {
+    let provider = deps
+        .llm_manager
+        .get_provider(&provider_id)
+        .map_err(|error| {
             tracing::warn!(%error, provider = %provider_id, "voice provider not configured");
-            return UserContent::text(format!(
-                "[Audio transcription failed for {}: provider '{}' is not configured]",
-                attachment.filename, provider_id
-            ));
-        }
-    };
+            format!("provider '{provider_id}' is not configured")
+        })?;
 
     if provider.api_type == ApiType::Anthropic {
-        return UserContent::text(format!(
-            "[Audio transcription failed for {}: provider '{}' does not support input_audio on this endpoint]",
-            attachment.filename, provider_id
+        return Err(format!(
+            "provider '{provider_id}' does not support input_audio on this endpoint"
         ));
     }
 
@@ -278,7 +471,7 @@ async fn transcribe_audio_attachment(
         "temperature": 0
     });
 
-    let response = match deps
+    let response = deps
         .llm_manager
         .http_client()
         .post(&endpoint)
@@ -287,28 +480,19 @@ async fn transcribe_audio_attachment(
         .json(&body)
         .send()
         .await
-    {
-        Ok(response) => response,
-        Err(error) => {
+        .map_err(|error| {
             tracing::warn!(%error, model = %voice_model, "voice transcription request failed");
-            return UserContent::text(format!(
-                "[Audio transcription failed for {}]",
-                attachment.filename
-            ));
-        }
-    };
+            "request failed".to_string()
+        })?;
 
     let status = response.status();
-    let response_body = match response.json::<serde_json::Value>().await {
-        Ok(body) => body,
-        Err(error) => {
+    let response_body = response
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|error| {
             tracing::warn!(%error, model = %voice_model, "invalid transcription response");
-            return UserContent::text(format!(
-                "[Audio transcription failed for {}]",
-                attachment.filename
-            ));
-        }
-    };
+            "invalid response".to_string()
+        })?;
 
     if !status.is_success() {
         let message = response_body["error"]["message"]
@@ -320,25 +504,10 @@ async fn transcribe_audio_attachment(
             error = %message,
             "voice transcription provider returned error"
         );
-        return UserContent::text(format!(
-            "[Audio transcription failed for {}: {}]",
-            attachment.filename, message
-        ));
+        return Err(message.to_string());
     }
 
-    let transcript = extract_transcript_text(&response_body);
-    if transcript.is_empty() {
-        tracing::warn!(model = %voice_model, "empty transcription returned");
-        return UserContent::text(format!(
-            "[Audio transcription returned empty text for {}]",
-            attachment.filename
-        ));
-    }
-
-    UserContent::text(format!(
-        "<voice_transcript name=\"{}\" mime=\"{}\">\n{}\n</voice_transcript>",
-        attachment.filename, attachment.mime_type, transcript
-    ))
+    Ok(extract_transcript_text(&response_body))
 }
 
 fn audio_format_for_attachment(attachment: &crate::Attachment) -> &'static str {
@@ -451,10 +620,9 @@ pub(crate) type SavedAttachmentWithBytes = (SavedAttachmentMeta, Vec<u8>);
 /// Build LLM-ready `UserContent` from pre-downloaded bytes. Used when
 /// `save_attachments` is enabled so we don't re-download from the URL.
 ///
-/// Audio attachments are NOT re-transcribed here — audio requires an LLM call
-/// which needs `AgentDeps`. When saving is enabled, audio files are saved to
-/// disk and transcribed via the normal `download_attachments` path (which will
-/// be called separately).
+/// Audio attachments are NOT transcribed here — transcription needs
+/// `AgentDeps`. Callers transcribe them with `transcribe_audio_attachments`,
+/// which reuses the saved bytes.
 pub(crate) fn content_from_bytes(bytes: &[u8], attachment: &crate::Attachment) -> UserContent {
     let is_image = IMAGE_MIME_PREFIXES
         .iter()
@@ -668,21 +836,80 @@ pub(crate) fn format_attachment_annotation(saved: &[SavedAttachmentMeta]) -> Str
 /// Called when loading conversation history so older messages that had
 /// attachments still show the `[Attachments: ...]` annotation.
 pub(crate) fn annotation_from_metadata(metadata: &serde_json::Value) -> Option<String> {
-    let attachments = metadata.get("attachments")?.as_array()?;
-    if attachments.is_empty() {
-        return None;
+    let saved: Vec<SavedAttachmentMeta> = metadata
+        .get("attachments")
+        .and_then(|value| value.as_array())
+        .map(|attachments| {
+            attachments
+                .iter()
+                .filter_map(|value| serde_json::from_value(value.clone()).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    let transcriptions: Vec<AudioTranscription> = metadata
+        .get(TRANSCRIPTIONS_METADATA_KEY)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .unwrap_or_default();
+
+    let mut lines = Vec::new();
+    if !saved.is_empty() {
+        lines.push(format_attachment_annotation(&saved));
     }
+    lines.extend(transcriptions.iter().filter_map(|transcription| {
+        let text = transcription.text.as_deref()?;
+        Some(format!(
+            "[Voice transcript ({}): {}]",
+            transcription.filename, text
+        ))
+    }));
 
-    let saved: Vec<SavedAttachmentMeta> = attachments
-        .iter()
-        .filter_map(|value| serde_json::from_value(value.clone()).ok())
-        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
 
-    if saved.is_empty() {
-        return None;
+/// Metadata key under which audio transcriptions are stored on a message.
+pub(crate) const TRANSCRIPTIONS_METADATA_KEY: &str = "transcriptions";
+
+/// Record transcriptions in inbound message metadata.
+pub(crate) fn record_transcriptions(
+    metadata: &mut std::collections::HashMap<String, serde_json::Value>,
+    transcriptions: &[AudioTranscription],
+) {
+    if transcriptions.is_empty() {
+        return;
+    }
+    if let Ok(value) = serde_json::to_value(transcriptions) {
+        metadata.insert(TRANSCRIPTIONS_METADATA_KEY.to_string(), value);
     }
+}
 
-    Some(format_attachment_annotation(&saved))
+/// Build LLM content for a message's attachments, reusing bytes saved to disk
+/// and transcriptions already produced for this message. Anything not covered
+/// is downloaded.
+pub(crate) async fn attachment_contents(
+    deps: &AgentDeps,
+    attachments: &[crate::Attachment],
+    saved: Option<&[SavedAttachmentWithBytes]>,
+    transcriptions: &[AudioTranscription],
+) -> Vec<UserContent> {
+    let mut content = Vec::new();
+    let mut pending = Vec::new();
+
+    for (index, attachment) in attachments.iter().enumerate() {
+        if let Some(transcription) = transcriptions.iter().find(|t| t.index == index) {
+            content.push(transcription.to_content());
+        } else if let Some((_, bytes)) = saved.and_then(|saved| saved.get(index))
+            && !attachment.mime_type.starts_with("audio/")
+        {
+            content.push(content_from_bytes(bytes, attachment));
+        } else {
+            pending.push(attachment.clone());
+        }
+    }
+
+    if !pending.is_empty() {
+        content.extend(download_attachments(deps, &pending).await);
+    }
+    content
 }
 
 /// Sanitize a user-provided filename to prevent path traversal attacks.
@@ -797,3 +1024,71 @@ async fn filename_taken(pool: &sqlx::SqlitePool, saved_dir: &Path, filename: &st
     // Also check filesystem in case of orphaned files
     saved_dir.join(filename).exists()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attachment(filename: &str, mime_type: &str) -> crate::Attachment {
+        crate::Attachment {
+            filename: filename.to_string(),
+            mime_type: mime_type.to_string(),
+            url: String::new(),
+            size_bytes: None,
+            auth_header: None,
+        }
+    }
+
+    #[test]
+    fn decodes_base64_data_urls() {
+        assert_eq!(
+            decode_data_url("audio/ogg;base64,aGVsbG8=").unwrap(),
+            b"hello"
+        );
+        assert!(decode_data_url("text/plain,hello").is_err());
+        assert!(decode_data_url("audio/ogg;base64").is_err());
+    }
+
+    #[test]
+    fn whisper_filename_gets_an_extension() {
+        assert_eq!(
+            whisper_upload_filename(&attachment("voice-note", "audio/ogg")),
+            "voice-note.ogg"
+        );
+        assert_eq!(
+            whisper_upload_filename(&attachment("memo.m4a", "audio/mp4")),
+            "memo.m4a"
+        );
+    }
+
+    #[test]
+    fn annotation_includes_transcripts() {
+        let mut metadata = std::collections::HashMap::new();
+        record_transcriptions(
+            &mut metadata,
+            &[
+                AudioTranscription {
+                    index: 0,
+                    filename: "note.ogg".into(),
+                    mime_type: "audio/ogg".into(),
+                    text: Some("pick up milk".into()),
+                    error: None,
+                },
+                AudioTranscription {
+                    index: 1,
+                    filename: "broken.ogg".into(),
+                    mime_type: "audio/ogg".into(),
+                    text: None,
+                    error: Some("request failed".into()),
+                },
+            ],
+        );
+
+        let metadata = serde_json::to_value(&metadata).unwrap();
+        assert_eq!(
+            annotation_from_metadata(&metadata).as_deref(),
+            Some("[Voice transcript (note.ogg): pick up milk]")
+        );
+        assert!(annotation_from_metadata(&serde_json::json!({})).is_none());
+    }
+}
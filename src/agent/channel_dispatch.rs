@@ -5,13 +5,18 @@
 //! and `spawn_opencode_worker_from_state`.
 
 use crate::agent::branch::{Branch, BranchExecutionConfig};
+use crate::agent::branch_cache::BranchCacheKey;
 use crate::agent::channel::ChannelState;
 use crate::agent::channel_prompt::TemporalContext;
 use crate::agent::worker::Worker;
+use crate::agent::worker_mailbox::WorkerMailbox;
 use crate::error::{AgentError, Error as SpacebotError};
+use crate::llm::budget::SpendScope;
+use crate::sandbox::container::ContainerSession;
 use crate::tools::{BranchToolProfile, MemoryPersistenceContractState};
 use crate::{AgentDeps, BranchId, ChannelId, ProcessEvent, WorkerId};
 use futures::FutureExt as _;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::broadcast;
 use tracing::Instrument as _;
@@ -83,8 +88,7 @@ fn classify_worker_completion_result(
     }
 }
 
-fn completion_flags(kind: WorkerCompletionKind) -> (bool, bool) {
-    let notify = true;
+fn completion_flags(kind: WorkerCompletionKind, notify: bool) -> (bool, bool) {
     let success = matches!(kind, WorkerCompletionKind::Success);
     (notify, success)
 }
@@ -94,7 +98,7 @@ pub(crate) fn map_worker_completion_result(
     result: std::result::Result<String, WorkerCompletionError>,
 ) -> (String, bool, bool) {
     let (result_text, kind) = classify_worker_completion_result(result);
-    let (notify, success) = completion_flags(kind);
+    let (notify, success) = completion_flags(kind, true);
     (result_text, notify, success)
 }
 
@@ -116,6 +120,8 @@ fn build_worker_status_text(
 #[derive(Debug, Clone)]
 struct BranchSpawnOptions {
     profile: BranchToolProfile,
+    /// Answer from, and feed, the branch result cache.
+    cacheable: bool,
 }
 
 /// Spawn a branch from a ChannelState. Used by the BranchTool.
@@ -142,6 +148,7 @@ pub async fn spawn_branch_from_state(
         "branch",
         BranchSpawnOptions {
             profile: BranchToolProfile::Default,
+            cacheable: true,
         },
     )
     .await
@@ -175,6 +182,43 @@ pub(crate) async fn spawn_memory_persistence_branch(
         "memory_persistence_branch",
         BranchSpawnOptions {
             profile: BranchToolProfile::MemoryPersistence { contract_state },
+            cacheable: false,
+        },
+    )
+    .await
+}
+
+/// Spawn a silent speculative prefetch branch.
+///
+/// Uses the regular branch prompt and tools, but with a task that predicts the
+/// user's likely follow-up and gathers context for it. Like memory persistence
+/// branches, the result is never injected into history directly — the channel
+/// caches it and only uses it if the next user message matches.
+pub(crate) async fn spawn_prefetch_branch(
+    state: &ChannelState,
+) -> std::result::Result<BranchId, AgentError> {
+    let rc = &state.deps.runtime_config;
+    let prompt_engine = rc.prompts.load();
+    let system_prompt = prompt_engine
+        .render_branch_prompt(
+            &rc.instance_dir.display().to_string(),
+            &rc.workspace_dir.display().to_string(),
+        )
+        .map_err(|e| AgentError::Other(anyhow::anyhow!("{e}")))?;
+    let prompt = prompt_engine
+        .render_system_prefetch()
+        .map_err(|e| AgentError::Other(anyhow::anyhow!("{e}")))?;
+
+    spawn_branch(
+        state,
+        "follow-up prefetch",
+        &prompt,
+        &system_prompt,
+        "preparing for follow-ups...",
+        "prefetch_branch",
+        BranchSpawnOptions {
+            profile: BranchToolProfile::Default,
+            cacheable: false,
         },
     )
     .await
@@ -218,6 +262,19 @@ fn ensure_dispatch_readiness(state: &ChannelState, dispatch_type: &'static str)
     }
 }
 
+/// The channel's memory scope override, or the agent default.
+async fn resolve_memory_scope(state: &ChannelState) -> crate::memory::MemoryScope {
+    match state.channel_store.memory_scope(&state.channel_id).await {
+        Ok(Some(scope)) => scope,
+        Ok(None) => state.deps.runtime_config.channel_config.load().memory_scope,
+        Err(error) => {
+            // Fail closed: a private channel must not fall back to global memory.
+            tracing::warn!(%error, channel_id = %state.channel_id, "failed to load channel memory scope");
+            crate::memory::MemoryScope::Channel
+        }
+    }
+}
+
 /// Shared branch spawning logic.
 ///
 /// Checks the branch limit, clones history, creates a Branch, spawns it as
@@ -231,7 +288,7 @@ async fn spawn_branch(
     dispatch_type: &'static str,
     branch_options: BranchSpawnOptions,
 ) -> std::result::Result<BranchId, AgentError> {
-    let BranchSpawnOptions { profile } = branch_options;
+    let BranchSpawnOptions { profile, cacheable } = branch_options;
     let memory_persistence_contract = match &profile {
         BranchToolProfile::MemoryPersistence { contract_state } => Some(contract_state.clone()),
         BranchToolProfile::Default => None,
@@ -247,13 +304,26 @@ async fn spawn_branch(
             });
         }
     }
-    ensure_dispatch_readiness(state, dispatch_type);
 
     let history = {
         let h = state.history.read().await;
         h.clone()
     };
 
+    let cache_config = **state.deps.runtime_config.branch_cache.load();
+    let cache_key = if cacheable && cache_config.enabled {
+        BranchCacheKey::new(&state.channel_id, prompt, &history)
+    } else {
+        None
+    };
+    if let Some(cache_key) = &cache_key
+        && let Some(conclusion) = state.deps.branch_cache.get(cache_key, &cache_config)
+    {
+        return replay_cached_branch(state, status_label, conclusion).await;
+    }
+
+    ensure_dispatch_readiness(state, dispatch_type);
+
     let tool_server = crate::tools::create_branch_tool_server(
         Some(state.clone()),
         state.deps.agent_id.clone(),
@@ -265,6 +335,7 @@ async fn spawn_branch(
         state.channel_store.clone(),
         crate::conversation::ProcessRunLogger::new(state.deps.sqlite_pool.clone()),
         profile,
+        resolve_memory_scope(state).await,
     );
     let branch_max_turns = **state.deps.runtime_config.branch_max_turns.load();
 
@@ -278,6 +349,7 @@ async fn spawn_branch(
         BranchExecutionConfig {
             max_turns: branch_max_turns,
             memory_persistence_contract,
+            cache_key,
         },
     );
 
@@ -364,6 +436,67 @@ async fn spawn_branch(
     Ok(branch_id)
 }
 
+/// Complete a branch from the result cache without running it.
+///
+/// Goes through the same start/result events as a real branch, so the channel
+/// queues the conclusion for its retrigger exactly as it would a fresh one.
+async fn replay_cached_branch(
+    state: &ChannelState,
+    status_label: &str,
+    conclusion: String,
+) -> std::result::Result<BranchId, AgentError> {
+    let branch_id: BranchId = uuid::Uuid::new_v4();
+
+    state.process_run_logger.log_branch_cache_hit(
+        &state.channel_id,
+        branch_id,
+        status_label,
+        &conclusion,
+    );
+
+    {
+        let mut branches = state.active_branches.write().await;
+        branches.insert(branch_id, tokio::spawn(async {}));
+    }
+
+    {
+        let mut status = state.status_block.write().await;
+        status.add_branch(branch_id, status_label);
+    }
+
+    #[cfg(feature = "metrics")]
+    crate::telemetry::Metrics::global()
+        .active_branches
+        .with_label_values(&[&*state.deps.agent_id])
+        .inc();
+
+    state
+        .deps
+        .event_tx
+        .send(crate::ProcessEvent::BranchStarted {
+            agent_id: state.deps.agent_id.clone(),
+            branch_id,
+            channel_id: state.channel_id.clone(),
+            description: status_label.to_string(),
+            reply_to_message_id: state.reply_target_message_id.read().await.clone(),
+        })
+        .ok();
+    state
+        .deps
+        .event_tx
+        .send(crate::ProcessEvent::BranchResult {
+            agent_id: state.deps.agent_id.clone(),
+            branch_id,
+            channel_id: state.channel_id.clone(),
+            conclusion,
+        })
+        .ok();
+
+    tracing::info!(branch_id = %branch_id, description = %status_label, "branch answered from cache");
+
+    Ok(branch_id)
+}
+
 /// Check whether the channel has capacity for another worker.
 ///
 /// Uses `worker_handles` as the source of truth for active workers, since
@@ -375,6 +508,23 @@ async fn check_worker_limit(state: &ChannelState) -> std::result::Result<(), Age
     reserve_worker_slot_local(active_worker_count, &state.channel_id, max_workers)
 }
 
+/// Refuse new workers while the agent's daily or the channel's hard spend
+/// limit is reached.
+async fn check_spend_limit(state: &ChannelState) -> std::result::Result<(), AgentError> {
+    let budget = state
+        .deps
+        .budget_guard(SpendScope::channel(state.channel_id.clone()));
+    match budget.hard_limit_reached().await {
+        Some(reached) => Err(AgentError::SpendLimitReached {
+            channel_id: state.channel_id.to_string(),
+            scope: reached.scope.as_str().to_string(),
+            spent_usd: reached.spent_usd,
+            limit_usd: reached.limit_usd,
+        }),
+        None => Ok(()),
+    }
+}
+
 /// Atomically check for duplicate tasks and reserve the task description.
 ///
 /// This prevents the TOCTOU race where two concurrent `spawn_worker` calls
@@ -429,13 +579,107 @@ pub async fn spawn_worker_from_state(
     task: impl Into<String>,
     interactive: bool,
     suggested_skills: &[&str],
+    result_schema: Option<serde_json::Value>,
+) -> std::result::Result<WorkerId, AgentError> {
+    spawn_reserved_worker(
+        state,
+        task.into(),
+        interactive,
+        suggested_skills,
+        result_schema,
+        None,
+        true,
+    )
+    .await
+}
+
+/// Spawn a fire-and-forget member of a worker batch. Its completion event
+/// carries `notify: false`, so the channel isn't retriggered per worker; the
+/// batch coordinator reports every result at once.
+pub async fn spawn_batch_worker_from_state(
+    state: &ChannelState,
+    task: &str,
+    suggested_skills: &[&str],
+    result_schema: Option<serde_json::Value>,
+) -> std::result::Result<WorkerId, AgentError> {
+    spawn_reserved_worker(
+        state,
+        task.to_string(),
+        false,
+        suggested_skills,
+        result_schema,
+        None,
+        false,
+    )
+    .await
+}
+
+/// Spawn a builtin worker whose shell and file tools are confined to a
+/// per-worker container with `directory` bound into it.
+pub async fn spawn_container_worker_from_state(
+    state: &ChannelState,
+    task: impl Into<String>,
+    directory: &str,
+    interactive: bool,
+    suggested_skills: &[&str],
+    result_schema: Option<serde_json::Value>,
+) -> std::result::Result<WorkerId, AgentError> {
+    if !state.deps.runtime_config.container.load().enabled {
+        return Err(AgentError::Other(anyhow::anyhow!(
+            "container workers are not enabled in config"
+        )));
+    }
+
+    let directory = expand_tilde(directory);
+    let directory = directory.canonicalize().map_err(|error| {
+        AgentError::Other(anyhow::anyhow!(
+            "container directory {} is not accessible: {error}",
+            directory.display()
+        ))
+    })?;
+    if !directory.is_dir() {
+        return Err(AgentError::Other(anyhow::anyhow!(
+            "container directory {} is not a directory",
+            directory.display()
+        )));
+    }
+
+    spawn_reserved_worker(
+        state,
+        task.into(),
+        interactive,
+        suggested_skills,
+        result_schema,
+        Some(directory),
+        true,
+    )
+    .await
+}
+
+async fn spawn_reserved_worker(
+    state: &ChannelState,
+    task: String,
+    interactive: bool,
+    suggested_skills: &[&str],
+    result_schema: Option<serde_json::Value>,
+    container_directory: Option<PathBuf>,
+    notify: bool,
 ) -> std::result::Result<WorkerId, AgentError> {
     check_worker_limit(state).await?;
-    let task = task.into();
+    check_spend_limit(state).await?;
     reserve_task_if_unique(state, &task).await?;
     ensure_dispatch_readiness(state, "worker");
 
-    let result = spawn_worker_inner(state, &task, interactive, suggested_skills).await;
+    let result = spawn_worker_inner(
+        state,
+        &task,
+        interactive,
+        suggested_skills,
+        result_schema,
+        container_directory.as_deref(),
+        notify,
+    )
+    .await;
 
     // Release the reservation regardless of success or failure.
     // On success the task is now in the status block; on failure it needs cleanup.
@@ -451,16 +695,27 @@ async fn spawn_worker_inner(
     task: &str,
     interactive: bool,
     suggested_skills: &[&str],
+    result_schema: Option<serde_json::Value>,
+    container_directory: Option<&Path>,
+    notify: bool,
 ) -> std::result::Result<WorkerId, AgentError> {
     let rc = &state.deps.runtime_config;
     let prompt_engine = rc.prompts.load();
+    let container_config = rc.container.load_full();
+    let worker_type = if container_directory.is_some() {
+        "container"
+    } else {
+        "builtin"
+    };
 
     let worker_status_text = build_worker_status_text(rc.as_ref(), &state.deps.sandbox);
 
-    let sandbox_enabled = state.deps.sandbox.mode_enabled();
-    let sandbox_containment_active = state.deps.sandbox.containment_active();
-    let sandbox_read_allowlist = state.deps.sandbox.prompt_read_allowlist();
-    let sandbox_write_allowlist = state.deps.sandbox.prompt_write_allowlist();
+    let (
+        sandbox_enabled,
+        sandbox_containment_active,
+        sandbox_read_allowlist,
+        sandbox_write_allowlist,
+    ) = worker_sandbox_summary(&state.deps.sandbox, container_directory);
     // Collect tool secret names so the worker template can list available credentials.
     let secrets_guard = rc.secrets.load();
     let tool_secret_names = match (*secrets_guard).as_ref() {
@@ -498,6 +753,13 @@ async fn spawn_worker_inner(
             worker_system_prompt
         }
     };
+    let system_prompt = append_container_prompt(
+        system_prompt,
+        &prompt_engine,
+        container_directory,
+        &container_config,
+    )
+    .map_err(|e| AgentError::Other(anyhow::anyhow!("{e}")))?;
 
     let worker = if interactive {
         let (worker, input_tx, inject_tx) = Worker::new_interactive(
@@ -541,6 +803,25 @@ async fn spawn_worker_inner(
         worker
     };
 
+    let worker = match container_directory {
+        Some(directory) => {
+            match ContainerSession::start(&container_config, worker.id, directory).await {
+                Ok(session) => worker.with_container(Arc::new(session)),
+                Err(error) => {
+                    state.worker_inputs.write().await.remove(&worker.id);
+                    state.worker_injections.write().await.remove(&worker.id);
+                    return Err(AgentError::Other(
+                        error.context("failed to start worker container"),
+                    ));
+                }
+            }
+        }
+        None => worker,
+    };
+
+    let worker = worker
+        .with_result_schema(result_schema)
+        .with_mailbox(WorkerMailbox::from_state(state));
     let worker_id = worker.id;
 
     let worker_span = tracing::info_span!(
@@ -555,7 +836,8 @@ async fn spawn_worker_inner(
         state.deps.agent_id.clone(),
         Some(state.channel_id.clone()),
         secrets_store,
-        "builtin",
+        worker_type,
+        notify,
         worker.run().instrument(worker_span),
     );
 
@@ -574,13 +856,13 @@ async fn spawn_worker_inner(
             worker_id,
             channel_id: Some(state.channel_id.clone()),
             task: task.to_string(),
-            worker_type: "builtin".into(),
+            worker_type: worker_type.into(),
             interactive,
-            directory: None,
+            directory: container_directory.map(|directory| directory.display().to_string()),
         })
         .ok();
 
-    tracing::info!(worker_id = %worker_id, task = %task, interactive, "worker spawned");
+    tracing::info!(worker_id = %worker_id, task = %task, interactive, worker_type, "worker spawned");
 
     Ok(worker_id)
 }
@@ -603,6 +885,7 @@ pub async fn spawn_opencode_worker_from_state(
     }
 
     check_worker_limit(state).await?;
+    check_spend_limit(state).await?;
     let task = task.into();
     reserve_task_if_unique(state, &task).await?;
     ensure_dispatch_readiness(state, "opencode_worker");
@@ -713,6 +996,7 @@ async fn spawn_opencode_worker_inner(
         Some(state.channel_id.clone()),
         oc_secrets_store,
         "opencode",
+        true,
         async move {
             let result = worker.run().await.map_err(SpacebotError::from);
 
@@ -786,6 +1070,7 @@ async fn spawn_opencode_worker_inner(
 /// The result text is scrubbed through the secret store's tool secret values
 /// before being sent via the event — tool secret values are replaced with
 /// `[REDACTED:<name>]` so they never propagate to channel context.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn spawn_worker_task<F>(
     worker_id: WorkerId,
     event_tx: broadcast::Sender<ProcessEvent>,
@@ -793,6 +1078,7 @@ pub(crate) fn spawn_worker_task<F>(
     channel_id: Option<ChannelId>,
     secrets_store: Option<Arc<crate::secrets::store::SecretsStore>>,
     #[cfg_attr(not(feature = "metrics"), allow(unused_variables))] worker_type: &'static str,
+    notify: bool,
     future: F,
 ) -> tokio::task::JoinHandle<()>
 where
@@ -860,7 +1146,7 @@ where
                 tracing::error!(worker_id = %worker_id, result = %result_text, "worker failed");
             }
         };
-        let (notify, success) = completion_flags(kind);
+        let (notify, success) = completion_flags(kind, notify);
         #[cfg(feature = "metrics")]
         {
             let metrics = crate::telemetry::Metrics::global();
@@ -900,6 +1186,13 @@ pub async fn resume_idle_worker_into_state(
         .parse::<uuid::Uuid>()
         .map_err(|error| format!("invalid worker ID '{}': {error}", idle_worker.id))?;
 
+    // Only idle rows are loaded, so completed workers are never replayed. A
+    // worker that's already live in this channel is left as it is rather
+    // than started a second time.
+    if state.worker_handles.read().await.contains_key(&worker_id) {
+        return Ok(worker_id);
+    }
+
     match idle_worker.worker_type.as_str() {
         "opencode" => {
             let session_id = idle_worker
@@ -966,6 +1259,7 @@ pub async fn resume_idle_worker_into_state(
                 Some(state.channel_id.clone()),
                 oc_secrets_store,
                 "opencode",
+                true,
                 async move {
                     let result = worker.run().await.map_err(SpacebotError::from)?;
                     // Persist final transcript.
@@ -1020,114 +1314,208 @@ pub async fn resume_idle_worker_into_state(
             tracing::info!(worker_id = %worker_id, task = %idle_worker.task, "OpenCode worker resumed");
             Ok(worker_id)
         }
-        _ => {
-            // Builtin worker resume: deserialize transcript blob back into
-            // Rig message history so the LLM can continue the conversation.
-            let prior_history = if let Some(blob) = &idle_worker.transcript {
-                let steps = crate::conversation::worker_transcript::deserialize_transcript(blob)
-                    .map_err(|error| format!("failed to deserialize transcript: {error}"))?;
-                crate::conversation::worker_transcript::transcript_to_history(&steps)
-            } else {
-                return Err("no transcript blob to restore history from".into());
-            };
+        "container" => {
+            if !state.deps.runtime_config.container.load().enabled {
+                return Err("container workers are not enabled".into());
+            }
+            let directory = idle_worker
+                .directory
+                .as_deref()
+                .map(PathBuf::from)
+                .ok_or("idle container worker has no directory persisted, cannot resume")?;
+            if !directory.is_dir() {
+                return Err(format!(
+                    "container directory {} no longer exists",
+                    directory.display()
+                ));
+            }
+            resume_builtin_worker(state, idle_worker, worker_id, Some(&directory)).await
+        }
+        _ => resume_builtin_worker(state, idle_worker, worker_id, None).await,
+    }
+}
 
-            let rc = &state.deps.runtime_config;
-            let prompt_engine = rc.prompts.load();
-
-            let worker_status_text = build_worker_status_text(rc.as_ref(), &state.deps.sandbox);
-
-            let sandbox_enabled = state.deps.sandbox.mode_enabled();
-            let sandbox_containment_active = state.deps.sandbox.containment_active();
-            let sandbox_read_allowlist = state.deps.sandbox.prompt_read_allowlist();
-            let sandbox_write_allowlist = state.deps.sandbox.prompt_write_allowlist();
-            let secrets_guard = rc.secrets.load();
-            let tool_secret_names = match (*secrets_guard).as_ref() {
-                Some(store) => store.tool_secret_names(),
-                None => Vec::new(),
-            };
-            let browser_config = (**rc.browser_config.load()).clone();
-            let system_prompt = prompt_engine
-                .render_worker_prompt(
-                    &rc.instance_dir.display().to_string(),
-                    &rc.workspace_dir.display().to_string(),
-                    sandbox_enabled,
-                    sandbox_containment_active,
-                    sandbox_read_allowlist,
-                    sandbox_write_allowlist,
-                    &tool_secret_names,
-                    browser_config.persist_session,
-                    worker_status_text,
-                )
-                .map_err(|error| format!("failed to render worker prompt: {error}"))?;
-            let brave_search_key = (**rc.brave_search_key.load()).clone();
+/// Resume an idle builtin worker from its persisted transcript. Container
+/// workers get a new container bound to `container_directory`.
+async fn resume_builtin_worker(
+    state: &ChannelState,
+    idle_worker: &crate::conversation::history::IdleWorkerRow,
+    worker_id: WorkerId,
+    container_directory: Option<&Path>,
+) -> std::result::Result<WorkerId, String> {
+    // Deserialize the transcript blob back into Rig message history so the
+    // LLM can continue the conversation.
+    let prior_history = if let Some(blob) = &idle_worker.transcript {
+        let steps = crate::conversation::worker_transcript::deserialize_transcript(blob)
+            .map_err(|error| format!("failed to deserialize transcript: {error}"))?;
+        crate::conversation::worker_transcript::transcript_to_history(&steps)
+    } else {
+        return Err("no transcript blob to restore history from".into());
+    };
 
-            let (worker, input_tx, inject_tx) = Worker::resume_interactive(
-                worker_id,
-                Some(state.channel_id.clone()),
-                &idle_worker.task,
-                &system_prompt,
-                state.deps.clone(),
-                browser_config,
-                state.screenshot_dir.clone(),
-                brave_search_key,
-                state.logs_dir.clone(),
-                prior_history,
-            );
+    let rc = &state.deps.runtime_config;
+    let prompt_engine = rc.prompts.load();
+    let container_config = rc.container.load_full();
+    let worker_type = if container_directory.is_some() {
+        "container"
+    } else {
+        "builtin"
+    };
 
-            state
-                .worker_inputs
-                .write()
-                .await
-                .insert(worker_id, input_tx);
-            state
-                .worker_injections
-                .write()
+    let worker_status_text = build_worker_status_text(rc.as_ref(), &state.deps.sandbox);
+
+    let (
+        sandbox_enabled,
+        sandbox_containment_active,
+        sandbox_read_allowlist,
+        sandbox_write_allowlist,
+    ) = worker_sandbox_summary(&state.deps.sandbox, container_directory);
+    let secrets_guard = rc.secrets.load();
+    let tool_secret_names = match (*secrets_guard).as_ref() {
+        Some(store) => store.tool_secret_names(),
+        None => Vec::new(),
+    };
+    let browser_config = (**rc.browser_config.load()).clone();
+    let system_prompt = prompt_engine
+        .render_worker_prompt(
+            &rc.instance_dir.display().to_string(),
+            &rc.workspace_dir.display().to_string(),
+            sandbox_enabled,
+            sandbox_containment_active,
+            sandbox_read_allowlist,
+            sandbox_write_allowlist,
+            &tool_secret_names,
+            browser_config.persist_session,
+            worker_status_text,
+        )
+        .map_err(|error| format!("failed to render worker prompt: {error}"))?;
+    let system_prompt = append_container_prompt(
+        system_prompt,
+        &prompt_engine,
+        container_directory,
+        &container_config,
+    )
+    .map_err(|error| format!("failed to render container prompt: {error}"))?;
+    let brave_search_key = (**rc.brave_search_key.load()).clone();
+
+    let (worker, input_tx, inject_tx) = Worker::resume_interactive(
+        worker_id,
+        Some(state.channel_id.clone()),
+        &idle_worker.task,
+        &system_prompt,
+        state.deps.clone(),
+        browser_config,
+        state.screenshot_dir.clone(),
+        brave_search_key,
+        state.logs_dir.clone(),
+        prior_history,
+    );
+    let worker = worker.with_mailbox(WorkerMailbox::from_state(state));
+    // The previous container went away with the previous process. Start a
+    // fresh one on the same directory rather than resuming on the host,
+    // which would drop the isolation the worker was spawned with.
+    let worker = match container_directory {
+        Some(directory) => {
+            let session = ContainerSession::start(&container_config, worker_id, directory)
                 .await
-                .insert(worker_id, inject_tx);
+                .map_err(|error| format!("failed to start worker container: {error:#}"))?;
+            worker.with_container(Arc::new(session))
+        }
+        None => worker,
+    };
 
-            let worker_span = tracing::info_span!(
-                "worker.resume",
-                worker_id = %worker_id,
-                channel_id = %state.channel_id,
-            );
-            let secrets_store = state.deps.runtime_config.secrets.load().as_ref().clone();
-            let handle = spawn_worker_task(
-                worker_id,
-                state.deps.event_tx.clone(),
-                state.deps.agent_id.clone(),
-                Some(state.channel_id.clone()),
-                secrets_store,
-                "builtin",
-                worker.run().instrument(worker_span),
-            );
+    state
+        .worker_inputs
+        .write()
+        .await
+        .insert(worker_id, input_tx);
+    state
+        .worker_injections
+        .write()
+        .await
+        .insert(worker_id, inject_tx);
 
-            state.worker_handles.write().await.insert(worker_id, handle);
+    let worker_span = tracing::info_span!(
+        "worker.resume",
+        worker_id = %worker_id,
+        channel_id = %state.channel_id,
+    );
+    let secrets_store = state.deps.runtime_config.secrets.load().as_ref().clone();
+    let handle = spawn_worker_task(
+        worker_id,
+        state.deps.event_tx.clone(),
+        state.deps.agent_id.clone(),
+        Some(state.channel_id.clone()),
+        secrets_store,
+        worker_type,
+        true,
+        worker.run().instrument(worker_span),
+    );
 
-            {
-                let mut status = state.status_block.write().await;
-                status.add_worker(worker_id, &idle_worker.task, false, true);
-            }
+    state.worker_handles.write().await.insert(worker_id, handle);
 
-            state
-                .deps
-                .event_tx
-                .send(ProcessEvent::WorkerStarted {
-                    agent_id: state.deps.agent_id.clone(),
-                    worker_id,
-                    channel_id: Some(state.channel_id.clone()),
-                    task: idle_worker.task.clone(),
-                    worker_type: "builtin".into(),
-                    interactive: true,
-                    directory: None,
-                })
-                .ok();
+    {
+        let mut status = state.status_block.write().await;
+        status.add_worker(worker_id, &idle_worker.task, false, true);
+    }
 
-            tracing::info!(worker_id = %worker_id, task = %idle_worker.task, "builtin worker resumed");
-            Ok(worker_id)
+    state
+        .deps
+        .event_tx
+        .send(ProcessEvent::WorkerStarted {
+            agent_id: state.deps.agent_id.clone(),
+            worker_id,
+            channel_id: Some(state.channel_id.clone()),
+            task: idle_worker.task.clone(),
+            worker_type: worker_type.into(),
+            interactive: true,
+            directory: container_directory.map(|directory| directory.display().to_string()),
+        })
+        .ok();
+
+    tracing::info!(worker_id = %worker_id, task = %idle_worker.task, worker_type, "worker resumed");
+    Ok(worker_id)
+}
+
+/// Sandbox facts for the worker prompt: enabled, containment active, read
+/// and write allowlists. A container worker's sandbox is derived once its
+/// container is up, and its only allowed path is the bound directory.
+pub(crate) fn worker_sandbox_summary(
+    sandbox: &crate::sandbox::Sandbox,
+    container_directory: Option<&Path>,
+) -> (bool, bool, Vec<String>, Vec<String>) {
+    match container_directory {
+        Some(directory) => {
+            let directory = directory.display().to_string();
+            (true, true, vec![directory.clone()], vec![directory])
         }
+        None => (
+            sandbox.mode_enabled(),
+            sandbox.containment_active(),
+            sandbox.prompt_read_allowlist(),
+            sandbox.prompt_write_allowlist(),
+        ),
     }
 }
 
+/// Append the container section to a container worker's system prompt.
+fn append_container_prompt(
+    system_prompt: String,
+    prompt_engine: &crate::prompts::PromptEngine,
+    container_directory: Option<&Path>,
+    container_config: &crate::config::ContainerConfig,
+) -> crate::error::Result<String> {
+    let Some(directory) = container_directory else {
+        return Ok(system_prompt);
+    };
+    let container_prompt = prompt_engine.render_worker_container(
+        &directory.display().to_string(),
+        &container_config.image,
+        &container_config.network,
+    )?;
+    Ok(format!("{system_prompt}\n\n{container_prompt}"))
+}
+
 /// Expand a leading `~` or `~/` in a path to the user's home directory.
 ///
 /// LLMs consistently produce tilde-prefixed paths because that's what appears
@@ -1178,6 +1566,7 @@ mod tests {
             Some(Arc::<str>::from("channel")),
             None,
             "builtin",
+            true,
             async {
                 Err::<String, crate::Error>(
                     crate::error::AgentError::Cancelled {
@@ -1224,6 +1613,7 @@ mod tests {
             Some(channel_id.clone()),
             None,
             "builtin",
+            true,
             async { Ok::<String, crate::Error>("result".to_string()) },
         );
 
@@ -413,6 +413,14 @@ pub(crate) fn event_is_for_channel(event: &ProcessEvent, channel_id: &ChannelId)
         | ProcessEvent::AgentMessageReceived {
             channel_id: event_channel,
             ..
+        }
+        | ProcessEvent::WorkerBatchComplete {
+            channel_id: event_channel,
+            ..
+        }
+        | ProcessEvent::TurnInterrupted {
+            channel_id: event_channel,
+            ..
         } => event_channel == channel_id,
         ProcessEvent::TextDelta {
             channel_id: event_channel,
@@ -434,7 +442,8 @@ pub(crate) fn event_is_for_channel(event: &ProcessEvent, channel_id: &ChannelId)
         | ProcessEvent::StatusUpdate { .. }
         | ProcessEvent::TaskUpdated { .. }
         | ProcessEvent::WorkerText { .. }
-        | ProcessEvent::CortexChatUpdate { .. } => false,
+        | ProcessEvent::CortexChatUpdate { .. }
+        | ProcessEvent::BudgetWarning { .. } => false,
     }
 }
 
@@ -1041,6 +1050,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn temporal_context_prefers_valid_channel_timezone() {
+        let context = crate::agent::channel_prompt::TemporalContext {
+            now_utc: chrono::Utc::now(),
+            timezone: crate::agent::channel_prompt::TemporalContext::resolve_timezone_from_names(
+                Some("America/Los_Angeles".to_string()),
+                None,
+            ),
+        };
+
+        let context = context.with_channel_timezone(Some("Not/A-Real-Tz"));
+        assert!(context.current_time_line().contains("America/Los_Angeles"));
+
+        let context = context.with_channel_timezone(Some("Asia/Tokyo"));
+        assert!(
+            context
+                .current_time_line()
+                .contains("Asia/Tokyo, UTC+09:00")
+        );
+    }
+
     #[test]
     fn format_batched_message_includes_absolute_and_relative_time() {
         let formatted = super::format_batched_user_message(
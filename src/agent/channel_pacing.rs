@@ -0,0 +1,160 @@
+//! Human-like reply pacing for channels.
+//!
+//! With a pace other than `instant`, a reply is cut into a few messages at
+//! paragraph boundaries and each one is preceded by a typing indicator held
+//! for roughly as long as a person would take to type it. Fenced code blocks
+//! are never cut.
+
+use crate::config::ResponsePace;
+
+use std::time::Duration;
+
+/// One message of a paced reply, with how long to show typing before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PacedChunk {
+    pub text: String,
+    pub typing: Duration,
+}
+
+struct PaceProfile {
+    chars_per_second: u64,
+    min_typing: Duration,
+    max_typing: Duration,
+    max_chunk_chars: usize,
+    max_chunks: usize,
+}
+
+fn profile(pace: ResponsePace) -> Option<PaceProfile> {
+    match pace {
+        ResponsePace::Instant => None,
+        ResponsePace::Casual => Some(PaceProfile {
+            chars_per_second: 60,
+            min_typing: Duration::from_millis(800),
+            max_typing: Duration::from_secs(4),
+            max_chunk_chars: 600,
+            max_chunks: 5,
+        }),
+        ResponsePace::Relaxed => Some(PaceProfile {
+            chars_per_second: 30,
+            min_typing: Duration::from_millis(1500),
+            max_typing: Duration::from_secs(8),
+            max_chunk_chars: 400,
+            max_chunks: 6,
+        }),
+    }
+}
+
+/// Split a reply into paced messages.
+///
+/// `instant` (and empty text) yields the text unchanged as a single chunk
+/// with no typing delay.
+pub fn plan(text: &str, pace: ResponsePace) -> Vec<PacedChunk> {
+    let Some(profile) = profile(pace).filter(|_| !text.trim().is_empty()) else {
+        return vec![PacedChunk {
+            text: text.to_string(),
+            typing: Duration::ZERO,
+        }];
+    };
+
+    let mut chunks: Vec<String> = Vec::new();
+    for block in split_blocks(text) {
+        match chunks.last_mut() {
+            Some(current) if current.len() + block.len() + 2 <= profile.max_chunk_chars => {
+                current.push_str("\n\n");
+                current.push_str(&block);
+            }
+            _ => chunks.push(block),
+        }
+    }
+
+    if chunks.len() > profile.max_chunks {
+        let tail = chunks.split_off(profile.max_chunks - 1).join("\n\n");
+        chunks.push(tail);
+    }
+
+    chunks
+        .into_iter()
+        .map(|text| {
+            let typing = typing_duration(&text, &profile);
+            PacedChunk { text, typing }
+        })
+        .collect()
+}
+
+fn typing_duration(text: &str, profile: &PaceProfile) -> Duration {
+    let chars = text.chars().count() as u64;
+    Duration::from_millis(chars * 1000 / profile.chars_per_second)
+        .clamp(profile.min_typing, profile.max_typing)
+}
+
+/// Split text into paragraphs at blank lines, keeping fenced code blocks
+/// whole.
+fn split_blocks(text: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut in_fence = false;
+
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        if !in_fence && line.trim().is_empty() {
+            if !current.is_empty() {
+                blocks.push(current.join("\n"));
+                current.clear();
+            }
+            continue;
+        }
+        current.push(line);
+    }
+    if !current.is_empty() {
+        blocks.push(current.join("\n"));
+    }
+
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instant_sends_text_unchanged() {
+        let text = "first\n\nsecond";
+        assert_eq!(
+            plan(text, ResponsePace::Instant),
+            vec![PacedChunk {
+                text: text.to_string(),
+                typing: Duration::ZERO,
+            }]
+        );
+    }
+
+    #[test]
+    fn casual_splits_long_replies_at_paragraphs_and_keeps_code_whole() {
+        let paragraph = "word ".repeat(100);
+        let code = "```\nfn main() {\n\n    println!(\"hi\");\n}\n```";
+        let text = format!("{paragraph}\n\n{code}\n\n{paragraph}");
+
+        let chunks = plan(&text, ResponsePace::Casual);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].text.ends_with(code));
+        assert_eq!(chunks[1].text, paragraph);
+        assert!(
+            chunks
+                .iter()
+                .all(|chunk| chunk.typing >= Duration::from_millis(800)
+                    && chunk.typing <= Duration::from_secs(4))
+        );
+    }
+
+    #[test]
+    fn chunk_count_is_capped() {
+        let text = std::iter::repeat_n("x".repeat(500), 10)
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let chunks = plan(&text, ResponsePace::Casual);
+        assert_eq!(chunks.len(), 5);
+        assert_eq!(chunks[4].text.matches('x').count(), 3000);
+    }
+}
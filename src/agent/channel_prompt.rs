@@ -44,6 +44,28 @@ impl TemporalContext {
         }
     }
 
+    /// Use a channel's own timezone over the agent's when it names a valid
+    /// IANA timezone.
+    pub(crate) fn with_channel_timezone(mut self, timezone: Option<&str>) -> Self {
+        if let Some(timezone_name) = timezone {
+            match timezone_name.parse::<Tz>() {
+                Ok(timezone) => {
+                    self.timezone = TemporalTimezone::Named {
+                        timezone_name: timezone_name.to_string(),
+                        timezone,
+                    };
+                }
+                Err(_) => {
+                    tracing::warn!(
+                        timezone = %timezone_name,
+                        "invalid channel timezone for temporal context, using the agent timezone"
+                    );
+                }
+            }
+        }
+        self
+    }
+
     pub(crate) fn resolve_timezone_from_names(
         user_timezone: Option<String>,
         cron_timezone: Option<String>,
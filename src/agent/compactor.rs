@@ -1,12 +1,18 @@
 //! Compactor: Programmatic context monitor that triggers background compaction.
 //!
 //! The compactor is NOT an LLM process. It watches a channel's context size and
-//! spawns compaction workers when thresholds are crossed. The LLM work (summarization
-//! + memory extraction) happens in the spawned worker, not here.
-
+//! spawns compaction workers when thresholds are crossed. The LLM work
+//! (summarization and memory extraction) happens in the spawned worker, not here,
+//! except when a turn's prompt is already past the aggressive threshold: then the
+//! summarization runs inline before the completion call.
+//! Usage is measured against the context window of the model the channel is
+//! routed to.
+
+use crate::config::CompactionConfig;
 use crate::error::Result;
 use crate::hooks::SpacebotHook;
 use crate::llm::SpacebotModel;
+use crate::llm::budget::SpendScope;
 use crate::{AgentDeps, ChannelId, ProcessId, ProcessType};
 use rig::agent::AgentBuilder;
 use rig::completion::CompletionModel;
@@ -38,70 +44,159 @@ impl Compactor {
 
     /// Check context size and trigger compaction if needed.
     ///
-    /// Called by the channel after each turn. Returns the action taken, if any.
-    pub async fn check_and_compact(&self) -> Result<Option<CompactionAction>> {
+    /// Called by the channel after each turn with the model the turn ran on.
+    /// Returns the action taken, if any.
+    pub async fn check_and_compact(&self, model_name: &str) -> Result<Option<CompactionAction>> {
         let is_compacting = *self.is_compacting.read().await;
         if is_compacting {
             return Ok(None);
         }
 
-        let rc = &self.deps.runtime_config;
-        let context_window = **rc.context_window.load();
-        let compaction_config = **rc.compaction.load();
+        let usage = {
+            let history = self.history.read().await;
+            estimate_history_tokens(&history) as f32 / self.context_window(model_name) as f32
+        };
+
+        let compaction_config = **self.deps.runtime_config.compaction.load();
+        let Some(action) = select_action(usage, &compaction_config) else {
+            return Ok(None);
+        };
+
+        tracing::info!(
+            channel_id = %self.channel_id,
+            usage = %format!("{:.1}%", usage * 100.0),
+            ?action,
+            "compaction triggered"
+        );
+        self.emit_triggered(usage);
+
+        match action {
+            CompactionAction::EmergencyTruncate => {
+                // Emergency is synchronous — fast, no LLM
+                self.emergency_truncate().await?;
+            }
+            CompactionAction::Background | CompactionAction::Aggressive => {
+                // Background/aggressive spawn a worker
+                self.spawn_compaction_worker(action).await;
+            }
+        }
 
+        Ok(Some(action))
+    }
+
+    /// Compact history before a turn's completion call if its prompt is
+    /// already past a threshold.
+    ///
+    /// `check_and_compact` runs after a turn, so a long incoming message or a
+    /// large system prompt can still push the request past the context window.
+    /// This estimates the whole prompt (system prompt, history, and the new
+    /// message) against `model_name`'s context window. Past the background
+    /// threshold the usual compaction worker is spawned and the turn goes
+    /// ahead. At or past the aggressive threshold the oldest history is
+    /// summarized inline and the turn waits for it; emergency truncation only
+    /// runs if the prompt still doesn't fit afterwards. Returns the action
+    /// taken, if any.
+    pub async fn compact_before_turn(
+        &self,
+        model_name: &str,
+        system_prompt: &str,
+        user_text: &str,
+    ) -> Result<Option<CompactionAction>> {
+        let context_window = self.context_window(model_name);
         let usage = {
             let history = self.history.read().await;
-            let estimated_tokens = estimate_history_tokens(&history);
-            estimated_tokens as f32 / context_window as f32
+            projected_usage(&history, system_prompt, user_text, context_window)
         };
 
-        let action = if usage >= compaction_config.emergency_threshold {
-            Some(CompactionAction::EmergencyTruncate)
-        } else if usage >= compaction_config.aggressive_threshold {
-            Some(CompactionAction::Aggressive)
-        } else if usage >= compaction_config.background_threshold {
-            Some(CompactionAction::Background)
-        } else {
-            None
+        let compaction_config = **self.deps.runtime_config.compaction.load();
+        let Some(action) = select_action(usage, &compaction_config) else {
+            return Ok(None);
         };
+        // A worker is already summarizing; it will free the room.
+        if action == CompactionAction::Background && *self.is_compacting.read().await {
+            return Ok(None);
+        }
+
+        tracing::warn!(
+            channel_id = %self.channel_id,
+            usage = %format!("{:.1}%", usage * 100.0),
+            ?action,
+            "prompt approaching context window, compacting before turn"
+        );
+        self.emit_triggered(usage);
+
+        if action == CompactionAction::Background {
+            self.spawn_compaction_worker(action).await;
+            return Ok(Some(action));
+        }
 
-        if let Some(action) = action {
-            tracing::info!(
+        self.compact_inline(action).await?;
+
+        let usage = {
+            let history = self.history.read().await;
+            projected_usage(&history, system_prompt, user_text, context_window)
+        };
+        if usage >= compaction_config.emergency_threshold {
+            tracing::warn!(
                 channel_id = %self.channel_id,
                 usage = %format!("{:.1}%", usage * 100.0),
-                ?action,
-                "compaction triggered"
+                "prompt still over the emergency threshold after compaction"
             );
-            if let Err(error) = self
-                .deps
-                .event_tx
-                .send(crate::ProcessEvent::CompactionTriggered {
-                    agent_id: self.deps.agent_id.clone(),
-                    channel_id: self.channel_id.clone(),
-                    threshold_reached: usage,
-                })
-            {
-                tracing::debug!(
-                    channel_id = %self.channel_id,
-                    %error,
-                    "failed to emit compaction-triggered event"
-                );
-            }
+            self.emergency_truncate().await?;
+        }
 
-            match action {
-                CompactionAction::EmergencyTruncate => {
-                    // Emergency is synchronous — fast, no LLM
-                    self.emergency_truncate().await?;
-                }
-                CompactionAction::Background | CompactionAction::Aggressive => {
-                    // Background/aggressive spawn a worker
-                    self.spawn_compaction_worker(action).await;
-                }
-            }
+        Ok(Some(action))
+    }
+
+    /// Summarize the oldest history with the compactor model and wait for it.
+    async fn compact_inline(&self, action: CompactionAction) -> Result<()> {
+        let prompt_engine = self.deps.runtime_config.prompts.load();
+        let compactor_prompt = prompt_engine.render_static("compactor")?;
+
+        *self.is_compacting.write().await = true;
+        let result = run_compaction(
+            &self.deps,
+            &compactor_prompt,
+            &self.history,
+            &self.channel_id,
+            compaction_fraction(action),
+        )
+        .await;
+        *self.is_compacting.write().await = false;
+
+        let turns_compacted = result?;
+        tracing::info!(
+            channel_id = %self.channel_id,
+            turns_compacted,
+            "inline compaction completed"
+        );
+        Ok(())
+    }
+
+    /// Context window of `model_name`, falling back to the agent's
+    /// `context_window` for models without a routing override.
+    fn context_window(&self, model_name: &str) -> usize {
+        let rc = &self.deps.runtime_config;
+        rc.routing
+            .load()
+            .context_window_for(model_name, **rc.context_window.load())
+    }
 
-            Ok(Some(action))
-        } else {
-            Ok(None)
+    fn emit_triggered(&self, usage: f32) {
+        if let Err(error) = self
+            .deps
+            .event_tx
+            .send(crate::ProcessEvent::CompactionTriggered {
+                agent_id: self.deps.agent_id.clone(),
+                channel_id: self.channel_id.clone(),
+                threshold_reached: usage,
+            })
+        {
+            tracing::debug!(
+                channel_id = %self.channel_id,
+                %error,
+                "failed to emit compaction-triggered event"
+            );
         }
     }
 
@@ -114,11 +209,7 @@ impl Compactor {
         *is_compacting = true;
         drop(is_compacting);
 
-        let fraction = match action {
-            CompactionAction::Background => 0.3,
-            CompactionAction::Aggressive => 0.5,
-            CompactionAction::EmergencyTruncate => unreachable!(),
-        };
+        let fraction = compaction_fraction(action);
 
         let history = self.history.clone();
         let is_compacting = self.is_compacting.clone();
@@ -193,6 +284,39 @@ impl Compactor {
     }
 }
 
+/// Pick the compaction action for a context usage fraction.
+fn select_action(usage: f32, config: &CompactionConfig) -> Option<CompactionAction> {
+    if usage >= config.emergency_threshold {
+        Some(CompactionAction::EmergencyTruncate)
+    } else if usage >= config.aggressive_threshold {
+        Some(CompactionAction::Aggressive)
+    } else if usage >= config.background_threshold {
+        Some(CompactionAction::Background)
+    } else {
+        None
+    }
+}
+
+/// Fraction of the oldest history summarized for an action.
+fn compaction_fraction(action: CompactionAction) -> f32 {
+    match action {
+        CompactionAction::Background => 0.3,
+        CompactionAction::Aggressive | CompactionAction::EmergencyTruncate => 0.5,
+    }
+}
+
+/// Fraction of `context_window` the next request would use: history plus the
+/// system prompt and the incoming message.
+fn projected_usage(
+    history: &[Message],
+    system_prompt: &str,
+    user_text: &str,
+    context_window: usize,
+) -> f32 {
+    let prompt_tokens = (system_prompt.len() + user_text.len()) / 4;
+    (estimate_history_tokens(history) + prompt_tokens) as f32 / context_window as f32
+}
+
 /// Run the actual compaction: summarize via LLM, extract memories, swap summary into history.
 #[tracing::instrument(skip(deps, compactor_prompt, history), fields(agent_id = %deps.agent_id))]
 async fn run_compaction(
@@ -220,11 +344,46 @@ async fn run_compaction(
     let transcript = render_messages_as_transcript(&removed_messages);
 
     // 3. Run the compaction LLM to produce summary + extracted memories
+    let summary = match summarize_transcript(deps, compactor_prompt, channel_id, &transcript).await
+    {
+        Ok(summary) => summary,
+        Err(error) => {
+            tracing::warn!(%error, "compaction LLM failed, using fallback summary");
+            format!("[Compaction summary of {remove_count} messages — LLM summarization failed]")
+        }
+    };
+
+    // 4. Insert the summary at the beginning of the channel's history
+    {
+        let mut hist = history.write().await;
+        let summary_message = format!("[Compaction Summary]: {summary}");
+        hist.insert(0, Message::from(summary_message));
+    }
+
+    Ok(remove_count)
+}
+
+/// Summarize a transcript with the compactor model.
+///
+/// The compactor also gets `memory_save`, so memories worth keeping are
+/// extracted from the transcript as a side effect. Shared by in-context
+/// compaction and the archival job.
+pub(crate) async fn summarize_transcript(
+    deps: &AgentDeps,
+    compactor_prompt: &str,
+    channel_id: &ChannelId,
+    transcript: &str,
+) -> std::result::Result<String, rig::completion::PromptError> {
     let routing = deps.runtime_config.routing.load();
-    let model_name = routing.resolve(ProcessType::Compactor, None).to_string();
+    let model_name = routing
+        .resolve(ProcessType::Compactor, Some(&**channel_id))
+        .to_string();
     let model = SpacebotModel::make(&deps.llm_manager, &model_name)
         .with_context(&*deps.agent_id, "compactor")
-        .with_routing((**routing).clone());
+        .with_channel(&**channel_id)
+        .with_routing((**routing).clone())
+        .with_response_cache()
+        .with_budget(deps.budget_guard(SpendScope::channel(channel_id.clone())));
 
     // Give the compaction worker memory_save so it can directly persist memories
     let tool_server: ToolServerHandle = crate::tools::create_cortex_tool_server(
@@ -248,26 +407,9 @@ async fn run_compaction(
     );
 
     let mut compaction_history = Vec::new();
-    let response = hook
-        .prompt_once(&agent, &mut compaction_history, &transcript)
-        .await;
-
-    let summary = match response {
-        Ok(text) => extract_summary_section(&text),
-        Err(error) => {
-            tracing::warn!(%error, "compaction LLM failed, using fallback summary");
-            format!("[Compaction summary of {remove_count} messages — LLM summarization failed]")
-        }
-    };
-
-    // 4. Insert the summary at the beginning of the channel's history
-    {
-        let mut hist = history.write().await;
-        let summary_message = format!("[Compaction Summary]: {summary}");
-        hist.insert(0, Message::from(summary_message));
-    }
-
-    Ok(remove_count)
+    hook.prompt_once(&agent, &mut compaction_history, transcript)
+        .await
+        .map(|text| extract_summary_section(&text))
 }
 
 /// Estimate token count for a history using chars/4 heuristic.
@@ -415,3 +557,61 @@ pub enum CompactionAction {
     /// Emergency truncation (no LLM, drop oldest 50%).
     EmergencyTruncate,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::RoutingConfig;
+
+    #[test]
+    fn select_action_follows_thresholds() {
+        let config = CompactionConfig::default();
+        assert_eq!(select_action(0.5, &config), None);
+        assert_eq!(select_action(0.79, &config), None);
+        assert_eq!(
+            select_action(0.80, &config),
+            Some(CompactionAction::Background)
+        );
+        assert_eq!(
+            select_action(0.85, &config),
+            Some(CompactionAction::Aggressive)
+        );
+        assert_eq!(
+            select_action(0.94, &config),
+            Some(CompactionAction::Aggressive)
+        );
+        assert_eq!(
+            select_action(0.95, &config),
+            Some(CompactionAction::EmergencyTruncate)
+        );
+        assert_eq!(
+            select_action(1.2, &config),
+            Some(CompactionAction::EmergencyTruncate)
+        );
+    }
+
+    #[test]
+    fn projected_usage_counts_prompt_and_uses_routed_window() {
+        let history = vec![Message::from("a".repeat(4_000))];
+        let system_prompt = "s".repeat(2_000);
+        let user_text = "u".repeat(2_000);
+
+        let mut routing = RoutingConfig::default();
+        routing.context_windows.insert("small/model".into(), 2_000);
+        let small = routing.context_window_for("small/model", 128_000);
+        let default = routing.context_window_for("other/model", 128_000);
+        assert_eq!(small, 2_000);
+        assert_eq!(default, 128_000);
+
+        // 1000 history tokens + 1000 prompt tokens.
+        let usage = projected_usage(&history, &system_prompt, &user_text, small);
+        assert!((usage - 1.0).abs() < f32::EPSILON);
+        assert_eq!(
+            select_action(usage, &CompactionConfig::default()),
+            Some(CompactionAction::EmergencyTruncate)
+        );
+
+        let usage = projected_usage(&history, &system_prompt, &user_text, default);
+        assert_eq!(select_action(usage, &CompactionConfig::default()), None);
+    }
+}
@@ -17,9 +17,10 @@ use crate::agent::worker::Worker;
 use crate::error::Result;
 use crate::hooks::CortexHook;
 use crate::llm::SpacebotModel;
+use crate::llm::budget::SpendScope;
 use crate::memory::maintenance as memory_maintenance;
 use crate::memory::search::{SearchConfig, SearchMode, SearchSort};
-use crate::memory::types::{Association, MemoryType, RelationType};
+use crate::memory::types::{Association, MemoryType, MemoryVisibility, RelationType};
 use crate::tasks::{TaskStatus, UpdateTaskInput};
 use crate::{
     AgentDeps, AgentId, BranchId, ChannelId, ProcessEvent, ProcessId, ProcessType, WorkerId,
@@ -755,6 +756,14 @@ pub enum Signal {
         channel_id: Option<ChannelId>,
         text_summary: String,
     },
+    /// Spend crossed a budget limit.
+    BudgetWarning {
+        scope: String,
+        channel_id: Option<ChannelId>,
+        spent_usd: f64,
+        limit_usd: f64,
+        hard: bool,
+    },
 }
 
 /// A persisted cortex action record.
@@ -895,6 +904,9 @@ impl Cortex {
     /// Process a process event and extract signals.
     pub async fn observe(&self, event: ProcessEvent) {
         self.observe_health_event(&event).await;
+        if let ProcessEvent::BudgetWarning { .. } = &event {
+            log_budget_warning(&CortexLogger::new(self.deps.sqlite_pool.clone()), &event);
+        }
         let Some(signal) = signal_from_event(event) else {
             return;
         };
@@ -1338,15 +1350,67 @@ fn signal_from_event(event: ProcessEvent) -> Option<Signal> {
             channel_id,
             status: "idle".to_string(),
         },
+        ProcessEvent::BudgetWarning {
+            scope,
+            channel_id,
+            spent_usd,
+            limit_usd,
+            hard,
+            ..
+        } => Signal::BudgetWarning {
+            scope,
+            channel_id,
+            spent_usd,
+            limit_usd,
+            hard,
+        },
         // UI-only events — no cortex signal needed.
         ProcessEvent::OpenCodeSessionCreated { .. }
         | ProcessEvent::OpenCodePartUpdated { .. }
         | ProcessEvent::WorkerInitialResult { .. }
         | ProcessEvent::WorkerText { .. }
-        | ProcessEvent::CortexChatUpdate { .. } => return None,
+        | ProcessEvent::CortexChatUpdate { .. }
+        | ProcessEvent::TurnInterrupted { .. } => return None,
+        // Each batch member already produced its own WorkerComplete signal.
+        ProcessEvent::WorkerBatchComplete { .. } => return None,
     })
 }
 
+/// Record a budget limit crossing in the cortex event log.
+fn log_budget_warning(logger: &CortexLogger, event: &ProcessEvent) {
+    let ProcessEvent::BudgetWarning {
+        scope,
+        channel_id,
+        worker_id,
+        spent_usd,
+        limit_usd,
+        hard,
+        ..
+    } = event
+    else {
+        return;
+    };
+
+    let kind = if *hard { "hard" } else { "soft" };
+    let target = match (worker_id, channel_id) {
+        (Some(worker_id), _) if scope == "worker" => format!(" for worker {worker_id}"),
+        (_, Some(channel_id)) if scope == "channel" => format!(" for channel {channel_id}"),
+        _ => String::new(),
+    };
+    logger.log(
+        "budget_warning",
+        &format!("{scope} {kind} spend limit reached{target}: ${spent_usd:.2} of ${limit_usd:.2}"),
+        Some(serde_json::json!({
+            "scope": scope,
+            "hard": hard,
+            "channel_id": channel_id.as_deref(),
+            "worker_id": worker_id.map(|id| id.to_string()),
+            "spent_usd": spent_usd,
+            "limit_usd": limit_usd,
+        })),
+    );
+}
+
 fn push_signal_into_buffer(buffer: &mut VecDeque<Signal>, signal: Signal) {
     if let Some(previous) = buffer.back_mut()
         && coalesce_signal(previous, &signal)
@@ -1521,6 +1585,10 @@ pub fn spawn_bulletin_loop(deps: AgentDeps, logger: CortexLogger) -> tokio::task
 pub fn spawn_warmup_loop(deps: AgentDeps, logger: CortexLogger) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
         tracing::info!("warmup loop started");
+        let job = deps
+            .runtime_config
+            .jobs
+            .register("warmup", "Refresh the memory bulletin and warm caches");
         let mut completed_initial_pass =
             has_completed_initial_warmup(deps.runtime_config.warmup_status.load().as_ref());
 
@@ -1532,7 +1600,7 @@ pub fn spawn_warmup_loop(deps: AgentDeps, logger: CortexLogger) -> tokio::task::
                     status.state = crate::config::WarmupState::Cold;
                     status.bulletin_age_secs = bulletin_age_secs(status.last_refresh_unix_ms);
                 });
-                tokio::time::sleep(Duration::from_secs(10)).await;
+                job.wait(Duration::from_secs(10)).await;
                 completed_initial_pass = false;
                 continue;
             }
@@ -1547,7 +1615,7 @@ pub fn spawn_warmup_loop(deps: AgentDeps, logger: CortexLogger) -> tokio::task::
             } else {
                 warmup_config.startup_delay_secs.max(1)
             };
-            tokio::time::sleep(Duration::from_secs(sleep_secs)).await;
+            job.wait(Duration::from_secs(sleep_secs)).await;
 
             if !completed_initial_pass {
                 completed_initial_pass =
@@ -1562,7 +1630,16 @@ pub fn spawn_warmup_loop(deps: AgentDeps, logger: CortexLogger) -> tokio::task::
             } else {
                 "startup"
             };
+            job.started();
             run_warmup_once(&deps, &logger, reason, false).await;
+            let status = deps.runtime_config.warmup_status.load();
+            job.finished(
+                status.last_error.is_none(),
+                status
+                    .last_error
+                    .clone()
+                    .unwrap_or_else(|| format!("{reason} warmup complete")),
+            );
             completed_initial_pass = true;
         }
     })
@@ -2219,6 +2296,8 @@ async fn gather_bulletin_sections(deps: &AgentDeps) -> String {
             memory_type: section.memory_type,
             sort_by: section.sort_by,
             max_results: section.max_results,
+            // Every channel sees the bulletin, so channel-scoped memories stay out.
+            visibility: MemoryVisibility::Global,
             ..Default::default()
         };
 
@@ -2358,7 +2437,9 @@ pub async fn generate_bulletin(deps: &AgentDeps, logger: &CortexLogger) -> bool
     let model_name = routing.resolve(ProcessType::Cortex, None).to_string();
     let model = SpacebotModel::make(&deps.llm_manager, &model_name)
         .with_context(&*deps.agent_id, "cortex")
-        .with_routing((**routing).clone());
+        .with_routing((**routing).clone())
+        .with_response_cache()
+        .with_budget(deps.budget_guard(SpendScope::agent()));
 
     // No tools needed — the LLM just synthesizes the pre-gathered data.
     // Attach CortexHook so observation/termination semantics stay consistent
@@ -2521,11 +2602,11 @@ async fn generate_profile(deps: &AgentDeps, logger: &CortexLogger) {
         }
     };
     let memory_bulletin = {
-        let bulletin = deps.runtime_config.memory_bulletin.load();
+        let bulletin = deps.runtime_config.rendered_bulletin();
         if bulletin.is_empty() {
             None
         } else {
-            Some(bulletin.as_ref().clone())
+            Some(bulletin)
         }
     };
 
@@ -2543,7 +2624,9 @@ async fn generate_profile(deps: &AgentDeps, logger: &CortexLogger) {
     let model_name = routing.resolve(ProcessType::Cortex, None).to_string();
     let model = SpacebotModel::make(&deps.llm_manager, &model_name)
         .with_context(&*deps.agent_id, "cortex")
-        .with_routing((**routing).clone());
+        .with_routing((**routing).clone())
+        .with_response_cache()
+        .with_budget(deps.budget_guard(SpendScope::agent()));
 
     let agent = AgentBuilder::new(model)
         .preamble(&profile_prompt)
@@ -2649,16 +2732,47 @@ pub fn spawn_ready_task_loop(deps: AgentDeps, logger: CortexLogger) -> tokio::ta
 
 async fn run_ready_task_loop(deps: &AgentDeps, logger: &CortexLogger) -> anyhow::Result<()> {
     tracing::info!("cortex ready-task loop started");
+    let job = deps.runtime_config.jobs.register(
+        "ready_task_pickup",
+        "Start a worker for the next ready task on the board",
+    );
 
     // Let startup settle before first pickup attempt.
     tokio::time::sleep(Duration::from_secs(10)).await;
 
     loop {
         let interval = deps.runtime_config.cortex.load().tick_interval_secs;
-        tokio::time::sleep(Duration::from_secs(interval.max(5))).await;
+        job.wait(Duration::from_secs(interval.max(5))).await;
 
-        if let Err(error) = pickup_one_ready_task(deps, logger).await {
-            tracing::warn!(%error, "ready-task pickup pass failed");
+        job.started();
+        let cluster = crate::cluster::global();
+        if let Some(cluster) = cluster
+            && let Err(error) = apply_finished_cluster_jobs(cluster, deps, logger).await
+        {
+            tracing::warn!(%error, "failed to apply finished cluster jobs");
+        }
+        if let Some(reached) = deps
+            .budget_guard(crate::llm::budget::SpendScope::agent())
+            .hard_limit_reached()
+            .await
+        {
+            job.finished(
+                true,
+                format!("paused: {} spend limit reached", reached.scope.as_str()),
+            );
+            continue;
+        }
+        if let Some(cluster) = cluster
+            && let Err(error) = run_one_cluster_job(cluster, deps, logger).await
+        {
+            tracing::warn!(%error, "failed to run cluster job");
+        }
+        match pickup_one_ready_task(deps, logger).await {
+            Ok(()) => job.finished(true, "pickup pass complete"),
+            Err(error) => {
+                tracing::warn!(%error, "ready-task pickup pass failed");
+                job.finished(false, error.to_string());
+            }
         }
     }
 }
@@ -2677,89 +2791,20 @@ async fn pickup_one_ready_task(deps: &AgentDeps, logger: &CortexLogger) -> anyho
         })),
     );
 
-    let prompt_engine = deps.runtime_config.prompts.load();
-    let sandbox_enabled = deps.sandbox.mode_enabled();
-    let sandbox_containment_active = deps.sandbox.containment_active();
-    let sandbox_read_allowlist = deps.sandbox.prompt_read_allowlist();
-    let sandbox_write_allowlist = deps.sandbox.prompt_write_allowlist();
-
-    // Collect tool secret names so the worker template can list available credentials.
-    let secrets_guard = deps.runtime_config.secrets.load();
-    let tool_secret_names = match (*secrets_guard).as_ref() {
-        Some(store) => store.tool_secret_names(),
-        None => Vec::new(),
-    };
-
-    let browser_config = (**deps.runtime_config.browser_config.load()).clone();
-
-    // Build worker status text (time + model) for the system prompt.
-    let system_info =
-        crate::agent::status::SystemInfo::from_runtime_config(&deps.runtime_config, &deps.sandbox);
-    let temporal_context =
-        crate::agent::channel_prompt::TemporalContext::from_runtime(&deps.runtime_config);
-    let current_time_line = temporal_context.current_time_line();
-    let worker_status_text = Some(system_info.render_for_worker(&current_time_line));
-
-    let worker_system_prompt = prompt_engine
-        .render_worker_prompt(
-            &deps.runtime_config.instance_dir.display().to_string(),
-            &deps.runtime_config.workspace_dir.display().to_string(),
-            sandbox_enabled,
-            sandbox_containment_active,
-            sandbox_read_allowlist,
-            sandbox_write_allowlist,
-            &tool_secret_names,
-            browser_config.persist_session,
-            worker_status_text,
-        )
-        .map_err(|error| anyhow::anyhow!("failed to render worker prompt: {error}"))?;
-
-    let mut task_prompt = format!("Execute task #{}: {}", task.task_number, task.title);
-    if let Some(description) = &task.description {
-        task_prompt.push_str("\n\nDescription:\n");
-        task_prompt.push_str(description);
-    }
-    if !task.subtasks.is_empty() {
-        task_prompt.push_str("\n\nSubtasks:\n");
-        for (index, subtask) in task.subtasks.iter().enumerate() {
-            let marker = if subtask.completed { "[x]" } else { "[ ]" };
-            task_prompt.push_str(&format!("{}. {} {}\n", index + 1, marker, subtask.title));
+    let task_prompt = task_worker_prompt(&task);
+    if let Some(cluster) = crate::cluster::global() {
+        match offload_ready_task(cluster, deps, logger, &task, &task_prompt).await {
+            Ok(true) => return Ok(()),
+            Ok(false) => {}
+            Err(error) => tracing::warn!(
+                %error,
+                task_number = task.task_number,
+                "failed to hand task to another node, running it here"
+            ),
         }
     }
 
-    let screenshot_dir = deps
-        .runtime_config
-        .workspace_dir
-        .join(".spacebot")
-        .join("screenshots");
-    let logs_dir = deps
-        .runtime_config
-        .workspace_dir
-        .join(".spacebot")
-        .join("logs");
-    if let Err(error) = std::fs::create_dir_all(&screenshot_dir) {
-        tracing::warn!(%error, path = %screenshot_dir.display(), "failed to create screenshot directory");
-    }
-    if let Err(error) = std::fs::create_dir_all(&logs_dir) {
-        tracing::warn!(%error, path = %logs_dir.display(), "failed to create logs directory");
-    }
-
-    let brave_search_key = (**deps.runtime_config.brave_search_key.load()).clone();
-    let (worker, inject_tx) = Worker::new(
-        None,
-        task_prompt,
-        worker_system_prompt,
-        deps.clone(),
-        browser_config,
-        screenshot_dir,
-        brave_search_key,
-        logs_dir,
-    );
-
-    // Detached workers are not channel-owned, so injection senders are not
-    // stored in ChannelState. The inject_tx is dropped here — detached task
-    // workers don't support mid-flight context injection.
-    drop(inject_tx);
+    let worker = new_task_worker(deps, task_prompt)?;
 
     let worker_id = worker.id;
     let (detached_worker_lifecycle, mut detached_cancel_rx) = register_detached_worker_for_pickup(
@@ -2815,6 +2860,8 @@ async fn pickup_one_ready_task(deps: &AgentDeps, logger: &CortexLogger) -> anyho
     let process_control_registry = deps.process_control_registry.clone();
     let runtime_config = deps.runtime_config.clone();
     tokio::spawn(async move {
+        let _worker_slot = crate::cluster::global().map(|cluster| cluster.worker_slot());
+
         // Scrub known secrets and unknown leak patterns from all worker output
         // before persisting, logging, or emitting events.
         let scrub = |text: String| -> String {
@@ -2904,6 +2951,7 @@ async fn pickup_one_ready_task(deps: &AgentDeps, logger: &CortexLogger) -> anyho
                                     &result_text,
                                     true,
                                     &agent_id,
+                                    &task_store,
                                     &links,
                                     &agent_names,
                                     &sqlite_pool,
@@ -2984,6 +3032,7 @@ async fn pickup_one_ready_task(deps: &AgentDeps, logger: &CortexLogger) -> anyho
                                     &error_message,
                                     false,
                                     &agent_id,
+                                    &task_store,
                                     &links,
                                     &agent_names,
                                     &sqlite_pool,
@@ -3051,6 +3100,7 @@ async fn pickup_one_ready_task(deps: &AgentDeps, logger: &CortexLogger) -> anyho
                                     &error_message,
                                     false,
                                     &agent_id,
+                                    &task_store,
                                     &links,
                                     &agent_names,
                                     &sqlite_pool,
@@ -3145,6 +3195,7 @@ async fn pickup_one_ready_task(deps: &AgentDeps, logger: &CortexLogger) -> anyho
                             &timeout_message,
                             false,
                             &agent_id,
+                            &task_store,
                             &links,
                             &agent_names,
                             &sqlite_pool,
@@ -3263,16 +3314,352 @@ async fn pickup_one_ready_task(deps: &AgentDeps, logger: &CortexLogger) -> anyho
     Ok(())
 }
 
-/// When a task with `metadata.delegating_agent_id` completes or fails, log the
-/// result in the link channel between the two agents and inject a retrigger
-/// system message into the delegating agent's originating channel so the user
-/// gets notified.
+/// The prompt a task worker runs: the task's title, description, and
+/// subtasks.
+fn task_worker_prompt(task: &crate::tasks::Task) -> String {
+    let mut task_prompt = format!("Execute task #{}: {}", task.task_number, task.title);
+    if let Some(description) = &task.description {
+        task_prompt.push_str("\n\nDescription:\n");
+        task_prompt.push_str(description);
+    }
+    if !task.subtasks.is_empty() {
+        task_prompt.push_str("\n\nSubtasks:\n");
+        for (index, subtask) in task.subtasks.iter().enumerate() {
+            let marker = if subtask.completed { "[x]" } else { "[ ]" };
+            task_prompt.push_str(&format!("{}. {} {}\n", index + 1, marker, subtask.title));
+        }
+    }
+    task_prompt
+}
+
+/// Build a detached worker for a task prompt, with the agent's worker system
+/// prompt and workspace directories.
+fn new_task_worker(deps: &AgentDeps, task_prompt: String) -> anyhow::Result<Worker> {
+    let prompt_engine = deps.runtime_config.prompts.load();
+    let sandbox_enabled = deps.sandbox.mode_enabled();
+    let sandbox_containment_active = deps.sandbox.containment_active();
+    let sandbox_read_allowlist = deps.sandbox.prompt_read_allowlist();
+    let sandbox_write_allowlist = deps.sandbox.prompt_write_allowlist();
+
+    // Collect tool secret names so the worker template can list available credentials.
+    let secrets_guard = deps.runtime_config.secrets.load();
+    let tool_secret_names = match (*secrets_guard).as_ref() {
+        Some(store) => store.tool_secret_names(),
+        None => Vec::new(),
+    };
+
+    let browser_config = (**deps.runtime_config.browser_config.load()).clone();
+
+    // Build worker status text (time + model) for the system prompt.
+    let system_info =
+        crate::agent::status::SystemInfo::from_runtime_config(&deps.runtime_config, &deps.sandbox);
+    let temporal_context =
+        crate::agent::channel_prompt::TemporalContext::from_runtime(&deps.runtime_config);
+    let current_time_line = temporal_context.current_time_line();
+    let worker_status_text = Some(system_info.render_for_worker(&current_time_line));
+
+    let worker_system_prompt = prompt_engine
+        .render_worker_prompt(
+            &deps.runtime_config.instance_dir.display().to_string(),
+            &deps.runtime_config.workspace_dir.display().to_string(),
+            sandbox_enabled,
+            sandbox_containment_active,
+            sandbox_read_allowlist,
+            sandbox_write_allowlist,
+            &tool_secret_names,
+            browser_config.persist_session,
+            worker_status_text,
+        )
+        .map_err(|error| anyhow::anyhow!("failed to render worker prompt: {error}"))?;
+
+    let screenshot_dir = deps
+        .runtime_config
+        .workspace_dir
+        .join(".spacebot")
+        .join("screenshots");
+    let logs_dir = deps
+        .runtime_config
+        .workspace_dir
+        .join(".spacebot")
+        .join("logs");
+    if let Err(error) = std::fs::create_dir_all(&screenshot_dir) {
+        tracing::warn!(%error, path = %screenshot_dir.display(), "failed to create screenshot directory");
+    }
+    if let Err(error) = std::fs::create_dir_all(&logs_dir) {
+        tracing::warn!(%error, path = %logs_dir.display(), "failed to create logs directory");
+    }
+
+    let brave_search_key = (**deps.runtime_config.brave_search_key.load()).clone();
+    let (worker, inject_tx) = Worker::new(
+        None,
+        task_prompt,
+        worker_system_prompt,
+        deps.clone(),
+        browser_config,
+        screenshot_dir,
+        brave_search_key,
+        logs_dir,
+    );
+
+    // Detached workers are not channel-owned, so injection senders are not
+    // stored in ChannelState. The inject_tx is dropped here — detached task
+    // workers don't support mid-flight context injection.
+    drop(inject_tx);
+
+    Ok(worker)
+}
+
+/// Hand a claimed ready task to a less busy node, when there is one. The
+/// task stays in progress here until [`apply_finished_cluster_jobs`] sees
+/// the result. Returns whether the task was handed off.
+async fn offload_ready_task(
+    cluster: &crate::cluster::Cluster,
+    deps: &AgentDeps,
+    logger: &CortexLogger,
+    task: &crate::tasks::Task,
+    task_prompt: &str,
+) -> anyhow::Result<bool> {
+    let Some(node_id) = cluster.offload_target().await? else {
+        return Ok(false);
+    };
+    let job_id = cluster
+        .enqueue_job(
+            &deps.agent_id,
+            task.task_number,
+            &task.title,
+            task_prompt,
+            &node_id,
+        )
+        .await?;
+
+    let _ = deps.event_tx.send(ProcessEvent::TaskUpdated {
+        agent_id: deps.agent_id.clone(),
+        task_number: task.task_number,
+        status: "in_progress".to_string(),
+        action: "updated".to_string(),
+    });
+    logger.log(
+        "task_pickup_offloaded",
+        &format!(
+            "Handed ready task #{} to cluster node {node_id}",
+            task.task_number
+        ),
+        Some(serde_json::json!({
+            "task_number": task.task_number,
+            "job_id": job_id,
+            "node_id": node_id,
+        })),
+    );
+    Ok(true)
+}
+
+/// Apply the results of tasks this node handed to other nodes: mark them
+/// done, or back to ready on failure, as a local worker would.
+async fn apply_finished_cluster_jobs(
+    cluster: &crate::cluster::Cluster,
+    deps: &AgentDeps,
+    logger: &CortexLogger,
+) -> anyhow::Result<()> {
+    for job in cluster.take_finished_jobs(&deps.agent_id).await? {
+        let status = if job.success {
+            TaskStatus::Done
+        } else {
+            TaskStatus::Ready
+        };
+        let updated = deps
+            .task_store
+            .update(
+                &deps.agent_id,
+                job.task_number,
+                UpdateTaskInput {
+                    status: Some(status),
+                    clear_worker_id: !job.success,
+                    ..Default::default()
+                },
+            )
+            .await;
+        let task = match updated {
+            Ok(Some(task)) => task,
+            Ok(None) => {
+                tracing::warn!(
+                    task_number = job.task_number,
+                    "cluster job finished for a task that no longer exists"
+                );
+                continue;
+            }
+            Err(error) => {
+                tracing::warn!(
+                    %error,
+                    task_number = job.task_number,
+                    "failed to apply cluster job result"
+                );
+                continue;
+            }
+        };
+
+        let _ = deps.event_tx.send(ProcessEvent::TaskUpdated {
+            agent_id: deps.agent_id.clone(),
+            task_number: job.task_number,
+            status: status.as_str().to_string(),
+            action: "updated".to_string(),
+        });
+        logger.log(
+            if job.success {
+                "task_pickup_completed"
+            } else {
+                "task_pickup_failed"
+            },
+            &format!(
+                "Task #{} {} on cluster node {}",
+                job.task_number,
+                if job.success { "completed" } else { "failed" },
+                job.worker_node
+            ),
+            Some(serde_json::json!({
+                "task_number": job.task_number,
+                "job_id": job.id,
+                "node_id": job.worker_node,
+            })),
+        );
+        notify_delegation_completion(
+            &task,
+            &job.result,
+            job.success,
+            &deps.agent_id,
+            &deps.task_store,
+            &deps.links,
+            &deps.agent_names,
+            &deps.sqlite_pool,
+            &deps.injection_tx,
+        )
+        .await;
+    }
+    Ok(())
+}
+
+/// Run the next cluster job another node handed to this one for this agent.
+/// The worker runs in the background; its result goes back to the job's
+/// origin node.
+async fn run_one_cluster_job(
+    cluster: &'static Arc<crate::cluster::Cluster>,
+    deps: &AgentDeps,
+    logger: &CortexLogger,
+) -> anyhow::Result<()> {
+    let Some(job) = cluster.claim_job(&deps.agent_id).await? else {
+        return Ok(());
+    };
+    let worker = match new_task_worker(deps, job.prompt.clone()) {
+        Ok(worker) => worker,
+        Err(error) => {
+            cluster
+                .finish_job(job.id, false, &error.to_string())
+                .await?;
+            return Err(error);
+        }
+    };
+
+    let worker_id = worker.id;
+    let task_description = format!(
+        "task #{} from {}: {}",
+        job.task_number, job.origin_node, job.title
+    );
+    let _ = deps.event_tx.send(ProcessEvent::WorkerStarted {
+        agent_id: deps.agent_id.clone(),
+        worker_id,
+        channel_id: None,
+        task: task_description.clone(),
+        worker_type: "task".to_string(),
+        interactive: false,
+        directory: None,
+    });
+    let run_logger = crate::conversation::history::ProcessRunLogger::new(deps.sqlite_pool.clone());
+    run_logger.log_worker_started(
+        None,
+        worker_id,
+        &task_description,
+        "task",
+        &deps.agent_id,
+        false,
+        None,
+    );
+    logger.log(
+        "cluster_job_started",
+        &format!(
+            "Running task #{} for cluster node {}",
+            job.task_number, job.origin_node
+        ),
+        Some(serde_json::json!({
+            "job_id": job.id,
+            "task_number": job.task_number,
+            "node_id": job.origin_node,
+            "worker_id": worker_id.to_string(),
+        })),
+    );
+
+    let agent_id = deps.agent_id.clone();
+    let event_tx = deps.event_tx.clone();
+    let secrets_snapshot = deps.runtime_config.secrets.load().clone();
+    tokio::spawn(async move {
+        let _worker_slot = cluster.worker_slot();
+        let scrub = |text: String| -> String {
+            let scrubbed = if let Some(store) = secrets_snapshot.as_ref() {
+                crate::secrets::scrub::scrub_with_store(&text, store)
+            } else {
+                text
+            };
+            crate::secrets::scrub::scrub_leaks(&scrubbed)
+        };
+
+        let (success, result) = match std::panic::AssertUnwindSafe(worker.run())
+            .catch_unwind()
+            .await
+        {
+            Ok(Ok(result)) => (true, scrub(result)),
+            Ok(Err(error)) => {
+                let (message, _notify, _success) = map_worker_completion_result(Err(
+                    WorkerCompletionError::failed(scrub(error.to_string())),
+                ));
+                (false, message)
+            }
+            Err(panic_payload) => {
+                let scrubbed_panic = scrub(crate::agent::panic_payload_to_string(&*panic_payload));
+                let (message, _notify, _success) =
+                    map_worker_completion_result(Err(WorkerCompletionError::failed(format!(
+                        "worker task panicked: {scrubbed_panic}"
+                    ))));
+                (false, message)
+            }
+        };
+        run_logger.log_worker_completed(worker_id, &result, success);
+        if let Err(error) = cluster.finish_job(job.id, success, &result).await {
+            tracing::warn!(%error, job_id = job.id, "failed to record cluster job result");
+        }
+        let _ = event_tx.send(ProcessEvent::WorkerComplete {
+            agent_id,
+            worker_id,
+            channel_id: None,
+            result,
+            notify: false,
+            success,
+        });
+    });
+    Ok(())
+}
+
+/// Longest delegated task result kept on the task for the delegating agent.
+const DELEGATION_RESULT_MAX_BYTES: usize = 8000;
+
+/// When a task with `metadata.delegating_agent_id` completes or fails, record
+/// the outcome in its metadata, log the result in the link channel between
+/// the two agents and inject a retrigger system message into the delegating
+/// agent's originating channel so the user gets notified.
 #[allow(clippy::too_many_arguments)]
 async fn notify_delegation_completion(
     task: &crate::tasks::Task,
     result_summary: &str,
     success: bool,
     executor_agent_id: &str,
+    task_store: &crate::tasks::TaskStore,
     links: &arc_swap::ArcSwap<Vec<crate::links::AgentLink>>,
     agent_names: &std::collections::HashMap<String, String>,
     sqlite_pool: &sqlx::SqlitePool,
@@ -3288,6 +3675,39 @@ async fn notify_delegation_completion(
         return; // Not a delegated task.
     };
 
+    // Record the outcome on the task so a delegating worker waiting on it
+    // through `delegate_to_agent` can read it.
+    let stored_result = if result_summary.len() > DELEGATION_RESULT_MAX_BYTES {
+        let boundary = result_summary.floor_char_boundary(DELEGATION_RESULT_MAX_BYTES);
+        format!("{}... [truncated]", &result_summary[..boundary])
+    } else {
+        result_summary.to_string()
+    };
+    let outcome = serde_json::json!({
+        "delegation_result": {
+            "success": success,
+            "result": stored_result,
+            "finished_at": chrono::Utc::now().to_rfc3339(),
+        },
+    });
+    if let Err(error) = task_store
+        .update(
+            executor_agent_id,
+            task.task_number,
+            UpdateTaskInput {
+                metadata: Some(outcome),
+                ..Default::default()
+            },
+        )
+        .await
+    {
+        tracing::warn!(
+            %error,
+            task_number = task.task_number,
+            "failed to record delegated task outcome"
+        );
+    }
+
     let originating_channel = task
         .metadata
         .get("originating_channel")
@@ -3380,16 +3800,25 @@ async fn notify_delegation_completion(
 
 async fn run_association_loop(deps: &AgentDeps, logger: &CortexLogger) -> anyhow::Result<()> {
     tracing::info!("cortex association loop started");
+    let job = deps.runtime_config.jobs.register(
+        "association",
+        "Link related memories by embedding similarity",
+    );
 
     // Short delay on startup to let the bulletin and embeddings settle
-    tokio::time::sleep(Duration::from_secs(10)).await;
+    job.wait(Duration::from_secs(10)).await;
 
     // Backfill: process all existing memories on first run
+    job.started();
     let backfill_count = run_association_pass(deps, logger, None).await;
     tracing::info!(
         associations_created = backfill_count,
         "association backfill complete"
     );
+    job.finished(
+        true,
+        format!("backfill created {backfill_count} associations"),
+    );
 
     let mut last_pass_at = chrono::Utc::now();
 
@@ -3397,15 +3826,17 @@ async fn run_association_loop(deps: &AgentDeps, logger: &CortexLogger) -> anyhow
         let cortex_config = **deps.runtime_config.cortex.load();
         let interval = cortex_config.association_interval_secs;
 
-        tokio::time::sleep(Duration::from_secs(interval)).await;
+        job.wait(Duration::from_secs(interval)).await;
 
         let since = Some(last_pass_at);
         last_pass_at = chrono::Utc::now();
 
+        job.started();
         let count = run_association_pass(deps, logger, since).await;
         if count > 0 {
             tracing::info!(associations_created = count, "association pass complete");
         }
+        job.finished(true, format!("created {count} associations"));
     }
 }
 
@@ -3944,6 +4375,15 @@ mod tests {
                 channel_id: Some(channel_id.clone()),
                 result: "initial result".to_string(),
             },
+            ProcessEvent::BudgetWarning {
+                agent_id: Arc::from("agent"),
+                scope: "channel".to_string(),
+                channel_id: Some(channel_id.clone()),
+                worker_id: None,
+                spent_usd: 5.2,
+                limit_usd: 5.0,
+                hard: false,
+            },
         ];
 
         for event in events {
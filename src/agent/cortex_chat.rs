@@ -8,6 +8,8 @@
 use crate::conversation::history::ProcessRunLogger;
 use crate::hooks::SpacebotHook;
 use crate::llm::SpacebotModel;
+use crate::llm::budget::SpendScope;
+use crate::secrets::content_cipher::{ContentCipher, is_encrypted_content};
 use crate::{AgentDeps, ProcessEvent, ProcessId, ProcessType};
 
 use rig::agent::{AgentBuilder, HookAction, PromptHook, ToolCallHookAction};
@@ -44,6 +46,17 @@ pub struct CortexChatThread {
     pub message_count: i64,
     pub first_message_at: String,
     pub last_message_at: String,
+    /// The thread this one was forked from, if any.
+    pub parent_thread_id: Option<String>,
+}
+
+/// Result of forking a thread at a message.
+#[derive(Debug, Clone, Serialize)]
+pub struct CortexChatThreadFork {
+    pub thread_id: String,
+    pub parent_thread_id: String,
+    pub forked_from_message_id: String,
+    pub message_count: u64,
 }
 
 /// A tool call + result pair persisted alongside assistant messages.
@@ -251,10 +264,19 @@ impl<M: CompletionModel> PromptHook<M> for CortexChatHook {
     }
 }
 
+/// Shown in place of message content that can't be decrypted, e.g. because
+/// the config key is missing or was replaced.
+const UNREADABLE_CONTENT: &str = "[encrypted content unavailable]";
+
 /// SQLite CRUD for cortex chat messages.
+///
+/// With a cipher attached, encrypted rows are decrypted on read. New rows
+/// are only encrypted when `encrypt_writes` is also set.
 #[derive(Debug, Clone)]
 pub struct CortexChatStore {
     pool: SqlitePool,
+    cipher: Option<Arc<ContentCipher>>,
+    encrypt_writes: bool,
 }
 
 #[derive(sqlx::FromRow)]
@@ -269,17 +291,17 @@ struct ChatMessageRow {
 }
 
 impl ChatMessageRow {
-    fn into_message(self) -> CortexChatMessage {
+    fn into_message(self, store: &CortexChatStore) -> CortexChatMessage {
         let tool_calls = self
             .tool_calls
-            .as_deref()
-            .and_then(|json| serde_json::from_str::<Vec<CortexChatToolCall>>(json).ok());
+            .map(|json| store.reveal(json))
+            .and_then(|json| serde_json::from_str::<Vec<CortexChatToolCall>>(&json).ok());
         CortexChatMessage {
             id: self.id,
             thread_id: self.thread_id,
             role: self.role,
-            content: self.content,
-            channel_context: self.channel_context,
+            content: store.reveal(self.content),
+            channel_context: self.channel_context.map(|context| store.reveal(context)),
             created_at: self.created_at.and_utc().to_rfc3339(),
             tool_calls,
         }
@@ -293,23 +315,101 @@ struct CortexChatThreadRow {
     first_message_at: chrono::NaiveDateTime,
     last_message_at: chrono::NaiveDateTime,
     preview: String,
+    parent_thread_id: Option<String>,
 }
 
 impl CortexChatThreadRow {
-    fn into_thread(self) -> CortexChatThread {
+    fn into_thread(self, store: &CortexChatStore) -> CortexChatThread {
         CortexChatThread {
             thread_id: self.thread_id,
-            preview: self.preview,
+            preview: store.reveal(self.preview),
             message_count: self.message_count as i64,
             first_message_at: self.first_message_at.and_utc().to_rfc3339(),
             last_message_at: self.last_message_at.and_utc().to_rfc3339(),
+            parent_thread_id: self.parent_thread_id,
         }
     }
 }
 
 impl CortexChatStore {
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            cipher: None,
+            encrypt_writes: false,
+        }
+    }
+
+    /// Store for an agent's database using the agent's content key, when a
+    /// config key is loaded. `encrypt_at_rest` also encrypts new rows.
+    pub fn for_agent(pool: SqlitePool, agent_id: &str, encrypt_at_rest: bool) -> Self {
+        let cipher = ContentCipher::for_agent(agent_id);
+        if encrypt_at_rest && cipher.is_none() {
+            tracing::warn!(
+                agent_id,
+                "storage.encrypt_at_rest is set but no config key is loaded; \
+                 cortex chat messages will be stored unencrypted"
+            );
+        }
+        Self::new(pool).with_cipher(cipher, encrypt_at_rest)
+    }
+
+    /// Attach the agent's content cipher. Without `encrypt_writes` the
+    /// cipher is only used to read rows written while encryption was on.
+    pub fn with_cipher(mut self, cipher: Option<Arc<ContentCipher>>, encrypt_writes: bool) -> Self {
+        self.cipher = cipher;
+        self.encrypt_writes = encrypt_writes;
+        self
+    }
+
+    /// Encrypt a value for storage when at-rest encryption is on.
+    fn seal(&self, value: &str) -> Result<String, sqlx::Error> {
+        match &self.cipher {
+            Some(cipher) if self.encrypt_writes => cipher
+                .encrypt(value)
+                .map_err(|error| sqlx::Error::Encode(Box::new(error))),
+            _ => Ok(value.to_string()),
+        }
+    }
+
+    /// Decrypt a stored value. Plaintext passes through unchanged.
+    fn reveal(&self, value: String) -> String {
+        if !is_encrypted_content(&value) {
+            return value;
+        }
+        let Some(cipher) = &self.cipher else {
+            tracing::warn!("cortex chat message is encrypted but no config key is loaded");
+            return UNREADABLE_CONTENT.to_string();
+        };
+        cipher.decrypt(&value).unwrap_or_else(|error| {
+            tracing::warn!(%error, "failed to decrypt cortex chat message");
+            UNREADABLE_CONTENT.to_string()
+        })
+    }
+
+    /// Decrypt a stored value for export. Unlike display reads, content that
+    /// can't be decrypted is an error rather than a placeholder, so an archive
+    /// never carries ciphertext the target can't read.
+    pub fn decrypt_for_export(&self, value: &str) -> anyhow::Result<String> {
+        if !is_encrypted_content(value) {
+            return Ok(value.to_string());
+        }
+        let cipher = self.cipher.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("cortex chat message is encrypted but no config key is loaded")
+        })?;
+        cipher
+            .decrypt(value)
+            .map_err(|error| anyhow::anyhow!("failed to decrypt cortex chat message: {error}"))
+    }
+
+    /// Prepare an imported value for storage, encrypting it the way
+    /// [`Self::save_message`] would.
+    pub fn seal_for_import(&self, value: &str) -> anyhow::Result<String> {
+        if is_encrypted_content(value) {
+            return Ok(value.to_string());
+        }
+        self.seal(value)
+            .map_err(|error| anyhow::anyhow!("failed to encrypt cortex chat message: {error}"))
     }
 
     /// Load chat history for a thread, newest first, then reverse to chronological order.
@@ -320,7 +420,7 @@ impl CortexChatStore {
     ) -> Result<Vec<CortexChatMessage>, sqlx::Error> {
         let rows: Vec<ChatMessageRow> = sqlx::query_as(
             "SELECT id, thread_id, role, content, channel_context, tool_calls, created_at \
-             FROM cortex_chat_messages WHERE thread_id = ? ORDER BY created_at DESC LIMIT ?",
+             FROM cortex_chat_messages WHERE thread_id = ? ORDER BY created_at DESC, rowid DESC LIMIT ?",
         )
         .bind(thread_id)
         .bind(limit)
@@ -328,7 +428,7 @@ impl CortexChatStore {
         .await?;
 
         let mut messages: Vec<CortexChatMessage> =
-            rows.into_iter().map(|row| row.into_message()).collect();
+            rows.into_iter().map(|row| row.into_message(self)).collect();
         messages.reverse();
         Ok(messages)
     }
@@ -345,6 +445,9 @@ impl CortexChatStore {
         tool_calls: Option<&str>,
     ) -> Result<String, sqlx::Error> {
         let id = uuid::Uuid::new_v4().to_string();
+        let content = self.seal(content)?;
+        let channel_context = channel_context.map(|value| self.seal(value)).transpose()?;
+        let tool_calls = tool_calls.map(|value| self.seal(value)).transpose()?;
         sqlx::query(
             "INSERT INTO cortex_chat_messages (id, thread_id, role, content, channel_context, tool_calls) \
              VALUES (?, ?, ?, ?, ?, ?)",
@@ -382,7 +485,11 @@ impl CortexChatStore {
                      SELECT content FROM cortex_chat_messages m2 \
                      WHERE m2.thread_id = cortex_chat_messages.thread_id \
                      ORDER BY m2.created_at ASC LIMIT 1 \
-                 ) as preview \
+                 ) as preview, \
+                 ( \
+                     SELECT parent_thread_id FROM cortex_chat_thread_forks f \
+                     WHERE f.thread_id = cortex_chat_messages.thread_id \
+                 ) as parent_thread_id \
              FROM cortex_chat_messages \
              GROUP BY thread_id \
              ORDER BY MAX(created_at) DESC",
@@ -390,17 +497,104 @@ impl CortexChatStore {
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(rows.into_iter().map(|row| row.into_thread()).collect())
+        Ok(rows.into_iter().map(|row| row.into_thread(self)).collect())
     }
 
     /// Delete all messages in a thread.
+    ///
+    /// Forks of this thread keep their copied messages; their parent link
+    /// simply points at a thread that no longer exists.
     pub async fn delete_thread(&self, thread_id: &str) -> Result<u64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
         let result = sqlx::query("DELETE FROM cortex_chat_messages WHERE thread_id = ?")
             .bind(thread_id)
-            .execute(&self.pool)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM cortex_chat_thread_forks WHERE thread_id = ?")
+            .bind(thread_id)
+            .execute(&mut *tx)
             .await?;
+        tx.commit().await?;
         Ok(result.rows_affected())
     }
+
+    /// Fork a thread at a message.
+    ///
+    /// Copies every message in `thread_id` up to and including `message_id`
+    /// into a new thread, keeping their timestamps and tool calls, and
+    /// records the parent link. The original thread is untouched. Encrypted
+    /// rows are copied as stored. Returns `None` when the message doesn't
+    /// exist in that thread.
+    pub async fn fork_thread(
+        &self,
+        thread_id: &str,
+        message_id: &str,
+    ) -> Result<Option<CortexChatThreadFork>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // Order by rowid within equal timestamps: CURRENT_TIMESTAMP only has
+        // second resolution, so a user message and its reply often tie.
+        let cutoff: Option<(chrono::NaiveDateTime, i64)> = sqlx::query_as(
+            "SELECT created_at, rowid FROM cortex_chat_messages WHERE id = ? AND thread_id = ?",
+        )
+        .bind(message_id)
+        .bind(thread_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((cutoff_at, cutoff_rowid)) = cutoff else {
+            return Ok(None);
+        };
+
+        let rows: Vec<ChatMessageRow> = sqlx::query_as(
+            "SELECT id, thread_id, role, content, channel_context, tool_calls, created_at \
+             FROM cortex_chat_messages \
+             WHERE thread_id = ? AND (created_at < ? OR (created_at = ? AND rowid <= ?)) \
+             ORDER BY created_at ASC, rowid ASC",
+        )
+        .bind(thread_id)
+        .bind(cutoff_at)
+        .bind(cutoff_at)
+        .bind(cutoff_rowid)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let new_thread_id = uuid::Uuid::new_v4().to_string();
+        for row in &rows {
+            sqlx::query(
+                "INSERT INTO cortex_chat_messages \
+                 (id, thread_id, role, content, channel_context, tool_calls, created_at) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(&new_thread_id)
+            .bind(&row.role)
+            .bind(&row.content)
+            .bind(&row.channel_context)
+            .bind(&row.tool_calls)
+            .bind(row.created_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query(
+            "INSERT INTO cortex_chat_thread_forks (thread_id, parent_thread_id, forked_from_message_id) \
+             VALUES (?, ?, ?)",
+        )
+        .bind(&new_thread_id)
+        .bind(thread_id)
+        .bind(message_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(Some(CortexChatThreadFork {
+            thread_id: new_thread_id,
+            parent_thread_id: thread_id.to_string(),
+            forked_from_message_id: message_id.to_string(),
+            message_count: rows.len() as u64,
+        }))
+    }
 }
 
 /// The cortex chat session for a single agent.
@@ -679,7 +873,8 @@ impl CortexChatSession {
         let model_name = routing.resolve(ProcessType::Cortex, None).to_string();
         let model = SpacebotModel::make(&self.deps.llm_manager, &model_name)
             .with_context(self.deps.agent_id.as_ref(), "cortex")
-            .with_routing(routing.as_ref().clone());
+            .with_routing(routing.as_ref().clone())
+            .with_budget(self.deps.budget_guard(SpendScope::agent()));
 
         let agent = AgentBuilder::new(model)
             .preamble(&system_prompt)
@@ -794,7 +989,7 @@ impl CortexChatSession {
         let prompt_engine = runtime_config.prompts.load();
 
         let identity_context = runtime_config.identity.load().render();
-        let memory_bulletin = runtime_config.memory_bulletin.load();
+        let memory_bulletin = runtime_config.rendered_bulletin();
         let agents_manifest = crate::self_awareness::agents_manifest_for_prompt();
         let changelog_highlights = crate::self_awareness::changelog_highlights();
         let runtime_config_snapshot = crate::self_awareness::runtime_snapshot_pretty(
@@ -824,7 +1019,7 @@ impl CortexChatSession {
 
         prompt_engine.render_cortex_chat_prompt(
             empty_to_none(identity_context),
-            empty_to_none(memory_bulletin.to_string()),
+            empty_to_none(memory_bulletin),
             channel_transcript,
             empty_to_none(agents_manifest),
             empty_to_none(changelog_highlights),
@@ -887,7 +1082,8 @@ impl CortexChatSession {
 
 #[cfg(test)]
 mod tests {
-    use super::{CortexChatSendError, try_acquire_send_lock};
+    use super::{CortexChatSendError, CortexChatStore, UNREADABLE_CONTENT, try_acquire_send_lock};
+    use crate::secrets::content_cipher::ContentCipher;
     use std::sync::Arc;
     use std::time::Duration;
     use tokio::sync::Mutex;
@@ -935,4 +1131,127 @@ mod tests {
             "single-flight lock should be released after timeout path"
         );
     }
+
+    async fn setup_store() -> CortexChatStore {
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("failed to create in-memory pool");
+        crate::db::MIGRATOR
+            .run(&pool)
+            .await
+            .expect("failed to run migrations");
+        CortexChatStore::new(pool)
+    }
+
+    #[tokio::test]
+    async fn fork_copies_messages_up_to_the_fork_point() {
+        let store = setup_store().await;
+        store
+            .save_message("main", "user", "first", None, None)
+            .await
+            .unwrap();
+        let fork_point = store
+            .save_message("main", "assistant", "second", None, Some("[]"))
+            .await
+            .unwrap();
+        store
+            .save_message("main", "user", "third", None, None)
+            .await
+            .unwrap();
+
+        let fork = store
+            .fork_thread("main", &fork_point)
+            .await
+            .unwrap()
+            .expect("fork point exists");
+        assert_eq!(fork.parent_thread_id, "main");
+        assert_eq!(fork.message_count, 2);
+
+        let forked = store.load_history(&fork.thread_id, 50).await.unwrap();
+        let contents: Vec<_> = forked.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["first", "second"]);
+        assert!(forked[1].tool_calls.is_some());
+        assert_eq!(store.load_history("main", 50).await.unwrap().len(), 3);
+
+        let threads = store.list_threads().await.unwrap();
+        let listed = threads
+            .iter()
+            .find(|thread| thread.thread_id == fork.thread_id)
+            .unwrap();
+        assert_eq!(listed.parent_thread_id.as_deref(), Some("main"));
+    }
+
+    #[tokio::test]
+    async fn encrypted_messages_are_unreadable_in_the_database() {
+        let plain = setup_store().await;
+        let cipher = Arc::new(ContentCipher::derive(&[7u8; 32], "main").unwrap());
+        let store = plain.clone().with_cipher(Some(cipher.clone()), true);
+
+        plain
+            .save_message("main", "user", "written before encryption", None, None)
+            .await
+            .unwrap();
+        let encrypted_id = store
+            .save_message(
+                "main",
+                "assistant",
+                "board memo draft",
+                Some("#finance"),
+                Some(
+                    r#"[{"id":"1","tool":"file","args":"{}","result":null,"status":"completed"}]"#,
+                ),
+            )
+            .await
+            .unwrap();
+
+        let (content, context, tool_calls): (String, Option<String>, Option<String>) =
+            sqlx::query_as(
+                "SELECT content, channel_context, tool_calls FROM cortex_chat_messages WHERE id = ?",
+            )
+            .bind(&encrypted_id)
+            .fetch_one(&store.pool)
+            .await
+            .unwrap();
+        assert!(!content.contains("memo"));
+        assert!(!context.unwrap().contains("finance"));
+        assert!(!tool_calls.unwrap().contains("file"));
+
+        let history = store.load_history("main", 50).await.unwrap();
+        assert_eq!(history[0].content, "written before encryption");
+        assert_eq!(history[1].content, "board memo draft");
+        assert_eq!(history[1].channel_context.as_deref(), Some("#finance"));
+        assert_eq!(history[1].tool_calls.as_ref().unwrap()[0].tool, "file");
+
+        // Turning writes off keeps old rows readable.
+        let read_only = plain.clone().with_cipher(Some(cipher), false);
+        let fork = read_only
+            .fork_thread("main", &encrypted_id)
+            .await
+            .unwrap()
+            .unwrap();
+        let forked = read_only.load_history(&fork.thread_id, 50).await.unwrap();
+        assert_eq!(forked[1].content, "board memo draft");
+
+        let history = plain.load_history("main", 50).await.unwrap();
+        assert_eq!(history[1].content, UNREADABLE_CONTENT);
+        assert!(history[1].tool_calls.is_none());
+    }
+
+    #[tokio::test]
+    async fn fork_rejects_messages_from_other_threads() {
+        let store = setup_store().await;
+        let other = store
+            .save_message("other", "user", "hello", None, None)
+            .await
+            .unwrap();
+
+        assert!(store.fork_thread("main", &other).await.unwrap().is_none());
+        assert!(
+            store
+                .fork_thread("main", "missing")
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
 }
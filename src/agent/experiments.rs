@@ -0,0 +1,567 @@
+//! A/B experiments on channel prompts and routing.
+//!
+//! An experiment compares two variants of the channel process. Each variant
+//! can replace one prompt template, the channel model, or both. A channel is
+//! given a variant on its first turn during the experiment, either one pinned
+//! through the API or a stable hash of the channel ID against the share of
+//! traffic sent to variant B, and keeps it until the experiment stops.
+//!
+//! Every turn records how many tools it called and how many failed, and
+//! feedback can be recorded against a channel's variant, so the report can
+//! compare the two.
+
+use crate::prompts::PromptEngine;
+use crate::{AgentDeps, ChannelId};
+
+use rig::message::{Message, ToolResultContent, UserContent};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row as _, SqlitePool};
+use std::sync::Arc;
+
+/// Prefix rig gives the result of a tool call that failed.
+const TOOL_ERROR_PREFIX: &str = "Toolset error:";
+
+/// One of the two variants under comparison.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VariantKey {
+    A,
+    B,
+}
+
+impl VariantKey {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::A => "a",
+            Self::B => "b",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "a" => Some(Self::A),
+            "b" => Some(Self::B),
+            _ => None,
+        }
+    }
+}
+
+/// What a variant changes. Unset fields keep the agent's normal behavior.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Variant {
+    /// Source for the experiment's template.
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Model for the channel process.
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExperimentStatus {
+    Running,
+    Stopped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Experiment {
+    pub id: String,
+    pub name: String,
+    /// The prompt template the variants' `prompt` replaces.
+    pub template: Option<String>,
+    pub variant_a: Variant,
+    pub variant_b: Variant,
+    /// Percent of channels without a pinned variant that get variant B.
+    pub traffic_b: u8,
+    pub status: ExperimentStatus,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub stopped_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl Experiment {
+    pub fn variant(&self, key: VariantKey) -> &Variant {
+        match key {
+            VariantKey::A => &self.variant_a,
+            VariantKey::B => &self.variant_b,
+        }
+    }
+
+    /// The variant for a channel that hasn't been pinned to one. Stable for
+    /// a given experiment and channel.
+    pub fn hashed_variant(&self, channel_id: &str) -> VariantKey {
+        // FNV-1a, so assignments don't depend on the std hasher's seed.
+        let hash = self
+            .id
+            .bytes()
+            .chain([0])
+            .chain(channel_id.bytes())
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+        if hash % 100 < u64::from(self.traffic_b) {
+            VariantKey::B
+        } else {
+            VariantKey::A
+        }
+    }
+}
+
+/// The variant a channel turn runs with.
+#[derive(Clone)]
+pub struct ExperimentArm {
+    pub experiment_id: String,
+    pub variant: VariantKey,
+    /// Engine with the variant's template, if it replaces one.
+    pub prompt_engine: Option<Arc<PromptEngine>>,
+    pub model: Option<String>,
+}
+
+/// The variant a channel should run its next turn with, if an experiment is
+/// running. Failures are logged and leave the channel on its normal behavior.
+pub async fn arm_for_channel(deps: &AgentDeps, channel_id: &ChannelId) -> Option<ExperimentArm> {
+    let experiment = (*deps.runtime_config.experiment.load_full()).clone()?;
+
+    let store = ExperimentStore::new(deps.sqlite_pool.clone());
+    let variant = match store
+        .assign(
+            &experiment.id,
+            channel_id,
+            experiment.hashed_variant(channel_id),
+        )
+        .await
+    {
+        Ok(variant) => variant,
+        Err(error) => {
+            tracing::warn!(%error, %channel_id, experiment_id = %experiment.id, "failed to assign experiment variant");
+            return None;
+        }
+    };
+
+    let settings = experiment.variant(variant);
+    let prompt_engine = match (&experiment.template, &settings.prompt) {
+        (Some(template), Some(source)) => {
+            match deps
+                .runtime_config
+                .prompts
+                .load()
+                .with_template(template, source)
+            {
+                Ok(engine) => Some(Arc::new(engine)),
+                Err(error) => {
+                    tracing::warn!(%error, experiment_id = %experiment.id, "failed to build experiment prompt, using the agent's");
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+
+    Some(ExperimentArm {
+        experiment_id: experiment.id.clone(),
+        variant,
+        prompt_engine,
+        model: settings.model.clone(),
+    })
+}
+
+/// Count the tool calls among the messages a turn added, and how many of
+/// them failed.
+pub fn tool_call_stats(messages: &[Message]) -> (i64, i64) {
+    let mut calls = 0;
+    let mut errors = 0;
+    for message in messages {
+        let Message::User { content } = message else {
+            continue;
+        };
+        for item in content.iter() {
+            let UserContent::ToolResult(result) = item else {
+                continue;
+            };
+            calls += 1;
+            let failed = result.content.iter().any(|content| {
+                matches!(content, ToolResultContent::Text(text) if text.text.starts_with(TOOL_ERROR_PREFIX))
+            });
+            if failed {
+                errors += 1;
+            }
+        }
+    }
+    (calls, errors)
+}
+
+/// Outcomes for one variant.
+#[derive(Debug, Clone, Serialize)]
+pub struct VariantReport {
+    pub variant: VariantKey,
+    pub channels: i64,
+    pub turns: i64,
+    pub turns_per_channel: f64,
+    pub tool_calls: i64,
+    pub tool_errors: i64,
+    pub tool_error_rate: f64,
+    pub positive_feedback: i64,
+    pub negative_feedback: i64,
+}
+
+/// Persists experiments, channel assignments, and outcomes.
+#[derive(Debug, Clone)]
+pub struct ExperimentStore {
+    pool: SqlitePool,
+}
+
+impl ExperimentStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        name: &str,
+        template: Option<&str>,
+        variant_a: &Variant,
+        variant_b: &Variant,
+        traffic_b: u8,
+    ) -> crate::error::Result<Experiment> {
+        let id = uuid::Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO experiments (id, name, template, variant_a, variant_b, traffic_b) \
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(name)
+        .bind(template)
+        .bind(serde_json::to_string(variant_a).unwrap_or_default())
+        .bind(serde_json::to_string(variant_b).unwrap_or_default())
+        .bind(i64::from(traffic_b))
+        .execute(&self.pool)
+        .await?;
+
+        self.get(&id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("created experiment {id} not found").into())
+    }
+
+    pub async fn get(&self, id: &str) -> crate::error::Result<Option<Experiment>> {
+        let row = sqlx::query(
+            "SELECT id, name, template, variant_a, variant_b, traffic_b, status, created_at, stopped_at \
+             FROM experiments WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.as_ref().map(experiment_from_row))
+    }
+
+    /// The running experiment, if any.
+    pub async fn running(&self) -> crate::error::Result<Option<Experiment>> {
+        let row = sqlx::query(
+            "SELECT id, name, template, variant_a, variant_b, traffic_b, status, created_at, stopped_at \
+             FROM experiments WHERE status = 'running' \
+             ORDER BY created_at DESC LIMIT 1",
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.as_ref().map(experiment_from_row))
+    }
+
+    /// Experiments, newest first.
+    pub async fn list(&self, limit: i64) -> crate::error::Result<Vec<Experiment>> {
+        let rows = sqlx::query(
+            "SELECT id, name, template, variant_a, variant_b, traffic_b, status, created_at, stopped_at \
+             FROM experiments ORDER BY created_at DESC LIMIT ?",
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.iter().map(experiment_from_row).collect())
+    }
+
+    /// Stop a running experiment. Returns false if it wasn't running.
+    pub async fn stop(&self, id: &str) -> crate::error::Result<bool> {
+        let result = sqlx::query(
+            "UPDATE experiments SET status = 'stopped', stopped_at = CURRENT_TIMESTAMP \
+             WHERE id = ? AND status = 'running'",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The variant a channel is assigned, assigning `default` if it has none.
+    pub async fn assign(
+        &self,
+        experiment_id: &str,
+        channel_id: &str,
+        default: VariantKey,
+    ) -> crate::error::Result<VariantKey> {
+        sqlx::query(
+            "INSERT OR IGNORE INTO experiment_assignments (experiment_id, channel_id, variant) \
+             VALUES (?, ?, ?)",
+        )
+        .bind(experiment_id)
+        .bind(channel_id)
+        .bind(default.as_str())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(self
+            .assignment(experiment_id, channel_id)
+            .await?
+            .unwrap_or(default))
+    }
+
+    pub async fn assignment(
+        &self,
+        experiment_id: &str,
+        channel_id: &str,
+    ) -> crate::error::Result<Option<VariantKey>> {
+        let variant: Option<String> = sqlx::query_scalar(
+            "SELECT variant FROM experiment_assignments WHERE experiment_id = ? AND channel_id = ?",
+        )
+        .bind(experiment_id)
+        .bind(channel_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(variant.as_deref().and_then(VariantKey::parse))
+    }
+
+    /// Pin a channel to a variant, replacing any assignment it has.
+    pub async fn pin(
+        &self,
+        experiment_id: &str,
+        channel_id: &str,
+        variant: VariantKey,
+    ) -> crate::error::Result<()> {
+        sqlx::query(
+            "INSERT INTO experiment_assignments (experiment_id, channel_id, variant, pinned) \
+             VALUES (?, ?, ?, 1) \
+             ON CONFLICT(experiment_id, channel_id) DO UPDATE SET \
+                 variant = excluded.variant, pinned = 1, assigned_at = CURRENT_TIMESTAMP",
+        )
+        .bind(experiment_id)
+        .bind(channel_id)
+        .bind(variant.as_str())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn record_turn(
+        &self,
+        experiment_id: &str,
+        channel_id: &str,
+        variant: VariantKey,
+        tool_calls: i64,
+        tool_errors: i64,
+    ) -> crate::error::Result<()> {
+        sqlx::query(
+            "INSERT INTO experiment_outcomes \
+                 (experiment_id, channel_id, variant, kind, tool_calls, tool_errors) \
+             VALUES (?, ?, ?, 'turn', ?, ?)",
+        )
+        .bind(experiment_id)
+        .bind(channel_id)
+        .bind(variant.as_str())
+        .bind(tool_calls)
+        .bind(tool_errors)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record feedback on a channel's replies against its variant. Returns
+    /// `None` if the channel isn't in the experiment.
+    pub async fn record_feedback(
+        &self,
+        experiment_id: &str,
+        channel_id: &str,
+        positive: bool,
+    ) -> crate::error::Result<Option<VariantKey>> {
+        let Some(variant) = self.assignment(experiment_id, channel_id).await? else {
+            return Ok(None);
+        };
+        sqlx::query(
+            "INSERT INTO experiment_outcomes (experiment_id, channel_id, variant, kind, score) \
+             VALUES (?, ?, ?, 'feedback', ?)",
+        )
+        .bind(experiment_id)
+        .bind(channel_id)
+        .bind(variant.as_str())
+        .bind(if positive { 1 } else { -1 })
+        .execute(&self.pool)
+        .await?;
+        Ok(Some(variant))
+    }
+
+    /// Outcomes for both variants of an experiment.
+    pub async fn report(&self, experiment_id: &str) -> crate::error::Result<Vec<VariantReport>> {
+        let channel_rows = sqlx::query(
+            "SELECT variant, COUNT(*) AS channels FROM experiment_assignments \
+             WHERE experiment_id = ? GROUP BY variant",
+        )
+        .bind(experiment_id)
+        .fetch_all(&self.pool)
+        .await?;
+        let outcome_rows = sqlx::query(
+            "SELECT variant, \
+                 SUM(kind = 'turn') AS turns, \
+                 SUM(tool_calls) AS tool_calls, \
+                 SUM(tool_errors) AS tool_errors, \
+                 SUM(kind = 'feedback' AND score > 0) AS positive, \
+                 SUM(kind = 'feedback' AND score < 0) AS negative \
+             FROM experiment_outcomes WHERE experiment_id = ? GROUP BY variant",
+        )
+        .bind(experiment_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let ratio = |numerator: i64, denominator: i64| {
+            if denominator == 0 {
+                0.0
+            } else {
+                numerator as f64 / denominator as f64
+            }
+        };
+
+        Ok([VariantKey::A, VariantKey::B]
+            .into_iter()
+            .map(|variant| {
+                let channels = channel_rows
+                    .iter()
+                    .find(|row| row.get::<String, _>("variant") == variant.as_str())
+                    .map(|row| row.get::<i64, _>("channels"))
+                    .unwrap_or(0);
+                let outcomes = outcome_rows
+                    .iter()
+                    .find(|row| row.get::<String, _>("variant") == variant.as_str());
+                let sum = |column: &str| {
+                    outcomes
+                        .and_then(|row| row.get::<Option<i64>, _>(column))
+                        .unwrap_or(0)
+                };
+                let (turns, tool_calls, tool_errors) =
+                    (sum("turns"), sum("tool_calls"), sum("tool_errors"));
+                VariantReport {
+                    variant,
+                    channels,
+                    turns,
+                    turns_per_channel: ratio(turns, channels),
+                    tool_calls,
+                    tool_errors,
+                    tool_error_rate: ratio(tool_errors, tool_calls),
+                    positive_feedback: sum("positive"),
+                    negative_feedback: sum("negative"),
+                }
+            })
+            .collect())
+    }
+}
+
+fn experiment_from_row(row: &sqlx::sqlite::SqliteRow) -> Experiment {
+    let variant =
+        |column: &str| serde_json::from_str(&row.get::<String, _>(column)).unwrap_or_default();
+    Experiment {
+        id: row.get("id"),
+        name: row.get("name"),
+        template: row.get("template"),
+        variant_a: variant("variant_a"),
+        variant_b: variant("variant_b"),
+        traffic_b: row.get::<i64, _>("traffic_b").clamp(0, 100) as u8,
+        status: if row.get::<String, _>("status") == "running" {
+            ExperimentStatus::Running
+        } else {
+            ExperimentStatus::Stopped
+        },
+        created_at: row.get("created_at"),
+        stopped_at: row.get("stopped_at"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn setup_store() -> ExperimentStore {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to create sqlite memory pool");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("failed to run migrations");
+        ExperimentStore::new(pool)
+    }
+
+    #[tokio::test]
+    async fn assignments_stick_and_outcomes_report_per_variant() {
+        let store = setup_store().await;
+        let variant_b = Variant {
+            prompt: None,
+            model: Some("anthropic/claude-sonnet-4".into()),
+        };
+        let experiment = store
+            .create(
+                "sonnet vs default",
+                None,
+                &Variant::default(),
+                &variant_b,
+                100,
+            )
+            .await
+            .unwrap();
+        assert_eq!(experiment.hashed_variant("discord:1"), VariantKey::B);
+        assert_eq!(
+            store.running().await.unwrap().map(|running| running.id),
+            Some(experiment.id.clone())
+        );
+
+        // A pin wins over the hash, and later assignments don't move it.
+        store
+            .pin(&experiment.id, "discord:1", VariantKey::A)
+            .await
+            .unwrap();
+        let assigned = store
+            .assign(&experiment.id, "discord:1", VariantKey::B)
+            .await
+            .unwrap();
+        assert_eq!(assigned, VariantKey::A);
+        store
+            .assign(&experiment.id, "discord:2", VariantKey::B)
+            .await
+            .unwrap();
+
+        store
+            .record_turn(&experiment.id, "discord:1", VariantKey::A, 4, 1)
+            .await
+            .unwrap();
+        store
+            .record_turn(&experiment.id, "discord:2", VariantKey::B, 2, 0)
+            .await
+            .unwrap();
+        store
+            .record_feedback(&experiment.id, "discord:2", true)
+            .await
+            .unwrap();
+        assert!(
+            store
+                .record_feedback(&experiment.id, "discord:3", false)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let report = store.report(&experiment.id).await.unwrap();
+        assert_eq!(report[0].variant, VariantKey::A);
+        assert_eq!(report[0].turns, 1);
+        assert_eq!(report[0].tool_error_rate, 0.25);
+        assert_eq!(report[1].channels, 1);
+        assert_eq!(report[1].positive_feedback, 1);
+
+        assert!(store.stop(&experiment.id).await.unwrap());
+        assert!(store.running().await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,471 @@
+//! Automatic fact extraction from channel transcripts.
+//!
+//! Memory persistence branches and the compactor only keep what the model
+//! decides to save. This pass doesn't depend on that: every
+//! `fact_extraction_interval_secs` it reads each channel's transcript past the
+//! point it last reached, asks the compactor model for durable facts,
+//! decisions, and commitments, and saves them as memories whose `source`
+//! names the messages they came from. Channels marked ephemeral are skipped,
+//! and what was said in them is never revisited.
+//!
+//! Progress is a per-channel rowid watermark in `fact_extraction_state`. The
+//! first pass starts every existing channel at its latest message, so turning
+//! extraction on doesn't replay years of history through the model.
+
+use crate::AgentDeps;
+use crate::ProcessEvent;
+use crate::ProcessType;
+use crate::agent::cortex::CortexLogger;
+use crate::agent::structured_output;
+use crate::config::CortexConfig;
+use crate::conversation::ChannelStore;
+use crate::conversation::history::{ConversationLogger, ConversationMessage};
+use crate::llm::SpacebotModel;
+use crate::llm::budget::SpendScope;
+use crate::memory::{Memory, MemoryScope, MemoryType};
+use crate::tools::memory_save::MAX_MEMORY_CONTENT_BYTES;
+
+use rig::agent::AgentBuilder;
+use rig::completion::{CompletionModel as _, Prompt as _};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row as _, SqlitePool};
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How often to re-check whether disabled extraction was re-enabled.
+const DISABLED_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Delay before the first pass, so startup isn't slowed down.
+const INITIAL_DELAY: Duration = Duration::from_secs(180);
+
+/// Prefix of `Memory::source` for extracted memories. It is followed by the
+/// comma-separated IDs of the messages the memory came from.
+pub const SOURCE_PREFIX: &str = "conversation:";
+
+/// Messages longer than this are cut in the transcript sent to the model.
+const MAX_MESSAGE_CHARS: usize = 2000;
+
+/// Upper bound on memories saved from one batch of messages. A model that
+/// returns more is treating chatter as facts.
+const MAX_FACTS_PER_BATCH: usize = 20;
+
+/// Result of one extraction pass.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExtractionReport {
+    pub channels_scanned: usize,
+    pub channels_skipped: usize,
+    pub messages_read: usize,
+    pub memories_saved: usize,
+    pub batches_failed: usize,
+}
+
+/// Per-channel extraction watermarks (SQLite).
+#[derive(Debug, Clone)]
+pub struct FactExtractionStore {
+    pool: SqlitePool,
+}
+
+impl FactExtractionStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// The rowid each channel has been read through.
+    pub async fn watermarks(&self) -> crate::error::Result<HashMap<String, i64>> {
+        let rows =
+            sqlx::query("SELECT channel_id, extracted_through_rowid FROM fact_extraction_state")
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("channel_id"), row.get("extracted_through_rowid")))
+            .collect())
+    }
+
+    /// Record that `channel_id` has been read through `rowid`.
+    pub async fn advance(&self, channel_id: &str, rowid: i64) -> crate::error::Result<()> {
+        sqlx::query(
+            "INSERT INTO fact_extraction_state (channel_id, extracted_through_rowid, updated_at) \
+             VALUES (?, ?, CURRENT_TIMESTAMP) \
+             ON CONFLICT(channel_id) DO UPDATE SET \
+                 extracted_through_rowid = MAX(extracted_through_rowid, excluded.extracted_through_rowid), \
+                 updated_at = CURRENT_TIMESTAMP",
+        )
+        .bind(channel_id)
+        .bind(rowid)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+/// One item as returned by the extraction model.
+#[derive(Debug, Clone, Deserialize)]
+struct ExtractedFact {
+    content: String,
+    #[serde(rename = "type")]
+    memory_type: String,
+    #[serde(default)]
+    importance: Option<f32>,
+    /// Transcript line numbers, starting at 1.
+    #[serde(default)]
+    messages: Vec<usize>,
+}
+
+/// Spawn the fact extraction loop for an agent.
+pub fn spawn_fact_extraction_loop(
+    deps: AgentDeps,
+    logger: CortexLogger,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        tracing::info!("fact extraction loop started");
+        let job = deps.runtime_config.jobs.register(
+            "fact_extraction",
+            "Extract memories from recent channel transcripts",
+        );
+        job.wait(INITIAL_DELAY).await;
+
+        loop {
+            let config = **deps.runtime_config.cortex.load();
+            if !config.fact_extraction_enabled {
+                job.wait(DISABLED_POLL_INTERVAL).await;
+                continue;
+            }
+
+            job.started();
+            match extract_once(&deps, config).await {
+                Ok(report) => {
+                    let summary = format!(
+                        "saved {} memories from {} messages",
+                        report.memories_saved, report.messages_read
+                    );
+                    if report.memories_saved > 0 || report.batches_failed > 0 {
+                        logger.log(
+                            "fact_extraction",
+                            &format!("Fact extraction {summary}"),
+                            serde_json::to_value(&report).ok(),
+                        );
+                    }
+                    job.finished(report.batches_failed == 0, summary);
+                }
+                Err(error) => {
+                    tracing::warn!(%error, "fact extraction pass failed");
+                    job.finished(false, error.to_string());
+                }
+            }
+
+            job.wait(Duration::from_secs(config.fact_extraction_interval_secs))
+                .await;
+        }
+    })
+}
+
+/// Run one extraction pass over every channel with new messages.
+pub async fn extract_once(
+    deps: &AgentDeps,
+    config: CortexConfig,
+) -> crate::error::Result<ExtractionReport> {
+    let state = FactExtractionStore::new(deps.sqlite_pool.clone());
+    let conversation = ConversationLogger::new(deps.sqlite_pool.clone());
+    let channel_store = ChannelStore::new(deps.sqlite_pool.clone());
+
+    let heads = conversation.channel_message_heads().await?;
+    let mut watermarks = state.watermarks().await?;
+    if watermarks.is_empty() {
+        // First pass: start from now rather than the beginning of history.
+        for (channel_id, head) in &heads {
+            state.advance(channel_id, *head).await?;
+        }
+        return Ok(ExtractionReport::default());
+    }
+
+    let ephemeral = channel_store.ephemeral_channels().await?;
+    let prompt_engine = deps.runtime_config.prompts.load();
+    let preamble = prompt_engine.render_static("fact_extraction")?;
+    let mut report = ExtractionReport::default();
+
+    for (channel_id, head) in heads {
+        let through = watermarks.remove(&channel_id).unwrap_or(0);
+        if head <= through {
+            continue;
+        }
+        if ephemeral.contains(&channel_id) {
+            // Skip past what was said, so clearing the flag later doesn't
+            // extract it after all.
+            state.advance(&channel_id, head).await?;
+            report.channels_skipped += 1;
+            continue;
+        }
+
+        let messages = conversation
+            .load_messages_after(
+                &channel_id,
+                through,
+                config.fact_extraction_max_messages as i64,
+            )
+            .await?;
+        let Some(last_rowid) = messages.last().map(|(rowid, _)| *rowid) else {
+            continue;
+        };
+        report.channels_scanned += 1;
+        report.messages_read += messages.len();
+
+        if messages.iter().any(|(_, message)| message.role == "user") {
+            let transcript = render_extraction_transcript(&messages);
+            // On failure the watermark stays put and the next pass retries.
+            let facts = match request_facts(deps, &preamble, &channel_id, &transcript).await {
+                Ok(facts) => facts,
+                Err(error) => {
+                    tracing::warn!(channel_id = %channel_id, %error, "fact extraction failed");
+                    report.batches_failed += 1;
+                    continue;
+                }
+            };
+
+            let scope = resolve_memory_scope(deps, &channel_store, &channel_id).await;
+            for fact in facts.iter().take(MAX_FACTS_PER_BATCH) {
+                let Some(memory) = fact_to_memory(fact, &messages, &channel_id, scope) else {
+                    continue;
+                };
+                match save_memory(deps, &memory).await {
+                    Ok(()) => report.memories_saved += 1,
+                    Err(error) => {
+                        tracing::warn!(channel_id = %channel_id, %error, "failed to save extracted memory");
+                    }
+                }
+            }
+        }
+
+        state.advance(&channel_id, last_rowid).await?;
+    }
+
+    Ok(report)
+}
+
+/// Ask the extraction model for the durable items in a transcript.
+async fn request_facts(
+    deps: &AgentDeps,
+    preamble: &str,
+    channel_id: &str,
+    transcript: &str,
+) -> anyhow::Result<Vec<ExtractedFact>> {
+    let routing = deps.runtime_config.routing.load();
+    let model_name = routing
+        .resolve(ProcessType::Compactor, Some(channel_id))
+        .to_string();
+    let model = SpacebotModel::make(&deps.llm_manager, &model_name)
+        .with_context(&*deps.agent_id, "fact_extraction")
+        .with_channel(channel_id)
+        .with_routing((**routing).clone())
+        .with_budget(deps.budget_guard(SpendScope::channel(channel_id)));
+    let agent = AgentBuilder::new(model).preamble(preamble).build();
+
+    let answer = agent.prompt(transcript).await?;
+    parse_extracted_facts(&answer)
+        .map_err(|errors| anyhow::anyhow!("unusable extraction answer: {}", errors.join("; ")))
+}
+
+/// Parse the model's answer into extracted items.
+fn parse_extracted_facts(answer: &str) -> Result<Vec<ExtractedFact>, Vec<String>> {
+    let schema = serde_json::json!({
+        "type": "object",
+        "required": ["facts"],
+        "properties": {
+            "facts": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["content", "type"],
+                    "properties": {
+                        "content": { "type": "string" },
+                        "type": { "type": "string" },
+                        "importance": { "type": ["number", "null"] },
+                        "messages": { "type": "array", "items": { "type": "integer" } }
+                    }
+                }
+            }
+        }
+    });
+    let value = structured_output::coerce(answer, &schema)?;
+    serde_json::from_value(value["facts"].clone()).map_err(|error| vec![error.to_string()])
+}
+
+/// Render messages as a numbered transcript. The numbers are what the model
+/// cites in `messages`.
+fn render_extraction_transcript(messages: &[(i64, ConversationMessage)]) -> String {
+    let mut output = String::new();
+    for (index, (_, message)) in messages.iter().enumerate() {
+        let sender = match (&message.sender_name, message.role.as_str()) {
+            (Some(name), _) => name.as_str(),
+            (None, "assistant") => "Assistant",
+            (None, _) => "User",
+        };
+        let content: String = message.content.chars().take(MAX_MESSAGE_CHARS).collect();
+        output.push_str(&format!(
+            "[{}] {} {}: {}\n",
+            index + 1,
+            message.created_at.format("%Y-%m-%d %H:%M"),
+            sender,
+            content
+        ));
+    }
+    output
+}
+
+/// Turn an extracted item into a memory, or `None` if it isn't usable.
+fn fact_to_memory(
+    fact: &ExtractedFact,
+    messages: &[(i64, ConversationMessage)],
+    channel_id: &str,
+    scope: MemoryScope,
+) -> Option<Memory> {
+    let content = fact.content.trim();
+    if content.is_empty() || content.len() > MAX_MEMORY_CONTENT_BYTES {
+        return None;
+    }
+    let memory_type = match fact.memory_type.trim().to_ascii_lowercase().as_str() {
+        "fact" => MemoryType::Fact,
+        "preference" => MemoryType::Preference,
+        "decision" => MemoryType::Decision,
+        "todo" | "commitment" => MemoryType::Todo,
+        "event" => MemoryType::Event,
+        _ => MemoryType::Fact,
+    };
+
+    let message_ids: Vec<&str> = fact
+        .messages
+        .iter()
+        .filter_map(|number| number.checked_sub(1).and_then(|index| messages.get(index)))
+        .map(|(_, message)| message.id.as_str())
+        .collect();
+    let mut memory = Memory::new(content, memory_type)
+        .with_source(format!("{SOURCE_PREFIX}{}", message_ids.join(",")));
+    if let Some(importance) = fact.importance {
+        memory = memory.with_importance(importance);
+    }
+    let channel_id: crate::ChannelId = channel_id.into();
+    Some(match scope {
+        MemoryScope::Channel => memory.scoped_to_channel(channel_id),
+        MemoryScope::Global => memory.with_channel_id(channel_id),
+    })
+}
+
+/// Save and index a memory, and tell the cortex about it.
+async fn save_memory(deps: &AgentDeps, memory: &Memory) -> crate::error::Result<()> {
+    let store = deps.memory_search.store();
+    store.save(memory).await?;
+    if let Err(error) = deps
+        .memory_search
+        .index_memory(&memory.id, &memory.content)
+        .await
+    {
+        // Don't leave a row that search can never find.
+        if let Err(error) = store.delete(&memory.id).await {
+            tracing::error!(%error, memory_id = %memory.id, "compensating delete failed");
+        }
+        return Err(error);
+    }
+
+    if deps.memory_event_tx.receiver_count() > 0 {
+        let _ = deps.memory_event_tx.send(ProcessEvent::MemorySaved {
+            agent_id: deps.agent_id.clone(),
+            memory_id: memory.id.clone(),
+            channel_id: memory.channel_id.clone(),
+            memory_type: memory.memory_type,
+            importance: memory.importance,
+            content_summary: crate::summarize_first_non_empty_line(
+                &memory.content,
+                crate::EVENT_SUMMARY_MAX_CHARS,
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// The channel's memory scope override, or the agent default.
+async fn resolve_memory_scope(
+    deps: &AgentDeps,
+    channel_store: &ChannelStore,
+    channel_id: &str,
+) -> MemoryScope {
+    match channel_store.memory_scope(channel_id).await {
+        Ok(Some(scope)) => scope,
+        Ok(None) => deps.runtime_config.channel_config.load().memory_scope,
+        Err(error) => {
+            // Fail closed, as branches do.
+            tracing::warn!(%error, channel_id, "failed to load channel memory scope");
+            MemoryScope::Channel
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: &str, role: &str, content: &str) -> (i64, ConversationMessage) {
+        (
+            0,
+            ConversationMessage {
+                id: id.into(),
+                channel_id: "discord:1".into(),
+                role: role.into(),
+                sender_name: None,
+                sender_id: None,
+                content: content.into(),
+                metadata: None,
+                created_at: chrono::Utc::now(),
+            },
+        )
+    }
+
+    #[test]
+    fn extracted_facts_link_their_source_messages() {
+        let answer = r#"Here you go:
+```json
+{"facts": [
+  {"content": "Dana will send the contract by Friday.", "type": "commitment", "importance": "0.8", "messages": [2, 9]},
+  {"content": "  ", "type": "fact"}
+]}
+```"#;
+        let facts = parse_extracted_facts(answer).unwrap();
+        assert_eq!(facts.len(), 2);
+
+        let messages = [
+            message("m1", "user", "Can you send the contract?"),
+            message("m2", "user", "I'll send it by Friday."),
+        ];
+        let memory = fact_to_memory(&facts[0], &messages, "discord:1", MemoryScope::Channel)
+            .expect("fact should convert");
+        assert_eq!(memory.memory_type, MemoryType::Todo);
+        assert_eq!(memory.importance, 0.8);
+        assert_eq!(memory.source.as_deref(), Some("conversation:m2"));
+        assert_eq!(memory.scope, MemoryScope::Channel);
+        assert!(fact_to_memory(&facts[1], &messages, "discord:1", MemoryScope::Global).is_none());
+
+        assert!(parse_extracted_facts("nothing to report").is_err());
+    }
+
+    #[tokio::test]
+    async fn watermarks_only_move_forward() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to create sqlite memory pool");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("failed to run migrations");
+        let store = FactExtractionStore::new(pool);
+
+        store.advance("discord:1", 40).await.unwrap();
+        store.advance("discord:1", 25).await.unwrap();
+        store.advance("discord:2", 7).await.unwrap();
+
+        let watermarks = store.watermarks().await.unwrap();
+        assert_eq!(watermarks.get("discord:1"), Some(&40));
+        assert_eq!(watermarks.get("discord:2"), Some(&7));
+    }
+}
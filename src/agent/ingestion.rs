@@ -11,9 +11,10 @@
 use crate::AgentDeps;
 use crate::ProcessId;
 use crate::ProcessType;
-use crate::config::IngestionConfig;
+use crate::config::{ChunkingStrategy, IngestionConfig};
 use crate::hooks::SpacebotHook;
 use crate::llm::SpacebotModel;
+use crate::llm::budget::SpendScope;
 use crate::tools::MemoryPersistenceContractState;
 
 use anyhow::Context as _;
@@ -45,19 +46,29 @@ pub fn spawn_ingestion_loop(ingest_dir: PathBuf, deps: AgentDeps) -> tokio::task
 async fn run_ingestion_loop(ingest_dir: &Path, deps: &AgentDeps) -> anyhow::Result<()> {
     tracing::info!(path = %ingest_dir.display(), "ingestion loop started");
 
+    let job = deps.runtime_config.jobs.register(
+        "ingestion",
+        "Import files from the ingest directory into memory",
+    );
+
     loop {
         let config = **deps.runtime_config.ingestion.load();
 
         if !config.enabled {
-            tokio::time::sleep(Duration::from_secs(config.poll_interval_secs)).await;
+            job.wait(Duration::from_secs(config.poll_interval_secs))
+                .await;
             continue;
         }
 
         // Scan for files
+        job.started();
         match scan_ingest_dir(ingest_dir).await {
             Ok(files) if !files.is_empty() => {
+                let total = files.len();
+                let mut failed = 0;
                 for file_path in files {
                     if let Err(error) = process_file(&file_path, deps, &config).await {
+                        failed += 1;
                         tracing::error!(
                             path = %file_path.display(),
                             %error,
@@ -65,15 +76,21 @@ async fn run_ingestion_loop(ingest_dir: &Path, deps: &AgentDeps) -> anyhow::Resu
                         );
                     }
                 }
+                job.finished(
+                    failed == 0,
+                    format!("ingested {} of {total} files", total - failed),
+                );
             }
             Err(error) => {
                 // Directory might not exist yet — that's fine
                 tracing::debug!(%error, "failed to scan ingest directory");
+                job.finished(true, "ingest directory not found");
             }
-            _ => {}
+            _ => job.finished(true, "no files to ingest"),
         }
 
-        tokio::time::sleep(Duration::from_secs(config.poll_interval_secs)).await;
+        job.wait(Duration::from_secs(config.poll_interval_secs))
+            .await;
     }
 }
 
@@ -148,6 +165,34 @@ fn is_supported_ingest_file(path: &Path) -> bool {
             | "html"
             | "htm"
             | "pdf"
+            | "docx"
+    ) || is_code_file(path)
+}
+
+/// Source files that get declaration-aware chunking under `auto`.
+fn is_code_file(path: &Path) -> bool {
+    let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+        return false;
+    };
+    matches!(
+        ext.to_lowercase().as_str(),
+        "rs" | "ts"
+            | "tsx"
+            | "js"
+            | "jsx"
+            | "mjs"
+            | "py"
+            | "go"
+            | "java"
+            | "kt"
+            | "swift"
+            | "rb"
+            | "c"
+            | "h"
+            | "cpp"
+            | "hpp"
+            | "cs"
+            | "sh"
     )
 }
 
@@ -186,7 +231,17 @@ async fn process_file(
 
     let hash = content_hash(&content);
     let file_size = content.len() as i64;
-    let chunks = chunk_text(&content, config.chunk_size);
+    let chunks: Vec<(String, Option<PageRange>)> = if content.contains(PAGE_BREAK) {
+        chunk_pages(&content, config.chunk_size.max(1))
+            .into_iter()
+            .map(|(chunk, pages)| (chunk, Some(pages)))
+            .collect()
+    } else {
+        chunk_content(&content, path, config)
+            .into_iter()
+            .map(|chunk| (chunk, None))
+            .collect()
+    };
     let total_chunks = chunks.len();
 
     let completed = load_completed_chunks(&deps.sqlite_pool, &hash).await?;
@@ -221,7 +276,7 @@ async fn process_file(
 
     let mut had_failure = false;
 
-    for (index, chunk) in chunks.iter().enumerate() {
+    for (index, (chunk, pages)) in chunks.iter().enumerate() {
         let chunk_number = index + 1;
 
         if completed.contains(&(index as i64)) {
@@ -240,7 +295,7 @@ async fn process_file(
             "processing chunk"
         );
 
-        match process_chunk(chunk, filename, chunk_number, total_chunks, deps).await {
+        match process_chunk(chunk, *pages, filename, chunk_number, total_chunks, deps).await {
             Ok(()) => {
                 record_chunk_completed(
                     &deps.sqlite_pool,
@@ -302,8 +357,9 @@ async fn process_file(
 
 /// Read an ingest file and return extracted text content.
 ///
-/// Plaintext-like files are read directly as UTF-8. PDFs are read as bytes and
-/// converted to text through the PDF extractor.
+/// Plaintext-like files are read directly as UTF-8. PDFs and DOCX files are
+/// read as bytes and converted to text, with pages separated by
+/// [`PAGE_BREAK`] so they can be chunked per page.
 async fn read_ingest_content(path: &Path) -> anyhow::Result<String> {
     let extension = path.extension().and_then(|extension| extension.to_str());
 
@@ -312,11 +368,25 @@ async fn read_ingest_content(path: &Path) -> anyhow::Result<String> {
             .await
             .with_context(|| format!("failed to read pdf file: {}", path.display()))?;
 
-        let extracted =
-            tokio::task::spawn_blocking(move || pdf_extract::extract_text_from_mem(&bytes))
-                .await
-                .context("pdf extraction task failed")?
-                .with_context(|| format!("failed to extract text from pdf: {}", path.display()))?;
+        let pages = tokio::task::spawn_blocking(move || {
+            pdf_extract::extract_text_from_mem_by_pages(&bytes)
+        })
+        .await
+        .context("pdf extraction task failed")?
+        .with_context(|| format!("failed to extract text from pdf: {}", path.display()))?;
+
+        return Ok(pages.join(&PAGE_BREAK.to_string()));
+    }
+
+    if extension.is_some_and(|ext| ext.eq_ignore_ascii_case("docx")) {
+        let bytes = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("failed to read docx file: {}", path.display()))?;
+
+        let extracted = tokio::task::spawn_blocking(move || extract_docx_text(&bytes))
+            .await
+            .context("docx extraction task failed")?
+            .with_context(|| format!("failed to extract text from docx: {}", path.display()))?;
 
         return Ok(extracted);
     }
@@ -326,6 +396,168 @@ async fn read_ingest_content(path: &Path) -> anyhow::Result<String> {
         .with_context(|| format!("failed to read file: {}", path.display()))
 }
 
+/// Separates pages in text extracted from paginated documents.
+const PAGE_BREAK: char = '\u{0C}';
+
+/// Extract paragraph text from a DOCX file's main document part.
+///
+/// Paragraphs become lines and tabs are kept. Page breaks, both explicit ones
+/// and the ones Word recorded when it last laid the document out, become
+/// [`PAGE_BREAK`]; a break with no text since the previous one is dropped so
+/// the two kinds don't double count.
+fn extract_docx_text(bytes: &[u8]) -> anyhow::Result<String> {
+    use std::io::Read as _;
+
+    let mut archive =
+        zip::ZipArchive::new(std::io::Cursor::new(bytes)).context("failed to read docx archive")?;
+    let mut xml = String::new();
+    archive
+        .by_name("word/document.xml")
+        .context("docx archive has no word/document.xml")?
+        .read_to_string(&mut xml)
+        .context("failed to read word/document.xml")?;
+
+    let mut text = String::new();
+    let mut in_text_run = false;
+    let mut rest = xml.as_str();
+
+    while let Some(open) = rest.find('<') {
+        if in_text_run {
+            text.push_str(&unescape_xml(&rest[..open]));
+        }
+        let Some(close) = rest[open..].find('>') else {
+            break;
+        };
+        let tag = &rest[open + 1..open + close];
+        rest = &rest[open + close + 1..];
+
+        let name = tag
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+        match name {
+            "w:t" => in_text_run = !tag.starts_with('/') && !tag.ends_with('/'),
+            // Tab stop definitions in paragraph properties carry `w:val`.
+            "w:tab" if !tag.contains("w:val=") => text.push('\t'),
+            "w:p" if tag.starts_with('/') => text.push('\n'),
+            "w:br" if tag.contains(r#"w:type="page""#) => push_page_break(&mut text),
+            "w:lastRenderedPageBreak" => push_page_break(&mut text),
+            "w:br" | "w:cr" => text.push('\n'),
+            _ => {}
+        }
+    }
+
+    Ok(text.trim_end_matches(['\n', PAGE_BREAK]).to_string())
+}
+
+fn push_page_break(text: &mut String) {
+    text.truncate(text.trim_end_matches('\n').len());
+    if !text.is_empty() && !text.ends_with(PAGE_BREAK) {
+        text.push(PAGE_BREAK);
+    }
+}
+
+fn unescape_xml(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        output.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(character) => {
+                output.push(character);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// First and last page (1-based) covered by a chunk.
+type PageRange = (usize, usize);
+
+/// Chunk paginated text along page boundaries. Consecutive short pages are
+/// packed together up to `chunk_size`; a page longer than that is split at
+/// line boundaries and each piece keeps its page number.
+fn chunk_pages(text: &str, chunk_size: usize) -> Vec<(String, PageRange)> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_pages: Option<PageRange> = None;
+
+    for (index, page) in text.split(PAGE_BREAK).enumerate() {
+        let page_number = index + 1;
+        let page = page.trim();
+        if page.is_empty() {
+            continue;
+        }
+
+        if let Some(pages) = current_pages
+            && current.len() + page.len() + 2 > chunk_size
+        {
+            chunks.push((std::mem::take(&mut current), pages));
+            current_pages = None;
+        }
+
+        if page.len() > chunk_size {
+            chunks.extend(
+                chunk_text(page, chunk_size)
+                    .into_iter()
+                    .map(|piece| (piece, (page_number, page_number))),
+            );
+            continue;
+        }
+
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(page);
+        current_pages = Some(match current_pages {
+            Some((first, _)) => (first, page_number),
+            None => (page_number, page_number),
+        });
+    }
+
+    if let Some(pages) = current_pages {
+        chunks.push((current, pages));
+    }
+
+    chunks
+}
+
+fn format_page_range((first, last): PageRange) -> String {
+    if first == last {
+        format!("page {first}")
+    } else {
+        format!("pages {first}-{last}")
+    }
+}
+
 // -- Progress tracking queries --------------------------------------------------
 
 /// Load the set of chunk indices already completed for a given content hash.
@@ -428,6 +660,228 @@ async fn complete_ingestion_file(
     Ok(())
 }
 
+/// Split file content into chunks using the configured strategy.
+fn chunk_content(text: &str, path: &Path, config: &IngestionConfig) -> Vec<String> {
+    let strategy = match config.chunking {
+        ChunkingStrategy::Auto => {
+            let is_markdown = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|ext| matches!(ext.to_lowercase().as_str(), "md" | "markdown"));
+            if is_markdown {
+                ChunkingStrategy::Headings
+            } else if is_code_file(path) {
+                ChunkingStrategy::Code
+            } else {
+                ChunkingStrategy::Lines
+            }
+        }
+        strategy => strategy,
+    };
+
+    let chunk_size = config.chunk_size.max(1);
+    match strategy {
+        ChunkingStrategy::Auto | ChunkingStrategy::Lines => chunk_text(text, chunk_size),
+        ChunkingStrategy::Headings => pack_segments(text, &heading_boundaries(text), chunk_size),
+        ChunkingStrategy::Window => chunk_windows(text, chunk_size, config.chunk_overlap),
+        ChunkingStrategy::Sentences => pack_segments(text, &sentence_boundaries(text), chunk_size),
+        ChunkingStrategy::Code => pack_segments(text, &code_boundaries(text), chunk_size),
+    }
+}
+
+/// Split `text` at the given byte offsets and greedily pack the pieces into
+/// chunks of up to `chunk_size`. A piece larger than `chunk_size` is split
+/// further at line boundaries.
+fn pack_segments(text: &str, boundaries: &[usize], chunk_size: usize) -> Vec<String> {
+    if text.len() <= chunk_size {
+        return vec![text.to_string()];
+    }
+
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut start = 0;
+
+    for &end in boundaries.iter().chain(std::iter::once(&text.len())) {
+        if end <= start {
+            continue;
+        }
+        let segment = &text[start..end];
+        start = end;
+
+        if !current.is_empty() && current.len() + segment.len() > chunk_size {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if segment.len() > chunk_size {
+            chunks.extend(chunk_text(segment, chunk_size));
+        } else {
+            current.push_str(segment);
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+        .into_iter()
+        .map(|chunk| {
+            chunk
+                .trim_start_matches(['\n', '\r'])
+                .trim_end()
+                .to_string()
+        })
+        .filter(|chunk| !chunk.is_empty())
+        .collect()
+}
+
+/// Byte offsets of lines that start a markdown heading, ignoring `#` lines
+/// inside fenced code blocks.
+fn heading_boundaries(text: &str) -> Vec<usize> {
+    let mut boundaries = Vec::new();
+    let mut in_fence = false;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        } else if !in_fence && is_markdown_heading(line) {
+            boundaries.push(offset);
+        }
+        offset += line.len();
+    }
+
+    boundaries
+}
+
+fn is_markdown_heading(line: &str) -> bool {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    (1..=6).contains(&hashes) && line[hashes..].starts_with([' ', '\t'])
+}
+
+/// Byte offsets just after each sentence end (`.`, `!`, or `?` followed by
+/// whitespace) and after each blank line.
+fn sentence_boundaries(text: &str) -> Vec<usize> {
+    let mut boundaries = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((index, ch)) = chars.next() {
+        let Some(&(next_index, next)) = chars.peek() else {
+            break;
+        };
+        let ends_sentence = matches!(ch, '.' | '!' | '?') && next.is_whitespace();
+        let ends_paragraph = ch == '\n' && next == '\n';
+        if ends_sentence || ends_paragraph {
+            // Keep the trailing whitespace with the sentence it follows.
+            let mut end = next_index;
+            while let Some(&(whitespace_index, whitespace)) = chars.peek() {
+                if !whitespace.is_whitespace() {
+                    break;
+                }
+                end = whitespace_index + whitespace.len_utf8();
+                chars.next();
+            }
+            if end > index {
+                boundaries.push(end);
+            }
+        }
+    }
+
+    boundaries
+}
+
+/// Byte offsets of top-level declarations in source code. A declaration is a
+/// line at column 0 that starts with a declaration keyword. Doc comments and
+/// attributes directly above it stay with the declaration.
+///
+/// This is a line heuristic rather than a parser, so it works for any
+/// brace- or indentation-based language without per-language grammars.
+fn code_boundaries(text: &str) -> Vec<usize> {
+    const DECLARATION_PREFIXES: &[&str] = &[
+        "fn ",
+        "pub ",
+        "pub(",
+        "async fn ",
+        "impl ",
+        "impl<",
+        "struct ",
+        "enum ",
+        "trait ",
+        "mod ",
+        "type ",
+        "const ",
+        "static ",
+        "unsafe ",
+        "macro_rules!",
+        "function ",
+        "async function ",
+        "export ",
+        "class ",
+        "interface ",
+        "abstract ",
+        "def ",
+        "async def ",
+        "func ",
+        "public ",
+        "private ",
+        "protected ",
+        "internal ",
+    ];
+    const PREAMBLE_PREFIXES: &[&str] = &["///", "//!", "//", "#[", "#!", "@", "/**", "/*", " *"];
+
+    let mut boundaries = Vec::new();
+    let mut preamble_start: Option<usize> = None;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        if DECLARATION_PREFIXES
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+        {
+            let start = preamble_start.unwrap_or(offset);
+            if start > 0 {
+                boundaries.push(start);
+            }
+            preamble_start = None;
+        } else if PREAMBLE_PREFIXES
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+        {
+            preamble_start.get_or_insert(offset);
+        } else {
+            preamble_start = None;
+        }
+        offset += line.len();
+    }
+
+    boundaries
+}
+
+/// Split text into fixed windows of `chunk_size` characters, each starting
+/// `overlap` characters before the previous one ended.
+fn chunk_windows(text: &str, chunk_size: usize, overlap: usize) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    if chars.len() <= chunk_size {
+        return vec![text.to_string()];
+    }
+
+    // Always advance by at least half a window so overlap can't stall.
+    let step = chunk_size
+        .saturating_sub(overlap)
+        .max(chunk_size.div_ceil(2));
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    loop {
+        let end = (start + chunk_size).min(chars.len());
+        chunks.push(chars[start..end].iter().collect());
+        if end == chars.len() {
+            break;
+        }
+        start += step;
+    }
+
+    chunks
+}
+
 /// Split text into chunks at line boundaries.
 ///
 /// Chunks target `chunk_size` characters but won't split mid-line. If a single
@@ -468,6 +922,7 @@ fn chunk_text(text: &str, chunk_size: usize) -> Vec<String> {
 #[tracing::instrument(skip(chunk, deps), fields(agent_id = %deps.agent_id, filename, chunk_number, total_chunks))]
 async fn process_chunk(
     chunk: &str,
+    pages: Option<PageRange>,
     filename: &str,
     chunk_number: usize,
     total_chunks: usize,
@@ -481,7 +936,8 @@ async fn process_chunk(
     let model = SpacebotModel::make(&deps.llm_manager, &model_name)
         .with_context(&*deps.agent_id, "branch")
         .with_worker_type("ingestion")
-        .with_routing((**routing).clone());
+        .with_routing((**routing).clone())
+        .with_budget(deps.budget_guard(SpendScope::agent()));
 
     let conversation_logger =
         crate::conversation::history::ConversationLogger::new(deps.sqlite_pool.clone());
@@ -500,6 +956,7 @@ async fn process_chunk(
         crate::tools::BranchToolProfile::MemoryPersistence {
             contract_state: contract_state.clone(),
         },
+        crate::memory::MemoryScope::Global,
     );
 
     let agent = AgentBuilder::new(model)
@@ -516,8 +973,13 @@ async fn process_chunk(
         deps.event_tx.clone(),
     );
 
-    let user_prompt =
-        prompt_engine.render_system_ingestion_chunk(filename, chunk_number, total_chunks, chunk)?;
+    let user_prompt = prompt_engine.render_system_ingestion_chunk(
+        filename,
+        chunk_number,
+        total_chunks,
+        pages.map(format_page_range).as_deref(),
+        chunk,
+    )?;
 
     let mut history = Vec::new();
     let result = hook.prompt_once(&agent, &mut history, &user_prompt).await;
@@ -604,6 +1066,67 @@ mod tests {
         assert_eq!(chunks.len(), 1);
     }
 
+    #[test]
+    fn test_chunk_content_auto_splits_markdown_at_headings() {
+        let text = "# One\nalpha alpha alpha\n\n## Two\nbeta beta beta\n\n## Three\ngamma\n";
+        let config = IngestionConfig {
+            chunk_size: 30,
+            ..IngestionConfig::default()
+        };
+        let chunks = chunk_content(text, Path::new("notes.md"), &config);
+        assert_eq!(
+            chunks,
+            vec![
+                "# One\nalpha alpha alpha",
+                "## Two\nbeta beta beta",
+                "## Three\ngamma",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_heading_boundaries_ignore_fenced_comments() {
+        let text = "# Title\n```sh\n# not a heading\n```\n## Next\n";
+        assert_eq!(
+            heading_boundaries(text),
+            vec![0, text.find("## Next").unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_code_boundaries_keep_doc_comments_with_declarations() {
+        let text =
+            "use std::fmt;\n\n/// Adds.\n#[inline]\nfn add() {\n    // body\n}\n\nstruct Point;\n";
+        let boundaries = code_boundaries(text);
+        assert_eq!(
+            boundaries,
+            vec![
+                text.find("/// Adds.").unwrap(),
+                text.find("struct Point").unwrap()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sentence_chunks_do_not_split_mid_sentence() {
+        let text = "First sentence here. Second one follows! Third asks why? Fourth ends it.";
+        let chunks = pack_segments(text, &sentence_boundaries(text), 45);
+        assert_eq!(
+            chunks,
+            vec![
+                "First sentence here. Second one follows!",
+                "Third asks why? Fourth ends it.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chunk_windows_overlap() {
+        let text = "abcdefghij";
+        let chunks = chunk_windows(text, 4, 1);
+        assert_eq!(chunks, vec!["abcd", "defg", "ghij"]);
+    }
+
     #[test]
     fn test_is_supported_ingest_file() {
         assert!(is_supported_ingest_file(Path::new("notes.txt")));
@@ -685,4 +1208,59 @@ mod tests {
             "max turns must be treated as chunk failure for retry"
         );
     }
+
+    #[test]
+    fn test_chunk_pages_packs_pages_and_tracks_range() {
+        let text = "first page\u{0C}second page\u{0C}\u{0C}fourth page";
+        let chunks = chunk_pages(text, 30);
+        assert_eq!(
+            chunks,
+            vec![
+                ("first page\n\nsecond page".to_string(), (1, 2)),
+                ("fourth page".to_string(), (4, 4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chunk_pages_splits_long_page_keeping_page_number() {
+        let long_page = "line one\nline two\nline three";
+        let text = format!("intro\u{0C}{long_page}");
+        let chunks = chunk_pages(&text, 12);
+        assert_eq!(chunks[0], ("intro".to_string(), (1, 1)));
+        assert!(chunks.len() > 2);
+        assert!(chunks[1..].iter().all(|(_, pages)| *pages == (2, 2)));
+    }
+
+    #[test]
+    fn test_extract_docx_text_reads_paragraphs_and_page_breaks() {
+        use std::io::Write as _;
+
+        let document = r#"<?xml version="1.0"?><w:document><w:body>
+            <w:p><w:pPr><w:tabs><w:tab w:val="left" w:pos="720"/></w:tabs></w:pPr><w:r><w:t>Tom &amp; Jerry</w:t><w:tab/><w:t xml:space="preserve"> run</w:t></w:r></w:p>
+            <w:p><w:r><w:br w:type="page"/></w:r></w:p>
+            <w:p><w:r><w:lastRenderedPageBreak/><w:t>Page two</w:t></w:r></w:p>
+        </w:body></w:document>"#;
+        let mut bytes = Vec::new();
+        {
+            let mut writer = zip::ZipWriter::new(std::io::Cursor::new(&mut bytes));
+            writer
+                .start_file(
+                    "word/document.xml",
+                    zip::write::SimpleFileOptions::default(),
+                )
+                .unwrap();
+            writer.write_all(document.as_bytes()).unwrap();
+            writer.finish().unwrap();
+        }
+
+        let text = extract_docx_text(&bytes).unwrap();
+        assert_eq!(text, "Tom & Jerry\t run\u{0C}Page two");
+        assert_eq!(chunk_pages(&text, 100)[0].1, (1, 2));
+    }
+
+    #[test]
+    fn test_docx_is_supported() {
+        assert!(is_supported_ingest_file(Path::new("report.docx")));
+    }
 }
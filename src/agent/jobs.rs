@@ -0,0 +1,247 @@
+//! Registry of an agent's periodic background jobs.
+//!
+//! Interval loops (ingestion, storage monitoring, association, ready-task
+//! pickup, warmup) register a [`Job`] and wait on it between runs instead of
+//! sleeping directly. That gives the API one place to list every job with its
+//! schedule and last result, run one early, or pause it without a restart.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::Notify;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+/// All jobs registered for one agent, in registration order.
+#[derive(Debug, Default)]
+pub struct JobRegistry {
+    jobs: RwLock<Vec<Arc<Job>>>,
+}
+
+impl JobRegistry {
+    /// Register a job, or return the existing one when a loop restarts.
+    pub fn register(&self, name: &'static str, description: &'static str) -> Arc<Job> {
+        let mut jobs = self.jobs.write().expect("job registry lock poisoned");
+        if let Some(job) = jobs.iter().find(|job| job.name == name) {
+            return job.clone();
+        }
+        let job = Arc::new(Job::new(name, description));
+        jobs.push(job.clone());
+        job
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<Job>> {
+        self.jobs
+            .read()
+            .expect("job registry lock poisoned")
+            .iter()
+            .find(|job| job.name == name)
+            .cloned()
+    }
+
+    pub fn snapshots(&self) -> Vec<JobSnapshot> {
+        self.jobs
+            .read()
+            .expect("job registry lock poisoned")
+            .iter()
+            .map(|job| job.snapshot())
+            .collect()
+    }
+}
+
+/// A periodic job's controls and run history.
+#[derive(Debug)]
+pub struct Job {
+    name: &'static str,
+    description: &'static str,
+    enabled: AtomicBool,
+    triggered: AtomicBool,
+    wake: Notify,
+    state: Mutex<JobState>,
+}
+
+#[derive(Debug, Default)]
+struct JobState {
+    running: bool,
+    interval_secs: Option<u64>,
+    next_run_at: Option<DateTime<Utc>>,
+    last_started_at: Option<DateTime<Utc>>,
+    last_finished_at: Option<DateTime<Utc>>,
+    last_result: Option<JobResult>,
+    run_count: u64,
+}
+
+/// Outcome of a single run.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobResult {
+    pub success: bool,
+    pub summary: String,
+}
+
+/// Point-in-time view of a job for the API.
+#[derive(Debug, Clone, Serialize)]
+pub struct JobSnapshot {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub enabled: bool,
+    pub running: bool,
+    pub interval_secs: Option<u64>,
+    /// When the next scheduled run is due. `None` while running or paused.
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_started_at: Option<DateTime<Utc>>,
+    pub last_finished_at: Option<DateTime<Utc>>,
+    pub last_result: Option<JobResult>,
+    pub run_count: u64,
+}
+
+impl Job {
+    fn new(name: &'static str, description: &'static str) -> Self {
+        Self {
+            name,
+            description,
+            enabled: AtomicBool::new(true),
+            triggered: AtomicBool::new(false),
+            wake: Notify::new(),
+            state: Mutex::new(JobState::default()),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Acquire)
+    }
+
+    /// Pause or resume scheduled runs. Resuming a paused job runs it right
+    /// away. Manual triggers still run while paused.
+    pub fn set_enabled(&self, enabled: bool) {
+        let was_enabled = self.enabled.swap(enabled, Ordering::AcqRel);
+        if enabled && !was_enabled {
+            self.wake.notify_one();
+        }
+    }
+
+    /// Run the job as soon as its loop is waiting. A trigger while the job is
+    /// running queues one more run.
+    pub fn trigger(&self) {
+        self.triggered.store(true, Ordering::Release);
+        self.wake.notify_one();
+    }
+
+    /// Wait until the next run is due: after `interval`, on a manual trigger,
+    /// or when a paused job is resumed. Parks while the job is paused.
+    pub async fn wait(&self, interval: Duration) {
+        self.update(|state| {
+            state.interval_secs = Some(interval.as_secs());
+            state.next_run_at = chrono::Duration::from_std(interval)
+                .ok()
+                .map(|interval| Utc::now() + interval);
+        });
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = self.wake.notified() => {}
+        }
+
+        while !self.triggered.swap(false, Ordering::AcqRel) && !self.is_enabled() {
+            self.update(|state| state.next_run_at = None);
+            self.wake.notified().await;
+        }
+
+        self.update(|state| state.next_run_at = None);
+    }
+
+    /// Mark a run as started.
+    pub fn started(&self) {
+        self.update(|state| {
+            state.running = true;
+            state.last_started_at = Some(Utc::now());
+        });
+    }
+
+    /// Mark the current run as finished with a short summary.
+    pub fn finished(&self, success: bool, summary: impl Into<String>) {
+        let summary = summary.into();
+        self.update(|state| {
+            state.running = false;
+            state.last_finished_at = Some(Utc::now());
+            state.last_result = Some(JobResult { success, summary });
+            state.run_count += 1;
+        });
+    }
+
+    pub fn snapshot(&self) -> JobSnapshot {
+        let state = self.state.lock().expect("job state lock poisoned");
+        JobSnapshot {
+            name: self.name,
+            description: self.description,
+            enabled: self.is_enabled(),
+            running: state.running,
+            interval_secs: state.interval_secs,
+            next_run_at: state.next_run_at,
+            last_started_at: state.last_started_at,
+            last_finished_at: state.last_finished_at,
+            last_result: state.last_result.clone(),
+            run_count: state.run_count,
+        }
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut JobState)) {
+        apply(&mut self.state.lock().expect("job state lock poisoned"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn trigger_wakes_wait_early() {
+        let registry = JobRegistry::default();
+        let job = registry.register("test", "test job");
+        assert!(Arc::ptr_eq(&job, &registry.register("test", "test job")));
+
+        job.trigger();
+        tokio::time::timeout(Duration::from_secs(1), job.wait(Duration::from_secs(3600)))
+            .await
+            .expect("triggered wait should return immediately");
+    }
+
+    #[tokio::test]
+    async fn paused_job_waits_until_resumed() {
+        let job = Arc::new(Job::new("test", "test job"));
+        job.set_enabled(false);
+
+        let waiter = tokio::spawn({
+            let job = job.clone();
+            async move { job.wait(Duration::from_millis(1)).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished());
+        assert_eq!(job.snapshot().next_run_at, None);
+
+        job.set_enabled(true);
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("resumed wait should return")
+            .unwrap();
+    }
+
+    #[test]
+    fn finished_records_result() {
+        let job = Job::new("test", "test job");
+        job.started();
+        assert!(job.snapshot().running);
+
+        job.finished(false, "boom");
+        let snapshot = job.snapshot();
+        assert!(!snapshot.running);
+        assert_eq!(snapshot.run_count, 1);
+        let result = snapshot.last_result.unwrap();
+        assert!(!result.success);
+        assert_eq!(result.summary, "boom");
+    }
+}
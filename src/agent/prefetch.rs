@@ -0,0 +1,195 @@
+//! Speculative follow-up prefetch for channels.
+//!
+//! After the channel replies, it can spawn a silent branch that predicts the
+//! most likely follow-up and gathers the context needed to answer it. The
+//! branch conclusion is parsed into a [`PrefetchedContext`] and held on the
+//! channel until the next user message. It is injected into that turn only
+//! when the message matches one of the predicted topics; otherwise it is
+//! dropped.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+/// Words that never count towards a topic match on their own.
+const STOPWORDS: &[&str] = &[
+    "about", "and", "are", "can", "for", "from", "how", "into", "its", "the", "that", "this",
+    "what", "when", "where", "which", "who", "why", "with", "you", "your",
+];
+
+/// Context gathered by a prefetch branch, waiting for a matching follow-up.
+#[derive(Debug, Clone)]
+pub struct PrefetchedContext {
+    /// Lowercased keywords or short phrases the follow-up is expected to contain.
+    pub topics: Vec<String>,
+    /// The gathered facts, injected verbatim when the follow-up matches.
+    pub context: String,
+    pub gathered_at: Instant,
+}
+
+impl PrefetchedContext {
+    /// Parse a prefetch branch conclusion.
+    ///
+    /// Expects a `TOPICS:` line followed by a `CONTEXT:` section. Returns `None`
+    /// when the branch answered `NONE` or the conclusion doesn't follow the
+    /// format closely enough to be matched safely.
+    pub fn parse(conclusion: &str) -> Option<Self> {
+        let trimmed = conclusion.trim();
+        if trimmed.eq_ignore_ascii_case("none") {
+            return None;
+        }
+
+        let mut topics = Vec::new();
+        let mut context_lines: Option<Vec<&str>> = None;
+        for line in trimmed.lines() {
+            if let Some(lines) = context_lines.as_mut() {
+                lines.push(line);
+                continue;
+            }
+            let label = line.trim().trim_start_matches(['*', '#', ' ']);
+            if let Some(rest) = strip_label(label, "TOPICS:") {
+                topics = rest
+                    .split(',')
+                    .map(|topic| topic.trim().trim_matches('*').trim().to_lowercase())
+                    .filter(|topic| !topic.is_empty())
+                    .collect();
+            } else if let Some(rest) = strip_label(label, "CONTEXT:") {
+                let rest = rest.trim();
+                context_lines = Some(if rest.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![rest]
+                });
+            }
+        }
+
+        let context = context_lines?.join("\n").trim().to_string();
+        if topics.is_empty() || context.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            topics,
+            context,
+            gathered_at: Instant::now(),
+        })
+    }
+
+    /// Whether the context is older than the configured TTL.
+    pub fn is_expired(&self, ttl: Duration) -> bool {
+        self.gathered_at.elapsed() > ttl
+    }
+
+    /// Whether a user message matches any predicted topic.
+    ///
+    /// A topic matches when every significant word in it appears in the
+    /// message, so "billing export" matches "can you check the export for
+    /// billing?" but not "what about billing?".
+    pub fn matches(&self, message: &str) -> bool {
+        let message_tokens = significant_tokens(message);
+        if message_tokens.is_empty() {
+            return false;
+        }
+
+        self.topics.iter().any(|topic| {
+            let topic_tokens = significant_tokens(topic);
+            !topic_tokens.is_empty() && topic_tokens.is_subset(&message_tokens)
+        })
+    }
+
+    /// Human-readable age for the injected prompt fragment.
+    pub fn age_label(&self) -> String {
+        let secs = self.gathered_at.elapsed().as_secs();
+        if secs < 60 {
+            format!("{secs}s")
+        } else {
+            format!("{}m", secs / 60)
+        }
+    }
+}
+
+fn strip_label<'a>(line: &'a str, label: &str) -> Option<&'a str> {
+    let head = line.get(..label.len())?;
+    head.eq_ignore_ascii_case(label)
+        .then(|| line[label.len()..].trim_start_matches('*'))
+}
+
+/// Lowercased words that carry meaning for matching. Identifiers such as
+/// `PROJ-123` are kept whole; short words and stopwords are dropped unless
+/// they contain a digit.
+fn significant_tokens(text: &str) -> HashSet<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+        .map(|token| token.trim_matches(['-', '_']).to_lowercase())
+        .filter(|token| {
+            let has_digit = token.chars().any(|c| c.is_ascii_digit());
+            !token.is_empty()
+                && (has_digit || (token.chars().count() >= 3 && !STOPWORDS.contains(&&**token)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_topics_and_context() {
+        let prefetched = PrefetchedContext::parse(
+            "TOPICS: PROJ-123, billing export, Alice\nCONTEXT:\nPROJ-123 is blocked on the CSV schema.\nOwner: Alice.",
+        )
+        .unwrap();
+
+        assert_eq!(
+            prefetched.topics,
+            vec!["proj-123", "billing export", "alice"]
+        );
+        assert_eq!(
+            prefetched.context,
+            "PROJ-123 is blocked on the CSV schema.\nOwner: Alice."
+        );
+    }
+
+    #[test]
+    fn rejects_none_and_malformed_conclusions() {
+        assert!(PrefetchedContext::parse("NONE").is_none());
+        assert!(PrefetchedContext::parse("  none \n").is_none());
+        assert!(PrefetchedContext::parse("I looked around but found nothing.").is_none());
+        assert!(PrefetchedContext::parse("TOPICS: billing\nCONTEXT:\n").is_none());
+        assert!(PrefetchedContext::parse("CONTEXT: some facts").is_none());
+    }
+
+    #[test]
+    fn tolerates_markdown_labels() {
+        let prefetched = PrefetchedContext::parse(
+            "**TOPICS:** deploy, rollback\n**CONTEXT:** Last deploy was v2.3.",
+        )
+        .unwrap();
+        assert_eq!(prefetched.topics, vec!["deploy", "rollback"]);
+        assert_eq!(prefetched.context, "Last deploy was v2.3.");
+    }
+
+    #[test]
+    fn matches_when_all_topic_words_present() {
+        let prefetched =
+            PrefetchedContext::parse("TOPICS: billing export, PROJ-123\nCONTEXT: facts").unwrap();
+
+        assert!(prefetched.matches("can you check the export for billing?"));
+        assert!(prefetched.matches("any update on proj-123"));
+        assert!(!prefetched.matches("what about billing?"));
+        assert!(!prefetched.matches("thanks!"));
+        assert!(!prefetched.matches("PROJ-124 please"));
+    }
+
+    #[test]
+    fn stopword_only_topics_never_match() {
+        let prefetched = PrefetchedContext::parse("TOPICS: what, the\nCONTEXT: facts").unwrap();
+        assert!(!prefetched.matches("what is the plan"));
+    }
+
+    #[test]
+    fn expires_after_ttl() {
+        let mut prefetched = PrefetchedContext::parse("TOPICS: deploy\nCONTEXT: facts").unwrap();
+        assert!(!prefetched.is_expired(Duration::from_secs(60)));
+        prefetched.gathered_at = Instant::now() - Duration::from_secs(120);
+        assert!(prefetched.is_expired(Duration::from_secs(60)));
+    }
+}
@@ -30,6 +30,13 @@ pub struct PromptSnapshot {
     pub history: serde_json::Value,
     /// Number of messages in the history.
     pub history_length: usize,
+    /// Model the turn was sent to. Missing on snapshots captured before
+    /// this was recorded.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Tools offered to the model on this turn, so it can be replayed.
+    #[serde(default)]
+    pub tool_definitions: Vec<rig::completion::ToolDefinition>,
 }
 
 /// Summary of a snapshot for listing (without the full content).
@@ -0,0 +1,360 @@
+//! Replay of recorded channel turns and worker runs.
+//!
+//! A replay rebuilds the context a process had right before one model call
+//! (a channel turn from its prompt snapshot, or one step of a worker run
+//! from its persisted transcript) and asks for that step again twice: once
+//! with the recorded model and system prompt (baseline) and once with a
+//! different model and/or system prompt (candidate). The two responses come
+//! back side by side, which makes regressions from a prompt edit or model
+//! swap visible before they ship.
+//!
+//! Replays are sandboxed by never executing tools. The model is offered the
+//! tools it had and the calls it would make are reported with their
+//! arguments, but nothing is sent to a messaging platform and no memory,
+//! task, file or history is touched. Only the next model step is replayed,
+//! since anything after it would depend on tool results.
+
+use crate::agent::prompt_snapshot::PromptSnapshot;
+use crate::config::RuntimeConfig;
+use crate::conversation::worker_transcript::{self, TranscriptStep};
+use crate::llm::{LlmManager, SpacebotModel};
+use crate::prompts::PromptEngine;
+use crate::sandbox::Sandbox;
+
+use anyhow::Context as _;
+use rig::completion::{CompletionModel as _, ToolDefinition};
+use rig::message::{AssistantContent, Message};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// The context a process had right before one model call.
+#[derive(Debug, Clone)]
+pub struct RecordedStep {
+    pub model: String,
+    pub system_prompt: String,
+    /// Messages before `prompt`.
+    pub history: Vec<Message>,
+    /// The message the model was answering.
+    pub prompt: Message,
+    pub tool_definitions: Vec<ToolDefinition>,
+}
+
+impl RecordedStep {
+    /// The channel turn captured in a prompt snapshot. `default_model` is
+    /// used for snapshots captured before the model was recorded.
+    pub fn from_snapshot(snapshot: &PromptSnapshot, default_model: &str) -> anyhow::Result<Self> {
+        let history: Vec<Message> = serde_json::from_value(snapshot.history.clone())
+            .context("snapshot history is not a valid message list")?;
+        let tool_definitions = if snapshot.tool_definitions.is_empty() {
+            recorded_tool_placeholders(&history)
+        } else {
+            snapshot.tool_definitions.clone()
+        };
+
+        Ok(Self {
+            model: snapshot
+                .model
+                .clone()
+                .unwrap_or_else(|| default_model.to_string()),
+            system_prompt: snapshot.system_prompt.clone(),
+            history,
+            prompt: Message::user(snapshot.user_message.clone()),
+            tool_definitions,
+        })
+    }
+
+    /// Model step `step` (0 is the first) of a worker run, rebuilt from its
+    /// transcript. Worker tool definitions aren't persisted, so the model is
+    /// offered placeholders for the tools the run actually called.
+    pub fn from_worker_transcript(
+        transcript: &[TranscriptStep],
+        step: usize,
+        model: String,
+        system_prompt: String,
+    ) -> anyhow::Result<Self> {
+        let messages = worker_transcript::transcript_to_history(transcript);
+        let model_steps: Vec<usize> = messages
+            .iter()
+            .enumerate()
+            .filter(|(_, message)| matches!(message, Message::Assistant { .. }))
+            .map(|(index, _)| index)
+            .collect();
+        let Some(&position) = model_steps.get(step) else {
+            anyhow::bail!(
+                "worker run has {} model steps, step {step} does not exist",
+                model_steps.len()
+            );
+        };
+
+        let mut history = messages[..position].to_vec();
+        let Some(prompt @ Message::User { .. }) = history.pop() else {
+            anyhow::bail!("model step {step} has no preceding user message to replay");
+        };
+
+        Ok(Self {
+            model,
+            system_prompt,
+            tool_definitions: recorded_tool_placeholders(&messages),
+            history,
+            prompt,
+        })
+    }
+}
+
+/// What to change for the candidate run. Unset fields keep the recording's.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReplayOverrides {
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+}
+
+/// A tool call the model asked for. Never executed.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayToolCall {
+    pub name: String,
+    pub arguments: serde_json::Value,
+}
+
+/// One side of a replay.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayOutcome {
+    pub model: String,
+    pub system_prompt_chars: usize,
+    pub text: String,
+    pub tool_calls: Vec<ReplayToolCall>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Baseline and candidate responses to the same recorded step.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayComparison {
+    pub history_length: usize,
+    pub baseline: ReplayOutcome,
+    pub candidate: ReplayOutcome,
+}
+
+/// Run the recorded step as recorded and with `overrides`, concurrently.
+pub async fn replay(
+    llm_manager: &Arc<LlmManager>,
+    agent_id: &str,
+    step: &RecordedStep,
+    overrides: &ReplayOverrides,
+) -> ReplayComparison {
+    let candidate_model = overrides.model.as_deref().unwrap_or(&step.model);
+    let candidate_prompt = overrides
+        .system_prompt
+        .as_deref()
+        .unwrap_or(&step.system_prompt);
+
+    let (baseline, candidate) = tokio::join!(
+        run_step(
+            llm_manager,
+            agent_id,
+            step,
+            &step.model,
+            &step.system_prompt
+        ),
+        run_step(
+            llm_manager,
+            agent_id,
+            step,
+            candidate_model,
+            candidate_prompt
+        ),
+    );
+
+    ReplayComparison {
+        history_length: step.history.len(),
+        baseline,
+        candidate,
+    }
+}
+
+/// Ask `model_name` for the recorded step's next response. No routing or
+/// fallbacks, so the comparison is between exactly the requested models.
+async fn run_step(
+    llm_manager: &Arc<LlmManager>,
+    agent_id: &str,
+    step: &RecordedStep,
+    model_name: &str,
+    system_prompt: &str,
+) -> ReplayOutcome {
+    let model = SpacebotModel::make(llm_manager, model_name).with_context(agent_id, "replay");
+    let mut outcome = ReplayOutcome {
+        model: model.full_model_name().to_string(),
+        system_prompt_chars: system_prompt.chars().count(),
+        text: String::new(),
+        tool_calls: Vec::new(),
+        input_tokens: 0,
+        output_tokens: 0,
+        duration_ms: 0,
+        error: None,
+    };
+
+    let started = std::time::Instant::now();
+    let result = model
+        .completion_request(step.prompt.clone())
+        .preamble(system_prompt.to_string())
+        .messages(step.history.clone())
+        .tools(step.tool_definitions.clone())
+        .send()
+        .await;
+    outcome.duration_ms = started.elapsed().as_millis() as u64;
+
+    match result {
+        Ok(response) => {
+            outcome.input_tokens = response.usage.input_tokens;
+            outcome.output_tokens = response.usage.output_tokens;
+            for content in response.choice.iter() {
+                match content {
+                    AssistantContent::Text(text) => {
+                        if !outcome.text.is_empty() {
+                            outcome.text.push('\n');
+                        }
+                        outcome.text.push_str(&text.text);
+                    }
+                    AssistantContent::ToolCall(call) => outcome.tool_calls.push(ReplayToolCall {
+                        name: call.function.name.clone(),
+                        arguments: call.function.arguments.clone(),
+                    }),
+                    _ => {}
+                }
+            }
+        }
+        Err(error) => outcome.error = Some(error.to_string()),
+    }
+    outcome
+}
+
+/// The worker system prompt a builtin worker spawned now would get, minus
+/// live status and skills. Worker prompts aren't stored with the run, so
+/// this stands in for the recorded one.
+pub fn render_worker_prompt(
+    runtime_config: &RuntimeConfig,
+    prompt_engine: &PromptEngine,
+    sandbox: &Sandbox,
+) -> crate::error::Result<String> {
+    let (sandbox_enabled, containment_active, read_allowlist, write_allowlist) =
+        crate::agent::channel_dispatch::worker_sandbox_summary(sandbox, None);
+    let tool_secret_names = match runtime_config.secrets.load().as_ref() {
+        Some(store) => store.tool_secret_names(),
+        None => Vec::new(),
+    };
+
+    prompt_engine.render_worker_prompt(
+        &runtime_config.instance_dir.display().to_string(),
+        &runtime_config.workspace_dir.display().to_string(),
+        sandbox_enabled,
+        containment_active,
+        read_allowlist,
+        write_allowlist,
+        &tool_secret_names,
+        runtime_config.browser_config.load().persist_session,
+        None,
+    )
+}
+
+/// Stand-in definitions for the tools called in `history`, for recordings
+/// that didn't keep the real ones. Providers reject tool calls in history
+/// without matching definitions.
+fn recorded_tool_placeholders(history: &[Message]) -> Vec<ToolDefinition> {
+    let mut names: Vec<String> = Vec::new();
+    for message in history {
+        if let Message::Assistant { content, .. } = message {
+            for item in content.iter() {
+                if let AssistantContent::ToolCall(call) = item
+                    && !names.contains(&call.function.name)
+                {
+                    names.push(call.function.name.clone());
+                }
+            }
+        }
+    }
+
+    names
+        .into_iter()
+        .map(|name| ToolDefinition {
+            name,
+            description: "Tool from the recorded run. Its definition was not recorded.".into(),
+            parameters: serde_json::json!({ "type": "object" }),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversation::worker_transcript::ActionContent;
+
+    fn worker_transcript() -> Vec<TranscriptStep> {
+        vec![
+            TranscriptStep::UserText {
+                text: "List the repo".into(),
+            },
+            TranscriptStep::Action {
+                content: vec![ActionContent::ToolCall {
+                    id: "call_1".into(),
+                    name: "shell".into(),
+                    args: r#"{"command":"ls"}"#.into(),
+                }],
+            },
+            TranscriptStep::ToolResult {
+                call_id: "call_1".into(),
+                name: "shell".into(),
+                text: "Cargo.toml\nsrc".into(),
+            },
+            TranscriptStep::Action {
+                content: vec![ActionContent::Text {
+                    text: "It has Cargo.toml and src.".into(),
+                }],
+            },
+        ]
+    }
+
+    #[test]
+    fn worker_steps_replay_from_the_preceding_message() {
+        let transcript = worker_transcript();
+
+        let first = RecordedStep::from_worker_transcript(
+            &transcript,
+            0,
+            "anthropic/claude-sonnet-4".into(),
+            "You are a worker.".into(),
+        )
+        .unwrap();
+        assert!(first.history.is_empty());
+        assert!(matches!(first.prompt, Message::User { .. }));
+
+        let second = RecordedStep::from_worker_transcript(
+            &transcript,
+            1,
+            "anthropic/claude-sonnet-4".into(),
+            "You are a worker.".into(),
+        )
+        .unwrap();
+        assert_eq!(second.history.len(), 2);
+        let tool_names: Vec<&str> = second
+            .tool_definitions
+            .iter()
+            .map(|definition| definition.name.as_str())
+            .collect();
+        assert_eq!(tool_names, vec!["shell"]);
+    }
+
+    #[test]
+    fn missing_worker_step_is_an_error() {
+        let error = RecordedStep::from_worker_transcript(
+            &worker_transcript(),
+            2,
+            "anthropic/claude-sonnet-4".into(),
+            String::new(),
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("2 model steps"));
+    }
+}
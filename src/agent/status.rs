@@ -65,9 +65,12 @@ impl SystemInfo {
         if rc.brave_search_key.load().is_some() {
             capabilities.push("web_search".to_string());
         }
-        if rc.opencode.load().enabled {
+        if crate::cli_worker::CliWorkerBackend::Opencode.is_usable(rc) {
             capabilities.push("opencode".to_string());
         }
+        if crate::cli_worker::CliWorkerBackend::Container.is_usable(rc) {
+            capabilities.push("container".to_string());
+        }
 
         let mcp_servers: Vec<String> = rc
             .mcp
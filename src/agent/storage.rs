@@ -0,0 +1,730 @@
+//! Per-agent disk usage monitoring.
+//!
+//! Periodically measures everything an agent keeps on disk — SQLite, the
+//! LanceDB memory index, saved attachments, screenshots, logs, and archives —
+//! and compares the total against the configured quota. Crossing the warning
+//! threshold is logged and recorded as a cortex event, and can optionally
+//! trigger cleanup: log/screenshot retention, a SQLite WAL checkpoint, and
+//! LanceDB compaction.
+//!
+//! LanceDB tables are also compacted and pruned on their own schedule, once
+//! the agent has been idle for a while, since every write leaves an old table
+//! version behind. The SQLite database gets the same treatment: ANALYZE, a
+//! VACUUM when enough of the file is free pages, and a WAL checkpoint.
+
+use crate::AgentDeps;
+use crate::ProcessEvent;
+use crate::agent::cortex::CortexLogger;
+use crate::config::{RuntimeConfig, StorageConfig};
+use crate::memory::MemorySearch;
+use crate::memory::lance::OptimizeReport;
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tokio::sync::broadcast;
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often to re-check whether a disabled monitor was re-enabled.
+const DISABLED_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Delay before the first measurement, so startup isn't slowed down.
+const INITIAL_DELAY: Duration = Duration::from_secs(30);
+
+/// How often a due LanceDB optimization re-checks whether the agent is idle.
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Usage relative to the configured quota.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageLevel {
+    Ok,
+    Warning,
+    Exceeded,
+}
+
+/// Disk usage snapshot for one agent, in bytes.
+#[derive(Debug, Clone, Serialize)]
+pub struct AgentDiskUsage {
+    /// SQLite database (including WAL/SHM files) and the redb settings store.
+    pub database_bytes: u64,
+    /// LanceDB memory embeddings and indexes.
+    pub memory_index_bytes: u64,
+    /// Saved channel attachments and files waiting in the ingest directory.
+    pub attachments_bytes: u64,
+    pub screenshots_bytes: u64,
+    pub logs_bytes: u64,
+    pub archives_bytes: u64,
+    /// Everything else in the data directory and workspace.
+    pub other_bytes: u64,
+    pub total_bytes: u64,
+    pub quota_bytes: Option<u64>,
+    pub percent_used: Option<f64>,
+    pub level: StorageLevel,
+    pub measured_at: String,
+}
+
+/// Result of a cleanup pass.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StorageCleanupReport {
+    pub logs_removed: usize,
+    pub screenshots_removed: usize,
+    pub bytes_freed: u64,
+    pub database_checkpointed: bool,
+    pub memory_index_optimized: bool,
+}
+
+/// Result of optimizing one LanceDB table.
+#[derive(Debug, Clone, Serialize)]
+pub struct VectorTableReport {
+    /// `memories` or `episodes`.
+    pub table: &'static str,
+    pub optimized: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(flatten)]
+    pub stats: OptimizeReport,
+}
+
+/// The directories that make up an agent's on-disk footprint.
+#[derive(Debug, Clone)]
+struct StoragePaths {
+    data_dir: PathBuf,
+    archives_dir: PathBuf,
+    workspace_dir: PathBuf,
+    screenshot_dir: PathBuf,
+}
+
+impl StoragePaths {
+    fn from_runtime_config(runtime_config: &RuntimeConfig) -> Self {
+        let screenshot_dir = runtime_config
+            .browser_config
+            .load()
+            .screenshot_dir
+            .clone()
+            .unwrap_or_else(|| runtime_config.data_dir.join("screenshots"));
+        Self {
+            data_dir: runtime_config.data_dir.clone(),
+            archives_dir: runtime_config.archives_dir.clone(),
+            workspace_dir: runtime_config.workspace_dir.clone(),
+            screenshot_dir,
+        }
+    }
+
+    fn logs_dir(&self) -> PathBuf {
+        self.data_dir.join("logs")
+    }
+}
+
+/// Measure an agent's current disk usage. Walks the filesystem, so this runs
+/// on the blocking pool.
+pub async fn measure(runtime_config: &RuntimeConfig) -> anyhow::Result<AgentDiskUsage> {
+    let paths = StoragePaths::from_runtime_config(runtime_config);
+    let config = **runtime_config.storage.load();
+    tokio::task::spawn_blocking(move || measure_paths(&paths, &config)).await?
+}
+
+fn measure_paths(paths: &StoragePaths, config: &StorageConfig) -> anyhow::Result<AgentDiskUsage> {
+    let data_dir = &paths.data_dir;
+    let database_bytes = [
+        "spacebot.db",
+        "spacebot.db-wal",
+        "spacebot.db-shm",
+        "config.redb",
+    ]
+    .iter()
+    .map(|name| path_size_bytes(&data_dir.join(name)))
+    .sum::<anyhow::Result<u64>>()?;
+    let memory_index_bytes = path_size_bytes(&data_dir.join("lancedb"))?;
+    let attachments_bytes = path_size_bytes(&paths.workspace_dir.join("saved"))?
+        + path_size_bytes(&paths.workspace_dir.join("ingest"))?;
+    let screenshots_bytes = path_size_bytes(&paths.screenshot_dir)?;
+    let logs_bytes = path_size_bytes(&paths.logs_dir())?;
+    let archives_bytes = path_size_bytes(&paths.archives_dir)?;
+
+    let mut total_bytes =
+        path_size_bytes(data_dir)? + path_size_bytes(&paths.workspace_dir)? + archives_bytes;
+    // A custom screenshot directory lives outside the data directory.
+    if !paths.screenshot_dir.starts_with(data_dir) {
+        total_bytes += screenshots_bytes;
+    }
+    let other_bytes = total_bytes.saturating_sub(
+        database_bytes
+            + memory_index_bytes
+            + attachments_bytes
+            + screenshots_bytes
+            + logs_bytes
+            + archives_bytes,
+    );
+
+    let quota_bytes = config.quota_bytes();
+    let (percent_used, level) = evaluate_level(total_bytes, quota_bytes, config.warn_percent);
+
+    Ok(AgentDiskUsage {
+        database_bytes,
+        memory_index_bytes,
+        attachments_bytes,
+        screenshots_bytes,
+        logs_bytes,
+        archives_bytes,
+        other_bytes,
+        total_bytes,
+        quota_bytes,
+        percent_used,
+        level,
+        measured_at: chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+fn evaluate_level(
+    total_bytes: u64,
+    quota_bytes: Option<u64>,
+    warn_percent: u8,
+) -> (Option<f64>, StorageLevel) {
+    let Some(quota_bytes) = quota_bytes.filter(|quota| *quota > 0) else {
+        return (None, StorageLevel::Ok);
+    };
+    let percent = total_bytes as f64 / quota_bytes as f64 * 100.0;
+    let level = if total_bytes >= quota_bytes {
+        StorageLevel::Exceeded
+    } else if percent >= f64::from(warn_percent) {
+        StorageLevel::Warning
+    } else {
+        StorageLevel::Ok
+    };
+    (Some(percent), level)
+}
+
+/// Total size of a file or directory tree. Symlinks are not followed and
+/// missing paths count as zero.
+fn path_size_bytes(root: &Path) -> anyhow::Result<u64> {
+    let mut total = 0u64;
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(path) = stack.pop() {
+        let metadata = match std::fs::symlink_metadata(&path) {
+            Ok(metadata) => metadata,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(error) => return Err(error.into()),
+        };
+
+        if metadata.is_file() {
+            total = total.saturating_add(metadata.len());
+        } else if metadata.is_dir() {
+            for entry in std::fs::read_dir(&path)? {
+                stack.push(entry?.path());
+            }
+        }
+    }
+
+    Ok(total)
+}
+
+/// Run retention and compaction for an agent.
+///
+/// Removes logs and screenshots older than `retention_days`, truncates the
+/// SQLite WAL, and compacts the LanceDB tables. Failures in one step are
+/// logged and don't stop the others.
+pub async fn cleanup(
+    agent_id: &str,
+    runtime_config: &RuntimeConfig,
+    sqlite_pool: &SqlitePool,
+    memory_search: &MemorySearch,
+) -> StorageCleanupReport {
+    let paths = StoragePaths::from_runtime_config(runtime_config);
+    let retention = Duration::from_secs(
+        runtime_config
+            .storage
+            .load()
+            .retention_days
+            .saturating_mul(24 * 60 * 60),
+    );
+    let cutoff = SystemTime::now()
+        .checked_sub(retention)
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+
+    let mut report = match tokio::task::spawn_blocking(move || {
+        let (logs_removed, logs_freed) = remove_files_older_than(&paths.logs_dir(), cutoff);
+        let (screenshots_removed, screenshots_freed) =
+            remove_files_older_than(&paths.screenshot_dir, cutoff);
+        StorageCleanupReport {
+            logs_removed,
+            screenshots_removed,
+            bytes_freed: logs_freed + screenshots_freed,
+            ..Default::default()
+        }
+    })
+    .await
+    {
+        Ok(report) => report,
+        Err(error) => {
+            tracing::warn!(%error, "storage retention task failed");
+            StorageCleanupReport::default()
+        }
+    };
+
+    match crate::db::maintenance::checkpoint(sqlite_pool).await {
+        Ok(_) => report.database_checkpointed = true,
+        Err(error) => tracing::warn!(%error, "failed to checkpoint sqlite wal"),
+    }
+
+    let tables = optimize_vector_tables(agent_id, memory_search).await;
+    report.memory_index_optimized = tables
+        .iter()
+        .any(|table| table.table == "memories" && table.optimized);
+
+    report
+}
+
+/// Compact data files and prune old versions in every LanceDB table the agent
+/// has: the memory embeddings and, when episodic recall is enabled, the
+/// conversation episodes. Failures are logged per table and don't stop the
+/// others.
+pub async fn optimize_vector_tables(
+    agent_id: &str,
+    memory_search: &MemorySearch,
+) -> Vec<VectorTableReport> {
+    let mut reports = vec![table_report(
+        agent_id,
+        "memories",
+        memory_search.embedding_table().optimize().await,
+    )];
+    if let Some(episodes) = memory_search.episodes() {
+        reports.push(table_report(
+            agent_id,
+            "episodes",
+            episodes.optimize().await,
+        ));
+    }
+    reports
+}
+
+fn table_report(
+    agent_id: &str,
+    table: &'static str,
+    result: crate::error::Result<OptimizeReport>,
+) -> VectorTableReport {
+    #[cfg(not(feature = "metrics"))]
+    let _ = agent_id;
+
+    match result {
+        Ok(stats) => {
+            tracing::debug!(
+                table,
+                versions_pruned = stats.versions_pruned,
+                bytes_reclaimed = stats.bytes_reclaimed,
+                fragments_removed = stats.fragments_removed,
+                "optimized lancedb table"
+            );
+            #[cfg(feature = "metrics")]
+            {
+                let metrics = crate::telemetry::Metrics::global();
+                metrics
+                    .vector_optimize_runs_total
+                    .with_label_values(&[agent_id, table, "success"])
+                    .inc();
+                metrics
+                    .vector_versions_pruned_total
+                    .with_label_values(&[agent_id, table])
+                    .inc_by(stats.versions_pruned);
+                metrics
+                    .vector_bytes_reclaimed_total
+                    .with_label_values(&[agent_id, table])
+                    .inc_by(stats.bytes_reclaimed);
+            }
+            VectorTableReport {
+                table,
+                optimized: true,
+                error: None,
+                stats,
+            }
+        }
+        Err(error) => {
+            tracing::warn!(%error, table, "failed to optimize lancedb table");
+            #[cfg(feature = "metrics")]
+            crate::telemetry::Metrics::global()
+                .vector_optimize_runs_total
+                .with_label_values(&[agent_id, table, "failure"])
+                .inc();
+            VectorTableReport {
+                table,
+                optimized: false,
+                error: Some(error.to_string()),
+                stats: OptimizeReport::default(),
+            }
+        }
+    }
+}
+
+/// Remove regular files under `root` last modified before `cutoff`. Returns
+/// the number of files removed and the bytes they occupied.
+fn remove_files_older_than(root: &Path, cutoff: SystemTime) -> (usize, u64) {
+    let mut removed = 0usize;
+    let mut freed = 0u64;
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(path) = stack.pop() {
+        let Ok(metadata) = std::fs::symlink_metadata(&path) else {
+            continue;
+        };
+
+        if metadata.is_dir() {
+            if let Ok(entries) = std::fs::read_dir(&path) {
+                stack.extend(entries.flatten().map(|entry| entry.path()));
+            }
+            continue;
+        }
+
+        if !metadata.is_file() {
+            continue;
+        }
+        let expired = metadata
+            .modified()
+            .map(|modified| modified < cutoff)
+            .unwrap_or(false);
+        if !expired {
+            continue;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(()) => {
+                removed += 1;
+                freed = freed.saturating_add(metadata.len());
+            }
+            Err(error) => {
+                tracing::warn!(%error, path = %path.display(), "failed to remove expired file");
+            }
+        }
+    }
+
+    (removed, freed)
+}
+
+/// Spawn the storage monitor loop for an agent.
+///
+/// Measures usage every `check_interval_secs`, publishes the result on the
+/// runtime config, and warns when the level worsens. With `auto_cleanup`
+/// enabled, cleanup runs whenever usage is at or above the warning threshold.
+pub fn spawn_storage_monitor(deps: AgentDeps, logger: CortexLogger) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        tracing::info!("storage monitor started");
+        let job = deps.runtime_config.jobs.register(
+            "storage_monitor",
+            "Measure disk usage and run retention cleanup",
+        );
+        job.wait(INITIAL_DELAY).await;
+        let mut previous_level = StorageLevel::Ok;
+
+        loop {
+            let config = **deps.runtime_config.storage.load();
+            if config.check_interval_secs == 0 {
+                job.wait(DISABLED_POLL_INTERVAL).await;
+                continue;
+            }
+
+            job.started();
+            match check_once(&deps, &logger, config, previous_level).await {
+                Ok(level) => {
+                    previous_level = level;
+                    job.finished(true, format!("usage level {level:?}").to_lowercase());
+                }
+                Err(error) => {
+                    tracing::warn!(%error, "failed to measure agent disk usage");
+                    job.finished(false, error.to_string());
+                }
+            }
+
+            job.wait(Duration::from_secs(config.check_interval_secs))
+                .await;
+        }
+    })
+}
+
+/// Spawn the scheduled LanceDB optimizer for an agent.
+///
+/// Every `optimize_interval_secs`, waits until the agent has seen no process
+/// activity (messages, branches, workers, tool calls) for
+/// `optimize_idle_secs`, then compacts and prunes its vector tables.
+pub fn spawn_vector_optimizer(
+    deps: AgentDeps,
+    logger: CortexLogger,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        tracing::info!("vector optimizer started");
+        let job = deps.runtime_config.jobs.register(
+            "vector_optimize",
+            "Compact and prune LanceDB tables while the agent is idle",
+        );
+        let mut event_rx = deps.event_tx.subscribe();
+        let mut last_activity = Instant::now();
+
+        loop {
+            let config = **deps.runtime_config.storage.load();
+            if config.optimize_interval_secs == 0 {
+                job.wait(DISABLED_POLL_INTERVAL).await;
+                continue;
+            }
+            job.wait(Duration::from_secs(config.optimize_interval_secs))
+                .await;
+            wait_until_idle(
+                &mut event_rx,
+                &mut last_activity,
+                Duration::from_secs(config.optimize_idle_secs),
+            )
+            .await;
+
+            job.started();
+            let reports = optimize_vector_tables(&deps.agent_id, &deps.memory_search).await;
+            let failed = reports.iter().filter(|report| !report.optimized).count();
+            let versions_pruned: u64 = reports
+                .iter()
+                .map(|report| report.stats.versions_pruned)
+                .sum();
+            let bytes_reclaimed: u64 = reports
+                .iter()
+                .map(|report| report.stats.bytes_reclaimed)
+                .sum();
+            let summary =
+                format!("pruned {versions_pruned} versions, reclaimed {bytes_reclaimed} bytes");
+            if versions_pruned > 0 || failed > 0 {
+                logger.log(
+                    "vector_optimize",
+                    &format!("LanceDB optimize {summary}"),
+                    serde_json::to_value(&reports).ok(),
+                );
+            }
+            job.finished(failed == 0, summary);
+        }
+    })
+}
+
+/// Spawn the scheduled SQLite maintenance pass for an agent.
+///
+/// Every `db_maintenance_interval_secs`, waits for the same idle window as
+/// the vector optimizer, then runs ANALYZE, a VACUUM if enough of the file
+/// is free pages, and a WAL checkpoint.
+pub fn spawn_db_maintenance(deps: AgentDeps, logger: CortexLogger) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        tracing::info!("database maintenance started");
+        let job = deps.runtime_config.jobs.register(
+            "db_maintenance",
+            "Analyze, vacuum, and checkpoint the SQLite database while the agent is idle",
+        );
+        let mut event_rx = deps.event_tx.subscribe();
+        let mut last_activity = Instant::now();
+
+        loop {
+            let config = **deps.runtime_config.storage.load();
+            if config.db_maintenance_interval_secs == 0 {
+                job.wait(DISABLED_POLL_INTERVAL).await;
+                continue;
+            }
+            job.wait(Duration::from_secs(config.db_maintenance_interval_secs))
+                .await;
+            wait_until_idle(
+                &mut event_rx,
+                &mut last_activity,
+                Duration::from_secs(config.optimize_idle_secs),
+            )
+            .await;
+
+            job.started();
+            match crate::db::maintenance::run(&deps.sqlite_pool, &config).await {
+                Ok(report) => {
+                    let summary = format!(
+                        "{}reclaimed {} bytes",
+                        if report.vacuumed { "vacuumed, " } else { "" },
+                        report.bytes_reclaimed
+                    );
+                    if report.vacuumed {
+                        logger.log(
+                            "db_maintenance",
+                            &format!("SQLite maintenance {summary}"),
+                            serde_json::to_value(&report).ok(),
+                        );
+                    }
+                    job.finished(true, summary);
+                }
+                Err(error) => {
+                    tracing::warn!(%error, "sqlite maintenance failed");
+                    job.finished(false, error.to_string());
+                }
+            }
+        }
+    })
+}
+
+/// Wait until no process activity has been seen for `idle_after`.
+async fn wait_until_idle(
+    event_rx: &mut broadcast::Receiver<ProcessEvent>,
+    last_activity: &mut Instant,
+    idle_after: Duration,
+) {
+    loop {
+        if drain_activity(event_rx) {
+            *last_activity = Instant::now();
+        }
+        let idle_for = last_activity.elapsed();
+        if idle_for >= idle_after {
+            return;
+        }
+        tokio::time::sleep(IDLE_POLL_INTERVAL.min(idle_after - idle_for)).await;
+    }
+}
+
+/// Drain pending process events. Returns whether any activity happened since
+/// the last drain; a lagged receiver counts as activity.
+fn drain_activity(event_rx: &mut broadcast::Receiver<ProcessEvent>) -> bool {
+    let mut active = false;
+    loop {
+        match event_rx.try_recv() {
+            Ok(_) | Err(broadcast::error::TryRecvError::Lagged(_)) => active = true,
+            Err(broadcast::error::TryRecvError::Empty)
+            | Err(broadcast::error::TryRecvError::Closed) => return active,
+        }
+    }
+}
+
+async fn check_once(
+    deps: &AgentDeps,
+    logger: &CortexLogger,
+    config: StorageConfig,
+    previous_level: StorageLevel,
+) -> anyhow::Result<StorageLevel> {
+    let mut usage = measure(&deps.runtime_config).await?;
+
+    if usage.level != StorageLevel::Ok && config.auto_cleanup {
+        let report = cleanup(
+            &deps.agent_id,
+            &deps.runtime_config,
+            &deps.sqlite_pool,
+            &deps.memory_search,
+        )
+        .await;
+        logger.log(
+            "storage_cleanup",
+            &format!(
+                "Storage cleanup removed {} logs and {} screenshots",
+                report.logs_removed, report.screenshots_removed
+            ),
+            serde_json::to_value(&report).ok(),
+        );
+        usage = measure(&deps.runtime_config).await?;
+    }
+
+    let level = usage.level;
+    if is_worse(level, previous_level) {
+        tracing::warn!(
+            agent_id = %deps.agent_id,
+            total_bytes = usage.total_bytes,
+            quota_bytes = usage.quota_bytes,
+            percent_used = usage.percent_used,
+            "agent disk usage {}",
+            match level {
+                StorageLevel::Exceeded => "exceeded its quota",
+                _ => "crossed the warning threshold",
+            }
+        );
+        logger.log(
+            "storage_warning",
+            &format!(
+                "Disk usage at {:.0}% of quota",
+                usage.percent_used.unwrap_or_default()
+            ),
+            serde_json::to_value(&usage).ok(),
+        );
+    }
+
+    deps.runtime_config
+        .storage_usage
+        .store(std::sync::Arc::new(Some(usage)));
+    Ok(level)
+}
+
+fn is_worse(level: StorageLevel, previous: StorageLevel) -> bool {
+    let rank = |level: StorageLevel| match level {
+        StorageLevel::Ok => 0,
+        StorageLevel::Warning => 1,
+        StorageLevel::Exceeded => 2,
+    };
+    rank(level) > rank(previous)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_follows_quota_thresholds() {
+        let mb = 1024 * 1024;
+        assert_eq!(evaluate_level(50 * mb, None, 80), (None, StorageLevel::Ok));
+        assert_eq!(
+            evaluate_level(50 * mb, Some(100 * mb), 80).1,
+            StorageLevel::Ok
+        );
+        assert_eq!(
+            evaluate_level(85 * mb, Some(100 * mb), 80).1,
+            StorageLevel::Warning
+        );
+        assert_eq!(
+            evaluate_level(100 * mb, Some(100 * mb), 80).1,
+            StorageLevel::Exceeded
+        );
+    }
+
+    #[test]
+    fn measures_categories_and_quota() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let paths = StoragePaths {
+            data_dir: root.join("data"),
+            archives_dir: root.join("archives"),
+            workspace_dir: root.join("workspace"),
+            screenshot_dir: root.join("data/screenshots"),
+        };
+        for (path, size) in [
+            ("data/spacebot.db", 400),
+            ("data/lancedb/table/data.lance", 300),
+            ("data/screenshots/a.png", 100),
+            ("data/logs/worker.log", 50),
+            ("workspace/saved/file.pdf", 100),
+            ("workspace/notes.md", 30),
+            ("archives/old.jsonl", 20),
+        ] {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, vec![0u8; size]).unwrap();
+        }
+
+        let config = StorageConfig {
+            quota_mb: Some(1),
+            ..StorageConfig::default()
+        };
+        let usage = measure_paths(&paths, &config).unwrap();
+        assert_eq!(usage.database_bytes, 400);
+        assert_eq!(usage.memory_index_bytes, 300);
+        assert_eq!(usage.screenshots_bytes, 100);
+        assert_eq!(usage.logs_bytes, 50);
+        assert_eq!(usage.attachments_bytes, 100);
+        assert_eq!(usage.archives_bytes, 20);
+        assert_eq!(usage.other_bytes, 30);
+        assert_eq!(usage.total_bytes, 1000);
+        assert_eq!(usage.level, StorageLevel::Ok);
+    }
+
+    #[test]
+    fn removes_only_expired_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let file = temp.path().join("nested/worker.log");
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, b"log").unwrap();
+
+        let past = SystemTime::now() - Duration::from_secs(3600);
+        assert_eq!(remove_files_older_than(temp.path(), past), (0, 0));
+        assert!(file.exists());
+
+        let future = SystemTime::now() + Duration::from_secs(3600);
+        assert_eq!(remove_files_older_than(temp.path(), future), (1, 3));
+        assert!(!file.exists());
+    }
+}
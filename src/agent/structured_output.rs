@@ -0,0 +1,395 @@
+//! Structured worker results: extract JSON from a worker's final answer and
+//! validate it against the caller's `result_schema`.
+//!
+//! Supports the JSON Schema subset LLM callers actually write: `type`
+//! (including type arrays and `integer`), `properties`, `required`,
+//! `additionalProperties`, `items`, `enum`, `const`, `anyOf`/`oneOf`, and
+//! the common length and range bounds. Unknown keywords are ignored.
+
+use serde_json::Value;
+
+/// Type names accepted in a schema's `type` keyword.
+const TYPE_NAMES: &[&str] = &[
+    "object", "array", "string", "number", "integer", "boolean", "null",
+];
+
+/// Check that a caller-supplied schema is usable before a worker is spawned.
+pub fn check_schema(schema: &Value) -> Result<(), String> {
+    match schema {
+        Value::Bool(_) => Ok(()),
+        Value::Object(map) => {
+            if let Some(type_value) = map.get("type") {
+                let names: Vec<&Value> = match type_value {
+                    Value::Array(names) => names.iter().collect(),
+                    other => vec![other],
+                };
+                for name in names {
+                    match name.as_str() {
+                        Some(name) if TYPE_NAMES.contains(&name) => {}
+                        _ => return Err(format!("unsupported schema type {name}")),
+                    }
+                }
+            }
+            if let Some(properties) = map.get("properties") {
+                let properties = properties
+                    .as_object()
+                    .ok_or("\"properties\" must be an object")?;
+                for (name, property) in properties {
+                    check_schema(property).map_err(|error| format!("{name}: {error}"))?;
+                }
+            }
+            if let Some(items) = map.get("items") {
+                check_schema(items).map_err(|error| format!("items: {error}"))?;
+            }
+            for keyword in ["anyOf", "oneOf"] {
+                if let Some(variants) = map.get(keyword) {
+                    let variants = variants
+                        .as_array()
+                        .ok_or_else(|| format!("\"{keyword}\" must be an array"))?;
+                    for variant in variants {
+                        check_schema(variant)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+        _ => Err("schema must be a JSON object".into()),
+    }
+}
+
+/// Parse a worker's final answer as JSON and validate it.
+///
+/// The answer may wrap the JSON in a fenced code block or surround it with
+/// prose. Strings holding numbers or booleans are coerced when the schema
+/// asks for those types. Returns every validation error on failure.
+pub fn coerce(answer: &str, schema: &Value) -> Result<Value, Vec<String>> {
+    let Some(mut value) = extract_json(answer) else {
+        return Err(vec!["the answer does not contain a JSON value".into()]);
+    };
+    coerce_scalars(&mut value, schema);
+
+    let mut errors = Vec::new();
+    validate(&value, schema, "$", &mut errors);
+    if errors.is_empty() {
+        Ok(value)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Find the JSON value in a model answer.
+fn extract_json(answer: &str) -> Option<Value> {
+    let trimmed = answer.trim();
+    if let Ok(value) = serde_json::from_str(trimmed) {
+        return Some(value);
+    }
+
+    // ```json ... ``` or ``` ... ```
+    if let Some(start) = trimmed.find("```") {
+        let after_fence = &trimmed[start + 3..];
+        let body_start = after_fence.find('\n').map(|index| index + 1).unwrap_or(0);
+        let body = &after_fence[body_start..];
+        if let Some(end) = body.find("```")
+            && let Ok(value) = serde_json::from_str(body[..end].trim())
+        {
+            return Some(value);
+        }
+    }
+
+    // The outermost object or array embedded in prose.
+    for (open, close) in [('{', '}'), ('[', ']')] {
+        if let (Some(start), Some(end)) = (trimmed.find(open), trimmed.rfind(close))
+            && start < end
+            && let Ok(value) = serde_json::from_str(&trimmed[start..=end])
+        {
+            return Some(value);
+        }
+    }
+
+    None
+}
+
+fn allows_type(schema: &Value, type_name: &str) -> bool {
+    match schema.get("type") {
+        Some(Value::String(name)) => name == type_name,
+        Some(Value::Array(names)) => names.iter().any(|name| name == type_name),
+        _ => false,
+    }
+}
+
+fn coerce_scalars(value: &mut Value, schema: &Value) {
+    match value {
+        Value::String(text) if !allows_type(schema, "string") => {
+            let text = text.trim();
+            let coerced = if allows_type(schema, "integer") {
+                text.parse::<i64>().ok().map(Value::from)
+            } else if allows_type(schema, "number") {
+                text.parse::<f64>()
+                    .ok()
+                    .and_then(|number| serde_json::Number::from_f64(number).map(Value::Number))
+            } else if allows_type(schema, "boolean") {
+                text.parse::<bool>().ok().map(Value::Bool)
+            } else {
+                None
+            };
+            if let Some(coerced) = coerced {
+                *value = coerced;
+            }
+        }
+        Value::Object(map) => {
+            if let Some(Value::Object(properties)) = schema.get("properties") {
+                for (name, property) in map.iter_mut() {
+                    if let Some(property_schema) = properties.get(name) {
+                        coerce_scalars(property, property_schema);
+                    }
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for item in items {
+                    coerce_scalars(item, item_schema);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn type_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_i64() || number.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn matches_type(value: &Value, type_name: &str) -> bool {
+    let actual = type_of(value);
+    actual == type_name || (type_name == "number" && actual == "integer")
+}
+
+fn validate(value: &Value, schema: &Value, path: &str, errors: &mut Vec<String>) {
+    let schema = match schema {
+        Value::Bool(true) => return,
+        Value::Bool(false) => {
+            errors.push(format!("{path}: no value is allowed here"));
+            return;
+        }
+        Value::Object(schema) => schema,
+        _ => return,
+    };
+
+    if let Some(type_value) = schema.get("type") {
+        let allowed: Vec<&str> = match type_value {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|name| matches_type(value, name)) {
+            errors.push(format!(
+                "{path}: expected {}, got {}",
+                allowed.join(" or "),
+                type_of(value)
+            ));
+            return;
+        }
+    }
+
+    if let Some(Value::Array(options)) = schema.get("enum")
+        && !options.contains(value)
+    {
+        errors.push(format!(
+            "{path}: must be one of {}",
+            Value::Array(options.clone())
+        ));
+    }
+    if let Some(expected) = schema.get("const")
+        && expected != value
+    {
+        errors.push(format!("{path}: must equal {expected}"));
+    }
+
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(Value::Array(variants)) = schema.get(keyword) {
+            let matching = variants
+                .iter()
+                .filter(|variant| {
+                    let mut variant_errors = Vec::new();
+                    validate(value, variant, path, &mut variant_errors);
+                    variant_errors.is_empty()
+                })
+                .count();
+            let ok = if keyword == "oneOf" {
+                matching == 1
+            } else {
+                matching > 0
+            };
+            if !ok {
+                errors.push(format!("{path}: does not match {keyword}"));
+            }
+        }
+    }
+
+    match value {
+        Value::Object(map) => {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !map.contains_key(name) {
+                        errors.push(format!("{path}: missing required property \"{name}\""));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (name, property) in map {
+                let property_path = format!("{path}.{name}");
+                match properties.and_then(|properties| properties.get(name)) {
+                    Some(property_schema) => {
+                        validate(property, property_schema, &property_path, errors)
+                    }
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            errors.push(format!("{property_path}: unexpected property"))
+                        }
+                        Some(additional @ Value::Object(_)) => {
+                            validate(property, additional, &property_path, errors)
+                        }
+                        _ => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            check_bound(schema, "minItems", items.len(), path, errors, |len, min| {
+                len >= min
+            });
+            check_bound(schema, "maxItems", items.len(), path, errors, |len, max| {
+                len <= max
+            });
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate(item, item_schema, &format!("{path}[{index}]"), errors);
+                }
+            }
+        }
+        Value::String(text) => {
+            let len = text.chars().count();
+            check_bound(schema, "minLength", len, path, errors, |len, min| {
+                len >= min
+            });
+            check_bound(schema, "maxLength", len, path, errors, |len, max| {
+                len <= max
+            });
+        }
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64)
+                && number < minimum
+            {
+                errors.push(format!("{path}: must be at least {minimum}"));
+            }
+            if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64)
+                && number > maximum
+            {
+                errors.push(format!("{path}: must be at most {maximum}"));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_bound(
+    schema: &serde_json::Map<String, Value>,
+    keyword: &str,
+    actual: usize,
+    path: &str,
+    errors: &mut Vec<String>,
+    within: impl Fn(usize, usize) -> bool,
+) {
+    if let Some(bound) = schema.get(keyword).and_then(Value::as_u64)
+        && !within(actual, bound as usize)
+    {
+        errors.push(format!("{path}: {keyword} is {bound}, got {actual}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "status": { "type": "string", "enum": ["pass", "fail"] },
+                "failures": { "type": "integer", "minimum": 0 },
+                "files": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["status", "failures"],
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn extracts_fenced_json_and_coerces_scalars() {
+        let answer =
+            "Done. Here is the result:\n```json\n{\"status\": \"pass\", \"failures\": \"0\"}\n```";
+        let value = coerce(answer, &schema()).unwrap();
+        assert_eq!(value, json!({ "status": "pass", "failures": 0 }));
+    }
+
+    #[test]
+    fn extracts_json_embedded_in_prose() {
+        let answer =
+            "Result: {\"status\": \"fail\", \"failures\": 2, \"files\": [\"a.rs\"]} — see above.";
+        let value = coerce(answer, &schema()).unwrap();
+        assert_eq!(value["files"], json!(["a.rs"]));
+    }
+
+    #[test]
+    fn reports_every_validation_error() {
+        let errors = coerce(
+            "{\"status\": \"maybe\", \"extra\": true, \"files\": [1]}",
+            &schema(),
+        )
+        .unwrap_err();
+        assert!(
+            errors
+                .iter()
+                .any(|error| error.contains("missing required property \"failures\""))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|error| error.starts_with("$.status: must be one of"))
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|error| error == "$.extra: unexpected property")
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|error| error == "$.files[0]: expected string, got integer")
+        );
+    }
+
+    #[test]
+    fn rejects_answers_without_json() {
+        let errors = coerce("All tests passed.", &schema()).unwrap_err();
+        assert_eq!(errors, vec!["the answer does not contain a JSON value"]);
+    }
+
+    #[test]
+    fn check_schema_rejects_unknown_types() {
+        assert!(check_schema(&schema()).is_ok());
+        assert!(check_schema(&json!({ "type": "date" })).is_err());
+        assert!(check_schema(&json!("object")).is_err());
+    }
+}
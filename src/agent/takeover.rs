@@ -0,0 +1,137 @@
+//! Operator takeover of a channel.
+//!
+//! While an operator has taken over a channel, inbound messages are still
+//! recorded but the agent doesn't run turns, and the operator's replies are
+//! sent under the bot's identity through the API. When control is handed
+//! back, the agent is given a transcript of the takeover so it can pick up
+//! the conversation where the operator left it.
+
+use crate::agent::channel::ChannelState;
+use crate::conversation::history::{ConversationMessage, SENT_BY_OPERATOR_KEY};
+use crate::error::Result;
+use crate::prompts::PromptEngine;
+
+use rig::message::{AssistantContent, Message};
+use rig::one_or_many::OneOrMany;
+
+/// Most messages from a takeover included in the hand-back transcript.
+pub const MAX_TRANSCRIPT_MESSAGES: i64 = 200;
+
+/// Whether a stored message was sent by an operator during a takeover.
+pub fn is_operator_message(message: &ConversationMessage) -> bool {
+    message
+        .metadata
+        .as_deref()
+        .and_then(|metadata| serde_json::from_str::<serde_json::Value>(metadata).ok())
+        .and_then(|metadata| metadata.get(SENT_BY_OPERATOR_KEY)?.as_bool())
+        .unwrap_or(false)
+}
+
+/// Render what happened in a channel during a takeover, oldest first.
+///
+/// Returns `None` if nobody spoke, so there is nothing to catch up on.
+pub fn render_takeover_transcript(
+    prompts: &PromptEngine,
+    messages: &[ConversationMessage],
+) -> Result<Option<String>> {
+    let lines: Vec<String> = messages
+        .iter()
+        .filter(|message| message.role == "user" || message.role == "assistant")
+        .map(|message| {
+            let author = if message.role == "user" {
+                message
+                    .sender_name
+                    .as_deref()
+                    .or(message.sender_id.as_deref())
+                    .unwrap_or("user")
+            } else if is_operator_message(message) {
+                "operator (as you)"
+            } else {
+                "you"
+            };
+            format!(
+                "[{}] {}: {}",
+                message.created_at.format("%Y-%m-%d %H:%M"),
+                author,
+                message.content
+            )
+        })
+        .collect();
+
+    if lines.is_empty() {
+        return Ok(None);
+    }
+
+    prompts
+        .render_system_takeover_transcript(&lines.join("\n"))
+        .map(Some)
+}
+
+/// Give a live channel the transcript of a takeover that just ended.
+///
+/// The transcript goes in as a user message followed by an acknowledgement,
+/// so the history still alternates and the next inbound message starts a
+/// fresh turn.
+pub async fn hand_back(state: &ChannelState, transcript: String) -> Result<()> {
+    let acknowledgement = state
+        .deps
+        .runtime_config
+        .prompts
+        .load()
+        .render_system_takeover_hand_back()?;
+
+    let mut history = state.history.write().await;
+    history.push(Message::from(transcript));
+    history.push(Message::Assistant {
+        id: None,
+        content: OneOrMany::one(AssistantContent::text(acknowledgement)),
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, sender_name: Option<&str>, content: &str) -> ConversationMessage {
+        ConversationMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            channel_id: "discord:1".into(),
+            role: role.into(),
+            sender_name: sender_name.map(String::from),
+            sender_id: None,
+            content: content.into(),
+            metadata: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn transcript_attributes_operator_replies() {
+        let prompts = PromptEngine::new("en").expect("failed to build prompt engine");
+        assert!(
+            render_takeover_transcript(&prompts, &[])
+                .expect("empty transcript should render")
+                .is_none()
+        );
+
+        let mut operator_reply = message("assistant", Some("Spacebot"), "Refund is on its way.");
+        operator_reply.metadata =
+            Some(serde_json::json!({ (SENT_BY_OPERATOR_KEY): true }).to_string());
+        let transcript = render_takeover_transcript(
+            &prompts,
+            &[
+                message("user", Some("Jamie"), "I was charged twice"),
+                operator_reply,
+                message("system", None, "worker finished"),
+            ],
+        )
+        .expect("transcript should render")
+        .expect("transcript should not be empty");
+
+        assert!(transcript.starts_with("[System: A human operator took over"));
+        assert!(transcript.contains("Jamie: I was charged twice"));
+        assert!(transcript.contains("operator (as you): Refund is on its way."));
+        assert!(!transcript.contains("worker finished"));
+    }
+}
@@ -1,17 +1,21 @@
 //! Worker: Independent task execution process.
 
 use crate::agent::compactor::estimate_history_tokens;
+use crate::agent::worker_mailbox::WorkerMailbox;
 use crate::config::BrowserConfig;
 use crate::error::Result;
 use crate::hooks::SpacebotHook;
 use crate::llm::SpacebotModel;
+use crate::llm::budget::SpendScope;
 use crate::llm::routing::{is_context_overflow_error, is_retriable_error};
+use crate::sandbox::container::ContainerSession;
 use crate::{AgentDeps, ChannelId, ProcessId, ProcessType, WorkerId};
 use rig::agent::AgentBuilder;
 use rig::completion::CompletionModel;
 use std::collections::HashMap;
 use std::fmt::Write as _;
 use std::path::PathBuf;
+use std::sync::Arc;
 use tokio::sync::{mpsc, watch};
 use uuid::Uuid;
 
@@ -46,6 +50,10 @@ const TRANSIENT_RETRY_BASE_DELAY: std::time::Duration = std::time::Duration::fro
 /// without completing the task.
 const MAX_SEGMENTS: usize = 10;
 
+/// Max correction prompts when the result doesn't match the caller's
+/// `result_schema` before the worker fails.
+const MAX_SCHEMA_RETRIES: usize = 2;
+
 /// Worker state machine.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum WorkerState {
@@ -90,6 +98,15 @@ pub struct Worker {
     pub status_rx: watch::Receiver<String>,
     /// Prior conversation history for resumed workers (set by `resume_interactive`).
     pub prior_history: Option<Vec<rig::message::Message>>,
+    /// JSON Schema the initial result must satisfy. When set, the result is
+    /// delivered as compact JSON.
+    pub result_schema: Option<serde_json::Value>,
+    /// The channel's worker mailbox, backing the `message_worker` tool.
+    /// `None` for workers without a parent channel.
+    pub mailbox: Option<WorkerMailbox>,
+    /// Container the worker's shell commands run in (`container` workers).
+    /// File tools are confined to its bound directory.
+    pub container: Option<Arc<ContainerSession>>,
 }
 
 impl Worker {
@@ -135,6 +152,9 @@ impl Worker {
                 status_tx,
                 status_rx,
                 prior_history: None,
+                result_schema: None,
+                mailbox: None,
+                container: None,
             },
             inject_tx,
         )
@@ -249,6 +269,24 @@ impl Worker {
         (worker, input_tx, inject_tx)
     }
 
+    /// Require the initial result to match a JSON Schema.
+    pub fn with_result_schema(mut self, schema: Option<serde_json::Value>) -> Self {
+        self.result_schema = schema;
+        self
+    }
+
+    /// Give the worker a `message_worker` tool onto its channel's workers.
+    pub fn with_mailbox(mut self, mailbox: WorkerMailbox) -> Self {
+        self.mailbox = Some(mailbox);
+        self
+    }
+
+    /// Run the worker's shell and file tools against a container.
+    pub fn with_container(mut self, session: Arc<ContainerSession>) -> Self {
+        self.container = Some(session);
+        self
+    }
+
     /// Check if the worker can transition to a new state.
     pub fn can_transition_to(&self, target: WorkerState) -> bool {
         use WorkerState::*;
@@ -297,6 +335,17 @@ impl Worker {
 
         let mcp_tools = self.deps.mcp_manager.get_tools().await;
 
+        let (workspace, sandbox) = match &self.container {
+            Some(session) => (
+                session.directory().to_path_buf(),
+                Arc::new(self.deps.sandbox.for_container(session.clone())),
+            ),
+            None => (
+                self.deps.runtime_config.workspace_dir.clone(),
+                self.deps.sandbox.clone(),
+            ),
+        };
+
         // Create per-worker ToolServer with task tools
         let worker_tool_server = crate::tools::create_worker_tool_server(
             self.deps.agent_id.clone(),
@@ -307,18 +356,75 @@ impl Worker {
             self.browser_config.clone(),
             self.screenshot_dir.clone(),
             self.brave_search_key.clone(),
-            self.deps.runtime_config.workspace_dir.clone(),
-            self.deps.sandbox.clone(),
+            workspace,
+            sandbox,
             mcp_tools,
             self.deps.runtime_config.clone(),
         );
+        if let Some(mailbox) = &self.mailbox
+            && let Err(error) = worker_tool_server
+                .add_tool(crate::tools::MessageWorkerTool::new(
+                    mailbox.clone(),
+                    self.id,
+                    self.deps.runtime_config.clone(),
+                ))
+                .await
+        {
+            tracing::warn!(worker_id = %self.id, %error, "failed to register message_worker tool");
+        }
+        if !crate::links::links_for_agent(&self.deps.links.load(), &self.deps.agent_id).is_empty() {
+            let messenger = crate::tools::SendAgentMessageTool::new(
+                self.deps.agent_id.clone(),
+                self.deps.links.clone(),
+                self.deps.agent_names.clone(),
+                self.deps.task_store_registry.clone(),
+                crate::conversation::history::ConversationLogger::new(
+                    self.deps.sqlite_pool.clone(),
+                ),
+            );
+            if let Err(error) = worker_tool_server
+                .add_tool(
+                    crate::tools::DelegateToAgentTool::new(messenger.clone()).for_worker(self.id),
+                )
+                .await
+            {
+                tracing::warn!(worker_id = %self.id, %error, "failed to register delegate_to_agent tool");
+            }
+            if !crate::teams::teams_coordinated_by(&self.deps.teams.load(), &self.deps.agent_id)
+                .is_empty()
+                && let Err(error) = worker_tool_server
+                    .add_tool(crate::tools::TeamDispatchTool::new(
+                        messenger,
+                        self.deps.teams.clone(),
+                        crate::teams::TeamRunStore::new(self.deps.sqlite_pool.clone()),
+                        self.id,
+                    ))
+                    .await
+            {
+                tracing::warn!(worker_id = %self.id, %error, "failed to register team_dispatch tool");
+            }
+        }
 
         let routing = self.deps.runtime_config.routing.load();
-        let model_name = routing.resolve(ProcessType::Worker, None).to_string();
-        let model = SpacebotModel::make(&self.deps.llm_manager, &model_name)
+        let model_name = routing
+            .resolve(ProcessType::Worker, self.channel_id.as_deref())
+            .to_string();
+        let mut model = SpacebotModel::make(&self.deps.llm_manager, &model_name)
             .with_context(&*self.deps.agent_id, "worker")
-            .with_worker_type("builtin")
-            .with_routing((**routing).clone());
+            .with_process_id(self.id.to_string())
+            .with_worker_type(if self.container.is_some() {
+                "container"
+            } else {
+                "builtin"
+            })
+            .with_routing((**routing).clone())
+            .with_budget(
+                self.deps
+                    .budget_guard(SpendScope::worker(self.id, self.channel_id.clone())),
+            );
+        if let Some(channel_id) = &self.channel_id {
+            model = model.with_channel(&**channel_id);
+        }
 
         let agent = AgentBuilder::new(model)
             .preamble(&self.system_prompt)
@@ -522,6 +628,62 @@ impl Worker {
             }
         }
 
+        // Structured output: parse the result against the caller's schema,
+        // asking the model to correct itself before giving up.
+        if !resuming && let Some(schema) = self.result_schema.clone() {
+            let mut attempts = 0;
+            loop {
+                match crate::agent::structured_output::coerce(&result, &schema) {
+                    Ok(value) => {
+                        result = value.to_string();
+                        break;
+                    }
+                    Err(errors) if attempts < MAX_SCHEMA_RETRIES => {
+                        attempts += 1;
+                        tracing::warn!(
+                            worker_id = %self.id,
+                            attempt = attempts,
+                            errors = ?errors,
+                            "worker result does not match result_schema, requesting correction"
+                        );
+                        self.hook.send_status(format!(
+                            "correcting result ({attempts}/{MAX_SCHEMA_RETRIES})"
+                        ));
+                        let prompt_engine = self.deps.runtime_config.prompts.load();
+                        let correction = prompt_engine
+                            .render_system_result_schema_correction(&schema, &errors)?;
+                        match self
+                            .hook
+                            .prompt_once(&agent, &mut history, &correction)
+                            .await
+                        {
+                            Ok(response) => result = response,
+                            Err(error) => {
+                                self.state = WorkerState::Failed;
+                                self.hook.send_status("failed");
+                                self.write_failure_log(&history, &error.to_string());
+                                self.persist_transcript(&compacted_history, &history).await;
+                                tracing::error!(worker_id = %self.id, %error, "worker correction prompt failed");
+                                return Err(crate::error::AgentError::Other(error.into()).into());
+                            }
+                        }
+                    }
+                    Err(errors) => {
+                        let reason = format!(
+                            "result does not match result_schema after {MAX_SCHEMA_RETRIES} corrections: {}",
+                            errors.join("; ")
+                        );
+                        self.state = WorkerState::Failed;
+                        self.hook.send_status("failed (invalid result)");
+                        self.write_failure_log(&history, &reason);
+                        self.persist_transcript(&compacted_history, &history).await;
+                        tracing::error!(worker_id = %self.id, %reason, "worker result failed schema validation");
+                        return Err(crate::error::AgentError::Other(anyhow::anyhow!(reason)).into());
+                    }
+                }
+            }
+        }
+
         // For interactive workers, enter a follow-up loop
         let mut follow_up_failure: Option<String> = None;
         if let Some(mut input_rx) = self.input_rx.take() {
@@ -0,0 +1,277 @@
+//! Parallel worker batches.
+//!
+//! `spawn_worker_batch` hands a list of tasks to a coordinator task that runs
+//! them as builtin workers, at most `concurrency` at a time. Batch members
+//! complete with `notify: false`, so instead of retriggering the channel once
+//! per worker the coordinator emits a single `WorkerBatchComplete` event with
+//! every result once the last one finishes.
+
+use crate::agent::channel::ChannelState;
+use crate::agent::channel_dispatch::spawn_batch_worker_from_state;
+use crate::error::AgentError;
+use crate::{BroadcastRecvResult, ProcessEvent, WorkerId};
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::Instrument as _;
+
+/// Upper bound on tasks in one batch.
+pub const MAX_BATCH_TASKS: usize = 20;
+
+/// How long the coordinator waits for an event before checking whether an
+/// in-flight worker exited without its completion reaching the batch (e.g.
+/// after the event receiver lagged).
+const RECONCILE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Outcome of one task in a batch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerBatchResult {
+    pub task: String,
+    /// `None` if the worker could not be spawned.
+    pub worker_id: Option<WorkerId>,
+    pub success: bool,
+    pub result: String,
+}
+
+impl WorkerBatchResult {
+    fn failed(task: String, worker_id: Option<WorkerId>, result: impl Into<String>) -> Self {
+        Self {
+            task,
+            worker_id,
+            success: false,
+            result: result.into(),
+        }
+    }
+}
+
+/// A set of tasks to run as one batch on a channel.
+#[derive(Debug, Clone)]
+pub struct WorkerBatch {
+    pub id: uuid::Uuid,
+    pub tasks: Vec<String>,
+    pub suggested_skills: Vec<String>,
+    pub result_schema: Option<serde_json::Value>,
+    pub concurrency: usize,
+}
+
+impl WorkerBatch {
+    /// Start the coordinator in the background.
+    pub fn spawn(self, state: ChannelState) {
+        // Subscribe before any member is spawned so no completion is missed.
+        let event_rx = state.deps.event_tx.subscribe();
+        let span = tracing::info_span!(
+            "worker_batch.run",
+            batch_id = %self.id,
+            channel_id = %state.channel_id,
+        );
+        tokio::spawn(self.run(state, event_rx).instrument(span));
+    }
+
+    async fn run(self, state: ChannelState, mut event_rx: broadcast::Receiver<ProcessEvent>) {
+        let suggested_skills: Vec<&str> =
+            self.suggested_skills.iter().map(String::as_str).collect();
+        let concurrency = self.concurrency.max(1);
+        let mut queue: VecDeque<(usize, String)> = self.tasks.iter().cloned().enumerate().collect();
+        let mut results: Vec<Option<WorkerBatchResult>> = vec![None; self.tasks.len()];
+        let mut in_flight: HashMap<WorkerId, (usize, String)> = HashMap::new();
+
+        loop {
+            while in_flight.len() < concurrency {
+                let Some((index, task)) = queue.pop_front() else {
+                    break;
+                };
+                match spawn_batch_worker_from_state(
+                    &state,
+                    &task,
+                    &suggested_skills,
+                    self.result_schema.clone(),
+                )
+                .await
+                {
+                    Ok(worker_id) => {
+                        in_flight.insert(worker_id, (index, task));
+                    }
+                    // The channel is at its worker limit; wait for one of
+                    // ours to finish and free a slot.
+                    Err(AgentError::WorkerLimitReached { .. }) if !in_flight.is_empty() => {
+                        queue.push_front((index, task));
+                        break;
+                    }
+                    Err(error) => {
+                        tracing::warn!(%error, task = %task, "batch worker failed to spawn");
+                        results[index] = Some(WorkerBatchResult::failed(
+                            task,
+                            None,
+                            format!("Worker failed to spawn: {error}"),
+                        ));
+                    }
+                }
+            }
+
+            if in_flight.is_empty() {
+                break;
+            }
+
+            match tokio::time::timeout(RECONCILE_INTERVAL, event_rx.recv()).await {
+                Ok(received) => match crate::classify_broadcast_recv_result(received) {
+                    BroadcastRecvResult::Event(ProcessEvent::WorkerComplete {
+                        worker_id,
+                        result,
+                        success,
+                        ..
+                    }) => {
+                        if let Some((index, task)) = in_flight.remove(&worker_id) {
+                            results[index] = Some(WorkerBatchResult {
+                                task,
+                                worker_id: Some(worker_id),
+                                success,
+                                result,
+                            });
+                        }
+                    }
+                    BroadcastRecvResult::Event(_) => {}
+                    BroadcastRecvResult::Lagged(count) => {
+                        tracing::warn!(count, "worker batch event receiver lagged");
+                    }
+                    BroadcastRecvResult::Closed => {
+                        for (worker_id, (index, task)) in in_flight.drain() {
+                            results[index] = Some(WorkerBatchResult::failed(
+                                task,
+                                Some(worker_id),
+                                "Event bus closed before the worker finished.",
+                            ));
+                        }
+                        for (index, task) in queue.drain(..) {
+                            results[index] = Some(WorkerBatchResult::failed(
+                                task,
+                                None,
+                                "Event bus closed before the worker started.",
+                            ));
+                        }
+                        break;
+                    }
+                },
+                Err(_) => {
+                    // No events pending, so any completion for a worker that
+                    // has left `worker_handles` was dropped by a lag.
+                    let handles = state.worker_handles.read().await;
+                    let exited: Vec<WorkerId> = in_flight
+                        .keys()
+                        .filter(|worker_id| !handles.contains_key(*worker_id))
+                        .copied()
+                        .collect();
+                    drop(handles);
+                    for worker_id in exited {
+                        if let Some((index, task)) = in_flight.remove(&worker_id) {
+                            results[index] = Some(WorkerBatchResult::failed(
+                                task,
+                                Some(worker_id),
+                                "Worker exited but its result did not reach the batch.",
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        let results: Vec<WorkerBatchResult> = results
+            .into_iter()
+            .zip(self.tasks)
+            .map(|(result, task)| {
+                result.unwrap_or_else(|| WorkerBatchResult::failed(task, None, "Task never ran."))
+            })
+            .collect();
+        let summary = summarize(&results);
+        let success = results.iter().all(|result| result.success);
+
+        tracing::info!(tasks = results.len(), %summary, "worker batch complete");
+
+        state
+            .deps
+            .event_tx
+            .send(ProcessEvent::WorkerBatchComplete {
+                agent_id: state.deps.agent_id.clone(),
+                batch_id: self.id,
+                channel_id: state.channel_id.clone(),
+                results,
+                summary,
+                success,
+            })
+            .ok();
+    }
+}
+
+/// One-line tally of a finished batch.
+pub fn summarize(results: &[WorkerBatchResult]) -> String {
+    let succeeded = results.iter().filter(|result| result.success).count();
+    let mut summary = format!("{succeeded} of {} tasks succeeded", results.len());
+    let failed: Vec<String> = results
+        .iter()
+        .enumerate()
+        .filter(|(_, result)| !result.success)
+        .map(|(index, _)| format!("#{}", index + 1))
+        .collect();
+    if !failed.is_empty() {
+        summary.push_str(&format!("; failed: {}", failed.join(", ")));
+    }
+    summary.push('.');
+    summary
+}
+
+/// Render a finished batch as the result text relayed to the channel.
+pub fn render(summary: &str, results: &[WorkerBatchResult]) -> String {
+    let mut output = summary.to_string();
+    for (index, result) in results.iter().enumerate() {
+        let status = if result.success {
+            "completed"
+        } else {
+            "failed"
+        };
+        let worker = result
+            .worker_id
+            .map(|worker_id| format!(", worker {worker_id}"))
+            .unwrap_or_default();
+        output.push_str(&format!(
+            "\n\n[{}] {} ({status}{worker})\n{}",
+            index + 1,
+            result.task,
+            result.result
+        ));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(task: &str, success: bool) -> WorkerBatchResult {
+        WorkerBatchResult {
+            task: task.to_string(),
+            worker_id: None,
+            success,
+            result: format!("{task} done"),
+        }
+    }
+
+    #[test]
+    fn summary_lists_failed_tasks() {
+        let results = vec![result("a", true), result("b", false), result("c", false)];
+        assert_eq!(
+            summarize(&results),
+            "1 of 3 tasks succeeded; failed: #2, #3."
+        );
+        assert_eq!(summarize(&[result("a", true)]), "1 of 1 tasks succeeded.");
+    }
+
+    #[test]
+    fn render_numbers_each_result() {
+        let results = vec![result("lint", true), result("test", false)];
+        let rendered = render(&summarize(&results), &results);
+        assert!(rendered.starts_with("1 of 2 tasks succeeded; failed: #2."));
+        assert!(rendered.contains("\n\n[1] lint (completed)\nlint done"));
+        assert!(rendered.contains("\n\n[2] test (failed)\ntest done"));
+    }
+}
@@ -0,0 +1,224 @@
+//! Worker mailbox: mediated message delivery to a channel's workers.
+//!
+//! The channel's `route` tool and workers' `message_worker` tool both deliver
+//! through here, so a message takes the same path whoever sends it: follow-up
+//! input for an idle interactive worker, context injection at the next turn
+//! boundary for a running one. Only workers of the owning channel are
+//! reachable.
+
+use crate::agent::channel::ChannelState;
+use crate::agent::status::StatusBlock;
+use crate::{ChannelId, WorkerId};
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{RwLock, mpsc};
+
+type SenderMap = Arc<RwLock<HashMap<WorkerId, mpsc::Sender<String>>>>;
+
+/// How a message reached its worker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    /// Delivered as follow-up input to an idle interactive worker.
+    Input,
+    /// Injected into a running worker at its next turn boundary.
+    Injected,
+    /// Not delivered: the worker is running and only accepts input once idle
+    /// (e.g. OpenCode workers, which don't support mid-flight injection).
+    Busy,
+}
+
+/// Error type for mailbox delivery.
+#[derive(Debug, thiserror::Error)]
+pub enum MailboxError {
+    #[error("Worker {0} not found. It may have already completed or been cancelled.")]
+    NotFound(WorkerId),
+    #[error("Worker {0} has stopped accepting input (channel closed)")]
+    InputClosed(WorkerId),
+    #[error("Worker {0} has stopped running (injection channel closed)")]
+    InjectionClosed(WorkerId),
+}
+
+/// Another worker in the same channel, as seen by a would-be sender.
+#[derive(Debug, Clone)]
+pub struct PeerWorker {
+    pub id: WorkerId,
+    pub task: String,
+    pub status: String,
+    pub interactive: bool,
+}
+
+/// Delivery handle onto one channel's worker pool.
+#[derive(Clone)]
+pub struct WorkerMailbox {
+    channel_id: ChannelId,
+    status_block: Arc<RwLock<StatusBlock>>,
+    worker_inputs: SenderMap,
+    worker_injections: SenderMap,
+}
+
+impl std::fmt::Debug for WorkerMailbox {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WorkerMailbox")
+            .field("channel_id", &self.channel_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WorkerMailbox {
+    pub fn from_state(state: &ChannelState) -> Self {
+        Self {
+            channel_id: state.channel_id.clone(),
+            status_block: state.status_block.clone(),
+            worker_inputs: state.worker_inputs.clone(),
+            worker_injections: state.worker_injections.clone(),
+        }
+    }
+
+    pub fn channel_id(&self) -> &ChannelId {
+        &self.channel_id
+    }
+
+    /// Active workers in the channel other than `except`.
+    pub async fn peers(&self, except: Option<WorkerId>) -> Vec<PeerWorker> {
+        let status = self.status_block.read().await;
+        status
+            .active_workers
+            .iter()
+            .filter(|worker| Some(worker.id) != except)
+            .map(|worker| PeerWorker {
+                id: worker.id,
+                task: worker.task.clone(),
+                status: worker.status.clone(),
+                interactive: worker.interactive,
+            })
+            .collect()
+    }
+
+    /// Deliver a message to a worker in this channel.
+    pub async fn deliver(
+        &self,
+        worker_id: WorkerId,
+        message: String,
+    ) -> Result<Delivery, MailboxError> {
+        // Check the status block to determine the worker's actual state.
+        // Using sender map presence alone is unreliable: interactive workers
+        // register both `worker_inputs` and `worker_injections` at spawn
+        // time, so the input sender is always present regardless of whether
+        // the worker is idle or running.
+        let worker_is_idle = {
+            let status = self.status_block.read().await;
+            status
+                .active_workers
+                .iter()
+                .find(|worker| worker.id == worker_id)
+                .map(|worker| worker.status == "idle")
+        };
+
+        match worker_is_idle {
+            // Worker is idle (WaitingForInput) — deliver as interactive follow-up.
+            Some(true) => {
+                let input_tx = self.worker_inputs.read().await.get(&worker_id).cloned();
+                if let Some(input_tx) = input_tx {
+                    input_tx
+                        .send(message)
+                        .await
+                        .map_err(|_| MailboxError::InputClosed(worker_id))?;
+                    return Ok(Delivery::Input);
+                }
+                // Worker is idle but has no input channel — shouldn't happen
+                // for interactive workers, but fall through to not found.
+            }
+            // Worker is running — use context injection.
+            Some(false) => {
+                let inject_tx = self.worker_injections.read().await.get(&worker_id).cloned();
+                if let Some(inject_tx) = inject_tx {
+                    inject_tx
+                        .send(message)
+                        .await
+                        .map_err(|_| MailboxError::InjectionClosed(worker_id))?;
+                    return Ok(Delivery::Injected);
+                }
+
+                // Worker is running but has no injection channel (e.g. OpenCode
+                // workers only support interactive follow-ups, not mid-flight
+                // injection).
+                if self.worker_inputs.read().await.contains_key(&worker_id) {
+                    return Ok(Delivery::Busy);
+                }
+            }
+            // Worker not found in status block.
+            None => {}
+        }
+
+        Err(MailboxError::NotFound(worker_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mailbox() -> WorkerMailbox {
+        WorkerMailbox {
+            channel_id: Arc::from("channel"),
+            status_block: Arc::new(RwLock::new(StatusBlock::new())),
+            worker_inputs: Arc::default(),
+            worker_injections: Arc::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn running_workers_receive_injections_and_idle_workers_input() {
+        let mailbox = mailbox();
+        let worker_id = uuid::Uuid::new_v4();
+        let (input_tx, mut input_rx) = mpsc::channel(1);
+        let (inject_tx, mut inject_rx) = mpsc::channel(1);
+        mailbox
+            .worker_inputs
+            .write()
+            .await
+            .insert(worker_id, input_tx);
+        mailbox
+            .worker_injections
+            .write()
+            .await
+            .insert(worker_id, inject_tx);
+        mailbox
+            .status_block
+            .write()
+            .await
+            .add_worker(worker_id, "consume", true, true);
+
+        let delivery = mailbox.deliver(worker_id, "batch 1".into()).await.unwrap();
+        assert_eq!(delivery, Delivery::Injected);
+        assert_eq!(inject_rx.recv().await.as_deref(), Some("batch 1"));
+
+        mailbox.status_block.write().await.active_workers[0].status = "idle".into();
+        let delivery = mailbox.deliver(worker_id, "batch 2".into()).await.unwrap();
+        assert_eq!(delivery, Delivery::Input);
+        assert_eq!(input_rx.recv().await.as_deref(), Some("batch 2"));
+    }
+
+    #[tokio::test]
+    async fn unknown_workers_are_not_found_and_peers_exclude_sender() {
+        let mailbox = mailbox();
+        let sender = uuid::Uuid::new_v4();
+        let peer = uuid::Uuid::new_v4();
+        {
+            let mut status = mailbox.status_block.write().await;
+            status.add_worker(sender, "produce", true, false);
+            status.add_worker(peer, "consume", true, true);
+        }
+
+        let peers = mailbox.peers(Some(sender)).await;
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peers[0].id, peer);
+
+        let error = mailbox
+            .deliver(uuid::Uuid::new_v4(), "hello".into())
+            .await
+            .unwrap_err();
+        assert!(matches!(error, MailboxError::NotFound(_)));
+    }
+}
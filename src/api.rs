@@ -4,33 +4,53 @@
 //! managing agents, viewing status, and interacting with the system.
 //! Includes an SSE endpoint for realtime event streaming.
 
+mod access;
 pub mod agents;
+mod audit;
 mod bindings;
 mod channels;
+mod cli_workers;
+mod cluster;
 mod config;
+mod config_history;
 mod cortex;
 mod cron;
+mod experiments;
 mod factory;
+mod health;
+pub mod ids;
 mod ingest;
+mod jobs;
 mod links;
 mod mcp;
 mod memories;
 mod messaging;
 mod models;
 mod opencode_proxy;
+mod outbox;
+mod profiles;
 mod projects;
+mod prompts;
 mod providers;
+mod rate_limit;
+mod replay;
 mod secrets;
 mod server;
 mod settings;
 mod skills;
 pub(crate) mod ssh;
 mod state;
+mod storage;
 mod system;
 mod tasks;
+mod teams;
 mod tools;
+mod traces;
+mod usage;
+mod users;
 mod webchat;
 mod workers;
 
+pub use rate_limit::RateLimiter;
 pub use server::start_http_server;
 pub use state::{AgentInfo, ApiEvent, ApiState};
@@ -0,0 +1,198 @@
+//! API users and roles.
+
+use super::state::ApiState;
+
+use crate::access::{AccessStore, ApiUser};
+use crate::audit::AuditEvent;
+use crate::config::ApiRole;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// How the caller authenticated.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum CallerKind {
+    /// A key from `[api.auth]`.
+    Key,
+    /// A token issued to an API user.
+    User,
+    /// No credentials; only possible while `[api.auth]` has no keys.
+    Anonymous,
+}
+
+/// The authenticated caller, attached to each request by the auth middleware.
+#[derive(Debug, Clone, Serialize)]
+pub(super) struct ApiCaller {
+    pub(super) kind: CallerKind,
+    pub(super) name: String,
+    pub(super) role: ApiRole,
+}
+
+#[derive(Serialize)]
+pub(super) struct ApiUsersResponse {
+    users: Vec<ApiUser>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct CreateApiUserRequest {
+    name: String,
+    role: ApiRole,
+}
+
+#[derive(Serialize)]
+pub(super) struct ApiUserTokenResponse {
+    user: ApiUser,
+    /// Shown only in this response; the server keeps a hash.
+    token: String,
+}
+
+#[derive(Deserialize)]
+pub(super) struct UpdateApiUserRequest {
+    role: ApiRole,
+}
+
+fn access_store(state: &ApiState) -> Result<Arc<AccessStore>, StatusCode> {
+    (**state.access_store.load())
+        .clone()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)
+}
+
+fn internal_error(error: crate::Error) -> StatusCode {
+    tracing::error!(%error, "API user operation failed");
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+/// GET /access/me — who the request is authenticated as, so the dashboard
+/// can hide controls the caller isn't allowed to use.
+pub(super) async fn current_caller(Extension(caller): Extension<ApiCaller>) -> Json<ApiCaller> {
+    Json(caller)
+}
+
+/// GET /access/users — list API users.
+pub(super) async fn list_api_users(
+    State(state): State<Arc<ApiState>>,
+) -> Result<Json<ApiUsersResponse>, StatusCode> {
+    let users = access_store(&state)?.list().await.map_err(internal_error)?;
+    Ok(Json(ApiUsersResponse { users }))
+}
+
+/// POST /access/users — create a user and return its token.
+///
+/// Refused while `[api.auth]` has no keys: the API is open to everyone then,
+/// so a token would restrict nothing.
+pub(super) async fn create_api_user(
+    State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
+    Json(request): Json<CreateApiUserRequest>,
+) -> Result<Json<ApiUserTokenResponse>, StatusCode> {
+    let name = request.name.trim();
+    if name.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if !state.auth.load().is_enabled() {
+        return Err(StatusCode::CONFLICT);
+    }
+    let store = access_store(&state)?;
+    if store.name_exists(name).await.map_err(internal_error)? {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let (user, token) = store
+        .create(name, request.role)
+        .await
+        .map_err(internal_error)?;
+    tracing::info!(user = %user.name, role = %user.role, by = %caller.name, "API user created");
+    super::audit::record(
+        &state,
+        &caller,
+        AuditEvent::new("api_user.create", &user.name).after(format!("role {}", user.role)),
+    )
+    .await;
+    Ok(Json(ApiUserTokenResponse { user, token }))
+}
+
+/// PUT /access/users/{id} — change a user's role.
+pub(super) async fn update_api_user(
+    State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateApiUserRequest>,
+) -> Result<Json<ApiUser>, StatusCode> {
+    let store = access_store(&state)?;
+    let previous = store
+        .get(&id)
+        .await
+        .map_err(internal_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let user = store
+        .set_role(&id, request.role)
+        .await
+        .map_err(internal_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    tracing::info!(user = %user.name, role = %user.role, by = %caller.name, "API user role changed");
+    super::audit::record(
+        &state,
+        &caller,
+        AuditEvent::new("api_user.update", &user.name)
+            .before(format!("role {}", previous.role))
+            .after(format!("role {}", user.role)),
+    )
+    .await;
+    Ok(Json(user))
+}
+
+/// POST /access/users/{id}/token — replace a user's token.
+pub(super) async fn rotate_api_user_token(
+    State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiUserTokenResponse>, StatusCode> {
+    let store = access_store(&state)?;
+    let token = store
+        .rotate_token(&id)
+        .await
+        .map_err(internal_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let user = store
+        .get(&id)
+        .await
+        .map_err(internal_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    tracing::info!(user = %user.name, by = %caller.name, "API user token rotated");
+    super::audit::record(
+        &state,
+        &caller,
+        AuditEvent::new("api_user.rotate_token", &user.name),
+    )
+    .await;
+    Ok(Json(ApiUserTokenResponse { user, token }))
+}
+
+/// DELETE /access/users/{id} — remove a user; its token stops working.
+pub(super) async fn delete_api_user(
+    State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    let store = access_store(&state)?;
+    let user = store
+        .get(&id)
+        .await
+        .map_err(internal_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if !store.delete(&id).await.map_err(internal_error)? {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    tracing::info!(user = %user.name, by = %caller.name, "API user deleted");
+    super::audit::record(
+        &state,
+        &caller,
+        AuditEvent::new("api_user.delete", &user.name).before(format!("role {}", user.role)),
+    )
+    .await;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,11 +1,14 @@
+use super::access::ApiCaller;
+use super::ids::AgentId;
 use super::state::{AgentInfo, ApiState};
 
 use crate::agent::cortex::CortexLogger;
+use crate::audit::{AuditEvent, summarize_text};
 use crate::conversation::channels::ChannelStore;
 
-use axum::Json;
 use axum::extract::{Query, State};
 use axum::http::StatusCode;
+use axum::{Extension, Json};
 use serde::{Deserialize, Serialize};
 use sqlx::Row as _;
 use std::collections::{HashMap, HashSet};
@@ -107,14 +110,22 @@ pub(super) struct IdentityResponse {
     role: Option<String>,
 }
 
+#[derive(Serialize)]
+pub(super) struct IdentityUpdateResponse {
+    #[serde(flatten)]
+    identity: IdentityResponse,
+    /// Config history versions recorded for the files that changed.
+    versions: Vec<u64>,
+}
+
 #[derive(Deserialize)]
 pub(super) struct IdentityQuery {
-    agent_id: String,
+    agent_id: AgentId,
 }
 
 #[derive(Deserialize)]
 pub(super) struct IdentityUpdateRequest {
-    agent_id: String,
+    agent_id: AgentId,
     soul: Option<String>,
     identity: Option<String>,
     role: Option<String>,
@@ -122,7 +133,7 @@ pub(super) struct IdentityUpdateRequest {
 
 #[derive(Deserialize)]
 pub(super) struct AgentOverviewQuery {
-    agent_id: String,
+    agent_id: AgentId,
 }
 
 #[derive(Deserialize)]
@@ -141,7 +152,7 @@ pub struct CreateAgentResult {
 
 #[derive(Deserialize)]
 pub(super) struct UpdateAgentRequest {
-    agent_id: String,
+    agent_id: AgentId,
     display_name: Option<String>,
     role: Option<String>,
     gradient_start: Option<String>,
@@ -150,17 +161,17 @@ pub(super) struct UpdateAgentRequest {
 
 #[derive(Deserialize)]
 pub(super) struct DeleteAgentQuery {
-    agent_id: String,
+    agent_id: AgentId,
 }
 
 #[derive(Deserialize)]
 pub(super) struct AgentMcpQuery {
-    agent_id: String,
+    agent_id: AgentId,
 }
 
 #[derive(Deserialize)]
 pub(super) struct ReconnectMcpRequest {
-    agent_id: String,
+    agent_id: AgentId,
     server_name: String,
 }
 
@@ -171,12 +182,12 @@ pub(super) struct AgentMcpResponse {
 
 #[derive(Deserialize)]
 pub(super) struct WarmupQuery {
-    agent_id: Option<String>,
+    agent_id: Option<AgentId>,
 }
 
 #[derive(Deserialize)]
 pub(super) struct WarmupTriggerRequest {
-    agent_id: Option<String>,
+    agent_id: Option<AgentId>,
     #[serde(default)]
     force: bool,
 }
@@ -286,7 +297,7 @@ pub(super) async fn list_agent_mcp(
 ) -> Result<Json<AgentMcpResponse>, StatusCode> {
     let managers = state.mcp_managers.load();
     let manager = managers
-        .get(&query.agent_id)
+        .get(query.agent_id.as_str())
         .cloned()
         .ok_or(StatusCode::NOT_FOUND)?;
     let servers = manager.statuses().await;
@@ -300,7 +311,7 @@ pub(super) async fn reconnect_agent_mcp(
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     let managers = state.mcp_managers.load();
     let manager = managers
-        .get(&request.agent_id)
+        .get(request.agent_id.as_str())
         .cloned()
         .ok_or(StatusCode::NOT_FOUND)?;
 
@@ -333,10 +344,10 @@ pub(super) async fn get_warmup_status(
 
     let mut statuses = if let Some(agent_id) = query.agent_id {
         let runtime_config = runtime_configs
-            .get(&agent_id)
+            .get(agent_id.as_str())
             .ok_or(StatusCode::NOT_FOUND)?;
         vec![WarmupStatusEntry {
-            agent_id,
+            agent_id: agent_id.into_inner(),
             status: hydrate_warmup_status(runtime_config),
         }]
     } else {
@@ -413,6 +424,7 @@ pub(super) async fn trigger_warmup(
         let task_store_registry = state.task_store_registry.clone();
         let injection_tx = state.injection_tx.clone();
         let humans = (**state.agent_humans.load()).clone();
+        let teams = (**state.agent_teams.load()).clone();
         tokio::spawn(async move {
             let (event_tx, memory_event_tx) = crate::create_process_event_buses();
             let project_store =
@@ -434,10 +446,12 @@ pub(super) async fn trigger_warmup(
                 links: Arc::new(arc_swap::ArcSwap::from_pointee(Vec::new())),
                 agent_names: Arc::new(std::collections::HashMap::new()),
                 humans: Arc::new(arc_swap::ArcSwap::from_pointee(humans)),
+                teams: Arc::new(arc_swap::ArcSwap::from_pointee(teams)),
                 task_store_registry,
                 process_control_registry: Arc::new(
                     crate::agent::process_control::ProcessControlRegistry::new(),
                 ),
+                branch_cache: Arc::new(crate::agent::branch_cache::BranchResultCache::new()),
                 injection_tx,
             };
             let logger = CortexLogger::new(sqlite_pool);
@@ -455,17 +469,26 @@ pub(super) async fn trigger_warmup(
 /// Create a new agent and initialize it live (directories, databases, memory, identity, cron, cortex).
 pub(super) async fn create_agent(
     State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
     Json(request): Json<CreateAgentRequest>,
 ) -> (StatusCode, Json<serde_json::Value>) {
     match create_agent_internal(&state, request).await {
-        Ok(result) => (
-            StatusCode::CREATED,
-            Json(serde_json::json!({
-                "success": result.success,
-                "agent_id": result.agent_id,
-                "message": result.message
-            })),
-        ),
+        Ok(result) => {
+            super::audit::record(
+                &state,
+                &caller,
+                AuditEvent::new("agent.create", &result.agent_id),
+            )
+            .await;
+            (
+                StatusCode::CREATED,
+                Json(serde_json::json!({
+                    "success": result.success,
+                    "agent_id": result.agent_id,
+                    "message": result.message
+                })),
+            )
+        }
         Err(message) => {
             let status = if message.contains("already exists") {
                 StatusCode::CONFLICT
@@ -606,15 +629,24 @@ pub async fn create_agent_internal(
         routing: None,
         max_concurrent_branches: None,
         max_concurrent_workers: None,
+        worker_batch_concurrency: None,
         max_turns: None,
         branch_max_turns: None,
         context_window: None,
         compaction: None,
         memory_persistence: None,
+        prefetch: None,
+        branch_cache: None,
         coalesce: None,
         ingestion: None,
         cortex: None,
         warmup: None,
+        storage: None,
+        archive: None,
+        moderation: None,
+        transcription: None,
+        budget: None,
+        memory_fts: None,
         browser: None,
         channel: None,
         mcp: None,
@@ -640,7 +672,7 @@ pub async fn create_agent_internal(
         })?;
     }
 
-    let db = crate::db::Db::connect(&agent_config.data_dir)
+    let db = crate::db::Db::connect(&agent_config.data_dir, &agent_config.storage)
         .await
         .map_err(|error| {
             tracing::error!(%error, agent_id = %agent_id, "failed to connect agent databases");
@@ -667,22 +699,30 @@ pub async fn create_agent_internal(
     };
 
     let memory_store = crate::memory::MemoryStore::new(db.sqlite.clone());
-    let embedding_table = crate::memory::EmbeddingTable::open_or_create(&db.lance)
-        .await
-        .map_err(|error| {
-            tracing::error!(%error, agent_id = %agent_id, "failed to init embeddings");
-            format!("failed to init embeddings: {error}")
-        })?;
+    let dimensions = embedding_model.dimensions();
+    let embedding_table =
+        crate::memory::EmbeddingTable::open_with_dimensions(&db.lance, dimensions)
+            .await
+            .map_err(|error| {
+                tracing::error!(%error, agent_id = %agent_id, "failed to init embeddings");
+                format!("failed to init embeddings: {error}")
+            })?
+            .with_fts_config(agent_config.memory_fts.clone())
+            .with_journal(crate::memory::EmbeddingJournal::new(db.sqlite.clone()));
 
     if let Err(error) = embedding_table.ensure_fts_index().await {
         tracing::warn!(%error, agent_id = %agent_id, "failed to create FTS index");
     }
 
-    let memory_search = std::sync::Arc::new(crate::memory::MemorySearch::new(
-        memory_store,
-        embedding_table,
-        embedding_model,
-    ));
+    let mut memory_search =
+        crate::memory::MemorySearch::new(memory_store, embedding_table, embedding_model);
+    match crate::memory::EpisodeTable::open_with_dimensions(&db.lance, dimensions).await {
+        Ok(episodes) => memory_search = memory_search.with_episodes(episodes),
+        Err(error) => {
+            tracing::warn!(%error, agent_id = %agent_id, "failed to init conversation episodes, episodic recall disabled");
+        }
+    }
+    let memory_search = std::sync::Arc::new(memory_search);
     let task_store = std::sync::Arc::new(crate::tasks::TaskStore::new(db.sqlite.clone()));
 
     let (event_tx, memory_event_tx) = crate::create_process_event_buses();
@@ -788,6 +828,7 @@ pub async fn create_agent_internal(
         process_control_registry: Arc::new(
             crate::agent::process_control::ProcessControlRegistry::new(),
         ),
+        branch_cache: Arc::new(crate::agent::branch_cache::BranchResultCache::new()),
         injection_tx: state.injection_tx.clone(),
         agent_names: {
             let configs = state.agent_configs.load();
@@ -812,6 +853,9 @@ pub async fn create_agent_internal(
         humans: Arc::new(arc_swap::ArcSwap::from_pointee(
             (**state.agent_humans.load()).clone(),
         )),
+        teams: Arc::new(arc_swap::ArcSwap::from_pointee(
+            (**state.agent_teams.load()).clone(),
+        )),
     };
 
     let event_rx = event_tx.subscribe();
@@ -869,7 +913,11 @@ pub async fn create_agent_internal(
         tracing::warn!(%error, agent_id = %agent_id, "failed to add factory tools to cortex chat");
     }
 
-    let cortex_store = crate::agent::cortex_chat::CortexChatStore::new(db.sqlite.clone());
+    let cortex_store = crate::agent::cortex_chat::CortexChatStore::for_agent(
+        db.sqlite.clone(),
+        &agent_id,
+        runtime_config.storage.load().encrypt_at_rest,
+    );
     let cortex_session = crate::agent::cortex_chat::CortexChatSession::new(
         deps.clone(),
         cortex_tool_server,
@@ -878,29 +926,21 @@ pub async fn create_agent_internal(
     )
     .with_factory(true);
 
-    let cortex_logger = crate::agent::cortex::CortexLogger::new(db.sqlite.clone());
-    let _warmup_loop = crate::agent::cortex::spawn_warmup_loop(deps.clone(), cortex_logger.clone());
-    let _cortex_loop = crate::agent::cortex::spawn_cortex_loop(deps.clone(), cortex_logger.clone());
-    let _association_loop =
-        crate::agent::cortex::spawn_association_loop(deps.clone(), cortex_logger);
-    crate::agent::cortex::spawn_ready_task_loop(
-        deps.clone(),
-        crate::agent::cortex::CortexLogger::new(db.sqlite.clone()),
-    );
+    crate::cli_worker::detect::spawn_startup_probe(deps.runtime_config.clone());
 
-    let ingestion_config = **runtime_config.ingestion.load();
-    if ingestion_config.enabled {
-        crate::agent::ingestion::spawn_ingestion_loop(agent_config.ingest_dir(), deps.clone());
-    }
+    crate::agent::supervise_background_loops(&deps, agent_config.ingest_dir());
 
     let sqlite_pool = db.sqlite.clone();
+    let branch_cache = deps.branch_cache.clone();
     let mut deps_with_cron = deps.clone();
     deps_with_cron.cron_tool = Some(cron_tool);
+    let outbox = crate::messaging::outbox::Outbox::new(sqlite_pool.clone());
     let agent = crate::Agent {
         id: arc_agent_id.clone(),
         config: agent_config.clone(),
         db,
         deps: deps_with_cron,
+        outbox,
     };
     if let Err(error) = state.agent_tx.send(agent).await {
         tracing::error!(%error, "failed to send new agent to main loop");
@@ -915,6 +955,12 @@ pub async fn create_agent_internal(
         searches.insert(agent_id.clone(), memory_search);
         state.memory_searches.store(std::sync::Arc::new(searches));
 
+        let mut branch_caches = (**state.branch_caches.load()).clone();
+        branch_caches.insert(agent_id.clone(), branch_cache);
+        state
+            .branch_caches
+            .store(std::sync::Arc::new(branch_caches));
+
         let mut task_stores = (**state.task_stores.load()).clone();
         task_stores.insert(agent_id.clone(), task_store.clone());
         state.task_stores.store(std::sync::Arc::new(task_stores));
@@ -1130,6 +1176,7 @@ pub(super) async fn update_agent(
 /// Delete an agent: remove from config.toml, clean up API state, signal main loop.
 pub(super) async fn delete_agent(
     State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
     Query(query): Query<DeleteAgentQuery>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     let agent_id = query.agent_id.trim().to_string();
@@ -1219,6 +1266,12 @@ pub(super) async fn delete_agent(
         searches.remove(&agent_id);
         state.memory_searches.store(std::sync::Arc::new(searches));
 
+        let mut branch_caches = (**state.branch_caches.load()).clone();
+        branch_caches.remove(&agent_id);
+        state
+            .branch_caches
+            .store(std::sync::Arc::new(branch_caches));
+
         let mut workspaces = (**state.agent_workspaces.load()).clone();
         workspaces.remove(&agent_id);
         state
@@ -1276,6 +1329,7 @@ pub(super) async fn delete_agent(
     }
 
     tracing::info!(agent_id = %agent_id, "agent deleted via API");
+    super::audit::record(&state, &caller, AuditEvent::new("agent.delete", &agent_id)).await;
 
     Ok(Json(serde_json::json!({
         "success": true,
@@ -1289,7 +1343,9 @@ pub(super) async fn agent_overview(
     Query(query): Query<AgentOverviewQuery>,
 ) -> Result<Json<AgentOverviewResponse>, StatusCode> {
     let pools = state.agent_pools.load();
-    let pool = pools.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let pool = pools
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let memory_rows = sqlx::query(
         "SELECT memory_type, COUNT(*) as count FROM memories WHERE forgotten = 0 GROUP BY memory_type",
@@ -1555,7 +1611,9 @@ pub(super) async fn get_agent_profile(
     Query(query): Query<AgentOverviewQuery>,
 ) -> Result<Json<AgentProfileResponse>, StatusCode> {
     let pools = state.agent_pools.load();
-    let pool = pools.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let pool = pools
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let profile = crate::agent::cortex::load_profile(pool, &query.agent_id).await;
 
@@ -1569,7 +1627,7 @@ pub(super) async fn get_identity(
 ) -> Result<Json<IdentityResponse>, StatusCode> {
     let identity_dirs = state.agent_identity_dirs.load();
     let identity_dir = identity_dirs
-        .get(&query.agent_id)
+        .get(query.agent_id.as_str())
         .ok_or(StatusCode::NOT_FOUND)?;
 
     let identity = crate::identity::Identity::load(identity_dir).await;
@@ -1582,50 +1640,345 @@ pub(super) async fn get_identity(
 }
 
 /// Update identity files for an agent. Only writes files for fields that are present.
-/// The file watcher will pick up changes and hot-reload identity into RuntimeConfig.
+///
+/// Every file is validated before any is written. The new identity is swapped
+/// into the agent's RuntimeConfig immediately, rather than waiting for the file
+/// watcher, and each changed file is recorded in the config history.
 pub(super) async fn update_identity(
     State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
     axum::Json(request): axum::Json<IdentityUpdateRequest>,
-) -> Result<Json<IdentityResponse>, StatusCode> {
+) -> Result<Json<IdentityUpdateResponse>, (StatusCode, String)> {
     let identity_dirs = state.agent_identity_dirs.load();
-    let identity_dir = identity_dirs
-        .get(&request.agent_id)
-        .ok_or(StatusCode::NOT_FOUND)?;
+    let identity_dir = identity_dirs.get(request.agent_id.as_str()).ok_or((
+        StatusCode::NOT_FOUND,
+        format!("agent '{}' not found", request.agent_id),
+    ))?;
+
+    let files: Vec<(&str, &String)> = [
+        ("SOUL.md", &request.soul),
+        ("IDENTITY.md", &request.identity),
+        ("ROLE.md", &request.role),
+    ]
+    .into_iter()
+    .filter_map(|(file_name, content)| content.as_ref().map(|content| (file_name, content)))
+    .collect();
+    if files.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "no identity files in request".into(),
+        ));
+    }
+    for (file_name, content) in &files {
+        crate::identity::validate_identity_file(file_name, content)
+            .map_err(|error| (StatusCode::BAD_REQUEST, error.to_string()))?;
+    }
 
-    if let Some(soul) = &request.soul {
-        tokio::fs::write(identity_dir.join("SOUL.md"), soul)
-            .await
-            .map_err(|error| {
-                tracing::warn!(%error, "failed to write SOUL.md");
-                StatusCode::INTERNAL_SERVER_ERROR
-            })?;
+    let changelog = (**state.config_changelog.load()).clone();
+    let mut versions = Vec::new();
+    for (file_name, content) in files {
+        let path = identity_dir.join(file_name);
+        let previous = tokio::fs::read_to_string(&path).await.unwrap_or_default();
+        tokio::fs::write(&path, content).await.map_err(|error| {
+            tracing::warn!(%error, file = file_name, "failed to write identity file");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("failed to write {file_name}"),
+            )
+        })?;
+        if let Some(changelog) = &changelog
+            && let Some(version) = changelog.observe(
+                &path,
+                crate::config::ConfigFileKind::Identity,
+                Some(request.agent_id.as_str()),
+            )
+        {
+            versions.push(version);
+        }
+        super::audit::record(
+            &state,
+            &caller,
+            AuditEvent::new(
+                "identity.update",
+                format!("{}/{file_name}", request.agent_id),
+            )
+            .before(summarize_text(&previous, None))
+            .after(summarize_text(content, Some(&previous))),
+        )
+        .await;
     }
 
-    if let Some(identity) = &request.identity {
-        tokio::fs::write(identity_dir.join("IDENTITY.md"), identity)
-            .await
-            .map_err(|error| {
-                tracing::warn!(%error, "failed to write IDENTITY.md");
-                StatusCode::INTERNAL_SERVER_ERROR
-            })?;
+    let updated = crate::identity::Identity::load(identity_dir).await;
+    if let Some(runtime_config) = state.runtime_configs.load().get(request.agent_id.as_str()) {
+        runtime_config.reload_identity(updated.clone());
     }
 
-    if let Some(role) = &request.role {
-        tokio::fs::write(identity_dir.join("ROLE.md"), role)
+    Ok(Json(IdentityUpdateResponse {
+        identity: IdentityResponse {
+            soul: updated.soul,
+            identity: updated.identity,
+            role: updated.role,
+        },
+        versions,
+    }))
+}
+
+#[derive(Deserialize)]
+pub(super) struct AgentImportQuery {
+    agent_id: AgentId,
+    /// Replace identity files the target agent already has.
+    #[serde(default)]
+    overwrite_identity: bool,
+}
+
+/// Export an agent's memories, conversation history, cortex state, and
+/// identity files as a portable tar.gz archive.
+pub(super) async fn export_agent(
+    State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
+    axum::extract::Path(agent_id): axum::extract::Path<AgentId>,
+) -> Result<impl axum::response::IntoResponse, (StatusCode, String)> {
+    let pool = state
+        .agent_pools
+        .load()
+        .get(agent_id.as_str())
+        .cloned()
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("agent '{agent_id}' not found"),
+        ))?;
+    let identity_dir = state
+        .agent_identity_dirs
+        .load()
+        .get(agent_id.as_str())
+        .cloned()
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            format!("agent '{agent_id}' not found"),
+        ))?;
+
+    let chat_store = crate::agent::cortex_chat::CortexChatStore::for_agent(
+        pool.clone(),
+        agent_id.as_str(),
+        false,
+    );
+    let archive = crate::export::export_agent(&pool, &chat_store, &agent_id, &identity_dir)
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, %agent_id, "agent export failed");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("agent export failed: {error}"),
+            )
+        })?;
+    super::audit::record(
+        &state,
+        &caller,
+        AuditEvent::new("agent.export", agent_id.as_str()).after(format!("{} bytes", archive.size)),
+    )
+    .await;
+
+    let headers = [
+        (
+            axum::http::header::CONTENT_TYPE,
+            "application/gzip".to_string(),
+        ),
+        (axum::http::header::CONTENT_LENGTH, archive.size.to_string()),
+        (
+            axum::http::header::CONTENT_DISPOSITION,
+            format!("attachment; filename=spacebot-agent-{agent_id}.tar.gz"),
+        ),
+    ];
+
+    Ok((headers, file_body(archive.file)))
+}
+
+/// Stream a file as a response body in fixed-size chunks.
+fn file_body(file: std::fs::File) -> axum::body::Body {
+    use tokio::io::AsyncReadExt as _;
+
+    let chunks =
+        futures::stream::try_unfold(tokio::fs::File::from_std(file), |mut file| async move {
+            let mut chunk = vec![0; 64 * 1024];
+            let read = file.read(&mut chunk).await?;
+            if read == 0 {
+                return Ok::<_, std::io::Error>(None);
+            }
+            chunk.truncate(read);
+            Ok(Some((axum::body::Bytes::from(chunk), file)))
+        });
+    axum::body::Body::from_stream(chunks)
+}
+
+/// Largest archive `import_agent` accepts. Uploads are spooled to disk, so
+/// this isn't bound by the API's in-memory body limit.
+const MAX_IMPORT_ARCHIVE_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+
+/// Write an uploaded archive to an anonymous temp file, positioned at the
+/// start.
+async fn spool_archive(body: axum::body::Body) -> Result<std::fs::File, (StatusCode, String)> {
+    use futures::StreamExt as _;
+    use tokio::io::{AsyncSeekExt as _, AsyncWriteExt as _};
+
+    let spool_error = |error: std::io::Error| {
+        tracing::warn!(%error, "failed to spool agent import archive");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to buffer export archive: {error}"),
+        )
+    };
+
+    let mut file = tokio::fs::File::from_std(tempfile::tempfile().map_err(spool_error)?);
+    let mut chunks = body.into_data_stream();
+    let mut size = 0u64;
+    while let Some(chunk) = chunks.next().await {
+        let chunk = chunk.map_err(|error| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("failed to read export archive: {error}"),
+            )
+        })?;
+        size += chunk.len() as u64;
+        if size > MAX_IMPORT_ARCHIVE_BYTES {
+            return Err((
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("export archive is larger than {MAX_IMPORT_ARCHIVE_BYTES} bytes"),
+            ));
+        }
+        file.write_all(&chunk).await.map_err(spool_error)?;
+    }
+    if size == 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "export archive payload is empty".to_string(),
+        ));
+    }
+
+    file.flush().await.map_err(spool_error)?;
+    file.rewind().await.map_err(spool_error)?;
+    Ok(file.into_std().await)
+}
+
+/// Import an agent export archive. The agent is created first when it
+/// doesn't exist, so an archive can be restored on a fresh instance. Rows
+/// that already exist are skipped, so importing the same archive twice is
+/// harmless.
+pub(super) async fn import_agent(
+    State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
+    Query(query): Query<AgentImportQuery>,
+    body: axum::body::Body,
+) -> Result<Json<crate::export::ImportReport>, (StatusCode, String)> {
+    let archive = spool_archive(body).await?;
+
+    let agent_id = query.agent_id;
+    let agent_created = !state
+        .agent_configs
+        .load()
+        .iter()
+        .any(|agent| agent.id == agent_id.as_str());
+    if agent_created {
+        let request = CreateAgentRequest {
+            agent_id: agent_id.to_string(),
+            display_name: None,
+            role: None,
+        };
+        create_agent_internal(&state, request)
             .await
-            .map_err(|error| {
-                tracing::warn!(%error, "failed to write ROLE.md");
-                StatusCode::INTERNAL_SERVER_ERROR
+            .map_err(|message| {
+                let status =
+                    if message.contains("cannot be empty") || message.contains("agent limit") {
+                        StatusCode::BAD_REQUEST
+                    } else {
+                        StatusCode::INTERNAL_SERVER_ERROR
+                    };
+                (
+                    status,
+                    format!("failed to create agent for import: {message}"),
+                )
             })?;
+        tracing::info!(%agent_id, "created agent for import");
     }
 
-    let updated = crate::identity::Identity::load(identity_dir).await;
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            format!("agent '{agent_id}' not found"),
+        )
+    };
+    let pool = state
+        .agent_pools
+        .load()
+        .get(agent_id.as_str())
+        .cloned()
+        .ok_or_else(not_found)?;
+    let memory_search = state
+        .memory_searches
+        .load()
+        .get(agent_id.as_str())
+        .cloned()
+        .ok_or_else(not_found)?;
+    let identity_dir = state
+        .agent_identity_dirs
+        .load()
+        .get(agent_id.as_str())
+        .cloned()
+        .ok_or_else(not_found)?;
 
-    Ok(Json(IdentityResponse {
-        soul: updated.soul,
-        identity: updated.identity,
-        role: updated.role,
-    }))
+    let encrypt_at_rest = state
+        .runtime_configs
+        .load()
+        .get(agent_id.as_str())
+        .is_some_and(|runtime_config| runtime_config.storage.load().encrypt_at_rest);
+    let chat_store = crate::agent::cortex_chat::CortexChatStore::for_agent(
+        pool.clone(),
+        agent_id.as_str(),
+        encrypt_at_rest,
+    );
+    let mut report = crate::export::import_agent(
+        &pool,
+        &chat_store,
+        &memory_search,
+        &agent_id,
+        &identity_dir,
+        archive,
+        // A freshly created agent only has scaffolded identity files.
+        query.overwrite_identity || agent_created,
+    )
+    .await
+    .map_err(|error| {
+        tracing::warn!(%error, %agent_id, "agent import failed");
+        (
+            StatusCode::BAD_REQUEST,
+            format!("agent import failed: {error}"),
+        )
+    })?;
+
+    report.agent_created = agent_created;
+
+    tracing::info!(
+        %agent_id,
+        source_agent_id = %report.source_agent_id,
+        memories_embedded = report.memories_embedded,
+        "agent state imported"
+    );
+    super::audit::record(
+        &state,
+        &caller,
+        AuditEvent::new("agent.import", agent_id.as_str()).after(format!(
+            "from {}, {} rows{}",
+            report.source_agent_id,
+            report
+                .tables
+                .values()
+                .map(|count| count.inserted)
+                .sum::<usize>(),
+            if agent_created { ", agent created" } else { "" }
+        )),
+    )
+    .await;
+
+    Ok(Json(report))
 }
 
 #[cfg(test)]
@@ -1681,6 +2034,90 @@ mod tests {
         ))
     }
 
+    #[tokio::test]
+    async fn test_identity_update_and_export_are_audited() {
+        use super::super::access::{ApiCaller, CallerKind};
+        use crate::audit::{AuditLog, AuditQuery};
+        use axum::Extension;
+
+        let state = test_api_state();
+        let access_pool = sqlx::SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("failed to open access database");
+        crate::access::MIGRATOR
+            .run(&access_pool)
+            .await
+            .expect("failed to migrate access database");
+        let audit_log = Arc::new(AuditLog::new(access_pool));
+        state.set_audit_log(audit_log.clone());
+
+        let agent_pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to open agent database");
+        sqlx::migrate!("./migrations")
+            .run(&agent_pool)
+            .await
+            .expect("failed to migrate agent database");
+        let identity_dir = tempfile::tempdir().expect("failed to create tempdir");
+        state
+            .agent_pools
+            .store(Arc::new(HashMap::from([("main".to_string(), agent_pool)])));
+        state.agent_identity_dirs.store(Arc::new(HashMap::from([(
+            "main".to_string(),
+            identity_dir.path().to_path_buf(),
+        )])));
+
+        let caller = ApiCaller {
+            kind: CallerKind::Key,
+            name: "ops".into(),
+            role: crate::config::ApiRole::Admin,
+        };
+        let request = serde_json::from_value(serde_json::json!({
+            "agent_id": "main",
+            "soul": "be kind",
+        }))
+        .expect("valid identity request");
+        let Json(updated) = super::update_identity(
+            State(state.clone()),
+            Extension(caller.clone()),
+            Json(request),
+        )
+        .await
+        .expect("identity update should succeed");
+        assert_eq!(updated.identity.soul.as_deref(), Some("be kind"));
+        super::export_agent(
+            State(state.clone()),
+            Extension(caller),
+            axum::extract::Path("main".parse().expect("valid agent id")),
+        )
+        .await
+        .expect("export should succeed");
+
+        let entries = audit_log
+            .query(&AuditQuery::default())
+            .await
+            .expect("failed to read audit log");
+        let actions: Vec<_> = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.action.as_str(),
+                    entry.target.as_str(),
+                    entry.actor.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                ("agent.export", "main", "ops"),
+                ("identity.update", "main/SOUL.md", "ops"),
+            ]
+        );
+    }
+
     #[test]
     fn test_compute_bulletin_age_secs_none_stays_none() {
         assert_eq!(compute_bulletin_age_secs(None, 1_000), None);
@@ -1744,7 +2181,7 @@ mod tests {
         let result = get_warmup_status(
             State(state),
             Query(WarmupQuery {
-                agent_id: Some("missing".to_string()),
+                agent_id: Some("missing".parse().unwrap()),
             }),
         )
         .await;
@@ -1783,7 +2220,7 @@ mod tests {
         let result = trigger_warmup(
             State(state),
             Json(WarmupTriggerRequest {
-                agent_id: Some("missing".to_string()),
+                agent_id: Some("missing".parse().unwrap()),
                 force: false,
             }),
         )
@@ -1816,7 +2253,7 @@ mod tests {
 
 #[derive(Deserialize)]
 pub(super) struct AvatarQuery {
-    agent_id: String,
+    agent_id: AgentId,
 }
 
 /// Serve the agent's avatar image.
@@ -1827,7 +2264,7 @@ pub(super) async fn get_avatar(
     let data_dir = state
         .agent_data_dirs
         .load()
-        .get(&query.agent_id)
+        .get(query.agent_id.as_str())
         .cloned()
         .ok_or(StatusCode::NOT_FOUND)?;
 
@@ -1864,7 +2301,7 @@ pub(super) async fn upload_avatar(
     let data_dir = state
         .agent_data_dirs
         .load()
-        .get(&query.agent_id)
+        .get(query.agent_id.as_str())
         .cloned()
         .ok_or(StatusCode::NOT_FOUND)?;
 
@@ -1920,7 +2357,7 @@ pub(super) async fn delete_avatar(
     let data_dir = state
         .agent_data_dirs
         .load()
-        .get(&query.agent_id)
+        .get(query.agent_id.as_str())
         .cloned()
         .ok_or(StatusCode::NOT_FOUND)?;
 
@@ -0,0 +1,86 @@
+//! Audit log of administrative actions.
+
+use super::access::{ApiCaller, CallerKind};
+use super::state::ApiState;
+
+use crate::audit::{AuditActor, AuditEntry, AuditEvent, AuditQuery};
+
+use axum::Json;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub(super) struct AuditLogQuery {
+    #[serde(default)]
+    actor: Option<String>,
+    /// An exact action, or a prefix ending in `.` such as `provider.`.
+    #[serde(default)]
+    action: Option<String>,
+    #[serde(default)]
+    target: Option<String>,
+    #[serde(default)]
+    since: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    until: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only entries older than this ID; pass the last ID of a page to get
+    /// the next one.
+    #[serde(default)]
+    before_id: Option<i64>,
+    #[serde(default)]
+    limit: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub(super) struct AuditLogResponse {
+    entries: Vec<AuditEntry>,
+}
+
+/// GET /audit — recorded administrative actions, newest first.
+pub(super) async fn list_audit_log(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<AuditLogQuery>,
+) -> Result<Json<AuditLogResponse>, StatusCode> {
+    let log = (**state.audit_log.load())
+        .clone()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    let entries = log
+        .query(&AuditQuery {
+            actor: query.actor,
+            action: query.action,
+            target: query.target,
+            since: query.since,
+            until: query.until,
+            before_id: query.before_id,
+            limit: query.limit,
+        })
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to query audit log");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    Ok(Json(AuditLogResponse { entries }))
+}
+
+/// Record an action the caller has completed. A failed write is logged but
+/// never fails the action itself, which has already happened.
+pub(super) async fn record(state: &ApiState, caller: &ApiCaller, event: AuditEvent) {
+    let Some(log) = (**state.audit_log.load()).clone() else {
+        return;
+    };
+    let actor = AuditActor {
+        name: caller.name.clone(),
+        kind: match caller.kind {
+            CallerKind::Key => "key",
+            CallerKind::User => "user",
+            CallerKind::Anonymous => "anonymous",
+        }
+        .to_string(),
+        role: caller.role.as_str().to_string(),
+    };
+    let action = event.action.clone();
+    if let Err(error) = log.record(&actor, event).await {
+        tracing::error!(%error, action, "failed to write audit log entry");
+    }
+}
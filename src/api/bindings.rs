@@ -1,3 +1,4 @@
+use super::ids::AgentId;
 use super::state::ApiState;
 
 use axum::Json;
@@ -17,6 +18,8 @@ pub(super) struct BindingResponse {
     channel_ids: Vec<String>,
     require_mention: bool,
     dm_allowed_users: Vec<String>,
+    mention: Option<String>,
+    prefix: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -27,12 +30,12 @@ pub(super) struct BindingsListResponse {
 #[derive(Deserialize)]
 pub(super) struct BindingsQuery {
     #[serde(default)]
-    agent_id: Option<String>,
+    agent_id: Option<AgentId>,
 }
 
 #[derive(Deserialize)]
 pub(super) struct CreateBindingRequest {
-    agent_id: String,
+    agent_id: AgentId,
     channel: String,
     #[serde(default)]
     adapter: Option<String>,
@@ -48,6 +51,10 @@ pub(super) struct CreateBindingRequest {
     require_mention: bool,
     #[serde(default)]
     dm_allowed_users: Vec<String>,
+    #[serde(default)]
+    mention: Option<String>,
+    #[serde(default)]
+    prefix: Option<String>,
     /// Optional: set platform credentials if not yet configured.
     #[serde(default)]
     platform_credentials: Option<PlatformCredentials>,
@@ -105,7 +112,7 @@ pub(super) struct CreateBindingResponse {
 
 #[derive(Deserialize)]
 pub(super) struct DeleteBindingRequest {
-    agent_id: String,
+    agent_id: AgentId,
     channel: String,
     #[serde(default)]
     adapter: Option<String>,
@@ -125,7 +132,7 @@ pub(super) struct DeleteBindingResponse {
 
 #[derive(Deserialize)]
 pub(super) struct UpdateBindingRequest {
-    original_agent_id: String,
+    original_agent_id: AgentId,
     original_channel: String,
     #[serde(default)]
     original_adapter: Option<String>,
@@ -136,7 +143,7 @@ pub(super) struct UpdateBindingRequest {
     #[serde(default)]
     original_chat_id: Option<String>,
 
-    agent_id: String,
+    agent_id: AgentId,
     channel: String,
     #[serde(default)]
     adapter: Option<String>,
@@ -152,6 +159,10 @@ pub(super) struct UpdateBindingRequest {
     require_mention: bool,
     #[serde(default)]
     dm_allowed_users: Vec<String>,
+    #[serde(default)]
+    mention: Option<String>,
+    #[serde(default)]
+    prefix: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -177,7 +188,12 @@ pub(super) async fn list_bindings(
 
     let filtered: Vec<BindingResponse> = bindings
         .into_iter()
-        .filter(|b| query.agent_id.as_ref().is_none_or(|id| &b.agent_id == id))
+        .filter(|b| {
+            query
+                .agent_id
+                .as_ref()
+                .is_none_or(|id| b.agent_id == id.as_str())
+        })
         .map(|b| BindingResponse {
             agent_id: b.agent_id,
             channel: b.channel,
@@ -188,6 +204,8 @@ pub(super) async fn list_bindings(
             channel_ids: b.channel_ids,
             require_mention: b.require_mention,
             dm_allowed_users: b.dm_allowed_users,
+            mention: b.mention,
+            prefix: b.prefix,
         })
         .collect();
 
@@ -414,7 +432,7 @@ pub(super) async fn create_binding(
         .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
 
     let mut binding_table = toml_edit::Table::new();
-    binding_table["agent_id"] = toml_edit::value(&request.agent_id);
+    binding_table["agent_id"] = toml_edit::value(request.agent_id.as_str());
     binding_table["channel"] = toml_edit::value(&request.channel);
     if let Some(adapter) = request
         .adapter
@@ -450,6 +468,11 @@ pub(super) async fn create_binding(
         }
         binding_table["dm_allowed_users"] = toml_edit::value(arr);
     }
+    for (key, value) in [("mention", &request.mention), ("prefix", &request.prefix)] {
+        if let Some(value) = value.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            binding_table[key] = toml_edit::value(value);
+        }
+    }
     bindings_array.push(binding_table);
 
     tokio::fs::write(&config_path, doc.to_string())
@@ -522,6 +545,14 @@ pub(super) async fn create_binding(
                     "discord",
                     &token,
                     discord_perms,
+                )
+                .with_voice(
+                    new_config
+                        .messaging
+                        .discord
+                        .as_ref()
+                        .map(|discord| discord.voice.clone())
+                        .unwrap_or_default(),
                 );
                 if let Err(error) = manager.register_and_start(adapter).await {
                     tracing::error!(%error, "failed to hot-start discord adapter");
@@ -687,7 +718,7 @@ pub(super) async fn update_binding(
         let matches_agent = table
             .get("agent_id")
             .and_then(|v| v.as_str())
-            .is_some_and(|v| v == request.original_agent_id);
+            .is_some_and(|v| v == request.original_agent_id.as_str());
         let matches_channel = table
             .get("channel")
             .and_then(|v| v.as_str())
@@ -743,7 +774,7 @@ pub(super) async fn update_binding(
         .get_mut(idx)
         .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    binding["agent_id"] = toml_edit::value(&request.agent_id);
+    binding["agent_id"] = toml_edit::value(request.agent_id.as_str());
     binding["channel"] = toml_edit::value(&request.channel);
 
     binding.remove("adapter");
@@ -802,6 +833,17 @@ pub(super) async fn update_binding(
         binding.remove("dm_allowed_users");
     }
 
+    // Omitted dispatch rules are left as they are; an empty string clears them.
+    for (key, value) in [("mention", &request.mention), ("prefix", &request.prefix)] {
+        match value.as_deref().map(str::trim) {
+            Some("") => {
+                binding.remove(key);
+            }
+            Some(value) => binding[key] = toml_edit::value(value),
+            None => {}
+        }
+    }
+
     tokio::fs::write(&config_path, doc.to_string())
         .await
         .map_err(|error| {
@@ -881,7 +923,7 @@ pub(super) async fn delete_binding(
         let matches_agent = table
             .get("agent_id")
             .and_then(|v: &toml_edit::Item| v.as_str())
-            .is_some_and(|v| v == request.agent_id);
+            .is_some_and(|v| v == request.agent_id.as_str());
         let matches_channel = table
             .get("channel")
             .and_then(|v: &toml_edit::Item| v.as_str())
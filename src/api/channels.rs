@@ -1,11 +1,22 @@
+use super::access::ApiCaller;
+use super::ids::{AgentId, ChannelId, ProcessRef};
 use super::state::ApiState;
 
+use crate::ProcessType;
+use crate::agent::takeover;
+use crate::audit::AuditEvent;
+use crate::config::{ModerationStrictness, ResponsePace};
 use crate::conversation::channels::ChannelStore;
-use crate::conversation::history::ProcessRunLogger;
+use crate::conversation::history::{
+    ConversationLogger, MessageSearchHit, MessageSearchQuery, ProcessRunLogger,
+};
+use crate::llm::routing::ChannelRouting;
+use crate::memory::MemoryScope;
+use crate::messaging::moderation::{ModerationAudit, ModerationEvent};
 
-use axum::Json;
 use axum::extract::{Query, State};
 use axum::http::StatusCode;
+use axum::{Extension, Json};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -30,7 +41,7 @@ pub(super) struct ChannelsResponse {
 pub(super) struct ListChannelsQuery {
     #[serde(default)]
     include_inactive: bool,
-    agent_id: Option<String>,
+    agent_id: Option<AgentId>,
     is_active: Option<bool>,
 }
 
@@ -65,7 +76,7 @@ pub(super) struct MessagesResponse {
 
 #[derive(Deserialize)]
 pub(super) struct MessagesQuery {
-    channel_id: String,
+    channel_id: ChannelId,
     #[serde(default = "default_message_limit")]
     limit: i64,
     before: Option<String>,
@@ -77,7 +88,7 @@ fn default_message_limit() -> i64 {
 
 #[derive(Deserialize)]
 pub(super) struct CancelProcessRequest {
-    channel_id: String,
+    channel_id: ChannelId,
     process_type: String,
     process_id: String,
 }
@@ -173,6 +184,110 @@ pub(super) async fn channel_messages(
     })
 }
 
+#[derive(Deserialize)]
+pub(super) struct ConversationSearchQuery {
+    q: String,
+    agent_id: Option<AgentId>,
+    channel_id: Option<ChannelId>,
+    /// RFC 3339. Anything else is rejected with 400.
+    before: Option<chrono::DateTime<chrono::Utc>>,
+    after: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default = "default_search_limit")]
+    limit: i64,
+}
+
+fn default_search_limit() -> i64 {
+    25
+}
+
+#[derive(Serialize)]
+pub(super) struct ConversationSearchResult {
+    agent_id: String,
+    #[serde(flatten)]
+    hit: MessageSearchHit,
+}
+
+#[derive(Serialize)]
+pub(super) struct ConversationSearchResponse {
+    results: Vec<ConversationSearchResult>,
+}
+
+/// Keyword search across conversation history for all (or one) agents.
+///
+/// Snippets are HTML-escaped, with matched terms wrapped in `<mark>` tags for
+/// the dashboard to render. Results from different agents are merged by BM25
+/// rank.
+pub(super) async fn search_conversations(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<ConversationSearchQuery>,
+) -> Result<Json<ConversationSearchResponse>, StatusCode> {
+    if query.q.trim().is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let pools = state.agent_pools.load();
+    if let Some(agent_id) = &query.agent_id
+        && !pools.contains_key(agent_id.as_str())
+    {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let limit = query.limit.clamp(1, 100);
+    let search_query = MessageSearchQuery {
+        text: &query.q,
+        channel_id: query.channel_id.as_deref(),
+        before: query.before,
+        after: query.after,
+        limit,
+    };
+
+    let mut results = Vec::new();
+    for (agent_id, pool) in pools.iter() {
+        if query.agent_id.as_deref().is_some_and(|id| id != agent_id) {
+            continue;
+        }
+        let logger = ConversationLogger::new(pool.clone());
+        match logger
+            .search_messages(&search_query, MATCH_OPEN, MATCH_CLOSE)
+            .await
+        {
+            Ok(hits) => results.extend(hits.into_iter().map(|mut hit| {
+                hit.snippet = highlight_snippet(&hit.snippet);
+                ConversationSearchResult {
+                    agent_id: agent_id.clone(),
+                    hit,
+                }
+            })),
+            Err(error) => {
+                tracing::warn!(%error, agent_id, "failed to search conversations");
+            }
+        }
+    }
+
+    results.sort_by(|left, right| left.hit.rank.total_cmp(&right.hit.rank));
+    results.truncate(limit as usize);
+
+    Ok(Json(ConversationSearchResponse { results }))
+}
+
+/// Placeholders SQLite puts around matched terms. Escaping leaves these
+/// control characters alone, so they're swapped for `<mark>` tags afterwards.
+const MATCH_OPEN: &str = "\u{2}";
+const MATCH_CLOSE: &str = "\u{3}";
+
+/// Escape a search snippet as HTML, then turn the match placeholders into
+/// `<mark>` tags. Message text is user input and must never become markup.
+fn highlight_snippet(snippet: &str) -> String {
+    snippet
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+        .replace(MATCH_OPEN, "<mark>")
+        .replace(MATCH_CLOSE, "</mark>")
+}
+
 /// Get live status (active workers, branches, completed items) for all channels.
 pub(super) async fn channel_status(
     State(state): State<Arc<ApiState>>,
@@ -195,30 +310,183 @@ pub(super) async fn channel_status(
 
 #[derive(Deserialize)]
 pub(super) struct DeleteChannelQuery {
-    agent_id: String,
-    channel_id: String,
+    agent_id: AgentId,
+    channel_id: ChannelId,
 }
 
 #[derive(Deserialize)]
 pub(super) struct SetChannelArchiveRequest {
-    agent_id: String,
-    channel_id: String,
+    agent_id: AgentId,
+    channel_id: ChannelId,
     archived: bool,
 }
 
+#[derive(Deserialize)]
+pub(super) struct SetChannelRoutingRequest {
+    agent_id: AgentId,
+    channel_id: ChannelId,
+    #[serde(flatten)]
+    routing: ChannelRouting,
+}
+
+#[derive(Deserialize)]
+pub(super) struct SetChannelPaceRequest {
+    agent_id: AgentId,
+    channel_id: ChannelId,
+    /// `None` clears the override so the agent default applies.
+    pace: Option<ResponsePace>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct SetChannelLanguageRequest {
+    agent_id: AgentId,
+    channel_id: ChannelId,
+    /// ISO 639-3 code or English name. `None` clears the override so the
+    /// detected language applies.
+    language: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct ChannelSettingsQuery {
+    agent_id: AgentId,
+    channel_id: ChannelId,
+}
+
+#[derive(Deserialize)]
+pub(super) struct SetChannelSettingsRequest {
+    agent_id: AgentId,
+    channel_id: ChannelId,
+    /// IANA timezone. `None` clears the override so the inferred timezone
+    /// applies.
+    timezone: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct SetChannelEphemeralRequest {
+    agent_id: AgentId,
+    channel_id: ChannelId,
+    ephemeral: bool,
+}
+
+#[derive(Deserialize)]
+pub(super) struct SetChannelMemoryScopeRequest {
+    agent_id: AgentId,
+    channel_id: ChannelId,
+    /// `None` clears the override so the agent default applies.
+    scope: Option<MemoryScope>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct SetChannelModerationRequest {
+    agent_id: AgentId,
+    channel_id: ChannelId,
+    /// `None` clears the override so the agent default applies.
+    strictness: Option<ModerationStrictness>,
+}
+
+#[derive(Serialize)]
+pub(super) struct ChannelModerationResponse {
+    channel_id: String,
+    strictness: Option<ModerationStrictness>,
+    /// Strictness the channel uses after the update.
+    resolved: ModerationStrictness,
+}
+
+#[derive(Deserialize)]
+pub(super) struct ModerationEventsQuery {
+    agent_id: AgentId,
+    #[serde(default)]
+    channel_id: Option<ChannelId>,
+    #[serde(default = "default_moderation_events_limit")]
+    limit: i64,
+}
+
+fn default_moderation_events_limit() -> i64 {
+    100
+}
+
+#[derive(Serialize)]
+pub(super) struct ModerationEventsResponse {
+    events: Vec<ModerationEvent>,
+}
+
+#[derive(Serialize)]
+pub(super) struct ChannelPaceResponse {
+    channel_id: String,
+    pace: Option<ResponsePace>,
+    /// Pace the channel uses after the update.
+    resolved: ResponsePace,
+}
+
+#[derive(Serialize)]
+pub(super) struct ChannelLanguageResponse {
+    channel_id: String,
+    language: Option<String>,
+    /// Language detected from the channel's messages, if any.
+    detected: Option<String>,
+    /// Language the channel replies in after the update.
+    resolved: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(super) struct ChannelSettingsResponse {
+    channel_id: String,
+    timezone: Option<String>,
+    /// Timezone the platform or people in the channel reported, if any.
+    inferred_timezone: Option<String>,
+    /// Timezone the channel reads times in, or "system" for the server's.
+    resolved_timezone: String,
+}
+
+#[derive(Serialize)]
+pub(super) struct ChannelMemoryScopeResponse {
+    channel_id: String,
+    scope: Option<MemoryScope>,
+    /// Scope the channel uses after the update.
+    resolved: MemoryScope,
+}
+
+#[derive(Serialize)]
+pub(super) struct ChannelRoutingResponse {
+    channel_id: String,
+    routing: ChannelRouting,
+    /// Models the channel's processes resolve to after the update.
+    resolved: ResolvedChannelModels,
+}
+
+#[derive(Serialize)]
+pub(super) struct ResolvedChannelModels {
+    channel: String,
+    branch: String,
+    worker: String,
+    compactor: String,
+}
+
 /// Delete a channel and its message history.
 pub(super) async fn delete_channel(
     State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
     Query(query): Query<DeleteChannelQuery>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     let pools = state.agent_pools.load();
-    let pool = pools.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let pool = pools
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
     let store = ChannelStore::new(pool.clone());
 
-    let deleted = store.delete(&query.channel_id).await.map_err(|error| {
+    let delete_error = |error: crate::error::Error| {
         tracing::error!(%error, "failed to delete channel");
         StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    };
+    let channel = store
+        .get(&query.channel_id)
+        .await
+        .map_err(delete_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let deleted = store
+        .delete(&query.channel_id)
+        .await
+        .map_err(delete_error)?;
 
     if !deleted {
         return Err(StatusCode::NOT_FOUND);
@@ -229,6 +497,26 @@ pub(super) async fn delete_channel(
         channel_id = %query.channel_id,
         "channel deleted via API"
     );
+    super::audit::record(
+        &state,
+        &caller,
+        AuditEvent::new(
+            "channel.delete",
+            format!("{}/{}", query.agent_id, query.channel_id),
+        )
+        .before(format!(
+            "{} channel {}, {}",
+            channel.platform,
+            channel.display_name.as_deref().unwrap_or("(unnamed)"),
+            if channel.is_active {
+                "active"
+            } else {
+                "archived"
+            }
+        ))
+        .after("deleted with its message history"),
+    )
+    .await;
 
     Ok(Json(serde_json::json!({ "success": true })))
 }
@@ -239,7 +527,9 @@ pub(super) async fn set_channel_archive(
     Json(request): Json<SetChannelArchiveRequest>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     let pools = state.agent_pools.load();
-    let pool = pools.get(&request.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let pool = pools
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
     let store = ChannelStore::new(pool.clone());
 
     let is_active = !request.archived;
@@ -265,6 +555,645 @@ pub(super) async fn set_channel_archive(
     Ok(Json(archive_update_response_payload(request.archived)))
 }
 
+/// Set or clear per-channel model overrides. Omitted or blank fields fall
+/// back to the agent's process-type routing; sending none clears the
+/// override entirely.
+pub(super) async fn set_channel_routing(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<SetChannelRoutingRequest>,
+) -> Result<Json<ChannelRoutingResponse>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let runtime_configs = state.runtime_configs.load();
+    let runtime_config = runtime_configs
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let normalize = |model: Option<String>| {
+        model
+            .map(|model| model.trim().to_string())
+            .filter(|model| !model.is_empty())
+    };
+    let routing = ChannelRouting {
+        channel: normalize(request.routing.channel),
+        branch: normalize(request.routing.branch),
+        worker: normalize(request.routing.worker),
+        compactor: normalize(request.routing.compactor),
+    };
+
+    let updated = ChannelStore::new(pool.clone())
+        .set_routing(&request.channel_id, &routing)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to persist channel routing");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if !updated {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    runtime_config.set_channel_routing(&request.channel_id, routing.clone());
+
+    tracing::info!(
+        agent_id = %request.agent_id,
+        channel_id = %request.channel_id,
+        cleared = routing.is_empty(),
+        "channel routing updated via API"
+    );
+
+    let live = runtime_config.routing.load();
+    let channel_id = Some(request.channel_id.as_str());
+    let resolved = ResolvedChannelModels {
+        channel: live.resolve(ProcessType::Channel, channel_id).to_string(),
+        branch: live.resolve(ProcessType::Branch, channel_id).to_string(),
+        worker: live.resolve(ProcessType::Worker, channel_id).to_string(),
+        compactor: live.resolve(ProcessType::Compactor, channel_id).to_string(),
+    };
+
+    Ok(Json(ChannelRoutingResponse {
+        channel_id: request.channel_id.to_string(),
+        routing,
+        resolved,
+    }))
+}
+
+/// Set or clear a channel's reply pacing. Takes effect on the next turn.
+pub(super) async fn set_channel_pace(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<SetChannelPaceRequest>,
+) -> Result<Json<ChannelPaceResponse>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let runtime_configs = state.runtime_configs.load();
+    let runtime_config = runtime_configs
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let updated = ChannelStore::new(pool.clone())
+        .set_pace(&request.channel_id, request.pace)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to persist channel pace");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if !updated {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    tracing::info!(
+        agent_id = %request.agent_id,
+        channel_id = %request.channel_id,
+        pace = request.pace.map(ResponsePace::as_str),
+        "channel pace updated via API"
+    );
+
+    Ok(Json(ChannelPaceResponse {
+        channel_id: request.channel_id.to_string(),
+        pace: request.pace,
+        resolved: request
+            .pace
+            .unwrap_or(runtime_config.channel_config.load().pace),
+    }))
+}
+
+/// Get a channel's settings.
+pub(super) async fn channel_settings(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<ChannelSettingsQuery>,
+) -> Result<Json<ChannelSettingsResponse>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let runtime_configs = state.runtime_configs.load();
+    let runtime_config = runtime_configs
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let store = ChannelStore::new(pool.clone());
+    let channel = store.get(&query.channel_id).await.map_err(|error| {
+        tracing::error!(%error, "failed to load channel");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    if channel.is_none() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let settings = channel_settings_response(&store, runtime_config, &query.channel_id).await?;
+    Ok(Json(settings))
+}
+
+/// Update a channel's settings. Takes effect on the next turn.
+pub(super) async fn set_channel_settings(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<SetChannelSettingsRequest>,
+) -> Result<Json<ChannelSettingsResponse>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let runtime_configs = state.runtime_configs.load();
+    let runtime_config = runtime_configs
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if let Some(timezone) = request.timezone.as_deref()
+        && timezone.parse::<chrono_tz::Tz>().is_err()
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let store = ChannelStore::new(pool.clone());
+    let updated = store
+        .set_timezone(&request.channel_id, request.timezone.as_deref())
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to persist channel timezone");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if !updated {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    tracing::info!(
+        agent_id = %request.agent_id,
+        channel_id = %request.channel_id,
+        timezone = request.timezone.as_deref(),
+        "channel settings updated via API"
+    );
+
+    let settings = channel_settings_response(&store, runtime_config, &request.channel_id).await?;
+    Ok(Json(settings))
+}
+
+async fn channel_settings_response(
+    store: &ChannelStore,
+    runtime_config: &crate::config::RuntimeConfig,
+    channel_id: &str,
+) -> Result<ChannelSettingsResponse, StatusCode> {
+    let timezone = store.timezone(channel_id).await.map_err(|error| {
+        tracing::error!(%error, "failed to load channel timezone");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let temporal_context =
+        crate::agent::channel_prompt::TemporalContext::from_runtime(runtime_config)
+            .with_channel_timezone(timezone.resolved());
+    let resolved_timezone = match temporal_context.timezone {
+        crate::agent::channel_prompt::TemporalTimezone::Named { timezone_name, .. } => {
+            timezone_name
+        }
+        crate::agent::channel_prompt::TemporalTimezone::SystemLocal => "system".to_string(),
+    };
+
+    Ok(ChannelSettingsResponse {
+        channel_id: channel_id.to_string(),
+        timezone: timezone.configured,
+        inferred_timezone: timezone.inferred,
+        resolved_timezone,
+    })
+}
+
+/// Set or clear a channel's reply language. Takes effect on the next turn.
+pub(super) async fn set_channel_language(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<SetChannelLanguageRequest>,
+) -> Result<Json<ChannelLanguageResponse>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let language = match request.language.as_deref() {
+        Some(input) => {
+            Some(crate::conversation::language::parse(input).ok_or(StatusCode::BAD_REQUEST)?)
+        }
+        None => None,
+    };
+
+    let store = ChannelStore::new(pool.clone());
+    let updated = store
+        .set_language(&request.channel_id, language)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to persist channel language");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if !updated {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    tracing::info!(
+        agent_id = %request.agent_id,
+        channel_id = %request.channel_id,
+        language,
+        "channel language updated via API"
+    );
+
+    let current = store.language(&request.channel_id).await.map_err(|error| {
+        tracing::error!(%error, "failed to load channel language");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(ChannelLanguageResponse {
+        channel_id: request.channel_id.to_string(),
+        resolved: current.resolved().map(str::to_string),
+        language: current.preferred,
+        detected: current.detected,
+    }))
+}
+
+/// Set or clear a channel's memory scope. Applies to branches spawned after
+/// the update; memories already saved keep their scope.
+pub(super) async fn set_channel_memory_scope(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<SetChannelMemoryScopeRequest>,
+) -> Result<Json<ChannelMemoryScopeResponse>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let runtime_configs = state.runtime_configs.load();
+    let runtime_config = runtime_configs
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let updated = ChannelStore::new(pool.clone())
+        .set_memory_scope(&request.channel_id, request.scope)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to persist channel memory scope");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if !updated {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    tracing::info!(
+        agent_id = %request.agent_id,
+        channel_id = %request.channel_id,
+        scope = request.scope.map(MemoryScope::as_str),
+        "channel memory scope updated via API"
+    );
+
+    Ok(Json(ChannelMemoryScopeResponse {
+        channel_id: request.channel_id.to_string(),
+        scope: request.scope,
+        resolved: request
+            .scope
+            .unwrap_or(runtime_config.channel_config.load().memory_scope),
+    }))
+}
+
+/// Mark a channel ephemeral or not. Fact extraction skips ephemeral
+/// channels, including anything said while the flag was set.
+pub(super) async fn set_channel_ephemeral(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<SetChannelEphemeralRequest>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let updated = ChannelStore::new(pool.clone())
+        .set_ephemeral(&request.channel_id, request.ephemeral)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to persist channel ephemeral flag");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if !updated {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    tracing::info!(
+        agent_id = %request.agent_id,
+        channel_id = %request.channel_id,
+        ephemeral = request.ephemeral,
+        "channel ephemeral flag updated via API"
+    );
+
+    Ok(Json(serde_json::json!({
+        "success": true,
+        "ephemeral": request.ephemeral,
+    })))
+}
+
+#[derive(Deserialize)]
+pub(super) struct ChannelTakeoverRequest {
+    agent_id: AgentId,
+    channel_id: ChannelId,
+    #[serde(flatten)]
+    action: TakeoverAction,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub(super) enum TakeoverAction {
+    /// Pause the agent so an operator can reply.
+    Start,
+    /// Send a reply under the bot's identity.
+    Send { content: String },
+    /// Hand control back to the agent.
+    End,
+}
+
+#[derive(Serialize)]
+pub(super) struct ChannelTakeoverResponse {
+    channel_id: String,
+    /// Whether an operator is in control after the action.
+    active: bool,
+    started_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Messages handed to the agent as the takeover transcript (`end` only).
+    transcript_messages: usize,
+}
+
+/// Take a channel over from the agent, reply as the bot, and hand it back.
+///
+/// While a takeover is running, inbound messages are recorded but the agent
+/// doesn't reply. Ending it gives the agent a transcript of everything said
+/// in the meantime.
+pub(super) async fn channel_takeover(
+    State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
+    Json(request): Json<ChannelTakeoverRequest>,
+) -> Result<Json<ChannelTakeoverResponse>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let store = ChannelStore::new(pool.clone());
+    let channel_id = request.channel_id.to_string();
+    let audit_target = format!("{}/{}", request.agent_id, channel_id);
+
+    match request.action {
+        TakeoverAction::Start => {
+            let updated = store.start_takeover(&channel_id).await.map_err(|error| {
+                tracing::error!(%error, "failed to start channel takeover");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+            if !updated {
+                return Err(StatusCode::NOT_FOUND);
+            }
+            let started_at = store
+                .takeover_started_at(&channel_id)
+                .await
+                .map_err(|error| {
+                    tracing::error!(%error, "failed to read channel takeover");
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+
+            tracing::info!(
+                agent_id = %request.agent_id,
+                channel_id = %channel_id,
+                "channel taken over via API"
+            );
+            super::audit::record(
+                &state,
+                &caller,
+                AuditEvent::new("channel.takeover.start", &audit_target)
+                    .before("agent in control")
+                    .after("operator in control"),
+            )
+            .await;
+
+            Ok(Json(ChannelTakeoverResponse {
+                channel_id,
+                active: true,
+                started_at,
+                transcript_messages: 0,
+            }))
+        }
+        TakeoverAction::Send { content } => {
+            let content = content.trim();
+            if content.is_empty() {
+                return Err(StatusCode::BAD_REQUEST);
+            }
+            let started_at = store
+                .takeover_started_at(&channel_id)
+                .await
+                .map_err(|error| {
+                    tracing::error!(%error, "failed to read channel takeover");
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+            // Replies are only sent while the agent is paused, so the two
+            // never talk over each other.
+            if started_at.is_none() {
+                return Err(StatusCode::CONFLICT);
+            }
+
+            let channel = store
+                .get(&channel_id)
+                .await
+                .map_err(|error| {
+                    tracing::error!(%error, "failed to load channel");
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?
+                .ok_or(StatusCode::NOT_FOUND)?;
+            let target = crate::messaging::target::resolve_broadcast_target(&channel)
+                .ok_or(StatusCode::UNPROCESSABLE_ENTITY)?;
+            let manager = state
+                .messaging_manager
+                .read()
+                .await
+                .clone()
+                .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+
+            manager
+                .broadcast(
+                    &target.adapter,
+                    &target.target,
+                    crate::OutboundResponse::Text(content.to_string()),
+                )
+                .await
+                .map_err(|error| {
+                    tracing::warn!(%error, channel_id = %channel_id, "failed to send takeover reply");
+                    StatusCode::BAD_GATEWAY
+                })?;
+
+            let agent_name = state
+                .agent_configs
+                .load()
+                .iter()
+                .find(|agent| agent.id == request.agent_id.as_str())
+                .and_then(|agent| agent.display_name.clone());
+            ConversationLogger::new(pool.clone()).log_operator_message(
+                &Arc::from(channel_id.as_str()),
+                content,
+                agent_name.as_deref(),
+            );
+            super::audit::record(
+                &state,
+                &caller,
+                AuditEvent::new("channel.takeover.send", &audit_target)
+                    .after(format!("operator reply, {} chars", content.chars().count())),
+            )
+            .await;
+
+            Ok(Json(ChannelTakeoverResponse {
+                channel_id,
+                active: true,
+                started_at,
+                transcript_messages: 0,
+            }))
+        }
+        TakeoverAction::End => {
+            let started_at = store.end_takeover(&channel_id).await.map_err(|error| {
+                tracing::error!(%error, "failed to end channel takeover");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+            let Some(started_at) = started_at else {
+                return Ok(Json(ChannelTakeoverResponse {
+                    channel_id,
+                    active: false,
+                    started_at: None,
+                    transcript_messages: 0,
+                }));
+            };
+
+            // Stored timestamps have second precision; include the second the
+            // takeover started in.
+            let after = (started_at - chrono::Duration::seconds(1))
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string();
+            let messages = ConversationLogger::new(pool.clone())
+                .load_channel_transcript(
+                    &channel_id,
+                    takeover::MAX_TRANSCRIPT_MESSAGES,
+                    None,
+                    Some(&after),
+                    true,
+                )
+                .await
+                .map_err(|error| {
+                    tracing::error!(%error, "failed to load takeover transcript");
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+
+            // A channel that isn't running picks the messages up from its
+            // history backfill when it next starts.
+            let channel_state = {
+                let states = state.channel_states.read().await;
+                states.get(&channel_id).cloned()
+            }
+            .filter(|channel_state| {
+                channel_state.deps.agent_id.as_ref() == request.agent_id.as_str()
+            });
+            let mut transcript_messages = 0;
+            if let Some(channel_state) = channel_state {
+                let prompts = channel_state.deps.runtime_config.prompts.load_full();
+                let handed_back = match takeover::render_takeover_transcript(&prompts, &messages) {
+                    Ok(Some(transcript)) => takeover::hand_back(&channel_state, transcript)
+                        .await
+                        .map(|()| messages.len()),
+                    Ok(None) => Ok(0),
+                    Err(error) => Err(error),
+                };
+                match handed_back {
+                    Ok(count) => transcript_messages = count,
+                    Err(error) => {
+                        tracing::warn!(%error, "failed to give takeover transcript to channel")
+                    }
+                }
+            }
+
+            tracing::info!(
+                agent_id = %request.agent_id,
+                channel_id = %channel_id,
+                transcript_messages,
+                "channel handed back to agent via API"
+            );
+            super::audit::record(
+                &state,
+                &caller,
+                AuditEvent::new("channel.takeover.end", &audit_target)
+                    .before(format!(
+                        "operator in control since {}",
+                        started_at.to_rfc3339()
+                    ))
+                    .after(format!(
+                        "agent in control, {transcript_messages} transcript messages handed back"
+                    )),
+            )
+            .await;
+
+            Ok(Json(ChannelTakeoverResponse {
+                channel_id,
+                active: false,
+                started_at: Some(started_at),
+                transcript_messages,
+            }))
+        }
+    }
+}
+
+/// Set or clear a channel's moderation strictness. Takes effect on the next
+/// reply.
+pub(super) async fn set_channel_moderation(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<SetChannelModerationRequest>,
+) -> Result<Json<ChannelModerationResponse>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let runtime_configs = state.runtime_configs.load();
+    let runtime_config = runtime_configs
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let updated = ChannelStore::new(pool.clone())
+        .set_moderation(&request.channel_id, request.strictness)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to persist channel moderation");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if !updated {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    tracing::info!(
+        agent_id = %request.agent_id,
+        channel_id = %request.channel_id,
+        strictness = request.strictness.map(ModerationStrictness::as_str),
+        "channel moderation updated via API"
+    );
+
+    Ok(Json(ChannelModerationResponse {
+        channel_id: request.channel_id.to_string(),
+        strictness: request.strictness,
+        resolved: request
+            .strictness
+            .unwrap_or(runtime_config.moderation.load().strictness),
+    }))
+}
+
+/// List replies moderation flagged, rewrote, or blocked, newest first.
+pub(super) async fn moderation_events(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<ModerationEventsQuery>,
+) -> Result<Json<ModerationEventsResponse>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let events = ModerationAudit::new(pool.clone())
+        .list(query.channel_id.as_deref(), query.limit.clamp(1, 500))
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to list moderation events");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(ModerationEventsResponse { events }))
+}
+
 fn archive_update_response_payload(archived: bool) -> serde_json::Value {
     serde_json::json!({
         "success": true,
@@ -278,16 +1207,18 @@ pub(super) async fn cancel_process(
     State(state): State<Arc<ApiState>>,
     Json(request): Json<CancelProcessRequest>,
 ) -> Result<Json<CancelProcessResponse>, StatusCode> {
-    match request.process_type.as_str() {
-        "worker" => {
-            let worker_id: crate::WorkerId = request
-                .process_id
-                .parse()
-                .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let process =
+        ProcessRef::parse(&request.process_type, &request.process_id).map_err(|error| {
+            tracing::debug!(%error, "rejected process cancel request");
+            StatusCode::BAD_REQUEST
+        })?;
 
+    match process {
+        ProcessRef::Worker(worker_id) => {
+            let worker_id = worker_id.get();
             let channel_state = {
                 let states = state.channel_states.read().await;
-                states.get(&request.channel_id).cloned()
+                states.get(request.channel_id.as_str()).cloned()
             };
 
             if let Some(channel_state) = channel_state {
@@ -353,19 +1284,15 @@ pub(super) async fn cancel_process(
 
             Err(StatusCode::NOT_FOUND)
         }
-        "branch" => {
+        ProcessRef::Branch(branch_id) => {
             let channel_state = {
                 let states = state.channel_states.read().await;
-                states.get(&request.channel_id).cloned()
+                states.get(request.channel_id.as_str()).cloned()
             }
             .ok_or(StatusCode::NOT_FOUND)?;
 
-            let branch_id: crate::BranchId = request
-                .process_id
-                .parse()
-                .map_err(|_| StatusCode::BAD_REQUEST)?;
             channel_state
-                .cancel_branch_with_reason(branch_id, "cancelled via API")
+                .cancel_branch_with_reason(branch_id.get(), "cancelled via API")
                 .await
                 .map_err(|_| StatusCode::NOT_FOUND)?;
             Ok(Json(CancelProcessResponse {
@@ -373,7 +1300,6 @@ pub(super) async fn cancel_process(
                 message: format!("Branch {} cancelled", request.process_id),
             }))
         }
-        _ => Err(StatusCode::BAD_REQUEST),
     }
 }
 
@@ -381,7 +1307,7 @@ pub(super) async fn cancel_process(
 
 #[derive(Deserialize)]
 pub(super) struct PromptInspectQuery {
-    channel_id: String,
+    channel_id: ChannelId,
 }
 
 /// Render the full prompt that the LLM would see on the next turn for a
@@ -394,7 +1320,7 @@ pub(super) async fn inspect_prompt(
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     let channel_state = {
         let states = state.channel_states.read().await;
-        states.get(&query.channel_id).cloned()
+        states.get(query.channel_id.as_str()).cloned()
     };
 
     let channel_state = match channel_state {
@@ -411,7 +1337,7 @@ pub(super) async fn inspect_prompt(
 
     // ── Gather all dynamic sections ──
     let identity_context = rc.identity.load().render();
-    let memory_bulletin = rc.memory_bulletin.load();
+    let memory_bulletin = rc.rendered_bulletin();
     let skills = rc.skills.load();
     let skills_prompt = skills
         .render_channel_prompt(&prompt_engine)
@@ -434,7 +1360,13 @@ pub(super) async fn inspect_prompt(
         rc.as_ref(),
         &channel_state.deps.sandbox,
     );
-    let temporal_context = crate::agent::channel_prompt::TemporalContext::from_runtime(rc.as_ref());
+    let channel_timezone = channel_state
+        .channel_store
+        .timezone(&query.channel_id)
+        .await
+        .unwrap_or_default();
+    let temporal_context = crate::agent::channel_prompt::TemporalContext::from_runtime(rc.as_ref())
+        .with_channel_timezone(channel_timezone.resolved());
     let current_time_line = temporal_context.current_time_line();
     let status_text = {
         let status = channel_state.status_block.read().await;
@@ -473,7 +1405,7 @@ pub(super) async fn inspect_prompt(
     let system_prompt = prompt_engine
         .render_channel_prompt_with_links(
             empty_to_none(identity_context),
-            empty_to_none(memory_bulletin.to_string()),
+            empty_to_none(memory_bulletin),
             empty_to_none(skills_prompt),
             worker_capabilities,
             conversation_context,
@@ -523,7 +1455,7 @@ pub(super) async fn inspect_prompt(
 
 #[derive(Deserialize)]
 pub(super) struct PromptCaptureBody {
-    channel_id: String,
+    channel_id: ChannelId,
     enabled: bool,
 }
 
@@ -537,7 +1469,7 @@ pub(super) async fn set_prompt_capture(
         let configs = state.runtime_configs.load();
         let channel_state = state.channel_states.read().await;
         channel_state
-            .get(&body.channel_id)
+            .get(body.channel_id.as_str())
             .map(|cs| cs.deps.runtime_config.clone())
             .or_else(|| {
                 // Fall back to first agent config if channel not active
@@ -569,7 +1501,7 @@ pub(super) async fn set_prompt_capture(
 
 #[derive(Deserialize)]
 pub(super) struct SnapshotListQuery {
-    channel_id: String,
+    channel_id: ChannelId,
     #[serde(default = "default_snapshot_limit")]
     limit: usize,
 }
@@ -600,7 +1532,7 @@ pub(super) async fn list_prompt_snapshots(
 
 #[derive(Deserialize)]
 pub(super) struct SnapshotGetQuery {
-    channel_id: String,
+    channel_id: ChannelId,
     timestamp_ms: i64,
 }
 
@@ -657,6 +1589,30 @@ async fn find_snapshot_store(
 mod tests {
     use super::*;
 
+    #[test]
+    fn search_snippets_escape_message_text() {
+        let snippet =
+            format!("<img src=x onerror=\"alert(1)\"> {MATCH_OPEN}deploy{MATCH_CLOSE} & go");
+        assert_eq!(
+            highlight_snippet(&snippet),
+            "&lt;img src=x onerror=&quot;alert(1)&quot;&gt; <mark>deploy</mark> &amp; go"
+        );
+    }
+
+    #[test]
+    fn search_rejects_malformed_dates() {
+        let parse = |uri: &str| {
+            Query::<ConversationSearchQuery>::try_from_uri(&uri.parse().expect("valid uri"))
+        };
+        let rejection = parse("/conversations/search?q=deploy&before=last+tuesday")
+            .err()
+            .expect("malformed date should be rejected");
+        assert_eq!(rejection.status(), StatusCode::BAD_REQUEST);
+        let Query(query) = parse("/conversations/search?q=deploy&after=2026-03-10T12:00:00Z")
+            .expect("RFC 3339 should parse");
+        assert!(query.after.is_some());
+    }
+
     #[test]
     fn resolve_is_active_filter_defaults_to_active_only() {
         let query = ListChannelsQuery {
@@ -0,0 +1,64 @@
+use super::ids::AgentId;
+use super::state::ApiState;
+
+use crate::cli_worker::BackendStatus;
+
+use axum::Json;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub(super) struct CliWorkerStatusQuery {
+    /// Limit the check to one agent. Checks every agent when omitted.
+    #[serde(default)]
+    agent_id: Option<AgentId>,
+}
+
+#[derive(Serialize)]
+pub(super) struct AgentCliWorkers {
+    agent_id: String,
+    /// One entry per enabled backend. Disabled backends are omitted.
+    backends: Vec<BackendStatus>,
+}
+
+#[derive(Serialize)]
+pub(super) struct CliWorkerStatusResponse {
+    agents: Vec<AgentCliWorkers>,
+}
+
+/// Re-probe every enabled CLI worker backend (OpenCode, container runtime)
+/// and report the results. `spawn_worker` picks up the new results on its
+/// next tool definition.
+pub(super) async fn cli_worker_status(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<CliWorkerStatusQuery>,
+) -> Result<Json<CliWorkerStatusResponse>, StatusCode> {
+    let runtime_configs = state.runtime_configs.load();
+
+    let targets: Vec<_> = match &query.agent_id {
+        Some(agent_id) => {
+            let runtime_config = runtime_configs
+                .get(agent_id.as_str())
+                .ok_or(StatusCode::NOT_FOUND)?;
+            vec![(agent_id.to_string(), runtime_config.clone())]
+        }
+        None => runtime_configs
+            .iter()
+            .map(|(agent_id, runtime_config)| (agent_id.clone(), runtime_config.clone()))
+            .collect(),
+    };
+
+    let mut agents = Vec::with_capacity(targets.len());
+    for (agent_id, runtime_config) in targets {
+        let backends = runtime_config
+            .cli_worker_health
+            .refresh(&runtime_config)
+            .await;
+        agents.push(AgentCliWorkers { agent_id, backends });
+    }
+    agents.sort_by(|a, b| a.agent_id.cmp(&b.agent_id));
+
+    Ok(Json(CliWorkerStatusResponse { agents }))
+}
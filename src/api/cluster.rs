@@ -0,0 +1,33 @@
+//! Cluster membership and coordination status.
+
+use crate::cluster::ClusterStatus;
+
+use axum::Json;
+use axum::http::StatusCode;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub(super) struct ClusterResponse {
+    /// False when this instance runs standalone; the status is then absent.
+    enabled: bool,
+    #[serde(flatten)]
+    status: Option<ClusterStatus>,
+}
+
+/// GET /cluster — nodes, their load, the leader, and pending shared work.
+pub(super) async fn cluster_status() -> Result<Json<ClusterResponse>, StatusCode> {
+    let Some(cluster) = crate::cluster::global() else {
+        return Ok(Json(ClusterResponse {
+            enabled: false,
+            status: None,
+        }));
+    };
+    let status = cluster.status().await.map_err(|error| {
+        tracing::error!(%error, "failed to read cluster status");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(ClusterResponse {
+        enabled: true,
+        status: Some(status),
+    }))
+}
@@ -1,9 +1,12 @@
+use super::access::ApiCaller;
+use super::ids::AgentId;
 use super::state::ApiState;
+use crate::audit::{AuditEvent, summarize_text};
 use crate::config::ClosePolicy;
 
-use axum::Json;
 use axum::extract::{Query, State};
 use axum::http::StatusCode;
+use axum::{Extension, Json};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
@@ -68,6 +71,7 @@ pub(super) struct CoalesceSection {
     max_wait_ms: u64,
     min_messages: usize,
     multi_user_only: bool,
+    sender_debounce_ms: u64,
 }
 
 #[derive(Serialize, Debug)]
@@ -131,12 +135,12 @@ pub(super) struct AgentConfigResponse {
 
 #[derive(Deserialize)]
 pub(super) struct AgentConfigQuery {
-    agent_id: String,
+    agent_id: AgentId,
 }
 
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, Debug)]
 pub(super) struct AgentConfigUpdateRequest {
-    agent_id: String,
+    agent_id: AgentId,
     #[serde(default)]
     routing: Option<RoutingUpdate>,
     #[serde(default)]
@@ -224,6 +228,7 @@ pub(super) struct CoalesceUpdate {
     max_wait_ms: Option<u64>,
     min_messages: Option<usize>,
     multi_user_only: Option<bool>,
+    sender_debounce_ms: Option<u64>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -276,7 +281,7 @@ pub(super) async fn get_agent_config(
 ) -> Result<Json<AgentConfigResponse>, StatusCode> {
     let runtime_configs = state.runtime_configs.load();
     let rc = runtime_configs
-        .get(&query.agent_id)
+        .get(query.agent_id.as_str())
         .ok_or(StatusCode::NOT_FOUND)?;
 
     let routing = rc.routing.load();
@@ -341,6 +346,7 @@ pub(super) async fn get_agent_config(
             max_wait_ms: coalesce.max_wait_ms,
             min_messages: coalesce.min_messages,
             multi_user_only: coalesce.multi_user_only,
+            sender_debounce_ms: coalesce.sender_debounce_ms,
         },
         memory_persistence: MemoryPersistenceSection {
             enabled: memory_persistence.enabled,
@@ -401,6 +407,7 @@ pub(super) async fn get_agent_config(
 /// This preserves formatting and comments while writing the new values.
 pub(super) async fn update_agent_config(
     State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
     axum::Json(request): axum::Json<AgentConfigUpdateRequest>,
 ) -> Result<Json<AgentConfigResponse>, StatusCode> {
     let config_path = state.config_path.read().await.clone();
@@ -472,7 +479,7 @@ pub(super) async fn update_agent_config(
         return Err(StatusCode::BAD_REQUEST);
     }
 
-    tokio::fs::write(&config_path, updated_content)
+    tokio::fs::write(&config_path, &updated_content)
         .await
         .map_err(|error| {
             tracing::warn!(%error, "failed to write config.toml");
@@ -483,6 +490,35 @@ pub(super) async fn update_agent_config(
     drop(_config_guard);
 
     tracing::info!(agent_id = %request.agent_id, "config.toml updated via API");
+    let sections = [
+        ("routing", request.routing.is_some()),
+        ("tuning", request.tuning.is_some()),
+        ("compaction", request.compaction.is_some()),
+        ("cortex", request.cortex.is_some()),
+        ("warmup", request.warmup.is_some()),
+        ("coalesce", request.coalesce.is_some()),
+        ("memory_persistence", request.memory_persistence.is_some()),
+        ("browser", request.browser.is_some()),
+        ("channel", request.channel.is_some()),
+        ("sandbox", request.sandbox.is_some()),
+        ("projects", request.projects.is_some()),
+        ("discord", request.discord.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(name, set)| set.then_some(name))
+    .collect::<Vec<_>>();
+    super::audit::record(
+        &state,
+        &caller,
+        AuditEvent::new("agent_config.update", request.agent_id.as_str())
+            .before(summarize_text(&config_content, None))
+            .after(format!(
+                "changed {}; {}",
+                sections.join(", "),
+                summarize_text(&updated_content, Some(&config_content))
+            )),
+    )
+    .await;
 
     match crate::config::Config::load_from_path(&config_path) {
         Ok(new_config) => {
@@ -493,8 +529,8 @@ pub(super) async fn update_agent_config(
             let runtime_configs = state.runtime_configs.load();
             let mcp_managers = state.mcp_managers.load();
             if let (Some(rc), Some(mcp_manager)) = (
-                runtime_configs.get(&request.agent_id).cloned(),
-                mcp_managers.get(&request.agent_id).cloned(),
+                runtime_configs.get(request.agent_id.as_str()).cloned(),
+                mcp_managers.get(request.agent_id.as_str()).cloned(),
             ) {
                 rc.reload_config(&new_config, &request.agent_id, &mcp_manager)
                     .await;
@@ -773,6 +809,9 @@ fn update_coalesce_table(
     if let Some(v) = coalesce.multi_user_only {
         table["multi_user_only"] = toml_edit::value(v);
     }
+    if let Some(v) = coalesce.sender_debounce_ms {
+        table["sender_debounce_ms"] = toml_edit::value(v as i64);
+    }
     Ok(())
 }
 
@@ -0,0 +1,170 @@
+use super::access::ApiCaller;
+use super::ids::AgentId;
+use super::state::ApiState;
+
+use crate::audit::{AuditEvent, summarize_text};
+use crate::config::{ConfigChangeSummary, ConfigChangelog, ConfigFileKind};
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub(super) struct ConfigHistoryQuery {
+    /// Only changes to this agent's files, plus config.toml changes.
+    #[serde(default)]
+    agent_id: Option<AgentId>,
+    #[serde(default = "default_history_limit")]
+    limit: usize,
+}
+
+fn default_history_limit() -> usize {
+    50
+}
+
+#[derive(Deserialize)]
+pub(super) struct ConfigHistoryEntryQuery {
+    version: u64,
+}
+
+#[derive(Deserialize)]
+pub(super) struct RevertConfigRequest {
+    version: u64,
+}
+
+#[derive(Serialize)]
+pub(super) struct ConfigHistoryResponse {
+    changes: Vec<ConfigChangeSummary>,
+}
+
+#[derive(Serialize)]
+pub(super) struct ConfigHistoryEntryResponse {
+    #[serde(flatten)]
+    change: ConfigChangeSummary,
+    before: Option<String>,
+    after: Option<String>,
+    diff: String,
+}
+
+#[derive(Serialize)]
+pub(super) struct RevertConfigResponse {
+    success: bool,
+    /// Version recorded for the revert. `None` when the file already matched.
+    version: Option<u64>,
+    path: PathBuf,
+    message: String,
+}
+
+fn changelog(state: &ApiState) -> Result<Arc<ConfigChangelog>, StatusCode> {
+    let guard = state.config_changelog.load();
+    (*guard).as_ref().clone().ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Recorded changes to config.toml, identity files, and skills, newest first.
+pub(super) async fn list_config_history(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<ConfigHistoryQuery>,
+) -> Result<Json<ConfigHistoryResponse>, StatusCode> {
+    let changelog = changelog(&state)?;
+
+    Ok(Json(ConfigHistoryResponse {
+        changes: changelog.list(query.agent_id.as_deref(), query.limit.clamp(1, 500)),
+    }))
+}
+
+/// One change with full before/after content and a line diff.
+pub(super) async fn get_config_history_entry(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<ConfigHistoryEntryQuery>,
+) -> Result<Json<ConfigHistoryEntryResponse>, StatusCode> {
+    let changelog = changelog(&state)?;
+    let change = changelog.get(query.version).ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(ConfigHistoryEntryResponse {
+        diff: change.diff(),
+        change: ConfigChangeSummary::from(&change),
+        before: change.before,
+        after: change.after,
+    }))
+}
+
+/// Restore the file touched by a change to its content before that change.
+/// The file watcher picks up the restored file and hot-reloads it.
+pub(super) async fn revert_config_change(
+    State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
+    Json(request): Json<RevertConfigRequest>,
+) -> Result<Json<RevertConfigResponse>, StatusCode> {
+    let changelog = changelog(&state)?;
+    let change = changelog
+        .get(request.version)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if change.kind == ConfigFileKind::Config {
+        let Some(before) = &change.before else {
+            // config.toml can't be deleted out from under a running instance.
+            return Ok(Json(RevertConfigResponse {
+                success: false,
+                version: None,
+                path: change.path,
+                message: "Cannot revert the creation of config.toml.".to_string(),
+            }));
+        };
+        if let Err(error) = crate::config::Config::validate_toml(before) {
+            return Ok(Json(RevertConfigResponse {
+                success: false,
+                version: None,
+                path: change.path,
+                message: format!("Previous config no longer validates: {error}"),
+            }));
+        }
+    }
+
+    // Hold the config write mutex so a revert can't interleave with other
+    // read-modify-write cycles on config.toml.
+    let _config_guard = state.config_write_mutex.lock().await;
+    let version = tokio::task::spawn_blocking(move || changelog.revert(request.version))
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, "config revert task failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .map_err(|error| {
+            tracing::warn!(%error, version = request.version, "failed to revert config change");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    tracing::info!(
+        reverted = request.version,
+        path = %change.path.display(),
+        "config change reverted via API"
+    );
+    super::audit::record(
+        &state,
+        &caller,
+        AuditEvent::new("config.revert", change.path.display().to_string())
+            .before(match &change.after {
+                Some(after) => summarize_text(after, None),
+                None => "absent".to_string(),
+            })
+            .after(format!(
+                "restored content from before change {}: {}",
+                request.version,
+                match &change.before {
+                    Some(before) => summarize_text(before, change.after.as_deref()),
+                    None => "removed".to_string(),
+                }
+            )),
+    )
+    .await;
+
+    Ok(Json(RevertConfigResponse {
+        success: true,
+        version,
+        path: change.path,
+        message: format!("Reverted change {}", request.version),
+    }))
+}
@@ -1,8 +1,10 @@
+use super::ids::{AgentId, ChannelId};
 use super::state::ApiState;
 
 use crate::agent::cortex::{CortexEvent, CortexLogger};
 use crate::agent::cortex_chat::{
     CortexChatEvent, CortexChatMessage, CortexChatSendError, CortexChatStore, CortexChatThread,
+    CortexChatThreadFork,
 };
 
 use axum::Json;
@@ -28,7 +30,7 @@ pub(super) struct CortexChatMessagesResponse {
 
 #[derive(Deserialize)]
 pub(super) struct CortexChatMessagesQuery {
-    agent_id: String,
+    agent_id: AgentId,
     /// If omitted, loads the latest thread.
     thread_id: Option<String>,
     #[serde(default = "default_cortex_chat_limit")]
@@ -41,15 +43,15 @@ fn default_cortex_chat_limit() -> i64 {
 
 #[derive(Deserialize)]
 pub(super) struct CortexChatSendRequest {
-    agent_id: String,
+    agent_id: AgentId,
     thread_id: String,
     message: String,
-    channel_id: Option<String>,
+    channel_id: Option<ChannelId>,
 }
 
 #[derive(Deserialize)]
 pub(super) struct CortexEventsQuery {
-    agent_id: String,
+    agent_id: AgentId,
     #[serde(default = "default_cortex_events_limit")]
     limit: i64,
     #[serde(default)]
@@ -69,6 +71,12 @@ fn map_cortex_chat_send_error(error: &CortexChatSendError) -> StatusCode {
     }
 }
 
+/// Cortex chat store for an agent. Handlers here only read or copy rows, so
+/// the store never needs to encrypt new ones.
+fn chat_store(pool: &sqlx::SqlitePool, agent_id: &str) -> CortexChatStore {
+    CortexChatStore::for_agent(pool.clone(), agent_id, false)
+}
+
 /// Load persisted cortex chat history for a thread.
 /// If no thread_id is provided, loads the latest thread.
 /// If no threads exist, returns an empty list with a fresh thread_id.
@@ -77,8 +85,10 @@ pub(super) async fn cortex_chat_messages(
     Query(query): Query<CortexChatMessagesQuery>,
 ) -> Result<Json<CortexChatMessagesResponse>, StatusCode> {
     let pools = state.agent_pools.load();
-    let pool = pools.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
-    let store = CortexChatStore::new(pool.clone());
+    let pool = pools
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let store = chat_store(pool, query.agent_id.as_str());
 
     let thread_id = if let Some(tid) = query.thread_id {
         tid
@@ -118,7 +128,7 @@ pub(super) async fn cortex_chat_send(
 ) -> Result<Sse<impl Stream<Item = Result<axum::response::sse::Event, Infallible>>>, StatusCode> {
     let sessions = state.cortex_chat_sessions.load();
     let session = sessions
-        .get(&request.agent_id)
+        .get(request.agent_id.as_str())
         .cloned()
         .ok_or(StatusCode::NOT_FOUND)?;
 
@@ -171,23 +181,33 @@ pub(super) struct CortexChatThreadsResponse {
 
 #[derive(Deserialize)]
 pub(super) struct CortexChatThreadsQuery {
-    agent_id: String,
+    agent_id: AgentId,
 }
 
 #[derive(Deserialize)]
 pub(super) struct CortexChatDeleteThreadRequest {
-    agent_id: String,
+    agent_id: AgentId,
     thread_id: String,
 }
 
+#[derive(Deserialize)]
+pub(super) struct CortexChatForkThreadRequest {
+    agent_id: AgentId,
+    thread_id: String,
+    /// Last message to carry over into the new thread.
+    message_id: String,
+}
+
 /// List all cortex chat threads for an agent, newest first.
 pub(super) async fn cortex_chat_threads(
     State(state): State<Arc<ApiState>>,
     Query(query): Query<CortexChatThreadsQuery>,
 ) -> Result<Json<CortexChatThreadsResponse>, StatusCode> {
     let pools = state.agent_pools.load();
-    let pool = pools.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
-    let store = CortexChatStore::new(pool.clone());
+    let pool = pools
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let store = chat_store(pool, query.agent_id.as_str());
 
     let threads = store.list_threads().await.map_err(|error| {
         tracing::warn!(%error, agent_id = %query.agent_id, "failed to list cortex chat threads");
@@ -203,8 +223,10 @@ pub(super) async fn cortex_chat_delete_thread(
     axum::Json(request): axum::Json<CortexChatDeleteThreadRequest>,
 ) -> Result<StatusCode, StatusCode> {
     let pools = state.agent_pools.load();
-    let pool = pools.get(&request.agent_id).ok_or(StatusCode::NOT_FOUND)?;
-    let store = CortexChatStore::new(pool.clone());
+    let pool = pools
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let store = chat_store(pool, request.agent_id.as_str());
 
     let deleted = store.delete_thread(&request.thread_id).await.map_err(|error| {
         tracing::warn!(%error, agent_id = %request.agent_id, thread_id = %request.thread_id, "failed to delete cortex chat thread");
@@ -218,13 +240,38 @@ pub(super) async fn cortex_chat_delete_thread(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Fork a cortex chat thread at a message into a new thread.
+pub(super) async fn cortex_chat_fork_thread(
+    State(state): State<Arc<ApiState>>,
+    axum::Json(request): axum::Json<CortexChatForkThreadRequest>,
+) -> Result<Json<CortexChatThreadFork>, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let store = chat_store(pool, request.agent_id.as_str());
+
+    let fork = store
+        .fork_thread(&request.thread_id, &request.message_id)
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, agent_id = %request.agent_id, thread_id = %request.thread_id, "failed to fork cortex chat thread");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(fork))
+}
+
 /// List cortex events for an agent with optional type filter, newest first.
 pub(super) async fn cortex_events(
     State(state): State<Arc<ApiState>>,
     Query(query): Query<CortexEventsQuery>,
 ) -> Result<Json<CortexEventsResponse>, StatusCode> {
     let pools = state.agent_pools.load();
-    let pool = pools.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let pool = pools
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
     let logger = CortexLogger::new(pool.clone());
 
     let limit = query.limit.min(200);
@@ -1,3 +1,4 @@
+use super::ids::AgentId;
 use super::state::ApiState;
 
 use axum::Json;
@@ -9,12 +10,12 @@ use std::sync::Arc;
 
 #[derive(Deserialize)]
 pub(super) struct CronQuery {
-    agent_id: String,
+    agent_id: AgentId,
 }
 
 #[derive(Deserialize)]
 pub(super) struct CronExecutionsQuery {
-    agent_id: String,
+    agent_id: AgentId,
     #[serde(default)]
     cron_id: Option<String>,
     #[serde(default = "default_cron_executions_limit")]
@@ -27,7 +28,7 @@ fn default_cron_executions_limit() -> i64 {
 
 #[derive(Deserialize, Debug)]
 pub(super) struct CreateCronRequest {
-    agent_id: String,
+    agent_id: AgentId,
     id: String,
     prompt: String,
     #[serde(default)]
@@ -45,6 +46,10 @@ pub(super) struct CreateCronRequest {
     run_once: bool,
     #[serde(default)]
     timeout_secs: Option<u64>,
+    /// IANA timezone for the schedule and active hours. Defaults to the
+    /// agent's `cron_timezone`.
+    #[serde(default)]
+    timezone: Option<String>,
 }
 
 fn default_interval() -> u64 {
@@ -57,19 +62,19 @@ fn default_enabled() -> bool {
 
 #[derive(Deserialize)]
 pub(super) struct DeleteCronRequest {
-    agent_id: String,
+    agent_id: AgentId,
     cron_id: String,
 }
 
 #[derive(Deserialize)]
 pub(super) struct TriggerCronRequest {
-    agent_id: String,
+    agent_id: AgentId,
     cron_id: String,
 }
 
 #[derive(Deserialize)]
 pub(super) struct ToggleCronRequest {
-    agent_id: String,
+    agent_id: AgentId,
     cron_id: String,
     enabled: bool,
 }
@@ -85,6 +90,7 @@ struct CronJobWithStats {
     run_once: bool,
     active_hours: Option<(u8, u8)>,
     timeout_secs: Option<u64>,
+    timezone: Option<String>,
     success_count: u64,
     failure_count: u64,
     last_executed_at: Option<String>,
@@ -114,9 +120,11 @@ pub(super) async fn list_cron_jobs(
 ) -> Result<Json<CronListResponse>, StatusCode> {
     let stores = state.cron_stores.load();
     let schedulers = state.cron_schedulers.load();
-    let store = stores.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
     let scheduler = schedulers
-        .get(&query.agent_id)
+        .get(query.agent_id.as_str())
         .ok_or(StatusCode::NOT_FOUND)?;
 
     let configs = store.load_all_unfiltered().await.map_err(|error| {
@@ -141,6 +149,7 @@ pub(super) async fn list_cron_jobs(
             run_once: config.run_once,
             active_hours: config.active_hours,
             timeout_secs: config.timeout_secs,
+            timezone: config.timezone,
             success_count: stats.success_count,
             failure_count: stats.failure_count,
             last_executed_at: stats.last_executed_at,
@@ -159,7 +168,9 @@ pub(super) async fn cron_executions(
     Query(query): Query<CronExecutionsQuery>,
 ) -> Result<Json<CronExecutionsResponse>, StatusCode> {
     let stores = state.cron_stores.load();
-    let store = stores.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let executions = if let Some(cron_id) = query.cron_id {
         store
@@ -267,6 +278,14 @@ fn validate_cron_request(request: &CreateCronRequest) -> Result<(), (StatusCode,
             "active_end_hour must be 0-23".into(),
         ));
     }
+    if let Some(timezone) = request.timezone.as_deref()
+        && timezone.parse::<chrono_tz::Tz>().is_err()
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("timezone '{timezone}' is not an IANA timezone"),
+        ));
+    }
 
     Ok(())
 }
@@ -300,13 +319,13 @@ pub(super) async fn create_or_update_cron(
         )
     };
 
-    let store = stores.get(&request.agent_id).ok_or_else(|| {
+    let store = stores.get(request.agent_id.as_str()).ok_or_else(|| {
         cron_err(
             StatusCode::NOT_FOUND,
             format!("agent '{}' not found", request.agent_id),
         )
     })?;
-    let scheduler = schedulers.get(&request.agent_id).ok_or_else(|| {
+    let scheduler = schedulers.get(request.agent_id.as_str()).ok_or_else(|| {
         cron_err(
             StatusCode::NOT_FOUND,
             format!("agent '{}' not found", request.agent_id),
@@ -333,6 +352,7 @@ pub(super) async fn create_or_update_cron(
         enabled: request.enabled,
         run_once: request.run_once,
         timeout_secs: request.timeout_secs,
+        timezone: request.timezone,
     };
 
     store.save(&config).await.map_err(|error| {
@@ -357,11 +377,13 @@ pub(super) async fn delete_cron(
     Query(query): Query<DeleteCronRequest>,
 ) -> Result<Json<CronActionResponse>, StatusCode> {
     let stores = state.cron_stores.load();
-    let store = stores.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let schedulers = state.cron_schedulers.load();
     let scheduler = schedulers
-        .get(&query.agent_id)
+        .get(query.agent_id.as_str())
         .ok_or(StatusCode::NOT_FOUND)?;
 
     scheduler.unregister(&query.cron_id).await;
@@ -384,7 +406,7 @@ pub(super) async fn trigger_cron(
 ) -> Result<Json<CronActionResponse>, StatusCode> {
     let schedulers = state.cron_schedulers.load();
     let scheduler = schedulers
-        .get(&request.agent_id)
+        .get(request.agent_id.as_str())
         .ok_or(StatusCode::NOT_FOUND)?;
 
     scheduler.trigger_now(&request.cron_id).await.map_err(|error| {
@@ -404,11 +426,13 @@ pub(super) async fn toggle_cron(
     Json(request): Json<ToggleCronRequest>,
 ) -> Result<Json<CronActionResponse>, StatusCode> {
     let stores = state.cron_stores.load();
-    let store = stores.get(&request.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let schedulers = state.cron_schedulers.load();
     let scheduler = schedulers
-        .get(&request.agent_id)
+        .get(request.agent_id.as_str())
         .ok_or(StatusCode::NOT_FOUND)?;
 
     store.update_enabled(&request.cron_id, request.enabled).await.map_err(|error| {
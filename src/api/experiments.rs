@@ -0,0 +1,323 @@
+//! REST API handlers for A/B experiments on channel prompts and routing.
+
+use super::ids::{AgentId, ChannelId};
+use super::state::ApiState;
+
+use crate::agent::experiments::{
+    Experiment, ExperimentStatus, ExperimentStore, Variant, VariantKey, VariantReport,
+};
+use crate::config::RuntimeConfig;
+use crate::prompts::PromptEngine;
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub(super) struct AgentQuery {
+    agent_id: AgentId,
+}
+
+#[derive(Deserialize)]
+pub(super) struct ExperimentsListQuery {
+    agent_id: AgentId,
+    #[serde(default = "default_experiments_limit")]
+    limit: i64,
+}
+
+fn default_experiments_limit() -> i64 {
+    50
+}
+
+#[derive(Deserialize)]
+pub(super) struct CreateExperimentRequest {
+    agent_id: AgentId,
+    name: String,
+    /// Template the variants' `prompt` replaces. Required if either sets one.
+    #[serde(default)]
+    template: Option<String>,
+    #[serde(default)]
+    variant_a: Variant,
+    #[serde(default)]
+    variant_b: Variant,
+    /// Percent of channels assigned to variant B.
+    #[serde(default = "default_traffic_b")]
+    traffic_b: u8,
+}
+
+fn default_traffic_b() -> u8 {
+    50
+}
+
+#[derive(Deserialize)]
+pub(super) struct AssignmentRequest {
+    agent_id: AgentId,
+    channel_id: ChannelId,
+    variant: VariantKey,
+}
+
+#[derive(Deserialize)]
+pub(super) struct FeedbackRequest {
+    agent_id: AgentId,
+    channel_id: ChannelId,
+    positive: bool,
+}
+
+#[derive(Serialize)]
+pub(super) struct ExperimentsListResponse {
+    experiments: Vec<Experiment>,
+}
+
+#[derive(Serialize)]
+pub(super) struct ExperimentResponse {
+    experiment: Experiment,
+}
+
+#[derive(Serialize)]
+pub(super) struct ReportResponse {
+    experiment: Experiment,
+    variants: Vec<VariantReport>,
+}
+
+#[derive(Serialize)]
+pub(super) struct ActionResponse {
+    success: bool,
+    message: String,
+}
+
+fn experiment_store(state: &ApiState, agent_id: &AgentId) -> Result<ExperimentStore, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools.get(agent_id.as_str()).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(ExperimentStore::new(pool.clone()))
+}
+
+fn runtime_config(state: &ApiState, agent_id: &AgentId) -> Result<Arc<RuntimeConfig>, StatusCode> {
+    state
+        .runtime_configs
+        .load()
+        .get(agent_id.as_str())
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn load_experiment(store: &ExperimentStore, id: &str) -> Result<Experiment, StatusCode> {
+    store
+        .get(id)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to load experiment");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// Check that an experiment's variants are usable before it starts.
+fn validate_experiment(request: &CreateExperimentRequest) -> Result<(), String> {
+    if request.name.trim().is_empty() {
+        return Err("name must not be empty".into());
+    }
+    if request.traffic_b > 100 {
+        return Err("traffic_b must be 0-100".into());
+    }
+
+    let variants = [&request.variant_a, &request.variant_b];
+    if variants.iter().any(|variant| {
+        variant
+            .model
+            .as_deref()
+            .is_some_and(|model| model.trim().is_empty())
+    }) {
+        return Err("variant model must not be empty".into());
+    }
+    let sets_prompt = variants.iter().any(|variant| variant.prompt.is_some());
+    match request.template.as_deref() {
+        Some(template) => {
+            if PromptEngine::builtin_source(template).is_none() {
+                return Err(format!("unknown template '{template}'"));
+            }
+            for source in variants
+                .iter()
+                .filter_map(|variant| variant.prompt.as_deref())
+            {
+                PromptEngine::preview(template, source, minijinja::Value::UNDEFINED)
+                    .map_err(|error| format!("{error:#}"))?;
+            }
+        }
+        None if sets_prompt => {
+            return Err("template is required when a variant sets a prompt".into());
+        }
+        None => {}
+    }
+    if variants
+        .iter()
+        .all(|variant| variant.prompt.is_none() && variant.model.is_none())
+    {
+        return Err("at least one variant must set a prompt or model".into());
+    }
+    Ok(())
+}
+
+/// GET /experiments — list experiments, newest first.
+pub(super) async fn list_experiments(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<ExperimentsListQuery>,
+) -> Result<Json<ExperimentsListResponse>, StatusCode> {
+    let store = experiment_store(&state, &query.agent_id)?;
+    let experiments = store
+        .list(query.limit.clamp(1, 500))
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to list experiments");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(ExperimentsListResponse { experiments }))
+}
+
+/// POST /experiments — create and start an experiment.
+pub(super) async fn create_experiment(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<CreateExperimentRequest>,
+) -> Result<Json<ExperimentResponse>, (StatusCode, String)> {
+    let store = experiment_store(&state, &request.agent_id)
+        .map_err(|status| (status, "agent not found".into()))?;
+    let runtime_config = runtime_config(&state, &request.agent_id)
+        .map_err(|status| (status, "agent not found".into()))?;
+    validate_experiment(&request).map_err(|message| (StatusCode::BAD_REQUEST, message))?;
+
+    let internal_error = |error: crate::error::Error| {
+        tracing::error!(%error, "failed to create experiment");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "failed to create experiment".to_string(),
+        )
+    };
+    if let Some(running) = store.running().await.map_err(internal_error)? {
+        return Err((
+            StatusCode::CONFLICT,
+            format!("experiment '{}' is already running", running.name),
+        ));
+    }
+
+    let experiment = store
+        .create(
+            request.name.trim(),
+            request.template.as_deref(),
+            &request.variant_a,
+            &request.variant_b,
+            request.traffic_b,
+        )
+        .await
+        .map_err(internal_error)?;
+    runtime_config
+        .experiment
+        .store(Arc::new(Some(Arc::new(experiment.clone()))));
+
+    tracing::info!(agent_id = %request.agent_id, experiment_id = %experiment.id, "experiment started");
+    Ok(Json(ExperimentResponse { experiment }))
+}
+
+/// GET /experiments/{id}/report — outcomes per variant.
+pub(super) async fn experiment_report(
+    State(state): State<Arc<ApiState>>,
+    Path(experiment_id): Path<String>,
+    Query(query): Query<AgentQuery>,
+) -> Result<Json<ReportResponse>, StatusCode> {
+    let store = experiment_store(&state, &query.agent_id)?;
+    let experiment = load_experiment(&store, &experiment_id).await?;
+    let variants = store.report(&experiment.id).await.map_err(|error| {
+        tracing::error!(%error, "failed to build experiment report");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(ReportResponse {
+        experiment,
+        variants,
+    }))
+}
+
+/// PUT /experiments/{id}/assignments — pin a channel to a variant.
+pub(super) async fn assign_channel(
+    State(state): State<Arc<ApiState>>,
+    Path(experiment_id): Path<String>,
+    Json(request): Json<AssignmentRequest>,
+) -> Result<Json<ActionResponse>, StatusCode> {
+    let store = experiment_store(&state, &request.agent_id)?;
+    let experiment = load_experiment(&store, &experiment_id).await?;
+    if experiment.status != ExperimentStatus::Running {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    store
+        .pin(&experiment.id, &request.channel_id, request.variant)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to pin experiment variant");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(ActionResponse {
+        success: true,
+        message: format!(
+            "{} pinned to variant {}",
+            request.channel_id,
+            request.variant.as_str()
+        ),
+    }))
+}
+
+/// POST /experiments/{id}/feedback — record feedback on a channel's replies.
+pub(super) async fn record_feedback(
+    State(state): State<Arc<ApiState>>,
+    Path(experiment_id): Path<String>,
+    Json(request): Json<FeedbackRequest>,
+) -> Result<Json<ActionResponse>, StatusCode> {
+    let store = experiment_store(&state, &request.agent_id)?;
+    let variant = store
+        .record_feedback(&experiment_id, &request.channel_id, request.positive)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to record experiment feedback");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(ActionResponse {
+        success: true,
+        message: format!("feedback recorded for variant {}", variant.as_str()),
+    }))
+}
+
+/// POST /experiments/{id}/stop — stop an experiment; channels go back to
+/// their normal prompt and model on their next turn.
+pub(super) async fn stop_experiment(
+    State(state): State<Arc<ApiState>>,
+    Path(experiment_id): Path<String>,
+    Json(request): Json<AgentQuery>,
+) -> Result<Json<ActionResponse>, StatusCode> {
+    let store = experiment_store(&state, &request.agent_id)?;
+    let runtime_config = runtime_config(&state, &request.agent_id)?;
+    let stopped = store.stop(&experiment_id).await.map_err(|error| {
+        tracing::error!(%error, "failed to stop experiment");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    if !stopped {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let is_active = runtime_config
+        .experiment
+        .load()
+        .as_deref()
+        .is_some_and(|experiment| experiment.id == experiment_id);
+    if is_active {
+        runtime_config.experiment.store(Arc::new(None));
+    }
+
+    Ok(Json(ActionResponse {
+        success: true,
+        message: "Experiment stopped".into(),
+    }))
+}
@@ -0,0 +1,257 @@
+//! Liveness and readiness checks for deployment orchestration.
+//!
+//! Both endpoints run the same dependency checks concurrently and report
+//! each one. `/health` always answers 200 so a liveness probe doesn't restart
+//! the process over an outage it can't fix; `/ready` answers 503 while a
+//! critical dependency is down so traffic is held back until it recovers.
+
+use super::state::ApiState;
+
+use crate::llm::probe::ProviderProbe;
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Upper bound for any single dependency check.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum CheckStatus {
+    Ok,
+    Degraded,
+    Down,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct DependencyCheck {
+    name: String,
+    status: CheckStatus,
+    /// Whether the instance is unready while this check is down.
+    critical: bool,
+    latency_ms: u64,
+    detail: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(super) struct HealthResponse {
+    /// `ok`, `degraded` (a non-critical dependency is failing) or `down`
+    /// (a critical dependency is failing).
+    status: &'static str,
+    ready: bool,
+    checks: Vec<DependencyCheck>,
+}
+
+/// Liveness: always 200, with per-dependency status in the body.
+pub(super) async fn health(State(state): State<Arc<ApiState>>) -> Json<HealthResponse> {
+    Json(run_checks(&state).await)
+}
+
+/// Readiness: 503 while any critical dependency is down.
+pub(super) async fn ready(
+    State(state): State<Arc<ApiState>>,
+) -> (StatusCode, Json<HealthResponse>) {
+    let response = run_checks(&state).await;
+    let status = if response.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(response))
+}
+
+async fn run_checks(state: &ApiState) -> HealthResponse {
+    let pools = state.agent_pools.load();
+    let sqlite = futures::future::join_all(pools.iter().map(|(agent_id, pool)| {
+        timed_check(format!("sqlite:{agent_id}"), true, async move {
+            sqlx::query("SELECT 1")
+                .execute(pool)
+                .await
+                .map(|_| None)
+                .map_err(|error| error.to_string())
+        })
+    }));
+
+    let searches = state.memory_searches.load();
+    let lancedb = futures::future::join_all(searches.iter().map(|(agent_id, search)| {
+        timed_check(format!("lancedb:{agent_id}"), true, async move {
+            search
+                .embedding_table()
+                .row_count()
+                .await
+                .map(|count| Some(format!("{count} embeddings")))
+                .map_err(|error| error.to_string())
+        })
+    }));
+
+    let embedding_model = state.embedding_model.read().await.clone();
+    let embedding = timed_check("embedding".to_string(), true, async move {
+        let model = embedding_model.ok_or_else(|| "embedding model not loaded".to_string())?;
+        model.health_check().await?;
+        Ok(Some(model.backend_name().to_string()))
+    });
+
+    let messaging_manager = state.messaging_manager.read().await.clone();
+    let messaging = async move {
+        let Some(manager) = messaging_manager else {
+            return Vec::new();
+        };
+        let started = Instant::now();
+        manager
+            .health_check_all(CHECK_TIMEOUT)
+            .await
+            .into_iter()
+            .map(|(adapter, result)| DependencyCheck {
+                name: format!("messaging:{adapter}"),
+                status: if result.is_ok() {
+                    CheckStatus::Ok
+                } else {
+                    CheckStatus::Down
+                },
+                critical: false,
+                latency_ms: started.elapsed().as_millis() as u64,
+                detail: result.err(),
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let (sqlite, lancedb, embedding, messaging) =
+        tokio::join!(sqlite, lancedb, embedding, messaging);
+
+    let mut checks: Vec<DependencyCheck> = sqlite.into_iter().chain(lancedb).collect();
+    checks.push(embedding);
+    checks.extend(messaging);
+
+    match state.llm_manager.read().await.clone() {
+        Some(manager) => checks.extend(provider_checks(&manager.provider_probes().await)),
+        None => checks.push(DependencyCheck {
+            name: "llm".to_string(),
+            status: CheckStatus::Down,
+            critical: true,
+            latency_ms: 0,
+            detail: Some("LLM manager not initialized".to_string()),
+        }),
+    }
+
+    summarize(checks)
+}
+
+/// Run `check` under [`CHECK_TIMEOUT`]. `Ok` carries an optional detail.
+async fn timed_check(
+    name: String,
+    critical: bool,
+    check: impl Future<Output = Result<Option<String>, String>>,
+) -> DependencyCheck {
+    let started = Instant::now();
+    let (status, detail) = match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+        Ok(Ok(detail)) => (CheckStatus::Ok, detail),
+        Ok(Err(error)) => (CheckStatus::Down, Some(error)),
+        Err(_) => (
+            CheckStatus::Down,
+            Some(format!("no answer within {}s", CHECK_TIMEOUT.as_secs())),
+        ),
+    };
+    if status == CheckStatus::Down {
+        tracing::warn!(check = %name, detail = ?detail, "health check failed");
+    }
+    DependencyCheck {
+        name,
+        status,
+        critical,
+        latency_ms: started.elapsed().as_millis() as u64,
+        detail,
+    }
+}
+
+/// Provider status from the background reachability probes, without making
+/// new requests. A single provider being down only degrades the instance
+/// since routing falls back to the others; all of them being down is
+/// critical.
+fn provider_checks(probes: &HashMap<String, ProviderProbe>) -> Vec<DependencyCheck> {
+    let all_down = !probes.is_empty() && probes.values().all(ProviderProbe::is_down);
+    let mut checks: Vec<DependencyCheck> = probes
+        .iter()
+        .map(|(provider, probe)| DependencyCheck {
+            name: format!("provider:{provider}"),
+            status: if probe.is_down() {
+                CheckStatus::Down
+            } else if probe.reachable && !probe.auth_error {
+                CheckStatus::Ok
+            } else {
+                CheckStatus::Degraded
+            },
+            critical: all_down,
+            latency_ms: probe.latency_ms,
+            detail: probe.error.clone(),
+        })
+        .collect();
+    checks.sort_by(|left, right| left.name.cmp(&right.name));
+    checks
+}
+
+fn summarize(checks: Vec<DependencyCheck>) -> HealthResponse {
+    let ready = !checks
+        .iter()
+        .any(|check| check.critical && check.status == CheckStatus::Down);
+    let status = if !ready {
+        "down"
+    } else if checks.iter().all(|check| check.status == CheckStatus::Ok) {
+        "ok"
+    } else {
+        "degraded"
+    };
+    HealthResponse {
+        status,
+        ready,
+        checks,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(reachable: bool, consecutive_failures: u32) -> ProviderProbe {
+        ProviderProbe {
+            reachable,
+            auth_error: false,
+            status: None,
+            latency_ms: 40,
+            error: (!reachable).then(|| "connection refused".to_string()),
+            consecutive_failures,
+            checked_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn one_provider_down_only_degrades() {
+        let probes = HashMap::from([
+            ("anthropic".to_string(), probe(true, 0)),
+            ("openai".to_string(), probe(false, 10)),
+        ]);
+        let response = summarize(provider_checks(&probes));
+
+        assert!(response.ready);
+        assert_eq!(response.status, "degraded");
+        assert_eq!(response.checks[1].status, CheckStatus::Down);
+        assert!(!response.checks[1].critical);
+    }
+
+    #[test]
+    fn all_providers_down_is_not_ready() {
+        let probes = HashMap::from([
+            ("anthropic".to_string(), probe(false, 10)),
+            ("openai".to_string(), probe(false, 10)),
+        ]);
+        let response = summarize(provider_checks(&probes));
+
+        assert!(!response.ready);
+        assert_eq!(response.status, "down");
+    }
+}
@@ -0,0 +1,345 @@
+//! Typed identifiers for API requests and responses.
+//!
+//! Handlers used to take raw strings and parse or trust them ad hoc. These
+//! wrappers validate once during deserialization, so a malformed ID is
+//! rejected by the `Query`/`Json` extractor with a consistent message before
+//! the handler runs, and serialize back to the same plain string clients sent.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Longest agent ID accepted from clients. Agent IDs name directories.
+const MAX_AGENT_ID_LEN: usize = 128;
+
+/// Longest channel ID accepted from clients.
+const MAX_CHANNEL_ID_LEN: usize = 512;
+
+/// Why an identifier failed to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdError {
+    kind: &'static str,
+    value: String,
+    reason: &'static str,
+}
+
+impl fmt::Display for IdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid {} '{}': {}", self.kind, self.value, self.reason)
+    }
+}
+
+impl std::error::Error for IdError {}
+
+fn id_error(kind: &'static str, value: &str, reason: &'static str) -> IdError {
+    IdError {
+        kind,
+        value: value.chars().take(64).collect(),
+        reason,
+    }
+}
+
+fn validate_text_id(
+    kind: &'static str,
+    value: &str,
+    max_len: usize,
+) -> std::result::Result<(), IdError> {
+    if value.is_empty() {
+        return Err(id_error(kind, value, "must not be empty"));
+    }
+    if value.len() > max_len {
+        return Err(id_error(kind, value, "too long"));
+    }
+    if value.trim() != value {
+        return Err(id_error(
+            kind,
+            value,
+            "must not have leading or trailing whitespace",
+        ));
+    }
+    if value.chars().any(char::is_control) {
+        return Err(id_error(kind, value, "must not contain control characters"));
+    }
+    Ok(())
+}
+
+macro_rules! text_id {
+    ($(#[$meta:meta])* $name:ident, $kind:literal, $max_len:expr, $extra:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            pub fn into_inner(self) -> String {
+                self.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = IdError;
+
+            fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+                validate_text_id($kind, value, $max_len)?;
+                let extra: fn(&str) -> Option<&'static str> = $extra;
+                if let Some(reason) = extra(value) {
+                    return Err(id_error($kind, value, reason));
+                }
+                Ok(Self(value.to_string()))
+            }
+        }
+
+        impl std::ops::Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl std::borrow::Borrow<str> for $name {
+            fn borrow(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> String {
+                id.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+                let value = String::deserialize(deserializer)?;
+                value.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+macro_rules! uuid_id {
+    ($(#[$meta:meta])* $name:ident, $kind:literal, $inner:ty) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name($inner);
+
+        impl $name {
+            pub fn get(self) -> $inner {
+                self.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = IdError;
+
+            fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+                uuid::Uuid::parse_str(value.trim())
+                    .map(Self)
+                    .map_err(|_| id_error($kind, value, "expected a UUID"))
+            }
+        }
+
+        impl From<$inner> for $name {
+            fn from(id: $inner) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<$name> for $inner {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+                serializer.collect_str(&self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+                let value = String::deserialize(deserializer)?;
+                value.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+text_id!(
+    /// An agent ID. Agent IDs name directories on disk, so path separators
+    /// and `..` are rejected.
+    AgentId,
+    "agent id",
+    MAX_AGENT_ID_LEN,
+    |value| {
+        if value.contains(['/', '\\']) || value == "." || value == ".." {
+            Some("must not contain path separators")
+        } else {
+            None
+        }
+    }
+);
+
+text_id!(
+    /// A channel ID such as `discord:123:456` or `portal:chat:main`.
+    ChannelId,
+    "channel id",
+    MAX_CHANNEL_ID_LEN,
+    |_| None
+);
+
+uuid_id!(
+    /// A worker ID.
+    WorkerId,
+    "worker id",
+    crate::WorkerId
+);
+
+uuid_id!(
+    /// A branch ID.
+    BranchId,
+    "branch id",
+    crate::BranchId
+);
+
+/// A worker or branch addressed by a `process_type` and `process_id` pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessRef {
+    Worker(WorkerId),
+    Branch(BranchId),
+}
+
+impl ProcessRef {
+    pub fn parse(process_type: &str, process_id: &str) -> std::result::Result<Self, IdError> {
+        match process_type {
+            "worker" => process_id.parse().map(Self::Worker),
+            "branch" => process_id.parse().map(Self::Branch),
+            _ => Err(id_error(
+                "process type",
+                process_type,
+                "expected 'worker' or 'branch'",
+            )),
+        }
+    }
+}
+
+impl From<&crate::AgentId> for AgentId {
+    fn from(id: &crate::AgentId) -> Self {
+        Self(id.to_string())
+    }
+}
+
+impl From<&crate::ChannelId> for ChannelId {
+    fn from(id: &crate::ChannelId) -> Self {
+        Self(id.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn agent_ids_reject_paths_and_blanks() {
+        assert_eq!("main".parse::<AgentId>().unwrap().as_str(), "main");
+        assert!("".parse::<AgentId>().is_err());
+        assert!(" main".parse::<AgentId>().is_err());
+        assert!("../main".parse::<AgentId>().is_err());
+        assert!("..".parse::<AgentId>().is_err());
+        assert!("a\nb".parse::<AgentId>().is_err());
+    }
+
+    #[test]
+    fn channel_ids_keep_platform_prefixes() {
+        let id: ChannelId = "discord:123:456".parse().unwrap();
+        assert_eq!(id, "discord:123:456");
+        assert_eq!(id.to_string(), "discord:123:456");
+    }
+
+    #[test]
+    fn worker_ids_parse_uuids_and_report_kind() {
+        let raw = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+        let id: WorkerId = raw.parse().unwrap();
+        assert_eq!(id.to_string(), raw);
+
+        let error = "nope".parse::<WorkerId>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid worker id 'nope': expected a UUID"
+        );
+    }
+
+    #[test]
+    fn process_refs_dispatch_on_type() {
+        let raw = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+        assert!(matches!(
+            ProcessRef::parse("branch", raw),
+            Ok(ProcessRef::Branch(_))
+        ));
+        assert!(ProcessRef::parse("worker", "nope").is_err());
+        assert!(ProcessRef::parse("channel", raw).is_err());
+    }
+
+    #[test]
+    fn ids_round_trip_through_serde_as_strings() {
+        let json = serde_json::json!({
+            "agent_id": "main",
+            "branch_id": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+        });
+
+        #[derive(Deserialize, Serialize)]
+        struct Request {
+            agent_id: AgentId,
+            branch_id: BranchId,
+        }
+
+        let request: Request = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&request).unwrap(), json);
+
+        let bad = serde_json::json!({ "agent_id": "", "branch_id": "x" });
+        let error = serde_json::from_value::<Request>(bad).err().unwrap();
+        assert!(error.to_string().contains("invalid agent id"));
+    }
+}
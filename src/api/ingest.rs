@@ -1,3 +1,4 @@
+use super::ids::AgentId;
 use super::state::ApiState;
 
 use axum::Json;
@@ -36,12 +37,12 @@ pub(super) struct IngestDeleteResponse {
 
 #[derive(Deserialize)]
 pub(super) struct IngestQuery {
-    agent_id: String,
+    agent_id: AgentId,
 }
 
 #[derive(Deserialize)]
 pub(super) struct IngestDeleteQuery {
-    agent_id: String,
+    agent_id: AgentId,
     content_hash: String,
 }
 
@@ -53,7 +54,9 @@ pub(super) async fn list_ingest_files(
     use sqlx::Row as _;
 
     let pools = state.agent_pools.load();
-    let pool = pools.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let pool = pools
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let rows = sqlx::query(
         r#"
@@ -102,7 +105,7 @@ pub(super) async fn upload_ingest_file(
 ) -> Result<Json<IngestUploadResponse>, StatusCode> {
     let workspaces = state.agent_workspaces.load();
     let workspace = workspaces
-        .get(&query.agent_id)
+        .get(query.agent_id.as_str())
         .ok_or(StatusCode::NOT_FOUND)?;
     let ingest_dir = workspace.join("ingest");
 
@@ -165,7 +168,7 @@ pub(super) async fn upload_ingest_file(
         if let Ok(content) = std::str::from_utf8(&data) {
             let hash = crate::agent::ingestion::content_hash(content);
             let pools = state.agent_pools.load();
-            if let Some(pool) = pools.get(&query.agent_id) {
+            if let Some(pool) = pools.get(query.agent_id.as_str()) {
                 let file_size = data.len() as i64;
                 let _ = sqlx::query(
                     r#"
@@ -200,7 +203,9 @@ pub(super) async fn delete_ingest_file(
     Query(query): Query<IngestDeleteQuery>,
 ) -> Result<Json<IngestDeleteResponse>, StatusCode> {
     let pools = state.agent_pools.load();
-    let pool = pools.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let pool = pools
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     sqlx::query("DELETE FROM ingestion_files WHERE content_hash = ?")
         .bind(&query.content_hash)
@@ -0,0 +1,126 @@
+use super::ids::AgentId;
+use super::state::ApiState;
+
+use crate::agent::jobs::JobSnapshot;
+
+use axum::Json;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub(super) struct JobsQuery {
+    /// Limit the listing to one agent. Lists every agent when omitted.
+    #[serde(default)]
+    agent_id: Option<AgentId>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TriggerJobRequest {
+    agent_id: AgentId,
+    name: String,
+}
+
+#[derive(Deserialize)]
+pub(super) struct ToggleJobRequest {
+    agent_id: AgentId,
+    name: String,
+    enabled: bool,
+}
+
+#[derive(Serialize)]
+pub(super) struct AgentJobs {
+    agent_id: String,
+    jobs: Vec<JobSnapshot>,
+}
+
+#[derive(Serialize)]
+pub(super) struct JobsResponse {
+    agents: Vec<AgentJobs>,
+}
+
+#[derive(Serialize)]
+pub(super) struct JobActionResponse {
+    success: bool,
+    message: String,
+}
+
+/// Periodic background jobs with their schedule and last result.
+pub(super) async fn list_jobs(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<JobsQuery>,
+) -> Result<Json<JobsResponse>, StatusCode> {
+    let runtime_configs = state.runtime_configs.load();
+
+    let mut agents: Vec<AgentJobs> = match &query.agent_id {
+        Some(agent_id) => {
+            let runtime_config = runtime_configs
+                .get(agent_id.as_str())
+                .ok_or(StatusCode::NOT_FOUND)?;
+            vec![AgentJobs {
+                agent_id: agent_id.to_string(),
+                jobs: runtime_config.jobs.snapshots(),
+            }]
+        }
+        None => runtime_configs
+            .iter()
+            .map(|(agent_id, runtime_config)| AgentJobs {
+                agent_id: agent_id.clone(),
+                jobs: runtime_config.jobs.snapshots(),
+            })
+            .collect(),
+    };
+    agents.sort_by(|a, b| a.agent_id.cmp(&b.agent_id));
+
+    Ok(Json(JobsResponse { agents }))
+}
+
+/// Run a job as soon as its loop is idle, without waiting for its interval.
+pub(super) async fn trigger_job(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<TriggerJobRequest>,
+) -> Result<Json<JobActionResponse>, StatusCode> {
+    let runtime_configs = state.runtime_configs.load();
+    let runtime_config = runtime_configs
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let job = runtime_config
+        .jobs
+        .get(&request.name)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    job.trigger();
+
+    Ok(Json(JobActionResponse {
+        success: true,
+        message: format!("Job '{}' triggered", request.name),
+    }))
+}
+
+/// Pause or resume a job's scheduled runs. Not persisted across restarts.
+pub(super) async fn toggle_job(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<ToggleJobRequest>,
+) -> Result<Json<JobActionResponse>, StatusCode> {
+    let runtime_configs = state.runtime_configs.load();
+    let runtime_config = runtime_configs
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let job = runtime_config
+        .jobs
+        .get(&request.name)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    job.set_enabled(request.enabled);
+
+    let status = if request.enabled {
+        "enabled"
+    } else {
+        "disabled"
+    };
+    Ok(Json(JobActionResponse {
+        success: true,
+        message: format!("Job '{}' {}", request.name, status),
+    }))
+}
@@ -1,10 +1,16 @@
+use super::ids::AgentId;
 use super::state::ApiState;
 
+use crate::config::RuntimeConfig;
+use crate::memory::pins::{check_pin_budget, pinned_words};
 use crate::memory::search::{SearchConfig, SearchMode};
 use crate::memory::types::{Association, Memory, MemorySearchResult, MemoryType};
+use crate::memory::{BulletinPin, BulletinPinStore, NewBulletinPin};
+use crate::memory::{MemoryAuditAction, MemoryAuditEntry, MemoryAuditStore, MemorySearch};
+use crate::tools::memory_save::MAX_MEMORY_CONTENT_BYTES;
 
 use axum::Json;
-use axum::extract::{Query, State};
+use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -35,7 +41,7 @@ pub(super) struct MemoryGraphNeighborsResponse {
 
 #[derive(Deserialize)]
 pub(super) struct MemoriesListQuery {
-    agent_id: String,
+    agent_id: AgentId,
     #[serde(default = "default_memories_limit")]
     limit: i64,
     #[serde(default)]
@@ -78,12 +84,15 @@ pub(super) fn parse_memory_type(type_str: &str) -> Option<MemoryType> {
 
 #[derive(Deserialize)]
 pub(super) struct MemoriesSearchQuery {
-    agent_id: String,
+    agent_id: AgentId,
     q: String,
     #[serde(default = "default_search_limit")]
     limit: usize,
     #[serde(default)]
     memory_type: Option<String>,
+    /// Attach per-source score breakdowns to each result.
+    #[serde(default)]
+    explain: bool,
 }
 
 fn default_search_limit() -> usize {
@@ -92,7 +101,7 @@ fn default_search_limit() -> usize {
 
 #[derive(Deserialize)]
 pub(super) struct MemoryGraphQuery {
-    agent_id: String,
+    agent_id: AgentId,
     #[serde(default = "default_graph_limit")]
     limit: i64,
     #[serde(default)]
@@ -109,7 +118,7 @@ fn default_graph_limit() -> i64 {
 
 #[derive(Deserialize)]
 pub(super) struct MemoryGraphNeighborsQuery {
-    agent_id: String,
+    agent_id: AgentId,
     memory_id: String,
     #[serde(default = "default_neighbor_depth")]
     depth: u32,
@@ -128,7 +137,9 @@ pub(super) async fn list_memories(
     Query(query): Query<MemoriesListQuery>,
 ) -> Result<Json<MemoriesListResponse>, StatusCode> {
     let searches = state.memory_searches.load();
-    let memory_search = searches.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let memory_search = searches
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
     let store = memory_search.store();
 
     let limit = query.limit.min(200);
@@ -156,12 +167,15 @@ pub(super) async fn search_memories(
     Query(query): Query<MemoriesSearchQuery>,
 ) -> Result<Json<MemoriesSearchResponse>, StatusCode> {
     let searches = state.memory_searches.load();
-    let memory_search = searches.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let memory_search = searches
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let config = SearchConfig {
         mode: SearchMode::Hybrid,
         memory_type: query.memory_type.as_deref().and_then(parse_memory_type),
         max_results: query.limit.min(100),
+        explain: query.explain,
         ..SearchConfig::default()
     };
 
@@ -181,7 +195,9 @@ pub(super) async fn memory_graph(
     Query(query): Query<MemoryGraphQuery>,
 ) -> Result<Json<MemoryGraphResponse>, StatusCode> {
     let searches = state.memory_searches.load();
-    let memory_search = searches.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let memory_search = searches
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
     let store = memory_search.store();
 
     let limit = query.limit.min(500);
@@ -223,7 +239,9 @@ pub(super) async fn memory_graph_neighbors(
     Query(query): Query<MemoryGraphNeighborsQuery>,
 ) -> Result<Json<MemoryGraphNeighborsResponse>, StatusCode> {
     let searches = state.memory_searches.load();
-    let memory_search = searches.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let memory_search = searches
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
     let store = memory_search.store();
 
     let depth = query.depth.min(3);
@@ -245,3 +263,433 @@ pub(super) async fn memory_graph_neighbors(
 
     Ok(Json(MemoryGraphNeighborsResponse { nodes, edges }))
 }
+
+#[derive(Deserialize)]
+pub(super) struct MemoryQuery {
+    agent_id: AgentId,
+}
+
+#[derive(Deserialize)]
+pub(super) struct MemoryAuditQuery {
+    agent_id: AgentId,
+    #[serde(default)]
+    memory_id: Option<String>,
+    #[serde(default = "default_memories_limit")]
+    limit: i64,
+}
+
+#[derive(Deserialize)]
+pub(super) struct CreateMemoryRequest {
+    agent_id: AgentId,
+    content: String,
+    #[serde(default = "default_memory_type")]
+    memory_type: MemoryType,
+    #[serde(default)]
+    importance: Option<f32>,
+}
+
+fn default_memory_type() -> MemoryType {
+    MemoryType::Fact
+}
+
+#[derive(Deserialize)]
+pub(super) struct UpdateMemoryRequest {
+    agent_id: AgentId,
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    memory_type: Option<MemoryType>,
+    #[serde(default)]
+    importance: Option<f32>,
+}
+
+#[derive(Serialize)]
+pub(super) struct MemoryDetailResponse {
+    memory: Memory,
+    associations: Vec<Association>,
+    history: Vec<MemoryAuditEntry>,
+}
+
+#[derive(Serialize)]
+pub(super) struct MemoryResponse {
+    memory: Memory,
+}
+
+#[derive(Serialize)]
+pub(super) struct MemoryAuditResponse {
+    entries: Vec<MemoryAuditEntry>,
+}
+
+/// Actor recorded in the audit trail for edits made through this API.
+const API_ACTOR: &str = "api";
+
+fn memory_context(
+    state: &ApiState,
+    agent_id: &AgentId,
+) -> Result<(Arc<MemorySearch>, MemoryAuditStore), StatusCode> {
+    let memory_search = state
+        .memory_searches
+        .load()
+        .get(agent_id.as_str())
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let pools = state.agent_pools.load();
+    let pool = pools.get(agent_id.as_str()).ok_or(StatusCode::NOT_FOUND)?;
+    Ok((memory_search, MemoryAuditStore::new(pool.clone())))
+}
+
+fn validate_memory_fields(content: Option<&str>, importance: Option<f32>) -> Result<(), String> {
+    if let Some(content) = content {
+        if content.trim().is_empty() {
+            return Err("content must not be empty".into());
+        }
+        if content.len() > MAX_MEMORY_CONTENT_BYTES {
+            return Err(format!(
+                "content exceeds maximum length of {MAX_MEMORY_CONTENT_BYTES} bytes"
+            ));
+        }
+    }
+    if let Some(importance) = importance
+        && !(0.0..=1.0).contains(&importance)
+    {
+        return Err("importance must be between 0.0 and 1.0".into());
+    }
+    Ok(())
+}
+
+async fn load_live_memory(
+    memory_search: &MemorySearch,
+    memory_id: &str,
+) -> Result<Memory, (StatusCode, String)> {
+    memory_search
+        .store()
+        .load(memory_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, memory_id, "failed to load memory");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to load memory".to_string(),
+            )
+        })?
+        .filter(|memory| !memory.forgotten)
+        .ok_or((StatusCode::NOT_FOUND, "memory not found".to_string()))
+}
+
+async fn record_audit(
+    audit: &MemoryAuditStore,
+    action: MemoryAuditAction,
+    before: Option<&Memory>,
+    after: Option<&Memory>,
+) {
+    if let Err(error) = audit.record(action, API_ACTOR, before, after).await {
+        tracing::warn!(%error, action = action.as_str(), "failed to record memory audit entry");
+    }
+}
+
+/// GET /agents/memories/{id} — one memory with its associations and edit
+/// history.
+pub(super) async fn get_memory(
+    State(state): State<Arc<ApiState>>,
+    Path(memory_id): Path<String>,
+    Query(query): Query<MemoryQuery>,
+) -> Result<Json<MemoryDetailResponse>, StatusCode> {
+    let (memory_search, audit) = memory_context(&state, &query.agent_id)?;
+    let internal_error = |error: crate::error::Error| {
+        tracing::error!(%error, memory_id = %memory_id, "failed to load memory");
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    let store = memory_search.store();
+    let memory = store
+        .load(&memory_id)
+        .await
+        .map_err(internal_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let associations = store
+        .get_associations(&memory_id)
+        .await
+        .map_err(internal_error)?;
+    let history = audit
+        .list(Some(&memory_id), 50)
+        .await
+        .map_err(internal_error)?;
+
+    Ok(Json(MemoryDetailResponse {
+        memory,
+        associations,
+        history,
+    }))
+}
+
+/// POST /agents/memories — create a memory by hand.
+pub(super) async fn create_memory(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<CreateMemoryRequest>,
+) -> Result<Json<MemoryResponse>, (StatusCode, String)> {
+    let (memory_search, audit) = memory_context(&state, &request.agent_id)
+        .map_err(|status| (status, "agent not found".into()))?;
+    validate_memory_fields(Some(&request.content), request.importance)
+        .map_err(|message| (StatusCode::BAD_REQUEST, message))?;
+
+    let mut memory =
+        Memory::new(request.content.trim(), request.memory_type).with_source(API_ACTOR);
+    if let Some(importance) = request.importance {
+        memory = memory.with_importance(importance);
+    }
+
+    let internal_error = |error: crate::error::Error| {
+        tracing::error!(%error, "failed to create memory");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "failed to create memory".to_string(),
+        )
+    };
+    let store = memory_search.store();
+    store.save(&memory).await.map_err(internal_error)?;
+    if let Err(error) = memory_search
+        .index_memory(&memory.id, &memory.content)
+        .await
+    {
+        // Don't leave a row that search can never find.
+        if let Err(error) = store.delete(&memory.id).await {
+            tracing::error!(%error, memory_id = %memory.id, "compensating delete failed");
+        }
+        return Err(internal_error(error));
+    }
+    record_audit(&audit, MemoryAuditAction::Create, None, Some(&memory)).await;
+
+    tracing::info!(agent_id = %request.agent_id, memory_id = %memory.id, "memory created via API");
+    Ok(Json(MemoryResponse { memory }))
+}
+
+/// PUT /agents/memories/{id} — correct a memory's content, type, or
+/// importance. Content changes are re-embedded.
+pub(super) async fn update_memory(
+    State(state): State<Arc<ApiState>>,
+    Path(memory_id): Path<String>,
+    Json(request): Json<UpdateMemoryRequest>,
+) -> Result<Json<MemoryResponse>, (StatusCode, String)> {
+    let (memory_search, audit) = memory_context(&state, &request.agent_id)
+        .map_err(|status| (status, "agent not found".into()))?;
+    validate_memory_fields(request.content.as_deref(), request.importance)
+        .map_err(|message| (StatusCode::BAD_REQUEST, message))?;
+    let before = load_live_memory(&memory_search, &memory_id).await?;
+
+    let mut memory = before.clone();
+    let content = request
+        .content
+        .map(|content| content.trim().to_string())
+        .filter(|content| *content != before.content);
+    if let Some(content) = &content {
+        memory.content = content.clone();
+    }
+    if let Some(memory_type) = request.memory_type {
+        memory.memory_type = memory_type;
+    }
+    if let Some(importance) = request.importance {
+        memory.importance = importance;
+    }
+    if memory == before {
+        return Ok(Json(MemoryResponse { memory }));
+    }
+    memory.updated_at = chrono::Utc::now();
+
+    let internal_error = |error: crate::error::Error| {
+        tracing::error!(%error, memory_id = %memory_id, "failed to update memory");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "failed to update memory".to_string(),
+        )
+    };
+    memory_search
+        .store()
+        .update(&memory)
+        .await
+        .map_err(internal_error)?;
+    if content.is_some()
+        && let Err(error) = memory_search
+            .index_memory(&memory.id, &memory.content)
+            .await
+    {
+        tracing::warn!(%error, memory_id = %memory_id, "failed to re-embed updated memory");
+    }
+    record_audit(
+        &audit,
+        MemoryAuditAction::Update,
+        Some(&before),
+        Some(&memory),
+    )
+    .await;
+
+    tracing::info!(agent_id = %request.agent_id, memory_id = %memory_id, "memory updated via API");
+    Ok(Json(MemoryResponse { memory }))
+}
+
+/// DELETE /agents/memories/{id} — forget a memory. Like `memory_delete`, the
+/// row is kept but excluded from search and recall.
+pub(super) async fn delete_memory(
+    State(state): State<Arc<ApiState>>,
+    Path(memory_id): Path<String>,
+    Query(query): Query<MemoryQuery>,
+) -> Result<Json<MemoryResponse>, (StatusCode, String)> {
+    let (memory_search, audit) = memory_context(&state, &query.agent_id)
+        .map_err(|status| (status, "agent not found".into()))?;
+    let before = load_live_memory(&memory_search, &memory_id).await?;
+
+    let forgotten = memory_search
+        .store()
+        .forget(&memory_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, memory_id = %memory_id, "failed to forget memory");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to forget memory".to_string(),
+            )
+        })?;
+    if !forgotten {
+        return Err((StatusCode::NOT_FOUND, "memory not found".into()));
+    }
+    let mut memory = before.clone();
+    memory.forgotten = true;
+    record_audit(
+        &audit,
+        MemoryAuditAction::Forget,
+        Some(&before),
+        Some(&memory),
+    )
+    .await;
+
+    tracing::info!(agent_id = %query.agent_id, memory_id = %memory_id, "memory forgotten via API");
+    Ok(Json(MemoryResponse { memory }))
+}
+
+/// GET /agents/memories/audit — manual edits, newest first.
+pub(super) async fn memory_audit(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<MemoryAuditQuery>,
+) -> Result<Json<MemoryAuditResponse>, StatusCode> {
+    let (_, audit) = memory_context(&state, &query.agent_id)?;
+    let entries = audit
+        .list(query.memory_id.as_deref(), query.limit.clamp(1, 500))
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to list memory audit entries");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(MemoryAuditResponse { entries }))
+}
+
+#[derive(Deserialize)]
+pub(super) struct BulletinQuery {
+    agent_id: AgentId,
+}
+
+#[derive(Deserialize)]
+pub(super) struct BulletinPinInput {
+    content: String,
+    /// Seconds until the pin lapses. Omit to keep it until unpinned.
+    #[serde(default)]
+    ttl_secs: Option<u32>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct BulletinUpdateRequest {
+    agent_id: AgentId,
+    pins: Vec<BulletinPinInput>,
+}
+
+#[derive(Serialize)]
+pub(super) struct BulletinResponse {
+    /// The bulletin as prompts see it: pins, then the generated summary.
+    rendered: String,
+    /// The latest generated summary, without pins.
+    generated: String,
+    pins: Vec<BulletinPin>,
+    pinned_words: usize,
+    max_pinned_words: usize,
+}
+
+fn bulletin_response(runtime_config: &RuntimeConfig, pins: Vec<BulletinPin>) -> BulletinResponse {
+    BulletinResponse {
+        rendered: runtime_config.rendered_bulletin(),
+        generated: runtime_config.memory_bulletin.load().to_string(),
+        pinned_words: pinned_words(pins.iter().map(|pin| pin.content.as_str())),
+        pins,
+        max_pinned_words: runtime_config.cortex.load().bulletin_pin_max_words,
+    }
+}
+
+fn bulletin_context(
+    state: &ApiState,
+    agent_id: &AgentId,
+) -> Result<(BulletinPinStore, Arc<RuntimeConfig>), StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools.get(agent_id.as_str()).ok_or(StatusCode::NOT_FOUND)?;
+    let runtime_config = state
+        .runtime_configs
+        .load()
+        .get(agent_id.as_str())
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok((BulletinPinStore::new(pool.clone()), runtime_config))
+}
+
+/// GET /agents/memories/bulletin — the current bulletin and its pins.
+pub(super) async fn get_bulletin(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<BulletinQuery>,
+) -> Result<Json<BulletinResponse>, StatusCode> {
+    let (store, runtime_config) = bulletin_context(&state, &query.agent_id)?;
+    let pins = store.reload(&runtime_config).await.map_err(|error| {
+        tracing::error!(%error, "failed to load bulletin pins");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(bulletin_response(&runtime_config, pins)))
+}
+
+/// PUT /agents/memories/bulletin — replace every pin. Takes effect on the
+/// next prompt.
+pub(super) async fn update_bulletin(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<BulletinUpdateRequest>,
+) -> Result<Json<BulletinResponse>, (StatusCode, String)> {
+    let (store, runtime_config) = bulletin_context(&state, &request.agent_id)
+        .map_err(|status| (status, "agent not found".into()))?;
+    let max_words = runtime_config.cortex.load().bulletin_pin_max_words;
+    check_pin_budget(
+        request.pins.iter().map(|pin| pin.content.as_str()),
+        max_words,
+    )
+    .map_err(|error| (StatusCode::BAD_REQUEST, error.to_string()))?;
+
+    let now = chrono::Utc::now();
+    let pins: Vec<NewBulletinPin> = request
+        .pins
+        .into_iter()
+        .map(|pin| NewBulletinPin {
+            content: pin.content,
+            expires_at: pin
+                .ttl_secs
+                .map(|secs| now + chrono::Duration::seconds(i64::from(secs))),
+        })
+        .collect();
+
+    let internal_error = |error: crate::error::Error| {
+        tracing::error!(%error, "failed to update bulletin pins");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "failed to update bulletin pins".to_string(),
+        )
+    };
+    store.replace(&pins, "api").await.map_err(internal_error)?;
+    let pins = store
+        .reload(&runtime_config)
+        .await
+        .map_err(internal_error)?;
+
+    tracing::info!(agent_id = %request.agent_id, pins = pins.len(), "bulletin pins updated");
+    Ok(Json(bulletin_response(&runtime_config, pins)))
+}
@@ -836,7 +836,8 @@ pub(super) async fn toggle_platform(
                                 "discord",
                                 &discord_config.token,
                                 perms,
-                            );
+                            )
+                            .with_voice(discord_config.voice.clone());
                             if let Err(error) = manager.register_and_start(adapter).await {
                                 tracing::error!(%error, "failed to start discord adapter on toggle");
                             }
@@ -851,17 +852,19 @@ pub(super) async fn toggle_platform(
                                 "discord",
                                 Some(instance.name.as_str()),
                             );
-                            let perms = std::sync::Arc::new(arc_swap::ArcSwap::from_pointee(
+                            let perms = manager.instance_permissions().discord.track(
+                                &runtime_key,
                                 crate::config::DiscordPermissions::from_instance_config(
                                     instance,
                                     &new_config.bindings,
                                 ),
-                            ));
+                            );
                             let adapter = crate::messaging::discord::DiscordAdapter::new(
                                 runtime_key,
                                 &instance.token,
                                 perms,
-                            );
+                            )
+                            .with_voice(discord_config.voice.clone());
                             if let Err(error) = manager.register_and_start(adapter).await {
                                 tracing::error!(%error, adapter = %instance.name, "failed to start named discord adapter on toggle");
                             }
@@ -917,12 +920,13 @@ pub(super) async fn toggle_platform(
                                 "slack",
                                 Some(instance.name.as_str()),
                             );
-                            let perms = std::sync::Arc::new(arc_swap::ArcSwap::from_pointee(
+                            let perms = manager.instance_permissions().slack.track(
+                                &runtime_key,
                                 crate::config::SlackPermissions::from_instance_config(
                                     instance,
                                     &new_config.bindings,
                                 ),
-                            ));
+                            );
                             match crate::messaging::slack::SlackAdapter::new(
                                 runtime_key,
                                 &instance.bot_token,
@@ -970,12 +974,13 @@ pub(super) async fn toggle_platform(
                                 "telegram",
                                 Some(instance.name.as_str()),
                             );
-                            let perms = std::sync::Arc::new(arc_swap::ArcSwap::from_pointee(
+                            let perms = manager.instance_permissions().telegram.track(
+                                &runtime_key,
                                 crate::config::TelegramPermissions::from_instance_config(
                                     instance,
                                     &new_config.bindings,
                                 ),
-                            ));
+                            );
                             let adapter = crate::messaging::telegram::TelegramAdapter::new(
                                 runtime_key,
                                 &instance.token,
@@ -1062,12 +1067,13 @@ pub(super) async fn toggle_platform(
                             );
                             let instance_dir = state.instance_dir.load();
                             let token_path = instance_dir.join(token_file_name);
-                            let perms = std::sync::Arc::new(arc_swap::ArcSwap::from_pointee(
+                            let perms = manager.instance_permissions().twitch.track(
+                                &runtime_key,
                                 crate::config::TwitchPermissions::from_instance_config(
                                     instance,
                                     &new_config.bindings,
                                 ),
-                            ));
+                            );
                             let adapter = crate::messaging::twitch::TwitchAdapter::new(
                                 runtime_key,
                                 &instance.username,
@@ -1,7 +1,8 @@
 use super::state::ApiState;
+use crate::llm::catalog::ModelPrice;
 
 use axum::Json;
-use axum::extract::{Query, State};
+use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -23,6 +24,8 @@ pub(super) struct ModelInfo {
     reasoning: bool,
     /// Whether this model accepts audio input.
     input_audio: bool,
+    /// List price, if known
+    pricing: Option<ModelPrice>,
 }
 
 #[derive(Serialize)]
@@ -36,6 +39,21 @@ pub(super) struct ModelsQuery {
     capability: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub(super) struct ProviderModelsQuery {
+    /// Bypass the cache and ask the provider again.
+    #[serde(default)]
+    refresh: bool,
+}
+
+#[derive(Serialize)]
+pub(super) struct ProviderModelsResponse {
+    provider: String,
+    models: Vec<ModelInfo>,
+    /// When the list was fetched from the provider (RFC 3339).
+    fetched_at: String,
+}
+
 #[derive(Deserialize)]
 struct ModelsDevProvider {
     #[allow(dead_code)]
@@ -58,6 +76,14 @@ struct ModelsDevModel {
     limit: Option<ModelsDevLimit>,
     modalities: Option<ModelsDevModalities>,
     status: Option<String>,
+    cost: Option<ModelsDevCost>,
+}
+
+/// USD per million tokens.
+#[derive(Deserialize)]
+struct ModelsDevCost {
+    input: Option<f64>,
+    output: Option<f64>,
 }
 
 #[derive(Deserialize)]
@@ -78,6 +104,13 @@ static MODELS_CACHE: std::sync::LazyLock<
 
 const MODELS_CACHE_TTL: std::time::Duration = std::time::Duration::from_secs(3600);
 
+/// Model lists fetched from the providers themselves, keyed by provider ID,
+/// with the time they were fetched.
+type ProviderModels = HashMap<String, (Vec<ModelInfo>, chrono::DateTime<chrono::Utc>)>;
+
+static PROVIDER_MODELS_CACHE: std::sync::LazyLock<tokio::sync::RwLock<ProviderModels>> =
+    std::sync::LazyLock::new(|| tokio::sync::RwLock::new(HashMap::new()));
+
 /// Models known to work with Spacebot's current voice transcription path
 /// (OpenAI-compatible `/v1/chat/completions` with `input_audio`).
 const KNOWN_VOICE_TRANSCRIPTION_MODELS: &[&str] = &[
@@ -141,6 +174,7 @@ fn as_openai_chatgpt_model(model: &ModelInfo) -> Option<ModelInfo> {
         tool_call: model.tool_call,
         reasoning: model.reasoning,
         input_audio: model.input_audio,
+        pricing: model.pricing,
     })
 }
 
@@ -156,6 +190,7 @@ fn extra_models() -> Vec<ModelInfo> {
             tool_call: true,
             reasoning: true,
             input_audio: false,
+            pricing: None,
         },
         // Moonshot AI (Kimi) - moonshot-v1-8k not on models.dev
         ModelInfo {
@@ -166,6 +201,7 @@ fn extra_models() -> Vec<ModelInfo> {
             tool_call: false,
             reasoning: false,
             input_audio: false,
+            pricing: None,
         },
     ]
 }
@@ -224,6 +260,13 @@ async fn fetch_models_dev() -> anyhow::Result<Vec<ModelInfo>> {
                         .any(|input| input.to_lowercase().contains("audio"))
                 });
 
+            let pricing = model.cost.as_ref().and_then(|cost| {
+                Some(ModelPrice {
+                    input_per_million: cost.input?,
+                    output_per_million: cost.output?,
+                })
+            });
+
             models.push(ModelInfo {
                 id: routing_id,
                 name: model.name.clone(),
@@ -232,6 +275,7 @@ async fn fetch_models_dev() -> anyhow::Result<Vec<ModelInfo>> {
                 tool_call: model.tool_call,
                 reasoning: model.reasoning,
                 input_audio,
+                pricing,
             });
         }
     }
@@ -458,3 +502,93 @@ pub(super) async fn refresh_models(
     )
     .await
 }
+
+/// Models a provider offers with the configured credentials, fetched from
+/// the provider's own model list and annotated from the models.dev catalog
+/// where it knows the model. Cached per provider for an hour.
+pub(super) async fn get_provider_models(
+    State(state): State<Arc<ApiState>>,
+    Path(provider): Path<String>,
+    Query(query): Query<ProviderModelsQuery>,
+) -> Result<Json<ProviderModelsResponse>, StatusCode> {
+    let provider = provider.trim().to_lowercase();
+
+    if !query.refresh {
+        let cache = PROVIDER_MODELS_CACHE.read().await;
+        if let Some((models, fetched_at)) = cache.get(&provider)
+            && (chrono::Utc::now() - *fetched_at)
+                .to_std()
+                .is_ok_and(|age| age < MODELS_CACHE_TTL)
+        {
+            return Ok(Json(ProviderModelsResponse {
+                provider,
+                models: models.clone(),
+                fetched_at: fetched_at.to_rfc3339(),
+            }));
+        }
+    }
+
+    let llm_manager = state
+        .llm_manager
+        .read()
+        .await
+        .clone()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    let listed = match crate::llm::catalog::list_provider_models(&llm_manager, &provider).await {
+        Ok(listed) => listed,
+        Err(error) => {
+            if let Some(crate::error::Error::Llm(llm_error)) = error.downcast_ref()
+                && matches!(**llm_error, crate::error::LlmError::UnknownProvider(_))
+            {
+                return Err(StatusCode::NOT_FOUND);
+            }
+            tracing::warn!(%provider, %error, "failed to list provider models");
+            // Serve a stale list rather than nothing.
+            let cache = PROVIDER_MODELS_CACHE.read().await;
+            let (models, fetched_at) = cache.get(&provider).ok_or(StatusCode::BAD_GATEWAY)?;
+            return Ok(Json(ProviderModelsResponse {
+                provider,
+                models: models.clone(),
+                fetched_at: fetched_at.to_rfc3339(),
+            }));
+        }
+    };
+
+    let catalog = ensure_models_cache().await;
+    let models: Vec<ModelInfo> = listed
+        .into_iter()
+        .map(|listed| {
+            let id = format!("{provider}/{}", listed.id);
+            let known = catalog.iter().find(|model| model.id == id);
+            ModelInfo {
+                id: id.clone(),
+                name: listed
+                    .name
+                    .or_else(|| known.map(|model| model.name.clone()))
+                    .unwrap_or_else(|| listed.id.clone()),
+                provider: provider.clone(),
+                context_window: listed
+                    .context_window
+                    .or_else(|| known.and_then(|model| model.context_window)),
+                tool_call: known.is_some_and(|model| model.tool_call),
+                reasoning: known.is_some_and(|model| model.reasoning),
+                input_audio: known.is_some_and(|model| model.input_audio),
+                pricing: listed
+                    .pricing
+                    .or_else(|| known.and_then(|model| model.pricing)),
+            }
+        })
+        .collect();
+
+    let fetched_at = chrono::Utc::now();
+    PROVIDER_MODELS_CACHE
+        .write()
+        .await
+        .insert(provider.clone(), (models.clone(), fetched_at));
+
+    Ok(Json(ProviderModelsResponse {
+        provider,
+        models,
+        fetched_at: fetched_at.to_rfc3339(),
+    }))
+}
@@ -0,0 +1,76 @@
+//! API handlers for the outbound delivery queue.
+
+use super::ids::{AgentId, ChannelId};
+use super::state::ApiState;
+
+use crate::messaging::outbox::{Outbox, OutboxFailure};
+
+use axum::Json;
+use axum::extract::{Query, State};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub(super) struct OutboxFailuresQuery {
+    agent_id: Option<AgentId>,
+    channel_id: Option<ChannelId>,
+    #[serde(default = "default_failure_limit")]
+    limit: i64,
+}
+
+fn default_failure_limit() -> i64 {
+    50
+}
+
+#[derive(Serialize)]
+pub(super) struct AgentOutboxFailure {
+    agent_id: String,
+    #[serde(flatten)]
+    failure: OutboxFailure,
+}
+
+#[derive(Serialize)]
+pub(super) struct OutboxFailuresResponse {
+    failures: Vec<AgentOutboxFailure>,
+}
+
+/// List responses that could not be delivered to their platform, newest
+/// first, across agents.
+pub(super) async fn list_outbox_failures(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<OutboxFailuresQuery>,
+) -> Json<OutboxFailuresResponse> {
+    let pools = state.agent_pools.load();
+    let limit = query.limit.clamp(1, 500);
+    let mut failures = Vec::new();
+
+    for (agent_id, pool) in pools.iter() {
+        if query.agent_id.as_deref().is_some_and(|id| id != agent_id) {
+            continue;
+        }
+        let outbox = Outbox::new(pool.clone());
+        match outbox
+            .list_failures(query.channel_id.as_deref(), limit)
+            .await
+        {
+            Ok(agent_failures) => {
+                failures.extend(
+                    agent_failures
+                        .into_iter()
+                        .map(|failure| AgentOutboxFailure {
+                            agent_id: agent_id.clone(),
+                            failure,
+                        }),
+                );
+            }
+            Err(error) => {
+                tracing::warn!(%error, agent_id, "failed to list outbox failures");
+            }
+        }
+    }
+
+    failures.sort_by(|left, right| right.failure.failed_at.cmp(&left.failure.failed_at));
+    failures.truncate(limit as usize);
+
+    Json(OutboxFailuresResponse { failures })
+}
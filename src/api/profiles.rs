@@ -0,0 +1,245 @@
+//! REST API handlers for reviewing and editing user profiles.
+
+use super::ids::AgentId;
+use super::state::ApiState;
+
+use crate::memory::profiles::{ProfileFactKind, UserProfile, UserProfileStore};
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub(super) struct AgentQuery {
+    agent_id: AgentId,
+}
+
+#[derive(Deserialize)]
+pub(super) struct ProfilesListQuery {
+    agent_id: AgentId,
+    #[serde(default)]
+    platform: Option<String>,
+    #[serde(default = "default_profiles_limit")]
+    limit: i64,
+}
+
+fn default_profiles_limit() -> i64 {
+    100
+}
+
+#[derive(Deserialize)]
+pub(super) struct UpdateProfileRequest {
+    agent_id: AgentId,
+    /// IANA timezone. An empty string clears it; omitting it leaves it as is.
+    #[serde(default)]
+    timezone: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct ProfileFactRequest {
+    agent_id: AgentId,
+    kind: String,
+    content: String,
+}
+
+#[derive(Serialize)]
+pub(super) struct ProfilesListResponse {
+    profiles: Vec<UserProfile>,
+}
+
+#[derive(Serialize)]
+pub(super) struct ProfileResponse {
+    profile: UserProfile,
+}
+
+#[derive(Serialize)]
+pub(super) struct ActionResponse {
+    success: bool,
+    message: String,
+}
+
+fn profile_store(state: &ApiState, agent_id: &AgentId) -> Result<UserProfileStore, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools.get(agent_id.as_str()).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(UserProfileStore::new(pool.clone()))
+}
+
+async fn load_profile(
+    store: &UserProfileStore,
+    profile_id: &str,
+) -> Result<UserProfile, StatusCode> {
+    store
+        .get(profile_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to load user profile");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+fn parse_fact(request: &ProfileFactRequest) -> Result<(ProfileFactKind, &str), StatusCode> {
+    let kind = ProfileFactKind::parse(&request.kind).ok_or(StatusCode::BAD_REQUEST)?;
+    let content = request.content.trim();
+    if content.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    Ok((kind, content))
+}
+
+/// GET /agents/user-profiles — list profiles, most recently seen first.
+pub(super) async fn list_profiles(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<ProfilesListQuery>,
+) -> Result<Json<ProfilesListResponse>, StatusCode> {
+    let store = profile_store(&state, &query.agent_id)?;
+    let profiles = store
+        .list(query.platform.as_deref(), query.limit.clamp(1, 500))
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to list user profiles");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(ProfilesListResponse { profiles }))
+}
+
+/// GET /agents/user-profiles/{id} — get one profile with its facts.
+pub(super) async fn get_profile(
+    State(state): State<Arc<ApiState>>,
+    Path(profile_id): Path<String>,
+    Query(query): Query<AgentQuery>,
+) -> Result<Json<ProfileResponse>, StatusCode> {
+    let store = profile_store(&state, &query.agent_id)?;
+    let profile = load_profile(&store, &profile_id).await?;
+    Ok(Json(ProfileResponse { profile }))
+}
+
+/// PUT /agents/user-profiles/{id} — set or clear the timezone.
+pub(super) async fn update_profile(
+    State(state): State<Arc<ApiState>>,
+    Path(profile_id): Path<String>,
+    Json(request): Json<UpdateProfileRequest>,
+) -> Result<Json<ProfileResponse>, StatusCode> {
+    let store = profile_store(&state, &request.agent_id)?;
+
+    if let Some(timezone) = request.timezone.as_deref().map(str::trim) {
+        let timezone = if timezone.is_empty() {
+            None
+        } else {
+            timezone
+                .parse::<chrono_tz::Tz>()
+                .map_err(|_| StatusCode::BAD_REQUEST)?;
+            Some(timezone)
+        };
+        let updated = store
+            .set_timezone(&profile_id, timezone)
+            .await
+            .map_err(|error| {
+                tracing::error!(%error, "failed to update user profile");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        if !updated {
+            return Err(StatusCode::NOT_FOUND);
+        }
+    }
+
+    let profile = load_profile(&store, &profile_id).await?;
+    Ok(Json(ProfileResponse { profile }))
+}
+
+/// DELETE /agents/user-profiles/{id} — delete a profile and its facts.
+pub(super) async fn delete_profile(
+    State(state): State<Arc<ApiState>>,
+    Path(profile_id): Path<String>,
+    Query(query): Query<AgentQuery>,
+) -> Result<Json<ActionResponse>, StatusCode> {
+    let store = profile_store(&state, &query.agent_id)?;
+    let deleted = store.delete(&profile_id).await.map_err(|error| {
+        tracing::error!(%error, "failed to delete user profile");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    if !deleted {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    Ok(Json(ActionResponse {
+        success: true,
+        message: "Profile deleted".into(),
+    }))
+}
+
+/// POST /agents/user-profiles/{id}/facts — add a fact to a profile.
+pub(super) async fn add_fact(
+    State(state): State<Arc<ApiState>>,
+    Path(profile_id): Path<String>,
+    Json(request): Json<ProfileFactRequest>,
+) -> Result<Json<ProfileResponse>, StatusCode> {
+    let store = profile_store(&state, &request.agent_id)?;
+    let (kind, content) = parse_fact(&request)?;
+    // Make sure the profile exists before the fact's foreign key is checked.
+    load_profile(&store, &profile_id).await?;
+
+    store
+        .add_fact(&profile_id, kind, content)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to add profile fact");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let profile = load_profile(&store, &profile_id).await?;
+    Ok(Json(ProfileResponse { profile }))
+}
+
+/// PUT /agents/user-profiles/{id}/facts/{fact_id} — rewrite a fact.
+pub(super) async fn update_fact(
+    State(state): State<Arc<ApiState>>,
+    Path((profile_id, fact_id)): Path<(String, String)>,
+    Json(request): Json<ProfileFactRequest>,
+) -> Result<Json<ProfileResponse>, StatusCode> {
+    let store = profile_store(&state, &request.agent_id)?;
+    let (kind, content) = parse_fact(&request)?;
+
+    let updated = store
+        .update_fact(&profile_id, &fact_id, kind, content)
+        .await
+        .map_err(|error| {
+            // Rewriting a fact into one the profile already has violates
+            // the uniqueness constraint.
+            tracing::warn!(%error, "failed to update profile fact");
+            StatusCode::CONFLICT
+        })?;
+    if !updated {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let profile = load_profile(&store, &profile_id).await?;
+    Ok(Json(ProfileResponse { profile }))
+}
+
+/// DELETE /agents/user-profiles/{id}/facts/{fact_id} — remove a fact.
+pub(super) async fn delete_fact(
+    State(state): State<Arc<ApiState>>,
+    Path((profile_id, fact_id)): Path<(String, String)>,
+    Query(query): Query<AgentQuery>,
+) -> Result<Json<ActionResponse>, StatusCode> {
+    let store = profile_store(&state, &query.agent_id)?;
+    let deleted = store
+        .delete_fact(&profile_id, &fact_id)
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to delete profile fact");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if !deleted {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    Ok(Json(ActionResponse {
+        success: true,
+        message: "Fact removed".into(),
+    }))
+}
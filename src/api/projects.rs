@@ -1,5 +1,6 @@
 //! REST API handlers for project, repo, and worktree management.
 
+use super::ids::AgentId;
 use super::state::ApiState;
 
 use axum::Json;
@@ -50,19 +51,19 @@ fn sanitize_segment(name: &str) -> Result<String, StatusCode> {
 
 #[derive(Deserialize)]
 pub(super) struct AgentQuery {
-    agent_id: String,
+    agent_id: AgentId,
 }
 
 #[derive(Deserialize)]
 pub(super) struct ProjectListQuery {
-    agent_id: String,
+    agent_id: AgentId,
     #[serde(default)]
     status: Option<String>,
 }
 
 #[derive(Deserialize)]
 pub(super) struct CreateProjectRequest {
-    agent_id: String,
+    agent_id: AgentId,
     name: String,
     #[serde(default)]
     description: Option<String>,
@@ -80,7 +81,7 @@ pub(super) struct CreateProjectRequest {
 
 #[derive(Deserialize)]
 pub(super) struct UpdateProjectRequest {
-    agent_id: String,
+    agent_id: AgentId,
     #[serde(default)]
     name: Option<String>,
     #[serde(default)]
@@ -97,7 +98,7 @@ pub(super) struct UpdateProjectRequest {
 
 #[derive(Deserialize)]
 pub(super) struct CreateRepoRequest {
-    agent_id: String,
+    agent_id: AgentId,
     name: String,
     path: String,
     #[serde(default)]
@@ -110,7 +111,7 @@ pub(super) struct CreateRepoRequest {
 
 #[derive(Deserialize)]
 pub(super) struct CreateWorktreeRequest {
-    agent_id: String,
+    agent_id: AgentId,
     repo_id: String,
     branch: String,
     #[serde(default)]
@@ -315,7 +316,9 @@ pub(super) async fn list_projects(
     Query(query): Query<ProjectListQuery>,
 ) -> Result<Json<ProjectListResponse>, StatusCode> {
     let stores = state.project_stores.load();
-    let store = stores.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let status = query.status.as_deref().and_then(ProjectStatus::parse);
 
@@ -336,11 +339,13 @@ pub(super) async fn create_project(
     Json(request): Json<CreateProjectRequest>,
 ) -> Result<Json<ProjectResponse>, StatusCode> {
     let stores = state.project_stores.load();
-    let store = stores.get(&request.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let project = store
         .create_project(CreateProjectInput {
-            agent_id: request.agent_id.clone(),
+            agent_id: request.agent_id.to_string(),
             name: request.name,
             description: request.description.unwrap_or_default(),
             icon: request.icon.unwrap_or_default(),
@@ -420,7 +425,9 @@ pub(super) async fn get_project(
     Query(query): Query<AgentQuery>,
 ) -> Result<Json<ProjectResponse>, StatusCode> {
     let stores = state.project_stores.load();
-    let store = stores.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let project = store
         .get_project_with_relations(&query.agent_id, &project_id)
@@ -441,7 +448,9 @@ pub(super) async fn update_project(
     Json(request): Json<UpdateProjectRequest>,
 ) -> Result<Json<ProjectResponse>, StatusCode> {
     let stores = state.project_stores.load();
-    let store = stores.get(&request.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let status = request.status.as_deref().and_then(ProjectStatus::parse);
 
@@ -485,7 +494,9 @@ pub(super) async fn delete_project(
     Query(query): Query<AgentQuery>,
 ) -> Result<Json<ActionResponse>, StatusCode> {
     let stores = state.project_stores.load();
-    let store = stores.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let deleted = store
         .delete_project(&query.agent_id, &project_id)
@@ -515,7 +526,9 @@ pub(super) async fn scan_project(
     Query(query): Query<AgentQuery>,
 ) -> Result<Json<ProjectResponse>, StatusCode> {
     let stores = state.project_stores.load();
-    let store = stores.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let project = store
         .get_project(&query.agent_id, &project_id)
@@ -600,7 +613,9 @@ pub(super) async fn create_repo(
     Json(request): Json<CreateRepoRequest>,
 ) -> Result<Json<RepoResponse>, StatusCode> {
     let stores = state.project_stores.load();
-    let store = stores.get(&request.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     // Verify project exists.
     store
@@ -641,7 +656,9 @@ pub(super) async fn delete_repo(
     Query(query): Query<AgentQuery>,
 ) -> Result<Json<ActionResponse>, StatusCode> {
     let stores = state.project_stores.load();
-    let store = stores.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     // Verify the repo belongs to this project.
     let repo = store
@@ -678,7 +695,9 @@ pub(super) async fn create_worktree(
     Json(request): Json<CreateWorktreeRequest>,
 ) -> Result<Json<WorktreeResponse>, StatusCode> {
     let stores = state.project_stores.load();
-    let store = stores.get(&request.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     // Look up the project and repo.
     let project = store
@@ -766,7 +785,9 @@ pub(super) async fn delete_worktree(
     Query(query): Query<AgentQuery>,
 ) -> Result<Json<ActionResponse>, StatusCode> {
     let stores = state.project_stores.load();
-    let store = stores.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     // Look up worktree and project for the git removal.
     let worktree = store
@@ -838,7 +859,9 @@ pub(super) async fn disk_usage(
     Query(query): Query<AgentQuery>,
 ) -> Result<Json<DiskUsageResponse>, StatusCode> {
     let stores = state.project_stores.load();
-    let store = stores.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let project = store
         .get_project(&query.agent_id, &project_id)
@@ -0,0 +1,432 @@
+//! REST API handlers for editing prompt templates with version history.
+//!
+//! Edits apply to one agent and take effect on its next render: the agent's
+//! prompt engine is rebuilt with the active overrides and swapped into its
+//! runtime config.
+
+use super::access::ApiCaller;
+use super::ids::AgentId;
+use super::state::ApiState;
+
+use crate::audit::{AuditEvent, summarize_text};
+use crate::config::RuntimeConfig;
+use crate::prompts::PromptEngine;
+use crate::prompts::engine::TEMPLATE_NAMES;
+use crate::prompts::versions::{DiffLine, PromptVersion, PromptVersionStore, line_diff};
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub(super) struct AgentQuery {
+    agent_id: AgentId,
+}
+
+#[derive(Deserialize)]
+pub(super) struct PromptQuery {
+    agent_id: AgentId,
+    #[serde(default = "default_history_limit")]
+    history_limit: i64,
+}
+
+fn default_history_limit() -> i64 {
+    20
+}
+
+#[derive(Deserialize)]
+pub(super) struct UpdatePromptRequest {
+    agent_id: AgentId,
+    content: String,
+    #[serde(default)]
+    note: Option<String>,
+    /// Variables to render the template with when validating it. Anything
+    /// missing renders as empty.
+    #[serde(default)]
+    sample_context: Option<serde_json::Value>,
+    /// Validate and preview without saving.
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Deserialize)]
+pub(super) struct RollbackRequest {
+    agent_id: AgentId,
+}
+
+#[derive(Serialize)]
+pub(super) struct PromptSummary {
+    name: &'static str,
+    overridden: bool,
+}
+
+#[derive(Serialize)]
+pub(super) struct PromptsListResponse {
+    prompts: Vec<PromptSummary>,
+}
+
+/// A version without its content, for history listings.
+#[derive(Serialize)]
+pub(super) struct VersionSummary {
+    id: String,
+    version: i64,
+    /// Whether this version reset the template to the bundled one.
+    reset: bool,
+    note: Option<String>,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<PromptVersion> for VersionSummary {
+    fn from(version: PromptVersion) -> Self {
+        Self {
+            id: version.id,
+            version: version.version,
+            reset: version.content.is_none(),
+            note: version.note,
+            created_at: version.created_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub(super) struct PromptResponse {
+    name: String,
+    /// The source in use.
+    content: String,
+    overridden: bool,
+    /// The version in use, or `None` if the template was never edited.
+    version: Option<i64>,
+    history: Vec<VersionSummary>,
+}
+
+#[derive(Serialize)]
+pub(super) struct UpdatePromptResponse {
+    /// The saved version, or `None` for a dry run.
+    version: Option<i64>,
+    preview: String,
+}
+
+#[derive(Serialize)]
+pub(super) struct VersionResponse {
+    version: PromptVersion,
+    /// Changes from the source in use to this version.
+    diff: Vec<DiffLine>,
+}
+
+#[derive(Serialize)]
+pub(super) struct ActionResponse {
+    success: bool,
+    message: String,
+}
+
+fn version_store(state: &ApiState, agent_id: &AgentId) -> Result<PromptVersionStore, StatusCode> {
+    let pools = state.agent_pools.load();
+    let pool = pools.get(agent_id.as_str()).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(PromptVersionStore::new(pool.clone()))
+}
+
+fn runtime_config(state: &ApiState, agent_id: &AgentId) -> Result<Arc<RuntimeConfig>, StatusCode> {
+    state
+        .runtime_configs
+        .load()
+        .get(agent_id.as_str())
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+fn known_template(name: &str) -> Result<&'static str, StatusCode> {
+    TEMPLATE_NAMES
+        .iter()
+        .copied()
+        .find(|known| *known == name)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// The source a version stands for: its content, or the bundled template for
+/// a reset.
+pub(super) fn version_source(version: &PromptVersion) -> &str {
+    version
+        .content
+        .as_deref()
+        .or_else(|| PromptEngine::builtin_source(&version.name))
+        .unwrap_or_default()
+}
+
+/// The template source an agent renders now: its latest override, or the
+/// bundled template.
+async fn active_source(store: &PromptVersionStore, name: &str) -> crate::error::Result<String> {
+    let latest = store.latest(name).await?;
+    Ok(latest
+        .as_ref()
+        .map(version_source)
+        .or_else(|| PromptEngine::builtin_source(name))
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// Rebuild an agent's prompt engine from its active overrides.
+async fn reload_prompts(
+    store: &PromptVersionStore,
+    runtime_config: &RuntimeConfig,
+) -> Result<(), StatusCode> {
+    let overrides = store.active_overrides().await.map_err(|error| {
+        tracing::error!(%error, "failed to load prompt overrides");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let engine = runtime_config
+        .prompts
+        .load()
+        .with_overrides(&overrides)
+        .map_err(|error| {
+            tracing::error!(%error, "failed to rebuild prompt engine");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    runtime_config.prompts.store(Arc::new(engine));
+    Ok(())
+}
+
+/// GET /prompts — list templates and whether each has been edited.
+pub(super) async fn list_prompts(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<AgentQuery>,
+) -> Result<Json<PromptsListResponse>, StatusCode> {
+    let runtime_config = runtime_config(&state, &query.agent_id)?;
+    let prompts = runtime_config.prompts.load();
+    let prompts = TEMPLATE_NAMES
+        .iter()
+        .map(|&name| PromptSummary {
+            name,
+            overridden: prompts.is_overridden(name),
+        })
+        .collect();
+
+    Ok(Json(PromptsListResponse { prompts }))
+}
+
+/// GET /prompts/{name} — the source in use and recent versions.
+pub(super) async fn get_prompt(
+    State(state): State<Arc<ApiState>>,
+    Path(name): Path<String>,
+    Query(query): Query<PromptQuery>,
+) -> Result<Json<PromptResponse>, StatusCode> {
+    let name = known_template(&name)?;
+    let store = version_store(&state, &query.agent_id)?;
+    let history = store
+        .history(name, query.history_limit.clamp(1, 200))
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to load prompt history");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let latest = history.first();
+    let content = latest
+        .map(version_source)
+        .or_else(|| PromptEngine::builtin_source(name))
+        .unwrap_or_default()
+        .to_string();
+    let overridden = latest.is_some_and(|version| version.content.is_some());
+    let version = latest.map(|version| version.version);
+
+    Ok(Json(PromptResponse {
+        name: name.to_string(),
+        content,
+        overridden,
+        version,
+        history: history.into_iter().map(VersionSummary::from).collect(),
+    }))
+}
+
+/// PUT /prompts/{name} — validate an edited template and make it active.
+pub(super) async fn update_prompt(
+    State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
+    Path(name): Path<String>,
+    Json(request): Json<UpdatePromptRequest>,
+) -> Result<Json<UpdatePromptResponse>, (StatusCode, String)> {
+    let name = known_template(&name).map_err(|status| (status, "unknown template".into()))?;
+    let store = version_store(&state, &request.agent_id)
+        .map_err(|status| (status, "agent not found".into()))?;
+    let runtime_config = runtime_config(&state, &request.agent_id)
+        .map_err(|status| (status, "agent not found".into()))?;
+
+    let context = match &request.sample_context {
+        Some(sample @ serde_json::Value::Object(_)) => minijinja::Value::from_serialize(sample),
+        Some(_) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "sample_context must be an object".into(),
+            ));
+        }
+        None => minijinja::Value::UNDEFINED,
+    };
+    let preview = PromptEngine::preview(name, &request.content, context)
+        .map_err(|error| (StatusCode::BAD_REQUEST, format!("{error:#}")))?;
+
+    if request.dry_run {
+        return Ok(Json(UpdatePromptResponse {
+            version: None,
+            preview,
+        }));
+    }
+
+    let internal_error = |error: crate::error::Error| {
+        tracing::error!(%error, template = name, "failed to save prompt version");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "failed to save prompt".to_string(),
+        )
+    };
+    let previous = active_source(&store, name).await.map_err(internal_error)?;
+    let version = store
+        .save(name, Some(&request.content), request.note.as_deref())
+        .await
+        .map_err(internal_error)?;
+    reload_prompts(&store, &runtime_config)
+        .await
+        .map_err(|status| (status, "failed to reload prompts".into()))?;
+
+    tracing::info!(agent_id = %request.agent_id, template = name, version = version.version, "prompt template updated");
+    super::audit::record(
+        &state,
+        &caller,
+        AuditEvent::new("prompt.update", format!("{}/{name}", request.agent_id))
+            .before(summarize_text(&previous, None))
+            .after(format!(
+                "version {}, {}",
+                version.version,
+                summarize_text(&request.content, Some(&previous))
+            )),
+    )
+    .await;
+    Ok(Json(UpdatePromptResponse {
+        version: Some(version.version),
+        preview,
+    }))
+}
+
+/// DELETE /prompts/{name} — go back to the bundled template.
+pub(super) async fn reset_prompt(
+    State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
+    Path(name): Path<String>,
+    Query(query): Query<AgentQuery>,
+) -> Result<Json<ActionResponse>, StatusCode> {
+    let name = known_template(&name)?;
+    let store = version_store(&state, &query.agent_id)?;
+    let runtime_config = runtime_config(&state, &query.agent_id)?;
+
+    let reset_error = |error: crate::error::Error| {
+        tracing::error!(%error, "failed to reset prompt template");
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    let previous = active_source(&store, name).await.map_err(reset_error)?;
+    let saved = store
+        .save(name, None, Some("reset to bundled template"))
+        .await
+        .map_err(reset_error)?;
+    reload_prompts(&store, &runtime_config).await?;
+
+    super::audit::record(
+        &state,
+        &caller,
+        AuditEvent::new("prompt.reset", format!("{}/{name}", query.agent_id))
+            .before(summarize_text(&previous, None))
+            .after(format!("version {}, bundled template", saved.version)),
+    )
+    .await;
+
+    Ok(Json(ActionResponse {
+        success: true,
+        message: format!("'{name}' reset to the bundled template"),
+    }))
+}
+
+/// GET /prompt-versions/{id} — one version with a diff against the source
+/// in use.
+pub(super) async fn get_prompt_version(
+    State(state): State<Arc<ApiState>>,
+    Path(version_id): Path<String>,
+    Query(query): Query<AgentQuery>,
+) -> Result<Json<VersionResponse>, StatusCode> {
+    let store = version_store(&state, &query.agent_id)?;
+    let load_error = |error: crate::error::Error| {
+        tracing::error!(%error, "failed to load prompt version");
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    let version = store
+        .get(&version_id)
+        .await
+        .map_err(load_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let latest = store.latest(&version.name).await.map_err(load_error)?;
+
+    let current = latest
+        .as_ref()
+        .map(version_source)
+        .or_else(|| PromptEngine::builtin_source(&version.name))
+        .unwrap_or_default();
+    let diff = line_diff(current, version_source(&version));
+
+    Ok(Json(VersionResponse { version, diff }))
+}
+
+/// POST /prompt-versions/{id}/rollback — make an earlier version active again.
+pub(super) async fn rollback_prompt_version(
+    State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
+    Path(version_id): Path<String>,
+    Json(request): Json<RollbackRequest>,
+) -> Result<Json<ActionResponse>, StatusCode> {
+    let store = version_store(&state, &request.agent_id)?;
+    let runtime_config = runtime_config(&state, &request.agent_id)?;
+    let load_error = |error: crate::error::Error| {
+        tracing::error!(%error, "failed to load prompt version");
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    let version = store
+        .get(&version_id)
+        .await
+        .map_err(load_error)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let previous = active_source(&store, &version.name)
+        .await
+        .map_err(load_error)?;
+
+    let note = format!("rollback to version {}", version.version);
+    let saved = store
+        .save(&version.name, version.content.as_deref(), Some(&note))
+        .await
+        .map_err(|error| {
+            tracing::error!(%error, "failed to roll back prompt template");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    reload_prompts(&store, &runtime_config).await?;
+
+    super::audit::record(
+        &state,
+        &caller,
+        AuditEvent::new(
+            "prompt.rollback",
+            format!("{}/{}", request.agent_id, version.name),
+        )
+        .before(summarize_text(&previous, None))
+        .after(format!(
+            "version {} restored as version {}, {}",
+            version.version,
+            saved.version,
+            summarize_text(version_source(&version), Some(&previous))
+        )),
+    )
+    .await;
+
+    Ok(Json(ActionResponse {
+        success: true,
+        message: format!(
+            "'{}' rolled back to version {} as version {}",
+            version.name, version.version, saved.version
+        ),
+    }))
+}
@@ -1,18 +1,26 @@
-use super::state::ApiState;
+use super::access::ApiCaller;
+use super::state::{ApiEvent, ApiState};
+use crate::audit::AuditEvent;
+use crate::config::LlmConfig;
 use crate::openai_auth::DeviceTokenPollResult;
+use crate::secrets::store::{SecretCategory, StoreState, SystemSecrets as _};
 
 use anyhow::Context as _;
+use axum::Extension;
 use axum::Json;
 use axum::extract::{Query, State};
 use axum::http::StatusCode;
+use axum::response::{Html, Sse};
+use futures::stream::Stream;
 use rig::agent::AgentBuilder;
 use rig::completion::{CompletionModel as _, Prompt as _};
 use serde::{Deserialize, Serialize};
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, mpsc};
 use tokio::time::sleep;
 use uuid::Uuid;
 
 use std::collections::HashMap;
+use std::convert::Infallible;
 use std::sync::{Arc, LazyLock};
 use std::time::Duration;
 
@@ -24,6 +32,22 @@ const OPENAI_DEVICE_OAUTH_MAX_POLL_INTERVAL_SECS: u64 = 30;
 static OPENAI_DEVICE_OAUTH_SESSIONS: LazyLock<RwLock<HashMap<String, DeviceOAuthSession>>> =
     LazyLock::new(|| RwLock::new(HashMap::new()));
 
+const PROVIDER_OAUTH_SESSION_TTL_SECS: i64 = 10 * 60;
+
+/// Authorization-code sign-ins for `[llm.oauth]` providers waiting on the
+/// provider's redirect, keyed by the `state` parameter.
+static PROVIDER_OAUTH_SESSIONS: LazyLock<RwLock<HashMap<String, ProviderOAuthSession>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+#[derive(Clone, Debug)]
+struct ProviderOAuthSession {
+    provider: String,
+    model: String,
+    verifier: String,
+    redirect_uri: String,
+    expires_at: i64,
+}
+
 #[derive(Clone, Debug)]
 struct DeviceOAuthSession {
     expires_at: i64,
@@ -67,6 +91,14 @@ pub(super) struct ProviderStatus {
 pub(super) struct ProvidersResponse {
     providers: ProviderStatus,
     has_any: bool,
+    /// Monthly usage against `[llm.quota.<provider>]` ceilings, keyed by provider ID.
+    quotas: HashMap<String, crate::llm::quota::QuotaStatus>,
+    /// Latest background health probe per provider ID.
+    health: HashMap<String, crate::llm::probe::ProviderProbe>,
+    /// Hit statistics for `[llm.response_cache]`.
+    response_cache: crate::llm::response_cache::ResponseCacheStats,
+    /// Whether each `[llm.oauth]` provider has been signed into.
+    oauth: HashMap<String, bool>,
 }
 
 #[derive(Deserialize)]
@@ -74,6 +106,9 @@ pub(super) struct ProviderUpdateRequest {
     provider: String,
     api_key: String,
     model: String,
+    /// Run a lightweight connectivity check with the key before persisting it.
+    #[serde(default)]
+    validate: bool,
 }
 
 #[derive(Serialize)]
@@ -112,6 +147,32 @@ pub(super) struct OpenAiOAuthBrowserStartResponse {
     state: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub(super) struct ProviderOAuthStartRequest {
+    provider: String,
+    model: String,
+    /// Where the provider should send the user back to. Ignored when the
+    /// provider config pins `redirect_uri`.
+    #[serde(default)]
+    redirect_uri: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(super) struct ProviderOAuthStartResponse {
+    success: bool,
+    message: String,
+    authorize_url: Option<String>,
+    state: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct ProviderOAuthCallbackQuery {
+    state: Option<String>,
+    code: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
 #[derive(Deserialize)]
 pub(super) struct OpenAiOAuthBrowserStatusRequest {
     state: String,
@@ -152,6 +213,15 @@ fn provider_toml_key(provider: &str) -> Option<&'static str> {
     }
 }
 
+/// Secrets store name for a provider's `[llm]` key, e.g. `ANTHROPIC_API_KEY`
+/// for `anthropic_key`. `None` for entries that aren't credentials.
+fn provider_secret_name(key_name: &str) -> Option<&'static str> {
+    LlmConfig::secret_fields()
+        .iter()
+        .find(|field| field.toml_key == key_name)
+        .map(|field| field.secret_name)
+}
+
 fn model_matches_provider(provider: &str, model: &str) -> bool {
     crate::llm::routing::provider_from_model(model) == provider
 }
@@ -218,9 +288,54 @@ fn build_test_llm_config(provider: &str, credential: &str) -> crate::config::Llm
         zai_coding_plan_key: (provider == "zai-coding-plan").then(|| credential.to_string()),
         github_copilot_key: (provider == "github-copilot").then(|| credential.to_string()),
         providers,
+        quotas: HashMap::new(),
+        oauth: HashMap::new(),
+        health_probe_interval_secs: 0,
+        response_cache: crate::config::ResponseCacheConfig::default(),
+        embedding: crate::config::EmbeddingConfig::default(),
+    }
+}
+
+/// Pick the model used to validate a provider key on save.
+///
+/// Prefers the provider's built-in default so the check exercises the key
+/// rather than a possibly mistyped model name. Providers without a matching
+/// default (e.g. Ollama, whose models are whatever is installed locally) fall
+/// back to the requested model.
+fn validation_model(provider: &str, requested_model: &str) -> String {
+    let default_model = crate::llm::routing::defaults_for_provider(provider).channel;
+    if model_matches_provider(provider, &default_model) {
+        default_model
+    } else {
+        requested_model.to_string()
     }
 }
 
+/// Send a one-shot prompt through the provider to verify the credential works.
+///
+/// Returns the model's reply on success, or a human-readable failure reason.
+async fn run_provider_connectivity_check(
+    provider: &str,
+    credential: &str,
+    model: String,
+) -> std::result::Result<String, String> {
+    let llm_config = build_test_llm_config(provider, credential);
+    let llm_manager = crate::llm::LlmManager::new(llm_config)
+        .await
+        .map(Arc::new)
+        .map_err(|error| format!("Failed to initialize provider: {error}"))?;
+
+    let model = crate::llm::SpacebotModel::make(&llm_manager, model);
+    let agent = AgentBuilder::new(model)
+        .preamble("You are running a provider connectivity check. Reply with exactly: OK")
+        .build();
+
+    agent
+        .prompt("Connection test")
+        .await
+        .map_err(|error| format!("Model test failed: {error}"))
+}
+
 fn apply_model_routing(doc: &mut toml_edit::DocumentMut, model: &str) {
     if doc.get("defaults").is_none() {
         doc["defaults"] = toml_edit::Item::Table(toml_edit::Table::new());
@@ -381,6 +496,12 @@ pub(super) async fn get_providers(
             .parse()
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
+        let secrets_store = state.secrets_store.load_full();
+        let stored = |name: &str| -> bool {
+            (*secrets_store)
+                .as_ref()
+                .is_some_and(|store| store.get(name).is_ok())
+        };
         let has_value = |key: &str, env_var: &str| -> bool {
             if let Some(llm) = doc.get("llm")
                 && let Some(val) = llm.get(key)
@@ -389,9 +510,12 @@ pub(super) async fn get_providers(
                 if let Some(var_name) = s.strip_prefix("env:") {
                     return std::env::var(var_name).is_ok();
                 }
+                if let Some(secret_name) = s.strip_prefix("secret:") {
+                    return stored(secret_name);
+                }
                 return !s.is_empty();
             }
-            std::env::var(env_var).is_ok()
+            std::env::var(env_var).is_ok() || stored(env_var)
         };
 
         (
@@ -493,7 +617,245 @@ pub(super) async fn get_providers(
         || providers.zai_coding_plan
         || providers.github_copilot;
 
-    Ok(Json(ProvidersResponse { providers, has_any }))
+    let (quotas, health, response_cache, oauth) = match state.llm_manager.read().await.as_ref() {
+        Some(llm_manager) => (
+            llm_manager.quota_statuses().await,
+            llm_manager.provider_probes().await,
+            llm_manager.response_cache_stats(),
+            llm_manager
+                .oauth_provider_ids()
+                .into_iter()
+                .map(|provider_id| {
+                    let connected =
+                        crate::llm::oauth::credentials_path(&instance_dir, &provider_id).exists();
+                    (provider_id, connected)
+                })
+                .collect(),
+        ),
+        None => (
+            HashMap::new(),
+            HashMap::new(),
+            Default::default(),
+            HashMap::new(),
+        ),
+    };
+    let has_any = has_any || oauth.values().any(|connected| *connected);
+
+    Ok(Json(ProvidersResponse {
+        providers,
+        has_any,
+        quotas,
+        health,
+        response_cache,
+        oauth,
+    }))
+}
+
+/// Start an authorization-code sign-in for a `[llm.oauth]` provider. The
+/// dashboard opens the returned URL; the provider redirects back to
+/// `provider_oauth_callback`.
+pub(super) async fn start_provider_oauth(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<ProviderOAuthStartRequest>,
+) -> Result<Json<ProviderOAuthStartResponse>, StatusCode> {
+    let failure = |message: String| {
+        Ok(Json(ProviderOAuthStartResponse {
+            success: false,
+            message,
+            authorize_url: None,
+            state: None,
+        }))
+    };
+
+    let provider = request.provider.trim().to_lowercase();
+    let model = request.model.trim();
+    if model.is_empty() {
+        return failure("Model cannot be empty".to_string());
+    }
+    if model.split_once('/').map(|(prefix, _)| prefix) != Some(provider.as_str()) {
+        return failure(format!(
+            "Model '{model}' must use provider '{provider}' (e.g. '{provider}/<model>')."
+        ));
+    }
+
+    let Some(oauth_config) = state
+        .llm_manager
+        .read()
+        .await
+        .as_ref()
+        .and_then(|llm_manager| llm_manager.oauth_config(&provider))
+    else {
+        return failure(format!(
+            "Provider '{provider}' is not configured under [llm.oauth.{provider}]"
+        ));
+    };
+
+    let Some(redirect_uri) = oauth_config
+        .redirect_uri
+        .clone()
+        .or_else(|| request.redirect_uri.clone())
+        .filter(|redirect_uri| !redirect_uri.trim().is_empty())
+    else {
+        return failure(
+            "No redirect URI: set redirect_uri in the provider config or send one with the request"
+                .to_string(),
+        );
+    };
+
+    prune_expired_provider_oauth_sessions().await;
+
+    let pkce = crate::auth::generate_pkce();
+    let state_key = Uuid::new_v4().to_string();
+    let authorize_url =
+        crate::llm::oauth::authorize_url(&oauth_config, &redirect_uri, &state_key, &pkce.challenge);
+
+    PROVIDER_OAUTH_SESSIONS.write().await.insert(
+        state_key.clone(),
+        ProviderOAuthSession {
+            provider,
+            model: model.to_string(),
+            verifier: pkce.verifier,
+            redirect_uri,
+            expires_at: chrono::Utc::now().timestamp() + PROVIDER_OAUTH_SESSION_TTL_SECS,
+        },
+    );
+
+    Ok(Json(ProviderOAuthStartResponse {
+        success: true,
+        message: "Authorization started".to_string(),
+        authorize_url: Some(authorize_url),
+        state: Some(state_key),
+    }))
+}
+
+/// Redirect target for `[llm.oauth]` sign-ins. Exempt from API auth: the
+/// single-use `state` ties the request to a sign-in started by an
+/// authenticated caller.
+pub(super) async fn provider_oauth_callback(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<ProviderOAuthCallbackQuery>,
+) -> (StatusCode, Html<String>) {
+    let page = |status: StatusCode, message: &str| {
+        (
+            status,
+            Html(format!(
+                "<!doctype html><html><body><p>{}</p><p>You can close this window.</p></body></html>",
+                html_escape(message)
+            )),
+        )
+    };
+
+    prune_expired_provider_oauth_sessions().await;
+    let state_key = query.state.as_deref().unwrap_or_default().trim();
+    // Removed up front so a state can't be replayed.
+    let session = PROVIDER_OAUTH_SESSIONS.write().await.remove(state_key);
+    let Some(session) = session else {
+        return page(
+            StatusCode::BAD_REQUEST,
+            "Unknown or expired sign-in. Please start again from the dashboard.",
+        );
+    };
+
+    if let Some(error) = query.error.as_deref() {
+        let detail = query.error_description.as_deref().unwrap_or(error);
+        return page(
+            StatusCode::BAD_REQUEST,
+            &format!("Sign-in was not completed: {detail}"),
+        );
+    }
+    let Some(code) = query.code.as_deref().filter(|code| !code.is_empty()) else {
+        return page(
+            StatusCode::BAD_REQUEST,
+            "The provider did not return an authorization code.",
+        );
+    };
+
+    match finalize_provider_oauth(&state, &session, code).await {
+        Ok(()) => page(
+            StatusCode::OK,
+            &format!(
+                "Signed in to '{}'. Model '{}' applied to defaults.",
+                session.provider, session.model
+            ),
+        ),
+        Err(error) => {
+            tracing::warn!(provider = %session.provider, %error, "provider OAuth sign-in failed");
+            page(
+                StatusCode::BAD_GATEWAY,
+                &format!("Sign-in failed: {error:#}"),
+            )
+        }
+    }
+}
+
+async fn prune_expired_provider_oauth_sessions() {
+    let now = chrono::Utc::now().timestamp();
+    PROVIDER_OAUTH_SESSIONS
+        .write()
+        .await
+        .retain(|_, session| session.expires_at > now);
+}
+
+async fn finalize_provider_oauth(
+    state: &Arc<ApiState>,
+    session: &ProviderOAuthSession,
+    code: &str,
+) -> anyhow::Result<()> {
+    let llm_manager = state
+        .llm_manager
+        .read()
+        .await
+        .clone()
+        .context("LLM manager is not initialized")?;
+    let oauth_config = llm_manager
+        .oauth_config(&session.provider)
+        .with_context(|| format!("provider '{}' is no longer configured", session.provider))?;
+
+    let credentials = crate::llm::oauth::exchange_code(
+        &oauth_config,
+        code,
+        &session.verifier,
+        &session.redirect_uri,
+    )
+    .await?;
+
+    let instance_dir = (**state.instance_dir.load()).clone();
+    crate::llm::oauth::save_credentials(&instance_dir, &session.provider, &credentials)
+        .context("failed to save OAuth credentials")?;
+    llm_manager
+        .set_oauth_credentials(&session.provider, credentials)
+        .await;
+
+    let config_path = state.config_path.read().await.clone();
+    let content = if config_path.exists() {
+        tokio::fs::read_to_string(&config_path)
+            .await
+            .context("failed to read config.toml")?
+    } else {
+        String::new()
+    };
+
+    let mut doc: toml_edit::DocumentMut = content.parse().context("failed to parse config.toml")?;
+    apply_model_routing(&mut doc, &session.model);
+    tokio::fs::write(&config_path, doc.to_string())
+        .await
+        .context("failed to write config.toml")?;
+
+    refresh_defaults_config(state).await;
+
+    state
+        .provider_setup_tx
+        .try_send(crate::ProviderSetupEvent::ProvidersConfigured)
+        .ok();
+
+    Ok(())
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
 }
 
 pub(super) async fn start_openai_browser_oauth(
@@ -736,6 +1098,7 @@ pub(super) async fn openai_browser_oauth_status(
 
 pub(super) async fn update_provider(
     State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
     Json(request): Json<ProviderUpdateRequest>,
 ) -> Result<Json<ProviderUpdateResponse>, StatusCode> {
     let normalized_provider = request.provider.trim().to_lowercase();
@@ -771,6 +1134,22 @@ pub(super) async fn update_provider(
         }));
     }
 
+    if request.validate {
+        let model = validation_model(&normalized_provider, normalized_model);
+        if let Err(reason) =
+            run_provider_connectivity_check(&normalized_provider, request.api_key.trim(), model)
+                .await
+        {
+            return Ok(Json(ProviderUpdateResponse {
+                success: false,
+                message: format!(
+                    "Key for provider '{}' failed validation and was not saved. {reason}",
+                    request.provider
+                ),
+            }));
+        }
+    }
+
     let config_path = state.config_path.read().await.clone();
 
     let content = if config_path.exists() {
@@ -788,8 +1167,34 @@ pub(super) async fn update_provider(
     if doc.get("llm").is_none() {
         doc["llm"] = toml_edit::Item::Table(toml_edit::Table::new());
     }
-
-    doc["llm"][key_name] = toml_edit::value(request.api_key);
+    let had_key = doc["llm"].get(key_name).is_some();
+
+    // Keep credentials out of config.toml: in the secrets store when it's
+    // available, else encrypted inline with the config key. The Ollama entry
+    // is a base URL, not a secret.
+    let secrets_store = state.secrets_store.load_full();
+    let stored_value = match (
+        (*secrets_store)
+            .as_ref()
+            .filter(|store| store.state() != StoreState::Locked),
+        provider_secret_name(key_name),
+        crate::config::resolve_config_cipher(),
+    ) {
+        (Some(store), Some(secret_name), _) => {
+            store
+                .set(secret_name, request.api_key.trim(), SecretCategory::System)
+                .map_err(|error| {
+                    tracing::error!(%error, secret_name, "failed to store provider key");
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+            format!("secret:{secret_name}")
+        }
+        (_, Some(_), Some(cipher)) => cipher
+            .encrypt_value(request.api_key.trim())
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+        _ => request.api_key,
+    };
+    doc["llm"][key_name] = toml_edit::value(stored_value);
     apply_model_routing(&mut doc, normalized_model);
 
     tokio::fs::write(&config_path, doc.to_string())
@@ -804,6 +1209,15 @@ pub(super) async fn update_provider(
         .try_send(crate::ProviderSetupEvent::ProvidersConfigured)
         .ok();
 
+    super::audit::record(
+        &state,
+        &caller,
+        AuditEvent::new("provider.update", &normalized_provider)
+            .before(if had_key { "key set" } else { "no key" })
+            .after(format!("key replaced, default model {normalized_model}")),
+    )
+    .await;
+
     Ok(Json(ProviderUpdateResponse {
         success: true,
         message: format!(
@@ -861,26 +1275,13 @@ pub(super) async fn test_provider_model(
         }));
     }
 
-    let llm_config = build_test_llm_config(&normalized_provider, request.api_key.trim());
-    let llm_manager = match crate::llm::LlmManager::new(llm_config).await {
-        Ok(manager) => Arc::new(manager),
-        Err(error) => {
-            return Ok(Json(ProviderModelTestResponse {
-                success: false,
-                message: format!("Failed to initialize provider: {error}"),
-                provider: request.provider,
-                model: request.model,
-                sample: None,
-            }));
-        }
-    };
-
-    let model = crate::llm::SpacebotModel::make(&llm_manager, normalized_model);
-    let agent = AgentBuilder::new(model)
-        .preamble("You are running a provider connectivity check. Reply with exactly: OK")
-        .build();
-
-    match agent.prompt("Connection test").await {
+    match run_provider_connectivity_check(
+        &normalized_provider,
+        request.api_key.trim(),
+        normalized_model,
+    )
+    .await
+    {
         Ok(sample) => Ok(Json(ProviderModelTestResponse {
             success: true,
             message: "Model responded successfully".to_string(),
@@ -888,9 +1289,9 @@ pub(super) async fn test_provider_model(
             model: request.model,
             sample: Some(sample),
         })),
-        Err(error) => Ok(Json(ProviderModelTestResponse {
+        Err(message) => Ok(Json(ProviderModelTestResponse {
             success: false,
-            message: format!("Model test failed: {error}"),
+            message,
             provider: request.provider,
             model: request.model,
             sample: None,
@@ -898,11 +1299,330 @@ pub(super) async fn test_provider_model(
     }
 }
 
+#[derive(Serialize)]
+pub(super) struct DebugCaptureResponse {
+    enabled: bool,
+    capacity: usize,
+    exchanges: Vec<crate::llm::debug_capture::CapturedExchange>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct DebugCaptureQuery {
+    /// Only exchanges from this process: a branch or worker ID, or a channel
+    /// or agent ID for processes without their own.
+    #[serde(default)]
+    process_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct DebugCaptureUpdateRequest {
+    enabled: bool,
+    #[serde(default)]
+    capacity: Option<usize>,
+}
+
+fn debug_capture_response(
+    capture: &crate::llm::debug_capture::DebugCapture,
+    process_id: Option<&str>,
+) -> Json<DebugCaptureResponse> {
+    Json(DebugCaptureResponse {
+        enabled: capture.is_enabled(),
+        capacity: capture.capacity(),
+        exchanges: capture.entries(process_id),
+    })
+}
+
+/// List recently captured (redacted) provider requests and responses,
+/// optionally for a single process.
+pub(super) async fn get_debug_captures(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<DebugCaptureQuery>,
+) -> Result<Json<DebugCaptureResponse>, StatusCode> {
+    let llm_manager = state.llm_manager.read().await;
+    let llm_manager = llm_manager
+        .as_ref()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    Ok(debug_capture_response(
+        llm_manager.debug_capture(),
+        query.process_id.as_deref(),
+    ))
+}
+
+/// Enable or disable provider debug capture and set the buffer size.
+pub(super) async fn update_debug_captures(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<DebugCaptureUpdateRequest>,
+) -> Result<Json<DebugCaptureResponse>, StatusCode> {
+    let llm_manager = state.llm_manager.read().await;
+    let llm_manager = llm_manager
+        .as_ref()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    llm_manager
+        .debug_capture()
+        .configure(request.enabled, request.capacity);
+    Ok(debug_capture_response(llm_manager.debug_capture(), None))
+}
+
+/// Drop all captured provider exchanges.
+pub(super) async fn clear_debug_captures(
+    State(state): State<Arc<ApiState>>,
+) -> Result<Json<DebugCaptureResponse>, StatusCode> {
+    let llm_manager = state.llm_manager.read().await;
+    let llm_manager = llm_manager
+        .as_ref()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    llm_manager.debug_capture().clear();
+    Ok(debug_capture_response(llm_manager.debug_capture(), None))
+}
+
+#[derive(Serialize)]
+pub(super) struct OllamaModelEntry {
+    #[serde(flatten)]
+    model: crate::llm::ollama::OllamaModel,
+    /// Whether the model is currently loaded into memory.
+    loaded: bool,
+    /// Memory used by the loaded model in bytes.
+    loaded_size: Option<u64>,
+    /// Portion of `loaded_size` resident in GPU memory.
+    size_vram: Option<u64>,
+    expires_at: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(super) struct OllamaModelsResponse {
+    models: Vec<OllamaModelEntry>,
+    /// Total GPU memory used by all loaded models in bytes.
+    vram_used: u64,
+}
+
+#[derive(Serialize)]
+pub(super) struct OllamaHealthResponse {
+    /// Whether the server answered just now.
+    reachable: bool,
+    version: Option<String>,
+    latency_ms: u64,
+    error: Option<String>,
+    /// Models currently loaded into memory.
+    loaded_models: usize,
+    /// Total GPU memory used by loaded models in bytes.
+    vram_used: u64,
+    /// Latest background health probe, if probing is enabled.
+    probe: Option<crate::llm::probe::ProviderProbe>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct OllamaPullRequest {
+    model: String,
+}
+
+/// List models installed on the configured Ollama server, with memory and
+/// VRAM usage for the ones currently loaded.
+pub(super) async fn list_ollama_models(
+    State(state): State<Arc<ApiState>>,
+) -> Result<Json<OllamaModelsResponse>, StatusCode> {
+    let client = {
+        let llm_manager = state.llm_manager.read().await;
+        let llm_manager = llm_manager
+            .as_ref()
+            .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+        llm_manager
+            .ollama_client()
+            .map_err(|_| StatusCode::NOT_FOUND)?
+    };
+
+    let (installed, running) = tokio::join!(client.list_models(), client.running_models());
+    let installed = installed.map_err(|error| {
+        tracing::warn!(%error, "failed to list Ollama models");
+        StatusCode::BAD_GATEWAY
+    })?;
+    // Older Ollama servers lack /api/ps; report everything as unloaded.
+    let running = running.unwrap_or_else(|error| {
+        tracing::debug!(%error, "failed to list running Ollama models");
+        Vec::new()
+    });
+
+    let vram_used = running.iter().map(|model| model.size_vram).sum();
+    let models = installed
+        .into_iter()
+        .map(|model| {
+            let loaded = running.iter().find(|running| running.name == model.name);
+            OllamaModelEntry {
+                loaded: loaded.is_some(),
+                loaded_size: loaded.map(|running| running.size),
+                size_vram: loaded.map(|running| running.size_vram),
+                expires_at: loaded.and_then(|running| running.expires_at.clone()),
+                model,
+            }
+        })
+        .collect();
+
+    Ok(Json(OllamaModelsResponse { models, vram_used }))
+}
+
+/// Check the configured Ollama server: whether it answers, its version, and
+/// what it currently has loaded.
+pub(super) async fn ollama_health(
+    State(state): State<Arc<ApiState>>,
+) -> Result<Json<OllamaHealthResponse>, StatusCode> {
+    let (client, probe) = {
+        let llm_manager = state.llm_manager.read().await;
+        let llm_manager = llm_manager
+            .as_ref()
+            .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+        let client = llm_manager
+            .ollama_client()
+            .map_err(|_| StatusCode::NOT_FOUND)?;
+        (client, llm_manager.provider_probes().await.remove("ollama"))
+    };
+
+    let started = std::time::Instant::now();
+    let version = client.version().await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+    let (version, error) = match version {
+        Ok(version) => (Some(version), None),
+        Err(error) => (None, Some(format!("{error:#}"))),
+    };
+    let running = if version.is_some() {
+        client.running_models().await.unwrap_or_else(|error| {
+            tracing::debug!(%error, "failed to list running Ollama models");
+            Vec::new()
+        })
+    } else {
+        Vec::new()
+    };
+
+    Ok(Json(OllamaHealthResponse {
+        reachable: version.is_some(),
+        version,
+        latency_ms,
+        error,
+        loaded_models: running.len(),
+        vram_used: running.iter().map(|model| model.size_vram).sum(),
+        probe,
+    }))
+}
+
+/// Download a model on the configured Ollama server and stream its progress
+/// back on this response.
+///
+/// Emits `progress` events while layers download, then a single `done` event
+/// whose `error` is set if the pull failed. The same events also go out on
+/// `/api/events`, and the pull keeps running if this client disconnects.
+pub(super) async fn pull_ollama_model(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<OllamaPullRequest>,
+) -> Result<Sse<impl Stream<Item = Result<axum::response::sse::Event, Infallible>>>, StatusCode> {
+    let model = request.model.trim().to_string();
+    if model.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let client = {
+        let llm_manager = state.llm_manager.read().await;
+        let llm_manager = llm_manager
+            .as_ref()
+            .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+        llm_manager
+            .ollama_client()
+            .map_err(|_| StatusCode::NOT_FOUND)?
+    };
+
+    let mut progress_rx = spawn_ollama_pull(state, client, model);
+    let stream = async_stream::stream! {
+        while let Some(event) = progress_rx.recv().await {
+            let event_name = match &event {
+                ApiEvent::OllamaPullProgress { done: true, .. } => "done",
+                _ => "progress",
+            };
+            if let Ok(json) = serde_json::to_string(&event) {
+                yield Ok(axum::response::sse::Event::default()
+                    .event(event_name)
+                    .data(json));
+            }
+        }
+    };
+
+    Ok(Sse::new(stream).keep_alive(axum::response::sse::KeepAlive::default()))
+}
+
+/// Run a pull in the background, broadcasting each progress line as an
+/// `ollama_pull_progress` event. The returned receiver gets the same events
+/// and closes after the final one.
+fn spawn_ollama_pull(
+    state: Arc<ApiState>,
+    client: crate::llm::ollama::OllamaClient,
+    model: String,
+) -> mpsc::UnboundedReceiver<ApiEvent> {
+    let (progress_tx, progress_rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let publish = |event: ApiEvent| {
+            // The requester may have disconnected; the pull carries on.
+            progress_tx.send(event.clone()).ok();
+            state.send_event(event);
+        };
+
+        let result = client
+            .pull_model(&model, |progress| {
+                publish(ApiEvent::OllamaPullProgress {
+                    model: model.clone(),
+                    status: progress.status.clone(),
+                    completed: progress.completed,
+                    total: progress.total,
+                    done: false,
+                    error: None,
+                });
+            })
+            .await;
+
+        let (status, error) = match result {
+            Ok(()) => ("success".to_string(), None),
+            Err(error) => {
+                tracing::warn!(%error, model = %model, "Ollama model pull failed");
+                ("error".to_string(), Some(error.to_string()))
+            }
+        };
+        publish(ApiEvent::OllamaPullProgress {
+            model: model.clone(),
+            status,
+            completed: None,
+            total: None,
+            done: true,
+            error,
+        });
+    });
+    progress_rx
+}
+
 pub(super) async fn delete_provider(
     State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
     axum::extract::Path(provider): axum::extract::Path<String>,
 ) -> Result<Json<ProviderUpdateResponse>, StatusCode> {
     let provider = provider.trim().to_lowercase();
+    // `[llm.oauth]` sign-ins live in their own credentials file. The provider
+    // config itself stays in config.toml so the user can sign in again.
+    let llm_manager = state.llm_manager.read().await.clone();
+    if let Some(llm_manager) = llm_manager
+        && llm_manager.has_oauth_provider(&provider)
+    {
+        let instance_dir = (**state.instance_dir.load()).clone();
+        crate::llm::oauth::delete_credentials(&instance_dir, &provider)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        llm_manager.clear_oauth_credentials(&provider).await;
+        super::audit::record(
+            &state,
+            &caller,
+            AuditEvent::new("provider.delete", &provider)
+                .before("signed in with OAuth")
+                .after("OAuth credentials removed"),
+        )
+        .await;
+        return Ok(Json(ProviderUpdateResponse {
+            success: true,
+            message: format!("OAuth credentials for '{provider}' removed"),
+        }));
+    }
+
     // OpenAI ChatGPT OAuth credentials are stored as a separate JSON file,
     // not in the TOML config, so handle removal separately.
     if provider == "openai-chatgpt" {
@@ -916,6 +1636,14 @@ pub(super) async fn delete_provider(
         if let Some(mgr) = state.llm_manager.read().await.as_ref() {
             mgr.clear_openai_oauth_credentials().await;
         }
+        super::audit::record(
+            &state,
+            &caller,
+            AuditEvent::new("provider.delete", &provider)
+                .before("signed in with OAuth")
+                .after("OAuth credentials removed"),
+        )
+        .await;
         return Ok(Json(ProviderUpdateResponse {
             success: true,
             message: "ChatGPT Plus OAuth credentials removed".into(),
@@ -960,16 +1688,40 @@ pub(super) async fn delete_provider(
         .parse()
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    if let Some(llm) = doc.get_mut("llm")
-        && let Some(table) = llm.as_table_mut()
-    {
-        table.remove(key_name);
-    }
+    let removed = doc
+        .get_mut("llm")
+        .and_then(|llm| llm.as_table_mut())
+        .and_then(|table| table.remove(key_name));
 
     tokio::fs::write(&config_path, doc.to_string())
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
+    // A key kept in the secrets store goes with its config entry.
+    let secrets_store = state.secrets_store.load_full();
+    if let Some(secret_name) = removed
+        .as_ref()
+        .and_then(|value| value.as_str())
+        .and_then(|value| value.strip_prefix("secret:"))
+        && let Some(store) = (*secrets_store).as_ref()
+        && let Err(error) = store.delete(secret_name)
+    {
+        tracing::warn!(%error, secret_name, "failed to delete provider key from secrets store");
+    }
+
+    super::audit::record(
+        &state,
+        &caller,
+        AuditEvent::new("provider.delete", &provider)
+            .before(if removed.is_some() {
+                "key set"
+            } else {
+                "no key"
+            })
+            .after("no key"),
+    )
+    .await;
+
     Ok(Json(ProviderUpdateResponse {
         success: true,
         message: format!("Provider '{}' removed", provider),
@@ -978,7 +1730,7 @@ pub(super) async fn delete_provider(
 
 #[cfg(test)]
 mod tests {
-    use super::build_test_llm_config;
+    use super::{build_test_llm_config, provider_secret_name, provider_toml_key, validation_model};
 
     #[test]
     fn build_test_llm_config_registers_ollama_provider_from_base_url() {
@@ -991,4 +1743,26 @@ mod tests {
         assert_eq!(provider.base_url, "http://remote-ollama.local:11434");
         assert_eq!(provider.api_key, "");
     }
+
+    #[test]
+    fn validation_model_prefers_provider_default() {
+        assert_eq!(
+            validation_model("openai", "openai/some-typo"),
+            "openai/gpt-4.1"
+        );
+    }
+
+    #[test]
+    fn validation_model_falls_back_to_requested_model_without_default() {
+        assert_eq!(validation_model("ollama", "ollama/llama3"), "ollama/llama3");
+    }
+
+    #[test]
+    fn provider_keys_map_to_canonical_secret_names() {
+        let secret_name = |provider| provider_toml_key(provider).and_then(provider_secret_name);
+        assert_eq!(secret_name("anthropic"), Some("ANTHROPIC_API_KEY"));
+        assert_eq!(secret_name("gemini"), Some("GEMINI_API_KEY"));
+        assert_eq!(secret_name("opencode-go"), Some("OPENCODE_GO_API_KEY"));
+        assert_eq!(secret_name("ollama"), None);
+    }
 }
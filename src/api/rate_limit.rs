@@ -0,0 +1,149 @@
+//! Token bucket rate limiting for the HTTP API.
+
+use crate::config::{ApiRateLimitConfig, RateLimitRule};
+
+use arc_swap::ArcSwap;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Buckets idle longer than this are dropped during pruning.
+const IDLE_BUCKET_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Bucket count above which idle buckets are pruned.
+const PRUNE_THRESHOLD: usize = 4096;
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl Bucket {
+    fn full(rule: RateLimitRule, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(rule.burst),
+            updated_at: now,
+        }
+    }
+
+    /// Take one token, or return how long until one is available.
+    fn take(&mut self, rule: RateLimitRule, now: Instant) -> Result<(), Duration> {
+        let per_second = f64::from(rule.requests_per_minute) / 60.0;
+        let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * per_second).min(f64::from(rule.burst));
+        self.updated_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / per_second))
+        }
+    }
+}
+
+/// Per-client, per-rule token buckets.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: ArcSwap<ApiRateLimitConfig>,
+    /// Keyed by (client identity, route rule index — `None` for the default).
+    buckets: Mutex<HashMap<(String, Option<usize>), Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: ApiRateLimitConfig) -> Self {
+        Self {
+            config: ArcSwap::from_pointee(config),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Swap in new limits. Existing buckets are reset since rule indices may
+    /// have shifted.
+    pub fn reload(&self, config: ApiRateLimitConfig) {
+        self.config.store(std::sync::Arc::new(config));
+        self.buckets
+            .lock()
+            .expect("rate limiter lock poisoned")
+            .clear();
+    }
+
+    /// Account for one request from `client` to `path` (relative to `/api`).
+    /// Returns the time until the client may retry when limited.
+    pub fn check(&self, client: &str, path: &str) -> Result<(), Duration> {
+        let config = self.config.load();
+        if !config.enabled {
+            return Ok(());
+        }
+
+        let (rule_index, rule) = config.rule_for(path);
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().expect("rate limiter lock poisoned");
+
+        if buckets.len() > PRUNE_THRESHOLD {
+            buckets.retain(|_, bucket| now.duration_since(bucket.updated_at) < IDLE_BUCKET_TTL);
+        }
+
+        buckets
+            .entry((client.to_string(), rule_index))
+            .or_insert_with(|| Bucket::full(rule, now))
+            .take(rule, now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RouteRateLimit;
+
+    fn config() -> ApiRateLimitConfig {
+        ApiRateLimitConfig {
+            enabled: true,
+            default: RateLimitRule {
+                requests_per_minute: 60,
+                burst: 3,
+            },
+            routes: vec![RouteRateLimit {
+                path: "/providers".to_string(),
+                rule: RateLimitRule {
+                    requests_per_minute: 60,
+                    burst: 1,
+                },
+            }],
+        }
+    }
+
+    #[test]
+    fn rejects_requests_beyond_burst() {
+        let limiter = RateLimiter::new(config());
+        for _ in 0..3 {
+            assert!(limiter.check("client", "/agents").is_ok());
+        }
+        let retry_after = limiter.check("client", "/agents").unwrap_err();
+        assert!(retry_after <= Duration::from_secs(1));
+
+        // Other clients have their own buckets.
+        assert!(limiter.check("other", "/agents").is_ok());
+    }
+
+    #[test]
+    fn route_rules_use_separate_buckets() {
+        let limiter = RateLimiter::new(config());
+        assert!(limiter.check("client", "/providers/test").is_ok());
+        assert!(limiter.check("client", "/providers").is_err());
+        // The default bucket is untouched by the route override.
+        assert!(limiter.check("client", "/agents").is_ok());
+        // Prefix matching respects path segments.
+        assert!(limiter.check("client", "/providersx").is_ok());
+    }
+
+    #[test]
+    fn disabled_limiter_allows_everything() {
+        let limiter = RateLimiter::new(ApiRateLimitConfig::default());
+        for _ in 0..1000 {
+            assert!(limiter.check("client", "/agents").is_ok());
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! REST API handler for replaying recorded channel turns and worker runs.
+
+use super::ids::{AgentId, ChannelId, WorkerId};
+use super::state::ApiState;
+
+use crate::ProcessType;
+use crate::agent::replay::{self, RecordedStep, ReplayComparison, ReplayOverrides};
+use crate::config::RuntimeConfig;
+use crate::conversation::history::ProcessRunLogger;
+use crate::conversation::worker_transcript;
+use crate::prompts::versions::PromptVersionStore;
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Identify a channel turn by `channel_id` + `timestamp_ms` (a captured
+/// prompt snapshot) or a worker run by `worker_id` + `step`.
+#[derive(Deserialize)]
+pub(super) struct ReplayRequest {
+    agent_id: AgentId,
+    #[serde(default)]
+    channel_id: Option<ChannelId>,
+    #[serde(default)]
+    timestamp_ms: Option<i64>,
+    #[serde(default)]
+    worker_id: Option<WorkerId>,
+    /// Model step of the worker run to replay, 0 for the first.
+    #[serde(default)]
+    step: usize,
+    /// Saved prompt template version to render the candidate worker prompt
+    /// with. Captured channel prompts are already rendered, so channel turns
+    /// take a `system_prompt` instead.
+    #[serde(default)]
+    prompt_version_id: Option<String>,
+    #[serde(flatten)]
+    overrides: ReplayOverrides,
+}
+
+#[derive(Serialize)]
+pub(super) struct ReplayResponse {
+    source: &'static str,
+    #[serde(flatten)]
+    comparison: ReplayComparison,
+}
+
+/// POST /replay — re-run a recorded step as recorded and with the requested
+/// model or prompt. Tools are never executed.
+pub(super) async fn replay_step(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<ReplayRequest>,
+) -> Result<Json<ReplayResponse>, StatusCode> {
+    let runtime_config = state
+        .runtime_configs
+        .load()
+        .get(request.agent_id.as_str())
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let llm_manager = state
+        .llm_manager
+        .read()
+        .await
+        .clone()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+
+    let mut overrides = request.overrides.clone();
+    let (source, step) = match (&request.channel_id, request.timestamp_ms, request.worker_id) {
+        (Some(channel_id), Some(timestamp_ms), None) => {
+            if request.prompt_version_id.is_some() {
+                return Err(StatusCode::BAD_REQUEST);
+            }
+            let step = recorded_channel_turn(&runtime_config, channel_id, timestamp_ms)?;
+            ("channel_turn", step)
+        }
+        (None, None, Some(worker_id)) => {
+            let step =
+                recorded_worker_step(&state, &runtime_config, &request, worker_id, &mut overrides)
+                    .await?;
+            ("worker_run", step)
+        }
+        _ => return Err(StatusCode::BAD_REQUEST),
+    };
+
+    let comparison =
+        replay::replay(&llm_manager, request.agent_id.as_str(), &step, &overrides).await;
+    Ok(Json(ReplayResponse { source, comparison }))
+}
+
+/// The channel turn captured in a prompt snapshot.
+fn recorded_channel_turn(
+    runtime_config: &RuntimeConfig,
+    channel_id: &ChannelId,
+    timestamp_ms: i64,
+) -> Result<RecordedStep, StatusCode> {
+    let snapshot_store = runtime_config.prompt_snapshots.load();
+    let snapshot_store = snapshot_store
+        .as_ref()
+        .as_ref()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let snapshot = snapshot_store
+        .get(channel_id.as_str(), timestamp_ms)
+        .map_err(|error| {
+            tracing::warn!(%error, "failed to get prompt snapshot for replay");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let routing = runtime_config.routing.load();
+    let default_model = routing.resolve(ProcessType::Channel, Some(channel_id.as_str()));
+    RecordedStep::from_snapshot(&snapshot, default_model).map_err(|error| {
+        tracing::warn!(%error, channel_id = %channel_id, "prompt snapshot cannot be replayed");
+        StatusCode::UNPROCESSABLE_ENTITY
+    })
+}
+
+/// One model step of a builtin worker run. Renders the candidate system
+/// prompt into `overrides` when a prompt version is requested.
+async fn recorded_worker_step(
+    state: &ApiState,
+    runtime_config: &RuntimeConfig,
+    request: &ReplayRequest,
+    worker_id: WorkerId,
+    overrides: &mut ReplayOverrides,
+) -> Result<RecordedStep, StatusCode> {
+    let pool = state
+        .agent_pools
+        .load()
+        .get(request.agent_id.as_str())
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let sandbox = state
+        .sandboxes
+        .load()
+        .get(request.agent_id.as_str())
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let detail = ProcessRunLogger::new(pool.clone())
+        .get_worker_detail(request.agent_id.as_str(), &worker_id.to_string())
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, %worker_id, "failed to load worker run for replay");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    // Other worker types run outside Spacebot's model loop.
+    if detail.worker_type != "builtin" {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+    let transcript = detail
+        .transcript_blob
+        .as_deref()
+        .ok_or(StatusCode::NOT_FOUND)
+        .and_then(|blob| {
+            worker_transcript::deserialize_transcript(blob).map_err(|error| {
+                tracing::warn!(%error, %worker_id, "failed to decompress transcript for replay");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })
+        })?;
+
+    let prompt_engine = runtime_config.prompts.load();
+    let render_error = |error: crate::error::Error| {
+        tracing::warn!(%error, "failed to render worker prompt for replay");
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+    let system_prompt = replay::render_worker_prompt(runtime_config, &prompt_engine, &sandbox)
+        .map_err(render_error)?;
+
+    if let Some(version_id) = &request.prompt_version_id {
+        if overrides.system_prompt.is_some() {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+        let version = PromptVersionStore::new(pool)
+            .get(version_id)
+            .await
+            .map_err(|error| {
+                tracing::warn!(%error, "failed to load prompt version for replay");
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?
+            .ok_or(StatusCode::NOT_FOUND)?;
+        let candidate_engine = prompt_engine
+            .with_template(&version.name, super::prompts::version_source(&version))
+            .map_err(|error| {
+                tracing::warn!(%error, version = %version.id, "prompt version does not compile");
+                StatusCode::UNPROCESSABLE_ENTITY
+            })?;
+        overrides.system_prompt = Some(
+            replay::render_worker_prompt(runtime_config, &candidate_engine, &sandbox)
+                .map_err(render_error)?,
+        );
+    }
+
+    let routing = runtime_config.routing.load();
+    let model = routing
+        .resolve(ProcessType::Worker, detail.channel_id.as_deref())
+        .to_string();
+    RecordedStep::from_worker_transcript(&transcript, request.step, model, system_prompt).map_err(
+        |error| {
+            tracing::warn!(%error, %worker_id, "worker run cannot be replayed");
+            StatusCode::UNPROCESSABLE_ENTITY
+        },
+    )
+}
@@ -4,19 +4,21 @@
 //! for the instance-level secret store. Secrets are global — shared across all
 //! agents in the instance.
 
+use super::access::ApiCaller;
 use super::state::ApiState;
+use crate::audit::AuditEvent;
 use crate::config::{
-    DefaultsConfig, DiscordConfig, EmailConfig, LlmConfig, SlackConfig, TelegramConfig,
-    TwitchConfig,
+    DefaultsConfig, DiscordConfig, EmailConfig, GithubConfig, IrcConfig, LlmConfig, MatrixConfig,
+    SlackConfig, TelegramConfig, TwitchConfig,
 };
 use crate::secrets::store::{
     ExportData, SecretCategory, SecretsStore, StoreState, SystemSecrets, auto_categorize,
 };
 
-use axum::Json;
 use axum::extract::{Path, State};
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
+use axum::{Extension, Json};
 use serde::{Deserialize, Serialize};
 
 use std::sync::Arc;
@@ -115,6 +117,7 @@ struct PutSecretResponse {
 /// `PUT /api/secrets/:name` — Add or update a secret.
 pub async fn put_secret(
     State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
     Path(name): Path<String>,
     Json(body): Json<PutSecretBody>,
 ) -> impl IntoResponse {
@@ -132,9 +135,18 @@ pub async fn put_secret(
     }
 
     let category = body.category.unwrap_or_else(|| auto_categorize(&name));
+    let existed = store.exists(&name);
 
     match store.set(&name, &body.value, category) {
         Ok(()) => {
+            super::audit::record(
+                &state,
+                &caller,
+                AuditEvent::new("secret.put", &name)
+                    .before(if existed { "set" } else { "not set" })
+                    .after(format!("set, {category} category")),
+            )
+            .await;
             let reload_required = category == SecretCategory::System;
             let message = if reload_required {
                 "Secret updated. Reload config or restart for the new value to take effect."
@@ -168,6 +180,7 @@ struct DeleteSecretResponse {
 /// `DELETE /api/secrets/:name` — Delete a secret.
 pub async fn delete_secret(
     State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
     Path(name): Path<String>,
 ) -> impl IntoResponse {
     let store = match get_secrets_store(&state) {
@@ -184,11 +197,21 @@ pub async fn delete_secret(
     }
 
     match store.delete(&name) {
-        Ok(()) => Json(DeleteSecretResponse {
-            deleted: name,
-            warning: None,
-        })
-        .into_response(),
+        Ok(()) => {
+            super::audit::record(
+                &state,
+                &caller,
+                AuditEvent::new("secret.delete", &name)
+                    .before("set")
+                    .after("not set"),
+            )
+            .await;
+            Json(DeleteSecretResponse {
+                deleted: name,
+                warning: None,
+            })
+            .into_response()
+        }
         Err(error) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(serde_json::json!({"error": error.to_string()})),
@@ -458,6 +481,9 @@ pub async fn migrate_secrets(State(state): State<Arc<ApiState>>) -> impl IntoRes
     migrate_section_secrets::<TelegramConfig>(&store, &mut doc, &mut migrated);
     migrate_section_secrets::<TwitchConfig>(&store, &mut doc, &mut migrated);
     migrate_section_secrets::<EmailConfig>(&store, &mut doc, &mut migrated);
+    migrate_section_secrets::<GithubConfig>(&store, &mut doc, &mut migrated);
+    migrate_section_secrets::<MatrixConfig>(&store, &mut doc, &mut migrated);
+    migrate_section_secrets::<IrcConfig>(&store, &mut doc, &mut migrated);
 
     // Write updated config.toml if any migrations were made.
     if !migrated.is_empty()
@@ -598,7 +624,11 @@ fn try_migrate_field(
         None => return,
     };
 
-    if value_str.starts_with("env:") || value_str.starts_with("secret:") || value_str.is_empty() {
+    if value_str.starts_with("env:")
+        || value_str.starts_with("secret:")
+        || crate::secrets::config_cipher::is_encrypted_value(&value_str)
+        || value_str.is_empty()
+    {
         return;
     }
 
@@ -679,7 +709,12 @@ fn migrate_section_secrets<T: SystemSecrets>(
 
             // Read the value from the instance entry.
             let value_str = match instance.get(field.toml_key).and_then(|v| v.as_str()) {
-                Some(s) if !s.is_empty() && !s.starts_with("env:") && !s.starts_with("secret:") => {
+                Some(s)
+                    if !s.is_empty()
+                        && !s.starts_with("env:")
+                        && !s.starts_with("secret:")
+                        && !crate::secrets::config_cipher::is_encrypted_value(s) =>
+                {
                     s.to_string()
                 }
                 _ => continue,
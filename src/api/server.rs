@@ -1,16 +1,21 @@
 //! HTTP server setup: router, static file serving, and API route wiring.
 
+use super::access::{ApiCaller, CallerKind};
 use super::state::ApiState;
 use super::{
-    agents, bindings, channels, config, cortex, cron, factory, ingest, links, mcp, memories,
-    messaging, models, opencode_proxy, projects, providers, secrets, settings, skills, ssh, system,
-    tasks, tools, webchat, workers,
+    access, agents, audit, bindings, channels, cli_workers, cluster, config, config_history,
+    cortex, cron, experiments, factory, health, ingest, jobs, links, mcp, memories, messaging,
+    models, opencode_proxy, outbox, profiles, projects, prompts, providers, replay, secrets,
+    settings, skills, ssh, storage, system, tasks, teams, tools, traces, usage, users, webchat,
+    workers,
 };
 
+use crate::config::{ApiAuthConfig, ApiRole};
+
 use axum::Json;
 
 use axum::Router;
-use axum::extract::{DefaultBodyLimit, Request, State};
+use axum::extract::{ConnectInfo, DefaultBodyLimit, Request, State};
 use axum::http::{StatusCode, Uri, header};
 use axum::middleware::{self, Next};
 use axum::response::{Html, IntoResponse, Response};
@@ -46,13 +51,41 @@ pub async fn start_http_server(
             axum::http::Method::DELETE,
             axum::http::Method::OPTIONS,
         ])
-        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION, header::ACCEPT]);
+        .allow_headers([
+            header::CONTENT_TYPE,
+            header::AUTHORIZATION,
+            header::ACCEPT,
+            header::HeaderName::from_static(API_KEY_HEADER),
+        ]);
+
+    if !bind.ip().is_loopback() && !state.auth.load().is_enabled() {
+        tracing::warn!(
+            %bind,
+            "HTTP API is reachable beyond localhost without authentication; set [api.auth] keys"
+        );
+    }
 
     let api_routes = Router::new()
-        .route("/health", get(system::health))
+        .route("/health", get(health::health))
+        .route("/ready", get(health::ready))
         .route("/idle", get(system::idle))
         .route("/status", get(system::status))
         .route("/system/storage", get(system::storage_status))
+        .route("/system/processes", get(system::processes))
+        .route("/system/db", get(system::db_status))
+        .route("/config/history", get(config_history::list_config_history))
+        .route(
+            "/config/history/entry",
+            get(config_history::get_config_history_entry),
+        )
+        .route(
+            "/config/history/revert",
+            post(config_history::revert_config_change),
+        )
+        .route("/cli-workers/status", get(cli_workers::cli_worker_status))
+        .route("/jobs", get(jobs::list_jobs))
+        .route("/jobs/trigger", post(jobs::trigger_job))
+        .route("/jobs/toggle", put(jobs::toggle_job))
         .route("/system/backup/export", get(system::backup_export))
         .route("/system/backup/restore", post(system::backup_restore))
         .route("/overview", get(agents::instance_overview))
@@ -70,6 +103,17 @@ pub async fn start_http_server(
             "/agents/warmup",
             get(agents::get_warmup_status).post(agents::trigger_warmup),
         )
+        .route("/agents/storage", get(storage::get_agent_storage))
+        .route(
+            "/agents/storage/cleanup",
+            post(storage::cleanup_agent_storage),
+        )
+        .route("/vector/optimize", post(storage::optimize_vector_tables))
+        .route("/usage/budget", get(usage::get_budget))
+        .route(
+            "/traces/{conversation_id}",
+            get(traces::get_conversation_traces),
+        )
         .route(
             "/mcp/servers",
             get(mcp::list_mcp_servers)
@@ -88,8 +132,31 @@ pub async fn start_http_server(
             get(channels::list_channels).delete(channels::delete_channel),
         )
         .route("/channels/archive", put(channels::set_channel_archive))
+        .route("/channels/routing", put(channels::set_channel_routing))
+        .route("/channels/pace", put(channels::set_channel_pace))
+        .route("/channels/language", put(channels::set_channel_language))
+        .route(
+            "/channels/settings",
+            get(channels::channel_settings).put(channels::set_channel_settings),
+        )
+        .route(
+            "/channels/memory-scope",
+            put(channels::set_channel_memory_scope),
+        )
+        .route("/channels/ephemeral", put(channels::set_channel_ephemeral))
+        .route("/channels/takeover", post(channels::channel_takeover))
+        .route(
+            "/channels/moderation",
+            put(channels::set_channel_moderation),
+        )
+        .route(
+            "/channels/moderation/events",
+            get(channels::moderation_events),
+        )
         .route("/channels/messages", get(channels::channel_messages))
         .route("/channels/status", get(channels::channel_status))
+        .route("/conversations/search", get(channels::search_conversations))
+        .route("/outbox/failures", get(outbox::list_outbox_failures))
         .route("/channels/inspect", get(channels::inspect_prompt))
         .route(
             "/channels/inspect/capture",
@@ -111,13 +178,88 @@ pub async fn start_http_server(
         )
         .route("/opencode/{port}", any(opencode_proxy::opencode_proxy))
         .route("/opencode/{port}/", any(opencode_proxy::opencode_proxy))
-        .route("/agents/memories", get(memories::list_memories))
+        .route(
+            "/agents/memories",
+            get(memories::list_memories).post(memories::create_memory),
+        )
+        .route("/agents/memories/audit", get(memories::memory_audit))
         .route("/agents/memories/search", get(memories::search_memories))
         .route("/agents/memories/graph", get(memories::memory_graph))
+        .route(
+            "/agents/memories/bulletin",
+            get(memories::get_bulletin).put(memories::update_bulletin),
+        )
+        .route(
+            "/agents/memories/{id}",
+            get(memories::get_memory)
+                .put(memories::update_memory)
+                .delete(memories::delete_memory),
+        )
         .route(
             "/agents/memories/graph/neighbors",
             get(memories::memory_graph_neighbors),
         )
+        .route("/agents/user-profiles", get(profiles::list_profiles))
+        .route(
+            "/agents/user-profiles/{id}",
+            get(profiles::get_profile)
+                .put(profiles::update_profile)
+                .delete(profiles::delete_profile),
+        )
+        .route("/agents/user-profiles/{id}/facts", post(profiles::add_fact))
+        .route("/users/{sender_id}/data", delete(users::erase_user_data))
+        .route("/access/me", get(access::current_caller))
+        .route("/audit", get(audit::list_audit_log))
+        .route("/cluster", get(cluster::cluster_status))
+        .route(
+            "/access/users",
+            get(access::list_api_users).post(access::create_api_user),
+        )
+        .route(
+            "/access/users/{id}",
+            put(access::update_api_user).delete(access::delete_api_user),
+        )
+        .route(
+            "/access/users/{id}/token",
+            post(access::rotate_api_user_token),
+        )
+        .route(
+            "/agents/user-profiles/{id}/facts/{fact_id}",
+            put(profiles::update_fact).delete(profiles::delete_fact),
+        )
+        .route(
+            "/experiments",
+            get(experiments::list_experiments).post(experiments::create_experiment),
+        )
+        .route(
+            "/experiments/{id}/report",
+            get(experiments::experiment_report),
+        )
+        .route(
+            "/experiments/{id}/assignments",
+            put(experiments::assign_channel),
+        )
+        .route(
+            "/experiments/{id}/feedback",
+            post(experiments::record_feedback),
+        )
+        .route("/experiments/{id}/stop", post(experiments::stop_experiment))
+        .route("/teams", get(teams::list_teams))
+        .route("/teams/{id}/runs", get(teams::list_team_runs))
+        .route("/teams/{id}/runs/{run_id}", get(teams::get_team_run))
+        .route("/prompts", get(prompts::list_prompts))
+        .route(
+            "/prompts/{*name}",
+            get(prompts::get_prompt)
+                .put(prompts::update_prompt)
+                .delete(prompts::reset_prompt),
+        )
+        .route("/prompt-versions/{id}", get(prompts::get_prompt_version))
+        .route(
+            "/prompt-versions/{id}/rollback",
+            post(prompts::rollback_prompt_version),
+        )
+        .route("/replay", post(replay::replay_step))
         .route("/cortex/events", get(cortex::cortex_events))
         .route("/cortex-chat/messages", get(cortex::cortex_chat_messages))
         .route("/cortex-chat/threads", get(cortex::cortex_chat_threads))
@@ -125,8 +267,25 @@ pub async fn start_http_server(
             "/cortex-chat/thread",
             delete(cortex::cortex_chat_delete_thread),
         )
+        .route(
+            "/cortex-chat/thread/fork",
+            post(cortex::cortex_chat_fork_thread),
+        )
         .route("/cortex-chat/send", post(cortex::cortex_chat_send))
         .route("/agents/profile", get(agents::get_agent_profile))
+        .route("/agents/{id}/export", post(agents::export_agent))
+        .route("/agents/{id}/schema", get(storage::get_agent_schema))
+        .route(
+            "/agents/{id}/db/integrity",
+            get(storage::check_agent_db_integrity),
+        )
+        .route(
+            "/agents/{id}/db/maintenance",
+            post(storage::run_agent_db_maintenance),
+        )
+        // Spools its body to disk under its own cap instead of the
+        // in-memory body limit below.
+        .route("/agents/import", post(agents::import_agent))
         .route(
             "/agents/avatar",
             get(agents::get_avatar)
@@ -235,7 +394,28 @@ pub async fn start_http_server(
             "/providers/openai/oauth/browser/status",
             get(providers::openai_browser_oauth_status),
         )
+        .route(
+            "/providers/oauth/start",
+            post(providers::start_provider_oauth),
+        )
+        .route(
+            "/providers/oauth/callback",
+            get(providers::provider_oauth_callback),
+        )
+        .route(
+            "/providers/{provider}/models",
+            get(models::get_provider_models),
+        )
         .route("/providers/test", post(providers::test_provider_model))
+        .route(
+            "/providers/debug/captures",
+            get(providers::get_debug_captures)
+                .put(providers::update_debug_captures)
+                .delete(providers::clear_debug_captures),
+        )
+        .route("/ollama/models", get(providers::list_ollama_models))
+        .route("/ollama/pull", post(providers::pull_ollama_model))
+        .route("/ollama/health", get(providers::ollama_health))
         .route("/providers/{provider}", delete(providers::delete_provider))
         .route("/models", get(models::get_models))
         .route("/models/refresh", post(models::refresh_models))
@@ -264,6 +444,7 @@ pub async fn start_http_server(
             "/config/raw",
             get(settings::get_raw_config).put(settings::update_raw_config),
         )
+        .route("/config/reload", post(settings::reload_config))
         .route(
             "/update/check",
             get(settings::update_check).post(settings::update_check_now),
@@ -273,7 +454,9 @@ pub async fn start_http_server(
         .route("/ssh/authorized-key", put(ssh::set_authorized_key))
         .route("/ssh/status", get(ssh::ssh_status))
         .route("/webchat/send", post(webchat::webchat_send))
+        .route("/webchat/upload", post(webchat::webchat_upload))
         .route("/webchat/history", get(webchat::webchat_history))
+        .route("/webchat/ws", get(webchat::webchat_ws))
         .route("/links", get(links::list_links).post(links::create_link))
         .route(
             "/links/{from}/{to}",
@@ -315,11 +498,14 @@ pub async fn start_http_server(
 
     let handle = tokio::spawn(async move {
         let mut shutdown = shutdown_rx;
-        if let Err(error) = axum::serve(listener, app)
-            .with_graceful_shutdown(async move {
-                let _ = shutdown.wait_for(|v| *v).await;
-            })
-            .await
+        if let Err(error) = axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(async move {
+            let _ = shutdown.wait_for(|v| *v).await;
+        })
+        .await
         {
             tracing::error!(%error, "HTTP server exited with error");
         }
@@ -328,35 +514,128 @@ pub async fn start_http_server(
     Ok(handle)
 }
 
+/// Header accepted as an alternative to `Authorization: Bearer <key>`.
+const API_KEY_HEADER: &str = "x-api-key";
+
 async fn api_auth_middleware(
     State(state): State<Arc<ApiState>>,
-    request: Request,
+    mut request: Request,
     next: Next,
 ) -> Response {
-    let Some(expected_token) = state.auth_token.as_deref() else {
-        return next.run(request).await;
-    };
-
     let path = request.uri().path();
-    if path == "/api/health" || path == "/health" {
+    let path = path.strip_prefix("/api").unwrap_or(path).to_string();
+    // Orchestrator probes carry no credentials. The OAuth callback is a
+    // browser redirect from the provider, so it can't carry an API key; its
+    // single-use `state` authenticates it instead.
+    if path == "/health" || path == "/ready" || path == "/providers/oauth/callback" {
         return next.run(request).await;
     }
 
-    let is_authorized = request
+    let auth = state.auth.load_full();
+    let presented = request
         .headers()
         .get(header::AUTHORIZATION)
         .and_then(|value| value.to_str().ok())
         .and_then(|value| value.strip_prefix("Bearer "))
-        .is_some_and(|token| token == expected_token);
+        .or_else(|| {
+            request
+                .headers()
+                .get(API_KEY_HEADER)
+                .and_then(|value| value.to_str().ok())
+        });
+    let caller = match presented {
+        Some(presented) => authenticate(&state, &auth, presented).await,
+        None => None,
+    };
 
-    if is_authorized {
-        next.run(request).await
-    } else {
-        (
-            StatusCode::UNAUTHORIZED,
-            Json(json!({"error": "unauthorized"})),
+    // Rate limit by credential when authenticated, otherwise by remote
+    // address, so failed auth attempts are limited too.
+    let client = match &caller {
+        Some(ApiCaller {
+            kind: CallerKind::User,
+            name,
+            ..
+        }) => format!("user:{name}"),
+        Some(ApiCaller { name, .. }) => format!("key:{name}"),
+        None => request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| format!("ip:{}", address.ip()))
+            .unwrap_or_else(|| "anonymous".to_string()),
+    };
+    if let Err(retry_after) = state.rate_limiter.check(&client, &path) {
+        let retry_after_secs = retry_after.as_secs().max(1);
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, retry_after_secs.to_string())],
+            Json(json!({"error": "rate limited", "retry_after_secs": retry_after_secs})),
         )
-            .into_response()
+            .into_response();
+    }
+
+    // Without configured keys the API is open and every caller is an admin.
+    let caller = match caller {
+        Some(caller) if auth.is_enabled() => caller,
+        _ if !auth.is_enabled() => ApiCaller {
+            kind: CallerKind::Anonymous,
+            name: "anonymous".to_string(),
+            role: ApiRole::Admin,
+        },
+        _ => {
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({"error": "unauthorized"})),
+            )
+                .into_response();
+        }
+    };
+
+    let required = crate::access::required_role(request.method(), &path);
+    if caller.role < required {
+        tracing::debug!(
+            caller = %caller.name,
+            role = %caller.role,
+            %required,
+            method = %request.method(),
+            %path,
+            "API request denied by role"
+        );
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "forbidden", "required_role": required})),
+        )
+            .into_response();
+    }
+
+    request.extensions_mut().insert(caller);
+    next.run(request).await
+}
+
+/// Resolve presented credentials: `[api.auth]` keys first, then API users.
+async fn authenticate(
+    state: &ApiState,
+    auth: &ApiAuthConfig,
+    presented: &str,
+) -> Option<ApiCaller> {
+    if let Some(key) = auth.authenticate(presented) {
+        return Some(ApiCaller {
+            kind: CallerKind::Key,
+            name: key.name.clone(),
+            role: key.role,
+        });
+    }
+
+    let store = (**state.access_store.load()).clone()?;
+    match store.authenticate(presented).await {
+        Ok(user) => user.map(|user| ApiCaller {
+            kind: CallerKind::User,
+            name: user.name,
+            role: user.role,
+        }),
+        Err(error) => {
+            tracing::warn!(%error, "failed to look up API user");
+            None
+        }
     }
 }
 
@@ -407,7 +686,7 @@ fn normalize_api_path(path: &str) -> String {
         } else if i >= 2 {
             let parent = parts.get(i - 1).copied().unwrap_or("");
             match parent {
-                "secrets" | "groups" | "humans" | "links" => normalized.push("{name}"),
+                "secrets" | "groups" | "humans" | "links" | "users" => normalized.push("{name}"),
                 "servers" | "providers" => normalized.push("{name}"),
                 "opencode" => normalized.push("{port}"),
                 "agents"
@@ -415,6 +694,7 @@ fn normalize_api_path(path: &str) -> String {
                         *part,
                         "mcp"
                             | "warmup"
+                            | "storage"
                             | "overview"
                             | "workers"
                             | "memories"
@@ -1,8 +1,11 @@
+use super::access::ApiCaller;
 use super::state::ApiState;
 
-use axum::Json;
-use axum::extract::State;
+use crate::audit::{AuditEvent, changed_toml_sections, summarize_text};
+
+use axum::extract::{Query, State};
 use axum::http::StatusCode;
+use axum::{Extension, Json};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
@@ -45,6 +48,25 @@ pub(super) struct GlobalSettingsUpdate {
     ssh_enabled: Option<bool>,
 }
 
+impl GlobalSettingsUpdate {
+    /// Names of the settings this update changes, for the audit log. The
+    /// Brave Search key is named but its value never leaves the request.
+    fn field_names(&self) -> Vec<&'static str> {
+        [
+            ("brave_search_key", self.brave_search_key.is_some()),
+            ("api_enabled", self.api_enabled.is_some()),
+            ("api_port", self.api_port.is_some()),
+            ("api_bind", self.api_bind.is_some()),
+            ("worker_log_mode", self.worker_log_mode.is_some()),
+            ("opencode", self.opencode.is_some()),
+            ("ssh_enabled", self.ssh_enabled.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, set)| set.then_some(name))
+        .collect()
+    }
+}
+
 #[derive(Deserialize)]
 pub(super) struct OpenCodeSettingsUpdate {
     enabled: Option<bool>,
@@ -85,6 +107,14 @@ pub(super) struct RawConfigUpdateResponse {
     message: String,
 }
 
+#[derive(Deserialize)]
+pub(super) struct ReloadConfigQuery {
+    /// Reload every subsystem and restart every adapter, not just what the
+    /// file diff shows.
+    #[serde(default)]
+    force: bool,
+}
+
 pub(super) async fn get_global_settings(
     State(state): State<Arc<ApiState>>,
 ) -> Result<Json<GlobalSettingsResponse>, StatusCode> {
@@ -235,8 +265,10 @@ pub(super) async fn get_global_settings(
 
 pub(super) async fn update_global_settings(
     State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
     Json(request): Json<GlobalSettingsUpdate>,
 ) -> Result<Json<GlobalSettingsUpdateResponse>, StatusCode> {
+    let changed_fields = request.field_names();
     let config_path = state.config_path.read().await.clone();
 
     let content = if config_path.exists() {
@@ -352,41 +384,7 @@ pub(super) async fn update_global_settings(
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-    let reload_path = config_path.clone();
-    match tokio::task::spawn_blocking(move || crate::config::Config::load_from_path(&reload_path))
-        .await
-    {
-        Ok(Ok(new_config)) => {
-            let runtime_configs = state.runtime_configs.load();
-            let mcp_managers = state.mcp_managers.load();
-            let reload_targets = runtime_configs
-                .iter()
-                .filter_map(|(agent_id, runtime_config)| {
-                    mcp_managers.get(agent_id).map(|mcp_manager| {
-                        (
-                            agent_id.clone(),
-                            runtime_config.clone(),
-                            mcp_manager.clone(),
-                        )
-                    })
-                })
-                .collect::<Vec<_>>();
-            drop(runtime_configs);
-            drop(mcp_managers);
-
-            for (agent_id, runtime_config, mcp_manager) in reload_targets {
-                runtime_config
-                    .reload_config(&new_config, &agent_id, &mcp_manager)
-                    .await;
-            }
-        }
-        Ok(Err(error)) => {
-            tracing::warn!(%error, "settings written but failed to reload config immediately");
-        }
-        Err(error) => {
-            tracing::warn!(%error, "settings written but config reload task failed");
-        }
-    }
+    reload_written_config(&state).await;
 
     // Toggle sshd after config is persisted so the state is consistent on restart.
     // On failure, roll back the persisted flag so GET /api/settings stays accurate.
@@ -417,6 +415,14 @@ pub(super) async fn update_global_settings(
         }
     }
 
+    super::audit::record(
+        &state,
+        &caller,
+        AuditEvent::new("settings.update", "config.toml")
+            .after(format!("changed {}", changed_fields.join(", "))),
+    )
+    .await;
+
     let message = if requires_restart {
         "Settings updated. API server changes require a restart to take effect.".to_string()
     } else {
@@ -495,6 +501,7 @@ pub(super) async fn get_raw_config(
 
 pub(super) async fn update_raw_config(
     State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
     Json(request): Json<RawConfigUpdateRequest>,
 ) -> Result<Json<RawConfigUpdateResponse>, StatusCode> {
     let config_path = state.config_path.read().await.clone();
@@ -510,6 +517,9 @@ pub(super) async fn update_raw_config(
         }));
     }
 
+    let previous = tokio::fs::read_to_string(&config_path)
+        .await
+        .unwrap_or_default();
     tokio::fs::write(&config_path, &request.content)
         .await
         .map_err(|error| {
@@ -518,39 +528,79 @@ pub(super) async fn update_raw_config(
         })?;
 
     tracing::info!("config.toml updated via raw editor");
-
-    match crate::config::Config::load_from_path(&config_path) {
-        Ok(new_config) => {
-            let runtime_configs = state.runtime_configs.load();
-            let mcp_managers = state.mcp_managers.load();
-            let reload_targets = runtime_configs
-                .iter()
-                .filter_map(|(agent_id, runtime_config)| {
-                    mcp_managers.get(agent_id).map(|mcp_manager| {
-                        (
-                            agent_id.clone(),
-                            runtime_config.clone(),
-                            mcp_manager.clone(),
-                        )
-                    })
-                })
-                .collect::<Vec<_>>();
-            drop(runtime_configs);
-            drop(mcp_managers);
-
-            for (agent_id, runtime_config, mcp_manager) in reload_targets {
-                runtime_config
-                    .reload_config(&new_config, &agent_id, &mcp_manager)
-                    .await;
-            }
-        }
-        Err(error) => {
-            tracing::warn!(%error, "config.toml written but failed to reload immediately");
-        }
+    let mut after = summarize_text(&request.content, Some(&previous));
+    if let Some(sections) = changed_toml_sections(&previous, &request.content)
+        && !sections.is_empty()
+    {
+        after.push_str(&format!("; changed [{}]", sections.join("], [")));
     }
+    super::audit::record(
+        &state,
+        &caller,
+        AuditEvent::new("config.update", "config.toml")
+            .before(summarize_text(&previous, None))
+            .after(after),
+    )
+    .await;
+
+    reload_written_config(&state).await;
 
     Ok(Json(RawConfigUpdateResponse {
         success: true,
         message: "Config saved and reloaded.".to_string(),
     }))
 }
+
+/// Apply a config.toml the API just wrote, without waiting for the file
+/// watcher. The watcher's own reload then finds nothing left to apply.
+async fn reload_written_config(state: &ApiState) {
+    let Some(reloader) = (**state.config_reloader.load()).clone() else {
+        return;
+    };
+    if let Err(error) = reloader.reload(false).await {
+        tracing::warn!(%error, "config.toml written but failed to reload immediately");
+    }
+}
+
+/// POST /config/reload — apply config.toml to the running instance now.
+///
+/// Returns what changed: sections applied, adapters started, stopped, or
+/// restarted, and settings that still need a restart.
+pub(super) async fn reload_config(
+    State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
+    Query(query): Query<ReloadConfigQuery>,
+) -> Result<Json<crate::config::ReloadReport>, (StatusCode, String)> {
+    let reloader = (**state.config_reloader.load()).clone().ok_or((
+        StatusCode::SERVICE_UNAVAILABLE,
+        "config reloader not ready".to_string(),
+    ))?;
+    let report = reloader.reload(query.force).await.map_err(|error| {
+        tracing::warn!(%error, "config reload via API failed");
+        (StatusCode::UNPROCESSABLE_ENTITY, format!("{error:#}"))
+    })?;
+
+    let mut after = if report.changed_sections.is_empty() {
+        "no changes".to_string()
+    } else {
+        format!("changed [{}]", report.changed_sections.join("], ["))
+    };
+    if !report.adapters_restarted.is_empty() {
+        after.push_str(&format!(
+            "; restarted {}",
+            report.adapters_restarted.join(", ")
+        ));
+    }
+    super::audit::record(
+        &state,
+        &caller,
+        AuditEvent::new("config.reload", "config.toml").after(if query.force {
+            format!("forced; {after}")
+        } else {
+            after
+        }),
+    )
+    .await;
+
+    Ok(Json(report))
+}
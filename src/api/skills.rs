@@ -1,3 +1,4 @@
+use super::ids::AgentId;
 use super::state::{ApiEvent, ApiState};
 
 use axum::Json;
@@ -39,6 +40,8 @@ pub(super) struct SkillInfo {
     source: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     source_repo: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    requires: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -48,7 +51,7 @@ pub(super) struct SkillsListResponse {
 
 #[derive(Deserialize)]
 pub(super) struct InstallSkillRequest {
-    agent_id: String,
+    agent_id: AgentId,
     spec: String,
     #[serde(default)]
     instance: bool,
@@ -61,7 +64,7 @@ pub(super) struct InstallSkillResponse {
 
 #[derive(Deserialize)]
 pub(super) struct RemoveSkillRequest {
-    agent_id: String,
+    agent_id: AgentId,
     name: String,
 }
 
@@ -102,7 +105,7 @@ pub(super) struct RegistrySearchResponse {
 
 #[derive(Deserialize)]
 pub(super) struct SkillContentQuery {
-    agent_id: String,
+    agent_id: AgentId,
     name: String,
 }
 
@@ -125,7 +128,7 @@ pub(super) struct UploadSkillResponse {
 
 #[derive(Deserialize)]
 pub(super) struct SkillsQuery {
-    agent_id: String,
+    agent_id: AgentId,
 }
 
 #[derive(Deserialize)]
@@ -174,7 +177,7 @@ pub(super) async fn list_skills(
     let configs = state.agent_configs.load();
     let agent = configs
         .iter()
-        .find(|a| a.id == query.agent_id)
+        .find(|a| a.id == query.agent_id.as_str())
         .ok_or(StatusCode::NOT_FOUND)?;
 
     let instance_dir = state.instance_dir.load();
@@ -196,6 +199,7 @@ pub(super) async fn list_skills(
                 crate::skills::SkillSource::Workspace => "workspace".to_string(),
             },
             source_repo: s.source_repo,
+            requires: s.requires,
         })
         .collect();
 
@@ -212,7 +216,7 @@ pub(super) async fn install_skill(
     let configs = state.agent_configs.load();
     let agent = configs
         .iter()
-        .find(|a| a.id == req.agent_id)
+        .find(|a| a.id == req.agent_id.as_str())
         .ok_or(StatusCode::NOT_FOUND)?;
 
     let target_dir = if req.instance {
@@ -241,7 +245,7 @@ pub(super) async fn remove_skill(
     let configs = state.agent_configs.load();
     let agent = configs
         .iter()
-        .find(|a| a.id == req.agent_id)
+        .find(|a| a.id == req.agent_id.as_str())
         .ok_or(StatusCode::NOT_FOUND)?;
 
     let instance_dir = state.instance_dir.load();
@@ -284,7 +288,7 @@ pub(super) async fn get_skill_content(
     let configs = state.agent_configs.load();
     let agent = configs
         .iter()
-        .find(|a| a.id == query.agent_id)
+        .find(|a| a.id == query.agent_id.as_str())
         .ok_or(StatusCode::NOT_FOUND)?;
 
     let instance_dir = state.instance_dir.load();
@@ -318,7 +322,7 @@ pub(super) async fn upload_skill(
     let configs = state.agent_configs.load();
     let agent = configs
         .iter()
-        .find(|a| a.id == query.agent_id)
+        .find(|a| a.id == query.agent_id.as_str())
         .ok_or(StatusCode::NOT_FOUND)?;
 
     let target_dir = agent.workspace.join("skills");
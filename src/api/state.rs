@@ -1,9 +1,14 @@
 //! Shared state for the HTTP API.
 
+use super::rate_limit::RateLimiter;
+use crate::agent::branch_cache::BranchResultCache;
 use crate::agent::channel::ChannelState;
 use crate::agent::cortex_chat::CortexChatSession;
 use crate::agent::status::StatusBlock;
-use crate::config::{Binding, DefaultsConfig, DiscordPermissions, RuntimeConfig, SlackPermissions};
+use crate::config::{
+    ApiAuthConfig, ApiRateLimitConfig, Binding, DefaultsConfig, DiscordPermissions, RuntimeConfig,
+    SlackPermissions,
+};
 use crate::conversation::worker_transcript::{ActionContent, TranscriptStep};
 use crate::cron::{CronStore, Scheduler};
 use crate::llm::LlmManager;
@@ -48,7 +53,11 @@ pub struct AgentInfo {
 /// State shared across all API handlers.
 pub struct ApiState {
     pub started_at: Instant,
-    pub auth_token: Option<String>,
+    /// Accepted API keys. Shared with the config watcher so key rotation
+    /// applies without a restart.
+    pub auth: Arc<ArcSwap<ApiAuthConfig>>,
+    /// Per-client request limits, reloaded alongside `auth`.
+    pub rate_limiter: Arc<RateLimiter>,
     /// Aggregated event stream from all agents. SSE clients subscribe here.
     pub event_tx: broadcast::Sender<ApiEvent>,
     /// Per-agent SQLite pools for querying channel/conversation data.
@@ -57,6 +66,8 @@ pub struct ApiState {
     pub agent_configs: arc_swap::ArcSwap<Vec<AgentInfo>>,
     /// Per-agent memory search instances for the memories API.
     pub memory_searches: arc_swap::ArcSwap<HashMap<String, Arc<MemorySearch>>>,
+    /// Per-agent branch conclusion caches, cleared by user data erasure.
+    pub branch_caches: arc_swap::ArcSwap<HashMap<String, Arc<BranchResultCache>>>,
     /// Live status blocks for active channels, keyed by channel_id.
     pub channel_status_blocks: RwLock<HashMap<String, Arc<tokio::sync::RwLock<StatusBlock>>>>,
     /// Live channel states for active channels, keyed by channel_id.
@@ -92,6 +103,14 @@ pub struct ApiState {
     pub sandboxes: ArcSwap<HashMap<String, Arc<crate::sandbox::Sandbox>>>,
     /// Instance-level secrets store (shared across all agents).
     pub secrets_store: ArcSwap<Option<Arc<crate::secrets::store::SecretsStore>>>,
+    /// Instance-level history of config, identity, and skill file changes.
+    pub config_changelog: ArcSwap<Option<Arc<crate::config::ConfigChangelog>>>,
+    /// Applies config.toml changes; shared with the file watcher.
+    pub config_reloader: ArcSwap<Option<Arc<crate::config::ConfigReloader>>>,
+    /// Instance-level API users, checked after the `[api.auth]` keys.
+    pub access_store: ArcSwap<Option<Arc<crate::access::AccessStore>>>,
+    /// Instance-level audit log of administrative actions.
+    pub audit_log: ArcSwap<Option<Arc<crate::audit::AuditLog>>>,
     /// Shared reference to the Discord permissions ArcSwap (same instance used by the adapter and file watcher).
     pub discord_permissions: RwLock<Option<Arc<ArcSwap<DiscordPermissions>>>>,
     /// Shared reference to the Slack permissions ArcSwap (same instance used by the adapter and file watcher).
@@ -131,6 +150,8 @@ pub struct ApiState {
     pub agent_groups: ArcSwap<Vec<crate::config::GroupDef>>,
     /// Org-level humans for the topology UI.
     pub agent_humans: ArcSwap<Vec<crate::config::HumanDef>>,
+    /// Coordinator-led agent teams.
+    pub agent_teams: ArcSwap<Vec<crate::config::TeamDef>>,
     /// Live transcript cache for running workers. Accumulates `TranscriptStep`s
     /// from `ToolStarted`/`ToolCompleted` events so that page refreshes can
     /// recover the transcript without waiting for the worker to complete.
@@ -165,6 +186,12 @@ pub enum ApiEvent {
         channel_id: String,
         is_typing: bool,
     },
+    /// A turn was cancelled by a new message and is restarting with it.
+    TurnInterrupted {
+        agent_id: String,
+        channel_id: String,
+        sender_id: String,
+    },
     /// Streaming text delta for an outbound assistant message.
     OutboundMessageDelta {
         agent_id: String,
@@ -278,6 +305,16 @@ pub enum ApiEvent {
         content: String,
         tool_calls: Option<Vec<crate::agent::cortex_chat::CortexChatToolCall>>,
     },
+    /// Progress from an Ollama model download started via the API.
+    OllamaPullProgress {
+        model: String,
+        status: String,
+        completed: Option<u64>,
+        total: Option<u64>,
+        /// True on the final event, whether the pull succeeded or failed.
+        done: bool,
+        error: Option<String>,
+    },
 }
 
 impl ApiState {
@@ -293,11 +330,13 @@ impl ApiState {
         let (event_tx, _) = broadcast::channel(512);
         Self {
             started_at: Instant::now(),
-            auth_token: None,
+            auth: Arc::new(ArcSwap::from_pointee(ApiAuthConfig::default())),
+            rate_limiter: Arc::new(RateLimiter::new(ApiRateLimitConfig::default())),
             event_tx,
             agent_pools: arc_swap::ArcSwap::from_pointee(HashMap::new()),
             agent_configs: arc_swap::ArcSwap::from_pointee(Vec::new()),
             memory_searches: arc_swap::ArcSwap::from_pointee(HashMap::new()),
+            branch_caches: arc_swap::ArcSwap::from_pointee(HashMap::new()),
             channel_status_blocks: RwLock::new(HashMap::new()),
             channel_states: RwLock::new(HashMap::new()),
             cortex_chat_sessions: arc_swap::ArcSwap::from_pointee(HashMap::new()),
@@ -314,6 +353,10 @@ impl ApiState {
             mcp_managers: ArcSwap::from_pointee(HashMap::new()),
             sandboxes: ArcSwap::from_pointee(HashMap::new()),
             secrets_store: ArcSwap::from_pointee(None),
+            config_changelog: ArcSwap::from_pointee(None),
+            config_reloader: ArcSwap::from_pointee(None),
+            access_store: ArcSwap::from_pointee(None),
+            audit_log: ArcSwap::from_pointee(None),
             discord_permissions: RwLock::new(None),
             slack_permissions: RwLock::new(None),
             bindings: RwLock::new(None),
@@ -333,6 +376,7 @@ impl ApiState {
             agent_links: ArcSwap::from_pointee(Vec::new()),
             agent_groups: ArcSwap::from_pointee(Vec::new()),
             agent_humans: ArcSwap::from_pointee(Vec::new()),
+            agent_teams: ArcSwap::from_pointee(Vec::new()),
             live_worker_transcripts: Arc::new(RwLock::new(HashMap::new())),
             ssh_mutex: tokio::sync::Mutex::new(()),
         }
@@ -671,6 +715,19 @@ impl ApiState {
                                     })
                                     .ok();
                             }
+                            ProcessEvent::TurnInterrupted {
+                                channel_id,
+                                sender_id,
+                                ..
+                            } => {
+                                api_tx
+                                    .send(ApiEvent::TurnInterrupted {
+                                        agent_id: agent_id.clone(),
+                                        channel_id: channel_id.to_string(),
+                                        sender_id: sender_id.clone(),
+                                    })
+                                    .ok();
+                            }
                             _ => {}
                         }
                     }
@@ -711,6 +768,11 @@ impl ApiState {
         self.memory_searches.store(Arc::new(searches));
     }
 
+    /// Set the branch conclusion caches for all agents.
+    pub fn set_branch_caches(&self, caches: HashMap<String, Arc<BranchResultCache>>) {
+        self.branch_caches.store(Arc::new(caches));
+    }
+
     /// Set the cortex chat sessions for all agents.
     pub fn set_cortex_chat_sessions(&self, sessions: HashMap<String, Arc<CortexChatSession>>) {
         self.cortex_chat_sessions.store(Arc::new(sessions));
@@ -776,6 +838,26 @@ impl ApiState {
         self.secrets_store.store(Arc::new(Some(store)));
     }
 
+    /// Set the instance-level config changelog.
+    pub fn set_config_changelog(&self, changelog: Arc<crate::config::ConfigChangelog>) {
+        self.config_changelog.store(Arc::new(Some(changelog)));
+    }
+
+    /// Set the config reloader.
+    pub fn set_config_reloader(&self, reloader: Arc<crate::config::ConfigReloader>) {
+        self.config_reloader.store(Arc::new(Some(reloader)));
+    }
+
+    /// Set the instance-level API user store.
+    pub fn set_access_store(&self, store: Arc<crate::access::AccessStore>) {
+        self.access_store.store(Arc::new(Some(store)));
+    }
+
+    /// Set the instance-level audit log.
+    pub fn set_audit_log(&self, log: Arc<crate::audit::AuditLog>) {
+        self.audit_log.store(Arc::new(Some(log)));
+    }
+
     /// Share the Discord permissions ArcSwap with the API so reads get hot-reloaded values.
     pub async fn set_discord_permissions(&self, permissions: Arc<ArcSwap<DiscordPermissions>>) {
         *self.discord_permissions.write().await = Some(permissions);
@@ -841,6 +923,11 @@ impl ApiState {
         self.agent_humans.store(Arc::new(humans));
     }
 
+    /// Set the coordinator-led agent teams.
+    pub fn set_agent_teams(&self, teams: Vec<crate::config::TeamDef>) {
+        self.agent_teams.store(Arc::new(teams));
+    }
+
     /// Send an event to all SSE subscribers.
     pub fn send_event(&self, event: ApiEvent) {
         let _ = self.event_tx.send(event);
@@ -0,0 +1,272 @@
+use super::ids::AgentId;
+use super::state::ApiState;
+
+use crate::agent::storage::{AgentDiskUsage, StorageCleanupReport, VectorTableReport};
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub(super) struct StorageQuery {
+    agent_id: AgentId,
+    /// Measure now instead of returning the monitor's last result.
+    #[serde(default)]
+    refresh: bool,
+}
+
+#[derive(Deserialize)]
+pub(super) struct StorageCleanupRequest {
+    agent_id: AgentId,
+}
+
+#[derive(Deserialize)]
+pub(super) struct VectorOptimizeRequest {
+    /// Optimize a single agent; all agents when omitted.
+    #[serde(default)]
+    agent_id: Option<AgentId>,
+}
+
+#[derive(Serialize)]
+pub(super) struct StorageUsageResponse {
+    agent_id: String,
+    usage: AgentDiskUsage,
+}
+
+#[derive(Serialize)]
+pub(super) struct StorageCleanupResponse {
+    agent_id: String,
+    report: StorageCleanupReport,
+    usage: AgentDiskUsage,
+}
+
+/// Disk usage for an agent. Measures on demand when the monitor hasn't
+/// produced a result yet or `refresh` is set.
+pub(super) async fn get_agent_storage(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<StorageQuery>,
+) -> Result<Json<StorageUsageResponse>, StatusCode> {
+    let runtime_config = state
+        .runtime_configs
+        .load()
+        .get(query.agent_id.as_str())
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let cached = (**runtime_config.storage_usage.load()).clone();
+    let usage = match cached {
+        Some(usage) if !query.refresh => usage,
+        _ => measure(&runtime_config, &query.agent_id).await?,
+    };
+
+    Ok(Json(StorageUsageResponse {
+        agent_id: query.agent_id.into_inner(),
+        usage,
+    }))
+}
+
+/// Run log/screenshot retention and database compaction for an agent now.
+pub(super) async fn cleanup_agent_storage(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<StorageCleanupRequest>,
+) -> Result<Json<StorageCleanupResponse>, StatusCode> {
+    let runtime_config = state
+        .runtime_configs
+        .load()
+        .get(request.agent_id.as_str())
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let pool = state
+        .agent_pools
+        .load()
+        .get(request.agent_id.as_str())
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let memory_search = state
+        .memory_searches
+        .load()
+        .get(request.agent_id.as_str())
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let report =
+        crate::agent::storage::cleanup(&request.agent_id, &runtime_config, &pool, &memory_search)
+            .await;
+    let usage = measure(&runtime_config, &request.agent_id).await?;
+
+    Ok(Json(StorageCleanupResponse {
+        agent_id: request.agent_id.into_inner(),
+        report,
+        usage,
+    }))
+}
+
+#[derive(Serialize)]
+pub(super) struct AgentVectorOptimizeResult {
+    agent_id: String,
+    tables: Vec<VectorTableReport>,
+}
+
+#[derive(Serialize)]
+pub(super) struct VectorOptimizeResponse {
+    results: Vec<AgentVectorOptimizeResult>,
+}
+
+/// Compact and prune LanceDB tables now, without waiting for the scheduled
+/// pass or for the agent to go idle.
+pub(super) async fn optimize_vector_tables(
+    State(state): State<Arc<ApiState>>,
+    Json(request): Json<VectorOptimizeRequest>,
+) -> Result<Json<VectorOptimizeResponse>, StatusCode> {
+    let memory_searches = state.memory_searches.load();
+    let mut targets: Vec<_> = match &request.agent_id {
+        Some(agent_id) => vec![(
+            agent_id.to_string(),
+            memory_searches
+                .get(agent_id.as_str())
+                .cloned()
+                .ok_or(StatusCode::NOT_FOUND)?,
+        )],
+        None => memory_searches
+            .iter()
+            .map(|(agent_id, memory_search)| (agent_id.clone(), memory_search.clone()))
+            .collect(),
+    };
+    targets.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut results = Vec::with_capacity(targets.len());
+    for (agent_id, memory_search) in targets {
+        let tables = crate::agent::storage::optimize_vector_tables(&agent_id, &memory_search).await;
+        results.push(AgentVectorOptimizeResult { agent_id, tables });
+    }
+
+    Ok(Json(VectorOptimizeResponse { results }))
+}
+
+#[derive(Serialize)]
+pub(super) struct SchemaResponse {
+    agent_id: String,
+    #[serde(flatten)]
+    status: crate::db::SchemaStatus,
+}
+
+/// Applied and pending schema migrations for an agent's SQLite database.
+pub(super) async fn get_agent_schema(
+    State(state): State<Arc<ApiState>>,
+    Path(agent_id): Path<AgentId>,
+) -> Result<Json<SchemaResponse>, StatusCode> {
+    let pool = state
+        .agent_pools
+        .load()
+        .get(agent_id.as_str())
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let status = crate::db::schema_status(&pool).await.map_err(|error| {
+        tracing::warn!(%error, %agent_id, "failed to read schema status");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(SchemaResponse {
+        agent_id: agent_id.into_inner(),
+        status,
+    }))
+}
+
+#[derive(Deserialize)]
+pub(super) struct IntegrityQuery {
+    /// Run the full check, which also verifies index contents.
+    #[serde(default)]
+    full: bool,
+}
+
+#[derive(Serialize)]
+pub(super) struct IntegrityResponse {
+    agent_id: String,
+    #[serde(flatten)]
+    report: crate::db::maintenance::IntegrityReport,
+}
+
+/// Check an agent's SQLite database for corruption. Runs the quick check
+/// unless `full` is set.
+pub(super) async fn check_agent_db_integrity(
+    State(state): State<Arc<ApiState>>,
+    Path(agent_id): Path<AgentId>,
+    Query(query): Query<IntegrityQuery>,
+) -> Result<Json<IntegrityResponse>, StatusCode> {
+    let pool = agent_pool(&state, &agent_id)?;
+    let report = crate::db::maintenance::integrity_check(&pool, !query.full)
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, %agent_id, "failed to run integrity check");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if !report.ok {
+        tracing::error!(%agent_id, problems = ?report.problems, "database integrity check failed");
+    }
+
+    Ok(Json(IntegrityResponse {
+        agent_id: agent_id.into_inner(),
+        report,
+    }))
+}
+
+#[derive(Serialize)]
+pub(super) struct DbMaintenanceResponse {
+    agent_id: String,
+    report: crate::db::maintenance::MaintenanceReport,
+}
+
+/// Run ANALYZE, VACUUM (past the free-page threshold), and a WAL checkpoint
+/// now, without waiting for the schedule or for the agent to go idle.
+pub(super) async fn run_agent_db_maintenance(
+    State(state): State<Arc<ApiState>>,
+    Path(agent_id): Path<AgentId>,
+) -> Result<Json<DbMaintenanceResponse>, StatusCode> {
+    let pool = agent_pool(&state, &agent_id)?;
+    let config = state
+        .runtime_configs
+        .load()
+        .get(agent_id.as_str())
+        .map(|runtime_config| **runtime_config.storage.load())
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let report = crate::db::maintenance::run(&pool, &config)
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, %agent_id, "database maintenance failed");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(DbMaintenanceResponse {
+        agent_id: agent_id.into_inner(),
+        report,
+    }))
+}
+
+fn agent_pool(state: &ApiState, agent_id: &AgentId) -> Result<sqlx::SqlitePool, StatusCode> {
+    state
+        .agent_pools
+        .load()
+        .get(agent_id.as_str())
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn measure(
+    runtime_config: &crate::config::RuntimeConfig,
+    agent_id: &str,
+) -> Result<AgentDiskUsage, StatusCode> {
+    let usage = crate::agent::storage::measure(runtime_config)
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, agent_id, "failed to measure agent disk usage");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    runtime_config
+        .storage_usage
+        .store(Arc::new(Some(usage.clone())));
+    Ok(usage)
+}
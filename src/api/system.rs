@@ -1,5 +1,8 @@
 use super::state::{ApiEvent, ApiState};
 
+use crate::db::maintenance::DbReport;
+use crate::supervisor::{ProcessSnapshot, Supervisor};
+
 use axum::Json;
 use axum::body::Bytes;
 use axum::extract::State;
@@ -16,11 +19,6 @@ use std::sync::Arc;
 use zip::CompressionMethod;
 use zip::write::SimpleFileOptions;
 
-#[derive(Serialize)]
-pub(super) struct HealthResponse {
-    status: &'static str,
-}
-
 #[derive(Serialize)]
 pub(super) struct IdleResponse {
     idle: bool,
@@ -36,10 +34,6 @@ pub(super) struct StatusResponse {
     uptime_seconds: u64,
 }
 
-pub(super) async fn health() -> Json<HealthResponse> {
-    Json(HealthResponse { status: "ok" })
-}
-
 /// Reports whether the instance is idle (no active workers or branches).
 /// Used by the platform to gate rolling updates.
 pub(super) async fn idle(State(state): State<Arc<ApiState>>) -> Json<IdleResponse> {
@@ -70,6 +64,78 @@ pub(super) async fn status(State(state): State<Arc<ApiState>>) -> Json<StatusRes
     })
 }
 
+#[derive(Serialize)]
+pub(super) struct ProcessesResponse {
+    processes: Vec<ProcessSnapshot>,
+}
+
+/// Supervised background tasks with their status, heartbeat and restart count.
+pub(super) async fn processes() -> Json<ProcessesResponse> {
+    Json(ProcessesResponse {
+        processes: Supervisor::global().snapshots(),
+    })
+}
+
+#[derive(Serialize)]
+pub(super) struct AgentDbStatus {
+    agent_id: String,
+    schema_version: Option<i64>,
+    latest_known_version: Option<i64>,
+    pending_migrations: usize,
+    database: Option<DbReport>,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub(super) struct DbStatusResponse {
+    agents: Vec<AgentDbStatus>,
+}
+
+/// Schema version, file layout, and table sizes of every agent's SQLite
+/// database. An agent whose database can't be read reports the error instead.
+pub(super) async fn db_status(State(state): State<Arc<ApiState>>) -> Json<DbStatusResponse> {
+    let mut pools: Vec<_> = state
+        .agent_pools
+        .load()
+        .iter()
+        .map(|(agent_id, pool)| (agent_id.clone(), pool.clone()))
+        .collect();
+    pools.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut agents = Vec::with_capacity(pools.len());
+    for (agent_id, pool) in pools {
+        let status = async {
+            let schema = crate::db::schema_status(&pool).await?;
+            let database = crate::db::maintenance::report(&pool).await?;
+            crate::error::Result::Ok((schema, database))
+        }
+        .await;
+        agents.push(match status {
+            Ok((schema, database)) => AgentDbStatus {
+                agent_id,
+                schema_version: schema.current_version,
+                latest_known_version: schema.latest_known_version,
+                pending_migrations: schema.pending.len(),
+                database: Some(database),
+                error: None,
+            },
+            Err(error) => {
+                tracing::warn!(%error, %agent_id, "failed to read database status");
+                AgentDbStatus {
+                    agent_id,
+                    schema_version: None,
+                    latest_known_version: None,
+                    pending_migrations: 0,
+                    database: None,
+                    error: Some(error.to_string()),
+                }
+            }
+        });
+    }
+
+    Json(DbStatusResponse { agents })
+}
+
 /// SSE endpoint streaming all agent events to connected clients.
 pub(super) async fn events_sse(
     State(state): State<Arc<ApiState>>,
@@ -86,6 +152,7 @@ pub(super) async fn events_sse(
                             ApiEvent::OutboundMessage { .. } => "outbound_message",
                             ApiEvent::OutboundMessageDelta { .. } => "outbound_message_delta",
                             ApiEvent::TypingState { .. } => "typing_state",
+                            ApiEvent::TurnInterrupted { .. } => "turn_interrupted",
                             ApiEvent::WorkerStarted { .. } => "worker_started",
                             ApiEvent::WorkerStatusUpdate { .. } => "worker_status",
                             ApiEvent::WorkerIdle { .. } => "worker_idle",
@@ -101,6 +168,7 @@ pub(super) async fn events_sse(
                             ApiEvent::OpenCodePartUpdated { .. } => "opencode_part_updated",
                             ApiEvent::WorkerText { .. } => "worker_text",
                             ApiEvent::CortexChatMessage { .. } => "cortex_chat_message",
+                            ApiEvent::OllamaPullProgress { .. } => "ollama_pull_progress",
                         };
                         yield Ok(axum::response::sse::Event::default()
                             .event(event_type)
@@ -1,3 +1,4 @@
+use super::ids::AgentId;
 use super::state::ApiState;
 
 use axum::Json;
@@ -8,7 +9,7 @@ use std::sync::Arc;
 
 #[derive(Deserialize)]
 pub(super) struct TaskListQuery {
-    agent_id: String,
+    agent_id: AgentId,
     #[serde(default)]
     status: Option<String>,
     #[serde(default)]
@@ -19,12 +20,12 @@ pub(super) struct TaskListQuery {
 
 #[derive(Deserialize)]
 pub(super) struct TaskGetQuery {
-    agent_id: String,
+    agent_id: AgentId,
 }
 
 #[derive(Deserialize)]
 pub(super) struct CreateTaskRequest {
-    agent_id: String,
+    agent_id: AgentId,
     title: String,
     #[serde(default)]
     description: Option<String>,
@@ -44,7 +45,7 @@ pub(super) struct CreateTaskRequest {
 
 #[derive(Deserialize)]
 pub(super) struct UpdateTaskRequest {
-    agent_id: String,
+    agent_id: AgentId,
     #[serde(default)]
     title: Option<String>,
     #[serde(default)]
@@ -67,7 +68,7 @@ pub(super) struct UpdateTaskRequest {
 
 #[derive(Deserialize)]
 pub(super) struct DeleteTaskQuery {
-    agent_id: String,
+    agent_id: AgentId,
 }
 
 #[derive(Serialize)]
@@ -95,7 +96,9 @@ pub(super) async fn list_tasks(
     Query(query): Query<TaskListQuery>,
 ) -> Result<Json<TaskListResponse>, StatusCode> {
     let stores = state.task_stores.load();
-    let store = stores.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let status = match query.status.as_deref() {
         None => None,
@@ -125,7 +128,9 @@ pub(super) async fn get_task(
     Query(query): Query<TaskGetQuery>,
 ) -> Result<Json<TaskResponse>, StatusCode> {
     let stores = state.task_stores.load();
-    let store = stores.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let task = store
         .get_by_number(&query.agent_id, number)
@@ -144,7 +149,9 @@ pub(super) async fn create_task(
     Json(request): Json<CreateTaskRequest>,
 ) -> Result<Json<TaskResponse>, StatusCode> {
     let stores = state.task_stores.load();
-    let store = stores.get(&request.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let status = match request.status.as_deref() {
         None => crate::tasks::TaskStatus::Backlog,
@@ -157,7 +164,7 @@ pub(super) async fn create_task(
 
     let task = store
         .create(crate::tasks::CreateTaskInput {
-            agent_id: request.agent_id.clone(),
+            agent_id: request.agent_id.to_string(),
             title: request.title,
             description: request.description,
             status,
@@ -192,7 +199,9 @@ pub(super) async fn update_task(
     Json(request): Json<UpdateTaskRequest>,
 ) -> Result<Json<TaskResponse>, StatusCode> {
     let stores = state.task_stores.load();
-    let store = stores.get(&request.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let status = match request.status.as_deref() {
         None => None,
@@ -248,7 +257,9 @@ pub(super) async fn delete_task(
     Query(query): Query<DeleteTaskQuery>,
 ) -> Result<Json<TaskActionResponse>, StatusCode> {
     let stores = state.task_stores.load();
-    let store = stores.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let deleted = store
         .delete(&query.agent_id, number)
@@ -265,7 +276,7 @@ pub(super) async fn delete_task(
     state
         .event_tx
         .send(super::state::ApiEvent::TaskUpdated {
-            agent_id: query.agent_id.clone(),
+            agent_id: query.agent_id.to_string(),
             task_number: number,
             status: "deleted".to_string(),
             action: "deleted".to_string(),
@@ -284,7 +295,9 @@ pub(super) async fn approve_task(
     Json(request): Json<UpdateTaskRequest>,
 ) -> Result<Json<TaskResponse>, StatusCode> {
     let stores = state.task_stores.load();
-    let store = stores.get(&request.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     let task = store
         .update(
@@ -324,7 +337,9 @@ pub(super) async fn execute_task(
     Json(request): Json<UpdateTaskRequest>,
 ) -> Result<Json<TaskResponse>, StatusCode> {
     let stores = state.task_stores.load();
-    let store = stores.get(&request.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let store = stores
+        .get(request.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     // Fetch current task to check if transition is needed.
     let current = store
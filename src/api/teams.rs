@@ -0,0 +1,123 @@
+//! REST API handlers for agent teams and their dispatch runs.
+
+use super::state::ApiState;
+
+use crate::config::TeamDef;
+use crate::teams::{TeamRun, TeamRunStore};
+
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub(super) struct TeamRunsQuery {
+    #[serde(default = "default_runs_limit")]
+    limit: i64,
+}
+
+fn default_runs_limit() -> i64 {
+    50
+}
+
+#[derive(Serialize)]
+pub(super) struct TeamSummary {
+    #[serde(flatten)]
+    team: TeamDef,
+    /// The coordinator's most recent dispatch for this team.
+    last_run: Option<TeamRun>,
+}
+
+#[derive(Serialize)]
+pub(super) struct TeamsListResponse {
+    teams: Vec<TeamSummary>,
+}
+
+#[derive(Serialize)]
+pub(super) struct TeamRunsResponse {
+    runs: Vec<TeamRun>,
+}
+
+#[derive(Serialize)]
+pub(super) struct TeamRunResponse {
+    run: TeamRun,
+}
+
+/// The team and a run store on its coordinator's database.
+fn team_and_store(state: &ApiState, team_id: &str) -> Result<(TeamDef, TeamRunStore), StatusCode> {
+    let team = state
+        .agent_teams
+        .load()
+        .iter()
+        .find(|team| team.id == team_id)
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let pools = state.agent_pools.load();
+    let pool = pools.get(&team.coordinator).ok_or(StatusCode::NOT_FOUND)?;
+    Ok((team, TeamRunStore::new(pool.clone())))
+}
+
+fn internal_error(error: crate::error::Error) -> StatusCode {
+    tracing::error!(%error, "failed to load team runs");
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+/// GET /teams — configured teams with each one's latest run.
+pub(super) async fn list_teams(
+    State(state): State<Arc<ApiState>>,
+) -> Result<Json<TeamsListResponse>, StatusCode> {
+    let team_ids: Vec<String> = state
+        .agent_teams
+        .load()
+        .iter()
+        .map(|team| team.id.clone())
+        .collect();
+
+    let mut teams = Vec::with_capacity(team_ids.len());
+    for team_id in team_ids {
+        let Ok((team, store)) = team_and_store(&state, &team_id) else {
+            continue;
+        };
+        let last_run = store
+            .list(Some(&team.id), 1)
+            .await
+            .map_err(internal_error)?
+            .into_iter()
+            .next();
+        teams.push(TeamSummary { team, last_run });
+    }
+
+    Ok(Json(TeamsListResponse { teams }))
+}
+
+/// GET /teams/{id}/runs — a team's runs, newest first.
+pub(super) async fn list_team_runs(
+    State(state): State<Arc<ApiState>>,
+    Path(team_id): Path<String>,
+    Query(query): Query<TeamRunsQuery>,
+) -> Result<Json<TeamRunsResponse>, StatusCode> {
+    let (team, store) = team_and_store(&state, &team_id)?;
+    let runs = store
+        .list(Some(&team.id), query.limit.clamp(1, 500))
+        .await
+        .map_err(internal_error)?;
+
+    Ok(Json(TeamRunsResponse { runs }))
+}
+
+/// GET /teams/{id}/runs/{run_id} — one run with every member's result.
+pub(super) async fn get_team_run(
+    State(state): State<Arc<ApiState>>,
+    Path((team_id, run_id)): Path<(String, String)>,
+) -> Result<Json<TeamRunResponse>, StatusCode> {
+    let (team, store) = team_and_store(&state, &team_id)?;
+    let run = store
+        .get(&run_id)
+        .await
+        .map_err(internal_error)?
+        .filter(|run| run.team_id == team.id)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(TeamRunResponse { run }))
+}
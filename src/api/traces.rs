@@ -0,0 +1,34 @@
+//! REST API handler for per-conversation LLM completion traces.
+
+use super::state::ApiState;
+
+use crate::llm::trace::CompletionTrace;
+
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use serde::Serialize;
+use std::sync::Arc;
+
+#[derive(Serialize)]
+pub(super) struct TracesResponse {
+    conversation_id: String,
+    traces: Vec<CompletionTrace>,
+}
+
+/// GET /traces/{conversation_id} — recent completion calls, newest first.
+pub(super) async fn get_conversation_traces(
+    State(state): State<Arc<ApiState>>,
+    Path(conversation_id): Path<String>,
+) -> Result<Json<TracesResponse>, StatusCode> {
+    let llm_manager = state.llm_manager.read().await;
+    let llm_manager = llm_manager
+        .as_ref()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    let traces = llm_manager.traces().for_conversation(&conversation_id);
+
+    Ok(Json(TracesResponse {
+        conversation_id,
+        traces,
+    }))
+}
@@ -0,0 +1,43 @@
+use super::ids::AgentId;
+use super::state::ApiState;
+
+use crate::llm::budget::BudgetStatus;
+
+use axum::Json;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub(super) struct BudgetQuery {
+    agent_id: AgentId,
+}
+
+#[derive(Serialize)]
+pub(super) struct BudgetResponse {
+    agent_id: String,
+    #[serde(flatten)]
+    status: BudgetStatus,
+}
+
+/// Today's spend for an agent against its `[defaults.budget]` limits.
+pub(super) async fn get_budget(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<BudgetQuery>,
+) -> Result<Json<BudgetResponse>, StatusCode> {
+    let runtime_config = state
+        .runtime_configs
+        .load()
+        .get(query.agent_id.as_str())
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let config = runtime_config.budget.load();
+    let status = runtime_config.spend.status(&config).await;
+
+    Ok(Json(BudgetResponse {
+        agent_id: query.agent_id.into_inner(),
+        status,
+    }))
+}
@@ -0,0 +1,170 @@
+//! Per-user data erasure across agents.
+
+use super::access::ApiCaller;
+use super::ids::AgentId;
+use super::state::ApiState;
+
+use crate::audit::AuditEvent;
+use crate::conversation::erasure::{ErasureMode, ErasureReport, ErasureRequest, PromptBuffers};
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::{Extension, Json};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+pub(super) struct EraseUserQuery {
+    /// Only erase the user on this platform (`discord`, `slack`, …). Without
+    /// it, the sender ID is matched on every platform.
+    #[serde(default)]
+    platform: Option<String>,
+    /// Only erase from this agent; all agents when omitted.
+    #[serde(default)]
+    agent_id: Option<AgentId>,
+    #[serde(default)]
+    mode: ErasureMode,
+}
+
+#[derive(Serialize)]
+pub(super) struct AgentErasureReport {
+    agent_id: String,
+    #[serde(flatten)]
+    report: ErasureReport,
+}
+
+#[derive(Serialize)]
+pub(super) struct EraseUserResponse {
+    sender_id: String,
+    platform: Option<String>,
+    mode: ErasureMode,
+    erased_at: String,
+    agents: Vec<AgentErasureReport>,
+}
+
+/// DELETE /users/{sender_id}/data — remove or anonymize everything the agents
+/// store about a platform user and return what was erased.
+pub(super) async fn erase_user_data(
+    State(state): State<Arc<ApiState>>,
+    Extension(caller): Extension<ApiCaller>,
+    Path(sender_id): Path<String>,
+    Query(query): Query<EraseUserQuery>,
+) -> Result<Json<EraseUserResponse>, StatusCode> {
+    let sender_id = sender_id.trim().to_string();
+    let platform = query
+        .platform
+        .map(|platform| platform.trim().to_string())
+        .filter(|platform| !platform.is_empty());
+    if sender_id.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let pools = state.agent_pools.load();
+    let memory_searches = state.memory_searches.load();
+    let runtime_configs = state.runtime_configs.load();
+    let branch_caches = state.branch_caches.load();
+    let llm_manager = state.llm_manager.read().await.clone();
+    let mut targets: Vec<_> = match &query.agent_id {
+        Some(agent_id) => vec![agent_id.to_string()],
+        None => pools.keys().cloned().collect(),
+    };
+    targets.sort();
+
+    let request = ErasureRequest {
+        sender_id: sender_id.clone(),
+        platform: platform.clone(),
+        mode: query.mode,
+    };
+    let mut agents = Vec::with_capacity(targets.len());
+    for agent_id in targets {
+        let (Some(pool), Some(memory_search)) =
+            (pools.get(&agent_id), memory_searches.get(&agent_id))
+        else {
+            return Err(StatusCode::NOT_FOUND);
+        };
+        let prompt_snapshots = runtime_configs
+            .get(&agent_id)
+            .and_then(|runtime_config| (*runtime_config.prompt_snapshots.load_full()).clone());
+        let buffers = PromptBuffers {
+            prompt_snapshots: prompt_snapshots.as_deref(),
+            traces: llm_manager.as_ref().map(|manager| manager.traces()),
+            branch_cache: branch_caches.get(&agent_id).map(|cache| cache.as_ref()),
+            response_cache: llm_manager.as_ref().map(|manager| manager.response_cache()),
+            debug_capture: llm_manager.as_ref().map(|manager| manager.debug_capture()),
+        };
+        let mut report =
+            crate::conversation::erasure::erase_sender(pool, memory_search, buffers, &request)
+                .await
+                .map_err(|error| {
+                    tracing::error!(%error, %agent_id, "user data erasure failed");
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+        retain_live_histories(&state, &agent_id, &mut report).await;
+        tracing::info!(
+            %agent_id,
+            platform = ?request.platform,
+            mode = ?request.mode,
+            messages = report.messages,
+            archived_messages = report.archived_messages,
+            memories = report.memories,
+            profiles = report.profiles,
+            outbox = report.outbox,
+            worker_transcripts = report.worker_transcripts,
+            moderation_events = report.moderation_events,
+            prompt_snapshots = report.prompt_snapshots,
+            traces = report.traces,
+            branch_cache = report.branch_cache,
+            response_cache = report.response_cache,
+            debug_captures = report.debug_captures,
+            "erased user data"
+        );
+        agents.push(AgentErasureReport { agent_id, report });
+    }
+
+    let target = match &platform {
+        Some(platform) => format!("{platform}:{sender_id}"),
+        None => sender_id.clone(),
+    };
+    let mode = match query.mode {
+        ErasureMode::Delete => "deleted",
+        ErasureMode::Anonymize => "anonymized",
+    };
+    super::audit::record(
+        &state,
+        &caller,
+        AuditEvent::new("user.erase", target).after(format!(
+            "{mode} in {}",
+            agents
+                .iter()
+                .map(|agent| agent.agent_id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    )
+    .await;
+
+    Ok(Json(EraseUserResponse {
+        sender_id,
+        platform,
+        mode: query.mode,
+        erased_at: chrono::Utc::now().to_rfc3339(),
+        agents,
+    }))
+}
+
+/// Note the user's channels that are running. Their in-memory history was
+/// loaded before erasure and still holds the user's messages.
+async fn retain_live_histories(state: &ApiState, agent_id: &str, report: &mut ErasureReport) {
+    let channel_states = state.channel_states.read().await;
+    for channel_id in &report.channels {
+        if channel_states
+            .get(channel_id)
+            .is_some_and(|channel| &*channel.deps.agent_id == agent_id)
+        {
+            report.retained.push(format!(
+                "{channel_id} is running; its in-memory history still holds this user's \
+                 messages until the channel compacts or restarts"
+            ));
+        }
+    }
+}
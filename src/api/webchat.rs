@@ -1,16 +1,21 @@
-use super::state::ApiState;
-use crate::{InboundMessage, MessageContent};
+use super::ids::AgentId;
+use super::state::{ApiEvent, ApiState};
+use crate::messaging::MessagingManager;
+use crate::{Attachment, InboundMessage, MessageContent};
 
 use axum::Json;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
 use axum::extract::{Query, State};
 use axum::http::StatusCode;
+use axum::response::Response;
+use futures::{SinkExt as _, StreamExt as _};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 
 #[derive(Deserialize)]
 pub(super) struct WebChatSendRequest {
-    agent_id: String,
+    agent_id: AgentId,
     session_id: String,
     #[serde(default = "default_sender_name")]
     sender_name: String,
@@ -39,38 +44,370 @@ pub(super) async fn webchat_send(
         .clone()
         .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
 
-    let conversation_id = request.session_id.clone();
+    inject_webchat_message(
+        &manager,
+        &request.agent_id,
+        &request.session_id,
+        &request.sender_name,
+        MessageContent::Text(request.message),
+    )
+    .await
+    .map_err(|error| {
+        tracing::warn!(%error, "failed to inject webchat message");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(WebChatSendResponse { ok: true }))
+}
 
+async fn inject_webchat_message(
+    manager: &MessagingManager,
+    agent_id: &str,
+    session_id: &str,
+    sender_name: &str,
+    content: MessageContent,
+) -> anyhow::Result<()> {
     let mut metadata = HashMap::new();
     metadata.insert(
         "display_name".into(),
-        serde_json::Value::String(request.sender_name.clone()),
+        serde_json::Value::String(sender_name.to_string()),
     );
 
     let inbound = InboundMessage {
         id: uuid::Uuid::new_v4().to_string(),
         source: "webchat".into(),
         adapter: Some("webchat".into()),
-        conversation_id,
-        sender_id: request.sender_name.clone(),
-        agent_id: Some(request.agent_id.into()),
-        content: MessageContent::Text(request.message),
+        conversation_id: session_id.to_string(),
+        sender_id: sender_name.to_string(),
+        agent_id: Some(agent_id.into()),
+        content,
         timestamp: chrono::Utc::now(),
         metadata,
-        formatted_author: Some(request.sender_name),
+        formatted_author: Some(sender_name.to_string()),
     };
 
-    manager.inject_message(inbound).await.map_err(|error| {
-        tracing::warn!(%error, "failed to inject webchat message");
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    manager.inject_message(inbound).await?;
+    Ok(())
+}
+
+/// Send a message with file attachments. Multipart fields: `agent_id`,
+/// `session_id`, optional `sender_name` and `message`, and one or more files.
+///
+/// Files are passed to the channel inline as `data:` URLs, so they go through
+/// the same attachment pipeline as other adapters — images reach vision
+/// models, text is inlined, and audio is transcribed.
+pub(super) async fn webchat_upload(
+    State(state): State<Arc<ApiState>>,
+    mut multipart: axum::extract::Multipart,
+) -> Result<Json<WebChatSendResponse>, StatusCode> {
+    use base64::Engine as _;
+
+    let manager = state
+        .messaging_manager
+        .read()
+        .await
+        .clone()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+
+    let mut agent_id = None;
+    let mut session_id = None;
+    let mut sender_name = default_sender_name();
+    let mut message = None;
+    let mut attachments = Vec::new();
+
+    while let Some(field) = multipart.next_field().await.map_err(|error| {
+        tracing::warn!(%error, "failed to read webchat upload field");
+        StatusCode::BAD_REQUEST
+    })? {
+        let name = field.name().unwrap_or_default().to_string();
+        let filename = field.file_name().map(str::to_string);
+        let mime_type = field
+            .content_type()
+            .unwrap_or("application/octet-stream")
+            .to_string();
+        let data = field.bytes().await.map_err(|error| {
+            tracing::warn!(%error, "failed to read webchat upload field");
+            StatusCode::BAD_REQUEST
+        })?;
+
+        if let Some(filename) = filename {
+            if data.is_empty() {
+                continue;
+            }
+            let encoded = base64::engine::general_purpose::STANDARD.encode(&data);
+            attachments.push(Attachment {
+                filename,
+                url: format!("data:{mime_type};base64,{encoded}"),
+                mime_type,
+                size_bytes: Some(data.len() as u64),
+                auth_header: None,
+            });
+            continue;
+        }
+
+        let value = String::from_utf8(data.to_vec()).map_err(|_| StatusCode::BAD_REQUEST)?;
+        match name.as_str() {
+            "agent_id" => agent_id = Some(value),
+            "session_id" => session_id = Some(value),
+            "sender_name" if !value.is_empty() => sender_name = value,
+            "message" if !value.trim().is_empty() => message = Some(value),
+            _ => {}
+        }
+    }
+
+    let (Some(agent_id), Some(session_id)) = (agent_id, session_id) else {
+        return Err(StatusCode::BAD_REQUEST);
+    };
+    let agent_id: AgentId = agent_id.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+    let content = match (message, attachments.is_empty()) {
+        (None, true) => return Err(StatusCode::BAD_REQUEST),
+        (Some(message), true) => MessageContent::Text(message),
+        (text, false) => MessageContent::Media { text, attachments },
+    };
+
+    inject_webchat_message(&manager, &agent_id, &session_id, &sender_name, content)
+        .await
+        .map_err(|error| {
+            tracing::warn!(%error, "failed to inject webchat upload");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
 
     Ok(Json(WebChatSendResponse { ok: true }))
 }
 
+#[derive(Deserialize)]
+pub(super) struct WebChatSocketQuery {
+    agent_id: AgentId,
+    session_id: String,
+    #[serde(default = "default_sender_name")]
+    sender_name: String,
+}
+
+/// Frames a client may send over the webchat socket.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WebChatClientFrame {
+    Message {
+        message: String,
+        /// Overrides the sender name given when connecting.
+        #[serde(default)]
+        sender_name: Option<String>,
+    },
+    Ping,
+}
+
+/// Control frames sent alongside forwarded `ApiEvent`s.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WebChatServerFrame {
+    Pong,
+    Lagged { skipped: u64 },
+    Error { message: String },
+}
+
+/// Persistent webchat session over a WebSocket.
+///
+/// Inbound `message` frames are injected like `POST /webchat/send`. Every
+/// `ApiEvent` for this session's conversation (messages, deltas, typing,
+/// worker and branch activity) is pushed to the client as it happens, using
+/// the same JSON shape as the SSE stream — including agent-initiated messages
+/// that no request triggered.
+pub(super) async fn webchat_ws(
+    State(state): State<Arc<ApiState>>,
+    Query(query): Query<WebChatSocketQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, StatusCode> {
+    let manager = state
+        .messaging_manager
+        .read()
+        .await
+        .clone()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+
+    Ok(upgrade.on_upgrade(move |socket| run_webchat_socket(state, manager, query, socket)))
+}
+
+async fn run_webchat_socket(
+    state: Arc<ApiState>,
+    manager: Arc<MessagingManager>,
+    query: WebChatSocketQuery,
+    socket: WebSocket,
+) {
+    let (mut sender, mut receiver) = socket.split();
+    let mut events = state.event_tx.subscribe();
+
+    tracing::debug!(
+        agent_id = %query.agent_id,
+        session_id = %query.session_id,
+        "webchat socket connected"
+    );
+
+    loop {
+        let outgoing = tokio::select! {
+            event = events.recv() => match crate::classify_broadcast_recv_result(event) {
+                crate::BroadcastRecvResult::Event(event) => {
+                    if !is_session_event(&event, &query.agent_id, &query.session_id) {
+                        continue;
+                    }
+                    serde_json::to_string(&event)
+                }
+                crate::BroadcastRecvResult::Lagged(skipped) => {
+                    serde_json::to_string(&WebChatServerFrame::Lagged { skipped })
+                }
+                crate::BroadcastRecvResult::Closed => break,
+            },
+            frame = receiver.next() => {
+                let text = match frame {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => continue,
+                    Some(Err(error)) => {
+                        tracing::debug!(%error, "webchat socket receive failed");
+                        break;
+                    }
+                };
+                match handle_client_frame(&manager, &query, text.as_str()).await {
+                    Some(reply) => serde_json::to_string(&reply),
+                    None => continue,
+                }
+            }
+        };
+
+        let Ok(json) = outgoing else {
+            continue;
+        };
+        if sender.send(Message::Text(json.into())).await.is_err() {
+            break;
+        }
+    }
+
+    tracing::debug!(
+        agent_id = %query.agent_id,
+        session_id = %query.session_id,
+        "webchat socket closed"
+    );
+}
+
+/// Act on a client frame, returning a reply frame if one is due.
+async fn handle_client_frame(
+    manager: &MessagingManager,
+    query: &WebChatSocketQuery,
+    text: &str,
+) -> Option<WebChatServerFrame> {
+    let frame = match serde_json::from_str::<WebChatClientFrame>(text) {
+        Ok(frame) => frame,
+        Err(error) => {
+            return Some(WebChatServerFrame::Error {
+                message: format!("invalid frame: {error}"),
+            });
+        }
+    };
+
+    match frame {
+        WebChatClientFrame::Ping => Some(WebChatServerFrame::Pong),
+        WebChatClientFrame::Message {
+            message,
+            sender_name,
+        } => {
+            let sender_name = sender_name.as_deref().unwrap_or(&query.sender_name);
+            match inject_webchat_message(
+                manager,
+                &query.agent_id,
+                &query.session_id,
+                sender_name,
+                MessageContent::Text(message),
+            )
+            .await
+            {
+                Ok(()) => None,
+                Err(error) => {
+                    tracing::warn!(%error, "failed to inject webchat message");
+                    Some(WebChatServerFrame::Error {
+                        message: "failed to deliver message".into(),
+                    })
+                }
+            }
+        }
+    }
+}
+
+/// Whether an event belongs to the given webchat conversation.
+fn is_session_event(event: &ApiEvent, agent_id: &str, session_id: &str) -> bool {
+    let (event_agent_id, channel_id) = match event {
+        ApiEvent::InboundMessage {
+            agent_id,
+            channel_id,
+            ..
+        }
+        | ApiEvent::OutboundMessage {
+            agent_id,
+            channel_id,
+            ..
+        }
+        | ApiEvent::OutboundMessageDelta {
+            agent_id,
+            channel_id,
+            ..
+        }
+        | ApiEvent::TypingState {
+            agent_id,
+            channel_id,
+            ..
+        }
+        | ApiEvent::TurnInterrupted {
+            agent_id,
+            channel_id,
+            ..
+        }
+        | ApiEvent::BranchStarted {
+            agent_id,
+            channel_id,
+            ..
+        }
+        | ApiEvent::BranchCompleted {
+            agent_id,
+            channel_id,
+            ..
+        } => (agent_id, Some(channel_id)),
+        ApiEvent::WorkerStarted {
+            agent_id,
+            channel_id,
+            ..
+        }
+        | ApiEvent::WorkerStatusUpdate {
+            agent_id,
+            channel_id,
+            ..
+        }
+        | ApiEvent::WorkerIdle {
+            agent_id,
+            channel_id,
+            ..
+        }
+        | ApiEvent::WorkerCompleted {
+            agent_id,
+            channel_id,
+            ..
+        }
+        | ApiEvent::ToolStarted {
+            agent_id,
+            channel_id,
+            ..
+        }
+        | ApiEvent::ToolCompleted {
+            agent_id,
+            channel_id,
+            ..
+        } => (agent_id, channel_id.as_ref()),
+        _ => return false,
+    };
+
+    event_agent_id == agent_id && channel_id.is_some_and(|channel_id| channel_id == session_id)
+}
+
 #[derive(Deserialize)]
 pub(super) struct WebChatHistoryQuery {
-    agent_id: String,
+    agent_id: AgentId,
     session_id: String,
     #[serde(default = "default_limit")]
     limit: i64,
@@ -92,7 +429,9 @@ pub(super) async fn webchat_history(
     Query(query): Query<WebChatHistoryQuery>,
 ) -> Result<Json<Vec<WebChatHistoryMessage>>, StatusCode> {
     let pools = state.agent_pools.load();
-    let pool = pools.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let pool = pools
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
     let logger = crate::conversation::ConversationLogger::new(pool.clone());
 
     let channel_id: crate::ChannelId = Arc::from(query.session_id.as_str());
@@ -116,3 +455,42 @@ pub(super) async fn webchat_history(
 
     Ok(Json(result))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_events_to_the_session() {
+        let typing = |agent_id: &str, channel_id: &str| ApiEvent::TypingState {
+            agent_id: agent_id.into(),
+            channel_id: channel_id.into(),
+            is_typing: true,
+        };
+        assert!(is_session_event(&typing("main", "s1"), "main", "s1"));
+        assert!(!is_session_event(&typing("main", "s2"), "main", "s1"));
+        assert!(!is_session_event(&typing("other", "s1"), "main", "s1"));
+
+        let worker = ApiEvent::WorkerIdle {
+            agent_id: "main".into(),
+            channel_id: None,
+            worker_id: "w1".into(),
+        };
+        assert!(!is_session_event(&worker, "main", "s1"));
+        assert!(!is_session_event(&ApiEvent::ConfigReloaded, "main", "s1"));
+    }
+
+    #[test]
+    fn parses_client_frames() {
+        let frame: WebChatClientFrame =
+            serde_json::from_str(r#"{"type":"message","message":"hi"}"#).unwrap();
+        assert!(matches!(
+            frame,
+            WebChatClientFrame::Message { ref message, sender_name: None } if message == "hi"
+        ));
+        assert!(matches!(
+            serde_json::from_str::<WebChatClientFrame>(r#"{"type":"ping"}"#).unwrap(),
+            WebChatClientFrame::Ping
+        ));
+    }
+}
@@ -1,5 +1,6 @@
 //! Workers API endpoints: list and detail views for worker runs.
 
+use super::ids::{AgentId, WorkerId};
 use super::state::ApiState;
 
 use crate::conversation::history::ProcessRunLogger;
@@ -13,7 +14,7 @@ use std::sync::Arc;
 
 #[derive(Deserialize)]
 pub(super) struct WorkerListQuery {
-    agent_id: String,
+    agent_id: AgentId,
     #[serde(default = "default_limit")]
     limit: i64,
     #[serde(default)]
@@ -54,8 +55,8 @@ pub(super) struct WorkerListItem {
 
 #[derive(Deserialize)]
 pub(super) struct WorkerDetailQuery {
-    agent_id: String,
-    worker_id: String,
+    agent_id: AgentId,
+    worker_id: WorkerId,
 }
 
 #[derive(Serialize)]
@@ -87,7 +88,9 @@ pub(super) async fn list_workers(
     Query(query): Query<WorkerListQuery>,
 ) -> Result<Json<WorkerListResponse>, StatusCode> {
     let pools = state.agent_pools.load();
-    let pool = pools.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let pool = pools
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
     let logger = ProcessRunLogger::new(pool.clone());
 
     let limit = query.limit.clamp(1, 200);
@@ -158,11 +161,14 @@ pub(super) async fn worker_detail(
     Query(query): Query<WorkerDetailQuery>,
 ) -> Result<Json<WorkerDetailResponse>, StatusCode> {
     let pools = state.agent_pools.load();
-    let pool = pools.get(&query.agent_id).ok_or(StatusCode::NOT_FOUND)?;
+    let pool = pools
+        .get(query.agent_id.as_str())
+        .ok_or(StatusCode::NOT_FOUND)?;
     let logger = ProcessRunLogger::new(pool.clone());
 
+    let worker_id = query.worker_id.to_string();
     let detail = logger
-        .get_worker_detail(&query.agent_id, &query.worker_id)
+        .get_worker_detail(&query.agent_id, &worker_id)
         .await
         .map_err(|error| {
             tracing::warn!(%error, worker_id = %query.worker_id, "failed to load worker detail");
@@ -179,7 +185,7 @@ pub(super) async fn worker_detail(
         None => {
             // No persisted transcript yet — check the live transcript cache
             // so page refreshes can recover in-progress worker transcripts.
-            state.get_live_transcript(&query.worker_id).await
+            state.get_live_transcript(&worker_id).await
         }
     };
 
@@ -0,0 +1,331 @@
+//! Append-only audit log of administrative actions.
+//!
+//! Entries live in the instance access database next to the API users, so
+//! every entry names the key or user that made the change. Triggers reject
+//! UPDATE and DELETE on the table; the log can only grow.
+//!
+//! Summaries describe a change without repeating secrets: a provider key is
+//! recorded as set or removed, never by value.
+
+use crate::error::Result;
+
+use anyhow::Context as _;
+use serde::Serialize;
+use sqlx::{QueryBuilder, Row as _, SqlitePool};
+
+/// Default and maximum page size for queries.
+const DEFAULT_QUERY_LIMIT: i64 = 100;
+const MAX_QUERY_LIMIT: i64 = 1000;
+
+/// An action to record, before the actor is attached.
+#[derive(Debug, Clone)]
+pub struct AuditEvent {
+    /// Dotted action name, e.g. `provider.update` or `channel.delete`.
+    pub action: String,
+    /// What was acted on: a provider, channel ID, prompt name, and so on.
+    pub target: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+impl AuditEvent {
+    pub fn new(action: impl Into<String>, target: impl Into<String>) -> Self {
+        Self {
+            action: action.into(),
+            target: target.into(),
+            before: None,
+            after: None,
+        }
+    }
+
+    pub fn before(mut self, summary: impl Into<String>) -> Self {
+        self.before = Some(summary.into());
+        self
+    }
+
+    pub fn after(mut self, summary: impl Into<String>) -> Self {
+        self.after = Some(summary.into());
+        self
+    }
+}
+
+/// Who performed an action.
+#[derive(Debug, Clone)]
+pub struct AuditActor {
+    pub name: String,
+    /// How the actor authenticated: `key`, `user`, or `anonymous`.
+    pub kind: String,
+    pub role: String,
+}
+
+/// A recorded entry.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub created_at: String,
+    pub actor: String,
+    pub actor_kind: String,
+    pub actor_role: String,
+    pub action: String,
+    pub target: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// Filters for reading the log. Results are newest first.
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    pub actor: Option<String>,
+    /// Matches the action exactly, or every action under a prefix ending
+    /// in `.` (`provider.` matches `provider.update` and `provider.delete`).
+    pub action: Option<String>,
+    pub target: Option<String>,
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+    pub until: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only entries older than this ID, for paging.
+    pub before_id: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// The audit log table.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    pool: SqlitePool,
+}
+
+impl AuditLog {
+    /// Uses the access database; see [`crate::access::connect`].
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn record(&self, actor: &AuditActor, event: AuditEvent) -> Result<i64> {
+        let result = sqlx::query(
+            "INSERT INTO audit_log \
+             (created_at, actor, actor_kind, actor_role, action, target, before_summary, after_summary) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(format_timestamp(chrono::Utc::now()))
+        .bind(&actor.name)
+        .bind(&actor.kind)
+        .bind(&actor.role)
+        .bind(&event.action)
+        .bind(&event.target)
+        .bind(&event.before)
+        .bind(&event.after)
+        .execute(&self.pool)
+        .await
+        .context("failed to write audit log entry")?;
+        Ok(result.last_insert_rowid())
+    }
+
+    pub async fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+        let mut builder = QueryBuilder::new(
+            "SELECT id, created_at, actor, actor_kind, actor_role, action, target, \
+             before_summary, after_summary FROM audit_log WHERE 1 = 1",
+        );
+        if let Some(actor) = &query.actor {
+            builder.push(" AND actor = ").push_bind(actor);
+        }
+        match query.action.as_deref() {
+            Some(prefix) if prefix.ends_with('.') => {
+                builder
+                    .push(" AND substr(action, 1, length(")
+                    .push_bind(prefix)
+                    .push(")) = ")
+                    .push_bind(prefix);
+            }
+            Some(action) => {
+                builder.push(" AND action = ").push_bind(action);
+            }
+            None => {}
+        }
+        if let Some(target) = &query.target {
+            builder.push(" AND target = ").push_bind(target);
+        }
+        if let Some(since) = query.since {
+            builder
+                .push(" AND created_at >= ")
+                .push_bind(format_timestamp(since));
+        }
+        if let Some(until) = query.until {
+            builder
+                .push(" AND created_at < ")
+                .push_bind(format_timestamp(until));
+        }
+        if let Some(before_id) = query.before_id {
+            builder.push(" AND id < ").push_bind(before_id);
+        }
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_QUERY_LIMIT)
+            .clamp(1, MAX_QUERY_LIMIT);
+        builder.push(" ORDER BY id DESC LIMIT ").push_bind(limit);
+
+        let rows = builder
+            .build()
+            .fetch_all(&self.pool)
+            .await
+            .context("failed to query audit log")?;
+        rows.into_iter()
+            .map(|row| {
+                Ok(AuditEntry {
+                    id: row.try_get("id")?,
+                    created_at: row.try_get("created_at")?,
+                    actor: row.try_get("actor")?,
+                    actor_kind: row.try_get("actor_kind")?,
+                    actor_role: row.try_get("actor_role")?,
+                    action: row.try_get("action")?,
+                    target: row.try_get("target")?,
+                    before: row.try_get("before_summary")?,
+                    after: row.try_get("after_summary")?,
+                })
+            })
+            .collect::<std::result::Result<_, sqlx::Error>>()
+            .context("failed to read audit log entry")
+            .map_err(Into::into)
+    }
+}
+
+/// Fixed-width UTC timestamps, so they sort and compare as text.
+fn format_timestamp(timestamp: chrono::DateTime<chrono::Utc>) -> String {
+    timestamp.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+/// Summarize a text document without its content: size, plus the lines
+/// added and removed relative to `previous` when given.
+pub fn summarize_text(text: &str, previous: Option<&str>) -> String {
+    let lines = text.lines().count();
+    let mut summary = format!("{lines} lines, {} bytes", text.len());
+    if let Some(previous) = previous {
+        let old: std::collections::HashSet<&str> = previous.lines().collect();
+        let new: std::collections::HashSet<&str> = text.lines().collect();
+        let added = text.lines().filter(|line| !old.contains(line)).count();
+        let removed = previous.lines().filter(|line| !new.contains(line)).count();
+        summary.push_str(&format!(" (+{added} -{removed})"));
+    }
+    summary
+}
+
+/// Top-level tables whose contents differ between two TOML documents.
+/// Returns None when either side doesn't parse.
+pub fn changed_toml_sections(before: &str, after: &str) -> Option<Vec<String>> {
+    let before: toml::Table = before.parse().ok()?;
+    let after: toml::Table = after.parse().ok()?;
+    let mut sections: Vec<String> = before
+        .keys()
+        .chain(after.keys())
+        .filter(|key| before.get(*key) != after.get(*key))
+        .cloned()
+        .collect();
+    sections.sort();
+    sections.dedup();
+    Some(sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn log() -> AuditLog {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        crate::access::MIGRATOR.run(&pool).await.unwrap();
+        AuditLog::new(pool)
+    }
+
+    fn actor(name: &str) -> AuditActor {
+        AuditActor {
+            name: name.to_string(),
+            kind: "key".to_string(),
+            role: "admin".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn entries_are_append_only_and_filterable() {
+        let log = log().await;
+        log.record(
+            &actor("alice"),
+            AuditEvent::new("provider.update", "openai")
+                .before("no key")
+                .after("key set"),
+        )
+        .await
+        .unwrap();
+        log.record(&actor("bob"), AuditEvent::new("provider.delete", "openai"))
+            .await
+            .unwrap();
+        log.record(
+            &actor("alice"),
+            AuditEvent::new("channel.delete", "discord:1"),
+        )
+        .await
+        .unwrap();
+
+        let all = log.query(&AuditQuery::default()).await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].action, "channel.delete", "newest first");
+
+        let providers = log
+            .query(&AuditQuery {
+                action: Some("provider.".to_string()),
+                ..AuditQuery::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(providers.len(), 2);
+
+        let alice = log
+            .query(&AuditQuery {
+                actor: Some("alice".to_string()),
+                action: Some("provider.update".to_string()),
+                ..AuditQuery::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(alice.len(), 1);
+        assert_eq!(alice[0].before.as_deref(), Some("no key"));
+        assert_eq!(alice[0].after.as_deref(), Some("key set"));
+
+        let page = log
+            .query(&AuditQuery {
+                before_id: Some(all[0].id),
+                limit: Some(1),
+                ..AuditQuery::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].id, all[1].id);
+
+        assert!(
+            sqlx::query("UPDATE audit_log SET actor = 'mallory'")
+                .execute(&log.pool)
+                .await
+                .is_err()
+        );
+        assert!(
+            sqlx::query("DELETE FROM audit_log")
+                .execute(&log.pool)
+                .await
+                .is_err()
+        );
+        assert_eq!(log.query(&AuditQuery::default()).await.unwrap().len(), 3);
+    }
+
+    #[test]
+    fn summaries_omit_content() {
+        assert_eq!(
+            summarize_text("a\nb\nc", Some("a\nx")),
+            "3 lines, 5 bytes (+2 -1)"
+        );
+        assert_eq!(
+            changed_toml_sections(
+                "[llm]\nx = 1\n[api]\nport = 1\n",
+                "[llm]\nx = 2\n[api]\nport = 1\n"
+            ),
+            Some(vec!["llm".to_string()])
+        );
+        assert!(changed_toml_sections("[llm", "").is_none());
+    }
+}
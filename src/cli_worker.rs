@@ -0,0 +1,66 @@
+//! Worker backends that drive an external CLI binary: OpenCode workers run
+//! the `opencode` server, container workers the Docker/Podman runtime.
+//!
+//! A missing or broken binary only shows up when a worker is spawned, so
+//! [`detect`] probes each enabled backend ahead of time and `spawn_worker`
+//! leaves unavailable ones out of its tool description.
+
+pub mod detect;
+
+pub use detect::{BackendStatus, CliWorkerHealth};
+
+use crate::config::RuntimeConfig;
+
+use serde::Serialize;
+
+/// An external worker backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CliWorkerBackend {
+    Opencode,
+    Container,
+}
+
+impl CliWorkerBackend {
+    pub const ALL: [Self; 2] = [Self::Opencode, Self::Container];
+
+    /// The `worker_type` value that selects this backend in `spawn_worker`.
+    pub fn worker_type(self) -> &'static str {
+        match self {
+            Self::Opencode => "opencode",
+            Self::Container => "container",
+        }
+    }
+
+    /// The configured binary, or `None` when the backend is disabled.
+    pub fn configured_binary(self, runtime_config: &RuntimeConfig) -> Option<String> {
+        match self {
+            Self::Opencode => {
+                let config = runtime_config.opencode.load();
+                config.enabled.then(|| config.path.clone())
+            }
+            Self::Container => {
+                let config = runtime_config.container.load();
+                config.enabled.then(|| config.runtime.clone())
+            }
+        }
+    }
+
+    /// Whether the backend is enabled and its binary wasn't found broken by
+    /// the last probe.
+    pub fn is_usable(self, runtime_config: &RuntimeConfig) -> bool {
+        self.configured_binary(runtime_config)
+            .is_some_and(|binary| runtime_config.cli_worker_health.is_available(self, &binary))
+    }
+
+    /// Arguments for a cheap invocation that succeeds only when the binary
+    /// is installed and runnable.
+    fn probe_args(self) -> &'static [&'static str] {
+        match self {
+            Self::Opencode => &["--version"],
+            // `version` (unlike `--version`) also reaches the daemon, so a
+            // stopped Docker daemon counts as unavailable.
+            Self::Container => &["version"],
+        }
+    }
+}
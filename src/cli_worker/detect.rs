@@ -0,0 +1,185 @@
+//! Health checks for CLI worker backends.
+//!
+//! Each enabled backend is probed once at startup and again whenever
+//! `GET /api/cli-workers/status` is called. Results are cached per agent in
+//! [`CliWorkerHealth`].
+
+use crate::cli_worker::CliWorkerBackend;
+use crate::config::RuntimeConfig;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::process::Command;
+
+/// How long a probe may run before the backend is marked unavailable.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest error or version string kept from a probe's output.
+const MAX_PROBE_OUTPUT_CHARS: usize = 300;
+
+/// Result of probing one backend.
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendStatus {
+    pub backend: CliWorkerBackend,
+    pub binary: String,
+    pub available: bool,
+    /// First line of the probe's stdout, when it succeeded.
+    pub version: Option<String>,
+    pub error: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Run `binary` with the backend's probe arguments.
+pub async fn probe(backend: CliWorkerBackend, binary: &str) -> BackendStatus {
+    let output = tokio::time::timeout(
+        PROBE_TIMEOUT,
+        Command::new(binary)
+            .args(backend.probe_args())
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await;
+
+    let (available, version, error) = match output {
+        Err(_) => (
+            false,
+            None,
+            Some(format!("timed out after {}s", PROBE_TIMEOUT.as_secs())),
+        ),
+        Ok(Err(error)) => (false, None, Some(format!("failed to run: {error}"))),
+        Ok(Ok(output)) if output.status.success() => {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let version = stdout
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .map(truncate);
+            (true, version, None)
+        }
+        Ok(Ok(output)) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let detail = stderr.trim();
+            let error = if detail.is_empty() {
+                format!("exited with {}", output.status)
+            } else {
+                format!("exited with {}: {}", output.status, truncate(detail))
+            };
+            (false, None, Some(error))
+        }
+    };
+
+    BackendStatus {
+        backend,
+        binary: binary.to_string(),
+        available,
+        version,
+        error,
+        checked_at: Utc::now(),
+    }
+}
+
+fn truncate(text: &str) -> String {
+    text.chars().take(MAX_PROBE_OUTPUT_CHARS).collect()
+}
+
+/// Latest probe results for one agent's enabled backends.
+#[derive(Debug, Default)]
+pub struct CliWorkerHealth {
+    statuses: RwLock<HashMap<CliWorkerBackend, BackendStatus>>,
+}
+
+impl CliWorkerHealth {
+    /// Probe every enabled backend and replace the cached results. Disabled
+    /// backends are dropped from the cache.
+    pub async fn refresh(&self, runtime_config: &RuntimeConfig) -> Vec<BackendStatus> {
+        let mut statuses = Vec::new();
+        for backend in CliWorkerBackend::ALL {
+            if let Some(binary) = backend.configured_binary(runtime_config) {
+                let status = probe(backend, &binary).await;
+                if !status.available {
+                    tracing::warn!(
+                        backend = backend.worker_type(),
+                        %binary,
+                        error = status.error.as_deref().unwrap_or_default(),
+                        "CLI worker backend unavailable"
+                    );
+                }
+                statuses.push(status);
+            }
+        }
+
+        *self
+            .statuses
+            .write()
+            .expect("cli worker health lock poisoned") = statuses
+            .iter()
+            .map(|status| (status.backend, status.clone()))
+            .collect();
+        statuses
+    }
+
+    /// The cached result for `backend`, if it has been probed.
+    pub fn status(&self, backend: CliWorkerBackend) -> Option<BackendStatus> {
+        self.statuses
+            .read()
+            .expect("cli worker health lock poisoned")
+            .get(&backend)
+            .cloned()
+    }
+
+    /// False only when the last probe of `binary` failed. A backend that
+    /// hasn't been probed yet, or whose binary changed since, counts as
+    /// available so a slow or stale probe never hides it.
+    pub fn is_available(&self, backend: CliWorkerBackend, binary: &str) -> bool {
+        self.status(backend)
+            .is_none_or(|status| status.binary != binary || status.available)
+    }
+}
+
+/// Probe an agent's backends in the background at startup.
+pub fn spawn_startup_probe(runtime_config: Arc<RuntimeConfig>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let statuses = runtime_config
+            .cli_worker_health
+            .refresh(&runtime_config)
+            .await;
+        tracing::debug!(backends = statuses.len(), "CLI worker backends probed");
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn missing_binary_is_unavailable() {
+        let status = probe(CliWorkerBackend::Opencode, "spacebot-test-no-such-binary").await;
+        assert!(!status.available);
+        assert!(status.error.unwrap().starts_with("failed to run"));
+    }
+
+    #[test]
+    fn unprobed_or_changed_binaries_count_as_available() {
+        let health = CliWorkerHealth::default();
+        assert!(health.is_available(CliWorkerBackend::Container, "docker"));
+
+        health.statuses.write().unwrap().insert(
+            CliWorkerBackend::Container,
+            BackendStatus {
+                backend: CliWorkerBackend::Container,
+                binary: "docker".into(),
+                available: false,
+                version: None,
+                error: Some("exited with 1".into()),
+                checked_at: Utc::now(),
+            },
+        );
+        assert!(!health.is_available(CliWorkerBackend::Container, "docker"));
+        assert!(health.is_available(CliWorkerBackend::Container, "podman"));
+    }
+}
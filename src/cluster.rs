@@ -0,0 +1,950 @@
+//! Multi-node clustering over a shared coordination database.
+//!
+//! With `[cluster]` enabled, several Spacebot processes running the same
+//! config share their work through one SQLite database every node can reach:
+//!
+//! - Each node records a heartbeat with a snapshot of its load. A node that
+//!   misses heartbeats for longer than the lease is treated as gone. The live
+//!   node with the lowest ID is the leader, and only the leader fires cron
+//!   jobs.
+//! - Inbound messages pass through a shared inbox. Each conversation is
+//!   leased to one node, which handles every message in it. A message another
+//!   node receives waits in the inbox until the lease holder claims it, and a
+//!   copy of a message that's already recorded is dropped, so each message is
+//!   handled once however many nodes receive it.
+//! - Ready tasks can be handed to the least busy node as cluster jobs. The
+//!   task stays on its own node's board, which applies the result.
+//!
+//! Agent databases stay per node. A conversation keeps its history on the
+//! node holding its lease and only moves when that node goes away.
+
+use crate::InboundMessage;
+use crate::api::ApiState;
+use crate::config::ClusterConfig;
+use crate::error::Result;
+use crate::messaging::MessagingManager;
+use crate::supervisor::{ProcessKind, ProcessSpec, Supervisor};
+
+use anyhow::Context as _;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection};
+use sqlx::{Row as _, SqlitePool};
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+/// Migrations for the shared coordination database.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations/cluster");
+
+/// This process's cluster membership. Unset when clustering is disabled.
+static CLUSTER: OnceLock<Arc<Cluster>> = OnceLock::new();
+
+/// Sources whose replies go back over a connection to the node that received
+/// the message, or that a node generates for itself. Their messages are
+/// always handled where they arrive.
+const NODE_LOCAL_SOURCES: &[&str] = &["webchat", "webhook", "cron", "system"];
+
+/// Metadata flag on a message this node claimed from the inbox, so the router
+/// doesn't record it again when it comes back through the inbound stream.
+const INBOX_CLAIM_KEY: &str = "cluster_inbox_claim";
+
+/// Handled inbox entries are kept this long to catch late duplicates.
+const INBOX_RETENTION_SECS: i64 = 24 * 60 * 60;
+
+/// Unclaimed messages older than this are dropped: no node took their
+/// conversation in time for a reply to still be useful.
+const UNCLAIMED_MAX_AGE_SECS: i64 = 60 * 60;
+
+/// How often the inbox is checked for messages waiting on this node.
+const INBOX_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Most inbox messages claimed in one poll.
+const INBOX_CLAIM_BATCH: i64 = 32;
+
+/// Join the cluster for the rest of the process.
+pub fn install(cluster: Arc<Cluster>) {
+    if CLUSTER.set(cluster).is_err() {
+        tracing::warn!("cluster membership already installed");
+    }
+}
+
+/// This process's cluster membership, when clustering is enabled.
+pub fn global() -> Option<&'static Arc<Cluster>> {
+    CLUSTER.get()
+}
+
+/// A node's load as of its last heartbeat.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeLoad {
+    pub agents: usize,
+    pub active_channels: usize,
+    /// Channel workers plus task workers, including cluster jobs.
+    pub active_workers: usize,
+    pub active_branches: usize,
+}
+
+/// A node as the coordination database sees it.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterNode {
+    pub node_id: String,
+    pub advertise_url: Option<String>,
+    pub version: String,
+    pub started_at: String,
+    pub last_seen: String,
+    /// Whether the node's last heartbeat is within the lease.
+    pub alive: bool,
+    pub leader: bool,
+    pub load: NodeLoad,
+    /// Conversations leased to the node.
+    pub conversations: i64,
+    /// Cluster jobs handed to the node that it hasn't started yet.
+    pub queued_jobs: i64,
+}
+
+/// The whole cluster, for the dashboard.
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterStatus {
+    /// The node answering.
+    pub node_id: String,
+    pub nodes: Vec<ClusterNode>,
+    /// Messages waiting in the inbox for their conversation's node.
+    pub waiting_messages: i64,
+    pub queued_jobs: i64,
+    pub running_jobs: i64,
+}
+
+/// What the router should do with an inbound message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delivery {
+    /// Handle it on this node.
+    Here,
+    /// Another node holds the conversation. The message waits in the inbox
+    /// for that node.
+    Waiting,
+    /// Already recorded by this or another node.
+    Duplicate,
+}
+
+/// A ready task handed to another node to run.
+#[derive(Debug, Clone)]
+pub struct ClusterJob {
+    pub id: i64,
+    pub agent_id: String,
+    pub task_number: i64,
+    pub title: String,
+    pub prompt: String,
+    pub origin_node: String,
+}
+
+/// A cluster job that finished, returned to the node it came from.
+#[derive(Debug, Clone)]
+pub struct FinishedJob {
+    pub id: i64,
+    pub task_number: i64,
+    pub success: bool,
+    pub result: String,
+    pub worker_node: String,
+}
+
+/// This node's membership in the cluster.
+#[derive(Debug)]
+pub struct Cluster {
+    pool: SqlitePool,
+    node_id: String,
+    advertise_url: Option<String>,
+    lease: TimeDelta,
+    started_at: String,
+    distribute_tasks: bool,
+    leader: AtomicBool,
+    /// Task workers running here, which channel status blocks don't cover.
+    task_workers: AtomicUsize,
+}
+
+impl Cluster {
+    /// Open (creating if needed) the coordination database and run its
+    /// migrations.
+    pub async fn connect(config: &ClusterConfig) -> Result<Self> {
+        if let Some(parent) = config.database.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!("failed to create cluster directory: {}", parent.display())
+            })?;
+        }
+        let options = SqliteConnectOptions::new()
+            .filename(&config.database)
+            .create_if_missing(true)
+            .busy_timeout(Duration::from_secs(10));
+        let pool = SqlitePool::connect_with(options).await.with_context(|| {
+            format!(
+                "failed to open cluster database: {}",
+                config.database.display()
+            )
+        })?;
+        MIGRATOR
+            .run(&pool)
+            .await
+            .context("failed to run cluster database migrations")?;
+        Ok(Self::new(pool, config))
+    }
+
+    pub fn new(pool: SqlitePool, config: &ClusterConfig) -> Self {
+        Self {
+            pool,
+            node_id: config.node_id.clone(),
+            advertise_url: config.advertise_url.clone(),
+            lease: TimeDelta::seconds(config.lease_secs as i64),
+            started_at: format_timestamp(Utc::now()),
+            distribute_tasks: config.distribute_tasks,
+            leader: AtomicBool::new(false),
+            task_workers: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// Whether this node was the leader as of its last heartbeat.
+    pub fn is_leader(&self) -> bool {
+        self.leader.load(Ordering::Acquire)
+    }
+
+    /// Count a task worker toward this node's load until the slot drops.
+    pub fn worker_slot(self: &Arc<Self>) -> WorkerSlot {
+        self.task_workers.fetch_add(1, Ordering::AcqRel);
+        WorkerSlot {
+            cluster: self.clone(),
+        }
+    }
+
+    /// Record that this node is alive, renew the leases it holds, and
+    /// prune old inbox entries.
+    pub async fn heartbeat(&self, load: &NodeLoad) -> Result<()> {
+        let now = Utc::now();
+        let now_text = format_timestamp(now);
+        let expires_at = format_timestamp(now + self.lease);
+        let status = serde_json::to_string(load).context("failed to serialize node load")?;
+
+        sqlx::query(
+            "INSERT INTO cluster_nodes (node_id, advertise_url, version, started_at, last_seen, status) \
+             VALUES (?, ?, ?, ?, ?, ?) \
+             ON CONFLICT(node_id) DO UPDATE SET advertise_url = excluded.advertise_url, \
+             version = excluded.version, started_at = excluded.started_at, \
+             last_seen = excluded.last_seen, status = excluded.status",
+        )
+        .bind(&self.node_id)
+        .bind(&self.advertise_url)
+        .bind(env!("CARGO_PKG_VERSION"))
+        .bind(&self.started_at)
+        .bind(&now_text)
+        .bind(status)
+        .execute(&self.pool)
+        .await
+        .context("failed to record cluster heartbeat")?;
+
+        sqlx::query("UPDATE conversation_leases SET expires_at = ? WHERE node_id = ?")
+            .bind(&expires_at)
+            .bind(&self.node_id)
+            .execute(&self.pool)
+            .await
+            .context("failed to renew conversation leases")?;
+        sqlx::query(
+            "UPDATE cluster_jobs SET lease_expires_at = ? WHERE worker_node = ? AND status = 'running'",
+        )
+        .bind(&expires_at)
+        .bind(&self.node_id)
+        .execute(&self.pool)
+        .await
+        .context("failed to renew cluster job leases")?;
+
+        let leader: Option<String> =
+            sqlx::query_scalar("SELECT MIN(node_id) FROM cluster_nodes WHERE last_seen >= ?")
+                .bind(format_timestamp(now - self.lease))
+                .fetch_one(&self.pool)
+                .await
+                .context("failed to find cluster leader")?;
+        let leader = leader.as_deref() == Some(self.node_id.as_str());
+        if self.leader.swap(leader, Ordering::AcqRel) != leader {
+            tracing::info!(node_id = %self.node_id, leader, "cluster leadership changed");
+        }
+
+        let retention_cutoff = format_timestamp(now - TimeDelta::seconds(INBOX_RETENTION_SECS));
+        let dropped = sqlx::query("DELETE FROM inbox WHERE claimed_by IS NULL AND received_at < ?")
+            .bind(format_timestamp(
+                now - TimeDelta::seconds(UNCLAIMED_MAX_AGE_SECS),
+            ))
+            .execute(&self.pool)
+            .await
+            .context("failed to prune cluster inbox")?
+            .rows_affected();
+        if dropped > 0 {
+            tracing::warn!(dropped, "dropped inbox messages no node claimed in time");
+        }
+        sqlx::query("DELETE FROM inbox WHERE claimed_by IS NOT NULL AND received_at < ?")
+            .bind(&retention_cutoff)
+            .execute(&self.pool)
+            .await
+            .context("failed to prune cluster inbox")?;
+        sqlx::query("DELETE FROM conversation_leases WHERE expires_at < ?")
+            .bind(&retention_cutoff)
+            .execute(&self.pool)
+            .await
+            .context("failed to prune conversation leases")?;
+        sqlx::query("DELETE FROM cluster_nodes WHERE last_seen < ?")
+            .bind(&retention_cutoff)
+            .execute(&self.pool)
+            .await
+            .context("failed to prune cluster nodes")?;
+        Ok(())
+    }
+
+    /// Leave the cluster on shutdown: release this node's conversations so
+    /// other nodes take them over at once, and requeue its running jobs.
+    pub async fn leave(&self) -> Result<()> {
+        sqlx::query("DELETE FROM conversation_leases WHERE node_id = ?")
+            .bind(&self.node_id)
+            .execute(&self.pool)
+            .await
+            .context("failed to release conversation leases")?;
+        sqlx::query(
+            "UPDATE cluster_jobs SET status = 'queued', worker_node = NULL, \
+             lease_expires_at = NULL, updated_at = ? \
+             WHERE worker_node = ? AND status = 'running'",
+        )
+        .bind(format_timestamp(Utc::now()))
+        .bind(&self.node_id)
+        .execute(&self.pool)
+        .await
+        .context("failed to requeue cluster jobs")?;
+        sqlx::query("DELETE FROM cluster_nodes WHERE node_id = ?")
+            .bind(&self.node_id)
+            .execute(&self.pool)
+            .await
+            .context("failed to remove cluster node")?;
+        self.leader.store(false, Ordering::Release);
+        Ok(())
+    }
+
+    /// Record a routed inbound message and decide whether this node handles
+    /// it. `message.agent_id` must already be resolved.
+    pub async fn accept(&self, message: &InboundMessage) -> Result<Delivery> {
+        if message.metadata.contains_key(INBOX_CLAIM_KEY)
+            || message.id.is_empty()
+            || NODE_LOCAL_SOURCES.contains(&message.source.as_str())
+        {
+            return Ok(Delivery::Here);
+        }
+
+        let conversation_key = conversation_key(message);
+        let message_key = format!(
+            "{}:{}:{}",
+            message.adapter_key(),
+            message.conversation_id,
+            message.id
+        );
+        let payload =
+            serde_json::to_string(message).context("failed to serialize inbound message")?;
+        let now = Utc::now();
+
+        let mut transaction = self
+            .pool
+            .begin_with("BEGIN IMMEDIATE")
+            .await
+            .context("failed to start inbox transaction")?;
+        let inserted = sqlx::query(
+            "INSERT INTO inbox (message_key, conversation_key, payload, received_by, received_at) \
+             VALUES (?, ?, ?, ?, ?) ON CONFLICT(message_key) DO NOTHING",
+        )
+        .bind(&message_key)
+        .bind(&conversation_key)
+        .bind(&payload)
+        .bind(&self.node_id)
+        .bind(format_timestamp(now))
+        .execute(&mut *transaction)
+        .await
+        .context("failed to record inbound message")?
+        .rows_affected()
+            > 0;
+
+        // A copy of a message another node recorded can still be ours to
+        // handle, as long as no node has claimed it yet.
+        let unclaimed = inserted || {
+            let claimed_by: Option<Option<String>> =
+                sqlx::query_scalar("SELECT claimed_by FROM inbox WHERE message_key = ?")
+                    .bind(&message_key)
+                    .fetch_optional(&mut *transaction)
+                    .await
+                    .context("failed to read inbox entry")?;
+            matches!(claimed_by, Some(None))
+        };
+
+        let delivery = if !unclaimed {
+            Delivery::Duplicate
+        } else if self
+            .take_lease(&mut transaction, &conversation_key, now)
+            .await?
+        {
+            self.mark_claimed(&mut transaction, &message_key, now)
+                .await?;
+            Delivery::Here
+        } else if inserted {
+            Delivery::Waiting
+        } else {
+            Delivery::Duplicate
+        };
+        transaction
+            .commit()
+            .await
+            .context("failed to commit inbox transaction")?;
+        Ok(delivery)
+    }
+
+    /// Claim inbox messages waiting on this node: their conversation is
+    /// leased here, or its lease has lapsed. Returns them oldest first, ready
+    /// to feed back into the inbound stream.
+    pub async fn claim_waiting(&self) -> Result<Vec<InboundMessage>> {
+        let now = Utc::now();
+        let candidates = sqlx::query(
+            "SELECT inbox.message_key, inbox.conversation_key, inbox.payload FROM inbox \
+             LEFT JOIN conversation_leases lease ON lease.conversation_key = inbox.conversation_key \
+             WHERE inbox.claimed_by IS NULL \
+             AND (lease.node_id IS NULL OR lease.node_id = ? OR lease.expires_at < ?) \
+             ORDER BY inbox.received_at LIMIT ?",
+        )
+        .bind(&self.node_id)
+        .bind(format_timestamp(now))
+        .bind(INBOX_CLAIM_BATCH)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to read cluster inbox")?;
+        if candidates.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut transaction = self
+            .pool
+            .begin_with("BEGIN IMMEDIATE")
+            .await
+            .context("failed to start inbox transaction")?;
+        let mut messages = Vec::new();
+        for row in candidates {
+            let message_key: String = row
+                .try_get("message_key")
+                .context("failed to read message_key")?;
+            let conversation_key: String = row
+                .try_get("conversation_key")
+                .context("failed to read conversation_key")?;
+            let payload: String = row.try_get("payload").context("failed to read payload")?;
+
+            if !self
+                .take_lease(&mut transaction, &conversation_key, now)
+                .await?
+                || !self
+                    .mark_claimed(&mut transaction, &message_key, now)
+                    .await?
+            {
+                continue;
+            }
+            match serde_json::from_str::<InboundMessage>(&payload) {
+                Ok(mut message) => {
+                    message
+                        .metadata
+                        .insert(INBOX_CLAIM_KEY.to_string(), serde_json::Value::Bool(true));
+                    messages.push(message);
+                }
+                Err(error) => {
+                    tracing::warn!(%error, %message_key, "dropping unreadable inbox message");
+                }
+            }
+        }
+        transaction
+            .commit()
+            .await
+            .context("failed to commit inbox transaction")?;
+        Ok(messages)
+    }
+
+    /// Take or renew the lease on a conversation. Fails while another node
+    /// holds an unexpired lease.
+    async fn take_lease(
+        &self,
+        connection: &mut SqliteConnection,
+        conversation_key: &str,
+        now: DateTime<Utc>,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT INTO conversation_leases (conversation_key, node_id, expires_at) VALUES (?, ?, ?) \
+             ON CONFLICT(conversation_key) DO UPDATE SET node_id = excluded.node_id, \
+             expires_at = excluded.expires_at \
+             WHERE conversation_leases.node_id = excluded.node_id \
+             OR conversation_leases.expires_at < ?",
+        )
+        .bind(conversation_key)
+        .bind(&self.node_id)
+        .bind(format_timestamp(now + self.lease))
+        .bind(format_timestamp(now))
+        .execute(connection)
+        .await
+        .context("failed to take conversation lease")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Mark an inbox message as this node's. Fails if another node got there
+    /// first.
+    async fn mark_claimed(
+        &self,
+        connection: &mut SqliteConnection,
+        message_key: &str,
+        now: DateTime<Utc>,
+    ) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE inbox SET claimed_by = ?, claimed_at = ? \
+             WHERE message_key = ? AND claimed_by IS NULL",
+        )
+        .bind(&self.node_id)
+        .bind(format_timestamp(now))
+        .bind(message_key)
+        .execute(connection)
+        .await
+        .context("failed to claim inbox message")?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Every node seen within the retention window, with its load.
+    pub async fn nodes(&self) -> Result<Vec<ClusterNode>> {
+        let now = Utc::now();
+        let alive_cutoff = format_timestamp(now - self.lease);
+        let rows = sqlx::query(
+            "SELECT node_id, advertise_url, version, started_at, last_seen, status, \
+             (SELECT COUNT(*) FROM conversation_leases lease \
+              WHERE lease.node_id = cluster_nodes.node_id AND lease.expires_at >= ?) AS conversations, \
+             (SELECT COUNT(*) FROM cluster_jobs job \
+              WHERE job.target_node = cluster_nodes.node_id AND job.status = 'queued') AS queued_jobs \
+             FROM cluster_nodes ORDER BY node_id",
+        )
+        .bind(format_timestamp(now))
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to list cluster nodes")?;
+
+        let mut nodes = Vec::with_capacity(rows.len());
+        for row in rows {
+            let last_seen: String = row
+                .try_get("last_seen")
+                .context("failed to read last_seen")?;
+            let status: String = row.try_get("status").context("failed to read status")?;
+            nodes.push(ClusterNode {
+                node_id: row.try_get("node_id").context("failed to read node_id")?,
+                advertise_url: row
+                    .try_get("advertise_url")
+                    .context("failed to read advertise_url")?,
+                version: row.try_get("version").context("failed to read version")?,
+                started_at: row
+                    .try_get("started_at")
+                    .context("failed to read started_at")?,
+                alive: last_seen >= alive_cutoff,
+                last_seen,
+                leader: false,
+                load: serde_json::from_str(&status).unwrap_or_default(),
+                conversations: row
+                    .try_get("conversations")
+                    .context("failed to read conversations")?,
+                queued_jobs: row
+                    .try_get("queued_jobs")
+                    .context("failed to read queued_jobs")?,
+            });
+        }
+        if let Some(leader) = nodes.iter_mut().find(|node| node.alive) {
+            leader.leader = true;
+        }
+        Ok(nodes)
+    }
+
+    /// Nodes, inbox, and job queue in one view.
+    pub async fn status(&self) -> Result<ClusterStatus> {
+        let nodes = self.nodes().await?;
+        let waiting_messages: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM inbox WHERE claimed_by IS NULL")
+                .fetch_one(&self.pool)
+                .await
+                .context("failed to count waiting messages")?;
+        let row = sqlx::query(
+            "SELECT COALESCE(SUM(status = 'queued'), 0) AS queued, \
+             COALESCE(SUM(status = 'running'), 0) AS running FROM cluster_jobs",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .context("failed to count cluster jobs")?;
+        Ok(ClusterStatus {
+            node_id: self.node_id.clone(),
+            nodes,
+            waiting_messages,
+            queued_jobs: row.try_get("queued").context("failed to read queued")?,
+            running_jobs: row.try_get("running").context("failed to read running")?,
+        })
+    }
+
+    /// The node a ready task should run on when that isn't this one.
+    /// Returns None when task distribution is off or no node is less busy.
+    pub async fn offload_target(&self) -> Result<Option<String>> {
+        if !self.distribute_tasks {
+            return Ok(None);
+        }
+        let nodes = self.nodes().await?;
+        Ok(least_busy_other(&nodes, &self.node_id).map(str::to_string))
+    }
+
+    /// Queue a ready task for `target_node` to run.
+    pub async fn enqueue_job(
+        &self,
+        agent_id: &str,
+        task_number: i64,
+        title: &str,
+        prompt: &str,
+        target_node: &str,
+    ) -> Result<i64> {
+        let now = format_timestamp(Utc::now());
+        let result = sqlx::query(
+            "INSERT INTO cluster_jobs \
+             (agent_id, task_number, title, prompt, origin_node, target_node, created_at, updated_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(agent_id)
+        .bind(task_number)
+        .bind(title)
+        .bind(prompt)
+        .bind(&self.node_id)
+        .bind(target_node)
+        .bind(&now)
+        .bind(&now)
+        .execute(&self.pool)
+        .await
+        .context("failed to queue cluster job")?;
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Start the next job for `agent_id` this node should run: one queued
+    /// for it, one whose target node is gone or hasn't started it within the
+    /// lease, or one whose worker node stopped renewing it.
+    pub async fn claim_job(&self, agent_id: &str) -> Result<Option<ClusterJob>> {
+        let now = Utc::now();
+        let row = sqlx::query(
+            "UPDATE cluster_jobs SET status = 'running', worker_node = ?1, \
+             lease_expires_at = ?2, updated_at = ?3 \
+             WHERE id = ( \
+                SELECT id FROM cluster_jobs WHERE agent_id = ?4 AND ( \
+                    (status = 'queued' AND (target_node = ?1 OR updated_at < ?5 \
+                        OR target_node NOT IN \
+                        (SELECT node_id FROM cluster_nodes WHERE last_seen >= ?5))) \
+                    OR (status = 'running' AND lease_expires_at < ?3)) \
+                ORDER BY id LIMIT 1) \
+             RETURNING id, agent_id, task_number, title, prompt, origin_node",
+        )
+        .bind(&self.node_id)
+        .bind(format_timestamp(now + self.lease))
+        .bind(format_timestamp(now))
+        .bind(agent_id)
+        .bind(format_timestamp(now - self.lease))
+        .fetch_optional(&self.pool)
+        .await
+        .context("failed to claim cluster job")?;
+        row.map(|row| {
+            Ok::<_, sqlx::Error>(ClusterJob {
+                id: row.try_get("id")?,
+                agent_id: row.try_get("agent_id")?,
+                task_number: row.try_get("task_number")?,
+                title: row.try_get("title")?,
+                prompt: row.try_get("prompt")?,
+                origin_node: row.try_get("origin_node")?,
+            })
+        })
+        .transpose()
+        .context("failed to read cluster job")
+        .map_err(Into::into)
+    }
+
+    /// Record a job's result for its origin node. Ignored when the job was
+    /// requeued in the meantime.
+    pub async fn finish_job(&self, job_id: i64, success: bool, result: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE cluster_jobs SET status = ?, result = ?, lease_expires_at = NULL, updated_at = ? \
+             WHERE id = ? AND worker_node = ? AND status = 'running'",
+        )
+        .bind(if success { "done" } else { "failed" })
+        .bind(result)
+        .bind(format_timestamp(Utc::now()))
+        .bind(job_id)
+        .bind(&self.node_id)
+        .execute(&self.pool)
+        .await
+        .context("failed to record cluster job result")?;
+        Ok(())
+    }
+
+    /// Remove and return the finished jobs this node handed out for
+    /// `agent_id`.
+    pub async fn take_finished_jobs(&self, agent_id: &str) -> Result<Vec<FinishedJob>> {
+        let rows = sqlx::query(
+            "DELETE FROM cluster_jobs \
+             WHERE agent_id = ? AND origin_node = ? AND status IN ('done', 'failed') \
+             RETURNING id, task_number, status, result, worker_node",
+        )
+        .bind(agent_id)
+        .bind(&self.node_id)
+        .fetch_all(&self.pool)
+        .await
+        .context("failed to collect finished cluster jobs")?;
+        rows.into_iter()
+            .map(|row| {
+                let status: String = row.try_get("status")?;
+                Ok(FinishedJob {
+                    id: row.try_get("id")?,
+                    task_number: row.try_get("task_number")?,
+                    success: status == "done",
+                    result: row
+                        .try_get::<Option<String>, _>("result")?
+                        .unwrap_or_default(),
+                    worker_node: row
+                        .try_get::<Option<String>, _>("worker_node")?
+                        .unwrap_or_default(),
+                })
+            })
+            .collect::<std::result::Result<_, sqlx::Error>>()
+            .context("failed to read finished cluster job")
+            .map_err(Into::into)
+    }
+}
+
+/// A running task worker, counted in this node's load until dropped.
+pub struct WorkerSlot {
+    cluster: Arc<Cluster>,
+}
+
+impl Drop for WorkerSlot {
+    fn drop(&mut self) {
+        self.cluster.task_workers.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// The live node other than `local` with the least work, when it has less
+/// than `local`. Work is active workers plus jobs queued for the node.
+fn least_busy_other<'a>(nodes: &'a [ClusterNode], local: &str) -> Option<&'a str> {
+    let busyness = |node: &ClusterNode| node.load.active_workers as i64 + node.queued_jobs;
+    let local = nodes.iter().find(|node| node.node_id == local)?;
+    nodes
+        .iter()
+        .filter(|node| node.alive && node.node_id != local.node_id)
+        .min_by_key(|node| (busyness(node), node.node_id.as_str()))
+        .filter(|node| busyness(node) < busyness(local))
+        .map(|node| node.node_id.as_str())
+}
+
+/// Conversations are leased per agent, matching how the router keys
+/// channels.
+fn conversation_key(message: &InboundMessage) -> String {
+    format!(
+        "{}/{}",
+        message.agent_id.as_deref().unwrap_or_default(),
+        message.conversation_id
+    )
+}
+
+/// Fixed-width UTC timestamps, so they sort and compare as text.
+fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+/// Start the heartbeat and inbox loops under the global supervisor.
+pub fn supervise(
+    cluster: Arc<Cluster>,
+    api_state: Arc<ApiState>,
+    messaging_manager: Arc<MessagingManager>,
+) {
+    let supervisor = Supervisor::global();
+    {
+        let cluster = cluster.clone();
+        supervisor.supervise(
+            ProcessSpec::new(ProcessKind::Maintenance, "cluster_heartbeat"),
+            move || {
+                let cluster = cluster.clone();
+                let api_state = api_state.clone();
+                tokio::spawn(async move { run_heartbeat_loop(&cluster, &api_state).await })
+            },
+        );
+    }
+    supervisor.supervise(
+        ProcessSpec::new(ProcessKind::Maintenance, "cluster_inbox"),
+        move || {
+            let cluster = cluster.clone();
+            let messaging_manager = messaging_manager.clone();
+            tokio::spawn(async move { run_inbox_loop(&cluster, &messaging_manager).await })
+        },
+    );
+    tracing::info!("cluster loops started");
+}
+
+async fn run_heartbeat_loop(cluster: &Cluster, api_state: &ApiState) {
+    let period = (cluster.lease / 3)
+        .to_std()
+        .unwrap_or(Duration::from_secs(10));
+    let mut interval = tokio::time::interval(period);
+    loop {
+        interval.tick().await;
+        let load = node_load(cluster, api_state).await;
+        if let Err(error) = cluster.heartbeat(&load).await {
+            tracing::warn!(%error, "cluster heartbeat failed");
+        }
+    }
+}
+
+async fn run_inbox_loop(cluster: &Cluster, messaging_manager: &MessagingManager) {
+    let mut interval = tokio::time::interval(INBOX_POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        let messages = match cluster.claim_waiting().await {
+            Ok(messages) => messages,
+            Err(error) => {
+                tracing::warn!(%error, "failed to claim waiting inbox messages");
+                continue;
+            }
+        };
+        for message in messages {
+            let conversation_id = message.conversation_id.clone();
+            if let Err(error) = messaging_manager.inject_message(message).await {
+                tracing::warn!(%error, %conversation_id, "failed to deliver claimed inbox message");
+            }
+        }
+    }
+}
+
+/// This node's load, from the live channel status blocks and the task
+/// workers it's running.
+async fn node_load(cluster: &Cluster, api_state: &ApiState) -> NodeLoad {
+    let blocks = api_state.channel_status_blocks.read().await;
+    let mut load = NodeLoad {
+        agents: api_state.agent_configs.load().len(),
+        active_channels: blocks.len(),
+        active_workers: cluster.task_workers.load(Ordering::Acquire),
+        active_branches: 0,
+    };
+    for status_block in blocks.values() {
+        let block = status_block.read().await;
+        load.active_workers += block.active_workers.len();
+        load.active_branches += block.active_branches.len();
+    }
+    load
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn pool() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        MIGRATOR.run(&pool).await.unwrap();
+        pool
+    }
+
+    fn node(pool: &SqlitePool, node_id: &str) -> Arc<Cluster> {
+        let config = ClusterConfig {
+            enabled: true,
+            node_id: node_id.to_string(),
+            database: "cluster.db".into(),
+            advertise_url: None,
+            lease_secs: 30,
+            distribute_tasks: true,
+        };
+        Arc::new(Cluster::new(pool.clone(), &config))
+    }
+
+    fn message(id: &str, conversation_id: &str) -> InboundMessage {
+        InboundMessage {
+            id: id.to_string(),
+            source: "discord".to_string(),
+            conversation_id: conversation_id.to_string(),
+            agent_id: Some(Arc::from("main")),
+            ..InboundMessage::empty()
+        }
+    }
+
+    #[tokio::test]
+    async fn each_message_is_handled_by_the_conversation_owner_once() {
+        let pool = pool().await;
+        let a = node(&pool, "a");
+        let b = node(&pool, "b");
+
+        // Both nodes receive the same message; the first takes the
+        // conversation and the copy is dropped.
+        assert_eq!(a.accept(&message("1", "c1")).await.unwrap(), Delivery::Here);
+        assert_eq!(
+            b.accept(&message("1", "c1")).await.unwrap(),
+            Delivery::Duplicate
+        );
+
+        // A later message only B received waits for A.
+        assert_eq!(
+            b.accept(&message("2", "c1")).await.unwrap(),
+            Delivery::Waiting
+        );
+        assert!(b.claim_waiting().await.unwrap().is_empty());
+        let waiting = a.claim_waiting().await.unwrap();
+        assert_eq!(waiting.len(), 1);
+        assert_eq!(waiting[0].id, "2");
+        assert_eq!(a.accept(&waiting[0]).await.unwrap(), Delivery::Here);
+        assert_eq!(
+            a.accept(&message("2", "c1")).await.unwrap(),
+            Delivery::Duplicate
+        );
+
+        // Other conversations are free for B, and local sources skip the
+        // inbox entirely.
+        assert_eq!(b.accept(&message("3", "c2")).await.unwrap(), Delivery::Here);
+        let webchat = InboundMessage {
+            source: "webchat".to_string(),
+            ..message("4", "c1")
+        };
+        assert_eq!(b.accept(&webchat).await.unwrap(), Delivery::Here);
+
+        // Once A leaves, B takes its conversations over.
+        a.leave().await.unwrap();
+        assert_eq!(b.accept(&message("5", "c1")).await.unwrap(), Delivery::Here);
+    }
+
+    #[tokio::test]
+    async fn jobs_run_on_the_target_and_return_to_the_origin() {
+        let pool = pool().await;
+        let a = node(&pool, "a");
+        let b = node(&pool, "b");
+        a.heartbeat(&NodeLoad {
+            active_workers: 3,
+            ..NodeLoad::default()
+        })
+        .await
+        .unwrap();
+        b.heartbeat(&NodeLoad::default()).await.unwrap();
+        assert!(a.is_leader());
+        assert!(!b.is_leader());
+
+        assert_eq!(a.offload_target().await.unwrap().as_deref(), Some("b"));
+        assert_eq!(b.offload_target().await.unwrap(), None);
+
+        a.enqueue_job("main", 7, "Write report", "Execute task #7", "b")
+            .await
+            .unwrap();
+        assert!(a.claim_job("main").await.unwrap().is_none());
+        let job = b.claim_job("main").await.unwrap().unwrap();
+        assert_eq!(job.task_number, 7);
+        assert_eq!(job.origin_node, "a");
+        assert!(b.claim_job("main").await.unwrap().is_none());
+
+        assert!(a.take_finished_jobs("main").await.unwrap().is_empty());
+        b.finish_job(job.id, true, "done").await.unwrap();
+        let finished = a.take_finished_jobs("main").await.unwrap();
+        assert_eq!(finished.len(), 1);
+        assert!(finished[0].success);
+        assert_eq!(finished[0].worker_node, "b");
+        assert!(a.take_finished_jobs("main").await.unwrap().is_empty());
+    }
+}
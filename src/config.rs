@@ -1,9 +1,11 @@
 //! Configuration loading and validation.
 
+mod changelog;
 mod load;
 mod onboarding;
 mod permissions;
 mod providers;
+mod reload;
 mod runtime;
 mod toml_schema;
 mod types;
@@ -11,16 +13,19 @@ mod watcher;
 
 // Re-export all public types from submodules so external consumers
 // continue to use `crate::config::TypeName` unchanged.
+pub use changelog::{ConfigChange, ConfigChangeSummary, ConfigChangelog, ConfigFileKind};
 pub(crate) use load::resolve_env_value;
-pub use load::set_resolve_secrets_store;
+pub use load::{resolve_config_cipher, set_resolve_config_cipher, set_resolve_secrets_store};
 pub use onboarding::run_onboarding;
 pub use permissions::{
-    DiscordPermissions, SignalPermissions, SlackPermissions, TelegramPermissions, TwitchPermissions,
+    DiscordPermissions, InstancePermissions, PermissionHandles, SignalPermissions,
+    SlackPermissions, TelegramPermissions, TwitchPermissions,
 };
 pub(crate) use providers::default_provider_config;
+pub use reload::{ConfigReloader, ReloadReport};
 pub use runtime::RuntimeConfig;
 pub use types::*;
-pub use watcher::spawn_file_watcher;
+pub use watcher::{spawn_file_watcher, supervise_file_watcher};
 
 // Re-export pub(crate) items that need crate-wide visibility.
 // (GEMINI_PROVIDER_BASE_URL is only used within config submodules, no re-export needed.)
@@ -367,6 +372,94 @@ name = "Custom OpenAI"
         assert_eq!(config.llm.openai_key.as_deref(), Some("legacy-openai-key"));
     }
 
+    #[test]
+    fn test_llm_quota_tables_parse() {
+        let toml = r#"
+[llm]
+anthropic_key = "test-key"
+
+[llm.quota.OpenRouter]
+monthly_usd = 25.5
+
+[llm.quota.anthropic]
+monthly_tokens = 1000000
+"#;
+
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+
+        assert_eq!(
+            config.llm.quotas.get("openrouter"),
+            Some(&ProviderQuota {
+                monthly_usd: Some(25.5),
+                monthly_tokens: None,
+            })
+        );
+        assert_eq!(
+            config.llm.quotas.get("anthropic"),
+            Some(&ProviderQuota {
+                monthly_usd: None,
+                monthly_tokens: Some(1_000_000),
+            })
+        );
+    }
+
+    #[test]
+    fn test_api_auth_and_rate_limit_parse() {
+        let toml = r#"
+[api]
+auth_token = "legacy-token"
+
+[api.auth]
+keys = [
+    { name = "dashboard", key = "new-key", role = "viewer" },
+    { key = "old-key", expires_at = "2000-01-01T00:00:00Z" },
+]
+
+[api.rate_limit]
+requests_per_minute = 120
+routes = [{ path = "providers/", requests_per_minute = 10 }]
+"#;
+
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+
+        let auth = &config.api.auth;
+        assert_eq!(auth.keys.len(), 3);
+        assert_eq!(
+            auth.authenticate("legacy-token")
+                .map(|key| key.name.as_str()),
+            Some("auth_token")
+        );
+        assert_eq!(
+            auth.authenticate("new-key").map(|key| key.name.as_str()),
+            Some("dashboard")
+        );
+        assert_eq!(
+            auth.authenticate("new-key").map(|key| key.role),
+            Some(ApiRole::Viewer)
+        );
+        assert_eq!(
+            auth.authenticate("legacy-token").map(|key| key.role),
+            Some(ApiRole::Admin)
+        );
+        assert!(auth.authenticate("old-key").is_none(), "expired key");
+        assert!(auth.authenticate("new-ke").is_none());
+
+        let invalid: TomlConfig =
+            toml::from_str("[api.auth]\nkeys = [{ key = \"k\", role = \"root\" }]\n")
+                .expect("failed to parse test TOML");
+        assert!(Config::from_toml(invalid, PathBuf::from(".")).is_err());
+
+        let rate_limit = &config.api.rate_limit;
+        assert!(rate_limit.enabled);
+        assert_eq!(rate_limit.default.requests_per_minute, 120);
+        let (index, rule) = rate_limit.rule_for("/providers/test");
+        assert_eq!(index, Some(0));
+        assert_eq!(rule.requests_per_minute, 10);
+        assert_eq!(rule.burst, 10);
+    }
+
     #[test]
     fn test_explicit_openrouter_provider_toml_injects_extra_headers() {
         let toml = r#"
@@ -579,6 +672,8 @@ bind = "127.0.0.1"
             channel_ids: vec![],
             require_mention: false,
             dm_allowed_users,
+            mention: None,
+            prefix: None,
         }
     }
 
@@ -1012,6 +1107,255 @@ startup_delay_secs = 2
         assert_eq!(resolved.warmup.startup_delay_secs, 2);
     }
 
+    #[test]
+    fn test_storage_default_and_agent_override_resolution() {
+        let toml = r#"
+[defaults.storage]
+quota_mb = 2048
+warn_percent = 90
+auto_cleanup = true
+optimize_interval_secs = 3600
+db_maintenance_interval_secs = 7200
+
+[[agents]]
+id = "main"
+
+[agents.storage]
+quota_mb = 0
+retention_days = 3
+optimize_idle_secs = 60
+db_vacuum_min_free_percent = 5
+encrypt_at_rest = true
+
+[[agents]]
+id = "other"
+"#;
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+        let main = config.agents[0].resolve(&config.instance_dir, &config.defaults);
+        let other = config.agents[1].resolve(&config.instance_dir, &config.defaults);
+
+        assert_eq!(other.storage.quota_bytes(), Some(2048 * 1024 * 1024));
+        assert_eq!(other.storage.warn_percent, 90);
+        assert!(other.storage.auto_cleanup);
+        assert_eq!(other.storage.retention_days, 14);
+        assert_eq!(other.storage.optimize_interval_secs, 3600);
+        assert_eq!(other.storage.optimize_idle_secs, 300);
+
+        assert_eq!(main.storage.quota_mb, None, "0 clears the inherited quota");
+        assert_eq!(main.storage.warn_percent, 90);
+        assert_eq!(main.storage.retention_days, 3);
+        assert_eq!(main.storage.optimize_interval_secs, 3600);
+        assert_eq!(main.storage.optimize_idle_secs, 60);
+        assert_eq!(main.storage.db_maintenance_interval_secs, 7200);
+        assert_eq!(main.storage.db_vacuum_min_free_percent, 5);
+        assert_eq!(other.storage.db_vacuum_min_free_percent, 20);
+        assert_eq!(other.storage.db_wal_autocheckpoint_pages, 1000);
+        assert!(main.storage.encrypt_at_rest);
+        assert!(!other.storage.encrypt_at_rest);
+
+        let invalid: TomlConfig = toml::from_str("[defaults.storage]\nwarn_percent = 0\n")
+            .expect("failed to parse test TOML");
+        assert!(Config::from_toml(invalid, PathBuf::from(".")).is_err());
+        let invalid: TomlConfig =
+            toml::from_str("[defaults.storage]\ndb_vacuum_min_free_percent = 101\n")
+                .expect("failed to parse test TOML");
+        assert!(Config::from_toml(invalid, PathBuf::from(".")).is_err());
+    }
+
+    #[test]
+    fn test_transcription_default_and_agent_override_resolution() {
+        let toml = r#"
+[defaults.transcription]
+url = "http://localhost:8080/v1/audio/transcriptions"
+model = "whisper-large-v3"
+language = "en"
+
+[[agents]]
+id = "main"
+
+[agents.transcription]
+url = ""
+language = "de"
+"#;
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+        let resolved = config.agents[0].resolve(&config.instance_dir, &config.defaults);
+
+        assert_eq!(
+            config.defaults.transcription.url.as_deref(),
+            Some("http://localhost:8080/v1/audio/transcriptions")
+        );
+        assert_eq!(config.defaults.transcription.max_file_mb, 25);
+
+        assert_eq!(resolved.transcription.url, None, "empty string clears url");
+        assert_eq!(resolved.transcription.model, "whisper-large-v3");
+        assert_eq!(resolved.transcription.language.as_deref(), Some("de"));
+    }
+
+    #[test]
+    fn test_budget_default_and_agent_override_resolution() {
+        let toml = r#"
+[defaults.budget]
+daily_hard_usd = 10.0
+channel_soft_usd = 1.5
+downgrade_model = "anthropic/claude-haiku-4.5"
+
+[[agents]]
+id = "main"
+
+[agents.budget]
+daily_hard_usd = 4.0
+downgrade_model = ""
+"#;
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+        let resolved = config.agents[0].resolve(&config.instance_dir, &config.defaults);
+
+        assert_eq!(config.defaults.budget.daily_hard_usd, Some(10.0));
+        assert_eq!(resolved.budget.daily_hard_usd, Some(4.0));
+        assert_eq!(resolved.budget.channel_soft_usd, Some(1.5));
+        assert_eq!(resolved.budget.worker_hard_usd, None);
+        assert_eq!(
+            resolved.budget.downgrade_model, None,
+            "empty string clears downgrade_model"
+        );
+
+        let invalid: TomlConfig = toml::from_str("[defaults.budget]\ndaily_soft_usd = -1.0\n")
+            .expect("failed to parse test TOML");
+        assert!(Config::from_toml(invalid, PathBuf::from(".")).is_err());
+    }
+
+    #[test]
+    fn test_memory_fts_default_and_agent_override_resolution() {
+        let toml = r#"
+[defaults.memory_fts]
+tokenizer = "simple"
+language = "german"
+stemming = true
+
+[[agents]]
+id = "main"
+
+[[agents]]
+id = "english"
+
+[agents.memory_fts]
+language = "English"
+remove_stop_words = true
+"#;
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+        let main = config.agents[0].resolve(&config.instance_dir, &config.defaults);
+        let english = config.agents[1].resolve(&config.instance_dir, &config.defaults);
+
+        assert!(config.defaults.memory_fts.is_configured());
+        assert_eq!(main.memory_fts, config.defaults.memory_fts);
+
+        assert_eq!(english.memory_fts.tokenizer.as_deref(), Some("simple"));
+        assert_eq!(english.memory_fts.language.as_deref(), Some("English"));
+        assert_eq!(english.memory_fts.stemming, Some(true));
+        assert_eq!(english.memory_fts.remove_stop_words, Some(true));
+
+        let invalid = r#"
+[defaults.memory_fts]
+tokenizer = "bpe"
+"#;
+        let parsed: TomlConfig = toml::from_str(invalid).expect("failed to parse test TOML");
+        assert!(Config::from_toml(parsed, PathBuf::from(".")).is_err());
+    }
+
+    #[test]
+    fn test_routing_sampling_default_and_agent_override_resolution() {
+        let toml = r#"
+[defaults.routing.sampling.channel]
+temperature = 0.8
+top_p = 0.9
+
+[defaults.routing.sampling.bogus]
+temperature = 0.5
+
+[[agents]]
+id = "main"
+
+[agents.routing.sampling.channel]
+temperature = 1.2
+max_tokens = 0
+
+[agents.routing.channel_sampling."discord:1"]
+top_p = 1.5
+temperature = 0.1
+"#;
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+        let main = config.agents[0].resolve(&config.instance_dir, &config.defaults);
+
+        assert!(!config.defaults.routing.sampling.contains_key("bogus"));
+        let channel = main.routing.sampling["channel"];
+        assert_eq!(channel.temperature, Some(1.2));
+        assert_eq!(channel.top_p, Some(0.9));
+        assert_eq!(channel.max_tokens, None);
+
+        let override_sampling = main.routing.channel_sampling["discord:1"];
+        assert_eq!(override_sampling.temperature, Some(0.1));
+        assert_eq!(override_sampling.top_p, None);
+    }
+
+    #[test]
+    fn test_prefetch_default_and_agent_override_resolution() {
+        let toml = r#"
+[defaults.prefetch]
+enabled = true
+ttl_secs = 120
+
+[[agents]]
+id = "main"
+
+[[agents]]
+id = "quiet"
+
+[agents.prefetch]
+enabled = false
+"#;
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+        let main = config.agents[0].resolve(&config.instance_dir, &config.defaults);
+        let quiet = config.agents[1].resolve(&config.instance_dir, &config.defaults);
+
+        assert!(!PrefetchConfig::default().enabled);
+        assert!(main.prefetch.enabled);
+        assert_eq!(main.prefetch.ttl_secs, 120);
+        assert!(!quiet.prefetch.enabled);
+        assert_eq!(quiet.prefetch.ttl_secs, 120);
+    }
+
+    #[test]
+    fn test_branch_cache_default_and_agent_override_resolution() {
+        let toml = r#"
+[defaults.branch_cache]
+ttl_secs = 60
+
+[[agents]]
+id = "main"
+
+[[agents]]
+id = "fresh"
+
+[agents.branch_cache]
+enabled = false
+"#;
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+        let main = config.agents[0].resolve(&config.instance_dir, &config.defaults);
+        let fresh = config.agents[1].resolve(&config.instance_dir, &config.defaults);
+
+        assert!(BranchCacheConfig::default().enabled);
+        assert!(main.branch_cache.enabled);
+        assert_eq!(main.branch_cache.ttl_secs, 60);
+        assert!(!fresh.branch_cache.enabled);
+        assert_eq!(fresh.branch_cache.ttl_secs, 60);
+    }
+
     #[test]
     fn test_cortex_default_and_agent_override_resolution() {
         let toml = r#"
@@ -1435,6 +1779,8 @@ maintenance_merge_similarity_threshold = 1.1
             channel_ids: vec![],
             require_mention: false,
             dm_allowed_users: vec![],
+            mention: None,
+            prefix: None,
         };
         assert_eq!(binding.runtime_adapter_key(), "telegram:sales");
     }
@@ -1451,6 +1797,8 @@ maintenance_merge_similarity_threshold = 1.1
             channel_ids: vec![],
             require_mention: false,
             dm_allowed_users: vec![],
+            mention: None,
+            prefix: None,
         };
         assert!(binding.uses_default_adapter());
     }
@@ -1482,6 +1830,8 @@ maintenance_merge_similarity_threshold = 1.1
             channel_ids: vec![],
             require_mention: false,
             dm_allowed_users: vec![],
+            mention: None,
+            prefix: None,
         };
         let message = test_inbound_message("telegram", None);
         assert!(binding_adapter_matches(&binding, &message));
@@ -1499,6 +1849,8 @@ maintenance_merge_similarity_threshold = 1.1
             channel_ids: vec![],
             require_mention: false,
             dm_allowed_users: vec![],
+            mention: None,
+            prefix: None,
         };
         let message = test_inbound_message("telegram", Some("telegram:support"));
         assert!(binding_adapter_matches(&binding, &message));
@@ -1516,6 +1868,8 @@ maintenance_merge_similarity_threshold = 1.1
             channel_ids: vec![],
             require_mention: false,
             dm_allowed_users: vec![],
+            mention: None,
+            prefix: None,
         };
         let message = test_inbound_message("telegram", None);
         assert!(!binding_adapter_matches(&binding, &message));
@@ -1533,6 +1887,8 @@ maintenance_merge_similarity_threshold = 1.1
             channel_ids: vec![],
             require_mention: false,
             dm_allowed_users: vec![],
+            mention: None,
+            prefix: None,
         };
         let message = test_inbound_message("telegram", Some("telegram:support"));
         assert!(!binding_adapter_matches(&binding, &message));
@@ -1550,11 +1906,55 @@ maintenance_merge_similarity_threshold = 1.1
             channel_ids: vec![],
             require_mention: false,
             dm_allowed_users: vec![],
+            mention: None,
+            prefix: None,
         };
         let message = test_inbound_message("telegram", Some("telegram:sales"));
         assert!(!binding_adapter_matches(&binding, &message));
     }
 
+    #[test]
+    fn dispatch_routes_by_mention_and_prefix_on_shared_adapter() {
+        let rule = |agent_id: &str, mention: Option<&str>, prefix: Option<&str>| Binding {
+            agent_id: agent_id.into(),
+            channel: "slack".into(),
+            adapter: None,
+            guild_id: None,
+            workspace_id: None,
+            chat_id: None,
+            channel_ids: vec![],
+            require_mention: false,
+            dm_allowed_users: vec![],
+            mention: mention.map(String::from),
+            prefix: prefix.map(String::from),
+        };
+        let bindings = vec![
+            rule("research", Some("research-bot"), None),
+            rule("support", None, Some("!support")),
+            rule("main", None, None),
+        ];
+        let dispatch = |text: &str| {
+            let mut message = test_inbound_message("slack", None);
+            message.content = crate::MessageContent::Text(text.into());
+            let agent_id = dispatch_inbound_message(&bindings, &mut message, "fallback")
+                .expect("message should be routed");
+            (agent_id.to_string(), message.content.to_string())
+        };
+
+        assert_eq!(
+            dispatch("hey @Research-Bot, any papers?"),
+            ("research".into(), "hey @Research-Bot, any papers?".into())
+        );
+        assert_eq!(
+            dispatch("!SUPPORT   my login is broken"),
+            ("support".into(), "my login is broken".into())
+        );
+        assert_eq!(
+            dispatch("@research-bots and !supportive folks"),
+            ("main".into(), "@research-bots and !supportive folks".into())
+        );
+    }
+
     #[test]
     fn validate_named_adapters_valid_config() {
         let messaging = MessagingConfig {
@@ -1575,6 +1975,9 @@ maintenance_merge_similarity_threshold = 1.1
             webhook: None,
             twitch: None,
             signal: None,
+            github: None,
+            matrix: None,
+            irc: None,
         };
         let bindings = vec![
             Binding {
@@ -1588,6 +1991,8 @@ maintenance_merge_similarity_threshold = 1.1
                 channel_ids: vec![],
                 require_mention: false,
                 dm_allowed_users: vec![],
+                mention: None,
+                prefix: None,
             },
             Binding {
                 agent_id: "support-agent".into(),
@@ -1600,6 +2005,8 @@ maintenance_merge_similarity_threshold = 1.1
                 channel_ids: vec![],
                 require_mention: false,
                 dm_allowed_users: vec![],
+                mention: None,
+                prefix: None,
             },
         ];
         assert!(validate_named_messaging_adapters(&messaging, &bindings).is_ok());
@@ -1620,6 +2027,9 @@ maintenance_merge_similarity_threshold = 1.1
             webhook: None,
             twitch: None,
             signal: None,
+            github: None,
+            matrix: None,
+            irc: None,
         };
         let bindings = vec![Binding {
             agent_id: "main".into(),
@@ -1631,6 +2041,8 @@ maintenance_merge_similarity_threshold = 1.1
             channel_ids: vec![],
             require_mention: false,
             dm_allowed_users: vec![],
+            mention: None,
+            prefix: None,
         }];
         assert!(validate_named_messaging_adapters(&messaging, &bindings).is_err());
     }
@@ -1683,6 +2095,9 @@ maintenance_merge_similarity_threshold = 1.1
             webhook: None,
             twitch: None,
             signal: None,
+            github: None,
+            matrix: None,
+            irc: None,
         };
         let bindings = vec![Binding {
             agent_id: "main".into(),
@@ -1694,6 +2109,8 @@ maintenance_merge_similarity_threshold = 1.1
             channel_ids: vec![],
             require_mention: false,
             dm_allowed_users: vec![],
+            mention: None,
+            prefix: None,
         }];
         assert!(validate_named_messaging_adapters(&messaging, &bindings).is_err());
     }
@@ -1718,6 +2135,9 @@ maintenance_merge_similarity_threshold = 1.1
             webhook: None,
             twitch: None,
             signal: None,
+            github: None,
+            matrix: None,
+            irc: None,
         };
         // Binding targets default adapter, but no default credentials exist
         let bindings = vec![Binding {
@@ -1730,6 +2150,8 @@ maintenance_merge_similarity_threshold = 1.1
             channel_ids: vec![],
             require_mention: false,
             dm_allowed_users: vec![],
+            mention: None,
+            prefix: None,
         }];
         assert!(validate_named_messaging_adapters(&messaging, &bindings).is_err());
     }
@@ -1872,6 +2294,63 @@ something = true
         warn_unknown_config_keys(toml_valid);
     }
 
+    #[test]
+    fn teams_link_coordinator_to_members() {
+        let _lock = env_test_lock().lock();
+        let _env = EnvGuard::new();
+
+        let toml = r#"
+[[agents]]
+id = "main"
+
+[[agents]]
+id = "research"
+
+[[agents]]
+id = "writer"
+
+[[links]]
+from = "writer"
+to = "main"
+direction = "two_way"
+kind = "peer"
+
+[[teams]]
+id = "launch"
+coordinator = "main"
+members = [
+    { agent = "research", specialty = "Market research" },
+    { agent = "writer", specialty = "Copy and announcements" },
+]
+"#;
+
+        let parsed: TomlConfig = toml::from_str(toml).expect("failed to parse test TOML");
+        let config = Config::from_toml(parsed, PathBuf::from(".")).expect("failed to build Config");
+
+        assert_eq!(config.teams.len(), 1);
+        assert_eq!(config.teams[0].members.len(), 2);
+        let research_link = config
+            .links
+            .iter()
+            .find(|link| link.from == "main" && link.to == "research")
+            .expect("coordinator should be linked to research");
+        assert_eq!(research_link.kind, "hierarchical");
+        // The existing peer link to the writer is kept as configured.
+        assert_eq!(
+            config
+                .links
+                .iter()
+                .filter(|link| link.to == "writer" || link.from == "writer")
+                .count(),
+            1
+        );
+
+        let unknown_member = toml.replace("agent = \"writer\"", "agent = \"designer\"");
+        let parsed: TomlConfig =
+            toml::from_str(&unknown_member).expect("failed to parse test TOML");
+        assert!(Config::from_toml(parsed, PathBuf::from(".")).is_err());
+    }
+
     #[test]
     fn top_level_mcp_servers_silently_ignored_by_serde() {
         // Demonstrates the root cause of issue #221: serde drops unknown fields.
@@ -0,0 +1,542 @@
+//! Versioned history of configuration file changes.
+//!
+//! The file watcher reports every change to config.toml, identity files, and
+//! SKILL.md files here, whether it came from the API, a factory tool, or a
+//! hand edit. Each change keeps the file's content before and after, so an
+//! operator can see what changed and restore the previous version.
+//!
+//! History is stored as JSON lines in `config_history.jsonl` in the instance
+//! directory and capped at [`MAX_ENTRIES`].
+
+use anyhow::Context as _;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Oldest entries are dropped once the history grows past this.
+const MAX_ENTRIES: usize = 500;
+
+/// Unchanged lines shown around each change in a diff.
+const DIFF_CONTEXT: usize = 3;
+
+/// Above this many line pairs the diff shows the changed region as a whole
+/// instead of aligning individual lines.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// What kind of file a change touched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigFileKind {
+    /// The instance config.toml (routing, providers, tuning, bindings, ...).
+    Config,
+    /// An agent's SOUL.md, IDENTITY.md, or ROLE.md.
+    Identity,
+    /// A skill's SKILL.md.
+    Skill,
+}
+
+/// One recorded change to a tracked file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    pub version: u64,
+    pub changed_at: DateTime<Utc>,
+    pub kind: ConfigFileKind,
+    pub path: PathBuf,
+    pub agent_id: Option<String>,
+    /// Content before the change. `None` when the file was created.
+    pub before: Option<String>,
+    /// Content after the change. `None` when the file was deleted.
+    pub after: Option<String>,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    /// Set when this change was made by reverting an earlier version.
+    pub reverted_version: Option<u64>,
+}
+
+impl ConfigChange {
+    /// Unified-style line diff from `before` to `after`.
+    pub fn diff(&self) -> String {
+        line_diff(
+            self.before.as_deref().unwrap_or_default(),
+            self.after.as_deref().unwrap_or_default(),
+        )
+    }
+}
+
+/// A change without file contents, for listings.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigChangeSummary {
+    pub version: u64,
+    pub changed_at: DateTime<Utc>,
+    pub kind: ConfigFileKind,
+    pub path: PathBuf,
+    pub agent_id: Option<String>,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    pub reverted_version: Option<u64>,
+}
+
+impl From<&ConfigChange> for ConfigChangeSummary {
+    fn from(change: &ConfigChange) -> Self {
+        Self {
+            version: change.version,
+            changed_at: change.changed_at,
+            kind: change.kind,
+            path: change.path.clone(),
+            agent_id: change.agent_id.clone(),
+            lines_added: change.lines_added,
+            lines_removed: change.lines_removed,
+            reverted_version: change.reverted_version,
+        }
+    }
+}
+
+/// Instance-wide changelog of tracked configuration files.
+#[derive(Debug)]
+pub struct ConfigChangelog {
+    log_path: PathBuf,
+    state: Mutex<ChangelogState>,
+}
+
+#[derive(Debug, Default)]
+struct ChangelogState {
+    entries: Vec<ConfigChange>,
+    /// Last content seen for each tracked file, keyed by normalized path.
+    known: HashMap<PathBuf, Option<String>>,
+}
+
+impl ConfigChangelog {
+    /// Open the changelog in `instance_dir`, loading any existing history.
+    pub fn open(instance_dir: &Path) -> Self {
+        let log_path = instance_dir.join("config_history.jsonl");
+        let mut entries = Vec::new();
+
+        if let Ok(content) = std::fs::read_to_string(&log_path) {
+            for line in content.lines().filter(|line| !line.trim().is_empty()) {
+                match serde_json::from_str::<ConfigChange>(line) {
+                    Ok(entry) => entries.push(entry),
+                    Err(error) => {
+                        tracing::warn!(%error, "skipping unreadable config history entry");
+                    }
+                }
+            }
+        }
+
+        Self {
+            log_path,
+            state: Mutex::new(ChangelogState {
+                entries,
+                known: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Remember a file's current content as the baseline for its next change.
+    /// Files that are already tracked keep their baseline.
+    pub fn track(&self, path: &Path) {
+        let key = normalize(path);
+        let mut state = self.state.lock().expect("changelog lock poisoned");
+        state
+            .known
+            .entry(key)
+            .or_insert_with(|| std::fs::read_to_string(path).ok());
+    }
+
+    /// Track every `<skill>/SKILL.md` under a skills directory.
+    pub fn track_skills(&self, skills_dir: &Path) {
+        let Ok(entries) = std::fs::read_dir(skills_dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let skill_file = entry.path().join("SKILL.md");
+            if skill_file.is_file() {
+                self.track(&skill_file);
+            }
+        }
+    }
+
+    /// Compare a file against its last known content and record a change if
+    /// it differs. Returns the new version, if any.
+    pub fn observe(
+        &self,
+        path: &Path,
+        kind: ConfigFileKind,
+        agent_id: Option<&str>,
+    ) -> Option<u64> {
+        self.record(path, kind, agent_id, None)
+    }
+
+    /// Most recent changes first, optionally limited to one agent. Changes to
+    /// config.toml apply to every agent and are always included.
+    pub fn list(&self, agent_id: Option<&str>, limit: usize) -> Vec<ConfigChangeSummary> {
+        let state = self.state.lock().expect("changelog lock poisoned");
+        state
+            .entries
+            .iter()
+            .rev()
+            .filter(|entry| match agent_id {
+                Some(agent_id) => {
+                    entry.agent_id.is_none() || entry.agent_id.as_deref() == Some(agent_id)
+                }
+                None => true,
+            })
+            .take(limit)
+            .map(ConfigChangeSummary::from)
+            .collect()
+    }
+
+    pub fn get(&self, version: u64) -> Option<ConfigChange> {
+        let state = self.state.lock().expect("changelog lock poisoned");
+        state
+            .entries
+            .iter()
+            .find(|entry| entry.version == version)
+            .cloned()
+    }
+
+    /// Restore a file to its content before `version`. Later changes to the
+    /// same file are undone too. The revert is recorded as a new version, so
+    /// it can itself be reverted. Returns that new version.
+    pub fn revert(&self, version: u64) -> anyhow::Result<Option<u64>> {
+        let change = self
+            .get(version)
+            .with_context(|| format!("config history version {version} not found"))?;
+
+        match &change.before {
+            Some(content) => {
+                if let Some(parent) = change.path.parent() {
+                    std::fs::create_dir_all(parent).with_context(|| {
+                        format!("failed to create directory: {}", parent.display())
+                    })?;
+                }
+                std::fs::write(&change.path, content).with_context(|| {
+                    format!("failed to restore file: {}", change.path.display())
+                })?;
+            }
+            None => match std::fs::remove_file(&change.path) {
+                Ok(()) => {}
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+                Err(error) => {
+                    return Err(error).with_context(|| {
+                        format!("failed to remove file: {}", change.path.display())
+                    });
+                }
+            },
+        }
+
+        Ok(self.record(
+            &change.path,
+            change.kind,
+            change.agent_id.as_deref(),
+            Some(version),
+        ))
+    }
+
+    fn record(
+        &self,
+        path: &Path,
+        kind: ConfigFileKind,
+        agent_id: Option<&str>,
+        reverted_version: Option<u64>,
+    ) -> Option<u64> {
+        let current = std::fs::read_to_string(path).ok();
+        let key = normalize(path);
+
+        let mut state = self.state.lock().expect("changelog lock poisoned");
+        let before = state.known.get(&key).cloned().flatten();
+        if before == current {
+            return None;
+        }
+
+        let (lines_added, lines_removed) = count_changed_lines(
+            before.as_deref().unwrap_or_default(),
+            current.as_deref().unwrap_or_default(),
+        );
+        let version = state.entries.last().map_or(1, |entry| entry.version + 1);
+        let change = ConfigChange {
+            version,
+            changed_at: Utc::now(),
+            kind,
+            path: path.to_path_buf(),
+            agent_id: agent_id.map(str::to_string),
+            before,
+            after: current.clone(),
+            lines_added,
+            lines_removed,
+            reverted_version,
+        };
+
+        state.known.insert(key, current);
+        state.entries.push(change);
+
+        if state.entries.len() > MAX_ENTRIES {
+            let excess = state.entries.len() - MAX_ENTRIES;
+            state.entries.drain(..excess);
+            if let Err(error) = self.rewrite(&state.entries) {
+                tracing::warn!(%error, "failed to compact config history");
+            }
+        } else if let Some(change) = state.entries.last()
+            && let Err(error) = self.append(change)
+        {
+            tracing::warn!(%error, "failed to persist config history entry");
+        }
+
+        tracing::info!(
+            version,
+            kind = ?kind,
+            path = %path.display(),
+            lines_added,
+            lines_removed,
+            "recorded config change"
+        );
+
+        Some(version)
+    }
+
+    fn append(&self, change: &ConfigChange) -> anyhow::Result<()> {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_path)
+            .with_context(|| format!("failed to open {}", self.log_path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(change)?)?;
+        Ok(())
+    }
+
+    fn rewrite(&self, entries: &[ConfigChange]) -> anyhow::Result<()> {
+        let mut content = String::new();
+        for entry in entries {
+            content.push_str(&serde_json::to_string(entry)?);
+            content.push('\n');
+        }
+        let tmp_path = self.log_path.with_extension("jsonl.tmp");
+        std::fs::write(&tmp_path, content)
+            .with_context(|| format!("failed to write {}", tmp_path.display()))?;
+        std::fs::rename(&tmp_path, &self.log_path)
+            .with_context(|| format!("failed to replace {}", self.log_path.display()))?;
+        Ok(())
+    }
+}
+
+/// Resolve symlinks in the parent directory so watcher event paths and
+/// configured paths compare equal. The file itself may not exist.
+fn normalize(path: &Path) -> PathBuf {
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => parent
+            .canonicalize()
+            .map(|parent| parent.join(name))
+            .unwrap_or_else(|_| path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffOp {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Align two texts line by line. Common leading and trailing lines are
+/// matched directly; the middle is aligned by longest common subsequence.
+fn diff_lines<'a>(before: &'a str, after: &'a str) -> Vec<(DiffOp, &'a str)> {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut ops: Vec<(DiffOp, &str)> = old[..prefix]
+        .iter()
+        .map(|line| (DiffOp::Equal, *line))
+        .collect();
+
+    if old_middle.len() * new_middle.len() > MAX_DIFF_CELLS {
+        ops.extend(old_middle.iter().map(|line| (DiffOp::Delete, *line)));
+        ops.extend(new_middle.iter().map(|line| (DiffOp::Insert, *line)));
+    } else {
+        // lcs[i][j] = length of the LCS of old_middle[i..] and new_middle[j..].
+        let width = new_middle.len() + 1;
+        let mut lcs = vec![0usize; (old_middle.len() + 1) * width];
+        for i in (0..old_middle.len()).rev() {
+            for j in (0..new_middle.len()).rev() {
+                lcs[i * width + j] = if old_middle[i] == new_middle[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < old_middle.len() && j < new_middle.len() {
+            if old_middle[i] == new_middle[j] {
+                ops.push((DiffOp::Equal, old_middle[i]));
+                i += 1;
+                j += 1;
+            } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+                ops.push((DiffOp::Delete, old_middle[i]));
+                i += 1;
+            } else {
+                ops.push((DiffOp::Insert, new_middle[j]));
+                j += 1;
+            }
+        }
+        ops.extend(old_middle[i..].iter().map(|line| (DiffOp::Delete, *line)));
+        ops.extend(new_middle[j..].iter().map(|line| (DiffOp::Insert, *line)));
+    }
+
+    ops.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| (DiffOp::Equal, *line)),
+    );
+    ops
+}
+
+fn count_changed_lines(before: &str, after: &str) -> (usize, usize) {
+    diff_lines(before, after)
+        .iter()
+        .fold((0, 0), |(added, removed), (op, _)| match op {
+            DiffOp::Insert => (added + 1, removed),
+            DiffOp::Delete => (added, removed + 1),
+            DiffOp::Equal => (added, removed),
+        })
+}
+
+/// Render a unified-style diff with [`DIFF_CONTEXT`] lines of context.
+fn line_diff(before: &str, after: &str) -> String {
+    let ops = diff_lines(before, after);
+
+    // 1-based line numbers in the old and new text at each op.
+    let mut positions = Vec::with_capacity(ops.len());
+    let (mut old_line, mut new_line) = (1, 1);
+    for (op, _) in &ops {
+        positions.push((old_line, new_line));
+        match op {
+            DiffOp::Equal => {
+                old_line += 1;
+                new_line += 1;
+            }
+            DiffOp::Delete => old_line += 1,
+            DiffOp::Insert => new_line += 1,
+        }
+    }
+
+    let changed: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (op, _))| *op != DiffOp::Equal)
+        .map(|(index, _)| index)
+        .collect();
+
+    let mut output = String::new();
+    let mut cursor = 0;
+    while cursor < changed.len() {
+        let mut end = cursor;
+        while end + 1 < changed.len() && changed[end + 1] - changed[end] <= DIFF_CONTEXT * 2 + 1 {
+            end += 1;
+        }
+
+        let start = changed[cursor].saturating_sub(DIFF_CONTEXT);
+        let stop = (changed[end] + DIFF_CONTEXT + 1).min(ops.len());
+        let hunk = &ops[start..stop];
+        let old_count = hunk.iter().filter(|(op, _)| *op != DiffOp::Insert).count();
+        let new_count = hunk.iter().filter(|(op, _)| *op != DiffOp::Delete).count();
+        let (old_start, new_start) = positions[start];
+
+        output.push_str(&format!(
+            "@@ -{old_start},{old_count} +{new_start},{new_count} @@\n"
+        ));
+        for (op, line) in hunk {
+            let marker = match op {
+                DiffOp::Equal => ' ',
+                DiffOp::Delete => '-',
+                DiffOp::Insert => '+',
+            };
+            output.push(marker);
+            output.push_str(line);
+            output.push('\n');
+        }
+
+        cursor = end + 1;
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_diff_shows_changed_lines_with_context() {
+        let before = "a\nb\nc\nd\ne\nf\ng\nh\n";
+        let after = "a\nb\nc\nd\nE\nf\ng\nh\ni\n";
+        assert_eq!(
+            line_diff(before, after),
+            "@@ -2,7 +2,8 @@\n b\n c\n d\n-e\n+E\n f\n g\n h\n+i\n"
+        );
+        assert_eq!(count_changed_lines(before, after), (2, 1));
+        assert_eq!(line_diff(before, before), "");
+    }
+
+    #[test]
+    fn observe_records_changes_and_revert_restores() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[llm]\nmodel = \"a\"\n").unwrap();
+
+        let changelog = ConfigChangelog::open(dir.path());
+        changelog.track(&path);
+        assert_eq!(changelog.observe(&path, ConfigFileKind::Config, None), None);
+
+        std::fs::write(&path, "[llm]\nmodel = \"b\"\n").unwrap();
+        let version = changelog
+            .observe(&path, ConfigFileKind::Config, None)
+            .unwrap();
+        assert_eq!(version, 1);
+
+        let revert_version = changelog.revert(version).unwrap().unwrap();
+        assert_eq!(revert_version, 2);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "[llm]\nmodel = \"a\"\n"
+        );
+        // The watcher seeing the reverted file must not record it again.
+        assert_eq!(changelog.observe(&path, ConfigFileKind::Config, None), None);
+
+        let reopened = ConfigChangelog::open(dir.path());
+        let history = reopened.list(None, 10);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].reverted_version, Some(1));
+    }
+
+    #[test]
+    fn reverting_a_created_file_removes_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let skill = dir.path().join("SKILL.md");
+
+        let changelog = ConfigChangelog::open(dir.path());
+        std::fs::write(&skill, "# skill\n").unwrap();
+        let version = changelog
+            .observe(&skill, ConfigFileKind::Skill, Some("main"))
+            .unwrap();
+
+        changelog.revert(version).unwrap();
+        assert!(!skill.exists());
+        assert_eq!(changelog.list(Some("other"), 10).len(), 0);
+        assert_eq!(changelog.list(Some("main"), 10).len(), 2);
+    }
+}
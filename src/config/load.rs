@@ -11,30 +11,148 @@ use super::providers::{
 };
 use super::toml_schema::*;
 use super::{
-    AgentConfig, ApiConfig, ApiType, Binding, BrowserConfig, ChannelConfig, ClosePolicy,
-    CoalesceConfig, CompactionConfig, Config, CortexConfig, CronDef, DefaultsConfig, DiscordConfig,
-    DiscordInstanceConfig, EmailConfig, EmailInstanceConfig, GroupDef, HumanDef, IngestionConfig,
-    LinkDef, LlmConfig, McpServerConfig, McpTransport, MemoryPersistenceConfig, MessagingConfig,
-    MetricsConfig, OpenCodeConfig, ProjectsConfig, ProviderConfig, SignalConfig,
-    SignalInstanceConfig, SlackCommandConfig, SlackConfig, SlackInstanceConfig, TelegramConfig,
-    TelegramInstanceConfig, TelemetryConfig, TwitchConfig, TwitchInstanceConfig, WarmupConfig,
-    WebhookConfig, normalize_adapter, validate_named_messaging_adapters,
+    AgentConfig, ApiAuthConfig, ApiConfig, ApiKeyConfig, ApiRateLimitConfig, ApiRole, ApiType,
+    ArchiveConfig, ArchivedMessages, Binding, BranchCacheConfig, BrowserConfig, BudgetConfig,
+    ChannelConfig, ChunkingStrategy, ClosePolicy, ClusterConfig, CoalesceConfig, CompactionConfig,
+    Config, ContainerConfig, CortexConfig, CronDef, DefaultsConfig, DiscordConfig,
+    DiscordInstanceConfig, DiscordVoiceConfig, EmailConfig, EmailInstanceConfig, EmbeddingConfig,
+    EmbeddingProviderKind, GitConfig, GithubConfig, GroupDef, HumanDef, IngestionConfig, IrcConfig,
+    LinkDef, LlmConfig, MatrixConfig, McpServerConfig, McpTransport, MemoryFtsConfig,
+    MemoryPersistenceConfig, MessagingConfig, MetricsConfig, ModerationAction, ModerationConfig,
+    ModerationRule, ModerationStrictness, OAuthProviderConfig, OpenCodeConfig, PrefetchConfig,
+    ProjectsConfig, ProviderConfig, ProviderQuota, RateLimitRule, ResponseCacheConfig,
+    ResponsePace, RouteRateLimit, SignalConfig, SignalInstanceConfig, SlackCommandConfig,
+    SlackConfig, SlackInstanceConfig, StorageConfig, TeamDef, TeamMemberDef, TelegramConfig,
+    TelegramInstanceConfig, TelemetryConfig, TranscriptionConfig, TurnInterrupt, TwitchConfig,
+    TwitchInstanceConfig, WarmupConfig, WebhookConfig, normalize_adapter,
+    validate_named_messaging_adapters,
 };
 use crate::error::{ConfigError, Result};
+use crate::memory::MemoryScope;
 
 use anyhow::Context as _;
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
-/// Resolve a value that might be an "env:VAR_NAME" or "secret:NAME" reference.
+/// Build the accepted API key list from `[api.auth]` plus the legacy
+/// `[api] auth_token`.
+fn resolve_api_auth(auth_token: Option<&str>, toml: TomlApiAuthConfig) -> Result<ApiAuthConfig> {
+    let mut keys = Vec::new();
+    if let Some(token) = auth_token {
+        keys.push(ApiKeyConfig {
+            name: "auth_token".to_string(),
+            key: token.to_string(),
+            expires_at: None,
+            role: ApiRole::Admin,
+        });
+    }
+
+    for (index, entry) in toml.keys.into_iter().enumerate() {
+        let name = entry.name.unwrap_or_else(|| format!("key-{}", index + 1));
+        let expires_at = entry
+            .expires_at
+            .map(|value| {
+                chrono::DateTime::parse_from_rfc3339(&value)
+                    .map(|timestamp| timestamp.with_timezone(&chrono::Utc))
+                    .map_err(|error| {
+                        ConfigError::Invalid(format!(
+                            "api.auth key '{name}' has invalid expires_at '{value}': {error}"
+                        ))
+                    })
+            })
+            .transpose()?;
+        let role = match entry.role.as_deref() {
+            None => ApiRole::Admin,
+            Some(value) => ApiRole::parse(value).ok_or_else(|| {
+                ConfigError::Invalid(format!(
+                    "api.auth key '{name}' has invalid role '{value}' \
+                     (expected admin, operator, or viewer)"
+                ))
+            })?,
+        };
+        // Unresolvable `env:` references are skipped rather than accepted as
+        // literal keys.
+        let Some(key) = resolve_env_value(&entry.key).filter(|key| !key.is_empty()) else {
+            tracing::warn!(name = %name, "api.auth key did not resolve, skipping");
+            continue;
+        };
+        keys.push(ApiKeyConfig {
+            name,
+            key,
+            expires_at,
+            role,
+        });
+    }
+
+    Ok(ApiAuthConfig { keys })
+}
+
+fn resolve_api_rate_limit(toml: Option<TomlApiRateLimitConfig>) -> Result<ApiRateLimitConfig> {
+    let Some(toml) = toml else {
+        return Ok(ApiRateLimitConfig::default());
+    };
+
+    let defaults = RateLimitRule::default();
+    let default = RateLimitRule {
+        requests_per_minute: toml
+            .requests_per_minute
+            .unwrap_or(defaults.requests_per_minute),
+        burst: toml.burst.unwrap_or(defaults.burst),
+    };
+    let routes = toml
+        .routes
+        .into_iter()
+        .map(|route| RouteRateLimit {
+            path: format!("/{}", route.path.trim_matches('/')),
+            rule: RateLimitRule {
+                requests_per_minute: route.requests_per_minute,
+                burst: route.burst.unwrap_or(route.requests_per_minute.max(1)),
+            },
+        })
+        .collect::<Vec<_>>();
+
+    for rule in std::iter::once(&default).chain(routes.iter().map(|route| &route.rule)) {
+        if rule.requests_per_minute == 0 || rule.burst == 0 {
+            return Err(ConfigError::Invalid(
+                "api.rate_limit requests_per_minute and burst must be >= 1".to_string(),
+            )
+            .into());
+        }
+    }
+
+    Ok(ApiRateLimitConfig {
+        enabled: toml.enabled,
+        default,
+        routes,
+    })
+}
+
+/// Resolve a value that might be an "env:VAR_NAME", "secret:NAME", or
+/// "enc:v1:..." reference.
 ///
-/// Three resolution modes:
+/// Four resolution modes:
 /// - `secret:NAME` — look up from the secrets store (if available).
 /// - `env:VAR_NAME` — read from system environment variable.
+/// - `enc:v1:...` — decrypt with the config key (if available).
 /// - Anything else — literal value.
 pub(crate) fn resolve_env_value(value: &str) -> Option<String> {
-    if let Some(alias) = value.strip_prefix("secret:") {
+    if crate::secrets::config_cipher::is_encrypted_value(value) {
+        let guard = RESOLVE_CONFIG_CIPHER.load();
+        match (*guard).as_ref() {
+            Some(cipher) => match cipher.decrypt_value(value) {
+                Ok(plaintext) => Some(plaintext),
+                Err(error) => {
+                    tracing::warn!(%error, "failed to decrypt encrypted config value");
+                    None
+                }
+            },
+            None => {
+                tracing::warn!("config contains encrypted values but no config key is loaded");
+                None
+            }
+        }
+    } else if let Some(alias) = value.strip_prefix("secret:") {
         let guard = RESOLVE_SECRETS_STORE.load();
         match (*guard).as_ref() {
             Some(store) => match store.get(alias) {
@@ -53,6 +171,20 @@ pub(crate) fn resolve_env_value(value: &str) -> Option<String> {
     }
 }
 
+/// Read an environment variable, falling back to the secret of the same name
+/// in the secrets store. Provider keys that `config.toml` doesn't mention are
+/// looked up this way, so a key stored under its canonical name (e.g.
+/// `ANTHROPIC_API_KEY`) needs no config entry.
+fn env_or_stored_secret(name: &str) -> Option<String> {
+    std::env::var(name).ok().or_else(|| {
+        let guard = RESOLVE_SECRETS_STORE.load();
+        (**guard)
+            .as_ref()
+            .and_then(|store| store.get(name).ok())
+            .map(|secret| secret.expose().to_string())
+    })
+}
+
 /// Process-wide reference to the secrets store for use during config resolution.
 ///
 /// Uses `ArcSwap` so it is accessible from any thread (file watcher, API
@@ -66,6 +198,30 @@ pub fn set_resolve_secrets_store(store: std::sync::Arc<crate::secrets::store::Se
     RESOLVE_SECRETS_STORE.store(std::sync::Arc::new(Some(store)));
 }
 
+/// Process-wide config key used to decrypt inline `enc:v1:` values.
+static RESOLVE_CONFIG_CIPHER: std::sync::LazyLock<
+    arc_swap::ArcSwap<Option<std::sync::Arc<crate::secrets::config_cipher::ConfigCipher>>>,
+> = std::sync::LazyLock::new(|| arc_swap::ArcSwap::from_pointee(None));
+
+/// Set the config cipher for config resolution (process-wide, any thread).
+pub fn set_resolve_config_cipher(
+    cipher: std::sync::Arc<crate::secrets::config_cipher::ConfigCipher>,
+) {
+    RESOLVE_CONFIG_CIPHER.store(std::sync::Arc::new(Some(cipher)));
+}
+
+/// The active config cipher, if a config key has been loaded.
+///
+/// When present, credentials written to config.toml through the API are
+/// stored encrypted.
+pub fn resolve_config_cipher() -> Option<std::sync::Arc<crate::secrets::config_cipher::ConfigCipher>>
+{
+    (**RESOLVE_CONFIG_CIPHER.load()).clone()
+}
+
+/// Default seconds between background provider health probes.
+const DEFAULT_HEALTH_PROBE_INTERVAL_SECS: u64 = 300;
+
 /// Known top-level keys in config.toml (must match `TomlConfig` field names).
 const KNOWN_TOP_LEVEL_KEYS: &[&str] = &[
     "llm",
@@ -73,6 +229,7 @@ const KNOWN_TOP_LEVEL_KEYS: &[&str] = &[
     "agents",
     "links",
     "groups",
+    "teams",
     "humans",
     "messaging",
     "bindings",
@@ -127,6 +284,110 @@ fn parse_close_policy(value: Option<&str>) -> Option<ClosePolicy> {
     }
 }
 
+fn parse_response_pace(value: Option<&str>) -> Option<ResponsePace> {
+    let value = value?;
+    let pace = ResponsePace::parse(value);
+    if pace.is_none() {
+        tracing::warn!(
+            value,
+            "unknown channel pace value, expected one of: instant, casual, relaxed"
+        );
+    }
+    pace
+}
+
+fn parse_turn_interrupt(value: Option<&str>) -> Option<TurnInterrupt> {
+    let value = value?;
+    let interrupt = TurnInterrupt::parse(value);
+    if interrupt.is_none() {
+        tracing::warn!(
+            value,
+            "unknown channel interrupt value, expected one of: queue, sender, any"
+        );
+    }
+    interrupt
+}
+
+fn parse_memory_scope(value: Option<&str>) -> Option<MemoryScope> {
+    let value = value?;
+    let scope = MemoryScope::parse(value);
+    if scope.is_none() {
+        tracing::warn!(
+            value,
+            "unknown channel memory_scope value, expected one of: global, channel"
+        );
+    }
+    scope
+}
+
+fn parse_archived_messages(value: Option<&str>) -> Option<ArchivedMessages> {
+    let value = value?;
+    let mode = ArchivedMessages::parse(value);
+    if mode.is_none() {
+        tracing::warn!(
+            value,
+            "unknown archive.raw_messages value, expected one of: cold, delete"
+        );
+    }
+    mode
+}
+
+fn parse_moderation_strictness(field: &str, value: Option<&str>) -> Option<ModerationStrictness> {
+    let value = value?;
+    let strictness = ModerationStrictness::parse(value);
+    if strictness.is_none() {
+        tracing::warn!(
+            field,
+            value,
+            "unknown moderation strictness, expected one of: off, lenient, standard, strict"
+        );
+    }
+    strictness
+}
+
+fn parse_chunking_strategy(value: Option<&str>) -> Option<ChunkingStrategy> {
+    match value? {
+        "auto" => Some(ChunkingStrategy::Auto),
+        "lines" => Some(ChunkingStrategy::Lines),
+        "headings" => Some(ChunkingStrategy::Headings),
+        "window" => Some(ChunkingStrategy::Window),
+        "sentences" => Some(ChunkingStrategy::Sentences),
+        "code" => Some(ChunkingStrategy::Code),
+        other => {
+            tracing::warn!(
+                value = other,
+                "unknown ingestion chunking value, expected one of: auto, lines, headings, window, sentences, code"
+            );
+            None
+        }
+    }
+}
+
+/// Resolve `[llm.embedding]`. Unlike most enum-valued settings, an unknown
+/// provider is an error: silently falling back to the local model would
+/// re-embed every memory at a different dimension.
+fn resolve_embedding_config(toml: TomlEmbeddingConfig) -> Result<EmbeddingConfig> {
+    let provider = match toml.provider.as_deref() {
+        None => EmbeddingProviderKind::default(),
+        Some(value) => EmbeddingProviderKind::parse(value).ok_or_else(|| {
+            ConfigError::Invalid(format!(
+                "unknown llm.embedding.provider '{value}', expected one of: fastembed, openai, gemini, ollama"
+            ))
+        })?,
+    };
+    if toml.dimensions == Some(0) {
+        return Err(ConfigError::Invalid("llm.embedding.dimensions must be >= 1".into()).into());
+    }
+
+    Ok(EmbeddingConfig {
+        provider,
+        model: toml.model,
+        dimensions: toml.dimensions,
+        base_url: toml.base_url,
+        api_key: toml.api_key.as_deref().and_then(resolve_env_value),
+    })
+}
+
 /// Resolve the effective close policy. When `persist_session` is enabled and no
 /// explicit `close_policy` was provided, default to `Detach` so browser tabs and
 /// cookies survive across workers.
@@ -181,6 +442,9 @@ impl CortexConfig {
             bulletin_max_turns: overrides
                 .bulletin_max_turns
                 .unwrap_or(defaults.bulletin_max_turns),
+            bulletin_pin_max_words: overrides
+                .bulletin_pin_max_words
+                .unwrap_or(defaults.bulletin_pin_max_words),
             maintenance_interval_secs,
             maintenance_decay_rate: overrides
                 .maintenance_decay_rate
@@ -206,12 +470,359 @@ impl CortexConfig {
             association_max_per_pass: overrides
                 .association_max_per_pass
                 .unwrap_or(defaults.association_max_per_pass),
+            fact_extraction_enabled: overrides
+                .fact_extraction_enabled
+                .unwrap_or(defaults.fact_extraction_enabled),
+            fact_extraction_interval_secs: overrides
+                .fact_extraction_interval_secs
+                .unwrap_or(defaults.fact_extraction_interval_secs)
+                .max(60),
+            fact_extraction_max_messages: overrides
+                .fact_extraction_max_messages
+                .unwrap_or(defaults.fact_extraction_max_messages)
+                .max(1),
         };
         config.validate_maintenance_bounds()?;
         Ok(config)
     }
 }
 
+impl ArchiveConfig {
+    fn resolve(overrides: TomlArchiveConfig, defaults: ArchiveConfig) -> Result<ArchiveConfig> {
+        let segment_size = overrides.segment_size.unwrap_or(defaults.segment_size);
+        if segment_size == 0 {
+            return Err(ConfigError::Invalid(
+                "archive.segment_size must be greater than 0".to_string(),
+            )
+            .into());
+        }
+
+        Ok(ArchiveConfig {
+            max_messages: overrides.max_messages.unwrap_or(defaults.max_messages),
+            max_age_days: overrides.max_age_days.unwrap_or(defaults.max_age_days),
+            keep_recent: overrides.keep_recent.unwrap_or(defaults.keep_recent),
+            segment_size,
+            raw_messages: parse_archived_messages(overrides.raw_messages.as_deref())
+                .unwrap_or(defaults.raw_messages),
+            check_interval_secs: overrides
+                .check_interval_secs
+                .unwrap_or(defaults.check_interval_secs),
+        })
+    }
+}
+
+impl ModerationConfig {
+    fn resolve(
+        overrides: TomlModerationConfig,
+        defaults: &ModerationConfig,
+    ) -> Result<ModerationConfig> {
+        let rules = match overrides.rules {
+            Some(rules) => rules
+                .into_iter()
+                .enumerate()
+                .map(|(index, rule)| ModerationRule::resolve(index, rule))
+                .collect::<Result<Vec<_>>>()?,
+            None => defaults.rules.clone(),
+        };
+
+        Ok(ModerationConfig {
+            strictness: parse_moderation_strictness(
+                "moderation.strictness",
+                overrides.strictness.as_deref(),
+            )
+            .unwrap_or(defaults.strictness),
+            rules,
+            classifier_model: overrides
+                .classifier_model
+                .filter(|model| !model.trim().is_empty())
+                .or_else(|| defaults.classifier_model.clone()),
+            classifier_level: parse_moderation_strictness(
+                "moderation.classifier_level",
+                overrides.classifier_level.as_deref(),
+            )
+            .unwrap_or(defaults.classifier_level),
+            block_notice: overrides
+                .block_notice
+                .or_else(|| defaults.block_notice.clone()),
+        })
+    }
+}
+
+impl ModerationRule {
+    fn resolve(index: usize, rule: TomlModerationRule) -> Result<ModerationRule> {
+        let name = rule.name.unwrap_or_else(|| format!("rule-{}", index + 1));
+        let pattern = match (rule.pattern, rule.keywords) {
+            (Some(pattern), None) => pattern,
+            (None, Some(keywords)) if !keywords.is_empty() => {
+                let alternatives: Vec<String> = keywords
+                    .iter()
+                    .map(|keyword| regex::escape(keyword))
+                    .collect();
+                format!(r"(?i)\b(?:{})\b", alternatives.join("|"))
+            }
+            _ => {
+                return Err(ConfigError::Invalid(format!(
+                    "moderation rule '{name}' needs exactly one of `pattern` or `keywords`"
+                ))
+                .into());
+            }
+        };
+        let pattern = regex::Regex::new(&pattern).map_err(|error| {
+            ConfigError::Invalid(format!(
+                "moderation rule '{name}' has an invalid pattern: {error}"
+            ))
+        })?;
+
+        let action = match rule.action.as_deref() {
+            None => ModerationAction::Flag,
+            Some(value) => ModerationAction::parse(value).ok_or_else(|| {
+                ConfigError::Invalid(format!(
+                    "moderation rule '{name}' has unknown action '{value}', expected one of: block, flag, rewrite"
+                ))
+            })?,
+        };
+
+        Ok(ModerationRule {
+            level: parse_moderation_strictness("moderation.rules.level", rule.level.as_deref())
+                .unwrap_or(ModerationStrictness::Standard),
+            name,
+            pattern,
+            action,
+            replacement: rule.replacement.unwrap_or_else(|| "[removed]".to_string()),
+        })
+    }
+}
+
+impl StorageConfig {
+    fn resolve(overrides: TomlStorageConfig, defaults: StorageConfig) -> Result<StorageConfig> {
+        let warn_percent = overrides.warn_percent.unwrap_or(defaults.warn_percent);
+        if !(1..=100).contains(&warn_percent) {
+            return Err(ConfigError::Invalid(
+                "storage.warn_percent must be between 1 and 100".to_string(),
+            )
+            .into());
+        }
+        let db_vacuum_min_free_percent = overrides
+            .db_vacuum_min_free_percent
+            .unwrap_or(defaults.db_vacuum_min_free_percent);
+        if db_vacuum_min_free_percent > 100 {
+            return Err(ConfigError::Invalid(
+                "storage.db_vacuum_min_free_percent must be between 0 and 100".to_string(),
+            )
+            .into());
+        }
+
+        Ok(StorageConfig {
+            // An explicit 0 clears an inherited quota.
+            quota_mb: match overrides.quota_mb {
+                Some(0) => None,
+                Some(quota_mb) => Some(quota_mb),
+                None => defaults.quota_mb,
+            },
+            warn_percent,
+            auto_cleanup: overrides.auto_cleanup.unwrap_or(defaults.auto_cleanup),
+            check_interval_secs: overrides
+                .check_interval_secs
+                .unwrap_or(defaults.check_interval_secs),
+            retention_days: overrides.retention_days.unwrap_or(defaults.retention_days),
+            optimize_interval_secs: overrides
+                .optimize_interval_secs
+                .unwrap_or(defaults.optimize_interval_secs),
+            optimize_idle_secs: overrides
+                .optimize_idle_secs
+                .unwrap_or(defaults.optimize_idle_secs),
+            db_maintenance_interval_secs: overrides
+                .db_maintenance_interval_secs
+                .unwrap_or(defaults.db_maintenance_interval_secs),
+            db_vacuum_min_free_percent,
+            db_wal_autocheckpoint_pages: overrides
+                .db_wal_autocheckpoint_pages
+                .unwrap_or(defaults.db_wal_autocheckpoint_pages),
+            db_journal_size_limit_mb: overrides
+                .db_journal_size_limit_mb
+                .unwrap_or(defaults.db_journal_size_limit_mb),
+            encrypt_at_rest: overrides
+                .encrypt_at_rest
+                .unwrap_or(defaults.encrypt_at_rest),
+        })
+    }
+}
+
+/// Tokenizers accepted by LanceDB's inverted index.
+const FTS_TOKENIZERS: &[&str] = &["simple", "whitespace", "raw", "ngram"];
+
+/// Languages with stemmers and stop-word lists in the FTS tokenizer.
+const FTS_LANGUAGES: &[&str] = &[
+    "Arabic",
+    "Danish",
+    "Dutch",
+    "English",
+    "Finnish",
+    "French",
+    "German",
+    "Greek",
+    "Hungarian",
+    "Italian",
+    "Norwegian",
+    "Portuguese",
+    "Romanian",
+    "Russian",
+    "Spanish",
+    "Swedish",
+    "Tamil",
+    "Turkish",
+];
+
+impl MemoryFtsConfig {
+    fn resolve(overrides: TomlMemoryFtsConfig, defaults: &MemoryFtsConfig) -> Result<Self> {
+        let tokenizer = match overrides.tokenizer {
+            Some(tokenizer) => {
+                let tokenizer = tokenizer.trim().to_lowercase();
+                if !FTS_TOKENIZERS.contains(&tokenizer.as_str()) {
+                    return Err(ConfigError::Invalid(format!(
+                        "memory_fts.tokenizer must be one of {}, got '{tokenizer}'",
+                        FTS_TOKENIZERS.join(", ")
+                    ))
+                    .into());
+                }
+                Some(tokenizer)
+            }
+            None => defaults.tokenizer.clone(),
+        };
+
+        let language = match overrides.language {
+            Some(language) => {
+                let language = language.trim();
+                let Some(canonical) = FTS_LANGUAGES
+                    .iter()
+                    .find(|candidate| candidate.eq_ignore_ascii_case(language))
+                else {
+                    return Err(ConfigError::Invalid(format!(
+                        "memory_fts.language '{language}' is not supported; expected one of {}",
+                        FTS_LANGUAGES.join(", ")
+                    ))
+                    .into());
+                };
+                Some(canonical.to_string())
+            }
+            None => defaults.language.clone(),
+        };
+
+        Ok(MemoryFtsConfig {
+            tokenizer,
+            language,
+            stemming: overrides.stemming.or(defaults.stemming),
+            remove_stop_words: overrides.remove_stop_words.or(defaults.remove_stop_words),
+            ascii_folding: overrides.ascii_folding.or(defaults.ascii_folding),
+        })
+    }
+}
+
+impl DiscordVoiceConfig {
+    fn resolve(toml: TomlDiscordVoiceConfig) -> Self {
+        let defaults = Self::default();
+        DiscordVoiceConfig {
+            enabled: toml.enabled,
+            tts_url: toml
+                .tts_url
+                .map(|url| url.trim().to_string())
+                .filter(|url| !url.is_empty()),
+            tts_api_key: toml.tts_api_key.as_deref().and_then(resolve_env_value),
+            tts_model: toml.tts_model.unwrap_or(defaults.tts_model),
+            tts_voice: toml.tts_voice.unwrap_or(defaults.tts_voice),
+            max_spoken_chars: toml.max_spoken_chars.unwrap_or(defaults.max_spoken_chars),
+            silence_ms: toml.silence_ms.unwrap_or(defaults.silence_ms).max(100),
+            min_utterance_ms: toml.min_utterance_ms.unwrap_or(defaults.min_utterance_ms),
+            max_utterance_secs: toml
+                .max_utterance_secs
+                .unwrap_or(defaults.max_utterance_secs)
+                .max(1),
+        }
+    }
+}
+
+impl TranscriptionConfig {
+    fn resolve(overrides: TomlTranscriptionConfig, defaults: &TranscriptionConfig) -> Self {
+        // Empty strings clear inherited values.
+        let non_empty = |value: String| {
+            let value = value.trim().to_string();
+            (!value.is_empty()).then_some(value)
+        };
+        TranscriptionConfig {
+            url: match overrides.url {
+                Some(url) => non_empty(url),
+                None => defaults.url.clone(),
+            },
+            api_key: match overrides.api_key {
+                Some(key) => resolve_env_value(&key),
+                None => defaults.api_key.clone(),
+            },
+            model: overrides
+                .model
+                .and_then(non_empty)
+                .unwrap_or_else(|| defaults.model.clone()),
+            language: match overrides.language {
+                Some(language) => non_empty(language),
+                None => defaults.language.clone(),
+            },
+            max_file_mb: overrides.max_file_mb.unwrap_or(defaults.max_file_mb),
+        }
+    }
+}
+
+impl BudgetConfig {
+    fn resolve(overrides: TomlBudgetConfig, defaults: &BudgetConfig) -> Result<BudgetConfig> {
+        let limit = |field: &str, value: Option<f64>, default: Option<f64>| -> Result<_> {
+            match value {
+                Some(value) if !value.is_finite() || value < 0.0 => Err(ConfigError::Invalid(
+                    format!("budget.{field} must be a non-negative amount, got {value}"),
+                )
+                .into()),
+                Some(value) => Ok(Some(value)),
+                None => Ok(default),
+            }
+        };
+
+        Ok(BudgetConfig {
+            daily_soft_usd: limit(
+                "daily_soft_usd",
+                overrides.daily_soft_usd,
+                defaults.daily_soft_usd,
+            )?,
+            daily_hard_usd: limit(
+                "daily_hard_usd",
+                overrides.daily_hard_usd,
+                defaults.daily_hard_usd,
+            )?,
+            channel_soft_usd: limit(
+                "channel_soft_usd",
+                overrides.channel_soft_usd,
+                defaults.channel_soft_usd,
+            )?,
+            channel_hard_usd: limit(
+                "channel_hard_usd",
+                overrides.channel_hard_usd,
+                defaults.channel_hard_usd,
+            )?,
+            worker_soft_usd: limit(
+                "worker_soft_usd",
+                overrides.worker_soft_usd,
+                defaults.worker_soft_usd,
+            )?,
+            worker_hard_usd: limit(
+                "worker_hard_usd",
+                overrides.worker_hard_usd,
+                defaults.worker_hard_usd,
+            )?,
+            // An empty string clears an inherited downgrade model.
+            downgrade_model: match overrides.downgrade_model {
+                Some(model) => Some(model.trim().to_string()).filter(|model| !model.is_empty()),
+                None => defaults.downgrade_model.clone(),
+            },
+        })
+    }
+}
+
 fn parse_otlp_headers(value: Option<String>) -> Result<HashMap<String, String>> {
     let Some(raw) = value else {
         return Ok(HashMap::new());
@@ -246,6 +857,82 @@ fn parse_otlp_headers(value: Option<String>) -> Result<HashMap<String, String>>
     Ok(headers)
 }
 
+/// Check team definitions against the configured agents.
+fn resolve_teams(toml: Vec<TomlTeamDef>, agents: &[AgentConfig]) -> Result<Vec<TeamDef>> {
+    let is_agent = |id: &str| agents.iter().any(|agent| agent.id == id);
+    let mut teams: Vec<TeamDef> = Vec::with_capacity(toml.len());
+    for team in toml {
+        if teams.iter().any(|existing| existing.id == team.id) {
+            return Err(ConfigError::Invalid(format!("duplicate team id '{}'", team.id)).into());
+        }
+        if !is_agent(&team.coordinator) {
+            return Err(ConfigError::Invalid(format!(
+                "team '{}' coordinator '{}' is not a configured agent",
+                team.id, team.coordinator
+            ))
+            .into());
+        }
+        if team.members.is_empty() {
+            return Err(ConfigError::Invalid(format!("team '{}' has no members", team.id)).into());
+        }
+        let mut members: Vec<TeamMemberDef> = Vec::with_capacity(team.members.len());
+        for member in team.members {
+            if !is_agent(&member.agent) {
+                return Err(ConfigError::Invalid(format!(
+                    "team '{}' member '{}' is not a configured agent",
+                    team.id, member.agent
+                ))
+                .into());
+            }
+            if member.agent == team.coordinator
+                || members
+                    .iter()
+                    .any(|existing| existing.agent == member.agent)
+            {
+                return Err(ConfigError::Invalid(format!(
+                    "team '{}' lists agent '{}' more than once",
+                    team.id, member.agent
+                ))
+                .into());
+            }
+            members.push(TeamMemberDef {
+                agent: member.agent,
+                specialty: member.specialty,
+            });
+        }
+        teams.push(TeamDef {
+            id: team.id,
+            name: team.name,
+            description: team.description,
+            coordinator: team.coordinator,
+            members,
+        });
+    }
+    Ok(teams)
+}
+
+/// Link each team's coordinator to its members so dispatched tasks go
+/// through the normal link channels. Existing links between the two are
+/// left as configured.
+fn add_team_links(teams: &[TeamDef], links: &mut Vec<LinkDef>) {
+    for team in teams {
+        for member in &team.members {
+            let linked = links.iter().any(|link| {
+                (link.from == team.coordinator && link.to == member.agent)
+                    || (link.from == member.agent && link.to == team.coordinator)
+            });
+            if !linked {
+                links.push(LinkDef {
+                    from: team.coordinator.clone(),
+                    to: member.agent.clone(),
+                    direction: "one_way".into(),
+                    kind: "hierarchical".into(),
+                });
+            }
+        }
+    }
+}
+
 fn parse_mcp_server_config(raw: TomlMcpServerConfig) -> Result<McpServerConfig> {
     if raw.name.trim().is_empty() {
         return Err(ConfigError::Invalid("mcp server name cannot be empty".into()).into());
@@ -433,6 +1120,14 @@ impl Config {
             zai_coding_plan_key: std::env::var("ZAI_CODING_PLAN_API_KEY").ok(),
             github_copilot_key: std::env::var("GITHUB_COPILOT_API_KEY").ok(),
             providers: HashMap::new(),
+            quotas: HashMap::new(),
+            oauth: HashMap::new(),
+            health_probe_interval_secs: std::env::var("SPACEBOT_PROVIDER_HEALTH_PROBE_SECS")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_HEALTH_PROBE_INTERVAL_SECS),
+            response_cache: ResponseCacheConfig::default(),
+            embedding: EmbeddingConfig::default(),
         };
 
         // Populate providers from env vars (same as from_toml does)
@@ -835,15 +1530,24 @@ impl Config {
             routing: Some(routing),
             max_concurrent_branches: None,
             max_concurrent_workers: None,
+            worker_batch_concurrency: None,
             max_turns: None,
             branch_max_turns: None,
             context_window: None,
             compaction: None,
             memory_persistence: None,
+            prefetch: None,
+            branch_cache: None,
             coalesce: None,
             ingestion: None,
             cortex: None,
             warmup: None,
+            storage: None,
+            archive: None,
+            moderation: None,
+            transcription: None,
+            budget: None,
+            memory_fts: None,
             browser: None,
             channel: None,
             mcp: None,
@@ -873,6 +1577,7 @@ impl Config {
                 kind: "hierarchical".into(),
             }],
             groups: Vec::new(),
+            teams: Vec::new(),
             humans: vec![HumanDef {
                 id: "admin".into(),
                 display_name: None,
@@ -895,6 +1600,7 @@ impl Config {
                     .unwrap_or_else(|_| "spacebot".into()),
                 sample_rate: 1.0,
             },
+            cluster: ClusterConfig::disabled(instance_dir),
         })
     }
 
@@ -953,77 +1659,76 @@ impl Config {
                 .anthropic_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("ANTHROPIC_API_KEY").ok())
+                .or_else(|| env_or_stored_secret("ANTHROPIC_API_KEY"))
                 .or_else(|| std::env::var("ANTHROPIC_AUTH_TOKEN").ok()),
             openai_key: toml
                 .llm
                 .openai_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("OPENAI_API_KEY").ok()),
+                .or_else(|| env_or_stored_secret("OPENAI_API_KEY")),
             openrouter_key: toml
                 .llm
                 .openrouter_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("OPENROUTER_API_KEY").ok()),
-            kilo_key: std::env::var("KILO_API_KEY")
-                .ok()
+                .or_else(|| env_or_stored_secret("OPENROUTER_API_KEY")),
+            kilo_key: env_or_stored_secret("KILO_API_KEY")
                 .or_else(|| toml.llm.kilo_key.as_deref().and_then(resolve_env_value)),
             zhipu_key: toml
                 .llm
                 .zhipu_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("ZHIPU_API_KEY").ok()),
+                .or_else(|| env_or_stored_secret("ZHIPU_API_KEY")),
             groq_key: toml
                 .llm
                 .groq_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("GROQ_API_KEY").ok()),
+                .or_else(|| env_or_stored_secret("GROQ_API_KEY")),
             together_key: toml
                 .llm
                 .together_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("TOGETHER_API_KEY").ok()),
+                .or_else(|| env_or_stored_secret("TOGETHER_API_KEY")),
             fireworks_key: toml
                 .llm
                 .fireworks_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("FIREWORKS_API_KEY").ok()),
+                .or_else(|| env_or_stored_secret("FIREWORKS_API_KEY")),
             deepseek_key: toml
                 .llm
                 .deepseek_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("DEEPSEEK_API_KEY").ok()),
+                .or_else(|| env_or_stored_secret("DEEPSEEK_API_KEY")),
             xai_key: toml
                 .llm
                 .xai_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("XAI_API_KEY").ok()),
+                .or_else(|| env_or_stored_secret("XAI_API_KEY")),
             mistral_key: toml
                 .llm
                 .mistral_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("MISTRAL_API_KEY").ok()),
+                .or_else(|| env_or_stored_secret("MISTRAL_API_KEY")),
             gemini_key: toml
                 .llm
                 .gemini_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("GEMINI_API_KEY").ok()),
+                .or_else(|| env_or_stored_secret("GEMINI_API_KEY")),
             ollama_key: toml
                 .llm
                 .ollama_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("OLLAMA_API_KEY").ok()),
+                .or_else(|| env_or_stored_secret("OLLAMA_API_KEY")),
             ollama_base_url: toml
                 .llm
                 .ollama_base_url
@@ -1035,8 +1740,8 @@ impl Config {
                 .opencode_zen_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("OPENCODE_ZEN_API_KEY").ok()),
-            opencode_go_key: std::env::var("OPENCODE_GO_API_KEY").ok().or_else(|| {
+                .or_else(|| env_or_stored_secret("OPENCODE_ZEN_API_KEY")),
+            opencode_go_key: env_or_stored_secret("OPENCODE_GO_API_KEY").or_else(|| {
                 toml.llm
                     .opencode_go_key
                     .as_deref()
@@ -1047,37 +1752,37 @@ impl Config {
                 .nvidia_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("NVIDIA_API_KEY").ok()),
+                .or_else(|| env_or_stored_secret("NVIDIA_API_KEY")),
             minimax_key: toml
                 .llm
                 .minimax_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("MINIMAX_API_KEY").ok()),
+                .or_else(|| env_or_stored_secret("MINIMAX_API_KEY")),
             minimax_cn_key: toml
                 .llm
                 .minimax_cn_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("MINIMAX_CN_API_KEY").ok()),
+                .or_else(|| env_or_stored_secret("MINIMAX_CN_API_KEY")),
             moonshot_key: toml
                 .llm
                 .moonshot_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("MOONSHOT_API_KEY").ok()),
+                .or_else(|| env_or_stored_secret("MOONSHOT_API_KEY")),
             zai_coding_plan_key: toml
                 .llm
                 .zai_coding_plan_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("ZAI_CODING_PLAN_API_KEY").ok()),
+                .or_else(|| env_or_stored_secret("ZAI_CODING_PLAN_API_KEY")),
             github_copilot_key: toml
                 .llm
                 .github_copilot_key
                 .as_deref()
                 .and_then(resolve_env_value)
-                .or_else(|| std::env::var("GITHUB_COPILOT_API_KEY").ok()),
+                .or_else(|| env_or_stored_secret("GITHUB_COPILOT_API_KEY")),
             providers: toml
                 .llm
                 .providers
@@ -1105,6 +1810,65 @@ impl Config {
                     ))
                 })
                 .collect::<anyhow::Result<_>>()?,
+            quotas: toml
+                .llm
+                .quota
+                .into_iter()
+                .map(|(provider_id, quota)| {
+                    (
+                        provider_id.to_lowercase(),
+                        ProviderQuota {
+                            monthly_usd: quota.monthly_usd,
+                            monthly_tokens: quota.monthly_tokens,
+                        },
+                    )
+                })
+                .collect(),
+            oauth: toml
+                .llm
+                .oauth
+                .into_iter()
+                .map(|(provider_id, config)| {
+                    let client_id = resolve_env_value(&config.client_id).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "failed to resolve OAuth client_id for provider '{}'",
+                            provider_id
+                        )
+                    })?;
+                    Ok((
+                        provider_id.to_lowercase(),
+                        OAuthProviderConfig {
+                            api_type: config.api_type,
+                            base_url: config.base_url,
+                            name: config.name,
+                            client_id,
+                            client_secret: config
+                                .client_secret
+                                .as_deref()
+                                .and_then(resolve_env_value),
+                            authorize_url: config.authorize_url,
+                            token_url: config.token_url,
+                            scopes: config.scopes,
+                            redirect_uri: config.redirect_uri,
+                            authorize_params: config.authorize_params,
+                        },
+                    ))
+                })
+                .collect::<anyhow::Result<_>>()?,
+            health_probe_interval_secs: toml
+                .llm
+                .health_probe_interval_secs
+                .unwrap_or(DEFAULT_HEALTH_PROBE_INTERVAL_SECS),
+            response_cache: {
+                let defaults = ResponseCacheConfig::default();
+                let cache = toml.llm.response_cache;
+                ResponseCacheConfig {
+                    enabled: cache.enabled.unwrap_or(defaults.enabled),
+                    ttl_secs: cache.ttl_secs.unwrap_or(defaults.ttl_secs),
+                    max_entries: cache.max_entries.unwrap_or(defaults.max_entries),
+                }
+            },
+            embedding: resolve_embedding_config(toml.llm.embedding)?,
         };
 
         // Detect if the Anthropic key came from ANTHROPIC_AUTH_TOKEN (proxy auth).
@@ -1112,7 +1876,7 @@ impl Config {
         // ANTHROPIC_AUTH_TOKEN (in that priority order). We only set use_bearer_auth
         // if AUTH_TOKEN was the actual source.
         let anthropic_from_auth_token = toml_llm_anthropic_key_was_none
-            && std::env::var("ANTHROPIC_API_KEY").is_err()
+            && env_or_stored_secret("ANTHROPIC_API_KEY").is_none()
             && std::env::var("ANTHROPIC_AUTH_TOKEN").is_ok();
 
         if let Some(anthropic_key) = llm.anthropic_key.clone() {
@@ -1404,6 +2168,10 @@ impl Config {
                 .defaults
                 .max_concurrent_workers
                 .unwrap_or(base_defaults.max_concurrent_workers),
+            worker_batch_concurrency: toml
+                .defaults
+                .worker_batch_concurrency
+                .unwrap_or(base_defaults.worker_batch_concurrency),
             max_turns: toml.defaults.max_turns.unwrap_or(base_defaults.max_turns),
             branch_max_turns: toml
                 .defaults
@@ -1440,6 +2208,22 @@ impl Config {
                         .unwrap_or(base_defaults.memory_persistence.message_interval),
                 })
                 .unwrap_or(base_defaults.memory_persistence),
+            prefetch: toml
+                .defaults
+                .prefetch
+                .map(|pf| PrefetchConfig {
+                    enabled: pf.enabled.unwrap_or(base_defaults.prefetch.enabled),
+                    ttl_secs: pf.ttl_secs.unwrap_or(base_defaults.prefetch.ttl_secs),
+                })
+                .unwrap_or(base_defaults.prefetch),
+            branch_cache: toml
+                .defaults
+                .branch_cache
+                .map(|bc| BranchCacheConfig {
+                    enabled: bc.enabled.unwrap_or(base_defaults.branch_cache.enabled),
+                    ttl_secs: bc.ttl_secs.unwrap_or(base_defaults.branch_cache.ttl_secs),
+                })
+                .unwrap_or(base_defaults.branch_cache),
             coalesce: toml
                 .defaults
                 .coalesce
@@ -1453,6 +2237,9 @@ impl Config {
                     multi_user_only: c
                         .multi_user_only
                         .unwrap_or(base_defaults.coalesce.multi_user_only),
+                    sender_debounce_ms: c
+                        .sender_debounce_ms
+                        .unwrap_or(base_defaults.coalesce.sender_debounce_ms),
                 })
                 .unwrap_or(base_defaults.coalesce),
             ingestion: toml
@@ -1464,6 +2251,11 @@ impl Config {
                         .poll_interval_secs
                         .unwrap_or(base_defaults.ingestion.poll_interval_secs),
                     chunk_size: ig.chunk_size.unwrap_or(base_defaults.ingestion.chunk_size),
+                    chunking: parse_chunking_strategy(ig.chunking.as_deref())
+                        .unwrap_or(base_defaults.ingestion.chunking),
+                    chunk_overlap: ig
+                        .chunk_overlap
+                        .unwrap_or(base_defaults.ingestion.chunk_overlap),
                 })
                 .unwrap_or(base_defaults.ingestion),
             cortex: toml
@@ -1486,6 +2278,41 @@ impl Config {
                         .unwrap_or(base_defaults.warmup.startup_delay_secs),
                 })
                 .unwrap_or(base_defaults.warmup),
+            storage: toml
+                .defaults
+                .storage
+                .map(|s| StorageConfig::resolve(s, base_defaults.storage))
+                .transpose()?
+                .unwrap_or(base_defaults.storage),
+            archive: toml
+                .defaults
+                .archive
+                .map(|a| ArchiveConfig::resolve(a, base_defaults.archive))
+                .transpose()?
+                .unwrap_or(base_defaults.archive),
+            moderation: toml
+                .defaults
+                .moderation
+                .map(|m| ModerationConfig::resolve(m, &base_defaults.moderation))
+                .transpose()?
+                .unwrap_or_else(|| base_defaults.moderation.clone()),
+            transcription: toml
+                .defaults
+                .transcription
+                .map(|t| TranscriptionConfig::resolve(t, &base_defaults.transcription))
+                .unwrap_or_else(|| base_defaults.transcription.clone()),
+            budget: toml
+                .defaults
+                .budget
+                .map(|b| BudgetConfig::resolve(b, &base_defaults.budget))
+                .transpose()?
+                .unwrap_or_else(|| base_defaults.budget.clone()),
+            memory_fts: toml
+                .defaults
+                .memory_fts
+                .map(|f| MemoryFtsConfig::resolve(f, &base_defaults.memory_fts))
+                .transpose()?
+                .unwrap_or_else(|| base_defaults.memory_fts.clone()),
             browser: {
                 let chrome_cache_dir = instance_dir.join("chrome_cache");
                 toml.defaults
@@ -1527,6 +2354,12 @@ impl Config {
                     save_attachments: channel_config
                         .save_attachments
                         .unwrap_or(base_defaults.channel.save_attachments),
+                    pace: parse_response_pace(channel_config.pace.as_deref())
+                        .unwrap_or(base_defaults.channel.pace),
+                    memory_scope: parse_memory_scope(channel_config.memory_scope.as_deref())
+                        .unwrap_or(base_defaults.channel.memory_scope),
+                    interrupt: parse_turn_interrupt(channel_config.interrupt.as_deref())
+                        .unwrap_or(base_defaults.channel.interrupt),
                 })
                 .unwrap_or(base_defaults.channel),
             mcp: default_mcp,
@@ -1579,6 +2412,37 @@ impl Config {
                     }
                 })
                 .unwrap_or_else(|| base_defaults.opencode.clone()),
+            container: toml
+                .defaults
+                .container
+                .map(|c| {
+                    let base = &base_defaults.container;
+                    ContainerConfig {
+                        enabled: c.enabled.unwrap_or(base.enabled),
+                        runtime: c
+                            .runtime
+                            .as_deref()
+                            .and_then(resolve_env_value)
+                            .unwrap_or_else(|| base.runtime.clone()),
+                        image: c.image.unwrap_or_else(|| base.image.clone()),
+                        network: c.network.unwrap_or_else(|| base.network.clone()),
+                        memory: c.memory.or_else(|| base.memory.clone()),
+                        cpus: c.cpus.or_else(|| base.cpus.clone()),
+                        pids_limit: c.pids_limit.or(base.pids_limit),
+                        user: c.user.or_else(|| base.user.clone()),
+                        mounts: c.mounts.unwrap_or_else(|| base.mounts.clone()),
+                    }
+                })
+                .unwrap_or_else(|| base_defaults.container.clone()),
+            git: toml
+                .defaults
+                .git
+                .map(|g| GitConfig {
+                    protected_branches: g
+                        .protected_branches
+                        .unwrap_or_else(|| base_defaults.git.protected_branches.clone()),
+                })
+                .unwrap_or_else(|| base_defaults.git.clone()),
             worker_log_mode: toml
                 .defaults
                 .worker_log_mode
@@ -1651,6 +2515,7 @@ impl Config {
                     routing: agent_routing,
                     max_concurrent_branches: a.max_concurrent_branches,
                     max_concurrent_workers: a.max_concurrent_workers,
+                    worker_batch_concurrency: a.worker_batch_concurrency,
                     max_turns: a.max_turns,
                     branch_max_turns: a.branch_max_turns,
                     context_window: a.context_window,
@@ -1671,6 +2536,14 @@ impl Config {
                             .message_interval
                             .unwrap_or(defaults.memory_persistence.message_interval),
                     }),
+                    prefetch: a.prefetch.map(|pf| PrefetchConfig {
+                        enabled: pf.enabled.unwrap_or(defaults.prefetch.enabled),
+                        ttl_secs: pf.ttl_secs.unwrap_or(defaults.prefetch.ttl_secs),
+                    }),
+                    branch_cache: a.branch_cache.map(|bc| BranchCacheConfig {
+                        enabled: bc.enabled.unwrap_or(defaults.branch_cache.enabled),
+                        ttl_secs: bc.ttl_secs.unwrap_or(defaults.branch_cache.ttl_secs),
+                    }),
                     coalesce: a.coalesce.map(|c| CoalesceConfig {
                         enabled: c.enabled.unwrap_or(defaults.coalesce.enabled),
                         debounce_ms: c.debounce_ms.unwrap_or(defaults.coalesce.debounce_ms),
@@ -1679,6 +2552,9 @@ impl Config {
                         multi_user_only: c
                             .multi_user_only
                             .unwrap_or(defaults.coalesce.multi_user_only),
+                        sender_debounce_ms: c
+                            .sender_debounce_ms
+                            .unwrap_or(defaults.coalesce.sender_debounce_ms),
                     }),
                     ingestion: a.ingestion.map(|ig| IngestionConfig {
                         enabled: ig.enabled.unwrap_or(defaults.ingestion.enabled),
@@ -1686,6 +2562,9 @@ impl Config {
                             .poll_interval_secs
                             .unwrap_or(defaults.ingestion.poll_interval_secs),
                         chunk_size: ig.chunk_size.unwrap_or(defaults.ingestion.chunk_size),
+                        chunking: parse_chunking_strategy(ig.chunking.as_deref())
+                            .unwrap_or(defaults.ingestion.chunking),
+                        chunk_overlap: ig.chunk_overlap.unwrap_or(defaults.ingestion.chunk_overlap),
                     }),
                     cortex: a
                         .cortex
@@ -1701,6 +2580,29 @@ impl Config {
                             .startup_delay_secs
                             .unwrap_or(defaults.warmup.startup_delay_secs),
                     }),
+                    storage: a
+                        .storage
+                        .map(|s| StorageConfig::resolve(s, defaults.storage))
+                        .transpose()?,
+                    archive: a
+                        .archive
+                        .map(|archive| ArchiveConfig::resolve(archive, defaults.archive))
+                        .transpose()?,
+                    moderation: a
+                        .moderation
+                        .map(|m| ModerationConfig::resolve(m, &defaults.moderation))
+                        .transpose()?,
+                    transcription: a
+                        .transcription
+                        .map(|t| TranscriptionConfig::resolve(t, &defaults.transcription)),
+                    budget: a
+                        .budget
+                        .map(|b| BudgetConfig::resolve(b, &defaults.budget))
+                        .transpose()?,
+                    memory_fts: a
+                        .memory_fts
+                        .map(|f| MemoryFtsConfig::resolve(f, &defaults.memory_fts))
+                        .transpose()?,
                     browser: a.browser.map(|b| BrowserConfig {
                         enabled: b.enabled.unwrap_or(defaults.browser.enabled),
                         headless: b.headless.unwrap_or(defaults.browser.headless),
@@ -1732,6 +2634,12 @@ impl Config {
                         save_attachments: channel_config
                             .save_attachments
                             .unwrap_or(defaults.channel.save_attachments),
+                        pace: parse_response_pace(channel_config.pace.as_deref())
+                            .unwrap_or(defaults.channel.pace),
+                        memory_scope: parse_memory_scope(channel_config.memory_scope.as_deref())
+                            .unwrap_or(defaults.channel.memory_scope),
+                        interrupt: parse_turn_interrupt(channel_config.interrupt.as_deref())
+                            .unwrap_or(defaults.channel.interrupt),
                     }),
                     mcp: match a.mcp {
                         Some(mcp_servers) => Some(
@@ -1784,15 +2692,24 @@ impl Config {
                 routing: None,
                 max_concurrent_branches: None,
                 max_concurrent_workers: None,
+                worker_batch_concurrency: None,
                 max_turns: None,
                 branch_max_turns: None,
                 context_window: None,
                 compaction: None,
                 memory_persistence: None,
+                prefetch: None,
+                branch_cache: None,
                 coalesce: None,
                 ingestion: None,
                 cortex: None,
                 warmup: None,
+                storage: None,
+                archive: None,
+                moderation: None,
+                transcription: None,
+                budget: None,
+                memory_fts: None,
                 browser: None,
                 channel: None,
                 mcp: None,
@@ -1848,6 +2765,10 @@ impl Config {
                     instances,
                     dm_allowed_users: d.dm_allowed_users,
                     allow_bot_messages: d.allow_bot_messages,
+                    voice: d
+                        .voice
+                        .map(DiscordVoiceConfig::resolve)
+                        .unwrap_or_default(),
                 })
             }),
             slack: toml.messaging.slack.and_then(|s| {
@@ -2227,6 +3148,71 @@ impl Config {
                     ignore_stories: s.ignore_stories,
                 })
             }),
+            github: toml.messaging.github.and_then(|g| {
+                let token = std::env::var("GITHUB_ADAPTER_TOKEN")
+                    .ok()
+                    .or_else(|| g.token.as_deref().and_then(resolve_env_value))?;
+                Some(GithubConfig {
+                    enabled: g.enabled,
+                    token,
+                    repos: g
+                        .repos
+                        .into_iter()
+                        .map(|repo| repo.trim().to_string())
+                        .filter(|repo| !repo.is_empty())
+                        .collect(),
+                    poll_interval_secs: g.poll_interval_secs,
+                    api_url: g
+                        .api_url
+                        .as_deref()
+                        .and_then(resolve_env_value)
+                        .unwrap_or_else(|| "https://api.github.com".into()),
+                })
+            }),
+            matrix: toml.messaging.matrix.and_then(|m| {
+                let homeserver_url = m.homeserver_url.as_deref().and_then(resolve_env_value)?;
+                let user_id = m.user_id.as_deref().and_then(resolve_env_value)?;
+                let password = std::env::var("MATRIX_PASSWORD")
+                    .ok()
+                    .or_else(|| m.password.as_deref().and_then(resolve_env_value))?;
+                Some(MatrixConfig {
+                    enabled: m.enabled,
+                    homeserver_url: homeserver_url.trim_end_matches('/').to_string(),
+                    user_id,
+                    password,
+                    device_name: m
+                        .device_name
+                        .filter(|name| !name.trim().is_empty())
+                        .unwrap_or_else(|| "Spacebot".into()),
+                    allowed_rooms: m.allowed_rooms,
+                    allowed_users: m.allowed_users,
+                    auto_join: m.auto_join,
+                    max_attachment_bytes: m.max_attachment_bytes,
+                })
+            }),
+            irc: toml.messaging.irc.and_then(|i| {
+                let server = i.server.as_deref().and_then(resolve_env_value)?;
+                let nickname = i.nickname.as_deref().and_then(resolve_env_value)?;
+                Some(IrcConfig {
+                    enabled: i.enabled,
+                    server,
+                    port: i.port,
+                    use_tls: i.use_tls,
+                    realname: i.realname.unwrap_or_else(|| "Spacebot".into()),
+                    username: i.username,
+                    server_password: std::env::var("IRC_SERVER_PASSWORD").ok().or_else(|| {
+                        i.server_password.as_deref().and_then(resolve_env_value)
+                    }),
+                    sasl_username: i.sasl_username,
+                    sasl_password: std::env::var("IRC_SASL_PASSWORD")
+                        .ok()
+                        .or_else(|| i.sasl_password.as_deref().and_then(resolve_env_value)),
+                    channels: i.channels,
+                    dm_allowed_users: i.dm_allowed_users,
+                    send_interval_ms: i.send_interval_ms,
+                    nickname,
+                })
+            }),
         };
 
         let bindings: Vec<Binding> = toml
@@ -2242,16 +3228,27 @@ impl Config {
                 channel_ids: b.channel_ids,
                 require_mention: b.require_mention,
                 dm_allowed_users: b.dm_allowed_users,
+                mention: b
+                    .mention
+                    .map(|mention| mention.trim().to_string())
+                    .filter(|mention| !mention.is_empty()),
+                prefix: b
+                    .prefix
+                    .map(|prefix| prefix.trim().to_string())
+                    .filter(|prefix| !prefix.is_empty()),
             })
             .collect();
 
         validate_named_messaging_adapters(&messaging, &bindings)?;
 
+        let api_auth_token = toml.api.auth_token.as_deref().and_then(resolve_env_value);
         let api = ApiConfig {
             enabled: toml.api.enabled,
             port: toml.api.port,
             bind: hosted_api_bind(toml.api.bind),
-            auth_token: toml.api.auth_token.as_deref().and_then(resolve_env_value),
+            auth: resolve_api_auth(api_auth_token.as_deref(), toml.api.auth)?,
+            rate_limit: resolve_api_rate_limit(toml.api.rate_limit)?,
+            auth_token: api_auth_token,
         };
 
         let metrics = MetricsConfig {
@@ -2283,6 +3280,41 @@ impl Config {
             }
         };
 
+        let cluster = {
+            let defaults = ClusterConfig::disabled(&instance_dir);
+            ClusterConfig {
+                enabled: toml.cluster.enabled,
+                node_id: toml
+                    .cluster
+                    .node_id
+                    .as_deref()
+                    .and_then(resolve_env_value)
+                    .filter(|node_id| !node_id.trim().is_empty())
+                    .unwrap_or(defaults.node_id),
+                database: toml
+                    .cluster
+                    .database
+                    .as_deref()
+                    .and_then(resolve_env_value)
+                    .map(|path| instance_dir.join(path))
+                    .unwrap_or(defaults.database),
+                advertise_url: toml
+                    .cluster
+                    .advertise_url
+                    .as_deref()
+                    .and_then(resolve_env_value),
+                lease_secs: toml
+                    .cluster
+                    .lease_secs
+                    .unwrap_or(defaults.lease_secs)
+                    .max(5),
+                distribute_tasks: toml
+                    .cluster
+                    .distribute_tasks
+                    .unwrap_or(defaults.distribute_tasks),
+            }
+        };
+
         let mut links: Vec<LinkDef> = toml
             .links
             .into_iter()
@@ -2361,6 +3393,9 @@ impl Config {
             }
         }
 
+        let teams = resolve_teams(toml.teams, &agents)?;
+        add_team_links(&teams, &mut links);
+
         Ok(Config {
             instance_dir,
             llm,
@@ -2368,11 +3403,13 @@ impl Config {
             agents,
             links,
             groups,
+            teams,
             humans,
             messaging,
             bindings,
             api,
             metrics,
+            cluster,
             telemetry,
         })
     }
@@ -3,7 +3,10 @@ use super::{
     SlackInstanceConfig, TelegramConfig, TelegramInstanceConfig, TwitchConfig,
     TwitchInstanceConfig,
 };
+use arc_swap::ArcSwap;
+
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 /// Hot-reloadable Discord permission filters.
 ///
@@ -464,6 +467,89 @@ fn binding_adapter_selector_matches(binding: &Binding, adapter_selector: Option<
     }
 }
 
+/// Live permission handles of one platform's named adapter instances, keyed
+/// by runtime adapter name (`discord:ops`).
+///
+/// Each named adapter gets its own `Arc<ArcSwap<..>>`. Registering it here
+/// lets a config reload swap new permissions into a running instance the
+/// same way the default adapter's shared handle is updated.
+pub struct PermissionHandles<P> {
+    handles: Mutex<HashMap<String, Arc<ArcSwap<P>>>>,
+}
+
+impl<P> Default for PermissionHandles<P> {
+    fn default() -> Self {
+        Self {
+            handles: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<P> std::fmt::Debug for PermissionHandles<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PermissionHandles")
+            .field("adapters", &self.lock().keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<P> PermissionHandles<P> {
+    /// Wrap `permissions` in a new handle for `adapter` and keep it for
+    /// reloads. Replaces any handle a previous adapter of that name had.
+    pub fn track(&self, adapter: &str, permissions: P) -> Arc<ArcSwap<P>> {
+        let handle = Arc::new(ArcSwap::from_pointee(permissions));
+        self.lock().insert(adapter.to_string(), handle.clone());
+        handle
+    }
+
+    /// Swap new permissions into `adapter`'s handle. Returns `false` if no
+    /// adapter of that name is tracked.
+    pub fn update(&self, adapter: &str, permissions: P) -> bool {
+        match self.lock().get(adapter) {
+            Some(handle) => {
+                handle.store(Arc::new(permissions));
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get(&self, adapter: &str) -> Option<Arc<ArcSwap<P>>> {
+        self.lock().get(adapter).cloned()
+    }
+
+    pub fn remove(&self, adapter: &str) {
+        self.lock().remove(adapter);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Arc<ArcSwap<P>>>> {
+        self.handles
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Permission handles of every running named adapter instance.
+#[derive(Debug, Default)]
+pub struct InstancePermissions {
+    pub discord: PermissionHandles<DiscordPermissions>,
+    pub slack: PermissionHandles<SlackPermissions>,
+    pub telegram: PermissionHandles<TelegramPermissions>,
+    pub twitch: PermissionHandles<TwitchPermissions>,
+    pub signal: PermissionHandles<SignalPermissions>,
+}
+
+impl InstancePermissions {
+    /// Forget a removed adapter's handle, whatever its platform.
+    pub fn remove(&self, adapter: &str) {
+        self.discord.remove(adapter);
+        self.slack.remove(adapter);
+        self.telegram.remove(adapter);
+        self.twitch.remove(adapter);
+        self.signal.remove(adapter);
+    }
+}
+
 /// Check if a string is valid base64 (URL-safe or standard).
 /// Signal group IDs are base64-encoded.
 fn is_valid_base64(s: &str) -> bool {
@@ -1,6 +1,6 @@
-use super::toml_schema::TomlRoutingConfig;
+use super::toml_schema::{TomlRoutingConfig, TomlSamplingConfig};
 use super::{ApiType, ProviderConfig};
-use crate::llm::routing::RoutingConfig;
+use crate::llm::routing::{RoutingConfig, SamplingConfig};
 
 use std::collections::HashMap;
 
@@ -309,6 +309,37 @@ pub(super) fn resolve_routing(
         None => base.fallbacks.clone(),
     };
 
+    let equivalents = match t.equivalents {
+        Some(e) => e,
+        None => base.equivalents.clone(),
+    };
+
+    let mut context_windows = base.context_windows.clone();
+    context_windows.extend(t.context_windows.unwrap_or_default());
+
+    let mut sampling = base.sampling.clone();
+    for (process_type, toml_sampling) in t.sampling {
+        if !matches!(
+            process_type.as_str(),
+            "channel" | "branch" | "worker" | "compactor" | "cortex"
+        ) {
+            tracing::warn!(
+                process_type = %process_type,
+                "unknown process type in routing.sampling, ignoring"
+            );
+            continue;
+        }
+        let inherited = sampling.get(&process_type).copied().unwrap_or_default();
+        let resolved = resolve_sampling(toml_sampling, &process_type).or(inherited);
+        sampling.insert(process_type, resolved);
+    }
+
+    let mut channel_sampling = base.channel_sampling.clone();
+    for (channel_id, toml_sampling) in t.channel_sampling {
+        let resolved = resolve_sampling(toml_sampling, &channel_id);
+        channel_sampling.insert(channel_id, resolved);
+    }
+
     RoutingConfig {
         channel: t.channel.unwrap_or_else(|| base.channel.clone()),
         branch: t.branch.unwrap_or_else(|| base.branch.clone()),
@@ -318,6 +349,9 @@ pub(super) fn resolve_routing(
         voice: t.voice.unwrap_or_else(|| base.voice.clone()),
         task_overrides,
         fallbacks,
+        equivalents,
+        context_windows,
+        latency_aware: t.latency_aware.unwrap_or(base.latency_aware),
         rate_limit_cooldown_secs: t
             .rate_limit_cooldown_secs
             .unwrap_or(base.rate_limit_cooldown_secs),
@@ -336,5 +370,47 @@ pub(super) fn resolve_routing(
         cortex_thinking_effort: t
             .cortex_thinking_effort
             .unwrap_or_else(|| base.cortex_thinking_effort.clone()),
+        sampling,
+        channel_sampling,
+        channel_overrides: base.channel_overrides.clone(),
+    }
+}
+
+/// Validate a sampling table, dropping out-of-range values with a warning.
+fn resolve_sampling(toml: TomlSamplingConfig, key: &str) -> SamplingConfig {
+    let temperature = toml.temperature.filter(|value| {
+        let valid = (0.0..=2.0).contains(value);
+        if !valid {
+            tracing::warn!(
+                key,
+                temperature = value,
+                "sampling temperature must be between 0 and 2, ignoring"
+            );
+        }
+        valid
+    });
+    let top_p = toml.top_p.filter(|value| {
+        let valid = *value > 0.0 && *value <= 1.0;
+        if !valid {
+            tracing::warn!(
+                key,
+                top_p = value,
+                "sampling top_p must be in (0, 1], ignoring"
+            );
+        }
+        valid
+    });
+    let max_tokens = toml.max_tokens.filter(|value| {
+        let valid = *value > 0;
+        if !valid {
+            tracing::warn!(key, "sampling max_tokens must be positive, ignoring");
+        }
+        valid
+    });
+
+    SamplingConfig {
+        temperature,
+        top_p,
+        max_tokens,
     }
 }
@@ -0,0 +1,1124 @@
+//! Unified config reload.
+//!
+//! [`ConfigReloader`] owns every live handle a config change can touch. A
+//! reload diffs the new config.toml against the last applied one and hands
+//! each changed section to its subsystem: provider keys, bindings and
+//! permissions, API keys, per-agent runtime config, and messaging adapters,
+//! which are started, stopped, or restarted one at a time. Settings that
+//! can't change in a running process are reported instead of applied.
+
+use super::{
+    Binding, Config, DiscordPermissions, InstancePermissions, MessagingConfig, RuntimeConfig,
+    SignalPermissions, SlackPermissions, TelegramPermissions, TwitchPermissions,
+    binding_runtime_adapter_key,
+};
+use crate::error::Result;
+
+use anyhow::Context as _;
+use serde::Serialize;
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Per-agent context needed to reload an agent: (id, workspace,
+/// identity_dir, runtime_config, mcp_manager).
+pub(super) type WatchedAgent = (
+    String,
+    PathBuf,
+    PathBuf,
+    Arc<RuntimeConfig>,
+    Arc<crate::mcp::McpManager>,
+);
+
+/// Platforms whose adapters are started from `[messaging]`. Other adapters
+/// (webhook, webchat) are left alone.
+const MANAGED_PLATFORMS: [&str; 9] = [
+    "discord", "slack", "telegram", "email", "github", "irc", "matrix", "twitch", "signal",
+];
+
+/// Settings read once at startup, as dotted config paths.
+const RESTART_ONLY: [&str; 7] = [
+    "llm.embedding",
+    "api.enabled",
+    "api.bind",
+    "api.port",
+    "metrics",
+    "telemetry",
+    "cluster",
+];
+
+/// What a reload changed.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReloadReport {
+    /// Top-level config sections that differ from the last applied config.
+    pub changed_sections: Vec<String>,
+    /// Subsystems the new values were applied to.
+    pub applied: Vec<String>,
+    pub adapters_started: Vec<String>,
+    pub adapters_stopped: Vec<String>,
+    pub adapters_restarted: Vec<String>,
+    /// Changed settings that only take effect after a restart.
+    pub restart_required: Vec<String>,
+}
+
+/// The difference between two config.toml documents.
+#[derive(Debug, Default)]
+pub(super) struct ConfigDiff {
+    sections: BTreeSet<String>,
+    restart_only: Vec<String>,
+    agents_added: Vec<String>,
+    agents_removed: Vec<String>,
+}
+
+impl ConfigDiff {
+    pub(super) fn between(old: &toml::Table, new: &toml::Table) -> Self {
+        let sections = old
+            .keys()
+            .chain(new.keys())
+            .filter(|key| old.get(*key) != new.get(*key))
+            .cloned()
+            .collect();
+        let restart_only = RESTART_ONLY
+            .iter()
+            .filter(|path| lookup(old, path) != lookup(new, path))
+            .map(|path| path.to_string())
+            .collect();
+
+        let old_agents = agent_ids(old);
+        let new_agents = agent_ids(new);
+        Self {
+            sections,
+            restart_only,
+            agents_added: new_agents.difference(&old_agents).cloned().collect(),
+            agents_removed: old_agents.difference(&new_agents).cloned().collect(),
+        }
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.sections.is_empty()
+    }
+
+    pub(super) fn changed(&self, section: &str) -> bool {
+        self.sections.contains(section)
+    }
+}
+
+fn lookup<'a>(table: &'a toml::Table, path: &str) -> Option<&'a toml::Value> {
+    let mut parts = path.split('.');
+    let mut value = table.get(parts.next()?)?;
+    for part in parts {
+        value = value.get(part)?;
+    }
+    Some(value)
+}
+
+fn agent_ids(table: &toml::Table) -> BTreeSet<String> {
+    table
+        .get("agents")
+        .and_then(|agents| agents.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|agent| agent.get("id")?.as_str().map(str::to_string))
+        .collect()
+}
+
+/// The config table behind each messaging adapter, keyed by runtime adapter
+/// name. A platform's default adapter is its table without `instances`; a
+/// named adapter is its entry in `instances`.
+fn adapter_fingerprints(table: &toml::Table) -> HashMap<String, toml::Value> {
+    let mut fingerprints = HashMap::new();
+    let Some(messaging) = table.get("messaging").and_then(|value| value.as_table()) else {
+        return fingerprints;
+    };
+    for platform in MANAGED_PLATFORMS {
+        let Some(platform_table) = messaging.get(platform).and_then(|value| value.as_table())
+        else {
+            continue;
+        };
+        let mut default = platform_table.clone();
+        let instances = default.remove("instances");
+        fingerprints.insert(platform.to_string(), toml::Value::Table(default));
+
+        for instance in instances
+            .as_ref()
+            .and_then(|instances| instances.as_array())
+            .into_iter()
+            .flatten()
+        {
+            if let Some(name) = instance.get("name").and_then(|name| name.as_str()) {
+                fingerprints.insert(
+                    binding_runtime_adapter_key(platform, Some(name)),
+                    instance.clone(),
+                );
+            }
+        }
+    }
+    fingerprints
+}
+
+/// Runtime names of the adapters the config enables.
+fn configured_adapters(messaging: &MessagingConfig) -> HashSet<String> {
+    let mut adapters = HashSet::new();
+    macro_rules! with_instances {
+        ($platform:literal, $config:expr) => {
+            if let Some(config) = &$config
+                && config.enabled
+            {
+                adapters.insert($platform.to_string());
+                for instance in config.instances.iter().filter(|instance| instance.enabled) {
+                    adapters.insert(binding_runtime_adapter_key(
+                        $platform,
+                        Some(instance.name.as_str()),
+                    ));
+                }
+            }
+        };
+    }
+    macro_rules! single {
+        ($platform:literal, $config:expr) => {
+            if $config.as_ref().is_some_and(|config| config.enabled) {
+                adapters.insert($platform.to_string());
+            }
+        };
+    }
+    with_instances!("discord", messaging.discord);
+    with_instances!("slack", messaging.slack);
+    with_instances!("telegram", messaging.telegram);
+    with_instances!("email", messaging.email);
+    with_instances!("twitch", messaging.twitch);
+    with_instances!("signal", messaging.signal);
+    single!("github", messaging.github);
+    single!("irc", messaging.irc);
+    single!("matrix", messaging.matrix);
+    adapters
+}
+
+fn is_managed_adapter(name: &str) -> bool {
+    let platform = name.split(':').next().unwrap_or(name);
+    MANAGED_PLATFORMS.contains(&platform)
+}
+
+/// The config last applied.
+struct AppliedConfig {
+    table: toml::Table,
+    /// Fingerprints of the adapters running after the last reload. An
+    /// adapter started elsewhere (the messaging API) has none, and is
+    /// adopted as-is rather than restarted.
+    adapters: HashMap<String, toml::Value>,
+}
+
+/// Applies config.toml changes to the running instance.
+pub struct ConfigReloader {
+    config_path: PathBuf,
+    instance_dir: PathBuf,
+    agents: Vec<WatchedAgent>,
+    discord_permissions: Option<Arc<arc_swap::ArcSwap<DiscordPermissions>>>,
+    slack_permissions: Option<Arc<arc_swap::ArcSwap<SlackPermissions>>>,
+    telegram_permissions: Option<Arc<arc_swap::ArcSwap<TelegramPermissions>>>,
+    twitch_permissions: Option<Arc<arc_swap::ArcSwap<TwitchPermissions>>>,
+    signal_permissions: Option<Arc<arc_swap::ArcSwap<SignalPermissions>>>,
+    bindings: Arc<arc_swap::ArcSwap<Vec<Binding>>>,
+    messaging_manager: Option<Arc<crate::messaging::MessagingManager>>,
+    llm_manager: Arc<crate::llm::LlmManager>,
+    agent_links: Arc<arc_swap::ArcSwap<Vec<crate::links::AgentLink>>>,
+    agent_humans: Arc<arc_swap::ArcSwap<Vec<crate::config::HumanDef>>>,
+    agent_teams: Arc<arc_swap::ArcSwap<Vec<crate::config::TeamDef>>>,
+    api_auth: Arc<arc_swap::ArcSwap<crate::config::ApiAuthConfig>>,
+    api_rate_limiter: Arc<crate::api::RateLimiter>,
+    /// Also serializes reloads, so a file change and an API request can't
+    /// interleave.
+    applied: tokio::sync::Mutex<AppliedConfig>,
+}
+
+impl std::fmt::Debug for ConfigReloader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigReloader")
+            .field("config_path", &self.config_path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ConfigReloader {
+    /// The config.toml on disk now is taken as already applied.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        config_path: PathBuf,
+        instance_dir: PathBuf,
+        agents: Vec<WatchedAgent>,
+        discord_permissions: Option<Arc<arc_swap::ArcSwap<DiscordPermissions>>>,
+        slack_permissions: Option<Arc<arc_swap::ArcSwap<SlackPermissions>>>,
+        telegram_permissions: Option<Arc<arc_swap::ArcSwap<TelegramPermissions>>>,
+        twitch_permissions: Option<Arc<arc_swap::ArcSwap<TwitchPermissions>>>,
+        signal_permissions: Option<Arc<arc_swap::ArcSwap<SignalPermissions>>>,
+        bindings: Arc<arc_swap::ArcSwap<Vec<Binding>>>,
+        messaging_manager: Option<Arc<crate::messaging::MessagingManager>>,
+        llm_manager: Arc<crate::llm::LlmManager>,
+        agent_links: Arc<arc_swap::ArcSwap<Vec<crate::links::AgentLink>>>,
+        agent_humans: Arc<arc_swap::ArcSwap<Vec<crate::config::HumanDef>>>,
+        agent_teams: Arc<arc_swap::ArcSwap<Vec<crate::config::TeamDef>>>,
+        api_auth: Arc<arc_swap::ArcSwap<crate::config::ApiAuthConfig>>,
+        api_rate_limiter: Arc<crate::api::RateLimiter>,
+    ) -> Self {
+        let table: toml::Table = std::fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|content| content.parse().ok())
+            .unwrap_or_default();
+        let adapters = adapter_fingerprints(&table);
+        Self {
+            config_path,
+            instance_dir,
+            agents,
+            discord_permissions,
+            slack_permissions,
+            telegram_permissions,
+            twitch_permissions,
+            signal_permissions,
+            bindings,
+            messaging_manager,
+            llm_manager,
+            agent_links,
+            agent_humans,
+            agent_teams,
+            api_auth,
+            api_rate_limiter,
+            applied: tokio::sync::Mutex::new(AppliedConfig { table, adapters }),
+        }
+    }
+
+    pub fn config_path(&self) -> &Path {
+        &self.config_path
+    }
+
+    pub fn instance_dir(&self) -> &Path {
+        &self.instance_dir
+    }
+
+    pub(super) fn agents(&self) -> &[WatchedAgent] {
+        &self.agents
+    }
+
+    /// Re-read config.toml and apply what changed since the last reload.
+    ///
+    /// With `force`, every subsystem is reloaded and every running adapter
+    /// restarted, which also picks up changed `secret:` and `env:` values
+    /// the file itself doesn't show.
+    pub async fn reload(&self, force: bool) -> Result<ReloadReport> {
+        let mut applied = self.applied.lock().await;
+
+        let content = tokio::fs::read_to_string(&self.config_path)
+            .await
+            .with_context(|| format!("failed to read {}", self.config_path.display()))?;
+        let table: toml::Table = content.parse().context("failed to parse config.toml")?;
+        let config_path = self.config_path.clone();
+        let config = tokio::task::spawn_blocking(move || Config::load_from_path(&config_path))
+            .await
+            .context("config load task failed")??;
+
+        let diff = ConfigDiff::between(&applied.table, &table);
+        let mut report = ReloadReport {
+            changed_sections: diff.sections.iter().cloned().collect(),
+            restart_required: diff.restart_only.clone(),
+            ..ReloadReport::default()
+        };
+        if !diff.agents_added.is_empty() {
+            report
+                .restart_required
+                .push(format!("agents added: {}", diff.agents_added.join(", ")));
+        }
+        if !diff.agents_removed.is_empty() {
+            report.restart_required.push(format!(
+                "agents removed: {}",
+                diff.agents_removed.join(", ")
+            ));
+        }
+        if diff.is_empty() && !force {
+            applied.table = table;
+            return Ok(report);
+        }
+
+        self.apply(&config, &diff, force, &mut report);
+
+        if force || diff.changed("defaults") || diff.changed("agents") {
+            for (agent_id, _, _, runtime_config, mcp_manager) in &self.agents {
+                runtime_config
+                    .reload_config(&config, agent_id, mcp_manager)
+                    .await;
+            }
+            report.applied.push("agents".to_string());
+        }
+
+        if force || diff.changed("messaging") {
+            applied.adapters = self
+                .reconcile_adapters(&config, &table, &applied.adapters, force, &mut report)
+                .await;
+        }
+
+        applied.table = table;
+        tracing::info!(
+            changed = %report.changed_sections.join(", "),
+            applied = %report.applied.join(", "),
+            restarted = %report.adapters_restarted.join(", "),
+            "config reloaded"
+        );
+        if !report.restart_required.is_empty() {
+            tracing::warn!(
+                settings = %report.restart_required.join("; "),
+                "some config changes take effect after a restart"
+            );
+        }
+        Ok(report)
+    }
+
+    /// Swap in instance-level values: provider keys, bindings, links, API
+    /// keys, and adapter permissions.
+    fn apply(&self, config: &Config, diff: &ConfigDiff, force: bool, report: &mut ReloadReport) {
+        let changed = |section: &str| force || diff.changed(section);
+
+        if changed("llm") {
+            self.llm_manager.reload_config(config.llm.clone());
+            report.applied.push("llm".to_string());
+        }
+
+        if changed("bindings") {
+            self.bindings.store(Arc::new(config.bindings.clone()));
+            tracing::info!("bindings reloaded ({} entries)", config.bindings.len());
+            report.applied.push("bindings".to_string());
+        }
+
+        if changed("links") {
+            match crate::links::AgentLink::from_config(&config.links) {
+                Ok(links) => {
+                    self.agent_links.store(Arc::new(links));
+                    tracing::info!("agent links reloaded ({} entries)", config.links.len());
+                    report.applied.push("links".to_string());
+                }
+                Err(error) => {
+                    tracing::error!(%error, "failed to parse links from reloaded config");
+                }
+            }
+        }
+
+        if changed("humans") {
+            self.agent_humans.store(Arc::new(config.humans.clone()));
+            tracing::info!("agent humans reloaded ({} entries)", config.humans.len());
+            report.applied.push("humans".to_string());
+        }
+
+        if changed("teams") {
+            self.agent_teams.store(Arc::new(config.teams.clone()));
+            tracing::info!("agent teams reloaded ({} entries)", config.teams.len());
+            report.applied.push("teams".to_string());
+        }
+
+        if changed("api") {
+            self.api_auth.store(Arc::new(config.api.auth.clone()));
+            self.api_rate_limiter.reload(config.api.rate_limit.clone());
+            tracing::info!("api keys reloaded ({} entries)", config.api.auth.keys.len());
+            report.applied.push("api".to_string());
+        }
+
+        // Permissions are built from both the platform config and bindings.
+        if !(changed("messaging") || changed("bindings")) {
+            return;
+        }
+
+        if let Some(ref perms) = self.discord_permissions
+            && let Some(discord_config) = &config.messaging.discord
+        {
+            let new_perms = DiscordPermissions::from_config(discord_config, &config.bindings);
+            perms.store(Arc::new(new_perms));
+            tracing::info!("discord permissions reloaded");
+        }
+
+        if let Some(ref perms) = self.slack_permissions
+            && let Some(slack_config) = &config.messaging.slack
+        {
+            let new_perms = SlackPermissions::from_config(slack_config, &config.bindings);
+            perms.store(Arc::new(new_perms));
+            tracing::info!("slack permissions reloaded");
+        }
+
+        if let Some(ref perms) = self.telegram_permissions
+            && let Some(telegram_config) = &config.messaging.telegram
+        {
+            let new_perms = TelegramPermissions::from_config(telegram_config, &config.bindings);
+            perms.store(Arc::new(new_perms));
+            tracing::info!("telegram permissions reloaded");
+        }
+
+        if let Some(ref perms) = self.twitch_permissions
+            && let Some(twitch_config) = &config.messaging.twitch
+        {
+            let new_perms = TwitchPermissions::from_config(twitch_config, &config.bindings);
+            perms.store(Arc::new(new_perms));
+            tracing::info!("twitch permissions reloaded");
+        }
+
+        if let Some(ref perms) = self.signal_permissions
+            && let Some(signal_config) = &config.messaging.signal
+        {
+            let new_perms = SignalPermissions::from_config(signal_config);
+            perms.store(Arc::new(new_perms));
+            tracing::info!("signal permissions reloaded");
+        }
+
+        if let Some(manager) = &self.messaging_manager {
+            reload_instance_permissions(config, manager.instance_permissions());
+        }
+
+        report.applied.push("permissions".to_string());
+    }
+
+    /// Stop adapters the config no longer enables, restart those whose
+    /// config changed, and start newly enabled ones. Returns the
+    /// fingerprints of the adapters now running.
+    async fn reconcile_adapters(
+        &self,
+        config: &Config,
+        table: &toml::Table,
+        previous: &HashMap<String, toml::Value>,
+        force: bool,
+        report: &mut ReloadReport,
+    ) -> HashMap<String, toml::Value> {
+        let Some(manager) = &self.messaging_manager else {
+            return HashMap::new();
+        };
+        let fingerprints = adapter_fingerprints(table);
+        let configured = configured_adapters(&config.messaging);
+
+        let running: Vec<String> = manager
+            .adapter_names()
+            .await
+            .into_iter()
+            .filter(|name| is_managed_adapter(name))
+            .collect();
+        for name in &running {
+            let outcome = if !configured.contains(name) {
+                &mut report.adapters_stopped
+            } else if force
+                || previous
+                    .get(name)
+                    .is_some_and(|old| fingerprints.get(name) != Some(old))
+            {
+                &mut report.adapters_restarted
+            } else {
+                continue;
+            };
+            match manager.remove_adapter(name).await {
+                Ok(()) => outcome.push(name.clone()),
+                Err(error) => tracing::warn!(%error, adapter = %name, "failed to stop adapter"),
+            }
+        }
+
+        self.start_configured_adapters(config).await;
+
+        let now_running: HashSet<String> = manager.adapter_names().await.into_iter().collect();
+        report.adapters_started = now_running
+            .iter()
+            .filter(|name| is_managed_adapter(name) && !running.contains(name))
+            .cloned()
+            .collect();
+        report.adapters_started.sort();
+        report.applied.push("messaging".to_string());
+
+        fingerprints
+            .into_iter()
+            .filter(|(name, _)| now_running.contains(name))
+            .collect()
+    }
+
+    /// Start every enabled adapter that isn't already running.
+    async fn start_configured_adapters(&self, config: &Config) {
+        let Some(manager) = self.messaging_manager.clone() else {
+            return;
+        };
+        let discord_permissions = self.discord_permissions.clone();
+        let slack_permissions = self.slack_permissions.clone();
+        let telegram_permissions = self.telegram_permissions.clone();
+        let twitch_permissions = self.twitch_permissions.clone();
+        let signal_permissions = self.signal_permissions.clone();
+        let instance_dir = self.instance_dir.clone();
+
+        // Discord: start default + named instances that are enabled and not already running.
+        if let Some(discord_config) = &config.messaging.discord
+            && discord_config.enabled
+        {
+            if !discord_config.token.is_empty() && !manager.has_adapter("discord").await {
+                let permissions = match discord_permissions {
+                    Some(ref existing) => existing.clone(),
+                    None => {
+                        let permissions =
+                            DiscordPermissions::from_config(discord_config, &config.bindings);
+                        Arc::new(arc_swap::ArcSwap::from_pointee(permissions))
+                    }
+                };
+                let adapter = crate::messaging::discord::DiscordAdapter::new(
+                    "discord",
+                    &discord_config.token,
+                    permissions,
+                )
+                .with_voice(discord_config.voice.clone());
+                if let Err(error) = manager.register_and_start(adapter).await {
+                    tracing::error!(%error, "failed to hot-start discord adapter from config change");
+                }
+            }
+
+            for instance in discord_config
+                .instances
+                .iter()
+                .filter(|instance| instance.enabled)
+            {
+                let runtime_key =
+                    binding_runtime_adapter_key("discord", Some(instance.name.as_str()));
+                // A running instance already had its permissions swapped in
+                // `apply`.
+                if manager.has_adapter(runtime_key.as_str()).await {
+                    continue;
+                }
+
+                let permissions = manager.instance_permissions().discord.track(
+                    &runtime_key,
+                    DiscordPermissions::from_instance_config(instance, &config.bindings),
+                );
+                let adapter = crate::messaging::discord::DiscordAdapter::new(
+                    runtime_key,
+                    &instance.token,
+                    permissions,
+                )
+                .with_voice(discord_config.voice.clone());
+                if let Err(error) = manager.register_and_start(adapter).await {
+                    tracing::error!(%error, adapter = %instance.name, "failed to hot-start named discord adapter from config change");
+                }
+            }
+        }
+
+        // Slack: start default + named instances that are enabled and not already running.
+        if let Some(slack_config) = &config.messaging.slack
+            && slack_config.enabled
+        {
+            if !slack_config.bot_token.is_empty()
+                && !slack_config.app_token.is_empty()
+                && !manager.has_adapter("slack").await
+            {
+                let permissions = match slack_permissions {
+                    Some(ref existing) => existing.clone(),
+                    None => {
+                        let permissions =
+                            SlackPermissions::from_config(slack_config, &config.bindings);
+                        Arc::new(arc_swap::ArcSwap::from_pointee(permissions))
+                    }
+                };
+                match crate::messaging::slack::SlackAdapter::new(
+                    "slack",
+                    &slack_config.bot_token,
+                    &slack_config.app_token,
+                    permissions,
+                    slack_config.commands.clone(),
+                ) {
+                    Ok(adapter) => {
+                        if let Err(error) = manager.register_and_start(adapter).await {
+                            tracing::error!(%error, "failed to hot-start slack adapter from config change");
+                        }
+                    }
+                    Err(error) => {
+                        tracing::error!(%error, "failed to build slack adapter from config change");
+                    }
+                }
+            }
+
+            for instance in slack_config
+                .instances
+                .iter()
+                .filter(|instance| instance.enabled)
+            {
+                let runtime_key =
+                    binding_runtime_adapter_key("slack", Some(instance.name.as_str()));
+                if manager.has_adapter(runtime_key.as_str()).await {
+                    continue;
+                }
+
+                let permissions = manager.instance_permissions().slack.track(
+                    &runtime_key,
+                    SlackPermissions::from_instance_config(instance, &config.bindings),
+                );
+                match crate::messaging::slack::SlackAdapter::new(
+                    runtime_key,
+                    &instance.bot_token,
+                    &instance.app_token,
+                    permissions,
+                    instance.commands.clone(),
+                ) {
+                    Ok(adapter) => {
+                        if let Err(error) = manager.register_and_start(adapter).await {
+                            tracing::error!(%error, adapter = %instance.name, "failed to hot-start named slack adapter from config change");
+                        }
+                    }
+                    Err(error) => {
+                        tracing::error!(%error, adapter = %instance.name, "failed to build named slack adapter from config change");
+                    }
+                }
+            }
+        }
+
+        // Telegram: start default + named instances that are enabled and not already running.
+        if let Some(telegram_config) = &config.messaging.telegram
+            && telegram_config.enabled
+        {
+            if !telegram_config.token.is_empty() && !manager.has_adapter("telegram").await {
+                let permissions = match telegram_permissions {
+                    Some(ref existing) => existing.clone(),
+                    None => {
+                        let permissions =
+                            TelegramPermissions::from_config(telegram_config, &config.bindings);
+                        Arc::new(arc_swap::ArcSwap::from_pointee(permissions))
+                    }
+                };
+                let adapter = crate::messaging::telegram::TelegramAdapter::new(
+                    "telegram",
+                    &telegram_config.token,
+                    permissions,
+                );
+                if let Err(error) = manager.register_and_start(adapter).await {
+                    tracing::error!(%error, "failed to hot-start telegram adapter from config change");
+                }
+            }
+
+            for instance in telegram_config
+                .instances
+                .iter()
+                .filter(|instance| instance.enabled)
+            {
+                let runtime_key =
+                    binding_runtime_adapter_key("telegram", Some(instance.name.as_str()));
+                if manager.has_adapter(runtime_key.as_str()).await {
+                    continue;
+                }
+
+                let permissions = manager.instance_permissions().telegram.track(
+                    &runtime_key,
+                    TelegramPermissions::from_instance_config(instance, &config.bindings),
+                );
+                let adapter = crate::messaging::telegram::TelegramAdapter::new(
+                    runtime_key,
+                    &instance.token,
+                    permissions,
+                );
+                if let Err(error) = manager.register_and_start(adapter).await {
+                    tracing::error!(%error, adapter = %instance.name, "failed to hot-start named telegram adapter from config change");
+                }
+            }
+        }
+
+        // Email: start default + named instances that are enabled and not already running.
+        if let Some(email_config) = &config.messaging.email
+            && email_config.enabled
+        {
+            if !email_config.imap_host.is_empty() && !manager.has_adapter("email").await {
+                match crate::messaging::email::EmailAdapter::from_config(email_config) {
+                    Ok(adapter) => {
+                        if let Err(error) = manager.register_and_start(adapter).await {
+                            tracing::error!(%error, "failed to hot-start email adapter from config change");
+                        }
+                    }
+                    Err(error) => {
+                        tracing::error!(%error, "failed to build email adapter from config change");
+                    }
+                }
+            }
+
+            for instance in email_config
+                .instances
+                .iter()
+                .filter(|instance| instance.enabled)
+            {
+                let runtime_key =
+                    binding_runtime_adapter_key("email", Some(instance.name.as_str()));
+                if manager.has_adapter(runtime_key.as_str()).await {
+                    continue;
+                }
+
+                match crate::messaging::email::EmailAdapter::from_instance_config(
+                    runtime_key.as_str(),
+                    instance,
+                ) {
+                    Ok(adapter) => {
+                        if let Err(error) = manager.register_and_start(adapter).await {
+                            tracing::error!(%error, adapter = %instance.name, "failed to hot-start named email adapter from config change");
+                        }
+                    }
+                    Err(error) => {
+                        tracing::error!(%error, adapter = %instance.name, "failed to build named email adapter from config change");
+                    }
+                }
+            }
+        }
+
+        // GitHub: start if enabled and not already running.
+        if let Some(github_config) = &config.messaging.github
+            && github_config.enabled
+            && !github_config.repos.is_empty()
+            && !manager.has_adapter("github").await
+        {
+            match crate::messaging::github::GithubAdapter::from_config(github_config) {
+                Ok(adapter) => {
+                    if let Err(error) = manager.register_and_start(adapter).await {
+                        tracing::error!(%error, "failed to hot-start github adapter from config change");
+                    }
+                }
+                Err(error) => {
+                    tracing::error!(%error, "failed to build github adapter from config change");
+                }
+            }
+        }
+
+        // IRC: start if enabled and not already running.
+        if let Some(irc_config) = &config.messaging.irc
+            && irc_config.enabled
+            && !manager.has_adapter("irc").await
+        {
+            let adapter = crate::messaging::irc::IrcAdapter::from_config(irc_config);
+            if let Err(error) = manager.register_and_start(adapter).await {
+                tracing::error!(%error, "failed to hot-start irc adapter from config change");
+            }
+        }
+
+        // Matrix: start if enabled and not already running.
+        if let Some(matrix_config) = &config.messaging.matrix
+            && matrix_config.enabled
+            && !manager.has_adapter("matrix").await
+        {
+            let adapter =
+                crate::messaging::matrix::MatrixAdapter::from_config(matrix_config, &instance_dir);
+            if let Err(error) = manager.register_and_start(adapter).await {
+                tracing::error!(%error, "failed to hot-start matrix adapter from config change");
+            }
+        }
+
+        // Twitch: start default + named instances that are enabled and not already running.
+        if let Some(twitch_config) = &config.messaging.twitch
+            && twitch_config.enabled
+        {
+            if !twitch_config.username.is_empty()
+                && !twitch_config.oauth_token.is_empty()
+                && !manager.has_adapter("twitch").await
+            {
+                let permissions = match twitch_permissions {
+                    Some(ref existing) => existing.clone(),
+                    None => {
+                        let permissions =
+                            TwitchPermissions::from_config(twitch_config, &config.bindings);
+                        Arc::new(arc_swap::ArcSwap::from_pointee(permissions))
+                    }
+                };
+                let token_path = instance_dir.join("twitch_token.json");
+                let adapter = crate::messaging::twitch::TwitchAdapter::new(
+                    "twitch",
+                    &twitch_config.username,
+                    &twitch_config.oauth_token,
+                    twitch_config.client_id.clone(),
+                    twitch_config.client_secret.clone(),
+                    twitch_config.refresh_token.clone(),
+                    Some(token_path),
+                    twitch_config.channels.clone(),
+                    twitch_config.trigger_prefix.clone(),
+                    permissions,
+                );
+                if let Err(error) = manager.register_and_start(adapter).await {
+                    tracing::error!(%error, "failed to hot-start twitch adapter from config change");
+                }
+            }
+
+            for instance in twitch_config
+                .instances
+                .iter()
+                .filter(|instance| instance.enabled)
+            {
+                let runtime_key =
+                    binding_runtime_adapter_key("twitch", Some(instance.name.as_str()));
+                if manager.has_adapter(runtime_key.as_str()).await {
+                    continue;
+                }
+
+                let token_file_name = {
+                    use std::hash::{Hash, Hasher};
+                    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                    instance.name.hash(&mut hasher);
+                    let name_hash = hasher.finish();
+                    format!(
+                        "twitch_token_{}_{name_hash:016x}.json",
+                        instance
+                            .name
+                            .chars()
+                            .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+                            .collect::<String>()
+                    )
+                };
+                let token_path = instance_dir.join(token_file_name);
+                let permissions = manager.instance_permissions().twitch.track(
+                    &runtime_key,
+                    TwitchPermissions::from_instance_config(instance, &config.bindings),
+                );
+                let adapter = crate::messaging::twitch::TwitchAdapter::new(
+                    runtime_key,
+                    &instance.username,
+                    &instance.oauth_token,
+                    instance.client_id.clone(),
+                    instance.client_secret.clone(),
+                    instance.refresh_token.clone(),
+                    Some(token_path),
+                    instance.channels.clone(),
+                    instance.trigger_prefix.clone(),
+                    permissions,
+                );
+                if let Err(error) = manager.register_and_start(adapter).await {
+                    tracing::error!(%error, adapter = %instance.name, "failed to hot-start named twitch adapter from config change");
+                }
+            }
+        }
+
+        // Signal: start default + named instances that are enabled and not already running.
+        if let Some(signal_config) = &config.messaging.signal
+            && signal_config.enabled
+        {
+            if !signal_config.http_url.is_empty()
+                && !signal_config.account.is_empty()
+                && !manager.has_adapter("signal").await
+            {
+                let permissions = match signal_permissions {
+                    Some(ref existing) => existing.clone(),
+                    None => {
+                        let permissions = SignalPermissions::from_config(signal_config);
+                        Arc::new(arc_swap::ArcSwap::from_pointee(permissions))
+                    }
+                };
+                let tmp_dir = instance_dir.join("tmp");
+                let adapter = crate::messaging::signal::SignalAdapter::new(
+                    "signal",
+                    &signal_config.http_url,
+                    &signal_config.account,
+                    signal_config.ignore_stories,
+                    permissions,
+                    tmp_dir,
+                );
+                if let Err(error) = manager.register_and_start(adapter).await {
+                    tracing::error!(%error, "failed to hot-start signal adapter from config change");
+                }
+            }
+
+            for instance in signal_config
+                .instances
+                .iter()
+                .filter(|instance| instance.enabled)
+            {
+                let runtime_key =
+                    binding_runtime_adapter_key("signal", Some(instance.name.as_str()));
+                if manager.has_adapter(runtime_key.as_str()).await {
+                    continue;
+                }
+
+                let permissions = manager.instance_permissions().signal.track(
+                    &runtime_key,
+                    SignalPermissions::from_instance_config(instance),
+                );
+                let tmp_dir = instance_dir.join("tmp");
+                let adapter = crate::messaging::signal::SignalAdapter::new(
+                    runtime_key,
+                    &instance.http_url,
+                    &instance.account,
+                    instance.ignore_stories,
+                    permissions,
+                    tmp_dir,
+                );
+                if let Err(error) = manager.register_and_start(adapter).await {
+                    tracing::error!(%error, adapter = %instance.name, "failed to hot-start named signal adapter from config change");
+                }
+            }
+        }
+    }
+}
+
+/// Rebuild the permissions of every tracked named adapter instance from the
+/// new config. Instances that aren't running have no handle and are skipped;
+/// they get fresh permissions when they start.
+fn reload_instance_permissions(config: &Config, handles: &InstancePermissions) {
+    let messaging = &config.messaging;
+    let mut reloaded = Vec::new();
+    for instance in messaging
+        .discord
+        .iter()
+        .flat_map(|discord| &discord.instances)
+    {
+        let key = binding_runtime_adapter_key("discord", Some(instance.name.as_str()));
+        let permissions = DiscordPermissions::from_instance_config(instance, &config.bindings);
+        if handles.discord.update(&key, permissions) {
+            reloaded.push(key);
+        }
+    }
+    for instance in messaging.slack.iter().flat_map(|slack| &slack.instances) {
+        let key = binding_runtime_adapter_key("slack", Some(instance.name.as_str()));
+        let permissions = SlackPermissions::from_instance_config(instance, &config.bindings);
+        if handles.slack.update(&key, permissions) {
+            reloaded.push(key);
+        }
+    }
+    for instance in messaging
+        .telegram
+        .iter()
+        .flat_map(|telegram| &telegram.instances)
+    {
+        let key = binding_runtime_adapter_key("telegram", Some(instance.name.as_str()));
+        let permissions = TelegramPermissions::from_instance_config(instance, &config.bindings);
+        if handles.telegram.update(&key, permissions) {
+            reloaded.push(key);
+        }
+    }
+    for instance in messaging.twitch.iter().flat_map(|twitch| &twitch.instances) {
+        let key = binding_runtime_adapter_key("twitch", Some(instance.name.as_str()));
+        let permissions = TwitchPermissions::from_instance_config(instance, &config.bindings);
+        if handles.twitch.update(&key, permissions) {
+            reloaded.push(key);
+        }
+    }
+    for instance in messaging.signal.iter().flat_map(|signal| &signal.instances) {
+        let key = binding_runtime_adapter_key("signal", Some(instance.name.as_str()));
+        if handles
+            .signal
+            .update(&key, SignalPermissions::from_instance_config(instance))
+        {
+            reloaded.push(key);
+        }
+    }
+    if !reloaded.is_empty() {
+        tracing::info!(adapters = %reloaded.join(", "), "named instance permissions reloaded");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(content: &str) -> toml::Table {
+        content.parse().unwrap()
+    }
+
+    #[test]
+    fn diff_reports_sections_and_restart_only_settings() {
+        let old = table(
+            r#"
+            [llm]
+            anthropic_key = "a"
+            [api]
+            port = 19898
+            [[agents]]
+            id = "main"
+            "#,
+        );
+        let new = table(
+            r#"
+            [llm]
+            anthropic_key = "b"
+            [api]
+            port = 19999
+            [[agents]]
+            id = "main"
+            [[agents]]
+            id = "ops"
+            "#,
+        );
+        let diff = ConfigDiff::between(&old, &new);
+        assert!(diff.changed("llm"));
+        assert!(diff.changed("api"));
+        assert!(diff.changed("agents"));
+        assert!(!diff.changed("messaging"));
+        assert_eq!(diff.restart_only, vec!["api.port".to_string()]);
+        assert_eq!(diff.agents_added, vec!["ops".to_string()]);
+        assert!(diff.agents_removed.is_empty());
+
+        assert!(ConfigDiff::between(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn adapter_fingerprints_split_default_and_named_instances() {
+        let old = table(
+            r#"
+            [messaging.discord]
+            enabled = true
+            token = "one"
+            [[messaging.discord.instances]]
+            name = "ops"
+            enabled = true
+            token = "two"
+            [messaging.webhook]
+            port = 18789
+            "#,
+        );
+        let new = table(
+            r#"
+            [messaging.discord]
+            enabled = true
+            token = "one"
+            [[messaging.discord.instances]]
+            name = "ops"
+            enabled = true
+            token = "three"
+            [messaging.webhook]
+            port = 18789
+            "#,
+        );
+        let before = adapter_fingerprints(&old);
+        let after = adapter_fingerprints(&new);
+        assert_eq!(before.len(), 2, "webhook isn't a managed adapter");
+        assert_eq!(before.get("discord"), after.get("discord"));
+        assert_ne!(before.get("discord:ops"), after.get("discord:ops"));
+        assert!(is_managed_adapter("discord:ops"));
+        assert!(!is_managed_adapter("webchat"));
+    }
+
+    #[test]
+    fn reload_swaps_running_named_instance_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            r#"
+            [llm]
+            anthropic_key = "test"
+            [messaging.discord]
+            enabled = true
+            token = "one"
+            [[messaging.discord.instances]]
+            name = "ops"
+            enabled = true
+            token = "two"
+            dm_allowed_users = ["42"]
+            [[messaging.discord.instances]]
+            name = "idle"
+            enabled = true
+            token = "three"
+            [[agents]]
+            id = "main"
+            "#,
+        )
+        .unwrap();
+        let config = Config::load_from_path(&config_path).unwrap();
+
+        let handles = InstancePermissions::default();
+        let ops = handles
+            .discord
+            .track("discord:ops", DiscordPermissions::default());
+        assert!(ops.load().dm_allowed_users.is_empty());
+
+        reload_instance_permissions(&config, &handles);
+
+        assert_eq!(ops.load().dm_allowed_users, vec![42]);
+        assert!(handles.discord.get("discord:idle").is_none());
+
+        handles.remove("discord:ops");
+        assert!(
+            !handles
+                .discord
+                .update("discord:ops", DiscordPermissions::default())
+        );
+    }
+}
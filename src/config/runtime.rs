@@ -1,14 +1,17 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use arc_swap::ArcSwap;
 
 use super::{
-    BrowserConfig, ChannelConfig, CoalesceConfig, CompactionConfig, Config, CortexConfig,
-    DefaultsConfig, IngestionConfig, McpServerConfig, MemoryPersistenceConfig, OpenCodeConfig,
-    ResolvedAgentConfig, WarmupConfig, WarmupStatus, WorkReadiness, evaluate_work_readiness,
+    ArchiveConfig, BranchCacheConfig, BrowserConfig, BudgetConfig, ChannelConfig, CoalesceConfig,
+    CompactionConfig, Config, ContainerConfig, CortexConfig, DefaultsConfig, GitConfig,
+    IngestionConfig, McpServerConfig, MemoryPersistenceConfig, ModerationConfig, OpenCodeConfig,
+    PrefetchConfig, ResolvedAgentConfig, StorageConfig, TranscriptionConfig, WarmupConfig,
+    WarmupStatus, WorkReadiness, evaluate_work_readiness,
 };
-use crate::llm::routing::RoutingConfig;
+use crate::llm::routing::{ChannelRouting, RoutingConfig};
 use crate::tools::browser::SharedBrowserHandle;
 
 /// Live configuration that can be hot-reloaded without restarting.
@@ -25,9 +28,15 @@ pub struct RuntimeConfig {
     /// files (SOUL.md, IDENTITY.md, ROLE.md) live here, outside the workspace
     /// sandbox boundary. Immutable after startup.
     pub identity_dir: PathBuf,
+    /// Agent data directory (SQLite, LanceDB, redb). Immutable after startup.
+    pub data_dir: PathBuf,
+    /// Agent archives directory. Immutable after startup.
+    pub archives_dir: PathBuf,
     pub routing: ArcSwap<RoutingConfig>,
     pub compaction: ArcSwap<CompactionConfig>,
     pub memory_persistence: ArcSwap<MemoryPersistenceConfig>,
+    pub prefetch: ArcSwap<PrefetchConfig>,
+    pub branch_cache: ArcSwap<BranchCacheConfig>,
     pub coalesce: ArcSwap<CoalesceConfig>,
     pub ingestion: ArcSwap<IngestionConfig>,
     pub channel_config: ArcSwap<ChannelConfig>,
@@ -36,6 +45,7 @@ pub struct RuntimeConfig {
     pub context_window: ArcSwap<usize>,
     pub max_concurrent_branches: ArcSwap<usize>,
     pub max_concurrent_workers: ArcSwap<usize>,
+    pub worker_batch_concurrency: ArcSwap<usize>,
     pub browser_config: ArcSwap<BrowserConfig>,
     pub mcp: ArcSwap<Vec<McpServerConfig>>,
     pub history_backfill_count: ArcSwap<usize>,
@@ -48,15 +58,35 @@ pub struct RuntimeConfig {
     pub warmup_status: ArcSwap<WarmupStatus>,
     /// Synchronizes warmup passes so periodic and API-triggered runs don't overlap.
     pub warmup_lock: Arc<tokio::sync::Mutex<()>>,
+    pub storage: ArcSwap<StorageConfig>,
+    /// Most recent disk usage measurement. None until the first check.
+    pub storage_usage: ArcSwap<Option<crate::agent::storage::AgentDiskUsage>>,
+    pub archive: ArcSwap<ArchiveConfig>,
+    /// Periodic background jobs, registered by their loops as they start.
+    pub jobs: crate::agent::jobs::JobRegistry,
+    pub moderation: ArcSwap<ModerationConfig>,
+    pub transcription: ArcSwap<TranscriptionConfig>,
+    pub budget: ArcSwap<BudgetConfig>,
+    /// Today's spend against `budget`, persisted in the data directory.
+    pub spend: Arc<crate::llm::budget::SpendLedger>,
     /// Cached memory bulletin generated by the cortex. Injected into every
     /// channel's system prompt. Empty string until the first cortex run.
     pub memory_bulletin: ArcSwap<String>,
+    /// Facts pinned ahead of the generated bulletin. Loaded at startup and
+    /// swapped by the pin tools and API.
+    pub bulletin_pins: ArcSwap<Vec<crate::memory::BulletinPin>>,
     pub prompts: ArcSwap<crate::prompts::PromptEngine>,
+    /// The running A/B experiment, loaded at startup and swapped by the API.
+    pub experiment: ArcSwap<Option<Arc<crate::agent::experiments::Experiment>>>,
     pub identity: ArcSwap<crate::identity::Identity>,
     pub skills: ArcSwap<crate::skills::SkillSet>,
     pub opencode: ArcSwap<OpenCodeConfig>,
     /// Shared pool of OpenCode server processes. Lazily initialized on first use.
     pub opencode_server_pool: ArcSwap<crate::opencode::OpenCodeServerPool>,
+    pub container: ArcSwap<ContainerConfig>,
+    pub git: ArcSwap<GitConfig>,
+    /// Latest health probes of the OpenCode/container worker binaries.
+    pub cli_worker_health: crate::cli_worker::CliWorkerHealth,
     /// Cron store, set after agent initialization.
     pub cron_store: ArcSwap<Option<Arc<crate::cron::CronStore>>>,
     /// Cron scheduler, set after agent initialization.
@@ -106,9 +136,13 @@ impl RuntimeConfig {
             instance_dir: instance_dir.to_path_buf(),
             workspace_dir: agent_config.workspace.clone(),
             identity_dir: agent_config.identity_dir.clone(),
+            data_dir: agent_config.data_dir.clone(),
+            archives_dir: agent_config.archives_dir.clone(),
             routing: ArcSwap::from_pointee(agent_config.routing.clone()),
             compaction: ArcSwap::from_pointee(agent_config.compaction),
             memory_persistence: ArcSwap::from_pointee(agent_config.memory_persistence),
+            prefetch: ArcSwap::from_pointee(agent_config.prefetch),
+            branch_cache: ArcSwap::from_pointee(agent_config.branch_cache),
             coalesce: ArcSwap::from_pointee(agent_config.coalesce),
             ingestion: ArcSwap::from_pointee(agent_config.ingestion),
             channel_config: ArcSwap::from_pointee(agent_config.channel),
@@ -117,6 +151,7 @@ impl RuntimeConfig {
             context_window: ArcSwap::from_pointee(agent_config.context_window),
             max_concurrent_branches: ArcSwap::from_pointee(agent_config.max_concurrent_branches),
             max_concurrent_workers: ArcSwap::from_pointee(agent_config.max_concurrent_workers),
+            worker_batch_concurrency: ArcSwap::from_pointee(agent_config.worker_batch_concurrency),
             browser_config: ArcSwap::from_pointee(agent_config.browser.clone()),
             mcp: ArcSwap::from_pointee(agent_config.mcp.clone()),
             history_backfill_count: ArcSwap::from_pointee(agent_config.history_backfill_count),
@@ -127,12 +162,27 @@ impl RuntimeConfig {
             warmup: ArcSwap::from_pointee(agent_config.warmup),
             warmup_status: ArcSwap::from_pointee(WarmupStatus::default()),
             warmup_lock: Arc::new(tokio::sync::Mutex::new(())),
+            storage: ArcSwap::from_pointee(agent_config.storage),
+            storage_usage: ArcSwap::from_pointee(None),
+            archive: ArcSwap::from_pointee(agent_config.archive),
+            jobs: crate::agent::jobs::JobRegistry::default(),
+            moderation: ArcSwap::from_pointee(agent_config.moderation.clone()),
+            transcription: ArcSwap::from_pointee(agent_config.transcription.clone()),
+            budget: ArcSwap::from_pointee(agent_config.budget.clone()),
+            spend: Arc::new(crate::llm::budget::SpendLedger::load(
+                &agent_config.data_dir,
+            )),
             memory_bulletin: ArcSwap::from_pointee(String::new()),
+            bulletin_pins: ArcSwap::from_pointee(Vec::new()),
             prompts: ArcSwap::from_pointee(prompts),
+            experiment: ArcSwap::from_pointee(None),
             identity: ArcSwap::from_pointee(identity),
             skills: ArcSwap::from_pointee(skills),
             opencode: ArcSwap::from_pointee(defaults.opencode.clone()),
             opencode_server_pool: ArcSwap::from_pointee(server_pool),
+            container: ArcSwap::from_pointee(defaults.container.clone()),
+            git: ArcSwap::from_pointee(defaults.git.clone()),
+            cli_worker_health: crate::cli_worker::CliWorkerHealth::default(),
             cron_store: ArcSwap::from_pointee(None),
             cron_scheduler: ArcSwap::from_pointee(None),
             settings: ArcSwap::from_pointee(None),
@@ -202,6 +252,28 @@ impl RuntimeConfig {
         self.work_readiness().ready
     }
 
+    /// Replace the live per-channel model overrides, e.g. with the set
+    /// loaded from the channels table at startup.
+    pub fn set_channel_routing_overrides(&self, overrides: HashMap<String, ChannelRouting>) {
+        let mut routing = (**self.routing.load()).clone();
+        routing.channel_overrides = overrides;
+        self.routing.store(Arc::new(routing));
+    }
+
+    /// Set or clear one channel's model overrides in the live routing config.
+    /// Persisting them is the caller's job.
+    pub fn set_channel_routing(&self, channel_id: &str, channel_routing: ChannelRouting) {
+        let mut routing = (**self.routing.load()).clone();
+        if channel_routing.is_empty() {
+            routing.channel_overrides.remove(channel_id);
+        } else {
+            routing
+                .channel_overrides
+                .insert(channel_id.to_string(), channel_routing);
+        }
+        self.routing.store(Arc::new(routing));
+    }
+
     /// Path to the saved attachments directory for persisted channel files.
     pub fn saved_dir(&self) -> std::path::PathBuf {
         self.workspace_dir.join("saved")
@@ -229,10 +301,15 @@ impl RuntimeConfig {
         let old_mcp = (**self.mcp.load()).clone();
         let new_mcp = resolved.mcp.clone();
 
-        self.routing.store(Arc::new(resolved.routing));
+        // Channel overrides live in the database, not config.toml.
+        let mut routing = resolved.routing;
+        routing.channel_overrides = self.routing.load().channel_overrides.clone();
+        self.routing.store(Arc::new(routing));
         self.compaction.store(Arc::new(resolved.compaction));
         self.memory_persistence
             .store(Arc::new(resolved.memory_persistence));
+        self.prefetch.store(Arc::new(resolved.prefetch));
+        self.branch_cache.store(Arc::new(resolved.branch_cache));
         self.coalesce.store(Arc::new(resolved.coalesce));
         self.ingestion.store(Arc::new(resolved.ingestion));
         let resolved_channel = resolved.channel;
@@ -267,6 +344,8 @@ impl RuntimeConfig {
             .store(Arc::new(resolved.max_concurrent_branches));
         self.max_concurrent_workers
             .store(Arc::new(resolved.max_concurrent_workers));
+        self.worker_batch_concurrency
+            .store(Arc::new(resolved.worker_batch_concurrency));
         let old_persist = self.browser_config.load().persist_session;
         let new_persist = resolved.browser.persist_session;
         if old_persist != new_persist {
@@ -287,6 +366,12 @@ impl RuntimeConfig {
         self.user_timezone.store(Arc::new(resolved.user_timezone));
         self.cortex.store(Arc::new(resolved.cortex));
         self.warmup.store(Arc::new(resolved.warmup));
+        self.storage.store(Arc::new(resolved.storage));
+        self.archive.store(Arc::new(resolved.archive));
+        self.moderation.store(Arc::new(resolved.moderation.clone()));
+        self.transcription
+            .store(Arc::new(resolved.transcription.clone()));
+        self.budget.store(Arc::new(resolved.budget.clone()));
         // Preserve project_paths from the current sandbox config when
         // reloading — the resolved config only has user-configured paths.
         let existing_project_paths = self.sandbox.load().project_paths.clone();
@@ -315,11 +400,28 @@ impl RuntimeConfig {
             );
         }
 
+        self.container
+            .store(Arc::new(config.defaults.container.clone()));
+        self.git.store(Arc::new(config.defaults.git.clone()));
+
         mcp_manager.reconcile(&old_mcp, &new_mcp).await;
 
         tracing::info!(agent_id, "runtime config reloaded");
     }
 
+    /// The bulletin as injected into prompts: unexpired pins, then the
+    /// generated bulletin.
+    pub fn rendered_bulletin(&self) -> String {
+        let pinned =
+            crate::memory::pins::render_pins(&self.bulletin_pins.load(), chrono::Utc::now());
+        let generated = self.memory_bulletin.load();
+        match (pinned.is_empty(), generated.is_empty()) {
+            (true, _) => generated.to_string(),
+            (false, true) => pinned,
+            (false, false) => format!("{pinned}\n{generated}"),
+        }
+    }
+
     /// Reload identity files from disk.
     pub fn reload_identity(&self, identity: crate::identity::Identity) {
         self.identity.store(Arc::new(identity));
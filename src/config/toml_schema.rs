@@ -16,6 +16,8 @@ pub(super) struct TomlConfig {
     #[serde(default)]
     pub(super) groups: Vec<TomlGroupDef>,
     #[serde(default)]
+    pub(super) teams: Vec<TomlTeamDef>,
+    #[serde(default)]
     pub(super) humans: Vec<TomlHumanDef>,
     #[serde(default)]
     pub(super) messaging: TomlMessagingConfig,
@@ -27,6 +29,8 @@ pub(super) struct TomlConfig {
     pub(super) metrics: TomlMetricsConfig,
     #[serde(default)]
     pub(super) telemetry: TomlTelemetryConfig,
+    #[serde(default)]
+    pub(super) cluster: TomlClusterConfig,
 }
 
 #[derive(Deserialize)]
@@ -58,6 +62,22 @@ pub(super) struct TomlGroupDef {
     pub(super) color: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub(super) struct TomlTeamDef {
+    pub(super) id: String,
+    pub(super) name: Option<String>,
+    pub(super) description: Option<String>,
+    pub(super) coordinator: String,
+    #[serde(default)]
+    pub(super) members: Vec<TomlTeamMemberDef>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlTeamMemberDef {
+    pub(super) agent: String,
+    pub(super) specialty: String,
+}
+
 #[derive(Deserialize)]
 pub(super) struct TomlHumanDef {
     pub(super) id: String,
@@ -78,6 +98,17 @@ pub(super) struct TomlTelemetryConfig {
     pub(super) sample_rate: Option<f64>,
 }
 
+#[derive(Deserialize, Default)]
+pub(super) struct TomlClusterConfig {
+    #[serde(default)]
+    pub(super) enabled: bool,
+    pub(super) node_id: Option<String>,
+    pub(super) database: Option<String>,
+    pub(super) advertise_url: Option<String>,
+    pub(super) lease_secs: Option<u64>,
+    pub(super) distribute_tasks: Option<bool>,
+}
+
 #[derive(Deserialize)]
 pub(super) struct TomlApiConfig {
     #[serde(default = "default_api_enabled")]
@@ -88,6 +119,10 @@ pub(super) struct TomlApiConfig {
     pub(super) bind: String,
     #[serde(default)]
     pub(super) auth_token: Option<String>,
+    #[serde(default)]
+    pub(super) auth: TomlApiAuthConfig,
+    #[serde(default)]
+    pub(super) rate_limit: Option<TomlApiRateLimitConfig>,
 }
 
 impl Default for TomlApiConfig {
@@ -97,10 +132,45 @@ impl Default for TomlApiConfig {
             port: default_api_port(),
             bind: default_api_bind(),
             auth_token: None,
+            auth: TomlApiAuthConfig::default(),
+            rate_limit: None,
         }
     }
 }
 
+#[derive(Deserialize, Default)]
+pub(super) struct TomlApiAuthConfig {
+    #[serde(default)]
+    pub(super) keys: Vec<TomlApiKeyConfig>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlApiKeyConfig {
+    pub(super) name: Option<String>,
+    pub(super) key: String,
+    /// RFC 3339 timestamp.
+    pub(super) expires_at: Option<String>,
+    /// "admin" (default), "operator", or "viewer".
+    pub(super) role: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlApiRateLimitConfig {
+    #[serde(default = "default_api_enabled")]
+    pub(super) enabled: bool,
+    pub(super) requests_per_minute: Option<u32>,
+    pub(super) burst: Option<u32>,
+    #[serde(default)]
+    pub(super) routes: Vec<TomlRouteRateLimit>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlRouteRateLimit {
+    pub(super) path: String,
+    pub(super) requests_per_minute: u32,
+    pub(super) burst: Option<u32>,
+}
+
 pub(super) fn default_api_enabled() -> bool {
     true
 }
@@ -154,6 +224,44 @@ pub(super) struct TomlProviderConfig {
     pub(super) name: Option<String>,
 }
 
+#[derive(Deserialize, Debug, Default)]
+pub(super) struct TomlProviderQuota {
+    pub(super) monthly_usd: Option<f64>,
+    pub(super) monthly_tokens: Option<u64>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(super) struct TomlOAuthProviderConfig {
+    pub(super) api_type: super::ApiType,
+    pub(super) base_url: String,
+    pub(super) name: Option<String>,
+    pub(super) client_id: String,
+    pub(super) client_secret: Option<String>,
+    pub(super) authorize_url: String,
+    pub(super) token_url: String,
+    #[serde(default)]
+    pub(super) scopes: Vec<String>,
+    pub(super) redirect_uri: Option<String>,
+    #[serde(default)]
+    pub(super) authorize_params: std::collections::BTreeMap<String, String>,
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct TomlResponseCacheConfig {
+    pub(super) enabled: Option<bool>,
+    pub(super) ttl_secs: Option<u64>,
+    pub(super) max_entries: Option<usize>,
+}
+
+#[derive(Deserialize, Default)]
+pub(super) struct TomlEmbeddingConfig {
+    pub(super) provider: Option<String>,
+    pub(super) model: Option<String>,
+    pub(super) dimensions: Option<usize>,
+    pub(super) base_url: Option<String>,
+    pub(super) api_key: Option<String>,
+}
+
 #[derive(Deserialize, Default)]
 pub(super) struct TomlLlmConfigFields {
     pub(super) anthropic_key: Option<String>,
@@ -181,6 +289,15 @@ pub(super) struct TomlLlmConfigFields {
     #[serde(default)]
     pub(super) providers: HashMap<String, TomlProviderConfig>,
     #[serde(default)]
+    pub(super) quota: HashMap<String, TomlProviderQuota>,
+    #[serde(default)]
+    pub(super) oauth: HashMap<String, TomlOAuthProviderConfig>,
+    pub(super) health_probe_interval_secs: Option<u64>,
+    #[serde(default)]
+    pub(super) response_cache: TomlResponseCacheConfig,
+    #[serde(default)]
+    pub(super) embedding: TomlEmbeddingConfig,
+    #[serde(default)]
     #[serde(flatten)]
     pub(super) extra: HashMap<String, toml::Value>,
 }
@@ -210,6 +327,11 @@ pub(super) struct TomlLlmConfig {
     pub(super) zai_coding_plan_key: Option<String>,
     pub(super) github_copilot_key: Option<String>,
     pub(super) providers: HashMap<String, TomlProviderConfig>,
+    pub(super) quota: HashMap<String, TomlProviderQuota>,
+    pub(super) oauth: HashMap<String, TomlOAuthProviderConfig>,
+    pub(super) health_probe_interval_secs: Option<u64>,
+    pub(super) response_cache: TomlResponseCacheConfig,
+    pub(super) embedding: TomlEmbeddingConfig,
 }
 
 impl<'de> Deserialize<'de> for TomlLlmConfig {
@@ -266,6 +388,11 @@ impl<'de> Deserialize<'de> for TomlLlmConfig {
             zai_coding_plan_key: fields.zai_coding_plan_key,
             github_copilot_key: fields.github_copilot_key,
             providers: fields.providers,
+            quota: fields.quota,
+            oauth: fields.oauth,
+            health_probe_interval_secs: fields.health_probe_interval_secs,
+            response_cache: fields.response_cache,
+            embedding: fields.embedding,
         })
     }
 }
@@ -275,15 +402,24 @@ pub(super) struct TomlDefaultsConfig {
     pub(super) routing: Option<TomlRoutingConfig>,
     pub(super) max_concurrent_branches: Option<usize>,
     pub(super) max_concurrent_workers: Option<usize>,
+    pub(super) worker_batch_concurrency: Option<usize>,
     pub(super) max_turns: Option<usize>,
     pub(super) branch_max_turns: Option<usize>,
     pub(super) context_window: Option<usize>,
     pub(super) compaction: Option<TomlCompactionConfig>,
     pub(super) memory_persistence: Option<TomlMemoryPersistenceConfig>,
+    pub(super) prefetch: Option<TomlPrefetchConfig>,
+    pub(super) branch_cache: Option<TomlBranchCacheConfig>,
     pub(super) coalesce: Option<TomlCoalesceConfig>,
     pub(super) ingestion: Option<TomlIngestionConfig>,
     pub(super) cortex: Option<TomlCortexConfig>,
     pub(super) warmup: Option<TomlWarmupConfig>,
+    pub(super) storage: Option<TomlStorageConfig>,
+    pub(super) archive: Option<TomlArchiveConfig>,
+    pub(super) moderation: Option<TomlModerationConfig>,
+    pub(super) transcription: Option<TomlTranscriptionConfig>,
+    pub(super) budget: Option<TomlBudgetConfig>,
+    pub(super) memory_fts: Option<TomlMemoryFtsConfig>,
     pub(super) browser: Option<TomlBrowserConfig>,
     pub(super) channel: Option<TomlChannelConfig>,
     #[serde(default)]
@@ -292,6 +428,8 @@ pub(super) struct TomlDefaultsConfig {
     pub(super) cron_timezone: Option<String>,
     pub(super) user_timezone: Option<String>,
     pub(super) opencode: Option<TomlOpenCodeConfig>,
+    pub(super) container: Option<TomlContainerConfig>,
+    pub(super) git: Option<TomlGitConfig>,
     pub(super) worker_log_mode: Option<String>,
     pub(super) projects: Option<TomlProjectsConfig>,
 }
@@ -313,6 +451,20 @@ pub(super) struct TomlRoutingConfig {
     #[serde(default)]
     pub(super) task_overrides: HashMap<String, String>,
     pub(super) fallbacks: Option<HashMap<String, Vec<String>>>,
+    pub(super) equivalents: Option<HashMap<String, Vec<String>>>,
+    pub(super) context_windows: Option<HashMap<String, usize>>,
+    pub(super) latency_aware: Option<bool>,
+    #[serde(default)]
+    pub(super) sampling: HashMap<String, TomlSamplingConfig>,
+    #[serde(default)]
+    pub(super) channel_sampling: HashMap<String, TomlSamplingConfig>,
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+pub(super) struct TomlSamplingConfig {
+    pub(super) temperature: Option<f64>,
+    pub(super) top_p: Option<f64>,
+    pub(super) max_tokens: Option<u64>,
 }
 
 #[derive(Deserialize)]
@@ -321,6 +473,18 @@ pub(super) struct TomlMemoryPersistenceConfig {
     pub(super) message_interval: Option<usize>,
 }
 
+#[derive(Deserialize)]
+pub(super) struct TomlPrefetchConfig {
+    pub(super) enabled: Option<bool>,
+    pub(super) ttl_secs: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlBranchCacheConfig {
+    pub(super) enabled: Option<bool>,
+    pub(super) ttl_secs: Option<u64>,
+}
+
 #[derive(Deserialize)]
 pub(super) struct TomlCoalesceConfig {
     pub(super) enabled: Option<bool>,
@@ -328,6 +492,7 @@ pub(super) struct TomlCoalesceConfig {
     pub(super) max_wait_ms: Option<u64>,
     pub(super) min_messages: Option<usize>,
     pub(super) multi_user_only: Option<bool>,
+    pub(super) sender_debounce_ms: Option<u64>,
 }
 
 #[derive(Deserialize)]
@@ -335,6 +500,8 @@ pub(super) struct TomlIngestionConfig {
     pub(super) enabled: Option<bool>,
     pub(super) poll_interval_secs: Option<u64>,
     pub(super) chunk_size: Option<usize>,
+    pub(super) chunking: Option<String>,
+    pub(super) chunk_overlap: Option<usize>,
 }
 
 #[derive(Deserialize)]
@@ -355,6 +522,7 @@ pub(super) struct TomlCortexConfig {
     pub(super) bulletin_interval_secs: Option<u64>,
     pub(super) bulletin_max_words: Option<usize>,
     pub(super) bulletin_max_turns: Option<usize>,
+    pub(super) bulletin_pin_max_words: Option<usize>,
     pub(super) maintenance_interval_secs: Option<u64>,
     pub(super) maintenance_decay_rate: Option<f32>,
     pub(super) maintenance_prune_threshold: Option<f32>,
@@ -364,6 +532,9 @@ pub(super) struct TomlCortexConfig {
     pub(super) association_similarity_threshold: Option<f32>,
     pub(super) association_updates_threshold: Option<f32>,
     pub(super) association_max_per_pass: Option<usize>,
+    pub(super) fact_extraction_enabled: Option<bool>,
+    pub(super) fact_extraction_interval_secs: Option<u64>,
+    pub(super) fact_extraction_max_messages: Option<usize>,
 }
 
 #[derive(Deserialize)]
@@ -374,6 +545,80 @@ pub(super) struct TomlWarmupConfig {
     pub(super) startup_delay_secs: Option<u64>,
 }
 
+#[derive(Deserialize)]
+pub(super) struct TomlStorageConfig {
+    pub(super) quota_mb: Option<u64>,
+    pub(super) warn_percent: Option<u8>,
+    pub(super) auto_cleanup: Option<bool>,
+    pub(super) check_interval_secs: Option<u64>,
+    pub(super) retention_days: Option<u64>,
+    pub(super) optimize_interval_secs: Option<u64>,
+    pub(super) optimize_idle_secs: Option<u64>,
+    pub(super) db_maintenance_interval_secs: Option<u64>,
+    pub(super) db_vacuum_min_free_percent: Option<u8>,
+    pub(super) db_wal_autocheckpoint_pages: Option<u32>,
+    pub(super) db_journal_size_limit_mb: Option<u64>,
+    pub(super) encrypt_at_rest: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlArchiveConfig {
+    pub(super) max_messages: Option<usize>,
+    pub(super) max_age_days: Option<u64>,
+    pub(super) keep_recent: Option<usize>,
+    pub(super) segment_size: Option<usize>,
+    pub(super) raw_messages: Option<String>,
+    pub(super) check_interval_secs: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlModerationConfig {
+    pub(super) strictness: Option<String>,
+    pub(super) rules: Option<Vec<TomlModerationRule>>,
+    pub(super) classifier_model: Option<String>,
+    pub(super) classifier_level: Option<String>,
+    pub(super) block_notice: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlModerationRule {
+    pub(super) name: Option<String>,
+    pub(super) pattern: Option<String>,
+    pub(super) keywords: Option<Vec<String>>,
+    pub(super) action: Option<String>,
+    pub(super) replacement: Option<String>,
+    pub(super) level: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlTranscriptionConfig {
+    pub(super) url: Option<String>,
+    pub(super) api_key: Option<String>,
+    pub(super) model: Option<String>,
+    pub(super) language: Option<String>,
+    pub(super) max_file_mb: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlBudgetConfig {
+    pub(super) daily_soft_usd: Option<f64>,
+    pub(super) daily_hard_usd: Option<f64>,
+    pub(super) channel_soft_usd: Option<f64>,
+    pub(super) channel_hard_usd: Option<f64>,
+    pub(super) worker_soft_usd: Option<f64>,
+    pub(super) worker_hard_usd: Option<f64>,
+    pub(super) downgrade_model: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlMemoryFtsConfig {
+    pub(super) tokenizer: Option<String>,
+    pub(super) language: Option<String>,
+    pub(super) stemming: Option<bool>,
+    pub(super) remove_stop_words: Option<bool>,
+    pub(super) ascii_folding: Option<bool>,
+}
+
 #[derive(Deserialize)]
 pub(super) struct TomlBrowserConfig {
     pub(super) enabled: Option<bool>,
@@ -389,6 +634,9 @@ pub(super) struct TomlBrowserConfig {
 pub(super) struct TomlChannelConfig {
     pub(super) listen_only_mode: Option<bool>,
     pub(super) save_attachments: Option<bool>,
+    pub(super) pace: Option<String>,
+    pub(super) memory_scope: Option<String>,
+    pub(super) interrupt: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -401,6 +649,24 @@ pub(super) struct TomlOpenCodeConfig {
     pub(super) permissions: Option<TomlOpenCodePermissions>,
 }
 
+#[derive(Deserialize)]
+pub(super) struct TomlContainerConfig {
+    pub(super) enabled: Option<bool>,
+    pub(super) runtime: Option<String>,
+    pub(super) image: Option<String>,
+    pub(super) network: Option<String>,
+    pub(super) memory: Option<String>,
+    pub(super) cpus: Option<String>,
+    pub(super) pids_limit: Option<u64>,
+    pub(super) user: Option<String>,
+    pub(super) mounts: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlGitConfig {
+    pub(super) protected_branches: Option<Vec<String>>,
+}
+
 #[derive(Deserialize)]
 pub(super) struct TomlOpenCodePermissions {
     pub(super) edit: Option<String>,
@@ -451,15 +717,24 @@ pub(super) struct TomlAgentConfig {
     pub(super) routing: Option<TomlRoutingConfig>,
     pub(super) max_concurrent_branches: Option<usize>,
     pub(super) max_concurrent_workers: Option<usize>,
+    pub(super) worker_batch_concurrency: Option<usize>,
     pub(super) max_turns: Option<usize>,
     pub(super) branch_max_turns: Option<usize>,
     pub(super) context_window: Option<usize>,
     pub(super) compaction: Option<TomlCompactionConfig>,
     pub(super) memory_persistence: Option<TomlMemoryPersistenceConfig>,
+    pub(super) prefetch: Option<TomlPrefetchConfig>,
+    pub(super) branch_cache: Option<TomlBranchCacheConfig>,
     pub(super) coalesce: Option<TomlCoalesceConfig>,
     pub(super) ingestion: Option<TomlIngestionConfig>,
     pub(super) cortex: Option<TomlCortexConfig>,
     pub(super) warmup: Option<TomlWarmupConfig>,
+    pub(super) storage: Option<TomlStorageConfig>,
+    pub(super) archive: Option<TomlArchiveConfig>,
+    pub(super) moderation: Option<TomlModerationConfig>,
+    pub(super) transcription: Option<TomlTranscriptionConfig>,
+    pub(super) budget: Option<TomlBudgetConfig>,
+    pub(super) memory_fts: Option<TomlMemoryFtsConfig>,
     pub(super) browser: Option<TomlBrowserConfig>,
     pub(super) channel: Option<TomlChannelConfig>,
     pub(super) mcp: Option<Vec<TomlMcpServerConfig>>,
@@ -501,6 +776,9 @@ pub(super) struct TomlMessagingConfig {
     pub(super) webhook: Option<TomlWebhookConfig>,
     pub(super) twitch: Option<TomlTwitchConfig>,
     pub(super) signal: Option<TomlSignalConfig>,
+    pub(super) github: Option<TomlGithubConfig>,
+    pub(super) matrix: Option<TomlMatrixConfig>,
+    pub(super) irc: Option<TomlIrcConfig>,
 }
 
 #[derive(Deserialize)]
@@ -514,6 +792,21 @@ pub(super) struct TomlDiscordConfig {
     pub(super) dm_allowed_users: Vec<String>,
     #[serde(default)]
     pub(super) allow_bot_messages: bool,
+    pub(super) voice: Option<TomlDiscordVoiceConfig>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlDiscordVoiceConfig {
+    #[serde(default)]
+    pub(super) enabled: bool,
+    pub(super) tts_url: Option<String>,
+    pub(super) tts_api_key: Option<String>,
+    pub(super) tts_model: Option<String>,
+    pub(super) tts_voice: Option<String>,
+    pub(super) max_spoken_chars: Option<usize>,
+    pub(super) silence_ms: Option<u64>,
+    pub(super) min_utterance_ms: Option<u64>,
+    pub(super) max_utterance_secs: Option<u64>,
 }
 
 #[derive(Deserialize)]
@@ -661,6 +954,59 @@ pub(super) struct TomlWebhookConfig {
     pub(super) auth_token: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub(super) struct TomlGithubConfig {
+    #[serde(default)]
+    pub(super) enabled: bool,
+    pub(super) token: Option<String>,
+    #[serde(default)]
+    pub(super) repos: Vec<String>,
+    #[serde(default = "default_github_poll_interval_secs")]
+    pub(super) poll_interval_secs: u64,
+    pub(super) api_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlMatrixConfig {
+    #[serde(default)]
+    pub(super) enabled: bool,
+    pub(super) homeserver_url: Option<String>,
+    pub(super) user_id: Option<String>,
+    pub(super) password: Option<String>,
+    pub(super) device_name: Option<String>,
+    #[serde(default)]
+    pub(super) allowed_rooms: Vec<String>,
+    #[serde(default)]
+    pub(super) allowed_users: Vec<String>,
+    #[serde(default = "default_enabled")]
+    pub(super) auto_join: bool,
+    #[serde(default = "default_matrix_max_attachment_bytes")]
+    pub(super) max_attachment_bytes: usize,
+}
+
+#[derive(Deserialize)]
+pub(super) struct TomlIrcConfig {
+    #[serde(default)]
+    pub(super) enabled: bool,
+    pub(super) server: Option<String>,
+    #[serde(default = "default_irc_port")]
+    pub(super) port: u16,
+    #[serde(default = "default_enabled")]
+    pub(super) use_tls: bool,
+    pub(super) nickname: Option<String>,
+    pub(super) username: Option<String>,
+    pub(super) realname: Option<String>,
+    pub(super) server_password: Option<String>,
+    pub(super) sasl_username: Option<String>,
+    pub(super) sasl_password: Option<String>,
+    #[serde(default)]
+    pub(super) channels: Vec<String>,
+    #[serde(default)]
+    pub(super) dm_allowed_users: Vec<String>,
+    #[serde(default = "default_irc_send_interval_ms")]
+    pub(super) send_interval_ms: u64,
+}
+
 #[derive(Deserialize)]
 pub(super) struct TomlTwitchConfig {
     #[serde(default)]
@@ -731,6 +1077,22 @@ pub(super) fn default_signal_ignore_stories() -> bool {
     true
 }
 
+pub(super) fn default_github_poll_interval_secs() -> u64 {
+    60
+}
+
+pub(super) fn default_matrix_max_attachment_bytes() -> usize {
+    20 * 1024 * 1024
+}
+
+pub(super) fn default_irc_port() -> u16 {
+    6697
+}
+
+pub(super) fn default_irc_send_interval_ms() -> u64 {
+    700
+}
+
 pub(super) fn default_webhook_port() -> u16 {
     18789
 }
@@ -785,4 +1147,6 @@ pub(super) struct TomlBinding {
     pub(super) require_mention: bool,
     #[serde(default)]
     pub(super) dm_allowed_users: Vec<String>,
+    pub(super) mention: Option<String>,
+    pub(super) prefix: Option<String>,
 }
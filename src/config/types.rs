@@ -47,6 +47,8 @@ pub struct Config {
     pub links: Vec<LinkDef>,
     /// Visual grouping of agents in the topology UI.
     pub groups: Vec<GroupDef>,
+    /// Coordinator-led agent teams.
+    pub teams: Vec<TeamDef>,
     /// Org-level humans (real people, shown in topology graph).
     pub humans: Vec<HumanDef>,
     /// Messaging platform credentials.
@@ -59,6 +61,8 @@ pub struct Config {
     pub metrics: MetricsConfig,
     /// OpenTelemetry export configuration.
     pub telemetry: TelemetryConfig,
+    /// Multi-node clustering.
+    pub cluster: ClusterConfig,
 }
 
 impl Config {
@@ -128,6 +132,40 @@ pub struct GroupDef {
     pub color: Option<String>,
 }
 
+/// A team of agents led by a coordinator. The coordinator's workers get a
+/// `team_dispatch` tool that fans a request out to the members.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TeamDef {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Agent ID of the coordinator.
+    pub coordinator: String,
+    pub members: Vec<TeamMemberDef>,
+}
+
+impl TeamDef {
+    pub fn display_name(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.id)
+    }
+
+    pub fn member(&self, agent_id: &str) -> Option<&TeamMemberDef> {
+        self.members.iter().find(|member| member.agent == agent_id)
+    }
+}
+
+/// A team member and what it should be given.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TeamMemberDef {
+    /// Agent ID of the member.
+    pub agent: String,
+    /// What the member handles, shown to the coordinator when it splits up
+    /// a request.
+    pub specialty: String,
+}
+
 /// HTTP API server configuration.
 #[derive(Debug, Clone)]
 pub struct ApiConfig {
@@ -138,6 +176,10 @@ pub struct ApiConfig {
     /// Address to bind the HTTP server on.
     pub bind: String,
     pub auth_token: Option<String>,
+    /// Keys accepted by the API. Includes `auth_token` when set.
+    pub auth: ApiAuthConfig,
+    /// Per-client request rate limits.
+    pub rate_limit: ApiRateLimitConfig,
 }
 
 impl Default for ApiConfig {
@@ -147,10 +189,175 @@ impl Default for ApiConfig {
             port: 19898,
             bind: "127.0.0.1".into(),
             auth_token: None,
+            auth: ApiAuthConfig::default(),
+            rate_limit: ApiRateLimitConfig::default(),
+        }
+    }
+}
+
+/// Credentials accepted by the HTTP API (`[api.auth]`).
+///
+/// Any unexpired key authenticates a request, so keys can be rotated without
+/// downtime: add the new key, move clients over, then remove the old one (or
+/// give it an `expires_at`). Changes are picked up on config reload. Keys are
+/// admins unless given a narrower `role`.
+#[derive(Debug, Clone, Default)]
+pub struct ApiAuthConfig {
+    pub keys: Vec<ApiKeyConfig>,
+}
+
+impl ApiAuthConfig {
+    /// Whether requests must present a key.
+    pub fn is_enabled(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    /// The unexpired key matching `presented`, if any.
+    pub fn authenticate(&self, presented: &str) -> Option<&ApiKeyConfig> {
+        let now = chrono::Utc::now();
+        self.keys.iter().find(|key| {
+            key.expires_at.is_none_or(|expires_at| expires_at > now)
+                && constant_time_eq(key.key.as_bytes(), presented.as_bytes())
+        })
+    }
+
+    /// The first unexpired key with the broadest role, for local clients
+    /// such as the CLI.
+    pub fn active_key(&self) -> Option<&str> {
+        let now = chrono::Utc::now();
+        self.keys
+            .iter()
+            .filter(|key| key.expires_at.is_none_or(|expires_at| expires_at > now))
+            .min_by_key(|key| std::cmp::Reverse(key.role))
+            .map(|key| key.key.as_str())
+    }
+}
+
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .fold(0u8, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+/// What an API credential may do. Each role includes everything the roles
+/// before it can do.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiRole {
+    /// Read channels, conversations, memories, and status.
+    Viewer,
+    /// Also send messages, run jobs, and manage channels, agents, and tasks.
+    Operator,
+    /// Also manage providers, secrets, config, and API users.
+    #[default]
+    Admin,
+}
+
+impl ApiRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApiRole::Viewer => "viewer",
+            ApiRole::Operator => "operator",
+            ApiRole::Admin => "admin",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "viewer" => Some(ApiRole::Viewer),
+            "operator" => Some(ApiRole::Operator),
+            "admin" => Some(ApiRole::Admin),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ApiRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A single API key.
+#[derive(Clone)]
+pub struct ApiKeyConfig {
+    /// Label used in logs and as the rate limit identity.
+    pub name: String,
+    pub key: String,
+    /// The key stops being accepted after this instant.
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub role: ApiRole,
+}
+
+impl std::fmt::Debug for ApiKeyConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiKeyConfig")
+            .field("name", &self.name)
+            .field("key", &"[REDACTED]")
+            .field("expires_at", &self.expires_at)
+            .field("role", &self.role)
+            .finish()
+    }
+}
+
+/// Token bucket rate limits for the HTTP API (`[api.rate_limit]`).
+///
+/// Each client (API key name, or remote address when unauthenticated) gets a
+/// bucket per matching rule. Requests beyond the burst are rejected with 429
+/// until tokens refill at `requests_per_minute`.
+#[derive(Debug, Clone, Default)]
+pub struct ApiRateLimitConfig {
+    pub enabled: bool,
+    /// Limit applied to routes without a more specific rule.
+    pub default: RateLimitRule,
+    /// Per-route overrides, matched by longest path prefix (relative to `/api`).
+    pub routes: Vec<RouteRateLimit>,
+}
+
+impl ApiRateLimitConfig {
+    /// Index of the matching route override (or `None` for the default) and
+    /// its rule.
+    pub fn rule_for(&self, path: &str) -> (Option<usize>, RateLimitRule) {
+        self.routes
+            .iter()
+            .enumerate()
+            .filter(|(_, route)| {
+                path.strip_prefix(route.path.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            })
+            .max_by_key(|(_, route)| route.path.len())
+            .map(|(index, route)| (Some(index), route.rule))
+            .unwrap_or((None, self.default))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitRule {
+    /// Sustained request rate.
+    pub requests_per_minute: u32,
+    /// Bucket size — requests allowed in a burst.
+    pub burst: u32,
+}
+
+impl Default for RateLimitRule {
+    fn default() -> Self {
+        Self {
+            requests_per_minute: 600,
+            burst: 120,
         }
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct RouteRateLimit {
+    /// Path prefix relative to `/api`, e.g. `/providers`.
+    pub path: String,
+    pub rule: RateLimitRule,
+}
+
 /// Prometheus metrics endpoint configuration.
 #[derive(Debug, Clone)]
 pub struct MetricsConfig {
@@ -172,6 +379,48 @@ impl Default for MetricsConfig {
     }
 }
 
+/// Multi-node clustering over a shared coordination database.
+#[derive(Debug, Clone)]
+pub struct ClusterConfig {
+    pub enabled: bool,
+    /// This node's name, unique within the cluster. Defaults to the host name.
+    pub node_id: String,
+    /// Coordination database every node opens. Must be on storage all nodes
+    /// share and that supports SQLite file locking.
+    pub database: PathBuf,
+    /// URL of this node's API, shown on the dashboard's node list.
+    pub advertise_url: Option<String>,
+    /// How long a heartbeat, conversation lease, or job lease stays valid
+    /// without renewal. A node that stops renewing is taken over after this.
+    pub lease_secs: u64,
+    /// Hand ready tasks to the least busy node instead of always running
+    /// them where they were picked up.
+    pub distribute_tasks: bool,
+}
+
+impl ClusterConfig {
+    pub(super) fn disabled(instance_dir: &Path) -> Self {
+        Self {
+            enabled: false,
+            node_id: default_node_id(),
+            database: instance_dir.join("data").join("cluster.db"),
+            advertise_url: None,
+            lease_secs: 30,
+            distribute_tasks: true,
+        }
+    }
+}
+
+/// The host name, from `HOSTNAME` or `/etc/hostname`.
+pub(super) fn default_node_id() -> String {
+    std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "spacebot".to_string())
+}
+
 /// API types supported by LLM providers.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ApiType {
@@ -245,6 +494,141 @@ impl std::fmt::Debug for ProviderConfig {
     }
 }
 
+/// Monthly usage ceiling for a provider. Once either limit is reached the
+/// provider is excluded from routing until the next calendar month (UTC).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProviderQuota {
+    pub monthly_usd: Option<f64>,
+    pub monthly_tokens: Option<u64>,
+}
+
+/// Opt-in cache of completion responses for idempotent calls such as
+/// compaction and cortex summaries (`[llm.response_cache]`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseCacheConfig {
+    pub enabled: bool,
+    /// How long a cached response stays valid.
+    pub ttl_secs: u64,
+    /// Entries kept before the oldest are evicted.
+    pub max_entries: usize,
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_secs: 3600,
+            max_entries: 512,
+        }
+    }
+}
+
+/// Backend that produces memory embeddings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmbeddingProviderKind {
+    /// all-MiniLM-L6-v2 run locally through fastembed (ONNX).
+    #[default]
+    FastEmbed,
+    /// OpenAI `/v1/embeddings`, or any host that speaks it.
+    OpenAi,
+    /// Gemini `batchEmbedContents`.
+    Gemini,
+    /// Ollama `/api/embed`.
+    Ollama,
+}
+
+impl EmbeddingProviderKind {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "fastembed" => Some(Self::FastEmbed),
+            "openai" => Some(Self::OpenAi),
+            "gemini" => Some(Self::Gemini),
+            "ollama" => Some(Self::Ollama),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::FastEmbed => "fastembed",
+            Self::OpenAi => "openai",
+            Self::Gemini => "gemini",
+            Self::Ollama => "ollama",
+        }
+    }
+}
+
+/// Model used to embed memories and conversation episodes
+/// (`[llm.embedding]`). Defaults to the local fastembed model; the remote
+/// providers are for hosts that can't run ONNX.
+#[derive(Clone, Default)]
+pub struct EmbeddingConfig {
+    pub provider: EmbeddingProviderKind,
+    /// Model name. Each provider has a default.
+    pub model: Option<String>,
+    /// Vector size to request. When unset, the model's known size is used,
+    /// or measured from a probe embedding at startup.
+    pub dimensions: Option<usize>,
+    /// Overrides the provider's endpoint.
+    pub base_url: Option<String>,
+    /// Falls back to `openai_key`, `gemini_key`, or `ollama_key`.
+    pub api_key: Option<String>,
+}
+
+impl std::fmt::Debug for EmbeddingConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EmbeddingConfig")
+            .field("provider", &self.provider)
+            .field("model", &self.model)
+            .field("dimensions", &self.dimensions)
+            .field("base_url", &self.base_url)
+            .field("api_key", &self.api_key.as_ref().map(|_| "[REDACTED]"))
+            .finish()
+    }
+}
+
+/// A provider that authenticates with OAuth instead of a static API key
+/// (`[llm.oauth.<provider>]`). Tokens come from the dashboard sign-in flow
+/// and are refreshed automatically; see `crate::llm::oauth`.
+#[derive(Clone)]
+pub struct OAuthProviderConfig {
+    /// API the provider speaks once authenticated.
+    pub api_type: ApiType,
+    pub base_url: String,
+    pub name: Option<String>,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    pub authorize_url: String,
+    pub token_url: String,
+    pub scopes: Vec<String>,
+    /// Callback URL registered with the provider. Defaults to the dashboard's
+    /// own `/api/providers/oauth/callback`.
+    pub redirect_uri: Option<String>,
+    /// Extra query parameters for the consent URL, e.g. Google's
+    /// `access_type = "offline"` to receive a refresh token.
+    pub authorize_params: std::collections::BTreeMap<String, String>,
+}
+
+impl std::fmt::Debug for OAuthProviderConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OAuthProviderConfig")
+            .field("api_type", &self.api_type)
+            .field("base_url", &self.base_url)
+            .field("name", &self.name)
+            .field("client_id", &self.client_id)
+            .field(
+                "client_secret",
+                &self.client_secret.as_ref().map(|_| "[REDACTED]"),
+            )
+            .field("authorize_url", &self.authorize_url)
+            .field("token_url", &self.token_url)
+            .field("scopes", &self.scopes)
+            .field("redirect_uri", &self.redirect_uri)
+            .field("authorize_params", &self.authorize_params)
+            .finish()
+    }
+}
+
 /// LLM provider credentials (instance-level).
 #[derive(Clone)]
 pub struct LlmConfig {
@@ -271,6 +655,14 @@ pub struct LlmConfig {
     pub zai_coding_plan_key: Option<String>,
     pub github_copilot_key: Option<String>,
     pub providers: HashMap<String, ProviderConfig>,
+    /// Monthly usage ceilings keyed by provider ID (`[llm.quota.<provider>]`).
+    pub quotas: HashMap<String, ProviderQuota>,
+    /// Providers signed into through OAuth, keyed by provider ID.
+    pub oauth: HashMap<String, OAuthProviderConfig>,
+    /// Seconds between background provider health probes. 0 disables probing.
+    pub health_probe_interval_secs: u64,
+    pub response_cache: ResponseCacheConfig,
+    pub embedding: EmbeddingConfig,
 }
 
 impl std::fmt::Debug for LlmConfig {
@@ -346,6 +738,14 @@ impl std::fmt::Debug for LlmConfig {
                 &self.github_copilot_key.as_ref().map(|_| "[REDACTED]"),
             )
             .field("providers", &self.providers)
+            .field("quotas", &self.quotas)
+            .field("oauth", &self.oauth)
+            .field(
+                "health_probe_interval_secs",
+                &self.health_probe_interval_secs,
+            )
+            .field("response_cache", &self.response_cache)
+            .field("embedding", &self.embedding)
             .finish()
     }
 }
@@ -525,15 +925,25 @@ pub struct DefaultsConfig {
     pub routing: RoutingConfig,
     pub max_concurrent_branches: usize,
     pub max_concurrent_workers: usize,
+    /// Max workers a single `spawn_worker_batch` call runs at once.
+    pub worker_batch_concurrency: usize,
     pub max_turns: usize,
     pub branch_max_turns: usize,
     pub context_window: usize,
     pub compaction: CompactionConfig,
     pub memory_persistence: MemoryPersistenceConfig,
+    pub prefetch: PrefetchConfig,
+    pub branch_cache: BranchCacheConfig,
     pub coalesce: CoalesceConfig,
     pub ingestion: IngestionConfig,
     pub cortex: CortexConfig,
     pub warmup: WarmupConfig,
+    pub storage: StorageConfig,
+    pub archive: ArchiveConfig,
+    pub moderation: ModerationConfig,
+    pub transcription: TranscriptionConfig,
+    pub budget: BudgetConfig,
+    pub memory_fts: MemoryFtsConfig,
     pub browser: BrowserConfig,
     pub channel: ChannelConfig,
     pub mcp: Vec<McpServerConfig>,
@@ -546,6 +956,8 @@ pub struct DefaultsConfig {
     pub history_backfill_count: usize,
     pub cron: Vec<CronDef>,
     pub opencode: OpenCodeConfig,
+    pub container: ContainerConfig,
+    pub git: GitConfig,
     /// Worker log mode: "errors_only", "all_separate", or "all_combined".
     pub worker_log_mode: crate::settings::WorkerLogMode,
     /// Projects workspace management defaults.
@@ -558,15 +970,24 @@ impl std::fmt::Debug for DefaultsConfig {
             .field("routing", &self.routing)
             .field("max_concurrent_branches", &self.max_concurrent_branches)
             .field("max_concurrent_workers", &self.max_concurrent_workers)
+            .field("worker_batch_concurrency", &self.worker_batch_concurrency)
             .field("max_turns", &self.max_turns)
             .field("branch_max_turns", &self.branch_max_turns)
             .field("context_window", &self.context_window)
             .field("compaction", &self.compaction)
             .field("memory_persistence", &self.memory_persistence)
+            .field("prefetch", &self.prefetch)
+            .field("branch_cache", &self.branch_cache)
             .field("coalesce", &self.coalesce)
             .field("ingestion", &self.ingestion)
             .field("cortex", &self.cortex)
             .field("warmup", &self.warmup)
+            .field("storage", &self.storage)
+            .field("archive", &self.archive)
+            .field("moderation", &self.moderation)
+            .field("transcription", &self.transcription)
+            .field("budget", &self.budget)
+            .field("memory_fts", &self.memory_fts)
             .field("browser", &self.browser)
             .field("channel", &self.channel)
             .field("mcp", &self.mcp)
@@ -579,6 +1000,8 @@ impl std::fmt::Debug for DefaultsConfig {
             .field("history_backfill_count", &self.history_backfill_count)
             .field("cron", &self.cron)
             .field("opencode", &self.opencode)
+            .field("container", &self.container)
+            .field("git", &self.git)
             .field("worker_log_mode", &self.worker_log_mode)
             .field("projects", &self.projects)
             .finish()
@@ -660,6 +1083,51 @@ impl Default for MemoryPersistenceConfig {
     }
 }
 
+/// Speculative follow-up prefetch configuration.
+///
+/// After the channel replies, spawns a silent branch that predicts the most
+/// likely follow-up and gathers the context it would need (linked tickets,
+/// referenced memories, files). The result is cached on the channel and only
+/// injected into the next turn when the user's message matches the prediction.
+#[derive(Debug, Clone, Copy)]
+pub struct PrefetchConfig {
+    /// Whether speculative prefetch branches are enabled.
+    pub enabled: bool,
+    /// Seconds a prefetched context stays valid before it is discarded.
+    pub ttl_secs: u64,
+}
+
+impl Default for PrefetchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_secs: 600,
+        }
+    }
+}
+
+/// Branch result cache configuration.
+///
+/// A branch spawned with the same task on the same channel, over exactly the
+/// same history as an earlier one, short-circuits to that branch's conclusion
+/// instead of re-deriving it. Entries expire after `ttl_secs`.
+#[derive(Debug, Clone, Copy)]
+pub struct BranchCacheConfig {
+    /// Whether repeated branch spawns are answered from the cache.
+    pub enabled: bool,
+    /// Seconds a cached conclusion stays valid.
+    pub ttl_secs: u64,
+}
+
+impl Default for BranchCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl_secs: 900,
+        }
+    }
+}
+
 impl Default for CompactionConfig {
     fn default() -> Self {
         Self {
@@ -687,6 +1155,10 @@ pub struct CoalesceConfig {
     pub min_messages: usize,
     /// Apply only to multi-user conversations (skip for DMs).
     pub multi_user_only: bool,
+    /// Quiet window for consecutive messages from one sender (milliseconds).
+    /// Each new message from the same sender restarts the window, up to
+    /// `max_wait_ms`. Applies in DMs too. 0 disables.
+    pub sender_debounce_ms: u64,
 }
 
 impl Default for CoalesceConfig {
@@ -697,6 +1169,7 @@ impl Default for CoalesceConfig {
             max_wait_ms: 5000,
             min_messages: 2,
             multi_user_only: true,
+            sender_debounce_ms: 1000,
         }
     }
 }
@@ -715,6 +1188,11 @@ pub struct IngestionConfig {
     /// Target chunk size in characters. Chunks may be slightly larger to avoid
     /// splitting mid-line.
     pub chunk_size: usize,
+    /// How files are split into chunks.
+    pub chunking: ChunkingStrategy,
+    /// Characters shared between consecutive chunks. Only used by the
+    /// `window` strategy.
+    pub chunk_overlap: usize,
 }
 
 impl Default for IngestionConfig {
@@ -723,10 +1201,52 @@ impl Default for IngestionConfig {
             enabled: true,
             poll_interval_secs: 30,
             chunk_size: 4000,
+            chunking: ChunkingStrategy::default(),
+            chunk_overlap: 200,
+        }
+    }
+}
+
+/// How ingested files are split into chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkingStrategy {
+    /// Pick by file type: `headings` for markdown, `code` for source files,
+    /// `lines` for everything else.
+    #[default]
+    Auto,
+    /// Pack whole lines up to the chunk size.
+    Lines,
+    /// Split before markdown headings, packing small sections together.
+    Headings,
+    /// Fixed-size windows that overlap by `chunk_overlap` characters.
+    Window,
+    /// Pack whole sentences up to the chunk size.
+    Sentences,
+    /// Split before top-level declarations (functions, types, classes),
+    /// keeping their doc comments and attributes attached.
+    Code,
+}
+
+impl ChunkingStrategy {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Lines => "lines",
+            Self::Headings => "headings",
+            Self::Window => "window",
+            Self::Sentences => "sentences",
+            Self::Code => "code",
         }
     }
 }
 
+impl std::fmt::Display for ChunkingStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// What happens when a worker explicitly calls "close" on the browser.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -756,6 +1276,44 @@ impl std::fmt::Display for ClosePolicy {
     }
 }
 
+/// How a channel paces its replies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponsePace {
+    /// Send each reply as soon as it's written, in one message.
+    #[default]
+    Instant,
+    /// Show typing, then send long replies as a few messages with short pauses.
+    Casual,
+    /// Like `Casual`, with slower typing and smaller messages.
+    Relaxed,
+}
+
+impl ResponsePace {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Instant => "instant",
+            Self::Casual => "casual",
+            Self::Relaxed => "relaxed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "instant" => Some(Self::Instant),
+            "casual" => Some(Self::Casual),
+            "relaxed" => Some(Self::Relaxed),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ResponsePace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Browser automation configuration for workers.
 #[derive(Debug, Clone)]
 pub struct BrowserConfig {
@@ -804,6 +1362,54 @@ pub struct ChannelConfig {
     /// `workspace/saved/` and tracked in the `saved_attachments` table so
     /// they can be recalled on later turns.
     pub save_attachments: bool,
+    /// Default reply pacing. Individual channels can override it.
+    pub pace: ResponsePace,
+    /// Default memory scope for branches spawned from a channel. Individual
+    /// channels can override it.
+    pub memory_scope: crate::memory::MemoryScope,
+    /// What a message arriving during a turn does to that turn.
+    pub interrupt: TurnInterrupt,
+}
+
+/// What a message arriving while a channel turn is running does to it.
+///
+/// An interrupted turn is cancelled before it acts and restarted with the new
+/// message. Once the turn has called a tool, new messages always wait.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TurnInterrupt {
+    /// New messages wait for the running turn to finish.
+    Queue,
+    /// Only the person the turn is answering can interrupt it.
+    #[default]
+    Sender,
+    /// Any new message interrupts the turn.
+    Any,
+}
+
+impl TurnInterrupt {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Queue => "queue",
+            Self::Sender => "sender",
+            Self::Any => "any",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "queue" => Some(Self::Queue),
+            "sender" => Some(Self::Sender),
+            "any" => Some(Self::Any),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for TurnInterrupt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 /// OpenCode subprocess worker configuration.
@@ -837,6 +1443,71 @@ impl Default for OpenCodeConfig {
     }
 }
 
+/// Container worker configuration. Container workers run their shell
+/// commands inside a per-worker Docker or Podman container.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContainerConfig {
+    /// Whether container workers are available.
+    pub enabled: bool,
+    /// Container runtime binary: "docker" or "podman" (or a path to either).
+    pub runtime: String,
+    /// Image each worker container is started from.
+    pub image: String,
+    /// Value for `--network`. "none" (default) cuts the container off from
+    /// the network entirely.
+    pub network: String,
+    /// Memory limit passed to `--memory` (e.g. "2g").
+    pub memory: Option<String>,
+    /// CPU limit passed to `--cpus` (e.g. "1.5").
+    pub cpus: Option<String>,
+    /// Process limit passed to `--pids-limit`.
+    pub pids_limit: Option<u64>,
+    /// User to run as inside the container (`--user`), e.g. "1000:1000" so
+    /// files written to the bound directory keep host ownership.
+    pub user: Option<String>,
+    /// Extra bind mounts in `host:container[:ro]` form. The worker's
+    /// directory is always mounted; these are added on top.
+    pub mounts: Vec<String>,
+}
+
+impl Default for ContainerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            runtime: "docker".to_string(),
+            image: "ubuntu:24.04".to_string(),
+            network: "none".to_string(),
+            memory: Some("2g".to_string()),
+            cpus: Some("2".to_string()),
+            pids_limit: Some(512),
+            user: None,
+            mounts: Vec::new(),
+        }
+    }
+}
+
+/// Guardrails for the worker `git_*` tools.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitConfig {
+    /// Branches workers may not commit to directly, delete, or reset.
+    pub protected_branches: Vec<String>,
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        Self {
+            protected_branches: vec!["main".to_string(), "master".to_string()],
+        }
+    }
+}
+
+impl GitConfig {
+    /// Whether `branch` is protected. Comparison is exact.
+    pub fn is_protected(&self, branch: &str) -> bool {
+        self.protected_branches.iter().any(|name| name == branch)
+    }
+}
+
 /// Cortex configuration.
 #[derive(Debug, Clone, Copy)]
 pub struct CortexConfig {
@@ -852,6 +1523,8 @@ pub struct CortexConfig {
     pub bulletin_max_words: usize,
     /// Max LLM turns for bulletin generation.
     pub bulletin_max_turns: usize,
+    /// Combined word limit for items pinned into the bulletin.
+    pub bulletin_pin_max_words: usize,
     /// Interval in seconds between memory maintenance passes.
     pub maintenance_interval_secs: u64,
     /// Per-day decay applied to memory importance during maintenance.
@@ -870,6 +1543,13 @@ pub struct CortexConfig {
     pub association_updates_threshold: f32,
     /// Max associations to create per pass (rate limit).
     pub association_max_per_pass: usize,
+    /// Whether the cortex extracts memories from channel transcripts on its
+    /// own, without waiting for a branch to call `memory_save`.
+    pub fact_extraction_enabled: bool,
+    /// Interval in seconds between fact extraction passes.
+    pub fact_extraction_interval_secs: u64,
+    /// Max transcript messages read per channel per extraction pass.
+    pub fact_extraction_max_messages: usize,
 }
 
 impl Default for CortexConfig {
@@ -884,6 +1564,7 @@ impl Default for CortexConfig {
             bulletin_interval_secs: 3600,
             bulletin_max_words: 1500,
             bulletin_max_turns: 15,
+            bulletin_pin_max_words: 300,
             maintenance_interval_secs: 3600,
             maintenance_decay_rate: 0.05,
             maintenance_prune_threshold: 0.1,
@@ -893,6 +1574,9 @@ impl Default for CortexConfig {
             association_similarity_threshold: 0.85,
             association_updates_threshold: 0.95,
             association_max_per_pass: 100,
+            fact_extraction_enabled: false,
+            fact_extraction_interval_secs: 1800,
+            fact_extraction_max_messages: 200,
         }
     }
 }
@@ -959,6 +1643,366 @@ impl Default for WarmupConfig {
     }
 }
 
+/// Per-agent disk usage monitoring.
+#[derive(Debug, Clone, Copy)]
+pub struct StorageConfig {
+    /// Soft quota for everything under the agent's directory, in megabytes.
+    /// None disables threshold warnings; usage is still measured.
+    pub quota_mb: Option<u64>,
+    /// Percentage of the quota at which a warning is emitted.
+    pub warn_percent: u8,
+    /// Run log/screenshot retention and database compaction automatically
+    /// when usage crosses the warning threshold.
+    pub auto_cleanup: bool,
+    /// Interval in seconds between usage measurements. 0 disables the monitor.
+    pub check_interval_secs: u64,
+    /// Age in days after which logs and screenshots are removed by cleanup.
+    pub retention_days: u64,
+    /// Interval in seconds between LanceDB compaction and version pruning
+    /// passes. 0 disables scheduled optimization.
+    pub optimize_interval_secs: u64,
+    /// Seconds without agent activity required before a scheduled
+    /// optimization runs. A due pass waits until the agent is idle.
+    pub optimize_idle_secs: u64,
+    /// Interval in seconds between SQLite ANALYZE/VACUUM passes. Passes wait
+    /// for `optimize_idle_secs` of inactivity like LanceDB optimization.
+    /// 0 disables scheduled database maintenance.
+    pub db_maintenance_interval_secs: u64,
+    /// Only VACUUM when at least this percentage of the database file is free
+    /// pages, since rewriting the whole file is expensive.
+    pub db_vacuum_min_free_percent: u8,
+    /// WAL size in pages at which SQLite checkpoints automatically.
+    /// Applied when the database is opened.
+    pub db_wal_autocheckpoint_pages: u32,
+    /// Size in megabytes the WAL file is truncated to after a checkpoint.
+    /// Applied when the database is opened.
+    pub db_journal_size_limit_mb: u64,
+    /// Encrypt cortex chat messages in SQLite with a key derived from the
+    /// instance config key. Rows already written stay as they are; both
+    /// forms are readable. Applied when the agent starts.
+    pub encrypt_at_rest: bool,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            quota_mb: None,
+            warn_percent: 80,
+            auto_cleanup: false,
+            check_interval_secs: 600,
+            retention_days: 14,
+            optimize_interval_secs: 6 * 60 * 60,
+            optimize_idle_secs: 300,
+            db_maintenance_interval_secs: 24 * 60 * 60,
+            db_vacuum_min_free_percent: 20,
+            db_wal_autocheckpoint_pages: 1000,
+            db_journal_size_limit_mb: 64,
+            encrypt_at_rest: false,
+        }
+    }
+}
+
+impl StorageConfig {
+    /// The quota in bytes, if one is configured.
+    pub fn quota_bytes(&self) -> Option<u64> {
+        self.quota_mb.map(|mb| mb.saturating_mul(1024 * 1024))
+    }
+}
+
+/// Automatic summarization and archival of old channel history.
+///
+/// Once a channel holds more than `max_messages` stored messages, or has
+/// messages older than `max_age_days`, the oldest ones are summarized in
+/// segments by the compactor model. The summaries stay recallable through
+/// `channel_recall`; the raw messages are moved to cold storage or deleted.
+#[derive(Debug, Clone, Copy)]
+pub struct ArchiveConfig {
+    /// Archive once a channel stores more than this many messages. 0 disables
+    /// the count trigger.
+    pub max_messages: usize,
+    /// Archive messages older than this many days. 0 disables the age trigger.
+    pub max_age_days: u64,
+    /// The most recent messages in a channel, which are never archived.
+    pub keep_recent: usize,
+    /// Number of messages summarized together into one summary.
+    pub segment_size: usize,
+    /// What happens to raw messages once they are summarized.
+    pub raw_messages: ArchivedMessages,
+    /// Interval in seconds between archival passes. 0 disables archival.
+    pub check_interval_secs: u64,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            max_messages: 0,
+            max_age_days: 0,
+            keep_recent: 200,
+            segment_size: 200,
+            raw_messages: ArchivedMessages::default(),
+            check_interval_secs: 3600,
+        }
+    }
+}
+
+impl ArchiveConfig {
+    /// Whether either archival trigger is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.check_interval_secs > 0 && (self.max_messages > 0 || self.max_age_days > 0)
+    }
+}
+
+/// What archival does with raw messages after summarizing them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchivedMessages {
+    /// Move them to the `conversation_messages_archive` table, out of the
+    /// live history and the search index.
+    #[default]
+    Cold,
+    /// Delete them.
+    Delete,
+}
+
+impl ArchivedMessages {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cold => "cold",
+            Self::Delete => "delete",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "cold" => Some(Self::Cold),
+            "delete" => Some(Self::Delete),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ArchivedMessages {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Moderation of outbound replies before they reach the messaging adapters.
+///
+/// Every reply is checked against the rules that apply at its channel's
+/// strictness. When `classifier_model` is set, replies in channels at
+/// `classifier_level` or stricter also go through an LLM classifier.
+#[derive(Debug, Clone)]
+pub struct ModerationConfig {
+    /// Strictness of channels without a per-channel override.
+    pub strictness: ModerationStrictness,
+    pub rules: Vec<ModerationRule>,
+    /// Model for the classifier pass. `None` disables the classifier.
+    pub classifier_model: Option<String>,
+    /// Least strict channel level the classifier runs at.
+    pub classifier_level: ModerationStrictness,
+    /// Sent in place of a blocked reply. Blocked replies are dropped when unset.
+    pub block_notice: Option<String>,
+}
+
+impl Default for ModerationConfig {
+    fn default() -> Self {
+        Self {
+            strictness: ModerationStrictness::Standard,
+            rules: Vec::new(),
+            classifier_model: None,
+            classifier_level: ModerationStrictness::Strict,
+            block_notice: None,
+        }
+    }
+}
+
+impl ModerationConfig {
+    /// Whether there is anything to check replies against.
+    pub fn is_enabled(&self) -> bool {
+        !self.rules.is_empty() || self.classifier_model.is_some()
+    }
+}
+
+/// A moderation rule: a pattern and what to do with replies that match it.
+#[derive(Debug, Clone)]
+pub struct ModerationRule {
+    pub name: String,
+    pub pattern: regex::Regex,
+    pub action: ModerationAction,
+    /// Substituted for each match when `action` is `Rewrite`.
+    pub replacement: String,
+    /// Least strict channel level the rule applies at.
+    pub level: ModerationStrictness,
+}
+
+/// How strictly a channel's replies are moderated. Ordered from least to
+/// most strict; rules and the classifier apply at their level and above.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationStrictness {
+    /// No moderation.
+    Off,
+    Lenient,
+    #[default]
+    Standard,
+    Strict,
+}
+
+impl ModerationStrictness {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Lenient => "lenient",
+            Self::Standard => "standard",
+            Self::Strict => "strict",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "off" => Some(Self::Off),
+            "lenient" => Some(Self::Lenient),
+            "standard" => Some(Self::Standard),
+            "strict" => Some(Self::Strict),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ModerationStrictness {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What happens to a reply that matches a moderation rule. Ordered by
+/// severity; when several rules match, the most severe action wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationAction {
+    /// Deliver the reply unchanged and record it in the audit log.
+    Flag,
+    /// Replace the matched text before delivering.
+    Rewrite,
+    /// Don't deliver the reply.
+    Block,
+}
+
+impl ModerationAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Flag => "flag",
+            Self::Rewrite => "rewrite",
+            Self::Block => "block",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "flag" => Some(Self::Flag),
+            "rewrite" => Some(Self::Rewrite),
+            "block" => Some(Self::Block),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ModerationAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Speech-to-text for audio attachments.
+///
+/// When `url` is set, audio is sent to that Whisper-compatible
+/// `/audio/transcriptions` endpoint (OpenAI, Groq, or a local whisper.cpp /
+/// faster-whisper server). Otherwise the `routing.voice` model transcribes it
+/// through chat completions with `input_audio`.
+#[derive(Clone)]
+pub struct TranscriptionConfig {
+    /// Full URL of a Whisper-compatible transcription endpoint.
+    pub url: Option<String>,
+    /// Bearer token for the endpoint. Supports "env:VAR_NAME" references.
+    pub api_key: Option<String>,
+    /// Model name sent to the endpoint.
+    pub model: String,
+    /// ISO-639-1 language hint. None lets the model detect the language.
+    pub language: Option<String>,
+    /// Audio files larger than this are not transcribed.
+    pub max_file_mb: u64,
+}
+
+impl std::fmt::Debug for TranscriptionConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TranscriptionConfig")
+            .field("url", &self.url)
+            .field("api_key", &self.api_key.as_ref().map(|_| "[REDACTED]"))
+            .field("model", &self.model)
+            .field("language", &self.language)
+            .field("max_file_mb", &self.max_file_mb)
+            .finish()
+    }
+}
+
+impl Default for TranscriptionConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            api_key: None,
+            model: "whisper-1".into(),
+            language: None,
+            max_file_mb: 25,
+        }
+    }
+}
+
+/// Spend limits in USD, tracked per agent.
+///
+/// Daily limits cover everything the agent spends in a UTC day, channel
+/// limits what a single channel (and its branches and workers) spends in a
+/// day, and worker limits a single worker's lifetime. Crossing a soft limit
+/// warns the cortex. Crossing a hard limit switches routing to
+/// `downgrade_model` and, for daily and channel limits, pauses worker spawns.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BudgetConfig {
+    pub daily_soft_usd: Option<f64>,
+    pub daily_hard_usd: Option<f64>,
+    pub channel_soft_usd: Option<f64>,
+    pub channel_hard_usd: Option<f64>,
+    pub worker_soft_usd: Option<f64>,
+    pub worker_hard_usd: Option<f64>,
+    /// Model used once a hard limit is reached. Without one, hard limits
+    /// only pause worker spawns, and a worker over its own hard limit fails.
+    pub downgrade_model: Option<String>,
+}
+
+/// Full-text index options for the memory embeddings table.
+///
+/// Unset fields keep LanceDB's defaults. Options only take effect when the
+/// index is built, so when any is set the index is rebuilt at startup.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryFtsConfig {
+    /// Base tokenizer: "simple", "whitespace", "raw", or "ngram".
+    pub tokenizer: Option<String>,
+    /// Language used for stemming and stop words (e.g. "English", "German").
+    pub language: Option<String>,
+    /// Reduce words to their stem so "running" matches "run".
+    pub stemming: Option<bool>,
+    /// Drop common stop words of `language` from the index.
+    pub remove_stop_words: Option<bool>,
+    /// Fold accented characters to ASCII so "café" matches "cafe".
+    pub ascii_folding: Option<bool>,
+}
+
+impl MemoryFtsConfig {
+    /// Whether any option differs from LanceDB's defaults.
+    pub fn is_configured(&self) -> bool {
+        *self != Self::default()
+    }
+}
+
 /// Projects configuration — agent-level defaults for project workspace management.
 #[derive(Debug, Clone)]
 pub struct ProjectsConfig {
@@ -1116,15 +2160,24 @@ pub struct AgentConfig {
     pub routing: Option<RoutingConfig>,
     pub max_concurrent_branches: Option<usize>,
     pub max_concurrent_workers: Option<usize>,
+    pub worker_batch_concurrency: Option<usize>,
     pub max_turns: Option<usize>,
     pub branch_max_turns: Option<usize>,
     pub context_window: Option<usize>,
     pub compaction: Option<CompactionConfig>,
     pub memory_persistence: Option<MemoryPersistenceConfig>,
+    pub prefetch: Option<PrefetchConfig>,
+    pub branch_cache: Option<BranchCacheConfig>,
     pub coalesce: Option<CoalesceConfig>,
     pub ingestion: Option<IngestionConfig>,
     pub cortex: Option<CortexConfig>,
     pub warmup: Option<WarmupConfig>,
+    pub storage: Option<StorageConfig>,
+    pub archive: Option<ArchiveConfig>,
+    pub moderation: Option<ModerationConfig>,
+    pub transcription: Option<TranscriptionConfig>,
+    pub budget: Option<BudgetConfig>,
+    pub memory_fts: Option<MemoryFtsConfig>,
     pub browser: Option<BrowserConfig>,
     pub channel: Option<ChannelConfig>,
     pub mcp: Option<Vec<McpServerConfig>>,
@@ -1179,15 +2232,25 @@ pub struct ResolvedAgentConfig {
     pub routing: RoutingConfig,
     pub max_concurrent_branches: usize,
     pub max_concurrent_workers: usize,
+    /// Max workers a single `spawn_worker_batch` call runs at once.
+    pub worker_batch_concurrency: usize,
     pub max_turns: usize,
     pub branch_max_turns: usize,
     pub context_window: usize,
     pub compaction: CompactionConfig,
     pub memory_persistence: MemoryPersistenceConfig,
+    pub prefetch: PrefetchConfig,
+    pub branch_cache: BranchCacheConfig,
     pub coalesce: CoalesceConfig,
     pub ingestion: IngestionConfig,
     pub cortex: CortexConfig,
     pub warmup: WarmupConfig,
+    pub storage: StorageConfig,
+    pub archive: ArchiveConfig,
+    pub moderation: ModerationConfig,
+    pub transcription: TranscriptionConfig,
+    pub budget: BudgetConfig,
+    pub memory_fts: MemoryFtsConfig,
     pub browser: BrowserConfig,
     pub channel: ChannelConfig,
     pub mcp: Vec<McpServerConfig>,
@@ -1209,15 +2272,24 @@ impl Default for DefaultsConfig {
             routing: RoutingConfig::default(),
             max_concurrent_branches: 5,
             max_concurrent_workers: 5,
+            worker_batch_concurrency: 3,
             max_turns: 5,
             branch_max_turns: 50,
             context_window: 128_000,
             compaction: CompactionConfig::default(),
             memory_persistence: MemoryPersistenceConfig::default(),
+            prefetch: PrefetchConfig::default(),
+            branch_cache: BranchCacheConfig::default(),
             coalesce: CoalesceConfig::default(),
             ingestion: IngestionConfig::default(),
             cortex: CortexConfig::default(),
             warmup: WarmupConfig::default(),
+            storage: StorageConfig::default(),
+            archive: ArchiveConfig::default(),
+            moderation: ModerationConfig::default(),
+            transcription: TranscriptionConfig::default(),
+            budget: BudgetConfig::default(),
+            memory_fts: MemoryFtsConfig::default(),
             browser: BrowserConfig::default(),
             channel: ChannelConfig::default(),
             mcp: Vec::new(),
@@ -1227,6 +2299,8 @@ impl Default for DefaultsConfig {
             history_backfill_count: 50,
             cron: Vec::new(),
             opencode: OpenCodeConfig::default(),
+            container: ContainerConfig::default(),
+            git: GitConfig::default(),
             worker_log_mode: crate::settings::WorkerLogMode::default(),
             projects: ProjectsConfig::default(),
         }
@@ -1272,6 +2346,9 @@ impl AgentConfig {
             max_concurrent_workers: self
                 .max_concurrent_workers
                 .unwrap_or(defaults.max_concurrent_workers),
+            worker_batch_concurrency: self
+                .worker_batch_concurrency
+                .unwrap_or(defaults.worker_batch_concurrency),
             max_turns: self.max_turns.unwrap_or(defaults.max_turns),
             branch_max_turns: self.branch_max_turns.unwrap_or(defaults.branch_max_turns),
             context_window: self.context_window.unwrap_or(defaults.context_window),
@@ -1279,10 +2356,30 @@ impl AgentConfig {
             memory_persistence: self
                 .memory_persistence
                 .unwrap_or(defaults.memory_persistence),
+            prefetch: self.prefetch.unwrap_or(defaults.prefetch),
+            branch_cache: self.branch_cache.unwrap_or(defaults.branch_cache),
             coalesce: self.coalesce.unwrap_or(defaults.coalesce),
             ingestion: self.ingestion.unwrap_or(defaults.ingestion),
             cortex: self.cortex.unwrap_or(defaults.cortex),
             warmup: self.warmup.unwrap_or(defaults.warmup),
+            storage: self.storage.unwrap_or(defaults.storage),
+            archive: self.archive.unwrap_or(defaults.archive),
+            moderation: self
+                .moderation
+                .clone()
+                .unwrap_or_else(|| defaults.moderation.clone()),
+            transcription: self
+                .transcription
+                .clone()
+                .unwrap_or_else(|| defaults.transcription.clone()),
+            budget: self
+                .budget
+                .clone()
+                .unwrap_or_else(|| defaults.budget.clone()),
+            memory_fts: self
+                .memory_fts
+                .clone()
+                .unwrap_or_else(|| defaults.memory_fts.clone()),
             browser: self
                 .browser
                 .clone()
@@ -1479,6 +2576,12 @@ pub struct Binding {
     pub require_mention: bool,
     /// User IDs allowed to DM the bot through this binding.
     pub dm_allowed_users: Vec<String>,
+    /// Only match messages that address this name as `@name` in their text.
+    /// Lets several agents share one bot account in the same channel.
+    pub mention: Option<String>,
+    /// Only match messages whose text starts with this prefix (e.g. `!research`).
+    /// The prefix is stripped before the agent sees the message.
+    pub prefix: Option<String>,
 }
 
 impl Binding {
@@ -1503,6 +2606,20 @@ impl Binding {
             return false;
         }
 
+        if self.mention.is_some() || self.prefix.is_some() {
+            let text = message_text(message).unwrap_or_default();
+            if let Some(name) = &self.mention
+                && !text_mentions_name(text, name)
+            {
+                return false;
+            }
+            if let Some(prefix) = &self.prefix
+                && strip_text_prefix(text, prefix).is_none()
+            {
+                return false;
+            }
+        }
+
         // For webchat messages, match based on agent_id in the message
         if message.source == "webchat"
             && let Some(message_agent_id) = &message.agent_id
@@ -1551,7 +2668,8 @@ impl Binding {
                 .and_then(|v| v.as_u64())
                 .map(|v| v.to_string());
 
-            // Also check Slack and Twitch channel IDs
+            // Also check Slack and Twitch channel IDs, GitHub repos, Matrix rooms,
+            // and IRC channels
             let slack_channel = message
                 .metadata
                 .get("slack_channel_id")
@@ -1560,12 +2678,25 @@ impl Binding {
                 .metadata
                 .get("twitch_channel")
                 .and_then(|v| v.as_str());
+            let github_repo = message.metadata.get("github_repo").and_then(|v| v.as_str());
+            let matrix_room = message
+                .metadata
+                .get("matrix_room_id")
+                .and_then(|v| v.as_str());
+            let irc_channel = message.metadata.get("irc_channel").and_then(|v| v.as_str());
 
             let direct_match = message_channel
                 .as_ref()
                 .is_some_and(|id| self.channel_ids.contains(id))
                 || slack_channel.is_some_and(|id| self.channel_ids.contains(&id.to_string()))
-                || twitch_channel.is_some_and(|id| self.channel_ids.contains(&id.to_string()));
+                || twitch_channel.is_some_and(|id| self.channel_ids.contains(&id.to_string()))
+                || github_repo.is_some_and(|repo| self.channel_ids.contains(&repo.to_string()))
+                || matrix_room.is_some_and(|room| self.channel_ids.contains(&room.to_string()))
+                || irc_channel.is_some_and(|channel| {
+                    self.channel_ids
+                        .iter()
+                        .any(|id| id.eq_ignore_ascii_case(channel))
+                });
             let parent_match = parent_channel
                 .as_ref()
                 .is_some_and(|id| self.channel_ids.contains(id));
@@ -1616,6 +2747,16 @@ impl Binding {
                     .and_then(|v| v.as_str())
                     == Some("private")
             }
+            "matrix" => message
+                .metadata
+                .get("matrix_is_direct")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            "irc" => message
+                .metadata
+                .get("irc_is_private")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
             _ => false,
         };
         if is_dm {
@@ -1629,6 +2770,9 @@ impl Binding {
             "slack" => "slack_mentions_or_replies_to_bot",
             "twitch" => "twitch_mentions_or_replies_to_bot",
             "telegram" => "telegram_mentions_or_replies_to_bot",
+            "github" => "github_mentions_or_replies_to_bot",
+            "matrix" => "matrix_mentions_or_replies_to_bot",
+            "irc" => "irc_mentions_or_replies_to_bot",
             // Unknown platforms: if require_mention is set, default to
             // requiring a mention (safe default).
             _ => return false,
@@ -1942,10 +3086,48 @@ pub fn resolve_agent_for_message(
     message: &crate::InboundMessage,
     default_agent_id: &str,
 ) -> Option<crate::AgentId> {
+    resolve_binding_for_message(bindings, message, default_agent_id).map(|(agent_id, _)| agent_id)
+}
+
+/// Resolve the agent for an inbound message and apply the matched binding's
+/// rewrites to it: a `prefix` rule has its prefix stripped from the text.
+///
+/// Returns `None` when the message is suppressed by `require_mention`.
+pub fn dispatch_inbound_message(
+    bindings: &[Binding],
+    message: &mut crate::InboundMessage,
+    default_agent_id: &str,
+) -> Option<crate::AgentId> {
+    let (agent_id, binding) = resolve_binding_for_message(bindings, message, default_agent_id)?;
+    if let Some(prefix) = binding.and_then(|binding| binding.prefix.as_deref()) {
+        let text = match &mut message.content {
+            crate::MessageContent::Text(text) => Some(text),
+            crate::MessageContent::Media { text, .. } => text.as_mut(),
+            crate::MessageContent::Interaction { .. } | crate::MessageContent::Command { .. } => {
+                None
+            }
+        };
+        if let Some(text) = text
+            && let Some(rest) = strip_text_prefix(text, prefix)
+        {
+            *text = rest.to_string();
+        }
+    }
+    Some(agent_id)
+}
+
+fn resolve_binding_for_message<'a>(
+    bindings: &'a [Binding],
+    message: &crate::InboundMessage,
+    default_agent_id: &str,
+) -> Option<(crate::AgentId, Option<&'a Binding>)> {
     for binding in bindings {
         if binding.matches_route(message) {
             if binding.passes_require_mention(message) {
-                return Some(std::sync::Arc::from(binding.agent_id.as_str()));
+                return Some((
+                    std::sync::Arc::from(binding.agent_id.as_str()),
+                    Some(binding),
+                ));
             }
             // Binding owns this message but require_mention blocked it.
             // Drop instead of falling through to the default agent.
@@ -1957,7 +3139,55 @@ pub fn resolve_agent_for_message(
             return None;
         }
     }
-    Some(std::sync::Arc::from(default_agent_id))
+    Some((std::sync::Arc::from(default_agent_id), None))
+}
+
+fn message_text(message: &crate::InboundMessage) -> Option<&str> {
+    match &message.content {
+        crate::MessageContent::Text(text) => Some(text),
+        crate::MessageContent::Media { text, .. } => text.as_deref(),
+        crate::MessageContent::Interaction { .. } | crate::MessageContent::Command { .. } => None,
+    }
+}
+
+/// Whether `text` addresses `name` as `@name`, ignoring case. The mention
+/// must end at a word boundary so `@research` doesn't match `@researchers`.
+fn text_mentions_name(text: &str, name: &str) -> bool {
+    let name = name.trim().trim_start_matches('@');
+    if name.is_empty() {
+        return false;
+    }
+    let needle = format!("@{}", name.to_lowercase());
+    let haystack = text.to_lowercase();
+    haystack.match_indices(&needle).any(|(index, _)| {
+        haystack[index + needle.len()..]
+            .chars()
+            .next()
+            .is_none_or(|next| !(next.is_alphanumeric() || next == '_' || next == '-'))
+    })
+}
+
+/// Strip `prefix` (ignoring case) from the start of `text`, along with the
+/// whitespace after it. The prefix must be followed by whitespace or the end
+/// of the text.
+fn strip_text_prefix<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let prefix = prefix.trim();
+    let text = text.trim_start();
+    if prefix.is_empty() || text.len() < prefix.len() || !text.is_char_boundary(prefix.len()) {
+        return None;
+    }
+    let (head, rest) = text.split_at(prefix.len());
+    if !head.eq_ignore_ascii_case(prefix) {
+        return None;
+    }
+    if rest
+        .chars()
+        .next()
+        .is_some_and(|next| !next.is_whitespace())
+    {
+        return None;
+    }
+    Some(rest.trim_start())
 }
 
 // ---------------------------------------------------------------------------
@@ -1974,6 +3204,9 @@ pub struct MessagingConfig {
     pub webhook: Option<WebhookConfig>,
     pub twitch: Option<TwitchConfig>,
     pub signal: Option<SignalConfig>,
+    pub github: Option<GithubConfig>,
+    pub matrix: Option<MatrixConfig>,
+    pub irc: Option<IrcConfig>,
 }
 
 #[derive(Clone)]
@@ -1986,6 +3219,68 @@ pub struct DiscordConfig {
     pub dm_allowed_users: Vec<String>,
     /// Whether to process messages from other bots (self-messages are always ignored).
     pub allow_bot_messages: bool,
+    /// Voice channel support, shared by every Discord bot instance.
+    pub voice: DiscordVoiceConfig,
+}
+
+/// Discord voice channel settings.
+///
+/// When enabled, channels get `join_voice` / `leave_voice` tools. Speech in a
+/// joined voice channel is transcribed with the agent's transcription
+/// settings, and short replies are spoken back through `tts_url`.
+#[derive(Clone)]
+pub struct DiscordVoiceConfig {
+    pub enabled: bool,
+    /// OpenAI-compatible `/audio/speech` endpoint. Without one, replies to
+    /// voice messages are posted as text only.
+    pub tts_url: Option<String>,
+    /// Bearer token for the TTS endpoint. Supports "env:VAR_NAME" references.
+    pub tts_api_key: Option<String>,
+    pub tts_model: String,
+    pub tts_voice: String,
+    /// Replies longer than this are posted as text without being spoken.
+    pub max_spoken_chars: usize,
+    /// Pause after which a speaker's utterance is sent for transcription.
+    pub silence_ms: u64,
+    /// Utterances shorter than this are dropped as noise.
+    pub min_utterance_ms: u64,
+    /// Longer speech is sent in pieces of at most this length.
+    pub max_utterance_secs: u64,
+}
+
+impl std::fmt::Debug for DiscordVoiceConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DiscordVoiceConfig")
+            .field("enabled", &self.enabled)
+            .field("tts_url", &self.tts_url)
+            .field(
+                "tts_api_key",
+                &self.tts_api_key.as_ref().map(|_| "[REDACTED]"),
+            )
+            .field("tts_model", &self.tts_model)
+            .field("tts_voice", &self.tts_voice)
+            .field("max_spoken_chars", &self.max_spoken_chars)
+            .field("silence_ms", &self.silence_ms)
+            .field("min_utterance_ms", &self.min_utterance_ms)
+            .field("max_utterance_secs", &self.max_utterance_secs)
+            .finish()
+    }
+}
+
+impl Default for DiscordVoiceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tts_url: None,
+            tts_api_key: None,
+            tts_model: "tts-1".into(),
+            tts_voice: "alloy".into(),
+            max_spoken_chars: 400,
+            silence_ms: 800,
+            min_utterance_ms: 400,
+            max_utterance_secs: 30,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -2019,6 +3314,7 @@ impl std::fmt::Debug for DiscordConfig {
             .field("instances", &self.instances)
             .field("dm_allowed_users", &self.dm_allowed_users)
             .field("allow_bot_messages", &self.allow_bot_messages)
+            .field("voice", &self.voice)
             .finish()
     }
 }
@@ -2460,6 +3756,185 @@ impl SystemSecrets for TwitchConfig {
     }
 }
 
+/// GitHub issue and pull request comment threads as channels.
+#[derive(Clone)]
+pub struct GithubConfig {
+    pub enabled: bool,
+    /// Token used to read comments and post replies. Needs issue and pull
+    /// request read/write access (and checks read for CI status).
+    pub token: String,
+    /// Repositories to watch, as `owner/repo`. Comments elsewhere are ignored.
+    pub repos: Vec<String>,
+    /// Seconds between polls of each repository.
+    pub poll_interval_secs: u64,
+    /// REST API base URL; override for GitHub Enterprise Server.
+    pub api_url: String,
+}
+
+impl std::fmt::Debug for GithubConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GithubConfig")
+            .field("enabled", &self.enabled)
+            .field("token", &"[REDACTED]")
+            .field("repos", &self.repos)
+            .field("poll_interval_secs", &self.poll_interval_secs)
+            .field("api_url", &self.api_url)
+            .finish()
+    }
+}
+
+impl SystemSecrets for GithubConfig {
+    fn section() -> &'static str {
+        "github"
+    }
+
+    fn is_messaging_adapter() -> bool {
+        true
+    }
+
+    fn secret_fields() -> &'static [SecretField] {
+        // Not GITHUB_TOKEN: that name is commonly a tool secret for `gh`.
+        &[SecretField {
+            toml_key: "token",
+            secret_name: "GITHUB_ADAPTER_TOKEN",
+            instance_pattern: None,
+        }]
+    }
+}
+
+/// Matrix rooms as channels, via a logged-in bot account.
+#[derive(Clone)]
+pub struct MatrixConfig {
+    pub enabled: bool,
+    /// Homeserver base URL (e.g. `https://matrix.example.org`).
+    pub homeserver_url: String,
+    /// Full user ID of the bot account (e.g. `@spacebot:example.org`).
+    pub user_id: String,
+    /// Password for the bot account. Only used when no stored session exists.
+    pub password: String,
+    /// Display name for the device created on first login.
+    pub device_name: String,
+    /// Room IDs the bot listens in. If empty, every joined room is allowed.
+    pub allowed_rooms: Vec<String>,
+    /// User IDs the bot accepts messages and invites from. If empty, everyone.
+    pub allowed_users: Vec<String>,
+    /// Accept room invites from allowed users into allowed rooms.
+    pub auto_join: bool,
+    /// Largest inbound or outbound attachment handled, in bytes.
+    pub max_attachment_bytes: usize,
+}
+
+impl std::fmt::Debug for MatrixConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MatrixConfig")
+            .field("enabled", &self.enabled)
+            .field("homeserver_url", &self.homeserver_url)
+            .field("user_id", &self.user_id)
+            .field("password", &"[REDACTED]")
+            .field("device_name", &self.device_name)
+            .field("allowed_rooms", &self.allowed_rooms)
+            .field("allowed_users", &self.allowed_users)
+            .field("auto_join", &self.auto_join)
+            .field("max_attachment_bytes", &self.max_attachment_bytes)
+            .finish()
+    }
+}
+
+impl SystemSecrets for MatrixConfig {
+    fn section() -> &'static str {
+        "matrix"
+    }
+
+    fn is_messaging_adapter() -> bool {
+        true
+    }
+
+    fn secret_fields() -> &'static [SecretField] {
+        &[SecretField {
+            toml_key: "password",
+            secret_name: "MATRIX_PASSWORD",
+            instance_pattern: None,
+        }]
+    }
+}
+
+/// IRC channels and private messages as channels.
+#[derive(Clone)]
+pub struct IrcConfig {
+    pub enabled: bool,
+    /// Server hostname (e.g. `irc.libera.chat`).
+    pub server: String,
+    pub port: u16,
+    pub use_tls: bool,
+    pub nickname: String,
+    /// Ident username. Defaults to the nickname.
+    pub username: Option<String>,
+    pub realname: String,
+    /// Server password sent with `PASS`, for bouncers and private servers.
+    pub server_password: Option<String>,
+    /// SASL PLAIN account name. Defaults to the nickname.
+    pub sasl_username: Option<String>,
+    /// SASL PLAIN password. SASL is only attempted when this is set.
+    pub sasl_password: Option<String>,
+    /// Channels to join, as `#name` or `#name key`.
+    pub channels: Vec<String>,
+    /// Nicks allowed to private-message the bot. If empty, PMs are ignored.
+    pub dm_allowed_users: Vec<String>,
+    /// Delay between consecutive outbound lines, in milliseconds.
+    pub send_interval_ms: u64,
+}
+
+impl std::fmt::Debug for IrcConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IrcConfig")
+            .field("enabled", &self.enabled)
+            .field("server", &self.server)
+            .field("port", &self.port)
+            .field("use_tls", &self.use_tls)
+            .field("nickname", &self.nickname)
+            .field("username", &self.username)
+            .field("realname", &self.realname)
+            .field(
+                "server_password",
+                &self.server_password.as_ref().map(|_| "[REDACTED]"),
+            )
+            .field("sasl_username", &self.sasl_username)
+            .field(
+                "sasl_password",
+                &self.sasl_password.as_ref().map(|_| "[REDACTED]"),
+            )
+            .field("channels", &self.channels)
+            .field("dm_allowed_users", &self.dm_allowed_users)
+            .field("send_interval_ms", &self.send_interval_ms)
+            .finish()
+    }
+}
+
+impl SystemSecrets for IrcConfig {
+    fn section() -> &'static str {
+        "irc"
+    }
+
+    fn is_messaging_adapter() -> bool {
+        true
+    }
+
+    fn secret_fields() -> &'static [SecretField] {
+        &[
+            SecretField {
+                toml_key: "server_password",
+                secret_name: "IRC_SERVER_PASSWORD",
+                instance_pattern: None,
+            },
+            SecretField {
+                toml_key: "sasl_password",
+                secret_name: "IRC_SASL_PASSWORD",
+                instance_pattern: None,
+            },
+        ]
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct WebhookConfig {
     pub enabled: bool,
@@ -1,12 +1,17 @@
 //! Conversation history and context management.
 
+pub mod archive;
 pub mod channels;
 pub mod context;
+pub mod erasure;
 pub mod history;
+pub mod language;
 pub mod worker_transcript;
 
+pub use archive::{ChannelArchive, ChannelSummary};
 pub use channels::ChannelStore;
 pub use history::{
-    ConversationLogger, ProcessRunLogger, TimelineItem, WorkerDetailRow, WorkerRunRow,
+    ConversationLogger, MessageSearchHit, MessageSearchQuery, ProcessRunLogger, TimelineItem,
+    WorkerDetailRow, WorkerRunRow,
 };
 pub use worker_transcript::{ActionContent, TranscriptStep};
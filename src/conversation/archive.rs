@@ -0,0 +1,358 @@
+//! Archived channel history: segment summaries and cold-stored messages.
+
+use crate::config::{ArchiveConfig, ArchivedMessages};
+use crate::conversation::history::ConversationMessage;
+
+use serde::Serialize;
+use sqlx::{Row as _, SqlitePool};
+
+/// A summary standing in for one archived segment of a channel's history.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelSummary {
+    pub id: String,
+    pub channel_id: String,
+    pub summary: String,
+    pub message_count: i64,
+    pub first_message_at: chrono::DateTime<chrono::Utc>,
+    pub last_message_at: chrono::DateTime<chrono::Utc>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Reads and writes `channel_summaries` and moves archived messages out of
+/// `conversation_messages`.
+#[derive(Debug, Clone)]
+pub struct ChannelArchive {
+    pool: SqlitePool,
+}
+
+impl ChannelArchive {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Every channel with stored messages, with its message count.
+    pub async fn channel_message_counts(&self) -> crate::error::Result<Vec<(String, i64)>> {
+        let rows = sqlx::query(
+            "SELECT channel_id, COUNT(*) AS message_count \
+             FROM conversation_messages \
+             GROUP BY channel_id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.try_get("channel_id").unwrap_or_default(),
+                    row.try_get("message_count").unwrap_or_default(),
+                )
+            })
+            .collect())
+    }
+
+    /// Number of messages in a channel older than `days` days.
+    pub async fn count_older_than(&self, channel_id: &str, days: u64) -> crate::error::Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM conversation_messages \
+             WHERE channel_id = ? AND created_at < datetime('now', ?)",
+        )
+        .bind(channel_id)
+        .bind(format!("-{days} days"))
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+
+    /// The oldest messages in a channel, oldest first.
+    pub async fn load_oldest(
+        &self,
+        channel_id: &str,
+        limit: i64,
+    ) -> crate::error::Result<Vec<ConversationMessage>> {
+        let rows = sqlx::query(
+            "SELECT id, channel_id, role, sender_name, sender_id, content, metadata, created_at \
+             FROM conversation_messages \
+             WHERE channel_id = ? \
+             ORDER BY created_at ASC, rowid ASC \
+             LIMIT ?",
+        )
+        .bind(channel_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ConversationMessage {
+                id: row.try_get("id").unwrap_or_default(),
+                channel_id: row.try_get("channel_id").unwrap_or_default(),
+                role: row.try_get("role").unwrap_or_default(),
+                sender_name: row.try_get("sender_name").ok(),
+                sender_id: row.try_get("sender_id").ok(),
+                content: row.try_get("content").unwrap_or_default(),
+                metadata: row.try_get("metadata").ok(),
+                created_at: row
+                    .try_get("created_at")
+                    .unwrap_or_else(|_| chrono::Utc::now()),
+            })
+            .collect())
+    }
+
+    /// Store a segment's summary and move its messages out of the live
+    /// history, atomically.
+    ///
+    /// With [`ArchivedMessages::Cold`] the messages are copied into
+    /// `conversation_messages_archive` first; with `Delete` they are dropped.
+    pub async fn archive_segment(
+        &self,
+        channel_id: &str,
+        summary: &str,
+        messages: &[ConversationMessage],
+        raw_messages: ArchivedMessages,
+    ) -> crate::error::Result<String> {
+        let summary_id = uuid::Uuid::new_v4().to_string();
+        if messages.is_empty() {
+            return Ok(summary_id);
+        }
+        let placeholders = vec!["?"; messages.len()].join(", ");
+
+        let mut tx = self.pool.begin().await?;
+
+        let insert_summary = format!(
+            "INSERT INTO channel_summaries \
+             (id, channel_id, summary, message_count, first_message_at, last_message_at) \
+             SELECT ?, ?, ?, COUNT(*), MIN(created_at), MAX(created_at) \
+             FROM conversation_messages WHERE id IN ({placeholders})"
+        );
+        let mut query = sqlx::query(&insert_summary)
+            .bind(&summary_id)
+            .bind(channel_id)
+            .bind(summary);
+        for message in messages {
+            query = query.bind(&message.id);
+        }
+        query.execute(&mut *tx).await?;
+
+        if raw_messages == ArchivedMessages::Cold {
+            let copy = format!(
+                "INSERT OR IGNORE INTO conversation_messages_archive \
+                 (id, channel_id, role, sender_name, sender_id, content, metadata, created_at, summary_id) \
+                 SELECT id, channel_id, role, sender_name, sender_id, content, metadata, created_at, ? \
+                 FROM conversation_messages WHERE id IN ({placeholders})"
+            );
+            let mut query = sqlx::query(&copy).bind(&summary_id);
+            for message in messages {
+                query = query.bind(&message.id);
+            }
+            query.execute(&mut *tx).await?;
+        }
+
+        let delete = format!("DELETE FROM conversation_messages WHERE id IN ({placeholders})");
+        let mut query = sqlx::query(&delete);
+        for message in messages {
+            query = query.bind(&message.id);
+        }
+        query.execute(&mut *tx).await?;
+
+        tx.commit().await?;
+        Ok(summary_id)
+    }
+
+    /// Summaries for a channel, oldest first, optionally limited to segments
+    /// overlapping the `after`..`before` window (RFC 3339 timestamps).
+    pub async fn list_summaries(
+        &self,
+        channel_id: &str,
+        before: Option<&str>,
+        after: Option<&str>,
+        limit: i64,
+    ) -> crate::error::Result<Vec<ChannelSummary>> {
+        let mut sql = String::from(
+            "SELECT id, channel_id, summary, message_count, first_message_at, last_message_at, created_at \
+             FROM channel_summaries \
+             WHERE channel_id = ?",
+        );
+        if before.is_some() {
+            sql.push_str(" AND datetime(first_message_at) < datetime(?)");
+        }
+        if after.is_some() {
+            sql.push_str(" AND datetime(last_message_at) > datetime(?)");
+        }
+        // Take the most recent segments when there are more than `limit`.
+        sql.push_str(" ORDER BY first_message_at DESC LIMIT ?");
+
+        let mut query = sqlx::query(&sql).bind(channel_id);
+        if let Some(before) = before {
+            query = query.bind(before);
+        }
+        if let Some(after) = after {
+            query = query.bind(after);
+        }
+        let rows = query.bind(limit).fetch_all(&self.pool).await?;
+
+        let mut summaries: Vec<ChannelSummary> = rows
+            .into_iter()
+            .map(|row| ChannelSummary {
+                id: row.try_get("id").unwrap_or_default(),
+                channel_id: row.try_get("channel_id").unwrap_or_default(),
+                summary: row.try_get("summary").unwrap_or_default(),
+                message_count: row.try_get("message_count").unwrap_or_default(),
+                first_message_at: row
+                    .try_get("first_message_at")
+                    .unwrap_or_else(|_| chrono::Utc::now()),
+                last_message_at: row
+                    .try_get("last_message_at")
+                    .unwrap_or_else(|_| chrono::Utc::now()),
+                created_at: row
+                    .try_get("created_at")
+                    .unwrap_or_else(|_| chrono::Utc::now()),
+            })
+            .collect();
+        summaries.reverse();
+        Ok(summaries)
+    }
+}
+
+/// How many of a channel's oldest messages are due for archival.
+///
+/// `total` is the channel's message count and `older_than_max_age` how many
+/// of them are past `max_age_days`. The newest `keep_recent` messages are
+/// never due, and only whole segments are archived, so the result is a
+/// multiple of `segment_size`.
+pub fn messages_due(config: &ArchiveConfig, total: i64, older_than_max_age: i64) -> i64 {
+    let archivable = total.saturating_sub(config.keep_recent as i64).max(0);
+
+    let by_count = if config.max_messages > 0 && total > config.max_messages as i64 {
+        archivable
+    } else {
+        0
+    };
+    let by_age = if config.max_age_days > 0 {
+        older_than_max_age.min(archivable)
+    } else {
+        0
+    };
+
+    let due = by_count.max(by_age);
+    let segment_size = config.segment_size.max(1) as i64;
+    due - due % segment_size
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(max_messages: usize, max_age_days: u64) -> ArchiveConfig {
+        ArchiveConfig {
+            max_messages,
+            max_age_days,
+            keep_recent: 100,
+            segment_size: 50,
+            ..ArchiveConfig::default()
+        }
+    }
+
+    #[test]
+    fn messages_due_keeps_recent_and_rounds_to_segments() {
+        // Under the count threshold, nothing is due.
+        assert_eq!(messages_due(&config(500, 0), 500, 0), 0);
+        // Over it, everything but the newest 100 is due, in whole segments.
+        assert_eq!(messages_due(&config(500, 0), 501, 0), 400);
+        assert_eq!(messages_due(&config(500, 0), 620, 0), 500);
+        // Age trigger archives only aged-out messages, never the recent ones.
+        assert_eq!(messages_due(&config(0, 30), 300, 120), 100);
+        assert_eq!(messages_due(&config(0, 30), 300, 290), 200);
+        // Disabled triggers archive nothing.
+        assert_eq!(messages_due(&config(0, 0), 10_000, 10_000), 0);
+    }
+
+    #[tokio::test]
+    async fn archive_segment_moves_messages_and_stores_summary() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to create sqlite memory pool");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("failed to run migrations");
+
+        for (index, content) in ["deploy failed", "rolled back", "all good now"]
+            .iter()
+            .enumerate()
+        {
+            sqlx::query(
+                "INSERT INTO conversation_messages (id, channel_id, role, content, created_at) \
+                 VALUES (?, 'discord:1', 'user', ?, ?)",
+            )
+            .bind(format!("m{index}"))
+            .bind(content)
+            .bind(format!("2026-01-0{} 12:00:00", index + 1))
+            .execute(&pool)
+            .await
+            .expect("failed to insert message");
+        }
+
+        let archive = ChannelArchive::new(pool.clone());
+        let oldest = archive
+            .load_oldest("discord:1", 2)
+            .await
+            .expect("load should succeed");
+        assert_eq!(oldest.len(), 2);
+        assert_eq!(oldest[0].content, "deploy failed");
+
+        let summary_id = archive
+            .archive_segment(
+                "discord:1",
+                "A deploy failed and was rolled back.",
+                &oldest,
+                ArchivedMessages::Cold,
+            )
+            .await
+            .expect("archive should succeed");
+
+        let counts = archive
+            .channel_message_counts()
+            .await
+            .expect("count should succeed");
+        assert_eq!(counts, vec![("discord:1".to_string(), 1)]);
+
+        let cold: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM conversation_messages_archive WHERE summary_id = ?",
+        )
+        .bind(&summary_id)
+        .fetch_one(&pool)
+        .await
+        .expect("archive count should succeed");
+        assert_eq!(cold, 2);
+
+        let summaries = archive
+            .list_summaries("discord:1", None, None, 10)
+            .await
+            .expect("list should succeed");
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].message_count, 2);
+        assert_eq!(
+            summaries[0].first_message_at.date_naive().to_string(),
+            "2026-01-01"
+        );
+        assert_eq!(
+            summaries[0].last_message_at.date_naive().to_string(),
+            "2026-01-02"
+        );
+
+        let same_day = archive
+            .list_summaries("discord:1", None, Some("2026-01-02T06:00:00Z"), 10)
+            .await
+            .expect("list should succeed");
+        assert_eq!(same_day.len(), 1);
+
+        let outside_window = archive
+            .list_summaries("discord:1", None, Some("2026-01-02T18:00:00Z"), 10)
+            .await
+            .expect("list should succeed");
+        assert!(outside_window.is_empty());
+    }
+}
@@ -1,7 +1,11 @@
 //! Channel tracking and metadata (SQLite).
 
+use crate::config::{ModerationStrictness, ResponsePace};
+use crate::llm::routing::ChannelRouting;
+use crate::memory::MemoryScope;
+
 use sqlx::{Row as _, SqlitePool};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// Tracks known channels in SQLite.
 ///
@@ -25,6 +29,38 @@ pub struct ChannelInfo {
     pub last_activity_at: chrono::DateTime<chrono::Utc>,
 }
 
+/// The language a channel's replies should use, as ISO 639-3 codes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct ChannelLanguage {
+    /// Set through the API. Wins over `detected`.
+    pub preferred: Option<String>,
+    /// The language people in the channel were last seen writing in.
+    pub detected: Option<String>,
+}
+
+impl ChannelLanguage {
+    /// The language to reply in, if any is known.
+    pub fn resolved(&self) -> Option<&str> {
+        self.preferred.as_deref().or(self.detected.as_deref())
+    }
+}
+
+/// A channel's timezone, as IANA names.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct ChannelTimezone {
+    /// Set through the API. Wins over `inferred`.
+    pub configured: Option<String>,
+    /// The timezone the platform or people in the channel last reported.
+    pub inferred: Option<String>,
+}
+
+impl ChannelTimezone {
+    /// The timezone to read times in, if any is known.
+    pub fn resolved(&self) -> Option<&str> {
+        self.configured.as_deref().or(self.inferred.as_deref())
+    }
+}
+
 impl ChannelStore {
     pub fn new(pool: SqlitePool) -> Self {
         Self { pool }
@@ -41,14 +77,20 @@ impl ChannelStore {
         let platform = extract_platform(&channel_id);
         let display_name = extract_display_name(&platform, &channel_id, metadata);
         let platform_meta = extract_platform_meta(&platform, metadata);
+        let inferred_timezone = metadata
+            .get(crate::metadata_keys::SENDER_TIMEZONE)
+            .and_then(|value| value.as_str())
+            .filter(|timezone| timezone.parse::<chrono_tz::Tz>().is_ok())
+            .map(str::to_string);
 
         tokio::spawn(async move {
             if let Err(error) = sqlx::query(
-                "INSERT INTO channels (id, platform, display_name, platform_meta, last_activity_at) \
-                 VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP) \
+                "INSERT INTO channels (id, platform, display_name, platform_meta, inferred_timezone, last_activity_at) \
+                 VALUES (?, ?, ?, ?, ?, CURRENT_TIMESTAMP) \
                  ON CONFLICT(id) DO UPDATE SET \
                      display_name = COALESCE(excluded.display_name, channels.display_name), \
                      platform_meta = COALESCE(excluded.platform_meta, channels.platform_meta), \
+                     inferred_timezone = COALESCE(excluded.inferred_timezone, channels.inferred_timezone), \
                      is_active = 1, \
                      last_activity_at = CURRENT_TIMESTAMP"
             )
@@ -56,6 +98,7 @@ impl ChannelStore {
             .bind(&platform)
             .bind(&display_name)
             .bind(&platform_meta)
+            .bind(&inferred_timezone)
             .execute(&pool)
             .await
             {
@@ -229,6 +272,344 @@ impl ChannelStore {
 
         Ok(result.rows_affected() > 0)
     }
+
+    /// Set or clear a channel's model overrides. An empty override is
+    /// stored as NULL. Returns false if the channel is unknown.
+    pub async fn set_routing(
+        &self,
+        channel_id: &str,
+        routing: &ChannelRouting,
+    ) -> crate::error::Result<bool> {
+        let routing_json = if routing.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(routing).map_err(|e| anyhow::anyhow!(e))?)
+        };
+
+        let result = sqlx::query("UPDATE channels SET routing = ? WHERE id = ?")
+            .bind(routing_json)
+            .bind(channel_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Set or clear a channel's reply pacing override. Returns false if the
+    /// channel is unknown.
+    pub async fn set_pace(
+        &self,
+        channel_id: &str,
+        pace: Option<ResponsePace>,
+    ) -> crate::error::Result<bool> {
+        let result = sqlx::query("UPDATE channels SET pace = ? WHERE id = ?")
+            .bind(pace.map(ResponsePace::as_str))
+            .bind(channel_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get a channel's reply pacing override, if one is set.
+    pub async fn pace(&self, channel_id: &str) -> crate::error::Result<Option<ResponsePace>> {
+        let pace: Option<String> = sqlx::query_scalar("SELECT pace FROM channels WHERE id = ?")
+            .bind(channel_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?
+            .flatten();
+
+        Ok(pace.as_deref().and_then(ResponsePace::parse))
+    }
+
+    /// Set or clear a channel's memory scope override. Returns false if the
+    /// channel is unknown.
+    pub async fn set_memory_scope(
+        &self,
+        channel_id: &str,
+        scope: Option<MemoryScope>,
+    ) -> crate::error::Result<bool> {
+        let result = sqlx::query("UPDATE channels SET memory_scope = ? WHERE id = ?")
+            .bind(scope.map(MemoryScope::as_str))
+            .bind(channel_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get a channel's memory scope override, if one is set.
+    pub async fn memory_scope(
+        &self,
+        channel_id: &str,
+    ) -> crate::error::Result<Option<MemoryScope>> {
+        let scope: Option<String> =
+            sqlx::query_scalar("SELECT memory_scope FROM channels WHERE id = ?")
+                .bind(channel_id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?
+                .flatten();
+
+        Ok(scope.as_deref().and_then(MemoryScope::parse))
+    }
+
+    /// Mark a channel ephemeral or not. Ephemeral channels are skipped by
+    /// automatic fact extraction. Returns false if the channel is unknown.
+    pub async fn set_ephemeral(
+        &self,
+        channel_id: &str,
+        ephemeral: bool,
+    ) -> crate::error::Result<bool> {
+        let result = sqlx::query("UPDATE channels SET ephemeral = ? WHERE id = ?")
+            .bind(if ephemeral { 1_i64 } else { 0_i64 })
+            .bind(channel_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// IDs of every channel marked ephemeral.
+    pub async fn ephemeral_channels(&self) -> crate::error::Result<HashSet<String>> {
+        let ids: Vec<String> = sqlx::query_scalar("SELECT id FROM channels WHERE ephemeral = 1")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(ids.into_iter().collect())
+    }
+
+    /// Set or clear a channel's moderation strictness override. Returns false
+    /// if the channel is unknown.
+    pub async fn set_moderation(
+        &self,
+        channel_id: &str,
+        strictness: Option<ModerationStrictness>,
+    ) -> crate::error::Result<bool> {
+        let result = sqlx::query("UPDATE channels SET moderation = ? WHERE id = ?")
+            .bind(strictness.map(ModerationStrictness::as_str))
+            .bind(channel_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get a channel's moderation strictness override, if one is set.
+    pub async fn moderation(
+        &self,
+        channel_id: &str,
+    ) -> crate::error::Result<Option<ModerationStrictness>> {
+        let strictness: Option<String> =
+            sqlx::query_scalar("SELECT moderation FROM channels WHERE id = ?")
+                .bind(channel_id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?
+                .flatten();
+
+        Ok(strictness.as_deref().and_then(ModerationStrictness::parse))
+    }
+
+    /// Set or clear a channel's preferred language. Returns false if the
+    /// channel is unknown.
+    pub async fn set_language(
+        &self,
+        channel_id: &str,
+        language: Option<&str>,
+    ) -> crate::error::Result<bool> {
+        let result = sqlx::query("UPDATE channels SET language = ? WHERE id = ?")
+            .bind(language)
+            .bind(channel_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record the language a channel's messages are written in.
+    /// Fire-and-forget.
+    pub fn set_detected_language(&self, channel_id: &str, language: &str) {
+        let pool = self.pool.clone();
+        let channel_id = channel_id.to_string();
+        let language = language.to_string();
+
+        tokio::spawn(async move {
+            if let Err(error) =
+                sqlx::query("UPDATE channels SET detected_language = ? WHERE id = ?")
+                    .bind(&language)
+                    .bind(&channel_id)
+                    .execute(&pool)
+                    .await
+            {
+                tracing::warn!(%error, %channel_id, "failed to record channel language");
+            }
+        });
+    }
+
+    /// Get a channel's preferred and detected languages.
+    pub async fn language(&self, channel_id: &str) -> crate::error::Result<ChannelLanguage> {
+        let row = sqlx::query("SELECT language, detected_language FROM channels WHERE id = ?")
+            .bind(channel_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(row
+            .map(|row| ChannelLanguage {
+                preferred: row.try_get("language").ok().flatten(),
+                detected: row.try_get("detected_language").ok().flatten(),
+            })
+            .unwrap_or_default())
+    }
+
+    /// Set or clear a channel's configured timezone. Returns false if the
+    /// channel is unknown.
+    pub async fn set_timezone(
+        &self,
+        channel_id: &str,
+        timezone: Option<&str>,
+    ) -> crate::error::Result<bool> {
+        let result = sqlx::query("UPDATE channels SET timezone = ? WHERE id = ?")
+            .bind(timezone)
+            .bind(channel_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record a timezone someone in the channel reported. Fire-and-forget.
+    pub fn set_inferred_timezone(&self, channel_id: &str, timezone: &str) {
+        let pool = self.pool.clone();
+        let channel_id = channel_id.to_string();
+        let timezone = timezone.to_string();
+
+        tokio::spawn(async move {
+            if let Err(error) =
+                sqlx::query("UPDATE channels SET inferred_timezone = ? WHERE id = ?")
+                    .bind(&timezone)
+                    .bind(&channel_id)
+                    .execute(&pool)
+                    .await
+            {
+                tracing::warn!(%error, %channel_id, "failed to record channel timezone");
+            }
+        });
+    }
+
+    /// Get a channel's configured and inferred timezones.
+    pub async fn timezone(&self, channel_id: &str) -> crate::error::Result<ChannelTimezone> {
+        let row = sqlx::query("SELECT timezone, inferred_timezone FROM channels WHERE id = ?")
+            .bind(channel_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(row
+            .map(|row| ChannelTimezone {
+                configured: row.try_get("timezone").ok().flatten(),
+                inferred: row.try_get("inferred_timezone").ok().flatten(),
+            })
+            .unwrap_or_default())
+    }
+
+    /// Hand a channel to an operator. Starting a takeover that is already
+    /// running keeps its original start time. Returns false if the channel is
+    /// unknown.
+    pub async fn start_takeover(&self, channel_id: &str) -> crate::error::Result<bool> {
+        let result = sqlx::query(
+            "UPDATE channels \
+             SET takeover_started_at = COALESCE(takeover_started_at, CURRENT_TIMESTAMP) \
+             WHERE id = ?",
+        )
+        .bind(channel_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Hand a channel back to the agent. Returns when the takeover started,
+    /// or `None` if there was none.
+    pub async fn end_takeover(
+        &self,
+        channel_id: &str,
+    ) -> crate::error::Result<Option<chrono::DateTime<chrono::Utc>>> {
+        let mut tx = self.pool.begin().await.map_err(|e| anyhow::anyhow!(e))?;
+        let started_at: Option<chrono::DateTime<chrono::Utc>> =
+            sqlx::query_scalar("SELECT takeover_started_at FROM channels WHERE id = ?")
+                .bind(channel_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?
+                .flatten();
+        if started_at.is_some() {
+            sqlx::query("UPDATE channels SET takeover_started_at = NULL WHERE id = ?")
+                .bind(channel_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?;
+        }
+        tx.commit().await.map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(started_at)
+    }
+
+    /// When the running takeover of a channel started, if an operator has
+    /// taken it over.
+    pub async fn takeover_started_at(
+        &self,
+        channel_id: &str,
+    ) -> crate::error::Result<Option<chrono::DateTime<chrono::Utc>>> {
+        let started_at =
+            sqlx::query_scalar("SELECT takeover_started_at FROM channels WHERE id = ?")
+                .bind(channel_id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?
+                .flatten();
+
+        Ok(started_at)
+    }
+
+    /// Load every channel's model overrides, keyed by channel ID.
+    ///
+    /// Rows with malformed JSON are skipped with a warning rather than
+    /// failing agent startup.
+    pub async fn load_routing(&self) -> crate::error::Result<HashMap<String, ChannelRouting>> {
+        let rows = sqlx::query("SELECT id, routing FROM channels WHERE routing IS NOT NULL")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        let mut overrides = HashMap::new();
+        for row in rows {
+            let channel_id: String = row.try_get("id").unwrap_or_default();
+            let routing_json: String = row.try_get("routing").unwrap_or_default();
+            match serde_json::from_str::<ChannelRouting>(&routing_json) {
+                Ok(routing) if !routing.is_empty() => {
+                    overrides.insert(channel_id, routing);
+                }
+                Ok(_) => {}
+                Err(error) => {
+                    tracing::warn!(%error, %channel_id, "ignoring malformed channel routing");
+                }
+            }
+        }
+
+        Ok(overrides)
+    }
 }
 
 fn row_to_channel_info(row: sqlx::sqlite::SqliteRow) -> ChannelInfo {
@@ -281,7 +662,7 @@ fn extract_display_name(
 }
 
 /// Build a JSON blob of platform-specific metadata worth persisting.
-fn extract_platform_meta(
+pub(crate) fn extract_platform_meta(
     platform: &str,
     metadata: &HashMap<String, serde_json::Value>,
 ) -> Option<String> {
@@ -309,7 +690,11 @@ fn extract_platform_meta(
             }
         }
         "telegram" => {
-            for key in ["telegram_chat_id", "telegram_chat_type"] {
+            for key in [
+                "telegram_chat_id",
+                "telegram_chat_type",
+                "telegram_thread_id",
+            ] {
                 if let Some(value) = metadata.get(key) {
                     meta.insert(key.to_string(), value.clone());
                 }
@@ -320,6 +705,27 @@ fn extract_platform_meta(
                 meta.insert("twitch_channel".to_string(), value.clone());
             }
         }
+        "github" => {
+            for key in ["github_repo", "github_issue_number", "github_kind"] {
+                if let Some(value) = metadata.get(key) {
+                    meta.insert(key.to_string(), value.clone());
+                }
+            }
+        }
+        "irc" => {
+            for key in ["irc_channel", "irc_reply_target", "irc_is_private"] {
+                if let Some(value) = metadata.get(key) {
+                    meta.insert(key.to_string(), value.clone());
+                }
+            }
+        }
+        "matrix" => {
+            for key in ["matrix_room_id", "matrix_is_direct", "matrix_is_encrypted"] {
+                if let Some(value) = metadata.get(key) {
+                    meta.insert(key.to_string(), value.clone());
+                }
+            }
+        }
         "email" => {
             for key in [
                 "email_from",
@@ -365,6 +771,16 @@ mod tests {
                 platform TEXT NOT NULL,
                 display_name TEXT,
                 platform_meta TEXT,
+                routing TEXT,
+                pace TEXT,
+                memory_scope TEXT,
+                ephemeral INTEGER NOT NULL DEFAULT 0,
+                takeover_started_at TIMESTAMP,
+                moderation TEXT,
+                language TEXT,
+                detected_language TEXT,
+                timezone TEXT,
+                inferred_timezone TEXT,
                 is_active INTEGER NOT NULL DEFAULT 1,
                 created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
                 last_activity_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
@@ -459,4 +875,231 @@ mod tests {
             .expect("channel should still exist");
         assert!(channel.is_active);
     }
+
+    #[tokio::test]
+    async fn routing_round_trips_and_clears() {
+        let store = setup_store().await;
+
+        sqlx::query("INSERT INTO channels (id, platform) VALUES (?, ?)")
+            .bind("discord:1")
+            .bind("discord")
+            .execute(&store.pool)
+            .await
+            .expect("channel should insert");
+
+        let routing = ChannelRouting {
+            channel: Some("anthropic/claude-haiku-4.5".into()),
+            ..Default::default()
+        };
+        assert!(store.set_routing("discord:1", &routing).await.unwrap());
+        assert!(!store.set_routing("discord:2", &routing).await.unwrap());
+
+        let loaded = store.load_routing().await.unwrap();
+        assert_eq!(loaded.get("discord:1"), Some(&routing));
+
+        store
+            .set_routing("discord:1", &ChannelRouting::default())
+            .await
+            .unwrap();
+        assert!(store.load_routing().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn pace_round_trips_and_clears() {
+        let store = setup_store().await;
+
+        sqlx::query("INSERT INTO channels (id, platform) VALUES (?, ?)")
+            .bind("discord:1")
+            .bind("discord")
+            .execute(&store.pool)
+            .await
+            .expect("channel should insert");
+
+        assert_eq!(store.pace("discord:1").await.unwrap(), None);
+        assert!(
+            store
+                .set_pace("discord:1", Some(ResponsePace::Casual))
+                .await
+                .unwrap()
+        );
+        assert!(
+            !store
+                .set_pace("discord:2", Some(ResponsePace::Casual))
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            store.pace("discord:1").await.unwrap(),
+            Some(ResponsePace::Casual)
+        );
+
+        store.set_pace("discord:1", None).await.unwrap();
+        assert_eq!(store.pace("discord:1").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn preferred_language_wins_over_detected() {
+        let store = setup_store().await;
+
+        sqlx::query("INSERT INTO channels (id, platform) VALUES (?, ?)")
+            .bind("discord:1")
+            .bind("discord")
+            .execute(&store.pool)
+            .await
+            .expect("channel should insert");
+
+        let language = store.language("discord:1").await.unwrap();
+        assert_eq!(language, ChannelLanguage::default());
+        assert_eq!(language.resolved(), None);
+
+        sqlx::query("UPDATE channels SET detected_language = 'spa' WHERE id = 'discord:1'")
+            .execute(&store.pool)
+            .await
+            .unwrap();
+        assert_eq!(
+            store.language("discord:1").await.unwrap().resolved(),
+            Some("spa")
+        );
+
+        assert!(store.set_language("discord:1", Some("deu")).await.unwrap());
+        assert!(!store.set_language("discord:2", Some("deu")).await.unwrap());
+        let language = store.language("discord:1").await.unwrap();
+        assert_eq!(language.resolved(), Some("deu"));
+        assert_eq!(language.detected.as_deref(), Some("spa"));
+
+        store.set_language("discord:1", None).await.unwrap();
+        assert_eq!(
+            store.language("discord:1").await.unwrap().resolved(),
+            Some("spa")
+        );
+    }
+
+    #[tokio::test]
+    async fn configured_timezone_wins_over_inferred() {
+        let store = setup_store().await;
+
+        sqlx::query("INSERT INTO channels (id, platform) VALUES (?, ?)")
+            .bind("slack:T1:C1")
+            .bind("slack")
+            .execute(&store.pool)
+            .await
+            .expect("channel should insert");
+        assert_eq!(
+            store.timezone("slack:T1:C1").await.unwrap().resolved(),
+            None
+        );
+
+        sqlx::query(
+            "UPDATE channels SET inferred_timezone = 'Asia/Tokyo' WHERE id = 'slack:T1:C1'",
+        )
+        .execute(&store.pool)
+        .await
+        .unwrap();
+        assert!(
+            store
+                .set_timezone("slack:T1:C1", Some("Europe/Berlin"))
+                .await
+                .unwrap()
+        );
+        assert!(!store.set_timezone("slack:T1:C2", None).await.unwrap());
+        let timezone = store.timezone("slack:T1:C1").await.unwrap();
+        assert_eq!(timezone.resolved(), Some("Europe/Berlin"));
+        assert_eq!(timezone.inferred.as_deref(), Some("Asia/Tokyo"));
+
+        store.set_timezone("slack:T1:C1", None).await.unwrap();
+        assert_eq!(
+            store.timezone("slack:T1:C1").await.unwrap().resolved(),
+            Some("Asia/Tokyo")
+        );
+    }
+
+    #[tokio::test]
+    async fn memory_scope_round_trips_and_clears() {
+        let store = setup_store().await;
+
+        sqlx::query("INSERT INTO channels (id, platform) VALUES (?, ?)")
+            .bind("discord:1")
+            .bind("discord")
+            .execute(&store.pool)
+            .await
+            .expect("channel should insert");
+
+        assert_eq!(store.memory_scope("discord:1").await.unwrap(), None);
+        assert!(
+            store
+                .set_memory_scope("discord:1", Some(MemoryScope::Channel))
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            store.memory_scope("discord:1").await.unwrap(),
+            Some(MemoryScope::Channel)
+        );
+
+        store.set_memory_scope("discord:1", None).await.unwrap();
+        assert_eq!(store.memory_scope("discord:1").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn ephemeral_flag_round_trips() {
+        let store = setup_store().await;
+
+        for id in ["discord:1", "discord:2"] {
+            sqlx::query("INSERT INTO channels (id, platform) VALUES (?, ?)")
+                .bind(id)
+                .bind("discord")
+                .execute(&store.pool)
+                .await
+                .expect("channel should insert");
+        }
+
+        assert!(store.ephemeral_channels().await.unwrap().is_empty());
+        assert!(store.set_ephemeral("discord:2", true).await.unwrap());
+        assert!(!store.set_ephemeral("discord:9", true).await.unwrap());
+        let ephemeral = store.ephemeral_channels().await.unwrap();
+        assert_eq!(ephemeral.len(), 1);
+        assert!(ephemeral.contains("discord:2"));
+
+        store.set_ephemeral("discord:2", false).await.unwrap();
+        assert!(store.ephemeral_channels().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn takeover_starts_once_and_ends() {
+        let store = setup_store().await;
+
+        sqlx::query("INSERT INTO channels (id, platform) VALUES (?, ?)")
+            .bind("discord:1")
+            .bind("discord")
+            .execute(&store.pool)
+            .await
+            .expect("channel should insert");
+
+        assert_eq!(store.takeover_started_at("discord:1").await.unwrap(), None);
+        assert!(!store.start_takeover("discord:2").await.unwrap());
+        assert!(store.start_takeover("discord:1").await.unwrap());
+
+        assert!(
+            store
+                .takeover_started_at("discord:1")
+                .await
+                .unwrap()
+                .is_some()
+        );
+        sqlx::query("UPDATE channels SET takeover_started_at = '2026-01-01 00:00:00'")
+            .execute(&store.pool)
+            .await
+            .unwrap();
+        // A second start keeps the original start time.
+        assert!(store.start_takeover("discord:1").await.unwrap());
+        let restarted = store.takeover_started_at("discord:1").await.unwrap();
+        assert_eq!(
+            restarted.map(|at| at.date_naive().to_string()),
+            Some("2026-01-01".to_string())
+        );
+
+        assert_eq!(store.end_takeover("discord:1").await.unwrap(), restarted);
+        assert_eq!(store.end_takeover("discord:1").await.unwrap(), None);
+        assert_eq!(store.takeover_started_at("discord:1").await.unwrap(), None);
+    }
 }
@@ -0,0 +1,879 @@
+//! Per-user data erasure.
+//!
+//! Removes what an agent stores about one platform user: their messages
+//! (live and archived), the attachments they sent, memories extracted from
+//! their messages, conversation episodes that quote them, their profile, and
+//! responses to them still queued in the outbox or dead-lettered there.
+//! Messages can be anonymized instead of deleted, which keeps the text so the
+//! surrounding conversation still reads, but drops the sender's ID, name, and
+//! message metadata.
+//!
+//! Debugging data can't be attributed to one sender, so it is cleared for
+//! every channel the user wrote in: worker transcripts, the content of
+//! moderation events, prompt snapshots, in-memory completion traces, and
+//! cached branch conclusions. The LLM response cache and provider debug
+//! captures aren't keyed by channel at all, so they are emptied.
+//!
+//! Anything that may still hold the user's words after erasure, such as
+//! memories the agent saved itself, is listed in the report's `retained`.
+
+use crate::agent::branch_cache::BranchResultCache;
+use crate::agent::fact_extraction::SOURCE_PREFIX;
+use crate::agent::prompt_snapshot::PromptSnapshotStore;
+use crate::error::Result;
+use crate::llm::debug_capture::DebugCapture;
+use crate::llm::response_cache::ResponseCache;
+use crate::llm::trace::TraceStore;
+use crate::memory::MemorySearch;
+use crate::memory::profiles::{channel_platform, profile_id};
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Row as _, SqlitePool};
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+
+/// Sender name left on anonymized messages.
+pub const ERASED_SENDER_NAME: &str = "[erased user]";
+
+/// Content left on redacted moderation events.
+pub const ERASED_CONTENT: &str = "[erased]";
+
+/// What happens to the user's messages. Memories, attachments, episodes,
+/// profiles, outbox entries, and debugging data are always removed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErasureMode {
+    #[default]
+    Delete,
+    Anonymize,
+}
+
+/// The user to erase. Without a platform, the sender ID is matched on every
+/// platform.
+#[derive(Debug, Clone)]
+pub struct ErasureRequest {
+    pub sender_id: String,
+    pub platform: Option<String>,
+    pub mode: ErasureMode,
+}
+
+impl ErasureRequest {
+    fn matches_channel(&self, channel_id: &str) -> bool {
+        self.platform
+            .as_deref()
+            .is_none_or(|platform| channel_platform(channel_id) == platform)
+    }
+}
+
+/// What erasure removed from one agent.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ErasureReport {
+    /// Messages deleted or anonymized, depending on the mode.
+    pub messages: u64,
+    pub archived_messages: u64,
+    pub attachments: u64,
+    pub memories: u64,
+    pub episodes: u64,
+    pub profiles: u64,
+    /// Queued and dead-lettered responses addressed to the user.
+    pub outbox: u64,
+    /// Worker runs in the user's channels whose transcripts were cleared.
+    pub worker_transcripts: u64,
+    /// Moderation events in the user's channels whose content was redacted.
+    pub moderation_events: u64,
+    pub prompt_snapshots: u64,
+    /// In-memory completion traces.
+    pub traces: u64,
+    /// Cached branch conclusions for the user's channels.
+    pub branch_cache: u64,
+    /// Cached LLM responses. All of them are dropped.
+    pub response_cache: u64,
+    /// Captured provider exchanges. All of them are dropped.
+    pub debug_captures: u64,
+    /// Channels the user had written in.
+    pub channels: Vec<String>,
+    /// Data derived from the user that erasure couldn't remove, for the
+    /// operator to review.
+    pub retained: Vec<String>,
+}
+
+/// Stores outside the agent database that keep copies of prompts. Missing
+/// ones (no LLM manager yet, snapshots off) are skipped.
+#[derive(Clone, Copy, Default)]
+pub struct PromptBuffers<'a> {
+    pub prompt_snapshots: Option<&'a PromptSnapshotStore>,
+    pub traces: Option<&'a TraceStore>,
+    pub branch_cache: Option<&'a BranchResultCache>,
+    pub response_cache: Option<&'a ResponseCache>,
+    pub debug_capture: Option<&'a DebugCapture>,
+}
+
+/// Data outside SQLite to remove once the database changes are committed.
+#[derive(Debug, Default)]
+struct ErasedData {
+    attachment_paths: Vec<PathBuf>,
+    memory_ids: Vec<String>,
+    /// Live message rowids per channel, for finding the episodes that quote them.
+    episode_rowids: HashMap<String, Vec<i64>>,
+}
+
+/// Erase a user from one agent: SQLite first, in one transaction, then
+/// attachment files, the LanceDB indexes, and the prompt buffers.
+pub async fn erase_sender(
+    pool: &SqlitePool,
+    memory_search: &MemorySearch,
+    buffers: PromptBuffers<'_>,
+    request: &ErasureRequest,
+) -> Result<ErasureReport> {
+    let (mut report, erased) = erase_records(pool, request).await?;
+    erase_prompt_buffers(buffers, &mut report);
+
+    for path in &erased.attachment_paths {
+        match tokio::fs::remove_file(path).await {
+            Ok(()) => {}
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => {
+                tracing::warn!(%error, path = %path.display(), "failed to remove erased attachment");
+                report.retained.push(format!(
+                    "attachment file {} could not be removed",
+                    path.display()
+                ));
+            }
+        }
+    }
+
+    let mut stale_embeddings = 0;
+    for memory_id in &erased.memory_ids {
+        if let Err(error) = memory_search.embedding_table().delete(memory_id).await {
+            tracing::warn!(%error, memory_id, "failed to remove erased memory embedding");
+            stale_embeddings += 1;
+        }
+    }
+    if stale_embeddings > 0 {
+        report.retained.push(format!(
+            "{stale_embeddings} memory embeddings could not be removed; \
+             their memories are deleted, so search no longer returns them"
+        ));
+    }
+
+    if let Some(episodes) = memory_search.episodes() {
+        for (channel_id, rowids) in &erased.episode_rowids {
+            match episodes.delete_covering(channel_id, rowids).await {
+                Ok(removed) => report.episodes += removed as u64,
+                Err(error) => {
+                    tracing::warn!(%error, channel_id, "failed to remove erased episodes");
+                    report.retained.push(format!(
+                        "conversation episodes in {channel_id} could not be removed"
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Clear the prompt buffers. They hold whole prompts, so everything stored
+/// for the user's channels goes, and the buffers that aren't keyed by
+/// channel are emptied.
+fn erase_prompt_buffers(buffers: PromptBuffers<'_>, report: &mut ErasureReport) {
+    if let Some(response_cache) = buffers.response_cache {
+        report.response_cache += response_cache.clear() as u64;
+    }
+    if let Some(debug_capture) = buffers.debug_capture {
+        report.debug_captures += debug_capture.clear() as u64;
+    }
+    for channel_id in &report.channels {
+        if let Some(traces) = buffers.traces {
+            report.traces += traces.clear_conversation(channel_id) as u64;
+        }
+        if let Some(branch_cache) = buffers.branch_cache {
+            report.branch_cache += branch_cache.clear_channel(channel_id) as u64;
+        }
+        let Some(prompt_snapshots) = buffers.prompt_snapshots else {
+            continue;
+        };
+        match prompt_snapshots.clear_channel(channel_id) {
+            Ok(removed) => report.prompt_snapshots += removed as u64,
+            Err(error) => {
+                tracing::warn!(%error, channel_id, "failed to remove erased prompt snapshots");
+                report.retained.push(format!(
+                    "prompt snapshots in {channel_id} could not be removed"
+                ));
+            }
+        }
+    }
+}
+
+async fn erase_records(
+    pool: &SqlitePool,
+    request: &ErasureRequest,
+) -> Result<(ErasureReport, ErasedData)> {
+    let mut report = ErasureReport::default();
+    let mut erased = ErasedData::default();
+    let mut channels = BTreeSet::new();
+    let mut message_ids = HashSet::new();
+    let mut attachment_ids = Vec::new();
+    let mut tx = pool.begin().await?;
+
+    let live = sqlx::query(
+        "SELECT rowid, id, channel_id, metadata FROM conversation_messages \
+         WHERE sender_id = ? AND role = 'user'",
+    )
+    .bind(&request.sender_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    for row in live {
+        let channel_id: String = row.try_get("channel_id")?;
+        if !request.matches_channel(&channel_id) {
+            continue;
+        }
+        let id: String = row.try_get("id")?;
+        attachment_ids.extend(attachment_ids_from_metadata(
+            row.try_get("metadata").ok().flatten(),
+        ));
+        erased
+            .episode_rowids
+            .entry(channel_id.clone())
+            .or_default()
+            .push(row.try_get("rowid")?);
+        erase_message(&mut tx, "conversation_messages", &id, request.mode).await?;
+        report.messages += 1;
+        message_ids.insert(id);
+        channels.insert(channel_id);
+    }
+
+    let archived = sqlx::query(
+        "SELECT id, channel_id, metadata, summary_id FROM conversation_messages_archive \
+         WHERE sender_id = ? AND role = 'user'",
+    )
+    .bind(&request.sender_id)
+    .fetch_all(&mut *tx)
+    .await?;
+    let mut summary_ids = BTreeSet::new();
+    for row in archived {
+        let channel_id: String = row.try_get("channel_id")?;
+        if !request.matches_channel(&channel_id) {
+            continue;
+        }
+        let id: String = row.try_get("id")?;
+        attachment_ids.extend(attachment_ids_from_metadata(
+            row.try_get("metadata").ok().flatten(),
+        ));
+        summary_ids.insert(row.try_get::<String, _>("summary_id")?);
+        erase_message(&mut tx, "conversation_messages_archive", &id, request.mode).await?;
+        report.archived_messages += 1;
+        message_ids.insert(id);
+        channels.insert(channel_id);
+    }
+    if !summary_ids.is_empty() {
+        report.retained.push(format!(
+            "{} channel summaries were written from this user's archived messages and may \
+             still paraphrase them: {}",
+            summary_ids.len(),
+            summary_ids.into_iter().collect::<Vec<_>>().join(", ")
+        ));
+    }
+
+    for attachment_id in attachment_ids {
+        let disk_path: Option<(String,)> =
+            sqlx::query_as("DELETE FROM saved_attachments WHERE id = ? RETURNING disk_path")
+                .bind(&attachment_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+        if let Some((disk_path,)) = disk_path {
+            erased.attachment_paths.push(PathBuf::from(disk_path));
+            report.attachments += 1;
+        }
+    }
+
+    let candidates: Vec<(String, String)> =
+        sqlx::query_as("SELECT id, source FROM memories WHERE source LIKE ?")
+            .bind(format!("{SOURCE_PREFIX}%"))
+            .fetch_all(&mut *tx)
+            .await?;
+    for (memory_id, source) in candidates {
+        let from_user = source
+            .strip_prefix(SOURCE_PREFIX)
+            .is_some_and(|ids| ids.split(',').any(|id| message_ids.contains(id.trim())));
+        if !from_user {
+            continue;
+        }
+        sqlx::query("DELETE FROM memory_audit WHERE memory_id = ?")
+            .bind(&memory_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM associations WHERE source_id = ? OR target_id = ?")
+            .bind(&memory_id)
+            .bind(&memory_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM memories WHERE id = ?")
+            .bind(&memory_id)
+            .execute(&mut *tx)
+            .await?;
+        erased.memory_ids.push(memory_id);
+        report.memories += 1;
+    }
+
+    for channel_id in &channels {
+        let kept: Vec<(String,)> = sqlx::query_as(
+            "SELECT id FROM memories \
+             WHERE channel_id = ? AND forgotten = 0 \
+             AND (source IS NULL OR source NOT LIKE ?) \
+             ORDER BY id",
+        )
+        .bind(channel_id)
+        .bind(format!("{SOURCE_PREFIX}%"))
+        .fetch_all(&mut *tx)
+        .await?;
+        if !kept.is_empty() {
+            report.retained.push(format!(
+                "{} memories the agent saved in {channel_id} (memory_save, compaction, \
+                 branches) can't be traced to a sender and were kept; review them for this \
+                 user: {}",
+                kept.len(),
+                kept.into_iter()
+                    .map(|(id,)| id)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+    }
+    if !channels.is_empty() {
+        let (unscoped,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM memories \
+             WHERE channel_id IS NULL AND forgotten = 0 \
+             AND (source IS NULL OR source NOT LIKE ?)",
+        )
+        .bind(format!("{SOURCE_PREFIX}%"))
+        .fetch_one(&mut *tx)
+        .await?;
+        if unscoped > 0 {
+            report.retained.push(format!(
+                "{unscoped} memories saved without a channel can't be traced to a sender and \
+                 were kept; they may describe this user"
+            ));
+        }
+    }
+
+    let profile_ids: Vec<String> = match &request.platform {
+        Some(platform) => vec![profile_id(platform, &request.sender_id)],
+        None => sqlx::query_as::<_, (String,)>("SELECT id FROM user_profiles WHERE sender_id = ?")
+            .bind(&request.sender_id)
+            .fetch_all(&mut *tx)
+            .await?
+            .into_iter()
+            .map(|(id,)| id)
+            .collect(),
+    };
+    for profile_id in profile_ids {
+        sqlx::query("DELETE FROM user_profile_facts WHERE profile_id = ?")
+            .bind(&profile_id)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query("DELETE FROM user_profiles WHERE id = ?")
+            .bind(&profile_id)
+            .execute(&mut *tx)
+            .await?;
+        report.profiles += result.rows_affected();
+    }
+
+    for table in ["outbox", "outbox_failures"] {
+        let rows: Vec<(i64, String)> = sqlx::query_as(&format!(
+            "SELECT id, channel_id FROM {table} WHERE sender_id = ?"
+        ))
+        .bind(&request.sender_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        for (id, channel_id) in rows {
+            if !request.matches_channel(&channel_id) {
+                continue;
+            }
+            sqlx::query(&format!("DELETE FROM {table} WHERE id = ?"))
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            report.outbox += 1;
+        }
+    }
+
+    for channel_id in &channels {
+        let result = sqlx::query(
+            "UPDATE worker_runs SET transcript = NULL \
+             WHERE channel_id = ? AND transcript IS NOT NULL",
+        )
+        .bind(channel_id)
+        .execute(&mut *tx)
+        .await?;
+        report.worker_transcripts += result.rows_affected();
+
+        let result = sqlx::query(
+            "UPDATE moderation_events SET original_content = ?, \
+             delivered_content = CASE WHEN delivered_content IS NULL THEN NULL ELSE ? END \
+             WHERE channel_id = ? AND original_content != ?",
+        )
+        .bind(ERASED_CONTENT)
+        .bind(ERASED_CONTENT)
+        .bind(channel_id)
+        .bind(ERASED_CONTENT)
+        .execute(&mut *tx)
+        .await?;
+        report.moderation_events += result.rows_affected();
+
+        let (worker_runs, running): (i64, i64) = sqlx::query_as(
+            "SELECT COUNT(*), COALESCE(SUM(status = 'running'), 0) \
+             FROM worker_runs WHERE channel_id = ?",
+        )
+        .bind(channel_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        let (branch_runs,): (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM branch_runs WHERE channel_id = ?")
+                .bind(channel_id)
+                .fetch_one(&mut *tx)
+                .await?;
+        let kept_runs = worker_runs + branch_runs;
+        if running > 0 {
+            report.retained.push(format!(
+                "{running} workers still running in {channel_id} will store their transcripts \
+                 when they finish; erase again once they complete"
+            ));
+        }
+        if kept_runs > 0 {
+            report.retained.push(format!(
+                "task descriptions and results of {kept_runs} worker and branch runs in \
+                 {channel_id} were kept for the run history; the agent wrote them and they \
+                 may paraphrase this user"
+            ));
+        }
+    }
+
+    tx.commit().await?;
+    report.channels = channels.into_iter().collect();
+    Ok((report, erased))
+}
+
+async fn erase_message(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    table: &str,
+    id: &str,
+    mode: ErasureMode,
+) -> Result<()> {
+    let statement = match mode {
+        ErasureMode::Delete => format!("DELETE FROM {table} WHERE id = ?"),
+        ErasureMode::Anonymize => format!(
+            "UPDATE {table} SET sender_id = NULL, sender_name = '{ERASED_SENDER_NAME}', \
+             metadata = NULL WHERE id = ?"
+        ),
+    };
+    sqlx::query(&statement).bind(id).execute(&mut **tx).await?;
+    Ok(())
+}
+
+/// IDs of the saved attachments recorded in a message's metadata.
+fn attachment_ids_from_metadata(metadata: Option<String>) -> Vec<String> {
+    metadata
+        .and_then(|metadata| serde_json::from_str::<serde_json::Value>(&metadata).ok())
+        .and_then(|metadata| metadata.get("attachments").cloned())
+        .and_then(|attachments| attachments.as_array().cloned())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|attachment| attachment.get("id")?.as_str().map(str::to_string))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn seeded_pool() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        crate::db::MIGRATOR.run(&pool).await.unwrap();
+
+        for (id, channel_id, sender_id, metadata) in [
+            (
+                "m1",
+                "discord:1",
+                "42",
+                r#"{"attachments": [{"id": "a1"}]}"#,
+            ),
+            ("m2", "discord:2", "42", "{}"),
+            ("m3", "discord:1", "7", "{}"),
+            ("m4", "slack:T1", "42", "{}"),
+        ] {
+            sqlx::query(
+                "INSERT INTO conversation_messages \
+                 (id, channel_id, role, sender_name, sender_id, content, metadata) \
+                 VALUES (?, ?, 'user', 'Someone', ?, 'hello', ?)",
+            )
+            .bind(id)
+            .bind(channel_id)
+            .bind(sender_id)
+            .bind(metadata)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query("INSERT INTO channels (id, platform) VALUES ('discord:1', 'discord')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO saved_attachments \
+             (id, channel_id, original_filename, saved_filename, mime_type, size_bytes, disk_path) \
+             VALUES ('a1', 'discord:1', 'cv.pdf', 'cv.pdf', 'application/pdf', 10, '/nonexistent/cv.pdf')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        for (id, source) in [
+            ("fact-1", "conversation:m3,m2"),
+            ("fact-2", "conversation:m3"),
+            ("fact-3", "conversation:m4"),
+        ] {
+            sqlx::query(
+                "INSERT INTO memories (id, content, memory_type, source) VALUES (?, 'x', 'fact', ?)",
+            )
+            .bind(id)
+            .bind(source)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        for (channel_id, sender_id) in [("discord:1", "42"), ("discord:1", "7"), ("slack:T1", "42")]
+        {
+            sqlx::query(
+                "INSERT INTO outbox (channel_id, adapter, response, target, sender_id) \
+                 VALUES (?, 'discord', '{}', '1', ?)",
+            )
+            .bind(channel_id)
+            .bind(sender_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO outbox_failures \
+             (channel_id, adapter, response, attempts, error, enqueued_at, sender_id) \
+             VALUES ('discord:2', 'discord', '{}', 5, 'gone', CURRENT_TIMESTAMP, '42')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        for profile_id in ["discord:42", "slack:42"] {
+            let (platform, sender_id) = profile_id.split_once(':').unwrap();
+            sqlx::query("INSERT INTO user_profiles (id, platform, sender_id) VALUES (?, ?, ?)")
+                .bind(profile_id)
+                .bind(platform)
+                .bind(sender_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        pool
+    }
+
+    async fn remaining(pool: &SqlitePool, sql: &str) -> Vec<String> {
+        sqlx::query_as::<_, (String,)>(sql)
+            .fetch_all(pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(id,)| id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn delete_erases_only_the_sender_on_the_platform() {
+        let pool = seeded_pool().await;
+        let request = ErasureRequest {
+            sender_id: "42".into(),
+            platform: Some("discord".into()),
+            mode: ErasureMode::Delete,
+        };
+        let (report, erased) = erase_records(&pool, &request).await.unwrap();
+
+        assert_eq!(report.messages, 2);
+        assert_eq!(report.attachments, 1);
+        assert_eq!(report.memories, 1);
+        assert_eq!(report.profiles, 1);
+        assert_eq!(report.outbox, 2);
+        assert_eq!(report.channels, vec!["discord:1", "discord:2"]);
+        assert_eq!(erased.memory_ids, vec!["fact-1"]);
+        assert_eq!(
+            erased.attachment_paths,
+            vec![PathBuf::from("/nonexistent/cv.pdf")]
+        );
+
+        let messages = remaining(&pool, "SELECT id FROM conversation_messages ORDER BY id").await;
+        assert_eq!(messages, vec!["m3", "m4"]);
+        let memories = remaining(&pool, "SELECT id FROM memories ORDER BY id").await;
+        assert_eq!(memories, vec!["fact-2", "fact-3"]);
+        let profiles = remaining(&pool, "SELECT id FROM user_profiles").await;
+        assert_eq!(profiles, vec!["slack:42"]);
+        let queued = remaining(&pool, "SELECT sender_id FROM outbox ORDER BY sender_id").await;
+        assert_eq!(queued, vec!["42", "7"]);
+        let failures = remaining(&pool, "SELECT channel_id FROM outbox_failures").await;
+        assert!(failures.is_empty());
+    }
+
+    #[tokio::test]
+    async fn anonymize_keeps_text_but_drops_identity() {
+        let pool = seeded_pool().await;
+        let request = ErasureRequest {
+            sender_id: "42".into(),
+            platform: None,
+            mode: ErasureMode::Anonymize,
+        };
+        let (report, _) = erase_records(&pool, &request).await.unwrap();
+
+        assert_eq!(report.messages, 3);
+        assert_eq!(report.memories, 2);
+        assert_eq!(report.profiles, 2);
+        assert_eq!(report.outbox, 3);
+
+        let rows: Vec<(String, Option<String>, String, Option<String>)> = sqlx::query_as(
+            "SELECT id, sender_id, sender_name, metadata FROM conversation_messages \
+             WHERE id != 'm3' ORDER BY id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(rows.len(), 3);
+        for (_, sender_id, sender_name, metadata) in rows {
+            assert_eq!(sender_id, None);
+            assert_eq!(sender_name, ERASED_SENDER_NAME);
+            assert_eq!(metadata, None);
+        }
+    }
+
+    #[tokio::test]
+    async fn clears_worker_transcripts_in_the_users_channels() {
+        let pool = seeded_pool().await;
+        sqlx::query("INSERT INTO channels (id, platform) VALUES ('discord:3', 'discord')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for (id, channel_id, status, transcript) in [
+            ("w1", "discord:1", "done", Some(b"hello from 42".to_vec())),
+            ("w2", "discord:1", "running", None),
+            ("w3", "discord:3", "done", Some(b"someone else".to_vec())),
+        ] {
+            sqlx::query(
+                "INSERT INTO worker_runs (id, channel_id, task, status, transcript) \
+                 VALUES (?, ?, 'look it up', ?, ?)",
+            )
+            .bind(id)
+            .bind(channel_id)
+            .bind(status)
+            .bind(transcript)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        let request = ErasureRequest {
+            sender_id: "42".into(),
+            platform: Some("discord".into()),
+            mode: ErasureMode::Delete,
+        };
+        let (report, _) = erase_records(&pool, &request).await.unwrap();
+
+        assert_eq!(report.worker_transcripts, 1);
+        let kept = remaining(
+            &pool,
+            "SELECT id FROM worker_runs WHERE transcript IS NOT NULL ORDER BY id",
+        )
+        .await;
+        assert_eq!(kept, vec!["w3"]);
+        assert!(
+            report
+                .retained
+                .iter()
+                .any(|entry| entry.contains("1 workers still running in discord:1"))
+        );
+        assert!(
+            report
+                .retained
+                .iter()
+                .any(|entry| entry.contains("2 worker and branch runs in discord:1"))
+        );
+    }
+
+    #[tokio::test]
+    async fn lists_memories_that_cant_be_traced_to_the_sender() {
+        let pool = seeded_pool().await;
+        for (id, channel_id, source) in [
+            ("saved-1", Some("discord:1"), None),
+            ("saved-2", Some("discord:1"), Some("branch")),
+            ("saved-3", Some("slack:T1"), None),
+            ("saved-4", None, None),
+        ] {
+            sqlx::query(
+                "INSERT INTO memories (id, content, memory_type, source, channel_id) \
+                 VALUES (?, '42 lives in Lisbon', 'fact', ?, ?)",
+            )
+            .bind(id)
+            .bind(source)
+            .bind(channel_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        let request = ErasureRequest {
+            sender_id: "42".into(),
+            platform: Some("discord".into()),
+            mode: ErasureMode::Delete,
+        };
+        let (report, _) = erase_records(&pool, &request).await.unwrap();
+
+        assert_eq!(report.memories, 1);
+        assert!(report.retained.iter().any(|entry| {
+            entry.starts_with("2 memories the agent saved in discord:1")
+                && entry.ends_with("saved-1, saved-2")
+        }));
+        assert!(
+            !report
+                .retained
+                .iter()
+                .any(|entry| entry.contains("slack:T1"))
+        );
+        assert!(
+            report
+                .retained
+                .iter()
+                .any(|entry| entry.starts_with("1 memories saved without a channel"))
+        );
+    }
+
+    #[tokio::test]
+    async fn redacts_moderation_events_in_the_users_channels() {
+        let pool = seeded_pool().await;
+        for (id, channel_id, delivered) in [
+            ("e1", "discord:1", Some("hi ***")),
+            ("e2", "discord:2", None),
+            ("e3", "slack:T1", Some("hi ***")),
+        ] {
+            sqlx::query(
+                "INSERT INTO moderation_events \
+                 (id, channel_id, action, source, strictness, original_content, delivered_content) \
+                 VALUES (?, ?, 'rewrite', 'rules', 'standard', 'hi 42, your address is ...', ?)",
+            )
+            .bind(id)
+            .bind(channel_id)
+            .bind(delivered)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        let request = ErasureRequest {
+            sender_id: "42".into(),
+            platform: Some("discord".into()),
+            mode: ErasureMode::Anonymize,
+        };
+        let (report, _) = erase_records(&pool, &request).await.unwrap();
+
+        assert_eq!(report.moderation_events, 2);
+        let rows: Vec<(String, String, Option<String>)> = sqlx::query_as(
+            "SELECT id, original_content, delivered_content FROM moderation_events ORDER BY id",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    "e1".into(),
+                    ERASED_CONTENT.into(),
+                    Some(ERASED_CONTENT.into())
+                ),
+                ("e2".into(), ERASED_CONTENT.into(), None),
+                (
+                    "e3".into(),
+                    "hi 42, your address is ...".into(),
+                    Some("hi ***".into())
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn clears_prompt_buffers_for_the_users_channels() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshots = PromptSnapshotStore::new(&dir.path().join("snapshots.redb")).unwrap();
+        let traces = TraceStore::new();
+        let branch_cache = BranchResultCache::new();
+        let debug_capture = DebugCapture::new();
+        debug_capture.configure(true, Some(10));
+        debug_capture.record(crate::llm::debug_capture::ExchangeRecord {
+            process_type: "channel",
+            process_id: "discord:1",
+            model: "anthropic/claude-sonnet-4",
+            endpoint: "https://api.anthropic.com/v1/messages",
+            status: 200,
+            duration: std::time::Duration::from_millis(10),
+            request: serde_json::json!({"messages": [{"content": "hello from 42"}]}),
+            response_text: "{}",
+            api_key: None,
+        });
+        for channel_id in ["discord:1", "discord:3"] {
+            let key =
+                crate::agent::branch_cache::BranchCacheKey::new(channel_id, "recap", &[]).unwrap();
+            branch_cache.insert(key, "42 asked about shipping", &Default::default());
+            snapshots
+                .save(&crate::agent::prompt_snapshot::PromptSnapshot {
+                    channel_id: channel_id.into(),
+                    timestamp_ms: 1,
+                    user_message: "hello from 42".into(),
+                    system_prompt: String::new(),
+                    system_prompt_chars: 0,
+                    history: serde_json::json!([]),
+                    history_length: 0,
+                    model: None,
+                    tool_definitions: Vec::new(),
+                })
+                .unwrap();
+            traces.record(crate::llm::trace::CompletionRecord {
+                conversation_id: channel_id,
+                agent_id: Some("main"),
+                process_type: Some("channel"),
+                worker_type: None,
+                model: "anthropic/claude-sonnet-4",
+                duration: std::time::Duration::from_millis(10),
+                input_tokens: 1,
+                output_tokens: 1,
+                cached_input_tokens: 0,
+                tool_calls: Vec::new(),
+                system_prompt: "",
+                prompt: "hello from 42",
+                response: "hi",
+                error: None,
+            });
+        }
+        let mut report = ErasureReport {
+            channels: vec!["discord:1".into()],
+            ..Default::default()
+        };
+        let buffers = PromptBuffers {
+            prompt_snapshots: Some(&snapshots),
+            traces: Some(&traces),
+            branch_cache: Some(&branch_cache),
+            response_cache: Some(&ResponseCache::new()),
+            debug_capture: Some(&debug_capture),
+        };
+        erase_prompt_buffers(buffers, &mut report);
+
+        assert_eq!(report.prompt_snapshots, 1);
+        assert_eq!(report.traces, 1);
+        assert_eq!(report.branch_cache, 1);
+        assert_eq!(report.debug_captures, 1);
+        assert!(debug_capture.entries(None).is_empty());
+        assert!(snapshots.list("discord:1", 10).unwrap().is_empty());
+        assert_eq!(snapshots.list("discord:3", 10).unwrap().len(), 1);
+        assert!(traces.for_conversation("discord:1").is_empty());
+        assert_eq!(traces.for_conversation("discord:3").len(), 1);
+        assert!(report.retained.is_empty());
+    }
+}
@@ -6,6 +6,13 @@ use serde::Serialize;
 use sqlx::{Row as _, SqlitePool};
 use std::collections::HashMap;
 
+/// Metadata key holding the platform message ID a bot reply was delivered as.
+pub const PLATFORM_MESSAGE_ID_KEY: &str = "platform_message_id";
+
+/// Metadata key marking a bot message written by an operator during a
+/// takeover rather than by the agent.
+pub const SENT_BY_OPERATOR_KEY: &str = "sent_by_operator";
+
 /// Persists conversation messages (user and assistant) to SQLite.
 ///
 /// All write methods are fire-and-forget — they spawn a tokio task and return
@@ -33,6 +40,11 @@ impl ConversationLogger {
         Self { pool }
     }
 
+    /// Archived summaries and cold storage for the same database.
+    pub fn archive(&self) -> crate::conversation::ChannelArchive {
+        crate::conversation::ChannelArchive::new(self.pool.clone())
+    }
+
     /// Log a user message. Fire-and-forget.
     pub fn log_user_message(
         &self,
@@ -107,6 +119,42 @@ impl ConversationLogger {
         channel_id: &ChannelId,
         content: &str,
         sender_name: Option<&str>,
+    ) {
+        self.insert_bot_message(channel_id, content, sender_name, None);
+    }
+
+    /// Log a delivered bot reply together with the platform message ID it was
+    /// sent as, so `edit_reply`/`delete_reply` can find it later.
+    /// Fire-and-forget.
+    pub fn log_bot_reply(
+        &self,
+        channel_id: &ChannelId,
+        content: &str,
+        sender_name: Option<&str>,
+        platform_message_id: &str,
+    ) {
+        let metadata = serde_json::json!({ (PLATFORM_MESSAGE_ID_KEY): platform_message_id });
+        self.insert_bot_message(channel_id, content, sender_name, Some(metadata.to_string()));
+    }
+
+    /// Log a message an operator sent under the bot's identity during a
+    /// takeover. Fire-and-forget.
+    pub fn log_operator_message(
+        &self,
+        channel_id: &ChannelId,
+        content: &str,
+        sender_name: Option<&str>,
+    ) {
+        let metadata = serde_json::json!({ (SENT_BY_OPERATOR_KEY): true });
+        self.insert_bot_message(channel_id, content, sender_name, Some(metadata.to_string()));
+    }
+
+    fn insert_bot_message(
+        &self,
+        channel_id: &ChannelId,
+        content: &str,
+        sender_name: Option<&str>,
+        metadata_json: Option<String>,
     ) {
         let pool = self.pool.clone();
         let id = uuid::Uuid::new_v4().to_string();
@@ -116,13 +164,14 @@ impl ConversationLogger {
 
         tokio::spawn(async move {
             if let Err(error) = sqlx::query(
-                "INSERT INTO conversation_messages (id, channel_id, role, sender_name, content) \
-                 VALUES (?, ?, 'assistant', ?, ?)",
+                "INSERT INTO conversation_messages (id, channel_id, role, sender_name, content, metadata) \
+                 VALUES (?, ?, 'assistant', ?, ?, ?)",
             )
             .bind(&id)
             .bind(&channel_id)
             .bind(&sender_name)
             .bind(&content)
+            .bind(&metadata_json)
             .execute(&pool)
             .await
             {
@@ -131,6 +180,87 @@ impl ConversationLogger {
         });
     }
 
+    /// Find a bot reply in this channel by the platform message ID it was
+    /// delivered as. Deleted replies are not returned.
+    pub async fn find_bot_reply(
+        &self,
+        channel_id: &ChannelId,
+        platform_message_id: &str,
+    ) -> crate::error::Result<Option<ConversationMessage>> {
+        let row = sqlx::query(
+            "SELECT id, channel_id, role, sender_name, sender_id, content, metadata, created_at \
+             FROM conversation_messages \
+             WHERE channel_id = ? AND role = 'assistant' \
+               AND json_extract(metadata, '$.platform_message_id') = ? \
+               AND json_extract(metadata, '$.deleted_at') IS NULL \
+             ORDER BY created_at DESC \
+             LIMIT 1",
+        )
+        .bind(channel_id.as_ref())
+        .bind(platform_message_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(row.map(|row| ConversationMessage {
+            id: row.try_get("id").unwrap_or_default(),
+            channel_id: row.try_get("channel_id").unwrap_or_default(),
+            role: row.try_get("role").unwrap_or_default(),
+            sender_name: row.try_get("sender_name").ok(),
+            sender_id: row.try_get("sender_id").ok(),
+            content: row.try_get("content").unwrap_or_default(),
+            metadata: row.try_get("metadata").ok(),
+            created_at: row
+                .try_get("created_at")
+                .unwrap_or_else(|_| chrono::Utc::now()),
+        }))
+    }
+
+    /// Replace the stored content of an edited bot reply. Fire-and-forget.
+    pub fn log_bot_reply_edited(&self, message_id: &str, content: &str) {
+        let pool = self.pool.clone();
+        let message_id = message_id.to_string();
+        let content = content.to_string();
+
+        tokio::spawn(async move {
+            if let Err(error) = sqlx::query(
+                "UPDATE conversation_messages \
+                 SET content = ?, metadata = json_set(COALESCE(metadata, '{}'), '$.edited_at', ?) \
+                 WHERE id = ?",
+            )
+            .bind(&content)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(&message_id)
+            .execute(&pool)
+            .await
+            {
+                tracing::warn!(%error, %message_id, "failed to persist bot reply edit");
+            }
+        });
+    }
+
+    /// Mark a bot reply as deleted on the platform. The row is kept for the
+    /// audit trail. Fire-and-forget.
+    pub fn log_bot_reply_deleted(&self, message_id: &str) {
+        let pool = self.pool.clone();
+        let message_id = message_id.to_string();
+
+        tokio::spawn(async move {
+            if let Err(error) = sqlx::query(
+                "UPDATE conversation_messages \
+                 SET metadata = json_set(COALESCE(metadata, '{}'), '$.deleted_at', ?) \
+                 WHERE id = ?",
+            )
+            .bind(chrono::Utc::now().to_rfc3339())
+            .bind(&message_id)
+            .execute(&pool)
+            .await
+            {
+                tracing::warn!(%error, %message_id, "failed to persist bot reply deletion");
+            }
+        });
+    }
+
     /// Load recent messages for a channel (oldest first).
     pub async fn load_recent(
         &self,
@@ -241,6 +371,215 @@ impl ConversationLogger {
         }
         Ok(messages)
     }
+
+    /// Highest message rowid in each channel. Rowids only grow, so these
+    /// act as high-water marks for incremental indexing.
+    pub async fn channel_message_heads(&self) -> crate::error::Result<Vec<(String, i64)>> {
+        let rows = sqlx::query(
+            "SELECT channel_id, MAX(rowid) AS head \
+             FROM conversation_messages \
+             GROUP BY channel_id",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.try_get("channel_id").unwrap_or_default(),
+                    row.try_get("head").unwrap_or_default(),
+                )
+            })
+            .collect())
+    }
+
+    /// Load a channel's messages with rowid greater than `after_rowid`, in
+    /// insertion order, paired with their rowids.
+    pub async fn load_messages_after(
+        &self,
+        channel_id: &str,
+        after_rowid: i64,
+        limit: i64,
+    ) -> crate::error::Result<Vec<(i64, ConversationMessage)>> {
+        let rows = sqlx::query(
+            "SELECT rowid, id, channel_id, role, sender_name, sender_id, content, metadata, created_at \
+             FROM conversation_messages \
+             WHERE channel_id = ? AND rowid > ? \
+             ORDER BY rowid ASC \
+             LIMIT ?",
+        )
+        .bind(channel_id)
+        .bind(after_rowid)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let rowid: i64 = row.try_get("rowid").unwrap_or_default();
+                let message = ConversationMessage {
+                    id: row.try_get("id").unwrap_or_default(),
+                    channel_id: row.try_get("channel_id").unwrap_or_default(),
+                    role: row.try_get("role").unwrap_or_default(),
+                    sender_name: row.try_get("sender_name").ok(),
+                    sender_id: row.try_get("sender_id").ok(),
+                    content: row.try_get("content").unwrap_or_default(),
+                    metadata: row.try_get("metadata").ok(),
+                    created_at: row
+                        .try_get("created_at")
+                        .unwrap_or_else(|_| chrono::Utc::now()),
+                };
+                (rowid, message)
+            })
+            .collect())
+    }
+
+    /// Keyword search across all persisted messages using the FTS5 index.
+    ///
+    /// Results are ordered by relevance (best first). Each hit carries a short
+    /// snippet with matched terms wrapped in `highlight_open` / `highlight_close`.
+    /// Returns an empty list when the query contains no searchable terms.
+    pub async fn search_messages(
+        &self,
+        query: &MessageSearchQuery<'_>,
+        highlight_open: &str,
+        highlight_close: &str,
+    ) -> crate::error::Result<Vec<MessageSearchHit>> {
+        let Some(match_expression) = fts5_match_expression(query.text) else {
+            return Ok(Vec::new());
+        };
+
+        let mut sql = String::from(
+            "SELECT m.id, m.channel_id, m.role, m.sender_name, m.content, m.created_at, \
+                    c.display_name AS channel_name, \
+                    snippet(conversation_messages_fts, 0, ?, ?, '…', 16) AS snippet, \
+                    bm25(conversation_messages_fts) AS rank \
+             FROM conversation_messages_fts \
+             JOIN conversation_messages m ON m.rowid = conversation_messages_fts.rowid \
+             LEFT JOIN channels c ON c.id = m.channel_id \
+             WHERE conversation_messages_fts MATCH ?",
+        );
+
+        if query.channel_id.is_some() {
+            sql.push_str(" AND m.channel_id = ?");
+        }
+        if query.before.is_some() {
+            sql.push_str(" AND datetime(m.created_at) < datetime(?)");
+        }
+        if query.after.is_some() {
+            sql.push_str(" AND datetime(m.created_at) > datetime(?)");
+        }
+        sql.push_str(" ORDER BY rank LIMIT ?");
+
+        let mut sql_query = sqlx::query(&sql)
+            .bind(highlight_open)
+            .bind(highlight_close)
+            .bind(&match_expression);
+        if let Some(channel_id) = query.channel_id {
+            sql_query = sql_query.bind(channel_id);
+        }
+        if let Some(before) = query.before {
+            sql_query = sql_query.bind(sqlite_datetime(before));
+        }
+        if let Some(after) = query.after {
+            sql_query = sql_query.bind(sqlite_datetime(after));
+        }
+        sql_query = sql_query.bind(query.limit);
+
+        let rows = sql_query
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| MessageSearchHit {
+                id: row.try_get("id").unwrap_or_default(),
+                channel_id: row.try_get("channel_id").unwrap_or_default(),
+                channel_name: row.try_get("channel_name").ok().flatten(),
+                role: row.try_get("role").unwrap_or_default(),
+                sender_name: row.try_get("sender_name").ok().flatten(),
+                content: row.try_get("content").unwrap_or_default(),
+                snippet: row.try_get("snippet").unwrap_or_default(),
+                rank: row.try_get("rank").unwrap_or_default(),
+                created_at: row
+                    .try_get("created_at")
+                    .unwrap_or_else(|_| chrono::Utc::now()),
+            })
+            .collect())
+    }
+}
+
+/// Filters for [`ConversationLogger::search_messages`].
+#[derive(Debug, Clone, Copy)]
+pub struct MessageSearchQuery<'a> {
+    /// Free-form keywords. All terms must match (implicit AND).
+    pub text: &'a str,
+    /// Restrict to a single channel.
+    pub channel_id: Option<&'a str>,
+    /// Only messages sent before this time.
+    pub before: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only messages sent after this time.
+    pub after: Option<chrono::DateTime<chrono::Utc>>,
+    pub limit: i64,
+}
+
+/// Format a time the way SQLite's `datetime()` reads it.
+fn sqlite_datetime(time: chrono::DateTime<chrono::Utc>) -> String {
+    time.format("%Y-%m-%d %H:%M:%S%.f").to_string()
+}
+
+/// A single full-text search hit.
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageSearchHit {
+    pub id: String,
+    pub channel_id: String,
+    pub channel_name: Option<String>,
+    pub role: String,
+    pub sender_name: Option<String>,
+    pub content: String,
+    /// Excerpt around the match with highlighted terms.
+    pub snippet: String,
+    /// BM25 score. Lower is more relevant.
+    pub rank: f64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Turn free-form user input into a safe FTS5 MATCH expression.
+///
+/// Raw input can't be passed to MATCH directly — characters like `-`, `:`,
+/// `*`, or unbalanced quotes are FTS5 syntax and produce query errors. Each
+/// whitespace-separated term is quoted as a literal phrase so the terms are
+/// ANDed together. A trailing `*` on a term is kept as a prefix match.
+pub fn fts5_match_expression(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .filter_map(|term| {
+            let prefix = term.ends_with('*');
+            let cleaned: String = term
+                .chars()
+                .filter(|character| *character != '"' && *character != '*')
+                .collect();
+            if cleaned.is_empty() {
+                return None;
+            }
+            Some(if prefix {
+                format!("\"{cleaned}\"*")
+            } else {
+                format!("\"{cleaned}\"")
+            })
+        })
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
 }
 
 /// A unified timeline item combining messages, branch runs, and worker runs.
@@ -261,6 +600,8 @@ pub enum TimelineItem {
         conclusion: Option<String>,
         started_at: String,
         completed_at: Option<String>,
+        /// Answered from the branch result cache instead of running.
+        cache_hit: bool,
     },
     WorkerRun {
         id: String,
@@ -332,6 +673,41 @@ impl ProcessRunLogger {
         });
     }
 
+    /// Record a branch answered from the result cache. Fire-and-forget.
+    ///
+    /// Writes the whole run at once. The channel still logs the start and
+    /// completion events it sees for the branch; those only touch the same row.
+    pub fn log_branch_cache_hit(
+        &self,
+        channel_id: &ChannelId,
+        branch_id: BranchId,
+        description: &str,
+        conclusion: &str,
+    ) {
+        let pool = self.pool.clone();
+        let id = branch_id.to_string();
+        let channel_id = channel_id.to_string();
+        let description = description.to_string();
+        let conclusion = conclusion.to_string();
+
+        tokio::spawn(async move {
+            if let Err(error) = sqlx::query(
+                "INSERT INTO branch_runs (id, channel_id, description, conclusion, completed_at, cache_hit) \
+                 VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP, 1) \
+                 ON CONFLICT(id) DO UPDATE SET cache_hit = 1",
+            )
+            .bind(&id)
+            .bind(&channel_id)
+            .bind(&description)
+            .bind(&conclusion)
+            .execute(&pool)
+            .await
+            {
+                tracing::warn!(%error, branch_id = %id, "failed to persist cached branch run");
+            }
+        });
+    }
+
     /// Record a worker starting. Fire-and-forget.
     #[allow(clippy::too_many_arguments)]
     pub fn log_worker_started(
@@ -730,17 +1106,17 @@ impl ProcessRunLogger {
             "SELECT * FROM ( \
                 SELECT 'message' AS item_type, id, role, sender_name, sender_id, content, \
                        NULL AS description, NULL AS conclusion, NULL AS task, NULL AS result, NULL AS status, \
-                       created_at AS timestamp, NULL AS completed_at \
+                       created_at AS timestamp, NULL AS completed_at, NULL AS cache_hit \
                 FROM conversation_messages WHERE channel_id = ?1 \
                 UNION ALL \
                 SELECT 'branch_run' AS item_type, id, NULL, NULL, NULL, NULL, \
                        description, conclusion, NULL, NULL, NULL, \
-                       started_at AS timestamp, completed_at \
+                       started_at AS timestamp, completed_at, cache_hit \
                 FROM branch_runs WHERE channel_id = ?1 \
                 UNION ALL \
                 SELECT 'worker_run' AS item_type, id, NULL, NULL, NULL, NULL, \
                        NULL, NULL, task, result, status, \
-                       started_at AS timestamp, completed_at \
+                       started_at AS timestamp, completed_at, NULL \
                 FROM worker_runs WHERE channel_id = ?1 \
             ) WHERE 1=1 {before_clause} ORDER BY timestamp DESC LIMIT ?2"
         );
@@ -784,6 +1160,7 @@ impl ProcessRunLogger {
                             .try_get::<chrono::DateTime<chrono::Utc>, _>("completed_at")
                             .ok()
                             .map(|t| t.to_rfc3339()),
+                        cache_hit: row.try_get::<bool, _>("cache_hit").unwrap_or(false),
                     }),
                     "worker_run" => Some(TimelineItem::WorkerRun {
                         id: row.try_get("id").unwrap_or_default(),
@@ -999,7 +1376,7 @@ pub struct WorkerDetailRow {
 
 #[cfg(test)]
 mod tests {
-    use super::ProcessRunLogger;
+    use super::{ConversationLogger, MessageSearchQuery, ProcessRunLogger, fts5_match_expression};
 
     async fn setup_worker_runs_table() -> sqlx::SqlitePool {
         let pool = sqlx::sqlite::SqlitePoolOptions::new()
@@ -1082,4 +1459,215 @@ mod tests {
         let status: String = sqlx::Row::try_get(&row, "status").expect("missing status");
         assert_eq!(status, "running");
     }
+
+    #[test]
+    fn fts5_match_expression_quotes_terms() {
+        assert_eq!(
+            fts5_match_expression("deploy rollback").as_deref(),
+            Some("\"deploy\" \"rollback\"")
+        );
+        assert_eq!(
+            fts5_match_expression("foo-bar \"baz").as_deref(),
+            Some("\"foo-bar\" \"baz\"")
+        );
+    }
+
+    #[test]
+    fn fts5_match_expression_keeps_prefix_and_skips_empty() {
+        assert_eq!(fts5_match_expression("depl*").as_deref(), Some("\"depl\"*"));
+        assert_eq!(fts5_match_expression("  \"\" * "), None);
+    }
+
+    #[tokio::test]
+    async fn search_messages_filters_by_channel_and_highlights() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to create sqlite memory pool");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("failed to run migrations");
+
+        for (id, channel_id, content) in [
+            ("m1", "discord:1", "the deploy failed on staging"),
+            ("m2", "discord:2", "deploy went fine in production"),
+            ("m3", "discord:1", "unrelated chatter"),
+        ] {
+            sqlx::query(
+                "INSERT INTO conversation_messages (id, channel_id, role, content) VALUES (?, ?, 'user', ?)",
+            )
+            .bind(id)
+            .bind(channel_id)
+            .bind(content)
+            .execute(&pool)
+            .await
+            .expect("failed to insert message");
+        }
+
+        let logger = ConversationLogger::new(pool);
+        let all = logger
+            .search_messages(
+                &MessageSearchQuery {
+                    text: "deploy",
+                    channel_id: None,
+                    before: None,
+                    after: None,
+                    limit: 10,
+                },
+                "[",
+                "]",
+            )
+            .await
+            .expect("search should succeed");
+        assert_eq!(all.len(), 2);
+
+        let scoped = logger
+            .search_messages(
+                &MessageSearchQuery {
+                    text: "deploy",
+                    channel_id: Some("discord:1"),
+                    before: None,
+                    after: None,
+                    limit: 10,
+                },
+                "[",
+                "]",
+            )
+            .await
+            .expect("search should succeed");
+        assert_eq!(scoped.len(), 1);
+        assert_eq!(scoped[0].id, "m1");
+        assert!(scoped[0].snippet.contains("[deploy]"));
+    }
+
+    #[tokio::test]
+    async fn search_messages_compares_same_day_bounds_by_time() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to create sqlite memory pool");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("failed to run migrations");
+
+        for (id, created_at) in [
+            ("morning", "2026-03-10 08:00:00"),
+            ("evening", "2026-03-10 20:00:00"),
+        ] {
+            sqlx::query(
+                "INSERT INTO conversation_messages (id, channel_id, role, content, created_at) \
+                 VALUES (?, 'discord:1', 'user', 'deploy status', ?)",
+            )
+            .bind(id)
+            .bind(created_at)
+            .execute(&pool)
+            .await
+            .expect("failed to insert message");
+        }
+
+        let logger = ConversationLogger::new(pool);
+        let search = |before, after| {
+            let logger = logger.clone();
+            async move {
+                logger
+                    .search_messages(
+                        &MessageSearchQuery {
+                            text: "deploy",
+                            channel_id: None,
+                            before,
+                            after,
+                            limit: 10,
+                        },
+                        "[",
+                        "]",
+                    )
+                    .await
+                    .expect("search should succeed")
+                    .into_iter()
+                    .map(|hit| hit.id)
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        let noon = "2026-03-10T12:00:00Z"
+            .parse::<chrono::DateTime<chrono::Utc>>()
+            .unwrap();
+        assert_eq!(search(Some(noon), None).await, vec!["morning"]);
+        assert_eq!(search(None, Some(noon)).await, vec!["evening"]);
+    }
+
+    #[tokio::test]
+    async fn find_bot_reply_matches_platform_id_within_channel() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to create sqlite memory pool");
+        sqlx::query(
+            "CREATE TABLE conversation_messages (
+                id TEXT PRIMARY KEY,
+                channel_id TEXT NOT NULL,
+                role TEXT NOT NULL,
+                sender_name TEXT,
+                sender_id TEXT,
+                content TEXT NOT NULL,
+                metadata TEXT,
+                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+            )",
+        )
+        .execute(&pool)
+        .await
+        .expect("failed to create conversation_messages table");
+
+        for (id, channel_id, platform_id, deleted) in [
+            ("a", "discord:1", "111", false),
+            ("b", "discord:2", "222", false),
+            ("c", "discord:1", "333", true),
+        ] {
+            let mut metadata = serde_json::json!({ "platform_message_id": platform_id });
+            if deleted {
+                metadata["deleted_at"] = serde_json::json!("2026-01-01T00:00:00Z");
+            }
+            sqlx::query(
+                "INSERT INTO conversation_messages (id, channel_id, role, content, metadata) \
+                 VALUES (?, ?, 'assistant', 'hello', ?)",
+            )
+            .bind(id)
+            .bind(channel_id)
+            .bind(metadata.to_string())
+            .execute(&pool)
+            .await
+            .expect("failed to insert reply row");
+        }
+
+        let logger = ConversationLogger::new(pool);
+        let channel_id: crate::ChannelId = std::sync::Arc::from("discord:1");
+
+        let found = logger
+            .find_bot_reply(&channel_id, "111")
+            .await
+            .expect("lookup should succeed")
+            .expect("reply should be found");
+        assert_eq!(found.id, "a");
+
+        // Replies from other channels and deleted replies are not editable.
+        assert!(
+            logger
+                .find_bot_reply(&channel_id, "222")
+                .await
+                .expect("lookup should succeed")
+                .is_none()
+        );
+        assert!(
+            logger
+                .find_bot_reply(&channel_id, "333")
+                .await
+                .expect("lookup should succeed")
+                .is_none()
+        );
+    }
 }
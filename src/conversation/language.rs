@@ -0,0 +1,97 @@
+//! Language detection for channel messages.
+//!
+//! Languages are identified by ISO 639-3 code (`"eng"`, `"spa"`, `"deu"`),
+//! the codes `whatlang` reports. A channel remembers the language its
+//! messages are written in, and a language set through the API overrides it.
+
+use whatlang::Lang;
+
+/// Fewer letters than this are too little to tell languages apart.
+const MIN_DETECTION_LETTERS: usize = 20;
+
+/// Detect the language `text` is written in.
+///
+/// Code blocks, links, mentions, and channel references are ignored. Returns
+/// `None` when there is too little text or the detection isn't reliable.
+pub fn detect(text: &str) -> Option<&'static str> {
+    let prose = detection_text(text);
+    if prose.chars().filter(|c| c.is_alphabetic()).count() < MIN_DETECTION_LETTERS {
+        return None;
+    }
+    let info = whatlang::detect(&prose)?;
+    info.is_reliable().then(|| info.lang().code())
+}
+
+/// Parse a language given as an ISO 639-3 code or an English name
+/// (`"spa"`, `"Spanish"`). Returns the code.
+pub fn parse(input: &str) -> Option<&'static str> {
+    let input = input.trim().to_lowercase();
+    if let Some(lang) = Lang::from_code(&input) {
+        return Some(lang.code());
+    }
+    Lang::all()
+        .iter()
+        .find(|lang| lang.eng_name().to_lowercase() == input)
+        .map(|lang| lang.code())
+}
+
+/// English name of a language code, e.g. `"Spanish"` for `"spa"`.
+pub fn display_name(code: &str) -> Option<&'static str> {
+    Lang::from_code(code).map(|lang| lang.eng_name())
+}
+
+/// The prose in a message, without code, links, or platform references.
+fn detection_text(text: &str) -> String {
+    let mut prose = Vec::new();
+    let mut in_code_block = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+        prose.extend(line.split_whitespace().filter(|word| {
+            !(word.starts_with("http://")
+                || word.starts_with("https://")
+                || word.starts_with('@')
+                || word.starts_with('<')
+                || word.starts_with('#')
+                || word.starts_with('`'))
+        }));
+    }
+    prose.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_languages_of_longer_messages_only() {
+        assert_eq!(
+            detect("Hola, ¿me puedes ayudar a preparar la reunión de mañana por la tarde?"),
+            Some("spa")
+        );
+        assert_eq!(
+            detect(
+                "Could you please help me prepare the agenda for the meeting tomorrow afternoon?"
+            ),
+            Some("eng")
+        );
+        assert_eq!(detect("ok danke"), None);
+        assert_eq!(
+            detect("<@U012AB3CD> https://example.com/some/long/path ```let x = 1;```"),
+            None
+        );
+    }
+
+    #[test]
+    fn parses_codes_and_english_names() {
+        assert_eq!(parse("spa"), Some("spa"));
+        assert_eq!(parse(" German "), Some("deu"));
+        assert_eq!(parse("klingon"), None);
+        assert_eq!(display_name("deu"), Some("German"));
+    }
+}
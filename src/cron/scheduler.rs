@@ -10,6 +10,7 @@ use crate::cron::store::CronStore;
 use crate::error::Result;
 use crate::messaging::MessagingManager;
 use crate::messaging::target::{BroadcastTarget, parse_delivery_target};
+use crate::supervisor::{ProcessHandle, ProcessKind, ProcessSpec, Restart, Supervisor};
 use crate::{AgentDeps, InboundMessage, MessageContent, OutboundResponse, RoutedResponse};
 use chrono::Timelike;
 use chrono_tz::Tz;
@@ -36,6 +37,9 @@ pub struct CronJob {
     /// Maximum wall-clock seconds to wait for the job to complete.
     /// `None` uses the default of 120 seconds.
     pub timeout_secs: Option<u64>,
+    /// IANA timezone the schedule and active hours are read in. `None` uses
+    /// the agent's `cron_timezone`.
+    pub timezone: Option<String>,
 }
 
 /// Serializable cron job config (for storage and TOML parsing).
@@ -57,6 +61,10 @@ pub struct CronConfig {
     /// Maximum wall-clock seconds to wait for the job to complete.
     /// `None` uses the default of 120 seconds.
     pub timeout_secs: Option<u64>,
+    /// IANA timezone the schedule and active hours are read in. `None` uses
+    /// the agent's `cron_timezone`.
+    #[serde(default)]
+    pub timezone: Option<String>,
 }
 
 fn default_interval() -> u64 {
@@ -97,7 +105,7 @@ const SYSTEM_TIMEZONE_LABEL: &str = "system";
 /// Scheduler that manages cron job timers and execution.
 pub struct Scheduler {
     jobs: Arc<RwLock<HashMap<String, CronJob>>>,
-    timers: Arc<RwLock<HashMap<String, tokio::task::JoinHandle<()>>>>,
+    timers: Arc<RwLock<HashMap<String, ProcessHandle>>>,
     context: CronContext,
 }
 
@@ -165,6 +173,7 @@ impl Scheduler {
             run_once: config.run_once,
             consecutive_failures: 0,
             timeout_secs: config.timeout_secs,
+            timezone: config.timezone,
         };
 
         {
@@ -206,253 +215,67 @@ impl Scheduler {
 
     /// Start a timer loop for a cron job.
     ///
-    /// Idempotent: if a timer is already running for this job, it is aborted before
+    /// Idempotent: if a timer is already running for this job, it is stopped before
     /// starting a new one. This prevents timer leaks when a job is re-registered via API.
+    /// The timer runs under the supervisor, which restarts it if it panics.
     ///
     /// When `anchor` is provided, interval-based jobs use it to compute the first
     /// sleep duration from the last known execution, preventing skipped or duplicate
     /// firings after a restart.
     async fn start_timer(&self, job_id: &str, anchor: Option<chrono::DateTime<chrono::Utc>>) {
         let job_id_for_map = job_id.to_string();
-        let job_id = job_id.to_string();
-        let jobs = self.jobs.clone();
-        let context = self.context.clone();
-
-        // Abort any existing timer for this job before starting a new one.
-        // Dropping a JoinHandle only detaches it — we must abort explicitly.
-        {
-            let mut timers = self.timers.write().await;
-            if let Some(old_handle) = timers.remove(&job_id) {
-                old_handle.abort();
-                tracing::debug!(cron_id = %job_id, "aborted existing timer before re-registering");
-            }
+        let timer_job_id = job_id.to_string();
+        let timer_jobs = self.jobs.clone();
+        let timer_context = self.context.clone();
+
+        // Stop any existing timer for this job before starting a new one.
+        // Dropping a handle only detaches the task — we must stop it explicitly.
+        let old_handle = self.timers.write().await.remove(job_id);
+        if let Some(old_handle) = old_handle {
+            old_handle.stop().await;
+            tracing::debug!(cron_id = %job_id, "stopped existing timer before re-registering");
         }
 
-        let handle = tokio::spawn(async move {
-            let execution_lock = Arc::new(std::sync::atomic::AtomicBool::new(false));
-            let mut interval_first_tick = true;
-
-            loop {
-                let job = {
-                    let j = jobs.read().await;
-                    match j.get(&job_id) {
-                        Some(j) if !j.enabled => {
-                            tracing::debug!(cron_id = %job_id, "cron job disabled, stopping timer");
-                            break;
-                        }
-                        Some(j) => j.clone(),
-                        None => {
-                            tracing::debug!(cron_id = %job_id, "cron job removed, stopping timer");
-                            break;
-                        }
-                    }
-                };
-
-                let sleep_duration = if let Some(cron_expr) = job.cron_expr.as_deref() {
-                    match next_fire_duration(&context, &job_id, cron_expr) {
-                        Some((duration, next_fire_utc, timezone)) => {
-                            tracing::debug!(
-                                cron_id = %job_id,
-                                cron_expr,
-                                cron_timezone = %timezone,
-                                next_fire_utc = %next_fire_utc.to_rfc3339(),
-                                sleep_secs = duration.as_secs(),
-                                "wall-clock cron next fire computed"
-                            );
-                            duration
-                        }
-                        None => {
-                            tracing::warn!(
-                                cron_id = %job_id,
-                                cron_expr,
-                                "failed to compute next wall-clock fire; retrying in 60s"
-                            );
-                            Duration::from_secs(60)
-                        }
-                    }
-                } else {
-                    let interval_secs = job.interval_secs;
-                    let delay = if interval_first_tick {
-                        interval_first_tick = false;
-                        anchored_initial_delay(interval_secs, anchor)
-                    } else {
-                        Duration::from_secs(interval_secs)
-                    };
-                    tracing::debug!(
-                        cron_id = %job_id,
-                        interval_secs,
-                        sleep_secs = delay.as_secs(),
-                        anchored = anchor.is_some(),
-                        "interval cron next fire computed"
-                    );
-                    delay
-                };
-
-                tokio::time::sleep(sleep_duration).await;
-
-                let job = {
-                    let j = jobs.read().await;
-                    match j.get(&job_id) {
-                        Some(j) if !j.enabled => {
-                            tracing::debug!(cron_id = %job_id, "cron job disabled, stopping timer");
-                            break;
-                        }
-                        Some(j) => j.clone(),
-                        None => {
-                            tracing::debug!(cron_id = %job_id, "cron job removed, stopping timer");
-                            break;
-                        }
-                    }
-                };
-
-                // Check active hours window
-                if let Some((start, end)) = job.active_hours {
-                    let (current_hour, timezone) = current_hour_and_timezone(&context, &job_id);
-                    let in_window = hour_in_active_window(current_hour, start, end);
-                    if !in_window {
-                        tracing::debug!(
-                            cron_id = %job_id,
-                            cron_timezone = %timezone,
-                            current_hour,
-                            start,
-                            end,
-                            "outside active hours, skipping"
-                        );
-                        continue;
-                    }
-                }
-
-                if execution_lock.load(std::sync::atomic::Ordering::Acquire) {
-                    tracing::debug!(cron_id = %job_id, "previous execution still running, skipping tick");
-                    continue;
-                }
-
-                tracing::info!(cron_id = %job_id, "cron job firing");
-                execution_lock.store(true, std::sync::atomic::Ordering::Release);
-
-                let exec_jobs = jobs.clone();
-                let exec_context = context.clone();
-                let exec_job_id = job_id.clone();
-                let guard = ExecutionGuard(execution_lock.clone());
-
-                tokio::spawn(async move {
-                    let _guard = guard;
-                    match run_cron_job(&job, &exec_context).await {
-                        Ok(()) => {
-                            #[cfg(feature = "metrics")]
-                            crate::telemetry::Metrics::global()
-                                .cron_executions_total
-                                .with_label_values(&[
-                                    &exec_context.deps.agent_id,
-                                    &exec_job_id,
-                                    "success",
-                                ])
-                                .inc();
-
-                            let mut j = exec_jobs.write().await;
-                            if let Some(j) = j.get_mut(&exec_job_id) {
-                                j.consecutive_failures = 0;
-                            }
-                        }
-                        Err(error) => {
-                            #[cfg(feature = "metrics")]
-                            crate::telemetry::Metrics::global()
-                                .cron_executions_total
-                                .with_label_values(&[
-                                    &exec_context.deps.agent_id,
-                                    &exec_job_id,
-                                    "failure",
-                                ])
-                                .inc();
-
-                            tracing::error!(
-                                cron_id = %exec_job_id,
-                                %error,
-                                "cron job execution failed"
-                            );
-
-                            let should_disable = {
-                                let mut j = exec_jobs.write().await;
-                                if let Some(j) = j.get_mut(&exec_job_id) {
-                                    j.consecutive_failures += 1;
-                                    j.consecutive_failures >= MAX_CONSECUTIVE_FAILURES
-                                } else {
-                                    false
-                                }
-                            };
-
-                            if should_disable {
-                                tracing::warn!(
-                                    cron_id = %exec_job_id,
-                                    "circuit breaker tripped after {MAX_CONSECUTIVE_FAILURES} consecutive failures, disabling"
-                                );
-
-                                {
-                                    let mut j = exec_jobs.write().await;
-                                    if let Some(j) = j.get_mut(&exec_job_id) {
-                                        j.enabled = false;
-                                    }
-                                }
-
-                                if let Err(error) =
-                                    exec_context.store.update_enabled(&exec_job_id, false).await
-                                {
-                                    tracing::error!(%error, "failed to persist cron job disabled state");
-                                }
-                            }
-                        }
-                    }
-
-                    if job.run_once {
-                        tracing::info!(cron_id = %exec_job_id, "run-once cron completed, disabling");
-
-                        {
-                            let mut j = exec_jobs.write().await;
-                            if let Some(j) = j.get_mut(&exec_job_id) {
-                                j.enabled = false;
-                            }
-                        }
-
-                        if let Err(error) =
-                            exec_context.store.update_enabled(&exec_job_id, false).await
-                        {
-                            tracing::error!(%error, "failed to persist run-once cron disabled state");
-                        }
-                    }
-                });
-            }
+        let spec = ProcessSpec::new(ProcessKind::Scheduler, format!("cron:{job_id}"))
+            .agent(&*self.context.deps.agent_id)
+            .restart(Restart::OnPanic);
+        let handle = Supervisor::global().supervise(spec, move || {
+            tokio::spawn(run_timer(
+                timer_job_id.clone(),
+                timer_jobs.clone(),
+                timer_context.clone(),
+                anchor,
+            ))
         });
 
-        // Insert the new handle. Any previously existing handle was already aborted above.
+        // Insert the new handle. Any previously existing handle was already stopped above.
         let mut timers = self.timers.write().await;
         timers.insert(job_id_for_map, handle);
     }
 
     /// Shutdown all cron job timers and wait for them to finish.
     pub async fn shutdown(&self) {
-        let handles: Vec<(String, tokio::task::JoinHandle<()>)> = {
+        let handles: Vec<(String, ProcessHandle)> = {
             let mut timers = self.timers.write().await;
             timers.drain().collect()
         };
 
         for (id, handle) in handles {
-            handle.abort();
-            let _ = handle.await;
+            handle.stop().await;
             tracing::debug!(cron_id = %id, "cron timer stopped");
         }
     }
 
     /// Unregister and stop a cron job.
     pub async fn unregister(&self, job_id: &str) {
-        // Remove the timer handle and abort it
+        // Remove the timer handle and stop it
         let handle = {
             let mut timers = self.timers.write().await;
             timers.remove(job_id)
         };
 
         if let Some(handle) = handle {
-            handle.abort();
-            let _ = handle.await;
+            handle.stop().await;
             tracing::debug!(cron_id = %job_id, "cron timer stopped");
         }
 
@@ -562,6 +385,7 @@ impl Scheduler {
                         run_once: config.run_once,
                         consecutive_failures: 0,
                         timeout_secs: config.timeout_secs,
+                        timezone: config.timezone,
                     },
                 );
             }
@@ -592,14 +416,14 @@ impl Scheduler {
         }
 
         if !enabled && was_enabled {
-            // Abort the timer immediately rather than waiting up to one full interval.
+            // Stop the timer immediately rather than waiting up to one full interval.
             let handle = {
                 let mut timers = self.timers.write().await;
                 timers.remove(job_id)
             };
             if let Some(handle) = handle {
-                handle.abort();
-                tracing::info!(cron_id = %job_id, "cron job disabled, timer aborted immediately");
+                handle.stop().await;
+                tracing::info!(cron_id = %job_id, "cron job disabled, timer stopped immediately");
             }
         }
 
@@ -607,6 +431,205 @@ impl Scheduler {
     }
 }
 
+/// Timer loop for one cron job: sleep until the next fire, run the job, repeat.
+/// Returns once the job is disabled or removed.
+async fn run_timer(
+    job_id: String,
+    jobs: Arc<RwLock<HashMap<String, CronJob>>>,
+    context: CronContext,
+    anchor: Option<chrono::DateTime<chrono::Utc>>,
+) {
+    let execution_lock = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let mut interval_first_tick = true;
+
+    loop {
+        let job = {
+            let j = jobs.read().await;
+            match j.get(&job_id) {
+                Some(j) if !j.enabled => {
+                    tracing::debug!(cron_id = %job_id, "cron job disabled, stopping timer");
+                    break;
+                }
+                Some(j) => j.clone(),
+                None => {
+                    tracing::debug!(cron_id = %job_id, "cron job removed, stopping timer");
+                    break;
+                }
+            }
+        };
+
+        let sleep_duration = if let Some(cron_expr) = job.cron_expr.as_deref() {
+            match next_fire_duration(&context, &job_id, cron_expr, job.timezone.as_deref()) {
+                Some((duration, next_fire_utc, timezone)) => {
+                    tracing::debug!(
+                        cron_id = %job_id,
+                        cron_expr,
+                        cron_timezone = %timezone,
+                        next_fire_utc = %next_fire_utc.to_rfc3339(),
+                        sleep_secs = duration.as_secs(),
+                        "wall-clock cron next fire computed"
+                    );
+                    duration
+                }
+                None => {
+                    tracing::warn!(
+                        cron_id = %job_id,
+                        cron_expr,
+                        "failed to compute next wall-clock fire; retrying in 60s"
+                    );
+                    Duration::from_secs(60)
+                }
+            }
+        } else {
+            let interval_secs = job.interval_secs;
+            let delay = if interval_first_tick {
+                interval_first_tick = false;
+                anchored_initial_delay(interval_secs, anchor)
+            } else {
+                Duration::from_secs(interval_secs)
+            };
+            tracing::debug!(
+                cron_id = %job_id,
+                interval_secs,
+                sleep_secs = delay.as_secs(),
+                anchored = anchor.is_some(),
+                "interval cron next fire computed"
+            );
+            delay
+        };
+
+        tokio::time::sleep(sleep_duration).await;
+
+        let job = {
+            let j = jobs.read().await;
+            match j.get(&job_id) {
+                Some(j) if !j.enabled => {
+                    tracing::debug!(cron_id = %job_id, "cron job disabled, stopping timer");
+                    break;
+                }
+                Some(j) => j.clone(),
+                None => {
+                    tracing::debug!(cron_id = %job_id, "cron job removed, stopping timer");
+                    break;
+                }
+            }
+        };
+
+        // Check active hours window
+        if let Some((start, end)) = job.active_hours {
+            let (current_hour, timezone) =
+                current_hour_and_timezone(&context, job.timezone.as_deref());
+            let in_window = hour_in_active_window(current_hour, start, end);
+            if !in_window {
+                tracing::debug!(
+                    cron_id = %job_id,
+                    cron_timezone = %timezone,
+                    current_hour,
+                    start,
+                    end,
+                    "outside active hours, skipping"
+                );
+                continue;
+            }
+        }
+
+        // Every node in a cluster runs the same timers; only the leader fires.
+        if crate::cluster::global().is_some_and(|cluster| !cluster.is_leader()) {
+            tracing::debug!(cron_id = %job_id, "not the cluster leader, skipping tick");
+            continue;
+        }
+
+        if execution_lock.load(std::sync::atomic::Ordering::Acquire) {
+            tracing::debug!(cron_id = %job_id, "previous execution still running, skipping tick");
+            continue;
+        }
+
+        tracing::info!(cron_id = %job_id, "cron job firing");
+        execution_lock.store(true, std::sync::atomic::Ordering::Release);
+
+        let exec_jobs = jobs.clone();
+        let exec_context = context.clone();
+        let exec_job_id = job_id.clone();
+        let guard = ExecutionGuard(execution_lock.clone());
+
+        tokio::spawn(async move {
+            let _guard = guard;
+            match run_cron_job(&job, &exec_context).await {
+                Ok(()) => {
+                    #[cfg(feature = "metrics")]
+                    crate::telemetry::Metrics::global()
+                        .cron_executions_total
+                        .with_label_values(&[&exec_context.deps.agent_id, &exec_job_id, "success"])
+                        .inc();
+
+                    let mut j = exec_jobs.write().await;
+                    if let Some(j) = j.get_mut(&exec_job_id) {
+                        j.consecutive_failures = 0;
+                    }
+                }
+                Err(error) => {
+                    #[cfg(feature = "metrics")]
+                    crate::telemetry::Metrics::global()
+                        .cron_executions_total
+                        .with_label_values(&[&exec_context.deps.agent_id, &exec_job_id, "failure"])
+                        .inc();
+
+                    tracing::error!(
+                        cron_id = %exec_job_id,
+                        %error,
+                        "cron job execution failed"
+                    );
+
+                    let should_disable = {
+                        let mut j = exec_jobs.write().await;
+                        if let Some(j) = j.get_mut(&exec_job_id) {
+                            j.consecutive_failures += 1;
+                            j.consecutive_failures >= MAX_CONSECUTIVE_FAILURES
+                        } else {
+                            false
+                        }
+                    };
+
+                    if should_disable {
+                        tracing::warn!(
+                            cron_id = %exec_job_id,
+                            "circuit breaker tripped after {MAX_CONSECUTIVE_FAILURES} consecutive failures, disabling"
+                        );
+
+                        {
+                            let mut j = exec_jobs.write().await;
+                            if let Some(j) = j.get_mut(&exec_job_id) {
+                                j.enabled = false;
+                            }
+                        }
+
+                        if let Err(error) =
+                            exec_context.store.update_enabled(&exec_job_id, false).await
+                        {
+                            tracing::error!(%error, "failed to persist cron job disabled state");
+                        }
+                    }
+                }
+            }
+
+            if job.run_once {
+                tracing::info!(cron_id = %exec_job_id, "run-once cron completed, disabling");
+
+                {
+                    let mut j = exec_jobs.write().await;
+                    if let Some(j) = j.get_mut(&exec_job_id) {
+                        j.enabled = false;
+                    }
+                }
+
+                if let Err(error) = exec_context.store.update_enabled(&exec_job_id, false).await {
+                    tracing::error!(%error, "failed to persist run-once cron disabled state");
+                }
+            }
+        });
+    }
+}
+
 fn cron_timezone_label(context: &CronContext) -> String {
     let timezone = context.deps.runtime_config.cron_timezone.load();
     match timezone.as_deref() {
@@ -615,33 +638,13 @@ fn cron_timezone_label(context: &CronContext) -> String {
     }
 }
 
-fn current_hour_and_timezone(context: &CronContext, cron_id: &str) -> (u8, String) {
-    let timezone = context.deps.runtime_config.cron_timezone.load();
-    match timezone.as_deref() {
-        Some(name) => match name.parse::<Tz>() {
-            Ok(timezone) => (
-                chrono::Utc::now().with_timezone(&timezone).hour() as u8,
-                name.into(),
-            ),
-            Err(error) => {
-                tracing::warn!(
-                    agent_id = %context.deps.agent_id,
-                    cron_id,
-                    cron_timezone = %name,
-                    %error,
-                    "invalid cron timezone in runtime config, falling back to system timezone"
-                );
-                (
-                    chrono::Local::now().hour() as u8,
-                    SYSTEM_TIMEZONE_LABEL.into(),
-                )
-            }
-        },
-        None => (
-            chrono::Local::now().hour() as u8,
-            SYSTEM_TIMEZONE_LABEL.into(),
-        ),
-    }
+fn current_hour_and_timezone(context: &CronContext, job_timezone: Option<&str>) -> (u8, String) {
+    let (timezone, timezone_label) = resolve_cron_timezone(context, job_timezone);
+    let current_hour = match timezone {
+        Some(timezone) => chrono::Utc::now().with_timezone(&timezone).hour(),
+        None => chrono::Local::now().hour(),
+    };
+    (current_hour as u8, timezone_label)
 }
 
 fn hour_in_active_window(current_hour: u8, start_hour: u8, end_hour: u8) -> bool {
@@ -750,7 +753,24 @@ fn expand_cron_expr(expr: &str) -> String {
     }
 }
 
-fn resolve_cron_timezone(context: &CronContext) -> (Option<chrono_tz::Tz>, String) {
+fn resolve_cron_timezone(
+    context: &CronContext,
+    job_timezone: Option<&str>,
+) -> (Option<chrono_tz::Tz>, String) {
+    if let Some(name) = job_timezone {
+        match name.parse::<Tz>() {
+            Ok(timezone) => return (Some(timezone), name.to_string()),
+            Err(error) => {
+                tracing::warn!(
+                    agent_id = %context.deps.agent_id,
+                    timezone = %name,
+                    %error,
+                    "invalid cron job timezone, falling back to cron_timezone"
+                );
+            }
+        }
+    }
+
     let timezone = context.deps.runtime_config.cron_timezone.load();
     match timezone.as_deref() {
         Some(name) => match name.parse::<Tz>() {
@@ -773,6 +793,7 @@ fn next_fire_duration(
     context: &CronContext,
     cron_id: &str,
     cron_expr: &str,
+    job_timezone: Option<&str>,
 ) -> Option<(Duration, chrono::DateTime<chrono::Utc>, String)> {
     // Expand 5-field standard cron to 7-field for the `cron` crate.
     let expanded = expand_cron_expr(cron_expr);
@@ -785,7 +806,7 @@ fn next_fire_duration(
     };
 
     let now_utc = chrono::Utc::now();
-    let (timezone, timezone_label) = resolve_cron_timezone(context);
+    let (timezone, timezone_label) = resolve_cron_timezone(context, job_timezone);
     let next_utc = if let Some(timezone) = timezone {
         let now_local = now_utc.with_timezone(&timezone);
         schedule
@@ -941,13 +962,26 @@ async fn run_cron_job(job: &CronJob, context: &CronContext) -> Result<()> {
     let has_result = !result_text.trim().is_empty();
 
     // Deliver result to target (only if there's something to say)
-    if has_result {
+    let moderated = if has_result {
+        crate::messaging::moderation::Moderator::new(
+            &context.deps,
+            format!("cron:{}", job.id).into(),
+        )
+        .moderate(OutboundResponse::Text(result_text.clone()), None)
+        .await
+    } else {
+        None
+    };
+    if has_result && moderated.is_none() {
+        tracing::info!(cron_id = %job.id, "cron result blocked by moderation");
+    }
+    if let Some(response) = moderated {
         if let Err(error) = context
             .messaging_manager
             .broadcast(
                 &job.delivery_target.adapter,
                 &job.delivery_target.target,
-                OutboundResponse::Text(result_text.clone()),
+                response,
             )
             .await
         {
@@ -972,7 +1006,7 @@ async fn run_cron_job(job: &CronJob, context: &CronContext) -> Result<()> {
             target = %job.delivery_target,
             "cron result delivered"
         );
-    } else {
+    } else if !has_result {
         tracing::debug!(cron_id = %job.id, "cron job produced no output, skipping delivery");
     }
 
@@ -25,8 +25,8 @@ impl CronStore {
 
         sqlx::query(
             r#"
-            INSERT INTO cron_jobs (id, prompt, cron_expr, interval_secs, delivery_target, active_start_hour, active_end_hour, enabled, run_once, timeout_secs)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO cron_jobs (id, prompt, cron_expr, interval_secs, delivery_target, active_start_hour, active_end_hour, enabled, run_once, timeout_secs, timezone)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             ON CONFLICT(id) DO UPDATE SET
                 prompt = excluded.prompt,
                 cron_expr = excluded.cron_expr,
@@ -36,7 +36,8 @@ impl CronStore {
                 active_end_hour = excluded.active_end_hour,
                 enabled = excluded.enabled,
                 run_once = excluded.run_once,
-                timeout_secs = excluded.timeout_secs
+                timeout_secs = excluded.timeout_secs,
+                timezone = excluded.timezone
             "#
         )
         .bind(&config.id)
@@ -49,6 +50,7 @@ impl CronStore {
         .bind(config.enabled as i64)
         .bind(config.run_once as i64)
         .bind(config.timeout_secs.map(|t| t as i64))
+        .bind(config.timezone.as_deref())
         .execute(&self.pool)
         .await
         .context("failed to save cron job")?;
@@ -60,7 +62,7 @@ impl CronStore {
     pub async fn load_all(&self) -> Result<Vec<CronConfig>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, prompt, cron_expr, interval_secs, delivery_target, active_start_hour, active_end_hour, enabled, run_once, timeout_secs
+            SELECT id, prompt, cron_expr, interval_secs, delivery_target, active_start_hour, active_end_hour, enabled, run_once, timeout_secs, timezone
             FROM cron_jobs
             WHERE enabled = 1
             ORDER BY created_at ASC
@@ -93,6 +95,7 @@ impl CronStore {
                     .ok()
                     .flatten()
                     .map(|t| t as u64),
+                timezone: row.try_get::<Option<String>, _>("timezone").ok().flatten(),
             })
             .collect();
 
@@ -152,7 +155,7 @@ impl CronStore {
     pub async fn load_all_unfiltered(&self) -> Result<Vec<CronConfig>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, prompt, cron_expr, interval_secs, delivery_target, active_start_hour, active_end_hour, enabled, run_once, timeout_secs
+            SELECT id, prompt, cron_expr, interval_secs, delivery_target, active_start_hour, active_end_hour, enabled, run_once, timeout_secs, timezone
             FROM cron_jobs
             ORDER BY created_at ASC
             "#,
@@ -184,6 +187,7 @@ impl CronStore {
                     .ok()
                     .flatten()
                     .map(|t| t as u64),
+                timezone: row.try_get::<Option<String>, _>("timezone").ok().flatten(),
             })
             .collect();
 
@@ -1,10 +1,23 @@
 //! Database connection management and migrations.
 
+pub mod maintenance;
+
+use crate::config::StorageConfig;
 use crate::error::{DbError, Result};
 use anyhow::Context as _;
+use serde::Serialize;
 use sqlx::SqlitePool;
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode};
 use std::path::Path;
 
+/// Migrations embedded at compile time from `./migrations`.
+///
+/// Every per-agent database is brought up to this set on connect. Keeping a
+/// single static means the binary, the schema endpoint, and the startup
+/// pre-flight all agree on which versions are known.
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
 /// Database connections bundle for per-agent databases.
 pub struct Db {
     /// SQLite pool for relational data.
@@ -21,15 +34,19 @@ use std::sync::Arc;
 
 impl Db {
     /// Connect to all databases and run migrations.
-    pub async fn connect(data_dir: &Path) -> Result<Self> {
+    ///
+    /// The SQLite file is opened in WAL mode, with the checkpoint tuning from
+    /// `storage` applied to every connection as it's opened.
+    pub async fn connect(data_dir: &Path, storage: &StorageConfig) -> Result<Self> {
         // SQLite
-        let sqlite_url = format!("sqlite:{}?mode=rwc", data_dir.join("spacebot.db").display());
-        let sqlite = SqlitePool::connect(&sqlite_url)
+        let options = sqlite_options(&data_dir.join("spacebot.db"), storage);
+        let sqlite = SqlitePool::connect_with(options)
             .await
             .with_context(|| "failed to connect to SQLite")?;
 
-        // Run migrations
-        sqlx::migrate!("./migrations")
+        // Refuse to touch a database written by a newer build, then migrate.
+        preflight(&sqlite).await?;
+        MIGRATOR
             .run(&sqlite)
             .await
             .with_context(|| "failed to run database migrations")?;
@@ -66,3 +83,239 @@ impl Db {
         // LanceDB and redb close automatically when dropped
     }
 }
+
+/// Connection options for an agent's SQLite file.
+///
+/// WAL lets readers proceed while a writer is active, and the autocheckpoint
+/// and journal size pragmas only take effect in that mode.
+fn sqlite_options(path: &Path, storage: &StorageConfig) -> SqliteConnectOptions {
+    SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .pragma(
+            "wal_autocheckpoint",
+            storage.db_wal_autocheckpoint_pages.to_string(),
+        )
+        .pragma(
+            "journal_size_limit",
+            storage
+                .db_journal_size_limit_mb
+                .saturating_mul(1024 * 1024)
+                .to_string(),
+        )
+}
+
+/// A migration recorded in `_sqlx_migrations`.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub description: String,
+    pub installed_on: String,
+    pub success: bool,
+    /// Execution time in milliseconds.
+    pub execution_time_ms: i64,
+}
+
+/// A migration compiled into this binary that hasn't been applied yet.
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingMigration {
+    pub version: i64,
+    pub description: String,
+}
+
+/// Schema version report for one database.
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaStatus {
+    /// Highest successfully applied version, if any.
+    pub current_version: Option<i64>,
+    /// Highest version known to this binary.
+    pub latest_known_version: Option<i64>,
+    pub applied: Vec<AppliedMigration>,
+    pub pending: Vec<PendingMigration>,
+    /// Applied versions this binary doesn't know about. Non-empty means the
+    /// database was migrated by a newer build.
+    pub unknown: Vec<i64>,
+}
+
+impl SchemaStatus {
+    pub fn is_up_to_date(&self) -> bool {
+        self.pending.is_empty() && self.unknown.is_empty()
+    }
+}
+
+/// Read the applied migrations and compare them against [`MIGRATOR`].
+pub async fn schema_status(pool: &SqlitePool) -> Result<SchemaStatus> {
+    let applied = applied_migrations(pool).await?;
+    let known: Vec<_> = MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .collect();
+
+    let pending = known
+        .iter()
+        .filter(|migration| {
+            !applied
+                .iter()
+                .any(|row| row.version == migration.version && row.success)
+        })
+        .map(|migration| PendingMigration {
+            version: migration.version,
+            description: migration.description.to_string(),
+        })
+        .collect();
+    let unknown = applied
+        .iter()
+        .map(|row| row.version)
+        .filter(|version| !known.iter().any(|migration| migration.version == *version))
+        .collect();
+
+    Ok(SchemaStatus {
+        current_version: applied
+            .iter()
+            .filter(|row| row.success)
+            .map(|row| row.version)
+            .max(),
+        latest_known_version: known.iter().map(|migration| migration.version).max(),
+        applied,
+        pending,
+        unknown,
+    })
+}
+
+/// Fail if the database has migrations applied that this binary doesn't
+/// ship. Running older code against a newer schema silently drops or
+/// misreads columns, so it's better to stop before opening any stores.
+pub async fn preflight(pool: &SqlitePool) -> Result<()> {
+    let status = schema_status(pool).await?;
+    if !status.unknown.is_empty() {
+        return Err(DbError::Migration(format!(
+            "database schema is newer than this build (unknown versions: {}; latest known: {}). \
+             Upgrade spacebot before opening this database.",
+            status
+                .unknown
+                .iter()
+                .map(i64::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+            status
+                .latest_known_version
+                .map_or_else(|| "none".to_string(), |version| version.to_string()),
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+async fn applied_migrations(pool: &SqlitePool) -> Result<Vec<AppliedMigration>> {
+    let table_exists: Option<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'",
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|error| DbError::Query(error.to_string()))?;
+    if table_exists.is_none() {
+        return Ok(Vec::new());
+    }
+
+    let rows = sqlx::query_as::<_, AppliedMigration>(
+        "SELECT version, description, CAST(installed_on AS TEXT) AS installed_on, success, \
+         execution_time / 1000000 AS execution_time_ms \
+         FROM _sqlx_migrations ORDER BY version",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|error| DbError::Query(error.to_string()))?;
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn migrated_pool() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("failed to create in-memory pool");
+        MIGRATOR.run(&pool).await.expect("failed to run migrations");
+        pool
+    }
+
+    #[test]
+    fn migration_versions_are_dated_and_ordered() {
+        let versions: Vec<i64> = MIGRATOR.iter().map(|migration| migration.version).collect();
+
+        assert!(
+            versions.windows(2).all(|pair| pair[0] < pair[1]),
+            "migration versions must be unique"
+        );
+        for version in versions {
+            assert_eq!(
+                version.to_string().len(),
+                14,
+                "migration {version} must be named YYYYMMDDNNNNNN_description.sql"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn fresh_database_has_everything_pending() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let status = schema_status(&pool).await.unwrap();
+
+        assert_eq!(status.current_version, None);
+        assert!(status.applied.is_empty());
+        assert_eq!(status.pending.len(), MIGRATOR.iter().count());
+        assert!(preflight(&pool).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn migrated_database_is_up_to_date() {
+        let pool = migrated_pool().await;
+        let status = schema_status(&pool).await.unwrap();
+
+        assert!(status.is_up_to_date());
+        assert_eq!(status.current_version, status.latest_known_version);
+    }
+
+    #[tokio::test]
+    async fn agent_database_opens_in_wal_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = StorageConfig::default();
+        let pool =
+            SqlitePool::connect_with(sqlite_options(&dir.path().join("spacebot.db"), &storage))
+                .await
+                .unwrap();
+
+        let (journal_mode,): (String,) = sqlx::query_as("PRAGMA journal_mode")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(journal_mode, "wal");
+        let (autocheckpoint,): (i64,) = sqlx::query_as("PRAGMA wal_autocheckpoint")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(
+            autocheckpoint,
+            i64::from(storage.db_wal_autocheckpoint_pages)
+        );
+    }
+
+    #[tokio::test]
+    async fn preflight_rejects_future_schema() {
+        let pool = migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time) \
+             VALUES (99990101000001, 'from the future', 1, x'00', 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let status = schema_status(&pool).await.unwrap();
+        assert_eq!(status.unknown, vec![99990101000001]);
+        let error = preflight(&pool).await.unwrap_err();
+        assert!(error.to_string().contains("newer than this build"));
+    }
+}
@@ -0,0 +1,333 @@
+//! SQLite maintenance: statistics, VACUUM, WAL checkpoints, and integrity checks.
+//!
+//! Every agent database runs in WAL mode. SQLite checkpoints the WAL on its
+//! own once it grows past `db_wal_autocheckpoint_pages`; the scheduled pass
+//! additionally refreshes query planner statistics, rewrites the file when
+//! enough of it is free pages, and truncates the WAL.
+
+use crate::config::StorageConfig;
+use crate::error::{DbError, Result};
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+use std::collections::HashMap;
+
+/// Maximum number of problems an integrity check reports.
+const MAX_INTEGRITY_ERRORS: u32 = 100;
+
+/// Page-level layout of a database file.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct PageStats {
+    pub page_size: i64,
+    pub page_count: i64,
+    /// Pages that are allocated in the file but hold no data.
+    pub freelist_count: i64,
+}
+
+impl PageStats {
+    pub fn file_bytes(&self) -> i64 {
+        self.page_size * self.page_count
+    }
+
+    pub fn free_percent(&self) -> f64 {
+        if self.page_count == 0 {
+            return 0.0;
+        }
+        self.freelist_count as f64 * 100.0 / self.page_count as f64
+    }
+}
+
+/// Rows and on-disk size of one table, including its indexes.
+#[derive(Debug, Clone, Serialize)]
+pub struct TableSize {
+    pub name: String,
+    pub rows: i64,
+    /// None when the SQLite build lacks the `dbstat` virtual table.
+    pub bytes: Option<i64>,
+}
+
+/// Size and configuration report for one database.
+#[derive(Debug, Clone, Serialize)]
+pub struct DbReport {
+    #[serde(flatten)]
+    pub pages: PageStats,
+    pub file_bytes: i64,
+    pub free_percent: f64,
+    pub journal_mode: String,
+    pub wal_autocheckpoint_pages: i64,
+    /// Largest first.
+    pub tables: Vec<TableSize>,
+}
+
+/// Outcome of `PRAGMA wal_checkpoint(TRUNCATE)`.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct CheckpointReport {
+    /// A reader or writer prevented the checkpoint from completing.
+    pub busy: bool,
+    /// Frames in the WAL before the checkpoint; -1 when not in WAL mode.
+    pub wal_frames: i64,
+    pub checkpointed_frames: i64,
+}
+
+/// Result of a maintenance pass.
+#[derive(Debug, Clone, Serialize)]
+pub struct MaintenanceReport {
+    pub vacuumed: bool,
+    /// Free pages as a percentage of the file before the pass.
+    pub free_percent: f64,
+    pub bytes_reclaimed: i64,
+    pub checkpoint: CheckpointReport,
+}
+
+/// Result of `PRAGMA integrity_check` or `PRAGMA quick_check`.
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityReport {
+    pub ok: bool,
+    /// Whether the faster check, which skips index content verification, ran.
+    pub quick: bool,
+    pub problems: Vec<String>,
+}
+
+pub async fn page_stats(pool: &SqlitePool) -> Result<PageStats> {
+    Ok(PageStats {
+        page_size: pragma_i64(pool, "page_size").await?,
+        page_count: pragma_i64(pool, "page_count").await?,
+        freelist_count: pragma_i64(pool, "freelist_count").await?,
+    })
+}
+
+/// Page layout, WAL settings, and per-table sizes.
+pub async fn report(pool: &SqlitePool) -> Result<DbReport> {
+    let pages = page_stats(pool).await?;
+    let journal_mode: (String,) = sqlx::query_as("PRAGMA journal_mode")
+        .fetch_one(pool)
+        .await
+        .map_err(|error| DbError::Query(error.to_string()))?;
+
+    Ok(DbReport {
+        file_bytes: pages.file_bytes(),
+        free_percent: pages.free_percent(),
+        pages,
+        journal_mode: journal_mode.0,
+        wal_autocheckpoint_pages: pragma_i64(pool, "wal_autocheckpoint").await?,
+        tables: table_sizes(pool).await?,
+    })
+}
+
+async fn table_sizes(pool: &SqlitePool) -> Result<Vec<TableSize>> {
+    let names: Vec<(String,)> = sqlx::query_as(
+        "SELECT name FROM sqlite_master \
+         WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|error| DbError::Query(error.to_string()))?;
+
+    // Index pages are attributed to the table they belong to.
+    let bytes: Option<HashMap<String, i64>> = sqlx::query_as::<_, (String, i64)>(
+        "SELECT m.tbl_name, SUM(d.pgsize) FROM dbstat d \
+         JOIN sqlite_master m ON m.name = d.name GROUP BY m.tbl_name",
+    )
+    .fetch_all(pool)
+    .await
+    .inspect_err(|error| tracing::debug!(%error, "dbstat unavailable, skipping table sizes"))
+    .ok()
+    .map(|rows| rows.into_iter().collect());
+
+    let mut tables = Vec::with_capacity(names.len());
+    for (name,) in names {
+        let rows: (i64,) = sqlx::query_as(&format!(
+            "SELECT COUNT(*) FROM \"{}\"",
+            name.replace('"', "\"\"")
+        ))
+        .fetch_one(pool)
+        .await
+        .map_err(|error| DbError::Query(error.to_string()))?;
+        tables.push(TableSize {
+            bytes: bytes
+                .as_ref()
+                .map(|bytes| bytes.get(&name).copied().unwrap_or(0)),
+            name,
+            rows: rows.0,
+        });
+    }
+
+    tables.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+    Ok(tables)
+}
+
+/// Copy the WAL back into the database file and truncate it.
+pub async fn checkpoint(pool: &SqlitePool) -> Result<CheckpointReport> {
+    let (busy, wal_frames, checkpointed_frames): (i64, i64, i64) =
+        sqlx::query_as("PRAGMA wal_checkpoint(TRUNCATE)")
+            .fetch_one(pool)
+            .await
+            .map_err(|error| DbError::Query(error.to_string()))?;
+    Ok(CheckpointReport {
+        busy: busy != 0,
+        wal_frames,
+        checkpointed_frames,
+    })
+}
+
+/// Refresh planner statistics, VACUUM when at least
+/// `db_vacuum_min_free_percent` of the file is free pages, then checkpoint.
+pub async fn run(pool: &SqlitePool, config: &StorageConfig) -> Result<MaintenanceReport> {
+    let before = page_stats(pool).await?;
+
+    sqlx::query("ANALYZE")
+        .execute(pool)
+        .await
+        .map_err(|error| DbError::Query(format!("ANALYZE failed: {error}")))?;
+
+    let free_percent = before.free_percent();
+    let vacuum =
+        before.freelist_count > 0 && free_percent >= f64::from(config.db_vacuum_min_free_percent);
+    if vacuum {
+        sqlx::query("VACUUM")
+            .execute(pool)
+            .await
+            .map_err(|error| DbError::Query(format!("VACUUM failed: {error}")))?;
+        rebuild_message_search_index(pool).await?;
+    }
+
+    let checkpoint = checkpoint(pool).await?;
+    let after = page_stats(pool).await?;
+
+    Ok(MaintenanceReport {
+        vacuumed: vacuum,
+        free_percent,
+        bytes_reclaimed: (before.file_bytes() - after.file_bytes()).max(0),
+        checkpoint,
+    })
+}
+
+/// The message full-text index is keyed by rowid, which SQLite doesn't
+/// promise to keep stable across VACUUM for tables without an INTEGER
+/// PRIMARY KEY. Rebuild it so search never points at the wrong message.
+async fn rebuild_message_search_index(pool: &SqlitePool) -> Result<()> {
+    let exists: Option<(String,)> =
+        sqlx::query_as("SELECT name FROM sqlite_master WHERE name = 'conversation_messages_fts'")
+            .fetch_optional(pool)
+            .await
+            .map_err(|error| DbError::Query(error.to_string()))?;
+    if exists.is_none() {
+        return Ok(());
+    }
+
+    sqlx::query(
+        "INSERT INTO conversation_messages_fts(conversation_messages_fts) VALUES ('rebuild')",
+    )
+    .execute(pool)
+    .await
+    .map_err(|error| DbError::Query(format!("failed to rebuild message search index: {error}")))?;
+    Ok(())
+}
+
+/// Verify the database structure. The quick check skips comparing index
+/// content against table content, which is most of the cost on large files.
+pub async fn integrity_check(pool: &SqlitePool, quick: bool) -> Result<IntegrityReport> {
+    let pragma = if quick {
+        "quick_check"
+    } else {
+        "integrity_check"
+    };
+    let rows: Vec<(String,)> = sqlx::query_as(&format!("PRAGMA {pragma}({MAX_INTEGRITY_ERRORS})"))
+        .fetch_all(pool)
+        .await
+        .map_err(|error| DbError::Query(error.to_string()))?;
+
+    let problems: Vec<String> = rows
+        .into_iter()
+        .map(|(row,)| row)
+        .filter(|row| row != "ok")
+        .collect();
+    Ok(IntegrityReport {
+        ok: problems.is_empty(),
+        quick,
+        problems,
+    })
+}
+
+async fn pragma_i64(pool: &SqlitePool, pragma: &str) -> Result<i64> {
+    let value: (i64,) = sqlx::query_as(&format!("PRAGMA {pragma}"))
+        .fetch_one(pool)
+        .await
+        .map_err(|error| DbError::Query(error.to_string()))?;
+    Ok(value.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn pool_with_rows() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("CREATE INDEX idx_notes_body ON notes(body)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for index in 0..500 {
+            sqlx::query("INSERT INTO notes (body) VALUES (?)")
+                .bind(format!("note {index} {}", "x".repeat(200)))
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        pool
+    }
+
+    #[tokio::test]
+    async fn report_counts_rows_per_table() {
+        let pool = pool_with_rows().await;
+        let report = report(&pool).await.unwrap();
+
+        let notes = report
+            .tables
+            .iter()
+            .find(|table| table.name == "notes")
+            .unwrap();
+        assert_eq!(notes.rows, 500);
+        assert!(notes.bytes.is_some_and(|bytes| bytes > 0));
+        assert_eq!(report.file_bytes, report.pages.file_bytes());
+        assert!(report.pages.page_count > 0);
+    }
+
+    #[tokio::test]
+    async fn maintenance_vacuums_only_past_free_threshold() {
+        let pool = pool_with_rows().await;
+        sqlx::query("DELETE FROM notes WHERE id > 50")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let free = page_stats(&pool).await.unwrap();
+        assert!(free.freelist_count > 0);
+
+        let strict = StorageConfig {
+            db_vacuum_min_free_percent: 100,
+            ..StorageConfig::default()
+        };
+        let skipped = run(&pool, &strict).await.unwrap();
+        assert!(!skipped.vacuumed);
+
+        let report = run(&pool, &StorageConfig::default()).await.unwrap();
+        assert!(report.vacuumed);
+        assert!(report.bytes_reclaimed > 0);
+        assert_eq!(page_stats(&pool).await.unwrap().freelist_count, 0);
+    }
+
+    #[tokio::test]
+    async fn integrity_check_passes_on_healthy_database() {
+        let pool = pool_with_rows().await;
+        for quick in [true, false] {
+            let report = integrity_check(&pool, quick).await.unwrap();
+            assert!(report.ok);
+            assert!(report.problems.is_empty());
+        }
+    }
+}
@@ -187,6 +187,16 @@ pub enum AgentError {
     #[error("max concurrent workers ({max}) reached for channel {channel_id}")]
     WorkerLimitReached { channel_id: String, max: usize },
 
+    #[error(
+        "{scope} spend limit reached for channel {channel_id} (${spent_usd:.2} of ${limit_usd:.2}), worker spawns are paused"
+    )]
+    SpendLimitReached {
+        channel_id: String,
+        scope: String,
+        spent_usd: f64,
+        limit_usd: f64,
+    },
+
     #[error(
         "duplicate worker task on channel {channel_id}: worker {existing_worker_id} is already running this task"
     )]
@@ -245,3 +255,28 @@ pub enum SettingsError {
     #[error("settings error: {0}")]
     Other(String),
 }
+
+/// A platform answered an outbound delivery with a non-success HTTP status.
+///
+/// Adapters that talk HTTP themselves return this so the outbox can decide
+/// whether to retry from the status instead of the message text.
+#[derive(Debug, thiserror::Error)]
+#[error("{endpoint} returned HTTP {status}: {body}")]
+pub struct DeliveryError {
+    pub endpoint: String,
+    pub status: u16,
+    pub body: String,
+}
+
+impl DeliveryError {
+    /// Rate limits, timeouts, and server errors can succeed later; any other
+    /// status is a rejection of the request itself.
+    pub fn is_transient(&self) -> bool {
+        is_transient_status(self.status)
+    }
+}
+
+/// Whether an HTTP status describes a temporary condition on the other end.
+pub fn is_transient_status(status: u16) -> bool {
+    matches!(status, 408 | 425 | 429 | 500..=599)
+}
@@ -0,0 +1,920 @@
+//! Portable agent state export and import.
+//!
+//! An export archive is a gzipped tar file with the following layout:
+//!
+//! - `manifest.json` — format version, source agent, and per-table row counts
+//! - `tables/<table>.jsonl` — one JSON object per row, keyed by column name
+//! - `identity/<FILE>.md` — SOUL.md, IDENTITY.md, and ROLE.md from the agent root
+//!
+//! Rows are dumped generically so new columns travel without code changes.
+//! Exports stage each table on disk a row at a time and write the archive to
+//! a temp file, so neither the database nor the archive is held in memory.
+//! Import only writes columns that exist in the target schema, uses
+//! `INSERT OR IGNORE` so re-importing the same archive is a no-op, rewrites
+//! `agent_id` columns to the target agent, and regenerates LanceDB embeddings
+//! for imported memories (vectors are not portable across embedding models).
+//!
+//! Cortex chat content encrypted at rest is keyed to the agent and the
+//! instance config key, so it's decrypted on export and re-encrypted on
+//! import under the target's key. Archives hold that content in plaintext.
+
+use crate::agent::cortex_chat::CortexChatStore;
+use crate::error::Result;
+use crate::memory::MemorySearch;
+
+use anyhow::{Context as _, anyhow};
+use base64::Engine as _;
+use futures::TryStreamExt as _;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteArguments, SqliteRow};
+use sqlx::{Column as _, Row as _, Sqlite, SqlitePool, TypeInfo as _, ValueRef as _};
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{Read, Seek as _, Write};
+use std::path::Path;
+use tokio::io::AsyncWriteExt as _;
+
+/// Archive format version. Bump when the layout changes incompatibly.
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// Tables included in an export, in dependency order (parents first).
+///
+/// Every table in the agent database is either listed here or in
+/// [`NOT_EXPORTED_TABLES`]; a test enforces it, so new migrations have to
+/// pick one.
+const EXPORT_TABLES: &[&str] = &[
+    "memories",
+    "associations",
+    "memory_audit",
+    "channels",
+    "conversation_messages",
+    "conversation_messages_archive",
+    "channel_summaries",
+    "branch_runs",
+    "worker_runs",
+    "team_runs",
+    "cortex_events",
+    "cortex_chat_messages",
+    "cortex_chat_thread_forks",
+    "tasks",
+    "cron_jobs",
+    "cron_executions",
+    "agent_profile",
+    "bulletin_pins",
+    "user_profiles",
+    "user_profile_facts",
+    "prompt_template_versions",
+    "experiments",
+    "experiment_assignments",
+    "experiment_outcomes",
+    "moderation_events",
+];
+
+/// Agent tables deliberately left out of exports.
+#[cfg(test)]
+const NOT_EXPORTED_TABLES: &[&str] = &[
+    // Reference files on the source machine's disk.
+    "projects",
+    "project_repos",
+    "project_worktrees",
+    "ingestion_files",
+    "ingestion_progress",
+    "saved_attachments",
+    // Delivery state for the source instance's adapters.
+    "outbox",
+    "outbox_failures",
+    // Journal of LanceDB writes; imported memories are re-embedded instead.
+    "embedding_journal",
+    // Cursor over `conversation_messages` rowids, which imports don't keep.
+    "fact_extraction_state",
+];
+
+/// Identity files copied from the agent root directory.
+const IDENTITY_FILES: &[&str] = &["SOUL.md", "IDENTITY.md", "ROLE.md"];
+
+/// Cortex chat columns that may hold encrypted content.
+const CHAT_CONTENT_COLUMNS: &[&str] = &["content", "channel_context", "tool_calls"];
+
+/// JSON key used to tag base64-encoded BLOB values.
+const BLOB_KEY: &str = "$blob";
+
+/// Contents of `manifest.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportManifest {
+    pub format_version: u32,
+    pub agent_id: String,
+    pub exported_at: String,
+    pub spacebot_version: String,
+    /// Row count per exported table.
+    pub tables: BTreeMap<String, usize>,
+    pub identity_files: Vec<String>,
+}
+
+/// Per-table outcome of an import.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TableImportCount {
+    /// Rows present in the archive.
+    pub read: usize,
+    /// Rows actually inserted (existing primary keys are skipped).
+    pub inserted: usize,
+}
+
+/// Summary of an import.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub source_agent_id: String,
+    /// Whether the target agent was created for this import.
+    pub agent_created: bool,
+    pub tables: BTreeMap<String, TableImportCount>,
+    pub memories_embedded: usize,
+    /// Identity files written to the target agent.
+    pub identity_files: Vec<String>,
+    /// Identity files left alone because the target already had them.
+    pub identity_files_skipped: Vec<String>,
+}
+
+/// A finished export archive in an anonymous temp file, positioned at the
+/// start. The file is deleted when it's dropped.
+#[derive(Debug)]
+pub struct ExportArchive {
+    pub file: std::fs::File,
+    /// Archive size in bytes.
+    pub size: u64,
+}
+
+type JsonRow = serde_json::Map<String, serde_json::Value>;
+
+/// Build a portable export archive for an agent. `chat_store` is the
+/// agent's cortex chat store, used to decrypt chat content.
+pub async fn export_agent(
+    pool: &SqlitePool,
+    chat_store: &CortexChatStore,
+    agent_id: &str,
+    identity_dir: &Path,
+) -> Result<ExportArchive> {
+    let staging = tempfile::tempdir().context("failed to create export staging directory")?;
+    let mut row_counts = BTreeMap::new();
+    for table in EXPORT_TABLES {
+        let staged = staging.path().join(format!("{table}.jsonl"));
+        let count = stage_table(pool, chat_store, table, &staged).await?;
+        row_counts.insert(table.to_string(), count);
+    }
+
+    let mut identity = Vec::new();
+    for file_name in IDENTITY_FILES {
+        if let Ok(content) = tokio::fs::read_to_string(identity_dir.join(file_name)).await {
+            identity.push((*file_name, content));
+        }
+    }
+
+    let manifest = ExportManifest {
+        format_version: EXPORT_FORMAT_VERSION,
+        agent_id: agent_id.to_string(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        spacebot_version: env!("CARGO_PKG_VERSION").to_string(),
+        tables: row_counts,
+        identity_files: identity.iter().map(|(name, _)| name.to_string()).collect(),
+    };
+
+    let archive =
+        tokio::task::spawn_blocking(move || write_archive(&manifest, staging.path(), &identity))
+            .await
+            .context("export archive task failed")??;
+
+    Ok(archive)
+}
+
+/// Write one table's rows to `path` as JSONL, streaming them from the
+/// database. Returns the row count.
+async fn stage_table(
+    pool: &SqlitePool,
+    chat_store: &CortexChatStore,
+    table: &str,
+    path: &Path,
+) -> Result<usize> {
+    let file = tokio::fs::File::create(path)
+        .await
+        .with_context(|| format!("failed to stage table {table}"))?;
+    let mut writer = tokio::io::BufWriter::new(file);
+
+    let sql = format!("SELECT * FROM {table}");
+    let mut rows = sqlx::query(&sql).fetch(pool);
+    let mut count = 0;
+    let mut line = Vec::new();
+    while let Some(row) = rows
+        .try_next()
+        .await
+        .with_context(|| format!("failed to read table {table}"))?
+    {
+        let mut row = row_to_json(&row);
+        if table == "cortex_chat_messages" {
+            map_chat_content(&mut row, |value| chat_store.decrypt_for_export(value))?;
+        }
+        line.clear();
+        serde_json::to_writer(&mut line, &row).context("failed to encode row")?;
+        line.push(b'\n');
+        writer
+            .write_all(&line)
+            .await
+            .with_context(|| format!("failed to stage table {table}"))?;
+        count += 1;
+    }
+    writer
+        .flush()
+        .await
+        .with_context(|| format!("failed to stage table {table}"))?;
+
+    Ok(count)
+}
+
+/// Restore an export archive into an existing agent. `chat_store` is the
+/// target's cortex chat store, which encrypts chat content when the target
+/// has at-rest encryption on. Identity files the target already has are kept
+/// unless `overwrite_identity` is set.
+pub async fn import_agent(
+    pool: &SqlitePool,
+    chat_store: &CortexChatStore,
+    memory_search: &MemorySearch,
+    agent_id: &str,
+    identity_dir: &Path,
+    archive: std::fs::File,
+    overwrite_identity: bool,
+) -> Result<ImportReport> {
+    let parsed =
+        tokio::task::spawn_blocking(move || read_archive(std::io::BufReader::new(archive)))
+            .await
+            .context("import archive task failed")??;
+
+    if parsed.manifest.format_version > EXPORT_FORMAT_VERSION {
+        return Err(anyhow!(
+            "archive format version {} is newer than supported version {}",
+            parsed.manifest.format_version,
+            EXPORT_FORMAT_VERSION
+        )
+        .into());
+    }
+
+    let (tables, memories_to_embed) =
+        import_rows(pool, chat_store, agent_id, &parsed.tables).await?;
+    let mut report = ImportReport {
+        source_agent_id: parsed.manifest.agent_id.clone(),
+        agent_created: false,
+        tables,
+        memories_embedded: 0,
+        identity_files: Vec::new(),
+        identity_files_skipped: Vec::new(),
+    };
+
+    let embedding_model = memory_search.embedding_model_arc();
+    for (memory_id, content) in memories_to_embed {
+        match embedding_model.embed_one(&content).await {
+            Ok(embedding) => {
+                if let Err(error) = memory_search
+                    .embedding_table()
+                    .store(&memory_id, &content, &embedding)
+                    .await
+                {
+                    tracing::warn!(%error, %memory_id, "failed to store imported memory embedding");
+                    continue;
+                }
+                report.memories_embedded += 1;
+            }
+            Err(error) => {
+                tracing::warn!(%error, %memory_id, "failed to embed imported memory");
+            }
+        }
+    }
+
+    if report.memories_embedded > 0
+        && let Err(error) = memory_search.embedding_table().ensure_fts_index().await
+    {
+        tracing::warn!(%error, "failed to refresh memory FTS index after import");
+    }
+
+    (report.identity_files, report.identity_files_skipped) =
+        restore_identity(identity_dir, parsed.identity, overwrite_identity).await?;
+
+    Ok(report)
+}
+
+/// Write archived identity files into the agent root. Returns the files
+/// written and the existing ones skipped because `overwrite` is off.
+async fn restore_identity(
+    identity_dir: &Path,
+    files: Vec<(String, String)>,
+    overwrite: bool,
+) -> Result<(Vec<String>, Vec<String>)> {
+    tokio::fs::create_dir_all(identity_dir)
+        .await
+        .with_context(|| format!("failed to create {}", identity_dir.display()))?;
+
+    let mut written = Vec::new();
+    let mut skipped = Vec::new();
+    for (file_name, content) in files {
+        let path = identity_dir.join(&file_name);
+        if !overwrite && tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            tracing::warn!(
+                file_name,
+                "identity file already exists, skipping it (set overwrite_identity to replace it)"
+            );
+            skipped.push(file_name);
+            continue;
+        }
+        tokio::fs::write(&path, content)
+            .await
+            .with_context(|| format!("failed to write {file_name}"))?;
+        written.push(file_name);
+    }
+    Ok((written, skipped))
+}
+
+/// Insert archived rows into the agent database in one transaction. Returns
+/// per-table counts and the imported memories that need embeddings.
+async fn import_rows(
+    pool: &SqlitePool,
+    chat_store: &CortexChatStore,
+    agent_id: &str,
+    archived: &BTreeMap<String, Vec<JsonRow>>,
+) -> Result<(BTreeMap<String, TableImportCount>, Vec<(String, String)>)> {
+    let mut tables = BTreeMap::new();
+    let mut memories_to_embed = Vec::new();
+
+    let mut transaction = pool.begin().await.context("failed to start import")?;
+    for table in EXPORT_TABLES {
+        let Some(rows) = archived.get(*table) else {
+            continue;
+        };
+        let known_columns = table_columns(&mut transaction, table).await?;
+        let mut count = TableImportCount {
+            read: rows.len(),
+            inserted: 0,
+        };
+
+        for row in rows {
+            let sealed;
+            let row = if *table == "cortex_chat_messages" {
+                let mut copy = row.clone();
+                map_chat_content(&mut copy, |value| chat_store.seal_for_import(value))?;
+                sealed = copy;
+                &sealed
+            } else {
+                row
+            };
+            let columns: Vec<&String> = row
+                .keys()
+                .filter(|column| known_columns.contains(column.as_str()))
+                .collect();
+            if columns.is_empty() {
+                continue;
+            }
+
+            let sql = format!(
+                "INSERT OR IGNORE INTO {table} ({}) VALUES ({})",
+                columns
+                    .iter()
+                    .map(|column| format!("\"{column}\""))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                vec!["?"; columns.len()].join(", ")
+            );
+
+            let mut query = sqlx::query(&sql);
+            for column in &columns {
+                query = if column.as_str() == "agent_id" {
+                    query.bind(agent_id.to_string())
+                } else {
+                    bind_json_value(query, &row[column.as_str()])
+                };
+            }
+
+            let result = query
+                .execute(&mut *transaction)
+                .await
+                .with_context(|| format!("failed to import row into {table}"))?;
+            if result.rows_affected() == 0 {
+                continue;
+            }
+            count.inserted += 1;
+
+            if *table == "memories"
+                && !row.get("forgotten").is_some_and(is_truthy)
+                && let (Some(id), Some(content)) = (
+                    row.get("id").and_then(|value| value.as_str()),
+                    row.get("content").and_then(|value| value.as_str()),
+                )
+            {
+                memories_to_embed.push((id.to_string(), content.to_string()));
+            }
+        }
+
+        tables.insert(table.to_string(), count);
+    }
+    transaction
+        .commit()
+        .await
+        .context("failed to commit import")?;
+
+    Ok((tables, memories_to_embed))
+}
+
+struct ParsedArchive {
+    manifest: ExportManifest,
+    tables: BTreeMap<String, Vec<JsonRow>>,
+    identity: Vec<(String, String)>,
+}
+
+/// Write the archive to a temp file from the manifest, the tables staged in
+/// `staging`, and the identity files.
+fn write_archive(
+    manifest: &ExportManifest,
+    staging: &Path,
+    identity: &[(&str, String)],
+) -> Result<ExportArchive> {
+    let output = tempfile::tempfile().context("failed to create export archive")?;
+    let encoder = flate2::write::GzEncoder::new(
+        std::io::BufWriter::new(output),
+        flate2::Compression::default(),
+    );
+    let mut builder = tar::Builder::new(encoder);
+
+    let manifest = serde_json::to_vec_pretty(manifest).context("failed to encode manifest")?;
+    append_entry(
+        &mut builder,
+        "manifest.json",
+        manifest.len() as u64,
+        manifest.as_slice(),
+    )?;
+
+    for table in EXPORT_TABLES {
+        let staged = std::fs::File::open(staging.join(format!("{table}.jsonl")))
+            .with_context(|| format!("failed to open staged table {table}"))?;
+        let size = staged
+            .metadata()
+            .with_context(|| format!("failed to open staged table {table}"))?
+            .len();
+        append_entry(
+            &mut builder,
+            &format!("tables/{table}.jsonl"),
+            size,
+            std::io::BufReader::new(staged),
+        )?;
+    }
+
+    for (file_name, content) in identity {
+        append_entry(
+            &mut builder,
+            &format!("identity/{file_name}"),
+            content.len() as u64,
+            content.as_bytes(),
+        )?;
+    }
+
+    let encoder = builder.into_inner().context("failed to finalize archive")?;
+    let writer = encoder.finish().context("failed to finalize archive")?;
+    let mut file = writer
+        .into_inner()
+        .map_err(|error| error.into_error())
+        .context("failed to finalize archive")?;
+    let size = file
+        .stream_position()
+        .context("failed to finalize archive")?;
+    file.rewind().context("failed to finalize archive")?;
+
+    Ok(ExportArchive { file, size })
+}
+
+fn append_entry<W: Write>(
+    builder: &mut tar::Builder<W>,
+    path: &str,
+    size: u64,
+    content: impl Read,
+) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(size);
+    header.set_mode(0o644);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    builder
+        .append_data(&mut header, path, content)
+        .with_context(|| format!("failed to write {path}"))?;
+    Ok(())
+}
+
+fn read_archive(archive: impl Read) -> Result<ParsedArchive> {
+    // Only the manifest, known tables, and known identity file names are read,
+    // so archive entries can't write outside the agent root.
+    let wanted: HashSet<String> = std::iter::once("manifest.json".to_string())
+        .chain(
+            EXPORT_TABLES
+                .iter()
+                .map(|table| format!("tables/{table}.jsonl")),
+        )
+        .chain(
+            IDENTITY_FILES
+                .iter()
+                .map(|file_name| format!("identity/{file_name}")),
+        )
+        .collect();
+
+    let mut entries = HashMap::new();
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(archive));
+    for entry in tar
+        .entries()
+        .context("export archive is not a valid tar.gz file")?
+    {
+        let mut entry = entry.context("export archive is not a valid tar.gz file")?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry
+            .path()
+            .context("invalid entry path in export archive")?
+            .to_string_lossy()
+            .into_owned();
+        if !wanted.contains(&path) {
+            continue;
+        }
+        let mut content = String::new();
+        entry
+            .read_to_string(&mut content)
+            .with_context(|| format!("failed to read {path}"))?;
+        entries.insert(path, content);
+    }
+
+    let manifest: ExportManifest = serde_json::from_str(
+        &entries
+            .remove("manifest.json")
+            .context("export archive is missing manifest.json")?,
+    )
+    .context("invalid manifest.json")?;
+
+    let mut tables = BTreeMap::new();
+    for table in EXPORT_TABLES {
+        let Some(content) = entries.remove(&format!("tables/{table}.jsonl")) else {
+            continue;
+        };
+        let rows = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str::<JsonRow>)
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .with_context(|| format!("invalid row in table {table}"))?;
+        tables.insert(table.to_string(), rows);
+    }
+
+    let mut identity = Vec::new();
+    for file_name in IDENTITY_FILES {
+        if let Some(content) = entries.remove(&format!("identity/{file_name}")) {
+            identity.push((file_name.to_string(), content));
+        }
+    }
+
+    Ok(ParsedArchive {
+        manifest,
+        tables,
+        identity,
+    })
+}
+
+async fn table_columns(
+    connection: &mut sqlx::SqliteConnection,
+    table: &str,
+) -> Result<HashSet<String>> {
+    let rows = sqlx::query(&format!("PRAGMA table_info({table})"))
+        .fetch_all(&mut *connection)
+        .await
+        .with_context(|| format!("failed to inspect table {table}"))?;
+    Ok(rows
+        .iter()
+        .filter_map(|row| row.try_get::<String, _>("name").ok())
+        .collect())
+}
+
+/// Convert a row of any shape to a JSON object using SQLite storage classes.
+fn row_to_json(row: &SqliteRow) -> JsonRow {
+    let mut object = JsonRow::new();
+    for column in row.columns() {
+        let index = column.ordinal();
+        let value = match row.try_get_raw(index) {
+            Ok(raw) if raw.is_null() => serde_json::Value::Null,
+            Ok(raw) => match raw.type_info().name() {
+                "INTEGER" => row
+                    .try_get_unchecked::<i64, _>(index)
+                    .map(serde_json::Value::from)
+                    .unwrap_or_default(),
+                "REAL" => row
+                    .try_get_unchecked::<f64, _>(index)
+                    .map(serde_json::Value::from)
+                    .unwrap_or_default(),
+                "BLOB" => row
+                    .try_get_unchecked::<Vec<u8>, _>(index)
+                    .map(|bytes| {
+                        serde_json::json!({
+                            BLOB_KEY: base64::engine::general_purpose::STANDARD.encode(bytes)
+                        })
+                    })
+                    .unwrap_or_default(),
+                _ => row
+                    .try_get_unchecked::<String, _>(index)
+                    .map(serde_json::Value::from)
+                    .unwrap_or_default(),
+            },
+            Err(_) => serde_json::Value::Null,
+        };
+        object.insert(column.name().to_string(), value);
+    }
+    object
+}
+
+fn bind_json_value<'q>(
+    query: sqlx::query::Query<'q, Sqlite, SqliteArguments<'q>>,
+    value: &serde_json::Value,
+) -> sqlx::query::Query<'q, Sqlite, SqliteArguments<'q>> {
+    match value {
+        serde_json::Value::Null => query.bind(None::<String>),
+        serde_json::Value::Bool(flag) => query.bind(*flag),
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(integer) => query.bind(integer),
+            None => query.bind(number.as_f64()),
+        },
+        serde_json::Value::String(text) => query.bind(text.clone()),
+        serde_json::Value::Object(object) => {
+            match object
+                .get(BLOB_KEY)
+                .and_then(|encoded| encoded.as_str())
+                .and_then(|encoded| {
+                    base64::engine::general_purpose::STANDARD
+                        .decode(encoded)
+                        .ok()
+                }) {
+                Some(bytes) => query.bind(bytes),
+                None => query.bind(value.to_string()),
+            }
+        }
+        serde_json::Value::Array(_) => query.bind(value.to_string()),
+    }
+}
+
+/// Apply `transform` to the string values of a cortex chat row's content
+/// columns.
+fn map_chat_content(
+    row: &mut JsonRow,
+    transform: impl Fn(&str) -> anyhow::Result<String>,
+) -> Result<()> {
+    for column in CHAT_CONTENT_COLUMNS {
+        if let Some(serde_json::Value::String(value)) = row.get_mut(*column) {
+            *value = transform(value)?;
+        }
+    }
+    Ok(())
+}
+
+fn is_truthy(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::Bool(flag) => *flag,
+        serde_json::Value::Number(number) => number.as_i64().is_some_and(|n| n != 0),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn migrated_pool() -> SqlitePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to create sqlite memory pool");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("failed to run migrations");
+        pool
+    }
+
+    #[tokio::test]
+    async fn archive_round_trips_rows_and_identity() {
+        let pool = migrated_pool().await;
+        sqlx::query(
+            "INSERT INTO conversation_messages (id, channel_id, role, content) \
+             VALUES ('m1', 'discord:1', 'user', 'hello there')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO memories (id, content, memory_type, importance) \
+             VALUES ('mem1', 'likes rust', 'preference', 0.7)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let identity_dir = tempfile::tempdir().unwrap();
+        std::fs::write(identity_dir.path().join("SOUL.md"), "be kind").unwrap();
+
+        let archive = export_agent(
+            &pool,
+            &CortexChatStore::new(pool.clone()),
+            "source",
+            identity_dir.path(),
+        )
+        .await
+        .expect("export should succeed");
+        let parsed = read_archive(archive.file).expect("archive should parse");
+
+        assert_eq!(parsed.manifest.agent_id, "source");
+        assert_eq!(parsed.manifest.tables["conversation_messages"], 1);
+        let memories = &parsed.tables["memories"];
+        assert_eq!(memories.len(), 1);
+        assert_eq!(memories[0]["content"], "likes rust");
+        assert_eq!(memories[0]["importance"], 0.7);
+        assert_eq!(
+            parsed.identity,
+            vec![("SOUL.md".to_string(), "be kind".to_string())]
+        );
+    }
+
+    #[tokio::test]
+    async fn every_agent_table_is_exported_or_excluded() {
+        let source = migrated_pool().await;
+        // Virtual and shadow tables (the FTS index) are rebuilt by triggers
+        // when rows are imported.
+        let tables: Vec<String> = sqlx::query_scalar(
+            "SELECT name FROM pragma_table_list \
+             WHERE schema = 'main' AND type = 'table' \
+             AND name NOT LIKE 'sqlite_%' AND name NOT LIKE '_sqlx_%'",
+        )
+        .fetch_all(&source)
+        .await
+        .unwrap();
+        for table in &tables {
+            assert!(
+                EXPORT_TABLES.contains(&table.as_str())
+                    || NOT_EXPORTED_TABLES.contains(&table.as_str()),
+                "table {table} must be added to EXPORT_TABLES or NOT_EXPORTED_TABLES"
+            );
+        }
+        for table in EXPORT_TABLES.iter().chain(NOT_EXPORTED_TABLES) {
+            assert!(
+                tables.iter().any(|name| name == table),
+                "{table} is not a table"
+            );
+        }
+
+        let identity_dir = tempfile::tempdir().unwrap();
+        let archive = export_agent(
+            &source,
+            &CortexChatStore::new(source.clone()),
+            "source",
+            identity_dir.path(),
+        )
+        .await
+        .expect("export should succeed");
+        let parsed = read_archive(archive.file).expect("archive should parse");
+        assert_eq!(parsed.manifest.tables.len(), EXPORT_TABLES.len());
+        assert_eq!(parsed.tables.len(), EXPORT_TABLES.len());
+
+        let target = migrated_pool().await;
+        let (imported, _) = import_rows(
+            &target,
+            &CortexChatStore::new(target.clone()),
+            "target",
+            &parsed.tables,
+        )
+        .await
+        .expect("import should succeed");
+        assert_eq!(imported.len(), EXPORT_TABLES.len());
+    }
+
+    #[tokio::test]
+    async fn existing_identity_files_are_kept_unless_overwriting() {
+        let identity_dir = tempfile::tempdir().unwrap();
+        std::fs::write(identity_dir.path().join("SOUL.md"), "target soul").unwrap();
+        let archived = || {
+            vec![
+                ("SOUL.md".to_string(), "source soul".to_string()),
+                ("ROLE.md".to_string(), "source role".to_string()),
+            ]
+        };
+
+        let (written, skipped) = restore_identity(identity_dir.path(), archived(), false)
+            .await
+            .expect("restore should succeed");
+        assert_eq!(written, vec!["ROLE.md".to_string()]);
+        assert_eq!(skipped, vec!["SOUL.md".to_string()]);
+        assert_eq!(
+            std::fs::read_to_string(identity_dir.path().join("SOUL.md")).unwrap(),
+            "target soul"
+        );
+
+        let (written, skipped) = restore_identity(identity_dir.path(), archived(), true)
+            .await
+            .expect("restore should succeed");
+        assert_eq!(written.len(), 2);
+        assert!(skipped.is_empty());
+        assert_eq!(
+            std::fs::read_to_string(identity_dir.path().join("SOUL.md")).unwrap(),
+            "source soul"
+        );
+    }
+
+    #[tokio::test]
+    async fn blob_columns_round_trip_through_export_and_import() {
+        let source = migrated_pool().await;
+        let transcript = vec![0u8, 1, 2, 255, 128];
+        sqlx::query(
+            "INSERT INTO worker_runs (id, task, status, agent_id, transcript) \
+             VALUES ('w1', 'summarize', 'done', 'source', ?)",
+        )
+        .bind(&transcript)
+        .execute(&source)
+        .await
+        .unwrap();
+
+        let identity_dir = tempfile::tempdir().unwrap();
+        let archive = export_agent(
+            &source,
+            &CortexChatStore::new(source.clone()),
+            "source",
+            identity_dir.path(),
+        )
+        .await
+        .expect("export should succeed");
+        let parsed = read_archive(archive.file).expect("archive should parse");
+        assert!(parsed.tables["worker_runs"][0]["transcript"][BLOB_KEY].is_string());
+
+        let target = migrated_pool().await;
+        let target_store = CortexChatStore::new(target.clone());
+        let (tables, _) = import_rows(&target, &target_store, "target", &parsed.tables)
+            .await
+            .expect("import should succeed");
+        assert_eq!(tables["worker_runs"].inserted, 1);
+
+        let (agent_id, imported): (String, Vec<u8>) =
+            sqlx::query_as("SELECT agent_id, transcript FROM worker_runs WHERE id = 'w1'")
+                .fetch_one(&target)
+                .await
+                .unwrap();
+        assert_eq!(agent_id, "target");
+        assert_eq!(imported, transcript);
+
+        let (tables, _) = import_rows(&target, &target_store, "target", &parsed.tables)
+            .await
+            .expect("re-import should succeed");
+        assert_eq!(tables["worker_runs"].inserted, 0);
+    }
+
+    #[tokio::test]
+    async fn encrypted_chat_moves_between_agents_and_keys() {
+        use crate::secrets::content_cipher::{ContentCipher, is_encrypted_content};
+        use std::sync::Arc;
+
+        let source = migrated_pool().await;
+        let source_cipher = ContentCipher::derive(&[7u8; 32], "source").unwrap();
+        let source_store =
+            CortexChatStore::new(source.clone()).with_cipher(Some(Arc::new(source_cipher)), true);
+        source_store
+            .save_message("t1", "user", "where is the launch plan?", None, None)
+            .await
+            .unwrap();
+
+        let identity_dir = tempfile::tempdir().unwrap();
+        let archive = export_agent(&source, &source_store, "source", identity_dir.path())
+            .await
+            .expect("export should succeed");
+        let parsed = read_archive(archive.file).expect("archive should parse");
+        assert_eq!(
+            parsed.tables["cortex_chat_messages"][0]["content"],
+            "where is the launch plan?"
+        );
+
+        // A different agent on an instance with a different config key.
+        let target = migrated_pool().await;
+        let target_cipher = ContentCipher::derive(&[9u8; 32], "target").unwrap();
+        let target_store =
+            CortexChatStore::new(target.clone()).with_cipher(Some(Arc::new(target_cipher)), true);
+        import_rows(&target, &target_store, "target", &parsed.tables)
+            .await
+            .expect("import should succeed");
+
+        let (stored,): (String,) = sqlx::query_as("SELECT content FROM cortex_chat_messages")
+            .fetch_one(&target)
+            .await
+            .unwrap();
+        assert!(is_encrypted_content(&stored));
+        let history = target_store.load_history("t1", 10).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].content, "where is the launch plan?");
+
+        // Without the source key the export refuses rather than shipping
+        // ciphertext nobody can read.
+        let keyless = CortexChatStore::new(source.clone());
+        assert!(
+            export_agent(&source, &keyless, "source", identity_dir.path())
+                .await
+                .is_err()
+        );
+    }
+}
@@ -61,6 +61,9 @@ pub struct SpacebotHook {
     /// append the messages to history before re-prompting.
     injected_messages: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
     memory_persistence_contract: Option<Arc<MemoryPersistenceContractState>>,
+    /// Set when a tool call passes the guards and starts executing. Channels
+    /// use it to stop interrupting a turn that has already acted.
+    tool_started: Option<Arc<std::sync::atomic::AtomicBool>>,
 }
 
 impl SpacebotHook {
@@ -115,6 +118,7 @@ impl SpacebotHook {
             inject_rx: None,
             injected_messages: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
             memory_persistence_contract: None,
+            tool_started: None,
         }
     }
 
@@ -132,6 +136,15 @@ impl SpacebotHook {
         self
     }
 
+    /// Attach a flag that is set once any tool call starts executing.
+    pub fn with_tool_started_flag(
+        mut self,
+        tool_started: Arc<std::sync::atomic::AtomicBool>,
+    ) -> Self {
+        self.tool_started = Some(tool_started);
+        self
+    }
+
     /// Attach a context injection receiver to this hook.
     ///
     /// When set, `on_completion_call` will drain pending messages from the
@@ -810,10 +823,10 @@ where
             }
         }
 
-        // Leak blocking is enforced at channel egress (`reply`). Worker and
-        // branch tool calls may legitimately handle secrets internally.
+        // Leak blocking is enforced at channel egress (`reply`, `edit_reply`).
+        // Worker and branch tool calls may legitimately handle secrets internally.
         if self.process_type == ProcessType::Channel
-            && tool_name == "reply"
+            && matches!(tool_name, "reply" | "edit_reply")
             && let Some(leak) = self.scan_for_leaks(args)
         {
             tracing::error!(
@@ -827,6 +840,10 @@ where
             };
         }
 
+        if let Some(tool_started) = &self.tool_started {
+            tool_started.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+
         // Send event without blocking. Truncate args to keep broadcast payloads bounded.
         let capped_args = crate::tools::truncate_output(args, 2_000);
         let event = ProcessEvent::ToolStarted {
@@ -2,4 +2,6 @@
 
 pub mod files;
 
-pub use files::{Identity, scaffold_identity_files};
+pub use files::{
+    Identity, MAX_IDENTITY_FILE_BYTES, scaffold_identity_files, validate_identity_file,
+};
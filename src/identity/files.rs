@@ -58,6 +58,27 @@ impl Identity {
     }
 }
 
+/// Largest identity file the API accepts. Identity is part of every system
+/// prompt, so a runaway file costs context on every turn.
+pub const MAX_IDENTITY_FILE_BYTES: usize = 32 * 1024;
+
+/// Check new content for an identity file before it's written.
+///
+/// Empty content is allowed: it clears the file, and an empty file is left
+/// out of the system prompt.
+pub fn validate_identity_file(file_name: &str, content: &str) -> anyhow::Result<()> {
+    if content.len() > MAX_IDENTITY_FILE_BYTES {
+        anyhow::bail!(
+            "{file_name} is {} bytes; the limit is {MAX_IDENTITY_FILE_BYTES}",
+            content.len()
+        );
+    }
+    if content.contains('\0') {
+        anyhow::bail!("{file_name} contains a NUL byte");
+    }
+    Ok(())
+}
+
 /// Default identity file templates for new agents.
 ///
 /// Uses the `main-agent` preset content so fresh instances start with a
@@ -103,5 +124,46 @@ pub async fn scaffold_identity_files(identity_dir: &Path) -> crate::error::Resul
 
 /// Load a file if it exists, returning None if missing.
 async fn load_optional_file(path: &Path) -> Option<String> {
-    tokio::fs::read_to_string(path).await.ok()
+    tokio::fs::read_to_string(path)
+        .await
+        .ok()
+        .filter(|content| !content.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_accepts_normal_and_empty_content() {
+        assert!(validate_identity_file("SOUL.md", "Curious and direct.").is_ok());
+        assert!(validate_identity_file("SOUL.md", "").is_ok());
+        assert!(validate_identity_file("ROLE.md", "  \n").is_ok());
+        assert!(validate_identity_file("ROLE.md", &"x".repeat(MAX_IDENTITY_FILE_BYTES)).is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_oversized_and_binary_content() {
+        let oversized = "x".repeat(MAX_IDENTITY_FILE_BYTES + 1);
+        let error = validate_identity_file("SOUL.md", &oversized).unwrap_err();
+        assert!(error.to_string().contains("SOUL.md"));
+
+        assert!(validate_identity_file("IDENTITY.md", "name\0").is_err());
+    }
+
+    #[tokio::test]
+    async fn cleared_files_are_left_out_of_the_prompt() {
+        let dir = tempfile::tempdir().unwrap();
+        tokio::fs::write(dir.path().join("SOUL.md"), "Curious.")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("ROLE.md"), "")
+            .await
+            .unwrap();
+
+        let identity = Identity::load(dir.path()).await;
+        assert_eq!(identity.soul.as_deref(), Some("Curious."));
+        assert!(identity.role.is_none());
+        assert!(!identity.render().contains("## Role"));
+    }
 }
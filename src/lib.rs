@@ -1,14 +1,19 @@
 //! Spacebot: A Rust agentic system where every LLM process has a dedicated role.
 
+pub mod access;
 pub mod agent;
 pub mod api;
+pub mod audit;
 pub mod auth;
+pub mod cli_worker;
+pub mod cluster;
 pub mod config;
 pub mod conversation;
 pub mod cron;
 pub mod daemon;
 pub mod db;
 pub mod error;
+pub mod export;
 pub mod factory;
 pub mod github_copilot_auth;
 pub mod hooks;
@@ -27,9 +32,12 @@ pub mod secrets;
 pub mod self_awareness;
 pub mod settings;
 pub mod skills;
+pub mod supervisor;
 pub mod tasks;
+pub mod teams;
 #[cfg(feature = "metrics")]
 pub mod telemetry;
+pub mod testing;
 pub mod tools;
 pub mod update;
 
@@ -38,7 +46,7 @@ pub use error::{Error, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 
 /// Signal from the API to the main event loop to trigger provider setup.
 #[derive(Debug)]
@@ -285,6 +293,17 @@ pub enum ProcessEvent {
         channel_id: Option<ChannelId>,
         result: String,
     },
+    /// Every worker started by one `spawn_worker_batch` call has finished.
+    /// Batch members complete with `notify: false`, so this is the only
+    /// result the channel is retriggered with.
+    WorkerBatchComplete {
+        agent_id: AgentId,
+        batch_id: uuid::Uuid,
+        channel_id: ChannelId,
+        results: Vec<crate::agent::worker_batch::WorkerBatchResult>,
+        summary: String,
+        success: bool,
+    },
     TextDelta {
         agent_id: AgentId,
         process_id: ProcessId,
@@ -308,6 +327,26 @@ pub enum ProcessEvent {
         channel_id: Option<ChannelId>,
         text: String,
     },
+    /// Spend crossed a `[defaults.budget]` limit for the first time today.
+    BudgetWarning {
+        agent_id: AgentId,
+        /// "daily", "channel", or "worker".
+        scope: String,
+        channel_id: Option<ChannelId>,
+        worker_id: Option<WorkerId>,
+        spent_usd: f64,
+        limit_usd: f64,
+        /// Hard limits downgrade routing and pause worker spawns.
+        hard: bool,
+    },
+    /// A channel turn was cancelled before it replied because a new message
+    /// arrived. The turn restarts with that message.
+    TurnInterrupted {
+        agent_id: AgentId,
+        channel_id: ChannelId,
+        /// Sender of the message that interrupted the turn.
+        sender_id: String,
+    },
 }
 
 /// Default broadcast capacity for the per-agent control event bus.
@@ -399,6 +438,9 @@ pub struct AgentDeps {
     /// Org-level human definitions (hot-reloadable). Used by `build_org_context()`
     /// to surface human display names, roles, and descriptions in agent prompts.
     pub humans: Arc<arc_swap::ArcSwap<Vec<config::HumanDef>>>,
+    /// Coordinator-led teams (hot-reloadable). Coordinators' workers get the
+    /// `team_dispatch` tool.
+    pub teams: Arc<arc_swap::ArcSwap<Vec<config::TeamDef>>>,
     /// Cross-agent task store registry. Maps agent_id → TaskStore for agents
     /// reachable via links. Used by `send_agent_message` to create tasks on
     /// target agents and by the cortex to look up delegation metadata.
@@ -406,6 +448,9 @@ pub struct AgentDeps {
     pub task_store_registry:
         Arc<arc_swap::ArcSwap<std::collections::HashMap<String, Arc<tasks::TaskStore>>>>,
     pub process_control_registry: Arc<agent::process_control::ProcessControlRegistry>,
+    /// Conclusions of recently completed branches, reused when the same task
+    /// is branched again over unchanged channel history.
+    pub branch_cache: Arc<agent::branch_cache::BranchResultCache>,
     /// Sender for injecting messages into channels from outside the normal
     /// inbound message flow (e.g. cross-agent task completion notifications).
     pub injection_tx: tokio::sync::mpsc::Sender<ChannelInjection>,
@@ -423,6 +468,16 @@ impl AgentDeps {
     pub fn routing(&self) -> arc_swap::Guard<Arc<llm::RoutingConfig>> {
         self.runtime_config.routing.load()
     }
+
+    /// Budget guard that charges completions to the given scope.
+    pub fn budget_guard(&self, scope: llm::budget::SpendScope) -> llm::budget::BudgetGuard {
+        llm::budget::BudgetGuard::new(
+            self.runtime_config.clone(),
+            self.agent_id.clone(),
+            self.event_tx.clone(),
+            scope,
+        )
+    }
 }
 
 /// A running agent instance with all its isolated resources.
@@ -431,6 +486,9 @@ pub struct Agent {
     pub config: config::ResolvedAgentConfig,
     pub db: db::Db,
     pub deps: AgentDeps,
+    /// Durable outbound queue shared by the agent's channels, so startup
+    /// replays and live responses keep per-channel order.
+    pub outbox: messaging::outbox::Outbox,
 }
 
 /// Standard metadata keys set by all adapters.
@@ -449,6 +507,8 @@ pub mod metadata_keys {
     pub const REPLY_TO_MESSAGE_ID: &str = "reply_to_message_id";
     /// Quoted reply text preview from the message being replied to.
     pub const REPLY_TO_TEXT: &str = "reply_to_text";
+    /// The sender's IANA timezone, when the platform knows it (Slack).
+    pub const SENDER_TIMEZONE: &str = "sender_timezone";
 }
 
 /// Inbound message from any messaging platform.
@@ -546,6 +606,15 @@ pub enum MessageContent {
         /// Platform-specific message reference (`ts` on Slack, message ID on Discord).
         message_ts: Option<String>,
     },
+    /// A native platform slash command bound to a skill.
+    ///
+    /// Produced by the Discord adapter for commands registered from skill
+    /// frontmatter. Arguments arrive already parsed, keyed by name.
+    Command {
+        /// Command name without the leading `/`.
+        name: String,
+        args: serde_json::Map<String, serde_json::Value>,
+    },
 }
 
 impl std::fmt::Display for MessageContent {
@@ -573,6 +642,16 @@ impl std::fmt::Display for MessageContent {
                     write!(f, "[interaction: {}]", action_id)
                 }
             }
+            MessageContent::Command { name, args } => {
+                write!(f, "/{name}")?;
+                for (key, value) in args {
+                    match value {
+                        serde_json::Value::String(text) => write!(f, " {key}:{text}")?,
+                        other => write!(f, " {key}:{other}")?,
+                    }
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -600,6 +679,39 @@ pub struct Attachment {
 pub struct RoutedResponse {
     pub response: OutboundResponse,
     pub target: InboundMessage,
+    /// Set when the sender wants to know how delivery went (and the platform
+    /// message ID, for later edits). `None` for fire-and-forget responses.
+    pub receipt: Option<DeliveryReceipt>,
+}
+
+/// Outcome of delivering a tracked outbound response: the platform message
+/// ID when the adapter reports one, or the delivery error.
+pub type DeliveryOutcome = std::result::Result<Option<String>, String>;
+
+/// One-shot slot the outbound router fills once a tracked response has been
+/// handed to the messaging adapter.
+///
+/// Wrapped so `RoutedResponse` stays `Clone`; only the first `complete`
+/// call is delivered.
+#[derive(Debug, Clone)]
+pub struct DeliveryReceipt(Arc<std::sync::Mutex<Option<oneshot::Sender<DeliveryOutcome>>>>);
+
+impl DeliveryReceipt {
+    pub fn new() -> (Self, oneshot::Receiver<DeliveryOutcome>) {
+        let (tx, rx) = oneshot::channel();
+        (Self(Arc::new(std::sync::Mutex::new(Some(tx)))), rx)
+    }
+
+    pub fn complete(&self, outcome: DeliveryOutcome) {
+        let sender = self
+            .0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take();
+        if let Some(sender) = sender {
+            sender.send(outcome).ok();
+        }
+    }
 }
 
 /// A sender that automatically pairs outbound responses with a captured
@@ -616,6 +728,11 @@ impl RoutedSender {
         Self { inner, target }
     }
 
+    /// Platform source of the message this sender replies to.
+    pub fn target_source(&self) -> &str {
+        &self.target.source
+    }
+
     pub async fn send(
         &self,
         response: OutboundResponse,
@@ -624,9 +741,30 @@ impl RoutedSender {
             .send(RoutedResponse {
                 response,
                 target: self.target.clone(),
+                receipt: None,
             })
             .await
     }
+
+    /// Send a response and return a receiver that resolves once the adapter
+    /// has delivered it, carrying the platform message ID when available.
+    pub async fn send_tracked(
+        &self,
+        response: OutboundResponse,
+    ) -> std::result::Result<
+        oneshot::Receiver<DeliveryOutcome>,
+        mpsc::error::SendError<RoutedResponse>,
+    > {
+        let (receipt, receiver) = DeliveryReceipt::new();
+        self.inner
+            .send(RoutedResponse {
+                response,
+                target: self.target.clone(),
+                receipt: Some(receipt),
+            })
+            .await?;
+        Ok(receiver)
+    }
 }
 
 /// Outbound response to messaging platforms.
@@ -658,12 +796,15 @@ pub enum OutboundResponse {
     Ephemeral {
         /// The message text (mrkdwn on Slack, plain text on others).
         text: String,
-        /// The user ID who should see the message. Required on Slack; ignored elsewhere.
+        /// The user ID who should see the message. Empty means the sender of the
+        /// triggering message. Ignored outside Slack.
         user_id: String,
     },
     /// Send a rich message with platform-specific formatting.
-    /// - Slack: uses `blocks` if present, falls back to `text`
+    /// - Slack: uses `blocks` if present, otherwise renders `cards`, `interactive_elements`
+    ///   and `poll` as Block Kit, falling back to `text`
     /// - Discord: uses `cards`, `interactive_elements`, `poll` if present, falls back to `text`
+    /// - Telegram: `interactive_elements` become an inline keyboard under `text`; `poll` is native
     /// - Other adapters: use `text` as-is
     RichMessage {
         /// Plain-text fallback — always present, used for notifications and adapters
@@ -679,7 +820,7 @@ pub enum OutboundResponse {
         /// Interactive elements (buttons, select menus). Maps to Discord ActionRows.
         #[serde(default)]
         interactive_elements: Vec<InteractiveElements>,
-        /// An optional poll (Discord only).
+        /// An optional poll. Native on Discord; one button per answer on Slack.
         #[serde(default)]
         poll: Option<Poll>,
     },
@@ -690,6 +831,17 @@ pub enum OutboundResponse {
         /// Unix epoch seconds when the message should be delivered.
         post_at: i64,
     },
+    /// Replace the text of a message the agent sent earlier, identified by
+    /// its platform message ID. No-op on platforms that can't edit messages.
+    Edit {
+        message_id: String,
+        text: String,
+    },
+    /// Delete a message the agent sent earlier, identified by its platform
+    /// message ID. No-op on platforms that can't delete messages.
+    Delete {
+        message_id: String,
+    },
     StreamStart,
     StreamChunk(String),
     StreamEnd,
@@ -1,11 +1,21 @@
 //! LLM provider management and routing.
 
 pub mod anthropic;
+pub mod budget;
+pub mod catalog;
+pub mod debug_capture;
+pub mod health;
 pub mod manager;
 pub mod model;
+pub mod oauth;
+pub mod ollama;
 pub mod pricing;
+pub mod probe;
 pub mod providers;
+pub mod quota;
+pub mod response_cache;
 pub mod routing;
+pub mod trace;
 
 pub use manager::LlmManager;
 pub use model::SpacebotModel;
@@ -82,6 +82,10 @@ pub fn build_anthropic_request(
         body["temperature"] = serde_json::json!(temperature);
     }
 
+    if let Some(top_p) = crate::llm::model::requested_top_p(request) {
+        body["top_p"] = serde_json::json!(top_p);
+    }
+
     if adaptive_thinking {
         body["thinking"] = serde_json::json!({ "type": "adaptive" });
         let effort = match thinking_effort {
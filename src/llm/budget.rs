@@ -0,0 +1,496 @@
+//! Per-agent spend limits (`[defaults.budget]`).
+//!
+//! Models built with a `BudgetGuard` charge the estimated cost of every
+//! completion (see `pricing`) to the agent's spend ledger: a total for the
+//! current UTC day, a daily total per channel, and a total per worker. Each
+//! scope can have a soft and a hard limit. The first time a limit is crossed
+//! in a day, a `BudgetWarning` event goes to the cortex. While a hard limit is
+//! reached, routing switches to the configured `downgrade_model`, and daily
+//! and channel hard limits pause worker spawns. Daily and channel totals are
+//! persisted to `spend.json` in the agent's data directory; worker totals
+//! live in memory and are reset with the day.
+
+use crate::config::{BudgetConfig, RuntimeConfig};
+use crate::{AgentId, ChannelId, ProcessEvent, WorkerId};
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// File name for persisted spend totals inside the agent data directory.
+pub const SPEND_FILE_NAME: &str = "spend.json";
+
+/// Which total a limit applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetScope {
+    Daily,
+    Channel,
+    Worker,
+}
+
+impl BudgetScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Channel => "channel",
+            Self::Worker => "worker",
+        }
+    }
+}
+
+/// What a completion is charged to besides the agent's daily total.
+#[derive(Debug, Clone, Default)]
+pub struct SpendScope {
+    pub channel_id: Option<ChannelId>,
+    pub worker_id: Option<WorkerId>,
+}
+
+impl SpendScope {
+    /// Charged to the daily total only (cortex, background jobs).
+    pub fn agent() -> Self {
+        Self::default()
+    }
+
+    /// Charged to a channel, for the channel itself and its branches.
+    pub fn channel(channel_id: impl Into<ChannelId>) -> Self {
+        Self {
+            channel_id: Some(channel_id.into()),
+            worker_id: None,
+        }
+    }
+
+    /// Charged to a worker and, if it has one, its parent channel.
+    pub fn worker(worker_id: WorkerId, channel_id: Option<ChannelId>) -> Self {
+        Self {
+            channel_id,
+            worker_id: Some(worker_id),
+        }
+    }
+}
+
+/// A limit that a scope's spend has reached.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetCrossing {
+    pub scope: BudgetScope,
+    pub hard: bool,
+    pub spent_usd: f64,
+    pub limit_usd: f64,
+}
+
+/// Spend against the limits of one scope, as reported by the usage API.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct ScopeStatus {
+    pub spent_usd: f64,
+    pub soft_limit_usd: Option<f64>,
+    pub hard_limit_usd: Option<f64>,
+    pub soft_exceeded: bool,
+    pub hard_exceeded: bool,
+}
+
+impl ScopeStatus {
+    fn new(spent_usd: f64, soft_limit_usd: Option<f64>, hard_limit_usd: Option<f64>) -> Self {
+        Self {
+            spent_usd,
+            soft_limit_usd,
+            hard_limit_usd,
+            soft_exceeded: soft_limit_usd.is_some_and(|limit| spent_usd >= limit),
+            hard_exceeded: hard_limit_usd.is_some_and(|limit| spent_usd >= limit),
+        }
+    }
+}
+
+/// Budget state for an agent.
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetStatus {
+    /// UTC day the totals apply to (`YYYY-MM-DD`).
+    pub day: String,
+    pub daily: ScopeStatus,
+    pub channels: HashMap<String, ScopeStatus>,
+    pub workers: HashMap<String, ScopeStatus>,
+    pub downgrade_model: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SpendTotals {
+    day: String,
+    daily_usd: f64,
+    channels: HashMap<String, f64>,
+    #[serde(skip)]
+    workers: HashMap<WorkerId, f64>,
+    /// Limits already reported today, keyed by scope, hardness, and the
+    /// channel or worker ID.
+    #[serde(skip)]
+    reported: HashSet<(BudgetScope, bool, String)>,
+}
+
+/// A scope's current total alongside its limits.
+struct ScopeTotal {
+    scope: BudgetScope,
+    /// Channel or worker ID; empty for the daily total.
+    key: String,
+    spent_usd: f64,
+    soft: Option<f64>,
+    hard: Option<f64>,
+}
+
+impl SpendTotals {
+    /// Each scope a charge applies to, daily first.
+    fn scopes(&self, scope: &SpendScope, config: &BudgetConfig) -> Vec<ScopeTotal> {
+        let mut scopes = vec![ScopeTotal {
+            scope: BudgetScope::Daily,
+            key: String::new(),
+            spent_usd: self.daily_usd,
+            soft: config.daily_soft_usd,
+            hard: config.daily_hard_usd,
+        }];
+        if let Some(channel_id) = &scope.channel_id {
+            scopes.push(ScopeTotal {
+                scope: BudgetScope::Channel,
+                key: channel_id.to_string(),
+                spent_usd: self
+                    .channels
+                    .get(&**channel_id)
+                    .copied()
+                    .unwrap_or_default(),
+                soft: config.channel_soft_usd,
+                hard: config.channel_hard_usd,
+            });
+        }
+        if let Some(worker_id) = scope.worker_id {
+            scopes.push(ScopeTotal {
+                scope: BudgetScope::Worker,
+                key: worker_id.to_string(),
+                spent_usd: self.workers.get(&worker_id).copied().unwrap_or_default(),
+                soft: config.worker_soft_usd,
+                hard: config.worker_hard_usd,
+            });
+        }
+        scopes
+    }
+}
+
+/// Tracks an agent's spend for the current day.
+#[derive(Debug)]
+pub struct SpendLedger {
+    totals: Mutex<SpendTotals>,
+    path: Option<PathBuf>,
+}
+
+impl SpendLedger {
+    /// In-memory ledger (totals reset on restart).
+    pub fn new() -> Self {
+        Self {
+            totals: Mutex::new(SpendTotals {
+                day: current_day(),
+                ..Default::default()
+            }),
+            path: None,
+        }
+    }
+
+    /// Ledger backed by `spend.json` in the given directory.
+    pub fn load(data_dir: &std::path::Path) -> Self {
+        let path = data_dir.join(SPEND_FILE_NAME);
+        let totals = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|error| {
+                tracing::warn!(%error, path = %path.display(), "invalid spend file, starting fresh");
+                SpendTotals::default()
+            }),
+            Err(_) => SpendTotals::default(),
+        };
+
+        Self {
+            totals: Mutex::new(totals),
+            path: Some(path),
+        }
+    }
+
+    /// Add a completion's cost and return the limits it crossed for the
+    /// first time today.
+    pub async fn record(
+        &self,
+        scope: &SpendScope,
+        cost_usd: f64,
+        config: &BudgetConfig,
+    ) -> Vec<BudgetCrossing> {
+        let (crossings, snapshot) = {
+            let mut totals = self.totals.lock().await;
+            roll_over(&mut totals);
+            totals.daily_usd += cost_usd;
+            if let Some(channel_id) = &scope.channel_id {
+                *totals.channels.entry(channel_id.to_string()).or_default() += cost_usd;
+            }
+            if let Some(worker_id) = scope.worker_id {
+                *totals.workers.entry(worker_id).or_default() += cost_usd;
+            }
+
+            let mut crossings = Vec::new();
+            for total in totals.scopes(scope, config) {
+                for (hard, limit) in [(false, total.soft), (true, total.hard)] {
+                    if let Some(limit_usd) = limit
+                        && total.spent_usd >= limit_usd
+                        && totals
+                            .reported
+                            .insert((total.scope, hard, total.key.clone()))
+                    {
+                        crossings.push(BudgetCrossing {
+                            scope: total.scope,
+                            hard,
+                            spent_usd: total.spent_usd,
+                            limit_usd,
+                        });
+                    }
+                }
+            }
+            (crossings, serde_json::to_string(&*totals))
+        };
+
+        if let Some(path) = &self.path
+            && let Ok(snapshot) = snapshot
+            && let Err(error) = tokio::fs::write(path, snapshot).await
+        {
+            tracing::warn!(%error, path = %path.display(), "failed to persist spend totals");
+        }
+
+        crossings
+    }
+
+    /// The first hard limit the scope has reached, checking the daily total
+    /// first, then the channel, then the worker.
+    pub async fn hard_limit_reached(
+        &self,
+        scope: &SpendScope,
+        config: &BudgetConfig,
+    ) -> Option<BudgetCrossing> {
+        let mut totals = self.totals.lock().await;
+        roll_over(&mut totals);
+        totals.scopes(scope, config).into_iter().find_map(|total| {
+            let limit_usd = total.hard.filter(|limit| total.spent_usd >= *limit)?;
+            Some(BudgetCrossing {
+                scope: total.scope,
+                hard: true,
+                spent_usd: total.spent_usd,
+                limit_usd,
+            })
+        })
+    }
+
+    /// Today's spend for every scope against the configured limits.
+    pub async fn status(&self, config: &BudgetConfig) -> BudgetStatus {
+        let mut totals = self.totals.lock().await;
+        roll_over(&mut totals);
+
+        BudgetStatus {
+            day: totals.day.clone(),
+            daily: ScopeStatus::new(
+                totals.daily_usd,
+                config.daily_soft_usd,
+                config.daily_hard_usd,
+            ),
+            channels: totals
+                .channels
+                .iter()
+                .map(|(channel_id, spent)| {
+                    let status =
+                        ScopeStatus::new(*spent, config.channel_soft_usd, config.channel_hard_usd);
+                    (channel_id.clone(), status)
+                })
+                .collect(),
+            workers: totals
+                .workers
+                .iter()
+                .map(|(worker_id, spent)| {
+                    let status =
+                        ScopeStatus::new(*spent, config.worker_soft_usd, config.worker_hard_usd);
+                    (worker_id.to_string(), status)
+                })
+                .collect(),
+            downgrade_model: config.downgrade_model.clone(),
+        }
+    }
+}
+
+impl Default for SpendLedger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Charges a model's completions to an agent's spend ledger and reports
+/// crossed limits. Attached with `SpacebotModel::with_budget`.
+#[derive(Clone)]
+pub struct BudgetGuard {
+    runtime_config: Arc<RuntimeConfig>,
+    agent_id: AgentId,
+    event_tx: tokio::sync::broadcast::Sender<ProcessEvent>,
+    scope: SpendScope,
+}
+
+impl BudgetGuard {
+    pub fn new(
+        runtime_config: Arc<RuntimeConfig>,
+        agent_id: AgentId,
+        event_tx: tokio::sync::broadcast::Sender<ProcessEvent>,
+        scope: SpendScope,
+    ) -> Self {
+        Self {
+            runtime_config,
+            agent_id,
+            event_tx,
+            scope,
+        }
+    }
+
+    /// Charge a completion's estimated cost to this guard's scope.
+    pub async fn record(&self, model_name: &str, usage: &rig::completion::Usage) {
+        let cost = crate::llm::pricing::estimate_cost(
+            model_name,
+            usage.input_tokens,
+            usage.output_tokens,
+            usage.cached_input_tokens,
+        );
+        if cost <= 0.0 {
+            return;
+        }
+
+        let config = self.runtime_config.budget.load();
+        let crossings = self
+            .runtime_config
+            .spend
+            .record(&self.scope, cost, &config)
+            .await;
+        for crossing in crossings {
+            tracing::warn!(
+                agent_id = %self.agent_id,
+                scope = crossing.scope.as_str(),
+                hard = crossing.hard,
+                spent_usd = crossing.spent_usd,
+                limit_usd = crossing.limit_usd,
+                "spend limit reached"
+            );
+            self.event_tx
+                .send(ProcessEvent::BudgetWarning {
+                    agent_id: self.agent_id.clone(),
+                    scope: crossing.scope.as_str().to_string(),
+                    channel_id: self.scope.channel_id.clone(),
+                    worker_id: self.scope.worker_id,
+                    spent_usd: crossing.spent_usd,
+                    limit_usd: crossing.limit_usd,
+                    hard: crossing.hard,
+                })
+                .ok();
+        }
+    }
+
+    /// The hard limit this guard's scope has reached, if any.
+    pub async fn hard_limit_reached(&self) -> Option<BudgetCrossing> {
+        let config = self.runtime_config.budget.load();
+        self.runtime_config
+            .spend
+            .hard_limit_reached(&self.scope, &config)
+            .await
+    }
+
+    /// Model to route to while a hard limit is reached.
+    pub fn downgrade_model(&self) -> Option<String> {
+        self.runtime_config.budget.load().downgrade_model.clone()
+    }
+}
+
+fn current_day() -> String {
+    chrono::Utc::now().format("%Y-%m-%d").to_string()
+}
+
+/// Reset totals when the UTC day changes.
+fn roll_over(totals: &mut SpendTotals) {
+    let day = current_day();
+    if totals.day != day {
+        *totals = SpendTotals {
+            day,
+            ..Default::default()
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> BudgetConfig {
+        BudgetConfig {
+            daily_soft_usd: Some(1.0),
+            daily_hard_usd: Some(2.0),
+            channel_hard_usd: Some(0.5),
+            worker_soft_usd: Some(0.25),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_each_crossing_once() {
+        let ledger = SpendLedger::new();
+        let config = limits();
+        let channel = SpendScope::channel("discord:1");
+        let worker = SpendScope::worker(uuid::Uuid::new_v4(), Some(Arc::from("discord:1")));
+
+        let crossings = ledger.record(&worker, 0.3, &config).await;
+        assert_eq!(crossings.len(), 1);
+        assert_eq!(crossings[0].scope, BudgetScope::Worker);
+        assert!(!crossings[0].hard);
+
+        let crossings = ledger.record(&channel, 0.3, &config).await;
+        assert_eq!(crossings.len(), 1);
+        assert_eq!(crossings[0].scope, BudgetScope::Channel);
+        assert!(crossings[0].hard);
+        assert!((crossings[0].spent_usd - 0.6).abs() < 1e-9);
+
+        assert!(ledger.record(&channel, 0.1, &config).await.is_empty());
+
+        let crossings = ledger.record(&SpendScope::agent(), 1.5, &config).await;
+        let daily: Vec<_> = crossings.iter().map(|crossing| crossing.hard).collect();
+        assert_eq!(daily, vec![false, true]);
+    }
+
+    #[tokio::test]
+    async fn hard_limit_checks_daily_before_channel() {
+        let ledger = SpendLedger::new();
+        let config = limits();
+        let channel = SpendScope::channel("discord:1");
+
+        assert_eq!(ledger.hard_limit_reached(&channel, &config).await, None);
+        ledger.record(&channel, 0.5, &config).await;
+        let reached = ledger.hard_limit_reached(&channel, &config).await.unwrap();
+        assert_eq!(reached.scope, BudgetScope::Channel);
+        assert_eq!(
+            ledger
+                .hard_limit_reached(&SpendScope::channel("discord:2"), &config)
+                .await,
+            None
+        );
+
+        ledger.record(&SpendScope::agent(), 2.0, &config).await;
+        let reached = ledger.hard_limit_reached(&channel, &config).await.unwrap();
+        assert_eq!(reached.scope, BudgetScope::Daily);
+        assert!(ledger.status(&config).await.daily.hard_exceeded);
+    }
+
+    #[test]
+    fn stale_day_is_reset() {
+        let mut totals = SpendTotals {
+            day: "2000-01-01".to_string(),
+            daily_usd: 3.0,
+            channels: HashMap::from([("discord:1".to_string(), 1.0)]),
+            ..Default::default()
+        };
+        totals
+            .reported
+            .insert((BudgetScope::Daily, true, String::new()));
+        roll_over(&mut totals);
+        assert_eq!(totals.daily_usd, 0.0);
+        assert!(totals.channels.is_empty());
+        assert!(totals.reported.is_empty());
+    }
+}
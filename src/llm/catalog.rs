@@ -0,0 +1,173 @@
+//! Live model lists fetched from providers.
+//!
+//! The dashboard's model pickers are normally fed by the models.dev catalog,
+//! which lags behind new releases and knows nothing of custom endpoints. This
+//! asks the provider which models the configured credentials can use, through
+//! the same model list endpoint as the health probe (or Ollama's native
+//! `/api/tags`).
+
+use crate::llm::LlmManager;
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const LIST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Price of a model in USD per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ModelPrice {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+/// A model as reported by the provider's model list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListedModel {
+    /// Model ID without the provider prefix.
+    pub id: String,
+    pub name: Option<String>,
+    pub context_window: Option<u64>,
+    pub pricing: Option<ModelPrice>,
+}
+
+/// List the models a provider offers with the configured credentials.
+pub async fn list_provider_models(
+    llm_manager: &LlmManager,
+    provider_id: &str,
+) -> anyhow::Result<Vec<ListedModel>> {
+    if provider_id == "ollama" {
+        let models = llm_manager.ollama_client()?.list_models().await?;
+        return Ok(models
+            .into_iter()
+            .map(|model| ListedModel {
+                id: model.name,
+                name: None,
+                context_window: None,
+                pricing: None,
+            })
+            .collect());
+    }
+
+    let provider = llm_manager.resolve_provider(provider_id).await?;
+    let response = crate::llm::probe::model_list_request(llm_manager.http_client(), &provider)
+        .timeout(LIST_TIMEOUT)
+        .send()
+        .await
+        .with_context(|| format!("failed to reach provider '{provider_id}'"))?
+        .error_for_status()
+        .with_context(|| format!("provider '{provider_id}' rejected the model list request"))?;
+    let body = response.text().await.context("failed to read model list")?;
+    parse_model_list(&body)
+}
+
+/// Model list bodies. OpenAI, Anthropic, OpenRouter, and Gemini wrap the list
+/// in `data`; a few OpenAI-compatible hosts return a bare array.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ModelListBody {
+    Wrapped { data: Vec<RawModel> },
+    Bare(Vec<RawModel>),
+}
+
+#[derive(Deserialize)]
+struct RawModel {
+    id: String,
+    /// `name` on OpenRouter, `display_name` on Anthropic.
+    #[serde(default, alias = "display_name")]
+    name: Option<String>,
+    /// `context_length` on OpenRouter, `context_window` on Groq,
+    /// `max_context_length` on Mistral.
+    #[serde(default, alias = "context_window", alias = "max_context_length")]
+    context_length: Option<u64>,
+    /// OpenRouter only: USD per token, as decimal strings.
+    #[serde(default)]
+    pricing: Option<RawPricing>,
+}
+
+#[derive(Deserialize)]
+struct RawPricing {
+    prompt: Option<String>,
+    completion: Option<String>,
+}
+
+fn parse_model_list(body: &str) -> anyhow::Result<Vec<ListedModel>> {
+    let models = match serde_json::from_str(body).context("unrecognized model list format")? {
+        ModelListBody::Wrapped { data } => data,
+        ModelListBody::Bare(models) => models,
+    };
+
+    let per_million = |price: &Option<String>| {
+        price
+            .as_deref()
+            .and_then(|price| price.parse::<f64>().ok())
+            // OpenRouter reports -1 for routers with variable pricing.
+            .filter(|price| *price >= 0.0)
+            .map(|price| price * 1_000_000.0)
+    };
+
+    let mut listed: Vec<ListedModel> = models
+        .into_iter()
+        .map(|model| {
+            let pricing = model.pricing.and_then(|pricing| {
+                Some(ModelPrice {
+                    input_per_million: per_million(&pricing.prompt)?,
+                    output_per_million: per_million(&pricing.completion)?,
+                })
+            });
+            ListedModel {
+                // Gemini's OpenAI-compatible endpoint prefixes IDs with `models/`.
+                id: model
+                    .id
+                    .strip_prefix("models/")
+                    .map(str::to_string)
+                    .unwrap_or(model.id),
+                name: model.name.filter(|name| !name.is_empty()),
+                context_window: model.context_length,
+                pricing,
+            }
+        })
+        .collect();
+    listed.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(listed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_provider_model_lists() {
+        let openrouter = r#"{"data": [{
+            "id": "anthropic/claude-sonnet-4",
+            "name": "Anthropic: Claude Sonnet 4",
+            "context_length": 200000,
+            "pricing": {"prompt": "0.000003", "completion": "0.000015"}
+        }, {
+            "id": "openrouter/auto",
+            "name": "Auto Router",
+            "context_length": 2000000,
+            "pricing": {"prompt": "-1", "completion": "-1"}
+        }]}"#;
+        let models = parse_model_list(openrouter).unwrap();
+        assert_eq!(models[0].context_window, Some(200000));
+        let price = models[0].pricing.unwrap();
+        assert!((price.input_per_million - 3.0).abs() < 1e-9);
+        assert!((price.output_per_million - 15.0).abs() < 1e-9);
+        assert_eq!(models[1].pricing, None);
+
+        let anthropic = r#"{"data": [{"id": "claude-opus-4-1", "display_name": "Claude Opus 4.1", "type": "model"}], "has_more": false}"#;
+        let models = parse_model_list(anthropic).unwrap();
+        assert_eq!(models[0].name.as_deref(), Some("Claude Opus 4.1"));
+
+        let gemini =
+            r#"{"object": "list", "data": [{"id": "models/gemini-2.5-pro", "object": "model"}]}"#;
+        assert_eq!(parse_model_list(gemini).unwrap()[0].id, "gemini-2.5-pro");
+
+        let bare = r#"[{"id": "meta-llama/Llama-3.3-70B", "context_window": 131072}]"#;
+        assert_eq!(
+            parse_model_list(bare).unwrap()[0].context_window,
+            Some(131072)
+        );
+    }
+}
@@ -0,0 +1,439 @@
+//! Opt-in capture of raw provider requests and responses for debugging.
+//!
+//! When enabled, the last N exchanges of each process (channel, branch,
+//! worker, …) with LLM providers are kept in memory so provider-specific
+//! formatting failures can be inspected through the API without a packet
+//! sniffer. Rings are per process so a busy channel can't push out everyone
+//! else's exchanges. Nothing is written to disk. Captured payloads are
+//! redacted before they are stored:
+//!
+//! - Values under credential-like JSON keys (`api_key`, `authorization`, …).
+//! - The provider's configured API key wherever it appears verbatim.
+//! - Known API key formats (via `secrets::scrub::scrub_leaks`).
+//! - Email addresses and phone numbers.
+//!
+//! Capture is off by default. Set `SPACEBOT_PROVIDER_DEBUG_CAPTURE=<N>` to
+//! enable it at startup, or toggle it at runtime via
+//! `PUT /api/providers/debug/captures`.
+
+use regex::Regex;
+use serde::Serialize;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// Env var that enables capture at startup with the given buffer size.
+pub const DEBUG_CAPTURE_ENV: &str = "SPACEBOT_PROVIDER_DEBUG_CAPTURE";
+
+/// Default number of exchanges kept per process when capture is enabled
+/// without a size.
+pub const DEFAULT_CAPACITY: usize = 20;
+
+/// Upper bound on the per-process buffer size — payloads can be large.
+pub const MAX_CAPACITY: usize = 200;
+
+/// Most processes with a ring at once. Past this, the ring that was written
+/// to least recently is dropped.
+const MAX_PROCESSES: usize = 32;
+
+/// Raw response bodies longer than this are truncated before storage.
+const MAX_RESPONSE_CHARS: usize = 256 * 1024;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// JSON keys whose values are always redacted (compared case-insensitively,
+/// ignoring `-` and `_`).
+const SENSITIVE_KEYS: &[&str] = &[
+    "apikey",
+    "authorization",
+    "xapikey",
+    "accesstoken",
+    "refreshtoken",
+    "token",
+    "password",
+    "secret",
+    "clientsecret",
+];
+
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").expect("hardcoded regex")
+});
+
+static PHONE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:\+\d{1,3}[\s.-]?)?\(?\d{3}\)?[\s.-]\d{3}[\s.-]\d{4}").expect("hardcoded regex")
+});
+
+/// One captured provider exchange.
+#[derive(Debug, Clone, Serialize)]
+pub struct CapturedExchange {
+    pub id: u64,
+    /// `channel`, `branch`, `worker`, `cortex`, …
+    pub process_type: String,
+    /// Branch or worker ID; the channel ID for other channel-scoped
+    /// processes, otherwise the agent ID.
+    pub process_id: String,
+    pub captured_at: String,
+    pub model: String,
+    pub endpoint: String,
+    pub status: u16,
+    pub duration_ms: u64,
+    /// Redacted JSON request body.
+    pub request: serde_json::Value,
+    /// Redacted response body (JSON when parseable, otherwise raw text —
+    /// e.g. SSE streams).
+    pub response: serde_json::Value,
+}
+
+/// An exchange as observed by the caller, before redaction.
+pub struct ExchangeRecord<'a> {
+    pub process_type: &'a str,
+    pub process_id: &'a str,
+    pub model: &'a str,
+    pub endpoint: &'a str,
+    pub status: u16,
+    pub duration: std::time::Duration,
+    pub request: serde_json::Value,
+    pub response_text: &'a str,
+    /// The provider credential used, redacted wherever it appears.
+    pub api_key: Option<&'a str>,
+}
+
+/// Ring buffers of recent provider exchanges, one per process.
+#[derive(Debug)]
+pub struct DebugCapture {
+    enabled: AtomicBool,
+    next_id: AtomicU64,
+    buffer: Mutex<CaptureBuffer>,
+}
+
+#[derive(Debug)]
+struct CaptureBuffer {
+    /// Exchanges kept per process.
+    capacity: usize,
+    /// Keyed by `(process_type, process_id)`.
+    rings: HashMap<(String, String), VecDeque<CapturedExchange>>,
+}
+
+impl CaptureBuffer {
+    fn len(&self) -> usize {
+        self.rings.values().map(VecDeque::len).sum()
+    }
+}
+
+impl DebugCapture {
+    /// Disabled capture buffer.
+    pub fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            next_id: AtomicU64::new(1),
+            buffer: Mutex::new(CaptureBuffer {
+                capacity: DEFAULT_CAPACITY,
+                rings: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Capture buffer configured from `SPACEBOT_PROVIDER_DEBUG_CAPTURE`.
+    pub fn from_env() -> Self {
+        let capture = Self::new();
+        if let Ok(value) = std::env::var(DEBUG_CAPTURE_ENV) {
+            match value.trim().parse::<usize>() {
+                Ok(0) => {}
+                Ok(capacity) => capture.configure(true, Some(capacity)),
+                Err(_) if matches!(value.trim(), "true" | "on") => capture.configure(true, None),
+                Err(_) => {
+                    tracing::warn!(value = %value, "invalid {DEBUG_CAPTURE_ENV} value, expected a buffer size");
+                }
+            }
+        }
+        capture
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Enable or disable capture and optionally resize the per-process
+    /// buffers. Disabling clears anything already captured.
+    pub fn configure(&self, enabled: bool, capacity: Option<usize>) {
+        let mut buffer = self.buffer.lock().expect("debug capture lock poisoned");
+        if let Some(capacity) = capacity {
+            buffer.capacity = capacity.clamp(1, MAX_CAPACITY);
+        }
+        if !enabled {
+            buffer.rings.clear();
+        }
+        let capacity = buffer.capacity;
+        for ring in buffer.rings.values_mut() {
+            while ring.len() > capacity {
+                ring.pop_front();
+            }
+        }
+        self.enabled.store(enabled, Ordering::Relaxed);
+        if enabled {
+            tracing::warn!(
+                capacity = buffer.capacity,
+                "provider debug capture enabled — raw (redacted) LLM payloads are held in memory"
+            );
+        }
+    }
+
+    /// Current per-process buffer size.
+    pub fn capacity(&self) -> usize {
+        self.buffer
+            .lock()
+            .expect("debug capture lock poisoned")
+            .capacity
+    }
+
+    /// Redact and store an exchange. No-op when capture is disabled.
+    pub fn record(&self, exchange: ExchangeRecord<'_>) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let api_key = exchange.api_key.filter(|key| key.len() >= 8);
+        let mut request = exchange.request;
+        redact_value(&mut request, api_key);
+
+        let response_text = truncate(exchange.response_text, MAX_RESPONSE_CHARS);
+        let mut response = serde_json::from_str(response_text)
+            .unwrap_or_else(|_| serde_json::Value::String(response_text.to_string()));
+        redact_value(&mut response, api_key);
+
+        let entry = CapturedExchange {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            process_type: exchange.process_type.to_string(),
+            process_id: exchange.process_id.to_string(),
+            captured_at: chrono::Utc::now().to_rfc3339(),
+            model: exchange.model.to_string(),
+            endpoint: redact_text(exchange.endpoint, api_key),
+            status: exchange.status,
+            duration_ms: exchange.duration.as_millis() as u64,
+            request,
+            response,
+        };
+
+        let key = (entry.process_type.clone(), entry.process_id.clone());
+        let mut buffer = self.buffer.lock().expect("debug capture lock poisoned");
+        if !buffer.rings.contains_key(&key) && buffer.rings.len() >= MAX_PROCESSES {
+            let stalest = buffer
+                .rings
+                .iter()
+                .min_by_key(|(_, ring)| ring.back().map_or(0, |entry| entry.id))
+                .map(|(key, _)| key.clone());
+            if let Some(stalest) = stalest {
+                buffer.rings.remove(&stalest);
+            }
+        }
+        let capacity = buffer.capacity;
+        let ring = buffer.rings.entry(key).or_default();
+        ring.push_back(entry);
+        while ring.len() > capacity {
+            ring.pop_front();
+        }
+    }
+
+    /// Captured exchanges, newest first. With a `process_id`, only that
+    /// process's exchanges (for a channel ID, that includes channel-scoped
+    /// processes without their own ID, like the compactor).
+    pub fn entries(&self, process_id: Option<&str>) -> Vec<CapturedExchange> {
+        let buffer = self.buffer.lock().expect("debug capture lock poisoned");
+        let mut entries: Vec<CapturedExchange> = buffer
+            .rings
+            .iter()
+            .filter(|((_, id), _)| process_id.is_none_or(|process_id| id == process_id))
+            .flat_map(|(_, ring)| ring.iter().cloned())
+            .collect();
+        entries.sort_unstable_by(|a, b| b.id.cmp(&a.id));
+        entries
+    }
+
+    /// Drop all captured exchanges. Returns how many were removed.
+    pub fn clear(&self) -> usize {
+        let mut buffer = self.buffer.lock().expect("debug capture lock poisoned");
+        let removed = buffer.len();
+        buffer.rings.clear();
+        removed
+    }
+}
+
+impl Default for DebugCapture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_sensitive_key(key: &str) -> bool {
+    let normalized: String = key
+        .chars()
+        .filter(|character| *character != '-' && *character != '_')
+        .flat_map(char::to_lowercase)
+        .collect();
+    SENSITIVE_KEYS.contains(&normalized.as_str())
+}
+
+/// Redact credentials and PII in a JSON value in place.
+fn redact_value(value: &mut serde_json::Value, api_key: Option<&str>) {
+    match value {
+        serde_json::Value::Object(object) => {
+            for (key, child) in object.iter_mut() {
+                if is_sensitive_key(key) && !child.is_null() {
+                    *child = serde_json::Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(child, api_key);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                redact_value(item, api_key);
+            }
+        }
+        serde_json::Value::String(text) => {
+            *text = redact_text(text, api_key);
+        }
+        _ => {}
+    }
+}
+
+fn redact_text(text: &str, api_key: Option<&str>) -> String {
+    let mut redacted = match api_key {
+        Some(key) => text.replace(key, REDACTED),
+        None => text.to_string(),
+    };
+    redacted = crate::secrets::scrub::scrub_leaks(&redacted);
+    redacted = EMAIL.replace_all(&redacted, "[EMAIL]").into_owned();
+    PHONE.replace_all(&redacted, "[PHONE]").into_owned()
+}
+
+fn truncate(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((index, _)) => &text[..index],
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(capture: &DebugCapture, model: &str) {
+        record_for(capture, "channel:one", model);
+    }
+
+    fn record_for(capture: &DebugCapture, process_id: &str, model: &str) {
+        capture.record(ExchangeRecord {
+            process_type: "channel",
+            process_id,
+            model,
+            endpoint: "https://api.example.com/v1/chat/completions",
+            status: 200,
+            duration: std::time::Duration::from_millis(5),
+            request: serde_json::json!({"model": model}),
+            response_text: "{}",
+            api_key: None,
+        });
+    }
+
+    #[test]
+    fn disabled_capture_stores_nothing() {
+        let capture = DebugCapture::new();
+        record(&capture, "a/model");
+        assert!(capture.entries(None).is_empty());
+    }
+
+    #[test]
+    fn keeps_only_the_newest_entries() {
+        let capture = DebugCapture::new();
+        capture.configure(true, Some(2));
+        record(&capture, "a/one");
+        record(&capture, "a/two");
+        record(&capture, "a/three");
+
+        let models: Vec<String> = capture
+            .entries(None)
+            .into_iter()
+            .map(|entry| entry.model)
+            .collect();
+        assert_eq!(models, vec!["a/three", "a/two"]);
+    }
+
+    #[test]
+    fn busy_process_keeps_its_own_ring() {
+        let capture = DebugCapture::new();
+        capture.configure(true, Some(2));
+        record_for(&capture, "channel:quiet", "a/quiet");
+        for _ in 0..5 {
+            record_for(&capture, "channel:busy", "a/busy");
+        }
+
+        let quiet = capture.entries(Some("channel:quiet"));
+        assert_eq!(quiet.len(), 1);
+        assert_eq!(quiet[0].model, "a/quiet");
+        assert_eq!(capture.entries(Some("channel:busy")).len(), 2);
+
+        let models: Vec<String> = capture
+            .entries(None)
+            .into_iter()
+            .map(|entry| entry.model)
+            .collect();
+        assert_eq!(models, vec!["a/busy", "a/busy", "a/quiet"]);
+    }
+
+    #[test]
+    fn drops_the_stalest_ring_past_the_process_limit() {
+        let capture = DebugCapture::new();
+        capture.configure(true, None);
+        for index in 0..=MAX_PROCESSES {
+            record_for(&capture, &format!("worker-{index}"), "a/model");
+        }
+
+        assert!(capture.entries(Some("worker-0")).is_empty());
+        assert_eq!(capture.entries(None).len(), MAX_PROCESSES);
+    }
+
+    #[test]
+    fn redacts_credentials_and_pii() {
+        let capture = DebugCapture::new();
+        capture.configure(true, None);
+        capture.record(ExchangeRecord {
+            process_type: "channel",
+            process_id: "channel:one",
+            model: "custom/model",
+            endpoint: "https://api.example.com/v1?key=custom-secret-key",
+            status: 400,
+            duration: std::time::Duration::from_millis(5),
+            request: serde_json::json!({
+                "api_key": "anything",
+                "messages": [{
+                    "role": "user",
+                    "content": "mail me at jane.doe@example.com or +1 (555) 123-4567, key custom-secret-key"
+                }]
+            }),
+            response_text: r#"{"error": {"message": "bad key sk-ant-REDACTED"}}"#,
+            api_key: Some("custom-secret-key"),
+        });
+
+        let entry = &capture.entries(None)[0];
+        assert_eq!(entry.request["api_key"], REDACTED);
+        let content = entry.request["messages"][0]["content"].as_str().unwrap();
+        assert!(!content.contains("jane.doe@example.com"));
+        assert!(!content.contains("555"));
+        assert!(!content.contains("custom-secret-key"));
+        assert!(!entry.endpoint.contains("custom-secret-key"));
+        let message = entry.response["error"]["message"].as_str().unwrap();
+        assert!(!message.contains("sk-ant-"));
+    }
+
+    #[test]
+    fn disabling_clears_entries() {
+        let capture = DebugCapture::new();
+        capture.configure(true, None);
+        record(&capture, "a/model");
+        capture.configure(false, None);
+        assert!(capture.entries(None).is_empty());
+    }
+}
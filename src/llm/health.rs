@@ -0,0 +1,192 @@
+//! Rolling latency and error-rate tracking per model.
+//!
+//! Every direct completion attempt feeds an exponentially weighted moving
+//! average of latency (successes only) and error rate. Latency-aware routing
+//! uses these to rank models that are interchangeable — the same model
+//! reached through different gateways — and send traffic to the fastest
+//! healthy one.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+/// Weight given to the newest sample in the moving averages.
+const EWMA_ALPHA: f64 = 0.2;
+
+/// Samples required before a model's averages are trusted for ranking.
+/// Models with fewer samples are tried first so every option gets measured.
+pub const MIN_SAMPLES: u64 = 3;
+
+/// Error rate at or above which a model is considered unhealthy.
+pub const UNHEALTHY_ERROR_RATE: f64 = 0.5;
+
+/// Rolling health statistics for one model.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct ModelHealth {
+    /// Moving average latency of successful requests, in milliseconds.
+    pub latency_ms: f64,
+    /// Moving average of failures (0.0 = always succeeds, 1.0 = always fails).
+    pub error_rate: f64,
+    /// Total attempts observed.
+    pub samples: u64,
+}
+
+impl ModelHealth {
+    fn record(&mut self, latency: Duration, success: bool) {
+        let failure = if success { 0.0 } else { 1.0 };
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+
+        if self.samples == 0 {
+            self.error_rate = failure;
+            if success {
+                self.latency_ms = latency_ms;
+            }
+        } else {
+            self.error_rate += EWMA_ALPHA * (failure - self.error_rate);
+            if success {
+                self.latency_ms = if self.latency_ms == 0.0 {
+                    latency_ms
+                } else {
+                    self.latency_ms + EWMA_ALPHA * (latency_ms - self.latency_ms)
+                };
+            }
+        }
+        self.samples += 1;
+    }
+
+    /// Whether the model has recently been failing most requests.
+    pub fn is_healthy(&self) -> bool {
+        self.error_rate < UNHEALTHY_ERROR_RATE
+    }
+}
+
+/// Shared per-model health statistics.
+#[derive(Debug, Default)]
+pub struct HealthTracker {
+    models: RwLock<HashMap<String, ModelHealth>>,
+}
+
+impl HealthTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the outcome of a single request attempt.
+    pub async fn record(&self, model_name: &str, latency: Duration, success: bool) {
+        self.models
+            .write()
+            .await
+            .entry(model_name.to_string())
+            .or_default()
+            .record(latency, success);
+    }
+
+    /// Current statistics for a model, if it has been used.
+    pub async fn get(&self, model_name: &str) -> Option<ModelHealth> {
+        self.models.read().await.get(model_name).copied()
+    }
+
+    /// Statistics for every model observed so far.
+    pub async fn snapshot(&self) -> HashMap<String, ModelHealth> {
+        self.models.read().await.clone()
+    }
+
+    /// Order candidates by preference: unmeasured models first (in the given
+    /// order), then healthy models by latency, then unhealthy models by
+    /// error rate.
+    pub async fn rank(&self, candidates: &[String]) -> Vec<String> {
+        let models = self.models.read().await;
+        rank_candidates(candidates, &models)
+    }
+}
+
+fn rank_candidates(candidates: &[String], models: &HashMap<String, ModelHealth>) -> Vec<String> {
+    let mut unmeasured = Vec::new();
+    let mut healthy = Vec::new();
+    let mut unhealthy = Vec::new();
+
+    for candidate in candidates {
+        match models.get(candidate) {
+            Some(health) if health.samples >= MIN_SAMPLES => {
+                if health.is_healthy() {
+                    healthy.push((candidate.clone(), health.latency_ms));
+                } else {
+                    unhealthy.push((candidate.clone(), health.error_rate));
+                }
+            }
+            _ => unmeasured.push(candidate.clone()),
+        }
+    }
+
+    healthy.sort_by(|left, right| left.1.total_cmp(&right.1));
+    unhealthy.sort_by(|left, right| left.1.total_cmp(&right.1));
+
+    unmeasured
+        .into_iter()
+        .chain(healthy.into_iter().map(|(name, _)| name))
+        .chain(unhealthy.into_iter().map(|(name, _)| name))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measured(latency_ms: f64, error_rate: f64) -> ModelHealth {
+        ModelHealth {
+            latency_ms,
+            error_rate,
+            samples: MIN_SAMPLES,
+        }
+    }
+
+    #[test]
+    fn ranks_healthy_models_by_latency() {
+        let models = HashMap::from([
+            ("direct/model".to_string(), measured(900.0, 0.0)),
+            ("gateway/model".to_string(), measured(300.0, 0.1)),
+        ]);
+        let ranked = rank_candidates(
+            &["direct/model".to_string(), "gateway/model".to_string()],
+            &models,
+        );
+        assert_eq!(ranked, vec!["gateway/model", "direct/model"]);
+    }
+
+    #[test]
+    fn unhealthy_models_rank_last_and_unmeasured_first() {
+        let models = HashMap::from([
+            ("fast/broken".to_string(), measured(100.0, 0.8)),
+            ("slow/ok".to_string(), measured(2000.0, 0.0)),
+        ]);
+        let ranked = rank_candidates(
+            &[
+                "fast/broken".to_string(),
+                "slow/ok".to_string(),
+                "new/model".to_string(),
+            ],
+            &models,
+        );
+        assert_eq!(ranked, vec!["new/model", "slow/ok", "fast/broken"]);
+    }
+
+    #[tokio::test]
+    async fn tracks_moving_averages() {
+        let tracker = HealthTracker::new();
+        tracker
+            .record("a/model", Duration::from_millis(100), true)
+            .await;
+        tracker
+            .record("a/model", Duration::from_millis(200), true)
+            .await;
+        tracker
+            .record("a/model", Duration::from_secs(5), false)
+            .await;
+
+        let health = tracker.get("a/model").await.unwrap();
+        assert_eq!(health.samples, 3);
+        assert!((health.latency_ms - 120.0).abs() < 1e-6);
+        assert!((health.error_rate - 0.2).abs() < 1e-6);
+    }
+}
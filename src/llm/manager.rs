@@ -9,9 +9,17 @@
 //! `get_api_key()` calls read the new values lock-free.
 
 use crate::auth::OAuthCredentials as AnthropicOAuthCredentials;
-use crate::config::{ApiType, LlmConfig, ProviderConfig};
+use crate::config::{ApiType, LlmConfig, OAuthProviderConfig, ProviderConfig};
 use crate::error::{LlmError, Result};
 use crate::github_copilot_auth::CopilotToken;
+use crate::llm::debug_capture::DebugCapture;
+use crate::llm::health::{HealthTracker, ModelHealth};
+use crate::llm::oauth::OAuthCredentials as ProviderOAuthCredentials;
+use crate::llm::ollama::OllamaClient;
+use crate::llm::probe::{ProbeOutcome, ProviderProbe};
+use crate::llm::quota::{QuotaStatus, QuotaTracker};
+use crate::llm::response_cache::{ResponseCache, ResponseCacheKey, ResponseCacheStats};
+use crate::llm::trace::TraceStore;
 use crate::openai_auth::OAuthCredentials as OpenAiOAuthCredentials;
 
 use anyhow::Context as _;
@@ -27,7 +35,7 @@ const COPILOT_EDITOR_VERSION: &str = "vscode/1.96.2";
 /// Matches Copilot Chat extension version 0.26.7.
 const COPILOT_EDITOR_PLUGIN_VERSION: &str = "copilot-chat/0.26.7";
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 /// Manages LLM provider clients and tracks rate limit state.
@@ -44,6 +52,21 @@ pub struct LlmManager {
     openai_oauth_credentials: RwLock<Option<OpenAiOAuthCredentials>>,
     /// Cached GitHub Copilot API token (exchanged from PAT, refreshed lazily).
     copilot_token: RwLock<Option<CopilotToken>>,
+    /// Cached credentials for `[llm.oauth.<provider>]` providers, keyed by
+    /// provider ID (loaded from disk on first use, refreshed lazily).
+    oauth_credentials: RwLock<HashMap<String, ProviderOAuthCredentials>>,
+    /// Monthly token and spend totals per provider, checked against `[llm.quota]`.
+    quota_tracker: QuotaTracker,
+    /// Rolling latency and error rate per model, used by latency-aware routing.
+    health: HealthTracker,
+    /// Opt-in ring buffer of recent raw (redacted) provider exchanges.
+    debug_capture: DebugCapture,
+    /// Recent completion traces per conversation.
+    traces: TraceStore,
+    /// Opt-in cache of text responses for idempotent calls.
+    response_cache: ResponseCache,
+    /// Latest background health probe result per provider.
+    provider_probes: RwLock<HashMap<String, ProviderProbe>>,
 }
 
 impl LlmManager {
@@ -62,6 +85,13 @@ impl LlmManager {
             anthropic_oauth_credentials: RwLock::new(None),
             openai_oauth_credentials: RwLock::new(None),
             copilot_token: RwLock::new(None),
+            oauth_credentials: RwLock::new(HashMap::new()),
+            quota_tracker: QuotaTracker::new(),
+            health: HealthTracker::new(),
+            debug_capture: DebugCapture::from_env(),
+            traces: TraceStore::new(),
+            response_cache: ResponseCache::new(),
+            provider_probes: RwLock::new(HashMap::new()),
         })
     }
 
@@ -138,10 +168,17 @@ impl LlmManager {
             config: ArcSwap::from_pointee(config),
             http_client,
             rate_limited: Arc::new(RwLock::new(HashMap::new())),
-            instance_dir: Some(instance_dir),
             anthropic_oauth_credentials: RwLock::new(anthropic_oauth_credentials),
             openai_oauth_credentials: RwLock::new(openai_oauth_credentials),
             copilot_token: RwLock::new(copilot_token),
+            oauth_credentials: RwLock::new(HashMap::new()),
+            quota_tracker: QuotaTracker::load(&instance_dir),
+            health: HealthTracker::new(),
+            debug_capture: DebugCapture::from_env(),
+            traces: TraceStore::new(),
+            response_cache: ResponseCache::new(),
+            provider_probes: RwLock::new(HashMap::new()),
+            instance_dir: Some(instance_dir),
         })
     }
 
@@ -299,6 +336,120 @@ impl LlmManager {
         }
     }
 
+    /// Whether the provider is configured under `[llm.oauth.<provider>]`.
+    pub fn has_oauth_provider(&self, provider_id: &str) -> bool {
+        self.config
+            .load()
+            .oauth
+            .contains_key(&provider_id.to_lowercase())
+    }
+
+    /// The `[llm.oauth.<provider>]` settings for a provider, if configured.
+    pub fn oauth_config(&self, provider_id: &str) -> Option<OAuthProviderConfig> {
+        self.config
+            .load()
+            .oauth
+            .get(&provider_id.to_lowercase())
+            .cloned()
+    }
+
+    /// IDs of every provider configured under `[llm.oauth]`.
+    pub fn oauth_provider_ids(&self) -> Vec<String> {
+        let mut provider_ids: Vec<String> = self.config.load().oauth.keys().cloned().collect();
+        provider_ids.sort();
+        provider_ids
+    }
+
+    /// Set a provider's OAuth credentials in memory after a successful sign-in.
+    pub async fn set_oauth_credentials(&self, provider_id: &str, creds: ProviderOAuthCredentials) {
+        self.oauth_credentials
+            .write()
+            .await
+            .insert(provider_id.to_lowercase(), creds);
+    }
+
+    /// Clear a provider's OAuth credentials from memory.
+    pub async fn clear_oauth_credentials(&self, provider_id: &str) {
+        self.oauth_credentials
+            .write()
+            .await
+            .remove(&provider_id.to_lowercase());
+    }
+
+    /// Get the OAuth access token for a `[llm.oauth]` provider, loading it
+    /// from disk on first use and refreshing it when needed.
+    pub async fn get_oauth_token(&self, provider_id: &str) -> Result<Option<String>> {
+        let provider_id = provider_id.to_lowercase();
+        let config = self.config.load();
+        let Some(oauth_config) = config.oauth.get(&provider_id) else {
+            return Ok(None);
+        };
+
+        let mut creds_guard = self.oauth_credentials.write().await;
+        if !creds_guard.contains_key(&provider_id)
+            && let Some(ref instance_dir) = self.instance_dir
+        {
+            match crate::llm::oauth::load_credentials(instance_dir, &provider_id) {
+                Ok(Some(creds)) => {
+                    tracing::info!(provider = %provider_id, "loaded OAuth credentials");
+                    creds_guard.insert(provider_id.clone(), creds);
+                }
+                Ok(None) => {}
+                Err(error) => {
+                    tracing::warn!(provider = %provider_id, %error, "failed to load OAuth credentials");
+                }
+            }
+        }
+        let Some(creds) = creds_guard.get(&provider_id) else {
+            return Ok(None);
+        };
+
+        if !creds.is_expired() {
+            return Ok(Some(creds.access_token.clone()));
+        }
+
+        tracing::info!(provider = %provider_id, "OAuth access token expired, refreshing...");
+        match creds.refresh(oauth_config).await {
+            Ok(new_creds) => {
+                if let Some(ref instance_dir) = self.instance_dir
+                    && let Err(error) =
+                        crate::llm::oauth::save_credentials(instance_dir, &provider_id, &new_creds)
+                {
+                    tracing::warn!(provider = %provider_id, %error, "failed to persist refreshed OAuth credentials");
+                }
+                let token = new_creds.access_token.clone();
+                creds_guard.insert(provider_id.clone(), new_creds);
+                tracing::info!(provider = %provider_id, "OAuth token refreshed successfully");
+                Ok(Some(token))
+            }
+            Err(error) => {
+                tracing::error!(provider = %provider_id, %error, "OAuth token refresh failed");
+                Ok(Some(creds.access_token.clone()))
+            }
+        }
+    }
+
+    /// Resolve a `[llm.oauth]` provider config, authenticated with the
+    /// current access token. Falls back to a static `[llm.provider]` entry
+    /// with the same ID when nobody has signed in yet.
+    pub async fn get_oauth_provider(&self, provider_id: &str) -> Result<ProviderConfig> {
+        let token = self.get_oauth_token(provider_id).await?;
+        let config = self.config.load();
+        let oauth_config = config.oauth.get(&provider_id.to_lowercase());
+
+        match (oauth_config, token) {
+            (Some(oauth_config), Some(token)) => Ok(ProviderConfig {
+                api_type: oauth_config.api_type.clone(),
+                base_url: oauth_config.base_url.clone(),
+                api_key: token,
+                name: oauth_config.name.clone(),
+                use_bearer_auth: true,
+                extra_headers: vec![],
+            }),
+            _ => self.get_provider(provider_id),
+        }
+    }
+
     /// Get OpenAI OAuth account id (for ChatGPT Plus/Pro account scoping headers).
     pub async fn get_openai_account_id(&self) -> Option<String> {
         self.openai_oauth_credentials
@@ -434,6 +585,27 @@ impl LlmManager {
         self.config.load().ollama_base_url.clone()
     }
 
+    /// Client for the configured Ollama server's native management API.
+    pub fn ollama_client(&self) -> Result<OllamaClient> {
+        let provider = self.get_provider("ollama")?;
+        let api_key = (!provider.api_key.is_empty()).then_some(provider.api_key);
+        Ok(OllamaClient::new(
+            self.http_client.clone(),
+            &provider.base_url,
+            api_key,
+        ))
+    }
+
+    /// Recent provider exchanges captured for debugging.
+    pub fn debug_capture(&self) -> &DebugCapture {
+        &self.debug_capture
+    }
+
+    /// Recent completion traces, grouped by conversation.
+    pub fn traces(&self) -> &TraceStore {
+        &self.traces
+    }
+
     /// Get the HTTP client.
     pub fn http_client(&self) -> &reqwest::Client {
         &self.http_client
@@ -475,4 +647,157 @@ impl LlmManager {
             .await
             .retain(|_, limited_at| limited_at.elapsed().as_secs() < cooldown_secs);
     }
+
+    /// Add a completed request's token usage and estimated cost to the
+    /// provider's monthly total.
+    pub async fn record_usage(
+        &self,
+        provider: &str,
+        model_name: &str,
+        usage: &rig::completion::Usage,
+    ) {
+        let tokens = usage.input_tokens + usage.output_tokens;
+        if tokens == 0 {
+            return;
+        }
+        let cost = crate::llm::pricing::estimate_cost(
+            model_name,
+            usage.input_tokens,
+            usage.output_tokens,
+            usage.cached_input_tokens,
+        );
+        self.quota_tracker.record(provider, tokens, cost).await;
+    }
+
+    /// Write pending usage totals to disk without waiting for the debounce.
+    pub async fn flush_usage(&self) {
+        self.quota_tracker.flush().await;
+    }
+
+    /// Check if a provider has reached its configured monthly quota.
+    pub async fn is_over_quota(&self, provider: &str) -> bool {
+        let config = self.config.load();
+        let Some(quota) = config.quotas.get(provider) else {
+            return false;
+        };
+        let usage = self.quota_tracker.usage(provider).await;
+        crate::llm::quota::is_exceeded(&usage, quota)
+    }
+
+    /// Look up a cached response for an idempotent completion.
+    pub fn cached_response(
+        &self,
+        key: &ResponseCacheKey,
+    ) -> Option<rig::completion::CompletionResponse<crate::llm::model::RawResponse>> {
+        self.response_cache
+            .get(key, &self.config.load().response_cache)
+    }
+
+    /// Cache the response to an idempotent completion.
+    pub fn cache_response(
+        &self,
+        key: ResponseCacheKey,
+        response: &rig::completion::CompletionResponse<crate::llm::model::RawResponse>,
+    ) {
+        self.response_cache
+            .insert(key, response, &self.config.load().response_cache);
+    }
+
+    /// Completion responses cached for repeat prompts.
+    pub fn response_cache(&self) -> &ResponseCache {
+        &self.response_cache
+    }
+
+    /// Response cache hit statistics.
+    pub fn response_cache_stats(&self) -> ResponseCacheStats {
+        self.response_cache
+            .stats(&self.config.load().response_cache)
+    }
+
+    /// Quota status for every provider with a configured quota.
+    pub async fn quota_statuses(&self) -> HashMap<String, QuotaStatus> {
+        let config = self.config.load();
+        let mut statuses = HashMap::with_capacity(config.quotas.len());
+        for (provider, quota) in &config.quotas {
+            statuses.insert(
+                provider.clone(),
+                self.quota_tracker.status(provider, quota).await,
+            );
+        }
+        statuses
+    }
+
+    /// Record the latency and outcome of a single completion attempt.
+    pub async fn record_outcome(&self, model_name: &str, latency: Duration, success: bool) {
+        self.health.record(model_name, latency, success).await;
+    }
+
+    /// Order interchangeable models by observed latency and health.
+    pub async fn rank_by_latency(&self, candidates: &[String]) -> Vec<String> {
+        self.health.rank(candidates).await
+    }
+
+    /// Rolling health statistics for every model used so far.
+    pub async fn model_health(&self) -> HashMap<String, ModelHealth> {
+        self.health.snapshot().await
+    }
+
+    /// Interval between background provider probes, or `None` when disabled.
+    pub fn health_probe_interval(&self) -> Option<Duration> {
+        let secs = self.config.load().health_probe_interval_secs;
+        (secs > 0).then(|| Duration::from_secs(secs))
+    }
+
+    /// Resolve a provider config with credentials, including OAuth tokens.
+    pub async fn resolve_provider(&self, provider_id: &str) -> Result<ProviderConfig> {
+        match provider_id {
+            "anthropic" => self.get_anthropic_provider().await,
+            "openai" => self.get_openai_provider().await,
+            _ if self.has_oauth_provider(provider_id) => self.get_oauth_provider(provider_id).await,
+            _ => self.get_provider(provider_id),
+        }
+    }
+
+    /// Providers to probe, with credentials resolved (including OAuth).
+    pub async fn probe_targets(&self) -> Vec<(String, ProviderConfig)> {
+        let config = self.config.load();
+        let provider_ids: std::collections::BTreeSet<String> = config
+            .providers
+            .keys()
+            .chain(config.oauth.keys())
+            .cloned()
+            .collect();
+        drop(config);
+        let mut targets = Vec::with_capacity(provider_ids.len());
+        for provider_id in provider_ids {
+            match self.resolve_provider(&provider_id).await {
+                Ok(provider) => targets.push((provider_id, provider)),
+                Err(error) => {
+                    tracing::debug!(provider = %provider_id, %error, "skipping health probe");
+                }
+            }
+        }
+        targets
+    }
+
+    /// Store the result of a background health probe.
+    pub async fn record_probe(&self, provider: &str, outcome: ProbeOutcome) {
+        let mut probes = self.provider_probes.write().await;
+        let probe = outcome.into_probe(probes.get(provider));
+        probes.insert(provider.to_string(), probe);
+    }
+
+    /// Whether recent health probes say the provider is unreachable.
+    pub async fn is_provider_down(&self, provider: &str) -> bool {
+        self.provider_probes
+            .read()
+            .await
+            .get(provider)
+            .is_some_and(ProviderProbe::is_down)
+    }
+
+    /// Latest probe result for every probed provider.
+    pub async fn provider_probes(&self) -> HashMap<String, ProviderProbe> {
+        self.provider_probes.read().await.clone()
+    }
 }
@@ -1,10 +1,13 @@
 //! SpacebotModel: Custom CompletionModel implementation that routes through LlmManager.
 
 use crate::config::{ApiType, ProviderConfig};
+use crate::llm::budget::BudgetGuard;
 use crate::llm::manager::LlmManager;
+use crate::llm::response_cache::ResponseCacheKey;
 use crate::llm::routing::{
     self, MAX_FALLBACK_ATTEMPTS, MAX_RETRIES_PER_MODEL, RETRY_BASE_DELAY_MS, RoutingConfig,
 };
+use crate::llm::trace;
 
 use futures::StreamExt as _;
 use rig::completion::{self, CompletionError, CompletionModel, CompletionRequest, GetTokenUsage};
@@ -17,6 +20,7 @@ use rig::streaming::{RawStreamingChoice, RawStreamingToolCall, StreamingCompleti
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::sync::Arc;
+use tracing::Instrument as _;
 
 const STREAM_REQUEST_TIMEOUT_SECS: u64 = 30 * 60;
 
@@ -53,6 +57,10 @@ pub struct SpacebotModel {
     agent_id: Option<String>,
     process_type: Option<String>,
     worker_type: Option<String>,
+    channel_id: Option<String>,
+    process_id: Option<String>,
+    cache_responses: bool,
+    budget: Option<BudgetGuard>,
 }
 
 impl SpacebotModel {
@@ -89,6 +97,72 @@ impl SpacebotModel {
         self
     }
 
+    /// Attach the channel this model works for, for per-channel sampling and
+    /// to group its completion traces under that conversation.
+    pub fn with_channel(mut self, channel_id: impl Into<String>) -> Self {
+        self.channel_id = Some(channel_id.into());
+        self
+    }
+
+    /// Attach the id of the branch or worker this model runs for, so its
+    /// debug captures are kept apart from its channel's.
+    pub fn with_process_id(mut self, process_id: impl Into<String>) -> Self {
+        self.process_id = Some(process_id.into());
+        self
+    }
+
+    /// The `(process_type, process_id)` this model's debug captures are
+    /// filed under. Channel-scoped processes without their own id use the
+    /// channel; everything else uses the agent.
+    fn capture_process(&self) -> (String, String) {
+        let process_type = self.process_type.as_deref().unwrap_or("unknown");
+        let process_id = self
+            .process_id
+            .as_deref()
+            .or(self.channel_id.as_deref())
+            .or(self.agent_id.as_deref())
+            .unwrap_or("unknown");
+        (process_type.to_string(), process_id.to_string())
+    }
+
+    /// Allow text responses to be served from and stored in the LLM
+    /// manager's response cache. Only for calls that are safe to replay.
+    pub fn with_response_cache(mut self) -> Self {
+        self.cache_responses = true;
+        self
+    }
+
+    /// Charge completions to a spend scope and apply its hard limits.
+    pub fn with_budget(mut self, budget: BudgetGuard) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Fill sampling parameters the request leaves unset from the routing
+    /// config for this process and channel. Applied once up front so fallback
+    /// models get the same settings.
+    fn apply_sampling(&self, mut request: CompletionRequest) -> CompletionRequest {
+        let (Some(routing), Some(process_type)) = (&self.routing, &self.process_type) else {
+            return request;
+        };
+        let sampling = routing.sampling_for(process_type, self.channel_id.as_deref());
+
+        request.temperature = request.temperature.or(sampling.temperature);
+        request.max_tokens = request.max_tokens.or(sampling.max_tokens);
+        if let Some(top_p) = sampling.top_p
+            && requested_top_p(&request).is_none()
+        {
+            let mut params = request
+                .additional_params
+                .take()
+                .filter(serde_json::Value::is_object)
+                .unwrap_or_else(|| serde_json::json!({}));
+            params["top_p"] = serde_json::json!(top_p);
+            request.additional_params = Some(params);
+        }
+        request
+    }
+
     async fn provider_config_for_current_model(&self) -> Result<ProviderConfig, CompletionError> {
         let provider_id = self
             .full_model_name
@@ -117,6 +191,11 @@ impl SpacebotModel {
                 .get_github_copilot_provider()
                 .await
                 .map_err(|error| CompletionError::ProviderError(error.to_string())),
+            _ if self.llm_manager.has_oauth_provider(provider_id) => self
+                .llm_manager
+                .get_oauth_provider(provider_id)
+                .await
+                .map_err(|error| CompletionError::ProviderError(error.to_string())),
             _ => self
                 .llm_manager
                 .get_provider(provider_id)
@@ -128,6 +207,302 @@ impl SpacebotModel {
     async fn attempt_completion(
         &self,
         request: CompletionRequest,
+    ) -> Result<completion::CompletionResponse<RawResponse>, CompletionError> {
+        let system_prompt = request
+            .preamble
+            .as_deref()
+            .map(|preamble| trace::clean_text(preamble, trace::MAX_TEXT_CHARS))
+            .unwrap_or_default();
+        let prompt = request
+            .chat_history
+            .iter()
+            .last()
+            .map(|message| trace::clean_text(&message_trace_text(message), trace::MAX_TEXT_CHARS))
+            .unwrap_or_default();
+        let span = tracing::info_span!(
+            "llm.completion",
+            gen_ai.system = %self.provider,
+            gen_ai.request.model = %self.full_model_name,
+            gen_ai.prompt = tracing::field::Empty,
+            gen_ai.usage.input_tokens = tracing::field::Empty,
+            gen_ai.usage.output_tokens = tracing::field::Empty,
+            gen_ai.usage.cached_input_tokens = tracing::field::Empty,
+            gen_ai.response.tool_calls = tracing::field::Empty,
+            agent_id = self.agent_id.as_deref().unwrap_or("unknown"),
+            process_type = self.process_type.as_deref().unwrap_or("unknown"),
+            conversation_id = self.channel_id.as_deref().unwrap_or(""),
+            duration_ms = tracing::field::Empty,
+            error = tracing::field::Empty,
+        );
+
+        let started = std::time::Instant::now();
+        let result = self
+            .dispatch_completion(request)
+            .instrument(span.clone())
+            .await;
+        let elapsed = started.elapsed();
+        self.llm_manager
+            .record_outcome(&self.full_model_name, elapsed, result.is_ok())
+            .await;
+        self.trace_completion(&span, elapsed, &system_prompt, &prompt, &result);
+
+        let response = result?;
+        self.llm_manager
+            .record_usage(&self.provider, &self.full_model_name, &response.usage)
+            .await;
+        if let Some(budget) = &self.budget {
+            budget.record(&self.full_model_name, &response.usage).await;
+        }
+        Ok(response)
+    }
+
+    /// Fill in the completion span and, when this call belongs to a
+    /// conversation, keep it in the LLM manager's trace store.
+    fn trace_completion(
+        &self,
+        span: &tracing::Span,
+        elapsed: std::time::Duration,
+        system_prompt: &str,
+        prompt: &str,
+        result: &Result<completion::CompletionResponse<RawResponse>, CompletionError>,
+    ) {
+        let mut usage = completion::Usage::default();
+        let mut response_text = String::new();
+        let mut tool_calls = Vec::new();
+        let mut error = None;
+        match result {
+            Ok(response) => {
+                usage = response.usage;
+                for content in response.choice.iter() {
+                    match content {
+                        AssistantContent::Text(text) => response_text.push_str(&text.text),
+                        AssistantContent::ToolCall(call) => tool_calls.push((
+                            call.function.name.clone(),
+                            call.function.arguments.to_string(),
+                        )),
+                        _ => {}
+                    }
+                }
+            }
+            Err(completion_error) => error = Some(completion_error.to_string()),
+        }
+
+        // Recorded last so log events inside the span don't repeat the prompt.
+        span.record("gen_ai.prompt", prompt);
+        span.record("duration_ms", elapsed.as_millis() as u64);
+        span.record("gen_ai.usage.input_tokens", usage.input_tokens);
+        span.record("gen_ai.usage.output_tokens", usage.output_tokens);
+        span.record(
+            "gen_ai.usage.cached_input_tokens",
+            usage.cached_input_tokens,
+        );
+        if !tool_calls.is_empty() {
+            let names: Vec<&str> = tool_calls.iter().map(|(name, _)| name.as_str()).collect();
+            span.record("gen_ai.response.tool_calls", names.join(",").as_str());
+        }
+        if let Some(error) = &error {
+            span.record("error", error.as_str());
+        }
+
+        let Some(conversation_id) = &self.channel_id else {
+            return;
+        };
+        self.llm_manager.traces().record(trace::CompletionRecord {
+            conversation_id,
+            agent_id: self.agent_id.as_deref(),
+            process_type: self.process_type.as_deref(),
+            worker_type: self.worker_type.as_deref(),
+            model: &self.full_model_name,
+            duration: elapsed,
+            input_tokens: usage.input_tokens,
+            output_tokens: usage.output_tokens,
+            cached_input_tokens: usage.cached_input_tokens,
+            tool_calls,
+            system_prompt,
+            prompt,
+            response: &response_text,
+            error,
+        });
+    }
+
+    /// Model to switch to because a hard spend limit is reached.
+    ///
+    /// Returns the configured downgrade model, unless this already is it.
+    /// A worker over its own hard limit with nothing to downgrade to fails;
+    /// daily and channel limits without one only pause worker spawns.
+    async fn budget_downgrade(&self) -> Result<Option<String>, CompletionError> {
+        let Some(budget) = &self.budget else {
+            return Ok(None);
+        };
+        let Some(reached) = budget.hard_limit_reached().await else {
+            return Ok(None);
+        };
+
+        match budget.downgrade_model() {
+            Some(model) if model == self.full_model_name => Ok(None),
+            Some(model) => {
+                tracing::debug!(
+                    model = %self.full_model_name,
+                    downgrade = %model,
+                    scope = reached.scope.as_str(),
+                    "spend limit reached, routing to downgrade model"
+                );
+                Ok(Some(model))
+            }
+            None if reached.scope == crate::llm::budget::BudgetScope::Worker => {
+                Err(CompletionError::ProviderError(format!(
+                    "worker spend limit reached (${:.2} of ${:.2})",
+                    reached.spent_usd, reached.limit_usd
+                )))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// This model's settings pointed at another model. Process context (for
+    /// tracing) and the budget guard carry over; routing and caching don't.
+    fn switch_to(&self, model_name: &str) -> SpacebotModel {
+        if model_name == self.full_model_name {
+            return self.clone();
+        }
+        let mut model = SpacebotModel::make(&self.llm_manager, model_name);
+        model.agent_id = self.agent_id.clone();
+        model.process_type = self.process_type.clone();
+        model.worker_type = self.worker_type.clone();
+        model.channel_id = self.channel_id.clone();
+        model.process_id = self.process_id.clone();
+        model.budget = self.budget.clone();
+        model
+    }
+
+    /// Decide which model to try first and what to fall back to.
+    ///
+    /// Normally that's this model followed by its fallback chain. In
+    /// latency-aware mode the model and its equivalents are ranked by observed
+    /// latency and health; the best one goes first and the rest are tried
+    /// before the configured fallbacks.
+    async fn routing_order(&self, routing: &RoutingConfig) -> (String, Vec<String>) {
+        let fallbacks = routing.get_fallbacks(&self.full_model_name);
+        let equivalents = routing.get_equivalents(&self.full_model_name);
+        if !routing.latency_aware || equivalents.is_empty() {
+            return (self.full_model_name.clone(), fallbacks.to_vec());
+        }
+
+        let mut candidates = vec![self.full_model_name.clone()];
+        candidates.extend(equivalents.iter().cloned());
+        let mut ranked = self.llm_manager.rank_by_latency(&candidates).await;
+        let primary = ranked.remove(0);
+
+        for fallback in fallbacks {
+            if !ranked.contains(fallback) && *fallback != primary {
+                ranked.push(fallback.clone());
+            }
+        }
+
+        if primary != self.full_model_name {
+            tracing::debug!(
+                model = %self.full_model_name,
+                selected = %primary,
+                "latency-aware routing selected equivalent model"
+            );
+        }
+
+        (primary, ranked)
+    }
+
+    /// Move models whose provider is failing background health probes behind
+    /// reachable ones. Nothing is dropped, so if every provider looks down the
+    /// chain is still attempted in order.
+    async fn prefer_reachable(
+        &self,
+        primary: String,
+        fallbacks: Vec<String>,
+    ) -> (String, Vec<String>) {
+        let mut reachable = Vec::new();
+        let mut down = Vec::new();
+        for model in std::iter::once(primary).chain(fallbacks) {
+            if self
+                .llm_manager
+                .is_provider_down(routing::provider_from_model(&model))
+                .await
+            {
+                down.push(model);
+            } else {
+                reachable.push(model);
+            }
+        }
+
+        if !down.is_empty() && !reachable.is_empty() {
+            tracing::debug!(
+                model = %self.full_model_name,
+                deprioritized = ?down,
+                "provider health probe failing, trying reachable models first"
+            );
+        }
+
+        let mut ordered = reachable.into_iter().chain(down);
+        let primary = ordered.next().expect("routing order always has a primary");
+        (primary, ordered.collect())
+    }
+
+    /// Fail fast when the provider has reached its monthly quota.
+    async fn ensure_within_quota(&self) -> Result<(), CompletionError> {
+        if self.llm_manager.is_over_quota(&self.provider).await {
+            return Err(CompletionError::ProviderError(format!(
+                "{} monthly quota exceeded",
+                self.provider
+            )));
+        }
+        Ok(())
+    }
+
+    /// Store a provider exchange in the debug capture buffer, if enabled.
+    fn capture_exchange(
+        &self,
+        snapshot: Option<(String, serde_json::Value)>,
+        status: reqwest::StatusCode,
+        started: std::time::Instant,
+        response_text: &str,
+        api_key: Option<&str>,
+    ) {
+        if let Some((endpoint, request)) = snapshot {
+            let (process_type, process_id) = self.capture_process();
+            self.llm_manager
+                .debug_capture()
+                .record(crate::llm::debug_capture::ExchangeRecord {
+                    process_type: &process_type,
+                    process_id: &process_id,
+                    model: &self.full_model_name,
+                    endpoint: &endpoint,
+                    status: status.as_u16(),
+                    duration: started.elapsed(),
+                    request,
+                    response_text,
+                    api_key,
+                });
+        }
+    }
+
+    /// Snapshot a request's URL and JSON body when debug capture is enabled.
+    fn debug_snapshot(
+        &self,
+        builder: &reqwest::RequestBuilder,
+    ) -> Option<(String, serde_json::Value)> {
+        if !self.llm_manager.debug_capture().is_enabled() {
+            return None;
+        }
+        let request = builder.try_clone()?.build().ok()?;
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .and_then(|bytes| serde_json::from_slice(bytes).ok())
+            .unwrap_or(serde_json::Value::Null);
+        Some((request.url().to_string(), body))
+    }
+
+    async fn dispatch_completion(
+        &self,
+        request: CompletionRequest,
     ) -> Result<completion::CompletionResponse<RawResponse>, CompletionError> {
         let provider_config = self.provider_config_for_current_model().await?;
 
@@ -193,11 +568,7 @@ impl SpacebotModel {
         model_name: &str,
         request: &CompletionRequest,
     ) -> Result<completion::CompletionResponse<RawResponse>, (CompletionError, bool)> {
-        let model = if model_name == self.full_model_name {
-            self.clone()
-        } else {
-            SpacebotModel::make(&self.llm_manager, model_name)
-        };
+        let model = self.switch_to(model_name);
 
         let mut last_error = None;
         for attempt in 0..MAX_RETRIES_PER_MODEL {
@@ -271,6 +642,10 @@ impl CompletionModel for SpacebotModel {
             agent_id: None,
             process_type: None,
             worker_type: None,
+            channel_id: None,
+            process_id: None,
+            cache_responses: false,
+            budget: None,
         }
     }
 
@@ -278,51 +653,86 @@ impl CompletionModel for SpacebotModel {
         &self,
         request: CompletionRequest,
     ) -> Result<completion::CompletionResponse<RawResponse>, CompletionError> {
+        let request = self.apply_sampling(request);
+
+        let cache_key = if self.cache_responses {
+            ResponseCacheKey::new(&self.full_model_name, &request)
+        } else {
+            None
+        };
+        if let Some(key) = &cache_key
+            && let Some(response) = self.llm_manager.cached_response(key)
+        {
+            tracing::debug!(model = %self.full_model_name, "completion served from response cache");
+            return Ok(response);
+        }
+
         #[cfg(feature = "metrics")]
         let start = std::time::Instant::now();
 
         let result = async move {
+            let downgrade = self.budget_downgrade().await?;
             let Some(routing) = &self.routing else {
                 // No routing config — just call the model directly, no fallback/retry
-                return self.attempt_completion(request).await;
+                let model = match &downgrade {
+                    Some(model_name) => self.switch_to(model_name),
+                    None => self.clone(),
+                };
+                model.ensure_within_quota().await?;
+                return model.attempt_completion(request).await;
             };
 
             let cooldown = routing.rate_limit_cooldown_secs;
-            let fallbacks = routing.get_fallbacks(&self.full_model_name);
+            let (primary, fallbacks) = match downgrade {
+                Some(model_name) => {
+                    let fallbacks = routing.get_fallbacks(&model_name).to_vec();
+                    (model_name, fallbacks)
+                }
+                None => self.routing_order(routing).await,
+            };
+            let (primary, fallbacks) = self.prefer_reachable(primary, fallbacks).await;
+            let primary_provider = routing::provider_from_model(&primary);
             let mut last_error: Option<CompletionError> = None;
 
             // Try the primary model (with retries) unless it's in rate-limit cooldown
             // and we have fallbacks to try instead.
-            let primary_rate_limited = self
-                .llm_manager
-                .is_rate_limited(&self.full_model_name, cooldown)
-                .await;
+            let primary_rate_limited = self.llm_manager.is_rate_limited(&primary, cooldown).await;
 
-            let skip_primary = primary_rate_limited && !fallbacks.is_empty();
+            let primary_over_quota = self.llm_manager.is_over_quota(primary_provider).await;
 
-            if skip_primary {
+            if primary_over_quota && fallbacks.is_empty() {
+                return Err(CompletionError::ProviderError(format!(
+                    "{primary_provider} monthly quota exceeded and no fallback models are configured"
+                )));
+            }
+
+            let skip_primary =
+                (primary_rate_limited || primary_over_quota) && !fallbacks.is_empty();
+
+            if primary_over_quota {
+                tracing::warn!(
+                    model = %primary,
+                    provider = %primary_provider,
+                    "primary provider over monthly quota, skipping to fallbacks"
+                );
+            } else if skip_primary {
                 tracing::debug!(
-                    model = %self.full_model_name,
+                    model = %primary,
                     "primary model in rate-limit cooldown, skipping to fallbacks"
                 );
             } else {
-                match self
-                    .attempt_with_retries(&self.full_model_name, &request)
-                    .await
-                {
+                match self.attempt_with_retries(&primary, &request).await {
                     Ok(response) => return Ok(response),
                     Err((error, was_rate_limit)) => {
                         if was_rate_limit {
-                            self.llm_manager
-                                .record_rate_limit(&self.full_model_name)
-                                .await;
+                            self.llm_manager.record_rate_limit(&primary).await;
                         }
                         if fallbacks.is_empty() {
                             // No fallbacks — this is the final error
                             return Err(error);
                         }
                         tracing::warn!(
-                            model = %self.full_model_name,
+                            model = %primary,
                             "primary model exhausted retries, trying fallbacks"
                         );
                         last_error = Some(error);
@@ -344,10 +754,22 @@ impl CompletionModel for SpacebotModel {
                     continue;
                 }
 
+                if self
+                    .llm_manager
+                    .is_over_quota(routing::provider_from_model(fallback_name))
+                    .await
+                {
+                    tracing::debug!(
+                        fallback = %fallback_name,
+                        "fallback provider over monthly quota, skipping"
+                    );
+                    continue;
+                }
+
                 match self.attempt_with_retries(fallback_name, &request).await {
                     Ok(response) => {
                         tracing::info!(
-                            original = %self.full_model_name,
+                            original = %primary,
                             fallback = %fallback_name,
                             attempt = index + 1,
                             "fallback model succeeded"
@@ -373,6 +795,10 @@ impl CompletionModel for SpacebotModel {
         }
         .await;
 
+        if let (Some(key), Ok(response)) = (cache_key, &result) {
+            self.llm_manager.cache_response(key, response);
+        }
+
         #[cfg(feature = "metrics")]
         {
             let elapsed = start.elapsed().as_secs_f64();
@@ -493,10 +919,17 @@ impl CompletionModel for SpacebotModel {
         &self,
         request: CompletionRequest,
     ) -> Result<StreamingCompletionResponse<RawStreamingResponse>, CompletionError> {
-        let provider_config = self.provider_config_for_current_model().await?;
+        let downgraded = self
+            .budget_downgrade()
+            .await?
+            .map(|model_name| self.switch_to(&model_name));
+        let model = downgraded.as_ref().unwrap_or(self);
+        model.ensure_within_quota().await?;
+        let request = self.apply_sampling(request);
+        let provider_config = model.provider_config_for_current_model().await?;
 
         match provider_config.api_type {
-            ApiType::OpenAiCompletions => self.stream_openai(request, &provider_config).await,
+            ApiType::OpenAiCompletions => model.stream_openai(request, &provider_config).await,
             ApiType::OpenAiChatCompletions => {
                 let endpoint = format!(
                     "{}/chat/completions",
@@ -511,38 +944,41 @@ impl CompletionModel for SpacebotModel {
                     .iter()
                     .map(|(key, value)| (key.as_str(), value.as_str()))
                     .collect();
-                self.stream_openai_compatible_with_optional_auth(
-                    request,
-                    display_name,
-                    &endpoint,
-                    Some(provider_config.api_key.clone()),
-                    &headers,
-                )
-                .await
+                model
+                    .stream_openai_compatible_with_optional_auth(
+                        request,
+                        display_name,
+                        &endpoint,
+                        Some(provider_config.api_key.clone()),
+                        &headers,
+                    )
+                    .await
             }
             ApiType::KiloGateway => {
                 let endpoint = format!(
                     "{}/chat/completions",
                     provider_config.base_url.trim_end_matches('/')
                 );
-                self.stream_openai_compatible_with_optional_auth(
-                    request,
-                    "Kilo Gateway",
-                    &endpoint,
-                    Some(provider_config.api_key.clone()),
-                    &[
-                        ("HTTP-Referer", "https://github.com/spacedriveapp/spacebot"),
-                        ("X-Title", "spacebot"),
-                    ],
-                )
-                .await
+                model
+                    .stream_openai_compatible_with_optional_auth(
+                        request,
+                        "Kilo Gateway",
+                        &endpoint,
+                        Some(provider_config.api_key.clone()),
+                        &[
+                            ("HTTP-Referer", "https://github.com/spacedriveapp/spacebot"),
+                            ("X-Title", "spacebot"),
+                        ],
+                    )
+                    .await
             }
             ApiType::Gemini => {
-                self.stream_openai_compatible(request, "Google Gemini", &provider_config)
+                model
+                    .stream_openai_compatible(request, "Google Gemini", &provider_config)
                     .await
             }
             ApiType::Anthropic | ApiType::OpenAiResponses => {
-                let response = self.attempt_completion(request).await?;
+                let response = model.attempt_completion(request).await?;
                 Ok(stream_from_completion_response(response))
             }
         }
@@ -575,6 +1011,8 @@ impl SpacebotModel {
         let is_oauth =
             anthropic_request.auth_path == crate::llm::anthropic::AnthropicAuthPath::OAuthToken;
         let original_tools = anthropic_request.original_tools;
+        let snapshot = self.debug_snapshot(&anthropic_request.builder);
+        let started = std::time::Instant::now();
 
         let response = anthropic_request
             .builder
@@ -586,6 +1024,7 @@ impl SpacebotModel {
         let response_text = response.text().await.map_err(|e| {
             CompletionError::ProviderError(format!("failed to read response body: {e}"))
         })?;
+        self.capture_exchange(snapshot, status, started, &response_text, Some(api_key));
 
         let response_body: serde_json::Value =
             serde_json::from_str(&response_text).map_err(|e| {
@@ -661,6 +1100,10 @@ impl SpacebotModel {
             body["temperature"] = serde_json::json!(temperature);
         }
 
+        if let Some(top_p) = requested_top_p(&request) {
+            body["top_p"] = serde_json::json!(top_p);
+        }
+
         if !request.tools.is_empty() {
             let tools: Vec<serde_json::Value> = request
                 .tools
@@ -766,6 +1209,10 @@ impl SpacebotModel {
             body["temperature"] = serde_json::json!(temperature);
         }
 
+        if !is_chatgpt_codex && let Some(top_p) = requested_top_p(&request) {
+            body["top_p"] = serde_json::json!(top_p);
+        }
+
         if is_chatgpt_codex {
             body["store"] = serde_json::json!(false);
             body["stream"] = serde_json::json!(true);
@@ -815,8 +1262,11 @@ impl SpacebotModel {
                 );
         }
 
+        let request_builder = request_builder.json(&body);
+        let snapshot = self.debug_snapshot(&request_builder);
+        let started = std::time::Instant::now();
+
         let response = request_builder
-            .json(&body)
             .send()
             .await
             .map_err(|e| CompletionError::ProviderError(e.to_string()))?;
@@ -825,6 +1275,7 @@ impl SpacebotModel {
         let response_text = response.text().await.map_err(|e| {
             CompletionError::ProviderError(format!("failed to read response body: {e}"))
         })?;
+        self.capture_exchange(snapshot, status, started, &response_text, Some(api_key));
 
         if !status.is_success() {
             let message = parse_openai_error_message(&response_text)
@@ -912,6 +1363,10 @@ impl SpacebotModel {
             body["temperature"] = serde_json::json!(temperature);
         }
 
+        if let Some(top_p) = requested_top_p(&request) {
+            body["top_p"] = serde_json::json!(top_p);
+        }
+
         if !request.tools.is_empty() {
             let tools: Vec<serde_json::Value> = request
                 .tools
@@ -1005,6 +1460,10 @@ impl SpacebotModel {
             body["temperature"] = serde_json::json!(temperature);
         }
 
+        if let Some(top_p) = requested_top_p(&request) {
+            body["top_p"] = serde_json::json!(top_p);
+        }
+
         if !request.tools.is_empty() {
             let tools: Vec<serde_json::Value> = request
                 .tools
@@ -1063,9 +1522,12 @@ impl SpacebotModel {
         F: FnMut(&serde_json::Value) -> reqwest::RequestBuilder,
     {
         let stream_request_body = with_streaming_enabled(&request_body);
-        let response = build_request(&stream_request_body)
+        let request_builder = build_request(&stream_request_body)
             .header("accept-encoding", "identity")
-            .timeout(std::time::Duration::from_secs(STREAM_REQUEST_TIMEOUT_SECS))
+            .timeout(std::time::Duration::from_secs(STREAM_REQUEST_TIMEOUT_SECS));
+        let mut snapshot = self.debug_snapshot(&request_builder);
+        let started = std::time::Instant::now();
+        let response = request_builder
             .send()
             .await
             .map_err(|error| CompletionError::ProviderError(error.to_string()))?;
@@ -1076,6 +1538,7 @@ impl SpacebotModel {
                 .text()
                 .await
                 .unwrap_or_else(|error| format!("failed to read error response body: {error}"));
+            self.capture_exchange(snapshot.take(), status, started, &response_text, None);
 
             return Err(CompletionError::ProviderError(format!(
                 "{provider_label} API error ({})",
@@ -1084,6 +1547,11 @@ impl SpacebotModel {
         }
 
         let provider_label = provider_label.to_string();
+        let llm_manager = self.llm_manager.clone();
+        let provider = self.provider.clone();
+        let full_model_name = self.full_model_name.clone();
+        let (process_type, process_id) = self.capture_process();
+        let budget = self.budget.clone();
         let stream = async_stream::stream! {
             let mut stream = response.bytes_stream();
             let mut block_buffer = String::new();
@@ -1167,6 +1635,20 @@ impl SpacebotModel {
                 }
             }
 
+            if let Some((endpoint, request)) = snapshot {
+                llm_manager.debug_capture().record(crate::llm::debug_capture::ExchangeRecord {
+                    process_type: &process_type,
+                    process_id: &process_id,
+                    model: &full_model_name,
+                    endpoint: &endpoint,
+                    status: status.as_u16(),
+                    duration: started.elapsed(),
+                    request,
+                    response_text: if saw_data_event { &sse_text } else { &raw_text },
+                    api_key: None,
+                });
+            }
+
             match flush_openai_streaming_tool_calls(&mut pending_tool_calls) {
                 Ok(events) => {
                     for event in events {
@@ -1196,6 +1678,12 @@ impl SpacebotModel {
                     }
                 };
 
+                llm_manager
+                    .record_usage(&provider, &full_model_name, &parsed_response.usage)
+                    .await;
+                if let Some(budget) = &budget {
+                    budget.record(&full_model_name, &parsed_response.usage).await;
+                }
                 yield Ok(RawStreamingChoice::FinalResponse(RawStreamingResponse {
                     body: response_body,
                     usage: Some(parsed_response.usage),
@@ -1229,6 +1717,12 @@ impl SpacebotModel {
                 yield Ok(RawStreamingChoice::MessageId(message_id));
             }
 
+            llm_manager
+                .record_usage(&provider, &full_model_name, &parsed_response.usage)
+                .await;
+            if let Some(budget) = &budget {
+                budget.record(&full_model_name, &parsed_response.usage).await;
+            }
             yield Ok(RawStreamingChoice::FinalResponse(RawStreamingResponse {
                 body: response_body,
                 usage: Some(parsed_response.usage),
@@ -1254,6 +1748,36 @@ fn reverse_map_tool_names(
     }
 }
 
+/// Plain text of a message for completion traces.
+fn message_trace_text(message: &Message) -> String {
+    let mut parts = Vec::new();
+    match message {
+        Message::User { content } => {
+            for item in content.iter() {
+                match item {
+                    UserContent::Text(text) => parts.push(text.text.clone()),
+                    UserContent::ToolResult(result) => {
+                        parts.push(tool_result_content_to_string(&result.content))
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Message::Assistant { content, .. } => {
+            for item in content.iter() {
+                match item {
+                    AssistantContent::Text(text) => parts.push(text.text.clone()),
+                    AssistantContent::ToolCall(call) => {
+                        parts.push(format!("[tool call: {}]", call.function.name))
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+    parts.join("\n")
+}
+
 fn tool_result_content_to_string(content: &OneOrMany<rig::message::ToolResultContent>) -> String {
     content
         .iter()
@@ -2336,6 +2860,12 @@ fn parse_openai_chat_sse_response(
     }))
 }
 
+/// `top_p` carried in a request's additional params, set from routing
+/// sampling config since rig's request has no dedicated field for it.
+pub(crate) fn requested_top_p(request: &CompletionRequest) -> Option<f64> {
+    request.additional_params.as_ref()?.get("top_p")?.as_f64()
+}
+
 // --- Response parsing ---
 
 fn make_tool_call(id: String, name: String, arguments: serde_json::Value) -> ToolCall {
@@ -0,0 +1,286 @@
+//! Generic OAuth for providers configured under `[llm.oauth.<provider>]`.
+//!
+//! Covers providers that hand out short-lived access tokens instead of API
+//! keys (Google, GitHub-backed gateways). The dashboard starts an
+//! authorization-code flow with PKCE, the provider redirects back to
+//! `/api/providers/oauth/callback`, and the resulting tokens are stored per
+//! provider in the instance directory. `LlmManager` refreshes them lazily
+//! when they are about to expire.
+
+use crate::config::OAuthProviderConfig;
+
+use anyhow::{Context as _, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Stored OAuth credentials for one provider.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct OAuthCredentials {
+    pub access_token: String,
+    /// Absent when the provider doesn't issue refresh tokens; the user has
+    /// to sign in again once the access token expires.
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// Expiry as Unix timestamp in milliseconds, if the provider reported one.
+    #[serde(default)]
+    pub expires_at: Option<i64>,
+}
+
+impl std::fmt::Debug for OAuthCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OAuthCredentials")
+            .field("access_token", &"[REDACTED]")
+            .field(
+                "refresh_token",
+                &self.refresh_token.as_ref().map(|_| "[REDACTED]"),
+            )
+            .field("expires_at", &self.expires_at)
+            .finish()
+    }
+}
+
+impl OAuthCredentials {
+    /// Check if the access token is expired or about to expire (within 5 minutes).
+    pub fn is_expired(&self) -> bool {
+        let now = chrono::Utc::now().timestamp_millis();
+        let buffer = 5 * 60 * 1000; // 5 minutes
+        self.expires_at
+            .is_some_and(|expires_at| now >= expires_at - buffer)
+    }
+
+    /// Refresh the access token. Providers that don't rotate refresh tokens
+    /// omit one from the response, so the current one is kept.
+    pub async fn refresh(&self, config: &OAuthProviderConfig) -> Result<Self> {
+        let Some(refresh_token) = self.refresh_token.as_deref() else {
+            anyhow::bail!("no refresh token stored; sign in again");
+        };
+
+        let mut form = vec![
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("client_id", config.client_id.as_str()),
+        ];
+        if let Some(client_secret) = config.client_secret.as_deref() {
+            form.push(("client_secret", client_secret));
+        }
+
+        let response = request_token(&config.token_url, &form)
+            .await
+            .context("token refresh failed")?;
+        Ok(response.into_credentials(self.refresh_token.clone()))
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<i64>,
+}
+
+impl TokenResponse {
+    fn into_credentials(self, previous_refresh_token: Option<String>) -> OAuthCredentials {
+        OAuthCredentials {
+            access_token: self.access_token,
+            refresh_token: self.refresh_token.or(previous_refresh_token),
+            expires_at: self
+                .expires_in
+                .map(|expires_in| chrono::Utc::now().timestamp_millis() + expires_in * 1000),
+        }
+    }
+}
+
+async fn request_token(token_url: &str, form: &[(&str, &str)]) -> Result<TokenResponse> {
+    let response = reqwest::Client::new()
+        .post(token_url)
+        .header("Accept", "application/json")
+        .form(form)
+        .send()
+        .await
+        .context("failed to send token request")?;
+
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .context("failed to read token response")?;
+
+    if !status.is_success() {
+        anyhow::bail!("token endpoint returned {status}: {text}");
+    }
+
+    serde_json::from_str(&text).context("failed to parse token response")
+}
+
+/// Build the URL the user is sent to for consent.
+pub fn authorize_url(
+    config: &OAuthProviderConfig,
+    redirect_uri: &str,
+    state: &str,
+    code_challenge: &str,
+) -> String {
+    let mut url = format!(
+        "{}{}response_type=code&client_id={}&redirect_uri={}&state={}&code_challenge={}&code_challenge_method=S256",
+        config.authorize_url,
+        if config.authorize_url.contains('?') {
+            '&'
+        } else {
+            '?'
+        },
+        urlencoding::encode(&config.client_id),
+        urlencoding::encode(redirect_uri),
+        urlencoding::encode(state),
+        code_challenge,
+    );
+    if !config.scopes.is_empty() {
+        url.push_str("&scope=");
+        url.push_str(&urlencoding::encode(&config.scopes.join(" ")));
+    }
+    for (key, value) in &config.authorize_params {
+        url.push('&');
+        url.push_str(&urlencoding::encode(key));
+        url.push('=');
+        url.push_str(&urlencoding::encode(value));
+    }
+    url
+}
+
+/// Exchange an authorization code from the callback for tokens.
+pub async fn exchange_code(
+    config: &OAuthProviderConfig,
+    code: &str,
+    verifier: &str,
+    redirect_uri: &str,
+) -> Result<OAuthCredentials> {
+    let mut form = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", redirect_uri),
+        ("client_id", config.client_id.as_str()),
+        ("code_verifier", verifier),
+    ];
+    if let Some(client_secret) = config.client_secret.as_deref() {
+        form.push(("client_secret", client_secret));
+    }
+
+    let response = request_token(&config.token_url, &form)
+        .await
+        .context("token exchange failed")?;
+    Ok(response.into_credentials(None))
+}
+
+/// Path to a provider's OAuth credentials within the instance directory.
+pub fn credentials_path(instance_dir: &Path, provider_id: &str) -> PathBuf {
+    instance_dir
+        .join("oauth")
+        .join(format!("{}.json", provider_id.to_lowercase()))
+}
+
+/// Load stored credentials for a provider from disk.
+pub fn load_credentials(
+    instance_dir: &Path,
+    provider_id: &str,
+) -> Result<Option<OAuthCredentials>> {
+    let path = credentials_path(instance_dir, provider_id);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let data = std::fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let creds = serde_json::from_str(&data)
+        .with_context(|| format!("failed to parse {}", path.display()))?;
+    Ok(Some(creds))
+}
+
+/// Save credentials for a provider with restricted permissions (0600).
+pub fn save_credentials(
+    instance_dir: &Path,
+    provider_id: &str,
+    creds: &OAuthCredentials,
+) -> Result<()> {
+    let path = credentials_path(instance_dir, provider_id);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let data = serde_json::to_string_pretty(creds).context("failed to serialize credentials")?;
+
+    std::fs::write(&path, &data).with_context(|| format!("failed to write {}", path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("failed to set permissions on {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+/// Remove stored credentials for a provider. Missing files are not an error.
+pub fn delete_credentials(instance_dir: &Path, provider_id: &str) -> Result<()> {
+    let path = credentials_path(instance_dir, provider_id);
+    match std::fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(error) => Err(error).with_context(|| format!("failed to remove {}", path.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ApiType;
+
+    fn config() -> OAuthProviderConfig {
+        OAuthProviderConfig {
+            api_type: ApiType::Gemini,
+            base_url: "https://generativelanguage.googleapis.com/v1beta/openai".into(),
+            name: Some("Google".into()),
+            client_id: "client id".into(),
+            client_secret: None,
+            authorize_url: "https://accounts.google.com/o/oauth2/v2/auth".into(),
+            token_url: "https://oauth2.googleapis.com/token".into(),
+            scopes: vec!["openid".into(), "email".into()],
+            redirect_uri: None,
+            authorize_params: [("access_type".to_string(), "offline".to_string())]
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn authorize_url_encodes_parameters() {
+        let url = authorize_url(
+            &config(),
+            "http://localhost:19898/api/providers/oauth/callback",
+            "abc",
+            "challenge",
+        );
+        assert_eq!(
+            url,
+            "https://accounts.google.com/o/oauth2/v2/auth?response_type=code&client_id=client%20id\
+             &redirect_uri=http%3A%2F%2Flocalhost%3A19898%2Fapi%2Fproviders%2Foauth%2Fcallback\
+             &state=abc&code_challenge=challenge&code_challenge_method=S256\
+             &scope=openid%20email&access_type=offline"
+        );
+    }
+
+    #[test]
+    fn refresh_keeps_previous_refresh_token() {
+        let response = TokenResponse {
+            access_token: "new".into(),
+            refresh_token: None,
+            expires_in: Some(3600),
+        };
+        let creds = response.into_credentials(Some("old-refresh".into()));
+        assert_eq!(creds.refresh_token.as_deref(), Some("old-refresh"));
+        assert!(!creds.is_expired());
+
+        let stale = OAuthCredentials {
+            expires_at: Some(chrono::Utc::now().timestamp_millis()),
+            ..creds
+        };
+        assert!(stale.is_expired());
+    }
+}
@@ -0,0 +1,287 @@
+//! Ollama native API client for local model management.
+//!
+//! Completions go through Ollama's OpenAI-compatible endpoint like any other
+//! provider. This module talks to the native `/api/*` endpoints instead, which
+//! expose what's installed, what's loaded into memory, and model downloads.
+
+use anyhow::Context as _;
+use futures::StreamExt as _;
+use serde::{Deserialize, Serialize};
+
+/// An installed model, as reported by `GET /api/tags`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaModel {
+    pub name: String,
+    /// On-disk size in bytes.
+    pub size: u64,
+    #[serde(default)]
+    pub digest: String,
+    #[serde(default)]
+    pub modified_at: Option<String>,
+    #[serde(default)]
+    pub details: OllamaModelDetails,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OllamaModelDetails {
+    #[serde(default)]
+    pub family: Option<String>,
+    #[serde(default)]
+    pub parameter_size: Option<String>,
+    #[serde(default)]
+    pub quantization_level: Option<String>,
+}
+
+/// A model currently loaded into memory, as reported by `GET /api/ps`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaRunningModel {
+    pub name: String,
+    /// Total memory used by the loaded model in bytes.
+    pub size: u64,
+    /// Portion of `size` resident in GPU memory.
+    #[serde(default)]
+    pub size_vram: u64,
+    #[serde(default)]
+    pub expires_at: Option<String>,
+}
+
+/// One progress line from `POST /api/pull`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OllamaPullProgress {
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub digest: Option<String>,
+    #[serde(default)]
+    pub total: Option<u64>,
+    #[serde(default)]
+    pub completed: Option<u64>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    models: Vec<OllamaModel>,
+}
+
+#[derive(Deserialize)]
+struct VersionResponse {
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct EmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+#[derive(Deserialize)]
+struct PsResponse {
+    #[serde(default)]
+    models: Vec<OllamaRunningModel>,
+}
+
+/// Client for an Ollama server's native API.
+#[derive(Debug, Clone)]
+pub struct OllamaClient {
+    http_client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl OllamaClient {
+    /// `base_url` is the server root (e.g. `http://localhost:11434`). A
+    /// trailing `/v1` from an OpenAI-style URL is stripped.
+    pub fn new(http_client: reqwest::Client, base_url: &str, api_key: Option<String>) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+        let base_url = base_url.strip_suffix("/v1").unwrap_or(base_url).to_string();
+        Self {
+            http_client,
+            base_url,
+            api_key: api_key.filter(|key| !key.is_empty()),
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let builder = self
+            .http_client
+            .request(method, format!("{}{path}", self.base_url));
+        match &self.api_key {
+            Some(key) => builder.bearer_auth(key),
+            None => builder,
+        }
+    }
+
+    /// Models installed on the server.
+    pub async fn list_models(&self) -> anyhow::Result<Vec<OllamaModel>> {
+        let response = self
+            .request(reqwest::Method::GET, "/api/tags")
+            .send()
+            .await
+            .with_context(|| format!("failed to reach Ollama at {}", self.base_url))?
+            .error_for_status()
+            .context("Ollama rejected the model list request")?;
+        let body: TagsResponse = response.json().await.context("invalid Ollama model list")?;
+        Ok(body.models)
+    }
+
+    /// The server's version. Cheap enough to use as a liveness check.
+    pub async fn version(&self) -> anyhow::Result<String> {
+        let response = self
+            .request(reqwest::Method::GET, "/api/version")
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await
+            .with_context(|| format!("failed to reach Ollama at {}", self.base_url))?
+            .error_for_status()
+            .context("Ollama rejected the version request")?;
+        let body: VersionResponse = response
+            .json()
+            .await
+            .context("invalid Ollama version response")?;
+        Ok(body.version)
+    }
+
+    /// Embed `input` with an embedding model, one vector per input.
+    /// `dimensions` truncates the output on models that support it.
+    pub async fn embed(
+        &self,
+        model: &str,
+        input: &[String],
+        dimensions: Option<usize>,
+    ) -> anyhow::Result<Vec<Vec<f32>>> {
+        let mut body = serde_json::json!({ "model": model, "input": input });
+        if let Some(dimensions) = dimensions {
+            body["dimensions"] = dimensions.into();
+        }
+        let response = self
+            .request(reqwest::Method::POST, "/api/embed")
+            .json(&body)
+            .send()
+            .await
+            .with_context(|| format!("failed to reach Ollama at {}", self.base_url))?
+            .error_for_status()
+            .with_context(|| format!("Ollama rejected embedding with '{model}'"))?;
+        let body: EmbedResponse = response
+            .json()
+            .await
+            .context("invalid Ollama embedding response")?;
+        Ok(body.embeddings)
+    }
+
+    /// Models currently loaded into memory, with VRAM usage.
+    pub async fn running_models(&self) -> anyhow::Result<Vec<OllamaRunningModel>> {
+        let response = self
+            .request(reqwest::Method::GET, "/api/ps")
+            .send()
+            .await
+            .with_context(|| format!("failed to reach Ollama at {}", self.base_url))?
+            .error_for_status()
+            .context("Ollama rejected the running model request")?;
+        let body: PsResponse = response
+            .json()
+            .await
+            .context("invalid Ollama running model list")?;
+        Ok(body.models)
+    }
+
+    /// Download a model, calling `on_progress` for every progress line.
+    ///
+    /// Pulls of large models can take far longer than the shared client's
+    /// request timeout, so this request gets a generous one of its own.
+    pub async fn pull_model<F>(&self, model: &str, mut on_progress: F) -> anyhow::Result<()>
+    where
+        F: FnMut(&OllamaPullProgress),
+    {
+        let response = self
+            .request(reqwest::Method::POST, "/api/pull")
+            .timeout(std::time::Duration::from_secs(24 * 60 * 60))
+            .json(&serde_json::json!({ "model": model, "stream": true }))
+            .send()
+            .await
+            .with_context(|| format!("failed to reach Ollama at {}", self.base_url))?
+            .error_for_status()
+            .with_context(|| format!("Ollama rejected pull of '{model}'"))?;
+
+        let mut stream = response.bytes_stream();
+        let mut buffer = String::new();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.context("Ollama pull stream failed")?;
+            buffer.push_str(&String::from_utf8_lossy(&chunk));
+
+            while let Some(newline) = buffer.find('\n') {
+                let line: String = buffer.drain(..=newline).collect();
+                if let Some(progress) = parse_progress_line(&line) {
+                    on_progress(&progress);
+                    if let Some(error) = progress.error {
+                        anyhow::bail!("Ollama failed to pull '{model}': {error}");
+                    }
+                }
+            }
+        }
+
+        if let Some(progress) = parse_progress_line(&buffer) {
+            on_progress(&progress);
+            if let Some(error) = progress.error {
+                anyhow::bail!("Ollama failed to pull '{model}': {error}");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_progress_line(line: &str) -> Option<OllamaPullProgress> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    match serde_json::from_str(line) {
+        Ok(progress) => Some(progress),
+        Err(error) => {
+            tracing::debug!(%error, line, "skipping unparseable Ollama pull progress line");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_openai_suffix_from_base_url() {
+        let client = OllamaClient::new(reqwest::Client::new(), "http://host:11434/v1/", None);
+        assert_eq!(client.base_url, "http://host:11434");
+    }
+
+    #[test]
+    fn parses_pull_progress_lines() {
+        let progress = parse_progress_line(
+            r#"{"status":"pulling abc","digest":"sha256:abc","total":100,"completed":40}"#,
+        )
+        .unwrap();
+        assert_eq!(progress.total, Some(100));
+        assert_eq!(progress.completed, Some(40));
+
+        let error = parse_progress_line(r#"{"error":"model not found"}"#).unwrap();
+        assert_eq!(error.error.as_deref(), Some("model not found"));
+        assert!(parse_progress_line("   ").is_none());
+    }
+
+    #[test]
+    fn parses_running_models() {
+        let body: PsResponse = serde_json::from_str(
+            r#"{"models":[{"name":"llama3:8b","model":"llama3:8b","size":5137025024,"size_vram":5137025024,"digest":"x","expires_at":"2026-01-01T00:00:00Z"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(body.models[0].size_vram, 5_137_025_024);
+    }
+
+    #[test]
+    fn parses_version() {
+        let body: VersionResponse = serde_json::from_str(r#"{"version":"0.6.2"}"#).unwrap();
+        assert_eq!(body.version, "0.6.2");
+    }
+}
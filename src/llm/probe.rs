@@ -0,0 +1,268 @@
+//! Periodic background health probes for configured providers.
+//!
+//! Every few minutes each provider's model list endpoint is fetched. The call
+//! is free on every supported API and exercises DNS, TLS, the gateway, and
+//! credentials without spending tokens. Providers that fail consecutive
+//! probes are tried after reachable ones during routing, so an outage is
+//! usually discovered before a user's message hits it.
+//!
+//! Only transport failures and 5xx responses count as "down". A 4xx still
+//! proves the provider is reachable; 401/403 are surfaced as credential
+//! problems without affecting routing (some OAuth tokens can't list models).
+
+use crate::config::{ApiType, ProviderConfig};
+use crate::llm::LlmManager;
+
+use serde::Serialize;
+
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+
+/// Consecutive failed probes after which a provider is treated as down.
+pub const DOWN_AFTER_FAILURES: u32 = 2;
+
+/// Per-request timeout for a probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delay before the first probe round, so startup isn't slowed down.
+const INITIAL_DELAY: Duration = Duration::from_secs(15);
+
+/// Latest probe result for a provider.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderProbe {
+    /// Whether the provider answered the last probe.
+    pub reachable: bool,
+    /// Whether the provider rejected the configured credentials.
+    pub auth_error: bool,
+    /// HTTP status of the last probe, if a response was received.
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+    pub consecutive_failures: u32,
+    pub checked_at: String,
+}
+
+impl ProviderProbe {
+    /// Whether routing should deprioritize this provider.
+    pub fn is_down(&self) -> bool {
+        self.consecutive_failures >= DOWN_AFTER_FAILURES
+    }
+}
+
+/// Outcome of a single probe request.
+#[derive(Debug, Clone)]
+pub struct ProbeOutcome {
+    pub reachable: bool,
+    pub status: Option<u16>,
+    pub latency: Duration,
+    pub error: Option<String>,
+}
+
+impl ProbeOutcome {
+    /// Fold this outcome into the previous probe state.
+    pub fn into_probe(self, previous: Option<&ProviderProbe>) -> ProviderProbe {
+        let consecutive_failures = if self.reachable {
+            0
+        } else {
+            previous.map_or(0, |probe| probe.consecutive_failures) + 1
+        };
+        ProviderProbe {
+            reachable: self.reachable,
+            auth_error: matches!(self.status, Some(401 | 403)),
+            status: self.status,
+            latency_ms: self.latency.as_millis() as u64,
+            error: self.error,
+            consecutive_failures,
+            checked_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// The model list URL for a provider. Base URL conventions follow the
+/// completion endpoints in `model.rs`.
+pub fn probe_url(provider: &ProviderConfig) -> String {
+    let base_url = provider.base_url.trim_end_matches('/');
+    match provider.api_type {
+        ApiType::Anthropic => {
+            let base_url = base_url.strip_suffix("/v1/messages").unwrap_or(base_url);
+            format!("{base_url}/v1/models")
+        }
+        ApiType::OpenAiCompletions | ApiType::OpenAiResponses => {
+            if base_url.ends_with("/v1") {
+                format!("{base_url}/models")
+            } else {
+                format!("{base_url}/v1/models")
+            }
+        }
+        ApiType::OpenAiChatCompletions | ApiType::KiloGateway | ApiType::Gemini => {
+            format!("{base_url}/models")
+        }
+    }
+}
+
+/// A model list request for a provider, with its credentials and headers.
+pub fn model_list_request(
+    http_client: &reqwest::Client,
+    provider: &ProviderConfig,
+) -> reqwest::RequestBuilder {
+    let mut builder = http_client.get(probe_url(provider));
+    if !provider.api_key.is_empty() {
+        builder = match provider.api_type {
+            ApiType::Anthropic => {
+                crate::llm::anthropic::apply_auth_headers(
+                    builder.header("anthropic-version", "2023-06-01"),
+                    &provider.api_key,
+                    false,
+                    provider.use_bearer_auth,
+                )
+                .0
+            }
+            _ => builder.bearer_auth(&provider.api_key),
+        };
+    }
+    for (name, value) in &provider.extra_headers {
+        builder = builder.header(name, value);
+    }
+    builder
+}
+
+/// Fetch a provider's model list and classify the result.
+pub async fn probe_provider(
+    http_client: &reqwest::Client,
+    provider: &ProviderConfig,
+) -> ProbeOutcome {
+    let builder = model_list_request(http_client, provider).timeout(PROBE_TIMEOUT);
+
+    let started = Instant::now();
+    match builder.send().await {
+        Ok(response) => {
+            let status = response.status();
+            let reachable = !status.is_server_error();
+            ProbeOutcome {
+                reachable,
+                status: Some(status.as_u16()),
+                latency: started.elapsed(),
+                error: (!status.is_success()).then(|| format!("HTTP {status}")),
+            }
+        }
+        Err(error) => ProbeOutcome {
+            reachable: false,
+            status: None,
+            latency: started.elapsed(),
+            error: Some(if error.is_timeout() {
+                "timed out".to_string()
+            } else {
+                error.to_string()
+            }),
+        },
+    }
+}
+
+/// Probe every configured provider once and record the results.
+pub async fn probe_all(llm_manager: &LlmManager) {
+    let providers = llm_manager.probe_targets().await;
+    let probes = providers
+        .into_iter()
+        .map(|(provider_id, config)| async move {
+            let outcome = probe_provider(llm_manager.http_client(), &config).await;
+            (provider_id, outcome)
+        });
+
+    for (provider_id, outcome) in futures::future::join_all(probes).await {
+        if !outcome.reachable {
+            tracing::warn!(
+                provider = %provider_id,
+                error = outcome.error.as_deref().unwrap_or("unknown"),
+                "provider health probe failed"
+            );
+        }
+        llm_manager.record_probe(&provider_id, outcome).await;
+    }
+}
+
+/// Run probes in the background until the manager is dropped. Does nothing
+/// when `health_probe_interval_secs` is 0.
+pub fn spawn_health_probe(llm_manager: &Arc<LlmManager>) -> Option<tokio::task::JoinHandle<()>> {
+    llm_manager.health_probe_interval()?;
+
+    // Hold a weak reference so a replaced manager (e.g. after provider setup)
+    // stops being probed once nothing else uses it.
+    let llm_manager: Weak<LlmManager> = Arc::downgrade(llm_manager);
+    Some(tokio::spawn(async move {
+        tokio::time::sleep(INITIAL_DELAY).await;
+        loop {
+            let Some(manager) = llm_manager.upgrade() else {
+                break;
+            };
+            // Re-read each round so config reloads take effect.
+            let Some(interval) = manager.health_probe_interval() else {
+                break;
+            };
+            probe_all(&manager).await;
+            drop(manager);
+            tokio::time::sleep(interval).await;
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn provider(api_type: ApiType, base_url: &str) -> ProviderConfig {
+        ProviderConfig {
+            api_type,
+            base_url: base_url.to_string(),
+            api_key: String::new(),
+            name: None,
+            use_bearer_auth: false,
+            extra_headers: vec![],
+        }
+    }
+
+    #[test]
+    fn builds_model_list_urls() {
+        assert_eq!(
+            probe_url(&provider(ApiType::Anthropic, "https://api.anthropic.com")),
+            "https://api.anthropic.com/v1/models"
+        );
+        assert_eq!(
+            probe_url(&provider(
+                ApiType::OpenAiCompletions,
+                "https://api.openai.com/"
+            )),
+            "https://api.openai.com/v1/models"
+        );
+        assert_eq!(
+            probe_url(&provider(
+                ApiType::OpenAiChatCompletions,
+                "https://api.z.ai/api/paas/v4"
+            )),
+            "https://api.z.ai/api/paas/v4/models"
+        );
+    }
+
+    #[test]
+    fn consecutive_failures_mark_provider_down() {
+        let failure = || ProbeOutcome {
+            reachable: false,
+            status: None,
+            latency: Duration::from_millis(10),
+            error: Some("connection refused".to_string()),
+        };
+        let first = failure().into_probe(None);
+        assert!(!first.is_down());
+        let second = failure().into_probe(Some(&first));
+        assert!(second.is_down());
+
+        let recovered = ProbeOutcome {
+            reachable: true,
+            status: Some(401),
+            latency: Duration::from_millis(10),
+            error: Some("HTTP 401 Unauthorized".to_string()),
+        }
+        .into_probe(Some(&second));
+        assert!(!recovered.is_down());
+        assert!(recovered.auth_error);
+    }
+}
@@ -0,0 +1,270 @@
+//! Monthly per-provider usage tracking and quota enforcement.
+//!
+//! Every successful completion adds its token count and estimated cost
+//! (see `pricing`) to the provider's running total for the current UTC
+//! calendar month. When a provider has a `[llm.quota.<provider>]` ceiling and
+//! either limit is reached, routing skips that provider until the month rolls
+//! over. Totals are persisted to `provider_usage.json` in the instance
+//! directory so restarts don't reset the ceiling. Writes are debounced, so a
+//! crash loses at most the last few seconds of usage.
+
+use crate::config::ProviderQuota;
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// File name for persisted usage totals inside the instance directory.
+pub const USAGE_FILE_NAME: &str = "provider_usage.json";
+
+/// How long usage accumulates in memory before it's written out.
+const PERSIST_DEBOUNCE: Duration = Duration::from_secs(5);
+
+/// Accumulated usage for one provider in the current month.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ProviderUsage {
+    /// Input plus output tokens.
+    pub tokens: u64,
+    /// Estimated spend in USD.
+    pub cost_usd: f64,
+}
+
+/// Quota state for a provider, as reported by the providers API.
+#[derive(Debug, Clone, Serialize)]
+pub struct QuotaStatus {
+    /// Calendar month the totals apply to (`YYYY-MM`, UTC).
+    pub month: String,
+    pub tokens_used: u64,
+    pub cost_usd: f64,
+    pub monthly_tokens: Option<u64>,
+    pub monthly_usd: Option<f64>,
+    /// Whether either ceiling has been reached.
+    pub exceeded: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct UsageLedger {
+    month: String,
+    providers: HashMap<String, ProviderUsage>,
+}
+
+/// Tracks monthly usage per provider.
+#[derive(Debug)]
+pub struct QuotaTracker {
+    ledger: Arc<Mutex<UsageLedger>>,
+    path: Option<PathBuf>,
+    /// Set while a debounced write is pending.
+    flush_scheduled: Arc<AtomicBool>,
+}
+
+impl QuotaTracker {
+    /// In-memory tracker (totals reset on restart).
+    pub fn new() -> Self {
+        Self {
+            ledger: Arc::new(Mutex::new(UsageLedger {
+                month: current_month(),
+                providers: HashMap::new(),
+            })),
+            path: None,
+            flush_scheduled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Tracker backed by `provider_usage.json` in the given directory.
+    pub fn load(instance_dir: &Path) -> Self {
+        let path = instance_dir.join(USAGE_FILE_NAME);
+        let ledger = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|error| {
+                tracing::warn!(%error, path = %path.display(), "invalid provider usage file, starting fresh");
+                UsageLedger::default()
+            }),
+            Err(_) => UsageLedger::default(),
+        };
+
+        Self {
+            ledger: Arc::new(Mutex::new(ledger)),
+            path: Some(path),
+            flush_scheduled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Add a completion's usage to the provider's monthly total. The file is
+    /// written once the debounce window passes.
+    pub async fn record(&self, provider: &str, tokens: u64, cost_usd: f64) {
+        {
+            let mut ledger = self.ledger.lock().await;
+            roll_over(&mut ledger);
+            let usage = ledger.providers.entry(provider.to_string()).or_default();
+            usage.tokens = usage.tokens.saturating_add(tokens);
+            usage.cost_usd += cost_usd;
+        }
+
+        let Some(path) = self.path.clone() else {
+            return;
+        };
+        if self.flush_scheduled.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let ledger = self.ledger.clone();
+        let flush_scheduled = self.flush_scheduled.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(PERSIST_DEBOUNCE).await;
+            // Cleared before the write, so usage recorded from here on
+            // schedules another one.
+            flush_scheduled.store(false, Ordering::Release);
+            let ledger = ledger.lock().await;
+            persist(&path, &ledger).await;
+        });
+    }
+
+    /// Write the totals now instead of waiting for the debounce, e.g. on
+    /// shutdown.
+    pub async fn flush(&self) {
+        if let Some(path) = &self.path {
+            let ledger = self.ledger.lock().await;
+            persist(path, &ledger).await;
+        }
+    }
+
+    /// Current month's usage for a provider.
+    pub async fn usage(&self, provider: &str) -> ProviderUsage {
+        let mut ledger = self.ledger.lock().await;
+        roll_over(&mut ledger);
+        ledger.providers.get(provider).copied().unwrap_or_default()
+    }
+
+    /// Quota status for a provider against its configured ceiling.
+    pub async fn status(&self, provider: &str, quota: &ProviderQuota) -> QuotaStatus {
+        let mut ledger = self.ledger.lock().await;
+        roll_over(&mut ledger);
+        let usage = ledger.providers.get(provider).copied().unwrap_or_default();
+
+        QuotaStatus {
+            month: ledger.month.clone(),
+            tokens_used: usage.tokens,
+            cost_usd: usage.cost_usd,
+            monthly_tokens: quota.monthly_tokens,
+            monthly_usd: quota.monthly_usd,
+            exceeded: is_exceeded(&usage, quota),
+        }
+    }
+}
+
+impl Default for QuotaTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether usage has reached either ceiling in a quota.
+pub fn is_exceeded(usage: &ProviderUsage, quota: &ProviderQuota) -> bool {
+    quota
+        .monthly_tokens
+        .is_some_and(|limit| usage.tokens >= limit)
+        || quota
+            .monthly_usd
+            .is_some_and(|limit| usage.cost_usd >= limit)
+}
+
+/// Replace the usage file through a temp file so a crash mid-write never
+/// leaves it truncated. Callers hold the ledger lock, so writes land in order.
+async fn persist(path: &Path, ledger: &UsageLedger) {
+    let result = async {
+        let snapshot = serde_json::to_string(ledger)?;
+        let tmp_path = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp_path, snapshot).await?;
+        tokio::fs::rename(&tmp_path, path).await?;
+        anyhow::Ok(())
+    }
+    .await;
+    if let Err(error) = result {
+        tracing::warn!(%error, path = %path.display(), "failed to persist provider usage");
+    }
+}
+
+fn current_month() -> String {
+    chrono::Utc::now().format("%Y-%m").to_string()
+}
+
+/// Reset totals when the calendar month changes.
+fn roll_over(ledger: &mut UsageLedger) {
+    let month = current_month();
+    if ledger.month != month {
+        ledger.month = month;
+        ledger.providers.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn accumulates_usage_per_provider() {
+        let tracker = QuotaTracker::new();
+        tracker.record("anthropic", 1_000, 0.5).await;
+        tracker.record("anthropic", 500, 0.25).await;
+        tracker.record("openai", 10, 0.01).await;
+
+        let usage = tracker.usage("anthropic").await;
+        assert_eq!(usage.tokens, 1_500);
+        assert!((usage.cost_usd - 0.75).abs() < 1e-9);
+        assert_eq!(tracker.usage("groq").await.tokens, 0);
+    }
+
+    #[tokio::test]
+    async fn reports_exceeded_when_either_ceiling_is_reached() {
+        let tracker = QuotaTracker::new();
+        tracker.record("openai", 2_000, 1.0).await;
+
+        let token_cap = ProviderQuota {
+            monthly_usd: None,
+            monthly_tokens: Some(2_000),
+        };
+        assert!(tracker.status("openai", &token_cap).await.exceeded);
+
+        let spend_cap = ProviderQuota {
+            monthly_usd: Some(5.0),
+            monthly_tokens: None,
+        };
+        assert!(!tracker.status("openai", &spend_cap).await.exceeded);
+    }
+
+    #[test]
+    fn stale_month_is_reset() {
+        let mut ledger = UsageLedger {
+            month: "2000-01".to_string(),
+            providers: HashMap::from([(
+                "anthropic".to_string(),
+                ProviderUsage {
+                    tokens: 42,
+                    cost_usd: 1.0,
+                },
+            )]),
+        };
+        roll_over(&mut ledger);
+        assert!(ledger.providers.is_empty());
+        assert_eq!(ledger.month, current_month());
+    }
+
+    #[tokio::test]
+    async fn persists_totals_to_instance_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let tracker = QuotaTracker::load(dir.path());
+        tracker.record("anthropic", 100, 0.1).await;
+        tracker.record("anthropic", 50, 0.05).await;
+        assert!(
+            !dir.path().join(USAGE_FILE_NAME).exists(),
+            "writes wait for the debounce window"
+        );
+
+        tracker.flush().await;
+        let reloaded = QuotaTracker::load(dir.path());
+        assert_eq!(reloaded.usage("anthropic").await.tokens, 150);
+        assert!(!dir.path().join("provider_usage.json.tmp").exists());
+    }
+}
@@ -0,0 +1,348 @@
+//! Opt-in cache of completion responses.
+//!
+//! Calls whose output only depends on their input — compaction summaries,
+//! cortex bulletins and profiles — opt in with
+//! `SpacebotModel::with_response_cache()`. When `[llm.response_cache]` is
+//! enabled, a repeat of the same prompt against the same model and
+//! temperature within the TTL is answered from memory instead of the
+//! provider. Only plain-text responses are stored, so a hit never replays
+//! tool calls and their side effects.
+
+use crate::config::ResponseCacheConfig;
+use crate::llm::model::RawResponse;
+
+use rig::completion::{self, CompletionRequest};
+use rig::message::AssistantContent;
+use rig::one_or_many::OneOrMany;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Identifies a cacheable completion: model, prompt fingerprint, temperature.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResponseCacheKey {
+    model: String,
+    prompt_hash: String,
+    temperature_bits: Option<u64>,
+}
+
+/// Everything in a request that shapes the response, apart from the model
+/// and temperature which are part of the key directly.
+#[derive(Serialize)]
+struct PromptFingerprint<'a> {
+    preamble: &'a Option<String>,
+    chat_history: &'a OneOrMany<rig::message::Message>,
+    documents: &'a [completion::Document],
+    tools: &'a [completion::ToolDefinition],
+    max_tokens: Option<u64>,
+    additional_params: &'a Option<serde_json::Value>,
+}
+
+impl ResponseCacheKey {
+    /// Fingerprint a request. Returns `None` if it can't be serialized, in
+    /// which case the call simply isn't cached.
+    pub fn new(model: &str, request: &CompletionRequest) -> Option<Self> {
+        let fingerprint = PromptFingerprint {
+            preamble: &request.preamble,
+            chat_history: &request.chat_history,
+            documents: &request.documents,
+            tools: &request.tools,
+            max_tokens: request.max_tokens,
+            additional_params: &request.additional_params,
+        };
+        Self::from_fingerprint(model, &fingerprint, request.temperature)
+    }
+
+    fn from_fingerprint(
+        model: &str,
+        fingerprint: &PromptFingerprint<'_>,
+        temperature: Option<f64>,
+    ) -> Option<Self> {
+        let bytes = serde_json::to_vec(fingerprint).ok()?;
+
+        Some(Self {
+            model: model.to_string(),
+            prompt_hash: hex::encode(Sha256::digest(&bytes)),
+            temperature_bits: temperature.map(f64::to_bits),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CachedResponse {
+    choice: OneOrMany<AssistantContent>,
+    usage: completion::Usage,
+    body: serde_json::Value,
+    message_id: Option<String>,
+    stored_at: Instant,
+}
+
+/// Cache hit statistics, reported by the providers API.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct ResponseCacheStats {
+    pub enabled: bool,
+    pub entries: usize,
+    pub hits: u64,
+    pub misses: u64,
+    /// Input plus output tokens the provider would have billed for the hits.
+    pub tokens_saved: u64,
+}
+
+/// In-memory response cache shared by every model on an `LlmManager`.
+#[derive(Debug, Default)]
+pub struct ResponseCache {
+    entries: Mutex<HashMap<ResponseCacheKey, CachedResponse>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    tokens_saved: AtomicU64,
+}
+
+impl ResponseCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Look up a fresh response. Hits report zero usage, since nothing was
+    /// billed for them.
+    pub fn get(
+        &self,
+        key: &ResponseCacheKey,
+        config: &ResponseCacheConfig,
+    ) -> Option<completion::CompletionResponse<RawResponse>> {
+        if !config.enabled {
+            return None;
+        }
+
+        let ttl = Duration::from_secs(config.ttl_secs);
+        let mut entries = self.lock();
+        let cached = match entries.get(key) {
+            Some(cached) if cached.stored_at.elapsed() < ttl => cached.clone(),
+            Some(_) => {
+                entries.remove(key);
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+        };
+        drop(entries);
+
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        self.tokens_saved.fetch_add(
+            cached.usage.input_tokens + cached.usage.output_tokens,
+            Ordering::Relaxed,
+        );
+
+        Some(completion::CompletionResponse {
+            choice: cached.choice,
+            usage: completion::Usage::default(),
+            raw_response: RawResponse { body: cached.body },
+            message_id: cached.message_id,
+        })
+    }
+
+    /// Store a response if caching is enabled and it is text only.
+    pub fn insert(
+        &self,
+        key: ResponseCacheKey,
+        response: &completion::CompletionResponse<RawResponse>,
+        config: &ResponseCacheConfig,
+    ) {
+        if !config.enabled || config.max_entries == 0 || !is_text_only(&response.choice) {
+            return;
+        }
+
+        let ttl = Duration::from_secs(config.ttl_secs);
+        let mut entries = self.lock();
+        if entries.len() >= config.max_entries {
+            entries.retain(|_, cached| cached.stored_at.elapsed() < ttl);
+        }
+        while entries.len() >= config.max_entries {
+            let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, cached)| cached.stored_at)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            entries.remove(&oldest);
+        }
+
+        entries.insert(
+            key,
+            CachedResponse {
+                choice: response.choice.clone(),
+                usage: response.usage,
+                body: response.raw_response.body.clone(),
+                message_id: response.message_id.clone(),
+                stored_at: Instant::now(),
+            },
+        );
+    }
+
+    pub fn stats(&self, config: &ResponseCacheConfig) -> ResponseCacheStats {
+        ResponseCacheStats {
+            enabled: config.enabled,
+            entries: self.lock().len(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            tokens_saved: self.tokens_saved.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Drop every cached response. Returns how many were removed.
+    pub fn clear(&self) -> usize {
+        let mut entries = self.lock();
+        let removed = entries.len();
+        entries.clear();
+        removed
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<ResponseCacheKey, CachedResponse>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn is_text_only(choice: &OneOrMany<AssistantContent>) -> bool {
+    choice.iter().all(|content| {
+        matches!(
+            content,
+            AssistantContent::Text(_) | AssistantContent::Reasoning(_)
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PREAMBLE: Option<String> = None;
+
+    fn key(model: &str, prompt: &str, temperature: Option<f64>) -> ResponseCacheKey {
+        let chat_history = OneOrMany::one(rig::message::Message::user(prompt));
+        let fingerprint = PromptFingerprint {
+            preamble: &PREAMBLE,
+            chat_history: &chat_history,
+            documents: &[],
+            tools: &[],
+            max_tokens: None,
+            additional_params: &None,
+        };
+        ResponseCacheKey::from_fingerprint(model, &fingerprint, temperature).unwrap()
+    }
+
+    fn text_response(text: &str) -> completion::CompletionResponse<RawResponse> {
+        completion::CompletionResponse {
+            choice: OneOrMany::one(AssistantContent::Text(rig::message::Text {
+                text: text.into(),
+            })),
+            usage: completion::Usage {
+                input_tokens: 100,
+                output_tokens: 20,
+                total_tokens: 120,
+                cached_input_tokens: 0,
+            },
+            raw_response: RawResponse {
+                body: serde_json::json!({}),
+            },
+            message_id: None,
+        }
+    }
+
+    fn enabled() -> ResponseCacheConfig {
+        ResponseCacheConfig {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn key_separates_prompt_model_and_temperature() {
+        let base = key("anthropic/a", "hello", Some(0.2));
+        assert_eq!(base, key("anthropic/a", "hello", Some(0.2)));
+        assert_ne!(base, key("anthropic/a", "other", Some(0.2)));
+        assert_ne!(base, key("anthropic/b", "hello", Some(0.2)));
+        assert_ne!(base, key("anthropic/a", "hello", None));
+    }
+
+    #[test]
+    fn hits_are_counted_and_report_no_usage() {
+        let cache = ResponseCache::new();
+        let config = enabled();
+        let key = key("anthropic/a", "hello", None);
+
+        assert!(cache.get(&key, &config).is_none());
+        cache.insert(key.clone(), &text_response("summary"), &config);
+        let hit = cache.get(&key, &config).expect("cached response");
+        assert_eq!(hit.usage.input_tokens, 0);
+
+        let stats = cache.stats(&config);
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+        assert_eq!(stats.tokens_saved, 120);
+    }
+
+    #[test]
+    fn clear_drops_every_entry() {
+        let cache = ResponseCache::new();
+        let config = enabled();
+        cache.insert(
+            key("anthropic/a", "one", None),
+            &text_response("a"),
+            &config,
+        );
+        cache.insert(
+            key("anthropic/a", "two", None),
+            &text_response("b"),
+            &config,
+        );
+
+        assert_eq!(cache.clear(), 2);
+        assert!(
+            cache
+                .get(&key("anthropic/a", "one", None), &config)
+                .is_none()
+        );
+        assert_eq!(cache.clear(), 0);
+    }
+
+    #[test]
+    fn disabled_or_expired_entries_miss() {
+        let cache = ResponseCache::new();
+        let key = key("anthropic/a", "hello", None);
+
+        cache.insert(key.clone(), &text_response("summary"), &Default::default());
+        assert!(cache.get(&key, &enabled()).is_none());
+
+        let expired = ResponseCacheConfig {
+            ttl_secs: 0,
+            ..enabled()
+        };
+        cache.insert(key.clone(), &text_response("summary"), &expired);
+        assert!(cache.get(&key, &expired).is_none());
+        assert_eq!(cache.stats(&expired).entries, 0);
+    }
+
+    #[test]
+    fn oldest_entry_is_evicted_at_capacity() {
+        let cache = ResponseCache::new();
+        let config = ResponseCacheConfig {
+            max_entries: 1,
+            ..enabled()
+        };
+        let first = key("anthropic/a", "one", None);
+        let second = key("anthropic/a", "two", None);
+
+        cache.insert(first.clone(), &text_response("1"), &config);
+        cache.insert(second.clone(), &text_response("2"), &config);
+
+        assert!(cache.get(&first, &config).is_none());
+        assert!(cache.get(&second, &config).is_some());
+    }
+}
@@ -24,6 +24,19 @@ pub struct RoutingConfig {
     /// try the next model in its chain.
     pub fallbacks: HashMap<String, Vec<String>>,
 
+    /// Interchangeable alternatives per model — the same model served through
+    /// another gateway (e.g. direct Anthropic vs OpenRouter). Only consulted
+    /// when `latency_aware` is set.
+    pub equivalents: HashMap<String, Vec<String>>,
+
+    /// Context window (in tokens) per model. Models not listed use the
+    /// agent's `context_window`.
+    pub context_windows: HashMap<String, usize>,
+
+    /// Prefer the fastest healthy model among a model and its equivalents,
+    /// based on rolling latency and error rates tracked by `LlmManager`.
+    pub latency_aware: bool,
+
     /// How long to deprioritize a rate-limited model (seconds).
     pub rate_limit_cooldown_secs: u64,
 
@@ -32,6 +45,74 @@ pub struct RoutingConfig {
     pub worker_thinking_effort: String,
     pub compactor_thinking_effort: String,
     pub cortex_thinking_effort: String,
+
+    /// Sampling overrides per process type, keyed by "channel", "branch",
+    /// "worker", "compactor", or "cortex".
+    pub sampling: HashMap<String, SamplingConfig>,
+
+    /// Sampling overrides for individual channels, keyed by channel ID.
+    /// Applied to the channel process on top of its process-type settings.
+    pub channel_sampling: HashMap<String, SamplingConfig>,
+
+    /// Model overrides for individual channels, keyed by channel ID.
+    /// Persisted in the `channels` table rather than config.toml, and set
+    /// through `PUT /api/channels/routing`.
+    pub channel_overrides: HashMap<String, ChannelRouting>,
+}
+
+/// Per-channel model overrides for the processes a channel runs. Unset
+/// fields fall through to the process-type defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ChannelRouting {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worker: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compactor: Option<String>,
+}
+
+impl ChannelRouting {
+    /// The override for a process type, if any. Cortex processes are not
+    /// tied to a channel and never take one.
+    pub fn model_for(&self, process_type: ProcessType) -> Option<&str> {
+        match process_type {
+            ProcessType::Channel => self.channel.as_deref(),
+            ProcessType::Branch => self.branch.as_deref(),
+            ProcessType::Worker => self.worker.as_deref(),
+            ProcessType::Compactor => self.compactor.as_deref(),
+            ProcessType::Cortex => None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.channel.is_none()
+            && self.branch.is_none()
+            && self.worker.is_none()
+            && self.compactor.is_none()
+    }
+}
+
+/// Sampling parameters sent with each completion request. Unset fields
+/// leave the provider's default in place.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SamplingConfig {
+    pub temperature: Option<f64>,
+    pub top_p: Option<f64>,
+    pub max_tokens: Option<u64>,
+}
+
+impl SamplingConfig {
+    /// Fill fields left unset here from `base`.
+    pub fn or(self, base: SamplingConfig) -> SamplingConfig {
+        SamplingConfig {
+            temperature: self.temperature.or(base.temperature),
+            top_p: self.top_p.or(base.top_p),
+            max_tokens: self.max_tokens.or(base.max_tokens),
+        }
+    }
 }
 
 impl Default for RoutingConfig {
@@ -52,27 +133,50 @@ impl RoutingConfig {
             voice: String::new(),
             task_overrides: HashMap::new(),
             fallbacks: HashMap::new(),
+            equivalents: HashMap::new(),
+            context_windows: HashMap::new(),
+            latency_aware: false,
             rate_limit_cooldown_secs: 60,
             channel_thinking_effort: "auto".into(),
             branch_thinking_effort: "auto".into(),
             worker_thinking_effort: "auto".into(),
             compactor_thinking_effort: "auto".into(),
             cortex_thinking_effort: "auto".into(),
+            sampling: HashMap::new(),
+            channel_sampling: HashMap::new(),
+            channel_overrides: HashMap::new(),
         }
     }
 }
 
 impl RoutingConfig {
-    /// Resolve the model name for a process type and optional task type.
-    pub fn resolve(&self, process_type: ProcessType, task_type: Option<&str>) -> &str {
-        // Check task-type override first (only for workers and branches)
-        if let Some(task) = task_type
-            && matches!(process_type, ProcessType::Worker | ProcessType::Branch)
-            && let Some(override_model) = self.task_overrides.get(task)
+    /// Resolve the model name for a process type, honoring the channel's
+    /// override when the process runs on behalf of a channel.
+    pub fn resolve(&self, process_type: ProcessType, channel_id: Option<&str>) -> &str {
+        if let Some(channel_id) = channel_id
+            && let Some(override_model) = self
+                .channel_overrides
+                .get(channel_id)
+                .and_then(|routing| routing.model_for(process_type))
+        {
+            return override_model;
+        }
+
+        self.default_for(process_type)
+    }
+
+    /// Resolve the model name for a worker or branch spawned with a task type.
+    pub fn resolve_for_task(&self, process_type: ProcessType, task_type: &str) -> &str {
+        if matches!(process_type, ProcessType::Worker | ProcessType::Branch)
+            && let Some(override_model) = self.task_overrides.get(task_type)
         {
             return override_model;
         }
 
+        self.default_for(process_type)
+    }
+
+    fn default_for(&self, process_type: ProcessType) -> &str {
         match process_type {
             ProcessType::Channel => &self.channel,
             ProcessType::Branch => &self.branch,
@@ -101,6 +205,16 @@ impl RoutingConfig {
         "auto"
     }
 
+    /// Resolve sampling for a process (by its metric label, e.g. "channel")
+    /// and, for channels, the channel ID.
+    pub fn sampling_for(&self, process_type: &str, channel_id: Option<&str>) -> SamplingConfig {
+        let process = self.sampling.get(process_type).copied().unwrap_or_default();
+        match channel_id.and_then(|id| self.channel_sampling.get(id)) {
+            Some(channel) if process_type == "channel" => channel.or(process),
+            _ => process,
+        }
+    }
+
     /// Get the fallback chain for a model, if any.
     pub fn get_fallbacks(&self, model_name: &str) -> &[String] {
         self.fallbacks
@@ -108,6 +222,23 @@ impl RoutingConfig {
             .map(|v| v.as_slice())
             .unwrap_or(&[])
     }
+
+    /// Get the context window for a model, falling back to `default` when the
+    /// model has no entry in `context_windows`.
+    pub fn context_window_for(&self, model_name: &str, default: usize) -> usize {
+        self.context_windows
+            .get(model_name)
+            .copied()
+            .unwrap_or(default)
+    }
+
+    /// Get the interchangeable alternatives for a model, if any.
+    pub fn get_equivalents(&self, model_name: &str) -> &[String] {
+        self.equivalents
+            .get(model_name)
+            .map(|v| v.as_slice())
+            .unwrap_or(&[])
+    }
 }
 
 /// Whether an HTTP status code should trigger a fallback to the next model.
@@ -470,3 +601,74 @@ pub fn is_rate_limit_error(error_message: &str) -> bool {
     let lower = error_message.to_lowercase();
     lower.contains("429") || lower.contains("rate limit")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_sampling_layers_over_process_sampling() {
+        let mut routing = RoutingConfig::default();
+        routing.sampling.insert(
+            "channel".into(),
+            SamplingConfig {
+                temperature: Some(0.7),
+                top_p: Some(0.9),
+                max_tokens: None,
+            },
+        );
+        routing.channel_sampling.insert(
+            "discord:42".into(),
+            SamplingConfig {
+                temperature: Some(1.1),
+                ..Default::default()
+            },
+        );
+
+        let sampling = routing.sampling_for("channel", Some("discord:42"));
+        assert_eq!(sampling.temperature, Some(1.1));
+        assert_eq!(sampling.top_p, Some(0.9));
+
+        let other_channel = routing.sampling_for("channel", Some("discord:7"));
+        assert_eq!(other_channel.temperature, Some(0.7));
+
+        // Channel overrides only apply to the channel process itself.
+        let worker = routing.sampling_for("worker", Some("discord:42"));
+        assert_eq!(worker, SamplingConfig::default());
+    }
+
+    #[test]
+    fn channel_overrides_apply_to_their_channel_only() {
+        let mut routing = RoutingConfig::for_model("anthropic/claude-sonnet-4".into());
+        routing.channel_overrides.insert(
+            "discord:42".into(),
+            ChannelRouting {
+                channel: Some("anthropic/claude-haiku-4.5".into()),
+                worker: Some("anthropic/claude-opus-4".into()),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            routing.resolve(ProcessType::Channel, Some("discord:42")),
+            "anthropic/claude-haiku-4.5"
+        );
+        assert_eq!(
+            routing.resolve(ProcessType::Worker, Some("discord:42")),
+            "anthropic/claude-opus-4"
+        );
+        // Unset fields fall through to the process-type default.
+        assert_eq!(
+            routing.resolve(ProcessType::Branch, Some("discord:42")),
+            "anthropic/claude-sonnet-4"
+        );
+        assert_eq!(
+            routing.resolve(ProcessType::Channel, Some("discord:7")),
+            "anthropic/claude-sonnet-4"
+        );
+        assert_eq!(
+            routing.resolve(ProcessType::Channel, None),
+            "anthropic/claude-sonnet-4"
+        );
+    }
+}
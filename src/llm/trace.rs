@@ -0,0 +1,257 @@
+//! Per-conversation traces of LLM completion calls.
+//!
+//! Every provider attempt made through `SpacebotModel` is emitted as an
+//! `llm.completion` tracing span carrying model, latency, token counts, tool
+//! calls and a truncated prompt. When `[telemetry] otlp_endpoint` is set those
+//! spans are exported over OTLP with the rest of the process traces.
+//!
+//! Calls made on behalf of a conversation (channel, branch, worker, compactor)
+//! are also kept here, in memory, so the last few completions behind an odd
+//! reply can be inspected through `GET /api/traces/{conversation_id}` without
+//! a collector. The store is bounded per conversation and in the number of
+//! conversations; nothing is written to disk. Prompt and response text is
+//! truncated and scrubbed of known secret formats before it is stored.
+
+use serde::Serialize;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Traces kept per conversation, oldest dropped first.
+pub const MAX_TRACES_PER_CONVERSATION: usize = 25;
+
+/// Conversations tracked at once. The least recently active one is evicted.
+pub const MAX_CONVERSATIONS: usize = 100;
+
+/// Prompt, system prompt and response text is cut to this many characters.
+pub const MAX_TEXT_CHARS: usize = 2000;
+
+/// Tool call arguments are cut to this many characters.
+const MAX_ARGUMENT_CHARS: usize = 500;
+
+/// One completion attempt against a provider.
+#[derive(Debug, Clone, Serialize)]
+pub struct CompletionTrace {
+    pub id: u64,
+    pub started_at: String,
+    pub conversation_id: String,
+    pub agent_id: Option<String>,
+    pub process_type: Option<String>,
+    pub worker_type: Option<String>,
+    pub model: String,
+    pub duration_ms: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cached_input_tokens: u64,
+    pub tool_calls: Vec<TracedToolCall>,
+    /// Truncated system prompt.
+    pub system_prompt: String,
+    /// Truncated text of the latest message in the request.
+    pub prompt: String,
+    /// Truncated text of the response.
+    pub response: String,
+    pub error: Option<String>,
+}
+
+/// A tool call requested by the model.
+#[derive(Debug, Clone, Serialize)]
+pub struct TracedToolCall {
+    pub name: String,
+    pub arguments: String,
+}
+
+/// A completion attempt as observed by the caller, before truncation.
+pub struct CompletionRecord<'a> {
+    pub conversation_id: &'a str,
+    pub agent_id: Option<&'a str>,
+    pub process_type: Option<&'a str>,
+    pub worker_type: Option<&'a str>,
+    pub model: &'a str,
+    pub duration: std::time::Duration,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cached_input_tokens: u64,
+    /// Tool name and JSON arguments for each requested call.
+    pub tool_calls: Vec<(String, String)>,
+    pub system_prompt: &'a str,
+    pub prompt: &'a str,
+    pub response: &'a str,
+    pub error: Option<String>,
+}
+
+/// Bounded in-memory store of completion traces, grouped by conversation.
+#[derive(Debug)]
+pub struct TraceStore {
+    next_id: AtomicU64,
+    conversations: Mutex<HashMap<String, VecDeque<CompletionTrace>>>,
+}
+
+impl TraceStore {
+    pub fn new() -> Self {
+        Self {
+            next_id: AtomicU64::new(1),
+            conversations: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Truncate, scrub and store a completion attempt.
+    pub fn record(&self, record: CompletionRecord<'_>) {
+        let started_at =
+            chrono::Utc::now() - chrono::Duration::from_std(record.duration).unwrap_or_default();
+        let trace = CompletionTrace {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            started_at: started_at.to_rfc3339(),
+            conversation_id: record.conversation_id.to_string(),
+            agent_id: record.agent_id.map(str::to_string),
+            process_type: record.process_type.map(str::to_string),
+            worker_type: record.worker_type.map(str::to_string),
+            model: record.model.to_string(),
+            duration_ms: record.duration.as_millis() as u64,
+            input_tokens: record.input_tokens,
+            output_tokens: record.output_tokens,
+            cached_input_tokens: record.cached_input_tokens,
+            tool_calls: record
+                .tool_calls
+                .into_iter()
+                .map(|(name, arguments)| TracedToolCall {
+                    name,
+                    arguments: clean_text(&arguments, MAX_ARGUMENT_CHARS),
+                })
+                .collect(),
+            system_prompt: clean_text(record.system_prompt, MAX_TEXT_CHARS),
+            prompt: clean_text(record.prompt, MAX_TEXT_CHARS),
+            response: clean_text(record.response, MAX_TEXT_CHARS),
+            error: record.error.map(|error| clean_text(&error, MAX_TEXT_CHARS)),
+        };
+
+        let mut conversations = self
+            .conversations
+            .lock()
+            .expect("trace store lock poisoned");
+        if !conversations.contains_key(&trace.conversation_id)
+            && conversations.len() >= MAX_CONVERSATIONS
+        {
+            evict_least_recent(&mut conversations);
+        }
+        let traces = conversations
+            .entry(trace.conversation_id.clone())
+            .or_default();
+        traces.push_back(trace);
+        while traces.len() > MAX_TRACES_PER_CONVERSATION {
+            traces.pop_front();
+        }
+    }
+
+    /// Traces for a conversation, newest first.
+    pub fn for_conversation(&self, conversation_id: &str) -> Vec<CompletionTrace> {
+        self.conversations
+            .lock()
+            .expect("trace store lock poisoned")
+            .get(conversation_id)
+            .map(|traces| traces.iter().rev().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Drop every trace kept for a conversation. Returns how many were removed.
+    pub fn clear_conversation(&self, conversation_id: &str) -> usize {
+        self.conversations
+            .lock()
+            .expect("trace store lock poisoned")
+            .remove(conversation_id)
+            .map_or(0, |traces| traces.len())
+    }
+}
+
+impl Default for TraceStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Drop the conversation whose newest trace is oldest.
+fn evict_least_recent(conversations: &mut HashMap<String, VecDeque<CompletionTrace>>) {
+    let oldest = conversations
+        .iter()
+        .min_by_key(|(_, traces)| traces.back().map_or(0, |trace| trace.id))
+        .map(|(conversation_id, _)| conversation_id.clone());
+    if let Some(conversation_id) = oldest {
+        conversations.remove(&conversation_id);
+    }
+}
+
+/// Truncate to `max_chars` and scrub known secret formats.
+pub fn clean_text(text: &str, max_chars: usize) -> String {
+    let truncated = match text.char_indices().nth(max_chars) {
+        Some((index, _)) => format!("{}…", &text[..index]),
+        None => text.to_string(),
+    };
+    crate::secrets::scrub::scrub_leaks(&truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(store: &TraceStore, conversation_id: &str, response: &str) {
+        store.record(CompletionRecord {
+            conversation_id,
+            agent_id: Some("main"),
+            process_type: Some("channel"),
+            worker_type: None,
+            model: "anthropic/claude-sonnet-4",
+            duration: std::time::Duration::from_millis(120),
+            input_tokens: 10,
+            output_tokens: 5,
+            cached_input_tokens: 0,
+            tool_calls: vec![("reply".into(), r#"{"content":"hi"}"#.into())],
+            system_prompt: "You are a bot.",
+            prompt: "hello",
+            response,
+            error: None,
+        });
+    }
+
+    #[test]
+    fn keeps_newest_traces_per_conversation() {
+        let store = TraceStore::new();
+        for index in 0..MAX_TRACES_PER_CONVERSATION + 3 {
+            record(&store, "discord:1", &index.to_string());
+        }
+        record(&store, "discord:2", "other");
+
+        let traces = store.for_conversation("discord:1");
+        assert_eq!(traces.len(), MAX_TRACES_PER_CONVERSATION);
+        assert_eq!(
+            traces[0].response,
+            (MAX_TRACES_PER_CONVERSATION + 2).to_string()
+        );
+        assert_eq!(store.for_conversation("discord:2").len(), 1);
+        assert!(store.for_conversation("missing").is_empty());
+    }
+
+    #[test]
+    fn evicts_least_recently_active_conversation() {
+        let store = TraceStore::new();
+        for index in 0..MAX_CONVERSATIONS {
+            record(&store, &format!("conversation:{index}"), "ok");
+        }
+        record(&store, "conversation:0", "still active");
+        record(&store, "conversation:new", "ok");
+
+        assert!(store.for_conversation("conversation:1").is_empty());
+        assert_eq!(store.for_conversation("conversation:0").len(), 2);
+        assert_eq!(store.for_conversation("conversation:new").len(), 1);
+    }
+
+    #[test]
+    fn truncates_long_text() {
+        let store = TraceStore::new();
+        let long = "é".repeat(MAX_TEXT_CHARS + 50);
+        record(&store, "discord:1", &long);
+
+        let response = &store.for_conversation("discord:1")[0].response;
+        assert_eq!(response.chars().count(), MAX_TEXT_CHARS + 1);
+        assert!(response.ends_with('…'));
+    }
+}
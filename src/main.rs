@@ -6,6 +6,7 @@ use clap::{Parser, Subcommand};
 use futures::StreamExt as _;
 
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
@@ -174,6 +175,19 @@ enum SecretsCommand {
         #[arg(long)]
         overwrite: bool,
     },
+    /// Encrypt plaintext provider keys in config.toml in place
+    EncryptConfig {
+        /// Store a newly generated config key in the OS credential store
+        /// instead of `config.key` in the instance directory
+        #[arg(long)]
+        keystore: bool,
+    },
+}
+
+/// Key for `active_channels`. Scoped by agent because several agents can be
+/// dispatched into the same platform conversation.
+fn active_channel_key(agent_id: &str, conversation_id: &str) -> String {
+    format!("{agent_id}/{conversation_id}")
 }
 
 /// Tracks an active conversation channel and its message sender.
@@ -241,7 +255,11 @@ fn render_conversation_history_backfill(
         .filter(|entry| entry.role == "user" || entry.role == "assistant")
         .map(|entry| {
             let author = if entry.role == "assistant" {
-                "(you)".to_string()
+                if spacebot::agent::takeover::is_operator_message(entry) {
+                    "(operator, as you)".to_string()
+                } else {
+                    "(you)".to_string()
+                }
             } else {
                 entry
                     .sender_name
@@ -309,10 +327,15 @@ fn forward_sse_event(
 
 /// Route an outbound response to the messaging adapter using the pinned target
 /// message for platform routing metadata (thread_ts, channel_id, etc.).
+/// Content goes through the agent's outbox so transient platform failures are
+/// retried in order; tracked responses report the final outcome through their
+/// receipt.
 async fn route_outbound(
     messaging: &std::sync::Arc<spacebot::messaging::MessagingManager>,
+    outbox: &spacebot::messaging::outbox::Outbox,
     target: &spacebot::InboundMessage,
     response: spacebot::OutboundResponse,
+    receipt: Option<spacebot::DeliveryReceipt>,
 ) {
     match response {
         spacebot::OutboundResponse::Status(status) => {
@@ -320,11 +343,7 @@ async fn route_outbound(
                 tracing::warn!(%error, "failed to send status update");
             }
         }
-        response => {
-            if let Err(error) = messaging.respond(target, response).await {
-                tracing::error!(%error, "failed to send outbound response");
-            }
-        }
+        response => outbox.deliver(messaging, target, response, receipt).await,
     }
 }
 
@@ -628,12 +647,17 @@ fn cmd_secrets(
     config_path: Option<std::path::PathBuf>,
     secrets_cmd: SecretsCommand,
 ) -> anyhow::Result<()> {
+    // Rewrites config.toml locally; doesn't need a running instance.
+    if let SecretsCommand::EncryptConfig { keystore } = secrets_cmd {
+        return cmd_encrypt_config(&config_path, keystore);
+    }
+
     // Bootstrap the secrets store so `secret:` references in config resolve.
     bootstrap_secrets_store(&config_path);
 
     let config = load_config(&config_path)?;
     let api_base = format!("http://{}:{}/api", config.api.bind, config.api.port);
-    let auth_token = config.api.auth_token.clone();
+    let auth_token = config.api.auth.active_key().map(ToString::to_string);
 
     let runtime = tokio::runtime::Builder::new_current_thread()
         .enable_all()
@@ -1017,10 +1041,102 @@ fn cmd_secrets(
                 }
                 Ok(())
             }
+            SecretsCommand::EncryptConfig { .. } => {
+                unreachable!("encrypt-config is dispatched before the API client is built")
+            }
         }
     })
 }
 
+/// Encrypt plaintext `[llm]` credentials in config.toml with the config key,
+/// generating the key on first use.
+fn cmd_encrypt_config(
+    config_path: &Option<std::path::PathBuf>,
+    use_keystore: bool,
+) -> anyhow::Result<()> {
+    use spacebot::secrets::config_cipher;
+
+    let instance_dir = resolve_instance_dir(config_path);
+    let toml_path = config_path
+        .clone()
+        .unwrap_or_else(|| instance_dir.join("config.toml"));
+
+    let content = std::fs::read_to_string(&toml_path)
+        .with_context(|| format!("failed to read {}", toml_path.display()))?;
+    let mut doc: toml_edit::DocumentMut = content
+        .parse()
+        .with_context(|| format!("failed to parse {}", toml_path.display()))?;
+
+    let (key, source) = match config_cipher::load_config_key(&instance_dir)? {
+        Some(existing) => existing,
+        None => {
+            let created = config_cipher::create_config_key(&instance_dir, use_keystore)?;
+            match &created.1 {
+                config_cipher::ConfigKeySource::File(path) => {
+                    eprintln!("Generated config key at {}", path.display());
+                    eprintln!("Keep this file out of backups you share alongside config.toml.");
+                }
+                config_cipher::ConfigKeySource::Keystore => {
+                    eprintln!("Generated config key in the OS credential store.");
+                }
+            }
+            created
+        }
+    };
+    let cipher = config_cipher::ConfigCipher::from_key(&key)?;
+
+    let encrypted = config_cipher::encrypt_llm_keys(&mut doc, &cipher)?;
+    if encrypted.is_empty() {
+        eprintln!(
+            "No plaintext provider keys found in {}.",
+            toml_path.display()
+        );
+        return Ok(());
+    }
+
+    std::fs::write(&toml_path, doc.to_string())
+        .with_context(|| format!("failed to write {}", toml_path.display()))?;
+
+    let source = match source {
+        config_cipher::ConfigKeySource::File(path) => path.display().to_string(),
+        config_cipher::ConfigKeySource::Keystore => "OS credential store".to_string(),
+    };
+    eprintln!(
+        "Encrypted {} value(s) with config key from {source}:",
+        encrypted.len()
+    );
+    for path in encrypted {
+        eprintln!("  {path}");
+    }
+    Ok(())
+}
+
+/// Load the inline config key (if set up) so `enc:v1:` values in config.toml
+/// decrypt during config resolution and agents can encrypt stored content.
+fn bootstrap_config_cipher(instance_dir: &std::path::Path) {
+    use spacebot::secrets::config_cipher;
+
+    let key = match config_cipher::load_config_key(instance_dir) {
+        Ok(Some((key, _source))) => key,
+        Ok(None) => return,
+        Err(error) => {
+            eprintln!("warning: failed to load config key: {error}");
+            return;
+        }
+    };
+    match config_cipher::ConfigCipher::from_key(&key) {
+        Ok(cipher) => spacebot::config::set_resolve_config_cipher(Arc::new(cipher)),
+        Err(error) => {
+            eprintln!("warning: invalid config key: {error}");
+            return;
+        }
+    }
+    // The same key is the master secret for at-rest content encryption.
+    if let Err(error) = spacebot::secrets::content_cipher::set_master_key(&key) {
+        eprintln!("warning: config key can't be used for content encryption: {error}");
+    }
+}
+
 /// Build an authenticated HTTP request to the control API.
 fn secrets_api_request(
     client: &reqwest::Client,
@@ -1318,6 +1434,7 @@ fn bootstrap_secrets_store(
     spacebot::secrets::keystore::probe_keyring_support();
 
     let instance_dir = resolve_instance_dir(config_path);
+    bootstrap_config_cipher(&instance_dir);
 
     let data_dir = instance_dir.join("data");
     if let Err(error) = std::fs::create_dir_all(&data_dir) {
@@ -1473,6 +1590,9 @@ fn has_provider_credentials(
     llm_config.has_any_key()
         || spacebot::auth::credentials_path(instance_dir).exists()
         || spacebot::openai_auth::credentials_path(instance_dir).exists()
+        || llm_config.oauth.keys().any(|provider_id| {
+            spacebot::llm::oauth::credentials_path(instance_dir, provider_id).exists()
+        })
 }
 
 async fn run(
@@ -1509,14 +1629,15 @@ async fn run(
     > = Arc::new(ArcSwap::from_pointee(std::collections::HashMap::new()));
 
     // Start HTTP API server if enabled
-    let mut api_state = spacebot::api::ApiState::new_with_provider_sender(
+    let api_state = spacebot::api::ApiState::new_with_provider_sender(
         provider_tx,
         agent_tx,
         agent_remove_tx,
         injection_tx.clone(),
         task_store_registry.clone(),
     );
-    api_state.auth_token = config.api.auth_token.clone();
+    api_state.auth.store(Arc::new(config.api.auth.clone()));
+    api_state.rate_limiter.reload(config.api.rate_limit.clone());
     let api_state = Arc::new(api_state);
 
     // Start background update checker
@@ -1582,11 +1703,13 @@ async fn run(
         .await
         .with_context(|| "failed to initialize LLM manager")?,
     );
+    let _health_probe = spacebot::llm::probe::spawn_health_probe(&llm_manager);
 
     // Shared embedding model (stateless, agent-agnostic)
     let embedding_cache_dir = config.instance_dir.join("embedding_cache");
     let embedding_model = Arc::new(
-        spacebot::memory::EmbeddingModel::new(&embedding_cache_dir)
+        spacebot::memory::EmbeddingModel::from_config(&config.llm, &embedding_cache_dir)
+            .await
             .context("failed to initialize embedding model")?,
     );
 
@@ -1595,7 +1718,8 @@ async fn run(
     // Initialize the language for all text lookups (must happen before PromptEngine/tools)
     spacebot::prompts::text::init("en").with_context(|| "failed to initialize language")?;
 
-    // Create the PromptEngine with bundled templates (no file watching, no user overrides)
+    // Create the PromptEngine with bundled templates. Agents apply their own
+    // edited templates on top when they are initialized.
     let prompt_engine = spacebot::prompts::PromptEngine::new("en")
         .with_context(|| "failed to initialize prompt engine")?;
 
@@ -1609,6 +1733,7 @@ async fn run(
 
     // Shared humans list (hot-reloadable via ArcSwap, same pattern as agent_links)
     let agent_humans = Arc::new(ArcSwap::from_pointee(config.humans.clone()));
+    let agent_teams = Arc::new(ArcSwap::from_pointee(config.teams.clone()));
 
     // These hold the initialized subsystems. Empty until agents are initialized.
     let mut agents: HashMap<spacebot::AgentId, spacebot::Agent> = HashMap::new();
@@ -1619,8 +1744,6 @@ async fn run(
         std::pin::Pin<Box<dyn futures::Stream<Item = spacebot::InboundMessage> + Send>>,
     > = None;
     let mut cron_schedulers_for_shutdown: Vec<Arc<spacebot::cron::Scheduler>> = Vec::new();
-    let mut _ingestion_handles: Vec<tokio::task::JoinHandle<()>> = Vec::new();
-    let mut _cortex_handles: Vec<tokio::task::JoinHandle<()>> = Vec::new();
     let bindings: Arc<ArcSwap<Vec<spacebot::config::Binding>>> =
         Arc::new(ArcSwap::from_pointee(config.bindings.clone()));
     api_state.set_bindings(bindings.clone()).await;
@@ -1637,6 +1760,41 @@ async fn run(
     api_state.set_agent_links((**agent_links.load()).clone());
     api_state.set_agent_groups(config.groups.clone());
     api_state.set_agent_humans(config.humans.clone());
+    api_state.set_agent_teams(config.teams.clone());
+
+    let config_changelog = Arc::new(spacebot::config::ConfigChangelog::open(
+        &config.instance_dir,
+    ));
+    api_state.set_config_changelog(config_changelog.clone());
+
+    match spacebot::access::connect(&config.instance_dir.join("data")).await {
+        Ok(pool) => {
+            api_state.set_access_store(Arc::new(spacebot::access::AccessStore::new(pool.clone())));
+            api_state.set_audit_log(Arc::new(spacebot::audit::AuditLog::new(pool)));
+        }
+        Err(error) => tracing::warn!(%error, "failed to open API access database"),
+    }
+
+    // Join the cluster before any inbound traffic, so no message is handled
+    // outside it. A node that can't reach the coordination database would
+    // duplicate the others' work, so it doesn't start.
+    if config.cluster.enabled {
+        let cluster = Arc::new(
+            spacebot::cluster::Cluster::connect(&config.cluster)
+                .await
+                .context("failed to join cluster")?,
+        );
+        cluster
+            .heartbeat(&spacebot::cluster::NodeLoad::default())
+            .await
+            .context("failed to join cluster")?;
+        tracing::info!(
+            node_id = cluster.node_id(),
+            leader = cluster.is_leader(),
+            "joined cluster"
+        );
+        spacebot::cluster::install(cluster);
+    }
 
     // Track whether agents have been initialized
     let mut agents_initialized = false;
@@ -1662,8 +1820,6 @@ async fn run(
             &mut messaging_manager,
             &mut inbound_stream,
             &mut cron_schedulers_for_shutdown,
-            &mut _ingestion_handles,
-            &mut _cortex_handles,
             &mut watcher_agents,
             &mut discord_permissions,
             &mut slack_permissions,
@@ -1672,6 +1828,7 @@ async fn run(
             &mut signal_permissions,
             agent_links.clone(),
             agent_humans.clone(),
+            agent_teams.clone(),
             injection_tx.clone(),
             task_store_registry.clone(),
             &bootstrapped_store,
@@ -1680,7 +1837,7 @@ async fn run(
         agents_initialized = true;
 
         // Start file watcher with populated agent data
-        _file_watcher = spacebot::config::spawn_file_watcher(
+        let config_reloader = Arc::new(spacebot::config::ConfigReloader::new(
             config_path.clone(),
             config.instance_dir.clone(),
             watcher_agents,
@@ -1694,10 +1851,16 @@ async fn run(
             llm_manager.clone(),
             agent_links.clone(),
             agent_humans.clone(),
-        );
+            agent_teams.clone(),
+            api_state.auth.clone(),
+            api_state.rate_limiter.clone(),
+        ));
+        api_state.set_config_reloader(config_reloader.clone());
+        _file_watcher =
+            spacebot::config::supervise_file_watcher(config_reloader, config_changelog.clone());
     } else {
         // Start file watcher in setup mode (no agents to watch yet)
-        _file_watcher = spacebot::config::spawn_file_watcher(
+        let config_reloader = Arc::new(spacebot::config::ConfigReloader::new(
             config_path.clone(),
             config.instance_dir.clone(),
             Vec::new(),
@@ -1711,7 +1874,13 @@ async fn run(
             llm_manager.clone(),
             agent_links.clone(),
             agent_humans.clone(),
-        );
+            agent_teams.clone(),
+            api_state.auth.clone(),
+            api_state.rate_limiter.clone(),
+        ));
+        api_state.set_config_reloader(config_reloader.clone());
+        _file_watcher =
+            spacebot::config::supervise_file_watcher(config_reloader, config_changelog.clone());
     }
 
     if foreground {
@@ -1723,7 +1892,9 @@ async fn run(
         tracing::info!(pid = std::process::id(), "spacebot daemon started");
     }
 
-    // Active conversation channels: conversation_id -> ActiveChannel
+    // Active conversation channels, keyed by agent and conversation (see
+    // `active_channel_key`) so agents sharing a platform channel each get
+    // their own channel process.
     let mut active_channels: HashMap<String, ActiveChannel> = HashMap::new();
 
     // Resume idle interactive workers that survived the restart.
@@ -1776,7 +1947,8 @@ async fn run(
             for (conversation_id, workers) in by_channel {
                 // Ensure the channel exists. If it's already in active_channels
                 // (unlikely at startup), use its state. Otherwise, pre-create it.
-                if !active_channels.contains_key(&conversation_id) {
+                let channel_key = active_channel_key(agent_id, &conversation_id);
+                if let Entry::Vacant(slot) = active_channels.entry(channel_key) {
                     // First pass: retire any workers whose sessions can't be
                     // reconnected. Only create the channel if at least one
                     // worker has a chance of resuming.
@@ -1939,29 +2111,47 @@ async fn run(
                     });
 
                     let messaging_for_outbound = messaging_manager.clone();
+                    let outbox = agent.outbox.clone();
+                    let moderator = spacebot::messaging::moderation::Moderator::new(
+                        &agent.deps,
+                        conversation_id.as_str().into(),
+                    );
                     let api_event_tx = api_state.event_tx.clone();
                     let sse_agent_id = agent_id.to_string();
                     let sse_channel_id = conversation_id.clone();
                     let outbound_handle = tokio::spawn(async move {
                         while let Some(routed) = response_rx.recv().await {
-                            let spacebot::RoutedResponse { response, target } = routed;
+                            let spacebot::RoutedResponse {
+                                response,
+                                target,
+                                receipt,
+                            } = routed;
+                            let Some(response) =
+                                moderator.moderate(response, receipt.as_ref()).await
+                            else {
+                                continue;
+                            };
                             forward_sse_event(
                                 &api_event_tx,
                                 &sse_agent_id,
                                 &sse_channel_id,
                                 &response,
                             );
-                            route_outbound(&messaging_for_outbound, &target, response).await;
+                            route_outbound(
+                                &messaging_for_outbound,
+                                &outbox,
+                                &target,
+                                response,
+                                receipt,
+                            )
+                            .await;
                         }
                     });
 
-                    active_channels.insert(
-                        conversation_id.clone(),
-                        ActiveChannel {
-                            message_tx: channel_tx,
-                            _outbound_handle: outbound_handle,
-                        },
-                    );
+                    slot.insert(ActiveChannel {
+                        message_tx: channel_tx,
+                        _outbound_handle: outbound_handle,
+                    });
 
                     tracing::info!(
                         conversation_id = %conversation_id,
@@ -1989,9 +2179,9 @@ async fn run(
                     existing.clone()
                 } else {
                     let current_bindings = bindings.load();
-                    let Some(resolved) = spacebot::config::resolve_agent_for_message(
+                    let Some(resolved) = spacebot::config::dispatch_inbound_message(
                         &current_bindings,
-                        &message,
+                        &mut message,
                         &default_agent_id,
                     ) else {
                         // Message suppressed by require_mention — drop it.
@@ -2001,10 +2191,30 @@ async fn run(
                     resolved
                 };
 
+                // In a cluster, the node holding the conversation handles it;
+                // anything else waits in the shared inbox or is a duplicate.
+                if let Some(cluster) = spacebot::cluster::global() {
+                    match cluster.accept(&message).await {
+                        Ok(spacebot::cluster::Delivery::Here) => {}
+                        Ok(delivery) => {
+                            tracing::debug!(
+                                conversation_id = %message.conversation_id,
+                                ?delivery,
+                                "inbound message left to another node"
+                            );
+                            continue;
+                        }
+                        Err(error) => {
+                            tracing::warn!(%error, "cluster inbox unavailable, handling message on this node");
+                        }
+                    }
+                }
+
                 let conversation_id = message.conversation_id.clone();
+                let channel_key = active_channel_key(&agent_id, &conversation_id);
 
                 // Find or create a channel for this conversation
-                if !active_channels.contains_key(&conversation_id) {
+                if !active_channels.contains_key(&channel_key) {
                     let Some(agent) = agents.get(&agent_id) else {
                         tracing::warn!(
                             agent_id = %agent_id,
@@ -2108,15 +2318,33 @@ async fn run(
                     // Spawn outbound response routing: reads from response_rx,
                     // sends to the messaging adapter and forwards to SSE
                     let messaging_for_outbound = messaging_manager.clone();
+                    let outbox = agent.outbox.clone();
+                    let moderator = spacebot::messaging::moderation::Moderator::new(
+                        &agent.deps,
+                        conversation_id.as_str().into(),
+                    );
                     let outbound_conversation_id = conversation_id.clone();
                     let api_event_tx = api_state.event_tx.clone();
                     let sse_agent_id = agent_id.to_string();
                     let sse_channel_id = conversation_id.clone();
                     let outbound_handle = tokio::spawn(async move {
                         while let Some(routed) = response_rx.recv().await {
-                            let spacebot::RoutedResponse { response, target } = routed;
+                            let spacebot::RoutedResponse {
+                                response,
+                                target,
+                                receipt,
+                            } = routed;
+                            let Some(response) = moderator.moderate(response, receipt.as_ref()).await else {
+                                continue;
+                            };
                             forward_sse_event(&api_event_tx, &sse_agent_id, &sse_channel_id, &response);
-                            route_outbound(&messaging_for_outbound, &target, response).await;
+                            route_outbound(
+                                &messaging_for_outbound,
+                                &outbox,
+                                &target,
+                                response,
+                                receipt,
+                            ).await;
                         }
                         tracing::debug!(
                             conversation_id = %outbound_conversation_id,
@@ -2124,7 +2352,7 @@ async fn run(
                         );
                     });
 
-                    active_channels.insert(conversation_id.clone(), ActiveChannel {
+                    active_channels.insert(channel_key.clone(), ActiveChannel {
                         message_tx: channel_tx,
                         _outbound_handle: outbound_handle,
                     });
@@ -2137,7 +2365,7 @@ async fn run(
                 }
 
                 // Forward the message to the channel
-                if let Some(active) = active_channels.get(&conversation_id) {
+                if let Some(active) = active_channels.get(&channel_key) {
                     // Emit inbound message to SSE clients
                     let sender_name = message.formatted_author.clone().or_else(|| {
                         message
@@ -2160,7 +2388,7 @@ async fn run(
                             %error,
                             "failed to forward message to channel"
                         );
-                        active_channels.remove(&conversation_id);
+                        active_channels.remove(&channel_key);
                     }
                 }
             }
@@ -2171,6 +2399,7 @@ async fn run(
             Some(agent_id) = agent_remove_rx.recv() => {
                 let key: spacebot::AgentId = Arc::from(agent_id.as_str());
                 if let Some(agent) = agents.remove(&key) {
+                    spacebot::supervisor::Supervisor::global().stop_agent(&agent_id);
                     agent.deps.mcp_manager.disconnect_all().await;
                     tracing::info!(agent_id = %agent_id, "removed agent from main loop");
                 } else {
@@ -2180,7 +2409,9 @@ async fn run(
             // Cross-agent message injection (e.g. delegated task completion retrigger).
             // Forwards the injected message to the target channel if it exists.
             Some(injection) = injection_rx.recv() => {
-                if let Some(active) = active_channels.get(&injection.conversation_id) {
+                let channel_key =
+                    active_channel_key(&injection.agent_id, &injection.conversation_id);
+                if let Some(active) = active_channels.get(&channel_key) {
                     if let Err(error) = active.message_tx.send(injection.message).await {
                         tracing::warn!(
                             %error,
@@ -2233,10 +2464,12 @@ async fn run(
                         {
                             Ok(new_llm) => {
                                 let new_llm_manager = Arc::new(new_llm);
+                                spacebot::llm::probe::spawn_health_probe(&new_llm_manager);
                                 // Update agent_humans from the reloaded config
                                 // before initialize_agents so agents see the
                                 // latest [[humans]] entries.
                                 agent_humans.store(Arc::new(new_config.humans.clone()));
+                                agent_teams.store(Arc::new(new_config.teams.clone()));
                                 let mut new_watcher_agents = Vec::new();
                                 let mut new_discord_permissions = None;
                                 let mut new_slack_permissions = None;
@@ -2253,8 +2486,6 @@ async fn run(
                                     &mut messaging_manager,
                                     &mut inbound_stream,
                                     &mut cron_schedulers_for_shutdown,
-                                    &mut _ingestion_handles,
-                                    &mut _cortex_handles,
                                     &mut new_watcher_agents,
                                     &mut new_discord_permissions,
                                     &mut new_slack_permissions,
@@ -2263,6 +2494,7 @@ async fn run(
                                     &mut new_signal_permissions,
                                     agent_links.clone(),
                                     agent_humans.clone(),
+                                    agent_teams.clone(),
                                     injection_tx.clone(),
                                     task_store_registry.clone(),
                                     &bootstrapped_store,
@@ -2270,7 +2502,7 @@ async fn run(
                                     Ok(()) => {
                                         agents_initialized = true;
                                         // Restart file watcher with the new agent data
-                                        _file_watcher = spacebot::config::spawn_file_watcher(
+                                        let config_reloader = Arc::new(spacebot::config::ConfigReloader::new(
                                             config_path.clone(),
                                             new_config.instance_dir.clone(),
                                             new_watcher_agents,
@@ -2284,6 +2516,14 @@ async fn run(
                                             new_llm_manager.clone(),
                                             agent_links.clone(),
                                             agent_humans.clone(),
+                                            agent_teams.clone(),
+                                            api_state.auth.clone(),
+                                            api_state.rate_limiter.clone(),
+                                        ));
+                                        api_state.set_config_reloader(config_reloader.clone());
+                                        _file_watcher = spacebot::config::supervise_file_watcher(
+                                            config_reloader,
+                                            config_changelog.clone(),
                                         );
                                         tracing::info!("agents initialized after provider setup");
                                     }
@@ -2319,12 +2559,23 @@ async fn run(
     // Graceful shutdown
     drop(active_channels);
 
+    // Stop supervised tasks first so adapter streams ending during shutdown
+    // aren't mistaken for crashes and restarted.
+    spacebot::supervisor::Supervisor::global().stop_all();
+
+    if let Some(cluster) = spacebot::cluster::global()
+        && let Err(error) = cluster.leave().await
+    {
+        tracing::warn!(%error, "failed to leave cluster cleanly");
+    }
+
     for scheduler in &cron_schedulers_for_shutdown {
         scheduler.shutdown().await;
     }
     drop(cron_schedulers_for_shutdown);
 
     messaging_manager.shutdown().await;
+    llm_manager.flush_usage().await;
 
     for (agent_id, agent) in agents {
         tracing::info!(%agent_id, "shutting down agent");
@@ -2388,8 +2639,6 @@ async fn initialize_agents(
         std::pin::Pin<Box<dyn futures::Stream<Item = spacebot::InboundMessage> + Send>>,
     >,
     cron_schedulers_for_shutdown: &mut Vec<Arc<spacebot::cron::Scheduler>>,
-    ingestion_handles: &mut Vec<tokio::task::JoinHandle<()>>,
-    cortex_handles: &mut Vec<tokio::task::JoinHandle<()>>,
     watcher_agents: &mut Vec<(
         String,
         std::path::PathBuf,
@@ -2404,6 +2653,7 @@ async fn initialize_agents(
     signal_permissions: &mut Option<Arc<ArcSwap<spacebot::config::SignalPermissions>>>,
     agent_links: Arc<ArcSwap<Vec<spacebot::links::AgentLink>>>,
     agent_humans: Arc<ArcSwap<Vec<spacebot::config::HumanDef>>>,
+    agent_teams: Arc<ArcSwap<Vec<spacebot::config::TeamDef>>>,
     injection_tx: tokio::sync::mpsc::Sender<spacebot::ChannelInjection>,
     task_store_registry: Arc<
         ArcSwap<std::collections::HashMap<String, Arc<spacebot::tasks::TaskStore>>>,
@@ -2465,7 +2715,7 @@ async fn initialize_agents(
         })?;
 
         // Per-agent database connections
-        let db = spacebot::db::Db::connect(&agent_config.data_dir)
+        let db = spacebot::db::Db::connect(&agent_config.data_dir, &agent_config.storage)
             .await
             .with_context(|| {
                 format!(
@@ -2528,22 +2778,81 @@ async fn initialize_agents(
             spacebot::memory::MemoryStore::with_agent_id(db.sqlite.clone(), &agent_config.id);
         let task_store = Arc::new(spacebot::tasks::TaskStore::new(db.sqlite.clone()));
         let project_store = Arc::new(spacebot::projects::ProjectStore::new(db.sqlite.clone()));
-        let embedding_table = spacebot::memory::EmbeddingTable::open_or_create(&db.lance)
-            .await
-            .with_context(|| {
-                format!("failed to init embeddings for agent '{}'", agent_config.id)
-            })?;
-
-        // Ensure FTS index exists for full-text search queries
-        if let Err(error) = embedding_table.ensure_fts_index().await {
+        let embedding_table = spacebot::memory::EmbeddingTable::open_with_dimensions(
+            &db.lance,
+            embedding_model.dimensions(),
+        )
+        .await
+        .with_context(|| format!("failed to init embeddings for agent '{}'", agent_config.id))?
+        .with_fts_config(agent_config.memory_fts.clone())
+        .with_journal(spacebot::memory::EmbeddingJournal::new(db.sqlite.clone()));
+        let reindex_memories = embedding_table.was_rebuilt();
+
+        // Ensure FTS index exists for full-text search queries. Custom
+        // tokenizer options are re-applied by rebuilding the index.
+        let fts_result = if agent_config.memory_fts.is_configured() {
+            embedding_table.rebuild_fts_index().await
+        } else {
+            embedding_table.ensure_fts_index().await
+        };
+        if let Err(error) = fts_result {
             tracing::warn!(%error, agent = %agent_config.id, "failed to create FTS index");
         }
 
-        let memory_search = Arc::new(spacebot::memory::MemorySearch::new(
+        let mut memory_search = spacebot::memory::MemorySearch::new(
             memory_store,
             embedding_table,
             embedding_model.clone(),
-        ));
+        );
+        match spacebot::memory::EpisodeTable::open_with_dimensions(
+            &db.lance,
+            embedding_model.dimensions(),
+        )
+        .await
+        {
+            Ok(episodes) => memory_search = memory_search.with_episodes(episodes),
+            Err(error) => {
+                tracing::warn!(%error, agent = %agent_config.id, "failed to init conversation episodes, episodic recall disabled");
+            }
+        }
+        let memory_search = Arc::new(memory_search);
+
+        // Embedding writes a crash cut short are repaired from the journal,
+        // and a recreated embeddings table (new agent, changed embedding
+        // model, or unrecoverable corruption) is refilled from SQLite, both
+        // in the background; search falls back to full-text matches meanwhile.
+        {
+            let memory_search = memory_search.clone();
+            let agent_id = agent_config.id.clone();
+            tokio::spawn(async move {
+                match memory_search.recover_journal().await {
+                    Ok(recovery) if recovery.reapplied + recovery.rolled_back > 0 => {
+                        tracing::info!(
+                            agent = %agent_id,
+                            reapplied = recovery.reapplied,
+                            rolled_back = recovery.rolled_back,
+                            "repaired embeddings from journal"
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(error) => {
+                        tracing::warn!(%error, agent = %agent_id, "failed to recover embedding journal");
+                    }
+                }
+                if !reindex_memories {
+                    return;
+                }
+                match memory_search.reindex_all().await {
+                    Ok(0) => {}
+                    Ok(count) => {
+                        tracing::info!(agent = %agent_id, count, "re-embedded memories");
+                    }
+                    Err(error) => {
+                        tracing::warn!(%error, agent = %agent_id, "failed to re-embed memories");
+                    }
+                }
+            });
+        }
 
         // Per-agent control and memory event buses (broadcast fan-out).
         let (event_tx, memory_event_tx) = spacebot::create_process_event_buses();
@@ -2570,12 +2879,32 @@ async fn initialize_agents(
             spacebot::skills::SkillSet::load(&config.skills_dir(), &agent_config.skills_dir())
                 .await;
 
+        // Apply prompt templates edited through the API. A broken override
+        // falls back to the bundled templates rather than failing startup.
+        let agent_prompt_engine =
+            match spacebot::prompts::versions::PromptVersionStore::new(db.sqlite.clone())
+                .active_overrides()
+                .await
+            {
+                Ok(overrides) if overrides.is_empty() => prompt_engine.clone(),
+                Ok(overrides) => prompt_engine
+                    .with_overrides(&overrides)
+                    .unwrap_or_else(|error| {
+                        tracing::warn!(%error, agent_id = %agent_config.id, "failed to apply prompt overrides, using bundled templates");
+                        prompt_engine.clone()
+                    }),
+                Err(error) => {
+                    tracing::warn!(%error, agent_id = %agent_config.id, "failed to load prompt overrides, using bundled templates");
+                    prompt_engine.clone()
+                }
+            };
+
         // Build the RuntimeConfig with all hot-reloadable values
         let runtime_config = Arc::new(spacebot::config::RuntimeConfig::new(
             &config.instance_dir,
             agent_config,
             &config.defaults,
-            prompt_engine.clone(),
+            agent_prompt_engine,
             identity,
             skills,
         ));
@@ -2590,6 +2919,32 @@ async fn initialize_agents(
         runtime_config
             .prompt_snapshots
             .store(Arc::new(prompt_snapshot_store.clone()));
+        match spacebot::conversation::ChannelStore::new(db.sqlite.clone())
+            .load_routing()
+            .await
+        {
+            Ok(overrides) => runtime_config.set_channel_routing_overrides(overrides),
+            Err(error) => {
+                tracing::warn!(%error, agent = %agent_config.id, "failed to load channel routing overrides");
+            }
+        }
+        match spacebot::agent::experiments::ExperimentStore::new(db.sqlite.clone())
+            .running()
+            .await
+        {
+            Ok(experiment) => runtime_config
+                .experiment
+                .store(Arc::new(experiment.map(Arc::new))),
+            Err(error) => {
+                tracing::warn!(%error, agent = %agent_config.id, "failed to load running experiment");
+            }
+        }
+        if let Err(error) = spacebot::memory::BulletinPinStore::new(db.sqlite.clone())
+            .reload(&runtime_config)
+            .await
+        {
+            tracing::warn!(%error, agent = %agent_config.id, "failed to load bulletin pins");
+        }
         if let Err(error) = settings_store.set_worker_log_mode(config.defaults.worker_log_mode) {
             tracing::warn!(%error, agent = %agent_config.id, "failed to set worker_log_mode from config");
         }
@@ -2645,18 +3000,22 @@ async fn initialize_agents(
             links: agent_links.clone(),
             agent_names: agent_name_map.clone(),
             humans: agent_humans.clone(),
+            teams: agent_teams.clone(),
             task_store_registry: task_store_registry.clone(),
             process_control_registry: Arc::new(
                 spacebot::agent::process_control::ProcessControlRegistry::new(),
             ),
+            branch_cache: Arc::new(spacebot::agent::branch_cache::BranchResultCache::new()),
             injection_tx: injection_tx.clone(),
         };
 
+        let outbox = spacebot::messaging::outbox::Outbox::new(db.sqlite.clone());
         let agent = spacebot::Agent {
             id: agent_id.clone(),
             config: agent_config.clone(),
             db,
             deps,
+            outbox,
         };
 
         tracing::info!(agent_id = %agent_config.id, "agent initialized");
@@ -2704,6 +3063,7 @@ async fn initialize_agents(
         let mut agent_pools = std::collections::HashMap::new();
         let mut agent_configs = Vec::new();
         let mut memory_searches = std::collections::HashMap::new();
+        let mut branch_caches = std::collections::HashMap::new();
         let mut mcp_managers = std::collections::HashMap::new();
         let mut task_stores = std::collections::HashMap::new();
         let mut project_stores = std::collections::HashMap::new();
@@ -2717,6 +3077,7 @@ async fn initialize_agents(
             api_state.register_agent_events(agent_id.to_string(), event_rx);
             agent_pools.insert(agent_id.to_string(), agent.db.sqlite.clone());
             memory_searches.insert(agent_id.to_string(), agent.deps.memory_search.clone());
+            branch_caches.insert(agent_id.to_string(), agent.deps.branch_cache.clone());
             mcp_managers.insert(agent_id.to_string(), agent.deps.mcp_manager.clone());
             task_stores.insert(agent_id.to_string(), agent.deps.task_store.clone());
             project_stores.insert(agent_id.to_string(), agent.deps.project_store.clone());
@@ -2741,6 +3102,7 @@ async fn initialize_agents(
         api_state.set_agent_pools(agent_pools);
         api_state.set_agent_configs(agent_configs);
         api_state.set_memory_searches(memory_searches);
+        api_state.set_branch_caches(branch_caches);
         api_state.set_mcp_managers(mcp_managers);
         api_state.set_task_stores(task_stores);
         api_state.set_project_stores(project_stores);
@@ -2813,6 +3175,16 @@ async fn initialize_agents(
         api_state.set_discord_permissions(perms.clone()).await;
     }
 
+    // Slash commands declared by agents' skills, registered by the startup
+    // Discord adapters when they connect.
+    let skill_commands = {
+        let skill_sets: Vec<_> = agents
+            .values()
+            .map(|agent| agent.deps.runtime_config.skills.load_full())
+            .collect();
+        spacebot::skills::merge_slash_commands(skill_sets.iter().map(|set| set.as_ref()))
+    };
+
     if let Some(discord_config) = &config.messaging.discord
         && discord_config.enabled
     {
@@ -2823,7 +3195,9 @@ async fn initialize_agents(
                 discord_permissions.clone().ok_or_else(|| {
                     anyhow::anyhow!("discord permissions not initialized when discord is enabled")
                 })?,
-            );
+            )
+            .with_slash_commands(skill_commands.clone())
+            .with_voice(discord_config.voice.clone());
             new_messaging_manager.register(adapter).await;
         }
 
@@ -2840,17 +3214,20 @@ async fn initialize_agents(
                 "discord",
                 Some(instance.name.as_str()),
             );
-            let perms = Arc::new(ArcSwap::from_pointee(
+            let perms = new_messaging_manager.instance_permissions().discord.track(
+                &runtime_key,
                 spacebot::config::DiscordPermissions::from_instance_config(
                     instance,
                     &config.bindings,
                 ),
-            ));
+            );
             let adapter = spacebot::messaging::discord::DiscordAdapter::new(
                 runtime_key,
                 &instance.token,
                 perms,
-            );
+            )
+            .with_slash_commands(skill_commands.clone())
+            .with_voice(discord_config.voice.clone());
             new_messaging_manager.register(adapter).await;
         }
     }
@@ -2899,12 +3276,13 @@ async fn initialize_agents(
                 "slack",
                 Some(instance.name.as_str()),
             );
-            let perms = Arc::new(ArcSwap::from_pointee(
+            let perms = new_messaging_manager.instance_permissions().slack.track(
+                &runtime_key,
                 spacebot::config::SlackPermissions::from_instance_config(
                     instance,
                     &config.bindings,
                 ),
-            ));
+            );
             match spacebot::messaging::slack::SlackAdapter::new(
                 runtime_key,
                 &instance.bot_token,
@@ -2956,12 +3334,13 @@ async fn initialize_agents(
                 "telegram",
                 Some(instance.name.as_str()),
             );
-            let perms = Arc::new(ArcSwap::from_pointee(
+            let perms = new_messaging_manager.instance_permissions().telegram.track(
+                &runtime_key,
                 spacebot::config::TelegramPermissions::from_instance_config(
                     instance,
                     &config.bindings,
                 ),
-            ));
+            );
             let adapter = spacebot::messaging::telegram::TelegramAdapter::new(
                 runtime_key,
                 &instance.token,
@@ -3012,6 +3391,40 @@ async fn initialize_agents(
         }
     }
 
+    if let Some(github_config) = &config.messaging.github
+        && github_config.enabled
+    {
+        if github_config.repos.is_empty() {
+            tracing::warn!("github adapter enabled but no repos are configured");
+        } else {
+            match spacebot::messaging::github::GithubAdapter::from_config(github_config) {
+                Ok(adapter) => {
+                    new_messaging_manager.register(adapter).await;
+                }
+                Err(error) => {
+                    tracing::error!(%error, "failed to build github adapter");
+                }
+            }
+        }
+    }
+
+    if let Some(irc_config) = &config.messaging.irc
+        && irc_config.enabled
+    {
+        let adapter = spacebot::messaging::irc::IrcAdapter::from_config(irc_config);
+        new_messaging_manager.register(adapter).await;
+    }
+
+    if let Some(matrix_config) = &config.messaging.matrix
+        && matrix_config.enabled
+    {
+        let adapter = spacebot::messaging::matrix::MatrixAdapter::from_config(
+            matrix_config,
+            &config.instance_dir,
+        );
+        new_messaging_manager.register(adapter).await;
+    }
+
     if let Some(webhook_config) = &config.messaging.webhook
         && webhook_config.enabled
     {
@@ -3080,12 +3493,13 @@ async fn initialize_agents(
                 )
             };
             let token_path = config.instance_dir.join(token_file_name);
-            let perms = Arc::new(ArcSwap::from_pointee(
+            let perms = new_messaging_manager.instance_permissions().twitch.track(
+                &runtime_key,
                 spacebot::config::TwitchPermissions::from_instance_config(
                     instance,
                     &config.bindings,
                 ),
-            ));
+            );
             let adapter = spacebot::messaging::twitch::TwitchAdapter::new(
                 runtime_key,
                 &instance.username,
@@ -3139,9 +3553,10 @@ async fn initialize_agents(
                 "signal",
                 Some(instance.name.as_str()),
             );
-            let perms = Arc::new(ArcSwap::from_pointee(
+            let perms = new_messaging_manager.instance_permissions().signal.track(
+                &runtime_key,
                 spacebot::config::SignalPermissions::from_instance_config(instance),
-            ));
+            );
             let adapter = spacebot::messaging::signal::SignalAdapter::new(
                 runtime_key,
                 &instance.http_url,
@@ -3181,6 +3596,41 @@ async fn initialize_agents(
 
     tracing::info!("messaging adapters started");
 
+    if let Some(cluster) = spacebot::cluster::global() {
+        spacebot::cluster::supervise(
+            cluster.clone(),
+            api_state.clone(),
+            messaging_manager.clone(),
+        );
+    }
+
+    // Redeliver responses that were still queued when the previous run exited
+    for (agent_id, agent) in agents.iter() {
+        // Queued before any channel starts, so live responses wait behind
+        // the replayed ones.
+        let replay = match agent.outbox.replay_pending(&messaging_manager).await {
+            Ok(replay) => replay,
+            Err(error) => {
+                tracing::warn!(%error, %agent_id, "failed to replay outbox");
+                continue;
+            }
+        };
+        let agent_id = agent_id.to_string();
+        tokio::spawn(async move {
+            let report = replay.finished().await;
+            if report.failed > 0 {
+                tracing::warn!(
+                    %agent_id,
+                    delivered = report.delivered,
+                    failed = report.failed,
+                    "replayed outbox, some responses were dead-lettered"
+                );
+            } else if report.delivered > 0 {
+                tracing::info!(%agent_id, delivered = report.delivered, "replayed outbox");
+            }
+        });
+    }
+
     // Initialize cron schedulers for each agent
     let mut cron_stores_map = std::collections::HashMap::new();
     let mut cron_schedulers_map = std::collections::HashMap::new();
@@ -3201,6 +3651,7 @@ async fn initialize_agents(
                 enabled: cron_def.enabled,
                 run_once: cron_def.run_once,
                 timeout_secs: cron_def.timeout_secs,
+                timezone: None,
             };
             if let Err(error) = store.save(&cron_config).await {
                 tracing::warn!(
@@ -3267,43 +3718,14 @@ async fn initialize_agents(
     api_state.set_cron_schedulers(cron_schedulers_map);
     tracing::info!("cron stores and schedulers registered with API state");
 
-    // Start memory ingestion loops for each agent
-    for (agent_id, agent) in agents.iter() {
-        let ingestion_config = **agent.deps.runtime_config.ingestion.load();
-        if ingestion_config.enabled {
-            let handle = spacebot::agent::ingestion::spawn_ingestion_loop(
-                agent.config.ingest_dir(),
-                agent.deps.clone(),
-            );
-            ingestion_handles.push(handle);
-            tracing::info!(agent_id = %agent_id, "memory ingestion loop started");
-        }
+    // Probe CLI worker backends so spawn_worker only offers usable ones
+    for agent in agents.values() {
+        spacebot::cli_worker::detect::spawn_startup_probe(agent.deps.runtime_config.clone());
     }
 
-    // Start cortex warmup, runtime, and association loops for each agent
-    for (agent_id, agent) in agents.iter() {
-        let cortex_logger = spacebot::agent::cortex::CortexLogger::new(agent.db.sqlite.clone());
-        let warmup_handle =
-            spacebot::agent::cortex::spawn_warmup_loop(agent.deps.clone(), cortex_logger.clone());
-        cortex_handles.push(warmup_handle);
-        tracing::info!(agent_id = %agent_id, "warmup loop started");
-
-        let cortex_handle =
-            spacebot::agent::cortex::spawn_cortex_loop(agent.deps.clone(), cortex_logger.clone());
-        cortex_handles.push(cortex_handle);
-        tracing::info!(agent_id = %agent_id, "cortex loop started");
-
-        let association_handle =
-            spacebot::agent::cortex::spawn_association_loop(agent.deps.clone(), cortex_logger);
-        cortex_handles.push(association_handle);
-        tracing::info!(agent_id = %agent_id, "cortex association loop started");
-
-        let ready_task_handle = spacebot::agent::cortex::spawn_ready_task_loop(
-            agent.deps.clone(),
-            spacebot::agent::cortex::CortexLogger::new(agent.db.sqlite.clone()),
-        );
-        cortex_handles.push(ready_task_handle);
-        tracing::info!(agent_id = %agent_id, "cortex ready-task loop started");
+    // Start cortex, maintenance, and ingestion loops for each agent
+    for agent in agents.values() {
+        spacebot::agent::supervise_background_loops(&agent.deps, agent.config.ingest_dir());
     }
 
     // Create cortex chat sessions for each agent
@@ -3350,7 +3772,11 @@ async fn initialize_agents(
                 }
             };
 
-            let store = spacebot::agent::cortex_chat::CortexChatStore::new(agent.db.sqlite.clone());
+            let store = spacebot::agent::cortex_chat::CortexChatStore::for_agent(
+                agent.db.sqlite.clone(),
+                agent_id,
+                agent.deps.runtime_config.storage.load().encrypt_at_rest,
+            );
             let session = spacebot::agent::cortex_chat::CortexChatSession::new(
                 agent.deps.clone(),
                 tool_server,
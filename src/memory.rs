@@ -1,14 +1,24 @@
 //! Memory storage and retrieval system.
 
+pub mod audit;
 pub mod embedding;
+pub mod episodes;
+pub mod journal;
 pub mod lance;
 pub mod maintenance;
+pub mod pins;
+pub mod profiles;
 pub mod search;
 pub mod store;
 pub mod types;
 
+pub use audit::{MemoryAuditAction, MemoryAuditEntry, MemoryAuditStore};
 pub use embedding::EmbeddingModel;
+pub use episodes::EpisodeTable;
+pub use journal::EmbeddingJournal;
 pub use lance::EmbeddingTable;
+pub use pins::{BulletinPin, BulletinPinStore, NewBulletinPin};
+pub use profiles::{UserProfile, UserProfileStore};
 pub use search::{MemorySearch, SearchConfig, SearchMode, SearchSort, curate_results};
 pub use store::MemoryStore;
-pub use types::{Association, Memory, MemoryType, RelationType};
+pub use types::{Association, Memory, MemoryScope, MemoryType, MemoryVisibility, RelationType};
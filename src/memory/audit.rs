@@ -0,0 +1,182 @@
+//! Audit trail for manual memory edits (SQLite).
+//!
+//! The agent changes its own memories through tools; admins change them
+//! through the API. Every API create, update, and forget records the memory
+//! before and after, so a correction can be traced and undone by hand.
+
+use crate::memory::Memory;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{Row as _, SqlitePool};
+
+/// What a manual edit did to a memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryAuditAction {
+    Create,
+    Update,
+    Forget,
+}
+
+impl MemoryAuditAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Create => "create",
+            Self::Update => "update",
+            Self::Forget => "forget",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "create" => Some(Self::Create),
+            "update" => Some(Self::Update),
+            "forget" => Some(Self::Forget),
+            _ => None,
+        }
+    }
+}
+
+/// One recorded edit.
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryAuditEntry {
+    pub id: i64,
+    pub memory_id: String,
+    pub action: MemoryAuditAction,
+    pub actor: String,
+    /// The memory before the edit; `None` for creates.
+    pub before: Option<Memory>,
+    pub after: Option<Memory>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Persists the memory audit trail.
+#[derive(Debug, Clone)]
+pub struct MemoryAuditStore {
+    pool: SqlitePool,
+}
+
+impl MemoryAuditStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn record(
+        &self,
+        action: MemoryAuditAction,
+        actor: &str,
+        before: Option<&Memory>,
+        after: Option<&Memory>,
+    ) -> crate::error::Result<()> {
+        let Some(memory_id) = after.or(before).map(|memory| memory.id.as_str()) else {
+            return Ok(());
+        };
+        let snapshot = |memory: &Memory| serde_json::to_string(memory).unwrap_or_default();
+        let before_json = before.map(snapshot);
+        let after_json = after.map(snapshot);
+        sqlx::query(
+            "INSERT INTO memory_audit (memory_id, action, actor, before_json, after_json, created_at) \
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(memory_id)
+        .bind(action.as_str())
+        .bind(actor)
+        .bind(before_json)
+        .bind(after_json)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Recorded edits, newest first, optionally for one memory.
+    pub async fn list(
+        &self,
+        memory_id: Option<&str>,
+        limit: i64,
+    ) -> crate::error::Result<Vec<MemoryAuditEntry>> {
+        let rows = sqlx::query(
+            "SELECT id, memory_id, action, actor, before_json, after_json, created_at \
+             FROM memory_audit WHERE ?1 IS NULL OR memory_id = ?1 \
+             ORDER BY id DESC LIMIT ?2",
+        )
+        .bind(memory_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.iter().filter_map(entry_from_row).collect())
+    }
+}
+
+fn entry_from_row(row: &sqlx::sqlite::SqliteRow) -> Option<MemoryAuditEntry> {
+    let snapshot = |column: &str| {
+        row.get::<Option<String>, _>(column)
+            .and_then(|json| serde_json::from_str(&json).ok())
+    };
+    Some(MemoryAuditEntry {
+        id: row.get("id"),
+        memory_id: row.get("memory_id"),
+        action: MemoryAuditAction::parse(row.get("action"))?,
+        actor: row.get("actor"),
+        before: snapshot("before_json"),
+        after: snapshot("after_json"),
+        created_at: row.get("created_at"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryType;
+
+    #[tokio::test]
+    async fn records_edits_newest_first() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to create sqlite memory pool");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("failed to run migrations");
+        let store = MemoryAuditStore::new(pool);
+
+        let created = Memory::new("The user lives in Lisbon.", MemoryType::Fact);
+        let mut updated = created.clone();
+        updated.content = "The user lives in Porto.".into();
+        store
+            .record(MemoryAuditAction::Create, "api", None, Some(&created))
+            .await
+            .unwrap();
+        store
+            .record(
+                MemoryAuditAction::Update,
+                "api",
+                Some(&created),
+                Some(&updated),
+            )
+            .await
+            .unwrap();
+        let other = Memory::new("Unrelated.", MemoryType::Fact);
+        store
+            .record(MemoryAuditAction::Forget, "api", Some(&other), None)
+            .await
+            .unwrap();
+
+        let entries = store.list(Some(&created.id), 10).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, MemoryAuditAction::Update);
+        assert_eq!(entries[0].before.as_ref(), Some(&created));
+        assert_eq!(
+            entries[0]
+                .after
+                .as_ref()
+                .map(|memory| memory.content.as_str()),
+            Some("The user lives in Porto.")
+        );
+        assert!(entries[1].before.is_none());
+        assert_eq!(store.list(None, 10).await.unwrap().len(), 3);
+    }
+}
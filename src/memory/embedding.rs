@@ -1,15 +1,56 @@
-//! Embedding generation via fastembed.
+//! Embedding generation via fastembed or a remote provider.
 
+pub mod providers;
+
+pub use providers::{EmbeddingProvider, EmbeddingProviderDyn};
+
+use crate::config::LlmConfig;
 use crate::error::{LlmError, Result};
+use crate::memory::lance::EMBEDDING_DIM;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Embedding model wrapper with thread-safe sharing.
 ///
 /// fastembed's TextEmbedding is not Send, so we hold it behind an Arc and
 /// use spawn_blocking to call into it from async contexts.
 pub struct EmbeddingModel {
-    model: Arc<fastembed::TextEmbedding>,
+    backend: Backend,
+    /// Length of every vector this model produces.
+    dimensions: usize,
+    /// When a remote provider last answered a health check, and how.
+    last_remote_check: std::sync::Mutex<Option<(Instant, std::result::Result<(), String>)>>,
+}
+
+/// How long a remote provider's health check result is reused. Health
+/// endpoints are polled often and each check is a billed embedding call.
+const REMOTE_CHECK_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Clone)]
+enum Backend {
+    FastEmbed(Arc<fastembed::TextEmbedding>),
+    /// Offline feature hashing, see [`EmbeddingModel::hashed`].
+    Hashed,
+    /// An embeddings API, see [`providers`].
+    Remote(Arc<dyn EmbeddingProviderDyn>),
+}
+
+impl Backend {
+    fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        match self {
+            Self::FastEmbed(model) => model
+                .embed(texts, None)
+                .map_err(|e| LlmError::EmbeddingFailed(e.to_string()).into()),
+            Self::Hashed => Ok(texts.iter().map(|text| hashed_embedding(text)).collect()),
+            Self::Remote(provider) => Err(LlmError::EmbeddingFailed(format!(
+                "{} embeddings can only be generated asynchronously",
+                provider.name()
+            ))
+            .into()),
+        }
+    }
 }
 
 impl EmbeddingModel {
@@ -23,15 +64,119 @@ impl EmbeddingModel {
             .map_err(|e| LlmError::EmbeddingFailed(e.to_string()))?;
 
         Ok(Self {
-            model: Arc::new(model),
+            backend: Backend::FastEmbed(Arc::new(model)),
+            dimensions: EMBEDDING_DIM as usize,
+            last_remote_check: std::sync::Mutex::new(None),
+        })
+    }
+
+    /// Create the model selected by `[llm.embedding]`.
+    ///
+    /// For a remote provider the vector size is the configured `dimensions`,
+    /// else the model's known size, else measured from a probe embedding, so
+    /// an unknown model with bad credentials fails here rather than on the
+    /// first memory save.
+    pub async fn from_config(llm_config: &LlmConfig, cache_dir: &Path) -> Result<Self> {
+        let Some(provider) = providers::from_config(llm_config)? else {
+            return Self::new(cache_dir);
+        };
+
+        let dimensions = match llm_config
+            .embedding
+            .dimensions
+            .or_else(|| providers::known_dimensions(provider.model()))
+        {
+            Some(dimensions) => dimensions,
+            None => provider
+                .embed(&["dimension probe".to_string()])
+                .await?
+                .first()
+                .map(Vec::len)
+                .filter(|dimensions| *dimensions > 0)
+                .ok_or_else(|| {
+                    LlmError::EmbeddingFailed(format!(
+                        "{} returned no probe embedding for '{}'",
+                        provider.name(),
+                        provider.model()
+                    ))
+                })?,
+        };
+
+        tracing::info!(
+            provider = provider.name(),
+            model = provider.model(),
+            dimensions,
+            "using remote embedding provider"
+        );
+        Ok(Self {
+            backend: Backend::Remote(provider),
+            dimensions,
+            last_remote_check: std::sync::Mutex::new(None),
         })
     }
 
-    /// Generate embeddings for multiple texts (blocking).
+    /// A deterministic model that needs no download.
+    ///
+    /// Words are hashed into buckets of a fixed-size vector, so similarity
+    /// reflects word overlap rather than meaning. Used by
+    /// [`crate::testing`] to exercise memory search without network access.
+    pub fn hashed() -> Self {
+        Self {
+            backend: Backend::Hashed,
+            dimensions: EMBEDDING_DIM as usize,
+            last_remote_check: std::sync::Mutex::new(None),
+        }
+    }
+
+    /// Length of every vector this model produces. The LanceDB tables are
+    /// created with this size.
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    /// Where embeddings come from: `fastembed`, `hashed`, or the remote
+    /// provider's name.
+    pub fn backend_name(&self) -> &str {
+        match &self.backend {
+            Backend::FastEmbed(_) => "fastembed",
+            Backend::Hashed => "hashed",
+            Backend::Remote(provider) => provider.name(),
+        }
+    }
+
+    /// Embed a short probe text to confirm the model works. A remote
+    /// provider's result is reused for [`REMOTE_CHECK_TTL`].
+    pub async fn health_check(self: &Arc<Self>) -> std::result::Result<(), String> {
+        let remote = matches!(self.backend, Backend::Remote(_));
+        if remote
+            && let Some((checked_at, result)) = self
+                .last_remote_check
+                .lock()
+                .expect("embedding check lock poisoned")
+                .as_ref()
+            && checked_at.elapsed() < REMOTE_CHECK_TTL
+        {
+            return result.clone();
+        }
+
+        let result = self
+            .embed_one("health check")
+            .await
+            .map(|_| ())
+            .map_err(|error| error.to_string());
+        if remote {
+            *self
+                .last_remote_check
+                .lock()
+                .expect("embedding check lock poisoned") = Some((Instant::now(), result.clone()));
+        }
+        result
+    }
+
+    /// Generate embeddings for multiple texts (blocking). Not available for
+    /// remote providers.
     pub fn embed(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
-        self.model
-            .embed(texts, None)
-            .map_err(|e| LlmError::EmbeddingFailed(e.to_string()).into())
+        self.backend.embed(texts)
     }
 
     /// Generate embedding for a single text (blocking).
@@ -40,28 +185,123 @@ impl EmbeddingModel {
         Ok(embeddings.into_iter().next().unwrap_or_default())
     }
 
-    /// Generate embedding for a single text (async, spawns blocking task).
+    /// Generate embedding for a single text (async).
     pub async fn embed_one(self: &Arc<Self>, text: &str) -> Result<Vec<f32>> {
         #[cfg(feature = "metrics")]
         let _timer = crate::telemetry::Metrics::global()
             .memory_embedding_duration_seconds
             .start_timer();
 
-        let text = text.to_string();
-        let model = self.model.clone();
-        let result = tokio::task::spawn_blocking(move || {
-            model.embed(vec![text], None).map_err(|e| {
-                crate::Error::from(crate::error::LlmError::EmbeddingFailed(e.to_string()))
-            })
-        })
-        .await
-        .map_err(|e| crate::Error::Other(anyhow::anyhow!("embedding task failed: {}", e)))??;
-
+        let result = self.embed_async(vec![text.to_string()]).await?;
         Ok(result.into_iter().next().unwrap_or_default())
     }
+
+    /// Generate embeddings for multiple texts (async).
+    pub async fn embed_many(self: &Arc<Self>, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.embed_async(texts).await
+    }
+
+    /// Local models run on a blocking task; remote providers are awaited
+    /// directly and their output checked against `dimensions`.
+    async fn embed_async(&self, texts: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        let Backend::Remote(provider) = &self.backend else {
+            let backend = self.backend.clone();
+            return tokio::task::spawn_blocking(move || backend.embed(texts))
+                .await
+                .map_err(|e| {
+                    crate::Error::Other(anyhow::anyhow!("embedding task failed: {}", e))
+                })?;
+        };
+
+        let mut embeddings = provider.embed(&texts).await?;
+        if embeddings.len() != texts.len() {
+            return Err(LlmError::EmbeddingFailed(format!(
+                "{} returned {} embeddings for {} texts",
+                provider.name(),
+                embeddings.len(),
+                texts.len()
+            ))
+            .into());
+        }
+        for embedding in &mut embeddings {
+            if embedding.len() != self.dimensions {
+                return Err(LlmError::EmbeddingFailed(format!(
+                    "{} returned a {}-dimension embedding, expected {}; \
+                     set llm.embedding.dimensions to match the model",
+                    provider.name(),
+                    embedding.len(),
+                    self.dimensions
+                ))
+                .into());
+            }
+            // fastembed's vectors are unit length; keep remote ones
+            // comparable under LanceDB's L2 distance.
+            normalize(embedding);
+        }
+        Ok(embeddings)
+    }
 }
 
 /// Async function to embed text using a shared model.
 pub async fn embed_text(model: &Arc<EmbeddingModel>, text: &str) -> Result<Vec<f32>> {
     model.embed_one(text).await
 }
+
+/// Bag-of-words feature hashing into a unit vector of `EMBEDDING_DIM` floats.
+fn hashed_embedding(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0f32; EMBEDDING_DIM as usize];
+    for word in text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+    {
+        let mut hasher = DefaultHasher::new();
+        word.to_lowercase().hash(&mut hasher);
+        let hash = hasher.finish();
+        let bucket = (hash % vector.len() as u64) as usize;
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[bucket] += sign;
+    }
+
+    normalize(&mut vector);
+    vector
+}
+
+/// Scale to unit length. Zero vectors are left as they are.
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|value| value * value).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for value in vector {
+            *value /= norm;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashed_embeddings_are_deterministic_and_normalized() {
+        let model = EmbeddingModel::hashed();
+        let first = model
+            .embed_one_blocking("Deploy the billing service")
+            .unwrap();
+        let second = model
+            .embed_one_blocking("deploy the BILLING service")
+            .unwrap();
+
+        assert_eq!(first.len(), EMBEDDING_DIM as usize);
+        assert_eq!(first, second);
+        let norm: f32 = first.iter().map(|value| value * value).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn hashed_embeddings_of_empty_text_are_zero() {
+        let embedding = EmbeddingModel::hashed().embed_one_blocking("").unwrap();
+        assert!(embedding.iter().all(|value| *value == 0.0));
+    }
+}
@@ -0,0 +1,370 @@
+//! Remote embedding providers.
+//!
+//! For hosts that can't run the local ONNX model. Each provider is a thin
+//! client for one embeddings API; [`EmbeddingModel`](super::EmbeddingModel)
+//! picks one from `[llm.embedding]` and settles the vector size the LanceDB
+//! tables are created with.
+
+use crate::config::{EmbeddingProviderKind, LlmConfig};
+use crate::error::{LlmError, Result};
+use crate::llm::ollama::OllamaClient;
+
+use serde::Deserialize;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+/// Texts sent per request. Gemini rejects batches over 100.
+const MAX_BATCH: usize = 96;
+
+const OPENAI_BASE_URL: &str = "https://api.openai.com";
+const GEMINI_BASE_URL: &str = "https://generativelanguage.googleapis.com";
+const OLLAMA_BASE_URL: &str = "http://localhost:11434";
+
+/// Boxed future returned by [`EmbeddingProviderDyn::embed`].
+type EmbedFuture<'a> =
+    Pin<Box<dyn std::future::Future<Output = Result<Vec<Vec<f32>>>> + Send + 'a>>;
+
+/// A service that turns text into embedding vectors.
+pub trait EmbeddingProvider: Send + Sync + 'static {
+    /// Provider name for logs and errors, e.g. `openai`.
+    fn name(&self) -> &str;
+
+    /// Model the provider embeds with.
+    fn model(&self) -> &str;
+
+    /// Embed a batch of texts, one vector per text, in order.
+    fn embed(
+        &self,
+        texts: &[String],
+    ) -> impl std::future::Future<Output = Result<Vec<Vec<f32>>>> + Send;
+}
+
+/// Dynamic trait for runtime polymorphism.
+/// Use this when you need `Arc<dyn EmbeddingProviderDyn>` for storing different providers.
+pub trait EmbeddingProviderDyn: Send + Sync + 'static {
+    fn name(&self) -> &str;
+
+    fn model(&self) -> &str;
+
+    fn embed<'a>(&'a self, texts: &'a [String]) -> EmbedFuture<'a>;
+}
+
+/// Blanket implementation: any type implementing EmbeddingProvider automatically implements EmbeddingProviderDyn.
+impl<T: EmbeddingProvider> EmbeddingProviderDyn for T {
+    fn name(&self) -> &str {
+        EmbeddingProvider::name(self)
+    }
+
+    fn model(&self) -> &str {
+        EmbeddingProvider::model(self)
+    }
+
+    fn embed<'a>(&'a self, texts: &'a [String]) -> EmbedFuture<'a> {
+        Box::pin(EmbeddingProvider::embed(self, texts))
+    }
+}
+
+/// Build the remote provider selected by `[llm.embedding]`, or `None` for the
+/// local model.
+pub fn from_config(llm_config: &LlmConfig) -> Result<Option<Arc<dyn EmbeddingProviderDyn>>> {
+    let config = &llm_config.embedding;
+    let http_client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|error| LlmError::EmbeddingFailed(error.to_string()))?;
+
+    let provider: Arc<dyn EmbeddingProviderDyn> = match config.provider {
+        EmbeddingProviderKind::FastEmbed => return Ok(None),
+        EmbeddingProviderKind::OpenAi => {
+            let api_key = config
+                .api_key
+                .clone()
+                .or_else(|| llm_config.openai_key.clone());
+            // OpenAI-compatible hosts on a custom base URL may not need a key.
+            if api_key.is_none() && config.base_url.is_none() {
+                return Err(missing_key("openai", "openai_key"));
+            }
+            Arc::new(OpenAiEmbeddings {
+                http_client,
+                base_url: config
+                    .base_url
+                    .clone()
+                    .unwrap_or_else(|| OPENAI_BASE_URL.into()),
+                api_key,
+                model: config
+                    .model
+                    .clone()
+                    .unwrap_or_else(|| "text-embedding-3-small".into()),
+                dimensions: config.dimensions,
+            })
+        }
+        EmbeddingProviderKind::Gemini => {
+            let api_key = config
+                .api_key
+                .clone()
+                .or_else(|| llm_config.gemini_key.clone())
+                .ok_or_else(|| missing_key("gemini", "gemini_key"))?;
+            Arc::new(GeminiEmbeddings {
+                http_client,
+                base_url: config
+                    .base_url
+                    .clone()
+                    .unwrap_or_else(|| GEMINI_BASE_URL.into()),
+                api_key,
+                model: config
+                    .model
+                    .clone()
+                    .unwrap_or_else(|| "gemini-embedding-001".into()),
+                dimensions: config.dimensions,
+            })
+        }
+        EmbeddingProviderKind::Ollama => {
+            let base_url = config
+                .base_url
+                .clone()
+                .or_else(|| llm_config.ollama_base_url.clone())
+                .unwrap_or_else(|| OLLAMA_BASE_URL.into());
+            let api_key = config
+                .api_key
+                .clone()
+                .or_else(|| llm_config.ollama_key.clone());
+            Arc::new(OllamaEmbeddings {
+                client: OllamaClient::new(http_client, &base_url, api_key),
+                model: config
+                    .model
+                    .clone()
+                    .unwrap_or_else(|| "nomic-embed-text".into()),
+                dimensions: config.dimensions,
+            })
+        }
+    };
+    Ok(Some(provider))
+}
+
+fn missing_key(provider: &str, fallback: &str) -> crate::Error {
+    LlmError::EmbeddingFailed(format!(
+        "embedding provider '{provider}' needs llm.embedding.api_key or llm.{fallback}"
+    ))
+    .into()
+}
+
+/// Native vector size of well-known embedding models, so startup doesn't
+/// need a probe request. Ollama tags (`:latest`, `:v1.5`) are ignored.
+pub fn known_dimensions(model: &str) -> Option<usize> {
+    let model = model.split(':').next().unwrap_or(model);
+    let model = model.rsplit('/').next().unwrap_or(model);
+    match model {
+        "text-embedding-3-small" | "text-embedding-ada-002" => Some(1536),
+        "text-embedding-3-large" | "gemini-embedding-001" => Some(3072),
+        "text-embedding-004" | "nomic-embed-text" => Some(768),
+        "mxbai-embed-large" | "bge-m3" | "snowflake-arctic-embed" => Some(1024),
+        "all-minilm" => Some(384),
+        _ => None,
+    }
+}
+
+/// OpenAI `/v1/embeddings`, also served by most OpenAI-compatible hosts.
+struct OpenAiEmbeddings {
+    http_client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    dimensions: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiResponse {
+    data: Vec<OpenAiEmbedding>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiEmbedding {
+    #[serde(default)]
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl OpenAiResponse {
+    /// Vectors in input order. The API doesn't promise to return them sorted.
+    fn into_vectors(mut self) -> Vec<Vec<f32>> {
+        self.data.sort_by_key(|item| item.index);
+        self.data.into_iter().map(|item| item.embedding).collect()
+    }
+}
+
+impl EmbeddingProvider for OpenAiEmbeddings {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let base_url = self.base_url.trim_end_matches('/');
+        let url = format!(
+            "{}/v1/embeddings",
+            base_url.strip_suffix("/v1").unwrap_or(base_url)
+        );
+
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(MAX_BATCH) {
+            let mut body = serde_json::json!({ "model": self.model, "input": batch });
+            if let Some(dimensions) = self.dimensions {
+                body["dimensions"] = dimensions.into();
+            }
+            let mut request = self.http_client.post(&url).json(&body);
+            if let Some(api_key) = &self.api_key {
+                request = request.bearer_auth(api_key);
+            }
+            let response: OpenAiResponse = send(request, "openai").await?;
+            vectors.extend(response.into_vectors());
+        }
+        Ok(vectors)
+    }
+}
+
+/// Gemini `batchEmbedContents`.
+struct GeminiEmbeddings {
+    http_client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    model: String,
+    dimensions: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct GeminiResponse {
+    embeddings: Vec<GeminiEmbedding>,
+}
+
+#[derive(Deserialize)]
+struct GeminiEmbedding {
+    values: Vec<f32>,
+}
+
+impl EmbeddingProvider for GeminiEmbeddings {
+    fn name(&self) -> &str {
+        "gemini"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        // Accept the OpenAI-compatible base URL used for completions too.
+        let base_url = self.base_url.trim_end_matches('/');
+        let base_url = base_url.strip_suffix("/openai").unwrap_or(base_url);
+        let base_url = base_url.strip_suffix("/v1beta").unwrap_or(base_url);
+        let url = format!("{base_url}/v1beta/models/{}:batchEmbedContents", self.model);
+
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(MAX_BATCH) {
+            let requests: Vec<serde_json::Value> = batch
+                .iter()
+                .map(|text| {
+                    let mut request = serde_json::json!({
+                        "model": format!("models/{}", self.model),
+                        "content": { "parts": [{ "text": text }] },
+                    });
+                    if let Some(dimensions) = self.dimensions {
+                        request["outputDimensionality"] = dimensions.into();
+                    }
+                    request
+                })
+                .collect();
+            let request = self
+                .http_client
+                .post(&url)
+                .header("x-goog-api-key", &self.api_key)
+                .json(&serde_json::json!({ "requests": requests }));
+            let response: GeminiResponse = send(request, "gemini").await?;
+            vectors.extend(response.embeddings.into_iter().map(|item| item.values));
+        }
+        Ok(vectors)
+    }
+}
+
+/// Ollama `/api/embed`.
+struct OllamaEmbeddings {
+    client: OllamaClient,
+    model: String,
+    dimensions: Option<usize>,
+}
+
+impl EmbeddingProvider for OllamaEmbeddings {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(MAX_BATCH) {
+            let batch = self
+                .client
+                .embed(&self.model, batch, self.dimensions)
+                .await
+                .map_err(|error| LlmError::EmbeddingFailed(format!("{error:#}")))?;
+            vectors.extend(batch);
+        }
+        Ok(vectors)
+    }
+}
+
+async fn send<T: serde::de::DeserializeOwned>(
+    request: reqwest::RequestBuilder,
+    provider: &str,
+) -> Result<T> {
+    let response = request.send().await.map_err(|error| {
+        LlmError::EmbeddingFailed(format!("failed to reach {provider}: {error}"))
+    })?;
+    let status = response.status();
+    let text = response.text().await.map_err(|error| {
+        LlmError::EmbeddingFailed(format!("failed to read {provider} response: {error}"))
+    })?;
+    if !status.is_success() {
+        return Err(
+            LlmError::EmbeddingFailed(format!("{provider} returned {status}: {text}")).into(),
+        );
+    }
+    serde_json::from_str(&text).map_err(|error| {
+        LlmError::EmbeddingFailed(format!("invalid {provider} embedding response: {error}")).into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_embedding_responses() {
+        let openai: OpenAiResponse = serde_json::from_str(
+            r#"{"object": "list", "data": [
+                {"object": "embedding", "index": 1, "embedding": [0.3, 0.4]},
+                {"object": "embedding", "index": 0, "embedding": [0.1, 0.2]}
+            ], "model": "text-embedding-3-small"}"#,
+        )
+        .unwrap();
+        assert_eq!(openai.into_vectors(), vec![vec![0.1, 0.2], vec![0.3, 0.4]]);
+
+        let gemini: GeminiResponse =
+            serde_json::from_str(r#"{"embeddings": [{"values": [0.5, 0.6, 0.7]}]}"#).unwrap();
+        assert_eq!(gemini.embeddings[0].values, vec![0.5, 0.6, 0.7]);
+    }
+
+    #[test]
+    fn knows_common_model_dimensions() {
+        assert_eq!(known_dimensions("text-embedding-3-large"), Some(3072));
+        assert_eq!(known_dimensions("nomic-embed-text:latest"), Some(768));
+        assert_eq!(known_dimensions("models/text-embedding-004"), Some(768));
+        assert_eq!(known_dimensions("my-finetune"), None);
+    }
+}
@@ -0,0 +1,643 @@
+//! Episodic recall: vector index over conversation history.
+//!
+//! Conversation messages are grouped into short per-channel episodes, embedded,
+//! and stored in a second LanceDB table next to the memory embeddings. Indexing
+//! is lazy — [`EpisodeTable::sync`] catches the table up from the conversation
+//! log before a search, so logging a message never waits on the embedder.
+
+use crate::conversation::history::{ConversationLogger, ConversationMessage};
+use crate::error::{DbError, Result};
+use crate::memory::EmbeddingModel;
+use crate::memory::lance::{EMBEDDING_DIM, OptimizeReport, embedding_dimensions};
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float32Type, Int64Type};
+use arrow_array::{Array, RecordBatchIterator};
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use tokio::sync::Mutex;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const TABLE_NAME: &str = "conversation_episodes";
+
+/// Maximum messages in one episode.
+const EPISODE_MAX_MESSAGES: usize = 8;
+/// Episodes are cut at roughly this many characters. The embedding model only
+/// reads the first ~256 tokens, so longer episodes would embed poorly.
+const EPISODE_MAX_CHARS: usize = 1200;
+/// A silence this long between messages ends an episode.
+const EPISODE_IDLE_GAP_SECS: i64 = 30 * 60;
+/// Messages read per channel per sync. Bounds the latency of the first search
+/// against a long history; later searches pick up where this one stopped.
+const SYNC_BATCH_MESSAGES: i64 = 400;
+
+/// A contiguous stretch of one channel's conversation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Episode {
+    /// ID of the episode's first message.
+    pub id: String,
+    pub channel_id: String,
+    pub first_rowid: i64,
+    pub last_rowid: i64,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    /// Transcript, one `sender: content` line per message.
+    pub content: String,
+}
+
+/// An episode returned by a similarity search.
+#[derive(Debug, Clone)]
+pub struct EpisodeMatch {
+    pub channel_id: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub content: String,
+    /// Cosine similarity to the query.
+    pub similarity: f32,
+}
+
+/// LanceDB table of embedded conversation episodes.
+#[derive(Clone)]
+pub struct EpisodeTable {
+    table: lancedb::Table,
+    /// Vector size of the `embedding` column.
+    dimensions: i32,
+    /// Per-channel rowid through which messages have been indexed. Loaded
+    /// from the table on first sync. The lock also serializes syncs so
+    /// concurrent searches don't index the same messages twice.
+    high_water: Arc<Mutex<Option<HashMap<String, i64>>>>,
+}
+
+impl std::fmt::Debug for EpisodeTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EpisodeTable").finish_non_exhaustive()
+    }
+}
+
+impl EpisodeTable {
+    /// Open the existing table or create a new one, sized for the local
+    /// fastembed model.
+    pub async fn open_or_create(connection: &lancedb::Connection) -> Result<Self> {
+        Self::open_with_dimensions(connection, EMBEDDING_DIM as usize).await
+    }
+
+    /// Open the existing table or create a new one holding vectors of
+    /// `dimensions` floats.
+    ///
+    /// A corrupted table, or one built for a different embedding size, is
+    /// dropped and recreated. Episodes are rebuilt from the conversation log
+    /// on the next sync.
+    pub async fn open_with_dimensions(
+        connection: &lancedb::Connection,
+        dimensions: usize,
+    ) -> Result<Self> {
+        let dimensions = i32::try_from(dimensions).map_err(|_| {
+            DbError::LanceDb(format!("unsupported embedding dimension {dimensions}"))
+        })?;
+        let existing = match connection.open_table(TABLE_NAME).execute().await {
+            Ok(table) if embedding_dimensions(&table).await == Some(dimensions) => Some(table),
+            Ok(_) => {
+                tracing::warn!(
+                    expected = dimensions,
+                    "episodes table was built for a different embedding size, recreating"
+                );
+                if let Err(error) = connection.drop_table(TABLE_NAME, &[]).await {
+                    tracing::warn!(%error, "drop_table failed, proceeding anyway");
+                }
+                None
+            }
+            Err(error) => {
+                tracing::debug!(%error, "failed to open episodes table, will create");
+                None
+            }
+        };
+        let table = match existing {
+            Some(table) => table,
+            None => match Self::create_empty_table(connection, dimensions).await {
+                Ok(table) => table,
+                Err(error) => {
+                    tracing::warn!(%error, "failed to create episodes table, recreating");
+                    if let Err(error) = connection.drop_table(TABLE_NAME, &[]).await {
+                        tracing::warn!(%error, "drop_table failed during recovery, proceeding anyway");
+                    }
+                    Self::create_empty_table(connection, dimensions).await?
+                }
+            },
+        };
+
+        Ok(Self {
+            table,
+            dimensions,
+            high_water: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    async fn create_empty_table(
+        connection: &lancedb::Connection,
+        dimensions: i32,
+    ) -> Result<lancedb::Table> {
+        let batches = RecordBatchIterator::new(
+            vec![].into_iter().map(Ok),
+            Arc::new(Self::schema(dimensions)),
+        );
+
+        connection
+            .create_table(TABLE_NAME, Box::new(batches))
+            .execute()
+            .await
+            .map_err(|e| DbError::LanceDb(e.to_string()).into())
+    }
+
+    /// Index any sealed episodes logged since the last sync. Returns the
+    /// number of episodes added.
+    pub async fn sync(
+        &self,
+        conversation_logger: &ConversationLogger,
+        embedding_model: &Arc<EmbeddingModel>,
+    ) -> Result<usize> {
+        let mut guard = self.high_water.lock().await;
+        if guard.is_none() {
+            *guard = Some(self.load_high_water().await?);
+        }
+        let Some(high_water) = guard.as_mut() else {
+            return Ok(0);
+        };
+
+        let now = Utc::now();
+        let mut added = 0;
+
+        for (channel_id, head) in conversation_logger.channel_message_heads().await? {
+            let indexed_through = high_water.get(&channel_id).copied().unwrap_or(0);
+            if head <= indexed_through {
+                continue;
+            }
+
+            let messages = conversation_logger
+                .load_messages_after(&channel_id, indexed_through, SYNC_BATCH_MESSAGES)
+                .await?;
+            let at_head = messages.last().is_some_and(|(rowid, _)| *rowid >= head);
+            let (episodes, consumed_through) =
+                seal_episodes(&channel_id, &messages, at_head, now, indexed_through);
+
+            if !episodes.is_empty() {
+                let texts = episodes
+                    .iter()
+                    .map(|episode| episode.content.clone())
+                    .collect();
+                let embeddings = embedding_model.embed_many(texts).await?;
+                self.store(&episodes, &embeddings).await?;
+                added += episodes.len();
+            }
+
+            high_water.insert(channel_id, consumed_through);
+        }
+
+        if added > 0 {
+            tracing::debug!(added, "indexed conversation episodes");
+        }
+
+        Ok(added)
+    }
+
+    /// Most similar episodes to `query_embedding`, optionally limited to one
+    /// channel.
+    pub async fn search(
+        &self,
+        query_embedding: &[f32],
+        channel_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<EpisodeMatch>> {
+        if query_embedding.len() != self.dimensions as usize {
+            return Err(DbError::LanceDb(format!(
+                "Query embedding dimension mismatch: expected {}, got {}",
+                self.dimensions,
+                query_embedding.len()
+            ))
+            .into());
+        }
+
+        use lancedb::query::{ExecutableQuery, QueryBase};
+
+        let mut query = self
+            .table
+            .query()
+            .nearest_to(query_embedding)
+            .map_err(|e| DbError::LanceDb(e.to_string()))?
+            .select(lancedb::query::Select::columns(&[
+                "channel_id",
+                "started_at",
+                "ended_at",
+                "content",
+            ]))
+            .limit(limit);
+        if let Some(channel_id) = channel_id {
+            query = query.only_if(format!("channel_id = '{}'", channel_id.replace('\'', "''")));
+        }
+
+        let results: Vec<arrow_array::RecordBatch> = query
+            .execute()
+            .await
+            .map_err(|e| DbError::LanceDb(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| DbError::LanceDb(e.to_string()))?;
+
+        let parse_time = |value: &str| {
+            DateTime::parse_from_rfc3339(value)
+                .map(|time| time.with_timezone(&Utc))
+                .unwrap_or_default()
+        };
+
+        let mut matches = Vec::new();
+        for batch in results {
+            let (
+                Some(channel_col),
+                Some(started_col),
+                Some(ended_col),
+                Some(content_col),
+                Some(dist_col),
+            ) = (
+                batch.column_by_name("channel_id"),
+                batch.column_by_name("started_at"),
+                batch.column_by_name("ended_at"),
+                batch.column_by_name("content"),
+                batch.column_by_name("_distance"),
+            )
+            else {
+                continue;
+            };
+            let channels = channel_col.as_string::<i32>();
+            let started = started_col.as_string::<i32>();
+            let ended = ended_col.as_string::<i32>();
+            let contents = content_col.as_string::<i32>();
+            let dists: &arrow_array::PrimitiveArray<Float32Type> = dist_col.as_primitive();
+
+            for i in 0..batch.num_rows() {
+                if !dists.is_valid(i) {
+                    continue;
+                }
+                matches.push(EpisodeMatch {
+                    channel_id: channels.value(i).to_string(),
+                    started_at: parse_time(started.value(i)),
+                    ended_at: parse_time(ended.value(i)),
+                    content: contents.value(i).to_string(),
+                    similarity: 1.0 - dists.value(i),
+                });
+            }
+        }
+
+        Ok(matches)
+    }
+
+    /// Remove every episode of a channel that includes one of the given
+    /// message rowids, returning how many were removed. Indexing has already
+    /// moved past them, so the surrounding messages aren't indexed again.
+    pub async fn delete_covering(&self, channel_id: &str, rowids: &[i64]) -> Result<usize> {
+        let mut removed = 0;
+        for chunk in rowids.chunks(100) {
+            let covering = chunk
+                .iter()
+                .map(|rowid| format!("(first_rowid <= {rowid} AND last_rowid >= {rowid})"))
+                .collect::<Vec<_>>()
+                .join(" OR ");
+            let predicate = format!(
+                "channel_id = '{}' AND ({covering})",
+                channel_id.replace('\'', "''")
+            );
+            removed += self
+                .table
+                .count_rows(Some(predicate.clone()))
+                .await
+                .map_err(|e| DbError::LanceDb(e.to_string()))?;
+            self.table
+                .delete(&predicate)
+                .await
+                .map_err(|e| DbError::LanceDb(e.to_string()))?;
+        }
+        Ok(removed)
+    }
+
+    /// Compact data files and prune old table versions.
+    pub async fn optimize(&self) -> Result<OptimizeReport> {
+        let stats = self
+            .table
+            .optimize(lancedb::table::OptimizeAction::All)
+            .await
+            .map_err(|e| DbError::LanceDb(format!("Failed to optimize episodes table: {}", e)))?;
+        Ok(stats.into())
+    }
+
+    async fn store(&self, episodes: &[Episode], embeddings: &[Vec<f32>]) -> Result<()> {
+        use arrow_array::{Int64Array, RecordBatch, StringArray};
+
+        if episodes.len() != embeddings.len()
+            || embeddings
+                .iter()
+                .any(|embedding| embedding.len() != self.dimensions as usize)
+        {
+            return Err(DbError::LanceDb(format!(
+                "Episode embeddings don't match: {} episodes, {} embeddings of dimension {}",
+                episodes.len(),
+                embeddings.len(),
+                self.dimensions
+            ))
+            .into());
+        }
+
+        let started: Vec<String> = episodes.iter().map(|e| e.started_at.to_rfc3339()).collect();
+        let ended: Vec<String> = episodes.iter().map(|e| e.ended_at.to_rfc3339()).collect();
+        let embedding_array =
+            arrow_array::FixedSizeListArray::from_iter_primitive::<Float32Type, _, _>(
+                embeddings
+                    .iter()
+                    .map(|embedding| Some(embedding.iter().map(|v| Some(*v)).collect::<Vec<_>>())),
+                self.dimensions,
+            );
+
+        let batch = RecordBatch::try_new(
+            Arc::new(Self::schema(self.dimensions)),
+            vec![
+                Arc::new(StringArray::from_iter_values(
+                    episodes.iter().map(|e| e.id.as_str()),
+                )) as arrow_array::ArrayRef,
+                Arc::new(StringArray::from_iter_values(
+                    episodes.iter().map(|e| e.channel_id.as_str()),
+                )),
+                Arc::new(Int64Array::from_iter_values(
+                    episodes.iter().map(|e| e.first_rowid),
+                )),
+                Arc::new(Int64Array::from_iter_values(
+                    episodes.iter().map(|e| e.last_rowid),
+                )),
+                Arc::new(StringArray::from(started)),
+                Arc::new(StringArray::from(ended)),
+                Arc::new(StringArray::from_iter_values(
+                    episodes.iter().map(|e| e.content.as_str()),
+                )),
+                Arc::new(embedding_array),
+            ],
+        )
+        .map_err(|e| DbError::LanceDb(e.to_string()))?;
+
+        let batches =
+            RecordBatchIterator::new(vec![Ok(batch)], Arc::new(Self::schema(self.dimensions)));
+        self.table
+            .add(Box::new(batches))
+            .execute()
+            .await
+            .map_err(|e| DbError::LanceDb(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Highest indexed message rowid per channel.
+    async fn load_high_water(&self) -> Result<HashMap<String, i64>> {
+        use lancedb::query::{ExecutableQuery, QueryBase};
+
+        let results: Vec<arrow_array::RecordBatch> = self
+            .table
+            .query()
+            .select(lancedb::query::Select::columns(&[
+                "channel_id",
+                "last_rowid",
+            ]))
+            .execute()
+            .await
+            .map_err(|e| DbError::LanceDb(e.to_string()))?
+            .try_collect()
+            .await
+            .map_err(|e| DbError::LanceDb(e.to_string()))?;
+
+        let mut high_water: HashMap<String, i64> = HashMap::new();
+        for batch in results {
+            let (Some(channel_col), Some(rowid_col)) = (
+                batch.column_by_name("channel_id"),
+                batch.column_by_name("last_rowid"),
+            ) else {
+                continue;
+            };
+            let channels = channel_col.as_string::<i32>();
+            let rowids = rowid_col.as_primitive::<Int64Type>();
+            for i in 0..batch.num_rows() {
+                let entry = high_water.entry(channels.value(i).to_string()).or_default();
+                *entry = (*entry).max(rowids.value(i));
+            }
+        }
+
+        Ok(high_water)
+    }
+
+    fn schema(dimensions: i32) -> arrow_schema::Schema {
+        use arrow_schema::{DataType, Field};
+
+        arrow_schema::Schema::new(vec![
+            Field::new("id", DataType::Utf8, false),
+            Field::new("channel_id", DataType::Utf8, false),
+            Field::new("first_rowid", DataType::Int64, false),
+            Field::new("last_rowid", DataType::Int64, false),
+            Field::new("started_at", DataType::Utf8, false),
+            Field::new("ended_at", DataType::Utf8, false),
+            Field::new("content", DataType::Utf8, false),
+            Field::new(
+                "embedding",
+                DataType::FixedSizeList(
+                    Arc::new(Field::new("item", DataType::Float32, true)),
+                    dimensions,
+                ),
+                false,
+            ),
+        ])
+    }
+}
+
+/// Group a channel's messages (in rowid order) into sealed episodes.
+///
+/// An episode seals when it reaches the size limits or is followed by a long
+/// silence. The trailing episode also seals once the silence has lasted that
+/// long as of `now`, but only when `at_head` says there are no later messages
+/// outside this batch. Anything left open is indexed by a later sync, so
+/// stored episodes never change.
+///
+/// Returns the episodes and the rowid through which messages were consumed.
+fn seal_episodes(
+    channel_id: &str,
+    messages: &[(i64, ConversationMessage)],
+    at_head: bool,
+    now: DateTime<Utc>,
+    indexed_through: i64,
+) -> (Vec<Episode>, i64) {
+    let mut episodes = Vec::new();
+    let mut open: Vec<(i64, &ConversationMessage, String)> = Vec::new();
+    let mut open_chars = 0;
+
+    for (rowid, message) in messages {
+        if message.role == "system" || message.content.trim().is_empty() {
+            continue;
+        }
+
+        let line = episode_line(message);
+        if let Some((_, last, _)) = open.last() {
+            let idle = (message.created_at - last.created_at).num_seconds();
+            if idle >= EPISODE_IDLE_GAP_SECS
+                || open.len() >= EPISODE_MAX_MESSAGES
+                || open_chars + line.len() > EPISODE_MAX_CHARS
+            {
+                episodes.push(build_episode(channel_id, &open));
+                open.clear();
+                open_chars = 0;
+            }
+        }
+        open_chars += line.len();
+        open.push((*rowid, message, line));
+    }
+
+    if let Some((_, last, _)) = open.last()
+        && at_head
+        && (now - last.created_at).num_seconds() >= EPISODE_IDLE_GAP_SECS
+    {
+        episodes.push(build_episode(channel_id, &open));
+        open.clear();
+    }
+
+    let consumed_through = match open.first() {
+        Some((rowid, _, _)) => rowid - 1,
+        None => messages
+            .last()
+            .map(|(rowid, _)| *rowid)
+            .unwrap_or(indexed_through),
+    };
+
+    (episodes, consumed_through)
+}
+
+fn build_episode(channel_id: &str, messages: &[(i64, &ConversationMessage, String)]) -> Episode {
+    let (first_rowid, first, _) = &messages[0];
+    let (last_rowid, last, _) = &messages[messages.len() - 1];
+    Episode {
+        id: first.id.clone(),
+        channel_id: channel_id.to_string(),
+        first_rowid: *first_rowid,
+        last_rowid: *last_rowid,
+        started_at: first.created_at,
+        ended_at: last.created_at,
+        content: messages
+            .iter()
+            .map(|(_, _, line)| line.as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// One transcript line, truncated so a single long message can't crowd out
+/// the rest of its episode.
+fn episode_line(message: &ConversationMessage) -> String {
+    let sender = match message.role.as_str() {
+        "user" => message.sender_name.as_deref().unwrap_or("user"),
+        _ => "assistant",
+    };
+    let content = message.content.trim();
+    let content: String = if content.chars().count() > EPISODE_MAX_CHARS {
+        let truncated: String = content.chars().take(EPISODE_MAX_CHARS).collect();
+        format!("{truncated}…")
+    } else {
+        content.to_string()
+    };
+    format!("{sender}: {content}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn message(id: &str, role: &str, content: &str, at: DateTime<Utc>) -> ConversationMessage {
+        ConversationMessage {
+            id: id.to_string(),
+            channel_id: "discord:1".to_string(),
+            role: role.to_string(),
+            sender_name: (role == "user").then(|| "alice".to_string()),
+            sender_id: None,
+            content: content.to_string(),
+            metadata: None,
+            created_at: at,
+        }
+    }
+
+    #[test]
+    fn seal_episodes_splits_on_idle_gap_and_keeps_open_tail() {
+        let now = Utc::now();
+        let start = now - Duration::hours(3);
+        let messages = vec![
+            (1, message("m1", "user", "should we ship on friday?", start)),
+            (
+                2,
+                message(
+                    "m2",
+                    "assistant",
+                    "let's wait",
+                    start + Duration::minutes(1),
+                ),
+            ),
+            (
+                3,
+                message(
+                    "m3",
+                    "system",
+                    "branch started",
+                    start + Duration::minutes(2),
+                ),
+            ),
+            // Two hours later: a new episode that is still open.
+            (
+                4,
+                message("m4", "user", "deploy is green", now - Duration::minutes(5)),
+            ),
+        ];
+
+        let (episodes, consumed) = seal_episodes("discord:1", &messages, true, now, 0);
+
+        assert_eq!(episodes.len(), 1);
+        assert_eq!(episodes[0].id, "m1");
+        assert_eq!(episodes[0].last_rowid, 2);
+        assert_eq!(
+            episodes[0].content,
+            "alice: should we ship on friday?\nassistant: let's wait"
+        );
+        assert_eq!(consumed, 3, "open tail starts at rowid 4");
+    }
+
+    #[test]
+    fn seal_episodes_seals_idle_tail_only_at_head() {
+        let now = Utc::now();
+        let earlier = now - Duration::hours(1);
+        let messages = vec![(7, message("m7", "user", "decided on postgres", earlier))];
+
+        let (episodes, consumed) = seal_episodes("discord:1", &messages, true, now, 6);
+        assert_eq!(episodes.len(), 1);
+        assert_eq!(consumed, 7);
+
+        let (episodes, consumed) = seal_episodes("discord:1", &messages, false, now, 6);
+        assert!(episodes.is_empty());
+        assert_eq!(consumed, 6);
+    }
+
+    #[test]
+    fn seal_episodes_caps_messages_per_episode() {
+        let now = Utc::now();
+        let start = now - Duration::hours(2);
+        let messages: Vec<_> = (0..EPISODE_MAX_MESSAGES as i64 + 1)
+            .map(|i| {
+                (
+                    i + 1,
+                    message(&format!("m{i}"), "user", "hi", start + Duration::seconds(i)),
+                )
+            })
+            .collect();
+
+        let (episodes, _) = seal_episodes("discord:1", &messages, true, now, 0);
+        assert_eq!(episodes.len(), 2);
+        assert_eq!(episodes[0].last_rowid, EPISODE_MAX_MESSAGES as i64);
+    }
+}
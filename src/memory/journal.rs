@@ -0,0 +1,228 @@
+//! Write-ahead journal for the LanceDB embeddings table (SQLite).
+//!
+//! Every write to the embeddings table is recorded here before it is sent to
+//! LanceDB and stamped with the resulting table version once it lands. On
+//! restart, entries that never got a version (the process died mid-write)
+//! are repaired one memory at a time against SQLite, which is the source of
+//! truth, instead of dropping the table and re-embedding everything. When a
+//! damaged table is rolled back to an earlier version, entries applied after
+//! that version are repaired the same way.
+//!
+//! Applied entries are kept as long as LanceDB keeps old table versions, so
+//! any version the table can be rolled back to is covered.
+
+use chrono::{DateTime, Utc};
+use sqlx::{Row as _, SqlitePool};
+
+/// How long applied entries are kept. Matches the age at which LanceDB's
+/// optimize pass prunes old table versions.
+pub const APPLIED_RETENTION: chrono::Duration = chrono::Duration::days(7);
+
+/// A write to the embeddings table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalOperation {
+    /// Add or replace the memory's embedding.
+    Upsert,
+    Delete,
+}
+
+impl JournalOperation {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Upsert => "upsert",
+            Self::Delete => "delete",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "upsert" => Some(Self::Upsert),
+            "delete" => Some(Self::Delete),
+            _ => None,
+        }
+    }
+}
+
+/// One recorded write.
+#[derive(Debug, Clone)]
+pub struct JournalEntry {
+    pub id: i64,
+    pub memory_id: String,
+    pub operation: JournalOperation,
+    /// Table version after the write, or `None` if it never finished.
+    pub applied_version: Option<u64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Persists the embeddings write journal.
+#[derive(Debug, Clone)]
+pub struct EmbeddingJournal {
+    pool: SqlitePool,
+}
+
+impl EmbeddingJournal {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a write about to be applied. Returns the entry ID to pass to
+    /// [`mark_applied`](Self::mark_applied).
+    pub async fn begin(
+        &self,
+        memory_id: &str,
+        operation: JournalOperation,
+    ) -> crate::error::Result<i64> {
+        let result = sqlx::query(
+            "INSERT INTO embedding_journal (memory_id, operation, created_at) VALUES (?, ?, ?)",
+        )
+        .bind(memory_id)
+        .bind(operation.as_str())
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Stamp an entry with the table version its write produced.
+    pub async fn mark_applied(&self, id: i64, version: u64) -> crate::error::Result<()> {
+        sqlx::query("UPDATE embedding_journal SET applied_version = ? WHERE id = ?")
+            .bind(version as i64)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Entries whose write may be missing from the table: those that never
+    /// finished and, after a rollback to `rolled_back_to`, those applied in
+    /// a later version. Oldest first.
+    pub async fn unresolved(
+        &self,
+        rolled_back_to: Option<u64>,
+    ) -> crate::error::Result<Vec<JournalEntry>> {
+        let rows = sqlx::query(
+            "SELECT id, memory_id, operation, applied_version, created_at \
+             FROM embedding_journal \
+             WHERE applied_version IS NULL OR (?1 IS NOT NULL AND applied_version > ?1) \
+             ORDER BY id",
+        )
+        .bind(rolled_back_to.map(|version| version as i64))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.iter().filter_map(entry_from_row).collect())
+    }
+
+    /// Drop entries once their writes have been repaired.
+    pub async fn resolve(&self, ids: &[i64]) -> crate::error::Result<()> {
+        let mut transaction = self.pool.begin().await?;
+        for id in ids {
+            sqlx::query("DELETE FROM embedding_journal WHERE id = ?")
+                .bind(id)
+                .execute(&mut *transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Drop every entry. Used when the table was recreated and is being
+    /// refilled from SQLite anyway.
+    pub async fn clear(&self) -> crate::error::Result<u64> {
+        let result = sqlx::query("DELETE FROM embedding_journal")
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Drop applied entries older than [`APPLIED_RETENTION`]. Returns the
+    /// number removed.
+    pub async fn prune_applied(&self) -> crate::error::Result<u64> {
+        let result = sqlx::query(
+            "DELETE FROM embedding_journal \
+             WHERE applied_version IS NOT NULL AND created_at < ?",
+        )
+        .bind(Utc::now() - APPLIED_RETENTION)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+/// The last recorded operation for each memory, in order of first
+/// appearance. Earlier writes to the same memory are superseded by it.
+pub fn latest_per_memory(entries: &[JournalEntry]) -> Vec<(String, JournalOperation)> {
+    let mut latest: Vec<(String, JournalOperation)> = Vec::new();
+    for entry in entries {
+        match latest
+            .iter_mut()
+            .find(|(memory_id, _)| *memory_id == entry.memory_id)
+        {
+            Some((_, operation)) => *operation = entry.operation,
+            None => latest.push((entry.memory_id.clone(), entry.operation)),
+        }
+    }
+    latest
+}
+
+fn entry_from_row(row: &sqlx::sqlite::SqliteRow) -> Option<JournalEntry> {
+    Some(JournalEntry {
+        id: row.get("id"),
+        memory_id: row.get("memory_id"),
+        operation: JournalOperation::parse(row.get("operation"))?,
+        applied_version: row
+            .get::<Option<i64>, _>("applied_version")
+            .map(|version| version as u64),
+        created_at: row.get("created_at"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn finds_unfinished_and_rolled_back_writes() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to create sqlite memory pool");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("failed to run migrations");
+        let journal = EmbeddingJournal::new(pool);
+
+        let first = journal
+            .begin("memory-a", JournalOperation::Upsert)
+            .await
+            .unwrap();
+        journal.mark_applied(first, 4).await.unwrap();
+        let second = journal
+            .begin("memory-b", JournalOperation::Upsert)
+            .await
+            .unwrap();
+        journal.mark_applied(second, 5).await.unwrap();
+        journal
+            .begin("memory-a", JournalOperation::Delete)
+            .await
+            .unwrap();
+
+        let pending = journal.unresolved(None).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].operation, JournalOperation::Delete);
+
+        let after_rollback = journal.unresolved(Some(4)).await.unwrap();
+        assert_eq!(
+            latest_per_memory(&after_rollback),
+            vec![
+                ("memory-b".to_string(), JournalOperation::Upsert),
+                ("memory-a".to_string(), JournalOperation::Delete),
+            ]
+        );
+
+        let ids: Vec<i64> = after_rollback.iter().map(|entry| entry.id).collect();
+        journal.resolve(&ids).await.unwrap();
+        assert_eq!(journal.unresolved(Some(0)).await.unwrap().len(), 1);
+    }
+}
@@ -1,46 +1,207 @@
 //! LanceDB table management and embedding storage with HNSW vector index and FTS.
 
+use crate::config::MemoryFtsConfig;
 use crate::error::{DbError, Result};
+use crate::memory::journal::{EmbeddingJournal, JournalOperation};
 use arrow_array::cast::AsArray;
 use arrow_array::types::Float32Type;
 use arrow_array::{Array, RecordBatchIterator};
 use futures::TryStreamExt;
+use serde::Serialize;
 use std::sync::Arc;
 
+/// What a compaction and prune pass reclaimed from a LanceDB table.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct OptimizeReport {
+    pub fragments_removed: usize,
+    pub fragments_added: usize,
+    pub versions_pruned: u64,
+    pub bytes_reclaimed: u64,
+}
+
+impl From<lancedb::table::OptimizeStats> for OptimizeReport {
+    fn from(stats: lancedb::table::OptimizeStats) -> Self {
+        let (fragments_removed, fragments_added) = stats
+            .compaction
+            .map(|metrics| (metrics.fragments_removed, metrics.fragments_added))
+            .unwrap_or_default();
+        let (versions_pruned, bytes_reclaimed) = stats
+            .prune
+            .map(|removal| (removal.old_versions, removal.bytes_removed))
+            .unwrap_or_default();
+        Self {
+            fragments_removed,
+            fragments_added,
+            versions_pruned,
+            bytes_reclaimed,
+        }
+    }
+}
+
 /// Schema constants for the embeddings table.
 const TABLE_NAME: &str = "memory_embeddings";
-const EMBEDDING_DIM: i32 = 384; // all-MiniLM-L6-v2 dimension
+pub(super) const EMBEDDING_DIM: i32 = 384; // all-MiniLM-L6-v2 dimension
+
+/// Vector size of the `embedding` column of an existing table, or `None` if
+/// the schema can't be read.
+pub(super) async fn embedding_dimensions(table: &lancedb::Table) -> Option<i32> {
+    let schema = table.schema().await.ok()?;
+    match schema.field_with_name("embedding").ok()?.data_type() {
+        arrow_schema::DataType::FixedSizeList(_, size) => Some(*size),
+        _ => None,
+    }
+}
+
+/// Whether every row of the table's current version can be read. Only the
+/// `id` column is scanned.
+async fn is_readable(table: &lancedb::Table) -> bool {
+    use lancedb::query::{ExecutableQuery, QueryBase};
+
+    if table.count_rows(None).await.is_err() {
+        return false;
+    }
+    let Ok(stream) = table
+        .query()
+        .select(lancedb::query::Select::columns(&["id"]))
+        .execute()
+        .await
+    else {
+        return false;
+    };
+    stream.try_collect::<Vec<_>>().await.is_ok()
+}
+
+/// Restore the newest earlier version that can be read, returning it. The
+/// table is left at its latest version if none can.
+async fn roll_back_to_readable(table: &lancedb::Table) -> Option<u64> {
+    let current = table.version().await.ok()?;
+    let mut versions: Vec<u64> = table
+        .list_versions()
+        .await
+        .ok()?
+        .into_iter()
+        .map(|version| version.version)
+        .filter(|version| *version < current)
+        .collect();
+    versions.sort_unstable_by(|left, right| right.cmp(left));
+
+    for version in versions {
+        if table.checkout(version).await.is_err() || !is_readable(table).await {
+            continue;
+        }
+        match table.restore().await {
+            Ok(()) => return Some(version),
+            Err(error) => {
+                tracing::warn!(%error, version, "failed to restore embeddings table version");
+                break;
+            }
+        }
+    }
+    if let Err(error) = table.checkout_latest().await {
+        tracing::warn!(%error, "failed to return embeddings table to its latest version");
+    }
+    None
+}
 
 /// LanceDB table for memory embeddings with HNSW index and FTS.
 pub struct EmbeddingTable {
     table: lancedb::Table,
+    fts: MemoryFtsConfig,
+    /// Vector size of the `embedding` column.
+    dimensions: i32,
+    /// The table was created on open, so memories already in SQLite have
+    /// no embeddings yet.
+    rebuilt: bool,
+    /// The latest table version was unreadable and the table was rolled
+    /// back to this one on open.
+    rolled_back_to: Option<u64>,
+    /// Records writes before they are applied, see [`crate::memory::journal`].
+    journal: Option<EmbeddingJournal>,
 }
 
 impl Clone for EmbeddingTable {
     fn clone(&self) -> Self {
         Self {
             table: self.table.clone(),
+            fts: self.fts.clone(),
+            dimensions: self.dimensions,
+            rebuilt: self.rebuilt,
+            rolled_back_to: self.rolled_back_to,
+            journal: self.journal.clone(),
         }
     }
 }
 
 impl EmbeddingTable {
-    /// Open existing table or create a new one.
-    ///
-    /// If the table exists but is corrupted (e.g. process killed mid-write),
-    /// it is dropped and recreated. Embeddings can be regenerated from SQLite.
+    /// Open existing table or create a new one, sized for the local
+    /// fastembed model.
     pub async fn open_or_create(connection: &lancedb::Connection) -> Result<Self> {
+        Self::open_with_dimensions(connection, EMBEDDING_DIM as usize).await
+    }
+
+    /// Open existing table or create a new one holding vectors of
+    /// `dimensions` floats.
+    ///
+    /// If the latest version of the table can't be read (e.g. process killed
+    /// mid-write), the table is rolled back to the newest readable version;
+    /// see [`rolled_back_to`](Self::rolled_back_to). If no version is
+    /// readable, the table can't be opened at all, or it was built for a
+    /// different embedding model size, it is dropped and recreated.
+    /// Embeddings can be regenerated from SQLite; see
+    /// [`was_rebuilt`](Self::was_rebuilt).
+    pub async fn open_with_dimensions(
+        connection: &lancedb::Connection,
+        dimensions: usize,
+    ) -> Result<Self> {
+        let dimensions = i32::try_from(dimensions).map_err(|_| {
+            DbError::LanceDb(format!("unsupported embedding dimension {dimensions}"))
+        })?;
+        let build = |table, rebuilt, rolled_back_to| Self {
+            table,
+            fts: MemoryFtsConfig::default(),
+            dimensions,
+            rebuilt,
+            rolled_back_to,
+            journal: None,
+        };
+
         // Try to open existing table
         match connection.open_table(TABLE_NAME).execute().await {
-            Ok(table) => return Ok(Self { table }),
+            Ok(table) => {
+                let existing = embedding_dimensions(&table).await;
+                if existing == Some(dimensions) {
+                    if is_readable(&table).await {
+                        return Ok(build(table, false, None));
+                    }
+                    if let Some(version) = roll_back_to_readable(&table).await {
+                        tracing::warn!(
+                            version,
+                            "embeddings table was unreadable, rolled back to the last readable version"
+                        );
+                        return Ok(build(table, false, Some(version)));
+                    }
+                    tracing::warn!("embeddings table has no readable version, recreating");
+                } else {
+                    tracing::warn!(
+                        existing = ?existing,
+                        expected = dimensions,
+                        "embeddings table was built for a different embedding size, recreating"
+                    );
+                }
+                if let Err(error) = connection.drop_table(TABLE_NAME, &[]).await {
+                    tracing::warn!(%error, "drop_table failed, proceeding anyway");
+                }
+            }
             Err(error) => {
                 tracing::debug!(%error, "failed to open embeddings table, will create");
             }
         }
 
         // Table doesn't exist or is unreadable — try creating it
-        match Self::create_empty_table(connection).await {
-            Ok(table) => return Ok(Self { table }),
+        match Self::create_empty_table(connection, dimensions).await {
+            Ok(table) => {
+                return Ok(build(table, true, None));
+            }
             Err(error) => {
                 tracing::warn!(
                     %error,
@@ -55,15 +216,54 @@ impl EmbeddingTable {
             tracing::warn!(%error, "drop_table failed during recovery, proceeding anyway");
         }
 
-        let table = Self::create_empty_table(connection).await?;
+        let table = Self::create_empty_table(connection, dimensions).await?;
         tracing::info!("embeddings table recovered — embeddings will be rebuilt from memory store");
 
-        Ok(Self { table })
+        Ok(build(table, true, None))
+    }
+
+    /// Whether the table was created on open rather than found intact.
+    /// Memories already in SQLite need re-embedding when it was; see
+    /// [`MemorySearch::reindex_all`](crate::memory::MemorySearch::reindex_all).
+    pub fn was_rebuilt(&self) -> bool {
+        self.rebuilt
+    }
+
+    /// The version the table was rolled back to on open because its latest
+    /// version was unreadable. Writes after it are repaired from the journal;
+    /// see [`MemorySearch::recover_journal`](crate::memory::MemorySearch::recover_journal).
+    pub fn rolled_back_to(&self) -> Option<u64> {
+        self.rolled_back_to
+    }
+
+    /// Record every write in `journal` before applying it.
+    pub fn with_journal(mut self, journal: EmbeddingJournal) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// The write journal, if one is attached.
+    pub fn journal(&self) -> Option<&EmbeddingJournal> {
+        self.journal.as_ref()
+    }
+
+    /// Use these tokenizer options whenever the FTS index is built.
+    pub fn with_fts_config(mut self, fts: MemoryFtsConfig) -> Self {
+        self.fts = fts;
+        self
+    }
+
+    /// The FTS options this table builds its index with.
+    pub fn fts_config(&self) -> &MemoryFtsConfig {
+        &self.fts
     }
 
     /// Create an empty embeddings table.
-    async fn create_empty_table(connection: &lancedb::Connection) -> Result<lancedb::Table> {
-        let schema = Self::schema();
+    async fn create_empty_table(
+        connection: &lancedb::Connection,
+        dimensions: i32,
+    ) -> Result<lancedb::Table> {
+        let schema = Self::schema(dimensions);
         let batches = RecordBatchIterator::new(vec![].into_iter().map(Ok), Arc::new(schema));
 
         connection
@@ -76,10 +276,70 @@ impl EmbeddingTable {
     /// Store an embedding with content for a memory.
     /// The content is stored for FTS search capability.
     pub async fn store(&self, memory_id: &str, content: &str, embedding: &[f32]) -> Result<()> {
-        if embedding.len() != EMBEDDING_DIM as usize {
+        let entry = self
+            .journal_begin(memory_id, JournalOperation::Upsert)
+            .await?;
+        self.add_row(memory_id, content, embedding).await?;
+        self.journal_applied(entry).await;
+        Ok(())
+    }
+
+    /// Replace a memory's embedding, as one journaled write.
+    pub async fn replace(&self, memory_id: &str, content: &str, embedding: &[f32]) -> Result<()> {
+        Self::validate_memory_id(memory_id)?;
+        let entry = self
+            .journal_begin(memory_id, JournalOperation::Upsert)
+            .await?;
+        self.delete_row(memory_id).await?;
+        self.add_row(memory_id, content, embedding).await?;
+        self.journal_applied(entry).await;
+        Ok(())
+    }
+
+    /// Delete an embedding by memory ID.
+    pub async fn delete(&self, memory_id: &str) -> Result<()> {
+        Self::validate_memory_id(memory_id)?;
+        let entry = self
+            .journal_begin(memory_id, JournalOperation::Delete)
+            .await?;
+        self.delete_row(memory_id).await?;
+        self.journal_applied(entry).await;
+        Ok(())
+    }
+
+    /// Record a write in the journal, if one is attached. A write that
+    /// can't be journaled isn't applied.
+    async fn journal_begin(
+        &self,
+        memory_id: &str,
+        operation: JournalOperation,
+    ) -> Result<Option<i64>> {
+        match &self.journal {
+            Some(journal) => Ok(Some(journal.begin(memory_id, operation).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Stamp a journal entry with the table version its write produced. A
+    /// failure here only means the write is repeated on the next restart.
+    async fn journal_applied(&self, entry: Option<i64>) {
+        let (Some(journal), Some(entry)) = (&self.journal, entry) else {
+            return;
+        };
+        let result = match self.table.version().await {
+            Ok(version) => journal.mark_applied(entry, version).await,
+            Err(error) => Err(DbError::LanceDb(error.to_string()).into()),
+        };
+        if let Err(error) = result {
+            tracing::warn!(%error, entry, "failed to mark embedding write as applied");
+        }
+    }
+
+    async fn add_row(&self, memory_id: &str, content: &str, embedding: &[f32]) -> Result<()> {
+        if embedding.len() != self.dimensions as usize {
             return Err(DbError::LanceDb(format!(
                 "Embedding dimension mismatch: expected {}, got {}",
-                EMBEDDING_DIM,
+                self.dimensions,
                 embedding.len()
             ))
             .into());
@@ -87,7 +347,7 @@ impl EmbeddingTable {
 
         use arrow_array::{RecordBatch, StringArray};
 
-        let schema = Self::schema();
+        let schema = Self::schema(self.dimensions);
 
         // Build arrays for the record batch
         let id_array = StringArray::from(vec![memory_id]);
@@ -97,7 +357,7 @@ impl EmbeddingTable {
         let embedding_array =
             arrow_array::FixedSizeListArray::from_iter_primitive::<Float32Type, _, _>(
                 vec![Some(embedding.iter().map(|v| Some(*v)).collect::<Vec<_>>())],
-                EMBEDDING_DIM,
+                self.dimensions,
             );
 
         let batch = RecordBatch::try_new(
@@ -111,7 +371,8 @@ impl EmbeddingTable {
         .map_err(|e| DbError::LanceDb(e.to_string()))?;
 
         // Create iterator for IntoArrow trait
-        let batches = RecordBatchIterator::new(vec![Ok(batch)], Arc::new(Self::schema()));
+        let batches =
+            RecordBatchIterator::new(vec![Ok(batch)], Arc::new(Self::schema(self.dimensions)));
 
         self.table
             .add(Box::new(batches))
@@ -122,9 +383,7 @@ impl EmbeddingTable {
         Ok(())
     }
 
-    /// Delete an embedding by memory ID.
-    pub async fn delete(&self, memory_id: &str) -> Result<()> {
-        Self::validate_memory_id(memory_id)?;
+    async fn delete_row(&self, memory_id: &str) -> Result<()> {
         let predicate = format!("id = '{}'", memory_id);
         self.table
             .delete(&predicate)
@@ -141,10 +400,10 @@ impl EmbeddingTable {
         query_embedding: &[f32],
         limit: usize,
     ) -> Result<Vec<(String, f32)>> {
-        if query_embedding.len() != EMBEDDING_DIM as usize {
+        if query_embedding.len() != self.dimensions as usize {
             return Err(DbError::LanceDb(format!(
                 "Query embedding dimension mismatch: expected {}, got {}",
-                EMBEDDING_DIM,
+                self.dimensions,
                 query_embedding.len()
             ))
             .into());
@@ -295,6 +554,17 @@ impl EmbeddingTable {
         Ok(matches)
     }
 
+    /// Number of stored embeddings. Answered from table metadata, so it is
+    /// cheap enough for health checks.
+    pub async fn row_count(&self) -> Result<usize> {
+        let count = self
+            .table
+            .count_rows(None)
+            .await
+            .map_err(|e| DbError::LanceDb(e.to_string()))?;
+        Ok(count)
+    }
+
     /// Create HNSW vector index and FTS index for better performance.
     /// Should be called after enough data accumulates.
     pub async fn create_indexes(&self) -> Result<()> {
@@ -310,6 +580,27 @@ impl EmbeddingTable {
         Ok(())
     }
 
+    /// Compact data files and prune old table versions.
+    ///
+    /// Every write creates a new LanceDB version, so the directory grows
+    /// without bound until versions are cleaned up.
+    pub async fn optimize(&self) -> Result<OptimizeReport> {
+        let stats = self
+            .table
+            .optimize(lancedb::table::OptimizeAction::All)
+            .await
+            .map_err(|e| DbError::LanceDb(format!("Failed to optimize table: {}", e)))?;
+
+        // Old versions are gone, so journal entries that could only repair a
+        // rollback to them are no longer needed.
+        if let Some(journal) = &self.journal
+            && let Err(error) = journal.prune_applied().await
+        {
+            tracing::warn!(%error, "failed to prune embedding journal");
+        }
+        Ok(stats.into())
+    }
+
     /// Ensure the FTS index exists on the content column.
     ///
     /// LanceDB requires an inverted index for `full_text_search()` queries.
@@ -318,7 +609,11 @@ impl EmbeddingTable {
     pub async fn ensure_fts_index(&self) -> Result<()> {
         match self
             .table
-            .create_index(&["content"], lancedb::index::Index::FTS(Default::default()))
+            .create_index(
+                &["content"],
+                lancedb::index::Index::FTS(self.fts_index_params()?),
+            )
+            .replace(false)
             .execute()
             .await
         {
@@ -339,8 +634,50 @@ impl EmbeddingTable {
         }
     }
 
+    /// Rebuild the FTS index with the current tokenizer options.
+    ///
+    /// Options only apply when the index is built, so this is how a changed
+    /// `memory_fts` config takes effect on an existing table.
+    pub async fn rebuild_fts_index(&self) -> Result<()> {
+        self.table
+            .create_index(
+                &["content"],
+                lancedb::index::Index::FTS(self.fts_index_params()?),
+            )
+            .replace(true)
+            .execute()
+            .await
+            .map_err(|e| DbError::LanceDb(format!("Failed to rebuild FTS index: {}", e)))?;
+        tracing::debug!(fts = ?self.fts, "FTS index rebuilt on content column");
+        Ok(())
+    }
+
+    /// Build inverted index parameters from the configured options, leaving
+    /// LanceDB's defaults in place for anything unset.
+    fn fts_index_params(&self) -> Result<lancedb::index::scalar::FtsIndexBuilder> {
+        let mut params = lancedb::index::scalar::FtsIndexBuilder::default();
+        if let Some(tokenizer) = &self.fts.tokenizer {
+            params = params.base_tokenizer(tokenizer.clone());
+        }
+        if let Some(language) = &self.fts.language {
+            params = params.language(language).map_err(|e| {
+                DbError::LanceDb(format!("Unsupported FTS language '{}': {}", language, e))
+            })?;
+        }
+        if let Some(stemming) = self.fts.stemming {
+            params = params.stem(stemming);
+        }
+        if let Some(remove_stop_words) = self.fts.remove_stop_words {
+            params = params.remove_stop_words(remove_stop_words);
+        }
+        if let Some(ascii_folding) = self.fts.ascii_folding {
+            params = params.ascii_folding(ascii_folding);
+        }
+        Ok(params)
+    }
+
     /// Get the Arrow schema for the embeddings table.
-    fn schema() -> arrow_schema::Schema {
+    fn schema(dimensions: i32) -> arrow_schema::Schema {
         arrow_schema::Schema::new(vec![
             arrow_schema::Field::new("id", arrow_schema::DataType::Utf8, false),
             arrow_schema::Field::new("content", arrow_schema::DataType::Utf8, false),
@@ -352,7 +689,7 @@ impl EmbeddingTable {
                         arrow_schema::DataType::Float32,
                         true,
                     )),
-                    EMBEDDING_DIM,
+                    dimensions,
                 ),
                 false,
             ),
@@ -1,7 +1,7 @@
 //! Memory maintenance: decay, prune, merge, reindex.
 
 use crate::error::Result;
-use crate::memory::{EmbeddingModel, EmbeddingTable, Memory, MemoryStore, MemoryType};
+use crate::memory::{EmbeddingModel, EmbeddingTable, Memory, MemoryScope, MemoryStore, MemoryType};
 use anyhow::Context;
 
 use sqlx::Row;
@@ -267,7 +267,12 @@ async fn merge_similar_memories(
             else {
                 continue;
             };
-            if candidate_memory.forgotten {
+            // Merging across scopes would leak a channel's memory into others.
+            if candidate_memory.forgotten
+                || candidate_memory.scope != active_survivor.scope
+                || (candidate_memory.scope == MemoryScope::Channel
+                    && candidate_memory.channel_id != active_survivor.channel_id)
+            {
                 continue;
             }
 
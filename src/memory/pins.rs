@@ -0,0 +1,266 @@
+//! Items pinned into the memory bulletin (SQLite).
+//!
+//! The generated bulletin is an LLM summary that changes every refresh. Pins
+//! are facts the cortex or an admin wants in every prompt regardless: they
+//! are rendered ahead of the generated bulletin until unpinned or until their
+//! TTL runs out. Their combined size is capped by
+//! `[defaults.cortex] bulletin_pin_max_words` so pins can't crowd out the rest
+//! of the context.
+
+use crate::config::RuntimeConfig;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{Row as _, SqlitePool};
+use std::sync::Arc;
+
+/// A fact pinned into the bulletin.
+#[derive(Debug, Clone, Serialize)]
+pub struct BulletinPin {
+    pub id: String,
+    pub content: String,
+    /// Who pinned it: `cortex` or `api`.
+    pub pinned_by: String,
+    /// When the pin lapses, or `None` if it stays until unpinned.
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl BulletinPin {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+/// A pin to add, before it has an ID.
+#[derive(Debug, Clone)]
+pub struct NewBulletinPin {
+    pub content: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Word count of a set of pin contents, as measured against the cap.
+pub fn pinned_words<'a>(contents: impl IntoIterator<Item = &'a str>) -> usize {
+    contents
+        .into_iter()
+        .map(|content| content.split_whitespace().count())
+        .sum()
+}
+
+/// Check a full set of pin contents against the word limit.
+pub fn check_pin_budget<'a>(
+    contents: impl IntoIterator<Item = &'a str>,
+    max_words: usize,
+) -> anyhow::Result<()> {
+    let mut words = 0;
+    for content in contents {
+        if content.trim().is_empty() {
+            anyhow::bail!("pinned items must not be empty");
+        }
+        words += pinned_words([content]);
+    }
+    if words > max_words {
+        anyhow::bail!(
+            "pinned items would total {words} words; the limit is {max_words} \
+             (`bulletin_pin_max_words`). Unpin something first."
+        );
+    }
+    Ok(())
+}
+
+/// Render active pins as a bulletin section, oldest first. Empty when
+/// nothing is pinned.
+pub fn render_pins(pins: &[BulletinPin], now: DateTime<Utc>) -> String {
+    let lines: Vec<String> = pins
+        .iter()
+        .filter(|pin| pin.is_active(now))
+        .map(|pin| format!("- {}", pin.content.trim()))
+        .collect();
+    if lines.is_empty() {
+        return String::new();
+    }
+    format!("### Pinned\n\n{}\n", lines.join("\n"))
+}
+
+/// Persists bulletin pins.
+#[derive(Debug, Clone)]
+pub struct BulletinPinStore {
+    pool: SqlitePool,
+}
+
+impl BulletinPinStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Unexpired pins, oldest first. Expired pins are deleted.
+    pub async fn active(&self) -> crate::error::Result<Vec<BulletinPin>> {
+        let now = Utc::now();
+        sqlx::query("DELETE FROM bulletin_pins WHERE expires_at IS NOT NULL AND expires_at <= ?")
+            .bind(now)
+            .execute(&self.pool)
+            .await?;
+
+        let rows = sqlx::query(
+            "SELECT id, content, pinned_by, expires_at, created_at \
+             FROM bulletin_pins ORDER BY created_at, rowid",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.iter().map(pin_from_row).collect())
+    }
+
+    pub async fn pin(
+        &self,
+        pin: &NewBulletinPin,
+        pinned_by: &str,
+    ) -> crate::error::Result<BulletinPin> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let created_at = Utc::now();
+        sqlx::query(
+            "INSERT INTO bulletin_pins (id, content, pinned_by, expires_at, created_at) \
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(pin.content.trim())
+        .bind(pinned_by)
+        .bind(pin.expires_at)
+        .bind(created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(BulletinPin {
+            id,
+            content: pin.content.trim().to_string(),
+            pinned_by: pinned_by.to_string(),
+            expires_at: pin.expires_at,
+            created_at,
+        })
+    }
+
+    /// Remove a pin. Returns whether it existed.
+    pub async fn unpin(&self, id: &str) -> crate::error::Result<bool> {
+        let result = sqlx::query("DELETE FROM bulletin_pins WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Replace every pin with `pins`, in one transaction.
+    pub async fn replace(
+        &self,
+        pins: &[NewBulletinPin],
+        pinned_by: &str,
+    ) -> crate::error::Result<()> {
+        let mut transaction = self.pool.begin().await?;
+        sqlx::query("DELETE FROM bulletin_pins")
+            .execute(&mut *transaction)
+            .await?;
+        let created_at = Utc::now();
+        for pin in pins {
+            sqlx::query(
+                "INSERT INTO bulletin_pins (id, content, pinned_by, expires_at, created_at) \
+                 VALUES (?, ?, ?, ?, ?)",
+            )
+            .bind(uuid::Uuid::new_v4().to_string())
+            .bind(pin.content.trim())
+            .bind(pinned_by)
+            .bind(pin.expires_at)
+            .bind(created_at)
+            .execute(&mut *transaction)
+            .await?;
+        }
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    /// Load active pins into the agent's runtime config, so the next prompt
+    /// renders them. Returns the pins.
+    pub async fn reload(
+        &self,
+        runtime_config: &RuntimeConfig,
+    ) -> crate::error::Result<Vec<BulletinPin>> {
+        let pins = self.active().await?;
+        runtime_config.bulletin_pins.store(Arc::new(pins.clone()));
+        Ok(pins)
+    }
+}
+
+fn pin_from_row(row: &sqlx::sqlite::SqliteRow) -> BulletinPin {
+    BulletinPin {
+        id: row.get("id"),
+        content: row.get("content"),
+        pinned_by: row.get("pinned_by"),
+        expires_at: row.get("expires_at"),
+        created_at: row.get("created_at"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn setup_store() -> BulletinPinStore {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to create sqlite memory pool");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("failed to run migrations");
+        BulletinPinStore::new(pool)
+    }
+
+    #[tokio::test]
+    async fn expired_pins_drop_out() {
+        let store = setup_store().await;
+        let now = Utc::now();
+        let kept = store
+            .pin(
+                &NewBulletinPin {
+                    content: "Launch is on Friday.".into(),
+                    expires_at: Some(now + chrono::Duration::hours(1)),
+                },
+                "api",
+            )
+            .await
+            .unwrap();
+        store
+            .pin(
+                &NewBulletinPin {
+                    content: "Old news.".into(),
+                    expires_at: Some(now - chrono::Duration::seconds(1)),
+                },
+                "cortex",
+            )
+            .await
+            .unwrap();
+
+        let pins = store.active().await.unwrap();
+        assert_eq!(pins.len(), 1);
+        assert_eq!(pins[0].id, kept.id);
+        assert_eq!(
+            render_pins(&pins, now),
+            "### Pinned\n\n- Launch is on Friday.\n"
+        );
+        assert_eq!(render_pins(&pins, now + chrono::Duration::hours(2)), "");
+
+        store
+            .replace(
+                &[NewBulletinPin {
+                    content: "Only this.".into(),
+                    expires_at: None,
+                }],
+                "api",
+            )
+            .await
+            .unwrap();
+        let pins = store.active().await.unwrap();
+        assert_eq!(pins.len(), 1);
+        assert!(store.unpin(&pins[0].id).await.unwrap());
+        assert!(store.active().await.unwrap().is_empty());
+    }
+}
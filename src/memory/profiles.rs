@@ -0,0 +1,464 @@
+//! Per-user long-term profiles (SQLite).
+//!
+//! A profile is keyed by platform and platform user ID, so the same person
+//! is recognized across every channel on that platform. Besides a display
+//! name and timezone it holds a list of short facts (preferences, roles,
+//! anything else worth knowing), which memory persistence branches add and
+//! admins review through the API. The profile of whoever is speaking is
+//! injected into the channel prompt for that turn.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Row as _, SqlitePool};
+
+/// Facts beyond this many per profile are not rendered into the prompt.
+const MAX_PROMPT_FACTS: usize = 20;
+
+/// What a profile fact describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileFactKind {
+    /// Likes, dislikes, and ways of working.
+    Preference,
+    /// Job, responsibilities, or standing in the community.
+    Role,
+    /// Anything else worth remembering about the person.
+    Fact,
+}
+
+impl ProfileFactKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Preference => "preference",
+            Self::Role => "role",
+            Self::Fact => "fact",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "preference" => Some(Self::Preference),
+            "role" => Some(Self::Role),
+            "fact" => Some(Self::Fact),
+            _ => None,
+        }
+    }
+}
+
+/// A single fact on a profile.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileFact {
+    pub id: String,
+    pub kind: ProfileFactKind,
+    pub content: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A user's profile with its facts.
+#[derive(Debug, Clone, Serialize)]
+pub struct UserProfile {
+    pub id: String,
+    pub platform: String,
+    pub sender_id: String,
+    pub display_name: Option<String>,
+    pub timezone: Option<String>,
+    pub facts: Vec<ProfileFact>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    pub last_seen_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl UserProfile {
+    /// Whether the profile holds anything beyond identity.
+    pub fn has_content(&self) -> bool {
+        self.timezone.is_some() || !self.facts.is_empty()
+    }
+}
+
+/// The profile ID for a platform user.
+pub fn profile_id(platform: &str, sender_id: &str) -> String {
+    format!("{platform}:{sender_id}")
+}
+
+/// The platform a channel is on, taken from its ID (`discord:…`, `slack:…`).
+///
+/// Profiles are keyed by this rather than by adapter name so that every
+/// channel and branch on a platform agrees on who a sender is.
+pub fn channel_platform(channel_id: &str) -> &str {
+    channel_id.split(':').next().unwrap_or(channel_id)
+}
+
+/// Persists user profiles and their facts.
+#[derive(Debug, Clone)]
+pub struct UserProfileStore {
+    pool: SqlitePool,
+}
+
+impl UserProfileStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Record that a user spoke, creating their profile if needed and
+    /// refreshing the display name. Fire-and-forget.
+    pub fn touch(&self, platform: &str, sender_id: &str, display_name: &str) {
+        let store = self.clone();
+        let platform = platform.to_string();
+        let sender_id = sender_id.to_string();
+        let display_name = display_name.to_string();
+
+        tokio::spawn(async move {
+            if let Err(error) = store
+                .upsert(&platform, &sender_id, Some(&display_name))
+                .await
+            {
+                tracing::warn!(%error, "failed to record user profile");
+            }
+        });
+    }
+
+    /// Create a profile or refresh its display name and last-seen time.
+    pub async fn upsert(
+        &self,
+        platform: &str,
+        sender_id: &str,
+        display_name: Option<&str>,
+    ) -> crate::error::Result<String> {
+        let id = profile_id(platform, sender_id);
+        sqlx::query(
+            "INSERT INTO user_profiles (id, platform, sender_id, display_name) \
+             VALUES (?, ?, ?, ?) \
+             ON CONFLICT(id) DO UPDATE SET \
+                 display_name = COALESCE(excluded.display_name, user_profiles.display_name), \
+                 last_seen_at = CURRENT_TIMESTAMP",
+        )
+        .bind(&id)
+        .bind(platform)
+        .bind(sender_id)
+        .bind(display_name)
+        .execute(&self.pool)
+        .await?;
+        Ok(id)
+    }
+
+    pub async fn get(&self, id: &str) -> crate::error::Result<Option<UserProfile>> {
+        let row = sqlx::query(
+            "SELECT id, platform, sender_id, display_name, timezone, created_at, updated_at, last_seen_at \
+             FROM user_profiles WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let mut profile = profile_from_row(&row);
+        profile.facts = self.facts(id).await?;
+        Ok(Some(profile))
+    }
+
+    /// Profiles most recently seen first, optionally on one platform.
+    pub async fn list(
+        &self,
+        platform: Option<&str>,
+        limit: i64,
+    ) -> crate::error::Result<Vec<UserProfile>> {
+        let rows = sqlx::query(
+            "SELECT id, platform, sender_id, display_name, timezone, created_at, updated_at, last_seen_at \
+             FROM user_profiles \
+             WHERE (?1 IS NULL OR platform = ?1) \
+             ORDER BY last_seen_at DESC \
+             LIMIT ?2",
+        )
+        .bind(platform)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut profiles = Vec::with_capacity(rows.len());
+        for row in rows {
+            let mut profile = profile_from_row(&row);
+            profile.facts = self.facts(&profile.id).await?;
+            profiles.push(profile);
+        }
+        Ok(profiles)
+    }
+
+    /// Profiles on a platform whose sender ID or display name matches
+    /// `query` exactly (display names case-insensitively).
+    pub async fn find(
+        &self,
+        platform: &str,
+        query: &str,
+    ) -> crate::error::Result<Vec<UserProfile>> {
+        let query = query.trim().trim_start_matches('@');
+        let ids: Vec<String> = sqlx::query_scalar(
+            "SELECT id FROM user_profiles \
+             WHERE platform = ? AND (sender_id = ? OR display_name = ? COLLATE NOCASE) \
+             ORDER BY last_seen_at DESC",
+        )
+        .bind(platform)
+        .bind(query)
+        .bind(query)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut profiles = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some(profile) = self.get(&id).await? {
+                profiles.push(profile);
+            }
+        }
+        Ok(profiles)
+    }
+
+    /// Set or clear a profile's timezone. Returns false if the profile
+    /// doesn't exist.
+    pub async fn set_timezone(
+        &self,
+        id: &str,
+        timezone: Option<&str>,
+    ) -> crate::error::Result<bool> {
+        let result = sqlx::query(
+            "UPDATE user_profiles SET timezone = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?",
+        )
+        .bind(timezone)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Add a fact to a profile. Adding a fact the profile already has only
+    /// refreshes it.
+    pub async fn add_fact(
+        &self,
+        profile_id: &str,
+        kind: ProfileFactKind,
+        content: &str,
+    ) -> crate::error::Result<String> {
+        let id: String = sqlx::query_scalar(
+            "INSERT INTO user_profile_facts (id, profile_id, kind, content) \
+             VALUES (?, ?, ?, ?) \
+             ON CONFLICT(profile_id, kind, content) DO UPDATE SET updated_at = CURRENT_TIMESTAMP \
+             RETURNING id",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(profile_id)
+        .bind(kind.as_str())
+        .bind(content.trim())
+        .fetch_one(&self.pool)
+        .await?;
+
+        sqlx::query("UPDATE user_profiles SET updated_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(profile_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(id)
+    }
+
+    /// Rewrite a fact. Returns false if it doesn't exist on the profile.
+    pub async fn update_fact(
+        &self,
+        profile_id: &str,
+        fact_id: &str,
+        kind: ProfileFactKind,
+        content: &str,
+    ) -> crate::error::Result<bool> {
+        let result = sqlx::query(
+            "UPDATE user_profile_facts \
+             SET kind = ?, content = ?, updated_at = CURRENT_TIMESTAMP \
+             WHERE id = ? AND profile_id = ?",
+        )
+        .bind(kind.as_str())
+        .bind(content.trim())
+        .bind(fact_id)
+        .bind(profile_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove a fact from a profile. Returns false if it doesn't exist there.
+    pub async fn delete_fact(&self, profile_id: &str, fact_id: &str) -> crate::error::Result<bool> {
+        let result = sqlx::query("DELETE FROM user_profile_facts WHERE id = ? AND profile_id = ?")
+            .bind(fact_id)
+            .bind(profile_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete a profile and its facts. The profile is recreated, empty, the
+    /// next time the user speaks.
+    pub async fn delete(&self, id: &str) -> crate::error::Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM user_profile_facts WHERE profile_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let result = sqlx::query("DELETE FROM user_profiles WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn facts(&self, profile_id: &str) -> crate::error::Result<Vec<ProfileFact>> {
+        let rows = sqlx::query(
+            "SELECT id, kind, content, created_at, updated_at \
+             FROM user_profile_facts \
+             WHERE profile_id = ? \
+             ORDER BY created_at ASC, rowid ASC",
+        )
+        .bind(profile_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let kind: String = row.try_get("kind").unwrap_or_default();
+                ProfileFact {
+                    id: row.try_get("id").unwrap_or_default(),
+                    kind: ProfileFactKind::parse(&kind).unwrap_or(ProfileFactKind::Fact),
+                    content: row.try_get("content").unwrap_or_default(),
+                    created_at: row
+                        .try_get("created_at")
+                        .unwrap_or_else(|_| chrono::Utc::now()),
+                    updated_at: row
+                        .try_get("updated_at")
+                        .unwrap_or_else(|_| chrono::Utc::now()),
+                }
+            })
+            .collect())
+    }
+}
+
+fn profile_from_row(row: &sqlx::sqlite::SqliteRow) -> UserProfile {
+    UserProfile {
+        id: row.try_get("id").unwrap_or_default(),
+        platform: row.try_get("platform").unwrap_or_default(),
+        sender_id: row.try_get("sender_id").unwrap_or_default(),
+        display_name: row.try_get("display_name").ok().flatten(),
+        timezone: row.try_get("timezone").ok().flatten(),
+        facts: Vec::new(),
+        created_at: row
+            .try_get("created_at")
+            .unwrap_or_else(|_| chrono::Utc::now()),
+        updated_at: row
+            .try_get("updated_at")
+            .unwrap_or_else(|_| chrono::Utc::now()),
+        last_seen_at: row
+            .try_get("last_seen_at")
+            .unwrap_or_else(|_| chrono::Utc::now()),
+    }
+}
+
+/// Render a profile as a prompt section, or None when there's nothing to say.
+pub fn render_profile_context(profile: &UserProfile) -> Option<String> {
+    if !profile.has_content() {
+        return None;
+    }
+
+    let name = profile
+        .display_name
+        .as_deref()
+        .unwrap_or(&profile.sender_id);
+    let mut output =
+        format!("## About {name}\n\nWhat you know about the person you're replying to:\n");
+    if let Some(timezone) = &profile.timezone {
+        output.push_str(&format!("- Timezone: {timezone}\n"));
+    }
+    for kind in [
+        ProfileFactKind::Role,
+        ProfileFactKind::Preference,
+        ProfileFactKind::Fact,
+    ] {
+        let label = match kind {
+            ProfileFactKind::Role => "Role",
+            ProfileFactKind::Preference => "Preference",
+            ProfileFactKind::Fact => "Fact",
+        };
+        for fact in profile
+            .facts
+            .iter()
+            .filter(|fact| fact.kind == kind)
+            .take(MAX_PROMPT_FACTS)
+        {
+            output.push_str(&format!("- {label}: {}\n", fact.content));
+        }
+    }
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn setup_store() -> UserProfileStore {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to create sqlite memory pool");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("failed to run migrations");
+        UserProfileStore::new(pool)
+    }
+
+    #[tokio::test]
+    async fn profiles_accumulate_facts_and_render() {
+        let store = setup_store().await;
+        let id = store
+            .upsert("discord", "42", Some("Alice"))
+            .await
+            .expect("upsert should succeed");
+        assert_eq!(id, "discord:42");
+
+        let empty = store.get(&id).await.unwrap().expect("profile exists");
+        assert!(render_profile_context(&empty).is_none());
+
+        store
+            .set_timezone(&id, Some("Europe/Berlin"))
+            .await
+            .unwrap();
+        let first = store
+            .add_fact(&id, ProfileFactKind::Preference, "prefers short answers")
+            .await
+            .unwrap();
+        let again = store
+            .add_fact(&id, ProfileFactKind::Preference, "prefers short answers ")
+            .await
+            .unwrap();
+        assert_eq!(first, again);
+        store
+            .add_fact(&id, ProfileFactKind::Role, "maintains the billing service")
+            .await
+            .unwrap();
+
+        // A later upsert without a name keeps the known one.
+        store.upsert("discord", "42", None).await.unwrap();
+
+        let found = store.find("discord", "@alice").await.unwrap();
+        assert_eq!(found.len(), 1);
+        let profile = &found[0];
+        assert_eq!(profile.display_name.as_deref(), Some("Alice"));
+        assert_eq!(profile.facts.len(), 2);
+        assert!(store.find("slack", "alice").await.unwrap().is_empty());
+
+        let rendered = render_profile_context(profile).expect("profile has content");
+        assert!(rendered.starts_with("## About Alice"));
+        assert!(rendered.contains("- Timezone: Europe/Berlin\n"));
+        assert!(rendered.contains("- Role: maintains the billing service\n"));
+        assert!(rendered.contains("- Preference: prefers short answers\n"));
+
+        assert!(store.delete_fact(&id, &first).await.unwrap());
+        assert!(store.delete(&id).await.unwrap());
+        assert!(store.get(&id).await.unwrap().is_none());
+    }
+}
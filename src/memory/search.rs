@@ -1,12 +1,25 @@
 //! Memory search: hybrid (vector + FTS + RRF + graph), temporal, importance, and typed queries.
 
 use crate::error::Result;
-use crate::memory::types::{Memory, MemorySearchResult, MemoryType, RelationType};
-use crate::memory::{EmbeddingModel, EmbeddingTable, MemoryStore};
-
-use std::collections::HashMap;
+use crate::memory::journal::{self, JournalOperation};
+use crate::memory::types::{
+    Memory, MemorySearchResult, MemoryType, MemoryVisibility, RelationType, ScoreExplanation,
+    SourceScore,
+};
+use crate::memory::{EmbeddingModel, EmbeddingTable, EpisodeTable, MemoryStore};
+
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+/// What [`MemorySearch::recover_journal`] repaired.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JournalRecovery {
+    /// Writes applied again.
+    pub reapplied: usize,
+    /// Upserts removed because the memory no longer exists.
+    pub rolled_back: usize,
+}
+
 /// Which search strategy to use.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum SearchMode {
@@ -38,6 +51,7 @@ pub struct MemorySearch {
     store: Arc<MemoryStore>,
     embedding_table: EmbeddingTable,
     embedding_model: Arc<EmbeddingModel>,
+    episodes: Option<EpisodeTable>,
 }
 
 impl Clone for MemorySearch {
@@ -46,6 +60,7 @@ impl Clone for MemorySearch {
             store: Arc::clone(&self.store),
             embedding_table: self.embedding_table.clone(),
             embedding_model: Arc::clone(&self.embedding_model),
+            episodes: self.episodes.clone(),
         }
     }
 }
@@ -69,9 +84,16 @@ impl MemorySearch {
             store,
             embedding_table,
             embedding_model,
+            episodes: None,
         }
     }
 
+    /// Attach the conversation episode index used for episodic recall.
+    pub fn with_episodes(mut self, episodes: EpisodeTable) -> Self {
+        self.episodes = Some(episodes);
+        self
+    }
+
     /// Get a reference to the memory store.
     pub fn store(&self) -> &MemoryStore {
         &self.store
@@ -92,6 +114,113 @@ impl MemorySearch {
         &self.embedding_model
     }
 
+    /// Get the conversation episode index, if one is attached.
+    pub fn episodes(&self) -> Option<&EpisodeTable> {
+        self.episodes.as_ref()
+    }
+
+    /// Embed `content` and replace the memory's entry in the vector and
+    /// full-text index. Used when a memory's content is written outside the
+    /// `memory_save` tool.
+    pub async fn index_memory(&self, memory_id: &str, content: &str) -> Result<()> {
+        let embedding = self.embedding_model.embed_one(content).await?;
+        self.embedding_table
+            .replace(memory_id, content, &embedding)
+            .await?;
+        self.embedding_table.ensure_fts_index().await
+    }
+
+    /// Re-embed every memory into the vector and full-text index. Run when
+    /// the embeddings table was recreated, e.g. after switching embedding
+    /// models. Returns the number of memories indexed.
+    pub async fn reindex_all(&self) -> Result<usize> {
+        let memories = self
+            .store
+            .get_sorted(SearchSort::Recent, i64::MAX, None)
+            .await?;
+        let mut indexed = 0;
+        for batch in memories.chunks(64) {
+            let texts = batch.iter().map(|memory| memory.content.clone()).collect();
+            let embeddings = self.embedding_model.embed_many(texts).await?;
+            for (memory, embedding) in batch.iter().zip(&embeddings) {
+                self.embedding_table
+                    .replace(&memory.id, &memory.content, embedding)
+                    .await?;
+                indexed += 1;
+            }
+        }
+        if indexed > 0 {
+            self.embedding_table.ensure_fts_index().await?;
+        }
+        Ok(indexed)
+    }
+
+    /// Repair embeddings writes the journal shows may be missing: writes a
+    /// crash cut short and, when the table was rolled back on open, writes
+    /// made after the restored version. An upsert is re-applied from the
+    /// memory's current content in SQLite, or rolled back if the memory no
+    /// longer exists; a delete is applied again. Memories that fail are
+    /// logged and left in the journal for the next restart.
+    pub async fn recover_journal(&self) -> Result<JournalRecovery> {
+        let mut recovery = JournalRecovery::default();
+        let Some(journal) = self.embedding_table.journal() else {
+            return Ok(recovery);
+        };
+        if self.embedding_table.was_rebuilt() {
+            // Every memory is re-embedded anyway; see `reindex_all`.
+            journal.clear().await?;
+            return Ok(recovery);
+        }
+
+        let entries = journal
+            .unresolved(self.embedding_table.rolled_back_to())
+            .await?;
+        let mut failed = HashSet::new();
+        for (memory_id, operation) in journal::latest_per_memory(&entries) {
+            match self.repair_embedding(&memory_id, operation).await {
+                Ok(true) => recovery.reapplied += 1,
+                Ok(false) => recovery.rolled_back += 1,
+                Err(error) => {
+                    tracing::warn!(%error, %memory_id, "failed to repair embedding from journal");
+                    failed.insert(memory_id);
+                }
+            }
+        }
+        if recovery.reapplied + recovery.rolled_back > 0 {
+            self.embedding_table.ensure_fts_index().await?;
+        }
+
+        let resolved: Vec<i64> = entries
+            .iter()
+            .filter(|entry| !failed.contains(&entry.memory_id))
+            .map(|entry| entry.id)
+            .collect();
+        journal.resolve(&resolved).await?;
+        Ok(recovery)
+    }
+
+    /// Bring one memory's embedding in line with SQLite. Returns whether the
+    /// journaled operation was re-applied (`false`: an upsert rolled back).
+    async fn repair_embedding(&self, memory_id: &str, operation: JournalOperation) -> Result<bool> {
+        let memory = match operation {
+            JournalOperation::Upsert => self.store.load(memory_id).await?,
+            JournalOperation::Delete => None,
+        };
+        match memory {
+            Some(memory) => {
+                let embedding = self.embedding_model.embed_one(&memory.content).await?;
+                self.embedding_table
+                    .replace(&memory.id, &memory.content, &embedding)
+                    .await?;
+                Ok(true)
+            }
+            None => {
+                self.embedding_table.delete(memory_id).await?;
+                Ok(operation == JournalOperation::Delete)
+            }
+        }
+    }
+
     /// Unified search entry point. Dispatches to the appropriate strategy
     /// based on `config.mode`.
     pub async fn search(
@@ -132,7 +261,12 @@ impl MemorySearch {
     ) -> Result<Vec<MemorySearchResult>> {
         let memories = self
             .store
-            .get_sorted(sort, config.max_results as i64, config.memory_type)
+            .get_sorted_visible(
+                sort,
+                config.max_results as i64,
+                config.memory_type,
+                &config.visibility,
+            )
             .await?;
 
         let total = memories.len();
@@ -151,6 +285,7 @@ impl MemorySearch {
                     memory,
                     score,
                     rank: rank + 1,
+                    explanation: None,
                 }
             })
             .collect();
@@ -245,6 +380,14 @@ impl MemorySearch {
         let fused_results =
             reciprocal_rank_fusion(&vector_results, &fts_results, &graph_results, config.rrf_k);
 
+        let source_scores = config.explain.then(|| {
+            (
+                source_scores(&vector_results, config.rrf_k),
+                source_scores(&fts_results, config.rrf_k),
+                source_scores(&graph_results, config.rrf_k),
+            )
+        });
+
         // Convert to MemorySearchResult with ranks, applying optional type filter
         let results: Vec<MemorySearchResult> = fused_results
             .into_iter()
@@ -252,12 +395,26 @@ impl MemorySearch {
                 config
                     .memory_type
                     .is_none_or(|t| scored.memory.memory_type == t)
+                    && config.visibility.allows(&scored.memory)
             })
             .enumerate()
-            .map(|(rank, scored)| MemorySearchResult {
-                memory: scored.memory,
-                score: scored.score as f32,
-                rank: rank + 1,
+            .map(|(rank, scored)| {
+                let explanation =
+                    source_scores
+                        .as_ref()
+                        .map(|(vector, fts, graph)| ScoreExplanation {
+                            vector: vector.get(&scored.memory.id).cloned(),
+                            fts: fts.get(&scored.memory.id).cloned(),
+                            graph: graph.get(&scored.memory.id).cloned(),
+                            rrf_k: config.rrf_k,
+                            min_score: config.min_score,
+                        });
+                MemorySearchResult {
+                    memory: scored.memory,
+                    score: scored.score as f32,
+                    rank: rank + 1,
+                    explanation,
+                }
             })
             .filter(|r| r.score >= config.min_score)
             .take(config.max_results_per_source)
@@ -356,6 +513,10 @@ pub struct SearchConfig {
     pub min_score: f32,
     /// Maximum graph traversal depth. Only used in hybrid mode.
     pub max_graph_depth: usize,
+    /// Attach a per-source score breakdown to each result. Only used in hybrid mode.
+    pub explain: bool,
+    /// Which memories may be returned, by scope.
+    pub visibility: MemoryVisibility,
 }
 
 impl Default for SearchConfig {
@@ -371,6 +532,8 @@ impl Default for SearchConfig {
             // score is ~0.016. Set threshold low enough to not discard everything.
             min_score: 0.0,
             max_graph_depth: 2,
+            explain: false,
+            visibility: MemoryVisibility::All,
         }
     }
 }
@@ -431,6 +594,25 @@ fn reciprocal_rank_fusion(
     fused
 }
 
+/// Rank, raw score, and RRF contribution of each memory in one source's list.
+/// Mirrors `reciprocal_rank_fusion`: a memory listed more than once keeps its
+/// best rank but accumulates every contribution.
+fn source_scores(results: &[ScoredMemory], k: f64) -> HashMap<String, SourceScore> {
+    let mut scores: HashMap<String, SourceScore> = HashMap::new();
+    for (rank, scored) in results.iter().enumerate() {
+        let contribution = 1.0 / (k + (rank as f64 + 1.0));
+        scores
+            .entry(scored.memory.id.clone())
+            .and_modify(|existing| existing.contribution += contribution)
+            .or_insert(SourceScore {
+                rank: rank + 1,
+                score: scored.score,
+                contribution,
+            });
+    }
+    scores
+}
+
 /// Curate search results to return only the most relevant.
 pub fn curate_results(
     results: &[MemorySearchResult],
@@ -494,6 +676,28 @@ mod tests {
         assert!(fused.is_empty());
     }
 
+    #[test]
+    fn test_source_scores_match_rrf_contributions() {
+        let vector = vec![make_scored("a", 0.9), make_scored("b", 0.7)];
+        let graph = vec![make_scored("b", 0.8), make_scored("b", 0.4)];
+
+        let vector_scores = source_scores(&vector, 60.0);
+        let graph_scores = source_scores(&graph, 60.0);
+        let fused = reciprocal_rank_fusion(&vector, &[], &graph, 60.0);
+
+        let b = &vector_scores["b"];
+        assert_eq!(b.rank, 2);
+        assert!((b.score - 0.7).abs() < 1e-10);
+
+        // Duplicate graph entries keep the first rank and sum contributions.
+        let b_graph = &graph_scores["b"];
+        assert_eq!(b_graph.rank, 1);
+        assert!((b_graph.score - 0.8).abs() < 1e-10);
+
+        let fused_b = fused.iter().find(|s| s.memory.id == "b").unwrap();
+        assert!((b.contribution + b_graph.contribution - fused_b.score).abs() < 1e-10);
+    }
+
     #[test]
     fn test_curate_results_respects_limit() {
         let results: Vec<MemorySearchResult> = (0..10)
@@ -501,6 +705,7 @@ mod tests {
                 memory: Memory::new(format!("mem {i}"), MemoryType::Fact),
                 score: 1.0 - (i as f32 * 0.1),
                 rank: i + 1,
+                explanation: None,
             })
             .collect();
 
@@ -671,4 +876,48 @@ mod tests {
         let results = search.search("", &config).await.unwrap();
         assert!(results.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_recover_journal_repairs_unfinished_writes() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        let store = crate::memory::MemoryStore::new(pool.clone());
+        let memory = Memory::new("The user prefers tea.", MemoryType::Preference);
+        store.save(&memory).await.unwrap();
+
+        let lance_dir = tempfile::tempdir().unwrap();
+        let lance_conn = lancedb::connect(lance_dir.path().to_str().unwrap())
+            .execute()
+            .await
+            .unwrap();
+        EmbeddingTable::open_or_create(&lance_conn).await.unwrap();
+        let journal = crate::memory::EmbeddingJournal::new(pool);
+        let embedding_table = EmbeddingTable::open_or_create(&lance_conn)
+            .await
+            .unwrap()
+            .with_journal(journal.clone());
+
+        // Writes recorded but never applied, as if the process died mid-write.
+        let missing_id = uuid::Uuid::new_v4().to_string();
+        journal
+            .begin(&memory.id, JournalOperation::Upsert)
+            .await
+            .unwrap();
+        journal
+            .begin(&missing_id, JournalOperation::Upsert)
+            .await
+            .unwrap();
+
+        let search = MemorySearch::new(store, embedding_table, Arc::new(EmbeddingModel::hashed()));
+        let recovery = search.recover_journal().await.unwrap();
+
+        assert_eq!(recovery.reapplied, 1);
+        assert_eq!(recovery.rolled_back, 1);
+        assert_eq!(search.embedding_table().row_count().await.unwrap(), 1);
+        assert!(journal.unresolved(None).await.unwrap().is_empty());
+    }
 }
@@ -2,7 +2,9 @@
 
 use crate::error::Result;
 use crate::memory::search::SearchSort;
-use crate::memory::types::{Association, Memory, MemoryType, RelationType};
+use crate::memory::types::{
+    Association, Memory, MemoryScope, MemoryType, MemoryVisibility, RelationType,
+};
 
 use anyhow::Context as _;
 use sqlx::{Row, SqlitePool};
@@ -69,8 +71,8 @@ impl MemoryStore {
         sqlx::query(
             r#"
             INSERT INTO memories (id, content, memory_type, importance, created_at, updated_at,
-                                 last_accessed_at, access_count, source, channel_id, forgotten)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                                 last_accessed_at, access_count, source, channel_id, scope, forgotten)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(&memory.id)
@@ -83,6 +85,7 @@ impl MemoryStore {
         .bind(memory.access_count)
         .bind(&memory.source)
         .bind(memory.channel_id.as_ref().map(|id| id.as_ref()))
+        .bind(memory.scope.as_str())
         .bind(memory.forgotten)
         .execute(&self.pool)
         .await
@@ -114,7 +117,7 @@ impl MemoryStore {
         let row = sqlx::query(
             r#"
             SELECT id, content, memory_type, importance, created_at, updated_at,
-                   last_accessed_at, access_count, source, channel_id, forgotten
+                   last_accessed_at, access_count, source, channel_id, scope, forgotten
             FROM memories
             WHERE id = ?
             "#,
@@ -148,7 +151,7 @@ impl MemoryStore {
             UPDATE memories
             SET content = ?, memory_type = ?, importance = ?, updated_at = ?,
                 last_accessed_at = ?, access_count = ?, source = ?, channel_id = ?,
-                forgotten = ?
+                scope = ?, forgotten = ?
             WHERE id = ?
             "#,
         )
@@ -160,6 +163,7 @@ impl MemoryStore {
         .bind(memory.access_count)
         .bind(&memory.source)
         .bind(memory.channel_id.as_ref().map(|id| id.as_ref()))
+        .bind(memory.scope.as_str())
         .bind(memory.forgotten)
         .bind(&memory.id)
         .execute(&self.pool)
@@ -554,7 +558,7 @@ impl MemoryStore {
         let rows = sqlx::query(
             r#"
             SELECT id, content, memory_type, importance, created_at, updated_at,
-                   last_accessed_at, access_count, source, channel_id, forgotten
+                   last_accessed_at, access_count, source, channel_id, scope, forgotten
             FROM memories
             WHERE memory_type = ? AND forgotten = 0
             ORDER BY importance DESC, updated_at DESC
@@ -575,7 +579,7 @@ impl MemoryStore {
         let rows = sqlx::query(
             r#"
             SELECT id, content, memory_type, importance, created_at, updated_at,
-                   last_accessed_at, access_count, source, channel_id, forgotten
+                   last_accessed_at, access_count, source, channel_id, scope, forgotten
             FROM memories
             WHERE importance >= ? AND forgotten = 0
             ORDER BY importance DESC, updated_at DESC
@@ -600,46 +604,60 @@ impl MemoryStore {
         sort: SearchSort,
         limit: i64,
         memory_type: Option<MemoryType>,
+    ) -> Result<Vec<Memory>> {
+        self.get_sorted_visible(sort, limit, memory_type, &MemoryVisibility::All)
+            .await
+    }
+
+    /// Like [`get_sorted`](Self::get_sorted), limited to memories `visibility`
+    /// allows.
+    pub async fn get_sorted_visible(
+        &self,
+        sort: SearchSort,
+        limit: i64,
+        memory_type: Option<MemoryType>,
+        visibility: &MemoryVisibility,
     ) -> Result<Vec<Memory>> {
         let order_clause = match sort {
             SearchSort::Recent => "ORDER BY created_at DESC",
             SearchSort::Importance => "ORDER BY importance DESC, created_at DESC",
             SearchSort::MostAccessed => "ORDER BY access_count DESC, created_at DESC",
         };
-
-        let (query_str, type_filter) = if let Some(ref memory_type) = memory_type {
-            (
-                format!(
-                    "SELECT id, content, memory_type, importance, created_at, updated_at, \
-                     last_accessed_at, access_count, source, channel_id, forgotten \
-                     FROM memories WHERE memory_type = ? AND forgotten = 0 {order_clause} LIMIT ?"
-                ),
-                Some(memory_type.to_string()),
-            )
+        let type_clause = if memory_type.is_some() {
+            "AND memory_type = ?"
         } else {
-            (
-                format!(
-                    "SELECT id, content, memory_type, importance, created_at, updated_at, \
-                     last_accessed_at, access_count, source, channel_id, forgotten \
-                     FROM memories WHERE forgotten = 0 {order_clause} LIMIT ?"
-                ),
-                None,
-            )
+            ""
+        };
+        let (scope_clause, scope_channel) = match visibility {
+            MemoryVisibility::All => ("", None),
+            MemoryVisibility::Global => ("AND scope = 'global'", None),
+            MemoryVisibility::GlobalAndChannel(channel_id) => (
+                "AND (scope = 'global' OR (scope = 'channel' AND channel_id = ?))",
+                Some(channel_id),
+            ),
+            MemoryVisibility::Channel(channel_id) => {
+                ("AND scope = 'channel' AND channel_id = ?", Some(channel_id))
+            }
         };
 
-        let rows = if let Some(type_str) = type_filter {
-            sqlx::query(&query_str)
-                .bind(type_str)
-                .bind(limit)
-                .fetch_all(&self.pool)
-                .await
-        } else {
-            sqlx::query(&query_str)
-                .bind(limit)
-                .fetch_all(&self.pool)
-                .await
+        let query_str = format!(
+            "SELECT id, content, memory_type, importance, created_at, updated_at, \
+             last_accessed_at, access_count, source, channel_id, scope, forgotten \
+             FROM memories WHERE forgotten = 0 {type_clause} {scope_clause} \
+             {order_clause} LIMIT ?"
+        );
+        let mut query = sqlx::query(&query_str);
+        if let Some(memory_type) = memory_type {
+            query = query.bind(memory_type.to_string());
+        }
+        if let Some(channel_id) = scope_channel {
+            query = query.bind(channel_id);
         }
-        .with_context(|| format!("failed to get sorted memories ({sort:?})"))?;
+        let rows = query
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .with_context(|| format!("failed to get sorted memories ({sort:?})"))?;
 
         Ok(rows.into_iter().map(|row| row_to_memory(&row)).collect())
     }
@@ -695,6 +713,11 @@ fn row_to_memory(row: &sqlx::sqlite::SqliteRow) -> Memory {
         access_count: row.try_get("access_count").unwrap_or(0),
         source: row.try_get("source").ok(),
         channel_id: channel_id.map(|id| Arc::from(id) as crate::ChannelId),
+        scope: row
+            .try_get::<String, _>("scope")
+            .ok()
+            .and_then(|scope| MemoryScope::parse(&scope))
+            .unwrap_or_default(),
         forgotten: row.try_get::<bool, _>("forgotten").unwrap_or(false),
     }
 }
@@ -776,6 +799,56 @@ mod tests {
         assert_eq!(loaded.memory_type, MemoryType::Fact);
     }
 
+    #[tokio::test]
+    async fn get_sorted_visible_respects_scope() {
+        let store = MemoryStore::connect_in_memory().await;
+        let global = Memory::new("shared", MemoryType::Fact);
+        let private =
+            Memory::new("dm only", MemoryType::Fact).scoped_to_channel(Arc::from("discord:dm"));
+        let other =
+            Memory::new("other dm", MemoryType::Fact).scoped_to_channel(Arc::from("discord:other"));
+        for memory in [&global, &private, &other] {
+            store.save(memory).await.unwrap();
+        }
+        assert_eq!(
+            store.load(&private.id).await.unwrap().unwrap().scope,
+            MemoryScope::Channel
+        );
+
+        let visible = |visibility: MemoryVisibility| {
+            let store = store.clone();
+            async move {
+                let mut contents: Vec<String> = store
+                    .get_sorted_visible(SearchSort::Recent, 10, None, &visibility)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|memory| memory.content)
+                    .collect();
+                contents.sort();
+                contents
+            }
+        };
+        assert_eq!(visible(MemoryVisibility::All).await.len(), 3);
+        assert_eq!(visible(MemoryVisibility::Global).await, ["shared"]);
+        assert_eq!(
+            visible(MemoryVisibility::for_channel(
+                "discord:dm",
+                MemoryScope::Global
+            ))
+            .await,
+            ["dm only", "shared"]
+        );
+        assert_eq!(
+            visible(MemoryVisibility::for_channel(
+                "discord:dm",
+                MemoryScope::Channel
+            ))
+            .await,
+            ["dm only"]
+        );
+    }
+
     #[tokio::test]
     async fn test_get_sorted_recent() {
         let store = MemoryStore::connect_in_memory().await;
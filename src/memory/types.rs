@@ -16,6 +16,10 @@ pub struct Memory {
     pub access_count: i64,
     pub source: Option<String>,
     pub channel_id: Option<crate::ChannelId>,
+    /// Who can read this memory. Channel-scoped memories are only visible
+    /// from `channel_id`.
+    #[serde(default)]
+    pub scope: MemoryScope,
     /// Soft-delete flag. Forgotten memories are excluded from search and recall
     /// but remain in the database.
     pub forgotten: bool,
@@ -39,6 +43,7 @@ impl Memory {
             access_count: 0,
             source: None,
             channel_id: None,
+            scope: MemoryScope::Global,
             forgotten: false,
         }
     }
@@ -61,6 +66,13 @@ impl Memory {
         self
     }
 
+    /// Scope the memory to a channel, so only that channel can read it.
+    pub fn scoped_to_channel(mut self, channel_id: crate::ChannelId) -> Self {
+        self.channel_id = Some(channel_id);
+        self.scope = MemoryScope::Channel;
+        self
+    }
+
     /// Identity memories have maximum importance and don't decay.
     pub const fn identity_importance() -> f32 {
         1.0
@@ -139,6 +151,78 @@ impl std::fmt::Display for MemoryType {
     }
 }
 
+/// Where a memory can be read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryScope {
+    /// Visible to every channel and to the bulletin.
+    #[default]
+    Global,
+    /// Visible only to the channel it was saved from.
+    Channel,
+}
+
+impl MemoryScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Channel => "channel",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "global" => Some(Self::Global),
+            "channel" => Some(Self::Channel),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for MemoryScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Which memories a search may return, by scope.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum MemoryVisibility {
+    /// Every memory. Used by the cortex chat and the API.
+    #[default]
+    All,
+    /// Global memories only. Used for the bulletin, which every channel sees.
+    Global,
+    /// Global memories plus those scoped to this channel.
+    GlobalAndChannel(String),
+    /// Only memories scoped to this channel.
+    Channel(String),
+}
+
+impl MemoryVisibility {
+    /// What a channel with the given scope policy may read.
+    pub fn for_channel(channel_id: &str, policy: MemoryScope) -> Self {
+        match policy {
+            MemoryScope::Global => Self::GlobalAndChannel(channel_id.to_string()),
+            MemoryScope::Channel => Self::Channel(channel_id.to_string()),
+        }
+    }
+
+    pub fn allows(&self, memory: &Memory) -> bool {
+        let in_channel = |channel_id: &str| {
+            memory.scope == MemoryScope::Channel && memory.channel_id.as_deref() == Some(channel_id)
+        };
+        match self {
+            Self::All => true,
+            Self::Global => memory.scope == MemoryScope::Global,
+            Self::GlobalAndChannel(channel_id) => {
+                memory.scope == MemoryScope::Global || in_channel(channel_id)
+            }
+            Self::Channel(channel_id) => in_channel(channel_id),
+        }
+    }
+}
+
 /// Association between memories.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Association {
@@ -211,6 +295,39 @@ pub struct MemorySearchResult {
     pub memory: Memory,
     pub score: f32,
     pub rank: usize,
+    /// Per-source score breakdown. Only set for hybrid searches with
+    /// `SearchConfig::explain` enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<ScoreExplanation>,
+}
+
+/// Why a hybrid search result scored the way it did.
+///
+/// The final score is the sum of each source's RRF contribution. A source is
+/// `None` when the memory didn't appear in that source's result list.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ScoreExplanation {
+    /// Vector similarity search. `score` is cosine similarity.
+    pub vector: Option<SourceScore>,
+    /// Full-text search. `score` is the BM25 relevance.
+    pub fts: Option<SourceScore>,
+    /// Graph traversal. `score` is importance weighted by association strength.
+    pub graph: Option<SourceScore>,
+    /// RRF k parameter used for fusion.
+    pub rrf_k: f64,
+    /// Minimum fused score required to be returned.
+    pub min_score: f32,
+}
+
+/// One source's contribution to a fused hybrid search score.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SourceScore {
+    /// 1-based position in the source's result list.
+    pub rank: usize,
+    /// Raw score reported by the source.
+    pub score: f64,
+    /// Amount added to the fused score, `1 / (k + rank)`.
+    pub contribution: f64,
 }
 
 /// Input for memory creation.
@@ -1,8 +1,13 @@
-//! Messaging adapters (Discord, Slack, Telegram, Twitch, Signal, Email, Webhook, WebChat).
+//! Messaging adapters (Discord, Slack, Telegram, Twitch, Signal, Email, GitHub, Matrix, IRC, Webhook, WebChat).
 
 pub mod discord;
 pub mod email;
+pub mod github;
+pub mod irc;
 pub mod manager;
+pub mod matrix;
+pub mod moderation;
+pub mod outbox;
 pub mod signal;
 pub mod slack;
 pub mod target;
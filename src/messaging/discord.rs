@@ -1,20 +1,26 @@
 //! Discord messaging adapter using serenity.
 
-use crate::config::DiscordPermissions;
+mod voice;
+
+use crate::config::{DiscordPermissions, DiscordVoiceConfig};
 use crate::messaging::apply_runtime_adapter_to_conversation_id;
 use crate::messaging::traits::{HistoryMessage, InboundStream, Messaging};
+use crate::skills::{SlashArgType, SlashCommand};
 use crate::{InboundMessage, MessageContent, OutboundResponse, StatusUpdate};
 
 use anyhow::Context as _;
 use arc_swap::ArcSwap;
 use async_trait::async_trait;
 use serenity::all::{
-    ButtonStyle, ChannelId, ChannelType, Context, CreateActionRow, CreateAttachment, CreateButton,
-    CreateEmbed, CreateEmbedFooter, CreateInteractionResponse, CreateInteractionResponseMessage,
-    CreateMessage, CreatePoll, CreatePollAnswer, CreateSelectMenu, CreateSelectMenuKind,
-    CreateSelectMenuOption, CreateThread, EditMessage, EventHandler, GatewayIntents, GetMessages,
-    Http, Interaction, Message, MessageId, ReactionType, Ready, ShardManager, User, UserId,
+    ButtonStyle, ChannelId, ChannelType, Command, CommandDataOptionValue, CommandInteraction,
+    CommandOptionType, Context, CreateActionRow, CreateAttachment, CreateButton, CreateCommand,
+    CreateCommandOption, CreateEmbed, CreateEmbedFooter, CreateInteractionResponse,
+    CreateInteractionResponseMessage, CreateMessage, CreatePoll, CreatePollAnswer,
+    CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption, CreateThread, EditMessage,
+    EventHandler, GatewayIntents, GetMessages, GuildId, Http, Interaction, Message, MessageId,
+    ReactionType, Ready, ShardManager, User, UserId,
 };
+use songbird::SerenityInit as _;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{RwLock, mpsc};
@@ -31,6 +37,11 @@ pub struct DiscordAdapter {
     /// Typing handles per message. Typing stops when the handle is dropped.
     typing_tasks: Arc<RwLock<HashMap<String, serenity::http::Typing>>>,
     shard_manager: Arc<RwLock<Option<Arc<ShardManager>>>>,
+    /// Skill slash commands to register on connect. `None` leaves the
+    /// bot's existing registrations alone.
+    slash_commands: Option<Arc<Vec<SlashCommand>>>,
+    /// Voice channel support, when `[messaging.discord.voice]` is enabled.
+    voice: Option<Arc<voice::DiscordVoice>>,
 }
 
 impl DiscordAdapter {
@@ -48,9 +59,28 @@ impl DiscordAdapter {
             active_messages: Arc::new(RwLock::new(HashMap::new())),
             typing_tasks: Arc::new(RwLock::new(HashMap::new())),
             shard_manager: Arc::new(RwLock::new(None)),
+            slash_commands: None,
+            voice: None,
         }
     }
 
+    /// Enable voice channels with these settings. No-op unless
+    /// `config.enabled`.
+    pub fn with_voice(mut self, config: DiscordVoiceConfig) -> Self {
+        self.voice = config
+            .enabled
+            .then(|| Arc::new(voice::DiscordVoice::new(config)));
+        self
+    }
+
+    /// Register these skill slash commands when the bot connects, replacing
+    /// any it registered before. Scoped to the allowed guilds when a guild
+    /// filter is set, global otherwise.
+    pub fn with_slash_commands(mut self, commands: Vec<SlashCommand>) -> Self {
+        self.slash_commands = Some(Arc::new(commands));
+        self
+    }
+
     async fn get_http(&self) -> anyhow::Result<Arc<Http>> {
         self.http
             .read()
@@ -96,6 +126,65 @@ impl DiscordAdapter {
             })
             .map(MessageId::new)
     }
+
+    /// Send text to the message's channel, splitting at Discord's length
+    /// limit and replying to the source message with the first chunk.
+    /// Returns the ID of the first chunk.
+    async fn send_text(
+        &self,
+        message: &InboundMessage,
+        text: &str,
+    ) -> anyhow::Result<Option<MessageId>> {
+        let http = self.get_http().await?;
+        let channel_id = self.extract_channel_id(message)?;
+        self.stop_typing(message).await;
+        let reply_to = Self::extract_reply_message_id(message);
+
+        let mut first_id = None;
+        for (index, chunk) in split_message(text, 2000).into_iter().enumerate() {
+            let mut builder = CreateMessage::new().content(chunk);
+            if index == 0
+                && let Some(reply_message_id) = reply_to
+            {
+                builder = builder.reference_message((channel_id, reply_message_id));
+            }
+            let sent = channel_id
+                .send_message(&*http, builder)
+                .await
+                .context("failed to send discord message")?;
+            first_id.get_or_insert(sent.id);
+        }
+        Ok(first_id)
+    }
+
+    /// Speak a reply to a message that came from a voice channel in that
+    /// channel's call. Runs in the background so text delivery isn't held up
+    /// by speech synthesis.
+    fn speak_reply(&self, message: &InboundMessage, text: &str) {
+        let Some(voice) = self.voice.clone() else {
+            return;
+        };
+        let Some(guild_id) = message
+            .metadata
+            .get(voice::VOICE_GUILD_KEY)
+            .and_then(|value| value.as_u64())
+        else {
+            return;
+        };
+        let text = text.to_string();
+        tokio::spawn(async move {
+            if let Err(error) = voice.speak(GuildId::new(guild_id), &text).await {
+                tracing::warn!(%error, %guild_id, "failed to speak discord voice reply");
+            }
+        });
+    }
+
+    fn parse_message_id(message_id: &str) -> anyhow::Result<MessageId> {
+        message_id
+            .parse::<u64>()
+            .map(MessageId::new)
+            .with_context(|| format!("invalid discord message id '{message_id}'"))
+    }
 }
 
 impl Messaging for DiscordAdapter {
@@ -106,23 +195,32 @@ impl Messaging for DiscordAdapter {
     async fn start(&self) -> crate::Result<InboundStream> {
         let (inbound_tx, inbound_rx) = mpsc::channel(256);
 
+        if let Some(voice) = &self.voice {
+            voice.set_inbound(inbound_tx.clone()).await;
+        }
+
         let handler = Handler {
             inbound_tx,
             runtime_key: self.runtime_key.clone(),
             permissions: self.permissions.clone(),
             http_slot: self.http.clone(),
             bot_user_id_slot: self.bot_user_id.clone(),
+            slash_commands: self.slash_commands.clone(),
         };
 
-        let intents = GatewayIntents::GUILD_MESSAGES
+        let mut intents = GatewayIntents::GUILD_MESSAGES
             | GatewayIntents::DIRECT_MESSAGES
             | GatewayIntents::MESSAGE_CONTENT
             | GatewayIntents::GUILDS;
+        if self.voice.is_some() {
+            intents |= GatewayIntents::GUILD_VOICE_STATES;
+        }
 
-        let mut client = serenity::Client::builder(&self.token, intents)
-            .event_handler(handler)
-            .await
-            .context("failed to build discord client")?;
+        let mut builder = serenity::Client::builder(&self.token, intents).event_handler(handler);
+        if let Some(voice) = &self.voice {
+            builder = builder.register_songbird_with(voice.songbird());
+        }
+        let mut client = builder.await.context("failed to build discord client")?;
 
         *self.http.write().await = Some(client.http.clone());
         *self.shard_manager.write().await = Some(client.shard_manager.clone());
@@ -147,21 +245,8 @@ impl Messaging for DiscordAdapter {
 
         match response {
             OutboundResponse::Text(text) => {
-                self.stop_typing(message).await;
-                let reply_to = Self::extract_reply_message_id(message);
-
-                for (index, chunk) in split_message(&text, 2000).into_iter().enumerate() {
-                    let mut builder = CreateMessage::new().content(chunk);
-                    if index == 0
-                        && let Some(reply_message_id) = reply_to
-                    {
-                        builder = builder.reference_message((channel_id, reply_message_id));
-                    }
-                    channel_id
-                        .send_message(&*http, builder)
-                        .await
-                        .context("failed to send discord message")?;
-                }
+                self.send_text(message, &text).await?;
+                self.speak_reply(message, &text);
             }
             OutboundResponse::RichMessage {
                 text,
@@ -174,6 +259,7 @@ impl Messaging for DiscordAdapter {
                 let reply_to = Self::extract_reply_message_id(message);
                 let parts =
                     prepare_rich_message_parts(text, &cards, &interactive_elements, poll.as_ref());
+                self.speak_reply(message, &parts.text);
                 if parts.dropped_invalid_poll {
                     tracing::warn!(
                         "dropping invalid discord poll payload while sending rich message"
@@ -344,6 +430,26 @@ impl Messaging for DiscordAdapter {
             OutboundResponse::StreamEnd => {
                 self.active_messages.write().await.remove(&message.id);
             }
+            OutboundResponse::Edit { message_id, text } => {
+                let message_id = Self::parse_message_id(&message_id)?;
+                let display_text = if text.len() > 2000 {
+                    let end = text.floor_char_boundary(1997);
+                    format!("{}...", &text[..end])
+                } else {
+                    text
+                };
+                channel_id
+                    .edit_message(&*http, message_id, EditMessage::new().content(display_text))
+                    .await
+                    .context("failed to edit discord message")?;
+            }
+            OutboundResponse::Delete { message_id } => {
+                let message_id = Self::parse_message_id(&message_id)?;
+                channel_id
+                    .delete_message(&*http, message_id)
+                    .await
+                    .context("failed to delete discord message")?;
+            }
             OutboundResponse::Status(status) => {
                 self.send_status(message, status).await?;
             }
@@ -398,6 +504,23 @@ impl Messaging for DiscordAdapter {
         Ok(())
     }
 
+    async fn respond_tracked(
+        &self,
+        message: &InboundMessage,
+        response: OutboundResponse,
+    ) -> crate::Result<Option<String>> {
+        match response {
+            OutboundResponse::Text(text) => {
+                let message_id = self.send_text(message, &text).await?;
+                Ok(message_id.map(|id| id.get().to_string()))
+            }
+            response => {
+                self.respond(message, response).await?;
+                Ok(None)
+            }
+        }
+    }
+
     async fn broadcast(&self, target: &str, response: OutboundResponse) -> crate::Result<()> {
         let http = self.get_http().await?;
 
@@ -560,6 +683,32 @@ impl Messaging for DiscordAdapter {
         Ok(history)
     }
 
+    fn supports_voice(&self) -> bool {
+        self.voice.is_some()
+    }
+
+    async fn join_voice(
+        &self,
+        conversation_id: &str,
+        voice_channel: &str,
+    ) -> crate::Result<String> {
+        let voice = self
+            .voice
+            .as_ref()
+            .context("voice is not enabled for this Discord bot")?;
+        let http = self.get_http().await?;
+        Ok(voice
+            .join(http, &self.runtime_key, conversation_id, voice_channel)
+            .await?)
+    }
+
+    async fn leave_voice(&self, conversation_id: &str) -> crate::Result<bool> {
+        let Some(voice) = &self.voice else {
+            return Ok(false);
+        };
+        Ok(voice.leave(&self.runtime_key, conversation_id).await?)
+    }
+
     async fn health_check(&self) -> crate::Result<()> {
         let http = self.get_http().await?;
         http.get_current_user()
@@ -588,6 +737,137 @@ struct Handler {
     permissions: Arc<ArcSwap<DiscordPermissions>>,
     http_slot: Arc<RwLock<Option<Arc<Http>>>>,
     bot_user_id_slot: Arc<RwLock<Option<UserId>>>,
+    slash_commands: Option<Arc<Vec<SlashCommand>>>,
+}
+
+impl Handler {
+    /// Replace the bot's slash commands with the skill commands.
+    async fn register_slash_commands(&self, ctx: &Context, commands: &[SlashCommand]) {
+        let builders: Vec<CreateCommand> = commands.iter().map(build_command).collect();
+        let guild_filter = self.permissions.load().guild_filter.clone();
+        match guild_filter {
+            Some(guild_ids) => {
+                for guild_id in guild_ids {
+                    if let Err(error) = GuildId::new(guild_id)
+                        .set_commands(&ctx.http, builders.clone())
+                        .await
+                    {
+                        tracing::warn!(%error, guild_id, "failed to register discord slash commands");
+                    }
+                }
+            }
+            None => {
+                if let Err(error) = Command::set_global_commands(&ctx.http, builders).await {
+                    tracing::warn!(%error, "failed to register discord slash commands");
+                }
+            }
+        }
+        tracing::info!(count = commands.len(), "discord slash commands registered");
+    }
+
+    /// Turn a slash command invocation into an inbound message.
+    async fn handle_command(&self, ctx: &Context, command: CommandInteraction) {
+        let user = &command.user;
+        let permissions = self.permissions.load();
+
+        let allowed = match command.guild_id {
+            None => permissions.dm_allowed_users.contains(&user.id.get()),
+            Some(guild_id) => {
+                permissions
+                    .guild_filter
+                    .as_ref()
+                    .is_none_or(|filter| filter.contains(&guild_id.get()))
+                    && permissions
+                        .channel_filter
+                        .get(&guild_id.get())
+                        .is_none_or(|channels| {
+                            channels.is_empty() || channels.contains(&command.channel_id.get())
+                        })
+            }
+        };
+        drop(permissions);
+
+        let args: serde_json::Map<String, serde_json::Value> = command
+            .data
+            .options
+            .iter()
+            .filter_map(|option| {
+                command_option_value(&option.value).map(|value| (option.name.clone(), value))
+            })
+            .collect();
+        let content = MessageContent::Command {
+            name: command.data.name.clone(),
+            args,
+        };
+
+        // Answer right away so Discord doesn't show "The application did not
+        // respond"; the echo also shows the channel what was asked.
+        let reply = if allowed {
+            CreateInteractionResponseMessage::new().content(format!("`{content}`"))
+        } else {
+            CreateInteractionResponseMessage::new()
+                .content("You can't use this command here.")
+                .ephemeral(true)
+        };
+        if let Err(error) = command
+            .create_response(&ctx.http, CreateInteractionResponse::Message(reply))
+            .await
+        {
+            tracing::warn!(%error, "failed to acknowledge slash command");
+        }
+        if !allowed {
+            return;
+        }
+
+        let base_conversation_id = match command.guild_id {
+            Some(guild_id) => format!("discord:{}:{}", guild_id, command.channel_id),
+            None => format!("discord:dm:{}", user.id),
+        };
+        let conversation_id =
+            apply_runtime_adapter_to_conversation_id(&self.runtime_key, base_conversation_id);
+
+        let display_name = command
+            .member
+            .as_ref()
+            .and_then(|member| member.nick.clone())
+            .or_else(|| user.global_name.clone())
+            .unwrap_or_else(|| user.name.clone());
+        let formatted_author = format!("{} (<@{}>)", display_name, user.id);
+
+        let mut metadata = HashMap::new();
+        metadata.insert("discord_channel_id".into(), command.channel_id.get().into());
+        metadata.insert("discord_user_id".into(), user.id.get().into());
+        metadata.insert("sender_id".into(), user.id.get().into());
+        metadata.insert("sender_display_name".into(), display_name.into());
+        metadata.insert("discord_command".into(), command.data.name.clone().into());
+        // A command is addressed to the bot by definition.
+        metadata.insert("discord_mentioned_bot".into(), true.into());
+        metadata.insert("discord_reply_to_bot".into(), false.into());
+        metadata.insert("discord_mentions_or_replies_to_bot".into(), true.into());
+        if let Some(guild_id) = command.guild_id {
+            metadata.insert("discord_guild_id".into(), guild_id.get().into());
+        }
+
+        let inbound = InboundMessage {
+            id: command.id.to_string(),
+            source: "discord".into(),
+            adapter: Some(self.runtime_key.clone()),
+            conversation_id,
+            sender_id: user.id.to_string(),
+            agent_id: None,
+            content,
+            timestamp: chrono::Utc::now(),
+            metadata,
+            formatted_author: Some(formatted_author),
+        };
+
+        if let Err(error) = self.inbound_tx.send(inbound).await {
+            tracing::warn!(
+                %error,
+                "failed to send inbound slash command from Discord (receiver dropped)"
+            );
+        }
+    }
 }
 
 #[async_trait]
@@ -598,6 +878,10 @@ impl EventHandler for Handler {
         *self.http_slot.write().await = Some(ctx.http.clone());
         *self.bot_user_id_slot.write().await = Some(ready.user.id);
         tracing::info!(guild_count = ready.guilds.len(), "discord guilds available");
+
+        if let Some(commands) = &self.slash_commands {
+            self.register_slash_commands(&ctx, commands).await;
+        }
     }
 
     async fn message(&self, ctx: Context, message: Message) {
@@ -677,7 +961,8 @@ impl EventHandler for Handler {
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
         let component = match interaction {
             Interaction::Component(c) => c,
-            _ => return, // Only handle component interactions
+            Interaction::Command(command) => return self.handle_command(&ctx, command).await,
+            _ => return,
         };
 
         // Acknowledge the interaction immediately to prevent "This interaction failed" in the UI.
@@ -984,6 +1269,47 @@ async fn build_metadata(
     (metadata, formatted_author)
 }
 
+/// Build the Discord registration for a skill slash command.
+fn build_command(command: &SlashCommand) -> CreateCommand {
+    let mut builder = CreateCommand::new(&command.name)
+        .description(command.description.as_deref().unwrap_or(&command.name));
+    for arg in &command.args {
+        let kind = match arg.kind {
+            SlashArgType::String => CommandOptionType::String,
+            SlashArgType::Integer => CommandOptionType::Integer,
+            SlashArgType::Number => CommandOptionType::Number,
+            SlashArgType::Boolean => CommandOptionType::Boolean,
+            SlashArgType::User => CommandOptionType::User,
+            SlashArgType::Channel => CommandOptionType::Channel,
+        };
+        let mut option = CreateCommandOption::new(
+            kind,
+            &arg.name,
+            arg.description.as_deref().unwrap_or(&arg.name),
+        )
+        .required(arg.required);
+        for choice in &arg.choices {
+            option = option.add_string_choice(choice, choice);
+        }
+        builder = builder.add_option(option);
+    }
+    builder
+}
+
+/// Argument value as the skill sees it. Users and channels become mentions
+/// so they can be used in replies as-is.
+fn command_option_value(value: &CommandDataOptionValue) -> Option<serde_json::Value> {
+    Some(match value {
+        CommandDataOptionValue::String(text) => text.clone().into(),
+        CommandDataOptionValue::Integer(number) => (*number).into(),
+        CommandDataOptionValue::Number(number) => (*number).into(),
+        CommandDataOptionValue::Boolean(flag) => (*flag).into(),
+        CommandDataOptionValue::User(user_id) => format!("<@{user_id}>").into(),
+        CommandDataOptionValue::Channel(channel_id) => format!("<#{channel_id}>").into(),
+        _ => return None,
+    })
+}
+
 /// Split a message into chunks that fit within Discord's 2000 char limit.
 /// Tries to split at newlines, then spaces, then hard-cuts.
 fn split_message(text: &str, max_len: usize) -> Vec<String> {
@@ -0,0 +1,582 @@
+//! Discord voice channels: listen, transcribe, and speak short replies.
+//!
+//! Enabled by `[messaging.discord.voice]`. While joined, each participant's
+//! audio is buffered until they pause, then sent into the text conversation
+//! the join came from as a WAV attachment. The channel's usual audio
+//! transcription turns it into text. Replies to those messages are posted
+//! as text and, when short enough, also spoken in the call.
+
+use crate::config::DiscordVoiceConfig;
+use crate::{Attachment, InboundMessage, MessageContent};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use serenity::all::{ChannelId, ChannelType, GuildId, Http, UserId};
+use songbird::driver::DecodeMode;
+use songbird::events::context_data::VoiceTick;
+use songbird::{CoreEvent, Event, EventContext, Songbird};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{RwLock, mpsc};
+
+/// Discord decodes voice to 48 kHz interleaved stereo.
+const SAMPLE_RATE: u32 = 48_000;
+const CHANNELS: usize = 2;
+
+/// Voice ticks arrive every 20 ms.
+const TICK_MS: u64 = 20;
+
+/// Metadata key set on messages spoken in a voice channel, holding the
+/// guild ID whose call a reply should be spoken in.
+pub(super) const VOICE_GUILD_KEY: &str = "discord_voice_guild_id";
+
+/// Voice support for one Discord bot.
+pub(super) struct DiscordVoice {
+    config: DiscordVoiceConfig,
+    songbird: Arc<Songbird>,
+    /// Where transcribable utterances are sent. Set when the adapter starts.
+    inbound_tx: RwLock<Option<mpsc::Sender<InboundMessage>>>,
+    http_client: reqwest::Client,
+}
+
+impl DiscordVoice {
+    pub(super) fn new(config: DiscordVoiceConfig) -> Self {
+        let songbird_config = songbird::Config::default().decode_mode(DecodeMode::Decode);
+        Self {
+            config,
+            songbird: Songbird::serenity_from_config(songbird_config),
+            inbound_tx: RwLock::new(None),
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    /// The voice manager to register with the serenity client.
+    pub(super) fn songbird(&self) -> Arc<Songbird> {
+        self.songbird.clone()
+    }
+
+    pub(super) async fn set_inbound(&self, inbound_tx: mpsc::Sender<InboundMessage>) {
+        *self.inbound_tx.write().await = Some(inbound_tx);
+    }
+
+    /// Join `voice_channel` (ID, `<#ID>` mention, or name) in the server of
+    /// `conversation_id` and start listening.
+    pub(super) async fn join(
+        &self,
+        http: Arc<Http>,
+        runtime_key: &str,
+        conversation_id: &str,
+        voice_channel: &str,
+    ) -> anyhow::Result<String> {
+        let (guild_id, text_channel_id) = parse_conversation(runtime_key, conversation_id)?;
+        let inbound_tx = self
+            .inbound_tx
+            .read()
+            .await
+            .clone()
+            .context("discord not connected")?;
+        let (voice_channel_id, channel_name) =
+            resolve_voice_channel(&http, guild_id, voice_channel).await?;
+
+        let call = self
+            .songbird
+            .join(guild_id, voice_channel_id)
+            .await
+            .map_err(|error| anyhow::anyhow!("failed to join #{channel_name}: {error}"))?;
+
+        let receiver = Receiver {
+            state: Arc::new(ReceiverState {
+                http,
+                runtime_key: runtime_key.to_string(),
+                conversation_id: conversation_id.to_string(),
+                guild_id,
+                text_channel_id,
+                voice_channel_id,
+                inbound_tx,
+                speakers: Mutex::new(HashMap::new()),
+                segmenter: Mutex::new(Segmenter::new(&self.config)),
+                names: RwLock::new(HashMap::new()),
+            }),
+        };
+        {
+            let mut call = call.lock().await;
+            // A rejoin replaces the listener from the previous join.
+            call.remove_all_global_events();
+            call.add_global_event(CoreEvent::SpeakingStateUpdate.into(), receiver.clone());
+            call.add_global_event(CoreEvent::VoiceTick.into(), receiver);
+        }
+
+        tracing::info!(
+            %guild_id,
+            %voice_channel_id,
+            %conversation_id,
+            "joined discord voice channel"
+        );
+        let mut joined = format!("Joined voice channel #{channel_name}.");
+        if self.config.tts_url.is_none() {
+            joined.push_str(" No TTS endpoint is configured, so replies will be text only.");
+        }
+        Ok(joined)
+    }
+
+    /// Leave the voice channel in the server of `conversation_id`.
+    pub(super) async fn leave(
+        &self,
+        runtime_key: &str,
+        conversation_id: &str,
+    ) -> anyhow::Result<bool> {
+        let (guild_id, _) = parse_conversation(runtime_key, conversation_id)?;
+        if self.songbird.get(guild_id).is_none() {
+            return Ok(false);
+        }
+        self.songbird
+            .remove(guild_id)
+            .await
+            .map_err(|error| anyhow::anyhow!("failed to leave voice channel: {error}"))?;
+        tracing::info!(%guild_id, "left discord voice channel");
+        Ok(true)
+    }
+
+    /// Speak `text` in the guild's call, if it's short enough and a TTS
+    /// endpoint is configured.
+    pub(super) async fn speak(&self, guild_id: GuildId, text: &str) -> anyhow::Result<()> {
+        let Some(url) = self.config.tts_url.as_deref() else {
+            return Ok(());
+        };
+        let text = spoken_text(text);
+        if text.is_empty() || text.chars().count() > self.config.max_spoken_chars {
+            return Ok(());
+        }
+        let Some(call) = self.songbird.get(guild_id) else {
+            return Ok(());
+        };
+
+        let audio = self.synthesize(url, &text).await?;
+        call.lock().await.play_input(audio.into());
+        Ok(())
+    }
+
+    /// Render speech through an OpenAI-compatible `/audio/speech` endpoint.
+    async fn synthesize(&self, url: &str, text: &str) -> anyhow::Result<Vec<u8>> {
+        let mut request = self
+            .http_client
+            .post(url)
+            .json(&serde_json::json!({
+                "model": self.config.tts_model,
+                "voice": self.config.tts_voice,
+                "input": text,
+                "response_format": "wav",
+            }))
+            .timeout(std::time::Duration::from_secs(60));
+        if let Some(api_key) = &self.config.tts_api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.send().await.context("TTS request failed")?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            anyhow::bail!("TTS endpoint returned {status}: {body}");
+        }
+        Ok(response
+            .bytes()
+            .await
+            .context("failed to read TTS audio")?
+            .to_vec())
+    }
+}
+
+/// Guild and text channel of a server conversation ID
+/// (`{runtime_key}:{guild_id}:{channel_id}`).
+fn parse_conversation(
+    runtime_key: &str,
+    conversation_id: &str,
+) -> anyhow::Result<(GuildId, ChannelId)> {
+    let rest = conversation_id
+        .strip_prefix(runtime_key)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .with_context(|| format!("'{conversation_id}' is not a conversation on this bot"))?;
+    let (guild, channel) = rest
+        .split_once(':')
+        .context("voice channels can only be joined from a server channel")?;
+    match (guild.parse::<u64>(), channel.parse::<u64>()) {
+        (Ok(guild), Ok(channel)) if guild != 0 && channel != 0 => {
+            Ok((GuildId::new(guild), ChannelId::new(channel)))
+        }
+        _ => anyhow::bail!("voice channels can only be joined from a server channel"),
+    }
+}
+
+/// Find a voice channel in the guild by ID, mention, or name.
+async fn resolve_voice_channel(
+    http: &Http,
+    guild_id: GuildId,
+    query: &str,
+) -> anyhow::Result<(ChannelId, String)> {
+    let channels = guild_id
+        .channels(http)
+        .await
+        .context("failed to list server channels")?;
+    let mut voice_channels: Vec<_> = channels
+        .values()
+        .filter(|channel| matches!(channel.kind, ChannelType::Voice | ChannelType::Stage))
+        .collect();
+    voice_channels.sort_by_key(|channel| channel.position);
+
+    let query = query.trim();
+    let query = query
+        .strip_prefix("<#")
+        .and_then(|id| id.strip_suffix('>'))
+        .unwrap_or(query)
+        .trim_start_matches('#');
+    let found = voice_channels.iter().find(|channel| {
+        channel.id.get().to_string() == query || channel.name.eq_ignore_ascii_case(query)
+    });
+
+    match found {
+        Some(channel) => Ok((channel.id, channel.name.clone())),
+        None if voice_channels.is_empty() => anyhow::bail!("this server has no voice channels"),
+        None => {
+            let names: Vec<&str> = voice_channels
+                .iter()
+                .map(|channel| channel.name.as_str())
+                .collect();
+            anyhow::bail!(
+                "no voice channel '{query}'; available: {}",
+                names.join(", ")
+            )
+        }
+    }
+}
+
+/// Listens to a call and forwards finished utterances.
+#[derive(Clone)]
+struct Receiver {
+    state: Arc<ReceiverState>,
+}
+
+struct ReceiverState {
+    http: Arc<Http>,
+    runtime_key: String,
+    conversation_id: String,
+    guild_id: GuildId,
+    text_channel_id: ChannelId,
+    voice_channel_id: ChannelId,
+    inbound_tx: mpsc::Sender<InboundMessage>,
+    /// Audio stream SSRC to the user speaking on it.
+    speakers: Mutex<HashMap<u32, UserId>>,
+    segmenter: Mutex<Segmenter>,
+    /// Display names already looked up.
+    names: RwLock<HashMap<UserId, String>>,
+}
+
+#[async_trait]
+impl songbird::EventHandler for Receiver {
+    async fn act(&self, ctx: &EventContext<'_>) -> Option<Event> {
+        match ctx {
+            EventContext::SpeakingStateUpdate(speaking) => {
+                if let Some(user_id) = speaking.user_id {
+                    self.state
+                        .speakers
+                        .lock()
+                        .expect("speaker map poisoned")
+                        .insert(speaking.ssrc, UserId::new(user_id.0));
+                }
+            }
+            EventContext::VoiceTick(tick) => {
+                let finished = self.state.collect(tick);
+                for (ssrc, samples) in finished {
+                    self.state.send_utterance(ssrc, samples).await;
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+impl ReceiverState {
+    /// Feed one tick into the segmenter and return finished utterances.
+    fn collect(&self, tick: &VoiceTick) -> Vec<(u32, Vec<i16>)> {
+        let mut segmenter = self.segmenter.lock().expect("segmenter poisoned");
+        let mut finished = Vec::new();
+        for (ssrc, data) in &tick.speaking {
+            if let Some(decoded) = &data.decoded_voice
+                && let Some(samples) = segmenter.speaking(*ssrc, decoded)
+            {
+                finished.push((*ssrc, samples));
+            }
+        }
+        for ssrc in &tick.silent {
+            if let Some(samples) = segmenter.silent(*ssrc) {
+                finished.push((*ssrc, samples));
+            }
+        }
+        finished
+    }
+
+    async fn send_utterance(&self, ssrc: u32, samples: Vec<i16>) {
+        let user_id = self
+            .speakers
+            .lock()
+            .expect("speaker map poisoned")
+            .get(&ssrc)
+            .copied();
+        let Some(user_id) = user_id else {
+            tracing::debug!(ssrc, "dropping voice from an unknown speaker");
+            return;
+        };
+        let display_name = self.display_name(user_id).await;
+
+        let wav = encode_wav_mono(&samples);
+        use base64::Engine as _;
+        let url = format!(
+            "data:audio/wav;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(&wav)
+        );
+
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            "discord_channel_id".into(),
+            self.text_channel_id.get().into(),
+        );
+        metadata.insert("discord_guild_id".into(), self.guild_id.get().into());
+        metadata.insert(
+            "discord_voice_channel_id".into(),
+            self.voice_channel_id.get().into(),
+        );
+        metadata.insert(VOICE_GUILD_KEY.into(), self.guild_id.get().into());
+        metadata.insert("discord_user_id".into(), user_id.get().into());
+        metadata.insert("sender_id".into(), user_id.get().into());
+        metadata.insert("sender_display_name".into(), display_name.clone().into());
+        metadata.insert(
+            "discord_user_mention".into(),
+            serde_json::Value::String(format!("<@{user_id}>")),
+        );
+        // Speaking in a call the bot joined is addressed to it.
+        metadata.insert("discord_mentioned_bot".into(), true.into());
+        metadata.insert("discord_reply_to_bot".into(), false.into());
+        metadata.insert("discord_mentions_or_replies_to_bot".into(), true.into());
+
+        let inbound = InboundMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            source: "discord".into(),
+            adapter: Some(self.runtime_key.clone()),
+            conversation_id: self.conversation_id.clone(),
+            sender_id: user_id.to_string(),
+            agent_id: None,
+            content: MessageContent::Media {
+                text: None,
+                attachments: vec![Attachment {
+                    filename: format!("voice-{user_id}.wav"),
+                    mime_type: "audio/wav".into(),
+                    url,
+                    size_bytes: Some(wav.len() as u64),
+                    auth_header: None,
+                }],
+            },
+            timestamp: chrono::Utc::now(),
+            metadata,
+            formatted_author: Some(format!("{display_name} (<@{user_id}>)")),
+        };
+
+        if let Err(error) = self.inbound_tx.send(inbound).await {
+            tracing::warn!(
+                %error,
+                "failed to send voice message from Discord (receiver dropped)"
+            );
+        }
+    }
+
+    async fn display_name(&self, user_id: UserId) -> String {
+        if let Some(name) = self.names.read().await.get(&user_id) {
+            return name.clone();
+        }
+        let name = match self.guild_id.member(&*self.http, user_id).await {
+            Ok(member) => member.display_name().to_string(),
+            Err(error) => {
+                tracing::debug!(%error, %user_id, "failed to look up voice speaker");
+                user_id.to_string()
+            }
+        };
+        self.names.write().await.insert(user_id, name.clone());
+        name
+    }
+}
+
+/// Splits each speaker's audio into utterances at pauses.
+struct Segmenter {
+    buffers: HashMap<u32, Utterance>,
+    /// Consecutive silent ticks that end an utterance.
+    silence_ticks: u32,
+    /// Interleaved samples below which an utterance is dropped as noise.
+    min_samples: usize,
+    /// Interleaved samples at which an utterance is cut and sent.
+    max_samples: usize,
+}
+
+#[derive(Default)]
+struct Utterance {
+    samples: Vec<i16>,
+    silent_ticks: u32,
+}
+
+impl Segmenter {
+    fn new(config: &DiscordVoiceConfig) -> Self {
+        let samples_per_ms = (SAMPLE_RATE as usize / 1000) * CHANNELS;
+        Self {
+            buffers: HashMap::new(),
+            silence_ticks: config.silence_ms.div_ceil(TICK_MS).max(1) as u32,
+            min_samples: config.min_utterance_ms as usize * samples_per_ms,
+            max_samples: config.max_utterance_secs as usize * 1000 * samples_per_ms,
+        }
+    }
+
+    /// Add a speaker's audio. Returns an utterance that hit the length cap.
+    fn speaking(&mut self, ssrc: u32, samples: &[i16]) -> Option<Vec<i16>> {
+        let utterance = self.buffers.entry(ssrc).or_default();
+        utterance.samples.extend_from_slice(samples);
+        utterance.silent_ticks = 0;
+        if utterance.samples.len() >= self.max_samples {
+            return Some(std::mem::take(&mut utterance.samples));
+        }
+        None
+    }
+
+    /// Note a silent tick. Returns the utterance once the pause is long enough.
+    fn silent(&mut self, ssrc: u32) -> Option<Vec<i16>> {
+        let utterance = self.buffers.get_mut(&ssrc)?;
+        if utterance.samples.is_empty() {
+            return None;
+        }
+        utterance.silent_ticks += 1;
+        if utterance.silent_ticks < self.silence_ticks {
+            return None;
+        }
+        let samples = std::mem::take(&mut utterance.samples);
+        utterance.silent_ticks = 0;
+        (samples.len() >= self.min_samples).then_some(samples)
+    }
+}
+
+/// Downmix interleaved stereo to mono and wrap it in a 16-bit PCM WAV.
+fn encode_wav_mono(stereo: &[i16]) -> Vec<u8> {
+    let mono: Vec<i16> = stereo
+        .chunks(CHANNELS)
+        .map(|frame| {
+            (frame.iter().map(|&sample| sample as i32).sum::<i32>() / frame.len() as i32) as i16
+        })
+        .collect();
+    let data_len = (mono.len() * 2) as u32;
+
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // byte rate
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in mono {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
+
+/// Strip markdown and mention syntax that TTS would read out literally.
+fn spoken_text(text: &str) -> String {
+    let mut spoken = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        spoken.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        match tail.find('>') {
+            // Drop `<@123>`, `<#123>`, `<:emoji:123>` and the like.
+            Some(end)
+                if tail[1..end].contains(|c: char| c.is_ascii_digit())
+                    && !tail[1..end].contains(char::is_whitespace) =>
+            {
+                rest = &tail[end + 1..];
+            }
+            _ => {
+                spoken.push('<');
+                rest = &tail[1..];
+            }
+        }
+    }
+    spoken.push_str(rest);
+    spoken
+        .chars()
+        .filter(|c| !matches!(c, '*' | '_' | '`' | '#' | '~' | '|'))
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn segmenter_splits_on_pauses_and_drops_noise() {
+        let config = DiscordVoiceConfig {
+            silence_ms: 40,
+            min_utterance_ms: 20,
+            max_utterance_secs: 1,
+            ..DiscordVoiceConfig::default()
+        };
+        let mut segmenter = Segmenter::new(&config);
+        let tick = vec![1i16; 1920]; // 20 ms of stereo
+
+        assert!(segmenter.speaking(7, &tick).is_none());
+        assert!(segmenter.silent(7).is_none());
+        assert_eq!(segmenter.silent(7).map(|samples| samples.len()), Some(1920));
+        // Nothing buffered, so further silence yields nothing.
+        assert!(segmenter.silent(7).is_none());
+
+        // A 1 s utterance is cut at the cap without waiting for a pause.
+        let cut = (0..50).find_map(|_| segmenter.speaking(7, &tick));
+        assert_eq!(cut.map(|samples| samples.len()), Some(96_000));
+
+        let mut short = Segmenter::new(&DiscordVoiceConfig {
+            min_utterance_ms: 100,
+            silence_ms: 20,
+            ..DiscordVoiceConfig::default()
+        });
+        short.speaking(1, &tick);
+        assert!(short.silent(1).is_none());
+    }
+
+    #[test]
+    fn wav_is_mono_pcm() {
+        let wav = encode_wav_mono(&[100, 300, -50, -150]);
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(u16::from_le_bytes([wav[22], wav[23]]), 1);
+        assert_eq!(u32::from_le_bytes([wav[40], wav[41], wav[42], wav[43]]), 4);
+        assert_eq!(i16::from_le_bytes([wav[44], wav[45]]), 200);
+        assert_eq!(i16::from_le_bytes([wav[46], wav[47]]), -100);
+    }
+
+    #[test]
+    fn spoken_text_drops_markup() {
+        assert_eq!(
+            spoken_text("**Done**, <@123> — see `#ops` <:tada:456>"),
+            "Done, — see ops"
+        );
+        assert_eq!(spoken_text("1 < 2 and 3 > 2"), "1 < 2 and 3 > 2");
+    }
+
+    #[test]
+    fn conversation_must_be_a_server_channel() {
+        let (guild, channel) = parse_conversation("discord:ops", "discord:ops:11:22").unwrap();
+        assert_eq!((guild.get(), channel.get()), (11, 22));
+        assert!(parse_conversation("discord", "discord:dm:33").is_err());
+        assert!(parse_conversation("discord", "discord:ops:11:22").is_err());
+    }
+}
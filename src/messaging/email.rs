@@ -90,6 +90,7 @@ struct EmailPollConfig {
     poll_interval: Duration,
     allowed_senders: Vec<String>,
     max_body_bytes: usize,
+    max_attachment_bytes: usize,
     runtime_key: String,
 }
 
@@ -257,6 +258,7 @@ impl EmailAdapter {
             poll_interval: self.poll_interval,
             allowed_senders: self.allowed_senders.clone(),
             max_body_bytes: self.max_body_bytes,
+            max_attachment_bytes: self.max_attachment_bytes,
             runtime_key: self.runtime_key.clone(),
         }
     }
@@ -477,8 +479,11 @@ impl Messaging for EmailAdapter {
                 )
                 .await?;
             }
+            // Sent email can't be edited or recalled.
             OutboundResponse::Reaction(_)
             | OutboundResponse::RemoveReaction(_)
+            | OutboundResponse::Edit { .. }
+            | OutboundResponse::Delete { .. }
             | OutboundResponse::Status(_) => {}
             OutboundResponse::Ephemeral { text, .. } => {
                 self.send_email(
@@ -562,6 +567,8 @@ impl Messaging for EmailAdapter {
             }
             OutboundResponse::Reaction(_)
             | OutboundResponse::RemoveReaction(_)
+            | OutboundResponse::Edit { .. }
+            | OutboundResponse::Delete { .. }
             | OutboundResponse::Status(_)
             | OutboundResponse::StreamStart
             | OutboundResponse::StreamChunk(_)
@@ -942,6 +949,14 @@ fn parse_inbound_email(
         body_text.push_str("\n\nAttachments: ");
         body_text.push_str(&attachment_names.join(", "));
     }
+    let oversized_attachments = collect_oversized_attachments(&parsed, config.max_attachment_bytes);
+    if !oversized_attachments.is_empty() {
+        body_text.push_str(&format!(
+            "\n\nAttachments over the {} byte limit were ignored: {}",
+            config.max_attachment_bytes,
+            oversized_attachments.join(", ")
+        ));
+    }
 
     let timestamp = headers
         .get_first_value("Date")
@@ -1210,6 +1225,7 @@ pub fn search_mailbox(
         poll_interval: Duration::from_secs(config.poll_interval_secs.max(5)),
         allowed_senders: config.allowed_senders.clone(),
         max_body_bytes: config.max_body_bytes.max(1024),
+        max_attachment_bytes: config.max_attachment_bytes.max(1024),
         runtime_key: "email".to_string(),
     })?;
 
@@ -1513,7 +1529,7 @@ fn extract_text_and_attachments(
     let mut body_text = if !plain_text_parts.is_empty() {
         plain_text_parts.join("\n\n")
     } else if !html_parts.is_empty() {
-        html_to_text(&html_parts.join("\n\n"))
+        html_to_markdown(&html_parts.join("\n\n"))
     } else {
         parsed.get_body().unwrap_or_default()
     };
@@ -1536,6 +1552,37 @@ fn extract_text_and_attachments(
     (body_text, attachment_names)
 }
 
+/// Names of attachments whose decoded size exceeds `max_attachment_bytes`.
+fn collect_oversized_attachments(
+    part: &mailparse::ParsedMail<'_>,
+    max_attachment_bytes: usize,
+) -> Vec<String> {
+    let mut oversized = Vec::new();
+    if part.subparts.is_empty() {
+        let disposition = part.get_content_disposition();
+        let filename = disposition
+            .params
+            .get("filename")
+            .cloned()
+            .or_else(|| part.ctype.params.get("name").cloned());
+        if let Some(filename) = filename
+            && part
+                .get_body_raw()
+                .is_ok_and(|body| body.len() > max_attachment_bytes)
+        {
+            oversized.push(filename);
+        }
+        return oversized;
+    }
+
+    for subpart in &part.subparts {
+        oversized.extend(collect_oversized_attachments(subpart, max_attachment_bytes));
+    }
+    oversized.sort();
+    oversized.dedup();
+    oversized
+}
+
 fn collect_parts(
     part: &mailparse::ParsedMail<'_>,
     plain_text_parts: &mut Vec<String>,
@@ -1581,17 +1628,171 @@ fn collect_parts(
     }
 }
 
-fn html_to_text(html: &str) -> String {
-    let without_tags = html_tag_regex().replace_all(html, " ");
-    let decoded = without_tags
+/// Convert an HTML email body into lightweight markdown for the agent.
+///
+/// Only the structure that survives well in a chat transcript is preserved:
+/// headings, paragraphs, line breaks, list items, links, emphasis, inline code,
+/// preformatted blocks, and quotes. Everything else is stripped.
+fn html_to_markdown(html: &str) -> String {
+    static DROPPED_BLOCKS: OnceLock<Regex> = OnceLock::new();
+    static HEADINGS: OnceLock<Regex> = OnceLock::new();
+    static LINKS: OnceLock<Regex> = OnceLock::new();
+    static PRE_BLOCKS: OnceLock<Regex> = OnceLock::new();
+    static STRONG: OnceLock<Regex> = OnceLock::new();
+    static EMPHASIS: OnceLock<Regex> = OnceLock::new();
+    static INLINE_CODE: OnceLock<Regex> = OnceLock::new();
+    static LINE_BREAKS: OnceLock<Regex> = OnceLock::new();
+    static LIST_ITEMS: OnceLock<Regex> = OnceLock::new();
+    static BLOCK_BOUNDARIES: OnceLock<Regex> = OnceLock::new();
+    static BLOCKQUOTES: OnceLock<Regex> = OnceLock::new();
+
+    let dropped_blocks = DROPPED_BLOCKS.get_or_init(|| {
+        Regex::new(r"(?is)<(script|style|head)\b[^>]*>.*?</(script|style|head)\s*>")
+            .expect("valid dropped block regex")
+    });
+    let headings = HEADINGS.get_or_init(|| {
+        Regex::new(r"(?is)<h([1-6])\b[^>]*>(.*?)</h[1-6]\s*>").expect("valid heading regex")
+    });
+    let links = LINKS.get_or_init(|| {
+        Regex::new(r#"(?is)<a\b[^>]*?href\s*=\s*["']([^"']+)["'][^>]*>(.*?)</a\s*>"#)
+            .expect("valid link regex")
+    });
+    let pre_blocks = PRE_BLOCKS
+        .get_or_init(|| Regex::new(r"(?is)<pre\b[^>]*>(.*?)</pre\s*>").expect("valid pre regex"));
+    let strong = STRONG.get_or_init(|| {
+        Regex::new(r"(?is)<(?:strong|b)\b[^>]*>(.*?)</(?:strong|b)\s*>")
+            .expect("valid strong regex")
+    });
+    let emphasis = EMPHASIS.get_or_init(|| {
+        Regex::new(r"(?is)<(?:em|i)\b[^>]*>(.*?)</(?:em|i)\s*>").expect("valid emphasis regex")
+    });
+    let inline_code = INLINE_CODE.get_or_init(|| {
+        Regex::new(r"(?is)<code\b[^>]*>(.*?)</code\s*>").expect("valid code regex")
+    });
+    let line_breaks =
+        LINE_BREAKS.get_or_init(|| Regex::new(r"(?i)<br\s*/?>").expect("valid br regex"));
+    let list_items =
+        LIST_ITEMS.get_or_init(|| Regex::new(r"(?i)<li\b[^>]*>").expect("valid li regex"));
+    let block_boundaries = BLOCK_BOUNDARIES.get_or_init(|| {
+        Regex::new(r"(?i)</?(?:p|div|ul|ol|table|tr|section|article|header|footer)\b[^>]*>")
+            .expect("valid block boundary regex")
+    });
+    let blockquotes = BLOCKQUOTES.get_or_init(|| {
+        Regex::new(r"(?is)<blockquote\b[^>]*>(.*?)</blockquote\s*>")
+            .expect("valid blockquote regex")
+    });
+
+    let stripped = dropped_blocks.replace_all(html, "");
+
+    // Source formatting whitespace is not meaningful in HTML outside `<pre>`;
+    // structure comes from tags, so fold it before tags become newlines.
+    let mut markdown = String::with_capacity(stripped.len());
+    let mut cursor = 0;
+    for captures in pre_blocks.captures_iter(&stripped) {
+        let whole = captures.get(0).expect("capture group 0 always exists");
+        markdown.push_str(&fold_whitespace(&stripped[cursor..whole.start()]));
+        markdown.push_str("\n\n```\n");
+        markdown.push_str(strip_html_tags(&captures[1]).trim_matches('\n'));
+        markdown.push_str("\n```\n\n");
+        cursor = whole.end();
+    }
+    markdown.push_str(&fold_whitespace(&stripped[cursor..]));
+
+    markdown = headings
+        .replace_all(&markdown, |captures: &regex::Captures<'_>| {
+            let level = captures[1].parse::<usize>().unwrap_or(1);
+            format!(
+                "\n\n{} {}\n\n",
+                "#".repeat(level),
+                strip_html_tags(&captures[2]).trim()
+            )
+        })
+        .into_owned();
+    markdown = links
+        .replace_all(&markdown, |captures: &regex::Captures<'_>| {
+            let href = captures[1].trim();
+            let text = strip_html_tags(&captures[2]);
+            let text = text.trim();
+            if text.is_empty() || text == href {
+                href.to_string()
+            } else {
+                format!("[{text}]({href})")
+            }
+        })
+        .into_owned();
+    markdown = strong.replace_all(&markdown, "**$1**").into_owned();
+    markdown = emphasis.replace_all(&markdown, "_${1}_").into_owned();
+    markdown = inline_code.replace_all(&markdown, "`$1`").into_owned();
+    markdown = blockquotes
+        .replace_all(&markdown, |captures: &regex::Captures<'_>| {
+            let inner = strip_html_tags(&line_breaks.replace_all(&captures[1], "\n"));
+            let quoted = inner
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(|line| format!("> {line}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            format!("\n\n{quoted}\n\n")
+        })
+        .into_owned();
+    markdown = line_breaks.replace_all(&markdown, "\n").into_owned();
+    markdown = list_items.replace_all(&markdown, "\n- ").into_owned();
+    markdown = block_boundaries.replace_all(&markdown, "\n\n").into_owned();
+    markdown = decode_html_entities(&strip_html_tags(&markdown));
+
+    let mut output = String::with_capacity(markdown.len());
+    let mut blank_run = 0;
+    let mut in_code_block = false;
+    for line in markdown.lines() {
+        if line.trim() == "```" {
+            in_code_block = !in_code_block;
+        } else if in_code_block {
+            output.push_str(line.trim_end());
+            output.push('\n');
+            continue;
+        }
+
+        let line = line.trim();
+        if line.is_empty() {
+            blank_run += 1;
+            if blank_run > 1 || output.is_empty() {
+                continue;
+            }
+        } else {
+            blank_run = 0;
+        }
+        output.push_str(line);
+        output.push('\n');
+    }
+
+    output.trim().to_string()
+}
+
+fn fold_whitespace(value: &str) -> String {
+    let mut folded = value.split_whitespace().collect::<Vec<_>>().join(" ");
+    if value.starts_with(char::is_whitespace) && !folded.is_empty() {
+        folded.insert(0, ' ');
+    }
+    if value.ends_with(char::is_whitespace) && !folded.is_empty() {
+        folded.push(' ');
+    }
+    folded
+}
+
+fn strip_html_tags(html: &str) -> String {
+    html_tag_regex().replace_all(html, "").into_owned()
+}
+
+fn decode_html_entities(value: &str) -> String {
+    value
         .replace("&nbsp;", " ")
-        .replace("&amp;", "&")
         .replace("&lt;", "<")
         .replace("&gt;", ">")
         .replace("&quot;", "\"")
-        .replace("&#39;", "'");
-
-    decoded.split_whitespace().collect::<Vec<_>>().join(" ")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
 }
 
 fn html_tag_regex() -> &'static Regex {
@@ -1756,8 +1957,9 @@ struct EmailReplyContext {
 mod tests {
     use super::{
         EmailSearchHit, EmailSearchQuery, build_imap_search_criterion, derive_thread_key,
-        extract_message_ids, is_local_mail_host, normalize_email_target, normalize_reply_subject,
-        normalize_search_folders, parse_primary_mailbox, sort_and_limit_search_hits,
+        extract_message_ids, html_to_markdown, is_local_mail_host, normalize_email_target,
+        normalize_reply_subject, normalize_search_folders, parse_primary_mailbox,
+        sort_and_limit_search_hits,
     };
 
     #[test]
@@ -1862,6 +2064,30 @@ mod tests {
         assert!(criterion.contains("TEXT \"release \\\\\\\"candidate\\\\\\\"\""));
     }
 
+    #[test]
+    fn html_to_markdown_preserves_structure() {
+        let html = r#"<html><head><style>p { color: red; }</style></head><body>
+            <h2>Status   update</h2>
+            <p>Deploy is <b>done</b>, see <a href="https://example.com/?a=1&amp;b=2">the log</a>.<br>Thanks &amp; bye</p>
+            <ul><li>first</li><li>second</li></ul>
+            </body></html>"#;
+
+        assert_eq!(
+            html_to_markdown(html),
+            "## Status update\n\nDeploy is **done**, see [the log](https://example.com/?a=1&b=2).\nThanks & bye\n\n- first\n- second"
+        );
+    }
+
+    #[test]
+    fn html_to_markdown_keeps_preformatted_whitespace() {
+        let html = "<p>Run:</p><pre>if a &lt; b {\n    go();\n}</pre>";
+
+        assert_eq!(
+            html_to_markdown(html),
+            "Run:\n\n```\nif a < b {\n    go();\n}\n```"
+        );
+    }
+
     #[test]
     fn normalize_search_folders_falls_back_to_inbox() {
         let folders = normalize_search_folders(&[], &[]);
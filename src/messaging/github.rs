@@ -0,0 +1,931 @@
+//! GitHub messaging adapter: issue and pull request threads as channels.
+//!
+//! Polls the REST API for new issue/PR conversation comments on an allowlist
+//! of repositories. Each issue or PR becomes one conversation
+//! (`github:owner/repo#123`); replies are posted back as comments. For pull
+//! requests, a one-line summary of the diff and CI state is prepended to each
+//! inbound message so the agent always sees the current picture.
+
+use crate::config::GithubConfig;
+use crate::error::DeliveryError;
+use crate::messaging::traits::{HistoryMessage, InboundStream, Messaging};
+use crate::{InboundMessage, MessageContent, OutboundResponse};
+
+use anyhow::Context as _;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{RwLock, mpsc, watch};
+use tokio::task::JoinHandle;
+
+/// HTTP connect timeout for the reqwest client.
+const HTTP_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Per-request timeout for API calls.
+const HTTP_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Maximum backoff between failed poll cycles.
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(300);
+
+/// GitHub rejects comment bodies longer than this many characters.
+const MAX_COMMENT_LENGTH: usize = 65_536;
+
+/// Largest text file posted inline as a fenced code block.
+const MAX_INLINE_FILE_BYTES: usize = 60_000;
+
+// ── GitHub API JSON shapes ──────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct ApiUser {
+    login: String,
+    #[serde(rename = "type", default)]
+    user_type: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiComment {
+    id: u64,
+    #[serde(default)]
+    body: Option<String>,
+    user: Option<ApiUser>,
+    created_at: DateTime<Utc>,
+    html_url: String,
+    issue_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiIssue {
+    title: String,
+    state: String,
+    #[serde(default)]
+    body: Option<String>,
+    user: Option<ApiUser>,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiPullRequest {
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    merged: bool,
+    additions: u64,
+    deletions: u64,
+    changed_files: u64,
+    head: ApiBranchRef,
+    base: ApiBranchRef,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiBranchRef {
+    #[serde(rename = "ref")]
+    name: String,
+    sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCheckRuns {
+    #[serde(default)]
+    check_runs: Vec<ApiCheckRun>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCheckRun {
+    name: String,
+    status: String,
+    conclusion: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiCreatedComment {
+    id: u64,
+}
+
+// ── Adapter ─────────────────────────────────────────────────────
+
+/// Poll cursor for one repository.
+#[derive(Debug, Clone)]
+struct RepoCursor {
+    /// Passed as `since`; comments updated at or after this are listed.
+    since: DateTime<Utc>,
+    /// Highest comment ID already delivered. Comment IDs are increasing, so
+    /// this filters edits of old comments out of the `since` window.
+    last_comment_id: u64,
+}
+
+/// Settings shared with the poll task.
+#[derive(Clone)]
+struct PollContext {
+    runtime_key: String,
+    api: ApiClient,
+    repos: Vec<String>,
+    poll_interval: Duration,
+    bot_login: String,
+}
+
+/// GitHub adapter state.
+pub struct GithubAdapter {
+    runtime_key: String,
+    api: ApiClient,
+    repos: Vec<String>,
+    poll_interval: Duration,
+    bot_login: Arc<RwLock<Option<String>>>,
+    shutdown_tx: Arc<RwLock<Option<watch::Sender<bool>>>>,
+    poll_task: Arc<RwLock<Option<JoinHandle<()>>>>,
+}
+
+impl GithubAdapter {
+    pub fn from_config(config: &GithubConfig) -> crate::Result<Self> {
+        Ok(Self {
+            runtime_key: "github".into(),
+            api: ApiClient::new(&config.api_url, &config.token)?,
+            repos: config.repos.clone(),
+            poll_interval: Duration::from_secs(config.poll_interval_secs.max(10)),
+            bot_login: Arc::new(RwLock::new(None)),
+            shutdown_tx: Arc::new(RwLock::new(None)),
+            poll_task: Arc::new(RwLock::new(None)),
+        })
+    }
+
+    /// Resolve `(repo, number)` from an inbound message.
+    fn thread_of(message: &InboundMessage) -> anyhow::Result<(String, u64)> {
+        let repo = message
+            .metadata
+            .get("github_repo")
+            .and_then(|value| value.as_str());
+        let number = message
+            .metadata
+            .get("github_issue_number")
+            .and_then(|value| value.as_u64());
+        if let (Some(repo), Some(number)) = (repo, number) {
+            return Ok((repo.to_string(), number));
+        }
+
+        let target = message
+            .conversation_id
+            .strip_prefix("github:")
+            .unwrap_or(&message.conversation_id);
+        parse_thread_target(target)
+            .with_context(|| format!("no github thread for '{}'", message.conversation_id))
+    }
+
+    async fn post_comment(&self, repo: &str, number: u64, body: &str) -> anyhow::Result<u64> {
+        let created: ApiCreatedComment = self
+            .api
+            .post(
+                &format!("/repos/{repo}/issues/{number}/comments"),
+                &serde_json::json!({ "body": truncate_comment(body) }),
+            )
+            .await?;
+        Ok(created.id)
+    }
+}
+
+impl Messaging for GithubAdapter {
+    fn name(&self) -> &str {
+        &self.runtime_key
+    }
+
+    async fn start(&self) -> crate::Result<InboundStream> {
+        if self.poll_task.read().await.is_some() {
+            return Err(anyhow::anyhow!("github adapter already started").into());
+        }
+
+        let me: ApiUser = self
+            .api
+            .get("/user")
+            .await
+            .context("failed to authenticate with github")?;
+        *self.bot_login.write().await = Some(me.login.clone());
+
+        let (inbound_tx, inbound_rx) = mpsc::channel(256);
+        let (shutdown_tx, mut shutdown_rx) = watch::channel(false);
+        *self.shutdown_tx.write().await = Some(shutdown_tx);
+
+        tracing::info!(login = %me.login, repos = ?self.repos, "github connected");
+
+        let context = PollContext {
+            runtime_key: self.runtime_key.clone(),
+            api: self.api.clone(),
+            repos: self.repos.clone(),
+            poll_interval: self.poll_interval,
+            bot_login: me.login,
+        };
+
+        let poll_task = tokio::spawn(async move {
+            // Start from now: comments written while the adapter was down are
+            // not replayed.
+            let started_at = Utc::now();
+            let mut cursors: HashMap<String, RepoCursor> = context
+                .repos
+                .iter()
+                .map(|repo| {
+                    (
+                        repo.clone(),
+                        RepoCursor {
+                            since: started_at,
+                            last_comment_id: 0,
+                        },
+                    )
+                })
+                .collect();
+            let mut retry_backoff = Duration::from_secs(5);
+
+            loop {
+                if *shutdown_rx.borrow() {
+                    break;
+                }
+
+                let mut had_error = false;
+                for repo in &context.repos {
+                    let Some(cursor) = cursors.get_mut(repo) else {
+                        continue;
+                    };
+                    match poll_repo_once(&context, repo, cursor, started_at).await {
+                        Ok(messages) => {
+                            for message in messages {
+                                if inbound_tx.send(message).await.is_err() {
+                                    tracing::warn!(
+                                        "github inbound channel closed, stopping adapter loop"
+                                    );
+                                    return;
+                                }
+                            }
+                        }
+                        Err(error) => {
+                            had_error = true;
+                            tracing::warn!(%repo, %error, "github poll failed");
+                        }
+                    }
+                }
+
+                let sleep_duration = if had_error {
+                    let current = retry_backoff;
+                    retry_backoff = (retry_backoff * 2).min(MAX_RETRY_BACKOFF);
+                    current.max(context.poll_interval)
+                } else {
+                    retry_backoff = Duration::from_secs(5);
+                    context.poll_interval
+                };
+
+                tokio::select! {
+                    _ = shutdown_rx.changed() => {
+                        if *shutdown_rx.borrow() {
+                            break;
+                        }
+                    }
+                    _ = tokio::time::sleep(sleep_duration) => {}
+                }
+            }
+
+            tracing::info!("github adapter loop stopped");
+        });
+
+        *self.poll_task.write().await = Some(poll_task);
+
+        let stream = tokio_stream::wrappers::ReceiverStream::new(inbound_rx);
+        Ok(Box::pin(stream))
+    }
+
+    async fn respond(
+        &self,
+        message: &InboundMessage,
+        response: OutboundResponse,
+    ) -> crate::Result<()> {
+        self.respond_tracked(message, response).await?;
+        Ok(())
+    }
+
+    async fn respond_tracked(
+        &self,
+        message: &InboundMessage,
+        response: OutboundResponse,
+    ) -> crate::Result<Option<String>> {
+        let (repo, number) = Self::thread_of(message)?;
+
+        match response {
+            OutboundResponse::Text(text)
+            | OutboundResponse::RichMessage { text, .. }
+            | OutboundResponse::ThreadReply { text, .. }
+            | OutboundResponse::Ephemeral { text, .. }
+            | OutboundResponse::ScheduledMessage { text, .. } => {
+                let id = self.post_comment(&repo, number, &text).await?;
+                Ok(Some(id.to_string()))
+            }
+            OutboundResponse::File {
+                filename,
+                data,
+                mime_type,
+                caption,
+            } => {
+                let body = file_comment_body(&filename, &data, &mime_type, caption.as_deref());
+                let id = self.post_comment(&repo, number, &body).await?;
+                Ok(Some(id.to_string()))
+            }
+            OutboundResponse::Reaction(emoji) => {
+                let Some(content) = reaction_content(&emoji) else {
+                    tracing::debug!(%emoji, "emoji has no github reaction equivalent");
+                    return Ok(None);
+                };
+                let path = match message
+                    .metadata
+                    .get("github_comment_id")
+                    .and_then(|value| value.as_u64())
+                {
+                    Some(comment_id) => {
+                        format!("/repos/{repo}/issues/comments/{comment_id}/reactions")
+                    }
+                    None => format!("/repos/{repo}/issues/{number}/reactions"),
+                };
+                let _: serde_json::Value = self
+                    .api
+                    .post(&path, &serde_json::json!({ "content": content }))
+                    .await?;
+                Ok(None)
+            }
+            OutboundResponse::Edit { message_id, text } => {
+                let _: serde_json::Value = self
+                    .api
+                    .patch(
+                        &format!("/repos/{repo}/issues/comments/{message_id}"),
+                        &serde_json::json!({ "body": truncate_comment(&text) }),
+                    )
+                    .await?;
+                Ok(None)
+            }
+            OutboundResponse::Delete { message_id } => {
+                self.api
+                    .delete(&format!("/repos/{repo}/issues/comments/{message_id}"))
+                    .await?;
+                Ok(None)
+            }
+            // No typing indicator or streaming on GitHub; the final text
+            // still arrives as a regular Text response.
+            OutboundResponse::RemoveReaction(_)
+            | OutboundResponse::StreamStart
+            | OutboundResponse::StreamChunk(_)
+            | OutboundResponse::StreamEnd
+            | OutboundResponse::Status(_) => Ok(None),
+        }
+    }
+
+    async fn broadcast(&self, target: &str, response: OutboundResponse) -> crate::Result<()> {
+        let (repo, number) = parse_thread_target(target)
+            .with_context(|| format!("invalid github broadcast target '{target}'"))?;
+
+        let text = match response {
+            OutboundResponse::Text(text)
+            | OutboundResponse::RichMessage { text, .. }
+            | OutboundResponse::ThreadReply { text, .. } => text,
+            OutboundResponse::File {
+                filename,
+                data,
+                mime_type,
+                caption,
+            } => file_comment_body(&filename, &data, &mime_type, caption.as_deref()),
+            _ => return Ok(()),
+        };
+        self.post_comment(&repo, number, &text).await?;
+        Ok(())
+    }
+
+    async fn fetch_history(
+        &self,
+        message: &InboundMessage,
+        limit: usize,
+    ) -> crate::Result<Vec<HistoryMessage>> {
+        let (repo, number) = Self::thread_of(message)?;
+        let bot_login = self.bot_login.read().await.clone().unwrap_or_default();
+
+        let issue: ApiIssue = self
+            .api
+            .get(&format!("/repos/{repo}/issues/{number}"))
+            .await?;
+        let comments: Vec<ApiComment> = self
+            .api
+            .get(&format!(
+                "/repos/{repo}/issues/{number}/comments?per_page=100"
+            ))
+            .await?;
+
+        let mut history = Vec::with_capacity(comments.len() + 1);
+        let opening_author = issue.user.map(|user| user.login).unwrap_or_default();
+        history.push(HistoryMessage {
+            is_bot: opening_author == bot_login,
+            author: opening_author,
+            content: format!(
+                "{}\n\n{}",
+                issue.title,
+                issue.body.unwrap_or_default().trim()
+            ),
+            timestamp: Some(issue.created_at),
+        });
+        for comment in comments {
+            // The triggering comment is delivered as the message itself.
+            if comment.id.to_string() == message.id {
+                continue;
+            }
+            let author = comment.user.map(|user| user.login).unwrap_or_default();
+            history.push(HistoryMessage {
+                is_bot: author == bot_login,
+                author,
+                content: comment.body.unwrap_or_default(),
+                timestamp: Some(comment.created_at),
+            });
+        }
+
+        let skip = history.len().saturating_sub(limit);
+        Ok(history.into_iter().skip(skip).collect())
+    }
+
+    async fn health_check(&self) -> crate::Result<()> {
+        let _: ApiUser = self
+            .api
+            .get("/user")
+            .await
+            .context("github health check failed")?;
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> crate::Result<()> {
+        if let Some(shutdown_tx) = self.shutdown_tx.write().await.take() {
+            shutdown_tx.send(true).ok();
+        }
+
+        if let Some(poll_task) = self.poll_task.write().await.take()
+            && let Err(error) = poll_task.await
+        {
+            tracing::warn!(%error, "github poll task join failed during shutdown");
+        }
+
+        tracing::info!("github adapter shut down");
+        Ok(())
+    }
+}
+
+// ── Polling ─────────────────────────────────────────────────────
+
+/// Fetch comments created since the last poll for one repository.
+async fn poll_repo_once(
+    context: &PollContext,
+    repo: &str,
+    cursor: &mut RepoCursor,
+    started_at: DateTime<Utc>,
+) -> anyhow::Result<Vec<InboundMessage>> {
+    let comments: Vec<ApiComment> = context
+        .api
+        .get(&format!(
+            "/repos/{repo}/issues/comments?sort=created&direction=asc&per_page=100&since={}",
+            cursor.since.format("%Y-%m-%dT%H:%M:%SZ")
+        ))
+        .await?;
+
+    let mut messages = Vec::new();
+    // One issue/PR lookup per thread per cycle.
+    let mut thread_context: HashMap<u64, (ApiIssue, Option<String>)> = HashMap::new();
+
+    for comment in comments {
+        if comment.id <= cursor.last_comment_id || comment.created_at < started_at {
+            continue;
+        }
+        cursor.last_comment_id = comment.id;
+        cursor.since = cursor.since.max(comment.created_at);
+
+        let Some(user) = &comment.user else {
+            continue;
+        };
+        // Skip our own comments and other bots (CI, dependabot, ...).
+        if user.login.eq_ignore_ascii_case(&context.bot_login) || user.user_type == "Bot" {
+            continue;
+        }
+
+        let Some(number) = issue_number_from_url(&comment.issue_url) else {
+            tracing::debug!(issue_url = %comment.issue_url, "unrecognized github issue url");
+            continue;
+        };
+
+        if let std::collections::hash_map::Entry::Vacant(entry) = thread_context.entry(number) {
+            let issue: ApiIssue = context
+                .api
+                .get(&format!("/repos/{repo}/issues/{number}"))
+                .await?;
+            let pr_summary = if issue.pull_request.is_some() {
+                match pull_request_summary(&context.api, repo, number).await {
+                    Ok(summary) => Some(summary),
+                    Err(error) => {
+                        tracing::debug!(%repo, number, %error, "failed to load pull request summary");
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+            entry.insert((issue, pr_summary));
+        }
+        let (issue, pr_summary) = &thread_context[&number];
+
+        messages.push(build_inbound_message(
+            &context.runtime_key,
+            &context.bot_login,
+            repo,
+            number,
+            issue,
+            pr_summary.as_deref(),
+            &comment,
+            user,
+        ));
+    }
+
+    Ok(messages)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn build_inbound_message(
+    runtime_key: &str,
+    bot_login: &str,
+    repo: &str,
+    number: u64,
+    issue: &ApiIssue,
+    pr_summary: Option<&str>,
+    comment: &ApiComment,
+    user: &ApiUser,
+) -> InboundMessage {
+    let is_pull_request = issue.pull_request.is_some();
+    let kind = if is_pull_request {
+        "pull_request"
+    } else {
+        "issue"
+    };
+
+    let body = comment.body.clone().unwrap_or_default();
+    let text = match pr_summary {
+        Some(summary) => format!("[{summary}]\n\n{body}"),
+        None => body.clone(),
+    };
+
+    let mut metadata = HashMap::new();
+    metadata.insert("github_repo".into(), serde_json::Value::from(repo));
+    metadata.insert(
+        "github_issue_number".into(),
+        serde_json::Value::from(number),
+    );
+    metadata.insert("github_kind".into(), serde_json::Value::from(kind));
+    metadata.insert(
+        "github_comment_id".into(),
+        serde_json::Value::from(comment.id),
+    );
+    metadata.insert(
+        "github_comment_url".into(),
+        serde_json::Value::from(comment.html_url.clone()),
+    );
+    metadata.insert(
+        "github_state".into(),
+        serde_json::Value::from(issue.state.clone()),
+    );
+    if let Some(summary) = pr_summary {
+        metadata.insert("github_pr_summary".into(), serde_json::Value::from(summary));
+    }
+    metadata.insert(
+        "github_mentions_or_replies_to_bot".into(),
+        serde_json::Value::from(mentions_login(&body, bot_login)),
+    );
+    metadata.insert(
+        crate::metadata_keys::MESSAGE_ID.into(),
+        serde_json::Value::from(comment.id.to_string()),
+    );
+    metadata.insert(
+        crate::metadata_keys::SERVER_NAME.into(),
+        serde_json::Value::from(repo),
+    );
+    metadata.insert(
+        crate::metadata_keys::CHANNEL_NAME.into(),
+        serde_json::Value::from(format!("{number} {}", issue.title)),
+    );
+    metadata.insert(
+        "sender_display_name".into(),
+        serde_json::Value::from(user.login.clone()),
+    );
+
+    InboundMessage {
+        id: comment.id.to_string(),
+        source: "github".into(),
+        adapter: Some(runtime_key.to_string()),
+        conversation_id: format!("github:{repo}#{number}"),
+        sender_id: user.login.clone(),
+        agent_id: None,
+        content: MessageContent::Text(text),
+        timestamp: comment.created_at,
+        metadata,
+        formatted_author: Some(format!("@{}", user.login)),
+    }
+}
+
+/// One-line summary of a pull request's diff and CI state.
+async fn pull_request_summary(api: &ApiClient, repo: &str, number: u64) -> anyhow::Result<String> {
+    let pull: ApiPullRequest = api.get(&format!("/repos/{repo}/pulls/{number}")).await?;
+    let checks: ApiCheckRuns = api
+        .get(&format!(
+            "/repos/{repo}/commits/{}/check-runs?per_page=100",
+            pull.head.sha
+        ))
+        .await?;
+    Ok(format_pull_request_summary(
+        number,
+        &pull,
+        &checks.check_runs,
+    ))
+}
+
+fn format_pull_request_summary(
+    number: u64,
+    pull: &ApiPullRequest,
+    checks: &[ApiCheckRun],
+) -> String {
+    let state = if pull.merged {
+        " merged"
+    } else if pull.draft {
+        " draft"
+    } else {
+        ""
+    };
+    format!(
+        "PR #{number}{state} {} -> {}: +{} -{} across {} files; CI: {}",
+        pull.head.name,
+        pull.base.name,
+        pull.additions,
+        pull.deletions,
+        pull.changed_files,
+        summarize_checks(checks)
+    )
+}
+
+fn summarize_checks(checks: &[ApiCheckRun]) -> String {
+    if checks.is_empty() {
+        return "no checks".to_string();
+    }
+
+    let mut passed = 0;
+    let mut pending = 0;
+    let mut failed = Vec::new();
+    for check in checks {
+        match (check.status.as_str(), check.conclusion.as_deref()) {
+            ("completed", Some("success" | "neutral" | "skipped")) => passed += 1,
+            ("completed", _) => failed.push(check.name.as_str()),
+            _ => pending += 1,
+        }
+    }
+
+    let mut parts = Vec::new();
+    if passed > 0 {
+        parts.push(format!("{passed} passed"));
+    }
+    if !failed.is_empty() {
+        parts.push(format!("{} failed ({})", failed.len(), failed.join(", ")));
+    }
+    if pending > 0 {
+        parts.push(format!("{pending} pending"));
+    }
+    parts.join(", ")
+}
+
+// ── Helpers ─────────────────────────────────────────────────────
+
+/// Parse `owner/repo#123` into its repository and issue number.
+pub fn parse_thread_target(target: &str) -> Option<(String, u64)> {
+    let (repo, number) = target.trim().rsplit_once('#')?;
+    let number = number.parse::<u64>().ok()?;
+    let (owner, name) = repo.split_once('/')?;
+    if owner.is_empty() || name.is_empty() || name.contains('/') {
+        return None;
+    }
+    Some((repo.to_string(), number))
+}
+
+/// Whether `text` contains `@login` as a whole word (case-insensitive).
+fn mentions_login(text: &str, login: &str) -> bool {
+    if login.is_empty() {
+        return false;
+    }
+    let text = text.to_lowercase();
+    let mention = format!("@{}", login.to_lowercase());
+    text.match_indices(&mention).any(|(start, _)| {
+        let end = start + mention.len();
+        text[end..]
+            .chars()
+            .next()
+            .is_none_or(|character| !(character.is_ascii_alphanumeric() || character == '-'))
+    })
+}
+
+/// Extract the issue number from an API `issue_url`.
+fn issue_number_from_url(issue_url: &str) -> Option<u64> {
+    issue_url.rsplit('/').next()?.parse().ok()
+}
+
+fn truncate_comment(text: &str) -> String {
+    if text.chars().count() <= MAX_COMMENT_LENGTH {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(MAX_COMMENT_LENGTH - 20).collect();
+    truncated.push_str("\n\n…(truncated)");
+    truncated
+}
+
+/// GitHub has no file upload API for comments: small text files are inlined
+/// as a code block, anything else is only named.
+fn file_comment_body(
+    filename: &str,
+    data: &[u8],
+    mime_type: &str,
+    caption: Option<&str>,
+) -> String {
+    let mut body = caption
+        .filter(|caption| !caption.trim().is_empty())
+        .map(|caption| format!("{caption}\n\n"))
+        .unwrap_or_default();
+
+    match std::str::from_utf8(data) {
+        Ok(text) if mime_type.starts_with("text/") && data.len() <= MAX_INLINE_FILE_BYTES => {
+            body.push_str(&format!("`{filename}`\n\n```\n{text}\n```"));
+        }
+        _ => {
+            body.push_str(&format!(
+                "_Attachment `{filename}` ({mime_type}, {} bytes) can't be posted to GitHub._",
+                data.len()
+            ));
+        }
+    }
+    body
+}
+
+/// Map an emoji to one of GitHub's fixed reaction types.
+fn reaction_content(emoji: &str) -> Option<&'static str> {
+    match emoji.trim().trim_end_matches('\u{fe0f}') {
+        "👍" | "+1" | "thumbsup" => Some("+1"),
+        "👎" | "-1" | "thumbsdown" => Some("-1"),
+        "😄" | "😀" | "😆" | "laugh" => Some("laugh"),
+        "😕" | "confused" => Some("confused"),
+        "❤" | "heart" => Some("heart"),
+        "🎉" | "hooray" | "tada" => Some("hooray"),
+        "🚀" | "rocket" => Some("rocket"),
+        "👀" | "eyes" => Some("eyes"),
+        _ => None,
+    }
+}
+
+// ── HTTP client ─────────────────────────────────────────────────
+
+#[derive(Clone)]
+struct ApiClient {
+    client: reqwest::Client,
+    base_url: String,
+    token: String,
+}
+
+impl ApiClient {
+    fn new(base_url: &str, token: &str) -> crate::Result<Self> {
+        let client = reqwest::Client::builder()
+            .connect_timeout(HTTP_CONNECT_TIMEOUT)
+            .timeout(HTTP_REQUEST_TIMEOUT)
+            .user_agent(concat!("spacebot/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .context("failed to build github http client")?;
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+        })
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, format!("{}{path}", self.base_url))
+            .bearer_auth(&self.token)
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28")
+    }
+
+    async fn send<T: serde::de::DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+        path: &str,
+    ) -> anyhow::Result<T> {
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("github request to {path} failed"))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(DeliveryError {
+                endpoint: format!("github {path}"),
+                status: status.as_u16(),
+                body: body.chars().take(200).collect(),
+            }
+            .into());
+        }
+        response
+            .json()
+            .await
+            .with_context(|| format!("invalid github response from {path}"))
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, path: &str) -> anyhow::Result<T> {
+        self.send(self.request(reqwest::Method::GET, path), path)
+            .await
+    }
+
+    async fn post<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+    ) -> anyhow::Result<T> {
+        self.send(self.request(reqwest::Method::POST, path).json(body), path)
+            .await
+    }
+
+    async fn patch<T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+    ) -> anyhow::Result<T> {
+        self.send(self.request(reqwest::Method::PATCH, path).json(body), path)
+            .await
+    }
+
+    async fn delete(&self, path: &str) -> anyhow::Result<()> {
+        let response = self
+            .request(reqwest::Method::DELETE, path)
+            .send()
+            .await
+            .with_context(|| format!("github request to {path} failed"))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(DeliveryError {
+                endpoint: format!("github {path}"),
+                status: status.as_u16(),
+                body: String::new(),
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_thread_target_requires_owner_repo_and_number() {
+        assert_eq!(
+            parse_thread_target("spacedriveapp/spacebot#42"),
+            Some(("spacedriveapp/spacebot".to_string(), 42))
+        );
+        assert_eq!(parse_thread_target("spacebot#42"), None);
+        assert_eq!(parse_thread_target("a/b/c#1"), None);
+        assert_eq!(parse_thread_target("owner/repo#abc"), None);
+        assert_eq!(
+            issue_number_from_url("https://api.github.com/repos/o/r/issues/17"),
+            Some(17)
+        );
+    }
+
+    #[test]
+    fn check_summary_groups_by_outcome() {
+        let run = |name: &str, status: &str, conclusion: Option<&str>| ApiCheckRun {
+            name: name.into(),
+            status: status.into(),
+            conclusion: conclusion.map(Into::into),
+        };
+        let checks = [
+            run("build", "completed", Some("success")),
+            run("lint", "completed", Some("failure")),
+            run("docs", "completed", Some("skipped")),
+            run("e2e", "in_progress", None),
+        ];
+        assert_eq!(
+            summarize_checks(&checks),
+            "2 passed, 1 failed (lint), 1 pending"
+        );
+        assert_eq!(summarize_checks(&[]), "no checks");
+    }
+
+    #[test]
+    fn reactions_map_to_github_content() {
+        assert_eq!(reaction_content("👍"), Some("+1"));
+        assert_eq!(reaction_content("❤️"), Some("heart"));
+        assert_eq!(reaction_content("🦀"), None);
+    }
+
+    #[test]
+    fn mentions_match_whole_logins_only() {
+        assert!(mentions_login("thoughts @Spacebot?", "spacebot"));
+        assert!(!mentions_login("cc @spacebot-ci", "spacebot"));
+        assert!(!mentions_login("no mention", "spacebot"));
+    }
+}
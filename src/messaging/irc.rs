@@ -0,0 +1,803 @@
+//! IRC messaging adapter over a plain or TLS socket.
+//!
+//! Speaks just enough of the client protocol to register (optionally with
+//! SASL PLAIN), join the configured channels, and exchange `PRIVMSG`s. Each
+//! channel is one conversation (`irc:#channel`); private messages from
+//! allowed nicks become `irc:dm:<nick>`. Replies are split into lines that fit
+//! the 512-byte protocol limit and sent with a fixed delay between lines so
+//! the server's flood protection doesn't disconnect the bot.
+
+use crate::config::IrcConfig;
+use crate::messaging::traits::{InboundStream, Messaging};
+use crate::{InboundMessage, MessageContent, OutboundResponse};
+
+use anyhow::Context as _;
+use base64::Engine as _;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt as _, AsyncRead, AsyncWrite, AsyncWriteExt as _, BufReader};
+use tokio::sync::{RwLock, mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Protocol limit for one line, including the trailing CRLF.
+const MAX_LINE_BYTES: usize = 512;
+
+/// Room left for the `:nick!user@host ` prefix the server adds when relaying
+/// our lines to other clients.
+const PREFIX_RESERVE_BYTES: usize = 100;
+
+/// Maximum backoff between reconnect attempts.
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(300);
+
+/// Registration (including SASL) must finish within this window.
+const REGISTRATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A server that sends nothing for this long is treated as gone. Servers
+/// ping idle clients every few minutes, so silence means a dead socket.
+const READ_TIMEOUT: Duration = Duration::from_secs(600);
+
+trait IrcStream: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> IrcStream for T {}
+
+/// A parsed protocol line. IRCv3 message tags are dropped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct IrcLine {
+    prefix: Option<String>,
+    command: String,
+    params: Vec<String>,
+}
+
+impl IrcLine {
+    /// Nick part of the `nick!user@host` prefix.
+    fn source_nick(&self) -> Option<&str> {
+        let prefix = self.prefix.as_deref()?;
+        Some(prefix.split_once('!').map_or(prefix, |(nick, _)| nick))
+    }
+}
+
+/// Settings shared with the connection task.
+#[derive(Clone)]
+struct SessionContext {
+    runtime_key: String,
+    server: String,
+    port: u16,
+    use_tls: bool,
+    nickname: String,
+    username: String,
+    realname: String,
+    server_password: Option<String>,
+    sasl: Option<(String, String)>,
+    channels: Vec<ChannelJoin>,
+    dm_allowed_users: Vec<String>,
+    send_interval: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ChannelJoin {
+    name: String,
+    key: Option<String>,
+}
+
+/// IRC adapter state.
+pub struct IrcAdapter {
+    context: SessionContext,
+    outbound_tx: Arc<RwLock<Option<mpsc::Sender<String>>>>,
+    connected: Arc<RwLock<bool>>,
+    shutdown_tx: Arc<RwLock<Option<watch::Sender<bool>>>>,
+    session_task: Arc<RwLock<Option<JoinHandle<()>>>>,
+}
+
+impl IrcAdapter {
+    pub fn from_config(config: &IrcConfig) -> Self {
+        let sasl = config.sasl_password.clone().map(|password| {
+            let username = config
+                .sasl_username
+                .clone()
+                .unwrap_or_else(|| config.nickname.clone());
+            (username, password)
+        });
+        Self {
+            context: SessionContext {
+                runtime_key: "irc".into(),
+                server: config.server.clone(),
+                port: config.port,
+                use_tls: config.use_tls,
+                nickname: config.nickname.clone(),
+                username: config
+                    .username
+                    .clone()
+                    .unwrap_or_else(|| config.nickname.clone()),
+                realname: config.realname.clone(),
+                server_password: config.server_password.clone(),
+                sasl,
+                channels: config
+                    .channels
+                    .iter()
+                    .filter_map(|entry| parse_channel_entry(entry))
+                    .collect(),
+                dm_allowed_users: config.dm_allowed_users.clone(),
+                send_interval: Duration::from_millis(config.send_interval_ms),
+            },
+            outbound_tx: Arc::new(RwLock::new(None)),
+            connected: Arc::new(RwLock::new(false)),
+            shutdown_tx: Arc::new(RwLock::new(None)),
+            session_task: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Queue a message for `target`, one `PRIVMSG` per protocol-sized line.
+    async fn send_privmsg(&self, target: &str, text: &str) -> anyhow::Result<()> {
+        let outbound_tx = self
+            .outbound_tx
+            .read()
+            .await
+            .clone()
+            .context("irc adapter not started")?;
+        for line in split_for_irc(text, max_payload_bytes(target)) {
+            outbound_tx
+                .send(format!("PRIVMSG {target} :{line}"))
+                .await
+                .context("irc connection task stopped")?;
+        }
+        Ok(())
+    }
+
+    fn reply_target(message: &InboundMessage) -> anyhow::Result<String> {
+        if let Some(target) = message
+            .metadata
+            .get("irc_reply_target")
+            .and_then(|value| value.as_str())
+        {
+            return Ok(target.to_string());
+        }
+        let target = message
+            .conversation_id
+            .strip_prefix("irc:")
+            .with_context(|| format!("no irc target for '{}'", message.conversation_id))?;
+        Ok(target.strip_prefix("dm:").unwrap_or(target).to_string())
+    }
+}
+
+impl Messaging for IrcAdapter {
+    fn name(&self) -> &str {
+        &self.context.runtime_key
+    }
+
+    async fn start(&self) -> crate::Result<InboundStream> {
+        if self.session_task.read().await.is_some() {
+            return Err(anyhow::anyhow!("irc adapter already started").into());
+        }
+
+        let (inbound_tx, inbound_rx) = mpsc::channel(256);
+        let (outbound_tx, mut outbound_rx) = mpsc::channel::<String>(256);
+        let (shutdown_tx, mut shutdown_rx) = watch::channel(false);
+        *self.outbound_tx.write().await = Some(outbound_tx);
+        *self.shutdown_tx.write().await = Some(shutdown_tx);
+
+        let context = self.context.clone();
+        let connected = self.connected.clone();
+
+        let session_task = tokio::spawn(async move {
+            let mut retry_backoff = Duration::from_secs(5);
+
+            loop {
+                if *shutdown_rx.borrow() {
+                    break;
+                }
+
+                let result = run_session(
+                    &context,
+                    &connected,
+                    &inbound_tx,
+                    &mut outbound_rx,
+                    &mut shutdown_rx,
+                )
+                .await;
+                *connected.write().await = false;
+
+                match result {
+                    Ok(SessionEnd::Shutdown) => break,
+                    Ok(SessionEnd::InboundClosed) => {
+                        tracing::warn!("irc inbound channel closed, stopping adapter loop");
+                        break;
+                    }
+                    Ok(SessionEnd::Disconnected { registered }) => {
+                        if registered {
+                            retry_backoff = Duration::from_secs(5);
+                        }
+                        tracing::warn!(server = %context.server, "irc connection closed, reconnecting");
+                    }
+                    Err(error) => {
+                        tracing::warn!(%error, server = %context.server, "irc session failed");
+                    }
+                }
+
+                tokio::select! {
+                    _ = shutdown_rx.changed() => {
+                        if *shutdown_rx.borrow() {
+                            break;
+                        }
+                    }
+                    _ = tokio::time::sleep(retry_backoff) => {}
+                }
+                retry_backoff = (retry_backoff * 2).min(MAX_RETRY_BACKOFF);
+            }
+
+            tracing::info!("irc adapter loop stopped");
+        });
+
+        *self.session_task.write().await = Some(session_task);
+
+        let stream = tokio_stream::wrappers::ReceiverStream::new(inbound_rx);
+        Ok(Box::pin(stream))
+    }
+
+    async fn respond(
+        &self,
+        message: &InboundMessage,
+        response: OutboundResponse,
+    ) -> crate::Result<()> {
+        let target = Self::reply_target(message)?;
+
+        match response {
+            OutboundResponse::Text(text)
+            | OutboundResponse::RichMessage { text, .. }
+            | OutboundResponse::ThreadReply { text, .. }
+            | OutboundResponse::Ephemeral { text, .. }
+            | OutboundResponse::ScheduledMessage { text, .. } => {
+                self.send_privmsg(&target, &text).await?;
+            }
+            OutboundResponse::File {
+                filename, caption, ..
+            } => {
+                // IRC is text-only — send a note about the file
+                let text = match caption {
+                    Some(caption) => format!("[File: {filename}] {caption}"),
+                    None => format!("[File: {filename}]"),
+                };
+                self.send_privmsg(&target, &text).await?;
+            }
+            // No reactions, edits, typing, or streaming on IRC. The final
+            // text still arrives as a regular Text response.
+            OutboundResponse::Reaction(_)
+            | OutboundResponse::RemoveReaction(_)
+            | OutboundResponse::Edit { .. }
+            | OutboundResponse::Delete { .. }
+            | OutboundResponse::StreamStart
+            | OutboundResponse::StreamChunk(_)
+            | OutboundResponse::StreamEnd
+            | OutboundResponse::Status(_) => {}
+        }
+
+        Ok(())
+    }
+
+    async fn broadcast(&self, target: &str, response: OutboundResponse) -> crate::Result<()> {
+        let target = target.strip_prefix("dm:").unwrap_or(target);
+        match response {
+            OutboundResponse::Text(text)
+            | OutboundResponse::RichMessage { text, .. }
+            | OutboundResponse::ThreadReply { text, .. } => {
+                self.send_privmsg(target, &text).await?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn health_check(&self) -> crate::Result<()> {
+        if !*self.connected.read().await {
+            return Err(anyhow::anyhow!("irc client not connected").into());
+        }
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> crate::Result<()> {
+        if let Some(shutdown_tx) = self.shutdown_tx.write().await.take() {
+            shutdown_tx.send(true).ok();
+        }
+
+        if let Some(session_task) = self.session_task.write().await.take()
+            && let Err(error) = session_task.await
+        {
+            tracing::warn!(%error, "irc session task join failed during shutdown");
+        }
+
+        self.outbound_tx.write().await.take();
+        tracing::info!("irc adapter shut down");
+        Ok(())
+    }
+}
+
+// ── Session ─────────────────────────────────────────────────────
+
+enum SessionEnd {
+    Shutdown,
+    InboundClosed,
+    Disconnected { registered: bool },
+}
+
+async fn connect(context: &SessionContext) -> anyhow::Result<Box<dyn IrcStream>> {
+    let tcp = tokio::net::TcpStream::connect((context.server.as_str(), context.port))
+        .await
+        .with_context(|| format!("failed to connect to {}:{}", context.server, context.port))?;
+
+    if !context.use_tls {
+        return Ok(Box::new(tcp));
+    }
+
+    let mut roots = rustls::RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let tls_config = rustls::ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let connector = tokio_rustls::TlsConnector::from(Arc::new(tls_config));
+    let server_name = rustls::pki_types::ServerName::try_from(context.server.clone())
+        .with_context(|| format!("invalid irc server name '{}'", context.server))?;
+    let tls = connector
+        .connect(server_name, tcp)
+        .await
+        .context("irc TLS handshake failed")?;
+    Ok(Box::new(tls))
+}
+
+/// Run one connection until it drops or the adapter shuts down.
+async fn run_session(
+    context: &SessionContext,
+    connected: &RwLock<bool>,
+    inbound_tx: &mpsc::Sender<InboundMessage>,
+    outbound_rx: &mut mpsc::Receiver<String>,
+    shutdown_rx: &mut watch::Receiver<bool>,
+) -> anyhow::Result<SessionEnd> {
+    let stream = connect(context).await?;
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+
+    // Nick currently held on the server. Differs from the configured one
+    // when it was taken and a fallback was used.
+    let mut nick = context.nickname.clone();
+
+    if context.sasl.is_some() {
+        write_line(&mut writer, "CAP REQ :sasl").await?;
+    }
+    if let Some(password) = &context.server_password {
+        write_line(&mut writer, &format!("PASS {password}")).await?;
+    }
+    write_line(&mut writer, &format!("NICK {nick}")).await?;
+    write_line(
+        &mut writer,
+        &format!("USER {} 0 * :{}", context.username, context.realname),
+    )
+    .await?;
+
+    let registration_deadline = Instant::now() + REGISTRATION_TIMEOUT;
+    let mut registered = false;
+    let mut next_send_at = Instant::now();
+
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep_until(registration_deadline), if !registered => {
+                anyhow::bail!("irc registration timed out");
+            }
+            _ = shutdown_rx.changed() => {
+                if *shutdown_rx.borrow() {
+                    write_line(&mut writer, "QUIT :shutting down").await.ok();
+                    return Ok(SessionEnd::Shutdown);
+                }
+            }
+            line = tokio::time::timeout(READ_TIMEOUT, lines.next_line()) => {
+                let Ok(line) = line else {
+                    anyhow::bail!("irc server went silent");
+                };
+                let Some(line) = line.context("failed to read from irc server")? else {
+                    return Ok(SessionEnd::Disconnected { registered });
+                };
+                let Some(parsed) = parse_line(&line) else {
+                    continue;
+                };
+
+                match parsed.command.as_str() {
+                    "PING" => {
+                        let token = parsed.params.first().map(String::as_str).unwrap_or_default();
+                        write_line(&mut writer, &format!("PONG :{token}")).await?;
+                    }
+                    "CAP" => {
+                        let subcommand = parsed.params.get(1).map(String::as_str);
+                        match subcommand {
+                            Some("ACK") => write_line(&mut writer, "AUTHENTICATE PLAIN").await?,
+                            Some("NAK") => anyhow::bail!("irc server does not support SASL"),
+                            _ => {}
+                        }
+                    }
+                    "AUTHENTICATE" if parsed.params.first().map(String::as_str) == Some("+") => {
+                        if let Some((username, password)) = &context.sasl {
+                            let payload = base64::engine::general_purpose::STANDARD
+                                .encode(format!("{username}\0{username}\0{password}"));
+                            write_line(&mut writer, &format!("AUTHENTICATE {payload}")).await?;
+                        }
+                    }
+                    // RPL_SASLSUCCESS
+                    "903" => write_line(&mut writer, "CAP END").await?,
+                    // ERR_SASLFAIL, ERR_SASLTOOLONG, ERR_SASLABORTED
+                    "904" | "905" | "906" => anyhow::bail!("irc SASL authentication failed"),
+                    // RPL_WELCOME
+                    "001" => {
+                        registered = true;
+                        if let Some(assigned) = parsed.params.first() {
+                            nick = assigned.clone();
+                        }
+                        for channel in &context.channels {
+                            let join = match &channel.key {
+                                Some(key) => format!("JOIN {} {key}", channel.name),
+                                None => format!("JOIN {}", channel.name),
+                            };
+                            write_line(&mut writer, &join).await?;
+                        }
+                        *connected.write().await = true;
+                        tracing::info!(
+                            server = %context.server,
+                            nick = %nick,
+                            channels = ?context.channels.iter().map(|c| &c.name).collect::<Vec<_>>(),
+                            "irc connected"
+                        );
+                    }
+                    // ERR_NICKNAMEINUSE, ERR_NICKCOLLISION
+                    "433" | "436" if !registered => {
+                        nick.push('_');
+                        write_line(&mut writer, &format!("NICK {nick}")).await?;
+                    }
+                    "NICK" => {
+                        if parsed.source_nick().is_some_and(|source| source.eq_ignore_ascii_case(&nick))
+                            && let Some(new_nick) = parsed.params.first()
+                        {
+                            nick = new_nick.clone();
+                        }
+                    }
+                    "ERROR" => {
+                        let reason = parsed.params.last().cloned().unwrap_or_default();
+                        tracing::warn!(%reason, "irc server closed the connection");
+                        return Ok(SessionEnd::Disconnected { registered });
+                    }
+                    "PRIVMSG" => {
+                        if let Some(message) = build_inbound_message(context, &nick, &parsed)
+                            && inbound_tx.send(message).await.is_err()
+                        {
+                            return Ok(SessionEnd::InboundClosed);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            outbound = outbound_rx.recv(), if registered => {
+                let Some(outbound) = outbound else {
+                    return Ok(SessionEnd::Shutdown);
+                };
+                // Space lines out so the server's flood protection never
+                // kicks in on long multi-line replies.
+                tokio::time::sleep_until(next_send_at).await;
+                write_line(&mut writer, &outbound).await?;
+                next_send_at = Instant::now() + context.send_interval;
+            }
+        }
+    }
+}
+
+async fn write_line<W: AsyncWrite + Unpin>(writer: &mut W, line: &str) -> anyhow::Result<()> {
+    // Never let a stray newline smuggle a second command onto the wire.
+    let line: String = line.chars().filter(|c| *c != '\r' && *c != '\n').collect();
+    writer
+        .write_all(format!("{line}\r\n").as_bytes())
+        .await
+        .context("failed to write to irc server")?;
+    writer.flush().await.context("failed to flush irc stream")
+}
+
+fn build_inbound_message(
+    context: &SessionContext,
+    bot_nick: &str,
+    line: &IrcLine,
+) -> Option<InboundMessage> {
+    let sender = line.source_nick()?.to_string();
+    if sender.eq_ignore_ascii_case(bot_nick) {
+        return None;
+    }
+    let target = line.params.first()?;
+    let raw_text = line.params.get(1)?;
+
+    // CTCP: keep ACTION (/me) as an emote, drop VERSION, PING, and the rest.
+    let text = match raw_text
+        .strip_prefix('\u{1}')
+        .map(|inner| inner.trim_end_matches('\u{1}'))
+    {
+        Some(ctcp) => {
+            let action = ctcp.strip_prefix("ACTION ")?;
+            format!("* {sender} {action}")
+        }
+        None => strip_formatting(raw_text),
+    };
+    if text.trim().is_empty() {
+        return None;
+    }
+
+    let is_private = !is_channel_name(target);
+    if is_private
+        && !context
+            .dm_allowed_users
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(&sender))
+    {
+        return None;
+    }
+
+    let channel = target.to_lowercase();
+    let (conversation_id, reply_target) = if is_private {
+        (format!("irc:dm:{}", sender.to_lowercase()), sender.clone())
+    } else {
+        (format!("irc:{channel}"), target.clone())
+    };
+    let message_id = uuid::Uuid::new_v4().to_string();
+
+    let mut metadata = HashMap::new();
+    metadata.insert(
+        "irc_reply_target".into(),
+        serde_json::Value::from(reply_target),
+    );
+    metadata.insert("irc_is_private".into(), serde_json::Value::from(is_private));
+    if !is_private {
+        metadata.insert(
+            "irc_channel".into(),
+            serde_json::Value::from(channel.clone()),
+        );
+        metadata.insert(
+            crate::metadata_keys::CHANNEL_NAME.into(),
+            serde_json::Value::from(channel),
+        );
+    }
+    metadata.insert(
+        "irc_mentions_or_replies_to_bot".into(),
+        serde_json::Value::from(mentions_nick(&text, bot_nick)),
+    );
+    metadata.insert(
+        crate::metadata_keys::MESSAGE_ID.into(),
+        serde_json::Value::from(message_id.clone()),
+    );
+    metadata.insert(
+        "sender_display_name".into(),
+        serde_json::Value::from(sender.clone()),
+    );
+
+    Some(InboundMessage {
+        id: message_id,
+        source: "irc".into(),
+        adapter: Some(context.runtime_key.clone()),
+        conversation_id,
+        sender_id: sender.clone(),
+        agent_id: None,
+        content: MessageContent::Text(text),
+        timestamp: chrono::Utc::now(),
+        metadata,
+        formatted_author: Some(sender),
+    })
+}
+
+// ── Protocol helpers ────────────────────────────────────────────
+
+/// Parse `[@tags] [:prefix] COMMAND [params...] [:trailing]`.
+fn parse_line(line: &str) -> Option<IrcLine> {
+    let mut rest = line.trim_end_matches(['\r', '\n']);
+    if let Some(tagged) = rest.strip_prefix('@') {
+        rest = tagged.split_once(' ')?.1.trim_start();
+    }
+
+    let mut prefix = None;
+    if let Some(prefixed) = rest.strip_prefix(':') {
+        let (source, remainder) = prefixed.split_once(' ')?;
+        prefix = Some(source.to_string());
+        rest = remainder.trim_start();
+    }
+
+    let (command, mut rest) = match rest.split_once(' ') {
+        Some((command, remainder)) => (command, remainder),
+        None => (rest, ""),
+    };
+    if command.is_empty() {
+        return None;
+    }
+
+    let mut params = Vec::new();
+    loop {
+        rest = rest.trim_start_matches(' ');
+        if rest.is_empty() {
+            break;
+        }
+        if let Some(trailing) = rest.strip_prefix(':') {
+            params.push(trailing.to_string());
+            break;
+        }
+        match rest.split_once(' ') {
+            Some((param, remainder)) => {
+                params.push(param.to_string());
+                rest = remainder;
+            }
+            None => {
+                params.push(rest.to_string());
+                break;
+            }
+        }
+    }
+
+    Some(IrcLine {
+        prefix,
+        command: command.to_ascii_uppercase(),
+        params,
+    })
+}
+
+/// Parse a `channels` entry: `#name` or `#name key`.
+fn parse_channel_entry(entry: &str) -> Option<ChannelJoin> {
+    let mut parts = entry.split_whitespace();
+    let name = parts.next()?;
+    let name = if is_channel_name(name) {
+        name.to_string()
+    } else {
+        format!("#{name}")
+    };
+    Some(ChannelJoin {
+        name,
+        key: parts.next().map(str::to_string),
+    })
+}
+
+pub(crate) fn is_channel_name(target: &str) -> bool {
+    target.starts_with(['#', '&', '+', '!'])
+}
+
+/// Payload bytes available for a `PRIVMSG` to `target`.
+fn max_payload_bytes(target: &str) -> usize {
+    let overhead = "PRIVMSG  :\r\n".len() + target.len() + PREFIX_RESERVE_BYTES;
+    MAX_LINE_BYTES.saturating_sub(overhead).max(64)
+}
+
+/// Split text into protocol lines: one per source line, long lines wrapped at
+/// word boundaries (or hard-cut on a char boundary), blank lines dropped.
+fn split_for_irc(text: &str, max_bytes: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for source_line in text.lines() {
+        let mut remaining = source_line.trim_end();
+        while !remaining.is_empty() {
+            if remaining.len() <= max_bytes {
+                lines.push(remaining.to_string());
+                break;
+            }
+
+            let mut safe_max = max_bytes;
+            while !remaining.is_char_boundary(safe_max) {
+                safe_max -= 1;
+            }
+            let split_at = remaining[..safe_max]
+                .rfind(' ')
+                .filter(|index| *index > 0)
+                .unwrap_or(safe_max);
+
+            lines.push(remaining[..split_at].to_string());
+            remaining = remaining[split_at..].trim_start();
+        }
+    }
+    lines
+}
+
+/// Remove mIRC color, bold, italic, underline, and reset control codes.
+fn strip_formatting(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(character) = chars.next() {
+        match character {
+            '\u{3}' => {
+                // Color: up to two digits, optionally `,` and two more.
+                for _ in 0..2 {
+                    if chars.peek().is_some_and(char::is_ascii_digit) {
+                        chars.next();
+                    }
+                }
+                if chars.peek() == Some(&',') {
+                    let mut lookahead = chars.clone();
+                    lookahead.next();
+                    if lookahead.peek().is_some_and(char::is_ascii_digit) {
+                        chars.next();
+                        for _ in 0..2 {
+                            if chars.peek().is_some_and(char::is_ascii_digit) {
+                                chars.next();
+                            }
+                        }
+                    }
+                }
+            }
+            '\u{2}' | '\u{1d}' | '\u{1f}' | '\u{1e}' | '\u{11}' | '\u{16}' | '\u{f}' => {}
+            _ => output.push(character),
+        }
+    }
+    output
+}
+
+/// Whether the message addresses the bot: `nick: ...`, `nick, ...`, or the
+/// nick appearing as a whole word anywhere.
+fn mentions_nick(text: &str, nick: &str) -> bool {
+    let nick = nick.to_lowercase();
+    text.to_lowercase()
+        .split(|c: char| c.is_whitespace() || matches!(c, ':' | ',' | '!' | '?' | '.' | '@'))
+        .any(|word| word == nick)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_line_handles_prefix_tags_and_trailing() {
+        let line =
+            parse_line("@time=2024-01-01T00:00:00Z :alice!a@host PRIVMSG #rust :hello there")
+                .expect("valid line");
+        assert_eq!(line.prefix.as_deref(), Some("alice!a@host"));
+        assert_eq!(line.source_nick(), Some("alice"));
+        assert_eq!(line.command, "PRIVMSG");
+        assert_eq!(line.params, vec!["#rust", "hello there"]);
+
+        let ping = parse_line("PING :irc.example.org").expect("valid line");
+        assert_eq!(ping.prefix, None);
+        assert_eq!(ping.params, vec!["irc.example.org"]);
+
+        let welcome = parse_line(":server 001 spacebot :Welcome").expect("valid line");
+        assert_eq!(welcome.params, vec!["spacebot", "Welcome"]);
+    }
+
+    #[test]
+    fn split_for_irc_wraps_long_lines_and_drops_blank_ones() {
+        let text = "short\n\nthe quick brown fox jumps";
+        assert_eq!(
+            split_for_irc(text, 10),
+            vec!["short", "the quick", "brown fox", "jumps"]
+        );
+        assert!(
+            split_for_irc(&"é".repeat(100), 9)
+                .iter()
+                .all(|line| line.len() <= 9)
+        );
+    }
+
+    #[test]
+    fn channel_entries_accept_keys_and_missing_hash() {
+        assert_eq!(
+            parse_channel_entry("#ops secret"),
+            Some(ChannelJoin {
+                name: "#ops".into(),
+                key: Some("secret".into()),
+            })
+        );
+        assert_eq!(
+            parse_channel_entry("spacebot"),
+            Some(ChannelJoin {
+                name: "#spacebot".into(),
+                key: None,
+            })
+        );
+        assert_eq!(parse_channel_entry("  "), None);
+    }
+
+    #[test]
+    fn formatting_codes_are_stripped() {
+        assert_eq!(
+            strip_formatting("\u{2}bold\u{2} \u{3}04,01red\u{3} text"),
+            "bold red text"
+        );
+        assert_eq!(strip_formatting("\u{3}12blue"), "blue");
+    }
+
+    #[test]
+    fn mentions_match_whole_nick() {
+        assert!(mentions_nick("spacebot: status?", "Spacebot"));
+        assert!(mentions_nick("hey spacebot, ping", "spacebot"));
+        assert!(!mentions_nick("spacebot_ is someone else", "spacebot"));
+    }
+}
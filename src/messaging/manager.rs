@@ -1,6 +1,8 @@
 //! MessagingManager: Fan-in and routing for all adapters.
 
+use crate::config::InstancePermissions;
 use crate::messaging::traits::{HistoryMessage, InboundStream, Messaging, MessagingDyn};
+use crate::supervisor::{ProcessKind, ProcessSpec, Supervisor};
 use crate::{InboundMessage, OutboundResponse, StatusUpdate};
 
 use anyhow::Context as _;
@@ -19,6 +21,8 @@ pub struct MessagingManager {
     fan_in_tx: mpsc::Sender<InboundMessage>,
     /// Receiver side, taken once by `start()`.
     fan_in_rx: RwLock<Option<mpsc::Receiver<InboundMessage>>>,
+    /// Permission handles of the named adapter instances, for config reloads.
+    instance_permissions: InstancePermissions,
 }
 
 impl MessagingManager {
@@ -28,9 +32,16 @@ impl MessagingManager {
             adapters: RwLock::new(HashMap::new()),
             fan_in_tx,
             fan_in_rx: RwLock::new(Some(fan_in_rx)),
+            instance_permissions: InstancePermissions::default(),
         }
     }
 
+    /// Permission handles of running named adapter instances. Whoever builds
+    /// a named adapter tracks its permissions here so a reload can update them.
+    pub fn instance_permissions(&self) -> &InstancePermissions {
+        &self.instance_permissions
+    }
+
     /// Register an adapter (before start). Use `register_and_start` for runtime addition.
     pub async fn register(&self, adapter: impl Messaging) {
         let name = adapter.name().to_string();
@@ -45,29 +56,32 @@ impl MessagingManager {
         self.adapters.write().await.insert(name, adapter);
     }
 
-    /// Maximum number of retry attempts for failed adapters before giving up.
-    const MAX_RETRY_ATTEMPTS: u32 = 12;
-
     /// Start all registered adapters and return the merged inbound stream.
     ///
     /// Each adapter's stream is forwarded into a shared channel, so adapters
     /// added later via `register_and_start` feed into the same stream.
     /// Adapters that fail to start (e.g. due to network not being ready) are
-    /// retried in the background with exponential backoff.
+    /// retried in the background by the supervisor, with exponential backoff.
     pub async fn start(&self) -> crate::Result<InboundStream> {
         let adapters = self.adapters.read().await;
         for (name, adapter) in adapters.iter() {
             match adapter.start().await {
-                Ok(stream) => Self::spawn_forwarder(name.clone(), stream, self.fan_in_tx.clone()),
+                Ok(stream) => Self::supervise_adapter(
+                    name.clone(),
+                    Arc::clone(adapter),
+                    Some(stream),
+                    self.fan_in_tx.clone(),
+                ),
                 Err(error) => {
                     tracing::warn!(
                         adapter = %name,
                         %error,
                         "adapter failed to start, will retry in background"
                     );
-                    Self::spawn_retry_task(
+                    Self::supervise_adapter(
                         name.clone(),
                         Arc::clone(adapter),
+                        None,
                         self.fan_in_tx.clone(),
                     );
                 }
@@ -100,6 +114,7 @@ impl MessagingManager {
             let adapters = self.adapters.read().await;
             if let Some(existing) = adapters.get(&name) {
                 tracing::info!(adapter = %name, "shutting down existing adapter before replacement");
+                Supervisor::global().stop(ProcessKind::Adapter, None, &name);
                 if let Err(error) = existing.shutdown().await {
                     tracing::warn!(adapter = %name, %error, "failed to shut down existing adapter");
                 }
@@ -112,7 +127,12 @@ impl MessagingManager {
             .start()
             .await
             .with_context(|| format!("failed to start adapter '{name}'"))?;
-        Self::spawn_forwarder(name.clone(), stream, self.fan_in_tx.clone());
+        Self::supervise_adapter(
+            name.clone(),
+            adapter.clone(),
+            Some(stream),
+            self.fan_in_tx.clone(),
+        );
 
         self.adapters.write().await.insert(name.clone(), adapter);
 
@@ -140,69 +160,84 @@ impl MessagingManager {
         self.adapters.read().await.keys().cloned().collect()
     }
 
-    /// Spawn a background task that retries starting a failed adapter with exponential backoff.
+    /// Run every adapter's health check concurrently, each bounded by
+    /// `timeout`. Results are sorted by adapter name.
+    pub async fn health_check_all(
+        &self,
+        timeout: std::time::Duration,
+    ) -> Vec<(String, std::result::Result<(), String>)> {
+        let adapters: Vec<(String, Arc<dyn MessagingDyn>)> = self
+            .adapters
+            .read()
+            .await
+            .iter()
+            .map(|(name, adapter)| (name.clone(), adapter.clone()))
+            .collect();
+
+        let checks = adapters.into_iter().map(|(name, adapter)| async move {
+            let result = match tokio::time::timeout(timeout, adapter.health_check()).await {
+                Ok(Ok(())) => Ok(()),
+                Ok(Err(error)) => Err(error.to_string()),
+                Err(_) => Err(format!("no answer within {}s", timeout.as_secs())),
+            };
+            (name, result)
+        });
+        let mut results = futures::future::join_all(checks).await;
+        results.sort_by(|left, right| left.0.cmp(&right.0));
+        results
+    }
+
+    /// Forward an adapter's inbound stream into the fan-in channel under the
+    /// supervisor.
     ///
-    /// Once the adapter starts successfully, its stream is forwarded into the
-    /// existing fan-in channel — the same mechanism used by `register_and_start`.
-    fn spawn_retry_task(
+    /// The first run forwards `stream` when the adapter is already started.
+    /// Every restart, and a first run without a stream, starts the adapter
+    /// again. A failed start or an ended stream counts as a crash, so the
+    /// supervisor retries with backoff until the adapter stays up.
+    fn supervise_adapter(
         name: String,
         adapter: Arc<dyn MessagingDyn>,
+        stream: Option<InboundStream>,
         fan_in_tx: mpsc::Sender<InboundMessage>,
     ) {
-        tokio::spawn(async move {
-            let mut delay = std::time::Duration::from_secs(5);
-            let max_delay = std::time::Duration::from_secs(60);
-
-            for attempt in 1..=Self::MAX_RETRY_ATTEMPTS {
-                tokio::time::sleep(delay).await;
-
-                match adapter.start().await {
-                    Ok(stream) => {
-                        tracing::info!(
-                            adapter = %name,
-                            attempt,
-                            "adapter started successfully after retry"
-                        );
-                        Self::spawn_forwarder(name, stream, fan_in_tx);
-                        return;
-                    }
-                    Err(error) => {
-                        tracing::warn!(
-                            adapter = %name,
-                            attempt,
-                            max_attempts = Self::MAX_RETRY_ATTEMPTS,
-                            %error,
-                            "adapter retry failed, next attempt in {:?}",
-                            delay.min(max_delay)
-                        );
+        let initial_stream = Arc::new(std::sync::Mutex::new(stream));
+        let spec = ProcessSpec::new(ProcessKind::Adapter, name.clone());
+        Supervisor::global().supervise(spec, move || {
+            let name = name.clone();
+            let adapter = adapter.clone();
+            let fan_in_tx = fan_in_tx.clone();
+            let initial_stream = initial_stream
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .take();
+            tokio::spawn(async move {
+                let mut stream = match initial_stream {
+                    Some(stream) => stream,
+                    None => match adapter.start().await {
+                        Ok(stream) => {
+                            tracing::info!(adapter = %name, "adapter started");
+                            stream
+                        }
+                        Err(error) => {
+                            tracing::warn!(adapter = %name, %error, "adapter failed to start");
+                            return;
+                        }
+                    },
+                };
+
+                while let Some(message) = stream.next().await {
+                    if fan_in_tx.send(message).await.is_err() {
+                        tracing::warn!(adapter = %name, "fan-in channel closed, stopping forwarder");
+                        break;
                     }
                 }
+                tracing::warn!(adapter = %name, "adapter stream ended");
 
-                delay = (delay * 2).min(max_delay);
-            }
-
-            tracing::error!(
-                adapter = %name,
-                "adapter failed to start after {} attempts, giving up",
-                Self::MAX_RETRY_ATTEMPTS
-            );
-        });
-    }
-
-    /// Spawn a task that forwards messages from an adapter stream into the fan-in channel.
-    fn spawn_forwarder(
-        name: String,
-        mut stream: InboundStream,
-        fan_in_tx: mpsc::Sender<InboundMessage>,
-    ) {
-        tokio::spawn(async move {
-            while let Some(message) = stream.next().await {
-                if fan_in_tx.send(message).await.is_err() {
-                    tracing::warn!(adapter = %name, "fan-in channel closed, stopping forwarder");
-                    break;
+                // Release the connection before the supervisor starts it again.
+                if let Err(error) = adapter.shutdown().await {
+                    tracing::warn!(adapter = %name, %error, "failed to shut down ended adapter");
                 }
-            }
-            tracing::info!(adapter = %name, "adapter stream ended");
+            })
         });
     }
 
@@ -228,6 +263,21 @@ impl MessagingManager {
         adapter.respond(message, response).await
     }
 
+    /// Route a response to the correct adapter and return the platform
+    /// message ID it was delivered as, when the adapter reports one.
+    pub async fn respond_tracked(
+        &self,
+        message: &InboundMessage,
+        response: OutboundResponse,
+    ) -> crate::Result<Option<String>> {
+        let adapters = self.adapters.read().await;
+        let adapter_key = message.adapter_key();
+        let adapter = adapters
+            .get(adapter_key)
+            .with_context(|| format!("no messaging adapter named '{}'", adapter_key))?;
+        adapter.respond_tracked(message, response).await
+    }
+
     /// Route a status update to the correct adapter.
     pub async fn send_status(
         &self,
@@ -270,10 +320,47 @@ impl MessagingManager {
         adapter.fetch_history(message, limit).await
     }
 
+    /// Whether the named adapter can join voice channels.
+    pub async fn supports_voice(&self, adapter_name: &str) -> bool {
+        let adapters = self.adapters.read().await;
+        adapters
+            .get(adapter_name)
+            .is_some_and(|adapter| adapter.supports_voice())
+    }
+
+    /// Join a voice channel through a specific adapter, for `conversation_id`.
+    pub async fn join_voice(
+        &self,
+        adapter_name: &str,
+        conversation_id: &str,
+        voice_channel: &str,
+    ) -> crate::Result<String> {
+        let adapters = self.adapters.read().await;
+        let adapter = adapters
+            .get(adapter_name)
+            .with_context(|| format!("no messaging adapter named '{adapter_name}'"))?;
+        adapter.join_voice(conversation_id, voice_channel).await
+    }
+
+    /// Leave the voice channel a specific adapter joined for `conversation_id`.
+    pub async fn leave_voice(
+        &self,
+        adapter_name: &str,
+        conversation_id: &str,
+    ) -> crate::Result<bool> {
+        let adapters = self.adapters.read().await;
+        let adapter = adapters
+            .get(adapter_name)
+            .with_context(|| format!("no messaging adapter named '{adapter_name}'"))?;
+        adapter.leave_voice(conversation_id).await
+    }
+
     /// Remove and shut down a single adapter by name.
     pub async fn remove_adapter(&self, name: &str) -> crate::Result<()> {
         let adapter = self.adapters.write().await.remove(name);
+        self.instance_permissions.remove(name);
         if let Some(adapter) = adapter {
+            Supervisor::global().stop(ProcessKind::Adapter, None, name);
             adapter.shutdown().await?;
             tracing::info!(adapter = %name, "adapter removed and shut down");
         }
@@ -304,6 +391,7 @@ impl MessagingManager {
     pub async fn shutdown(&self) {
         let adapters = self.adapters.read().await;
         for (name, adapter) in adapters.iter() {
+            Supervisor::global().stop(ProcessKind::Adapter, None, name);
             if let Err(error) = adapter.shutdown().await {
                 tracing::warn!(adapter = %name, %error, "failed to shut down adapter");
             }
@@ -0,0 +1,947 @@
+//! Matrix messaging adapter using matrix-sdk.
+//!
+//! Each joined room is one conversation (`matrix:!room:server`). The client
+//! keeps a SQLite state and crypto store under the instance directory so the
+//! bot's device identity and room keys survive restarts, which is what lets it
+//! read and reply in end-to-end encrypted rooms. Typing notices and read
+//! receipts are driven from the channel's status updates.
+
+use crate::config::MatrixConfig;
+use crate::messaging::traits::{HistoryMessage, InboundStream, Messaging};
+use crate::{Attachment, InboundMessage, MessageContent, OutboundResponse, StatusUpdate};
+
+use anyhow::Context as _;
+use base64::Engine as _;
+use matrix_sdk::attachment::AttachmentConfig;
+use matrix_sdk::authentication::matrix::MatrixSession;
+use matrix_sdk::config::SyncSettings;
+use matrix_sdk::media::MediaEventContent;
+use matrix_sdk::room::MessagesOptions;
+use matrix_sdk::ruma::api::client::receipt::create_receipt::v3::ReceiptType;
+use matrix_sdk::ruma::events::AnySyncMessageLikeEvent;
+use matrix_sdk::ruma::events::AnySyncTimelineEvent;
+use matrix_sdk::ruma::events::SyncMessageLikeEvent;
+use matrix_sdk::ruma::events::reaction::ReactionEventContent;
+use matrix_sdk::ruma::events::receipt::ReceiptThread;
+use matrix_sdk::ruma::events::relation::Annotation;
+use matrix_sdk::ruma::events::room::member::{MembershipState, StrippedRoomMemberEvent};
+use matrix_sdk::ruma::events::room::message::{
+    MessageType, OriginalSyncRoomMessageEvent, Relation, RoomMessageEventContent,
+};
+use matrix_sdk::ruma::{EventId, OwnedEventId, OwnedUserId, RoomId, UserId};
+use matrix_sdk::{Client, Room, RoomState};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{RwLock, mpsc, watch};
+use tokio::task::JoinHandle;
+
+/// Matrix caps events at 64 KiB; keep text chunks well below that once the
+/// HTML rendering of the markdown is added alongside the plain body.
+const MAX_MESSAGE_LENGTH: usize = 16_000;
+
+/// Typing notices expire after a few seconds, so they are refreshed on this
+/// interval while the agent is working.
+const TYPING_REFRESH_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Maximum backoff between failed sync attempts.
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(300);
+
+/// File name of the persisted login session inside the store directory.
+const SESSION_FILE: &str = "session.json";
+
+/// Settings shared with the sync event handlers.
+struct HandlerContext {
+    runtime_key: String,
+    bot_user_id: OwnedUserId,
+    allowed_rooms: Vec<String>,
+    allowed_users: Vec<String>,
+    auto_join: bool,
+    max_attachment_bytes: usize,
+    inbound_tx: mpsc::Sender<InboundMessage>,
+}
+
+/// Matrix adapter state.
+pub struct MatrixAdapter {
+    runtime_key: String,
+    homeserver_url: String,
+    user_id: String,
+    password: String,
+    device_name: String,
+    store_dir: PathBuf,
+    allowed_rooms: Vec<String>,
+    allowed_users: Vec<String>,
+    auto_join: bool,
+    max_attachment_bytes: usize,
+    client: Arc<RwLock<Option<Client>>>,
+    /// Repeating typing notice tasks per conversation_id.
+    typing_tasks: Arc<RwLock<HashMap<String, JoinHandle<()>>>>,
+    shutdown_tx: Arc<RwLock<Option<watch::Sender<bool>>>>,
+    sync_task: Arc<RwLock<Option<JoinHandle<()>>>>,
+}
+
+impl std::fmt::Debug for MatrixAdapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MatrixAdapter")
+            .field("runtime_key", &self.runtime_key)
+            .field("homeserver_url", &self.homeserver_url)
+            .field("user_id", &self.user_id)
+            .field("password", &"[REDACTED]")
+            .field("store_dir", &self.store_dir)
+            .field("allowed_rooms", &self.allowed_rooms)
+            .field("allowed_users", &self.allowed_users)
+            .finish()
+    }
+}
+
+impl MatrixAdapter {
+    pub fn from_config(config: &MatrixConfig, instance_dir: &std::path::Path) -> Self {
+        Self {
+            runtime_key: "matrix".into(),
+            homeserver_url: config.homeserver_url.clone(),
+            user_id: config.user_id.clone(),
+            password: config.password.clone(),
+            device_name: config.device_name.clone(),
+            store_dir: instance_dir.join("matrix"),
+            allowed_rooms: config.allowed_rooms.clone(),
+            allowed_users: config.allowed_users.clone(),
+            auto_join: config.auto_join,
+            max_attachment_bytes: config.max_attachment_bytes,
+            client: Arc::new(RwLock::new(None)),
+            typing_tasks: Arc::new(RwLock::new(HashMap::new())),
+            shutdown_tx: Arc::new(RwLock::new(None)),
+            sync_task: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    async fn client(&self) -> anyhow::Result<Client> {
+        self.client
+            .read()
+            .await
+            .clone()
+            .context("matrix adapter not started")
+    }
+
+    /// Resolve the joined room an inbound message belongs to.
+    async fn room_of(&self, message: &InboundMessage) -> anyhow::Result<Room> {
+        let room_id = message
+            .metadata
+            .get("matrix_room_id")
+            .and_then(|value| value.as_str())
+            .map(str::to_string)
+            .or_else(|| {
+                message
+                    .conversation_id
+                    .strip_prefix("matrix:")
+                    .map(str::to_string)
+            })
+            .with_context(|| format!("no matrix room for '{}'", message.conversation_id))?;
+        self.joined_room(&room_id).await
+    }
+
+    async fn joined_room(&self, room_id: &str) -> anyhow::Result<Room> {
+        let room_id = RoomId::parse(room_id)
+            .with_context(|| format!("invalid matrix room id '{room_id}'"))?;
+        let room = self
+            .client()
+            .await?
+            .get_room(&room_id)
+            .with_context(|| format!("matrix room {room_id} is not known to this account"))?;
+        if room.state() != RoomState::Joined {
+            anyhow::bail!("matrix room {room_id} is not joined");
+        }
+        Ok(room)
+    }
+
+    /// Log in, restoring the persisted session when one exists so the device
+    /// ID (and with it the E2EE identity) stays stable across restarts.
+    async fn connect(&self) -> anyhow::Result<Client> {
+        tokio::fs::create_dir_all(&self.store_dir)
+            .await
+            .with_context(|| format!("failed to create {}", self.store_dir.display()))?;
+
+        let client = Client::builder()
+            .homeserver_url(&self.homeserver_url)
+            .sqlite_store(&self.store_dir, None)
+            .build()
+            .await
+            .context("failed to build matrix client")?;
+
+        let session_path = self.store_dir.join(SESSION_FILE);
+        let restored = match tokio::fs::read_to_string(&session_path).await {
+            Ok(raw) => match serde_json::from_str::<MatrixSession>(&raw) {
+                Ok(session) if session.meta.user_id.as_str() == self.user_id => {
+                    match client.restore_session(session).await {
+                        Ok(()) => true,
+                        Err(error) => {
+                            tracing::warn!(%error, "failed to restore matrix session, logging in again");
+                            false
+                        }
+                    }
+                }
+                Ok(_) => {
+                    tracing::info!(
+                        "stored matrix session belongs to another user, logging in again"
+                    );
+                    false
+                }
+                Err(error) => {
+                    tracing::warn!(%error, "stored matrix session is unreadable, logging in again");
+                    false
+                }
+            },
+            Err(_) => false,
+        };
+
+        if !restored {
+            client
+                .matrix_auth()
+                .login_username(&self.user_id, &self.password)
+                .initial_device_display_name(&self.device_name)
+                .await
+                .context("matrix login failed")?;
+
+            if let Some(session) = client.matrix_auth().session() {
+                let raw = serde_json::to_string(&session)
+                    .context("failed to serialize matrix session")?;
+                tokio::fs::write(&session_path, raw)
+                    .await
+                    .with_context(|| format!("failed to write {}", session_path.display()))?;
+            }
+        }
+
+        Ok(client)
+    }
+
+    async fn stop_typing(&self, conversation_id: &str) {
+        if let Some(handle) = self.typing_tasks.write().await.remove(conversation_id) {
+            handle.abort();
+        }
+    }
+
+    async fn send_text(&self, room: &Room, text: &str) -> anyhow::Result<Option<String>> {
+        let mut last_event_id = None;
+        for chunk in split_message(text, MAX_MESSAGE_LENGTH) {
+            let response = room
+                .send(RoomMessageEventContent::text_markdown(chunk))
+                .await
+                .context("failed to send matrix message")?;
+            last_event_id = Some(response.event_id.to_string());
+        }
+        Ok(last_event_id)
+    }
+
+    async fn send_file(
+        &self,
+        room: &Room,
+        filename: &str,
+        data: Vec<u8>,
+        mime_type: &str,
+        caption: Option<&str>,
+    ) -> anyhow::Result<Option<String>> {
+        if data.len() > self.max_attachment_bytes {
+            anyhow::bail!(
+                "attachment '{filename}' exceeds max_attachment_bytes ({} > {})",
+                data.len(),
+                self.max_attachment_bytes
+            );
+        }
+        if let Some(caption) = caption.filter(|caption| !caption.trim().is_empty()) {
+            self.send_text(room, caption).await?;
+        }
+        let content_type: mime::Mime = mime_type.parse().unwrap_or(mime::APPLICATION_OCTET_STREAM);
+        let response = room
+            .send_attachment(filename, &content_type, data, AttachmentConfig::new())
+            .await
+            .context("failed to upload matrix attachment")?;
+        Ok(Some(response.event_id.to_string()))
+    }
+}
+
+impl Messaging for MatrixAdapter {
+    fn name(&self) -> &str {
+        &self.runtime_key
+    }
+
+    async fn start(&self) -> crate::Result<InboundStream> {
+        if self.sync_task.read().await.is_some() {
+            return Err(anyhow::anyhow!("matrix adapter already started").into());
+        }
+
+        let client = self.connect().await?;
+        let bot_user_id = client
+            .user_id()
+            .context("matrix client has no user id after login")?
+            .to_owned();
+
+        // Initial sync establishes the room list and the point to resume from;
+        // messages sent while the adapter was down are not replayed.
+        let initial = client
+            .sync_once(SyncSettings::default())
+            .await
+            .context("initial matrix sync failed")?;
+
+        let (inbound_tx, inbound_rx) = mpsc::channel(256);
+        let (shutdown_tx, mut shutdown_rx) = watch::channel(false);
+        *self.shutdown_tx.write().await = Some(shutdown_tx);
+
+        let context = Arc::new(HandlerContext {
+            runtime_key: self.runtime_key.clone(),
+            bot_user_id: bot_user_id.clone(),
+            allowed_rooms: self.allowed_rooms.clone(),
+            allowed_users: self.allowed_users.clone(),
+            auto_join: self.auto_join,
+            max_attachment_bytes: self.max_attachment_bytes,
+            inbound_tx,
+        });
+
+        let message_context = context.clone();
+        client.add_event_handler(
+            move |event: OriginalSyncRoomMessageEvent, room: Room, client: Client| {
+                let context = message_context.clone();
+                async move {
+                    handle_room_message(&context, &client, room, event).await;
+                }
+            },
+        );
+
+        let invite_context = context.clone();
+        client.add_event_handler(move |event: StrippedRoomMemberEvent, room: Room| {
+            let context = invite_context.clone();
+            async move {
+                handle_invite(&context, room, event).await;
+            }
+        });
+
+        *self.client.write().await = Some(client.clone());
+        tracing::info!(user_id = %bot_user_id, homeserver = %self.homeserver_url, "matrix connected");
+
+        let sync_task = tokio::spawn(async move {
+            let mut settings = SyncSettings::default().token(initial.next_batch);
+            let mut retry_backoff = Duration::from_secs(5);
+
+            loop {
+                let result = tokio::select! {
+                    _ = shutdown_rx.changed() => {
+                        if *shutdown_rx.borrow() {
+                            break;
+                        }
+                        continue;
+                    }
+                    result = client.sync(settings.clone()) => result,
+                };
+
+                // `sync` only returns on error; the client keeps its own sync
+                // token, so a fresh settings value resumes where it left off.
+                if let Err(error) = result {
+                    tracing::warn!(%error, backoff_secs = retry_backoff.as_secs(), "matrix sync failed");
+                }
+                settings = SyncSettings::default();
+
+                tokio::select! {
+                    _ = shutdown_rx.changed() => {
+                        if *shutdown_rx.borrow() {
+                            break;
+                        }
+                    }
+                    _ = tokio::time::sleep(retry_backoff) => {}
+                }
+                retry_backoff = (retry_backoff * 2).min(MAX_RETRY_BACKOFF);
+            }
+
+            tracing::info!("matrix sync loop stopped");
+        });
+
+        *self.sync_task.write().await = Some(sync_task);
+
+        let stream = tokio_stream::wrappers::ReceiverStream::new(inbound_rx);
+        Ok(Box::pin(stream))
+    }
+
+    async fn respond(
+        &self,
+        message: &InboundMessage,
+        response: OutboundResponse,
+    ) -> crate::Result<()> {
+        self.respond_tracked(message, response).await?;
+        Ok(())
+    }
+
+    async fn respond_tracked(
+        &self,
+        message: &InboundMessage,
+        response: OutboundResponse,
+    ) -> crate::Result<Option<String>> {
+        let room = self.room_of(message).await?;
+
+        match response {
+            OutboundResponse::Text(text)
+            | OutboundResponse::RichMessage { text, .. }
+            | OutboundResponse::ThreadReply { text, .. }
+            | OutboundResponse::Ephemeral { text, .. }
+            | OutboundResponse::ScheduledMessage { text, .. } => {
+                self.stop_typing(&message.conversation_id).await;
+                Ok(self.send_text(&room, &text).await?)
+            }
+            OutboundResponse::File {
+                filename,
+                data,
+                mime_type,
+                caption,
+            } => {
+                self.stop_typing(&message.conversation_id).await;
+                Ok(self
+                    .send_file(&room, &filename, data, &mime_type, caption.as_deref())
+                    .await?)
+            }
+            OutboundResponse::Reaction(emoji) => {
+                let Some(event_id) = inbound_event_id(message) else {
+                    return Ok(None);
+                };
+                room.send(ReactionEventContent::new(Annotation::new(event_id, emoji)))
+                    .await
+                    .context("failed to send matrix reaction")?;
+                Ok(None)
+            }
+            OutboundResponse::Delete { message_id } => {
+                let event_id = EventId::parse(&message_id)
+                    .with_context(|| format!("invalid matrix event id '{message_id}'"))?;
+                room.redact(&event_id, None, None)
+                    .await
+                    .context("failed to redact matrix message")?;
+                Ok(None)
+            }
+            OutboundResponse::Status(status) => {
+                self.send_status(message, status).await?;
+                Ok(None)
+            }
+            // Edits and streaming are not wired up; the final text still
+            // arrives as a regular Text response.
+            OutboundResponse::Edit { .. }
+            | OutboundResponse::RemoveReaction(_)
+            | OutboundResponse::StreamStart
+            | OutboundResponse::StreamChunk(_)
+            | OutboundResponse::StreamEnd => Ok(None),
+        }
+    }
+
+    async fn send_status(
+        &self,
+        message: &InboundMessage,
+        status: StatusUpdate,
+    ) -> crate::Result<()> {
+        match status {
+            StatusUpdate::Thinking => {
+                let room = self.room_of(message).await?;
+
+                // The agent has picked the message up: mark it read.
+                if let Some(event_id) = inbound_event_id(message)
+                    && let Err(error) = room
+                        .send_single_receipt(ReceiptType::Read, ReceiptThread::Unthreaded, event_id)
+                        .await
+                {
+                    tracing::debug!(%error, "failed to send matrix read receipt");
+                }
+
+                let typing_room = room.clone();
+                let handle = tokio::spawn(async move {
+                    loop {
+                        if let Err(error) = typing_room.typing_notice(true).await {
+                            tracing::debug!(%error, "failed to send matrix typing notice");
+                            break;
+                        }
+                        tokio::time::sleep(TYPING_REFRESH_INTERVAL).await;
+                    }
+                });
+
+                if let Some(previous) = self
+                    .typing_tasks
+                    .write()
+                    .await
+                    .insert(message.conversation_id.clone(), handle)
+                {
+                    previous.abort();
+                }
+            }
+            _ => {
+                self.stop_typing(&message.conversation_id).await;
+                if let Ok(room) = self.room_of(message).await
+                    && let Err(error) = room.typing_notice(false).await
+                {
+                    tracing::debug!(%error, "failed to clear matrix typing notice");
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn broadcast(&self, target: &str, response: OutboundResponse) -> crate::Result<()> {
+        let room = self.joined_room(target).await?;
+
+        match response {
+            OutboundResponse::Text(text)
+            | OutboundResponse::RichMessage { text, .. }
+            | OutboundResponse::ThreadReply { text, .. } => {
+                self.send_text(&room, &text).await?;
+            }
+            OutboundResponse::File {
+                filename,
+                data,
+                mime_type,
+                caption,
+            } => {
+                self.send_file(&room, &filename, data, &mime_type, caption.as_deref())
+                    .await?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    async fn fetch_history(
+        &self,
+        message: &InboundMessage,
+        limit: usize,
+    ) -> crate::Result<Vec<HistoryMessage>> {
+        let room = self.room_of(message).await?;
+        let bot_user_id = self.client().await?.user_id().map(ToOwned::to_owned);
+
+        let mut options = MessagesOptions::backward();
+        options.limit = u32::try_from(limit.saturating_add(1))
+            .unwrap_or(u32::MAX)
+            .into();
+        let messages = room
+            .messages(options)
+            .await
+            .context("failed to fetch matrix room history")?;
+
+        let mut history = Vec::with_capacity(messages.chunk.len());
+        for timeline_event in messages.chunk {
+            let Ok(AnySyncTimelineEvent::MessageLike(AnySyncMessageLikeEvent::RoomMessage(
+                SyncMessageLikeEvent::Original(event),
+            ))) = timeline_event.raw().deserialize()
+            else {
+                continue;
+            };
+            // The triggering message is delivered as the message itself.
+            if event.event_id.as_str() == message.id {
+                continue;
+            }
+            let Some(content) = message_body(&event.content.msgtype) else {
+                continue;
+            };
+            let author = match room.get_member_no_sync(&event.sender).await {
+                Ok(Some(member)) => member
+                    .display_name()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| event.sender.to_string()),
+                _ => event.sender.to_string(),
+            };
+            history.push(HistoryMessage {
+                is_bot: bot_user_id.as_deref() == Some(event.sender.as_ref()),
+                author,
+                content,
+                timestamp: chrono::DateTime::from_timestamp_millis(i64::from(
+                    event.origin_server_ts.get(),
+                )),
+            });
+        }
+
+        // `messages` pages backwards from the newest event.
+        history.reverse();
+        let skip = history.len().saturating_sub(limit);
+        Ok(history.into_iter().skip(skip).collect())
+    }
+
+    async fn health_check(&self) -> crate::Result<()> {
+        let client = self.client().await?;
+        client
+            .whoami()
+            .await
+            .context("matrix health check failed")?;
+        Ok(())
+    }
+
+    async fn shutdown(&self) -> crate::Result<()> {
+        for (_, handle) in self.typing_tasks.write().await.drain() {
+            handle.abort();
+        }
+
+        if let Some(shutdown_tx) = self.shutdown_tx.write().await.take() {
+            shutdown_tx.send(true).ok();
+        }
+
+        if let Some(sync_task) = self.sync_task.write().await.take()
+            && let Err(error) = sync_task.await
+        {
+            tracing::warn!(%error, "matrix sync task join failed during shutdown");
+        }
+
+        self.client.write().await.take();
+        tracing::info!("matrix adapter shut down");
+        Ok(())
+    }
+}
+
+// ── Event handling ──────────────────────────────────────────────
+
+/// Accept invites from allowed users into allowed rooms.
+async fn handle_invite(context: &HandlerContext, room: Room, event: StrippedRoomMemberEvent) {
+    if event.state_key != context.bot_user_id
+        || event.content.membership != MembershipState::Invite
+        || !context.auto_join
+    {
+        return;
+    }
+    if !is_allowed(room.room_id().as_str(), &context.allowed_rooms)
+        || !is_allowed(event.sender.as_str(), &context.allowed_users)
+    {
+        tracing::info!(room_id = %room.room_id(), inviter = %event.sender, "ignoring matrix invite");
+        return;
+    }
+
+    // The server may not have finished processing the invite yet; retry a
+    // few times with backoff before giving up.
+    let mut delay = Duration::from_secs(2);
+    for attempt in 1..=4 {
+        match room.join().await {
+            Ok(_) => {
+                tracing::info!(room_id = %room.room_id(), "joined matrix room");
+                return;
+            }
+            Err(error) => {
+                tracing::debug!(%error, attempt, room_id = %room.room_id(), "matrix join failed");
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+        }
+    }
+    tracing::warn!(room_id = %room.room_id(), "giving up on matrix invite");
+}
+
+async fn handle_room_message(
+    context: &HandlerContext,
+    client: &Client,
+    room: Room,
+    event: OriginalSyncRoomMessageEvent,
+) {
+    if room.state() != RoomState::Joined || event.sender == context.bot_user_id {
+        return;
+    }
+    // Edits arrive as new events carrying `m.replace`; the original was
+    // already delivered.
+    if matches!(event.content.relates_to, Some(Relation::Replacement(_))) {
+        return;
+    }
+    if !is_allowed(room.room_id().as_str(), &context.allowed_rooms)
+        || !is_allowed(event.sender.as_str(), &context.allowed_users)
+    {
+        return;
+    }
+
+    let text = message_body(&event.content.msgtype);
+    let attachment = match download_attachment(client, &event.content.msgtype, context).await {
+        Ok(attachment) => attachment,
+        Err(error) => {
+            tracing::warn!(%error, event_id = %event.event_id, "failed to download matrix media");
+            None
+        }
+    };
+
+    let content = match attachment {
+        Some(attachment) => MessageContent::Media {
+            text: caption_of(&event.content.msgtype),
+            attachments: vec![attachment],
+        },
+        None => match text {
+            Some(text) => MessageContent::Text(text),
+            None => return,
+        },
+    };
+
+    let sender_display_name = match room.get_member_no_sync(&event.sender).await {
+        Ok(Some(member)) => member.display_name().map(str::to_string),
+        _ => None,
+    };
+    let room_name = room.name();
+    let is_direct = room.is_direct().await.unwrap_or(false);
+    let is_encrypted = room.is_encrypted().await.unwrap_or(false);
+    let raw_body = match &content {
+        MessageContent::Text(text) => text.clone(),
+        MessageContent::Media { text, .. } => text.clone().unwrap_or_default(),
+        MessageContent::Interaction { .. } | MessageContent::Command { .. } => String::new(),
+    };
+    let mentioned = event
+        .content
+        .mentions
+        .as_ref()
+        .is_some_and(|mentions| mentions.user_ids.contains(&context.bot_user_id))
+        || mentions_user(&raw_body, &context.bot_user_id);
+
+    let room_id = room.room_id().to_string();
+    let mut metadata = HashMap::new();
+    metadata.insert(
+        "matrix_room_id".into(),
+        serde_json::Value::from(room_id.clone()),
+    );
+    metadata.insert(
+        "matrix_event_id".into(),
+        serde_json::Value::from(event.event_id.to_string()),
+    );
+    metadata.insert(
+        "matrix_sender".into(),
+        serde_json::Value::from(event.sender.to_string()),
+    );
+    metadata.insert(
+        "matrix_is_direct".into(),
+        serde_json::Value::from(is_direct),
+    );
+    metadata.insert(
+        "matrix_is_encrypted".into(),
+        serde_json::Value::from(is_encrypted),
+    );
+    metadata.insert(
+        "matrix_mentions_or_replies_to_bot".into(),
+        serde_json::Value::from(mentioned),
+    );
+    metadata.insert(
+        crate::metadata_keys::MESSAGE_ID.into(),
+        serde_json::Value::from(event.event_id.to_string()),
+    );
+    if let Some(room_name) = &room_name {
+        metadata.insert(
+            crate::metadata_keys::CHANNEL_NAME.into(),
+            serde_json::Value::from(room_name.clone()),
+        );
+    }
+    metadata.insert(
+        "sender_display_name".into(),
+        serde_json::Value::from(
+            sender_display_name
+                .clone()
+                .unwrap_or_else(|| event.sender.to_string()),
+        ),
+    );
+
+    let formatted_author = match &sender_display_name {
+        Some(name) => format!("{name} ({})", event.sender),
+        None => event.sender.to_string(),
+    };
+
+    let message = InboundMessage {
+        id: event.event_id.to_string(),
+        source: "matrix".into(),
+        adapter: Some(context.runtime_key.clone()),
+        conversation_id: format!("matrix:{room_id}"),
+        sender_id: event.sender.to_string(),
+        agent_id: None,
+        content,
+        timestamp: chrono::DateTime::from_timestamp_millis(i64::from(event.origin_server_ts.get()))
+            .unwrap_or_else(chrono::Utc::now),
+        metadata,
+        formatted_author: Some(formatted_author),
+    };
+
+    if context.inbound_tx.send(message).await.is_err() {
+        tracing::warn!("matrix inbound channel closed, dropping message");
+    }
+}
+
+/// Download media attached to a message through the SDK, which also handles
+/// decrypting files posted in encrypted rooms. The bytes are handed to the
+/// channel as a `data:` URL since Matrix media URLs need the client's auth.
+async fn download_attachment(
+    client: &Client,
+    msgtype: &MessageType,
+    context: &HandlerContext,
+) -> anyhow::Result<Option<Attachment>> {
+    let (filename, mime_type, size, bytes) = match msgtype {
+        MessageType::Image(content) => (
+            content.filename().to_string(),
+            content.info.as_ref().and_then(|info| info.mimetype.clone()),
+            content.info.as_ref().and_then(|info| info.size),
+            fetch_media(client, content.clone(), context).await?,
+        ),
+        MessageType::File(content) => (
+            content.filename().to_string(),
+            content.info.as_ref().and_then(|info| info.mimetype.clone()),
+            content.info.as_ref().and_then(|info| info.size),
+            fetch_media(client, content.clone(), context).await?,
+        ),
+        MessageType::Audio(content) => (
+            content.filename().to_string(),
+            content.info.as_ref().and_then(|info| info.mimetype.clone()),
+            content.info.as_ref().and_then(|info| info.size),
+            fetch_media(client, content.clone(), context).await?,
+        ),
+        MessageType::Video(content) => (
+            content.filename().to_string(),
+            content.info.as_ref().and_then(|info| info.mimetype.clone()),
+            content.info.as_ref().and_then(|info| info.size),
+            fetch_media(client, content.clone(), context).await?,
+        ),
+        _ => return Ok(None),
+    };
+
+    let Some(bytes) = bytes else {
+        return Ok(None);
+    };
+    let mime_type = mime_type.unwrap_or_else(|| {
+        mime_guess::from_path(&filename)
+            .first_or_octet_stream()
+            .to_string()
+    });
+    let encoded = base64::engine::general_purpose::STANDARD.encode(&bytes);
+
+    Ok(Some(Attachment {
+        filename,
+        url: format!("data:{mime_type};base64,{encoded}"),
+        mime_type,
+        size_bytes: Some(size.map(u64::from).unwrap_or(bytes.len() as u64)),
+        auth_header: None,
+    }))
+}
+
+/// Fetch media bytes unless the advertised or actual size exceeds the limit.
+async fn fetch_media(
+    client: &Client,
+    content: impl MediaEventContent,
+    context: &HandlerContext,
+) -> anyhow::Result<Option<Vec<u8>>> {
+    let bytes = client
+        .media()
+        .get_file(&content, true)
+        .await
+        .context("matrix media download failed")?;
+    Ok(bytes.filter(|bytes| {
+        let within_limit = bytes.len() <= context.max_attachment_bytes;
+        if !within_limit {
+            tracing::info!(
+                size = bytes.len(),
+                limit = context.max_attachment_bytes,
+                "skipping oversized matrix attachment"
+            );
+        }
+        within_limit
+    }))
+}
+
+/// Text the agent should see for a message, if it carries any.
+fn message_body(msgtype: &MessageType) -> Option<String> {
+    let body = match msgtype {
+        MessageType::Text(content) => content.body.clone(),
+        MessageType::Notice(content) => content.body.clone(),
+        MessageType::Emote(content) => format!("* {}", content.body),
+        MessageType::Image(content) => format!("[image: {}]", content.body),
+        MessageType::File(content) => format!("[file: {}]", content.body),
+        MessageType::Audio(content) => format!("[audio: {}]", content.body),
+        MessageType::Video(content) => format!("[video: {}]", content.body),
+        _ => return None,
+    };
+    let body = body.trim();
+    (!body.is_empty()).then(|| body.to_string())
+}
+
+/// Caption sent alongside media. Matrix puts it in `body` only when a
+/// separate `filename` is present; otherwise `body` is just the file name.
+fn caption_of(msgtype: &MessageType) -> Option<String> {
+    match msgtype {
+        MessageType::Image(content) => content.caption().map(str::to_string),
+        MessageType::File(content) => content.caption().map(str::to_string),
+        MessageType::Audio(content) => content.caption().map(str::to_string),
+        MessageType::Video(content) => content.caption().map(str::to_string),
+        _ => None,
+    }
+}
+
+fn inbound_event_id(message: &InboundMessage) -> Option<OwnedEventId> {
+    let raw = message
+        .metadata
+        .get("matrix_event_id")
+        .and_then(|value| value.as_str())
+        .unwrap_or(&message.id);
+    EventId::parse(raw).ok()
+}
+
+/// An empty allowlist allows everything; otherwise the value must match an
+/// entry exactly (room IDs and user IDs are case-sensitive in Matrix).
+fn is_allowed(value: &str, allowlist: &[String]) -> bool {
+    allowlist.is_empty() || allowlist.iter().any(|entry| entry == value)
+}
+
+/// Whether the plain body mentions the bot by full user ID or localpart.
+fn mentions_user(body: &str, user_id: &UserId) -> bool {
+    if body.contains(user_id.as_str()) {
+        return true;
+    }
+    let localpart = user_id.localpart().to_lowercase();
+    body.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-' || c == '.' || c == '@'))
+        .any(|word| word.trim_start_matches('@').trim_end_matches('.') == localpart)
+}
+
+/// Split a message into chunks that fit within `max_len`.
+/// Tries to split at newlines, then spaces, then hard-cuts.
+fn split_message(text: &str, max_len: usize) -> Vec<String> {
+    if text.len() <= max_len {
+        return vec![text.to_string()];
+    }
+
+    let mut chunks = Vec::new();
+    let mut remaining = text;
+
+    while !remaining.is_empty() {
+        if remaining.len() <= max_len {
+            chunks.push(remaining.to_string());
+            break;
+        }
+
+        let safe_max = {
+            let mut i = max_len.min(remaining.len());
+            while !remaining.is_char_boundary(i) {
+                i -= 1;
+            }
+            i
+        };
+
+        let split_at = remaining[..safe_max]
+            .rfind('\n')
+            .or_else(|| remaining[..safe_max].rfind(' '))
+            .unwrap_or(safe_max);
+
+        chunks.push(remaining[..split_at].to_string());
+        remaining = remaining[split_at..].trim_start();
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_allowlist_allows_everything() {
+        assert!(is_allowed("!room:example.org", &[]));
+        let allowlist = vec!["!room:example.org".to_string()];
+        assert!(is_allowed("!room:example.org", &allowlist));
+        assert!(!is_allowed("!other:example.org", &allowlist));
+    }
+
+    #[test]
+    fn mentions_match_user_id_or_localpart() {
+        let user_id = UserId::parse("@spacebot:example.org").expect("valid user id");
+        assert!(mentions_user("hey @spacebot:example.org", &user_id));
+        assert!(mentions_user("Spacebot, thoughts?", &user_id));
+        assert!(!mentions_user("ask spacebot-ci instead", &user_id));
+    }
+
+    #[test]
+    fn split_message_prefers_newlines() {
+        let chunks = split_message("first line\nsecond line", 12);
+        assert_eq!(chunks, vec!["first line", "second line"]);
+    }
+}
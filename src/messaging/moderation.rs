@@ -0,0 +1,632 @@
+//! Moderation of outbound replies.
+//!
+//! Every response a channel sends passes through its [`Moderator`] before it
+//! reaches the outbox. The agent's `[moderation]` rules are checked against
+//! the user-visible text at the channel's strictness, and in strict enough
+//! channels an LLM classifier gets a look too. A match flags the reply (it
+//! goes out unchanged), rewrites the matched text, or blocks it. Anything
+//! moderation acts on is recorded in `moderation_events`.
+//!
+//! Streamed chunks are checked by the rules against a rolling window of the
+//! stream so far, so a match split across chunks is still caught. Text
+//! already sent can't be taken back: a rewrite only replaces the part of a
+//! match in the new chunk, and a block drops the rest of the stream. The
+//! classifier only sees complete messages.
+
+use crate::config::{ModerationAction, ModerationRule, ModerationStrictness, RuntimeConfig};
+use crate::conversation::channels::ChannelStore;
+use crate::llm::{LlmManager, SpacebotModel};
+use crate::{AgentDeps, AgentId, ChannelId, DeliveryReceipt, OutboundResponse};
+
+use rig::agent::AgentBuilder;
+use rig::completion::{CompletionModel as _, Prompt as _};
+use serde::Serialize;
+use sqlx::{Row as _, SqlitePool};
+use std::sync::{Arc, Mutex};
+
+/// Bytes of already-sent stream text kept for matching across chunks.
+const STREAM_WINDOW_BYTES: usize = 512;
+
+/// What the rules made of a reply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleVerdict {
+    /// The most severe action among the matched rules.
+    pub action: ModerationAction,
+    /// Names of the matched rules.
+    pub rules: Vec<String>,
+}
+
+/// Check `text` against the rules that apply at `strictness`, applying
+/// rewrites in place. Returns `None` if no rule matched.
+pub fn apply_rules(
+    rules: &[ModerationRule],
+    strictness: ModerationStrictness,
+    text: &mut String,
+) -> Option<RuleVerdict> {
+    if strictness == ModerationStrictness::Off {
+        return None;
+    }
+
+    let mut verdict: Option<RuleVerdict> = None;
+    for rule in rules.iter().filter(|rule| rule.level <= strictness) {
+        if !rule.pattern.is_match(text) {
+            continue;
+        }
+        if rule.action == ModerationAction::Rewrite {
+            *text = rule
+                .pattern
+                .replace_all(text, rule.replacement.as_str())
+                .into_owned();
+        }
+        match &mut verdict {
+            Some(verdict) => {
+                verdict.action = verdict.action.max(rule.action);
+                verdict.rules.push(rule.name.clone());
+            }
+            None => {
+                verdict = Some(RuleVerdict {
+                    action: rule.action,
+                    rules: vec![rule.name.clone()],
+                });
+            }
+        }
+    }
+    verdict
+}
+
+/// Check a streamed `chunk` against the rules, with `sent` (the tail of the
+/// stream already delivered) in front of it. Only matches that end inside
+/// the chunk count; a rewrite replaces the part of each match that falls in
+/// the chunk.
+pub fn apply_stream_rules(
+    rules: &[ModerationRule],
+    strictness: ModerationStrictness,
+    sent: &str,
+    chunk: &mut String,
+) -> Option<RuleVerdict> {
+    if strictness == ModerationStrictness::Off {
+        return None;
+    }
+
+    let mut verdict: Option<RuleVerdict> = None;
+    for rule in rules.iter().filter(|rule| rule.level <= strictness) {
+        let window = format!("{sent}{chunk}");
+        let spans: Vec<(usize, usize)> = rule
+            .pattern
+            .find_iter(&window)
+            .filter(|found| found.end() > sent.len())
+            .map(|found| {
+                (
+                    found.start().max(sent.len()) - sent.len(),
+                    found.end() - sent.len(),
+                )
+            })
+            .collect();
+        if spans.is_empty() {
+            continue;
+        }
+        if rule.action == ModerationAction::Rewrite {
+            let mut rewritten = String::with_capacity(chunk.len());
+            let mut copied = 0;
+            for (start, end) in spans {
+                rewritten.push_str(&chunk[copied..start]);
+                rewritten.push_str(&rule.replacement);
+                copied = end;
+            }
+            rewritten.push_str(&chunk[copied..]);
+            *chunk = rewritten;
+        }
+        match &mut verdict {
+            Some(verdict) => {
+                verdict.action = verdict.action.max(rule.action);
+                verdict.rules.push(rule.name.clone());
+            }
+            None => {
+                verdict = Some(RuleVerdict {
+                    action: rule.action,
+                    rules: vec![rule.name.clone()],
+                });
+            }
+        }
+    }
+    verdict
+}
+
+/// The stream a channel is currently sending.
+#[derive(Debug, Default)]
+struct StreamState {
+    /// The last [`STREAM_WINDOW_BYTES`] of delivered text.
+    sent: String,
+    /// Set once a chunk was blocked; the rest of the stream is dropped.
+    blocked: bool,
+}
+
+impl StreamState {
+    fn push(&mut self, chunk: &str) {
+        self.sent.push_str(chunk);
+        if self.sent.len() > STREAM_WINDOW_BYTES {
+            let mut cut = self.sent.len() - STREAM_WINDOW_BYTES;
+            while !self.sent.is_char_boundary(cut) {
+                cut += 1;
+            }
+            self.sent.drain(..cut);
+        }
+    }
+}
+
+/// The user-visible text of a response, for checking and rewriting.
+fn text_fields(response: &mut OutboundResponse) -> Vec<&mut String> {
+    match response {
+        OutboundResponse::Text(text) | OutboundResponse::StreamChunk(text) => vec![text],
+        OutboundResponse::ThreadReply { thread_name, text } => vec![thread_name, text],
+        OutboundResponse::File {
+            caption: Some(caption),
+            ..
+        } => vec![caption],
+        OutboundResponse::Ephemeral { text, .. }
+        | OutboundResponse::ScheduledMessage { text, .. }
+        | OutboundResponse::Edit { text, .. } => vec![text],
+        OutboundResponse::RichMessage { text, cards, .. } => {
+            let mut fields = vec![text];
+            for card in cards {
+                fields.extend(card.title.as_mut());
+                fields.extend(card.description.as_mut());
+                fields.extend(card.footer.as_mut());
+                for field in &mut card.fields {
+                    fields.push(&mut field.name);
+                    fields.push(&mut field.value);
+                }
+            }
+            fields
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Checks a channel's outbound responses against the agent's moderation
+/// config.
+#[derive(Clone)]
+pub struct Moderator {
+    agent_id: AgentId,
+    channel_id: ChannelId,
+    runtime_config: Arc<RuntimeConfig>,
+    llm_manager: Arc<LlmManager>,
+    channel_store: ChannelStore,
+    audit: ModerationAudit,
+    stream: Arc<Mutex<StreamState>>,
+}
+
+impl Moderator {
+    pub fn new(deps: &AgentDeps, channel_id: ChannelId) -> Self {
+        Self {
+            agent_id: deps.agent_id.clone(),
+            channel_id,
+            runtime_config: deps.runtime_config.clone(),
+            llm_manager: deps.llm_manager.clone(),
+            channel_store: ChannelStore::new(deps.sqlite_pool.clone()),
+            audit: ModerationAudit::new(deps.sqlite_pool.clone()),
+            stream: Arc::default(),
+        }
+    }
+
+    /// Moderate a response. Returns what to deliver: the response itself
+    /// (possibly rewritten), the block notice in place of a blocked response,
+    /// or nothing. A blocked response's receipt is completed with an error so
+    /// the sender knows it didn't go out.
+    pub async fn moderate(
+        &self,
+        mut response: OutboundResponse,
+        receipt: Option<&DeliveryReceipt>,
+    ) -> Option<OutboundResponse> {
+        if matches!(
+            response,
+            OutboundResponse::StreamStart | OutboundResponse::StreamEnd
+        ) {
+            *self.stream.lock().expect("moderation stream lock poisoned") = StreamState::default();
+            return Some(response);
+        }
+
+        let config = self.runtime_config.moderation.load();
+        if !config.is_enabled() {
+            return Some(response);
+        }
+
+        let strictness = match self.channel_store.moderation(&self.channel_id).await {
+            Ok(strictness) => strictness.unwrap_or(config.strictness),
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %self.channel_id, "failed to load channel moderation level");
+                config.strictness
+            }
+        };
+        if strictness == ModerationStrictness::Off {
+            return Some(response);
+        }
+
+        let original = text_fields(&mut response)
+            .iter()
+            .map(|field| field.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut verdict: Option<RuleVerdict> = None;
+        if let OutboundResponse::StreamChunk(chunk) = &mut response {
+            let mut stream = self.stream.lock().expect("moderation stream lock poisoned");
+            if stream.blocked {
+                return None;
+            }
+            verdict = apply_stream_rules(&config.rules, strictness, &stream.sent, chunk);
+            if verdict
+                .as_ref()
+                .is_some_and(|verdict| verdict.action == ModerationAction::Block)
+            {
+                stream.blocked = true;
+            } else {
+                stream.push(chunk);
+            }
+        } else {
+            if original.trim().is_empty() {
+                return Some(response);
+            }
+            for field in text_fields(&mut response) {
+                if let Some(field_verdict) = apply_rules(&config.rules, strictness, field) {
+                    verdict = Some(match verdict {
+                        Some(mut verdict) => {
+                            verdict.action = verdict.action.max(field_verdict.action);
+                            verdict.rules.extend(field_verdict.rules);
+                            verdict
+                        }
+                        None => field_verdict,
+                    });
+                }
+            }
+        }
+        let mut source = "rules";
+
+        let blocked_by_rules = verdict
+            .as_ref()
+            .is_some_and(|verdict| verdict.action == ModerationAction::Block);
+        if !blocked_by_rules
+            && !matches!(response, OutboundResponse::StreamChunk(_))
+            && strictness >= config.classifier_level
+            && let Some(model_name) = config.classifier_model.as_deref()
+            && let Some(action) = self.classify(model_name, &original).await
+            && verdict
+                .as_ref()
+                .is_none_or(|verdict| action > verdict.action)
+        {
+            verdict = Some(RuleVerdict {
+                action,
+                rules: verdict.map(|verdict| verdict.rules).unwrap_or_default(),
+            });
+            source = "classifier";
+        }
+
+        let Some(verdict) = verdict else {
+            return Some(response);
+        };
+
+        if verdict.action == ModerationAction::Rewrite
+            && let OutboundResponse::RichMessage { blocks, .. } = &mut response
+        {
+            // Block Kit payloads can't be rewritten reliably; fall back to
+            // the rewritten plain text.
+            blocks.clear();
+        }
+
+        let delivered = (verdict.action != ModerationAction::Block).then(|| {
+            text_fields(&mut response)
+                .iter()
+                .map(|field| field.as_str())
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+
+        tracing::info!(
+            agent_id = %self.agent_id,
+            channel_id = %self.channel_id,
+            action = %verdict.action,
+            source,
+            rules = %verdict.rules.join(","),
+            "moderation acted on outbound response"
+        );
+        self.audit
+            .record(ModerationEventInput {
+                channel_id: &self.channel_id,
+                action: verdict.action,
+                source,
+                rules: &verdict.rules,
+                strictness,
+                original_content: &original,
+                delivered_content: delivered.as_deref(),
+            })
+            .await;
+
+        if verdict.action != ModerationAction::Block {
+            return Some(response);
+        }
+
+        if let Some(receipt) = receipt {
+            receipt.complete(Err(
+                "the reply was blocked by moderation and not delivered".into()
+            ));
+        }
+        match (&response, config.block_notice.as_deref()) {
+            // A notice in the middle of a stream or in place of an edit would
+            // read as part of the message; drop those silently.
+            (OutboundResponse::StreamChunk(_) | OutboundResponse::Edit { .. }, _) | (_, None) => {
+                None
+            }
+            (_, Some(notice)) => Some(OutboundResponse::Text(notice.to_string())),
+        }
+    }
+
+    /// Ask the classifier model about a reply. Returns `None` when it allows
+    /// it or can't be reached; moderation fails open.
+    async fn classify(&self, model_name: &str, text: &str) -> Option<ModerationAction> {
+        let prompt_engine = self.runtime_config.prompts.load();
+        let preamble = match prompt_engine.render_static("moderation_classifier") {
+            Ok(preamble) => preamble,
+            Err(error) => {
+                tracing::warn!(%error, "failed to render moderation classifier prompt");
+                return None;
+            }
+        };
+
+        let model = SpacebotModel::make(&self.llm_manager, model_name)
+            .with_context(&*self.agent_id, "moderation");
+        let agent = AgentBuilder::new(model).preamble(&preamble).build();
+
+        match agent.prompt(text).await {
+            Ok(answer) => parse_classifier_answer(&answer),
+            Err(error) => {
+                tracing::warn!(
+                    %error,
+                    channel_id = %self.channel_id,
+                    "moderation classifier failed, allowing reply"
+                );
+                None
+            }
+        }
+    }
+}
+
+/// Read the classifier's one-word answer.
+fn parse_classifier_answer(answer: &str) -> Option<ModerationAction> {
+    let word = answer
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .trim_matches(|c: char| !c.is_ascii_alphabetic())
+        .to_ascii_lowercase();
+    match word.as_str() {
+        "allow" => None,
+        "flag" => Some(ModerationAction::Flag),
+        "block" => Some(ModerationAction::Block),
+        _ => {
+            tracing::warn!(
+                answer,
+                "unexpected moderation classifier answer, allowing reply"
+            );
+            None
+        }
+    }
+}
+
+/// A recorded moderation decision.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModerationEvent {
+    pub id: String,
+    pub channel_id: String,
+    pub action: String,
+    pub source: String,
+    pub rules: Vec<String>,
+    pub strictness: String,
+    pub original_content: String,
+    pub delivered_content: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+struct ModerationEventInput<'a> {
+    channel_id: &'a str,
+    action: ModerationAction,
+    source: &'a str,
+    rules: &'a [String],
+    strictness: ModerationStrictness,
+    original_content: &'a str,
+    delivered_content: Option<&'a str>,
+}
+
+/// Reads and writes the `moderation_events` audit log.
+#[derive(Debug, Clone)]
+pub struct ModerationAudit {
+    pool: SqlitePool,
+}
+
+impl ModerationAudit {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    async fn record(&self, event: ModerationEventInput<'_>) {
+        let rules = (!event.rules.is_empty()).then(|| event.rules.join(","));
+        if let Err(error) = sqlx::query(
+            "INSERT INTO moderation_events \
+             (id, channel_id, action, source, rules, strictness, original_content, delivered_content) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(event.channel_id)
+        .bind(event.action.as_str())
+        .bind(event.source)
+        .bind(rules)
+        .bind(event.strictness.as_str())
+        .bind(event.original_content)
+        .bind(event.delivered_content)
+        .execute(&self.pool)
+        .await
+        {
+            tracing::warn!(%error, "failed to record moderation event");
+        }
+    }
+
+    /// Recorded events, newest first, optionally for one channel.
+    pub async fn list(
+        &self,
+        channel_id: Option<&str>,
+        limit: i64,
+    ) -> crate::error::Result<Vec<ModerationEvent>> {
+        let rows = sqlx::query(
+            "SELECT id, channel_id, action, source, rules, strictness, original_content, \
+                    delivered_content, created_at \
+             FROM moderation_events \
+             WHERE (?1 IS NULL OR channel_id = ?1) \
+             ORDER BY created_at DESC, rowid DESC \
+             LIMIT ?2",
+        )
+        .bind(channel_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let rules: Option<String> = row.try_get("rules").ok().flatten();
+                ModerationEvent {
+                    id: row.try_get("id").unwrap_or_default(),
+                    channel_id: row.try_get("channel_id").unwrap_or_default(),
+                    action: row.try_get("action").unwrap_or_default(),
+                    source: row.try_get("source").unwrap_or_default(),
+                    rules: rules
+                        .map(|rules| rules.split(',').map(String::from).collect())
+                        .unwrap_or_default(),
+                    strictness: row.try_get("strictness").unwrap_or_default(),
+                    original_content: row.try_get("original_content").unwrap_or_default(),
+                    delivered_content: row.try_get("delivered_content").ok().flatten(),
+                    created_at: row
+                        .try_get("created_at")
+                        .unwrap_or_else(|_| chrono::Utc::now()),
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(
+        name: &str,
+        pattern: &str,
+        action: ModerationAction,
+        level: ModerationStrictness,
+    ) -> ModerationRule {
+        ModerationRule {
+            name: name.into(),
+            pattern: regex::Regex::new(pattern).expect("valid pattern"),
+            action,
+            replacement: "[removed]".into(),
+            level,
+        }
+    }
+
+    #[test]
+    fn rules_apply_by_strictness_and_most_severe_action_wins() {
+        let rules = vec![
+            rule(
+                "api-keys",
+                r"sk-[a-z0-9]+",
+                ModerationAction::Rewrite,
+                ModerationStrictness::Lenient,
+            ),
+            rule(
+                "profanity",
+                r"(?i)\bdarn\b",
+                ModerationAction::Flag,
+                ModerationStrictness::Standard,
+            ),
+            rule(
+                "competitors",
+                r"(?i)\bacme\b",
+                ModerationAction::Block,
+                ModerationStrictness::Strict,
+            ),
+        ];
+
+        let mut text = "darn, use sk-abc123 with Acme".to_string();
+        let verdict = apply_rules(&rules, ModerationStrictness::Standard, &mut text)
+            .expect("rules should match");
+        assert_eq!(verdict.action, ModerationAction::Rewrite);
+        assert_eq!(verdict.rules, vec!["api-keys", "profanity"]);
+        assert_eq!(text, "darn, use [removed] with Acme");
+
+        let mut text = "darn, Acme".to_string();
+        let verdict = apply_rules(&rules, ModerationStrictness::Strict, &mut text)
+            .expect("rules should match");
+        assert_eq!(verdict.action, ModerationAction::Block);
+
+        let mut text = "darn, Acme".to_string();
+        assert!(apply_rules(&rules, ModerationStrictness::Lenient, &mut text).is_none());
+        assert!(apply_rules(&rules, ModerationStrictness::Off, &mut text).is_none());
+    }
+
+    #[test]
+    fn stream_rules_match_across_chunk_boundaries() {
+        let rules = vec![
+            rule(
+                "api-keys",
+                r"sk-[a-z0-9]{6}",
+                ModerationAction::Rewrite,
+                ModerationStrictness::Lenient,
+            ),
+            rule(
+                "competitors",
+                r"(?i)\bacme corp\b",
+                ModerationAction::Block,
+                ModerationStrictness::Lenient,
+            ),
+        ];
+        let strictness = ModerationStrictness::Standard;
+
+        let mut chunk = "use sk-ab".to_string();
+        assert!(apply_stream_rules(&rules, strictness, "", &mut chunk).is_none());
+        let mut chunk = "c123 to log in".to_string();
+        let verdict = apply_stream_rules(&rules, strictness, "use sk-ab", &mut chunk)
+            .expect("the split key should match");
+        assert_eq!(verdict.action, ModerationAction::Rewrite);
+        assert_eq!(chunk, "[removed] to log in");
+
+        // A match already judged in an earlier chunk isn't counted again.
+        let mut chunk = " then".to_string();
+        assert!(apply_stream_rules(&rules, strictness, "sk-abc123", &mut chunk).is_none());
+
+        let mut chunk = "corp instead".to_string();
+        let verdict = apply_stream_rules(&rules, strictness, "try Acme ", &mut chunk)
+            .expect("the split name should match");
+        assert_eq!(verdict.action, ModerationAction::Block);
+    }
+
+    #[test]
+    fn stream_window_keeps_the_tail_on_char_boundaries() {
+        let mut stream = StreamState::default();
+        stream.push(&"é".repeat(STREAM_WINDOW_BYTES));
+        stream.push("done");
+        assert!(stream.sent.len() <= STREAM_WINDOW_BYTES);
+        assert!(stream.sent.ends_with("édone"));
+    }
+
+    #[test]
+    fn classifier_answers_parse_leniently() {
+        assert_eq!(parse_classifier_answer("ALLOW"), None);
+        assert_eq!(
+            parse_classifier_answer("Block. It leaks a password."),
+            Some(ModerationAction::Block)
+        );
+        assert_eq!(
+            parse_classifier_answer("**flag**"),
+            Some(ModerationAction::Flag)
+        );
+        assert_eq!(parse_classifier_answer("maybe"), None);
+    }
+}
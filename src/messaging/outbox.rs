@@ -0,0 +1,882 @@
+//! Durable outbound delivery queue with retry and dead-lettering (SQLite).
+//!
+//! Every channel routes its responses through a single task, so responses
+//! already go out in order. The outbox makes that task durable: a response is
+//! persisted before it is handed to the adapter, retried with backoff when the
+//! platform reports a transient failure (rate limits, 5xx, dropped
+//! connections), and moved to `outbox_failures` when it fails permanently or
+//! runs out of attempts.
+//!
+//! Retries run on a background task per channel, so a flaky adapter never
+//! stalls the channel's outbound path. While a channel has a response being
+//! retried, its later responses are persisted and queued behind it on that
+//! task instead of being sent directly, so retries never reorder a
+//! conversation.
+//!
+//! Delivery is at-least-once. A crash between the platform accepting a
+//! message and the row being removed replays that message on startup.
+//! Replayed rows go through the same per-channel retry tasks, queued before
+//! the channel's first live response, so they keep their place too. That
+//! needs one `Outbox` per agent, shared by its channels.
+//!
+//! Rows hold only the adapter, the resolved broadcast target, and the ID of
+//! the sender being answered (so erasing a user also drops their queued
+//! responses), never the inbound message that triggered the response. Live
+//! deliveries still reply through the inbound message; replays after a
+//! restart go out as broadcasts to the stored target.
+
+use crate::error::{DeliveryError, is_transient_status};
+use crate::messaging::MessagingManager;
+use crate::messaging::target::{BroadcastTarget, resolve_inbound_target};
+use crate::{DeliveryReceipt, InboundMessage, OutboundResponse};
+
+use serde::Serialize;
+use sqlx::{Row as _, SqlitePool};
+use tokio::sync::{mpsc, oneshot};
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Attempts per response before it is dead-lettered.
+const MAX_ATTEMPTS: i64 = 6;
+
+/// Delay before the first retry. Doubles after each failed attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Upper bound on the delay between attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Per-agent outbound queue backed by the agent's SQLite database.
+#[derive(Debug, Clone)]
+pub struct Outbox {
+    pool: SqlitePool,
+    /// Background retry queues, one per channel with a response being retried.
+    retries: Arc<Mutex<HashMap<String, mpsc::UnboundedSender<PendingDelivery>>>>,
+}
+
+/// A response that was queued but not yet delivered.
+#[derive(Debug, Clone)]
+pub struct PendingDelivery {
+    /// Outbox row, or `None` when the response couldn't be persisted.
+    pub id: Option<i64>,
+    pub channel_id: String,
+    pub route: Route,
+    pub response: OutboundResponse,
+    /// Attempts made so far, including those of a previous run.
+    pub attempts: i64,
+    /// Completed with the final outcome. Set for tracked live responses
+    /// and replays.
+    pub receipt: Option<DeliveryReceipt>,
+}
+
+/// Where a delivery attempt sends the response.
+#[derive(Debug, Clone)]
+pub enum Route {
+    /// Reply to the inbound message, keeping the adapter's threading.
+    Reply(Box<InboundMessage>),
+    /// Broadcast to a stored target, used when replaying after a restart.
+    Broadcast { adapter: String, target: String },
+}
+
+/// Responses from a previous run, queued on their channels' retry tasks by
+/// [`Outbox::replay_pending`].
+#[derive(Debug)]
+pub struct Replay {
+    outcomes: Vec<(
+        String,
+        Option<i64>,
+        oneshot::Receiver<crate::DeliveryOutcome>,
+    )>,
+}
+
+/// What replaying the queue on startup did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    pub delivered: usize,
+    /// Responses dead-lettered during replay.
+    pub failed: usize,
+}
+
+/// Result of a single delivery attempt.
+enum Attempt {
+    /// Delivered or dead-lettered; the receipt has been completed.
+    Done(crate::DeliveryOutcome),
+    /// Failed transiently and should be tried again.
+    Retry(PendingDelivery),
+}
+
+/// A response that could not be delivered.
+#[derive(Debug, Clone, Serialize)]
+pub struct OutboxFailure {
+    pub id: i64,
+    pub channel_id: String,
+    pub adapter: String,
+    pub response: serde_json::Value,
+    pub attempts: i64,
+    pub error: String,
+    pub enqueued_at: String,
+    pub failed_at: String,
+}
+
+impl Outbox {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            pool,
+            retries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Deliver a response through the messaging manager, persisting it until
+    /// the platform accepts it.
+    ///
+    /// Makes one attempt inline. A transient failure hands the response to
+    /// the channel's background retry task and returns; so does any response
+    /// sent while that task is still working, which keeps the channel's
+    /// responses in order. The receipt, if any, is completed with the final
+    /// outcome.
+    pub async fn deliver(
+        &self,
+        messaging: &Arc<MessagingManager>,
+        target: &InboundMessage,
+        response: OutboundResponse,
+        receipt: Option<DeliveryReceipt>,
+    ) {
+        if !is_durable(&response) {
+            let route = Route::Reply(Box::new(target.clone()));
+            let outcome = send_once(messaging, &route, response, receipt.is_some())
+                .await
+                .map_err(|error| error.to_string());
+            if let Err(error) = &outcome {
+                tracing::error!(%error, "failed to send outbound response");
+            }
+            if let Some(receipt) = receipt {
+                receipt.complete(outcome);
+            }
+            return;
+        }
+
+        let id = match resolve_inbound_target(target) {
+            Some(broadcast_target) => {
+                match self
+                    .enqueue(
+                        &target.conversation_id,
+                        &target.sender_id,
+                        &broadcast_target,
+                        &response,
+                    )
+                    .await
+                {
+                    Ok(id) => Some(id),
+                    Err(error) => {
+                        // Still try to send; losing durability beats losing the message.
+                        tracing::warn!(%error, "failed to persist outbound response, sending without outbox");
+                        None
+                    }
+                }
+            }
+            None => {
+                tracing::debug!(
+                    conversation_id = %target.conversation_id,
+                    "no broadcast target for conversation, sending without outbox"
+                );
+                None
+            }
+        };
+
+        let delivery = PendingDelivery {
+            id,
+            channel_id: target.conversation_id.clone(),
+            route: Route::Reply(Box::new(target.clone())),
+            response,
+            attempts: 0,
+            receipt,
+        };
+        let Some(delivery) = self.queue_behind_retries(delivery) else {
+            return;
+        };
+        if let Attempt::Retry(delivery) = self.attempt(messaging, delivery).await {
+            self.retry_in_background(messaging, delivery);
+        }
+    }
+
+    /// Redeliver responses left in the queue by a previous run.
+    ///
+    /// Called once on startup after the adapters are running and before the
+    /// agent's channels start. Queues each row, oldest first, on its
+    /// channel's retry task and returns; live responses sent afterwards wait
+    /// behind them. Channels replay independently, so one dead adapter
+    /// doesn't hold up the rest. Await [`Replay::finished`] for the outcome.
+    pub async fn replay_pending(&self, messaging: &Arc<MessagingManager>) -> crate::Result<Replay> {
+        let pending = self.pending().await?;
+        let mut outcomes = Vec::with_capacity(pending.len());
+        for mut delivery in pending {
+            tracing::info!(
+                channel_id = %delivery.channel_id,
+                outbox_id = ?delivery.id,
+                "replaying undelivered outbound response"
+            );
+            let (receipt, outcome) = DeliveryReceipt::new();
+            delivery.receipt = Some(receipt);
+            outcomes.push((delivery.channel_id.clone(), delivery.id, outcome));
+            self.retry_in_background(messaging, delivery);
+        }
+        Ok(Replay { outcomes })
+    }
+
+    /// List dead-lettered responses, newest first.
+    pub async fn list_failures(
+        &self,
+        channel_id: Option<&str>,
+        limit: i64,
+    ) -> crate::Result<Vec<OutboxFailure>> {
+        let rows = sqlx::query(
+            "SELECT id, channel_id, adapter, response, attempts, error, enqueued_at, failed_at \
+             FROM outbox_failures \
+             WHERE (?1 IS NULL OR channel_id = ?1) \
+             ORDER BY id DESC \
+             LIMIT ?2",
+        )
+        .bind(channel_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let response: String = row.try_get("response").unwrap_or_default();
+                OutboxFailure {
+                    id: row.try_get("id").unwrap_or_default(),
+                    channel_id: row.try_get("channel_id").unwrap_or_default(),
+                    adapter: row.try_get("adapter").unwrap_or_default(),
+                    response: serde_json::from_str(&response)
+                        .unwrap_or(serde_json::Value::String(response)),
+                    attempts: row.try_get("attempts").unwrap_or_default(),
+                    error: row.try_get("error").unwrap_or_default(),
+                    enqueued_at: row
+                        .try_get::<chrono::NaiveDateTime, _>("enqueued_at")
+                        .map(|value| value.and_utc().to_rfc3339())
+                        .unwrap_or_default(),
+                    failed_at: row
+                        .try_get::<chrono::NaiveDateTime, _>("failed_at")
+                        .map(|value| value.and_utc().to_rfc3339())
+                        .unwrap_or_default(),
+                }
+            })
+            .collect())
+    }
+
+    /// Hand `delivery` to the channel's retry task if one is running, so it
+    /// goes out after the responses already waiting there. Returns the
+    /// delivery back when the channel has nothing pending.
+    fn queue_behind_retries(&self, delivery: PendingDelivery) -> Option<PendingDelivery> {
+        let retries = self.lock_retries();
+        match retries.get(&delivery.channel_id) {
+            // The task removes its entry before exiting, under this lock, so
+            // the receiver is still alive.
+            Some(queue) => {
+                queue.send(delivery).ok();
+                None
+            }
+            None => Some(delivery),
+        }
+    }
+
+    /// Queue a transiently failed delivery on the channel's retry task,
+    /// starting the task if the channel has none.
+    fn retry_in_background(&self, messaging: &Arc<MessagingManager>, delivery: PendingDelivery) {
+        let mut retries = self.lock_retries();
+        if let Some(queue) = retries.get(&delivery.channel_id) {
+            queue.send(delivery).ok();
+            return;
+        }
+        let channel_id = delivery.channel_id.clone();
+        let (queue, mut pending) = mpsc::unbounded_channel();
+        queue.send(delivery).ok();
+        retries.insert(channel_id.clone(), queue);
+        drop(retries);
+
+        let outbox = self.clone();
+        let messaging = messaging.clone();
+        tokio::spawn(async move {
+            loop {
+                let delivery = {
+                    let mut retries = outbox.lock_retries();
+                    match pending.try_recv() {
+                        Ok(delivery) => delivery,
+                        Err(_) => {
+                            retries.remove(&channel_id);
+                            return;
+                        }
+                    }
+                };
+                outbox.deliver_with_retry(&messaging, delivery).await.ok();
+            }
+        });
+    }
+
+    /// Attempt a delivery until it is delivered or dead-lettered, backing
+    /// off between attempts.
+    async fn deliver_with_retry(
+        &self,
+        messaging: &MessagingManager,
+        mut delivery: PendingDelivery,
+    ) -> crate::DeliveryOutcome {
+        loop {
+            if delivery.attempts > 0 {
+                tokio::time::sleep(backoff(delivery.attempts)).await;
+            }
+            match self.attempt(messaging, delivery).await {
+                Attempt::Done(outcome) => return outcome,
+                Attempt::Retry(next) => delivery = next,
+            }
+        }
+    }
+
+    /// Make one delivery attempt. A delivered or permanently failed response
+    /// leaves the queue and has its receipt completed.
+    async fn attempt(
+        &self,
+        messaging: &MessagingManager,
+        mut delivery: PendingDelivery,
+    ) -> Attempt {
+        delivery.attempts += 1;
+        let tracked = delivery.receipt.is_some();
+        let error = match send_once(
+            messaging,
+            &delivery.route,
+            delivery.response.clone(),
+            tracked,
+        )
+        .await
+        {
+            Ok(message_id) => {
+                if let Some(id) = delivery.id
+                    && let Err(error) = self.complete(id).await
+                {
+                    tracing::warn!(%error, outbox_id = id, "failed to clear delivered outbox row");
+                }
+                return finish(delivery.receipt, Ok(message_id));
+            }
+            Err(error) => error,
+        };
+
+        let transient = is_transient_delivery_error(&error);
+        let error = error.to_string();
+        if !transient || delivery.attempts >= MAX_ATTEMPTS {
+            tracing::error!(
+                %error,
+                attempts = delivery.attempts,
+                conversation_id = %delivery.channel_id,
+                "outbound response failed, moving to dead letters"
+            );
+            if let Some(id) = delivery.id
+                && let Err(db_error) = self.dead_letter(id, delivery.attempts, &error).await
+            {
+                tracing::warn!(%db_error, outbox_id = id, "failed to dead-letter outbox row");
+            }
+            return finish(delivery.receipt, Err(error));
+        }
+
+        tracing::warn!(
+            %error,
+            attempts = delivery.attempts,
+            retry_in_ms = backoff(delivery.attempts).as_millis() as u64,
+            conversation_id = %delivery.channel_id,
+            "transient delivery failure, retrying"
+        );
+        if let Some(id) = delivery.id
+            && let Err(db_error) = self.record_attempt(id, delivery.attempts, &error).await
+        {
+            tracing::warn!(%db_error, outbox_id = id, "failed to record outbox attempt");
+        }
+        Attempt::Retry(delivery)
+    }
+
+    fn lock_retries(
+        &self,
+    ) -> std::sync::MutexGuard<'_, HashMap<String, mpsc::UnboundedSender<PendingDelivery>>> {
+        self.retries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    async fn enqueue(
+        &self,
+        channel_id: &str,
+        sender_id: &str,
+        target: &BroadcastTarget,
+        response: &OutboundResponse,
+    ) -> crate::Result<i64> {
+        let response_json = serde_json::to_string(response)
+            .map_err(|error| anyhow::anyhow!("failed to serialize outbound response: {error}"))?;
+
+        let result = sqlx::query(
+            "INSERT INTO outbox (channel_id, adapter, response, target, sender_id) \
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(channel_id)
+        .bind(&target.adapter)
+        .bind(response_json)
+        .bind(&target.target)
+        .bind(sender_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    async fn pending(&self) -> crate::Result<Vec<PendingDelivery>> {
+        let rows = sqlx::query(
+            "SELECT id, channel_id, adapter, response, target, attempts FROM outbox ORDER BY id ASC",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut pending = Vec::with_capacity(rows.len());
+        for row in rows {
+            let id: i64 = row.try_get("id")?;
+            let response: String = row.try_get("response")?;
+            match serde_json::from_str(&response) {
+                Ok(response) => pending.push(PendingDelivery {
+                    id: Some(id),
+                    channel_id: row.try_get("channel_id")?,
+                    route: Route::Broadcast {
+                        adapter: row.try_get("adapter")?,
+                        target: row.try_get("target")?,
+                    },
+                    response,
+                    attempts: row.try_get("attempts")?,
+                    receipt: None,
+                }),
+                Err(_) => {
+                    tracing::warn!(
+                        outbox_id = id,
+                        "unreadable outbox row, moving to dead letters"
+                    );
+                    let attempts: i64 = row.try_get("attempts").unwrap_or_default();
+                    self.dead_letter(id, attempts, "unreadable outbox row")
+                        .await?;
+                }
+            }
+        }
+        Ok(pending)
+    }
+
+    async fn record_attempt(&self, id: i64, attempts: i64, error: &str) -> crate::Result<()> {
+        sqlx::query("UPDATE outbox SET attempts = ?, last_error = ? WHERE id = ?")
+            .bind(attempts)
+            .bind(error)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn complete(&self, id: i64) -> crate::Result<()> {
+        sqlx::query("DELETE FROM outbox WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn dead_letter(&self, id: i64, attempts: i64, error: &str) -> crate::Result<()> {
+        let mut transaction = self.pool.begin().await?;
+        sqlx::query(
+            "INSERT INTO outbox_failures \
+             (channel_id, adapter, response, attempts, error, enqueued_at, sender_id) \
+             SELECT channel_id, adapter, response, ?, ?, created_at, sender_id \
+             FROM outbox WHERE id = ?",
+        )
+        .bind(attempts)
+        .bind(error)
+        .bind(id)
+        .execute(&mut *transaction)
+        .await?;
+        sqlx::query("DELETE FROM outbox WHERE id = ?")
+            .bind(id)
+            .execute(&mut *transaction)
+            .await?;
+        transaction.commit().await?;
+        Ok(())
+    }
+}
+
+impl Replay {
+    /// Wait until every replayed response is delivered or dead-lettered.
+    pub async fn finished(self) -> ReplayReport {
+        let mut report = ReplayReport::default();
+        for (channel_id, outbox_id, outcome) in self.outcomes {
+            match outcome.await {
+                Ok(Ok(_)) => report.delivered += 1,
+                Ok(Err(error)) => {
+                    tracing::warn!(
+                        %error,
+                        %channel_id,
+                        ?outbox_id,
+                        "replayed outbound response was dead-lettered"
+                    );
+                    report.failed += 1;
+                }
+                Err(_) => {
+                    tracing::warn!(
+                        %channel_id,
+                        ?outbox_id,
+                        "retry task dropped a replayed outbound response"
+                    );
+                    report.failed += 1;
+                }
+            }
+        }
+        report
+    }
+}
+
+/// Complete the receipt, if any, with a delivery's final outcome.
+fn finish(receipt: Option<DeliveryReceipt>, outcome: crate::DeliveryOutcome) -> Attempt {
+    if let Some(receipt) = receipt {
+        receipt.complete(outcome.clone());
+    }
+    Attempt::Done(outcome)
+}
+
+/// Delay before the attempt after `attempts` failed ones.
+fn backoff(attempts: i64) -> Duration {
+    let doublings = attempts.saturating_sub(1).clamp(0, 16) as u32;
+    (INITIAL_BACKOFF * 2u32.pow(doublings)).min(MAX_BACKOFF)
+}
+
+async fn send_once(
+    messaging: &MessagingManager,
+    route: &Route,
+    response: OutboundResponse,
+    tracked: bool,
+) -> crate::Result<Option<String>> {
+    match route {
+        Route::Reply(message) if tracked => messaging.respond_tracked(message, response).await,
+        Route::Reply(message) => messaging.respond(message, response).await.map(|()| None),
+        Route::Broadcast { adapter, target } => messaging
+            .broadcast(adapter, target, response)
+            .await
+            .map(|()| None),
+    }
+}
+
+/// Whether a response is worth persisting. Streaming chunks and status
+/// updates are only meaningful in the moment, so they are sent once.
+fn is_durable(response: &OutboundResponse) -> bool {
+    !matches!(
+        response,
+        OutboundResponse::Status(_)
+            | OutboundResponse::StreamStart
+            | OutboundResponse::StreamChunk(_)
+            | OutboundResponse::StreamEnd
+    )
+}
+
+/// Whether an adapter error is a temporary platform condition worth
+/// retrying rather than a permanent rejection.
+///
+/// Decided from the typed errors adapters return (HTTP statuses, network and
+/// SMTP failures, platform SDK errors), never from the message text.
+pub fn is_transient_delivery_error(error: &crate::Error) -> bool {
+    match error {
+        crate::Error::Io(error) => is_transient_io_error(error),
+        crate::Error::Other(error) => error.chain().any(is_transient_cause),
+        _ => false,
+    }
+}
+
+fn is_transient_cause(cause: &(dyn std::error::Error + 'static)) -> bool {
+    if let Some(error) = cause.downcast_ref::<DeliveryError>() {
+        return error.is_transient();
+    }
+    if let Some(error) = cause.downcast_ref::<reqwest::Error>() {
+        return error.is_timeout()
+            || error.is_connect()
+            || error
+                .status()
+                .is_some_and(|status| is_transient_status(status.as_u16()));
+    }
+    if let Some(error) = cause.downcast_ref::<std::io::Error>() {
+        return is_transient_io_error(error);
+    }
+    if let Some(error) = cause.downcast_ref::<lettre::transport::smtp::Error>() {
+        return error.is_transient() || error.is_timeout();
+    }
+    if let Some(error) = cause.downcast_ref::<teloxide::RequestError>() {
+        return matches!(
+            error,
+            teloxide::RequestError::RetryAfter(_)
+                | teloxide::RequestError::Network(_)
+                | teloxide::RequestError::Io(_)
+        );
+    }
+    if let Some(error) = cause.downcast_ref::<serenity::Error>() {
+        return match error {
+            serenity::Error::Http(serenity::http::HttpError::UnsuccessfulRequest(response)) => {
+                is_transient_status(response.status_code.as_u16())
+            }
+            serenity::Error::Http(serenity::http::HttpError::Request(_)) => true,
+            _ => false,
+        };
+    }
+    if let Some(error) = cause.downcast_ref::<slack_morphism::errors::SlackClientError>() {
+        return match error {
+            slack_morphism::errors::SlackClientError::RateLimitError(_)
+            | slack_morphism::errors::SlackClientError::HttpProtocolError(_) => true,
+            slack_morphism::errors::SlackClientError::HttpError(error) => {
+                is_transient_status(error.status_code.as_u16())
+            }
+            _ => false,
+        };
+    }
+    false
+}
+
+fn is_transient_io_error(error: &std::io::Error) -> bool {
+    matches!(
+        error.kind(),
+        std::io::ErrorKind::TimedOut
+            | std::io::ErrorKind::ConnectionReset
+            | std::io::ErrorKind::ConnectionAborted
+            | std::io::ErrorKind::ConnectionRefused
+            | std::io::ErrorKind::BrokenPipe
+            | std::io::ErrorKind::Interrupted
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn test_outbox() -> Outbox {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to create sqlite memory pool");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("failed to run migrations");
+        Outbox::new(pool)
+    }
+
+    #[test]
+    fn classifies_transient_errors() {
+        let status = |status| {
+            crate::Error::from(anyhow::Error::new(DeliveryError {
+                endpoint: "github /repos/acme/app/issues/1/comments".into(),
+                status,
+                body: String::new(),
+            }))
+        };
+        assert!(is_transient_delivery_error(&status(429)));
+        assert!(is_transient_delivery_error(&status(503)));
+        assert!(!is_transient_delivery_error(&status(403)));
+
+        let reset = anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::ConnectionReset))
+            .context("failed to send email");
+        assert!(is_transient_delivery_error(&reset.into()));
+
+        let blocked =
+            anyhow::Error::new(teloxide::RequestError::Api(teloxide::ApiError::BotBlocked))
+                .context("failed to send telegram message");
+        assert!(!is_transient_delivery_error(&blocked.into()));
+    }
+
+    #[test]
+    fn error_text_does_not_decide_retries() {
+        let rejected = crate::Error::from(anyhow::Error::new(DeliveryError {
+            endpoint: "signal RPC".into(),
+            status: 413,
+            body: "attachment exceeds 25000 bytes, connection closed".into(),
+        }));
+        assert!(!is_transient_delivery_error(&rejected));
+        assert!(!is_transient_delivery_error(&crate::Error::from(
+            anyhow::anyhow!("HTTP 503 service unavailable: connection timed out")
+        )));
+    }
+
+    #[tokio::test]
+    async fn dead_letters_keep_order_and_clear_queue() {
+        let outbox = test_outbox().await;
+        let target = BroadcastTarget {
+            adapter: "discord".into(),
+            target: "2".into(),
+        };
+
+        let first = outbox
+            .enqueue(
+                "discord:1:2",
+                "42",
+                &target,
+                &OutboundResponse::Text("first".into()),
+            )
+            .await
+            .expect("enqueue first");
+        let second = outbox
+            .enqueue(
+                "discord:1:2",
+                "42",
+                &target,
+                &OutboundResponse::Text("second".into()),
+            )
+            .await
+            .expect("enqueue second");
+        assert!(first < second);
+
+        let pending = outbox.pending().await.expect("pending");
+        let texts: Vec<_> = pending
+            .iter()
+            .map(|delivery| match &delivery.response {
+                OutboundResponse::Text(text) => text.as_str(),
+                _ => "",
+            })
+            .collect();
+        assert_eq!(texts, ["first", "second"]);
+        assert!(pending.iter().all(|delivery| matches!(
+            &delivery.route,
+            Route::Broadcast { adapter, target } if adapter == "discord" && target == "2"
+        )));
+
+        outbox
+            .record_attempt(first, 2, "HTTP 429")
+            .await
+            .expect("record attempt");
+        outbox
+            .dead_letter(first, 3, "Missing Permissions")
+            .await
+            .expect("dead letter");
+        outbox.complete(second).await.expect("complete");
+
+        assert!(outbox.pending().await.expect("pending").is_empty());
+        let failures = outbox
+            .list_failures(Some("discord:1:2"), 10)
+            .await
+            .expect("failures");
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].attempts, 3);
+        assert_eq!(failures[0].error, "Missing Permissions");
+        assert_eq!(failures[0].response, serde_json::json!({ "text": "first" }));
+        assert!(
+            outbox
+                .list_failures(Some("discord:9:9"), 10)
+                .await
+                .expect("failures")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        assert_eq!(backoff(1), INITIAL_BACKOFF);
+        assert_eq!(backoff(2), INITIAL_BACKOFF * 2);
+        assert_eq!(backoff(3), INITIAL_BACKOFF * 4);
+        assert_eq!(backoff(40), MAX_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn retries_in_the_background_without_reordering() {
+        let outbox = test_outbox().await;
+        let adapter = Arc::new(crate::testing::MemoryAdapter::new("memory"));
+        let messaging = Arc::new(MessagingManager::new());
+        messaging.register_shared(adapter.clone()).await;
+        let message = adapter.message("room", "alice", "hi");
+
+        adapter.fail_next(1);
+        let started = std::time::Instant::now();
+        outbox
+            .deliver(
+                &messaging,
+                &message,
+                OutboundResponse::Text("first".into()),
+                None,
+            )
+            .await;
+        let (receipt, outcome) = DeliveryReceipt::new();
+        outbox
+            .deliver(
+                &messaging,
+                &message,
+                OutboundResponse::Text("second".into()),
+                Some(receipt),
+            )
+            .await;
+        assert!(
+            started.elapsed() < INITIAL_BACKOFF,
+            "deliver waited on the retry"
+        );
+        assert!(adapter.texts().is_empty(), "second skipped the retry queue");
+
+        let outcome = tokio::time::timeout(Duration::from_secs(10), outcome)
+            .await
+            .expect("retry never finished")
+            .expect("receipt dropped");
+        assert!(outcome.is_ok());
+        assert_eq!(adapter.texts(), ["first", "second"]);
+    }
+
+    #[tokio::test]
+    async fn replayed_responses_go_out_before_live_ones() {
+        let outbox = test_outbox().await;
+        let adapter = Arc::new(crate::testing::MemoryAdapter::new("memory"));
+        let messaging = Arc::new(MessagingManager::new());
+        messaging.register_shared(adapter.clone()).await;
+        let message = adapter.message("room", "alice", "hi");
+        let target = BroadcastTarget {
+            adapter: "memory".into(),
+            target: "room".into(),
+        };
+        for text in ["old one", "old two"] {
+            outbox
+                .enqueue(
+                    "room",
+                    "alice",
+                    &target,
+                    &OutboundResponse::Text(text.into()),
+                )
+                .await
+                .expect("enqueue");
+        }
+
+        adapter.fail_next(1);
+        let replay = outbox
+            .replay_pending(&messaging)
+            .await
+            .expect("replay should start");
+        let (receipt, outcome) = DeliveryReceipt::new();
+        outbox
+            .deliver(
+                &messaging,
+                &message,
+                OutboundResponse::Text("live".into()),
+                Some(receipt),
+            )
+            .await;
+        assert!(
+            adapter.texts().is_empty(),
+            "live response skipped the replay"
+        );
+
+        let report = tokio::time::timeout(Duration::from_secs(10), replay.finished())
+            .await
+            .expect("replay never finished");
+        assert_eq!(
+            report,
+            ReplayReport {
+                delivered: 2,
+                failed: 0
+            }
+        );
+        let outcome = tokio::time::timeout(Duration::from_secs(10), outcome)
+            .await
+            .expect("live response never finished")
+            .expect("receipt dropped");
+        assert!(outcome.is_ok());
+        assert_eq!(adapter.texts(), ["old one", "old two", "live"]);
+    }
+}
@@ -31,6 +31,7 @@
 //! - **Scheduled messages:** Not supported; sent immediately.
 
 use crate::config::SignalPermissions;
+use crate::error::DeliveryError;
 use crate::messaging::traits::{
     InboundStream, Messaging, apply_runtime_adapter_to_conversation_id,
 };
@@ -324,7 +325,12 @@ impl SignalAdapter {
         if !status.is_success() {
             let truncated_length = std::cmp::min(response_body.len(), 512);
             let truncated_body = String::from_utf8_lossy(&response_body[..truncated_length]);
-            anyhow::bail!("signal RPC HTTP {}: {truncated_body}", status.as_u16());
+            return Err(DeliveryError {
+                endpoint: "signal RPC".into(),
+                status: status.as_u16(),
+                body: truncated_body.into_owned(),
+            }
+            .into());
         }
 
         if response_body.is_empty() {
@@ -830,6 +836,12 @@ impl Messaging for SignalAdapter {
                 // Signal can't edit sent messages — streaming is not supported.
                 // StreamStart/Chunk/End are no-ops.
             }
+            OutboundResponse::Edit { .. } | OutboundResponse::Delete { .. } => {
+                tracing::debug!(
+                    conversation_id = %message.conversation_id,
+                    "signal: message edits and deletes not supported, dropping"
+                );
+            }
             OutboundResponse::Status(status) => {
                 self.send_status(message, status).await?;
             }
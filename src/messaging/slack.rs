@@ -6,34 +6,56 @@
 //! - Plain text and file-attachment messages (Socket Mode)
 //! - `app_mention` events — agent responds when @-mentioned in any channel
 //! - Message subtype filtering (edits/deletes ignored)
+//! - Block Kit button / select clicks and modal form submissions
 //! - Per-workspace / per-channel / DM permission filtering (hot-reloadable)
 //! - Full user identity resolution (display name, mention tag)
 //!
 //! **Outbound**
 //! - Plain text with smart UTF-8-safe chunking
-//! - Thread replies
+//! - Thread replies, with `thread_name` mapped to the thread it started
 //! - File uploads (v2 flow)
 //! - Emoji reactions (add + remove)
 //! - Ephemeral messages (visible only to the triggering user)
-//! - Block Kit rich messages with plain-text fallback
+//! - Block Kit rich messages with plain-text fallback; cards, buttons, selects
+//!   and polls are rendered as blocks, several selects as a modal form
 //! - Scheduled messages (`chat.scheduleMessage`)
 //! - Streaming via `chat.update` edits
 //! - Typing indicator via `assistant.threads.setStatus`
 //! - DM broadcast via `conversations.open`
 
+mod block_kit;
+
 use crate::config::{SlackCommandConfig, SlackPermissions};
 use crate::messaging::apply_runtime_adapter_to_conversation_id;
 use crate::messaging::traits::{HistoryMessage, InboundStream, Messaging};
-use crate::{InboundMessage, MessageContent, OutboundResponse, StatusUpdate};
+use crate::{
+    Card, InboundMessage, InteractiveElements, MessageContent, OutboundResponse, Poll, StatusUpdate,
+};
 
 use anyhow::Context as _;
 use arc_swap::ArcSwap;
 use slack_morphism::prelude::*;
 use std::collections::HashMap;
+use std::hash::Hash;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::{RwLock, mpsc};
 use tokio::time::{Duration, timeout};
 
+/// Most modal forms kept for their "Answer" buttons. The oldest are
+/// forgotten first; their buttons then do nothing.
+const MAX_MODAL_FORMS: usize = 500;
+
+/// Most `thread_name` → thread mappings kept. A forgotten name starts a new
+/// thread on its next use.
+const MAX_NAMED_THREADS: usize = 1_000;
+
+/// Modal form views by form ID, with when they were stored.
+type ModalForms = Arc<RwLock<HashMap<String, (Instant, serde_json::Value)>>>;
+
+/// Thread `ts` by (channel ID, lowercased thread name), with when it was last used.
+type NamedThreads = Arc<RwLock<HashMap<(String, String), (Instant, String)>>>;
+
 /// State shared with socket mode callbacks via `SlackClientEventsUserState`.
 struct SlackAdapterState {
     inbound_tx: mpsc::Sender<InboundMessage>,
@@ -48,12 +70,16 @@ struct SlackAdapterState {
     user_identity_cache: Arc<RwLock<HashMap<String, SlackUserIdentity>>>,
     /// Cache of resolved channel names to avoid repeated `conversations.info` API calls.
     channel_name_cache: Arc<RwLock<HashMap<String, String>>>,
+    /// Modal forms sent by the adapter, opened when their button is clicked.
+    modal_forms: ModalForms,
 }
 
 #[derive(Debug, Clone)]
 struct SlackUserIdentity {
     display_name: String,
     username: Option<String>,
+    /// IANA timezone from the user's Slack profile.
+    timezone: Option<String>,
 }
 
 /// Slack adapter.
@@ -73,6 +99,11 @@ pub struct SlackAdapter {
     shutdown_tx: Arc<RwLock<Option<mpsc::Sender<()>>>>,
     /// Slash command routing: command string → agent_id.
     commands: Arc<HashMap<String, String>>,
+    /// Modal forms generated from rich messages, shared with the listener.
+    modal_forms: ModalForms,
+    /// (channel ID, lowercased thread name) → thread `ts`, so replies with
+    /// the same `thread_name` land in the same thread.
+    named_threads: NamedThreads,
 }
 
 impl SlackAdapter {
@@ -103,6 +134,8 @@ impl SlackAdapter {
             active_messages: Arc::new(RwLock::new(HashMap::new())),
             shutdown_tx: Arc::new(RwLock::new(None)),
             commands: Arc::new(commands_map),
+            modal_forms: Arc::new(RwLock::new(HashMap::new())),
+            named_threads: Arc::new(RwLock::new(HashMap::new())),
         })
     }
 
@@ -110,6 +143,110 @@ impl SlackAdapter {
     fn session(&self) -> SlackClientSession<'_, SlackClientHyperHttpsConnector> {
         self.client.open_session(&self.token)
     }
+
+    /// Post text to the message's channel (and thread), splitting at Slack's
+    /// length limit. Returns the `ts` of the first chunk.
+    async fn post_text(
+        &self,
+        message: &InboundMessage,
+        text: &str,
+    ) -> crate::Result<Option<String>> {
+        let session = self.session();
+        let channel_id = extract_channel_id(message)?;
+        let thread_ts = extract_thread_ts(message);
+
+        let mut first_ts = None;
+        for chunk in split_message(text, 12_000) {
+            let mut req =
+                SlackApiChatPostMessageRequest::new(channel_id.clone(), markdown_content(chunk));
+            req = req.opt_thread_ts(thread_ts.clone());
+            let resp = session
+                .chat_post_message(&req)
+                .await
+                .context("failed to send slack message")?;
+            first_ts.get_or_insert(resp.ts.0);
+        }
+        Ok(first_ts)
+    }
+
+    /// Pick the thread for a `ThreadReply` named `thread_name`.
+    ///
+    /// Slack threads have no names, so the adapter remembers which thread a
+    /// name started in each channel and keeps replying there. A triggering
+    /// message that is already in a thread keeps the reply in that thread;
+    /// otherwise a new thread starts under the triggering message. Returns
+    /// the thread `ts` and whether this reply starts it.
+    async fn named_thread(
+        &self,
+        message: &InboundMessage,
+        channel_id: &SlackChannelId,
+        thread_name: &str,
+    ) -> (Option<SlackTs>, bool) {
+        let key = (channel_id.0.clone(), thread_name.trim().to_lowercase());
+        if let Some(thread_ts) = extract_thread_ts(message) {
+            let mut threads = self.named_threads.write().await;
+            insert_bounded(&mut threads, key, thread_ts.0.clone(), MAX_NAMED_THREADS);
+            return (Some(thread_ts), false);
+        }
+        if let Some((_, thread_ts)) = self.named_threads.read().await.get(&key) {
+            return (Some(SlackTs(thread_ts.clone())), false);
+        }
+        let Some(message_ts) = extract_message_ts(message) else {
+            return (None, false);
+        };
+        let mut threads = self.named_threads.write().await;
+        insert_bounded(&mut threads, key, message_ts.0.clone(), MAX_NAMED_THREADS);
+        (Some(message_ts), true)
+    }
+
+    /// Build the content of a rich message. Explicit Block Kit `blocks` are
+    /// sent as given; otherwise cards, interactive elements and polls are
+    /// rendered as blocks so they don't degrade to plain text.
+    async fn rich_message_content(
+        &self,
+        text: String,
+        blocks: Vec<serde_json::Value>,
+        cards: &[Card],
+        interactive_elements: &[InteractiveElements],
+        poll: Option<&Poll>,
+    ) -> SlackMessageContent {
+        let blocks = if blocks.is_empty() {
+            let rendered = block_kit::render_rich_message(&text, cards, interactive_elements, poll);
+            if let Some(form) = rendered.form {
+                let mut forms = self.modal_forms.write().await;
+                insert_bounded(&mut forms, form.id, form.view, MAX_MODAL_FORMS);
+            }
+            rendered.blocks
+        } else {
+            blocks
+        };
+
+        let attempted = blocks.len();
+        let slack_blocks = deserialize_blocks(&blocks);
+        let dropped = attempted - slack_blocks.len();
+        if slack_blocks.is_empty() {
+            if attempted > 0 {
+                tracing::warn!(
+                    attempted,
+                    "all {} block(s) failed to deserialise — sending plain text fallback",
+                    attempted
+                );
+            }
+            return SlackMessageContent::new().with_text(text);
+        }
+        if dropped > 0 {
+            tracing::warn!(
+                dropped,
+                attempted,
+                "{} of {} block(s) dropped due to deserialisation errors",
+                dropped,
+                attempted
+            );
+        }
+        SlackMessageContent::new()
+            .with_text(text)
+            .with_blocks(slack_blocks)
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -574,21 +711,16 @@ async fn handle_command_event(
     })
 }
 
-/// Handle Slack Block Kit interaction events (button clicks, select menus, etc.).
+/// Handle Slack interaction events: Block Kit clicks (buttons, select menus)
+/// and modal form submissions.
 ///
-/// Only `block_actions` is turned into an `InboundMessage`; other interaction
-/// types (view submissions, shortcuts, etc.) are logged and acknowledged.
+/// Other interaction types (shortcuts, dialogs, etc.) are logged and
+/// acknowledged.
 async fn handle_interaction_event(
     event: SlackInteractionEvent,
-    _client: Arc<SlackHyperClient>,
+    client: Arc<SlackHyperClient>,
     states: SlackClientEventsUserState,
 ) -> UserCallbackResult<()> {
-    let SlackInteractionEvent::BlockActions(block_actions) = event else {
-        // Acknowledge non-block-action interactions without processing.
-        tracing::debug!("received non-block-action interaction event — ignoring");
-        return Ok(());
-    };
-
     let state_guard = states.read().await;
     let adapter_state = state_guard
         .get_user_state::<Arc<SlackAdapterState>>()
@@ -598,6 +730,29 @@ async fn handle_interaction_event(
             )
         })?;
 
+    match event {
+        SlackInteractionEvent::BlockActions(block_actions) => {
+            handle_block_actions(block_actions, &client, adapter_state).await;
+        }
+        SlackInteractionEvent::ViewSubmission(submission) => {
+            handle_view_submission(submission, adapter_state).await;
+        }
+        _ => {
+            // Acknowledge other interactions without processing.
+            tracing::debug!("received unsupported interaction event — ignoring");
+        }
+    }
+
+    Ok(())
+}
+
+/// Turn `block_actions` clicks into inbound `Interaction` messages, or open
+/// the modal form behind an "Answer" button.
+async fn handle_block_actions(
+    block_actions: SlackInteractionBlockActionsEvent,
+    client: &Arc<SlackHyperClient>,
+    adapter_state: &SlackAdapterState,
+) {
     let user_id = block_actions
         .user
         .as_ref()
@@ -612,32 +767,9 @@ async fn handle_interaction_event(
         .map(|c| c.id.0.clone())
         .unwrap_or_default();
 
-    // Apply workspace / channel permission filters — interactions are subject to
-    // the same access rules as regular messages.
-    {
-        let perms = adapter_state.permissions.load();
-
-        if let Some(ref filter) = perms.workspace_filter
-            && !filter.contains(&team_id)
-        {
-            tracing::debug!(
-                team_id = %team_id,
-                "block_actions interaction from unauthorized workspace — dropping"
-            );
-            return Ok(());
-        }
-
-        if !channel_id.is_empty()
-            && let Some(allowed) = perms.channel_filter.get(&team_id)
-            && !allowed.is_empty()
-            && !allowed.contains(&channel_id)
-        {
-            tracing::debug!(
-                channel_id = %channel_id,
-                "block_actions interaction from unauthorized channel — dropping"
-            );
-            return Ok(());
-        }
+    // Interactions are subject to the same access rules as regular messages.
+    if !interaction_permitted(&adapter_state.permissions.load(), &team_id, &channel_id) {
+        return;
     }
 
     let message_ts = match &block_actions.container {
@@ -660,13 +792,33 @@ async fn handle_interaction_event(
 
     if actions.is_empty() {
         tracing::debug!("block_actions interaction had no actions — ignoring");
-        return Ok(());
+        return;
     }
 
     for (idx, action) in actions.iter().enumerate() {
         let action_id = action.action_id.0.clone();
+
+        if let Some(form_id) = action_id.strip_prefix(block_kit::MODAL_OPEN_ACTION_PREFIX) {
+            open_modal_form(
+                adapter_state,
+                client,
+                form_id,
+                &block_actions.trigger_id,
+                &channel_id,
+                message_ts.as_deref(),
+            )
+            .await;
+            continue;
+        }
+
         let block_id = action.block_id.as_ref().map(|b| b.0.clone());
-        let value = action.value.clone();
+        // Buttons carry a `value`; select menus carry the selected option.
+        let value = action.value.clone().or_else(|| {
+            action
+                .selected_option
+                .as_ref()
+                .map(|option| option.value.clone())
+        });
         let label = action.selected_option.as_ref().map(|o| match &o.text {
             SlackBlockText::Plain(pt) => pt.text.clone(),
             SlackBlockText::MarkDown(md) => md.text.clone(),
@@ -687,37 +839,8 @@ async fn handle_interaction_event(
             format!("{}:{}", msg_id, idx)
         };
 
-        let mut metadata = HashMap::new();
-        metadata.insert(
-            "slack_workspace_id".into(),
-            serde_json::Value::String(team_id.clone()),
-        );
-        metadata.insert(
-            "slack_channel_id".into(),
-            serde_json::Value::String(channel_id.clone()),
-        );
-        metadata.insert(
-            "slack_user_id".into(),
-            serde_json::Value::String(user_id.clone()),
-        );
-        metadata.insert(
-            "sender_id".into(),
-            serde_json::Value::String(user_id.clone()),
-        );
-        metadata.insert(
-            "slack_user_mention".into(),
-            serde_json::Value::String(format!("<@{}>", user_id)),
-        );
-        if let Some(ref ts) = message_ts {
-            metadata.insert(
-                "slack_thread_ts".into(),
-                serde_json::Value::String(ts.clone()),
-            );
-            metadata.insert(
-                "slack_message_ts".into(),
-                serde_json::Value::String(ts.clone()),
-            );
-        }
+        let mut metadata =
+            interaction_metadata(&team_id, &channel_id, &user_id, message_ts.as_deref());
         metadata.insert(
             "slack_action_id".into(),
             serde_json::Value::String(action_id),
@@ -746,8 +869,206 @@ async fn handle_interaction_event(
             tracing::warn!(%error, "failed to enqueue block interaction as inbound message");
         }
     }
+}
 
-    Ok(())
+/// Open a modal form for the user who clicked its button. The conversation
+/// it answers travels in the view's `private_metadata`.
+async fn open_modal_form(
+    adapter_state: &SlackAdapterState,
+    client: &Arc<SlackHyperClient>,
+    form_id: &str,
+    trigger_id: &SlackTriggerId,
+    channel_id: &str,
+    message_ts: Option<&str>,
+) {
+    let Some(mut view) = adapter_state
+        .modal_forms
+        .read()
+        .await
+        .get(form_id)
+        .map(|(_, view)| view.clone())
+    else {
+        tracing::debug!(
+            form_id,
+            "slack modal form is no longer available — ignoring"
+        );
+        return;
+    };
+    view["private_metadata"] = serde_json::Value::String(
+        serde_json::json!({ "channel_id": channel_id, "message_ts": message_ts }).to_string(),
+    );
+    let view = match serde_json::from_value::<SlackView>(view) {
+        Ok(view) => view,
+        Err(error) => {
+            tracing::warn!(%error, form_id, "failed to build slack modal form");
+            return;
+        }
+    };
+
+    let token = SlackApiToken::new(SlackApiTokenValue(adapter_state.bot_token.clone()));
+    let session = client.open_session(&token);
+    if let Err(error) = session
+        .views_open(&SlackApiViewsOpenRequest::new(trigger_id.clone(), view))
+        .await
+    {
+        tracing::warn!(%error, form_id, "failed to open slack modal form");
+    }
+}
+
+/// Turn a submitted modal form into one inbound `Interaction` carrying every
+/// choice as `action_id=value`.
+async fn handle_view_submission(
+    submission: SlackInteractionViewSubmissionEvent,
+    adapter_state: &SlackAdapterState,
+) {
+    // Read the payload in Slack's wire shape rather than through the typed view.
+    let payload = match serde_json::to_value(&submission) {
+        Ok(payload) => payload,
+        Err(error) => {
+            tracing::warn!(%error, "failed to read slack view submission");
+            return;
+        }
+    };
+    let view = &payload["view"];
+    if view["callback_id"].as_str() != Some(block_kit::MODAL_CALLBACK_ID) {
+        tracing::debug!("view submission for a modal the adapter didn't open — ignoring");
+        return;
+    }
+
+    let form_metadata: serde_json::Value = view["private_metadata"]
+        .as_str()
+        .and_then(|raw| serde_json::from_str(raw).ok())
+        .unwrap_or_default();
+    let Some(channel_id) = form_metadata["channel_id"]
+        .as_str()
+        .filter(|channel_id| !channel_id.is_empty())
+    else {
+        tracing::debug!("modal form submission has no channel — ignoring");
+        return;
+    };
+    let message_ts = form_metadata["message_ts"].as_str();
+    let team_id = payload
+        .pointer("/team/id")
+        .and_then(|id| id.as_str())
+        .unwrap_or_default();
+    let user_id = payload
+        .pointer("/user/id")
+        .and_then(|id| id.as_str())
+        .unwrap_or_default();
+
+    if !interaction_permitted(&adapter_state.permissions.load(), team_id, channel_id) {
+        return;
+    }
+
+    let values = block_kit::form_submission_values(view)
+        .into_iter()
+        .map(|(action_id, value, _)| format!("{action_id}={value}"))
+        .collect();
+    let content = MessageContent::Interaction {
+        action_id: block_kit::MODAL_SUBMIT_ACTION_ID.into(),
+        block_id: None,
+        values,
+        label: None,
+        message_ts: message_ts.map(str::to_string),
+    };
+
+    let base_conversation_id = format!("slack:{team_id}:{channel_id}");
+    let conversation_id =
+        apply_runtime_adapter_to_conversation_id(&adapter_state.runtime_key, base_conversation_id);
+    let mut metadata = interaction_metadata(team_id, channel_id, user_id, message_ts);
+    metadata.insert(
+        "slack_action_id".into(),
+        serde_json::Value::String(block_kit::MODAL_SUBMIT_ACTION_ID.into()),
+    );
+
+    let inbound = InboundMessage {
+        id: view["id"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+        source: "slack".into(),
+        adapter: Some(adapter_state.runtime_key.clone()),
+        conversation_id,
+        sender_id: user_id.to_string(),
+        agent_id: None,
+        content,
+        timestamp: chrono::Utc::now(),
+        metadata,
+        formatted_author: Some(format!("<@{user_id}>")),
+    };
+
+    if let Err(error) = adapter_state.inbound_tx.send(inbound).await {
+        tracing::warn!(%error, "failed to enqueue modal form submission as inbound message");
+    }
+}
+
+/// Apply the workspace / channel permission filters to an interaction.
+fn interaction_permitted(perms: &SlackPermissions, team_id: &str, channel_id: &str) -> bool {
+    if let Some(ref filter) = perms.workspace_filter
+        && !filter.iter().any(|allowed| allowed == team_id)
+    {
+        tracing::debug!(
+            team_id = %team_id,
+            "interaction from unauthorized workspace — dropping"
+        );
+        return false;
+    }
+
+    if !channel_id.is_empty()
+        && let Some(allowed) = perms.channel_filter.get(team_id)
+        && !allowed.is_empty()
+        && !allowed.iter().any(|allowed| allowed == channel_id)
+    {
+        tracing::debug!(
+            channel_id = %channel_id,
+            "interaction from unauthorized channel — dropping"
+        );
+        return false;
+    }
+
+    true
+}
+
+/// Metadata shared by inbound interactions. Replies thread under the message
+/// the interaction came from.
+fn interaction_metadata(
+    team_id: &str,
+    channel_id: &str,
+    user_id: &str,
+    message_ts: Option<&str>,
+) -> HashMap<String, serde_json::Value> {
+    let mut metadata = HashMap::new();
+    metadata.insert(
+        "slack_workspace_id".into(),
+        serde_json::Value::String(team_id.into()),
+    );
+    metadata.insert(
+        "slack_channel_id".into(),
+        serde_json::Value::String(channel_id.into()),
+    );
+    metadata.insert(
+        "slack_user_id".into(),
+        serde_json::Value::String(user_id.into()),
+    );
+    metadata.insert(
+        "sender_id".into(),
+        serde_json::Value::String(user_id.into()),
+    );
+    metadata.insert(
+        "slack_user_mention".into(),
+        serde_json::Value::String(format!("<@{user_id}>")),
+    );
+    if let Some(ts) = message_ts {
+        metadata.insert(
+            "slack_thread_ts".into(),
+            serde_json::Value::String(ts.into()),
+        );
+        metadata.insert(
+            "slack_message_ts".into(),
+            serde_json::Value::String(ts.into()),
+        );
+    }
+    metadata
 }
 
 // ---------------------------------------------------------------------------
@@ -783,6 +1104,7 @@ impl Messaging for SlackAdapter {
             commands: self.commands.clone(),
             user_identity_cache: Arc::new(RwLock::new(HashMap::new())),
             channel_name_cache: Arc::new(RwLock::new(HashMap::new())),
+            modal_forms: self.modal_forms.clone(),
         });
 
         let callbacks = SlackSocketModeListenerCallbacks::new()
@@ -909,25 +1231,17 @@ impl Messaging for SlackAdapter {
 
         match response {
             OutboundResponse::Text(text) => {
-                let thread_ts = extract_thread_ts(message);
-
-                for chunk in split_message(&text, 12_000) {
-                    let mut req = SlackApiChatPostMessageRequest::new(
-                        channel_id.clone(),
-                        markdown_content(chunk),
-                    );
-                    req = req.opt_thread_ts(thread_ts.clone());
-                    session
-                        .chat_post_message(&req)
-                        .await
-                        .context("failed to send slack message")?;
-                }
+                self.post_text(message, &text).await?;
             }
-            OutboundResponse::ThreadReply {
-                thread_name: _,
-                text,
-            } => {
-                let thread_ts = extract_thread_ts(message).or_else(|| extract_message_ts(message));
+            OutboundResponse::ThreadReply { thread_name, text } => {
+                let (thread_ts, starts_thread) =
+                    self.named_thread(message, &channel_id, &thread_name).await;
+                // The name opens the thread, standing in for Discord's thread title.
+                let text = if starts_thread && !thread_name.trim().is_empty() {
+                    format!("**{}**\n\n{text}", thread_name.trim())
+                } else {
+                    text
+                };
 
                 for chunk in split_message(&text, 12_000) {
                     let mut req = SlackApiChatPostMessageRequest::new(
@@ -1010,6 +1324,11 @@ impl Messaging for SlackAdapter {
 
             OutboundResponse::Ephemeral { text, user_id } => {
                 let thread_ts = extract_thread_ts(message);
+                let user_id = if user_id.is_empty() {
+                    message.sender_id.clone()
+                } else {
+                    user_id
+                };
                 let req = SlackApiChatPostEphemeralRequest::new(
                     channel_id.clone(),
                     SlackUserId(user_id),
@@ -1022,34 +1341,23 @@ impl Messaging for SlackAdapter {
                     .context("failed to send slack ephemeral message")?;
             }
 
-            OutboundResponse::RichMessage { text, blocks, .. } => {
+            OutboundResponse::RichMessage {
+                text,
+                blocks,
+                cards,
+                interactive_elements,
+                poll,
+            } => {
                 let thread_ts = extract_thread_ts(message);
-                let attempted = blocks.len();
-                let slack_blocks = deserialize_blocks(&blocks);
-                let dropped = attempted - slack_blocks.len();
-                let content = if slack_blocks.is_empty() {
-                    if attempted > 0 {
-                        tracing::warn!(
-                            attempted,
-                            "all {} block(s) failed to deserialise — sending plain text fallback",
-                            attempted
-                        );
-                    }
-                    SlackMessageContent::new().with_text(text)
-                } else {
-                    if dropped > 0 {
-                        tracing::warn!(
-                            dropped,
-                            attempted,
-                            "{} of {} block(s) dropped due to deserialisation errors",
-                            dropped,
-                            attempted
-                        );
-                    }
-                    SlackMessageContent::new()
-                        .with_text(text)
-                        .with_blocks(slack_blocks)
-                };
+                let content = self
+                    .rich_message_content(
+                        text,
+                        blocks,
+                        &cards,
+                        &interactive_elements,
+                        poll.as_ref(),
+                    )
+                    .await;
                 let mut req = SlackApiChatPostMessageRequest::new(channel_id.clone(), content);
                 req = req.opt_thread_ts(thread_ts);
                 session
@@ -1113,6 +1421,32 @@ impl Messaging for SlackAdapter {
                 self.active_messages.write().await.remove(&message.id);
             }
 
+            OutboundResponse::Edit { message_id, text } => {
+                let display_text = if text.len() > 12_000 {
+                    let end = text.floor_char_boundary(11_997);
+                    format!("{}...", &text[..end])
+                } else {
+                    text
+                };
+                let req = SlackApiChatUpdateRequest::new(
+                    channel_id.clone(),
+                    markdown_content(display_text),
+                    SlackTs(message_id),
+                );
+                session
+                    .chat_update(&req)
+                    .await
+                    .context("failed to edit slack message")?;
+            }
+
+            OutboundResponse::Delete { message_id } => {
+                let req = SlackApiChatDeleteRequest::new(channel_id.clone(), SlackTs(message_id));
+                session
+                    .chat_delete(&req)
+                    .await
+                    .context("failed to delete slack message")?;
+            }
+
             OutboundResponse::Status(_) => {
                 // Status updates are handled via send_status(); ignored here.
             }
@@ -1121,6 +1455,20 @@ impl Messaging for SlackAdapter {
         Ok(())
     }
 
+    async fn respond_tracked(
+        &self,
+        message: &InboundMessage,
+        response: OutboundResponse,
+    ) -> crate::Result<Option<String>> {
+        match response {
+            OutboundResponse::Text(text) => self.post_text(message, &text).await,
+            response => {
+                self.respond(message, response).await?;
+                Ok(None)
+            }
+        }
+    }
+
     async fn broadcast(&self, target: &str, response: OutboundResponse) -> crate::Result<()> {
         let session = self.session();
 
@@ -1156,15 +1504,22 @@ impl Messaging for SlackAdapter {
                         .context("failed to broadcast slack message")?;
                 }
             }
-            OutboundResponse::RichMessage { text, blocks, .. } => {
-                let slack_blocks = deserialize_blocks(&blocks);
-                let content = if slack_blocks.is_empty() {
-                    SlackMessageContent::new().with_text(text)
-                } else {
-                    SlackMessageContent::new()
-                        .with_text(text)
-                        .with_blocks(slack_blocks)
-                };
+            OutboundResponse::RichMessage {
+                text,
+                blocks,
+                cards,
+                interactive_elements,
+                poll,
+            } => {
+                let content = self
+                    .rich_message_content(
+                        text,
+                        blocks,
+                        &cards,
+                        &interactive_elements,
+                        poll.as_ref(),
+                    )
+                    .await;
                 let mut req = SlackApiChatPostMessageRequest::new(channel_id.clone(), content);
                 req = req.opt_thread_ts(thread_ts.clone());
                 session
@@ -1545,6 +1900,12 @@ async fn build_metadata_and_author(
                     serde_json::Value::String(name.clone()),
                 );
             }
+            if let Some(ref timezone) = identity.timezone {
+                metadata.insert(
+                    crate::metadata_keys::SENDER_TIMEZONE.into(),
+                    serde_json::Value::String(timezone.clone()),
+                );
+            }
             formatted_author = Some(identity.display_name.clone());
         }
     }
@@ -1614,6 +1975,25 @@ fn deserialize_blocks(values: &[serde_json::Value]) -> Vec<SlackBlock> {
         .collect()
 }
 
+/// Insert into a map bounded to `max` entries, evicting the oldest first.
+fn insert_bounded<K: Eq + Hash + Clone, V>(
+    map: &mut HashMap<K, (Instant, V)>,
+    key: K,
+    value: V,
+    max: usize,
+) {
+    if map.len() >= max
+        && !map.contains_key(&key)
+        && let Some(oldest) = map
+            .iter()
+            .min_by_key(|(_, (stored_at, _))| *stored_at)
+            .map(|(key, _)| key.clone())
+    {
+        map.remove(&oldest);
+    }
+    map.insert(key, (Instant::now(), value));
+}
+
 /// Strip the leading `<@BOT_USER_ID>` mention from an `app_mention` event text.
 ///
 /// Slack always formats user IDs in uppercase (e.g. `<@U012AB3CD>`), so a
@@ -1636,6 +2016,8 @@ fn variant_name(response: &OutboundResponse) -> &'static str {
         OutboundResponse::Ephemeral { .. } => "Ephemeral",
         OutboundResponse::RichMessage { .. } => "RichMessage",
         OutboundResponse::ScheduledMessage { .. } => "ScheduledMessage",
+        OutboundResponse::Edit { .. } => "Edit",
+        OutboundResponse::Delete { .. } => "Delete",
         OutboundResponse::StreamStart => "StreamStart",
         OutboundResponse::StreamChunk(_) => "StreamChunk",
         OutboundResponse::StreamEnd => "StreamEnd",
@@ -1725,6 +2107,7 @@ fn resolve_slack_user_identity(user: &SlackUser, user_id: &str) -> SlackUserIden
     SlackUserIdentity {
         display_name,
         username,
+        timezone: user.tz.clone().filter(|tz| !tz.trim().is_empty()),
     }
 }
 
@@ -1803,4 +2186,18 @@ mod tests {
         let result = sanitize_reaction_name(":partyparrot:");
         assert_eq!(result, "partyparrot");
     }
+
+    #[test]
+    fn insert_bounded_evicts_the_oldest_entry() {
+        let mut map = HashMap::new();
+        insert_bounded(&mut map, "first", 1, 2);
+        insert_bounded(&mut map, "second", 2, 2);
+        insert_bounded(&mut map, "first", 3, 2);
+        assert_eq!(map.len(), 2);
+
+        insert_bounded(&mut map, "third", 4, 2);
+        assert_eq!(map.len(), 2);
+        assert!(!map.contains_key("second"));
+        assert_eq!(map["first"].1, 3);
+    }
 }
@@ -0,0 +1,469 @@
+//! Block Kit rendering for `OutboundResponse::RichMessage`.
+//!
+//! Cards become header/markdown/field sections, buttons and single select
+//! menus become `actions` blocks, and polls become one button per answer, so
+//! clicks come back through the regular `block_actions` handler. When a
+//! message carries two or more select menus they are collected into a modal
+//! form instead: the message gets one button that opens the form, and the
+//! agent receives every choice in a single interaction on submit.
+
+use crate::{Button, ButtonStyle, Card, InteractiveElements, Poll, SelectMenu, SelectOption};
+
+use serde_json::{Value, json};
+
+/// Prefix of the `action_id` on buttons that open a modal form. The rest is
+/// the form ID.
+pub(super) const MODAL_OPEN_ACTION_PREFIX: &str = "spacebot_open_form:";
+
+/// `callback_id` of modal forms, used to recognise their submissions.
+pub(super) const MODAL_CALLBACK_ID: &str = "spacebot_form";
+
+/// `action_id` reported to the agent when a modal form is submitted.
+pub(super) const MODAL_SUBMIT_ACTION_ID: &str = "modal_form";
+
+/// Slack's per-message block limit.
+const MAX_BLOCKS: usize = 50;
+
+/// Cumulative character limit for `markdown` blocks in one payload.
+const MAX_MARKDOWN_CHARS: usize = 12_000;
+
+const MAX_SECTION_CHARS: usize = 3_000;
+const MAX_FIELD_CHARS: usize = 2_000;
+const MAX_FIELDS_PER_SECTION: usize = 10;
+const MAX_HEADER_CHARS: usize = 150;
+const MAX_ELEMENTS_PER_ACTIONS: usize = 25;
+const MAX_PLAIN_TEXT_CHARS: usize = 75;
+const MAX_OPTION_VALUE_CHARS: usize = 150;
+const MAX_SELECT_OPTIONS: usize = 100;
+const MAX_MODAL_TITLE_CHARS: usize = 24;
+
+/// A modal form collected from a message's select menus.
+#[derive(Debug, Clone)]
+pub(super) struct ModalForm {
+    pub id: String,
+    /// The `modal` view, without `private_metadata`; that is filled in with
+    /// the conversation when the form is opened.
+    pub view: Value,
+}
+
+/// Block Kit rendering of a rich message.
+#[derive(Debug)]
+pub(super) struct RenderedMessage {
+    pub blocks: Vec<Value>,
+    pub form: Option<ModalForm>,
+}
+
+/// Render a rich message's text, cards, interactive elements and poll as
+/// Block Kit blocks.
+pub(super) fn render_rich_message(
+    text: &str,
+    cards: &[Card],
+    interactive_elements: &[InteractiveElements],
+    poll: Option<&Poll>,
+) -> RenderedMessage {
+    let mut renderer = Renderer::default();
+    renderer.markdown(text);
+
+    for (index, card) in cards.iter().enumerate() {
+        if index > 0 || !text.trim().is_empty() {
+            renderer.push(json!({ "type": "divider" }));
+        }
+        renderer.card(card);
+    }
+
+    let selects: Vec<&SelectMenu> = interactive_elements
+        .iter()
+        .filter_map(|element| match element {
+            InteractiveElements::Select { select } => Some(select),
+            InteractiveElements::Buttons { .. } => None,
+        })
+        .collect();
+    let form = (selects.len() > 1).then(|| modal_form(&selects));
+
+    for (row, element) in interactive_elements.iter().enumerate() {
+        match element {
+            InteractiveElements::Buttons { buttons } => {
+                let elements = buttons
+                    .iter()
+                    .enumerate()
+                    .map(|(index, button)| button_element(button, row, index))
+                    .collect();
+                renderer.actions(elements);
+            }
+            InteractiveElements::Select { select } if form.is_none() => {
+                renderer.actions(vec![static_select(select)]);
+            }
+            InteractiveElements::Select { .. } => {}
+        }
+    }
+
+    if let Some(form) = &form {
+        renderer.actions(vec![json!({
+            "type": "button",
+            "text": plain_text("Answer"),
+            "action_id": format!("{MODAL_OPEN_ACTION_PREFIX}{}", form.id),
+            "style": "primary",
+        })]);
+    }
+
+    if let Some(poll) = poll {
+        renderer.section(&format!("*{}*", poll.question.trim()));
+        let answers = poll
+            .answers
+            .iter()
+            .enumerate()
+            .map(|(index, answer)| {
+                json!({
+                    "type": "button",
+                    "text": plain_text(answer),
+                    "action_id": format!("poll_{index}"),
+                    "value": truncate(answer, 2_000),
+                })
+            })
+            .collect();
+        renderer.actions(answers);
+    }
+
+    RenderedMessage {
+        blocks: renderer.finish(),
+        form,
+    }
+}
+
+/// Pull each select's choice out of a submitted modal form, in the order the
+/// form shows them. `view` is the submission's serialised view.
+///
+/// Returns `(action_id, value, label)` for every answered select.
+pub(super) fn form_submission_values(view: &Value) -> Vec<(String, String, Option<String>)> {
+    let Some(blocks) = view.get("blocks").and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    let state = view.pointer("/state/values");
+    blocks
+        .iter()
+        .filter_map(|block| {
+            let block_id = block.get("block_id")?.as_str()?;
+            let action_id = block.pointer("/element/action_id")?.as_str()?;
+            let selected = state?
+                .get(block_id)?
+                .get(action_id)?
+                .get("selected_option")?;
+            let value = selected.get("value")?.as_str()?.to_string();
+            let label = selected
+                .pointer("/text/text")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            Some((action_id.to_string(), value, label))
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct Renderer {
+    blocks: Vec<Value>,
+    markdown_chars: usize,
+    dropped: usize,
+}
+
+impl Renderer {
+    fn push(&mut self, block: Value) {
+        if self.blocks.len() < MAX_BLOCKS {
+            self.blocks.push(block);
+        } else {
+            self.dropped += 1;
+        }
+    }
+
+    /// Standard markdown, as a `markdown` block while the payload budget
+    /// lasts and as `mrkdwn` sections after that.
+    fn markdown(&mut self, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        let length = text.chars().count();
+        if self.markdown_chars + length <= MAX_MARKDOWN_CHARS {
+            self.markdown_chars += length;
+            self.push(json!({ "type": "markdown", "text": text }));
+        } else {
+            self.section(text);
+        }
+    }
+
+    fn section(&mut self, text: &str) {
+        for chunk in super::split_message(text, MAX_SECTION_CHARS) {
+            self.push(json!({
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": chunk },
+            }));
+        }
+    }
+
+    fn card(&mut self, card: &Card) {
+        let title = card
+            .title
+            .as_deref()
+            .map(str::trim)
+            .filter(|title| !title.is_empty());
+        match (title, card.url.as_deref()) {
+            (Some(title), Some(url)) => self.section(&format!("*<{url}|{title}>*")),
+            (Some(title), None) => self.push(json!({
+                "type": "header",
+                "text": plain_text_limited(title, MAX_HEADER_CHARS),
+            })),
+            (None, _) => {}
+        }
+        if let Some(description) = &card.description {
+            self.markdown(description);
+        }
+        let fields: Vec<Value> = card
+            .fields
+            .iter()
+            .filter(|field| !field.name.trim().is_empty() || !field.value.trim().is_empty())
+            .map(|field| {
+                json!({
+                    "type": "mrkdwn",
+                    "text": truncate(
+                        &format!("*{}*\n{}", field.name.trim(), field.value.trim()),
+                        MAX_FIELD_CHARS,
+                    ),
+                })
+            })
+            .collect();
+        for chunk in fields.chunks(MAX_FIELDS_PER_SECTION) {
+            self.push(json!({ "type": "section", "fields": chunk }));
+        }
+        if let Some(footer) = card
+            .footer
+            .as_deref()
+            .map(str::trim)
+            .filter(|footer| !footer.is_empty())
+        {
+            self.push(json!({
+                "type": "context",
+                "elements": [{ "type": "mrkdwn", "text": truncate(footer, MAX_FIELD_CHARS) }],
+            }));
+        }
+    }
+
+    fn actions(&mut self, elements: Vec<Value>) {
+        for chunk in elements.chunks(MAX_ELEMENTS_PER_ACTIONS) {
+            self.push(json!({ "type": "actions", "elements": chunk }));
+        }
+    }
+
+    fn finish(self) -> Vec<Value> {
+        if self.dropped > 0 {
+            tracing::warn!(
+                dropped = self.dropped,
+                "rich message exceeds slack's {MAX_BLOCKS} block limit, dropping the rest"
+            );
+        }
+        self.blocks
+    }
+}
+
+fn button_element(button: &Button, row: usize, index: usize) -> Value {
+    let action_id = button
+        .custom_id
+        .clone()
+        .unwrap_or_else(|| format!("button_{row}_{index}"));
+    let mut element = json!({
+        "type": "button",
+        "text": plain_text(&button.label),
+        "action_id": action_id,
+        "value": action_id,
+    });
+    // Slack buttons only come in default, primary and danger.
+    match button.style {
+        ButtonStyle::Primary | ButtonStyle::Success => element["style"] = json!("primary"),
+        ButtonStyle::Danger => element["style"] = json!("danger"),
+        ButtonStyle::Secondary | ButtonStyle::Link => {}
+    }
+    if let Some(url) = &button.url {
+        element["url"] = json!(url);
+    }
+    element
+}
+
+fn static_select(select: &SelectMenu) -> Value {
+    let mut element = json!({
+        "type": "static_select",
+        "action_id": select.custom_id,
+        "options": select
+            .options
+            .iter()
+            .take(MAX_SELECT_OPTIONS)
+            .map(select_option)
+            .collect::<Vec<_>>(),
+    });
+    if let Some(placeholder) = &select.placeholder {
+        element["placeholder"] = plain_text(placeholder);
+    }
+    element
+}
+
+fn select_option(option: &SelectOption) -> Value {
+    let label = match &option.emoji {
+        Some(emoji) => format!("{emoji} {}", option.label),
+        None => option.label.clone(),
+    };
+    let mut rendered = json!({
+        "text": plain_text(&label),
+        "value": truncate(&option.value, MAX_OPTION_VALUE_CHARS),
+    });
+    if let Some(description) = &option.description {
+        rendered["description"] = plain_text(description);
+    }
+    rendered
+}
+
+fn modal_form(selects: &[&SelectMenu]) -> ModalForm {
+    let blocks: Vec<Value> = selects
+        .iter()
+        .map(|select| {
+            let label = select.placeholder.as_deref().unwrap_or(&select.custom_id);
+            json!({
+                "type": "input",
+                "block_id": select.custom_id,
+                "label": plain_text_limited(label, 2_000),
+                "element": static_select(select),
+            })
+        })
+        .collect();
+    ModalForm {
+        id: uuid::Uuid::new_v4().simple().to_string(),
+        view: json!({
+            "type": "modal",
+            "callback_id": MODAL_CALLBACK_ID,
+            "title": plain_text_limited("Answer", MAX_MODAL_TITLE_CHARS),
+            "submit": plain_text("Submit"),
+            "close": plain_text("Cancel"),
+            "blocks": blocks,
+        }),
+    }
+}
+
+fn plain_text(text: &str) -> Value {
+    plain_text_limited(text, MAX_PLAIN_TEXT_CHARS)
+}
+
+fn plain_text_limited(text: &str, max_chars: usize) -> Value {
+    json!({ "type": "plain_text", "text": truncate(text, max_chars), "emoji": true })
+}
+
+/// Cut `text` to `max_chars` characters, marking the cut with `…`.
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CardField;
+
+    fn select(custom_id: &str) -> InteractiveElements {
+        InteractiveElements::Select {
+            select: SelectMenu {
+                custom_id: custom_id.into(),
+                options: vec![
+                    SelectOption {
+                        label: "Europe".into(),
+                        value: "eu".into(),
+                        description: None,
+                        emoji: None,
+                    },
+                    SelectOption {
+                        label: "United States".into(),
+                        value: "us".into(),
+                        description: None,
+                        emoji: None,
+                    },
+                ],
+                placeholder: Some(format!("Pick a {custom_id}")),
+            },
+        }
+    }
+
+    #[test]
+    fn renders_cards_buttons_and_a_single_select_inline() {
+        let card = Card {
+            title: Some("Deploy".into()),
+            description: Some("Shipped **v2**".into()),
+            fields: vec![CardField {
+                name: "Region".into(),
+                value: "eu".into(),
+                inline: true,
+            }],
+            footer: Some("build 42".into()),
+            ..Card::default()
+        };
+        let buttons = InteractiveElements::Buttons {
+            buttons: vec![Button {
+                label: "Roll back".into(),
+                custom_id: Some("rollback".into()),
+                style: ButtonStyle::Danger,
+                url: None,
+            }],
+        };
+
+        let rendered = render_rich_message("Done.", &[card], &[buttons, select("region")], None);
+        let types: Vec<&str> = rendered
+            .blocks
+            .iter()
+            .map(|block| block["type"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            types,
+            [
+                "markdown", "divider", "header", "markdown", "section", "context", "actions",
+                "actions"
+            ]
+        );
+        assert_eq!(rendered.blocks[6]["elements"][0]["action_id"], "rollback");
+        assert_eq!(rendered.blocks[6]["elements"][0]["style"], "danger");
+        assert_eq!(rendered.blocks[7]["elements"][0]["type"], "static_select");
+        assert!(rendered.form.is_none());
+    }
+
+    #[test]
+    fn collects_several_selects_into_a_modal_form() {
+        let rendered =
+            render_rich_message("Where?", &[], &[select("region"), select("zone")], None);
+        let form = rendered.form.expect("two selects make a form");
+        assert_eq!(rendered.blocks.len(), 2);
+        assert_eq!(
+            rendered.blocks[1]["elements"][0]["action_id"],
+            format!("{MODAL_OPEN_ACTION_PREFIX}{}", form.id)
+        );
+        assert_eq!(form.view["blocks"][1]["block_id"], "zone");
+
+        let mut submitted = form.view.clone();
+        submitted["state"] = json!({
+            "values": {
+                "zone": { "zone": { "type": "static_select", "selected_option": { "text": { "type": "plain_text", "text": "Europe" }, "value": "eu" } } },
+                "region": { "region": { "type": "static_select", "selected_option": { "text": { "type": "plain_text", "text": "United States" }, "value": "us" } } }
+            }
+        });
+        assert_eq!(
+            form_submission_values(&submitted),
+            [
+                (
+                    "region".to_string(),
+                    "us".to_string(),
+                    Some("United States".to_string())
+                ),
+                (
+                    "zone".to_string(),
+                    "eu".to_string(),
+                    Some("Europe".to_string())
+                ),
+            ]
+        );
+    }
+}
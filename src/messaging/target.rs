@@ -1,6 +1,7 @@
 //! Shared delivery target parsing and channel target resolution.
 
-use crate::conversation::channels::ChannelInfo;
+use crate::InboundMessage;
+use crate::conversation::channels::{ChannelInfo, extract_platform_meta};
 
 /// Canonical target for `MessagingManager::broadcast`.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -70,17 +71,24 @@ pub fn resolve_broadcast_target(channel: &ChannelInfo) -> Option<BroadcastTarget
             }
         }
         "telegram" => {
-            if let Some(chat_id) = channel
-                .platform_meta
-                .as_ref()
+            let meta = channel.platform_meta.as_ref();
+            if let Some(chat_id) = meta
                 .and_then(|meta| meta.get("telegram_chat_id"))
                 .and_then(json_value_to_string)
             {
-                chat_id
+                // Forum topics are addressed as `chat_id:thread_id`.
+                match meta
+                    .and_then(|meta| meta.get("telegram_thread_id"))
+                    .and_then(json_value_to_string)
+                {
+                    Some(thread_id) => format!("{chat_id}:{thread_id}"),
+                    None => chat_id,
+                }
             } else {
                 let parts: Vec<&str> = channel.id.split(':').collect();
                 match parts.as_slice() {
                     ["telegram", chat_id] => (*chat_id).to_string(),
+                    ["telegram", chat_id, thread_id] => format!("{chat_id}:{thread_id}"),
                     _ => return None,
                 }
             }
@@ -140,6 +148,12 @@ pub fn resolve_broadcast_target(channel: &ChannelInfo) -> Option<BroadcastTarget
                 .and_then(normalize_email_target)
                 .or_else(|| from.as_deref().and_then(normalize_email_target))?
         }
+        // Channel IDs are `github:owner/repo#123`; the target is the rest.
+        "github" => channel.id.strip_prefix("github:")?.to_string(),
+        // Channel IDs are `matrix:!room:server`; room IDs contain colons.
+        "matrix" => channel.id.strip_prefix("matrix:")?.to_string(),
+        // Channel IDs are `irc:#channel` or `irc:dm:nick`.
+        "irc" => channel.id.strip_prefix("irc:")?.to_string(),
         _ => return None,
     };
 
@@ -151,6 +165,28 @@ pub fn resolve_broadcast_target(channel: &ChannelInfo) -> Option<BroadcastTarget
     })
 }
 
+/// Resolve the broadcast target for the conversation an inbound message came
+/// from, on the adapter that received it.
+pub fn resolve_inbound_target(message: &InboundMessage) -> Option<BroadcastTarget> {
+    let channel = ChannelInfo {
+        id: message.conversation_id.clone(),
+        platform: message.source.clone(),
+        display_name: None,
+        platform_meta: extract_platform_meta(&message.source, &message.metadata)
+            .and_then(|meta| serde_json::from_str(&meta).ok()),
+        is_active: true,
+        created_at: message.timestamp,
+        last_activity_at: message.timestamp,
+    };
+    let mut target = resolve_broadcast_target(&channel)?;
+    // Named adapters (`telegram:support`) have to deliver through the
+    // instance that received the message. Signal resolves its own.
+    if target.adapter == message.source {
+        target.adapter = message.adapter_key().to_string();
+    }
+    Some(target)
+}
+
 pub fn normalize_target(adapter: &str, raw_target: &str) -> Option<String> {
     let trimmed = raw_target.trim();
     if trimmed.is_empty() {
@@ -166,6 +202,9 @@ pub fn normalize_target(adapter: &str, raw_target: &str) -> Option<String> {
         // Webchat targets are full conversation IDs (e.g. "portal:chat:main")
         "webchat" => Some(trimmed.to_string()),
         "signal" => normalize_signal_target(trimmed),
+        "github" => normalize_github_target(trimmed),
+        "matrix" => normalize_matrix_target(trimmed),
+        "irc" => normalize_irc_target(trimmed),
         _ => Some(trimmed.to_string()),
     }
 }
@@ -224,8 +263,17 @@ fn normalize_slack_target(raw_target: &str) -> Option<String> {
 
 fn normalize_telegram_target(raw_target: &str) -> Option<String> {
     let target = strip_repeated_prefix(raw_target, "telegram");
-    let chat_id = target.parse::<i64>().ok()?;
-    Some(chat_id.to_string())
+    match target.split_once(':') {
+        Some((chat_id, thread_id)) => {
+            let chat_id = chat_id.parse::<i64>().ok()?;
+            let thread_id = thread_id.parse::<i32>().ok()?;
+            Some(format!("{chat_id}:{thread_id}"))
+        }
+        None => {
+            let chat_id = target.parse::<i64>().ok()?;
+            Some(chat_id.to_string())
+        }
+    }
 }
 
 fn normalize_twitch_target(raw_target: &str) -> Option<String> {
@@ -258,6 +306,41 @@ fn normalize_email_target(raw_target: &str) -> Option<String> {
     None
 }
 
+fn normalize_github_target(raw_target: &str) -> Option<String> {
+    let target = strip_repeated_prefix(raw_target, "github");
+    let (repo, number) = crate::messaging::github::parse_thread_target(target)?;
+    Some(format!("{repo}#{number}"))
+}
+
+fn normalize_matrix_target(raw_target: &str) -> Option<String> {
+    let target = strip_repeated_prefix(raw_target, "matrix");
+    let (localpart, server) = target.strip_prefix('!')?.split_once(':')?;
+    if localpart.is_empty() || server.is_empty() {
+        return None;
+    }
+    Some(target.to_string())
+}
+
+fn normalize_irc_target(raw_target: &str) -> Option<String> {
+    let target = strip_repeated_prefix(raw_target, "irc");
+
+    if let Some(nick) = target.strip_prefix("dm:") {
+        if !nick.is_empty() && !nick.contains(char::is_whitespace) {
+            return Some(format!("dm:{nick}"));
+        }
+        return None;
+    }
+
+    if crate::messaging::irc::is_channel_name(target)
+        && target.len() > 1
+        && !target.contains([' ', ',', '\x07'])
+    {
+        return Some(target.to_lowercase());
+    }
+
+    None
+}
+
 fn normalize_signal_target(raw_target: &str) -> Option<String> {
     let target = strip_repeated_prefix(raw_target, "signal");
 
@@ -425,7 +508,8 @@ pub fn parse_signal_target_parts(parts: &[&str]) -> Option<BroadcastTarget> {
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_delivery_target, resolve_broadcast_target};
+    use super::{parse_delivery_target, resolve_broadcast_target, resolve_inbound_target};
+    use crate::InboundMessage;
     use crate::conversation::channels::ChannelInfo;
 
     fn test_channel_info(id: &str, platform: &str) -> ChannelInfo {
@@ -476,6 +560,95 @@ mod tests {
         );
     }
 
+    #[test]
+    fn resolve_inbound_target_uses_receiving_adapter() {
+        let mut message = InboundMessage::empty();
+        message.source = "discord".into();
+        message.adapter = Some("discord:support".into());
+        message.conversation_id = "discord:1:2".into();
+        message
+            .metadata
+            .insert("discord_channel_id".into(), serde_json::json!("3"));
+
+        assert_eq!(
+            resolve_inbound_target(&message),
+            Some(super::BroadcastTarget {
+                adapter: "discord:support".to_string(),
+                target: "3".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn telegram_topic_targets_keep_the_thread_id() {
+        let parsed = parse_delivery_target("telegram:-1001234567890:42");
+        assert_eq!(
+            parsed,
+            Some(super::BroadcastTarget {
+                adapter: "telegram".to_string(),
+                target: "-1001234567890:42".to_string(),
+            })
+        );
+        assert_eq!(parse_delivery_target("telegram:-100123:general"), None);
+
+        let channel = test_channel_info("telegram:-1001234567890:42", "telegram");
+        assert_eq!(
+            resolve_broadcast_target(&channel).map(|target| target.target),
+            Some("-1001234567890:42".to_string())
+        );
+    }
+
+    #[test]
+    fn resolve_github_target_from_channel_id() {
+        let channel = test_channel_info("github:spacedriveapp/spacebot#42", "github");
+        assert_eq!(
+            resolve_broadcast_target(&channel),
+            Some(super::BroadcastTarget {
+                adapter: "github".to_string(),
+                target: "spacedriveapp/spacebot#42".to_string(),
+            })
+        );
+        assert_eq!(parse_delivery_target("github:spacebot#42"), None);
+    }
+
+    #[test]
+    fn resolve_matrix_target_from_channel_id() {
+        let channel = test_channel_info("matrix:!abc123:example.org", "matrix");
+        assert_eq!(
+            resolve_broadcast_target(&channel),
+            Some(super::BroadcastTarget {
+                adapter: "matrix".to_string(),
+                target: "!abc123:example.org".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_delivery_target("matrix:!abc123:example.org").map(|target| target.target),
+            Some("!abc123:example.org".to_string())
+        );
+        assert_eq!(parse_delivery_target("matrix:#alias:example.org"), None);
+    }
+
+    #[test]
+    fn resolve_irc_target_from_channel_id() {
+        let channel = test_channel_info("irc:#spacebot", "irc");
+        assert_eq!(
+            resolve_broadcast_target(&channel),
+            Some(super::BroadcastTarget {
+                adapter: "irc".to_string(),
+                target: "#spacebot".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_delivery_target("irc:dm:jamie").map(|target| target.target),
+            Some("dm:jamie".to_string())
+        );
+        assert_eq!(
+            parse_delivery_target("irc:#SpaceBot").map(|target| target.target),
+            Some("#spacebot".to_string())
+        );
+        assert_eq!(parse_delivery_target("irc:spacebot"), None);
+    }
+
     #[test]
     fn resolve_twitch_target_from_channel_id() {
         let channel = test_channel_info("twitch:jamiepinelive", "twitch");
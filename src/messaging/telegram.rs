@@ -1,4 +1,8 @@
 //! Telegram messaging adapter using teloxide.
+//!
+//! Interactive elements render as inline keyboards whose presses arrive as
+//! `Interaction` messages, back-to-back photos go out as one album, and forum
+//! topics are separate conversations that `ThreadReply` can create by name.
 
 use crate::config::TelegramPermissions;
 use crate::messaging::apply_runtime_adapter_to_conversation_id;
@@ -11,8 +15,10 @@ use regex::Regex;
 use teloxide::payloads::setters::*;
 use teloxide::requests::{Request, Requester};
 use teloxide::types::{
-    ChatAction, ChatId, FileId, InputFile, InputPollOption, MediaKind, MessageId, MessageKind,
-    ParseMode, ReactionType, ReplyParameters, UpdateKind, UserId,
+    CallbackQuery, Chat, ChatAction, ChatId, ChatKind, ChatPublic, FileId, InlineKeyboardButton,
+    InlineKeyboardMarkup, InputFile, InputMedia, InputMediaPhoto, InputPollOption, MediaKind,
+    MessageId, MessageKind, ParseMode, PublicChatKind, PublicChatSupergroup, ReactionType,
+    ReplyParameters, ThreadId, UpdateKind, UserId,
 };
 use teloxide::{ApiError, Bot, RequestError};
 
@@ -25,6 +31,12 @@ use tokio::task::JoinHandle;
 /// Maximum number of rejected DM users to remember.
 const REJECTED_USERS_CAPACITY: usize = 50;
 
+/// Maximum number of stored callback actions (see [`CallbackAction`]).
+const CALLBACK_ACTIONS_CAPACITY: usize = 500;
+
+/// Maximum number of forum topics created for thread names to remember.
+const NAMED_TOPICS_CAPACITY: usize = 200;
+
 /// Telegram adapter state.
 pub struct TelegramAdapter {
     runtime_key: String,
@@ -38,6 +50,13 @@ pub struct TelegramAdapter {
     typing_tasks: Arc<RwLock<HashMap<String, JoinHandle<()>>>>,
     /// Shutdown signal for the polling loop.
     shutdown_tx: Arc<RwLock<Option<mpsc::Sender<()>>>>,
+    /// Keyboard actions too long for Telegram's callback data, by token.
+    callback_actions: Arc<RwLock<VecDeque<(String, CallbackAction)>>>,
+    /// Forum topics created for `ThreadReply` names, oldest first.
+    named_topics: Arc<RwLock<VecDeque<NamedTopic>>>,
+    /// Photos held briefly per conversation_id so consecutive ones can be
+    /// sent as a single album.
+    pending_albums: Arc<RwLock<HashMap<String, PendingAlbum>>>,
 }
 
 /// Tracks an in-progress streaming message edit.
@@ -47,6 +66,46 @@ struct ActiveStream {
     last_edit: Instant,
 }
 
+/// A forum topic created for a `ThreadReply` name.
+struct NamedTopic {
+    chat_id: ChatId,
+    name: String,
+    thread_id: ThreadId,
+}
+
+/// Photos waiting to be sent together.
+struct PendingAlbum {
+    chat_id: ChatId,
+    thread_id: Option<ThreadId>,
+    photos: Vec<AlbumPhoto>,
+}
+
+/// One photo of a [`PendingAlbum`].
+struct AlbumPhoto {
+    filename: String,
+    data: Vec<u8>,
+    caption: Option<String>,
+}
+
+/// What a pressed inline keyboard button reports back.
+#[derive(Debug, Clone, PartialEq)]
+struct CallbackAction {
+    /// Button `custom_id` or select menu `custom_id`.
+    action_id: String,
+    /// The chosen option's value, for select menus.
+    value: Option<String>,
+}
+
+/// Where an outgoing message goes and what it carries besides text.
+#[derive(Debug, Clone, Default)]
+struct SendOptions {
+    /// Forum topic to post in.
+    thread_id: Option<ThreadId>,
+    reply_to: Option<MessageId>,
+    /// Attached to the last chunk of a split message.
+    keyboard: Option<InlineKeyboardMarkup>,
+}
+
 /// Telegram's per-message character limit.
 const MAX_MESSAGE_LENGTH: usize = 4096;
 
@@ -56,6 +115,21 @@ const FORMATTED_SPLIT_LENGTH: usize = MAX_MESSAGE_LENGTH / 2;
 /// Minimum interval between streaming edits to avoid rate limits.
 const STREAM_EDIT_INTERVAL: std::time::Duration = std::time::Duration::from_millis(1000);
 
+/// How long a photo waits for more photos to share its album.
+const ALBUM_WINDOW: std::time::Duration = std::time::Duration::from_millis(750);
+
+/// Telegram's media group size limit.
+const MAX_ALBUM_SIZE: usize = 10;
+
+/// Telegram's callback data limit in bytes.
+const MAX_CALLBACK_DATA_LENGTH: usize = 64;
+
+/// Separates a select menu's `custom_id` from the chosen value in callback data.
+const CALLBACK_VALUE_SEPARATOR: char = '\u{1f}';
+
+/// Marks callback data that is a token for a stored [`CallbackAction`].
+const CALLBACK_TOKEN_PREFIX: char = '\u{1e}';
+
 impl TelegramAdapter {
     pub fn new(
         runtime_key: impl Into<String>,
@@ -74,6 +148,9 @@ impl TelegramAdapter {
             active_messages: Arc::new(RwLock::new(HashMap::new())),
             typing_tasks: Arc::new(RwLock::new(HashMap::new())),
             shutdown_tx: Arc::new(RwLock::new(None)),
+            callback_actions: Arc::new(RwLock::new(VecDeque::new())),
+            named_topics: Arc::new(RwLock::new(VecDeque::new())),
+            pending_albums: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -96,11 +173,132 @@ impl TelegramAdapter {
         Ok(MessageId(id))
     }
 
+    /// Forum topic the triggering message was posted in, if any.
+    fn extract_thread_id(&self, message: &InboundMessage) -> Option<ThreadId> {
+        message
+            .metadata
+            .get("telegram_thread_id")
+            .and_then(|v| v.as_i64())
+            .map(|id| ThreadId(MessageId(id as i32)))
+    }
+
     async fn stop_typing(&self, conversation_id: &str) {
         if let Some(handle) = self.typing_tasks.write().await.remove(conversation_id) {
             handle.abort();
         }
     }
+
+    /// Send a rich message: text with an inline keyboard for its interactive
+    /// elements, then the poll if there is one. Returns the text message's ID.
+    async fn send_rich_message(
+        &self,
+        chat_id: ChatId,
+        options: SendOptions,
+        text: &str,
+        interactive_elements: &[crate::InteractiveElements],
+        poll: Option<&crate::Poll>,
+    ) -> anyhow::Result<Option<MessageId>> {
+        let (keyboard, stored_actions) = build_inline_keyboard(interactive_elements);
+        if !stored_actions.is_empty() {
+            let mut actions = self.callback_actions.write().await;
+            for entry in stored_actions {
+                if actions.len() >= CALLBACK_ACTIONS_CAPACITY {
+                    actions.pop_front();
+                }
+                actions.push_back(entry);
+            }
+        }
+
+        let thread_id = options.thread_id;
+        let options = SendOptions {
+            keyboard,
+            ..options
+        };
+        let message_id = send_formatted(&self.bot, chat_id, text, &options).await?;
+
+        if let Some(poll_data) = poll {
+            send_poll(&self.bot, chat_id, thread_id, poll_data).await?;
+        }
+        Ok(message_id)
+    }
+
+    /// Find or create the forum topic named `name`. Returns `None` when the
+    /// chat doesn't allow it (topics disabled or missing admin rights).
+    async fn named_topic(&self, chat_id: ChatId, name: &str) -> Option<ThreadId> {
+        if let Some(topic) = self
+            .named_topics
+            .read()
+            .await
+            .iter()
+            .find(|topic| topic.chat_id == chat_id && topic.name == name)
+        {
+            return Some(topic.thread_id);
+        }
+
+        let topic = match self.bot.create_forum_topic(chat_id, name).send().await {
+            Ok(topic) => topic,
+            Err(error) => {
+                tracing::debug!(
+                    %error,
+                    chat_id = chat_id.0,
+                    "failed to create telegram forum topic, replying to the source message"
+                );
+                return None;
+            }
+        };
+
+        let mut topics = self.named_topics.write().await;
+        if topics.len() >= NAMED_TOPICS_CAPACITY {
+            topics.pop_front();
+        }
+        topics.push_back(NamedTopic {
+            chat_id,
+            name: name.to_string(),
+            thread_id: topic.thread_id,
+        });
+        Some(topic.thread_id)
+    }
+
+    /// Send the conversation's held album now, if there is one.
+    async fn flush_pending_album(&self, conversation_id: &str) {
+        if let Err(error) = flush_album(&self.bot, &self.pending_albums, conversation_id).await {
+            tracing::warn!(%error, %conversation_id, "failed to send telegram album");
+        }
+    }
+
+    /// Hold a photo for the conversation's album. The first photo schedules
+    /// the album to be sent once [`ALBUM_WINDOW`] has passed.
+    async fn queue_album_photo(
+        &self,
+        conversation_id: &str,
+        chat_id: ChatId,
+        thread_id: Option<ThreadId>,
+        photo: AlbumPhoto,
+    ) {
+        let mut albums = self.pending_albums.write().await;
+        let album = albums
+            .entry(conversation_id.to_string())
+            .or_insert_with(|| PendingAlbum {
+                chat_id,
+                thread_id,
+                photos: Vec::new(),
+            });
+        album.photos.push(photo);
+        if album.photos.len() > 1 {
+            return;
+        }
+        drop(albums);
+
+        let bot = self.bot.clone();
+        let pending_albums = self.pending_albums.clone();
+        let conversation_id = conversation_id.to_string();
+        tokio::spawn(async move {
+            tokio::time::sleep(ALBUM_WINDOW).await;
+            if let Err(error) = flush_album(&bot, &pending_albums, &conversation_id).await {
+                tracing::warn!(%error, %conversation_id, "failed to send telegram album");
+            }
+        });
+    }
 }
 
 impl Messaging for TelegramAdapter {
@@ -134,6 +332,7 @@ impl Messaging for TelegramAdapter {
         let permissions = self.permissions.clone();
         let bot_user_id = self.bot_user_id.clone();
         let bot_username = self.bot_username.clone();
+        let callback_actions = self.callback_actions.clone();
 
         tokio::spawn(async move {
             let mut offset = 0i32;
@@ -194,6 +393,26 @@ impl Messaging for TelegramAdapter {
 
                             let message = match &update.kind {
                                 UpdateKind::Message(message) => message,
+                                UpdateKind::CallbackQuery(query) => {
+                                    let Some(inbound) = callback_inbound(
+                                        &bot,
+                                        query,
+                                        &permissions.load(),
+                                        &runtime_key,
+                                        &callback_actions,
+                                    )
+                                    .await else {
+                                        continue;
+                                    };
+                                    if let Err(error) = inbound_tx.send(inbound).await {
+                                        tracing::warn!(
+                                            %error,
+                                            "failed to send inbound interaction from Telegram (receiver dropped)"
+                                        );
+                                        return;
+                                    }
+                                    continue;
+                                }
                                 _ => continue,
                             };
 
@@ -247,7 +466,8 @@ impl Messaging for TelegramAdapter {
                             }
 
                             let content = build_content(&bot, message, &text).await;
-                            let base_conversation_id = format!("telegram:{chat_id}");
+                            let base_conversation_id =
+                                base_conversation_id(chat_id, topic_thread_id(message));
                             let conversation_id = apply_runtime_adapter_to_conversation_id(
                                 &runtime_key,
                                 base_conversation_id,
@@ -299,29 +519,69 @@ impl Messaging for TelegramAdapter {
         response: OutboundResponse,
     ) -> crate::Result<()> {
         let chat_id = self.extract_chat_id(message)?;
+        let thread_id = self.extract_thread_id(message);
+        let options = SendOptions {
+            thread_id,
+            ..SendOptions::default()
+        };
+
+        // Anything other than another photo sends the held album first so
+        // the conversation keeps its order.
+        let is_album_photo = matches!(
+            &response,
+            OutboundResponse::File { mime_type, data, .. } if is_telegram_photo(mime_type, data.len())
+        );
+        if !is_album_photo {
+            self.flush_pending_album(&message.conversation_id).await;
+        }
 
         match response {
             OutboundResponse::Text(text) => {
                 self.stop_typing(&message.conversation_id).await;
-                send_formatted(&self.bot, chat_id, &text, None).await?;
-            }
-            OutboundResponse::RichMessage { text, poll, .. } => {
-                self.stop_typing(&message.conversation_id).await;
-                send_formatted(&self.bot, chat_id, &text, None).await?;
-
-                if let Some(poll_data) = poll {
-                    send_poll(&self.bot, chat_id, &poll_data).await?;
-                }
+                send_formatted(&self.bot, chat_id, &text, &options).await?;
             }
-            OutboundResponse::ThreadReply {
-                thread_name: _,
+            OutboundResponse::RichMessage {
                 text,
+                interactive_elements,
+                poll,
+                ..
             } => {
                 self.stop_typing(&message.conversation_id).await;
+                self.send_rich_message(
+                    chat_id,
+                    options,
+                    &text,
+                    &interactive_elements,
+                    poll.as_ref(),
+                )
+                .await?;
+            }
+            OutboundResponse::ThreadReply { thread_name, text } => {
+                self.stop_typing(&message.conversation_id).await;
 
-                // Telegram doesn't have named threads. Reply to the source message instead.
-                let reply_to = self.extract_message_id(message).ok();
-                send_formatted(&self.bot, chat_id, &text, reply_to).await?;
+                // Forums get a topic per thread name. Elsewhere, or when the
+                // topic can't be created, reply to the source message instead.
+                let in_forum = message
+                    .metadata
+                    .get("telegram_is_forum")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                let topic = if in_forum {
+                    self.named_topic(chat_id, &thread_name).await
+                } else {
+                    None
+                };
+                let options = match topic {
+                    Some(topic_id) => SendOptions {
+                        thread_id: Some(topic_id),
+                        ..SendOptions::default()
+                    },
+                    None => SendOptions {
+                        reply_to: self.extract_message_id(message).ok(),
+                        ..options
+                    },
+                };
+                send_formatted(&self.bot, chat_id, &text, &options).await?;
             }
             OutboundResponse::File {
                 filename,
@@ -332,19 +592,23 @@ impl Messaging for TelegramAdapter {
                 self.stop_typing(&message.conversation_id).await;
 
                 // Use send_audio for audio files so Telegram renders an inline player.
+                // Photos are held briefly so consecutive ones share an album.
                 // Fall back to send_document for everything else.
                 if mime_type.starts_with("audio/") {
                     let input_file = InputFile::memory(data.clone()).file_name(filename.clone());
+                    let mut request = self.bot.send_audio(chat_id, input_file);
+                    if let Some(thread_id) = thread_id {
+                        request = request.message_thread_id(thread_id);
+                    }
                     let sent = if let Some(ref caption_text) = caption {
                         let html_caption = markdown_to_telegram_html(caption_text);
-                        self.bot
-                            .send_audio(chat_id, input_file)
+                        request
                             .caption(&html_caption)
                             .parse_mode(ParseMode::Html)
                             .send()
                             .await
                     } else {
-                        self.bot.send_audio(chat_id, input_file).send().await
+                        request.send().await
                     };
 
                     if let Err(error) = sent {
@@ -355,6 +619,9 @@ impl Messaging for TelegramAdapter {
                             );
                             let fallback_file = InputFile::memory(data).file_name(filename);
                             let mut request = self.bot.send_audio(chat_id, fallback_file);
+                            if let Some(thread_id) = thread_id {
+                                request = request.message_thread_id(thread_id);
+                            }
                             if let Some(caption_text) = caption {
                                 request = request.caption(caption_text);
                             }
@@ -367,18 +634,29 @@ impl Messaging for TelegramAdapter {
                                 .context("failed to send telegram audio with HTML caption")?;
                         }
                     }
+                } else if is_album_photo {
+                    let photo = AlbumPhoto {
+                        filename,
+                        data,
+                        caption,
+                    };
+                    self.queue_album_photo(&message.conversation_id, chat_id, thread_id, photo)
+                        .await;
                 } else {
                     let input_file = InputFile::memory(data.clone()).file_name(filename.clone());
+                    let mut request = self.bot.send_document(chat_id, input_file);
+                    if let Some(thread_id) = thread_id {
+                        request = request.message_thread_id(thread_id);
+                    }
                     let sent = if let Some(ref caption_text) = caption {
                         let html_caption = markdown_to_telegram_html(caption_text);
-                        self.bot
-                            .send_document(chat_id, input_file)
+                        request
                             .caption(&html_caption)
                             .parse_mode(ParseMode::Html)
                             .send()
                             .await
                     } else {
-                        self.bot.send_document(chat_id, input_file).send().await
+                        request.send().await
                     };
 
                     if let Err(error) = sent {
@@ -389,6 +667,9 @@ impl Messaging for TelegramAdapter {
                             );
                             let fallback_file = InputFile::memory(data).file_name(filename);
                             let mut request = self.bot.send_document(chat_id, fallback_file);
+                            if let Some(thread_id) = thread_id {
+                                request = request.message_thread_id(thread_id);
+                            }
                             if let Some(caption_text) = caption {
                                 request = request.caption(caption_text);
                             }
@@ -428,9 +709,11 @@ impl Messaging for TelegramAdapter {
             OutboundResponse::StreamStart => {
                 self.stop_typing(&message.conversation_id).await;
 
-                let placeholder = self
-                    .bot
-                    .send_message(chat_id, "...")
+                let mut request = self.bot.send_message(chat_id, "...");
+                if let Some(thread_id) = thread_id {
+                    request = request.message_thread_id(thread_id);
+                }
+                let placeholder = request
                     .send()
                     .await
                     .context("failed to send stream placeholder")?;
@@ -485,6 +768,39 @@ impl Messaging for TelegramAdapter {
                     .await
                     .remove(&message.conversation_id);
             }
+            OutboundResponse::Edit { message_id, text } => {
+                let message_id = parse_message_id(&message_id)?;
+                let display_text = if text.len() > MAX_MESSAGE_LENGTH {
+                    let end = text.floor_char_boundary(MAX_MESSAGE_LENGTH - 3);
+                    format!("{}...", &text[..end])
+                } else {
+                    text
+                };
+
+                let html = markdown_to_telegram_html(&display_text);
+                if let Err(html_error) = self
+                    .bot
+                    .edit_message_text(chat_id, message_id, &html)
+                    .parse_mode(ParseMode::Html)
+                    .send()
+                    .await
+                {
+                    tracing::debug!(%html_error, "HTML edit failed, retrying as plain text");
+                    self.bot
+                        .edit_message_text(chat_id, message_id, &display_text)
+                        .send()
+                        .await
+                        .context("failed to edit telegram message")?;
+                }
+            }
+            OutboundResponse::Delete { message_id } => {
+                let message_id = parse_message_id(&message_id)?;
+                self.bot
+                    .delete_message(chat_id, message_id)
+                    .send()
+                    .await
+                    .context("failed to delete telegram message")?;
+            }
             OutboundResponse::Status(status) => {
                 self.send_status(message, status).await?;
             }
@@ -492,11 +808,11 @@ impl Messaging for TelegramAdapter {
             OutboundResponse::RemoveReaction(_) => {} // no-op
             OutboundResponse::Ephemeral { text, .. } => {
                 // Telegram has no ephemeral messages — send as regular text
-                send_formatted(&self.bot, chat_id, &text, None).await?;
+                send_formatted(&self.bot, chat_id, &text, &options).await?;
             }
             OutboundResponse::ScheduledMessage { text, .. } => {
                 // Telegram has no scheduled messages — send immediately
-                send_formatted(&self.bot, chat_id, &text, None).await?;
+                send_formatted(&self.bot, chat_id, &text, &options).await?;
             }
         }
 
@@ -511,6 +827,7 @@ impl Messaging for TelegramAdapter {
         match status {
             StatusUpdate::Thinking => {
                 let chat_id = self.extract_chat_id(message)?;
+                let thread_id = self.extract_thread_id(message);
                 let bot = self.bot.clone();
                 let conversation_id = message.conversation_id.clone();
 
@@ -518,11 +835,11 @@ impl Messaging for TelegramAdapter {
                 // Send one immediately, then repeat every 4 seconds.
                 let handle = tokio::spawn(async move {
                     loop {
-                        if let Err(error) = bot
-                            .send_chat_action(chat_id, ChatAction::Typing)
-                            .send()
-                            .await
-                        {
+                        let mut request = bot.send_chat_action(chat_id, ChatAction::Typing);
+                        if let Some(thread_id) = thread_id {
+                            request = request.message_thread_id(thread_id);
+                        }
+                        if let Err(error) = request.send().await {
                             tracing::debug!(%error, "failed to send typing indicator");
                             break;
                         }
@@ -543,21 +860,78 @@ impl Messaging for TelegramAdapter {
         Ok(())
     }
 
+    async fn respond_tracked(
+        &self,
+        message: &InboundMessage,
+        response: OutboundResponse,
+    ) -> crate::Result<Option<String>> {
+        match response {
+            OutboundResponse::Text(text) => {
+                let chat_id = self.extract_chat_id(message)?;
+                let options = SendOptions {
+                    thread_id: self.extract_thread_id(message),
+                    ..SendOptions::default()
+                };
+                self.flush_pending_album(&message.conversation_id).await;
+                self.stop_typing(&message.conversation_id).await;
+                let message_id = send_formatted(&self.bot, chat_id, &text, &options).await?;
+                Ok(message_id.map(|id| id.0.to_string()))
+            }
+            OutboundResponse::RichMessage {
+                text,
+                interactive_elements,
+                poll,
+                ..
+            } => {
+                let chat_id = self.extract_chat_id(message)?;
+                let options = SendOptions {
+                    thread_id: self.extract_thread_id(message),
+                    ..SendOptions::default()
+                };
+                self.flush_pending_album(&message.conversation_id).await;
+                self.stop_typing(&message.conversation_id).await;
+                let message_id = self
+                    .send_rich_message(
+                        chat_id,
+                        options,
+                        &text,
+                        &interactive_elements,
+                        poll.as_ref(),
+                    )
+                    .await?;
+                Ok(message_id.map(|id| id.0.to_string()))
+            }
+            response => {
+                self.respond(message, response).await?;
+                Ok(None)
+            }
+        }
+    }
+
     async fn broadcast(&self, target: &str, response: OutboundResponse) -> crate::Result<()> {
-        let chat_id = ChatId(
-            target
-                .parse::<i64>()
-                .context("invalid telegram chat id for broadcast target")?,
-        );
+        let (chat_id, thread_id) = parse_broadcast_target(target)?;
+        let options = SendOptions {
+            thread_id,
+            ..SendOptions::default()
+        };
 
         if let OutboundResponse::Text(text) = response {
-            send_formatted(&self.bot, chat_id, &text, None).await?;
-        } else if let OutboundResponse::RichMessage { text, poll, .. } = response {
-            send_formatted(&self.bot, chat_id, &text, None).await?;
-
-            if let Some(poll_data) = poll {
-                send_poll(&self.bot, chat_id, &poll_data).await?;
-            }
+            send_formatted(&self.bot, chat_id, &text, &options).await?;
+        } else if let OutboundResponse::RichMessage {
+            text,
+            interactive_elements,
+            poll,
+            ..
+        } = response
+        {
+            self.send_rich_message(
+                chat_id,
+                options,
+                &text,
+                &interactive_elements,
+                poll.as_ref(),
+            )
+            .await?;
         }
 
         Ok(())
@@ -579,6 +953,12 @@ impl Messaging for TelegramAdapter {
             handle.abort();
         }
 
+        // Send any held albums rather than dropping them
+        let held: Vec<String> = self.pending_albums.read().await.keys().cloned().collect();
+        for conversation_id in held {
+            self.flush_pending_album(&conversation_id).await;
+        }
+
         // Signal the polling loop to stop
         if let Some(tx) = self.shutdown_tx.read().await.as_ref() {
             tx.send(()).await.ok();
@@ -801,18 +1181,17 @@ fn build_metadata(
         serde_json::Value::String(message.id.0.to_string()),
     );
 
-    let chat_type = if message.chat.is_private() {
-        "private"
-    } else if message.chat.is_group() {
-        "group"
-    } else if message.chat.is_supergroup() {
-        "supergroup"
-    } else if message.chat.is_channel() {
-        "channel"
-    } else {
-        "unknown"
-    };
+    let chat_type = chat_type(&message.chat);
     metadata.insert("telegram_chat_type".into(), chat_type.into());
+    if is_forum(&message.chat) {
+        metadata.insert("telegram_is_forum".into(), true.into());
+    }
+    if let Some(thread_id) = topic_thread_id(message) {
+        metadata.insert(
+            "telegram_thread_id".into(),
+            serde_json::Value::Number(thread_id.0.0.into()),
+        );
+    }
 
     if let Some(title) = &message.chat.title() {
         metadata.insert("telegram_chat_title".into(), (*title).into());
@@ -933,6 +1312,410 @@ fn build_metadata(
     (metadata, formatted_author)
 }
 
+/// Short name for the kind of chat, as stored in `telegram_chat_type`.
+fn chat_type(chat: &Chat) -> &'static str {
+    if chat.is_private() {
+        "private"
+    } else if chat.is_group() {
+        "group"
+    } else if chat.is_supergroup() {
+        "supergroup"
+    } else if chat.is_channel() {
+        "channel"
+    } else {
+        "unknown"
+    }
+}
+
+/// Whether a chat is a supergroup with forum topics enabled.
+fn is_forum(chat: &Chat) -> bool {
+    matches!(
+        &chat.kind,
+        ChatKind::Public(ChatPublic {
+            kind: PublicChatKind::Supergroup(PublicChatSupergroup { is_forum: true, .. }),
+            ..
+        })
+    )
+}
+
+/// The forum topic a message was posted in. Replies in ordinary groups carry
+/// a thread ID too, so only topic messages count.
+fn topic_thread_id(message: &teloxide::types::Message) -> Option<ThreadId> {
+    if message.is_topic_message {
+        message.thread_id
+    } else {
+        None
+    }
+}
+
+/// Conversation ID before the runtime adapter is applied. Each forum topic is
+/// its own conversation.
+fn base_conversation_id(chat_id: i64, thread_id: Option<ThreadId>) -> String {
+    match thread_id {
+        Some(thread_id) => format!("telegram:{chat_id}:{}", thread_id.0.0),
+        None => format!("telegram:{chat_id}"),
+    }
+}
+
+/// Parse a broadcast target: a chat ID, optionally followed by `:` and a
+/// forum topic ID.
+fn parse_broadcast_target(target: &str) -> anyhow::Result<(ChatId, Option<ThreadId>)> {
+    let (chat_id, thread_id) = match target.split_once(':') {
+        Some((chat_id, thread_id)) => (chat_id, Some(thread_id)),
+        None => (target, None),
+    };
+    let chat_id = chat_id
+        .parse::<i64>()
+        .context("invalid telegram chat id for broadcast target")?;
+    let thread_id = thread_id
+        .map(|id| id.parse::<i32>().map(|id| ThreadId(MessageId(id))))
+        .transpose()
+        .context("invalid telegram topic id for broadcast target")?;
+    Ok((ChatId(chat_id), thread_id))
+}
+
+/// Build an inbound `Interaction` from a pressed inline keyboard button.
+///
+/// Answers the callback query so the client stops its loading indicator.
+/// Returns `None` for presses that should be dropped: no callback data, no
+/// message to attach to, an expired stored action, or a sender the
+/// permissions don't allow.
+async fn callback_inbound(
+    bot: &Bot,
+    query: &CallbackQuery,
+    permissions: &TelegramPermissions,
+    runtime_key: &str,
+    callback_actions: &RwLock<VecDeque<(String, CallbackAction)>>,
+) -> Option<InboundMessage> {
+    let data = query.data.as_deref()?;
+    let message = query.message.as_ref()?;
+
+    let action = if data.starts_with(CALLBACK_TOKEN_PREFIX) {
+        callback_actions
+            .read()
+            .await
+            .iter()
+            .find(|(token, _)| token == data)
+            .map(|(_, action)| action.clone())
+    } else {
+        Some(parse_callback_data(data))
+    };
+
+    let mut answer = bot.answer_callback_query(query.id.clone());
+    if action.is_none() {
+        answer = answer.text("This button has expired.");
+    }
+    if let Err(error) = answer.send().await {
+        tracing::debug!(%error, "failed to answer telegram callback query");
+    }
+    let action = action?;
+
+    let chat = message.chat();
+    let chat_id = chat.id.0;
+    let user = &query.from;
+    if chat.is_private() {
+        if !permissions.dm_allowed_users.is_empty()
+            && !permissions.dm_allowed_users.contains(&(user.id.0 as i64))
+        {
+            return None;
+        }
+    } else if let Some(filter) = &permissions.chat_filter
+        && !filter.contains(&chat_id)
+    {
+        return None;
+    }
+
+    let thread_id = query.regular_message().and_then(topic_thread_id);
+    let conversation_id = apply_runtime_adapter_to_conversation_id(
+        runtime_key,
+        base_conversation_id(chat_id, thread_id),
+    );
+
+    let mut metadata = HashMap::new();
+    metadata.insert(
+        "telegram_chat_id".into(),
+        serde_json::Value::Number(chat_id.into()),
+    );
+    metadata.insert(
+        "telegram_message_id".into(),
+        serde_json::Value::Number(message.id().0.into()),
+    );
+    metadata.insert("telegram_chat_type".into(), chat_type(chat).into());
+    if is_forum(chat) {
+        metadata.insert("telegram_is_forum".into(), true.into());
+    }
+    if let Some(thread_id) = thread_id {
+        metadata.insert(
+            "telegram_thread_id".into(),
+            serde_json::Value::Number(thread_id.0.0.into()),
+        );
+    }
+    // Pressing one of the bot's buttons is addressed to the bot.
+    metadata.insert(
+        "telegram_mentions_or_replies_to_bot".into(),
+        serde_json::Value::Bool(true),
+    );
+    metadata.insert(
+        "telegram_user_id".into(),
+        serde_json::Value::Number(user.id.0.into()),
+    );
+    let display_name = build_display_name(user);
+    metadata.insert("display_name".into(), display_name.clone().into());
+    metadata.insert("sender_display_name".into(), display_name.clone().into());
+    let formatted_author = match &user.username {
+        Some(username) => {
+            metadata.insert("telegram_username".into(), username.clone().into());
+            format!("{display_name} (@{username})")
+        }
+        None => display_name,
+    };
+
+    Some(InboundMessage {
+        id: query.id.to_string(),
+        source: "telegram".into(),
+        adapter: Some(runtime_key.to_string()),
+        conversation_id,
+        sender_id: user.id.0.to_string(),
+        agent_id: None,
+        content: MessageContent::Interaction {
+            action_id: action.action_id,
+            block_id: None,
+            values: action.value.into_iter().collect(),
+            label: None,
+            message_ts: Some(message.id().0.to_string()),
+        },
+        timestamp: chrono::Utc::now(),
+        metadata,
+        formatted_author: Some(formatted_author),
+    })
+}
+
+/// Build an inline keyboard for `elements`.
+///
+/// Each button row stays a row and each select option gets a row of its own.
+/// Link buttons open their URL; the rest report back through callback data.
+/// Actions whose callback data wouldn't fit Telegram's 64-byte limit get a
+/// token instead and are returned so the caller can store them.
+fn build_inline_keyboard(
+    elements: &[crate::InteractiveElements],
+) -> (Option<InlineKeyboardMarkup>, Vec<(String, CallbackAction)>) {
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = Vec::new();
+    let mut stored = Vec::new();
+    let mut callback_button = |label: &str, action: CallbackAction| {
+        let data = callback_data(&action).unwrap_or_else(|| {
+            let token = format!(
+                "{CALLBACK_TOKEN_PREFIX}{}",
+                &uuid::Uuid::new_v4().simple().to_string()[..16]
+            );
+            stored.push((token.clone(), action));
+            token
+        });
+        InlineKeyboardButton::callback(label, data)
+    };
+
+    for element in elements {
+        match element {
+            crate::InteractiveElements::Buttons { buttons } => {
+                let mut row = Vec::new();
+                for button in buttons {
+                    if button.style == crate::ButtonStyle::Link {
+                        let Some(url) = button
+                            .url
+                            .as_deref()
+                            .and_then(|url| reqwest::Url::parse(url).ok())
+                        else {
+                            continue;
+                        };
+                        row.push(InlineKeyboardButton::url(&button.label, url));
+                    } else {
+                        let action = CallbackAction {
+                            action_id: button
+                                .custom_id
+                                .clone()
+                                .unwrap_or_else(|| button.label.clone()),
+                            value: None,
+                        };
+                        row.push(callback_button(&button.label, action));
+                    }
+                }
+                if !row.is_empty() {
+                    rows.push(row);
+                }
+            }
+            crate::InteractiveElements::Select { select } => {
+                for option in &select.options {
+                    let label = match &option.emoji {
+                        Some(emoji) => format!("{emoji} {}", option.label),
+                        None => option.label.clone(),
+                    };
+                    let action = CallbackAction {
+                        action_id: select.custom_id.clone(),
+                        value: Some(option.value.clone()),
+                    };
+                    rows.push(vec![callback_button(&label, action)]);
+                }
+            }
+        }
+    }
+
+    let keyboard = (!rows.is_empty()).then(|| InlineKeyboardMarkup::new(rows));
+    (keyboard, stored)
+}
+
+/// Encode an action as callback data, or `None` if it doesn't fit.
+fn callback_data(action: &CallbackAction) -> Option<String> {
+    let data = match &action.value {
+        Some(value) => format!("{}{CALLBACK_VALUE_SEPARATOR}{value}", action.action_id),
+        None => action.action_id.clone(),
+    };
+    let fits = !data.is_empty() && data.len() <= MAX_CALLBACK_DATA_LENGTH;
+    (fits && !data.starts_with(CALLBACK_TOKEN_PREFIX)).then_some(data)
+}
+
+/// Decode callback data produced by [`callback_data`].
+fn parse_callback_data(data: &str) -> CallbackAction {
+    match data.split_once(CALLBACK_VALUE_SEPARATOR) {
+        Some((action_id, value)) => CallbackAction {
+            action_id: action_id.to_string(),
+            value: Some(value.to_string()),
+        },
+        None => CallbackAction {
+            action_id: data.to_string(),
+            value: None,
+        },
+    }
+}
+
+/// Send the photos held for `conversation_id`: several as albums of up to
+/// ten, a single one as a plain photo.
+async fn flush_album(
+    bot: &Bot,
+    pending_albums: &RwLock<HashMap<String, PendingAlbum>>,
+    conversation_id: &str,
+) -> anyhow::Result<()> {
+    let Some(album) = pending_albums.write().await.remove(conversation_id) else {
+        return Ok(());
+    };
+
+    let mut photos = album.photos.into_iter().peekable();
+    while photos.peek().is_some() {
+        let mut batch: Vec<AlbumPhoto> = photos.by_ref().take(MAX_ALBUM_SIZE).collect();
+        if batch.len() == 1 {
+            let photo = batch.remove(0);
+            send_photo(bot, album.chat_id, album.thread_id, photo).await?;
+        } else {
+            send_media_group(bot, album.chat_id, album.thread_id, &batch).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Send a single photo, retrying with a plain caption if the HTML one is rejected.
+async fn send_photo(
+    bot: &Bot,
+    chat_id: ChatId,
+    thread_id: Option<ThreadId>,
+    photo: AlbumPhoto,
+) -> anyhow::Result<()> {
+    let AlbumPhoto {
+        filename,
+        data,
+        caption,
+    } = photo;
+
+    let input_file = InputFile::memory(data.clone()).file_name(filename.clone());
+    let mut request = bot.send_photo(chat_id, input_file);
+    if let Some(thread_id) = thread_id {
+        request = request.message_thread_id(thread_id);
+    }
+    let sent = if let Some(ref caption_text) = caption {
+        let html_caption = markdown_to_telegram_html(caption_text);
+        request
+            .caption(&html_caption)
+            .parse_mode(ParseMode::Html)
+            .send()
+            .await
+    } else {
+        request.send().await
+    };
+
+    if let Err(error) = sent {
+        if !should_retry_plain_caption(&error) {
+            return Err(error).context("failed to send telegram photo with HTML caption");
+        }
+        tracing::debug!(
+            %error,
+            "HTML caption parse failed, retrying telegram photo with plain caption"
+        );
+        let fallback_file = InputFile::memory(data).file_name(filename);
+        let mut request = bot.send_photo(chat_id, fallback_file);
+        if let Some(thread_id) = thread_id {
+            request = request.message_thread_id(thread_id);
+        }
+        if let Some(caption_text) = caption {
+            request = request.caption(caption_text);
+        }
+        request
+            .send()
+            .await
+            .context("failed to send telegram photo")?;
+    }
+    Ok(())
+}
+
+/// Send photos as one album, retrying with plain captions if the HTML ones
+/// are rejected.
+async fn send_media_group(
+    bot: &Bot,
+    chat_id: ChatId,
+    thread_id: Option<ThreadId>,
+    photos: &[AlbumPhoto],
+) -> anyhow::Result<()> {
+    let media = |html: bool| -> Vec<InputMedia> {
+        photos
+            .iter()
+            .map(|photo| {
+                let input_file =
+                    InputFile::memory(photo.data.clone()).file_name(photo.filename.clone());
+                let mut media = InputMediaPhoto::new(input_file);
+                if let Some(caption) = &photo.caption {
+                    media = if html {
+                        media
+                            .caption(markdown_to_telegram_html(caption))
+                            .parse_mode(ParseMode::Html)
+                    } else {
+                        media.caption(caption.clone())
+                    };
+                }
+                InputMedia::Photo(media)
+            })
+            .collect()
+    };
+
+    let mut request = bot.send_media_group(chat_id, media(true));
+    if let Some(thread_id) = thread_id {
+        request = request.message_thread_id(thread_id);
+    }
+    if let Err(error) = request.send().await {
+        if !should_retry_plain_caption(&error) {
+            return Err(error).context("failed to send telegram album");
+        }
+        tracing::debug!(
+            %error,
+            "HTML caption parse failed, retrying telegram album with plain captions"
+        );
+        let mut request = bot.send_media_group(chat_id, media(false));
+        if let Some(thread_id) = thread_id {
+            request = request.message_thread_id(thread_id);
+        }
+        request
+            .send()
+            .await
+            .context("failed to send telegram album")?;
+    }
+    Ok(())
+}
+
 /// Build a display name from a Telegram user, preferring full name.
 fn build_display_name(user: &teloxide::types::User) -> String {
     let first = &user.first_name;
@@ -948,7 +1731,12 @@ fn build_display_name(user: &teloxide::types::User) -> String {
 /// max 100 chars. `open_period` only supports 5–600 seconds so we only set it
 /// when `duration_hours` converts to ≤600s; otherwise the poll stays open
 /// indefinitely (until manually stopped via the Telegram client).
-async fn send_poll(bot: &Bot, chat_id: ChatId, poll: &crate::Poll) -> anyhow::Result<()> {
+async fn send_poll(
+    bot: &Bot,
+    chat_id: ChatId,
+    thread_id: Option<ThreadId>,
+    poll: &crate::Poll,
+) -> anyhow::Result<()> {
     let question = if poll.question.len() > 300 {
         format!(
             "{}…",
@@ -979,6 +1767,9 @@ async fn send_poll(bot: &Bot, chat_id: ChatId, poll: &crate::Poll) -> anyhow::Re
     let mut request = bot
         .send_poll(chat_id, question, options)
         .is_anonymous(false);
+    if let Some(thread_id) = thread_id {
+        request = request.message_thread_id(thread_id);
+    }
 
     // Telegram's open_period only supports 5–600 seconds. Apply it when the
     // requested duration fits; otherwise leave unset so the poll stays open
@@ -1028,6 +1819,13 @@ fn split_message(text: &str, max_len: usize) -> Vec<String> {
     chunks
 }
 
+/// Telegram's `sendPhoto` accepts JPEG, PNG, and WebP up to 10 MB. Anything
+/// else (including animated GIFs) is sent as a document.
+fn is_telegram_photo(mime_type: &str, size_bytes: usize) -> bool {
+    const MAX_PHOTO_BYTES: usize = 10 * 1024 * 1024;
+    matches!(mime_type, "image/jpeg" | "image/png" | "image/webp") && size_bytes <= MAX_PHOTO_BYTES
+}
+
 /// Return true when Telegram rejected rich text entities and a plain-caption retry is safe.
 fn should_retry_plain_caption(error: &RequestError) -> bool {
     matches!(error, RequestError::Api(ApiError::CantParseEntities(_)))
@@ -1208,27 +2006,35 @@ async fn send_plain_text(
     bot: &Bot,
     chat_id: ChatId,
     text: &str,
-    reply_to: Option<MessageId>,
-) -> anyhow::Result<()> {
+    options: &SendOptions,
+) -> anyhow::Result<MessageId> {
     let mut request = bot.send_message(chat_id, text);
-    if let Some(reply_id) = reply_to {
+    if let Some(thread_id) = options.thread_id {
+        request = request.message_thread_id(thread_id);
+    }
+    if let Some(reply_id) = options.reply_to {
         request = request.reply_parameters(ReplyParameters::new(reply_id));
     }
-    request
+    if let Some(keyboard) = &options.keyboard {
+        request = request.reply_markup(keyboard.clone());
+    }
+    let sent = request
         .send()
         .await
         .context("failed to send telegram message")?;
-    Ok(())
+    Ok(sent.id)
 }
 
 /// Send a message with Telegram HTML formatting, splitting at the message
 /// length limit. Falls back to plain text if the API rejects the HTML.
+/// Returns the ID of the first chunk sent.
 async fn send_formatted(
     bot: &Bot,
     chat_id: ChatId,
     text: &str,
-    reply_to: Option<MessageId>,
-) -> anyhow::Result<()> {
+    options: &SendOptions,
+) -> anyhow::Result<Option<MessageId>> {
+    let mut first_id = None;
     let mut pending_chunks: VecDeque<String> =
         VecDeque::from(split_message(text, MAX_MESSAGE_LENGTH));
     while let Some(markdown_chunk) = pending_chunks.pop_front() {
@@ -1242,25 +2048,56 @@ async fn send_formatted(
                 }
                 continue;
             }
+        }
 
+        // Only the last chunk carries the keyboard.
+        let chunk_options = if pending_chunks.is_empty() {
+            options.clone()
+        } else {
+            SendOptions {
+                keyboard: None,
+                ..options.clone()
+            }
+        };
+
+        if html_chunk.len() > MAX_MESSAGE_LENGTH {
             let plain_chunk = strip_html_tags(&html_chunk);
-            send_plain_text(bot, chat_id, &plain_chunk, reply_to).await?;
+            let sent_id = send_plain_text(bot, chat_id, &plain_chunk, &chunk_options).await?;
+            first_id.get_or_insert(sent_id);
             continue;
         }
 
         let mut request = bot
             .send_message(chat_id, &html_chunk)
             .parse_mode(ParseMode::Html);
-        if let Some(reply_id) = reply_to {
+        if let Some(thread_id) = chunk_options.thread_id {
+            request = request.message_thread_id(thread_id);
+        }
+        if let Some(reply_id) = chunk_options.reply_to {
             request = request.reply_parameters(ReplyParameters::new(reply_id));
         }
-        if let Err(error) = request.send().await {
-            tracing::debug!(%error, "HTML send failed, retrying as plain text");
-            let plain_chunk = strip_html_tags(&html_chunk);
-            send_plain_text(bot, chat_id, &plain_chunk, reply_to).await?;
+        if let Some(keyboard) = &chunk_options.keyboard {
+            request = request.reply_markup(keyboard.clone());
         }
+        let sent_id = match request.send().await {
+            Ok(sent) => sent.id,
+            Err(error) => {
+                tracing::debug!(%error, "HTML send failed, retrying as plain text");
+                let plain_chunk = strip_html_tags(&html_chunk);
+                send_plain_text(bot, chat_id, &plain_chunk, &chunk_options).await?
+            }
+        };
+        first_id.get_or_insert(sent_id);
     }
-    Ok(())
+    Ok(first_id)
+}
+
+/// Parse a platform message ID previously returned by `send_formatted`.
+fn parse_message_id(message_id: &str) -> anyhow::Result<MessageId> {
+    message_id
+        .parse::<i32>()
+        .map(MessageId)
+        .with_context(|| format!("invalid telegram message id '{message_id}'"))
 }
 
 #[cfg(test)]
@@ -1434,4 +2271,89 @@ mod tests {
         assert!(should_retry_plain_caption(&parse_error));
         assert!(!should_retry_plain_caption(&non_parse_error));
     }
+
+    #[test]
+    fn photos_only_for_small_still_images() {
+        assert!(is_telegram_photo("image/png", 1024));
+        assert!(!is_telegram_photo("image/gif", 1024));
+        assert!(!is_telegram_photo("application/pdf", 1024));
+        assert!(!is_telegram_photo("image/jpeg", 11 * 1024 * 1024));
+    }
+
+    #[test]
+    fn parses_numeric_message_ids() {
+        assert_eq!(parse_message_id("42").expect("valid id"), MessageId(42));
+        assert!(parse_message_id("not-a-number").is_err());
+    }
+
+    #[test]
+    fn callback_data_round_trips_and_overflows_to_tokens() {
+        let select = CallbackAction {
+            action_id: "size".into(),
+            value: Some("large".into()),
+        };
+        let data = callback_data(&select).expect("fits");
+        assert_eq!(parse_callback_data(&data), select);
+
+        let button = CallbackAction {
+            action_id: "approve".into(),
+            value: None,
+        };
+        assert_eq!(
+            parse_callback_data(&callback_data(&button).unwrap()),
+            button
+        );
+
+        let long = CallbackAction {
+            action_id: "choice".into(),
+            value: Some("x".repeat(MAX_CALLBACK_DATA_LENGTH)),
+        };
+        assert_eq!(callback_data(&long), None);
+
+        let elements = vec![
+            crate::InteractiveElements::Buttons {
+                buttons: vec![crate::Button {
+                    label: "Approve".into(),
+                    custom_id: Some("approve".into()),
+                    style: crate::ButtonStyle::Success,
+                    url: None,
+                }],
+            },
+            crate::InteractiveElements::Select {
+                select: crate::SelectMenu {
+                    custom_id: "choice".into(),
+                    options: vec![crate::SelectOption {
+                        label: "Long".into(),
+                        value: "x".repeat(MAX_CALLBACK_DATA_LENGTH),
+                        description: None,
+                        emoji: None,
+                    }],
+                    placeholder: None,
+                },
+            },
+        ];
+        let (keyboard, stored) = build_inline_keyboard(&elements);
+        assert_eq!(keyboard.expect("keyboard").inline_keyboard.len(), 2);
+        assert_eq!(stored.len(), 1);
+        assert!(stored[0].0.starts_with(CALLBACK_TOKEN_PREFIX));
+        assert_eq!(stored[0].1, long);
+    }
+
+    #[test]
+    fn forum_topics_are_separate_conversations_and_targets() {
+        let topic = ThreadId(MessageId(42));
+        assert_eq!(
+            base_conversation_id(-100123, Some(topic)),
+            "telegram:-100123:42"
+        );
+        assert_eq!(base_conversation_id(-100123, None), "telegram:-100123");
+
+        let (chat_id, thread_id) = parse_broadcast_target("-100123:42").expect("valid");
+        assert_eq!((chat_id, thread_id), (ChatId(-100123), Some(topic)));
+        assert_eq!(
+            parse_broadcast_target("-100123").expect("valid"),
+            (ChatId(-100123), None)
+        );
+        assert!(parse_broadcast_target("-100123:general").is_err());
+    }
 }
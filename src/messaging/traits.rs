@@ -35,6 +35,21 @@ pub trait Messaging: Send + Sync + 'static {
         response: OutboundResponse,
     ) -> impl std::future::Future<Output = Result<()>> + Send;
 
+    /// Send a response and return the platform ID of the message it created.
+    ///
+    /// Adapters that support `Edit`/`Delete` override this for the variants
+    /// they can track. The default delivers through `respond` and reports no ID.
+    fn respond_tracked(
+        &self,
+        message: &InboundMessage,
+        response: OutboundResponse,
+    ) -> impl std::future::Future<Output = Result<Option<String>>> + Send {
+        async move {
+            self.respond(message, response).await?;
+            Ok(None)
+        }
+    }
+
     /// Send a status update.
     fn send_status(
         &self,
@@ -65,6 +80,37 @@ pub trait Messaging: Send + Sync + 'static {
         async { Ok(Vec::new()) }
     }
 
+    /// Whether this adapter can join voice channels.
+    fn supports_voice(&self) -> bool {
+        false
+    }
+
+    /// Join the voice channel `voice_channel` (ID or name) in the server of
+    /// `conversation_id`, tying what is said there to that conversation.
+    /// Returns a short description of the channel joined.
+    fn join_voice(
+        &self,
+        conversation_id: &str,
+        voice_channel: &str,
+    ) -> impl std::future::Future<Output = Result<String>> + Send {
+        let _ = (conversation_id, voice_channel);
+        async {
+            Err(crate::error::Error::Other(anyhow::anyhow!(
+                "voice channels are not supported on this platform"
+            )))
+        }
+    }
+
+    /// Leave the voice channel joined for `conversation_id`'s server.
+    /// Returns whether a voice channel was joined.
+    fn leave_voice(
+        &self,
+        conversation_id: &str,
+    ) -> impl std::future::Future<Output = Result<bool>> + Send {
+        let _ = conversation_id;
+        async { Ok(false) }
+    }
+
     /// Health check.
     fn health_check(&self) -> impl std::future::Future<Output = Result<()>> + Send;
 
@@ -89,6 +135,12 @@ pub trait MessagingDyn: Send + Sync + 'static {
         response: OutboundResponse,
     ) -> Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>>;
 
+    fn respond_tracked<'a>(
+        &'a self,
+        message: &'a InboundMessage,
+        response: OutboundResponse,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<Option<String>>> + Send + 'a>>;
+
     fn send_status<'a>(
         &'a self,
         message: &'a InboundMessage,
@@ -107,6 +159,19 @@ pub trait MessagingDyn: Send + Sync + 'static {
         limit: usize,
     ) -> Pin<Box<dyn std::future::Future<Output = Result<Vec<HistoryMessage>>> + Send + 'a>>;
 
+    fn supports_voice(&self) -> bool;
+
+    fn join_voice<'a>(
+        &'a self,
+        conversation_id: &'a str,
+        voice_channel: &'a str,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<String>> + Send + 'a>>;
+
+    fn leave_voice<'a>(
+        &'a self,
+        conversation_id: &'a str,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<bool>> + Send + 'a>>;
+
     fn health_check<'a>(
         &'a self,
     ) -> Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>>;
@@ -135,6 +200,14 @@ impl<T: Messaging> MessagingDyn for T {
         Box::pin(Messaging::respond(self, message, response))
     }
 
+    fn respond_tracked<'a>(
+        &'a self,
+        message: &'a InboundMessage,
+        response: OutboundResponse,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<Option<String>>> + Send + 'a>> {
+        Box::pin(Messaging::respond_tracked(self, message, response))
+    }
+
     fn send_status<'a>(
         &'a self,
         message: &'a InboundMessage,
@@ -159,6 +232,25 @@ impl<T: Messaging> MessagingDyn for T {
         Box::pin(Messaging::fetch_history(self, message, limit))
     }
 
+    fn supports_voice(&self) -> bool {
+        Messaging::supports_voice(self)
+    }
+
+    fn join_voice<'a>(
+        &'a self,
+        conversation_id: &'a str,
+        voice_channel: &'a str,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<String>> + Send + 'a>> {
+        Box::pin(Messaging::join_voice(self, conversation_id, voice_channel))
+    }
+
+    fn leave_voice<'a>(
+        &'a self,
+        conversation_id: &'a str,
+    ) -> Pin<Box<dyn std::future::Future<Output = Result<bool>> + Send + 'a>> {
+        Box::pin(Messaging::leave_voice(self, conversation_id))
+    }
+
     fn health_check<'a>(
         &'a self,
     ) -> Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>> {
@@ -436,6 +436,8 @@ impl Messaging for TwitchAdapter {
             // Reactions, status updates, and Slack-specific variants aren't meaningful in Twitch chat
             OutboundResponse::Reaction(_)
             | OutboundResponse::RemoveReaction(_)
+            | OutboundResponse::Edit { .. }
+            | OutboundResponse::Delete { .. }
             | OutboundResponse::Status(_) => {}
             OutboundResponse::Ephemeral { text, .. } => {
                 // No ephemeral concept in Twitch — send as regular chat message
@@ -194,9 +194,11 @@ impl Messaging for WebhookAdapter {
                 filename: None,
                 caption: None,
             },
-            // Reactions, status updates, and remove-reaction aren't meaningful over webhook
+            // Reactions, status updates, and edits aren't meaningful over webhook
             OutboundResponse::Reaction(_)
             | OutboundResponse::RemoveReaction(_)
+            | OutboundResponse::Edit { .. }
+            | OutboundResponse::Delete { .. }
             | OutboundResponse::Status(_) => return Ok(()),
             // Slack-specific rich variants — fall back to plain text
             OutboundResponse::Ephemeral { text, .. } => WebhookResponse {
@@ -1,5 +1,6 @@
 pub mod engine;
 pub mod text;
+pub mod versions;
 
 pub use engine::{PromptEngine, SkillInfo};
 pub use text::{get as get_text, init as init_language};
@@ -1,9 +1,9 @@
 use crate::error::Result;
 use anyhow::Context;
-use minijinja::{Environment, Value, context};
+use minijinja::{Environment, UndefinedBehavior, Value, context};
 use serde::Serialize;
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, LazyLock, Mutex};
 
 /// A completed background process result, passed to the retrigger template.
 #[derive(Clone, Debug, Serialize)]
@@ -18,11 +18,66 @@ pub struct RetriggerResult {
     pub result: String,
 }
 
+/// Every template the engine loads, by name.
+///
+/// These are the names `render` accepts and the ones that can be overridden
+/// through the prompt editor API.
+pub const TEMPLATE_NAMES: &[&str] = &[
+    "channel",
+    "branch",
+    "worker",
+    "cortex",
+    "cortex_bulletin",
+    "compactor",
+    "memory_persistence",
+    "ingestion",
+    "cortex_chat",
+    "cortex_profile",
+    "factory",
+    "moderation_classifier",
+    "fact_extraction",
+    "adapters/email",
+    "adapters/cron",
+    "adapters/signal",
+    "adapters/github",
+    "adapters/matrix",
+    "adapters/irc",
+    "fragments/worker_capabilities",
+    "fragments/conversation_context",
+    "fragments/skills_channel",
+    "fragments/skills_worker",
+    "fragments/worker_container",
+    "fragments/worker_message",
+    "fragments/available_channels",
+    "fragments/org_context",
+    "fragments/projects_context",
+    "fragments/system/retrigger",
+    "fragments/system/truncation",
+    "fragments/system/worker_overflow",
+    "fragments/system/worker_compact",
+    "fragments/system/memory_persistence",
+    "fragments/system/cortex_synthesis",
+    "fragments/system/profile_synthesis",
+    "fragments/system/ingestion_chunk",
+    "fragments/system/history_backfill",
+    "fragments/system/tool_syntax_correction",
+    "fragments/system/interrupted_turn",
+    "fragments/system/result_schema_correction",
+    "fragments/system/archive_transcript",
+    "fragments/system/prefetch",
+    "fragments/system/takeover_transcript",
+    "fragments/system/takeover_hand_back",
+    "fragments/coalesce_hint",
+    "fragments/prefetched_context",
+    "fragments/language_context",
+];
+
 /// Template engine for rendering system prompts with dynamic variables.
 ///
 /// Prompts are bundled in the binary as `include_str!` embedded templates.
-/// Language selection is done at initialization and templates are not
-/// reloadable at runtime (no file watching, no hot reload).
+/// Language selection is done at initialization. An agent's engine can have
+/// some templates replaced by versions edited through the API; those swap in
+/// by building a new engine with [`PromptEngine::with_overrides`].
 #[derive(Clone)]
 pub struct PromptEngine {
     /// The MiniJinja environment holding all templates for the configured language.
@@ -30,6 +85,8 @@ pub struct PromptEngine {
     env: Arc<Environment<'static>>,
     /// Selected language code (e.g., "en").
     language: String,
+    /// Names of the templates replaced by edited versions.
+    overridden: Arc<HashSet<String>>,
 }
 
 impl PromptEngine {
@@ -46,123 +103,96 @@ impl PromptEngine {
         }
 
         let mut env = Environment::new();
+        for name in TEMPLATE_NAMES {
+            env.add_template(name, crate::prompts::text::get(name))?;
+        }
 
-        // Register all templates from the central text registry
-        // Process prompts
-        env.add_template("channel", crate::prompts::text::get("channel"))?;
-        env.add_template("branch", crate::prompts::text::get("branch"))?;
-        env.add_template("worker", crate::prompts::text::get("worker"))?;
-        env.add_template("cortex", crate::prompts::text::get("cortex"))?;
-        env.add_template(
-            "cortex_bulletin",
-            crate::prompts::text::get("cortex_bulletin"),
-        )?;
-        env.add_template("compactor", crate::prompts::text::get("compactor"))?;
-        env.add_template(
-            "memory_persistence",
-            crate::prompts::text::get("memory_persistence"),
-        )?;
-        env.add_template("ingestion", crate::prompts::text::get("ingestion"))?;
-        env.add_template("cortex_chat", crate::prompts::text::get("cortex_chat"))?;
-        env.add_template(
-            "cortex_profile",
-            crate::prompts::text::get("cortex_profile"),
-        )?;
-        env.add_template("factory", crate::prompts::text::get("factory"))?;
-
-        // Adapter-specific prompt fragments
-        env.add_template(
-            "adapters/email",
-            crate::prompts::text::get("adapters/email"),
-        )?;
-        env.add_template("adapters/cron", crate::prompts::text::get("adapters/cron"))?;
-        env.add_template(
-            "adapters/signal",
-            crate::prompts::text::get("adapters/signal"),
-        )?;
-
-        // Fragment templates
-        env.add_template(
-            "fragments/worker_capabilities",
-            crate::prompts::text::get("fragments/worker_capabilities"),
-        )?;
-        env.add_template(
-            "fragments/conversation_context",
-            crate::prompts::text::get("fragments/conversation_context"),
-        )?;
-        env.add_template(
-            "fragments/skills_channel",
-            crate::prompts::text::get("fragments/skills_channel"),
-        )?;
-        env.add_template(
-            "fragments/skills_worker",
-            crate::prompts::text::get("fragments/skills_worker"),
-        )?;
-        env.add_template(
-            "fragments/available_channels",
-            crate::prompts::text::get("fragments/available_channels"),
-        )?;
-        env.add_template(
-            "fragments/org_context",
-            crate::prompts::text::get("fragments/org_context"),
-        )?;
-        env.add_template(
-            "fragments/projects_context",
-            crate::prompts::text::get("fragments/projects_context"),
-        )?;
+        Ok(Self {
+            env: Arc::new(env),
+            language: language.to_string(),
+            overridden: Arc::default(),
+        })
+    }
 
-        // System message fragments
-        env.add_template(
-            "fragments/system/retrigger",
-            crate::prompts::text::get("fragments/system/retrigger"),
-        )?;
-        env.add_template(
-            "fragments/system/truncation",
-            crate::prompts::text::get("fragments/system/truncation"),
-        )?;
-        env.add_template(
-            "fragments/system/worker_overflow",
-            crate::prompts::text::get("fragments/system/worker_overflow"),
-        )?;
-        env.add_template(
-            "fragments/system/worker_compact",
-            crate::prompts::text::get("fragments/system/worker_compact"),
-        )?;
-        env.add_template(
-            "fragments/system/memory_persistence",
-            crate::prompts::text::get("fragments/system/memory_persistence"),
-        )?;
-        env.add_template(
-            "fragments/system/cortex_synthesis",
-            crate::prompts::text::get("fragments/system/cortex_synthesis"),
-        )?;
-        env.add_template(
-            "fragments/system/profile_synthesis",
-            crate::prompts::text::get("fragments/system/profile_synthesis"),
-        )?;
-        env.add_template(
-            "fragments/system/ingestion_chunk",
-            crate::prompts::text::get("fragments/system/ingestion_chunk"),
-        )?;
-        env.add_template(
-            "fragments/system/history_backfill",
-            crate::prompts::text::get("fragments/system/history_backfill"),
-        )?;
-        env.add_template(
-            "fragments/system/tool_syntax_correction",
-            crate::prompts::text::get("fragments/system/tool_syntax_correction"),
-        )?;
-        env.add_template(
-            "fragments/coalesce_hint",
-            crate::prompts::text::get("fragments/coalesce_hint"),
-        )?;
+    /// Build an engine with the given templates replaced by edited sources.
+    ///
+    /// `overrides` maps template names to source and replaces any overrides
+    /// this engine already has. Names that aren't in [`TEMPLATE_NAMES`] are
+    /// skipped. Fails if an override doesn't compile.
+    pub fn with_overrides(&self, overrides: &HashMap<String, String>) -> anyhow::Result<Self> {
+        let mut env = Environment::new();
+        let mut overridden = HashSet::new();
+        for name in TEMPLATE_NAMES {
+            match overrides.get(*name) {
+                Some(source) => {
+                    env.add_template(name, intern_source(source))
+                        .with_context(|| {
+                            format!("override of template '{name}' doesn't compile")
+                        })?;
+                    overridden.insert(name.to_string());
+                }
+                None => env.add_template(name, crate::prompts::text::get(name))?,
+            }
+        }
+        for name in overrides.keys() {
+            if !overridden.contains(name) {
+                tracing::warn!(template = %name, "ignoring override of unknown prompt template");
+            }
+        }
 
         Ok(Self {
             env: Arc::new(env),
-            language: language.to_string(),
+            language: self.language.clone(),
+            overridden: Arc::new(overridden),
         })
     }
 
+    /// Build an engine with one more template replaced, keeping this
+    /// engine's other overrides.
+    pub fn with_template(&self, name: &str, source: &str) -> anyhow::Result<Self> {
+        let Some(name) = TEMPLATE_NAMES.iter().copied().find(|known| *known == name) else {
+            anyhow::bail!("unknown template '{name}'");
+        };
+        let mut env = (*self.env).clone();
+        env.add_template(name, intern_source(source))
+            .with_context(|| format!("override of template '{name}' doesn't compile"))?;
+        let mut overridden = (*self.overridden).clone();
+        overridden.insert(name.to_string());
+
+        Ok(Self {
+            env: Arc::new(env),
+            language: self.language.clone(),
+            overridden: Arc::new(overridden),
+        })
+    }
+
+    /// Whether a template has been replaced by an edited version.
+    pub fn is_overridden(&self, name: &str) -> bool {
+        self.overridden.contains(name)
+    }
+
+    /// The bundled source of a template, or `None` if there's no such template.
+    pub fn builtin_source(name: &str) -> Option<&'static str> {
+        TEMPLATE_NAMES
+            .contains(&name)
+            .then(|| crate::prompts::text::get(name))
+    }
+
+    /// Check an edited template by compiling it and rendering it against
+    /// `context`. Variables missing from the context render as empty, so
+    /// this catches syntax errors and bad filter or type usage rather than
+    /// missing data. Returns the rendered text.
+    pub fn preview(name: &str, source: &str, context: Value) -> Result<String> {
+        let mut env = Environment::new();
+        env.set_undefined_behavior(UndefinedBehavior::Chainable);
+        env.add_template(name, source)
+            .with_context(|| format!("template '{name}' doesn't compile"))?;
+        env.get_template(name)
+            .and_then(|template| template.render(context))
+            .with_context(|| format!("template '{name}' failed to render"))
+            .map_err(Into::into)
+    }
+
     /// Render a template by name with the given context variables.
     ///
     /// # Arguments
@@ -314,6 +344,23 @@ impl PromptEngine {
         )
     }
 
+    /// Render the container section appended to a container worker's prompt.
+    pub fn render_worker_container(
+        &self,
+        directory: &str,
+        image: &str,
+        network: &str,
+    ) -> Result<String> {
+        self.render(
+            "fragments/worker_container",
+            context! {
+                directory => directory,
+                image => image,
+                network => network,
+            },
+        )
+    }
+
     /// Render the retrigger message with specific process results embedded.
     ///
     /// Each result includes the process type, ID, and full result text so the
@@ -332,6 +379,36 @@ impl PromptEngine {
         self.render_static("fragments/system/tool_syntax_correction")
     }
 
+    /// Correction message when a worker's result doesn't match its `result_schema`.
+    pub fn render_system_result_schema_correction(
+        &self,
+        schema: &serde_json::Value,
+        errors: &[String],
+    ) -> Result<String> {
+        self.render(
+            "fragments/system/result_schema_correction",
+            context! {
+                schema => format!("{schema:#}"),
+                errors => errors,
+            },
+        )
+    }
+
+    /// Compactor input for a segment of stored channel history being archived.
+    pub fn render_system_archive_transcript(&self, transcript: &str) -> Result<String> {
+        self.render(
+            "fragments/system/archive_transcript",
+            context! {
+                transcript => transcript,
+            },
+        )
+    }
+
+    /// Assistant placeholder recorded after the message of an interrupted turn.
+    pub fn render_system_interrupted_turn(&self) -> Result<String> {
+        self.render_static("fragments/system/interrupted_turn")
+    }
+
     /// Convenience method for rendering truncation marker.
     pub fn render_system_truncation(&self, remove_count: usize) -> Result<String> {
         self.render(
@@ -363,6 +440,58 @@ impl PromptEngine {
         self.render_static("fragments/system/memory_persistence")
     }
 
+    /// Convenience method for rendering the speculative prefetch branch prompt.
+    pub fn render_system_prefetch(&self) -> Result<String> {
+        self.render_static("fragments/system/prefetch")
+    }
+
+    /// Render the catch-up message given to a channel when an operator hands
+    /// it back after a takeover.
+    pub fn render_system_takeover_transcript(&self, transcript: &str) -> Result<String> {
+        self.render(
+            "fragments/system/takeover_transcript",
+            context! {
+                transcript => transcript,
+            },
+        )
+    }
+
+    /// Assistant acknowledgement recorded after the takeover transcript.
+    pub fn render_system_takeover_hand_back(&self) -> Result<String> {
+        self.render_static("fragments/system/takeover_hand_back")
+    }
+
+    /// Render the prefetched follow-up context injected into a matching turn.
+    pub fn render_prefetched_context(
+        &self,
+        topics: &[String],
+        age: &str,
+        context: &str,
+    ) -> Result<String> {
+        self.render(
+            "fragments/prefetched_context",
+            context! {
+                topics => topics,
+                age => age,
+                context => context,
+            },
+        )
+    }
+
+    /// Render the reply-language section of a channel's system prompt.
+    ///
+    /// `preferred` is true when the language was set for the channel rather
+    /// than detected from its messages.
+    pub fn render_language_context(&self, language: &str, preferred: bool) -> Result<String> {
+        self.render(
+            "fragments/language_context",
+            context! {
+                language => language,
+                preferred => preferred,
+            },
+        )
+    }
+
     /// Retry nudge sent to a memory-persistence branch that missed its terminal completion call.
     pub fn render_system_memory_persistence_contract_retry(&self) -> Result<String> {
         self.render_static("fragments/system/memory_persistence_contract_retry")
@@ -404,6 +533,7 @@ impl PromptEngine {
         filename: &str,
         chunk_number: usize,
         total_chunks: usize,
+        pages: Option<&str>,
         chunk: &str,
     ) -> Result<String> {
         self.render(
@@ -412,6 +542,7 @@ impl PromptEngine {
                 filename => filename,
                 chunk_number => chunk_number,
                 total_chunks => total_chunks,
+                pages => pages,
                 chunk => chunk,
             },
         )
@@ -444,6 +575,17 @@ impl PromptEngine {
         )
     }
 
+    /// Frame a message one worker sends another through the worker mailbox.
+    pub fn render_worker_message(&self, from_worker_id: &str, message: &str) -> Result<String> {
+        self.render(
+            "fragments/worker_message",
+            context! {
+                from_worker_id => from_worker_id,
+                message => message,
+            },
+        )
+    }
+
     /// Render the complete channel system prompt with all dynamic components.
     #[allow(clippy::too_many_arguments)]
     pub fn render_channel_prompt(
@@ -481,6 +623,9 @@ impl PromptEngine {
             "email" => "adapters/email",
             "cron" => "adapters/cron",
             "signal" => "adapters/signal",
+            "github" => "adapters/github",
+            "matrix" => "adapters/matrix",
+            "irc" => "adapters/irc",
             _ => return None,
         };
 
@@ -639,6 +784,8 @@ pub struct SkillInfo {
     /// Whether the spawning channel suggested this skill for the current task.
     /// Workers should prioritise suggested skills but may read others too.
     pub suggested: bool,
+    /// Skills this one builds on; `read_skill` includes them.
+    pub requires: Vec<String>,
 }
 
 /// Information about a channel for template rendering.
@@ -678,5 +825,22 @@ pub struct ProjectWorktreeContext {
     pub repo_name: String,
 }
 
+/// Give an edited template source the `'static` lifetime the environment
+/// needs. Each distinct source is leaked once; edits are rare, so this stays
+/// small for the life of the process.
+fn intern_source(source: &str) -> &'static str {
+    static SOURCES: LazyLock<Mutex<HashSet<&'static str>>> = LazyLock::new(Mutex::default);
+
+    let mut sources = SOURCES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(existing) = sources.get(source) {
+        return existing;
+    }
+    let leaked: &'static str = Box::leak(source.to_owned().into_boxed_str());
+    sources.insert(leaked);
+    leaked
+}
+
 // All templates are now loaded from the centralized text registry (src/prompts/text.rs)
 // to support multiple languages at compile time.
@@ -65,11 +65,18 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         ("en", "ingestion") => include_str!("../../prompts/en/ingestion.md.j2"),
         ("en", "cortex_chat") => include_str!("../../prompts/en/cortex_chat.md.j2"),
         ("en", "factory") => include_str!("../../prompts/en/factory.md.j2"),
+        ("en", "moderation_classifier") => {
+            include_str!("../../prompts/en/moderation_classifier.md.j2")
+        }
+        ("en", "fact_extraction") => include_str!("../../prompts/en/fact_extraction.md.j2"),
 
         // Adapter-specific prompt fragments
         ("en", "adapters/email") => include_str!("../../prompts/en/adapters/email.md.j2"),
         ("en", "adapters/cron") => include_str!("../../prompts/en/adapters/cron.md.j2"),
         ("en", "adapters/signal") => include_str!("../../prompts/en/adapters/signal.md.j2"),
+        ("en", "adapters/github") => include_str!("../../prompts/en/adapters/github.md.j2"),
+        ("en", "adapters/matrix") => include_str!("../../prompts/en/adapters/matrix.md.j2"),
+        ("en", "adapters/irc") => include_str!("../../prompts/en/adapters/irc.md.j2"),
 
         // Fragment Templates
         ("en", "fragments/worker_capabilities") => {
@@ -84,6 +91,9 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         ("en", "fragments/skills_worker") => {
             include_str!("../../prompts/en/fragments/skills_worker.md.j2")
         }
+        ("en", "fragments/worker_container") => {
+            include_str!("../../prompts/en/fragments/worker_container.md.j2")
+        }
         ("en", "fragments/available_channels") => {
             include_str!("../../prompts/en/fragments/available_channels.md.j2")
         }
@@ -119,6 +129,27 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         ("en", "fragments/system/tool_syntax_correction") => {
             include_str!("../../prompts/en/fragments/system/tool_syntax_correction.md.j2")
         }
+        ("en", "fragments/system/interrupted_turn") => {
+            include_str!("../../prompts/en/fragments/system/interrupted_turn.md.j2")
+        }
+        ("en", "fragments/system/result_schema_correction") => {
+            include_str!("../../prompts/en/fragments/system/result_schema_correction.md.j2")
+        }
+        ("en", "fragments/system/archive_transcript") => {
+            include_str!("../../prompts/en/fragments/system/archive_transcript.md.j2")
+        }
+        ("en", "fragments/system/prefetch") => {
+            include_str!("../../prompts/en/fragments/system/prefetch.md.j2")
+        }
+        ("en", "fragments/system/takeover_transcript") => {
+            include_str!("../../prompts/en/fragments/system/takeover_transcript.md.j2")
+        }
+        ("en", "fragments/system/takeover_hand_back") => {
+            include_str!("../../prompts/en/fragments/system/takeover_hand_back.md.j2")
+        }
+        ("en", "fragments/worker_message") => {
+            include_str!("../../prompts/en/fragments/worker_message.md.j2")
+        }
         // Agent Communication Fragments
         ("en", "fragments/org_context") => {
             include_str!("../../prompts/en/fragments/org_context.md.j2")
@@ -127,6 +158,14 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         ("en", "fragments/coalesce_hint") => {
             include_str!("../../prompts/en/fragments/coalesce_hint.md.j2")
         }
+        // Prefetched Context
+        ("en", "fragments/prefetched_context") => {
+            include_str!("../../prompts/en/fragments/prefetched_context.md.j2")
+        }
+        // Language Context
+        ("en", "fragments/language_context") => {
+            include_str!("../../prompts/en/fragments/language_context.md.j2")
+        }
         // Projects Context
         ("en", "fragments/projects_context") => {
             include_str!("../../prompts/en/fragments/projects_context.md.j2")
@@ -134,10 +173,22 @@ fn lookup(lang: &str, key: &str) -> &'static str {
 
         // Tool Descriptions
         ("en", "tools/reply") => include_str!("../../prompts/en/tools/reply_description.md.j2"),
+        ("en", "tools/edit_reply") => {
+            include_str!("../../prompts/en/tools/edit_reply_description.md.j2")
+        }
+        ("en", "tools/delete_reply") => {
+            include_str!("../../prompts/en/tools/delete_reply_description.md.j2")
+        }
         ("en", "tools/branch") => include_str!("../../prompts/en/tools/branch_description.md.j2"),
         ("en", "tools/spawn_worker") => {
             include_str!("../../prompts/en/tools/spawn_worker_description.md.j2")
         }
+        ("en", "tools/spawn_worker_batch") => {
+            include_str!("../../prompts/en/tools/spawn_worker_batch_description.md.j2")
+        }
+        ("en", "tools/message_worker") => {
+            include_str!("../../prompts/en/tools/message_worker_description.md.j2")
+        }
         ("en", "tools/route") => include_str!("../../prompts/en/tools/route_description.md.j2"),
         ("en", "tools/cancel") => include_str!("../../prompts/en/tools/cancel_description.md.j2"),
         ("en", "tools/skip") => include_str!("../../prompts/en/tools/skip_description.md.j2"),
@@ -149,6 +200,21 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         ("en", "tools/install_skill") => {
             include_str!("../../prompts/en/tools/install_skill_description.md.j2")
         }
+        ("en", "tools/bulletin_pin") => {
+            include_str!("../../prompts/en/tools/bulletin_pin_description.md.j2")
+        }
+        ("en", "tools/bulletin_unpin") => {
+            include_str!("../../prompts/en/tools/bulletin_unpin_description.md.j2")
+        }
+        ("en", "tools/skill_create") => {
+            include_str!("../../prompts/en/tools/skill_create_description.md.j2")
+        }
+        ("en", "tools/skill_update") => {
+            include_str!("../../prompts/en/tools/skill_update_description.md.j2")
+        }
+        ("en", "tools/skill_test") => {
+            include_str!("../../prompts/en/tools/skill_test_description.md.j2")
+        }
         ("en", "tools/file_read") => {
             include_str!("../../prompts/en/tools/file_read_description.md.j2")
         }
@@ -161,6 +227,18 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         ("en", "tools/file_list") => {
             include_str!("../../prompts/en/tools/file_list_description.md.j2")
         }
+        ("en", "tools/git_status") => {
+            include_str!("../../prompts/en/tools/git_status_description.md.j2")
+        }
+        ("en", "tools/git_diff") => {
+            include_str!("../../prompts/en/tools/git_diff_description.md.j2")
+        }
+        ("en", "tools/git_commit") => {
+            include_str!("../../prompts/en/tools/git_commit_description.md.j2")
+        }
+        ("en", "tools/git_branch") => {
+            include_str!("../../prompts/en/tools/git_branch_description.md.j2")
+        }
         ("en", "tools/browser") => include_str!("../../prompts/en/tools/browser_description.md.j2"),
         ("en", "tools/web_search") => {
             include_str!("../../prompts/en/tools/web_search_description.md.j2")
@@ -177,9 +255,18 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         ("en", "tools/memory_delete") => {
             include_str!("../../prompts/en/tools/memory_delete_description.md.j2")
         }
+        ("en", "tools/profile_update") => {
+            include_str!("../../prompts/en/tools/profile_update_description.md.j2")
+        }
         ("en", "tools/channel_recall") => {
             include_str!("../../prompts/en/tools/channel_recall_description.md.j2")
         }
+        ("en", "tools/conversation_search") => {
+            include_str!("../../prompts/en/tools/conversation_search_description.md.j2")
+        }
+        ("en", "tools/episodic_search") => {
+            include_str!("../../prompts/en/tools/episodic_search_description.md.j2")
+        }
         ("en", "tools/email_search") => {
             include_str!("../../prompts/en/tools/email_search_description.md.j2")
         }
@@ -190,6 +277,12 @@ fn lookup(lang: &str, key: &str) -> &'static str {
             include_str!("../../prompts/en/tools/send_file_description.md.j2")
         }
         ("en", "tools/cron") => include_str!("../../prompts/en/tools/cron_description.md.j2"),
+        ("en", "tools/join_voice") => {
+            include_str!("../../prompts/en/tools/join_voice_description.md.j2")
+        }
+        ("en", "tools/leave_voice") => {
+            include_str!("../../prompts/en/tools/leave_voice_description.md.j2")
+        }
         ("en", "tools/send_message_to_another_channel") => {
             include_str!("../../prompts/en/tools/send_message_description.md.j2")
         }
@@ -199,6 +292,12 @@ fn lookup(lang: &str, key: &str) -> &'static str {
         ("en", "tools/send_agent_message") => {
             include_str!("../../prompts/en/tools/send_agent_message_description.md.j2")
         }
+        ("en", "tools/delegate_to_agent") => {
+            include_str!("../../prompts/en/tools/delegate_to_agent_description.md.j2")
+        }
+        ("en", "tools/team_dispatch") => {
+            include_str!("../../prompts/en/tools/team_dispatch_description.md.j2")
+        }
         ("en", "tools/task_create") => {
             include_str!("../../prompts/en/tools/task_create_description.md.j2")
         }
@@ -0,0 +1,266 @@
+//! Version history for prompt templates edited through the API (SQLite).
+//!
+//! Every save adds a version; the latest version of a template is the one the
+//! agent uses. A version without content resets the template to the bundled
+//! one, and rolling back saves an older version's content again, so history
+//! is never rewritten.
+
+use serde::Serialize;
+use sqlx::{Row as _, SqlitePool};
+use std::collections::HashMap;
+
+/// Above this many compared line pairs, diffs fall back to replacing the
+/// whole template instead of aligning lines.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// A saved version of a template.
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptVersion {
+    pub id: String,
+    pub name: String,
+    pub version: i64,
+    /// The template source, or `None` if this version reset it to the
+    /// bundled template.
+    pub content: Option<String>,
+    pub note: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Persists prompt template versions.
+#[derive(Debug, Clone)]
+pub struct PromptVersionStore {
+    pool: SqlitePool,
+}
+
+impl PromptVersionStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Save a new version of a template. `None` content resets it to the
+    /// bundled template.
+    pub async fn save(
+        &self,
+        name: &str,
+        content: Option<&str>,
+        note: Option<&str>,
+    ) -> crate::error::Result<PromptVersion> {
+        let id = uuid::Uuid::new_v4().to_string();
+        sqlx::query(
+            "INSERT INTO prompt_template_versions (id, name, version, content, note) \
+             SELECT ?1, ?2, COALESCE(MAX(version), 0) + 1, ?3, ?4 \
+             FROM prompt_template_versions WHERE name = ?2",
+        )
+        .bind(&id)
+        .bind(name)
+        .bind(content)
+        .bind(note)
+        .execute(&self.pool)
+        .await?;
+
+        self.get(&id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("saved prompt version {id} not found").into())
+    }
+
+    pub async fn get(&self, id: &str) -> crate::error::Result<Option<PromptVersion>> {
+        let row = sqlx::query(
+            "SELECT id, name, version, content, note, created_at \
+             FROM prompt_template_versions WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.as_ref().map(version_from_row))
+    }
+
+    /// The version of a template in use, if it was ever edited.
+    pub async fn latest(&self, name: &str) -> crate::error::Result<Option<PromptVersion>> {
+        let row = sqlx::query(
+            "SELECT id, name, version, content, note, created_at \
+             FROM prompt_template_versions WHERE name = ? \
+             ORDER BY version DESC LIMIT 1",
+        )
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.as_ref().map(version_from_row))
+    }
+
+    /// Versions of a template, newest first.
+    pub async fn history(
+        &self,
+        name: &str,
+        limit: i64,
+    ) -> crate::error::Result<Vec<PromptVersion>> {
+        let rows = sqlx::query(
+            "SELECT id, name, version, content, note, created_at \
+             FROM prompt_template_versions WHERE name = ? \
+             ORDER BY version DESC LIMIT ?",
+        )
+        .bind(name)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.iter().map(version_from_row).collect())
+    }
+
+    /// The source of every template whose latest version overrides the
+    /// bundled one, keyed by template name.
+    pub async fn active_overrides(&self) -> crate::error::Result<HashMap<String, String>> {
+        let rows = sqlx::query(
+            "SELECT v.name, v.content FROM prompt_template_versions v \
+             WHERE v.version = ( \
+                 SELECT MAX(version) FROM prompt_template_versions WHERE name = v.name \
+             ) AND v.content IS NOT NULL",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("name"), row.get("content")))
+            .collect())
+    }
+}
+
+fn version_from_row(row: &sqlx::sqlite::SqliteRow) -> PromptVersion {
+    PromptVersion {
+        id: row.get("id"),
+        name: row.get("name"),
+        version: row.get("version"),
+        content: row.get("content"),
+        note: row.get("note"),
+        created_at: row.get("created_at"),
+    }
+}
+
+/// Whether a diff line is shared, removed, or added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffOp {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// One line of a line-based diff.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DiffLine {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+/// Diff two template sources line by line, aligning them on their longest
+/// common subsequence.
+pub fn line_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let line = |op, text: &str| DiffLine {
+        op,
+        text: text.to_string(),
+    };
+
+    if old.len().saturating_mul(new.len()) > MAX_DIFF_CELLS {
+        return old
+            .iter()
+            .map(|text| line(DiffOp::Delete, text))
+            .chain(new.iter().map(|text| line(DiffOp::Insert, text)))
+            .collect();
+    }
+
+    // lengths[i][j] is the LCS length of old[i..] and new[j..].
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(line(DiffOp::Equal, old[i]));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            diff.push(line(DiffOp::Delete, old[i]));
+            i += 1;
+        } else {
+            diff.push(line(DiffOp::Insert, new[j]));
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().map(|text| line(DiffOp::Delete, text)));
+    diff.extend(new[j..].iter().map(|text| line(DiffOp::Insert, text)));
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn setup_store() -> PromptVersionStore {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to create sqlite memory pool");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("failed to run migrations");
+        PromptVersionStore::new(pool)
+    }
+
+    #[tokio::test]
+    async fn latest_version_decides_the_active_override() {
+        let store = setup_store().await;
+        let first = store.save("channel", Some("v1"), None).await.unwrap();
+        store.save("channel", Some("v2"), None).await.unwrap();
+        store.save("worker", Some("w1"), None).await.unwrap();
+        assert_eq!(first.version, 1);
+
+        let overrides = store.active_overrides().await.unwrap();
+        assert_eq!(overrides.get("channel").map(String::as_str), Some("v2"));
+        assert_eq!(overrides.get("worker").map(String::as_str), Some("w1"));
+
+        // A reset drops the override; rolling back brings an old one back as
+        // a new version.
+        store.save("worker", None, Some("reset")).await.unwrap();
+        let rollback = store
+            .save("channel", first.content.as_deref(), Some("rollback"))
+            .await
+            .unwrap();
+        assert_eq!(rollback.version, 3);
+
+        let overrides = store.active_overrides().await.unwrap();
+        assert_eq!(overrides.get("channel").map(String::as_str), Some("v1"));
+        assert!(!overrides.contains_key("worker"));
+        assert_eq!(store.history("channel", 10).await.unwrap().len(), 3);
+    }
+
+    #[test]
+    fn diff_aligns_unchanged_lines() {
+        let diff = line_diff("a\nb\nc", "a\nx\nc\nd");
+        let ops: Vec<(DiffOp, &str)> = diff
+            .iter()
+            .map(|line| (line.op, line.text.as_str()))
+            .collect();
+        assert_eq!(
+            ops,
+            vec![
+                (DiffOp::Equal, "a"),
+                (DiffOp::Delete, "b"),
+                (DiffOp::Insert, "x"),
+                (DiffOp::Equal, "c"),
+                (DiffOp::Insert, "d"),
+            ]
+        );
+    }
+}
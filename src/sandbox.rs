@@ -4,6 +4,12 @@
 //! On Linux, uses bubblewrap (bwrap) for mount namespace isolation.
 //! On macOS, uses sandbox-exec with a generated SBPL profile.
 //! Falls back to no sandboxing when neither backend is available.
+//! Container workers swap all of this for a per-worker Docker/Podman
+//! container (see [`container`]).
+
+pub mod container;
+
+use container::ContainerSession;
 
 use arc_swap::ArcSwap;
 use serde::{Deserialize, Serialize};
@@ -164,6 +170,9 @@ pub struct Sandbox {
     /// injects them as env vars via `--setenv` (bubblewrap) or `Command::env()`
     /// (passthrough/sandbox-exec).
     secrets_store: ArcSwap<Option<Arc<crate::secrets::store::SecretsStore>>>,
+    /// Set for a container worker's sandbox: every command runs inside this
+    /// container instead of going through the host backend.
+    container: Option<Arc<ContainerSession>>,
 }
 
 impl std::fmt::Debug for Sandbox {
@@ -175,6 +184,7 @@ impl std::fmt::Debug for Sandbox {
             .field("data_dir", &self.data_dir)
             .field("tools_bin", &self.tools_bin)
             .field("backend", &self.backend)
+            .field("container", &self.container.as_ref().map(|c| c.name()))
             .finish()
     }
 }
@@ -237,6 +247,29 @@ impl Sandbox {
             tools_bin,
             backend,
             secrets_store: ArcSwap::from_pointee(None),
+            container: None,
+        }
+    }
+
+    /// Derive the sandbox for a container worker.
+    ///
+    /// Commands run inside `session`'s container, and path checks are scoped
+    /// to the directory bound into it: no agent workspace, writable paths, or
+    /// project paths. The container is removed once the last clone of
+    /// `session` is dropped.
+    pub fn for_container(&self, session: Arc<ContainerSession>) -> Self {
+        let config = SandboxConfig {
+            mode: SandboxMode::Enabled,
+            ..SandboxConfig::default()
+        };
+        Self {
+            config: Arc::new(ArcSwap::from_pointee(config)),
+            workspace: canonicalize_or_self(session.directory()),
+            data_dir: self.data_dir.clone(),
+            tools_bin: self.tools_bin.clone(),
+            backend: SandboxBackend::None,
+            secrets_store: ArcSwap::from_pointee(None),
+            container: Some(session),
         }
     }
 
@@ -303,7 +336,8 @@ impl Sandbox {
     /// If mode is enabled but no backend is available, this returns false
     /// because subprocesses fall back to passthrough execution.
     pub fn containment_active(&self) -> bool {
-        self.mode_enabled() && !matches!(self.backend, SandboxBackend::None)
+        self.container.is_some()
+            || (self.mode_enabled() && !matches!(self.backend, SandboxBackend::None))
     }
 
     /// Read-allowlisted filesystem paths exposed to shell subprocesses when
@@ -312,6 +346,9 @@ impl Sandbox {
         if !self.containment_active() {
             return Vec::new();
         }
+        if self.container.is_some() {
+            return vec![self.workspace.display().to_string()];
+        }
 
         let config = self.config.load();
         let mut paths = Vec::new();
@@ -367,6 +404,9 @@ impl Sandbox {
         if !self.containment_active() {
             return Vec::new();
         }
+        if self.container.is_some() {
+            return vec![self.workspace.display().to_string()];
+        }
 
         let config = self.config.load();
         let mut paths = Vec::new();
@@ -406,6 +446,8 @@ impl Sandbox {
     ///
     /// Reads the current `SandboxMode` from the shared `ArcSwap<SandboxConfig>`
     /// on every call, so changes via the API take effect immediately.
+    ///
+    /// A container worker's sandbox always routes through `<runtime> exec`.
     pub fn wrap(
         &self,
         program: &str,
@@ -413,6 +455,10 @@ impl Sandbox {
         working_dir: &Path,
         command_env: &HashMap<String, String>,
     ) -> Command {
+        if let Some(container) = &self.container {
+            return container.exec(program, args, working_dir, command_env);
+        }
+
         let config = self.config.load();
 
         // Prepend tools/bin to PATH for all commands
@@ -890,6 +936,7 @@ impl Sandbox {
             tools_bin: PathBuf::new(),
             backend: SandboxBackend::None,
             secrets_store: ArcSwap::from_pointee(None),
+            container: None,
         }
     }
 }
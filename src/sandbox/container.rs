@@ -0,0 +1,298 @@
+//! Per-worker containers for `container` workers.
+//!
+//! A container worker gets its own long-lived Docker or Podman container,
+//! started from the configured image with the worker's directory bind-mounted
+//! at the same path. Shell commands run via `<runtime> exec`; file tools keep
+//! running on the host but are confined to that directory, so both see the
+//! same files. The container is removed when the session is dropped.
+
+use crate::WorkerId;
+use crate::config::ContainerConfig;
+
+use anyhow::Context as _;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::process::Command;
+
+/// Label set on every worker container, for finding leftovers by hand.
+const WORKER_LABEL: &str = "spacebot.worker_id";
+
+/// A running worker container.
+#[derive(Debug)]
+pub struct ContainerSession {
+    runtime: String,
+    name: String,
+    directory: PathBuf,
+}
+
+impl ContainerSession {
+    /// Start a container for `worker_id` with `directory` bound into it.
+    pub async fn start(
+        config: &ContainerConfig,
+        worker_id: WorkerId,
+        directory: &Path,
+    ) -> anyhow::Result<Self> {
+        let name = format!("spacebot-worker-{worker_id}");
+        // A worker resumed after a crash may still have its old container.
+        // Remove it so the name is free and the worker doesn't end up with
+        // two containers.
+        Command::new(&config.runtime)
+            .args(["rm", "-f", name.as_str()])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await
+            .with_context(|| format!("failed to run container runtime '{}'", config.runtime))?;
+
+        let output = Command::new(&config.runtime)
+            .args(run_args(config, &name, worker_id, directory))
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .with_context(|| format!("failed to run container runtime '{}'", config.runtime))?;
+
+        if !output.status.success() {
+            anyhow::bail!(
+                "{} run failed: {}",
+                config.runtime,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        tracing::info!(
+            %worker_id,
+            container = %name,
+            image = %config.image,
+            directory = %directory.display(),
+            "worker container started"
+        );
+
+        Ok(Self {
+            runtime: config.runtime.clone(),
+            name,
+            directory: directory.to_path_buf(),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The host directory bound into the container (at the same path).
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Build a command that runs `program` inside the container.
+    pub fn exec(
+        &self,
+        program: &str,
+        args: &[&str],
+        working_dir: &Path,
+        command_env: &HashMap<String, String>,
+    ) -> Command {
+        let mut cmd = Command::new(&self.runtime);
+        cmd.args(exec_args(
+            &self.name,
+            program,
+            args,
+            working_dir,
+            command_env,
+        ));
+        cmd.stdin(Stdio::null());
+        cmd
+    }
+}
+
+impl Drop for ContainerSession {
+    fn drop(&mut self) {
+        // `rm -f` stops and removes in one step. Inside the runtime the
+        // command is spawned without waiting so drop never blocks a worker
+        // thread; tokio reaps the child in the background.
+        let args = ["rm", "-f", self.name.as_str()];
+        let result = if tokio::runtime::Handle::try_current().is_ok() {
+            Command::new(&self.runtime)
+                .args(args)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .map(|_| ())
+        } else {
+            std::process::Command::new(&self.runtime)
+                .args(args)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .map(|_| ())
+        };
+
+        match result {
+            Ok(()) => tracing::debug!(container = %self.name, "worker container removed"),
+            Err(error) => {
+                tracing::warn!(container = %self.name, %error, "failed to remove worker container")
+            }
+        }
+    }
+}
+
+/// Arguments for `<runtime> run` that start an idle worker container.
+fn run_args(
+    config: &ContainerConfig,
+    name: &str,
+    worker_id: WorkerId,
+    directory: &Path,
+) -> Vec<String> {
+    let directory = directory.to_string_lossy();
+    let mut args = vec![
+        "run".to_string(),
+        "--detach".to_string(),
+        "--rm".to_string(),
+        "--init".to_string(),
+        "--name".to_string(),
+        name.to_string(),
+        "--label".to_string(),
+        format!("{WORKER_LABEL}={worker_id}"),
+        "--network".to_string(),
+        config.network.clone(),
+        "--security-opt".to_string(),
+        "no-new-privileges".to_string(),
+    ];
+    if let Some(memory) = &config.memory {
+        args.extend(["--memory".to_string(), memory.clone()]);
+    }
+    if let Some(cpus) = &config.cpus {
+        args.extend(["--cpus".to_string(), cpus.clone()]);
+    }
+    if let Some(pids_limit) = config.pids_limit {
+        args.extend(["--pids-limit".to_string(), pids_limit.to_string()]);
+    }
+    if let Some(user) = &config.user {
+        args.extend(["--user".to_string(), user.clone()]);
+    }
+    args.extend([
+        "--volume".to_string(),
+        format!("{directory}:{directory}"),
+        "--workdir".to_string(),
+        directory.into_owned(),
+    ]);
+    for mount in &config.mounts {
+        args.extend(["--volume".to_string(), mount.clone()]);
+    }
+    // Keep the container alive regardless of the image's own entrypoint;
+    // all work happens through `exec`.
+    args.extend([
+        "--entrypoint".to_string(),
+        "sleep".to_string(),
+        config.image.clone(),
+        "infinity".to_string(),
+    ]);
+    args
+}
+
+/// Arguments for `<runtime> exec` running one command in the container.
+///
+/// Only the per-command env vars reach the container, filtered the same way
+/// as the other backends. Tool secrets and `passthrough_env` are deliberately
+/// not forwarded: container workers exist to run untrusted code.
+fn exec_args(
+    name: &str,
+    program: &str,
+    args: &[&str],
+    working_dir: &Path,
+    command_env: &HashMap<String, String>,
+) -> Vec<String> {
+    let mut exec = vec![
+        "exec".to_string(),
+        "--workdir".to_string(),
+        working_dir.to_string_lossy().into_owned(),
+        "--env".to_string(),
+        "CI=true".to_string(),
+        "--env".to_string(),
+        "DEBIAN_FRONTEND=noninteractive".to_string(),
+    ];
+
+    let mut names: Vec<&String> = command_env.keys().collect();
+    names.sort();
+    for env_name in names {
+        if super::is_reserved_env_var(env_name) {
+            tracing::debug!(%env_name, "skipping reserved per-command env var");
+            continue;
+        }
+        if super::is_dangerous_env_var(env_name) {
+            tracing::warn!(%env_name, "dropping dangerous per-command env var");
+            continue;
+        }
+        exec.extend([
+            "--env".to_string(),
+            format!("{env_name}={}", command_env[env_name]),
+        ]);
+    }
+
+    exec.push(name.to_string());
+    exec.push(program.to_string());
+    exec.extend(args.iter().map(|arg| arg.to_string()));
+    exec
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_args_bind_directory_and_apply_limits() {
+        let config = ContainerConfig {
+            user: Some("1000:1000".into()),
+            mounts: vec!["/srv/cache:/cache:ro".into()],
+            ..ContainerConfig::default()
+        };
+        let worker_id = uuid::Uuid::new_v4();
+        let args = run_args(&config, "box", worker_id, Path::new("/work/repo"));
+        let joined = args.join(" ");
+
+        assert!(joined.starts_with("run --detach --rm --init --name box"));
+        assert!(joined.contains("--network none"));
+        assert!(joined.contains("--memory 2g --cpus 2 --pids-limit 512 --user 1000:1000"));
+        assert!(joined.contains("--volume /work/repo:/work/repo --workdir /work/repo"));
+        assert!(joined.contains("--volume /srv/cache:/cache:ro"));
+        assert!(joined.ends_with("--entrypoint sleep ubuntu:24.04 infinity"));
+    }
+
+    #[test]
+    fn exec_args_filter_env_and_append_command() {
+        let env = HashMap::from([
+            ("PATH".to_string(), "/evil".to_string()),
+            ("LD_PRELOAD".to_string(), "x.so".to_string()),
+            ("RUST_LOG".to_string(), "debug".to_string()),
+        ]);
+        let args = exec_args(
+            "box",
+            "sh",
+            &["-c", "cargo test"],
+            Path::new("/work/repo"),
+            &env,
+        );
+
+        assert_eq!(
+            args,
+            [
+                "exec",
+                "--workdir",
+                "/work/repo",
+                "--env",
+                "CI=true",
+                "--env",
+                "DEBIAN_FRONTEND=noninteractive",
+                "--env",
+                "RUST_LOG=debug",
+                "box",
+                "sh",
+                "-c",
+                "cargo test",
+            ]
+        );
+    }
+}
@@ -1,5 +1,7 @@
 //! Credential storage, output protection, and OS keystore integration.
 
+pub mod config_cipher;
+pub mod content_cipher;
 pub mod keystore;
 pub mod scrub;
 pub mod store;
@@ -0,0 +1,298 @@
+//! Inline encryption for credential values stored directly in `config.toml`.
+//!
+//! Encrypted values look like `enc:v1:<base64>` where the payload is a 12-byte
+//! AES-256-GCM nonce followed by the ciphertext. They are decrypted
+//! transparently by `resolve_env_value()` during config loading, so the TOML
+//! file itself never contains a usable key and is safe to back up or share.
+//!
+//! The 32-byte config key is loaded from, in order:
+//! 1. The file named by `SPACEBOT_CONFIG_KEY_FILE`.
+//! 2. `<instance_dir>/config.key`.
+//! 3. The OS credential store (Keychain / kernel keyring).
+//!
+//! This key is independent of the secrets store master key, but it protects
+//! more than inline config values: `content_cipher` derives each agent's
+//! at-rest content key from it (see `bootstrap_config_cipher` in `main.rs`).
+//! Rotating or losing the config key therefore also leaves conversation
+//! content already encrypted at rest unreadable, not just `enc:v1:` values.
+
+use crate::error::SecretsError;
+use crate::secrets::keystore::platform_keystore;
+use crate::secrets::store::{decrypt_bytes, encrypt_bytes};
+
+use aes_gcm::{Aes256Gcm, KeyInit};
+use base64::Engine as _;
+use rand::RngCore;
+
+use std::path::{Path, PathBuf};
+
+/// Prefix marking an encrypted config value.
+pub const ENCRYPTED_VALUE_PREFIX: &str = "enc:v1:";
+
+/// Environment variable pointing at an explicit config key file.
+pub const CONFIG_KEY_FILE_ENV: &str = "SPACEBOT_CONFIG_KEY_FILE";
+
+/// Default key file name inside the instance directory.
+pub const CONFIG_KEY_FILE_NAME: &str = "config.key";
+
+/// Keystore entry used when the config key lives in the OS credential store.
+const KEYSTORE_CONFIG_KEY_ID: &str = "config";
+
+/// Config key length in bytes (AES-256).
+const CONFIG_KEY_LEN: usize = 32;
+
+/// Where the active config key was loaded from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigKeySource {
+    File(PathBuf),
+    Keystore,
+}
+
+/// Encrypts and decrypts inline config values.
+pub struct ConfigCipher {
+    cipher: Aes256Gcm,
+}
+
+impl std::fmt::Debug for ConfigCipher {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("ConfigCipher(***)")
+    }
+}
+
+impl ConfigCipher {
+    /// Build a cipher from a raw 32-byte key.
+    pub fn from_key(key: &[u8]) -> Result<Self, SecretsError> {
+        if key.len() != CONFIG_KEY_LEN {
+            return Err(SecretsError::InvalidKey);
+        }
+        let cipher = Aes256Gcm::new_from_slice(key).map_err(|_| SecretsError::InvalidKey)?;
+        Ok(Self { cipher })
+    }
+
+    /// Encrypt a plaintext value into `enc:v1:<base64>` form.
+    pub fn encrypt_value(&self, plaintext: &str) -> Result<String, SecretsError> {
+        let stored = encrypt_bytes(&self.cipher, plaintext.as_bytes())?;
+        let encoded = base64::engine::general_purpose::STANDARD.encode(stored);
+        Ok(format!("{ENCRYPTED_VALUE_PREFIX}{encoded}"))
+    }
+
+    /// Decrypt an `enc:v1:<base64>` value back to plaintext.
+    pub fn decrypt_value(&self, value: &str) -> Result<String, SecretsError> {
+        let encoded = value.strip_prefix(ENCRYPTED_VALUE_PREFIX).ok_or_else(|| {
+            SecretsError::DecryptionFailed("value is not an encrypted config value".to_string())
+        })?;
+        let stored = base64::engine::general_purpose::STANDARD
+            .decode(encoded.trim())
+            .map_err(|error| SecretsError::DecryptionFailed(format!("invalid base64: {error}")))?;
+        let plaintext = decrypt_bytes(&self.cipher, &stored)?;
+        String::from_utf8(plaintext)
+            .map_err(|error| SecretsError::DecryptionFailed(format!("invalid UTF-8: {error}")))
+    }
+}
+
+/// Whether a raw config value is in encrypted form.
+pub fn is_encrypted_value(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_VALUE_PREFIX)
+}
+
+/// Path of the config key file for an instance, honoring the env override.
+pub fn config_key_path(instance_dir: &Path) -> PathBuf {
+    std::env::var_os(CONFIG_KEY_FILE_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| instance_dir.join(CONFIG_KEY_FILE_NAME))
+}
+
+/// Load the config key from the key file or OS credential store.
+///
+/// Returns `Ok(None)` when no key has been set up yet.
+pub fn load_config_key(
+    instance_dir: &Path,
+) -> Result<Option<(Vec<u8>, ConfigKeySource)>, SecretsError> {
+    let key_path = config_key_path(instance_dir);
+    if key_path.exists() {
+        let contents = std::fs::read_to_string(&key_path).map_err(|error| {
+            SecretsError::Other(anyhow::anyhow!(
+                "failed to read config key file {}: {error}",
+                key_path.display()
+            ))
+        })?;
+        let key = hex::decode(contents.trim()).map_err(|_| SecretsError::InvalidKey)?;
+        return Ok(Some((key, ConfigKeySource::File(key_path))));
+    }
+
+    Ok(platform_keystore()
+        .load_key(KEYSTORE_CONFIG_KEY_ID)?
+        .map(|key| (key, ConfigKeySource::Keystore)))
+}
+
+/// Generate a new config key and persist it to the key file or OS keystore.
+///
+/// Refuses to overwrite an existing key — values encrypted with it would
+/// become unreadable.
+pub fn create_config_key(
+    instance_dir: &Path,
+    use_keystore: bool,
+) -> Result<(Vec<u8>, ConfigKeySource), SecretsError> {
+    if load_config_key(instance_dir)?.is_some() {
+        return Err(SecretsError::Other(anyhow::anyhow!(
+            "a config key already exists — refusing to overwrite it"
+        )));
+    }
+
+    let mut key = vec![0u8; CONFIG_KEY_LEN];
+    rand::rng().fill_bytes(&mut key);
+
+    if use_keystore {
+        platform_keystore().store_key(KEYSTORE_CONFIG_KEY_ID, &key)?;
+        return Ok((key, ConfigKeySource::Keystore));
+    }
+
+    let key_path = config_key_path(instance_dir);
+    write_key_file(&key_path, &hex::encode(&key)).map_err(|error| {
+        SecretsError::Other(anyhow::anyhow!(
+            "failed to write config key file {}: {error}",
+            key_path.display()
+        ))
+    })?;
+    Ok((key, ConfigKeySource::File(key_path)))
+}
+
+fn write_key_file(path: &Path, contents: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::io::Write as _;
+        use std::os::unix::fs::OpenOptionsExt as _;
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(path)?;
+        file.write_all(contents.as_bytes())
+    }
+
+    #[cfg(not(unix))]
+    {
+        std::fs::write(path, contents)
+    }
+}
+
+/// Encrypt every plaintext credential in the `[llm]` section of a config document.
+///
+/// Covers the shorthand `*_key` fields and `api_key` in `[llm.provider.*]`
+/// tables. `env:` / `secret:` references and already-encrypted values are left
+/// alone. Returns the dotted paths of the values that were encrypted.
+pub fn encrypt_llm_keys(
+    doc: &mut toml_edit::DocumentMut,
+    cipher: &ConfigCipher,
+) -> Result<Vec<String>, SecretsError> {
+    let mut encrypted = Vec::new();
+    let Some(llm) = doc.get_mut("llm").and_then(|item| item.as_table_like_mut()) else {
+        return Ok(encrypted);
+    };
+
+    for (key, item) in llm.iter_mut() {
+        if key.get().ends_with("_key")
+            && let Some(value) = item.as_str()
+            && should_encrypt(value)
+        {
+            *item = toml_edit::value(cipher.encrypt_value(value)?);
+            encrypted.push(format!("llm.{}", key.get()));
+        }
+    }
+
+    if let Some(providers) = llm
+        .get_mut("provider")
+        .and_then(|item| item.as_table_like_mut())
+    {
+        for (provider_id, provider) in providers.iter_mut() {
+            let Some(provider) = provider.as_table_like_mut() else {
+                continue;
+            };
+            if let Some(item) = provider.get_mut("api_key")
+                && let Some(value) = item.as_str()
+                && should_encrypt(value)
+            {
+                *item = toml_edit::value(cipher.encrypt_value(value)?);
+                encrypted.push(format!("llm.provider.{}.api_key", provider_id.get()));
+            }
+        }
+    }
+
+    Ok(encrypted)
+}
+
+fn should_encrypt(value: &str) -> bool {
+    !value.is_empty()
+        && !is_encrypted_value(value)
+        && !value.starts_with("env:")
+        && !value.starts_with("secret:")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_cipher() -> ConfigCipher {
+        ConfigCipher::from_key(&[7u8; CONFIG_KEY_LEN]).expect("valid key")
+    }
+
+    #[test]
+    fn round_trips_values() {
+        let cipher = test_cipher();
+        let encrypted = cipher.encrypt_value("sk-test-123").unwrap();
+        assert!(is_encrypted_value(&encrypted));
+        assert!(!encrypted.contains("sk-test-123"));
+        assert_eq!(cipher.decrypt_value(&encrypted).unwrap(), "sk-test-123");
+    }
+
+    #[test]
+    fn rejects_wrong_key() {
+        let encrypted = test_cipher().encrypt_value("sk-test-123").unwrap();
+        let other = ConfigCipher::from_key(&[9u8; CONFIG_KEY_LEN]).unwrap();
+        assert!(other.decrypt_value(&encrypted).is_err());
+    }
+
+    #[test]
+    fn rejects_short_key() {
+        assert!(ConfigCipher::from_key(&[1u8; 16]).is_err());
+    }
+
+    #[test]
+    fn encrypts_only_plaintext_llm_keys() {
+        let mut doc: toml_edit::DocumentMut = r#"
+[llm]
+anthropic_key = "sk-ant-plain"
+openai_key = "env:OPENAI_API_KEY"
+groq_key = "secret:GROQ_API_KEY"
+ollama_base_url = "http://localhost:11434"
+
+[llm.provider.custom]
+api_type = "openai_completions"
+api_key = "custom-plain"
+"#
+        .parse()
+        .unwrap();
+
+        let cipher = test_cipher();
+        let encrypted = encrypt_llm_keys(&mut doc, &cipher).unwrap();
+        assert_eq!(
+            encrypted,
+            vec!["llm.anthropic_key", "llm.provider.custom.api_key"]
+        );
+
+        let anthropic = doc["llm"]["anthropic_key"].as_str().unwrap();
+        assert_eq!(cipher.decrypt_value(anthropic).unwrap(), "sk-ant-plain");
+        assert_eq!(
+            doc["llm"]["openai_key"].as_str(),
+            Some("env:OPENAI_API_KEY")
+        );
+        assert_eq!(
+            doc["llm"]["ollama_base_url"].as_str(),
+            Some("http://localhost:11434")
+        );
+    }
+}
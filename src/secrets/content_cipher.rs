@@ -0,0 +1,145 @@
+//! At-rest encryption for content agents store in SQLite.
+//!
+//! Encrypted values look like `enc:content:v1:<base64>` where the payload is a
+//! 12-byte AES-256-GCM nonce followed by the ciphertext. Each agent gets its
+//! own key, derived from the instance config key (see `config_cipher`) and
+//! the agent ID, so a row copied into another agent's database can't be
+//! decrypted there.
+//!
+//! The derivation is a single SHA-256 rather than Argon2id: the config key is
+//! 32 random bytes, never a passphrase, so there is nothing to brute-force.
+
+use crate::error::SecretsError;
+use crate::secrets::store::{decrypt_bytes, encrypt_bytes};
+
+use aes_gcm::{Aes256Gcm, KeyInit};
+use base64::Engine as _;
+use sha2::{Digest, Sha256};
+
+use std::sync::Arc;
+
+/// Prefix marking an encrypted content value.
+pub const ENCRYPTED_CONTENT_PREFIX: &str = "enc:content:v1:";
+
+/// Domain separator so the derived keys never equal any other use of the
+/// config key.
+const KEY_DERIVATION_CONTEXT: &[u8] = b"spacebot content encryption v1";
+
+/// Master key length in bytes (AES-256).
+const MASTER_KEY_LEN: usize = 32;
+
+/// Process-wide master key, set once the config key has been loaded.
+static MASTER_KEY: std::sync::LazyLock<arc_swap::ArcSwap<Option<Vec<u8>>>> =
+    std::sync::LazyLock::new(|| arc_swap::ArcSwap::from_pointee(None));
+
+/// Set the master key content keys are derived from (process-wide).
+pub fn set_master_key(key: &[u8]) -> Result<(), SecretsError> {
+    if key.len() != MASTER_KEY_LEN {
+        return Err(SecretsError::InvalidKey);
+    }
+    MASTER_KEY.store(Arc::new(Some(key.to_vec())));
+    Ok(())
+}
+
+/// Encrypts and decrypts one agent's stored content.
+pub struct ContentCipher {
+    cipher: Aes256Gcm,
+}
+
+impl std::fmt::Debug for ContentCipher {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("ContentCipher(***)")
+    }
+}
+
+impl ContentCipher {
+    /// Derive an agent's cipher from a raw 32-byte master key.
+    pub fn derive(master_key: &[u8], agent_id: &str) -> Result<Self, SecretsError> {
+        if master_key.len() != MASTER_KEY_LEN {
+            return Err(SecretsError::InvalidKey);
+        }
+        let mut hasher = Sha256::new();
+        hasher.update(KEY_DERIVATION_CONTEXT);
+        hasher.update((agent_id.len() as u64).to_be_bytes());
+        hasher.update(agent_id.as_bytes());
+        hasher.update(master_key);
+        let key = hasher.finalize();
+        let cipher = Aes256Gcm::new_from_slice(&key).map_err(|_| SecretsError::InvalidKey)?;
+        Ok(Self { cipher })
+    }
+
+    /// The agent's cipher from the process-wide master key, or None when no
+    /// config key has been loaded.
+    pub fn for_agent(agent_id: &str) -> Option<Arc<Self>> {
+        let master_key = MASTER_KEY.load();
+        let master_key = master_key.as_deref()?;
+        match Self::derive(master_key, agent_id) {
+            Ok(cipher) => Some(Arc::new(cipher)),
+            Err(error) => {
+                tracing::warn!(%error, agent_id, "failed to derive content encryption key");
+                None
+            }
+        }
+    }
+
+    /// Encrypt plaintext into `enc:content:v1:<base64>` form.
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, SecretsError> {
+        let stored = encrypt_bytes(&self.cipher, plaintext.as_bytes())?;
+        let encoded = base64::engine::general_purpose::STANDARD.encode(stored);
+        Ok(format!("{ENCRYPTED_CONTENT_PREFIX}{encoded}"))
+    }
+
+    /// Decrypt an `enc:content:v1:<base64>` value back to plaintext.
+    pub fn decrypt(&self, value: &str) -> Result<String, SecretsError> {
+        let encoded = value
+            .strip_prefix(ENCRYPTED_CONTENT_PREFIX)
+            .ok_or_else(|| {
+                SecretsError::DecryptionFailed("value is not encrypted content".to_string())
+            })?;
+        let stored = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|error| SecretsError::DecryptionFailed(format!("invalid base64: {error}")))?;
+        let plaintext = decrypt_bytes(&self.cipher, &stored)?;
+        String::from_utf8(plaintext)
+            .map_err(|error| SecretsError::DecryptionFailed(format!("invalid UTF-8: {error}")))
+    }
+}
+
+/// Whether a stored value is in encrypted form.
+pub fn is_encrypted_content(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_CONTENT_PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_content() {
+        let cipher = ContentCipher::derive(&[7u8; MASTER_KEY_LEN], "main").unwrap();
+        let encrypted = cipher.encrypt("quarterly report draft").unwrap();
+        assert!(is_encrypted_content(&encrypted));
+        assert!(!encrypted.contains("quarterly"));
+        assert_eq!(
+            cipher.decrypt(&encrypted).unwrap(),
+            "quarterly report draft"
+        );
+    }
+
+    #[test]
+    fn keys_differ_per_agent() {
+        let main = ContentCipher::derive(&[7u8; MASTER_KEY_LEN], "main").unwrap();
+        let other = ContentCipher::derive(&[7u8; MASTER_KEY_LEN], "other").unwrap();
+        let encrypted = main.encrypt("secret").unwrap();
+        assert!(other.decrypt(&encrypted).is_err());
+
+        let rotated = ContentCipher::derive(&[9u8; MASTER_KEY_LEN], "main").unwrap();
+        assert!(rotated.decrypt(&encrypted).is_err());
+    }
+
+    #[test]
+    fn rejects_short_master_key() {
+        assert!(ContentCipher::derive(&[1u8; 16], "main").is_err());
+        assert!(set_master_key(&[1u8; 16]).is_err());
+    }
+}
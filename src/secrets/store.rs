@@ -983,7 +983,7 @@ fn derive_cipher(master_key: &[u8], salt: &[u8; 16]) -> Result<Aes256Gcm, Secret
 }
 
 /// Encrypt bytes with AES-256-GCM. Returns nonce (12 bytes) + ciphertext.
-fn encrypt_bytes(cipher: &Aes256Gcm, plaintext: &[u8]) -> Result<Vec<u8>, SecretsError> {
+pub(crate) fn encrypt_bytes(cipher: &Aes256Gcm, plaintext: &[u8]) -> Result<Vec<u8>, SecretsError> {
     let mut nonce_bytes = [0u8; 12];
     rand::rng().fill_bytes(&mut nonce_bytes);
     let nonce = Nonce::from_slice(&nonce_bytes);
@@ -999,7 +999,7 @@ fn encrypt_bytes(cipher: &Aes256Gcm, plaintext: &[u8]) -> Result<Vec<u8>, Secret
 }
 
 /// Decrypt nonce+ciphertext bytes with AES-256-GCM.
-fn decrypt_bytes(cipher: &Aes256Gcm, stored: &[u8]) -> Result<Vec<u8>, SecretsError> {
+pub(crate) fn decrypt_bytes(cipher: &Aes256Gcm, stored: &[u8]) -> Result<Vec<u8>, SecretsError> {
     if stored.len() < 12 {
         return Err(SecretsError::DecryptionFailed(
             "stored value too short for nonce".to_string(),
@@ -1101,8 +1101,8 @@ pub trait SystemSecrets {
 /// here.
 pub fn system_secret_registry() -> Vec<&'static SecretField> {
     use crate::config::{
-        DefaultsConfig, DiscordConfig, EmailConfig, LlmConfig, SignalConfig, SlackConfig,
-        TelegramConfig, TwitchConfig,
+        DefaultsConfig, DiscordConfig, EmailConfig, GithubConfig, IrcConfig, LlmConfig,
+        MatrixConfig, SignalConfig, SlackConfig, TelegramConfig, TwitchConfig,
     };
 
     let mut fields = Vec::new();
@@ -1117,6 +1117,9 @@ pub fn system_secret_registry() -> Vec<&'static SecretField> {
     fields.extend(TwitchConfig::secret_fields());
     fields.extend(EmailConfig::secret_fields());
     fields.extend(SignalConfig::secret_fields());
+    fields.extend(GithubConfig::secret_fields());
+    fields.extend(MatrixConfig::secret_fields());
+    fields.extend(IrcConfig::secret_fields());
     fields
 }
 
@@ -1309,6 +1312,12 @@ mod tests {
 
         // Messaging adapter tokens (default adapters via AdapterSecrets):
         assert_eq!(auto_categorize("DISCORD_BOT_TOKEN"), SecretCategory::System);
+        assert_eq!(
+            auto_categorize("GITHUB_ADAPTER_TOKEN"),
+            SecretCategory::System
+        );
+        assert_eq!(auto_categorize("MATRIX_PASSWORD"), SecretCategory::System);
+        assert_eq!(auto_categorize("IRC_SASL_PASSWORD"), SecretCategory::System);
         assert_eq!(auto_categorize("SLACK_BOT_TOKEN"), SecretCategory::System);
         assert_eq!(auto_categorize("SLACK_APP_TOKEN"), SecretCategory::System);
         assert_eq!(
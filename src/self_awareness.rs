@@ -99,6 +99,7 @@ pub fn runtime_snapshot_value(agent_id: &str, runtime_config: &RuntimeConfig) ->
     let browser = runtime_config.browser_config.load();
     let sandbox = runtime_config.sandbox.load();
     let opencode = runtime_config.opencode.load();
+    let container = runtime_config.container.load();
     let mcp_servers = runtime_config
         .mcp
         .load()
@@ -217,6 +218,8 @@ pub fn runtime_snapshot_value(agent_id: &str, runtime_config: &RuntimeConfig) ->
             "association_similarity_threshold": cortex.association_similarity_threshold,
             "association_updates_threshold": cortex.association_updates_threshold,
             "association_max_per_pass": cortex.association_max_per_pass,
+            "fact_extraction_enabled": cortex.fact_extraction_enabled,
+            "fact_extraction_interval_secs": cortex.fact_extraction_interval_secs,
         },
         "warmup": {
             "enabled": warmup.enabled,
@@ -268,6 +271,16 @@ pub fn runtime_snapshot_value(agent_id: &str, runtime_config: &RuntimeConfig) ->
             "max_restart_retries": opencode.max_restart_retries,
             "permissions": opencode.permissions,
         },
+        "container": {
+            "enabled": container.enabled,
+            "runtime": container.runtime,
+            "image": container.image,
+            "network": container.network,
+            "memory": container.memory,
+            "cpus": container.cpus,
+            "pids_limit": container.pids_limit,
+            "mounts_count": container.mounts.len(),
+        },
         "mcp_servers": mcp_servers,
         "brave_search": {
             "configured": runtime_config.brave_search_key.load().is_some(),
@@ -11,10 +11,22 @@
 //! The channel sees a summary of available skills and is instructed to
 //! delegate skill work to workers. Workers receive the full skill content
 //! in their system prompt.
+//!
+//! Skills can be composed. `requires: [other-skill]` makes another skill part
+//! of this one: workers reading it get every required skill first, in
+//! dependency order. `includes: [partials/setup.md]` inlines markdown files
+//! (relative to the skill directory, within the same skills directory) into
+//! the skill body at load time.
+//!
+//! A skill can also bind a platform slash command with `slash_command`
+//! frontmatter (see [`SlashCommand`]), so common workflows can be invoked
+//! with structured arguments instead of free text.
 
 mod installer;
+mod slash_command;
 
 pub use installer::{install_from_file, install_from_github};
+pub use slash_command::{SlashArgType, SlashCommand, SlashCommandArg};
 
 use anyhow::Context as _;
 use std::collections::HashMap;
@@ -37,6 +49,10 @@ pub struct Skill {
     pub source: SkillSource,
     /// GitHub `owner/repo` that this skill was installed from, if any.
     pub source_repo: Option<String>,
+    /// Names of skills this one builds on, from the `requires` frontmatter.
+    pub requires: Vec<String>,
+    /// Slash command bound to this skill, from the `slash_command` frontmatter.
+    pub slash_command: Option<SlashCommand>,
 }
 
 /// Where a skill was loaded from, used for precedence tracking.
@@ -82,6 +98,12 @@ impl SkillSet {
             }
         }
 
+        for skill in set.skills.values() {
+            if let Err(error) = set.resolve(&skill.name) {
+                tracing::warn!(skill = %skill.name, %error, "skill requirements can't be resolved");
+            }
+        }
+
         if !set.skills.is_empty() {
             tracing::info!(
                 count = set.skills.len(),
@@ -98,6 +120,102 @@ impl SkillSet {
         self.skills.get(&name.to_lowercase())
     }
 
+    /// A skill and everything it requires, transitively, in the order a
+    /// worker should read them: each skill after the skills it requires,
+    /// ending with `name` itself.
+    ///
+    /// Fails if `name` or a requirement isn't loaded, or if requirements form
+    /// a cycle.
+    pub fn resolve(&self, name: &str) -> anyhow::Result<Vec<&Skill>> {
+        fn visit<'a>(
+            set: &'a SkillSet,
+            name: &str,
+            path: &mut Vec<String>,
+            ordered: &mut Vec<&'a Skill>,
+        ) -> anyhow::Result<()> {
+            let key = name.to_lowercase();
+            if ordered.iter().any(|skill| skill.name.to_lowercase() == key) {
+                return Ok(());
+            }
+            if let Some(start) = path.iter().position(|visiting| *visiting == key) {
+                let mut cycle = path[start..].to_vec();
+                cycle.push(key);
+                anyhow::bail!("skill requirements form a cycle: {}", cycle.join(" -> "));
+            }
+            let skill = match (set.get(name), path.last()) {
+                (Some(skill), _) => skill,
+                (None, Some(parent)) => {
+                    anyhow::bail!("skill '{parent}' requires '{name}', which is not installed")
+                }
+                (None, None) => anyhow::bail!("skill '{name}' is not installed"),
+            };
+
+            path.push(key);
+            for requirement in &skill.requires {
+                visit(set, requirement, path, ordered)?;
+            }
+            path.pop();
+            ordered.push(skill);
+            Ok(())
+        }
+
+        let mut ordered = Vec::new();
+        visit(self, name, &mut Vec::new(), &mut ordered)?;
+        Ok(ordered)
+    }
+
+    /// The full instructions for a skill as a worker reads them: every
+    /// required skill first, in dependency order, then the skill itself.
+    pub fn render_skill(&self, name: &str) -> anyhow::Result<String> {
+        let ordered = self.resolve(name)?;
+        let Some((skill, requirements)) = ordered.split_last() else {
+            anyhow::bail!("skill '{name}' is not installed");
+        };
+        if requirements.is_empty() {
+            return Ok(skill.content.clone());
+        }
+
+        let mut rendered = String::new();
+        for requirement in requirements {
+            rendered.push_str(&format!(
+                "<required_skill name=\"{}\">\n{}\n</required_skill>\n\n",
+                requirement.name,
+                requirement.content.trim_end()
+            ));
+        }
+        rendered.push_str(&skill.content);
+        Ok(rendered)
+    }
+
+    /// The skill bound to slash command `name`, with its command.
+    pub fn find_by_command(&self, name: &str) -> Option<(&Skill, &SlashCommand)> {
+        self.skills.values().find_map(|skill| {
+            skill
+                .slash_command
+                .as_ref()
+                .filter(|command| command.name == name)
+                .map(|command| (skill, command))
+        })
+    }
+
+    /// Slash commands declared by these skills, ready to register with a
+    /// platform. Commands without a description borrow the skill's.
+    pub fn slash_commands(&self) -> Vec<SlashCommand> {
+        let mut commands: Vec<SlashCommand> = self
+            .skills
+            .values()
+            .filter_map(|skill| {
+                let mut command = skill.slash_command.clone()?;
+                if command.description.is_none() {
+                    command.description = Some(slash_command::default_description(skill));
+                }
+                Some(command)
+            })
+            .collect();
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+        commands
+    }
+
     /// Iterate over all loaded skills.
     pub fn iter(&self) -> impl Iterator<Item = &Skill> {
         self.skills.values()
@@ -135,6 +253,7 @@ impl SkillSet {
                 description: s.description.clone(),
                 location: s.file_path.display().to_string(),
                 suggested: false,
+                requires: s.requires.clone(),
             })
             .collect();
 
@@ -157,7 +276,13 @@ impl SkillSet {
         let mut sorted_skills: Vec<&Skill> = self.skills.values().collect();
         sorted_skills.sort_by(|a, b| a.name.cmp(&b.name));
 
-        let suggested_lower: Vec<String> = suggested.iter().map(|s| s.to_lowercase()).collect();
+        // A suggested skill brings its requirements along.
+        let mut suggested_lower: Vec<String> = suggested.iter().map(|s| s.to_lowercase()).collect();
+        for name in suggested {
+            if let Ok(ordered) = self.resolve(name) {
+                suggested_lower.extend(ordered.iter().map(|skill| skill.name.to_lowercase()));
+            }
+        }
 
         let skill_infos: Vec<crate::prompts::SkillInfo> = sorted_skills
             .into_iter()
@@ -166,6 +291,7 @@ impl SkillSet {
                 name: s.name.clone(),
                 description: s.description.clone(),
                 location: s.file_path.display().to_string(),
+                requires: s.requires.clone(),
             })
             .collect();
 
@@ -232,11 +358,29 @@ impl SkillSet {
                 base_dir: s.base_dir.clone(),
                 source: s.source.clone(),
                 source_repo: s.source_repo.clone(),
+                requires: s.requires.clone(),
             })
             .collect()
     }
 }
 
+/// Merge slash commands from several agents' skill sets. A platform has one
+/// command namespace per bot, so the first declaration of a name wins.
+pub fn merge_slash_commands<'a>(sets: impl IntoIterator<Item = &'a SkillSet>) -> Vec<SlashCommand> {
+    let mut merged: Vec<SlashCommand> = Vec::new();
+    for command in sets.into_iter().flat_map(SkillSet::slash_commands) {
+        match merged.iter().find(|existing| existing.name == command.name) {
+            Some(existing) if existing != &command => tracing::warn!(
+                command = %command.name,
+                "slash command declared by more than one skill with different definitions, keeping the first"
+            ),
+            Some(_) => {}
+            None => merged.push(command),
+        }
+    }
+    merged
+}
+
 /// Public skill information for API responses.
 #[derive(Debug, Clone)]
 pub struct SkillInfo {
@@ -246,6 +390,7 @@ pub struct SkillInfo {
     pub base_dir: PathBuf,
     pub source: SkillSource,
     pub source_repo: Option<String>,
+    pub requires: Vec<String>,
 }
 
 /// Load all skills from a directory.
@@ -314,6 +459,24 @@ async fn load_skill(
 
     let description = frontmatter.get("description").cloned().unwrap_or_default();
     let source_repo = frontmatter.get("source_repo").cloned();
+    let requires = frontmatter_list(&frontmatter, "requires");
+    let slash_command = frontmatter.get("slash_command").and_then(|raw| {
+        SlashCommand::parse(raw)
+            .inspect_err(|error| {
+                tracing::warn!(
+                    path = %file_path.display(),
+                    %error,
+                    "ignoring invalid slash_command"
+                );
+            })
+            .ok()
+    });
+
+    let mut body = body;
+    for include in frontmatter_list(&frontmatter, "includes") {
+        let partial = read_include(base_dir, &include).await?;
+        body = format!("{}\n\n{}", body.trim_end(), partial.trim());
+    }
 
     // Resolve {baseDir} template variable in the body
     let base_dir_str = base_dir.to_string_lossy();
@@ -327,9 +490,86 @@ async fn load_skill(
         content,
         source,
         source_repo,
+        requires,
+        slash_command,
     })
 }
 
+/// Read a partial named in a skill's `includes`.
+///
+/// Paths are relative to the skill directory and may reach sibling
+/// directories (`../shared/auth.md`), but not outside the skills directory
+/// the skill was loaded from.
+async fn read_include(base_dir: &Path, include: &str) -> anyhow::Result<String> {
+    let skills_dir = base_dir.parent().unwrap_or(base_dir);
+    let skills_dir = tokio::fs::canonicalize(skills_dir)
+        .await
+        .with_context(|| format!("failed to resolve {}", skills_dir.display()))?;
+    let path = tokio::fs::canonicalize(base_dir.join(include))
+        .await
+        .with_context(|| format!("included file not found: {include}"))?;
+    if !path.starts_with(&skills_dir) {
+        anyhow::bail!("included file is outside the skills directory: {include}");
+    }
+
+    tokio::fs::read_to_string(&path)
+        .await
+        .with_context(|| format!("failed to read included file {}", path.display()))
+}
+
+/// Longest skill name accepted by [`validate_skill_file`].
+const MAX_SKILL_NAME_LEN: usize = 64;
+
+/// Check that `content` is a complete SKILL.md and return its name and
+/// description.
+///
+/// Stricter than loading, which falls back to the directory name: a skill
+/// written by the agent must have frontmatter with a lowercase, hyphenated
+/// `name` (it becomes the directory name), a `description`, and a
+/// non-empty body.
+pub fn validate_skill_file(content: &str) -> anyhow::Result<(String, String)> {
+    if !content.trim_start().starts_with("---") {
+        anyhow::bail!("missing frontmatter: SKILL.md must start with a --- block");
+    }
+    let (frontmatter, body) = parse_frontmatter(content)?;
+
+    let name = frontmatter
+        .get("name")
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .context("frontmatter is missing `name`")?;
+    let valid_name = name.len() <= MAX_SKILL_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !name.starts_with('-')
+        && !name.ends_with('-');
+    if !valid_name {
+        anyhow::bail!(
+            "invalid skill name '{name}': use lowercase letters, digits, and hyphens \
+             (at most {MAX_SKILL_NAME_LEN} characters)"
+        );
+    }
+
+    let description = frontmatter
+        .get("description")
+        .map(|description| description.trim())
+        .filter(|description| !description.is_empty())
+        .context("frontmatter is missing `description`")?;
+
+    if body.trim().is_empty() {
+        anyhow::bail!("skill has no instructions after the frontmatter");
+    }
+    if frontmatter_list(&frontmatter, "requires")
+        .iter()
+        .any(|requirement| requirement.eq_ignore_ascii_case(name))
+    {
+        anyhow::bail!("skill '{name}' can't require itself");
+    }
+
+    Ok((name.to_string(), description.to_string()))
+}
+
 /// Parse YAML frontmatter from a markdown file.
 ///
 /// Expects `---` delimiters. Returns the frontmatter key-value pairs and
@@ -360,17 +600,61 @@ fn parse_frontmatter(content: &str) -> anyhow::Result<(HashMap<String, String>,
     // (name, description, homepage, user-invocable, etc.)
     // The metadata field can be complex JSON but we don't need to parse it — we just
     // need name and description for Spacebot's purposes.
+    //
+    // List keys (`requires`, `includes`) accept inline `[a, b]` or block
+    // `- a` items and are stored comma-joined; read them with `frontmatter_list`.
+    // JSON keys (`slash_command`) keep their raw inline JSON.
+    let mut open_list: Option<String> = None;
     for line in frontmatter_str.lines() {
         let line = line.trim();
         if line.is_empty() || line.starts_with('#') {
             continue;
         }
 
+        if let Some(key) = &open_list {
+            if let Some(item) = line.strip_prefix("- ") {
+                let item = unquote(item.trim());
+                map.entry(key.clone())
+                    .and_modify(|items: &mut String| {
+                        items.push_str(", ");
+                        items.push_str(item);
+                    })
+                    .or_insert_with(|| item.to_string());
+                continue;
+            }
+            open_list = None;
+        }
+
         // Handle simple `key: value` pairs
         if let Some((key, value)) = line.split_once(':') {
             let key = key.trim().to_string();
             let value = value.trim();
 
+            if LIST_KEYS.contains(&key.as_str()) {
+                match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                    Some(items) => {
+                        let items: Vec<&str> = items
+                            .split(',')
+                            .map(|item| unquote(item.trim()))
+                            .filter(|item| !item.is_empty())
+                            .collect();
+                        map.insert(key, items.join(", "));
+                    }
+                    None if value.is_empty() => open_list = Some(key),
+                    None => {
+                        map.insert(key, unquote(value).to_string());
+                    }
+                }
+                continue;
+            }
+
+            if JSON_KEYS.contains(&key.as_str()) {
+                if !value.is_empty() {
+                    map.insert(key, value.to_string());
+                }
+                continue;
+            }
+
             // Skip complex multi-line values (metadata JSON blocks, etc.)
             if value.is_empty() || value.starts_with('{') || value.starts_with('[') {
                 continue;
@@ -391,6 +675,31 @@ fn parse_frontmatter(content: &str) -> anyhow::Result<(HashMap<String, String>,
     Ok((map, body))
 }
 
+/// Frontmatter keys that hold lists.
+const LIST_KEYS: &[&str] = &["requires", "includes"];
+
+/// Frontmatter keys that hold inline JSON.
+const JSON_KEYS: &[&str] = &["slash_command"];
+
+fn unquote(value: &str) -> &str {
+    value.trim_matches('"').trim_matches('\'')
+}
+
+/// Split a list key from `parse_frontmatter` into its items.
+fn frontmatter_list(frontmatter: &HashMap<String, String>, key: &str) -> Vec<String> {
+    frontmatter
+        .get(key)
+        .map(|items| {
+            items
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -442,6 +751,24 @@ mod tests {
         assert!(body.starts_with("# GitHub Skill"));
     }
 
+    #[test]
+    fn test_parse_frontmatter_slash_command() {
+        let content = indoc::indoc! {r#"
+            ---
+            name: standup-report
+            description: Summarize yesterday's work for a team.
+            slash_command: { "name": "standup", "args": [{ "name": "team", "required": true }] }
+            ---
+
+            # Standup
+        "#};
+
+        let (fm, _body) = parse_frontmatter(content).unwrap();
+        let command = SlashCommand::parse(fm.get("slash_command").unwrap()).unwrap();
+        assert_eq!(command.name, "standup");
+        assert_eq!(command.args[0].name, "team");
+    }
+
     #[test]
     fn test_parse_frontmatter_no_frontmatter() {
         let content = "# Just a markdown file\n\nNo frontmatter here.";
@@ -488,6 +815,8 @@ mod tests {
                 content: "# Weather\n\nUse curl.".into(),
                 source: SkillSource::Instance,
                 source_repo: None,
+                requires: Vec::new(),
+                slash_command: None,
             },
         );
 
@@ -511,6 +840,8 @@ mod tests {
                 content: "# Weather\n\nUse curl.".into(),
                 source: SkillSource::Instance,
                 source_repo: None,
+                requires: Vec::new(),
+                slash_command: None,
             },
         );
 
@@ -533,6 +864,144 @@ mod tests {
         assert!(prompt.is_empty());
     }
 
+    #[test]
+    fn test_validate_skill_file() {
+        let (name, description) = validate_skill_file(
+            "---\nname: weekly-report\ndescription: Build the weekly report.\n---\n\n# Weekly Report\n\nSteps.",
+        )
+        .unwrap();
+        assert_eq!(name, "weekly-report");
+        assert_eq!(description, "Build the weekly report.");
+
+        let rejected = [
+            "# No frontmatter\n\nSteps.",
+            "---\ndescription: Missing name.\n---\n\nSteps.",
+            "---\nname: Weekly Report\ndescription: Spaces.\n---\n\nSteps.",
+            "---\nname: ../escape\ndescription: Path.\n---\n\nSteps.",
+            "---\nname: weekly-report\n---\n\nSteps.",
+            "---\nname: weekly-report\ndescription: No body.\n---\n\n",
+        ];
+        for content in rejected {
+            assert!(validate_skill_file(content).is_err(), "accepted: {content}");
+        }
+    }
+
+    #[test]
+    fn test_parse_frontmatter_lists() {
+        let content = indoc::indoc! {r#"
+            ---
+            name: release
+            description: Cut a release.
+            requires: [changelog, "git-tag"]
+            includes:
+              - partials/checklist.md
+              - ../shared/auth.md
+            ---
+
+            # Release
+        "#};
+
+        let (fm, _body) = parse_frontmatter(content).unwrap();
+        assert_eq!(
+            frontmatter_list(&fm, "requires"),
+            vec!["changelog", "git-tag"]
+        );
+        assert_eq!(
+            frontmatter_list(&fm, "includes"),
+            vec!["partials/checklist.md", "../shared/auth.md"]
+        );
+        assert_eq!(fm.get("description").unwrap(), "Cut a release.");
+    }
+
+    fn skill_requiring(name: &str, requires: &[&str]) -> Skill {
+        Skill {
+            requires: requires.iter().map(|r| r.to_string()).collect(),
+            slash_command: None,
+            content: format!("# {name}"),
+            ..make_skill(name, SkillSource::Workspace)
+        }
+    }
+
+    #[test]
+    fn resolve_orders_requirements_and_detects_cycles() {
+        let mut set = SkillSet::default();
+        for skill in [
+            skill_requiring("release", &["changelog", "git-tag"]),
+            skill_requiring("changelog", &["git-tag"]),
+            skill_requiring("git-tag", &[]),
+        ] {
+            set.skills.insert(skill.name.clone(), skill);
+        }
+
+        let order: Vec<&str> = set
+            .resolve("release")
+            .unwrap()
+            .iter()
+            .map(|skill| skill.name.as_str())
+            .collect();
+        assert_eq!(order, vec!["git-tag", "changelog", "release"]);
+
+        let rendered = set.render_skill("release").unwrap();
+        assert!(rendered.find("# git-tag").unwrap() < rendered.find("# changelog").unwrap());
+        assert!(rendered.ends_with("# release"));
+
+        let engine = crate::prompts::PromptEngine::new("en").unwrap();
+        let prompt = set.render_worker_skills(&["changelog"], &engine).unwrap();
+        assert_eq!(prompt.matches("suggested=\"true\"").count(), 2);
+
+        set.skills
+            .insert("git-tag".into(), skill_requiring("git-tag", &["release"]));
+        let error = set.resolve("release").unwrap_err().to_string();
+        assert!(error.contains("cycle"), "unexpected error: {error}");
+
+        set.skills
+            .insert("git-tag".into(), skill_requiring("git-tag", &["missing"]));
+        let error = set.resolve("release").unwrap_err().to_string();
+        assert!(error.contains("'missing'"), "unexpected error: {error}");
+    }
+
+    #[tokio::test]
+    async fn includes_are_inlined_within_the_skills_directory() {
+        let root = std::env::temp_dir().join(format!("skills-{}", uuid::Uuid::new_v4()));
+        let skills_dir = root.join("skills");
+        let skill_dir = skills_dir.join("release");
+        std::fs::create_dir_all(skills_dir.join("shared")).unwrap();
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(skills_dir.join("shared/auth.md"), "Log in first.").unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: release\ndescription: Cut a release.\nincludes: [../shared/auth.md]\n---\n\n# Release",
+        )
+        .unwrap();
+
+        let skill = load_skill(
+            &skill_dir.join("SKILL.md"),
+            &skill_dir,
+            SkillSource::Workspace,
+        )
+        .await
+        .unwrap();
+        assert_eq!(skill.content, "# Release\n\nLog in first.");
+
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: release\ndescription: Cut a release.\nincludes: [../../outside.md]\n---\n\n# Release",
+        )
+        .unwrap();
+        std::fs::write(root.join("outside.md"), "Not a partial.").unwrap();
+        assert!(
+            load_skill(
+                &skill_dir.join("SKILL.md"),
+                &skill_dir,
+                SkillSource::Workspace
+            )
+            .await
+            .is_err()
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
     fn make_skill(name: &str, source: SkillSource) -> Skill {
         Skill {
             name: name.into(),
@@ -542,6 +1011,8 @@ mod tests {
             content: format!("# {name}"),
             source,
             source_repo: None,
+            requires: Vec::new(),
+            slash_command: None,
         }
     }
 
@@ -0,0 +1,223 @@
+//! Slash commands declared by skills.
+//!
+//! A skill can bind itself to a platform slash command with a
+//! `slash_command` frontmatter key holding inline JSON:
+//!
+//! ```yaml
+//! slash_command: { "name": "standup", "description": "Post a standup summary", "args": [{ "name": "team", "type": "string", "required": true }] }
+//! ```
+//!
+//! Adapters that support native commands (Discord) register these at
+//! startup. Invocations arrive as [`crate::MessageContent::Command`] with the
+//! arguments already parsed, and the channel hands them straight to a worker
+//! running the skill instead of interpreting free text.
+
+use serde::{Deserialize, Serialize};
+
+/// Most arguments a command can take (Discord's option limit).
+const MAX_ARGS: usize = 25;
+
+/// Longest command or argument name (Discord's limit).
+const MAX_NAME_LEN: usize = 32;
+
+/// Longest command or argument description (Discord's limit).
+const MAX_DESCRIPTION_LEN: usize = 100;
+
+/// A slash command bound to a skill.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlashCommand {
+    /// Command name without the leading `/`. Lowercase letters, digits,
+    /// `-` and `_`.
+    pub name: String,
+    /// Shown in the platform's command picker. Defaults to the skill's
+    /// description.
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub args: Vec<SlashCommandArg>,
+}
+
+/// One argument of a slash command.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlashCommandArg {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub kind: SlashArgType,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    /// Fixed values to pick from. Only for `string` arguments.
+    #[serde(default)]
+    pub choices: Vec<String>,
+}
+
+/// Value type of a slash command argument.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlashArgType {
+    #[default]
+    String,
+    Integer,
+    Number,
+    Boolean,
+    /// A platform user, passed to the skill as a mention.
+    User,
+    /// A platform channel, passed to the skill as a mention.
+    Channel,
+}
+
+impl SlashCommand {
+    /// Parse the `slash_command` frontmatter value.
+    pub fn parse(raw: &str) -> anyhow::Result<Self> {
+        let command: Self = serde_json::from_str(raw)
+            .map_err(|error| anyhow::anyhow!("invalid slash_command JSON: {error}"))?;
+        command.validate()?;
+        Ok(command)
+    }
+
+    /// Check the command against platform limits.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        validate_name("command", &self.name)?;
+        if let Some(description) = &self.description {
+            validate_description(&self.name, description)?;
+        }
+        if self.args.len() > MAX_ARGS {
+            anyhow::bail!(
+                "command '{}' has {} args; at most {MAX_ARGS} are allowed",
+                self.name,
+                self.args.len()
+            );
+        }
+
+        let mut seen_optional = false;
+        for (index, arg) in self.args.iter().enumerate() {
+            validate_name("argument", &arg.name)?;
+            if self.args[..index]
+                .iter()
+                .any(|earlier| earlier.name == arg.name)
+            {
+                anyhow::bail!("command '{}' has two args named '{}'", self.name, arg.name);
+            }
+            if let Some(description) = &arg.description {
+                validate_description(&arg.name, description)?;
+            }
+            if arg.required && seen_optional {
+                anyhow::bail!(
+                    "command '{}': required arg '{}' must come before optional args",
+                    self.name,
+                    arg.name
+                );
+            }
+            seen_optional |= !arg.required;
+            if !arg.choices.is_empty() && arg.kind != SlashArgType::String {
+                anyhow::bail!(
+                    "command '{}': choices are only supported on string args",
+                    self.name
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Worker task for an invocation of this command bound to `skill_name`.
+    pub fn worker_task(
+        &self,
+        skill_name: &str,
+        args: &serde_json::Map<String, serde_json::Value>,
+    ) -> String {
+        let mut task = format!(
+            "Run the `{skill_name}` skill. A user invoked it with `/{}`",
+            self.name
+        );
+        if args.is_empty() {
+            task.push_str(" and no arguments.");
+            return task;
+        }
+        task.push_str(" with these arguments:");
+        // Declared order first, then anything the platform sent that the
+        // command doesn't declare.
+        let declared = self
+            .args
+            .iter()
+            .filter_map(|arg| args.get_key_value(&arg.name));
+        let undeclared = args
+            .iter()
+            .filter(|(name, _)| !self.args.iter().any(|arg| &arg.name == *name));
+        for (name, value) in declared.chain(undeclared) {
+            let value = match value {
+                serde_json::Value::String(text) => text.clone(),
+                other => other.to_string(),
+            };
+            task.push_str(&format!("\n- {name}: {value}"));
+        }
+        task
+    }
+}
+
+/// Picker description for a command that doesn't set one: the skill's
+/// description, cut to fit.
+pub(super) fn default_description(skill: &super::Skill) -> String {
+    let description = skill.description.trim();
+    if description.is_empty() {
+        return format!("Run the {} skill", skill.name)
+            .chars()
+            .take(MAX_DESCRIPTION_LEN)
+            .collect();
+    }
+    if description.chars().count() <= MAX_DESCRIPTION_LEN {
+        return description.to_string();
+    }
+    let mut truncated: String = description.chars().take(MAX_DESCRIPTION_LEN - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+fn validate_name(what: &str, name: &str) -> anyhow::Result<()> {
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if name.is_empty() || name.len() > MAX_NAME_LEN || !valid_chars {
+        anyhow::bail!(
+            "{what} name '{name}' must be 1-{MAX_NAME_LEN} lowercase letters, digits, '-' or '_'"
+        );
+    }
+    Ok(())
+}
+
+fn validate_description(name: &str, description: &str) -> anyhow::Result<()> {
+    let length = description.chars().count();
+    if length == 0 || length > MAX_DESCRIPTION_LEN {
+        anyhow::bail!("description of '{name}' must be 1-{MAX_DESCRIPTION_LEN} characters");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_validates_frontmatter() {
+        let command = SlashCommand::parse(
+            r#"{ "name": "standup", "args": [{ "name": "team", "required": true, "choices": ["eng", "ops"] }, { "name": "days", "type": "integer" }] }"#,
+        )
+        .unwrap();
+        assert_eq!(command.args[0].kind, SlashArgType::String);
+        assert_eq!(command.args[1].kind, SlashArgType::Integer);
+
+        let args = serde_json::json!({ "days": 3, "team": "eng" });
+        assert_eq!(
+            command.worker_task("standup-report", args.as_object().unwrap()),
+            "Run the `standup-report` skill. A user invoked it with `/standup` with these arguments:\n- team: eng\n- days: 3"
+        );
+
+        assert!(SlashCommand::parse(r#"{ "name": "Stand Up" }"#).is_err());
+        assert!(
+            SlashCommand::parse(
+                r#"{ "name": "standup", "args": [{ "name": "days" }, { "name": "team", "required": true }] }"#
+            )
+            .is_err()
+        );
+    }
+}
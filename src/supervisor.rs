@@ -0,0 +1,521 @@
+//! Supervision of long-lived background tasks.
+//!
+//! Messaging adapters, the config watcher, cortex and maintenance loops, and
+//! cron timers are started through [`Supervisor::supervise`] instead of a bare
+//! `tokio::spawn`. The supervisor records a heartbeat for each task while it
+//! is alive and restarts it with exponential backoff when it crashes. A task
+//! that keeps crashing without ever running stably is marked failed and left
+//! down. `GET /api/system/processes` lists every supervised task.
+
+use crate::agent::panic_payload_to_string;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// Instance-wide supervisor. Initialized on first use.
+static SUPERVISOR: LazyLock<Supervisor> = LazyLock::new(Supervisor::new);
+
+/// How often the supervisor records that a running task is still alive.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Restart timing. The backoff doubles after each crash up to `max_backoff`,
+/// and resets once a task has stayed up for `stable_after`.
+#[derive(Debug, Clone, Copy)]
+struct RestartPolicy {
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    stable_after: Duration,
+    /// Crashes in a row, without a stable run in between, before giving up.
+    max_consecutive_crashes: u32,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            stable_after: Duration::from_secs(60),
+            max_consecutive_crashes: 10,
+        }
+    }
+}
+
+/// What a supervised task does, for grouping in the API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessKind {
+    /// A messaging adapter's connection and inbound stream.
+    Adapter,
+    /// A file watcher, e.g. config hot reload.
+    Watcher,
+    /// A cortex loop: warmup, bulletins, associations, ready-task pickup.
+    Cortex,
+    /// Housekeeping loops: ingestion, storage monitoring, archival.
+    Maintenance,
+    /// A cron job timer.
+    Scheduler,
+}
+
+/// When a supervised task is restarted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Restart {
+    /// The task is meant to run until stopped, so any exit is a crash.
+    Always,
+    /// The task may finish on its own; only a panic is a crash. A task that
+    /// finishes cleanly is removed from the supervisor.
+    OnPanic,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessStatus {
+    Running,
+    /// Crashed and waiting out its backoff before the next restart.
+    Restarting,
+    /// Crashed too many times in a row and is no longer restarted.
+    Failed,
+}
+
+/// Identity and restart behaviour of a supervised task.
+#[derive(Debug, Clone)]
+pub struct ProcessSpec {
+    kind: ProcessKind,
+    name: String,
+    agent_id: Option<String>,
+    restart: Restart,
+}
+
+impl ProcessSpec {
+    pub fn new(kind: ProcessKind, name: impl Into<String>) -> Self {
+        Self {
+            kind,
+            name: name.into(),
+            agent_id: None,
+            restart: Restart::Always,
+        }
+    }
+
+    /// Attribute the task to an agent, so it stops with the agent.
+    pub fn agent(mut self, agent_id: impl Into<String>) -> Self {
+        self.agent_id = Some(agent_id.into());
+        self
+    }
+
+    pub fn restart(mut self, restart: Restart) -> Self {
+        self.restart = restart;
+        self
+    }
+
+    fn same_process(&self, other: &Self) -> bool {
+        self.kind == other.kind && self.agent_id == other.agent_id && self.name == other.name
+    }
+}
+
+/// Point-in-time view of a supervised task for the API.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessSnapshot {
+    pub kind: ProcessKind,
+    pub name: String,
+    pub agent_id: Option<String>,
+    pub status: ProcessStatus,
+    pub restart_count: u32,
+    /// When the current run (or the last one, if down) started.
+    pub started_at: DateTime<Utc>,
+    /// Last time the supervisor saw the task alive.
+    pub last_heartbeat_at: DateTime<Utc>,
+    /// How the most recent crash ended the task.
+    pub last_error: Option<String>,
+    /// When the next restart is due, while restarting.
+    pub next_restart_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+struct Process {
+    spec: ProcessSpec,
+    stop: Notify,
+    state: Mutex<ProcessState>,
+}
+
+#[derive(Debug)]
+struct ProcessState {
+    status: ProcessStatus,
+    restart_count: u32,
+    started_at: DateTime<Utc>,
+    last_heartbeat_at: DateTime<Utc>,
+    last_error: Option<String>,
+    next_restart_at: Option<DateTime<Utc>>,
+}
+
+impl Process {
+    fn snapshot(&self) -> ProcessSnapshot {
+        let state = self.state.lock().expect("process state lock poisoned");
+        ProcessSnapshot {
+            kind: self.spec.kind,
+            name: self.spec.name.clone(),
+            agent_id: self.spec.agent_id.clone(),
+            status: state.status,
+            restart_count: state.restart_count,
+            started_at: state.started_at,
+            last_heartbeat_at: state.last_heartbeat_at,
+            last_error: state.last_error.clone(),
+            next_restart_at: state.next_restart_at,
+        }
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut ProcessState)) {
+        apply(&mut self.state.lock().expect("process state lock poisoned"));
+    }
+}
+
+type Registry = Arc<RwLock<Vec<Arc<Process>>>>;
+
+fn unregister(registry: &Registry, process: &Arc<Process>) {
+    registry
+        .write()
+        .expect("supervisor lock poisoned")
+        .retain(|entry| !Arc::ptr_eq(entry, process));
+}
+
+/// Registry of supervised tasks, in the order they were started.
+#[derive(Debug, Default)]
+pub struct Supervisor {
+    processes: Registry,
+    policy: RestartPolicy,
+}
+
+/// Handle to one supervised task. Dropping it leaves the task running.
+#[derive(Debug)]
+pub struct ProcessHandle {
+    process: Arc<Process>,
+    registry: Registry,
+    monitor: JoinHandle<()>,
+}
+
+impl ProcessHandle {
+    /// Stop the task without restarting it and wait until it has finished.
+    pub async fn stop(self) {
+        unregister(&self.registry, &self.process);
+        self.process.stop.notify_one();
+        let _ = self.monitor.await;
+    }
+}
+
+impl Supervisor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn global() -> &'static Self {
+        &SUPERVISOR
+    }
+
+    /// Start the task `spawn` returns and keep it running. `spawn` is called
+    /// again for every restart, so it must build the task from scratch.
+    ///
+    /// Supervising a task with the same kind, agent and name as an existing
+    /// one stops and replaces the existing one.
+    pub fn supervise<F>(&self, spec: ProcessSpec, spawn: F) -> ProcessHandle
+    where
+        F: Fn() -> JoinHandle<()> + Send + Sync + 'static,
+    {
+        let now = Utc::now();
+        let process = Arc::new(Process {
+            spec,
+            stop: Notify::new(),
+            state: Mutex::new(ProcessState {
+                status: ProcessStatus::Running,
+                restart_count: 0,
+                started_at: now,
+                last_heartbeat_at: now,
+                last_error: None,
+                next_restart_at: None,
+            }),
+        });
+
+        {
+            let mut processes = self.processes.write().expect("supervisor lock poisoned");
+            if let Some(index) = processes
+                .iter()
+                .position(|existing| existing.spec.same_process(&process.spec))
+            {
+                let replaced = processes.remove(index);
+                replaced.stop.notify_one();
+            }
+            processes.push(process.clone());
+        }
+
+        let monitor = tokio::spawn(monitor(
+            process.clone(),
+            self.processes.clone(),
+            self.policy,
+            spawn,
+        ));
+
+        ProcessHandle {
+            process,
+            registry: self.processes.clone(),
+            monitor,
+        }
+    }
+
+    /// Stop one task without restarting it. Does not wait for it to finish.
+    pub fn stop(&self, kind: ProcessKind, agent_id: Option<&str>, name: &str) {
+        self.stop_matching(|spec| {
+            spec.kind == kind && spec.agent_id.as_deref() == agent_id && spec.name == name
+        });
+    }
+
+    /// Stop every task attributed to an agent, e.g. when it is removed.
+    pub fn stop_agent(&self, agent_id: &str) {
+        self.stop_matching(|spec| spec.agent_id.as_deref() == Some(agent_id));
+    }
+
+    /// Stop every supervised task. Used on shutdown.
+    pub fn stop_all(&self) {
+        self.stop_matching(|_| true);
+    }
+
+    fn stop_matching(&self, matches: impl Fn(&ProcessSpec) -> bool) {
+        self.processes
+            .write()
+            .expect("supervisor lock poisoned")
+            .retain(|process| {
+                if matches(&process.spec) {
+                    process.stop.notify_one();
+                    false
+                } else {
+                    true
+                }
+            });
+    }
+
+    pub fn snapshots(&self) -> Vec<ProcessSnapshot> {
+        self.processes
+            .read()
+            .expect("supervisor lock poisoned")
+            .iter()
+            .map(|process| process.snapshot())
+            .collect()
+    }
+}
+
+/// Run, watch and restart one supervised task until it is stopped, finishes
+/// cleanly under [`Restart::OnPanic`], or gives up after repeated crashes.
+async fn monitor<F>(process: Arc<Process>, registry: Registry, policy: RestartPolicy, spawn: F)
+where
+    F: Fn() -> JoinHandle<()> + Send + Sync + 'static,
+{
+    let spec = &process.spec;
+    let mut backoff = policy.initial_backoff;
+    let mut consecutive_crashes = 0;
+
+    loop {
+        let started = Instant::now();
+        let mut task = spawn();
+        process.update(|state| {
+            state.status = ProcessStatus::Running;
+            state.started_at = Utc::now();
+            state.last_heartbeat_at = state.started_at;
+            state.next_restart_at = None;
+        });
+
+        let mut heartbeat = tokio::time::interval_at(
+            tokio::time::Instant::now() + HEARTBEAT_INTERVAL,
+            HEARTBEAT_INTERVAL,
+        );
+        let result = loop {
+            tokio::select! {
+                result = &mut task => break result,
+                _ = heartbeat.tick() => {
+                    process.update(|state| state.last_heartbeat_at = Utc::now());
+                }
+                _ = process.stop.notified() => {
+                    task.abort();
+                    let _ = task.await;
+                    tracing::debug!(kind = ?spec.kind, name = %spec.name, "supervised process stopped");
+                    return;
+                }
+            }
+        };
+
+        let error = match result {
+            Ok(()) if spec.restart == Restart::OnPanic => {
+                unregister(&registry, &process);
+                return;
+            }
+            Ok(()) => "exited unexpectedly".to_string(),
+            Err(error) if error.is_panic() => {
+                format!(
+                    "panicked: {}",
+                    panic_payload_to_string(&*error.into_panic())
+                )
+            }
+            Err(error) => error.to_string(),
+        };
+
+        if started.elapsed() >= policy.stable_after {
+            backoff = policy.initial_backoff;
+            consecutive_crashes = 0;
+        }
+        consecutive_crashes += 1;
+
+        if consecutive_crashes > policy.max_consecutive_crashes {
+            tracing::error!(
+                kind = ?spec.kind,
+                name = %spec.name,
+                agent_id = ?spec.agent_id,
+                %error,
+                crashes = consecutive_crashes,
+                "supervised process keeps crashing, giving up"
+            );
+            process.update(|state| {
+                state.status = ProcessStatus::Failed;
+                state.last_error = Some(error);
+            });
+            return;
+        }
+
+        tracing::warn!(
+            kind = ?spec.kind,
+            name = %spec.name,
+            agent_id = ?spec.agent_id,
+            %error,
+            "supervised process crashed, restarting in {backoff:?}"
+        );
+        let next_restart_at = chrono::Duration::from_std(backoff)
+            .ok()
+            .map(|backoff| Utc::now() + backoff);
+        process.update(|state| {
+            state.status = ProcessStatus::Restarting;
+            state.last_error = Some(error);
+            state.next_restart_at = next_restart_at;
+        });
+
+        tokio::select! {
+            _ = tokio::time::sleep(backoff) => {}
+            _ = process.stop.notified() => return,
+        }
+
+        process.update(|state| state.restart_count += 1);
+        backoff = (backoff * 2).min(policy.max_backoff);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn fast_supervisor() -> Supervisor {
+        Supervisor {
+            processes: Registry::default(),
+            policy: RestartPolicy {
+                initial_backoff: Duration::from_millis(5),
+                max_backoff: Duration::from_millis(20),
+                stable_after: Duration::from_secs(60),
+                max_consecutive_crashes: 3,
+            },
+        }
+    }
+
+    async fn wait_for(mut condition: impl FnMut() -> bool) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !condition() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("condition not reached in time");
+    }
+
+    #[tokio::test]
+    async fn crashed_process_is_restarted() {
+        let supervisor = fast_supervisor();
+        let runs = Arc::new(AtomicU32::new(0));
+        let counter = runs.clone();
+        supervisor.supervise(ProcessSpec::new(ProcessKind::Cortex, "flaky"), move || {
+            let run = counter.fetch_add(1, Ordering::SeqCst);
+            tokio::spawn(async move {
+                if run < 2 {
+                    panic!("crash {run}");
+                }
+                std::future::pending::<()>().await;
+            })
+        });
+
+        wait_for(|| runs.load(Ordering::SeqCst) == 3).await;
+        wait_for(|| supervisor.snapshots()[0].status == ProcessStatus::Running).await;
+        let snapshot = &supervisor.snapshots()[0];
+        assert_eq!(snapshot.restart_count, 2);
+        assert_eq!(snapshot.last_error.as_deref(), Some("panicked: crash 1"));
+    }
+
+    #[tokio::test]
+    async fn process_that_keeps_crashing_is_marked_failed() {
+        let supervisor = fast_supervisor();
+        supervisor.supervise(
+            ProcessSpec::new(ProcessKind::Adapter, "broken").agent("main"),
+            || tokio::spawn(async {}),
+        );
+
+        wait_for(|| supervisor.snapshots()[0].status == ProcessStatus::Failed).await;
+        let snapshot = &supervisor.snapshots()[0];
+        assert_eq!(snapshot.restart_count, 3);
+        assert_eq!(snapshot.last_error.as_deref(), Some("exited unexpectedly"));
+    }
+
+    #[tokio::test]
+    async fn on_panic_process_that_finishes_is_removed() {
+        let supervisor = fast_supervisor();
+        supervisor.supervise(
+            ProcessSpec::new(ProcessKind::Scheduler, "cron:once").restart(Restart::OnPanic),
+            || tokio::spawn(async {}),
+        );
+
+        wait_for(|| supervisor.snapshots().is_empty()).await;
+    }
+
+    #[tokio::test]
+    async fn stopping_an_agent_aborts_its_processes() {
+        let supervisor = fast_supervisor();
+        let spawn_pending = || tokio::spawn(std::future::pending::<()>());
+        let handle = supervisor.supervise(
+            ProcessSpec::new(ProcessKind::Cortex, "cortex").agent("main"),
+            spawn_pending,
+        );
+        supervisor.supervise(
+            ProcessSpec::new(ProcessKind::Cortex, "cortex").agent("other"),
+            spawn_pending,
+        );
+
+        supervisor.stop_agent("main");
+        tokio::time::timeout(Duration::from_secs(5), handle.monitor)
+            .await
+            .expect("monitor did not stop")
+            .unwrap();
+
+        let remaining = supervisor.snapshots();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].agent_id.as_deref(), Some("other"));
+    }
+
+    #[tokio::test]
+    async fn supervising_the_same_process_replaces_it() {
+        let supervisor = fast_supervisor();
+        let spec = ProcessSpec::new(ProcessKind::Watcher, "config");
+        let first =
+            supervisor.supervise(spec.clone(), || tokio::spawn(std::future::pending::<()>()));
+        supervisor.supervise(spec, || tokio::spawn(std::future::pending::<()>()));
+
+        tokio::time::timeout(Duration::from_secs(5), first.monitor)
+            .await
+            .expect("replaced monitor did not stop")
+            .unwrap();
+        assert_eq!(supervisor.snapshots().len(), 1);
+    }
+}
@@ -0,0 +1,19 @@
+//! Coordinator-led agent teams.
+//!
+//! Teams are defined in config via `[[teams]]` sections. A team's
+//! coordinator fans requests out to its members with the `team_dispatch`
+//! tool; each dispatch is tracked as a run in the coordinator's database.
+
+pub mod store;
+
+pub use store::{TeamRun, TeamRunMember, TeamRunStatus, TeamRunStore};
+
+use crate::config::TeamDef;
+
+/// Teams coordinated by `agent_id`.
+pub fn teams_coordinated_by<'a>(teams: &'a [TeamDef], agent_id: &str) -> Vec<&'a TeamDef> {
+    teams
+        .iter()
+        .filter(|team| team.coordinator == agent_id)
+        .collect()
+}
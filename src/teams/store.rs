@@ -0,0 +1,248 @@
+//! Team run storage (SQLite).
+
+use crate::tools::delegate_to_agent::DelegationStatus;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Row as _, SqlitePool};
+
+/// Overall state of a team run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TeamRunStatus {
+    /// At least one member hasn't finished.
+    Running,
+    /// Every member completed its part.
+    Completed,
+    /// Some members completed and some failed.
+    Partial,
+    /// No member completed.
+    Failed,
+}
+
+impl TeamRunStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Partial => "partial",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "running" => Some(Self::Running),
+            "completed" => Some(Self::Completed),
+            "partial" => Some(Self::Partial),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+
+    /// Status of a run with these members.
+    pub fn from_members(members: &[TeamRunMember]) -> Self {
+        if members.iter().any(|member| !member.status.is_finished()) {
+            return Self::Running;
+        }
+        let completed = members
+            .iter()
+            .filter(|member| member.status == DelegationStatus::Completed)
+            .count();
+        if completed == members.len() {
+            Self::Completed
+        } else if completed == 0 {
+            Self::Failed
+        } else {
+            Self::Partial
+        }
+    }
+}
+
+/// One member's share of a team run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamRunMember {
+    pub agent_id: String,
+    pub task: String,
+    /// Task number on the member agent. `None` if the task couldn't be created.
+    pub task_number: Option<i64>,
+    pub status: DelegationStatus,
+    /// The member's result, or why it failed.
+    pub result: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TeamRun {
+    pub id: String,
+    pub team_id: String,
+    pub worker_id: Option<String>,
+    pub request: String,
+    pub status: TeamRunStatus,
+    pub members: Vec<TeamRunMember>,
+    /// The coordinator worker's final result, once it has one.
+    pub synthesis: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Persists team runs in the coordinator's database.
+#[derive(Debug, Clone)]
+pub struct TeamRunStore {
+    pool: SqlitePool,
+}
+
+const TEAM_RUN_COLUMNS: &str = "team_runs.id, team_runs.team_id, team_runs.worker_id, \
+     team_runs.request, team_runs.status, team_runs.members, team_runs.started_at, \
+     team_runs.completed_at, worker_runs.result AS synthesis";
+
+impl TeamRunStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        team_id: &str,
+        worker_id: Option<&str>,
+        request: &str,
+        members: &[TeamRunMember],
+    ) -> crate::error::Result<TeamRun> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let status = TeamRunStatus::from_members(members);
+        sqlx::query(
+            "INSERT INTO team_runs (id, team_id, worker_id, request, status, members, completed_at) \
+             VALUES (?, ?, ?, ?, ?, ?, CASE WHEN ? THEN CURRENT_TIMESTAMP END)",
+        )
+        .bind(&id)
+        .bind(team_id)
+        .bind(worker_id)
+        .bind(request)
+        .bind(status.as_str())
+        .bind(serde_json::to_string(members).unwrap_or_else(|_| "[]".into()))
+        .bind(status != TeamRunStatus::Running)
+        .execute(&self.pool)
+        .await?;
+
+        self.get(&id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("created team run {id} not found").into())
+    }
+
+    pub async fn get(&self, id: &str) -> crate::error::Result<Option<TeamRun>> {
+        let row = sqlx::query(&format!(
+            "SELECT {TEAM_RUN_COLUMNS} FROM team_runs \
+             LEFT JOIN worker_runs ON worker_runs.id = team_runs.worker_id \
+             WHERE team_runs.id = ?"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.as_ref().and_then(team_run_from_row))
+    }
+
+    /// Runs, newest first, optionally for one team.
+    pub async fn list(
+        &self,
+        team_id: Option<&str>,
+        limit: i64,
+    ) -> crate::error::Result<Vec<TeamRun>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {TEAM_RUN_COLUMNS} FROM team_runs \
+             LEFT JOIN worker_runs ON worker_runs.id = team_runs.worker_id \
+             WHERE ?1 IS NULL OR team_runs.team_id = ?1 \
+             ORDER BY team_runs.started_at DESC LIMIT ?2"
+        ))
+        .bind(team_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.iter().filter_map(team_run_from_row).collect())
+    }
+
+    /// Record members' progress. The run is closed once every member has
+    /// finished. Returns the run's new status.
+    pub async fn update_members(
+        &self,
+        id: &str,
+        members: &[TeamRunMember],
+    ) -> crate::error::Result<TeamRunStatus> {
+        let status = TeamRunStatus::from_members(members);
+        sqlx::query(
+            "UPDATE team_runs SET members = ?, status = ?, \
+             completed_at = CASE WHEN ? THEN COALESCE(completed_at, CURRENT_TIMESTAMP) END \
+             WHERE id = ?",
+        )
+        .bind(serde_json::to_string(members).unwrap_or_else(|_| "[]".into()))
+        .bind(status.as_str())
+        .bind(status != TeamRunStatus::Running)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(status)
+    }
+}
+
+fn team_run_from_row(row: &sqlx::sqlite::SqliteRow) -> Option<TeamRun> {
+    Some(TeamRun {
+        id: row.get("id"),
+        team_id: row.get("team_id"),
+        worker_id: row.get("worker_id"),
+        request: row.get("request"),
+        status: TeamRunStatus::parse(row.get("status"))?,
+        members: serde_json::from_str(&row.get::<String, _>("members")).unwrap_or_default(),
+        synthesis: row.get("synthesis"),
+        started_at: row.get("started_at"),
+        completed_at: row.get("completed_at"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(agent_id: &str, status: DelegationStatus) -> TeamRunMember {
+        TeamRunMember {
+            agent_id: agent_id.into(),
+            task: "Look into it.".into(),
+            task_number: Some(1),
+            status,
+            result: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn run_closes_once_every_member_finishes() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("failed to create sqlite memory pool");
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("failed to run migrations");
+        let store = TeamRunStore::new(pool);
+
+        let mut members = vec![
+            member("research", DelegationStatus::Queued),
+            member("writer", DelegationStatus::InProgress),
+        ];
+        let run = store
+            .create("launch", None, "Plan the launch.", &members)
+            .await
+            .unwrap();
+        assert_eq!(run.status, TeamRunStatus::Running);
+        assert!(run.completed_at.is_none());
+
+        members[0].status = DelegationStatus::Completed;
+        members[1].status = DelegationStatus::Failed;
+        let status = store.update_members(&run.id, &members).await.unwrap();
+        assert_eq!(status, TeamRunStatus::Partial);
+
+        let runs = store.list(Some("launch"), 10).await.unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].members[1].status, DelegationStatus::Failed);
+        assert!(runs[0].completed_at.is_some());
+        assert!(store.list(Some("other"), 10).await.unwrap().is_empty());
+    }
+}
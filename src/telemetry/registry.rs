@@ -184,6 +184,19 @@ pub struct Metrics {
     /// Ingestion files processed.
     /// Labels: agent_id, result.
     pub ingestion_files_processed_total: IntCounterVec,
+
+    // -- Vector maintenance --
+    /// LanceDB optimize passes.
+    /// Labels: agent_id, table, result.
+    pub vector_optimize_runs_total: IntCounterVec,
+
+    /// Old LanceDB table versions removed by pruning.
+    /// Labels: agent_id, table.
+    pub vector_versions_pruned_total: IntCounterVec,
+
+    /// Bytes freed by LanceDB version pruning.
+    /// Labels: agent_id, table.
+    pub vector_bytes_reclaimed_total: IntCounterVec,
 }
 
 impl Metrics {
@@ -525,6 +538,34 @@ impl Metrics {
         )
         .expect("hardcoded metric descriptor");
 
+        // -- Vector maintenance --
+        let vector_optimize_runs_total = IntCounterVec::new(
+            Opts::new(
+                "spacebot_vector_optimize_runs_total",
+                "LanceDB compaction and prune passes",
+            ),
+            &["agent_id", "table", "result"],
+        )
+        .expect("hardcoded metric descriptor");
+
+        let vector_versions_pruned_total = IntCounterVec::new(
+            Opts::new(
+                "spacebot_vector_versions_pruned_total",
+                "Old LanceDB table versions removed by pruning",
+            ),
+            &["agent_id", "table"],
+        )
+        .expect("hardcoded metric descriptor");
+
+        let vector_bytes_reclaimed_total = IntCounterVec::new(
+            Opts::new(
+                "spacebot_vector_bytes_reclaimed_total",
+                "Bytes freed by LanceDB version pruning",
+            ),
+            &["agent_id", "table"],
+        )
+        .expect("hardcoded metric descriptor");
+
         // === Register all metrics ===
 
         // Existing (upgraded)
@@ -659,6 +700,17 @@ impl Metrics {
             .register(Box::new(ingestion_files_processed_total.clone()))
             .expect("hardcoded metric");
 
+        // New: Vector maintenance
+        registry
+            .register(Box::new(vector_optimize_runs_total.clone()))
+            .expect("hardcoded metric");
+        registry
+            .register(Box::new(vector_versions_pruned_total.clone()))
+            .expect("hardcoded metric");
+        registry
+            .register(Box::new(vector_bytes_reclaimed_total.clone()))
+            .expect("hardcoded metric");
+
         Self {
             registry,
             llm_requests_total,
@@ -700,6 +752,9 @@ impl Metrics {
             worker_cost_dollars,
             cron_executions_total,
             ingestion_files_processed_total,
+            vector_optimize_runs_total,
+            vector_versions_pruned_total,
+            vector_bytes_reclaimed_total,
         }
     }
 
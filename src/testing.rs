@@ -0,0 +1,31 @@
+//! In-memory harness for exercising the agent loop without network access.
+//!
+//! [`TestAgent`] wires a real agent into a temp directory: a fresh SQLite
+//! database with migrations applied, LanceDB tables, offline hashed
+//! embeddings, and every process type routed to a [`ScriptedLlm`] — a local
+//! OpenAI-compatible server that answers from a script. [`TestAgent::channel`]
+//! starts a real channel event loop whose outbound responses land in a
+//! [`MemoryAdapter`], so a test can drive full turns, branch and worker
+//! spawns, and tool calls deterministically.
+//!
+//! ```ignore
+//! let agent = TestAgent::start().await?;
+//! agent.llm.push(ScriptedResponse::tool_call(
+//!     "reply",
+//!     serde_json::json!({ "content": "hi there" }),
+//! ));
+//! let channel = agent.channel("general");
+//! channel.send("alice", "hello").await?;
+//! assert_eq!(channel.next_text(Duration::from_secs(5)).await.as_deref(), Some("hi there"));
+//! ```
+
+mod adapter;
+mod agent;
+mod llm;
+
+pub use adapter::{Delivered, MemoryAdapter};
+pub use agent::{TEST_ADAPTER, TestAgent, TestAgentBuilder, TestChannel};
+pub use llm::{
+    RecordedRequest, SCRIPTED_MODEL, SCRIPTED_PROVIDER, ScriptedLlm, ScriptedResponse,
+    ScriptedToolCall,
+};
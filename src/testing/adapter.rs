@@ -0,0 +1,239 @@
+//! In-memory messaging adapter.
+
+use crate::error::Result;
+use crate::messaging::traits::{HistoryMessage, InboundStream, Messaging};
+use crate::{InboundMessage, MessageContent, OutboundResponse, StatusUpdate};
+
+use tokio::sync::{Notify, mpsc};
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Something the adapter was asked to deliver.
+#[derive(Debug, Clone)]
+pub enum Delivered {
+    Response {
+        conversation_id: String,
+        response: OutboundResponse,
+    },
+    Status {
+        conversation_id: String,
+        status: StatusUpdate,
+    },
+    Broadcast {
+        target: String,
+        response: OutboundResponse,
+    },
+}
+
+impl Delivered {
+    /// The text of a delivered response, for the variants that carry one.
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Self::Response { response, .. } | Self::Broadcast { response, .. } => match response {
+                OutboundResponse::Text(text)
+                | OutboundResponse::ThreadReply { text, .. }
+                | OutboundResponse::Ephemeral { text, .. }
+                | OutboundResponse::RichMessage { text, .. } => Some(text),
+                _ => None,
+            },
+            Self::Status { .. } => None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Outbox {
+    delivered: Vec<Delivered>,
+    history: HashMap<String, Vec<HistoryMessage>>,
+    /// Responses and broadcasts still to reject with a transient error.
+    failures: usize,
+}
+
+/// A [`Messaging`] adapter that keeps everything in memory.
+///
+/// Tests push inbound messages with [`MemoryAdapter::send`] and inspect
+/// what the agent delivered with [`MemoryAdapter::delivered`] or wait for it
+/// with [`MemoryAdapter::next_text`].
+pub struct MemoryAdapter {
+    name: String,
+    inbound_tx: mpsc::UnboundedSender<InboundMessage>,
+    inbound_rx: Mutex<Option<mpsc::UnboundedReceiver<InboundMessage>>>,
+    outbox: Arc<Mutex<Outbox>>,
+    notify: Arc<Notify>,
+}
+
+impl MemoryAdapter {
+    pub fn new(name: impl Into<String>) -> Self {
+        let (inbound_tx, inbound_rx) = mpsc::unbounded_channel();
+        Self {
+            name: name.into(),
+            inbound_tx,
+            inbound_rx: Mutex::new(Some(inbound_rx)),
+            outbox: Arc::new(Mutex::new(Outbox::default())),
+            notify: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Build a text message from `sender` in `conversation_id` as this
+    /// adapter would receive it.
+    pub fn message(&self, conversation_id: &str, sender: &str, text: &str) -> InboundMessage {
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            "display_name".into(),
+            serde_json::Value::String(sender.to_string()),
+        );
+        InboundMessage {
+            id: uuid::Uuid::new_v4().to_string(),
+            source: self.name.clone(),
+            adapter: Some(self.name.clone()),
+            conversation_id: conversation_id.to_string(),
+            sender_id: sender.to_string(),
+            agent_id: None,
+            content: MessageContent::Text(text.to_string()),
+            timestamp: chrono::Utc::now(),
+            metadata,
+            formatted_author: Some(sender.to_string()),
+        }
+    }
+
+    /// Deliver an inbound message to whoever started the adapter.
+    pub fn send(&self, message: InboundMessage) {
+        // The receiver only goes away once the stream is dropped, at which
+        // point nobody is listening anyway.
+        let _ = self.inbound_tx.send(message);
+    }
+
+    /// Seed platform history returned by `fetch_history` for a conversation.
+    pub fn set_history(&self, conversation_id: &str, history: Vec<HistoryMessage>) {
+        self.lock()
+            .history
+            .insert(conversation_id.to_string(), history);
+    }
+
+    /// Reject the next `count` responses and broadcasts with a dropped
+    /// connection, which the outbox treats as transient.
+    pub fn fail_next(&self, count: usize) {
+        self.lock().failures = count;
+    }
+
+    /// Everything delivered so far.
+    pub fn delivered(&self) -> Vec<Delivered> {
+        self.lock().delivered.clone()
+    }
+
+    /// Texts of delivered responses, in order.
+    pub fn texts(&self) -> Vec<String> {
+        self.lock()
+            .delivered
+            .iter()
+            .filter_map(|delivered| delivered.text().map(str::to_string))
+            .collect()
+    }
+
+    /// Wait until at least `count` text responses have been delivered and
+    /// return the `count`-th one.
+    pub async fn nth_text(&self, count: usize, timeout: Duration) -> Option<String> {
+        let wait = async {
+            loop {
+                let notified = self.notify.notified();
+                if let Some(text) = self.texts().get(count.saturating_sub(1)) {
+                    return text.clone();
+                }
+                notified.await;
+            }
+        };
+        tokio::time::timeout(timeout, wait).await.ok()
+    }
+
+    /// Wait for the next text response after the ones already delivered.
+    pub async fn next_text(&self, timeout: Duration) -> Option<String> {
+        let seen = self.texts().len();
+        self.nth_text(seen + 1, timeout).await
+    }
+
+    fn record(&self, delivered: Delivered) {
+        self.lock().delivered.push(delivered);
+        self.notify.notify_waiters();
+    }
+
+    /// Consume one injected failure, if any are left.
+    fn injected_failure(&self) -> Result<()> {
+        let mut outbox = self.lock();
+        if outbox.failures == 0 {
+            return Ok(());
+        }
+        outbox.failures -= 1;
+        Err(std::io::Error::from(std::io::ErrorKind::ConnectionReset).into())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Outbox> {
+        self.outbox
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Messaging for MemoryAdapter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn start(&self) -> Result<InboundStream> {
+        let receiver = self
+            .inbound_rx
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("memory adapter '{}' already started", self.name))?;
+        Ok(Box::pin(
+            tokio_stream::wrappers::UnboundedReceiverStream::new(receiver),
+        ))
+    }
+
+    async fn respond(&self, message: &InboundMessage, response: OutboundResponse) -> Result<()> {
+        self.injected_failure()?;
+        self.record(Delivered::Response {
+            conversation_id: message.conversation_id.clone(),
+            response,
+        });
+        Ok(())
+    }
+
+    async fn send_status(&self, message: &InboundMessage, status: StatusUpdate) -> Result<()> {
+        self.record(Delivered::Status {
+            conversation_id: message.conversation_id.clone(),
+            status,
+        });
+        Ok(())
+    }
+
+    async fn broadcast(&self, target: &str, response: OutboundResponse) -> Result<()> {
+        self.injected_failure()?;
+        self.record(Delivered::Broadcast {
+            target: target.to_string(),
+            response,
+        });
+        Ok(())
+    }
+
+    async fn fetch_history(
+        &self,
+        message: &InboundMessage,
+        limit: usize,
+    ) -> Result<Vec<HistoryMessage>> {
+        let history = self
+            .lock()
+            .history
+            .get(&message.conversation_id)
+            .cloned()
+            .unwrap_or_default();
+        let skip = history.len().saturating_sub(limit);
+        Ok(history.into_iter().skip(skip).collect())
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        Ok(())
+    }
+}
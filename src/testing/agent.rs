@@ -0,0 +1,290 @@
+//! Temp-dir agent wiring and a driveable channel.
+
+use crate::agent::channel::Channel;
+use crate::config::{Config, ResolvedAgentConfig};
+use crate::messaging::Messaging as _;
+use crate::testing::adapter::MemoryAdapter;
+use crate::testing::llm::{SCRIPTED_MODEL, SCRIPTED_PROVIDER, ScriptedLlm};
+use crate::{AgentDeps, ChannelId, ProcessEvent, RoutedResponse};
+
+use anyhow::Context as _;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Adapter name used for channels opened through [`TestAgent::channel`].
+pub const TEST_ADAPTER: &str = "test";
+
+/// Builder for [`TestAgent`].
+pub struct TestAgentBuilder {
+    agent_id: String,
+    extra_toml: String,
+}
+
+impl TestAgentBuilder {
+    pub fn agent_id(mut self, agent_id: impl Into<String>) -> Self {
+        self.agent_id = agent_id.into();
+        self
+    }
+
+    /// Extra TOML appended to the generated config.toml, e.g. a
+    /// `[defaults.memory_persistence]` table. The scripted provider and
+    /// routing are already configured.
+    pub fn config_toml(mut self, toml: impl AsRef<str>) -> Self {
+        self.extra_toml.push_str(toml.as_ref());
+        self.extra_toml.push('\n');
+        self
+    }
+
+    pub async fn build(self) -> anyhow::Result<TestAgent> {
+        let llm = ScriptedLlm::start().await?;
+        let dir = tempfile::tempdir().context("failed to create temp instance dir")?;
+
+        let config_path = dir.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            render_config(&self.agent_id, llm.base_url(), &self.extra_toml),
+        )
+        .context("failed to write test config.toml")?;
+        let config = Config::load_from_path(&config_path)?;
+        let agent_config = config
+            .resolve_agents()
+            .into_iter()
+            .find(|agent| agent.id == self.agent_id)
+            .context("test agent missing from resolved config")?;
+
+        for directory in [
+            agent_config.workspace.clone(),
+            agent_config.data_dir.clone(),
+            agent_config.archives_dir.clone(),
+            agent_config.ingest_dir(),
+            agent_config.logs_dir(),
+            agent_config.saved_dir(),
+            agent_config.screenshot_dir(),
+        ] {
+            std::fs::create_dir_all(&directory)
+                .with_context(|| format!("failed to create {}", directory.display()))?;
+        }
+
+        let db = crate::db::Db::connect(&agent_config.data_dir, &agent_config.storage).await?;
+        let llm_manager = Arc::new(crate::llm::LlmManager::new(config.llm.clone()).await?);
+
+        let memory_store =
+            crate::memory::MemoryStore::with_agent_id(db.sqlite.clone(), &agent_config.id);
+        let embedding_table = crate::memory::EmbeddingTable::open_or_create(&db.lance).await?;
+        let mut memory_search = crate::memory::MemorySearch::new(
+            memory_store,
+            embedding_table,
+            Arc::new(crate::memory::EmbeddingModel::hashed()),
+        );
+        if let Ok(episodes) = crate::memory::EpisodeTable::open_or_create(&db.lance).await {
+            memory_search = memory_search.with_episodes(episodes);
+        }
+
+        crate::identity::scaffold_identity_files(&agent_config.identity_dir).await?;
+        let identity = crate::identity::Identity::load(&agent_config.identity_dir).await;
+        let skills =
+            crate::skills::SkillSet::load(&config.skills_dir(), &agent_config.skills_dir()).await;
+        let runtime_config = Arc::new(crate::config::RuntimeConfig::new(
+            &config.instance_dir,
+            &agent_config,
+            &config.defaults,
+            crate::prompts::PromptEngine::new("en")?,
+            identity,
+            skills,
+        ));
+        let settings_store = Arc::new(crate::settings::SettingsStore::new(
+            &agent_config.data_dir.join("settings.redb"),
+        )?);
+        runtime_config.set_settings(settings_store, None);
+
+        let sandbox = Arc::new(
+            crate::sandbox::Sandbox::new(
+                runtime_config.sandbox.clone(),
+                agent_config.workspace.clone(),
+                &config.instance_dir,
+                agent_config.data_dir.clone(),
+            )
+            .await,
+        );
+
+        let (event_tx, memory_event_tx) = crate::create_process_event_buses();
+        let deps = AgentDeps {
+            agent_id: Arc::from(agent_config.id.as_str()),
+            memory_search: Arc::new(memory_search),
+            llm_manager,
+            mcp_manager: Arc::new(crate::mcp::McpManager::new(agent_config.mcp.clone())),
+            task_store: Arc::new(crate::tasks::TaskStore::new(db.sqlite.clone())),
+            project_store: Arc::new(crate::projects::ProjectStore::new(db.sqlite.clone())),
+            cron_tool: None,
+            runtime_config,
+            event_tx,
+            memory_event_tx,
+            sqlite_pool: db.sqlite.clone(),
+            messaging_manager: None,
+            sandbox,
+            links: Arc::new(arc_swap::ArcSwap::from_pointee(Vec::new())),
+            agent_names: Arc::new(std::collections::HashMap::new()),
+            humans: Arc::new(arc_swap::ArcSwap::from_pointee(Vec::new())),
+            teams: Arc::new(arc_swap::ArcSwap::from_pointee(Vec::new())),
+            task_store_registry: Arc::new(arc_swap::ArcSwap::from_pointee(
+                std::collections::HashMap::new(),
+            )),
+            process_control_registry: Arc::new(
+                crate::agent::process_control::ProcessControlRegistry::new(),
+            ),
+            branch_cache: Arc::new(crate::agent::branch_cache::BranchResultCache::new()),
+            injection_tx: mpsc::channel(16).0,
+        };
+
+        Ok(TestAgent {
+            deps,
+            config: agent_config,
+            llm,
+            _db: db,
+            _dir: dir,
+        })
+    }
+}
+
+/// A fully wired agent in a temp directory, backed by a [`ScriptedLlm`],
+/// a fresh SQLite database, LanceDB tables, and offline hashed embeddings.
+///
+/// Everything is torn down when the value is dropped.
+pub struct TestAgent {
+    pub deps: AgentDeps,
+    pub config: ResolvedAgentConfig,
+    pub llm: ScriptedLlm,
+    _db: crate::db::Db,
+    _dir: tempfile::TempDir,
+}
+
+impl TestAgent {
+    pub fn builder() -> TestAgentBuilder {
+        TestAgentBuilder {
+            agent_id: "test".to_string(),
+            extra_toml: String::new(),
+        }
+    }
+
+    /// Build an agent with the default configuration.
+    pub async fn start() -> anyhow::Result<Self> {
+        Self::builder().build().await
+    }
+
+    /// Subscribe to the agent's process event bus.
+    pub fn events(&self) -> broadcast::Receiver<ProcessEvent> {
+        self.deps.event_tx.subscribe()
+    }
+
+    /// Open a channel for `conversation_id` and start its event loop.
+    pub fn channel(&self, conversation_id: &str) -> TestChannel {
+        let channel_id: ChannelId = Arc::from(format!("{TEST_ADAPTER}:{conversation_id}").as_str());
+        let (response_tx, mut response_rx) = mpsc::channel::<RoutedResponse>(64);
+        let (channel, message_tx) = Channel::new(
+            channel_id.clone(),
+            self.deps.clone(),
+            response_tx,
+            self.deps.event_tx.subscribe(),
+            self.config.screenshot_dir(),
+            self.config.logs_dir(),
+            None,
+            None,
+        );
+        let task = tokio::spawn(async move {
+            if let Err(error) = channel.run().await {
+                tracing::warn!(%error, "test channel exited with an error");
+            }
+        });
+
+        let adapter = Arc::new(MemoryAdapter::new(TEST_ADAPTER));
+        let outbound = adapter.clone();
+        let router = tokio::spawn(async move {
+            while let Some(routed) = response_rx.recv().await {
+                let outcome = outbound
+                    .respond_tracked(&routed.target, routed.response)
+                    .await
+                    .map_err(|error| error.to_string());
+                if let Some(receipt) = routed.receipt {
+                    receipt.complete(outcome);
+                }
+            }
+        });
+
+        TestChannel {
+            id: channel_id,
+            conversation_id: conversation_id.to_string(),
+            agent_id: self.deps.agent_id.clone(),
+            message_tx,
+            adapter,
+            tasks: [task, router],
+        }
+    }
+}
+
+/// A running channel driven by a test.
+pub struct TestChannel {
+    pub id: ChannelId,
+    conversation_id: String,
+    agent_id: crate::AgentId,
+    message_tx: mpsc::Sender<crate::InboundMessage>,
+    adapter: Arc<MemoryAdapter>,
+    tasks: [JoinHandle<()>; 2],
+}
+
+impl TestChannel {
+    /// Send a user message into the channel.
+    pub async fn send(&self, sender: &str, text: &str) -> anyhow::Result<()> {
+        let mut message = self.adapter.message(&self.conversation_id, sender, text);
+        message.agent_id = Some(self.agent_id.clone());
+        self.message_tx
+            .send(message)
+            .await
+            .context("test channel stopped")
+    }
+
+    /// Wait for the next text the channel delivers.
+    pub async fn next_text(&self, timeout: Duration) -> Option<String> {
+        self.adapter.next_text(timeout).await
+    }
+
+    /// The adapter that records everything this channel delivered.
+    pub fn adapter(&self) -> &MemoryAdapter {
+        &self.adapter
+    }
+}
+
+impl Drop for TestChannel {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+fn render_config(agent_id: &str, base_url: &str, extra_toml: &str) -> String {
+    format!(
+        r#"[llm]
+health_probe_interval_secs = 0
+
+[llm.provider.{SCRIPTED_PROVIDER}]
+api_type = "openai_chat_completions"
+base_url = "{base_url}"
+api_key = "scripted"
+
+[defaults.routing]
+channel = "{SCRIPTED_MODEL}"
+branch = "{SCRIPTED_MODEL}"
+worker = "{SCRIPTED_MODEL}"
+compactor = "{SCRIPTED_MODEL}"
+cortex = "{SCRIPTED_MODEL}"
+voice = "{SCRIPTED_MODEL}"
+
+[[agents]]
+id = "{agent_id}"
+
+{extra_toml}"#
+    )
+}
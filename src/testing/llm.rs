@@ -0,0 +1,336 @@
+//! Scripted OpenAI-compatible model server.
+
+use crate::config::{ApiType, ProviderConfig};
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Provider ID the harness registers the scripted server under.
+pub const SCRIPTED_PROVIDER: &str = "scripted";
+
+/// Model name routed to the scripted server for every process type.
+pub const SCRIPTED_MODEL: &str = "scripted/test-model";
+
+/// One canned model turn.
+#[derive(Debug, Clone)]
+pub enum ScriptedResponse {
+    /// Plain assistant text.
+    Text(String),
+    /// One or more tool calls in a single assistant turn.
+    ToolCalls(Vec<ScriptedToolCall>),
+    /// An HTTP error from the provider.
+    Error { status: u16, message: String },
+    /// Another response, served after a pause.
+    Delayed(Duration, Box<ScriptedResponse>),
+}
+
+#[derive(Debug, Clone)]
+pub struct ScriptedToolCall {
+    pub name: String,
+    pub arguments: serde_json::Value,
+}
+
+impl ScriptedResponse {
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text(text.into())
+    }
+
+    pub fn tool_call(name: impl Into<String>, arguments: serde_json::Value) -> Self {
+        Self::ToolCalls(vec![ScriptedToolCall {
+            name: name.into(),
+            arguments,
+        }])
+    }
+
+    pub fn error(status: u16, message: impl Into<String>) -> Self {
+        Self::Error {
+            status,
+            message: message.into(),
+        }
+    }
+
+    /// Hold this response for `delay` before answering, e.g. to land a new
+    /// message while a turn is still waiting on the model.
+    pub fn delayed(self, delay: Duration) -> Self {
+        Self::Delayed(delay, Box::new(self))
+    }
+}
+
+/// A chat completion request the server received.
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub body: serde_json::Value,
+}
+
+impl RecordedRequest {
+    /// The system prompt, if the request had one.
+    pub fn system_prompt(&self) -> Option<&str> {
+        self.messages()
+            .find(|message| message["role"] == "system")
+            .and_then(|message| message["content"].as_str())
+    }
+
+    /// Text of the most recent user message.
+    pub fn last_user_message(&self) -> Option<String> {
+        self.messages()
+            .filter(|message| message["role"] == "user")
+            .last()
+            .map(|message| content_text(&message["content"]))
+    }
+
+    /// Names of the tools offered to the model.
+    pub fn tool_names(&self) -> Vec<&str> {
+        self.body["tools"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|tool| tool["function"]["name"].as_str())
+            .collect()
+    }
+
+    /// Whether any message in the request contains `needle`.
+    pub fn contains(&self, needle: &str) -> bool {
+        self.messages()
+            .any(|message| content_text(&message["content"]).contains(needle))
+    }
+
+    fn last_user_message_contains(&self, needle: &str) -> bool {
+        self.last_user_message()
+            .is_some_and(|message| message.contains(needle))
+    }
+
+    fn messages(&self) -> impl Iterator<Item = &serde_json::Value> {
+        self.body["messages"].as_array().into_iter().flatten()
+    }
+}
+
+#[derive(Default)]
+struct Script {
+    /// Responses served only to requests whose latest user message contains
+    /// the paired needle.
+    rules: Vec<(String, ScriptedResponse)>,
+    /// Responses served in order to everything else.
+    queue: VecDeque<ScriptedResponse>,
+    requests: Vec<RecordedRequest>,
+}
+
+impl Script {
+    fn next_response(&mut self, request: &RecordedRequest) -> Option<ScriptedResponse> {
+        if let Some(index) = self
+            .rules
+            .iter()
+            .position(|(needle, _)| request.last_user_message_contains(needle))
+        {
+            return Some(self.rules.remove(index).1);
+        }
+        self.queue.pop_front()
+    }
+}
+
+/// A local HTTP server that speaks the OpenAI chat completions API and
+/// answers from a script.
+///
+/// Responses are consumed in order. Rules added with [`ScriptedLlm::when`]
+/// take priority and are matched against the request's latest user
+/// message, which keeps scripts deterministic when a channel and its
+/// branches or workers call the model concurrently: a branch or worker
+/// starts from its task description, while the channel's latest user
+/// message is still the inbound one.
+///
+/// An exhausted script answers with a 500, so a test that under-scripts
+/// fails loudly instead of hanging.
+pub struct ScriptedLlm {
+    base_url: String,
+    script: Arc<Mutex<Script>>,
+    server: JoinHandle<()>,
+}
+
+impl ScriptedLlm {
+    /// Bind to an ephemeral localhost port and start serving.
+    pub async fn start() -> anyhow::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let base_url = format!("http://{}", listener.local_addr()?);
+        let script = Arc::new(Mutex::new(Script::default()));
+
+        let app = axum::Router::new()
+            .route("/chat/completions", post(chat_completions))
+            .route("/v1/chat/completions", post(chat_completions))
+            .with_state(script.clone());
+        let server = tokio::spawn(async move {
+            if let Err(error) = axum::serve(listener, app).await {
+                tracing::warn!(%error, "scripted LLM server stopped");
+            }
+        });
+
+        Ok(Self {
+            base_url,
+            script,
+            server,
+        })
+    }
+
+    /// Queue a response for the next unmatched request.
+    pub fn push(&self, response: ScriptedResponse) -> &Self {
+        self.lock().queue.push_back(response);
+        self
+    }
+
+    /// Serve `response` once, to the first request whose latest user
+    /// message contains `needle`.
+    pub fn when(&self, needle: impl Into<String>, response: ScriptedResponse) -> &Self {
+        self.lock().rules.push((needle.into(), response));
+        self
+    }
+
+    /// Every request received so far, in arrival order.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.lock().requests.clone()
+    }
+
+    /// Responses queued or ruled but not yet served.
+    pub fn remaining(&self) -> usize {
+        let script = self.lock();
+        script.queue.len() + script.rules.len()
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Provider config pointing at this server.
+    pub fn provider_config(&self) -> ProviderConfig {
+        ProviderConfig {
+            api_type: ApiType::OpenAiChatCompletions,
+            base_url: self.base_url.clone(),
+            api_key: "scripted".to_string(),
+            name: Some("Scripted test model".to_string()),
+            use_bearer_auth: false,
+            extra_headers: Vec::new(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Script> {
+        self.script
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Drop for ScriptedLlm {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+async fn chat_completions(
+    State(script): State<Arc<Mutex<Script>>>,
+    Json(body): Json<serde_json::Value>,
+) -> Response {
+    let request = RecordedRequest { body };
+    let mut response = {
+        let mut script = script
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let response = script.next_response(&request);
+        script.requests.push(request);
+        response
+    };
+    while let Some(ScriptedResponse::Delayed(delay, inner)) = response {
+        tokio::time::sleep(delay).await;
+        response = Some(*inner);
+    }
+
+    match response {
+        Some(ScriptedResponse::Text(text)) => Json(completion_body(serde_json::json!({
+            "role": "assistant",
+            "content": text,
+        })))
+        .into_response(),
+        Some(ScriptedResponse::ToolCalls(calls)) => {
+            let tool_calls: Vec<_> = calls
+                .iter()
+                .enumerate()
+                .map(|(index, call)| {
+                    serde_json::json!({
+                        "id": format!("call_{}", uuid::Uuid::new_v4().simple()),
+                        "type": "function",
+                        "index": index,
+                        "function": {
+                            "name": call.name,
+                            "arguments": call.arguments.to_string(),
+                        },
+                    })
+                })
+                .collect();
+            Json(completion_body(serde_json::json!({
+                "role": "assistant",
+                "content": null,
+                "tool_calls": tool_calls,
+            })))
+            .into_response()
+        }
+        Some(ScriptedResponse::Error { status, message }) => error_response(
+            StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            &message,
+        ),
+        Some(ScriptedResponse::Delayed(..)) => unreachable!("delays are unwrapped above"),
+        None => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "scripted LLM has no response left for this request",
+        ),
+    }
+}
+
+fn completion_body(message: serde_json::Value) -> serde_json::Value {
+    let finish_reason = if message.get("tool_calls").is_some() {
+        "tool_calls"
+    } else {
+        "stop"
+    };
+    serde_json::json!({
+        "id": format!("chatcmpl-{}", uuid::Uuid::new_v4().simple()),
+        "object": "chat.completion",
+        "model": "test-model",
+        "choices": [{
+            "index": 0,
+            "message": message,
+            "finish_reason": finish_reason,
+        }],
+        "usage": {
+            "prompt_tokens": 0,
+            "completion_tokens": 0,
+            "total_tokens": 0,
+        },
+    })
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    (
+        status,
+        Json(serde_json::json!({
+            "error": { "message": message, "type": "scripted_error" },
+        })),
+    )
+        .into_response()
+}
+
+fn content_text(content: &serde_json::Value) -> String {
+    match content {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| part["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
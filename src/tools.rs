@@ -9,40 +9,58 @@
 //! - `reply`, `branch`, `spawn_worker`, `route`, `cancel`, `skip`, `react` — added
 //!   dynamically per conversation turn via `add_channel_tools()` /
 //!   `remove_channel_tools()` because they hold per-channel state.
+//! - `join_voice` + `leave_voice` — added alongside them when the adapter can
+//!   join voice channels.
 //! - No memory tools — the channel delegates memory work to branches.
 //!
 //! **Branch ToolServer** (one per branch, isolated):
 //! - `memory_save` + `memory_recall` + `memory_delete` + `channel_recall`
+//! - `conversation_search` for keyword search across all channel history
+//! - `episodic_search` for semantic recall of past conversations
 //! - `spacebot_docs` for embedded self-documentation lookup
 //! - `task_create` + `task_list` + `task_update`
-//! - `spawn_worker` is included for channel-originated branches only
+//! - `spawn_worker` and `profile_update` are included for channel-originated
+//!   branches only
 //!
 //! **Worker ToolServer** (one per worker, created at spawn time):
 //! - `shell`, `file_read`/`file_write`/`file_edit`/`file_list` — stateless, registered at creation
 //! - `task_update` — scoped to the worker's assigned task
 //! - `set_status` — per-worker instance, registered at creation
+//! - `delegate_to_agent` — added at spawn time when the agent has links
+//! - `team_dispatch` — added at spawn time when the agent coordinates a team
 //!
 //! **Cortex ToolServer** (one per agent):
 //! - `memory_save` — registered at startup
 //!
 //! **Cortex Chat ToolServer** (interactive admin chat):
 //! - branch + worker tool superset plus `spacebot_docs`, `config_inspect`, and `spawn_worker`
+//! - `skill_create` + `skill_update` + `skill_test` for writing workspace skills
+//! - `bulletin_pin` + `bulletin_unpin` for facts kept in every prompt
 
 pub mod attachment_recall;
 pub mod branch_tool;
 pub mod browser;
+pub mod bulletin_pin;
 pub mod cancel;
 pub mod channel_recall;
 pub mod config_inspect;
+pub mod conversation_search;
 pub mod cron;
+pub mod delegate_to_agent;
+pub mod delete_reply;
+pub mod edit_reply;
 pub mod email_search;
+pub mod episodic_search;
 pub mod file;
+pub mod git;
 pub mod install_skill;
 pub mod mcp;
 pub mod memory_delete;
 pub mod memory_persistence_complete;
 pub mod memory_recall;
 pub mod memory_save;
+pub mod message_worker;
+pub mod profile_update;
 pub mod project_manage;
 pub mod react;
 pub mod read_skill;
@@ -54,13 +72,17 @@ pub mod send_file;
 pub mod send_message_to_another_channel;
 pub mod set_status;
 pub mod shell;
+pub mod skill_authoring;
 pub mod skills_search;
 pub mod skip;
 pub mod spacebot_docs;
 pub mod spawn_worker;
+pub mod spawn_worker_batch;
 pub mod task_create;
 pub mod task_list;
 pub mod task_update;
+pub mod team_dispatch;
+pub mod voice;
 pub mod web_search;
 pub mod worker_inspect;
 
@@ -79,6 +101,10 @@ pub use browser::{
     BrowserError, BrowserOutput, SharedBrowserHandle, TabInfo, new_shared_browser_handle,
     register_browser_tools,
 };
+pub use bulletin_pin::{
+    BulletinPinArgs, BulletinPinError, BulletinPinOutput, BulletinPinTool, BulletinUnpinArgs,
+    BulletinUnpinTool,
+};
 pub use cancel::{CancelArgs, CancelError, CancelOutput, CancelTool};
 pub use channel_recall::{
     ChannelRecallArgs, ChannelRecallError, ChannelRecallOutput, ChannelRecallTool,
@@ -86,13 +112,31 @@ pub use channel_recall::{
 pub use config_inspect::{
     ConfigInspectArgs, ConfigInspectError, ConfigInspectOutput, ConfigInspectTool,
 };
+pub use conversation_search::{
+    ConversationSearchArgs, ConversationSearchError, ConversationSearchOutput,
+    ConversationSearchTool,
+};
 pub use cron::{CronArgs, CronError, CronOutput, CronTool};
+pub use delegate_to_agent::{
+    DelegateToAgentArgs, DelegateToAgentError, DelegateToAgentOutput, DelegateToAgentTool,
+    DelegationStatus,
+};
+pub use delete_reply::{DeleteReplyArgs, DeleteReplyError, DeleteReplyOutput, DeleteReplyTool};
+pub use edit_reply::{EditReplyArgs, EditReplyError, EditReplyOutput, EditReplyTool};
 pub use email_search::{EmailSearchArgs, EmailSearchError, EmailSearchOutput, EmailSearchTool};
+pub use episodic_search::{
+    EpisodicSearchArgs, EpisodicSearchError, EpisodicSearchOutput, EpisodicSearchTool,
+};
 pub use file::{
     FileEditArgs, FileEditTool, FileEntry, FileEntryOutput, FileError, FileListArgs, FileListTool,
     FileOutput, FileReadArgs, FileReadTool, FileType, FileWriteArgs, FileWriteTool,
     register_file_tools,
 };
+pub use git::{
+    GitBranchAction, GitBranchArgs, GitBranchTool, GitCommitArgs, GitCommitTool, GitContext,
+    GitDiffArgs, GitDiffTool, GitError, GitOutput, GitStatusArgs, GitStatusTool,
+    register_git_tools,
+};
 pub use install_skill::{
     InstallSkillArgs, InstallSkillError, InstallSkillOutput, InstallSkillTool,
 };
@@ -111,6 +155,12 @@ pub use memory_recall::{
 pub use memory_save::{
     AssociationInput, MemorySaveArgs, MemorySaveError, MemorySaveOutput, MemorySaveTool,
 };
+pub use message_worker::{
+    MessageWorkerArgs, MessageWorkerError, MessageWorkerOutput, MessageWorkerTool,
+};
+pub use profile_update::{
+    ProfileFactInput, ProfileUpdateArgs, ProfileUpdateError, ProfileUpdateOutput, ProfileUpdateTool,
+};
 pub use project_manage::{
     ProjectManageArgs, ProjectManageError, ProjectManageOutput, ProjectManageTool,
 };
@@ -128,6 +178,11 @@ pub use send_message_to_another_channel::{
 };
 pub use set_status::{SetStatusArgs, SetStatusError, SetStatusOutput, SetStatusTool, StatusKind};
 pub use shell::{EnvVar, ShellArgs, ShellError, ShellOutput, ShellResult, ShellTool};
+pub use skill_authoring::{
+    SkillCreateArgs, SkillCreateTool, SkillTestArgs, SkillTestOutput, SkillTestTool,
+    SkillToolError, SkillUpdateArgs, SkillUpdateTool, SkillWriteOutput,
+    register_skill_authoring_tools,
+};
 pub use skills_search::{
     SkillsSearchArgs, SkillsSearchError, SkillsSearchOutput, SkillsSearchTool,
 };
@@ -138,9 +193,18 @@ pub use spacebot_docs::{
 pub use spawn_worker::{
     DetachedSpawnWorkerTool, SpawnWorkerArgs, SpawnWorkerError, SpawnWorkerOutput, SpawnWorkerTool,
 };
+pub use spawn_worker_batch::{
+    SpawnWorkerBatchArgs, SpawnWorkerBatchError, SpawnWorkerBatchOutput, SpawnWorkerBatchTool,
+};
 pub use task_create::{TaskCreateArgs, TaskCreateError, TaskCreateOutput, TaskCreateTool};
 pub use task_list::{TaskListArgs, TaskListError, TaskListOutput, TaskListTool};
 pub use task_update::{TaskUpdateArgs, TaskUpdateError, TaskUpdateOutput, TaskUpdateTool};
+pub use team_dispatch::{
+    TeamAssignment, TeamDispatchArgs, TeamDispatchError, TeamDispatchOutput, TeamDispatchTool,
+};
+pub use voice::{
+    JoinVoiceArgs, JoinVoiceTool, LeaveVoiceArgs, LeaveVoiceTool, VoiceError, VoiceOutput,
+};
 pub use web_search::{SearchResult, WebSearchArgs, WebSearchError, WebSearchOutput, WebSearchTool};
 pub use worker_inspect::{
     WorkerInspectArgs, WorkerInspectError, WorkerInspectOutput, WorkerInspectTool,
@@ -172,7 +236,7 @@ pub use factory_update_identity::{
 
 use crate::agent::channel::ChannelState;
 use crate::config::{BrowserConfig, RuntimeConfig};
-use crate::memory::MemorySearch;
+use crate::memory::{MemoryScope, MemorySearch, MemoryVisibility};
 use crate::sandbox::Sandbox;
 use crate::tasks::TaskStore;
 use crate::{AgentId, ChannelId, ProcessEvent, RoutedSender, WorkerId};
@@ -361,6 +425,14 @@ pub async fn add_channel_tools(
     let conversation_id = conversation_id.into();
 
     if allow_direct_reply {
+        let pace = match state.channel_store.pace(&state.channel_id).await {
+            Ok(Some(pace)) => pace,
+            Ok(None) => state.deps.runtime_config.channel_config.load().pace,
+            Err(error) => {
+                tracing::warn!(%error, channel_id = %state.channel_id, "failed to load channel pace");
+                state.deps.runtime_config.channel_config.load().pace
+            }
+        };
         let agent_display_name = state
             .deps
             .agent_names
@@ -375,11 +447,42 @@ pub async fn add_channel_tools(
                 state.channel_id.clone(),
                 replied_flag.clone(),
                 agent_display_name,
+                vec![
+                    state.deps.runtime_config.workspace_dir.clone(),
+                    state
+                        .deps
+                        .runtime_config
+                        .browser_config
+                        .load()
+                        .screenshot_dir
+                        .clone()
+                        .unwrap_or_else(|| state.deps.runtime_config.data_dir.join("screenshots")),
+                ],
+                pace,
+            ))
+            .await?;
+        handle
+            .add_tool(EditReplyTool::new(
+                response_tx.clone(),
+                conversation_id.clone(),
+                state.conversation_logger.clone(),
+                state.channel_id.clone(),
+            ))
+            .await?;
+        handle
+            .add_tool(DeleteReplyTool::new(
+                response_tx.clone(),
+                conversation_id.clone(),
+                state.conversation_logger.clone(),
+                state.channel_id.clone(),
             ))
             .await?;
     }
     handle.add_tool(BranchTool::new(state.clone())).await?;
     handle.add_tool(SpawnWorkerTool::new(state.clone())).await?;
+    handle
+        .add_tool(SpawnWorkerBatchTool::new(state.clone()))
+        .await?;
     handle.add_tool(RouteTool::new(state.clone())).await?;
     if let Some(messaging_manager) = &state.deps.messaging_manager {
         let send_message_display_name = state
@@ -390,6 +493,8 @@ pub async fn add_channel_tools(
             .unwrap_or_else(|| state.deps.agent_id.to_string());
         handle
             .add_tool(SendMessageTool::new(
+                state.deps.clone(),
+                state.channel_id.clone(),
                 messaging_manager.clone(),
                 state.channel_store.clone(),
                 state.conversation_logger.clone(),
@@ -397,6 +502,24 @@ pub async fn add_channel_tools(
                 current_adapter.clone(),
             ))
             .await?;
+        if let Some(adapter) = &current_adapter
+            && messaging_manager.supports_voice(adapter).await
+        {
+            handle
+                .add_tool(JoinVoiceTool::new(
+                    messaging_manager.clone(),
+                    adapter.clone(),
+                    conversation_id.clone(),
+                ))
+                .await?;
+            handle
+                .add_tool(LeaveVoiceTool::new(
+                    messaging_manager.clone(),
+                    adapter.clone(),
+                    conversation_id.clone(),
+                ))
+                .await?;
+        }
     }
     handle
         .add_tool(SendFileTool::new(
@@ -426,15 +549,26 @@ pub async fn add_channel_tools(
             ))
             .await?;
     }
+    // Jobs created here run on the channel's clock.
+    let channel_timezone = match state.channel_store.timezone(&state.channel_id).await {
+        Ok(timezone) => timezone.resolved().map(str::to_string),
+        Err(error) => {
+            tracing::warn!(%error, channel_id = %state.channel_id, "failed to load channel timezone");
+            None
+        }
+    };
     handle.add_tool(CancelTool::new(state)).await?;
     handle
         .add_tool(SkipTool::new(skip_flag.clone(), response_tx.clone()))
         .await?;
     handle.add_tool(ReactTool::new(response_tx.clone())).await?;
     if let Some(cron_tool) = cron_tool {
-        let cron_tool = cron_tool.with_default_delivery_target(
-            default_delivery_target_for_conversation(&conversation_id, slack_thread_ts),
-        );
+        let cron_tool = cron_tool
+            .with_default_delivery_target(default_delivery_target_for_conversation(
+                &conversation_id,
+                slack_thread_ts,
+            ))
+            .with_default_timezone(channel_timezone);
         handle.add_tool(cron_tool).await?;
     }
     if let Some(mut agent_msg) = send_agent_message_tool {
@@ -479,21 +613,26 @@ pub async fn remove_channel_tools(
 ) -> Result<(), rig::tool::server::ToolServerError> {
     if allow_direct_reply {
         handle.remove_tool(ReplyTool::NAME).await?;
+        handle.remove_tool(EditReplyTool::NAME).await?;
+        handle.remove_tool(DeleteReplyTool::NAME).await?;
     }
     handle.remove_tool(BranchTool::NAME).await?;
     handle.remove_tool(SpawnWorkerTool::NAME).await?;
+    handle.remove_tool(SpawnWorkerBatchTool::NAME).await?;
     handle.remove_tool(RouteTool::NAME).await?;
     handle.remove_tool(CancelTool::NAME).await?;
     handle.remove_tool(SkipTool::NAME).await?;
     handle.remove_tool(SendFileTool::NAME).await?;
     handle.remove_tool(ReactTool::NAME).await?;
     handle.remove_tool(ProjectManageTool::NAME).await?;
-    // Cron, send_message, send_agent_message, and attachment_recall removal is
-    // best-effort since not all channels have them
+    // Cron, send_message, send_agent_message, attachment_recall, and voice
+    // removal is best-effort since not all channels have them
     let _ = handle.remove_tool(CronTool::NAME).await;
     let _ = handle.remove_tool(SendMessageTool::NAME).await;
     let _ = handle.remove_tool(SendAgentMessageTool::NAME).await;
     let _ = handle.remove_tool(AttachmentRecallTool::NAME).await;
+    let _ = handle.remove_tool(JoinVoiceTool::NAME).await;
+    let _ = handle.remove_tool(LeaveVoiceTool::NAME).await;
     Ok(())
 }
 
@@ -510,6 +649,10 @@ fn memory_save_with_events(
 /// Each branch gets its own isolated ToolServer so `memory_recall` is never
 /// visible to the channel. Includes memory tools, task-board tools, and
 /// `spacebot_docs` for on-demand self-documentation lookup.
+///
+/// For channel-originated branches, `memory_scope` is the channel's resolved
+/// scope policy: it decides which memories the memory tools can read and
+/// whether saves are scoped to the channel.
 #[allow(clippy::too_many_arguments)]
 pub fn create_branch_tool_server(
     state: Option<ChannelState>,
@@ -522,6 +665,7 @@ pub fn create_branch_tool_server(
     channel_store: crate::conversation::ChannelStore,
     run_logger: crate::conversation::history::ProcessRunLogger,
     profile: BranchToolProfile,
+    memory_scope: MemoryScope,
 ) -> ToolServerHandle {
     let mut memory_save = memory_save_with_events(
         memory_search.clone(),
@@ -531,12 +675,28 @@ pub fn create_branch_tool_server(
     if let BranchToolProfile::MemoryPersistence { contract_state } = &profile {
         memory_save = memory_save.with_contract_state(contract_state.clone());
     }
+    let visibility = match &state {
+        Some(state) => {
+            if memory_scope == MemoryScope::Channel {
+                memory_save = memory_save.scoped_to_channel(state.channel_id.clone());
+            }
+            MemoryVisibility::for_channel(&state.channel_id, memory_scope)
+        }
+        None => MemoryVisibility::All,
+    };
 
     let mut server = ToolServer::new()
         .tool(memory_save)
-        .tool(MemoryRecallTool::new(memory_search.clone()))
-        .tool(MemoryDeleteTool::new(memory_search))
-        .tool(ChannelRecallTool::new(conversation_logger, channel_store))
+        .tool(MemoryRecallTool::new(memory_search.clone()).with_visibility(visibility.clone()))
+        .tool(MemoryDeleteTool::new(memory_search.clone()).with_visibility(visibility))
+        .tool(ChannelRecallTool::new(
+            conversation_logger.clone(),
+            channel_store.clone(),
+        ))
+        .tool(ConversationSearchTool::new(
+            conversation_logger.clone(),
+            channel_store.clone(),
+        ))
         .tool(SpacebotDocsTool::new())
         .tool(EmailSearchTool::new(runtime_config))
         .tool(WorkerInspectTool::new(run_logger, agent_id.to_string()))
@@ -548,12 +708,26 @@ pub fn create_branch_tool_server(
         .tool(TaskListTool::new(task_store.clone(), agent_id.to_string()))
         .tool(TaskUpdateTool::for_branch(task_store, agent_id.clone()));
 
+    if memory_search.episodes().is_some() {
+        server = server.tool(EpisodicSearchTool::new(
+            memory_search,
+            conversation_logger,
+            channel_store,
+        ));
+    }
+
     if let BranchToolProfile::MemoryPersistence { contract_state } = profile {
         server = server.tool(MemoryPersistenceCompleteTool::new(contract_state));
     }
 
     if let Some(state) = state {
-        server = server.tool(SpawnWorkerTool::new(state));
+        server = server
+            .tool(ProfileUpdateTool::new(
+                crate::memory::UserProfileStore::new(state.deps.sqlite_pool.clone()),
+                state.channel_store.clone(),
+                &state.channel_id,
+            ))
+            .tool(SpawnWorkerTool::new(state));
     }
 
     server.run()
@@ -565,7 +739,7 @@ pub fn create_branch_tool_server(
 /// the specific worker's ID so status updates route correctly. The browser tool
 /// is included when browser automation is enabled in the agent config.
 ///
-/// Shell commands are sandboxed via the `Sandbox` backend.
+/// Shell and git commands are sandboxed via the `Sandbox` backend.
 /// File operations are restricted to `workspace` via path validation.
 #[allow(clippy::too_many_arguments)]
 pub fn create_worker_tool_server(
@@ -598,7 +772,8 @@ pub fn create_worker_tool_server(
         })
         .tool(ReadSkillTool::new(runtime_config.clone()));
 
-    server = register_file_tools(server, workspace, sandbox);
+    server = register_file_tools(server, workspace.clone(), sandbox.clone());
+    server = register_git_tools(server, workspace, sandbox, runtime_config.clone());
 
     if let Some(store) = runtime_config.secrets.load().as_ref() {
         server = server.tool(SecretSetTool::new(store.clone()));
@@ -664,6 +839,7 @@ pub fn create_cortex_chat_tool_server(
     cortex_ctx: Option<crate::tools::spawn_worker::CortexChatContext>,
 ) -> ToolServerHandle {
     let logs_dir = workspace.join(".spacebot").join("logs");
+    let pin_store = crate::memory::BulletinPinStore::new(deps.sqlite_pool.clone());
 
     let spawn_tool = {
         let tool = DetachedSpawnWorkerTool::new(deps, screenshot_dir.clone(), logs_dir);
@@ -680,8 +856,15 @@ pub fn create_cortex_chat_tool_server(
             memory_event_tx,
         ))
         .tool(MemoryRecallTool::new(memory_search.clone()))
-        .tool(MemoryDeleteTool::new(memory_search))
-        .tool(ChannelRecallTool::new(conversation_logger, channel_store))
+        .tool(MemoryDeleteTool::new(memory_search.clone()))
+        .tool(ChannelRecallTool::new(
+            conversation_logger.clone(),
+            channel_store.clone(),
+        ))
+        .tool(ConversationSearchTool::new(
+            conversation_logger.clone(),
+            channel_store.clone(),
+        ))
         .tool(SpacebotDocsTool::new())
         .tool(ConfigInspectTool::new(
             agent_id.to_string(),
@@ -689,8 +872,13 @@ pub fn create_cortex_chat_tool_server(
         ))
         .tool(SkillsSearchTool::new(runtime_config.clone()))
         .tool(InstallSkillTool::new(runtime_config.clone(), api_state))
+        .tool(BulletinPinTool::new(
+            pin_store.clone(),
+            runtime_config.clone(),
+        ))
+        .tool(BulletinUnpinTool::new(pin_store, runtime_config.clone()))
         .tool(WorkerInspectTool::new(run_logger, agent_id.to_string()))
-        .tool(spawn_tool)
+        .tool(spawn_tool.clone())
         .tool(TaskCreateTool::new(
             task_store.clone(),
             agent_id.to_string(),
@@ -700,6 +888,15 @@ pub fn create_cortex_chat_tool_server(
         .tool(TaskUpdateTool::for_branch(task_store, agent_id.clone()))
         .tool(ShellTool::new(workspace.clone(), sandbox.clone()));
 
+    if memory_search.episodes().is_some() {
+        server = server.tool(EpisodicSearchTool::new(
+            memory_search,
+            conversation_logger,
+            channel_store,
+        ));
+    }
+
+    server = register_skill_authoring_tools(server, runtime_config.clone(), Some(spawn_tool));
     server = register_file_tools(server, workspace, sandbox);
 
     if browser_config.enabled {
@@ -0,0 +1,207 @@
+//! Bulletin pin tools for cortex chat: `bulletin_pin`, `bulletin_unpin`.
+//!
+//! Pins are facts rendered ahead of the generated memory bulletin in every
+//! prompt, until unpinned or until their TTL runs out. The combined size of
+//! all pins is capped by `[defaults.cortex] bulletin_pin_max_words`.
+
+use crate::config::RuntimeConfig;
+use crate::memory::pins::check_pin_budget;
+use crate::memory::{BulletinPin, BulletinPinStore, NewBulletinPin};
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Error type for the bulletin pin tools.
+#[derive(Debug, thiserror::Error)]
+#[error("bulletin pin failed: {0}")]
+pub struct BulletinPinError(String);
+
+impl From<crate::error::Error> for BulletinPinError {
+    fn from(error: crate::error::Error) -> Self {
+        Self(error.to_string())
+    }
+}
+
+/// Output from `bulletin_pin` and `bulletin_unpin`.
+#[derive(Debug, Serialize)]
+pub struct BulletinPinOutput {
+    pub success: bool,
+    pub message: String,
+    /// Pins active after the call.
+    pub pins: Vec<BulletinPin>,
+}
+
+/// Tool for pinning a fact into every prompt's bulletin.
+#[derive(Clone)]
+pub struct BulletinPinTool {
+    store: BulletinPinStore,
+    runtime_config: Arc<RuntimeConfig>,
+}
+
+impl std::fmt::Debug for BulletinPinTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BulletinPinTool").finish_non_exhaustive()
+    }
+}
+
+impl BulletinPinTool {
+    pub fn new(store: BulletinPinStore, runtime_config: Arc<RuntimeConfig>) -> Self {
+        Self {
+            store,
+            runtime_config,
+        }
+    }
+}
+
+/// Arguments for bulletin_pin tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct BulletinPinArgs {
+    /// The fact to pin, as one short sentence or two.
+    pub content: String,
+    /// Hours until the pin lapses. Omit to keep it until unpinned.
+    #[serde(default)]
+    pub ttl_hours: Option<u32>,
+}
+
+impl Tool for BulletinPinTool {
+    const NAME: &'static str = "bulletin_pin";
+
+    type Error = BulletinPinError;
+    type Args = BulletinPinArgs;
+    type Output = BulletinPinOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/bulletin_pin").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "content": {
+                        "type": "string",
+                        "description": "The fact to pin. Keep it short; all pins share a word budget."
+                    },
+                    "ttl_hours": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": "Hours until the pin lapses. Omit to keep it until unpinned."
+                    }
+                },
+                "required": ["content"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let existing = self.store.active().await?;
+        let max_words = self.runtime_config.cortex.load().bulletin_pin_max_words;
+        let contents = existing
+            .iter()
+            .map(|pin| pin.content.as_str())
+            .chain([args.content.as_str()]);
+        if let Err(error) = check_pin_budget(contents, max_words) {
+            return Ok(BulletinPinOutput {
+                success: false,
+                message: error.to_string(),
+                pins: existing,
+            });
+        }
+
+        let expires_at = args
+            .ttl_hours
+            .filter(|hours| *hours > 0)
+            .map(|hours| chrono::Utc::now() + chrono::Duration::hours(i64::from(hours)));
+        let pin = self
+            .store
+            .pin(
+                &NewBulletinPin {
+                    content: args.content,
+                    expires_at,
+                },
+                "cortex",
+            )
+            .await?;
+        let pins = self.store.reload(&self.runtime_config).await?;
+        tracing::info!(pin_id = %pin.id, "bulletin pin added");
+
+        Ok(BulletinPinOutput {
+            success: true,
+            message: format!("Pinned as {}.", pin.id),
+            pins,
+        })
+    }
+}
+
+/// Tool for removing a bulletin pin.
+#[derive(Clone)]
+pub struct BulletinUnpinTool {
+    store: BulletinPinStore,
+    runtime_config: Arc<RuntimeConfig>,
+}
+
+impl std::fmt::Debug for BulletinUnpinTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BulletinUnpinTool").finish_non_exhaustive()
+    }
+}
+
+impl BulletinUnpinTool {
+    pub fn new(store: BulletinPinStore, runtime_config: Arc<RuntimeConfig>) -> Self {
+        Self {
+            store,
+            runtime_config,
+        }
+    }
+}
+
+/// Arguments for bulletin_unpin tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct BulletinUnpinArgs {
+    /// ID of the pin to remove.
+    pub id: String,
+}
+
+impl Tool for BulletinUnpinTool {
+    const NAME: &'static str = "bulletin_unpin";
+
+    type Error = BulletinPinError;
+    type Args = BulletinUnpinArgs;
+    type Output = BulletinPinOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/bulletin_unpin").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "string",
+                        "description": "ID of the pin to remove, as returned by bulletin_pin."
+                    }
+                },
+                "required": ["id"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let removed = self.store.unpin(args.id.trim()).await?;
+        let pins = self.store.reload(&self.runtime_config).await?;
+        let message = if removed {
+            tracing::info!(pin_id = %args.id, "bulletin pin removed");
+            format!("Unpinned {}.", args.id)
+        } else {
+            format!("No pin with ID {}.", args.id)
+        };
+
+        Ok(BulletinPinOutput {
+            success: removed,
+            message,
+            pins,
+        })
+    }
+}
@@ -1,5 +1,6 @@
 //! Channel transcript recall tool for branches. Queries any channel including the current one.
 
+use crate::conversation::archive::ChannelArchive;
 use crate::conversation::channels::ChannelStore;
 use crate::conversation::history::ConversationLogger;
 
@@ -11,18 +12,24 @@ use serde::{Deserialize, Serialize};
 /// Maximum messages to return in a single recall.
 const MAX_TRANSCRIPT_MESSAGES: i64 = 100;
 
+/// Maximum archived summaries to return alongside a transcript.
+const MAX_ARCHIVED_SUMMARIES: i64 = 20;
+
 /// Tool for recalling conversation transcript from any channel.
 #[derive(Debug, Clone)]
 pub struct ChannelRecallTool {
     conversation_logger: ConversationLogger,
     channel_store: ChannelStore,
+    archive: ChannelArchive,
 }
 
 impl ChannelRecallTool {
     pub fn new(conversation_logger: ConversationLogger, channel_store: ChannelStore) -> Self {
+        let archive = conversation_logger.archive();
         Self {
             conversation_logger,
             channel_store,
+            archive,
         }
     }
 }
@@ -68,6 +75,15 @@ pub struct TranscriptMessage {
     pub timestamp: String,
 }
 
+/// A summary of archived messages that are no longer in the transcript.
+#[derive(Debug, Serialize)]
+pub struct ArchivedSummary {
+    pub summary: String,
+    pub message_count: i64,
+    pub from: String,
+    pub to: String,
+}
+
 /// Output from channel recall tool.
 #[derive(Debug, Serialize)]
 pub struct ChannelRecallOutput {
@@ -79,6 +95,8 @@ pub struct ChannelRecallOutput {
     pub channel_name: Option<String>,
     /// The transcript messages, if a channel was queried.
     pub messages: Vec<TranscriptMessage>,
+    /// Summaries of older, archived history in the queried time range.
+    pub archived_summaries: Vec<ArchivedSummary>,
     /// Available channels, if listing mode.
     pub available_channels: Vec<ChannelListEntry>,
     /// Formatted summary for the agent.
@@ -204,13 +222,38 @@ impl Tool for ChannelRecallTool {
             })
             .collect();
 
-        let summary = format_transcript(&channel.display_name, &channel.id, &transcript);
+        let archived_summaries: Vec<ArchivedSummary> = self
+            .archive
+            .list_summaries(
+                &channel.id,
+                args.before.as_deref(),
+                args.after.as_deref(),
+                MAX_ARCHIVED_SUMMARIES,
+            )
+            .await
+            .map_err(|e| ChannelRecallError(format!("Failed to load archived summaries: {e}")))?
+            .into_iter()
+            .map(|summary| ArchivedSummary {
+                summary: summary.summary,
+                message_count: summary.message_count,
+                from: summary.first_message_at.to_rfc3339(),
+                to: summary.last_message_at.to_rfc3339(),
+            })
+            .collect();
+
+        let summary = format_transcript(
+            &channel.display_name,
+            &channel.id,
+            &archived_summaries,
+            &transcript,
+        );
 
         Ok(ChannelRecallOutput {
             action: "transcript".to_string(),
             channel_id: Some(channel.id),
             channel_name: channel.display_name,
             messages: transcript,
+            archived_summaries,
             available_channels: vec![],
             summary,
         })
@@ -241,6 +284,7 @@ impl ChannelRecallTool {
             channel_id: None,
             channel_name: None,
             messages: vec![],
+            archived_summaries: vec![],
             available_channels: entries,
             summary,
         })
@@ -250,9 +294,10 @@ impl ChannelRecallTool {
 fn format_transcript(
     channel_name: &Option<String>,
     channel_id: &str,
+    archived_summaries: &[ArchivedSummary],
     messages: &[TranscriptMessage],
 ) -> String {
-    if messages.is_empty() {
+    if messages.is_empty() && archived_summaries.is_empty() {
         return format!(
             "No messages found in channel {}.",
             channel_name.as_deref().unwrap_or(channel_id)
@@ -260,10 +305,25 @@ fn format_transcript(
     }
 
     let label = channel_name.as_deref().unwrap_or(channel_id);
-    let mut output = format!(
+    let mut output = String::new();
+
+    if !archived_summaries.is_empty() {
+        output.push_str(&format!(
+            "## Archived history from #{label} ({} summaries)\n\n",
+            archived_summaries.len()
+        ));
+        for archived in archived_summaries {
+            output.push_str(&format!(
+                "**{} to {}** ({} messages): {}\n\n",
+                archived.from, archived.to, archived.message_count, archived.summary
+            ));
+        }
+    }
+
+    output.push_str(&format!(
         "## Transcript from #{label} ({} messages)\n\n",
         messages.len()
-    );
+    ));
 
     for message in messages {
         let sender = match &message.sender {
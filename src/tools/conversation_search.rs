@@ -0,0 +1,236 @@
+//! Keyword search across persisted conversation history in all channels.
+
+use crate::conversation::channels::ChannelStore;
+use crate::conversation::history::{ConversationLogger, MessageSearchQuery};
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Maximum hits to return in a single search.
+const MAX_SEARCH_RESULTS: i64 = 50;
+
+/// Tool for full-text searching conversation messages across channels.
+#[derive(Debug, Clone)]
+pub struct ConversationSearchTool {
+    conversation_logger: ConversationLogger,
+    channel_store: ChannelStore,
+}
+
+impl ConversationSearchTool {
+    pub fn new(conversation_logger: ConversationLogger, channel_store: ChannelStore) -> Self {
+        Self {
+            conversation_logger,
+            channel_store,
+        }
+    }
+}
+
+/// Error type for conversation search tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Conversation search failed: {0}")]
+pub struct ConversationSearchError(String);
+
+/// Arguments for conversation search tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ConversationSearchArgs {
+    /// Keywords to search for. All terms must appear in a message. Append `*`
+    /// to a term for prefix matching (e.g. "deploy*").
+    pub query: String,
+    /// Restrict the search to one channel. Can be a channel name, a partial
+    /// name, or a full channel ID. If omitted, searches all channels.
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Maximum number of results to return (default 20, max 50).
+    #[serde(default = "default_result_limit")]
+    pub limit: i64,
+    /// Only match messages sent before this timestamp (RFC 3339).
+    #[serde(default)]
+    pub before: Option<String>,
+    /// Only match messages sent after this timestamp (RFC 3339).
+    #[serde(default)]
+    pub after: Option<String>,
+}
+
+fn default_result_limit() -> i64 {
+    20
+}
+
+/// A single search hit in the tool output.
+#[derive(Debug, Serialize)]
+pub struct ConversationSearchResult {
+    pub message_id: String,
+    pub channel_id: String,
+    pub channel_name: Option<String>,
+    pub role: String,
+    pub sender: Option<String>,
+    pub snippet: String,
+    pub timestamp: String,
+}
+
+/// Output from conversation search tool.
+#[derive(Debug, Serialize)]
+pub struct ConversationSearchOutput {
+    pub query: String,
+    pub results: Vec<ConversationSearchResult>,
+    /// Formatted summary for the agent.
+    pub summary: String,
+}
+
+impl Tool for ConversationSearchTool {
+    const NAME: &'static str = "conversation_search";
+
+    type Error = ConversationSearchError;
+    type Args = ConversationSearchArgs;
+    type Output = ConversationSearchOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/conversation_search").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Keywords to search for. All terms must match. Append * for prefix matching (e.g. \"deploy*\")."
+                    },
+                    "channel": {
+                        "type": "string",
+                        "description": "Channel name (e.g. \"general\") or full channel ID. Omit to search all channels."
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": 50,
+                        "default": 20,
+                        "description": "Maximum number of results to return (1-50)"
+                    },
+                    "before": {
+                        "type": "string",
+                        "description": "Only match messages before this timestamp (RFC 3339, e.g. \"2026-01-15T00:00:00Z\")"
+                    },
+                    "after": {
+                        "type": "string",
+                        "description": "Only match messages after this timestamp (RFC 3339, e.g. \"2026-01-15T00:00:00Z\")"
+                    }
+                },
+                "required": ["query"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> std::result::Result<Self::Output, Self::Error> {
+        let limit = args.limit.clamp(1, MAX_SEARCH_RESULTS);
+
+        let channel_id = match args.channel.as_deref() {
+            Some(channel_query) => {
+                let found = self
+                    .channel_store
+                    .find_by_name(channel_query)
+                    .await
+                    .map_err(|e| {
+                        ConversationSearchError(format!("Failed to search channels: {e}"))
+                    })?;
+                match found {
+                    Some(channel) => Some(channel.id),
+                    None => {
+                        return Ok(ConversationSearchOutput {
+                            query: args.query,
+                            results: vec![],
+                            summary: format!(
+                                "No channel matching \"{channel_query}\" was found. Use channel_recall without arguments to list available channels."
+                            ),
+                        });
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let before = parse_bound("before", args.before.as_deref())?;
+        let after = parse_bound("after", args.after.as_deref())?;
+
+        let hits = self
+            .conversation_logger
+            .search_messages(
+                &MessageSearchQuery {
+                    text: &args.query,
+                    channel_id: channel_id.as_deref(),
+                    before,
+                    after,
+                    limit,
+                },
+                "**",
+                "**",
+            )
+            .await
+            .map_err(|e| ConversationSearchError(format!("Failed to search messages: {e}")))?;
+
+        let results: Vec<ConversationSearchResult> = hits
+            .into_iter()
+            .map(|hit| ConversationSearchResult {
+                message_id: hit.id,
+                channel_id: hit.channel_id,
+                channel_name: hit.channel_name,
+                role: hit.role,
+                sender: hit.sender_name,
+                snippet: hit.snippet,
+                timestamp: hit.created_at.to_rfc3339(),
+            })
+            .collect();
+
+        let summary = format_results(&args.query, &results);
+
+        Ok(ConversationSearchOutput {
+            query: args.query,
+            results,
+            summary,
+        })
+    }
+}
+
+/// Parse an optional RFC 3339 date filter. A malformed one is an error
+/// rather than a filter that silently matches nothing.
+fn parse_bound(
+    name: &str,
+    value: Option<&str>,
+) -> std::result::Result<Option<chrono::DateTime<chrono::Utc>>, ConversationSearchError> {
+    value
+        .map(|value| {
+            chrono::DateTime::parse_from_rfc3339(value.trim())
+                .map(|time| time.with_timezone(&chrono::Utc))
+                .map_err(|_| {
+                    ConversationSearchError(format!(
+                        "Invalid `{name}` timestamp \"{value}\": use RFC 3339, e.g. \"2026-01-15T00:00:00Z\""
+                    ))
+                })
+        })
+        .transpose()
+}
+
+fn format_results(query: &str, results: &[ConversationSearchResult]) -> String {
+    if results.is_empty() {
+        return format!("No messages matched \"{query}\".");
+    }
+
+    let mut output = format!(
+        "## Conversation search: \"{query}\" ({} results)\n\n",
+        results.len()
+    );
+
+    for result in results {
+        let channel = result
+            .channel_name
+            .as_deref()
+            .unwrap_or(result.channel_id.as_str());
+        let sender = result.sender.as_deref().unwrap_or("assistant");
+        output.push_str(&format!(
+            "- #{channel} — **{sender}** ({}) at {}: {}\n  channel ID: `{}`\n",
+            result.role, result.timestamp, result.snippet, result.channel_id,
+        ));
+    }
+
+    output
+}
@@ -21,6 +21,7 @@ pub struct CronTool {
     store: Arc<CronStore>,
     scheduler: Arc<Scheduler>,
     default_delivery_target: Option<String>,
+    default_timezone: Option<String>,
 }
 
 impl CronTool {
@@ -29,6 +30,7 @@ impl CronTool {
             store,
             scheduler,
             default_delivery_target: None,
+            default_timezone: None,
         }
     }
 
@@ -36,6 +38,12 @@ impl CronTool {
         self.default_delivery_target = default_delivery_target;
         self
     }
+
+    /// Timezone for jobs created without one, usually the conversation's.
+    pub fn with_default_timezone(mut self, default_timezone: Option<String>) -> Self {
+        self.default_timezone = default_timezone;
+        self
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -78,6 +86,10 @@ pub struct CronArgs {
     /// Optional for "create": if true, run only once and disable after first execution attempt.
     #[serde(default)]
     pub run_once: Option<bool>,
+    /// Optional for "create": IANA timezone the schedule and active hours are read in.
+    /// Defaults to the current conversation's timezone, then the agent's.
+    #[serde(default)]
+    pub timezone: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -98,6 +110,7 @@ pub struct CronEntry {
     pub delivery_target: String,
     pub run_once: bool,
     pub active_hours: Option<String>,
+    pub timezone: Option<String>,
 }
 
 impl Tool for CronTool {
@@ -158,6 +171,10 @@ impl Tool for CronTool {
                     "run_once": {
                         "type": "boolean",
                         "description": "For 'create': if true, run this job once and auto-disable after the first execution attempt."
+                    },
+                    "timezone": {
+                        "type": "string",
+                        "description": "For 'create': IANA timezone (e.g. 'Europe/Berlin') the schedule and active hours are read in. Defaults to the current conversation's timezone."
                     }
                 },
                 "required": ["action"]
@@ -268,6 +285,20 @@ impl CronTool {
             _ => None,
         };
         let run_once = args.run_once.unwrap_or(false);
+        let timezone = args
+            .timezone
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(ToString::to_string)
+            .or_else(|| self.default_timezone.clone());
+        if let Some(timezone) = timezone.as_deref()
+            && timezone.parse::<chrono_tz::Tz>().is_err()
+        {
+            return Err(CronError(format!(
+                "'timezone' must be an IANA timezone (got '{timezone}')"
+            )));
+        }
 
         let config = CronConfig {
             id: id.clone(),
@@ -279,6 +310,7 @@ impl CronTool {
             enabled: true,
             run_once,
             timeout_secs: args.timeout_secs,
+            timezone: timezone.clone(),
         };
 
         // Persist to database
@@ -297,7 +329,7 @@ impl CronTool {
             .as_deref()
             .map(|expr| format!("on schedule `{expr}`"))
             .unwrap_or_else(|| format_interval(interval_secs));
-        let timezone = self.scheduler.cron_timezone_label();
+        let timezone = timezone.unwrap_or_else(|| self.scheduler.cron_timezone_label());
         let mut message = if run_once {
             format!("Cron job '{id}' created. First run {schedule_desc}; it then disables itself.")
         } else {
@@ -345,6 +377,7 @@ impl CronTool {
                 active_hours: config
                     .active_hours
                     .map(|(s, e)| format!("{s:02}:00-{e:02}:00")),
+                timezone: config.timezone,
             })
             .collect();
 
@@ -357,7 +390,9 @@ impl CronTool {
         };
         Ok(CronOutput {
             success: true,
-            message: format!("{count} active cron job(s); {timezone_note}."),
+            message: format!(
+                "{count} active cron job(s); {timezone_note} unless a job sets its own timezone."
+            ),
             jobs: Some(entries),
         })
     }
@@ -0,0 +1,332 @@
+//! Delegate a task to another agent and wait for, or poll, its result.
+//!
+//! Workers use this to hand part of their job to a specialist agent on the
+//! same instance. The task goes through the same path as `send_agent_message`:
+//! it is created ready on the target agent, whose cortex picks it up and runs
+//! it in a worker, and the assignment and outcome are logged on both sides of
+//! the link channel. Unlike `send_agent_message`, the caller's turn continues:
+//! it can wait up to [`MAX_WAIT_SECS`] for the result, or return immediately
+//! and poll later with the task number.
+
+use crate::WorkerId;
+use crate::tasks::{Task, TaskStatus, TaskStore};
+use crate::tools::send_agent_message::SendAgentMessageTool;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Longest a single call may wait for a result.
+const MAX_WAIT_SECS: u64 = 300;
+
+/// How often the target agent's task is checked while waiting.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Tool for delegating work to a linked agent and collecting the result.
+#[derive(Debug, Clone)]
+pub struct DelegateToAgentTool {
+    /// Resolves targets, checks links and creates the task.
+    messenger: SendAgentMessageTool,
+    worker_id: Option<WorkerId>,
+}
+
+impl DelegateToAgentTool {
+    /// `messenger` should have no originating channel: the caller collects
+    /// the result itself, so the delegating channel isn't retriggered.
+    pub fn new(messenger: SendAgentMessageTool) -> Self {
+        Self {
+            messenger,
+            worker_id: None,
+        }
+    }
+
+    /// Record the delegating worker on the task.
+    pub fn for_worker(mut self, worker_id: WorkerId) -> Self {
+        self.worker_id = Some(worker_id);
+        self
+    }
+}
+
+/// Error type for delegate_to_agent tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Delegation failed: {0}")]
+pub struct DelegateToAgentError(String);
+
+/// Arguments for delegate_to_agent tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DelegateToAgentArgs {
+    /// Target agent ID or name.
+    pub target: String,
+    /// The task to delegate. Omit to check on `task_number` instead.
+    #[serde(default)]
+    pub task: Option<String>,
+    /// A task number returned by an earlier call, to check its result.
+    #[serde(default)]
+    pub task_number: Option<i64>,
+    /// Seconds to wait for the result before returning. 0 returns right away.
+    #[serde(default)]
+    pub wait_seconds: u64,
+}
+
+/// Where a delegated task stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DelegationStatus {
+    /// Waiting for the target agent's cortex to pick it up.
+    Queued,
+    InProgress,
+    Completed,
+    /// The last attempt failed. The task is queued again for a retry.
+    Failed,
+    /// Moved out of the ready queue on the target agent.
+    OnHold,
+}
+
+impl DelegationStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed)
+    }
+}
+
+/// Output from delegate_to_agent tool.
+#[derive(Debug, Serialize)]
+pub struct DelegateToAgentOutput {
+    pub target_agent: String,
+    pub task_number: i64,
+    pub status: DelegationStatus,
+    /// The target agent's result, or the failure, once finished.
+    pub result: Option<String>,
+    pub message: String,
+}
+
+impl Tool for DelegateToAgentTool {
+    const NAME: &'static str = "delegate_to_agent";
+
+    type Error = DelegateToAgentError;
+    type Args = DelegateToAgentArgs;
+    type Output = DelegateToAgentOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/delegate_to_agent").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "target": {
+                        "type": "string",
+                        "description": "The target agent's ID or name."
+                    },
+                    "task": {
+                        "type": "string",
+                        "description": "The task to delegate, with everything the other agent needs to do it. First sentence becomes the title. Omit to check on task_number."
+                    },
+                    "task_number": {
+                        "type": "integer",
+                        "description": "Task number from an earlier delegate_to_agent call, to check its result."
+                    },
+                    "wait_seconds": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "maximum": MAX_WAIT_SECS,
+                        "description": "How long to wait for the result before returning. 0 returns immediately; poll later with task_number."
+                    }
+                },
+                "required": ["target"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let wait = Duration::from_secs(args.wait_seconds.min(MAX_WAIT_SECS));
+        let (target_agent_id, target_display, task_store, task_number) = match (
+            args.task,
+            args.task_number,
+        ) {
+            (Some(task), None) => {
+                let delegated = self
+                    .messenger
+                    .assign_task(
+                        &args.target,
+                        &task,
+                        serde_json::json!({
+                            "delegated_via": Self::NAME,
+                            "delegating_worker_id": self.worker_id.map(|id| id.to_string()),
+                        }),
+                    )
+                    .await
+                    .map_err(|error| DelegateToAgentError(error.0))?;
+                (
+                    delegated.target_agent_id,
+                    delegated.target_display,
+                    delegated.task_store,
+                    delegated.task_number,
+                )
+            }
+            (None, Some(task_number)) => {
+                let (target_agent_id, target_display) = self
+                    .messenger
+                    .resolve_linked_target(&args.target)
+                    .map_err(|error| DelegateToAgentError(error.0))?;
+                let task_store = self
+                    .messenger
+                    .target_task_store(&target_agent_id, &target_display)
+                    .map_err(|error| DelegateToAgentError(error.0))?;
+                (target_agent_id, target_display, task_store, task_number)
+            }
+            _ => {
+                return Err(DelegateToAgentError(
+                        "pass either `task` to delegate new work or `task_number` to check on earlier work"
+                            .into(),
+                    ));
+            }
+        };
+
+        let deadline = tokio::time::Instant::now() + wait;
+        loop {
+            let (status, result) = check_delegation(
+                &task_store,
+                &target_agent_id,
+                &target_display,
+                task_number,
+                self.messenger.agent_id(),
+            )
+            .await
+            .map_err(DelegateToAgentError)?;
+            if status.is_finished() || tokio::time::Instant::now() >= deadline {
+                let message = match status {
+                    DelegationStatus::Completed => format!("{target_display} completed the task."),
+                    DelegationStatus::Failed => format!(
+                        "{target_display}'s last attempt failed; the task is queued for a retry."
+                    ),
+                    _ => format!(
+                        "Not finished yet. Call again with task_number {task_number} to check."
+                    ),
+                };
+                return Ok(DelegateToAgentOutput {
+                    target_agent: target_display,
+                    task_number,
+                    status,
+                    result,
+                    message,
+                });
+            }
+            tokio::time::sleep(
+                POLL_INTERVAL.min(deadline.saturating_duration_since(tokio::time::Instant::now())),
+            )
+            .await;
+        }
+    }
+}
+
+/// Status and, once finished, result of a task `delegating_agent_id`
+/// delegated to `target_agent_id`.
+pub(crate) async fn check_delegation(
+    task_store: &TaskStore,
+    target_agent_id: &str,
+    target_display: &str,
+    task_number: i64,
+    delegating_agent_id: &str,
+) -> Result<(DelegationStatus, Option<String>), String> {
+    let task = task_store
+        .get_by_number(target_agent_id, task_number)
+        .await
+        .map_err(|error| error.to_string())?
+        .ok_or_else(|| {
+            format!("task #{task_number} no longer exists on agent '{target_display}'")
+        })?;
+    if !delegated_by(&task, delegating_agent_id) {
+        return Err(format!(
+            "task #{task_number} on agent '{target_display}' was not delegated by you"
+        ));
+    }
+    Ok(delegation_state(&task))
+}
+
+/// Whether `agent_id` delegated `task`.
+fn delegated_by(task: &Task, agent_id: &str) -> bool {
+    task.metadata
+        .get("delegating_agent_id")
+        .and_then(|value| value.as_str())
+        == Some(agent_id)
+}
+
+/// Status and, once finished, result of a delegated task. The target's
+/// cortex records the outcome under `metadata.delegation_result`.
+fn delegation_state(task: &Task) -> (DelegationStatus, Option<String>) {
+    let outcome = task.metadata.get("delegation_result");
+    let result = outcome
+        .and_then(|outcome| outcome.get("result"))
+        .and_then(|result| result.as_str())
+        .map(str::to_string);
+    let last_failed = outcome
+        .and_then(|outcome| outcome.get("success"))
+        .and_then(|success| success.as_bool())
+        == Some(false);
+
+    match task.status {
+        TaskStatus::Done => (DelegationStatus::Completed, result),
+        TaskStatus::InProgress => (DelegationStatus::InProgress, None),
+        TaskStatus::Ready if last_failed => (DelegationStatus::Failed, result),
+        TaskStatus::Ready => (DelegationStatus::Queued, None),
+        TaskStatus::PendingApproval | TaskStatus::Backlog => (DelegationStatus::OnHold, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::TaskPriority;
+
+    fn task(status: TaskStatus, metadata: serde_json::Value) -> Task {
+        Task {
+            id: "task-1".into(),
+            agent_id: "research".into(),
+            task_number: 7,
+            title: "Summarize the report.".into(),
+            description: None,
+            status,
+            priority: TaskPriority::Medium,
+            subtasks: Vec::new(),
+            metadata,
+            source_memory_id: None,
+            worker_id: None,
+            created_by: "agent:main".into(),
+            approved_at: None,
+            approved_by: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+            completed_at: None,
+        }
+    }
+
+    #[test]
+    fn reports_result_once_finished() {
+        let done = task(
+            TaskStatus::Done,
+            serde_json::json!({
+                "delegating_agent_id": "main",
+                "delegation_result": { "success": true, "result": "Three findings." },
+            }),
+        );
+        assert!(delegated_by(&done, "main"));
+        assert!(!delegated_by(&done, "other"));
+        assert_eq!(
+            delegation_state(&done),
+            (DelegationStatus::Completed, Some("Three findings.".into()))
+        );
+
+        let retrying = task(
+            TaskStatus::Ready,
+            serde_json::json!({
+                "delegation_result": { "success": false, "result": "Worker timed out." },
+            }),
+        );
+        assert_eq!(delegation_state(&retrying).0, DelegationStatus::Failed);
+
+        let queued = task(TaskStatus::Ready, serde_json::json!({}));
+        assert_eq!(delegation_state(&queued), (DelegationStatus::Queued, None));
+    }
+}
@@ -0,0 +1,117 @@
+//! Delete reply tool for removing a message the agent already sent (channel only).
+
+use crate::conversation::ConversationLogger;
+use crate::tools::reply::await_delivery;
+use crate::{ChannelId, OutboundResponse, RoutedSender};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tool for deleting a previously sent reply.
+///
+/// Only replies the agent sent in the current conversation can be deleted;
+/// the `message_id` comes from the `reply` tool's output.
+#[derive(Debug, Clone)]
+pub struct DeleteReplyTool {
+    response_tx: RoutedSender,
+    conversation_id: String,
+    conversation_logger: ConversationLogger,
+    channel_id: ChannelId,
+}
+
+impl DeleteReplyTool {
+    pub fn new(
+        response_tx: RoutedSender,
+        conversation_id: impl Into<String>,
+        conversation_logger: ConversationLogger,
+        channel_id: ChannelId,
+    ) -> Self {
+        Self {
+            response_tx,
+            conversation_id: conversation_id.into(),
+            conversation_logger,
+            channel_id,
+        }
+    }
+}
+
+/// Error type for delete_reply tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Delete reply failed: {0}")]
+pub struct DeleteReplyError(String);
+
+/// Arguments for delete_reply tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DeleteReplyArgs {
+    /// The `message_id` returned by the reply tool.
+    pub message_id: String,
+}
+
+/// Output from delete_reply tool.
+#[derive(Debug, Serialize)]
+pub struct DeleteReplyOutput {
+    pub success: bool,
+    pub message_id: String,
+}
+
+impl Tool for DeleteReplyTool {
+    const NAME: &'static str = "delete_reply";
+
+    type Error = DeleteReplyError;
+    type Args = DeleteReplyArgs;
+    type Output = DeleteReplyOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/delete_reply").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "message_id": {
+                        "type": "string",
+                        "description": "The message_id returned by the reply tool for the message to delete."
+                    }
+                },
+                "required": ["message_id"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let message_id = args.message_id.trim().to_string();
+        tracing::info!(
+            conversation_id = %self.conversation_id,
+            message_id = %message_id,
+            "delete_reply tool called"
+        );
+
+        let reply = self
+            .conversation_logger
+            .find_bot_reply(&self.channel_id, &message_id)
+            .await
+            .map_err(|error| DeleteReplyError(format!("failed to look up reply: {error}")))?
+            .ok_or_else(|| {
+                DeleteReplyError(format!(
+                    "no reply with message_id '{message_id}' in this conversation"
+                ))
+            })?;
+
+        let delivery = self
+            .response_tx
+            .send_tracked(OutboundResponse::Delete {
+                message_id: message_id.clone(),
+            })
+            .await
+            .map_err(|error| DeleteReplyError(format!("failed to send delete: {error}")))?;
+        await_delivery(delivery).await.map_err(DeleteReplyError)?;
+
+        self.conversation_logger.log_bot_reply_deleted(&reply.id);
+
+        Ok(DeleteReplyOutput {
+            success: true,
+            message_id,
+        })
+    }
+}
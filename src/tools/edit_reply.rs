@@ -0,0 +1,160 @@
+//! Edit reply tool for changing a message the agent already sent (channel only).
+
+use crate::conversation::ConversationLogger;
+use crate::tools::reply::{await_delivery, convert_mentions};
+use crate::{ChannelId, OutboundResponse, RoutedSender};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tool for editing a previously sent reply.
+///
+/// Only replies the agent sent in the current conversation can be edited;
+/// the `message_id` comes from the `reply` tool's output.
+#[derive(Debug, Clone)]
+pub struct EditReplyTool {
+    response_tx: RoutedSender,
+    conversation_id: String,
+    conversation_logger: ConversationLogger,
+    channel_id: ChannelId,
+}
+
+impl EditReplyTool {
+    pub fn new(
+        response_tx: RoutedSender,
+        conversation_id: impl Into<String>,
+        conversation_logger: ConversationLogger,
+        channel_id: ChannelId,
+    ) -> Self {
+        Self {
+            response_tx,
+            conversation_id: conversation_id.into(),
+            conversation_logger,
+            channel_id,
+        }
+    }
+}
+
+/// Error type for edit_reply tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Edit reply failed: {0}")]
+pub struct EditReplyError(String);
+
+/// Arguments for edit_reply tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct EditReplyArgs {
+    /// The `message_id` returned by the reply tool.
+    pub message_id: String,
+    /// The new message content, replacing the old text entirely.
+    pub content: String,
+}
+
+/// Output from edit_reply tool.
+#[derive(Debug, Serialize)]
+pub struct EditReplyOutput {
+    pub success: bool,
+    pub message_id: String,
+    pub content: String,
+}
+
+impl Tool for EditReplyTool {
+    const NAME: &'static str = "edit_reply";
+
+    type Error = EditReplyError;
+    type Args = EditReplyArgs;
+    type Output = EditReplyOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/edit_reply").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "message_id": {
+                        "type": "string",
+                        "description": "The message_id returned by the reply tool for the message to edit."
+                    },
+                    "content": {
+                        "type": "string",
+                        "description": "The full replacement text. Can be markdown formatted."
+                    }
+                },
+                "required": ["message_id", "content"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let message_id = args.message_id.trim().to_string();
+        tracing::info!(
+            conversation_id = %self.conversation_id,
+            message_id = %message_id,
+            content_len = args.content.len(),
+            "edit_reply tool called"
+        );
+
+        if args.content.trim().is_empty() {
+            return Err(EditReplyError(
+                "content is empty; use delete_reply to remove a message".into(),
+            ));
+        }
+
+        let reply = self
+            .conversation_logger
+            .find_bot_reply(&self.channel_id, &message_id)
+            .await
+            .map_err(|error| EditReplyError(format!("failed to look up reply: {error}")))?
+            .ok_or_else(|| {
+                EditReplyError(format!(
+                    "no reply with message_id '{message_id}' in this conversation"
+                ))
+            })?;
+
+        let source = self.conversation_id.split(':').next().unwrap_or("unknown");
+        let converted_content = convert_mentions(
+            &args.content,
+            &self.channel_id,
+            &self.conversation_logger,
+            source,
+        )
+        .await;
+
+        if crate::tools::should_block_user_visible_text(&converted_content) {
+            return Err(EditReplyError(
+                "blocked edit content: looks like tool syntax or structured payload".into(),
+            ));
+        }
+
+        if let Some(leak) = crate::secrets::scrub::scan_for_leaks(&converted_content) {
+            tracing::error!(
+                conversation_id = %self.conversation_id,
+                leak_prefix = %&leak[..leak.len().min(8)],
+                "edit_reply tool blocked content matching secret pattern"
+            );
+            return Err(EditReplyError(
+                "blocked edit content: potential secret detected".into(),
+            ));
+        }
+
+        let delivery = self
+            .response_tx
+            .send_tracked(OutboundResponse::Edit {
+                message_id: message_id.clone(),
+                text: converted_content.clone(),
+            })
+            .await
+            .map_err(|error| EditReplyError(format!("failed to send edit: {error}")))?;
+        await_delivery(delivery).await.map_err(EditReplyError)?;
+
+        self.conversation_logger
+            .log_bot_reply_edited(&reply.id, &converted_content);
+
+        Ok(EditReplyOutput {
+            success: true,
+            message_id,
+            content: converted_content,
+        })
+    }
+}
@@ -0,0 +1,230 @@
+//! Semantic search over past conversations in all channels.
+
+use crate::conversation::channels::ChannelStore;
+use crate::conversation::history::ConversationLogger;
+use crate::memory::MemorySearch;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Maximum episodes to return in a single search.
+const MAX_EPISODE_RESULTS: usize = 20;
+
+/// Tool for recalling past conversation episodes by meaning rather than
+/// keywords.
+#[derive(Debug, Clone)]
+pub struct EpisodicSearchTool {
+    memory_search: Arc<MemorySearch>,
+    conversation_logger: ConversationLogger,
+    channel_store: ChannelStore,
+}
+
+impl EpisodicSearchTool {
+    pub fn new(
+        memory_search: Arc<MemorySearch>,
+        conversation_logger: ConversationLogger,
+        channel_store: ChannelStore,
+    ) -> Self {
+        Self {
+            memory_search,
+            conversation_logger,
+            channel_store,
+        }
+    }
+}
+
+/// Error type for episodic search tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Episodic search failed: {0}")]
+pub struct EpisodicSearchError(String);
+
+/// Arguments for episodic search tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct EpisodicSearchArgs {
+    /// What the conversation was about, in natural language.
+    pub query: String,
+    /// Restrict the search to one channel. Can be a channel name, a partial
+    /// name, or a full channel ID. If omitted, searches all channels.
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Maximum number of episodes to return (default 5, max 20).
+    #[serde(default = "default_result_limit")]
+    pub limit: usize,
+}
+
+fn default_result_limit() -> usize {
+    5
+}
+
+/// A single matching conversation episode.
+#[derive(Debug, Serialize)]
+pub struct EpisodicSearchResult {
+    pub channel_id: String,
+    pub channel_name: Option<String>,
+    pub started_at: String,
+    pub ended_at: String,
+    pub similarity: f32,
+    pub transcript: String,
+}
+
+/// Output from episodic search tool.
+#[derive(Debug, Serialize)]
+pub struct EpisodicSearchOutput {
+    pub query: String,
+    pub results: Vec<EpisodicSearchResult>,
+    /// Formatted summary for the agent.
+    pub summary: String,
+}
+
+impl Tool for EpisodicSearchTool {
+    const NAME: &'static str = "episodic_search";
+
+    type Error = EpisodicSearchError;
+    type Args = EpisodicSearchArgs;
+    type Output = EpisodicSearchOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/episodic_search").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "What the conversation was about, e.g. \"decision about the database migration\"."
+                    },
+                    "channel": {
+                        "type": "string",
+                        "description": "Channel name (e.g. \"general\") or full channel ID. Omit to search all channels."
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": 20,
+                        "default": 5,
+                        "description": "Maximum number of conversation excerpts to return (1-20)"
+                    }
+                },
+                "required": ["query"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> std::result::Result<Self::Output, Self::Error> {
+        let Some(episodes) = self.memory_search.episodes() else {
+            return Err(EpisodicSearchError(
+                "episodic recall is unavailable for this agent".to_string(),
+            ));
+        };
+        let limit = args.limit.clamp(1, MAX_EPISODE_RESULTS);
+
+        let channel_id = match args.channel.as_deref() {
+            Some(channel_query) => {
+                let found = self
+                    .channel_store
+                    .find_by_name(channel_query)
+                    .await
+                    .map_err(|e| EpisodicSearchError(format!("Failed to search channels: {e}")))?;
+                match found {
+                    Some(channel) => Some(channel.id),
+                    None => {
+                        return Ok(EpisodicSearchOutput {
+                            query: args.query,
+                            results: vec![],
+                            summary: format!(
+                                "No channel matching \"{channel_query}\" was found. Use channel_recall without arguments to list available channels."
+                            ),
+                        });
+                    }
+                }
+            }
+            None => None,
+        };
+
+        // Catch the index up before searching. A failed sync still leaves
+        // everything indexed so far searchable.
+        if let Err(error) = episodes
+            .sync(
+                &self.conversation_logger,
+                self.memory_search.embedding_model_arc(),
+            )
+            .await
+        {
+            tracing::warn!(%error, "failed to index conversation episodes");
+        }
+
+        let query_embedding = self
+            .memory_search
+            .embedding_model_arc()
+            .embed_one(&args.query)
+            .await
+            .map_err(|e| EpisodicSearchError(format!("Failed to embed query: {e}")))?;
+        let matches = episodes
+            .search(&query_embedding, channel_id.as_deref(), limit)
+            .await
+            .map_err(|e| EpisodicSearchError(format!("Failed to search episodes: {e}")))?;
+
+        let mut channel_names: HashMap<String, Option<String>> = HashMap::new();
+        let mut results = Vec::with_capacity(matches.len());
+        for episode in matches {
+            let channel_name = match channel_names.get(&episode.channel_id) {
+                Some(name) => name.clone(),
+                None => {
+                    let name = self.channel_store.resolve_name(&episode.channel_id).await;
+                    channel_names.insert(episode.channel_id.clone(), name.clone());
+                    name
+                }
+            };
+            results.push(EpisodicSearchResult {
+                channel_id: episode.channel_id,
+                channel_name,
+                started_at: episode.started_at.to_rfc3339(),
+                ended_at: episode.ended_at.to_rfc3339(),
+                similarity: episode.similarity,
+                transcript: episode.content,
+            });
+        }
+
+        let summary = format_results(&args.query, &results);
+
+        Ok(EpisodicSearchOutput {
+            query: args.query,
+            results,
+            summary,
+        })
+    }
+}
+
+fn format_results(query: &str, results: &[EpisodicSearchResult]) -> String {
+    if results.is_empty() {
+        return format!("No past conversations matched \"{query}\".");
+    }
+
+    let mut output = format!(
+        "## Past conversations about \"{query}\" ({} results)\n\n",
+        results.len()
+    );
+
+    for result in results {
+        let channel = result
+            .channel_name
+            .as_deref()
+            .unwrap_or(result.channel_id.as_str());
+        output.push_str(&format!(
+            "### #{channel} — {} to {} (similarity {:.2})\nchannel ID: `{}`\n\n{}\n\n",
+            result.started_at,
+            result.ended_at,
+            result.similarity,
+            result.channel_id,
+            result.transcript,
+        ));
+    }
+
+    output
+}
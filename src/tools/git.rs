@@ -0,0 +1,570 @@
+//! Git tools for workers: `git_status`, `git_diff`, `git_commit`, `git_branch`.
+//!
+//! These wrap the `git` CLI with structured arguments so workers don't have to
+//! assemble shell strings. Every command runs through the worker's `Sandbox`
+//! (inside the container for container workers) with the same working
+//! directory rules as the shell tool. Guardrails: nothing here pushes, commits
+//! to or deletes a protected branch (`[defaults.git] protected_branches`), or
+//! force-deletes a branch.
+
+use crate::config::RuntimeConfig;
+use crate::sandbox::Sandbox;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+
+/// Upper bound on a single git invocation.
+const GIT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Shared context for the git tools: where commands run and which branches
+/// are protected.
+#[derive(Debug, Clone)]
+pub struct GitContext {
+    workspace: PathBuf,
+    sandbox: Arc<Sandbox>,
+    runtime_config: Arc<RuntimeConfig>,
+}
+
+impl GitContext {
+    pub fn new(
+        workspace: PathBuf,
+        sandbox: Arc<Sandbox>,
+        runtime_config: Arc<RuntimeConfig>,
+    ) -> Self {
+        Self {
+            workspace,
+            sandbox,
+            runtime_config,
+        }
+    }
+
+    /// Resolve `working_dir` the same way the shell tool does: relative to
+    /// the workspace, and confined to allowed paths when sandboxing is on.
+    fn resolve_dir(&self, working_dir: Option<&str>) -> Result<PathBuf, GitError> {
+        let Some(dir) = working_dir else {
+            return Ok(self.workspace.clone());
+        };
+        let raw_path = Path::new(dir);
+        let resolved = if raw_path.is_absolute() {
+            raw_path.to_path_buf()
+        } else {
+            self.workspace.join(raw_path)
+        };
+        let canonical = resolved.canonicalize().unwrap_or(resolved);
+
+        if self.sandbox.mode_enabled() && !self.sandbox.is_path_allowed(&canonical) {
+            return Err(GitError(format!(
+                "working_dir must be within the workspace ({}) or an allowed project path.",
+                self.workspace.display()
+            )));
+        }
+        Ok(canonical)
+    }
+
+    /// Run `git <args>` in `dir`. Non-zero exits are returned, not raised.
+    async fn run(&self, dir: &Path, args: &[&str]) -> Result<GitRun, GitError> {
+        let mut full_args = vec!["--no-pager", "-c", "color.ui=false"];
+        full_args.extend_from_slice(args);
+
+        // Never block on a credential or editor prompt.
+        let env = HashMap::from([
+            ("GIT_TERMINAL_PROMPT".to_string(), "0".to_string()),
+            ("GIT_EDITOR".to_string(), "true".to_string()),
+        ]);
+
+        let mut cmd = self.sandbox.wrap("git", &full_args, dir, &env);
+        cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+        let output = tokio::time::timeout(GIT_TIMEOUT, cmd.output())
+            .await
+            .map_err(|_| GitError(format!("git {} timed out", args.join(" "))))?
+            .map_err(|error| GitError(format!("failed to run git: {error}")))?;
+
+        Ok(GitRun {
+            exit_code: output.status.code().unwrap_or(-1),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        })
+    }
+
+    /// Name of the checked-out branch, or `None` on a detached HEAD.
+    async fn current_branch(&self, dir: &Path) -> Result<Option<String>, GitError> {
+        let run = self
+            .run(dir, &["symbolic-ref", "--quiet", "--short", "HEAD"])
+            .await?;
+        if run.exit_code != 0 {
+            return Ok(None);
+        }
+        Ok(Some(run.stdout.trim().to_string()))
+    }
+
+    fn ensure_unprotected(&self, branch: &str, action: &str) -> Result<(), GitError> {
+        if self.runtime_config.git.load().is_protected(branch) {
+            return Err(GitError(format!(
+                "'{branch}' is a protected branch; workers may not {action} it. \
+                 Create a feature branch with git_branch and work there."
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Raw result of one git invocation.
+#[derive(Debug)]
+struct GitRun {
+    exit_code: i32,
+    stdout: String,
+    stderr: String,
+}
+
+impl GitRun {
+    fn into_output(self, branch: Option<String>, commit: Option<String>) -> GitOutput {
+        let combined = match (self.stdout.trim().is_empty(), self.stderr.trim().is_empty()) {
+            (false, false) => format!("{}\n{}", self.stdout.trim_end(), self.stderr.trim_end()),
+            (false, true) => self.stdout.trim_end().to_string(),
+            (true, false) => self.stderr.trim_end().to_string(),
+            (true, true) => "[No output]".to_string(),
+        };
+        GitOutput {
+            success: self.exit_code == 0,
+            exit_code: self.exit_code,
+            branch,
+            commit,
+            output: crate::tools::truncate_output(&combined, crate::tools::MAX_TOOL_OUTPUT_BYTES),
+        }
+    }
+}
+
+/// Error type for git tools.
+#[derive(Debug, thiserror::Error)]
+#[error("Git operation failed: {0}")]
+pub struct GitError(String);
+
+/// Output shared by all git tools.
+#[derive(Debug, Serialize)]
+pub struct GitOutput {
+    /// Whether git exited successfully.
+    pub success: bool,
+    /// git's exit code.
+    pub exit_code: i32,
+    /// Checked-out branch after the operation, when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Commit hash created by `git_commit`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Combined stdout and stderr.
+    pub output: String,
+}
+
+/// Reject names that git would parse as options.
+fn validate_ref_name(name: &str) -> Result<(), GitError> {
+    if name.is_empty() || name.starts_with('-') || name.chars().any(char::is_whitespace) {
+        return Err(GitError(format!("invalid branch name '{name}'")));
+    }
+    Ok(())
+}
+
+// git_status
+
+/// Tool that shows the working tree status.
+#[derive(Debug, Clone)]
+pub struct GitStatusTool {
+    context: GitContext,
+}
+
+/// Arguments for git_status.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GitStatusArgs {
+    /// Repository directory (defaults to the workspace).
+    pub working_dir: Option<String>,
+}
+
+impl Tool for GitStatusTool {
+    const NAME: &'static str = "git_status";
+
+    type Error = GitError;
+    type Args = GitStatusArgs;
+    type Output = GitOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/git_status").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "working_dir": {
+                        "type": "string",
+                        "description": "Repository directory. Defaults to the workspace."
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let dir = self.context.resolve_dir(args.working_dir.as_deref())?;
+        let branch = self.context.current_branch(&dir).await?;
+        let run = self
+            .context
+            .run(&dir, &["status", "--short", "--branch"])
+            .await?;
+        Ok(run.into_output(branch, None))
+    }
+}
+
+// git_diff
+
+/// Tool that shows unstaged or staged changes.
+#[derive(Debug, Clone)]
+pub struct GitDiffTool {
+    context: GitContext,
+}
+
+/// Arguments for git_diff.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GitDiffArgs {
+    /// Repository directory (defaults to the workspace).
+    pub working_dir: Option<String>,
+    /// Show staged changes instead of unstaged ones.
+    #[serde(default)]
+    pub staged: bool,
+    /// Show only a per-file summary (`--stat`).
+    #[serde(default)]
+    pub stat: bool,
+    /// Limit the diff to these paths.
+    #[serde(default)]
+    pub paths: Vec<String>,
+}
+
+impl Tool for GitDiffTool {
+    const NAME: &'static str = "git_diff";
+
+    type Error = GitError;
+    type Args = GitDiffArgs;
+    type Output = GitOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/git_diff").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "working_dir": {
+                        "type": "string",
+                        "description": "Repository directory. Defaults to the workspace."
+                    },
+                    "staged": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Show staged changes instead of unstaged ones"
+                    },
+                    "stat": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Only show a per-file summary of changes"
+                    },
+                    "paths": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Limit the diff to these paths"
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let dir = self.context.resolve_dir(args.working_dir.as_deref())?;
+        let git_args = diff_args(args.staged, args.stat, &args.paths);
+        let git_args: Vec<&str> = git_args.iter().map(String::as_str).collect();
+        let run = self.context.run(&dir, &git_args).await?;
+        Ok(run.into_output(None, None))
+    }
+}
+
+fn diff_args(staged: bool, stat: bool, paths: &[String]) -> Vec<String> {
+    let mut args = vec!["diff".to_string()];
+    if staged {
+        args.push("--cached".to_string());
+    }
+    if stat {
+        args.push("--stat".to_string());
+    }
+    args.push("--".to_string());
+    args.extend(paths.iter().cloned());
+    args
+}
+
+// git_commit
+
+/// Tool that stages changes and creates a commit.
+#[derive(Debug, Clone)]
+pub struct GitCommitTool {
+    context: GitContext,
+}
+
+/// Arguments for git_commit.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GitCommitArgs {
+    /// Repository directory (defaults to the workspace).
+    pub working_dir: Option<String>,
+    /// The commit message.
+    pub message: String,
+    /// Paths to stage before committing.
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Stage every change (including new and deleted files) before committing.
+    #[serde(default)]
+    pub all: bool,
+}
+
+impl Tool for GitCommitTool {
+    const NAME: &'static str = "git_commit";
+
+    type Error = GitError;
+    type Args = GitCommitArgs;
+    type Output = GitOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/git_commit").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "working_dir": {
+                        "type": "string",
+                        "description": "Repository directory. Defaults to the workspace."
+                    },
+                    "message": {
+                        "type": "string",
+                        "description": "The commit message"
+                    },
+                    "paths": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Paths to stage before committing"
+                    },
+                    "all": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Stage every change, including new and deleted files"
+                    }
+                },
+                "required": ["message"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        if args.message.trim().is_empty() {
+            return Err(GitError("commit message cannot be empty".to_string()));
+        }
+        let dir = self.context.resolve_dir(args.working_dir.as_deref())?;
+
+        let Some(branch) = self.context.current_branch(&dir).await? else {
+            return Err(GitError(
+                "HEAD is detached; switch to a branch before committing".to_string(),
+            ));
+        };
+        self.context.ensure_unprotected(&branch, "commit to")?;
+
+        if args.all {
+            let run = self.context.run(&dir, &["add", "--all"]).await?;
+            if run.exit_code != 0 {
+                return Ok(run.into_output(Some(branch), None));
+            }
+        } else if !args.paths.is_empty() {
+            let mut add_args = vec!["add", "--"];
+            add_args.extend(args.paths.iter().map(String::as_str));
+            let run = self.context.run(&dir, &add_args).await?;
+            if run.exit_code != 0 {
+                return Ok(run.into_output(Some(branch), None));
+            }
+        }
+
+        let run = self
+            .context
+            .run(&dir, &["commit", "--message", &args.message])
+            .await?;
+        if run.exit_code != 0 {
+            return Ok(run.into_output(Some(branch), None));
+        }
+
+        let head = self.context.run(&dir, &["rev-parse", "HEAD"]).await?;
+        let commit = (head.exit_code == 0).then(|| head.stdout.trim().to_string());
+        Ok(run.into_output(Some(branch), commit))
+    }
+}
+
+// git_branch
+
+/// Tool that lists, creates, switches, and deletes branches.
+#[derive(Debug, Clone)]
+pub struct GitBranchTool {
+    context: GitContext,
+}
+
+/// What git_branch should do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GitBranchAction {
+    List,
+    Create,
+    Switch,
+    Delete,
+}
+
+/// Arguments for git_branch.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GitBranchArgs {
+    /// Repository directory (defaults to the workspace).
+    pub working_dir: Option<String>,
+    /// The operation to perform.
+    pub action: GitBranchAction,
+    /// Branch name (required for everything except `list`).
+    pub name: Option<String>,
+    /// Commit or branch to start a new branch from (`create` only).
+    pub start_point: Option<String>,
+}
+
+impl Tool for GitBranchTool {
+    const NAME: &'static str = "git_branch";
+
+    type Error = GitError;
+    type Args = GitBranchArgs;
+    type Output = GitOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/git_branch").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "working_dir": {
+                        "type": "string",
+                        "description": "Repository directory. Defaults to the workspace."
+                    },
+                    "action": {
+                        "type": "string",
+                        "enum": ["list", "create", "switch", "delete"],
+                        "description": "list: show local branches. create: create a branch and switch to it. switch: check out an existing branch. delete: delete a fully merged branch."
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "Branch name. Required for create, switch, and delete."
+                    },
+                    "start_point": {
+                        "type": "string",
+                        "description": "For create: commit or branch to start from. Defaults to HEAD."
+                    }
+                },
+                "required": ["action"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let dir = self.context.resolve_dir(args.working_dir.as_deref())?;
+
+        if args.action == GitBranchAction::List {
+            let branch = self.context.current_branch(&dir).await?;
+            let run = self.context.run(&dir, &["branch", "--list", "-vv"]).await?;
+            return Ok(run.into_output(branch, None));
+        }
+
+        let name = args
+            .name
+            .as_deref()
+            .ok_or_else(|| GitError("`name` is required for this action".to_string()))?;
+        validate_ref_name(name)?;
+        if let Some(start_point) = args.start_point.as_deref() {
+            validate_ref_name(start_point)?;
+        }
+
+        let run = match args.action {
+            GitBranchAction::List => unreachable!("handled above"),
+            GitBranchAction::Create => {
+                let mut git_args = vec!["switch", "--create", name];
+                if let Some(start_point) = args.start_point.as_deref() {
+                    git_args.push(start_point);
+                }
+                self.context.run(&dir, &git_args).await?
+            }
+            GitBranchAction::Switch => self.context.run(&dir, &["switch", name]).await?,
+            GitBranchAction::Delete => {
+                self.context.ensure_unprotected(name, "delete")?;
+                // `-d` (not `-D`) so unmerged work is never thrown away.
+                self.context.run(&dir, &["branch", "-d", name]).await?
+            }
+        };
+
+        let branch = self.context.current_branch(&dir).await?;
+        Ok(run.into_output(branch, None))
+    }
+}
+
+// Tool registration helper
+
+/// Register the git tools on a `ToolServer`, sharing one `GitContext`.
+pub fn register_git_tools(
+    server: rig::tool::server::ToolServer,
+    workspace: PathBuf,
+    sandbox: Arc<Sandbox>,
+    runtime_config: Arc<RuntimeConfig>,
+) -> rig::tool::server::ToolServer {
+    let context = GitContext::new(workspace, sandbox, runtime_config);
+
+    server
+        .tool(GitStatusTool {
+            context: context.clone(),
+        })
+        .tool(GitDiffTool {
+            context: context.clone(),
+        })
+        .tool(GitCommitTool {
+            context: context.clone(),
+        })
+        .tool(GitBranchTool { context })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_args_put_paths_after_separator() {
+        assert_eq!(diff_args(false, false, &[]), ["diff", "--"]);
+        assert_eq!(
+            diff_args(true, true, &["-rf".to_string(), "src/lib.rs".to_string()]),
+            ["diff", "--cached", "--stat", "--", "-rf", "src/lib.rs"]
+        );
+    }
+
+    #[test]
+    fn ref_names_cannot_smuggle_options() {
+        assert!(validate_ref_name("feature/login").is_ok());
+        assert!(validate_ref_name("--force").is_err());
+        assert!(validate_ref_name("-D").is_err());
+        assert!(validate_ref_name("a b").is_err());
+        assert!(validate_ref_name("").is_err());
+    }
+
+    #[test]
+    fn protected_branches_match_exactly() {
+        let config = crate::config::GitConfig::default();
+        assert!(config.is_protected("main"));
+        assert!(config.is_protected("master"));
+        assert!(!config.is_protected("main-fix"));
+    }
+}
@@ -3,7 +3,7 @@
 //! Soft-deletes a memory by setting its `forgotten` flag. The memory stays in
 //! the database but is excluded from all search and recall operations.
 
-use crate::memory::MemorySearch;
+use crate::memory::{MemorySearch, MemoryVisibility};
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
 use schemars::JsonSchema;
@@ -14,12 +14,22 @@ use std::sync::Arc;
 #[derive(Debug, Clone)]
 pub struct MemoryDeleteTool {
     memory_search: Arc<MemorySearch>,
+    visibility: MemoryVisibility,
 }
 
 impl MemoryDeleteTool {
     /// Create a new memory delete tool.
     pub fn new(memory_search: Arc<MemorySearch>) -> Self {
-        Self { memory_search }
+        Self {
+            memory_search,
+            visibility: MemoryVisibility::All,
+        }
+    }
+
+    /// Only allow forgetting memories `visibility` allows.
+    pub fn with_visibility(mut self, visibility: MemoryVisibility) -> Self {
+        self.visibility = visibility;
+        self
     }
 }
 
@@ -83,7 +93,7 @@ impl Tool for MemoryDeleteTool {
             .await
             .map_err(|e| MemoryDeleteError(format!("Failed to look up memory: {e}")))?;
 
-        let Some(memory) = memory else {
+        let Some(memory) = memory.filter(|memory| self.visibility.allows(memory)) else {
             return Ok(MemoryDeleteOutput {
                 forgotten: false,
                 message: format!("No memory found with ID: {}", args.memory_id),
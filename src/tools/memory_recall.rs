@@ -1,9 +1,9 @@
 //! Memory recall tool for branches.
 
 use crate::error::Result;
-use crate::memory::MemorySearch;
 use crate::memory::search::{SearchConfig, SearchMode, SearchSort, curate_results};
-use crate::memory::types::Memory;
+use crate::memory::types::{Memory, ScoreExplanation};
+use crate::memory::{MemorySearch, MemoryVisibility};
 
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
@@ -16,12 +16,22 @@ use std::sync::Arc;
 #[derive(Debug, Clone)]
 pub struct MemoryRecallTool {
     memory_search: Arc<MemorySearch>,
+    visibility: MemoryVisibility,
 }
 
 impl MemoryRecallTool {
     /// Create a new memory recall tool.
     pub fn new(memory_search: Arc<MemorySearch>) -> Self {
-        Self { memory_search }
+        Self {
+            memory_search,
+            visibility: MemoryVisibility::All,
+        }
+    }
+
+    /// Only return memories `visibility` allows.
+    pub fn with_visibility(mut self, visibility: MemoryVisibility) -> Self {
+        self.visibility = visibility;
+        self
     }
 }
 
@@ -53,6 +63,9 @@ pub struct MemoryRecallArgs {
     /// Sort order for non-hybrid modes: "recent" (default), "importance", "most_accessed".
     #[serde(default)]
     pub sort_by: Option<String>,
+    /// Include a per-source score breakdown for each result (hybrid mode only).
+    #[serde(default)]
+    pub explain: bool,
 }
 
 fn default_max_results() -> usize {
@@ -130,6 +143,9 @@ pub struct MemoryOutput {
     pub created_at: String,
     /// The relevance score from the search.
     pub relevance_score: f32,
+    /// How the relevance score was computed, when `explain` was requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<ScoreExplanation>,
 }
 
 impl Tool for MemoryRecallTool {
@@ -176,6 +192,11 @@ impl Tool for MemoryRecallTool {
                         "enum": ["recent", "importance", "most_accessed"],
                         "default": "recent",
                         "description": "Sort order for non-hybrid modes. Default: recent."
+                    },
+                    "explain": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Hybrid mode only. Show each result's vector, keyword, and graph rank and how much each contributed to its relevance score."
                     }
                 }
             }),
@@ -217,6 +238,8 @@ impl Tool for MemoryRecallTool {
             sort_by,
             max_results: args.max_results,
             max_results_per_source: args.max_results * 2,
+            explain: args.explain,
+            visibility: self.visibility.clone(),
             ..Default::default()
         };
 
@@ -248,6 +271,7 @@ impl Tool for MemoryRecallTool {
                 importance: result.memory.importance,
                 created_at: result.memory.created_at.to_rfc3339(),
                 relevance_score: result.score,
+                explanation: result.explanation.clone(),
             });
         }
 
@@ -287,18 +311,42 @@ pub fn format_memories(memories: &[MemoryOutput]) -> String {
     for (i, memory) in memories.iter().enumerate() {
         let preview = memory.content.lines().next().unwrap_or(&memory.content);
         output.push_str(&format!(
-            "{}. [{}] (importance: {:.2}, relevance: {:.2})\n   {}\n\n",
+            "{}. [{}] (importance: {:.2}, relevance: {:.2})\n   {}\n",
             i + 1,
             memory.memory_type,
             memory.importance,
             memory.relevance_score,
             preview
         ));
+        if let Some(explanation) = &memory.explanation {
+            output.push_str(&format!("   why: {}\n", format_explanation(explanation)));
+        }
+        output.push('\n');
     }
 
     output
 }
 
+/// One-line summary of a score breakdown, e.g.
+/// `vector #2 (0.81, +0.0161) · fts #1 (3.20, +0.0164) · graph — · k=60, min 0.00`.
+fn format_explanation(explanation: &ScoreExplanation) -> String {
+    let source = |name: &str, score: &Option<crate::memory::types::SourceScore>| match score {
+        Some(score) => format!(
+            "{name} #{} ({:.2}, +{:.4})",
+            score.rank, score.score, score.contribution
+        ),
+        None => format!("{name} —"),
+    };
+    format!(
+        "{} · {} · {} · k={}, min {:.2}",
+        source("vector", &explanation.vector),
+        source("fts", &explanation.fts),
+        source("graph", &explanation.graph),
+        explanation.rrf_k,
+        explanation.min_score
+    )
+}
+
 /// Legacy convenience function for direct memory recall.
 pub async fn memory_recall(
     memory_search: Arc<MemorySearch>,
@@ -312,6 +360,7 @@ pub async fn memory_recall(
         memory_type: None,
         mode: None,
         sort_by: None,
+        explain: false,
     };
 
     let output = tool
@@ -385,4 +434,24 @@ mod tests {
     fn test_parse_memory_type_invalid() {
         assert!(parse_memory_type("invalid").is_err());
     }
+
+    #[test]
+    fn test_format_explanation_marks_missing_sources() {
+        use crate::memory::types::SourceScore;
+        let explanation = ScoreExplanation {
+            vector: Some(SourceScore {
+                rank: 2,
+                score: 0.81,
+                contribution: 1.0 / 62.0,
+            }),
+            fts: None,
+            graph: None,
+            rrf_k: 60.0,
+            min_score: 0.0,
+        };
+        assert_eq!(
+            format_explanation(&explanation),
+            "vector #2 (0.81, +0.0161) · fts — · graph — · k=60, min 0.00"
+        );
+    }
 }
@@ -12,7 +12,7 @@ use std::sync::Arc;
 
 /// Maximum allowed memory content length (bytes). Prevents oversized memories
 /// from bloating the database and embedding index.
-const MAX_MEMORY_CONTENT_BYTES: usize = 50_000;
+pub(crate) const MAX_MEMORY_CONTENT_BYTES: usize = 50_000;
 
 /// Tool for saving memories to the store.
 #[derive(Debug, Clone)]
@@ -20,6 +20,8 @@ pub struct MemorySaveTool {
     memory_search: Arc<MemorySearch>,
     event_context: Option<MemorySaveEventContext>,
     contract_state: Option<Arc<super::memory_persistence_complete::MemoryPersistenceContractState>>,
+    /// When set, every saved memory is scoped to this channel.
+    scope_channel: Option<crate::ChannelId>,
 }
 
 #[derive(Debug, Clone)]
@@ -35,6 +37,7 @@ impl MemorySaveTool {
             memory_search,
             event_context: None,
             contract_state: None,
+            scope_channel: None,
         }
     }
 
@@ -51,6 +54,13 @@ impl MemorySaveTool {
         self
     }
 
+    /// Scope every saved memory to `channel_id`, so other channels and the
+    /// bulletin never see it.
+    pub fn scoped_to_channel(mut self, channel_id: crate::ChannelId) -> Self {
+        self.scope_channel = Some(channel_id);
+        self
+    }
+
     pub fn with_contract_state(
         mut self,
         contract_state: Arc<super::memory_persistence_complete::MemoryPersistenceContractState>,
@@ -240,7 +250,9 @@ impl Tool for MemorySaveTool {
             memory = memory.with_source(source);
         }
 
-        if let Some(channel_id) = args.channel_id {
+        if let Some(channel_id) = &self.scope_channel {
+            memory = memory.scoped_to_channel(channel_id.clone());
+        } else if let Some(channel_id) = args.channel_id {
             memory = memory.with_channel_id(Arc::from(channel_id.as_str()));
         }
 
@@ -0,0 +1,168 @@
+//! Message worker tool: lets a worker send a message to another worker in the
+//! same channel through the channel's worker mailbox.
+
+use crate::WorkerId;
+use crate::agent::worker_mailbox::{Delivery, WorkerMailbox};
+use crate::config::RuntimeConfig;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Max messages one worker may send over its lifetime. Bounds runaway
+/// ping-pong between two workers.
+const MAX_MESSAGES_PER_WORKER: usize = 50;
+
+/// Tool for messaging a sibling worker.
+#[derive(Debug, Clone)]
+pub struct MessageWorkerTool {
+    mailbox: WorkerMailbox,
+    worker_id: WorkerId,
+    runtime_config: Arc<RuntimeConfig>,
+    sent: Arc<AtomicUsize>,
+}
+
+impl MessageWorkerTool {
+    pub fn new(
+        mailbox: WorkerMailbox,
+        worker_id: WorkerId,
+        runtime_config: Arc<RuntimeConfig>,
+    ) -> Self {
+        Self {
+            mailbox,
+            worker_id,
+            runtime_config,
+            sent: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+/// Error type for message worker tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Message worker failed: {0}")]
+pub struct MessageWorkerError(String);
+
+/// Arguments for message worker tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MessageWorkerArgs {
+    /// The ID of the worker to message (UUID format).
+    pub worker_id: String,
+    /// The message to send.
+    pub message: String,
+}
+
+/// Output from message worker tool.
+#[derive(Debug, Serialize)]
+pub struct MessageWorkerOutput {
+    /// Whether the message was delivered.
+    pub delivered: bool,
+    /// The recipient worker ID.
+    pub worker_id: WorkerId,
+    /// Status message.
+    pub message: String,
+}
+
+impl Tool for MessageWorkerTool {
+    const NAME: &'static str = "message_worker";
+
+    type Error = MessageWorkerError;
+    type Args = MessageWorkerArgs;
+    type Output = MessageWorkerOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        let peers = self.mailbox.peers(Some(self.worker_id)).await;
+        let peer_list = if peers.is_empty() {
+            "No other workers are running in this channel right now.".to_string()
+        } else {
+            peers
+                .iter()
+                .map(|peer| format!("- {}: {} ({})", peer.id, peer.task, peer.status))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let description = crate::prompts::text::get("tools/message_worker")
+            .replace("{worker_id}", &self.worker_id.to_string())
+            .replace("{peers}", &peer_list);
+
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description,
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "worker_id": {
+                        "type": "string",
+                        "description": "The ID of the worker to message (another worker in this channel)"
+                    },
+                    "message": {
+                        "type": "string",
+                        "description": "The message to send. Include everything the recipient needs — it can't see your history."
+                    }
+                },
+                "required": ["worker_id", "message"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let worker_id = args
+            .worker_id
+            .parse::<WorkerId>()
+            .map_err(|error| MessageWorkerError(format!("Invalid worker ID: {error}")))?;
+        if worker_id == self.worker_id {
+            return Err(MessageWorkerError("a worker can't message itself".into()));
+        }
+        if self.sent.fetch_add(1, Ordering::Relaxed) >= MAX_MESSAGES_PER_WORKER {
+            return Err(MessageWorkerError(format!(
+                "message limit reached ({MAX_MESSAGES_PER_WORKER} per worker)"
+            )));
+        }
+
+        let framed = self
+            .runtime_config
+            .prompts
+            .load()
+            .render_worker_message(&self.worker_id.to_string(), &args.message)
+            .map_err(|error| MessageWorkerError(error.to_string()))?;
+        let delivery = self
+            .mailbox
+            .deliver(worker_id, framed)
+            .await
+            .map_err(|error| MessageWorkerError(error.to_string()))?;
+
+        tracing::info!(
+            from_worker_id = %self.worker_id,
+            to_worker_id = %worker_id,
+            channel_id = %self.mailbox.channel_id(),
+            ?delivery,
+            "worker message delivered"
+        );
+
+        let (delivered, message) = match delivery {
+            Delivery::Input => (
+                true,
+                format!("Message delivered to idle worker {worker_id}; it will start on it now."),
+            ),
+            Delivery::Injected => (
+                true,
+                format!(
+                    "Message delivered to running worker {worker_id}; it will see it at its next turn."
+                ),
+            ),
+            Delivery::Busy => (
+                false,
+                format!(
+                    "Worker {worker_id} is busy and can't take messages until it is idle. Try again later."
+                ),
+            ),
+        };
+
+        Ok(MessageWorkerOutput {
+            delivered,
+            worker_id,
+            message,
+        })
+    }
+}
@@ -0,0 +1,234 @@
+//! User profile tool for branches.
+//!
+//! Reads and updates the long-term profile of someone in the conversation:
+//! their timezone and the preferences, roles, and facts worth knowing the
+//! next time they speak.
+
+use crate::conversation::ChannelStore;
+use crate::memory::profiles::{ProfileFactKind, UserProfile, UserProfileStore, channel_platform};
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Tool for viewing and updating user profiles on the channel's platform.
+#[derive(Debug, Clone)]
+pub struct ProfileUpdateTool {
+    store: UserProfileStore,
+    channel_store: ChannelStore,
+    channel_id: String,
+    platform: String,
+}
+
+impl ProfileUpdateTool {
+    /// Create a profile tool scoped to the platform a channel is on.
+    pub fn new(store: UserProfileStore, channel_store: ChannelStore, channel_id: &str) -> Self {
+        Self {
+            store,
+            channel_store,
+            channel_id: channel_id.to_string(),
+            platform: channel_platform(channel_id).to_string(),
+        }
+    }
+}
+
+/// Error type for profile update tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Profile update failed: {0}")]
+pub struct ProfileUpdateError(String);
+
+/// A fact to add to a profile.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ProfileFactInput {
+    /// preference, role, or fact.
+    pub kind: String,
+    /// Short statement about the person.
+    pub content: String,
+}
+
+/// Arguments for profile update tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ProfileUpdateArgs {
+    /// The person's display name as shown in the conversation, or their
+    /// platform user ID.
+    pub user: String,
+    /// IANA timezone (e.g. "Europe/Berlin"). An empty string clears it.
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Facts to add.
+    #[serde(default)]
+    pub add: Vec<ProfileFactInput>,
+    /// IDs of facts to remove because they are wrong or outdated.
+    #[serde(default)]
+    pub remove: Vec<String>,
+}
+
+/// Output from profile update tool.
+#[derive(Debug, Serialize)]
+pub struct ProfileUpdateOutput {
+    pub profile_id: String,
+    /// The profile after the update.
+    pub summary: String,
+}
+
+impl Tool for ProfileUpdateTool {
+    const NAME: &'static str = "profile_update";
+
+    type Error = ProfileUpdateError;
+    type Args = ProfileUpdateArgs;
+    type Output = ProfileUpdateOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/profile_update").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "user": {
+                        "type": "string",
+                        "description": "The person's display name as it appears in the conversation, or their platform user ID"
+                    },
+                    "timezone": {
+                        "type": "string",
+                        "description": "IANA timezone such as \"Europe/Berlin\". Pass an empty string to clear it."
+                    },
+                    "add": {
+                        "type": "array",
+                        "description": "Facts to add to the profile",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "kind": {
+                                    "type": "string",
+                                    "enum": ["preference", "role", "fact"]
+                                },
+                                "content": {
+                                    "type": "string",
+                                    "description": "One short statement, e.g. \"prefers answers in bullet points\""
+                                }
+                            },
+                            "required": ["kind", "content"]
+                        }
+                    },
+                    "remove": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "IDs of facts that are wrong or outdated"
+                    }
+                },
+                "required": ["user"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> std::result::Result<Self::Output, Self::Error> {
+        let matches = self
+            .store
+            .find(&self.platform, &args.user)
+            .await
+            .map_err(|e| ProfileUpdateError(format!("failed to look up user: {e}")))?;
+
+        let profile = match matches.as_slice() {
+            [profile] => profile,
+            [] => {
+                return Err(ProfileUpdateError(format!(
+                    "no one named \"{}\" has spoken on {}",
+                    args.user, self.platform
+                )));
+            }
+            _ => {
+                let ids: Vec<&str> = matches
+                    .iter()
+                    .map(|profile| profile.sender_id.as_str())
+                    .collect();
+                return Err(ProfileUpdateError(format!(
+                    "several people are named \"{}\"; pass one of these user IDs instead: {}",
+                    args.user,
+                    ids.join(", ")
+                )));
+            }
+        };
+
+        if let Some(timezone) = args.timezone.as_deref().map(str::trim) {
+            let timezone = if timezone.is_empty() {
+                None
+            } else {
+                timezone.parse::<chrono_tz::Tz>().map_err(|_| {
+                    ProfileUpdateError(format!("\"{timezone}\" is not an IANA timezone"))
+                })?;
+                Some(timezone)
+            };
+            self.store
+                .set_timezone(&profile.id, timezone)
+                .await
+                .map_err(|e| ProfileUpdateError(format!("failed to set timezone: {e}")))?;
+            // Someone saying where they are is the best hint at which clock
+            // the channel runs on.
+            if let Some(timezone) = timezone {
+                self.channel_store
+                    .set_inferred_timezone(&self.channel_id, timezone);
+            }
+        }
+
+        for fact in &args.add {
+            let kind = ProfileFactKind::parse(&fact.kind).ok_or_else(|| {
+                ProfileUpdateError(format!(
+                    "unknown fact kind \"{}\", expected preference, role, or fact",
+                    fact.kind
+                ))
+            })?;
+            if fact.content.trim().is_empty() {
+                continue;
+            }
+            self.store
+                .add_fact(&profile.id, kind, &fact.content)
+                .await
+                .map_err(|e| ProfileUpdateError(format!("failed to add fact: {e}")))?;
+        }
+
+        for fact_id in &args.remove {
+            self.store
+                .delete_fact(&profile.id, fact_id)
+                .await
+                .map_err(|e| ProfileUpdateError(format!("failed to remove fact: {e}")))?;
+        }
+
+        let updated = self
+            .store
+            .get(&profile.id)
+            .await
+            .map_err(|e| ProfileUpdateError(format!("failed to reload profile: {e}")))?
+            .ok_or_else(|| ProfileUpdateError("profile was deleted".into()))?;
+
+        Ok(ProfileUpdateOutput {
+            profile_id: updated.id.clone(),
+            summary: format_profile(&updated),
+        })
+    }
+}
+
+fn format_profile(profile: &UserProfile) -> String {
+    let name = profile
+        .display_name
+        .as_deref()
+        .unwrap_or(&profile.sender_id);
+    let mut output = format!("## Profile of {name} (`{}`)\n\n", profile.sender_id);
+    output.push_str(&format!(
+        "Timezone: {}\n",
+        profile.timezone.as_deref().unwrap_or("unknown")
+    ));
+    if profile.facts.is_empty() {
+        output.push_str("No facts yet.\n");
+    }
+    for fact in &profile.facts {
+        output.push_str(&format!(
+            "- [{}] {} (id: `{}`)\n",
+            fact.kind.as_str(),
+            fact.content,
+            fact.id
+        ));
+    }
+    output
+}
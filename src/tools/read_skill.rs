@@ -39,7 +39,7 @@ pub struct ReadSkillArgs {
 /// Output from read_skill tool.
 #[derive(Debug, Serialize)]
 pub struct ReadSkillOutput {
-    /// The full skill instructions.
+    /// The full skill instructions, preceded by any required skills.
     pub content: String,
 }
 
@@ -72,14 +72,17 @@ impl Tool for ReadSkillTool {
 
     async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
         let skills = self.runtime_config.skills.load();
-        match skills.get(&args.name) {
-            Some(skill) => Ok(ReadSkillOutput {
-                content: skill.content.clone(),
-            }),
-            None => Err(ReadSkillError(format!(
+        if skills.get(&args.name).is_none() {
+            return Err(ReadSkillError(format!(
                 "skill '{}' not found. Available skills are listed in <available_skills> in your system prompt.",
                 args.name
-            ))),
+            )));
         }
+
+        // Required skills come first, in dependency order.
+        skills
+            .render_skill(&args.name)
+            .map(|content| ReadSkillOutput { content })
+            .map_err(|error| ReadSkillError(error.to_string()))
     }
 }
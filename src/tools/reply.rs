@@ -1,5 +1,6 @@
 //! Reply tool for sending messages to users (channel only).
 
+use crate::config::ResponsePace;
 use crate::conversation::ConversationLogger;
 
 use crate::{ChannelId, OutboundResponse, RoutedSender};
@@ -9,9 +10,11 @@ use rig::tool::Tool;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::LazyLock;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 static BROKEN_DISCORD_MENTION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"<{2,}@(!?)>\s*(\d{15,22})>").expect("hardcoded broken mention regex")
@@ -45,10 +48,16 @@ pub struct ReplyTool {
     channel_id: ChannelId,
     replied_flag: RepliedFlag,
     agent_display_name: String,
+    /// Directories attachments may be read from. Relative attachment paths
+    /// resolve against the first entry (the workspace).
+    attachment_roots: Vec<PathBuf>,
+    /// How plain-text replies are paced in this channel.
+    pace: ResponsePace,
 }
 
 impl ReplyTool {
     /// Create a new reply tool bound to a conversation's response channel.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         response_tx: RoutedSender,
         conversation_id: impl Into<String>,
@@ -56,6 +65,8 @@ impl ReplyTool {
         channel_id: ChannelId,
         replied_flag: RepliedFlag,
         agent_display_name: impl Into<String>,
+        attachment_roots: Vec<PathBuf>,
+        pace: ResponsePace,
     ) -> Self {
         Self {
             response_tx,
@@ -64,8 +75,39 @@ impl ReplyTool {
             channel_id,
             replied_flag,
             agent_display_name: agent_display_name.into(),
+            attachment_roots,
+            pace,
         }
     }
+
+    /// Send a plain-text reply as paced chunks, showing typing before each
+    /// one. Returns the platform ID of the last message sent.
+    async fn send_paced(&self, text: &str) -> Result<Option<String>, ReplyError> {
+        let mut message_id = None;
+        for chunk in crate::agent::channel_pacing::plan(text, self.pace) {
+            if !chunk.typing.is_zero() {
+                self.response_tx
+                    .send(OutboundResponse::Status(crate::StatusUpdate::Thinking))
+                    .await
+                    .ok();
+                tokio::time::sleep(chunk.typing).await;
+            }
+            let delivery = self
+                .response_tx
+                .send_tracked(OutboundResponse::Text(chunk.text))
+                .await
+                .map_err(|e| ReplyError(format!("failed to send reply: {e}")))?;
+            match await_delivery(delivery).await {
+                Ok(id) => message_id = id.or(message_id),
+                Err(error) => tracing::warn!(
+                    conversation_id = %self.conversation_id,
+                    %error,
+                    "reply delivery not confirmed"
+                ),
+            }
+        }
+        Ok(message_id)
+    }
 }
 
 /// Error type for reply tool.
@@ -95,6 +137,15 @@ pub struct ReplyArgs {
     /// Optional: a poll to attach to the message.
     #[serde(default)]
     pub poll: Option<crate::Poll>,
+    /// Optional: paths of files to attach, inside the workspace or the
+    /// agent's screenshot directory. Sent after the message text.
+    #[serde(default)]
+    pub attachments: Option<Vec<String>>,
+    /// Optional: show the reply only to the person who sent the triggering
+    /// message. Slack only; other platforms post it normally. Sends the
+    /// text alone, without cards, interactive elements or polls.
+    #[serde(default)]
+    pub ephemeral: bool,
 }
 
 /// Output from reply tool.
@@ -103,6 +154,33 @@ pub struct ReplyOutput {
     pub success: bool,
     pub conversation_id: String,
     pub content: String,
+    /// Filenames of attachments delivered with the reply.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<String>,
+    /// Platform ID of the sent message, for `edit_reply`/`delete_reply`.
+    /// `None` when the platform doesn't support edits or delivery wasn't
+    /// confirmed in time.
+    pub message_id: Option<String>,
+}
+
+/// Maximum number of attachments per reply (Discord's per-message limit).
+const MAX_ATTACHMENTS: usize = 10;
+
+/// How long to wait for the adapter to confirm a tracked send.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Wait for the outbound router to report on a tracked response. Returns the
+/// platform message ID when the adapter reports one.
+pub(crate) async fn await_delivery(
+    delivery: tokio::sync::oneshot::Receiver<crate::DeliveryOutcome>,
+) -> Result<Option<String>, String> {
+    match tokio::time::timeout(DELIVERY_TIMEOUT, delivery).await {
+        Ok(Ok(outcome)) => outcome,
+        // The router dropped the receipt without reporting (e.g. cron runs
+        // that collect text instead of delivering it).
+        Ok(Err(_)) => Ok(None),
+        Err(_) => Err("timed out waiting for the platform to confirm delivery".into()),
+    }
 }
 
 /// Convert @username mentions to platform-specific syntax using conversation metadata.
@@ -110,7 +188,7 @@ pub struct ReplyOutput {
 /// Scans recent conversation history to build a name→ID mapping, then replaces
 /// @DisplayName with the platform's mention format (<@ID> for Discord/Slack,
 /// @username for Telegram).
-async fn convert_mentions(
+pub(crate) async fn convert_mentions(
     content: &str,
     channel_id: &ChannelId,
     conversation_logger: &ConversationLogger,
@@ -219,6 +297,28 @@ pub(crate) fn normalize_discord_mention_tokens(content: &str, source: &str) -> S
     normalized
 }
 
+/// Resolve an attachment path to a canonical file inside one of `roots`.
+/// Relative paths resolve against the first root (the workspace).
+fn resolve_attachment_path(raw: &str, roots: &[PathBuf]) -> Result<PathBuf, ReplyError> {
+    let raw_path = Path::new(raw.trim());
+    let path = match roots.first() {
+        Some(workspace) if raw_path.is_relative() => workspace.join(raw_path),
+        _ => raw_path.to_path_buf(),
+    };
+
+    let mut last_error = None;
+    for root in roots {
+        match crate::tools::send_file::validate_path_within(&path, root) {
+            Ok(canonical) => return Ok(canonical),
+            Err(error) => last_error = Some(error),
+        }
+    }
+
+    Err(ReplyError(last_error.unwrap_or_else(|| {
+        format!("no attachment directories configured for '{raw}'")
+    })))
+}
+
 fn normalize_poll_payload(poll: crate::Poll) -> Option<crate::Poll> {
     let question = poll.question.trim().to_string();
     if question.is_empty() {
@@ -252,7 +352,7 @@ impl Tool for ReplyTool {
     type Output = ReplyOutput;
 
     async fn definition(&self, _prompt: String) -> ToolDefinition {
-        let parameters = serde_json::json!({
+        let mut parameters = serde_json::json!({
             "type": "object",
             "properties": {
                 "content": {
@@ -346,12 +446,23 @@ impl Tool for ReplyTool {
                         "duration_hours": { "type": "integer", "description": "Defaults to 24 if omitted" }
                     },
                     "required": ["question", "answers"]
+                },
+                "attachments": {
+                    "type": "array",
+                    "description": "Optional: files to attach, as absolute paths or paths relative to the workspace. Files must live in the workspace or the screenshot directory. Executables and scripts are rejected. Max 10 files.",
+                    "items": { "type": "string" }
                 }
             },
             "required": ["content"]
         });
 
         let source = self.conversation_id.split(':').next().unwrap_or("unknown");
+        if source == "slack" {
+            parameters["properties"]["ephemeral"] = serde_json::json!({
+                "type": "boolean",
+                "description": "Show the reply only to the person who asked. Use for private or noisy answers that the rest of the channel doesn't need. Text only."
+            });
+        }
         let mut description = crate::prompts::text::get("tools/reply").to_string();
         if source == "email" {
             description.push_str(
@@ -414,7 +525,34 @@ impl Tool for ReplyTool {
             ));
         }
 
-        let response = if let Some(name) = thread_name {
+        // Read and validate every attachment before sending anything so a bad
+        // path doesn't leave the user with half a reply.
+        let attachment_paths = args.attachments.unwrap_or_default();
+        if attachment_paths.len() > MAX_ATTACHMENTS {
+            return Err(ReplyError(format!(
+                "too many attachments ({}, max {MAX_ATTACHMENTS})",
+                attachment_paths.len()
+            )));
+        }
+        let max_bytes = crate::tools::send_file::max_upload_bytes(source);
+        let mut files = Vec::with_capacity(attachment_paths.len());
+        for raw_path in &attachment_paths {
+            let path = resolve_attachment_path(raw_path, &self.attachment_roots)?;
+            let file = crate::tools::send_file::read_outbound_file(&path, max_bytes)
+                .await
+                .map_err(ReplyError)?;
+            files.push(file);
+        }
+        let attachment_names: Vec<String> =
+            files.iter().map(|file| file.filename.clone()).collect();
+
+        let response = if args.ephemeral {
+            // The adapter addresses it to the sender of the triggering message.
+            OutboundResponse::Ephemeral {
+                text: converted_content.clone(),
+                user_id: String::new(),
+            }
+        } else if let Some(name) = thread_name {
             // Cap thread names at 100 characters (Discord limit)
             let thread_name = if name.len() > 100 {
                 name[..name.floor_char_boundary(100)].to_string()
@@ -437,16 +575,64 @@ impl Tool for ReplyTool {
             OutboundResponse::Text(converted_content.clone())
         };
 
-        self.response_tx
-            .send(response)
-            .await
-            .map_err(|e| ReplyError(format!("failed to send reply: {e}")))?;
+        // An attachment-only reply skips the empty text message.
+        let mut message_id = None;
+        if matches!(response, OutboundResponse::Text(_))
+            && self.pace != ResponsePace::Instant
+            && !converted_content.trim().is_empty()
+        {
+            message_id = self.send_paced(&converted_content).await?;
+        } else if !converted_content.trim().is_empty() || files.is_empty() {
+            let delivery = self
+                .response_tx
+                .send_tracked(response)
+                .await
+                .map_err(|e| ReplyError(format!("failed to send reply: {e}")))?;
+            match await_delivery(delivery).await {
+                Ok(id) => message_id = id,
+                Err(error) => tracing::warn!(
+                    conversation_id = %self.conversation_id,
+                    %error,
+                    "reply delivery not confirmed"
+                ),
+            }
+        }
 
-        self.conversation_logger.log_bot_message_with_name(
-            &self.channel_id,
-            &converted_content,
-            Some(&self.agent_display_name),
-        );
+        for file in files {
+            tracing::info!(
+                conversation_id = %self.conversation_id,
+                filename = %file.filename,
+                mime_type = %file.mime_type,
+                size_bytes = file.data.len(),
+                "sending reply attachment"
+            );
+            self.response_tx
+                .send(file.into_response(None))
+                .await
+                .map_err(|e| ReplyError(format!("failed to send attachment: {e}")))?;
+        }
+
+        let logged_content = if attachment_names.is_empty() {
+            converted_content.clone()
+        } else {
+            format!(
+                "{converted_content}\n[Attached: {}]",
+                attachment_names.join(", ")
+            )
+        };
+        match &message_id {
+            Some(platform_message_id) => self.conversation_logger.log_bot_reply(
+                &self.channel_id,
+                logged_content.trim(),
+                Some(&self.agent_display_name),
+                platform_message_id,
+            ),
+            None => self.conversation_logger.log_bot_message_with_name(
+                &self.channel_id,
+                logged_content.trim(),
+                Some(&self.agent_display_name),
+            ),
+        }
 
         // Mark the turn as handled so handle_agent_result skips the fallback send.
         self.replied_flag.store(true, Ordering::Relaxed);
@@ -457,6 +643,8 @@ impl Tool for ReplyTool {
             success: true,
             conversation_id: self.conversation_id.clone(),
             content: converted_content,
+            attachments: attachment_names,
+            message_id,
         })
     }
 }
@@ -464,7 +652,8 @@ impl Tool for ReplyTool {
 #[cfg(test)]
 mod tests {
     use super::{
-        normalize_discord_mention_tokens, normalize_poll_payload, sanitize_discord_user_id,
+        normalize_discord_mention_tokens, normalize_poll_payload, resolve_attachment_path,
+        sanitize_discord_user_id,
     };
     use crate::Poll;
 
@@ -538,4 +727,32 @@ mod tests {
         assert!(normalized.allow_multiselect);
         assert_eq!(normalized.duration_hours, 12);
     }
+
+    #[test]
+    fn resolves_attachments_inside_roots_only() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let workspace = temp_dir.path().join("workspace");
+        let screenshots = temp_dir.path().join("screenshots");
+        let outside = temp_dir.path().join("outside");
+        for dir in [&workspace, &screenshots, &outside] {
+            std::fs::create_dir_all(dir).expect("failed to create dir");
+        }
+        std::fs::write(workspace.join("report.md"), "ok").expect("failed to write");
+        std::fs::write(screenshots.join("page.png"), "png").expect("failed to write");
+        std::fs::write(outside.join("secret.txt"), "no").expect("failed to write");
+
+        let roots = vec![workspace.clone(), screenshots.clone()];
+
+        let relative = resolve_attachment_path("report.md", &roots).expect("relative path");
+        assert!(relative.ends_with("workspace/report.md"));
+
+        let screenshot = screenshots.join("page.png");
+        resolve_attachment_path(&screenshot.to_string_lossy(), &roots)
+            .expect("screenshot path should be allowed");
+
+        let secret = outside.join("secret.txt");
+        let error = resolve_attachment_path(&secret.to_string_lossy(), &roots)
+            .expect_err("path outside roots should be rejected");
+        assert!(error.to_string().contains("ACCESS DENIED"));
+    }
 }
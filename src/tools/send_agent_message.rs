@@ -100,73 +100,22 @@ impl SendAgentMessageTool {
 
         None
     }
-}
-
-/// Error type for send_agent_message tool.
-#[derive(Debug, thiserror::Error)]
-#[error("SendAgentMessage failed: {0}")]
-pub struct SendAgentMessageError(String);
-
-/// Arguments for send_agent_message tool.
-#[derive(Debug, Deserialize, JsonSchema)]
-pub struct SendAgentMessageArgs {
-    /// Target agent ID or name.
-    pub target: String,
-    /// The task to assign. First sentence is used as the task title;
-    /// full content becomes the task description.
-    pub message: String,
-}
 
-/// Output from send_agent_message tool.
-#[derive(Debug, Serialize)]
-pub struct SendAgentMessageOutput {
-    pub success: bool,
-    pub target_agent: String,
-    pub task_number: Option<i64>,
-    pub message: String,
-}
-
-impl Tool for SendAgentMessageTool {
-    const NAME: &'static str = "send_agent_message";
-
-    type Error = SendAgentMessageError;
-    type Args = SendAgentMessageArgs;
-    type Output = SendAgentMessageOutput;
-
-    async fn definition(&self, _prompt: String) -> ToolDefinition {
-        ToolDefinition {
-            name: Self::NAME.to_string(),
-            description: crate::prompts::text::get("tools/send_agent_message").to_string(),
-            parameters: serde_json::json!({
-                "type": "object",
-                "properties": {
-                    "target": {
-                        "type": "string",
-                        "description": "The target agent's ID or name."
-                    },
-                    "message": {
-                        "type": "string",
-                        "description": "The task to assign. First sentence becomes the title; full content is the description."
-                    }
-                },
-                "required": ["target", "message"]
-            }),
-        }
+    /// The agent this tool sends on behalf of.
+    pub(crate) fn agent_id(&self) -> &str {
+        &self.agent_id
     }
 
-    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
-        tracing::info!(
-            from = %self.agent_id,
-            target = %args.target,
-            message_len = args.message.len(),
-            "send_agent_message tool called"
-        );
-
+    /// Resolve `target` to a linked agent that this agent may message.
+    /// Returns its ID and display name.
+    pub(crate) fn resolve_linked_target(
+        &self,
+        target: &str,
+    ) -> Result<(String, String), SendAgentMessageError> {
         // Resolve target agent ID (could be name or ID)
-        let target_agent_id = self.resolve_agent_id(&args.target).ok_or_else(|| {
+        let target_agent_id = self.resolve_agent_id(target).ok_or_else(|| {
             SendAgentMessageError(format!(
-                "unknown agent '{}'. Check your organization context for available agents.",
-                args.target
+                "unknown agent '{target}'. Check your organization context for available agents."
             ))
         })?;
 
@@ -175,8 +124,7 @@ impl Tool for SendAgentMessageTool {
         let link = crate::links::find_link_between(&links, &self.agent_id, &target_agent_id)
             .ok_or_else(|| {
                 SendAgentMessageError(format!(
-                    "no communication link exists between you and agent '{}'.",
-                    args.target
+                    "no communication link exists between you and agent '{target}'."
                 ))
             })?;
 
@@ -186,49 +134,77 @@ impl Tool for SendAgentMessageTool {
 
         if link.direction == crate::links::LinkDirection::OneWay && is_to_agent {
             return Err(SendAgentMessageError(format!(
-                "the link to agent '{}' is one-way and you cannot initiate messages.",
-                args.target
+                "the link to agent '{target}' is one-way and you cannot initiate messages."
             )));
         }
 
         let receiving_agent_id = if link.from_agent_id == sending_agent_id {
-            &link.to_agent_id
+            link.to_agent_id.clone()
         } else {
-            &link.from_agent_id
+            link.from_agent_id.clone()
         };
 
         let target_display = self
             .agent_names
-            .get(receiving_agent_id)
+            .get(&receiving_agent_id)
             .cloned()
-            .unwrap_or_else(|| receiving_agent_id.to_string());
+            .unwrap_or_else(|| receiving_agent_id.clone());
 
-        // Look up the target agent's task store from the cross-agent registry.
-        let registry = self.task_store_registry.load();
-        let target_task_store = registry.get(receiving_agent_id).ok_or_else(|| {
-            SendAgentMessageError(format!(
-                "target agent '{}' has no task store available. It may not be initialized.",
-                target_display
-            ))
-        })?;
+        Ok((receiving_agent_id, target_display))
+    }
+
+    /// The task store of a target agent, from the cross-agent registry.
+    pub(crate) fn target_task_store(
+        &self,
+        target_agent_id: &str,
+        target_display: &str,
+    ) -> Result<Arc<TaskStore>, SendAgentMessageError> {
+        self.task_store_registry
+            .load()
+            .get(target_agent_id)
+            .cloned()
+            .ok_or_else(|| {
+                SendAgentMessageError(format!(
+                    "target agent '{target_display}' has no task store available. It may not be initialized."
+                ))
+            })
+    }
+
+    /// Create a ready task on a linked agent and log the assignment on both
+    /// sides of the link channel. `extra_metadata` is merged into the task's
+    /// delegation metadata.
+    pub(crate) async fn assign_task(
+        &self,
+        target: &str,
+        message: &str,
+        extra_metadata: serde_json::Value,
+    ) -> Result<DelegatedTask, SendAgentMessageError> {
+        let (receiving_agent_id, target_display) = self.resolve_linked_target(target)?;
+        let target_task_store = self.target_task_store(&receiving_agent_id, &target_display)?;
+        let sending_agent_id = self.agent_id.as_ref();
 
         // Extract title from the message: first sentence or first 120 chars.
-        let title = extract_task_title(&args.message);
+        let title = extract_task_title(message);
 
         // Build task metadata with delegation context.
-        let metadata = serde_json::json!({
+        let mut metadata = serde_json::json!({
             "delegated_by": sending_agent_id,
             "delegating_agent_id": sending_agent_id,
             "originating_channel": self.originating_channel,
         });
+        if let (Some(metadata), Some(extra)) =
+            (metadata.as_object_mut(), extra_metadata.as_object())
+        {
+            metadata.extend(extra.clone());
+        }
 
         // Create the task on the target agent's store.
         // Agent-delegated tasks skip pending_approval and go straight to ready.
         let task = target_task_store
             .create(crate::tasks::CreateTaskInput {
-                agent_id: receiving_agent_id.to_string(),
+                agent_id: receiving_agent_id.clone(),
                 title: title.clone(),
-                description: Some(args.message.clone()),
+                description: Some(message.to_string()),
                 status: crate::tasks::TaskStatus::Ready,
                 priority: crate::tasks::TaskPriority::Medium,
                 subtasks: Vec::new(),
@@ -252,27 +228,18 @@ impl Tool for SendAgentMessageTool {
             .get(sending_agent_id)
             .cloned()
             .unwrap_or_else(|| sending_agent_id.to_string());
-        let link_channel_id = link.channel_id_for(sending_agent_id);
-
-        self.conversation_logger.log_system_message(
-            &link_channel_id,
-            &format!(
-                "{sender_display} assigned task #{task_number} to {target_display}: \"{title}\""
-            ),
-        );
-
-        // Also log to the receiver's side of the link channel.
-        let receiver_link_channel_id = link.channel_id_for(receiving_agent_id);
-        self.conversation_logger.log_system_message(
-            &receiver_link_channel_id,
-            &format!(
+        let links = self.links.load();
+        if let Some(link) =
+            crate::links::find_link_between(&links, sending_agent_id, &receiving_agent_id)
+        {
+            let record = format!(
                 "{sender_display} assigned task #{task_number} to {target_display}: \"{title}\""
-            ),
-        );
-
-        // End the current turn immediately after delegation.
-        if let Some(ref flag) = self.skip_flag {
-            flag.store(true, Ordering::Relaxed);
+            );
+            self.conversation_logger
+                .log_system_message(&link.channel_id_for(sending_agent_id), &record);
+            // Also log to the receiver's side of the link channel.
+            self.conversation_logger
+                .log_system_message(&link.channel_id_for(&receiving_agent_id), &record);
         }
 
         tracing::info!(
@@ -282,9 +249,96 @@ impl Tool for SendAgentMessageTool {
             "task delegated to target agent"
         );
 
+        Ok(DelegatedTask {
+            target_agent_id: receiving_agent_id,
+            target_display,
+            task_number,
+            task_store: target_task_store,
+        })
+    }
+}
+
+/// A task created on another agent by [`SendAgentMessageTool::assign_task`].
+pub(crate) struct DelegatedTask {
+    pub target_agent_id: String,
+    pub target_display: String,
+    pub task_number: i64,
+    pub task_store: Arc<TaskStore>,
+}
+
+/// Error type for send_agent_message tool.
+#[derive(Debug, thiserror::Error)]
+#[error("SendAgentMessage failed: {0}")]
+pub struct SendAgentMessageError(pub(crate) String);
+
+/// Arguments for send_agent_message tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SendAgentMessageArgs {
+    /// Target agent ID or name.
+    pub target: String,
+    /// The task to assign. First sentence is used as the task title;
+    /// full content becomes the task description.
+    pub message: String,
+}
+
+/// Output from send_agent_message tool.
+#[derive(Debug, Serialize)]
+pub struct SendAgentMessageOutput {
+    pub success: bool,
+    pub target_agent: String,
+    pub task_number: Option<i64>,
+    pub message: String,
+}
+
+impl Tool for SendAgentMessageTool {
+    const NAME: &'static str = "send_agent_message";
+
+    type Error = SendAgentMessageError;
+    type Args = SendAgentMessageArgs;
+    type Output = SendAgentMessageOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/send_agent_message").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "target": {
+                        "type": "string",
+                        "description": "The target agent's ID or name."
+                    },
+                    "message": {
+                        "type": "string",
+                        "description": "The task to assign. First sentence becomes the title; full content is the description."
+                    }
+                },
+                "required": ["target", "message"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        tracing::info!(
+            from = %self.agent_id,
+            target = %args.target,
+            message_len = args.message.len(),
+            "send_agent_message tool called"
+        );
+
+        let delegated = self
+            .assign_task(&args.target, &args.message, serde_json::json!({}))
+            .await?;
+        let task_number = delegated.task_number;
+
+        // End the current turn immediately after delegation.
+        if let Some(ref flag) = self.skip_flag {
+            flag.store(true, Ordering::Relaxed);
+        }
+
         Ok(SendAgentMessageOutput {
             success: true,
-            target_agent: target_display,
+            target_agent: delegated.target_display,
             task_number: Some(task_number),
             message: format!(
                 "Task #{task_number} assigned. The target agent's cortex will pick it up and execute it autonomously. \
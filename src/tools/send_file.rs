@@ -6,7 +6,7 @@ use rig::completion::ToolDefinition;
 use rig::tool::Tool;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// Tool for sending files to users.
@@ -33,55 +33,138 @@ impl SendFileTool {
     }
 
     /// Validate that a path falls within the workspace boundary.
-    ///
-    /// Checks both the canonicalized path and individual path components for
-    /// symlinks to prevent TOCTOU races where a symlink is swapped between
-    /// validation and the actual file read.
-    fn validate_workspace_path(&self, path: &std::path::Path) -> Result<PathBuf, SendFileError> {
-        let workspace = &self.workspace;
-
-        let canonical = path.canonicalize().map_err(|error| {
-            SendFileError(format!("can't resolve path '{}': {error}", path.display()))
-        })?;
-        let workspace_canonical = workspace
-            .canonicalize()
-            .unwrap_or_else(|_| workspace.clone());
-
-        if !canonical.starts_with(&workspace_canonical) {
-            return Err(SendFileError(format!(
-                "ACCESS DENIED: Path is outside the workspace boundary. \
-                 File operations are restricted to {}.",
-                workspace.display()
-            )));
-        }
+    fn validate_workspace_path(&self, path: &Path) -> Result<PathBuf, SendFileError> {
+        validate_path_within(path, &self.workspace).map_err(SendFileError)
+    }
+}
 
-        // Reject paths containing symlinks within the workspace to prevent
-        // TOCTOU races where a path component is replaced with a symlink
-        // between this check and the file read.
-        let relative_original = path
-            .strip_prefix(workspace)
-            .or_else(|_| path.strip_prefix(&workspace_canonical))
-            .unwrap_or(path);
-        let mut walk = workspace_canonical.clone();
-        for component in relative_original.components() {
-            walk.push(component);
-            match walk.symlink_metadata() {
-                Ok(meta) if meta.is_symlink() => {
-                    return Err(SendFileError(
-                        "ACCESS DENIED: Symlinks are not allowed within the workspace.".into(),
-                    ));
-                }
-                Ok(_) => {}
-                Err(error) => {
-                    return Err(SendFileError(format!(
-                        "can't verify path component '{}': {error}",
-                        walk.display()
-                    )));
-                }
+/// Validate that a path falls within `root`.
+///
+/// Checks both the canonicalized path and individual path components for
+/// symlinks to prevent TOCTOU races where a symlink is swapped between
+/// validation and the actual file read.
+pub(crate) fn validate_path_within(path: &Path, root: &Path) -> Result<PathBuf, String> {
+    let canonical = path
+        .canonicalize()
+        .map_err(|error| format!("can't resolve path '{}': {error}", path.display()))?;
+    let root_canonical = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+
+    if !canonical.starts_with(&root_canonical) {
+        return Err(format!(
+            "ACCESS DENIED: Path is outside the workspace boundary. \
+             File operations are restricted to {}.",
+            root.display()
+        ));
+    }
+
+    // Reject paths containing symlinks within the root to prevent TOCTOU
+    // races where a path component is replaced with a symlink between this
+    // check and the file read.
+    let relative_original = path
+        .strip_prefix(root)
+        .or_else(|_| path.strip_prefix(&root_canonical))
+        .unwrap_or(path);
+    let mut walk = root_canonical.clone();
+    for component in relative_original.components() {
+        walk.push(component);
+        match walk.symlink_metadata() {
+            Ok(meta) if meta.is_symlink() => {
+                return Err("ACCESS DENIED: Symlinks are not allowed within the workspace.".into());
+            }
+            Ok(_) => {}
+            Err(error) => {
+                return Err(format!(
+                    "can't verify path component '{}': {error}",
+                    walk.display()
+                ));
             }
         }
+    }
+
+    Ok(canonical)
+}
 
-        Ok(canonical)
+/// A file read from disk and checked for size and type, ready to be sent
+/// as an `OutboundResponse::File`.
+#[derive(Debug)]
+pub(crate) struct OutboundFile {
+    pub filename: String,
+    pub data: Vec<u8>,
+    pub mime_type: String,
+}
+
+impl OutboundFile {
+    pub(crate) fn into_response(self, caption: Option<String>) -> OutboundResponse {
+        OutboundResponse::File {
+            filename: self.filename,
+            data: self.data,
+            mime_type: self.mime_type,
+            caption,
+        }
+    }
+}
+
+/// Read an already-validated path for sending, enforcing `max_bytes` and
+/// rejecting executable and script types.
+pub(crate) async fn read_outbound_file(
+    path: &Path,
+    max_bytes: u64,
+) -> Result<OutboundFile, String> {
+    let metadata = tokio::fs::metadata(path)
+        .await
+        .map_err(|error| format!("can't read file '{}': {error}", path.display()))?;
+
+    if !metadata.is_file() {
+        return Err(format!("'{}' is not a file", path.display()));
+    }
+
+    if metadata.len() == 0 {
+        return Err(format!("'{}' is empty", path.display()));
+    }
+
+    if metadata.len() > max_bytes {
+        return Err(format!(
+            "file is too large ({} bytes, max {} bytes)",
+            metadata.len(),
+            max_bytes,
+        ));
+    }
+
+    let mime_type = mime_guess::from_path(path)
+        .first_or_octet_stream()
+        .to_string();
+    if BLOCKED_MIME_TYPES.contains(&mime_type.as_str()) {
+        return Err(format!(
+            "'{}' has a blocked file type ({mime_type})",
+            path.display()
+        ));
+    }
+
+    let data = tokio::fs::read(path)
+        .await
+        .map_err(|error| format!("failed to read '{}': {error}", path.display()))?;
+
+    let filename = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "file".into());
+
+    Ok(OutboundFile {
+        filename,
+        data,
+        mime_type,
+    })
+}
+
+/// Largest upload the platform behind `source` accepts from a bot.
+pub(crate) fn max_upload_bytes(source: &str) -> u64 {
+    match source {
+        // Bot API upload limit for documents and audio.
+        "telegram" => 50 * 1024 * 1024,
+        // Attachments are base64-encoded, so leave headroom under the common
+        // 25 MB message limit.
+        "email" => 18 * 1024 * 1024,
+        _ => MAX_FILE_SIZE_BYTES,
     }
 }
 
@@ -108,9 +191,21 @@ pub struct SendFileOutput {
     pub size_bytes: u64,
 }
 
-/// Maximum file size: 25 MB (Discord's limit for non-boosted servers).
+/// Default maximum file size: 25 MB (Discord's limit for non-boosted servers).
 const MAX_FILE_SIZE_BYTES: u64 = 25 * 1024 * 1024;
 
+/// Executables and scripts are never delivered to users.
+const BLOCKED_MIME_TYPES: &[&str] = &[
+    "application/x-msdownload",
+    "application/x-msdos-program",
+    "application/x-msi",
+    "application/vnd.microsoft.portable-executable",
+    "application/x-executable",
+    "application/x-sh",
+    "application/x-csh",
+    "application/x-bat",
+];
+
 impl Tool for SendFileTool {
     const NAME: &'static str = "send_file";
 
@@ -157,51 +252,21 @@ impl Tool for SendFileTool {
             })?
         };
 
-        let metadata = tokio::fs::metadata(&path).await.map_err(|error| {
-            SendFileError(format!("can't read file '{}': {error}", path.display()))
-        })?;
-
-        if !metadata.is_file() {
-            return Err(SendFileError(format!("'{}' is not a file", path.display())));
-        }
-
-        if metadata.len() > MAX_FILE_SIZE_BYTES {
-            return Err(SendFileError(format!(
-                "file is too large ({} bytes, max {} bytes)",
-                metadata.len(),
-                MAX_FILE_SIZE_BYTES,
-            )));
-        }
-
-        let data = tokio::fs::read(&path).await.map_err(|error| {
-            SendFileError(format!("failed to read '{}': {error}", path.display()))
-        })?;
-
-        let filename = path
-            .file_name()
-            .map(|name| name.to_string_lossy().into_owned())
-            .unwrap_or_else(|| "file".into());
-
-        let mime_type = mime_guess::from_path(&path)
-            .first_or_octet_stream()
-            .to_string();
-
-        let size_bytes = data.len() as u64;
+        let file = read_outbound_file(&path, max_upload_bytes(self.response_tx.target_source()))
+            .await
+            .map_err(SendFileError)?;
+        let filename = file.filename.clone();
+        let size_bytes = file.data.len() as u64;
 
         tracing::info!(
             file_path = %path.display(),
             filename = %filename,
-            mime_type = %mime_type,
+            mime_type = %file.mime_type,
             size_bytes,
             "send_file tool called"
         );
 
-        let response = OutboundResponse::File {
-            filename: filename.clone(),
-            data,
-            mime_type,
-            caption: args.caption,
-        };
+        let response = file.into_response(args.caption);
 
         self.response_tx
             .send(response)
@@ -344,4 +409,32 @@ mod tests {
             other => panic!("expected File response, got {other:?}"),
         }
     }
+
+    #[tokio::test]
+    async fn read_outbound_file_rejects_executables_and_oversized_files() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+
+        let script = temp_dir.path().join("install.sh");
+        fs::write(&script, "#!/bin/sh").expect("failed to write script");
+        let error = read_outbound_file(&script, MAX_FILE_SIZE_BYTES)
+            .await
+            .expect_err("scripts should be rejected");
+        assert!(
+            error.contains("blocked file type"),
+            "unexpected error: {error}"
+        );
+
+        let report = temp_dir.path().join("report.txt");
+        fs::write(&report, "0123456789").expect("failed to write report");
+        let error = read_outbound_file(&report, 4)
+            .await
+            .expect_err("oversized file should be rejected");
+        assert!(error.contains("too large"), "unexpected error: {error}");
+
+        let file = read_outbound_file(&report, MAX_FILE_SIZE_BYTES)
+            .await
+            .expect("text file should be accepted");
+        assert_eq!(file.filename, "report.txt");
+        assert_eq!(file.mime_type, "text/plain");
+    }
 }
@@ -1,9 +1,10 @@
 //! Send message tool for cross-channel messaging and DMs.
 
-use crate::ChannelId;
 use crate::conversation::ChannelStore;
 use crate::conversation::history::ConversationLogger;
 use crate::messaging::MessagingManager;
+use crate::messaging::moderation::Moderator;
+use crate::{AgentDeps, ChannelId, DeliveryReceipt};
 
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
@@ -21,10 +22,15 @@ fn is_valid_uuid(s: &str) -> bool {
 ///
 /// Resolves targets by name or ID via the channel store, extracts the
 /// platform-specific target from channel metadata, and delivers via
-/// `MessagingManager::broadcast()`. Logs the sent message to the destination
-/// channel's conversation history so it appears in future transcripts.
+/// `MessagingManager::broadcast()`. Messages are moderated like channel
+/// replies first, under the destination channel's strictness (or the sending
+/// channel's for targets without a channel). Logs the sent message to the
+/// destination channel's conversation history so it appears in future
+/// transcripts.
 #[derive(Clone)]
 pub struct SendMessageTool {
+    deps: AgentDeps,
+    channel_id: ChannelId,
     messaging_manager: Arc<MessagingManager>,
     channel_store: ChannelStore,
     conversation_logger: ConversationLogger,
@@ -40,6 +46,8 @@ impl std::fmt::Debug for SendMessageTool {
 
 impl SendMessageTool {
     pub fn new(
+        deps: AgentDeps,
+        channel_id: ChannelId,
         messaging_manager: Arc<MessagingManager>,
         channel_store: ChannelStore,
         conversation_logger: ConversationLogger,
@@ -47,6 +55,8 @@ impl SendMessageTool {
         current_adapter: Option<String>,
     ) -> Self {
         Self {
+            deps,
+            channel_id,
             messaging_manager,
             channel_store,
             conversation_logger,
@@ -54,6 +64,29 @@ impl SendMessageTool {
             current_adapter,
         }
     }
+
+    /// Run a message through moderation as if it were a reply in
+    /// `channel_id`. Returns the text to deliver, or an error if moderation
+    /// blocked it; a block notice is never sent in its place.
+    async fn moderate(
+        &self,
+        channel_id: ChannelId,
+        message: String,
+    ) -> Result<String, SendMessageError> {
+        let (receipt, mut outcome) = DeliveryReceipt::new();
+        let moderated = Moderator::new(&self.deps, channel_id)
+            .moderate(crate::OutboundResponse::Text(message), Some(&receipt))
+            .await;
+        if let Ok(Err(error)) = outcome.try_recv() {
+            return Err(SendMessageError(error));
+        }
+        match moderated {
+            Some(crate::OutboundResponse::Text(text)) => Ok(text),
+            _ => Err(SendMessageError(
+                "the message was blocked by moderation and not sent".into(),
+            )),
+        }
+    }
 }
 
 /// Error type for send_message tool.
@@ -159,11 +192,12 @@ impl Tool for SendMessageTool {
                 target.adapter = current_adapter.clone();
             }
 
+            let message = self.moderate(self.channel_id.clone(), args.message).await?;
             self.messaging_manager
                 .broadcast(
                     &target.adapter,
                     &target.target,
-                    crate::OutboundResponse::Text(args.message),
+                    crate::OutboundResponse::Text(message),
                 )
                 .await
                 .map_err(|error| SendMessageError(format!("failed to send message: {error}")))?;
@@ -191,11 +225,12 @@ impl Tool for SendMessageTool {
         {
             match parse_implicit_signal_shorthand(&args.target, current_adapter) {
                 Ok(Some(target)) => {
+                    let message = self.moderate(self.channel_id.clone(), args.message).await?;
                     self.messaging_manager
                         .broadcast(
                             &target.adapter,
                             &target.target,
-                            crate::OutboundResponse::Text(args.message),
+                            crate::OutboundResponse::Text(message),
                         )
                         .await
                         .map_err(|error| {
@@ -225,11 +260,12 @@ impl Tool for SendMessageTool {
 
         // Check for explicit email target
         if let Some(explicit_target) = parse_explicit_email_target(&args.target) {
+            let message = self.moderate(self.channel_id.clone(), args.message).await?;
             self.messaging_manager
                 .broadcast(
                     &explicit_target.adapter,
                     &explicit_target.target,
-                    crate::OutboundResponse::Text(args.message),
+                    crate::OutboundResponse::Text(message),
                 )
                 .await
                 .map_err(|error| SendMessageError(format!("failed to send message: {error}")))?;
@@ -277,21 +313,24 @@ impl Tool for SendMessageTool {
                 ))
             })?;
 
+        let destination_channel_id: ChannelId = Arc::from(channel.id.as_str());
+        let message = self
+            .moderate(destination_channel_id.clone(), args.message)
+            .await?;
         self.messaging_manager
             .broadcast(
                 &broadcast_target.adapter,
                 &broadcast_target.target,
-                crate::OutboundResponse::Text(args.message.clone()),
+                crate::OutboundResponse::Text(message.clone()),
             )
             .await
             .map_err(|error| SendMessageError(format!("failed to send message: {error}")))?;
 
         // Log the sent message to the destination channel's conversation history
         // so it appears in future transcripts and channel recall.
-        let destination_channel_id: ChannelId = Arc::from(channel.id.as_str());
         self.conversation_logger.log_bot_message_with_name(
             &destination_channel_id,
-            &args.message,
+            &message,
             Some(&self.agent_display_name),
         );
 
@@ -0,0 +1,501 @@
+//! Skill authoring tools for cortex chat: `skill_create`, `skill_update`,
+//! `skill_test`.
+//!
+//! These let the agent grow its own skill library from admin conversations.
+//! Skills are written as `{workspace}/skills/{name}/SKILL.md`, validated with
+//! the same rules as `skills::validate_skill_file`, and the agent's skill set
+//! is reloaded so workers see the change on their next spawn. Instance-level
+//! skills are shared across agents and are never edited here.
+
+use crate::config::RuntimeConfig;
+use crate::skills::{SkillSet, SkillSource, validate_skill_file};
+use crate::tools::spawn_worker::{DetachedSpawnWorkerArgs, DetachedSpawnWorkerTool};
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Error type for the skill authoring tools.
+#[derive(Debug, thiserror::Error)]
+#[error("skill tool failed: {0}")]
+pub struct SkillToolError(String);
+
+/// Output from `skill_create` and `skill_update`.
+#[derive(Debug, Serialize)]
+pub struct SkillWriteOutput {
+    pub success: bool,
+    pub message: String,
+    /// Name of the skill that was written.
+    pub name: Option<String>,
+    /// Path of the written SKILL.md.
+    pub path: Option<String>,
+}
+
+impl SkillWriteOutput {
+    fn rejected(message: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            message: message.into(),
+            name: None,
+            path: None,
+        }
+    }
+}
+
+/// Write a validated SKILL.md into the workspace and reload the agent's skills.
+///
+/// Fails if the written skill doesn't load, e.g. because an `includes` file
+/// is missing.
+async fn write_skill(
+    runtime_config: &RuntimeConfig,
+    name: &str,
+    skill_file: &Path,
+    content: &str,
+) -> Result<(), SkillToolError> {
+    if let Some(parent) = skill_file.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(|error| {
+            SkillToolError(format!("failed to create {}: {error}", parent.display()))
+        })?;
+    }
+    tokio::fs::write(skill_file, content)
+        .await
+        .map_err(|error| {
+            SkillToolError(format!("failed to write {}: {error}", skill_file.display()))
+        })?;
+
+    reload_skills(runtime_config).await;
+    let loaded = runtime_config
+        .skills
+        .load()
+        .get(name)
+        .is_some_and(|skill| skill.source == SkillSource::Workspace);
+    if !loaded {
+        return Err(SkillToolError(format!(
+            "wrote {} but the skill failed to load; check that its `includes` files exist",
+            skill_file.display()
+        )));
+    }
+    Ok(())
+}
+
+async fn reload_skills(runtime_config: &RuntimeConfig) {
+    let skills = SkillSet::load(
+        &runtime_config.instance_dir.join("skills"),
+        &runtime_config.workspace_dir.join("skills"),
+    )
+    .await;
+    runtime_config.reload_skills(skills);
+}
+
+/// Tool for writing a new skill into the agent's workspace.
+#[derive(Clone)]
+pub struct SkillCreateTool {
+    runtime_config: Arc<RuntimeConfig>,
+}
+
+impl std::fmt::Debug for SkillCreateTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SkillCreateTool").finish_non_exhaustive()
+    }
+}
+
+impl SkillCreateTool {
+    pub fn new(runtime_config: Arc<RuntimeConfig>) -> Self {
+        Self { runtime_config }
+    }
+}
+
+/// Arguments for skill_create tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SkillCreateArgs {
+    /// Full SKILL.md content: frontmatter with `name` and `description`,
+    /// followed by the instructions.
+    pub content: String,
+}
+
+impl Tool for SkillCreateTool {
+    const NAME: &'static str = "skill_create";
+
+    type Error = SkillToolError;
+    type Args = SkillCreateArgs;
+    type Output = SkillWriteOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/skill_create").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "content": {
+                        "type": "string",
+                        "description": "Full SKILL.md content: a --- frontmatter block with `name` (lowercase, hyphenated) and `description`, then the instructions in markdown."
+                    }
+                },
+                "required": ["content"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let (name, _description) = match validate_skill_file(&args.content) {
+            Ok(parsed) => parsed,
+            Err(error) => return Ok(SkillWriteOutput::rejected(format!("{error:#}"))),
+        };
+
+        let skill_dir = self.runtime_config.workspace_dir.join("skills").join(&name);
+        let existing = self.runtime_config.skills.load().get(&name).cloned();
+        if skill_dir.exists()
+            || existing
+                .as_ref()
+                .is_some_and(|skill| skill.source == SkillSource::Workspace)
+        {
+            return Ok(SkillWriteOutput::rejected(format!(
+                "skill '{name}' already exists; use skill_update to change it"
+            )));
+        }
+
+        let skill_file = skill_dir.join("SKILL.md");
+        write_skill(&self.runtime_config, &name, &skill_file, &args.content).await?;
+
+        let mut message = format!("Created skill '{name}'.");
+        if existing.is_some() {
+            message.push_str(" It overrides the instance-level skill of the same name.");
+        }
+        tracing::info!(skill = %name, path = %skill_file.display(), "skill created");
+
+        Ok(SkillWriteOutput {
+            success: true,
+            message,
+            name: Some(name),
+            path: Some(skill_file.display().to_string()),
+        })
+    }
+}
+
+/// Tool for rewriting an existing workspace skill.
+#[derive(Clone)]
+pub struct SkillUpdateTool {
+    runtime_config: Arc<RuntimeConfig>,
+}
+
+impl std::fmt::Debug for SkillUpdateTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SkillUpdateTool").finish_non_exhaustive()
+    }
+}
+
+impl SkillUpdateTool {
+    pub fn new(runtime_config: Arc<RuntimeConfig>) -> Self {
+        Self { runtime_config }
+    }
+}
+
+/// Arguments for skill_update tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SkillUpdateArgs {
+    /// Name of the skill to update.
+    pub name: String,
+    /// New full SKILL.md content. The frontmatter `name` must stay the same.
+    pub content: String,
+}
+
+impl Tool for SkillUpdateTool {
+    const NAME: &'static str = "skill_update";
+
+    type Error = SkillToolError;
+    type Args = SkillUpdateArgs;
+    type Output = SkillWriteOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/skill_update").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "Name of the workspace skill to update."
+                    },
+                    "content": {
+                        "type": "string",
+                        "description": "Replacement SKILL.md content. Keep the same `name` in the frontmatter."
+                    }
+                },
+                "required": ["name", "content"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let Some(skill) = self.runtime_config.skills.load().get(&args.name).cloned() else {
+            return Ok(SkillWriteOutput::rejected(format!(
+                "skill '{}' not found; use skill_create for new skills",
+                args.name
+            )));
+        };
+        if skill.source == SkillSource::Instance {
+            return Ok(SkillWriteOutput::rejected(format!(
+                "'{}' is an instance-level skill shared across agents and can't be edited \
+                 here; create a workspace skill with the same name to override it",
+                skill.name
+            )));
+        }
+
+        let (name, _description) = match validate_skill_file(&args.content) {
+            Ok(parsed) => parsed,
+            Err(error) => return Ok(SkillWriteOutput::rejected(format!("{error:#}"))),
+        };
+        if !name.eq_ignore_ascii_case(&skill.name) {
+            return Ok(SkillWriteOutput::rejected(format!(
+                "frontmatter name '{name}' doesn't match '{}'; skills can't be renamed",
+                skill.name
+            )));
+        }
+
+        write_skill(&self.runtime_config, &name, &skill.file_path, &args.content).await?;
+        tracing::info!(skill = %name, path = %skill.file_path.display(), "skill updated");
+
+        Ok(SkillWriteOutput {
+            success: true,
+            message: format!("Updated skill '{name}'."),
+            name: Some(name),
+            path: Some(skill.file_path.display().to_string()),
+        })
+    }
+}
+
+/// Tool for checking a skill and optionally trying it out with a dry-run worker.
+#[derive(Clone)]
+pub struct SkillTestTool {
+    runtime_config: Arc<RuntimeConfig>,
+    spawn_tool: Option<DetachedSpawnWorkerTool>,
+}
+
+impl std::fmt::Debug for SkillTestTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SkillTestTool").finish_non_exhaustive()
+    }
+}
+
+impl SkillTestTool {
+    pub fn new(runtime_config: Arc<RuntimeConfig>) -> Self {
+        Self {
+            runtime_config,
+            spawn_tool: None,
+        }
+    }
+
+    /// Allow `scenario` runs by spawning dry-run workers through `spawn_tool`.
+    pub fn with_spawn_tool(mut self, spawn_tool: DetachedSpawnWorkerTool) -> Self {
+        self.spawn_tool = Some(spawn_tool);
+        self
+    }
+}
+
+/// Arguments for skill_test tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SkillTestArgs {
+    /// Name of the skill to test.
+    pub name: String,
+    /// Example task to dry-run the skill against. When set, a worker reads
+    /// the skill and walks through it without side effects.
+    #[serde(default)]
+    pub scenario: Option<String>,
+}
+
+/// Output from skill_test tool.
+#[derive(Debug, Serialize)]
+pub struct SkillTestOutput {
+    /// Whether the skill passed validation.
+    pub valid: bool,
+    /// Problems found in the skill file.
+    pub issues: Vec<String>,
+    /// The dry-run worker, if one was spawned.
+    pub worker_id: Option<crate::WorkerId>,
+    pub message: String,
+}
+
+/// `{baseDir}/...` paths referenced in a skill body that don't exist on disk.
+fn missing_resources(raw: &str, base_dir: &Path) -> Vec<String> {
+    let mut missing: Vec<String> = raw
+        .match_indices("{baseDir}/")
+        .map(|(start, marker)| {
+            raw[start + marker.len()..]
+                .split(|c: char| c.is_whitespace() || "`'\"()<>[]".contains(c))
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches(['.', ',', ':', ';'])
+        })
+        .filter(|relative| !relative.is_empty() && !base_dir.join(relative).exists())
+        .map(str::to_string)
+        .collect();
+    missing.sort();
+    missing.dedup();
+    missing
+}
+
+impl Tool for SkillTestTool {
+    const NAME: &'static str = "skill_test";
+
+    type Error = SkillToolError;
+    type Args = SkillTestArgs;
+    type Output = SkillTestOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/skill_test").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "name": {
+                        "type": "string",
+                        "description": "Name of the installed skill to test."
+                    },
+                    "scenario": {
+                        "type": "string",
+                        "description": "Optional example task. A worker reads the skill and walks through it for this task without side effects, then reports back."
+                    }
+                },
+                "required": ["name"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let Some(skill) = self.runtime_config.skills.load().get(&args.name).cloned() else {
+            return Ok(SkillTestOutput {
+                valid: false,
+                issues: vec![format!("skill '{}' is not loaded", args.name)],
+                worker_id: None,
+                message:
+                    "Skill not found. Check the name with skills_search(action=\"installed\")."
+                        .into(),
+            });
+        };
+
+        let raw = tokio::fs::read_to_string(&skill.file_path)
+            .await
+            .map_err(|error| {
+                SkillToolError(format!(
+                    "failed to read {}: {error}",
+                    skill.file_path.display()
+                ))
+            })?;
+
+        let mut issues = Vec::new();
+        if let Err(error) = validate_skill_file(&raw) {
+            issues.push(format!("{error:#}"));
+        }
+        if let Err(error) = self.runtime_config.skills.load().resolve(&skill.name) {
+            issues.push(format!("{error:#}"));
+        }
+        issues.extend(
+            missing_resources(&raw, &skill.base_dir)
+                .into_iter()
+                .map(|relative| format!("referenced file not found: {{baseDir}}/{relative}")),
+        );
+        let valid = issues.is_empty();
+
+        let Some(scenario) = args
+            .scenario
+            .as_deref()
+            .map(str::trim)
+            .filter(|scenario| !scenario.is_empty())
+        else {
+            let message = if valid {
+                format!("Skill '{}' is valid.", skill.name)
+            } else {
+                format!("Skill '{}' has {} issue(s).", skill.name, issues.len())
+            };
+            return Ok(SkillTestOutput {
+                valid,
+                issues,
+                worker_id: None,
+                message,
+            });
+        };
+        if !valid {
+            return Ok(SkillTestOutput {
+                valid,
+                issues,
+                worker_id: None,
+                message: "Fix the issues before a dry run.".into(),
+            });
+        }
+        let Some(spawn_tool) = &self.spawn_tool else {
+            return Ok(SkillTestOutput {
+                valid,
+                issues,
+                worker_id: None,
+                message: "Skill is valid; dry runs aren't available in this session.".into(),
+            });
+        };
+
+        let task = format!(
+            "Dry run of the skill '{name}'. Read it with read_skill(name=\"{name}\"), then work \
+             through its instructions for this scenario:\n\n{scenario}\n\n\
+             This is a test. Don't change files outside a scratch directory, send messages, or \
+             call services with side effects; describe what you would do at those steps instead. \
+             Report whether the instructions were clear and complete, which steps you could \
+             follow, and anything missing, ambiguous, or broken.",
+            name = skill.name,
+        );
+        let spawned = spawn_tool
+            .call(DetachedSpawnWorkerArgs { task })
+            .await
+            .map_err(|error| SkillToolError(error.to_string()))?;
+
+        Ok(SkillTestOutput {
+            valid,
+            issues,
+            worker_id: Some(spawned.worker_id),
+            message: format!(
+                "Skill '{}' is valid. Dry-run worker {} spawned; it will report back when done.",
+                skill.name, spawned.worker_id
+            ),
+        })
+    }
+}
+
+/// Register `skill_create`, `skill_update`, and `skill_test` on a ToolServer.
+pub fn register_skill_authoring_tools(
+    server: rig::tool::server::ToolServer,
+    runtime_config: Arc<RuntimeConfig>,
+    spawn_tool: Option<DetachedSpawnWorkerTool>,
+) -> rig::tool::server::ToolServer {
+    let test_tool = SkillTestTool::new(runtime_config.clone());
+    let test_tool = match spawn_tool {
+        Some(spawn_tool) => test_tool.with_spawn_tool(spawn_tool),
+        None => test_tool,
+    };
+
+    server
+        .tool(SkillCreateTool::new(runtime_config.clone()))
+        .tool(SkillUpdateTool::new(runtime_config))
+        .tool(test_tool)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_resources_reports_absent_files() {
+        let base_dir = std::env::temp_dir().join(format!("skill-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(base_dir.join("scripts")).unwrap();
+        std::fs::write(base_dir.join("scripts/run.sh"), "echo ok").unwrap();
+
+        let raw = "Run `{baseDir}/scripts/run.sh`, then read {baseDir}/references/api.md.\n\
+                   Again: \"{baseDir}/references/api.md\"";
+        let missing = missing_resources(raw, &base_dir);
+        assert_eq!(missing, vec!["references/api.md".to_string()]);
+
+        std::fs::remove_dir_all(&base_dir).unwrap();
+    }
+}
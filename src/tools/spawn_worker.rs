@@ -7,7 +7,10 @@
 
 use crate::WorkerId;
 use crate::agent::channel::ChannelState;
-use crate::agent::channel_dispatch::{spawn_opencode_worker_from_state, spawn_worker_from_state};
+use crate::agent::channel_dispatch::{
+    spawn_container_worker_from_state, spawn_opencode_worker_from_state, spawn_worker_from_state,
+};
+use crate::cli_worker::CliWorkerBackend;
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
 use schemars::JsonSchema;
@@ -50,12 +53,13 @@ pub struct SpawnWorkerArgs {
     /// Worker type: "builtin" (default) runs a Rig agent loop with shell/file
     /// tools. "opencode" spawns an OpenCode subprocess with full coding agent
     /// capabilities. Use "opencode" for complex coding tasks that benefit from
-    /// codebase exploration and context management.
+    /// codebase exploration and context management. "container" runs the
+    /// builtin worker with its shell confined to a per-worker container.
     #[serde(default)]
     pub worker_type: Option<String>,
-    /// Working directory for the worker. Required for "opencode" workers
-    /// unless project_id or worktree_id is set. The OpenCode agent will
-    /// operate in this directory.
+    /// Working directory for the worker. Required for "opencode" and
+    /// "container" workers unless project_id or worktree_id is set. The
+    /// worker will operate in this directory.
     #[serde(default)]
     pub directory: Option<String>,
     /// Project ID to associate this worker with. When set, the worker gets
@@ -67,6 +71,10 @@ pub struct SpawnWorkerArgs {
     /// automatically set to the worktree path.
     #[serde(default)]
     pub worktree_id: Option<String>,
+    /// JSON Schema the worker's initial result must match. The result is
+    /// delivered as JSON. Builtin workers only.
+    #[serde(default)]
+    pub result_schema: Option<serde_json::Value>,
 }
 
 /// Output from spawn worker tool.
@@ -93,7 +101,10 @@ impl Tool for SpawnWorkerTool {
         let rc = &self.state.deps.runtime_config;
         let browser_enabled = rc.browser_config.load().enabled;
         let web_search_enabled = rc.brave_search_key.load().is_some();
-        let opencode_enabled = rc.opencode.load().enabled;
+        // Backends whose binary failed its last health probe are left out
+        // rather than offered and failing at spawn time.
+        let opencode_enabled = CliWorkerBackend::Opencode.is_usable(rc);
+        let container_enabled = CliWorkerBackend::Container.is_usable(rc);
 
         let mut tools_list = vec!["shell", "file_read", "file_write", "file_edit", "file_list"];
         if browser_enabled {
@@ -103,16 +114,18 @@ impl Tool for SpawnWorkerTool {
             tools_list.push("web_search");
         }
 
-        let opencode_note = if opencode_enabled {
-            " Set `worker_type` to \"opencode\" with a `directory` path for complex coding tasks — this spawns a full OpenCode coding agent with codebase exploration, context management, and its own tool suite. If `worker_type` is omitted, the builtin worker is used."
-        } else {
-            ""
-        };
+        let mut opencode_note = String::new();
+        if opencode_enabled {
+            opencode_note.push_str(" Set `worker_type` to \"opencode\" with a `directory` path for complex coding tasks — this spawns a full OpenCode coding agent with codebase exploration, context management, and its own tool suite. If `worker_type` is omitted, the builtin worker is used.");
+        }
+        if container_enabled {
+            opencode_note.push_str(" Set `worker_type` to \"container\" with a `directory` path to run untrusted code (unreviewed repos, downloaded scripts, generated code) — the builtin worker runs with its shell inside an isolated container that can only see that directory.");
+        }
 
         let base_description = crate::prompts::text::get("tools/spawn_worker");
         let description = base_description
             .replace("{tools}", &tools_list.join(", "))
-            .replace("{opencode_note}", opencode_note);
+            .replace("{opencode_note}", &opencode_note);
 
         let mut properties = serde_json::json!({
             "task": {
@@ -128,24 +141,41 @@ impl Tool for SpawnWorkerTool {
                 "type": "array",
                 "items": { "type": "string" },
                 "description": "Skill names from <available_skills> that are likely relevant to this task. The worker sees all skills and decides what to read, but suggested skills are flagged as recommended."
+            },
+            "result_schema": {
+                "type": "object",
+                "description": "Optional JSON Schema for the worker's result. When set, the worker's initial answer is validated against it (with correction retries) and reported back as JSON. Builtin workers only."
             }
         });
 
-        if opencode_enabled && let Some(obj) = properties.as_object_mut() {
+        if (opencode_enabled || container_enabled)
+            && let Some(obj) = properties.as_object_mut()
+        {
+            let mut worker_types = vec!["builtin"];
+            let mut worker_type_description =
+                "\"builtin\" (default) runs a Rig agent loop.".to_string();
+            if opencode_enabled {
+                worker_types.push("opencode");
+                worker_type_description.push_str(" \"opencode\" spawns a full OpenCode coding agent — use for complex multi-file coding tasks. Do not claim OpenCode unless this field is explicitly set to \"opencode\".");
+            }
+            if container_enabled {
+                worker_types.push("container");
+                worker_type_description.push_str(" \"container\" runs the builtin worker with its shell inside an isolated per-worker container bound to `directory` — use for untrusted code.");
+            }
             obj.insert(
                 "worker_type".to_string(),
                 serde_json::json!({
                     "type": "string",
-                    "enum": ["builtin", "opencode"],
+                    "enum": worker_types,
                     "default": "builtin",
-                    "description": "\"builtin\" (default) runs a Rig agent loop. \"opencode\" spawns a full OpenCode coding agent — use for complex multi-file coding tasks. Do not claim OpenCode unless this field is explicitly set to \"opencode\"."
+                    "description": worker_type_description
                 }),
             );
             obj.insert(
                 "directory".to_string(),
                 serde_json::json!({
                     "type": "string",
-                    "description": "Working directory for the worker. Required when worker_type is \"opencode\" unless project_id or worktree_id is set. The OpenCode agent operates in this directory."
+                    "description": "Working directory for the worker. Required when worker_type is \"opencode\" or \"container\" unless project_id or worktree_id is set. The worker operates in this directory."
                 }),
             );
             obj.insert(
@@ -178,6 +208,25 @@ impl Tool for SpawnWorkerTool {
     async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
         let readiness = self.state.deps.runtime_config.work_readiness();
         let is_opencode = args.worker_type.as_deref() == Some("opencode");
+        let is_container = args.worker_type.as_deref() == Some("container");
+
+        let rc = &self.state.deps.runtime_config;
+        for backend in CliWorkerBackend::ALL {
+            if args.worker_type.as_deref() == Some(backend.worker_type())
+                && backend.configured_binary(rc).is_some()
+                && !backend.is_usable(rc)
+            {
+                let detail = rc
+                    .cli_worker_health
+                    .status(backend)
+                    .and_then(|status| status.error)
+                    .unwrap_or_default();
+                return Err(SpawnWorkerError(format!(
+                    "{} workers are unavailable: the backend failed its health check ({detail})",
+                    backend.worker_type()
+                )));
+            }
+        }
 
         // Reject if an active worker already has the same task. This prevents
         // duplicate workers when the LLM emits multiple spawn_worker calls in
@@ -200,6 +249,16 @@ impl Tool for SpawnWorkerTool {
             }
         }
 
+        if let Some(schema) = &args.result_schema {
+            if is_opencode {
+                return Err(SpawnWorkerError(
+                    "result_schema is only supported for builtin workers".into(),
+                ));
+            }
+            crate::agent::structured_output::check_schema(schema)
+                .map_err(|error| SpawnWorkerError(format!("invalid result_schema: {error}")))?;
+        }
+
         // Resolve working directory from project/worktree if not explicitly set.
         let resolved_directory = resolve_directory_from_project(
             &self.state.deps,
@@ -220,6 +279,27 @@ impl Tool for SpawnWorkerTool {
             spawn_opencode_worker_from_state(&self.state, &args.task, directory, true)
                 .await
                 .map_err(|e| SpawnWorkerError(format!("{e}")))?
+        } else if is_container {
+            let directory = resolved_directory.as_deref().ok_or_else(|| {
+                SpawnWorkerError(
+                    "directory is required for container workers (set directory, project_id, or worktree_id)".into(),
+                )
+            })?;
+
+            spawn_container_worker_from_state(
+                &self.state,
+                &args.task,
+                directory,
+                args.interactive,
+                &args
+                    .suggested_skills
+                    .iter()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>(),
+                args.result_schema.clone(),
+            )
+            .await
+            .map_err(|e| SpawnWorkerError(format!("{e}")))?
         } else {
             spawn_worker_from_state(
                 &self.state,
@@ -230,6 +310,7 @@ impl Tool for SpawnWorkerTool {
                     .iter()
                     .map(String::as_str)
                     .collect::<Vec<_>>(),
+                args.result_schema.clone(),
             )
             .await
             .map_err(|e| SpawnWorkerError(format!("{e}")))?
@@ -244,7 +325,13 @@ impl Tool for SpawnWorkerTool {
             );
         }
 
-        let worker_type_label = if is_opencode { "OpenCode" } else { "builtin" };
+        let worker_type_label = if is_opencode {
+            "OpenCode"
+        } else if is_container {
+            "Container"
+        } else {
+            "builtin"
+        };
         // OpenCode workers are always interactive regardless of args.interactive.
         let effectively_interactive = args.interactive || is_opencode;
         let message = if effectively_interactive {
@@ -482,6 +569,7 @@ impl Tool for DetachedSpawnWorkerTool {
             None,
             secrets_store,
             "builtin",
+            true,
             worker.run().instrument(worker_span),
         );
 
@@ -0,0 +1,170 @@
+//! Spawn worker batch tool: run several independent tasks in parallel and get
+//! one aggregated result back.
+
+use crate::agent::channel::ChannelState;
+use crate::agent::worker_batch::{MAX_BATCH_TASKS, WorkerBatch};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Tool for spawning a batch of workers.
+#[derive(Debug, Clone)]
+pub struct SpawnWorkerBatchTool {
+    state: ChannelState,
+}
+
+impl SpawnWorkerBatchTool {
+    /// Create a new spawn worker batch tool with access to channel state.
+    pub fn new(state: ChannelState) -> Self {
+        Self { state }
+    }
+}
+
+/// Error type for spawn worker batch tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Worker batch spawn failed: {0}")]
+pub struct SpawnWorkerBatchError(String);
+
+/// Arguments for spawn worker batch tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SpawnWorkerBatchArgs {
+    /// Independent task descriptions, one worker each.
+    pub tasks: Vec<String>,
+    /// Skill names to suggest to every worker in the batch.
+    #[serde(default)]
+    pub suggested_skills: Vec<String>,
+    /// Max workers to run at once. Capped by the agent's
+    /// `worker_batch_concurrency`.
+    #[serde(default)]
+    pub max_concurrency: Option<usize>,
+    /// JSON Schema every worker's result must match.
+    #[serde(default)]
+    pub result_schema: Option<serde_json::Value>,
+}
+
+/// Output from spawn worker batch tool.
+#[derive(Debug, Serialize)]
+pub struct SpawnWorkerBatchOutput {
+    /// The ID the aggregated result will be reported under.
+    pub batch_id: uuid::Uuid,
+    pub task_count: usize,
+    pub concurrency: usize,
+    /// Status message.
+    pub message: String,
+}
+
+impl Tool for SpawnWorkerBatchTool {
+    const NAME: &'static str = "spawn_worker_batch";
+
+    type Error = SpawnWorkerBatchError;
+    type Args = SpawnWorkerBatchArgs;
+    type Output = SpawnWorkerBatchOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        let concurrency = **self
+            .state
+            .deps
+            .runtime_config
+            .worker_batch_concurrency
+            .load();
+        let description = crate::prompts::text::get("tools/spawn_worker_batch")
+            .replace("{max_tasks}", &MAX_BATCH_TASKS.to_string())
+            .replace("{concurrency}", &concurrency.to_string());
+
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description,
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "tasks": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "minItems": 1,
+                        "maxItems": MAX_BATCH_TASKS,
+                        "description": "Independent task descriptions, one builtin worker each. Each must be self-contained since workers can't see your conversation or each other."
+                    },
+                    "suggested_skills": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Skill names from <available_skills> to flag as recommended for every worker in the batch."
+                    },
+                    "max_concurrency": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "description": format!("Max workers running at once (default and upper bound: {concurrency}).")
+                    },
+                    "result_schema": {
+                        "type": "object",
+                        "description": "Optional JSON Schema every worker's result must match. Results are reported back as JSON."
+                    }
+                },
+                "required": ["tasks"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        if args.tasks.is_empty() {
+            return Err(SpawnWorkerBatchError("tasks must not be empty".into()));
+        }
+        if args.tasks.len() > MAX_BATCH_TASKS {
+            return Err(SpawnWorkerBatchError(format!(
+                "a batch holds at most {MAX_BATCH_TASKS} tasks, got {}",
+                args.tasks.len()
+            )));
+        }
+        let mut seen = HashSet::new();
+        for task in &args.tasks {
+            if task.trim().is_empty() {
+                return Err(SpawnWorkerBatchError("tasks must not be blank".into()));
+            }
+            if !seen.insert(task.as_str()) {
+                return Err(SpawnWorkerBatchError(format!(
+                    "duplicate task in batch: {task}"
+                )));
+            }
+        }
+        if let Some(schema) = &args.result_schema {
+            crate::agent::structured_output::check_schema(schema).map_err(|error| {
+                SpawnWorkerBatchError(format!("invalid result_schema: {error}"))
+            })?;
+        }
+
+        let configured = **self
+            .state
+            .deps
+            .runtime_config
+            .worker_batch_concurrency
+            .load();
+        let concurrency = args
+            .max_concurrency
+            .unwrap_or(configured)
+            .clamp(1, configured.max(1));
+
+        let batch = WorkerBatch {
+            id: uuid::Uuid::new_v4(),
+            tasks: args.tasks,
+            suggested_skills: args.suggested_skills,
+            result_schema: args.result_schema,
+            concurrency,
+        };
+        let batch_id = batch.id;
+        let task_count = batch.tasks.len();
+        batch.spawn(self.state.clone());
+
+        tracing::info!(%batch_id, task_count, concurrency, "worker batch spawned");
+
+        Ok(SpawnWorkerBatchOutput {
+            batch_id,
+            task_count,
+            concurrency,
+            message: format!(
+                "Batch {batch_id} started: {task_count} tasks, up to {concurrency} at a time. \
+                 All results will be reported together when the last worker finishes."
+            ),
+        })
+    }
+}
@@ -0,0 +1,379 @@
+//! Fan a request out to a team's members and collect their results.
+//!
+//! Given to the workers of a team coordinator. Each assignment becomes a
+//! ready task on the member agent, created through the same path as
+//! `delegate_to_agent`, and the run is tracked in the coordinator's
+//! `team_runs` table. The tool waits for the members in parallel and returns
+//! their results for the coordinator to synthesize; a run that outlives the
+//! wait can be checked again by its ID.
+
+use crate::WorkerId;
+use crate::config::TeamDef;
+use crate::teams::{TeamRunMember, TeamRunStatus, TeamRunStore};
+use crate::tools::delegate_to_agent::{DelegationStatus, check_delegation};
+use crate::tools::send_agent_message::SendAgentMessageTool;
+
+use arc_swap::ArcSwap;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Longest a single call may wait for the members.
+const MAX_WAIT_SECS: u64 = 600;
+
+/// How often unfinished members are checked while waiting.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Tool for dispatching a request to the members of a coordinated team.
+#[derive(Debug, Clone)]
+pub struct TeamDispatchTool {
+    /// Resolves members and creates their tasks.
+    messenger: SendAgentMessageTool,
+    teams: Arc<ArcSwap<Vec<TeamDef>>>,
+    run_store: TeamRunStore,
+    worker_id: WorkerId,
+}
+
+impl TeamDispatchTool {
+    pub fn new(
+        messenger: SendAgentMessageTool,
+        teams: Arc<ArcSwap<Vec<TeamDef>>>,
+        run_store: TeamRunStore,
+        worker_id: WorkerId,
+    ) -> Self {
+        Self {
+            messenger,
+            teams,
+            run_store,
+            worker_id,
+        }
+    }
+
+    /// Find one of this agent's teams by ID or name. `None` picks the only
+    /// team, if there is just one.
+    fn resolve_team(&self, team: Option<&str>) -> Result<TeamDef, TeamDispatchError> {
+        let teams = self.teams.load();
+        let coordinated = crate::teams::teams_coordinated_by(&teams, self.messenger.agent_id());
+        let found = match team {
+            Some(team) => coordinated.into_iter().find(|candidate| {
+                candidate.id == team || candidate.display_name().eq_ignore_ascii_case(team)
+            }),
+            None if coordinated.len() > 1 => {
+                return Err(TeamDispatchError(
+                    "you coordinate several teams; pass `team` to pick one".into(),
+                ));
+            }
+            None => coordinated.into_iter().next(),
+        };
+        found.cloned().ok_or_else(|| match team {
+            Some(team) => TeamDispatchError(format!("you don't coordinate a team called '{team}'")),
+            None => TeamDispatchError("you don't coordinate any team".into()),
+        })
+    }
+
+    /// Create a task on each assigned member.
+    async fn assign(
+        &self,
+        team: &TeamDef,
+        assignments: Vec<TeamAssignment>,
+    ) -> Result<Vec<TeamRunMember>, TeamDispatchError> {
+        if assignments.is_empty() {
+            return Err(TeamDispatchError(
+                "pass at least one assignment, or `run_id` to check on an earlier run".into(),
+            ));
+        }
+
+        // Check every member before creating anything, so a typo doesn't
+        // leave half a run behind.
+        let mut resolved = Vec::with_capacity(assignments.len());
+        for assignment in assignments {
+            let (agent_id, _) = self
+                .messenger
+                .resolve_linked_target(&assignment.member)
+                .map_err(|error| TeamDispatchError(error.0))?;
+            if team.member(&agent_id).is_none() {
+                return Err(TeamDispatchError(format!(
+                    "'{}' is not a member of team '{}'",
+                    assignment.member,
+                    team.display_name()
+                )));
+            }
+            resolved.push((agent_id, assignment.task));
+        }
+
+        let mut members = Vec::with_capacity(resolved.len());
+        for (agent_id, task) in resolved {
+            let assigned = self
+                .messenger
+                .assign_task(
+                    &agent_id,
+                    &task,
+                    serde_json::json!({
+                        "delegated_via": Self::NAME,
+                        "delegating_worker_id": self.worker_id.to_string(),
+                        "team_id": team.id,
+                    }),
+                )
+                .await;
+            members.push(match assigned {
+                Ok(delegated) => TeamRunMember {
+                    agent_id,
+                    task,
+                    task_number: Some(delegated.task_number),
+                    status: DelegationStatus::Queued,
+                    result: None,
+                },
+                Err(error) => TeamRunMember {
+                    agent_id,
+                    task,
+                    task_number: None,
+                    status: DelegationStatus::Failed,
+                    result: Some(error.0),
+                },
+            });
+        }
+        Ok(members)
+    }
+
+    /// Check unfinished members' tasks. Returns whether any member changed.
+    async fn refresh(&self, members: &mut [TeamRunMember]) -> bool {
+        let mut changed = false;
+        for member in members
+            .iter_mut()
+            .filter(|member| !member.status.is_finished())
+        {
+            let Some(task_number) = member.task_number else {
+                continue;
+            };
+            let checked = match self.messenger.resolve_linked_target(&member.agent_id) {
+                Ok((agent_id, display)) => {
+                    match self.messenger.target_task_store(&agent_id, &display) {
+                        Ok(task_store) => {
+                            check_delegation(
+                                &task_store,
+                                &agent_id,
+                                &display,
+                                task_number,
+                                self.messenger.agent_id(),
+                            )
+                            .await
+                        }
+                        Err(error) => Err(error.0),
+                    }
+                }
+                Err(error) => Err(error.0),
+            };
+            let (status, result) =
+                checked.unwrap_or_else(|error| (DelegationStatus::Failed, Some(error)));
+            if status != member.status {
+                member.status = status;
+                member.result = result;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+/// Error type for team_dispatch tool.
+#[derive(Debug, thiserror::Error)]
+#[error("Team dispatch failed: {0}")]
+pub struct TeamDispatchError(String);
+
+/// One member's part of a request.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TeamAssignment {
+    /// Member agent ID or name.
+    pub member: String,
+    /// What this member should do, with everything it needs to do it.
+    pub task: String,
+}
+
+/// Arguments for team_dispatch tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct TeamDispatchArgs {
+    /// Team ID or name. Optional when you coordinate a single team.
+    #[serde(default)]
+    pub team: Option<String>,
+    /// The request being split up, recorded on the run.
+    #[serde(default)]
+    pub request: Option<String>,
+    /// Which members get which part of the request.
+    #[serde(default)]
+    pub assignments: Vec<TeamAssignment>,
+    /// A run ID returned by an earlier call, to check its results.
+    #[serde(default)]
+    pub run_id: Option<String>,
+    /// Seconds to wait for the members before returning. 0 returns right away.
+    #[serde(default)]
+    pub wait_seconds: u64,
+}
+
+/// Output from team_dispatch tool.
+#[derive(Debug, Serialize)]
+pub struct TeamDispatchOutput {
+    pub run_id: String,
+    pub team: String,
+    pub status: TeamRunStatus,
+    pub members: Vec<TeamRunMember>,
+    pub message: String,
+}
+
+impl Tool for TeamDispatchTool {
+    const NAME: &'static str = "team_dispatch";
+
+    type Error = TeamDispatchError;
+    type Args = TeamDispatchArgs;
+    type Output = TeamDispatchOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        let mut description = crate::prompts::text::get("tools/team_dispatch").to_string();
+        let teams = self.teams.load();
+        for team in crate::teams::teams_coordinated_by(&teams, self.messenger.agent_id()) {
+            description.push_str(&format!("\n\nTeam `{}`", team.id));
+            if let Some(name) = &team.name {
+                description.push_str(&format!(" ({name})"));
+            }
+            if let Some(team_description) = &team.description {
+                description.push_str(&format!(": {team_description}"));
+            }
+            for member in &team.members {
+                description.push_str(&format!("\n- `{}`: {}", member.agent, member.specialty));
+            }
+        }
+
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description,
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "team": {
+                        "type": "string",
+                        "description": "Team ID or name. Optional when you coordinate a single team."
+                    },
+                    "request": {
+                        "type": "string",
+                        "description": "The overall request being split up, recorded on the run."
+                    },
+                    "assignments": {
+                        "type": "array",
+                        "description": "One entry per member that should take part. Pick members by specialty; not every member needs a part.",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "member": {
+                                    "type": "string",
+                                    "description": "Member agent ID or name."
+                                },
+                                "task": {
+                                    "type": "string",
+                                    "description": "This member's part, with everything it needs to do it. First sentence becomes the title."
+                                }
+                            },
+                            "required": ["member", "task"]
+                        }
+                    },
+                    "run_id": {
+                        "type": "string",
+                        "description": "Run ID from an earlier team_dispatch call, to check its results instead of dispatching."
+                    },
+                    "wait_seconds": {
+                        "type": "integer",
+                        "minimum": 0,
+                        "maximum": MAX_WAIT_SECS,
+                        "description": "How long to wait for the members before returning. 0 returns immediately; check later with run_id."
+                    }
+                }
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let wait = Duration::from_secs(args.wait_seconds.min(MAX_WAIT_SECS));
+        let run = match args.run_id {
+            Some(run_id) => self
+                .run_store
+                .get(&run_id)
+                .await
+                .map_err(|error| TeamDispatchError(error.to_string()))?
+                .ok_or_else(|| TeamDispatchError(format!("no team run with ID '{run_id}'")))?,
+            None => {
+                let team = self.resolve_team(args.team.as_deref())?;
+                let request = args.request.unwrap_or_else(|| {
+                    args.assignments
+                        .iter()
+                        .map(|assignment| assignment.task.as_str())
+                        .collect::<Vec<_>>()
+                        .join("\n\n")
+                });
+                let members = self.assign(&team, args.assignments).await?;
+                let worker_id = self.worker_id.to_string();
+                let run = self
+                    .run_store
+                    .create(&team.id, Some(&worker_id), &request, &members)
+                    .await
+                    .map_err(|error| TeamDispatchError(error.to_string()))?;
+                tracing::info!(
+                    team_id = %team.id,
+                    run_id = %run.id,
+                    members = members.len(),
+                    "team request dispatched"
+                );
+                run
+            }
+        };
+
+        let mut members = run.members;
+        let mut status = run.status;
+        let deadline = tokio::time::Instant::now() + wait;
+        loop {
+            if self.refresh(&mut members).await {
+                status = self
+                    .run_store
+                    .update_members(&run.id, &members)
+                    .await
+                    .map_err(|error| TeamDispatchError(error.to_string()))?;
+            }
+            if status != TeamRunStatus::Running || tokio::time::Instant::now() >= deadline {
+                break;
+            }
+            tokio::time::sleep(
+                POLL_INTERVAL.min(deadline.saturating_duration_since(tokio::time::Instant::now())),
+            )
+            .await;
+        }
+
+        let message = match status {
+            TeamRunStatus::Running => {
+                let waiting = members
+                    .iter()
+                    .filter(|member| !member.status.is_finished())
+                    .count();
+                format!(
+                    "Still waiting on {waiting} member(s). Call again with run_id {} to check.",
+                    run.id
+                )
+            }
+            TeamRunStatus::Completed => {
+                "Every member has answered. Combine their results into one response to the request."
+                    .to_string()
+            }
+            TeamRunStatus::Partial | TeamRunStatus::Failed => {
+                "All members have finished, but some failed. Combine what came back and say what is missing."
+                    .to_string()
+            }
+        };
+
+        Ok(TeamDispatchOutput {
+            run_id: run.id,
+            team: run.team_id,
+            status,
+            members,
+            message,
+        })
+    }
+}
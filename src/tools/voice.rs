@@ -0,0 +1,175 @@
+//! Voice channel tools for channels: `join_voice`, `leave_voice`.
+//!
+//! Only added when the channel's adapter can join voice (Discord with
+//! `[messaging.discord.voice] enabled = true`). While joined, what people say
+//! arrives in this channel as transcribed messages, and short replies to them
+//! are also spoken in the voice channel.
+
+use crate::messaging::MessagingManager;
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Error type for the voice tools.
+#[derive(Debug, thiserror::Error)]
+#[error("voice failed: {0}")]
+pub struct VoiceError(String);
+
+/// Output from `join_voice` and `leave_voice`.
+#[derive(Debug, Serialize)]
+pub struct VoiceOutput {
+    pub success: bool,
+    pub message: String,
+}
+
+/// Tool for joining a voice channel in the current server.
+#[derive(Clone)]
+pub struct JoinVoiceTool {
+    messaging_manager: Arc<MessagingManager>,
+    adapter: String,
+    conversation_id: String,
+}
+
+impl std::fmt::Debug for JoinVoiceTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JoinVoiceTool")
+            .field("adapter", &self.adapter)
+            .field("conversation_id", &self.conversation_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl JoinVoiceTool {
+    pub fn new(
+        messaging_manager: Arc<MessagingManager>,
+        adapter: impl Into<String>,
+        conversation_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            messaging_manager,
+            adapter: adapter.into(),
+            conversation_id: conversation_id.into(),
+        }
+    }
+}
+
+/// Arguments for join_voice tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct JoinVoiceArgs {
+    /// Voice channel name, ID, or mention.
+    pub channel: String,
+}
+
+impl Tool for JoinVoiceTool {
+    const NAME: &'static str = "join_voice";
+
+    type Error = VoiceError;
+    type Args = JoinVoiceArgs;
+    type Output = VoiceOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/join_voice").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "channel": {
+                        "type": "string",
+                        "description": "The voice channel to join, by name, ID, or mention."
+                    }
+                },
+                "required": ["channel"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let joined = self
+            .messaging_manager
+            .join_voice(&self.adapter, &self.conversation_id, &args.channel)
+            .await
+            .map_err(|error| VoiceError(error.to_string()))?;
+
+        Ok(VoiceOutput {
+            success: true,
+            message: format!(
+                "Joined voice channel {joined}. What people say there will arrive here as messages."
+            ),
+        })
+    }
+}
+
+/// Tool for leaving the voice channel joined from this channel.
+#[derive(Clone)]
+pub struct LeaveVoiceTool {
+    messaging_manager: Arc<MessagingManager>,
+    adapter: String,
+    conversation_id: String,
+}
+
+impl std::fmt::Debug for LeaveVoiceTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LeaveVoiceTool")
+            .field("adapter", &self.adapter)
+            .field("conversation_id", &self.conversation_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl LeaveVoiceTool {
+    pub fn new(
+        messaging_manager: Arc<MessagingManager>,
+        adapter: impl Into<String>,
+        conversation_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            messaging_manager,
+            adapter: adapter.into(),
+            conversation_id: conversation_id.into(),
+        }
+    }
+}
+
+/// Arguments for leave_voice tool.
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct LeaveVoiceArgs {}
+
+impl Tool for LeaveVoiceTool {
+    const NAME: &'static str = "leave_voice";
+
+    type Error = VoiceError;
+    type Args = LeaveVoiceArgs;
+    type Output = VoiceOutput;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: crate::prompts::text::get("tools/leave_voice").to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {}
+            }),
+        }
+    }
+
+    async fn call(&self, _args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let left = self
+            .messaging_manager
+            .leave_voice(&self.adapter, &self.conversation_id)
+            .await
+            .map_err(|error| VoiceError(error.to_string()))?;
+
+        Ok(VoiceOutput {
+            success: left,
+            message: if left {
+                "Left the voice channel.".to_string()
+            } else {
+                "Not in a voice channel for this conversation.".to_string()
+            },
+        })
+    }
+}
@@ -0,0 +1,222 @@
+//! Full channel turns against the in-memory test harness.
+//!
+//! Every model call is answered by `spacebot::testing::ScriptedLlm`, so these
+//! run offline and deterministically.
+
+use spacebot::ProcessEvent;
+use spacebot::testing::{ScriptedResponse, TestAgent};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+#[tokio::test]
+async fn channel_replies_through_the_reply_tool() {
+    let agent = TestAgent::start()
+        .await
+        .expect("failed to start test agent");
+    agent.llm.push(ScriptedResponse::tool_call(
+        "reply",
+        serde_json::json!({ "content": "hi alice" }),
+    ));
+
+    let channel = agent.channel("general");
+    channel.send("alice", "hello there").await.unwrap();
+
+    assert_eq!(
+        channel.next_text(TIMEOUT).await.as_deref(),
+        Some("hi alice")
+    );
+
+    let requests = agent.llm.requests();
+    assert!(!requests.is_empty());
+    assert!(requests[0].tool_names().contains(&"reply"));
+    assert!(
+        requests[0]
+            .last_user_message()
+            .is_some_and(|message| message.contains("hello there"))
+    );
+}
+
+#[tokio::test]
+async fn plain_text_falls_back_to_a_reply() {
+    let agent = TestAgent::start()
+        .await
+        .expect("failed to start test agent");
+    agent.llm.push(ScriptedResponse::text("plain answer"));
+
+    let channel = agent.channel("general");
+    channel.send("alice", "what's up?").await.unwrap();
+
+    assert_eq!(
+        channel.next_text(TIMEOUT).await.as_deref(),
+        Some("plain answer")
+    );
+}
+
+#[tokio::test]
+async fn branch_result_is_relayed_on_retrigger() {
+    let agent = TestAgent::start()
+        .await
+        .expect("failed to start test agent");
+    agent
+        .llm
+        .push(ScriptedResponse::tool_call(
+            "branch",
+            serde_json::json!({ "description": "check the deploy log" }),
+        ))
+        .push(ScriptedResponse::tool_call(
+            "skip",
+            serde_json::json!({ "reason": "waiting on branch" }),
+        ))
+        .push(ScriptedResponse::tool_call(
+            "reply",
+            serde_json::json!({ "content": "v2 shipped on Tuesday" }),
+        ));
+    agent.llm.when(
+        "check the deploy log",
+        ScriptedResponse::text("Deploy v2 shipped on Tuesday."),
+    );
+
+    let channel = agent.channel("general");
+    channel.send("alice", "when did v2 ship?").await.unwrap();
+
+    assert_eq!(
+        channel.next_text(TIMEOUT).await.as_deref(),
+        Some("v2 shipped on Tuesday")
+    );
+    assert_eq!(agent.llm.remaining(), 0);
+}
+
+#[tokio::test]
+async fn follow_up_does_not_restart_a_turn_that_spawned_a_worker() {
+    let agent = TestAgent::start()
+        .await
+        .expect("failed to start test agent");
+    agent
+        .llm
+        .push(ScriptedResponse::tool_call(
+            "spawn_worker",
+            serde_json::json!({ "task": "Index the repository for search" }),
+        ))
+        .push(
+            ScriptedResponse::tool_call("reply", serde_json::json!({ "content": "on it" }))
+                .delayed(Duration::from_millis(500)),
+        )
+        .push(ScriptedResponse::tool_call(
+            "skip",
+            serde_json::json!({ "reason": "worker result already covered" }),
+        ));
+    agent
+        .llm
+        .when(
+            "Index the repository",
+            ScriptedResponse::tool_call(
+                "set_status",
+                serde_json::json!({ "status": "indexed", "kind": "outcome" }),
+            ),
+        )
+        .when(
+            "Index the repository",
+            ScriptedResponse::text("Indexed 12 files."),
+        )
+        .when(
+            "also the docs",
+            ScriptedResponse::tool_call("reply", serde_json::json!({ "content": "noted" })),
+        );
+
+    let mut events = agent.events();
+    let channel = agent.channel("general");
+    channel
+        .send("alice", "can you look at the codebase?")
+        .await
+        .unwrap();
+
+    // Follow up while the turn waits on the model after spawning the worker.
+    tokio::time::timeout(TIMEOUT, async {
+        while !matches!(events.recv().await, Ok(ProcessEvent::WorkerStarted { .. })) {}
+    })
+    .await
+    .expect("worker never started");
+    channel.send("alice", "also the docs").await.unwrap();
+
+    assert_eq!(channel.next_text(TIMEOUT).await.as_deref(), Some("on it"));
+    assert_eq!(channel.next_text(TIMEOUT).await.as_deref(), Some("noted"));
+
+    let mut workers_started = 1;
+    while let Ok(event) = events.try_recv() {
+        if matches!(event, ProcessEvent::WorkerStarted { .. }) {
+            workers_started += 1;
+        }
+    }
+    assert_eq!(workers_started, 1);
+}
+
+#[tokio::test]
+async fn overflowing_prompt_is_summarized_before_the_turn() {
+    let agent = TestAgent::start()
+        .await
+        .expect("failed to start test agent");
+    let channel = agent.channel("general");
+
+    let pasted_log = format!("launch code ZEBRA {}", "x".repeat(200_000));
+    agent.llm.push(ScriptedResponse::tool_call(
+        "reply",
+        serde_json::json!({ "content": "got the log" }),
+    ));
+    channel.send("alice", &pasted_log).await.unwrap();
+    assert_eq!(
+        channel.next_text(TIMEOUT).await.as_deref(),
+        Some("got the log")
+    );
+
+    agent.llm.push(ScriptedResponse::tool_call(
+        "reply",
+        serde_json::json!({ "content": "sure" }),
+    ));
+    channel.send("alice", "thanks").await.unwrap();
+    assert_eq!(channel.next_text(TIMEOUT).await.as_deref(), Some("sure"));
+
+    // Shrink the window so the next prompt lands between the aggressive and
+    // emergency thresholds.
+    let system_prompt_len = agent
+        .llm
+        .requests()
+        .last()
+        .and_then(|request| request.system_prompt().map(str::len))
+        .unwrap_or_default();
+    let projected_tokens = (system_prompt_len + pasted_log.len()) / 4;
+    agent
+        .deps
+        .runtime_config
+        .context_window
+        .store(std::sync::Arc::new(projected_tokens * 10 / 9));
+
+    agent
+        .llm
+        .when(
+            "ZEBRA",
+            ScriptedResponse::text("Alice pasted a long log with the launch code."),
+        )
+        .push(ScriptedResponse::tool_call(
+            "reply",
+            serde_json::json!({ "content": "it was ZEBRA" }),
+        ));
+    channel.send("alice", "what was the code?").await.unwrap();
+    assert_eq!(
+        channel.next_text(TIMEOUT).await.as_deref(),
+        Some("it was ZEBRA")
+    );
+
+    let requests = agent.llm.requests();
+    let turn = requests
+        .iter()
+        .find(|request| {
+            request
+                .last_user_message()
+                .is_some_and(|message| message.contains("what was the code?"))
+        })
+        .expect("turn request missing");
+    assert!(turn.contains("[Compaction Summary]: Alice pasted a long log with the launch code."));
+    assert!(!turn.contains(&pasted_log));
+    assert_eq!(agent.llm.remaining(), 0);
+}
@@ -52,7 +52,7 @@ async fn bootstrap_deps() -> anyhow::Result<spacebot::AgentDeps> {
     let resolved_agents = config.resolve_agents();
     let agent_config = resolved_agents.first().context("no agents configured")?;
 
-    let db = spacebot::db::Db::connect(&agent_config.data_dir)
+    let db = spacebot::db::Db::connect(&agent_config.data_dir, &agent_config.storage)
         .await
         .context("failed to connect databases")?;
 
@@ -123,12 +123,14 @@ async fn bootstrap_deps() -> anyhow::Result<spacebot::AgentDeps> {
         links: Arc::new(arc_swap::ArcSwap::from_pointee(Vec::new())),
         agent_names: Arc::new(std::collections::HashMap::new()),
         humans: Arc::new(arc_swap::ArcSwap::from_pointee(Vec::new())),
+        teams: Arc::new(arc_swap::ArcSwap::from_pointee(Vec::new())),
         task_store_registry: Arc::new(arc_swap::ArcSwap::from_pointee(
             std::collections::HashMap::new(),
         )),
         process_control_registry: Arc::new(
             spacebot::agent::process_control::ProcessControlRegistry::new(),
         ),
+        branch_cache: Arc::new(spacebot::agent::branch_cache::BranchResultCache::new()),
         injection_tx: tokio::sync::mpsc::channel(1).0,
     })
 }